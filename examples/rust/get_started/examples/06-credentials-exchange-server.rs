@@ -1,16 +1,39 @@
 // This node starts a tcp listener, a secure channel listener, and an echoer worker.
 // It then runs forever waiting for messages.
-use hello_ockam::Echoer;
 use ockam::abac::AbacAccessControl;
 use ockam::access_control::AllowAll;
-use ockam::identity::{SecureChannelListenerOptions, Vault};
+use ockam::identity::{IdentitiesAttributes, PeerAttributes, SecureChannelListenerOptions, Vault};
 use ockam::vault::{EdDSACurve25519SecretKey, SigningSecret, SoftwareVaultForSigning};
-use ockam::{Context, Result, TcpListenerOptions};
+use ockam::{Context, Result, Routed, TcpListenerOptions, Worker};
 use ockam::{Node, TcpTransportExtension};
 use ockam_api::enroll::enrollment::Enrollment;
 use ockam_api::nodes::NodeManager;
 use ockam_api::DefaultAddress;
 use ockam_multiaddr::MultiAddr;
+use std::sync::Arc;
+
+/// An echoer printing the attributes attested by the credential issuer
+/// for the identity sending each message
+struct Echoer {
+    identities_attributes: Arc<IdentitiesAttributes>,
+}
+
+#[ockam::worker]
+impl Worker for Echoer {
+    type Context = Context;
+    type Message = String;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        // The attributes were verified when the client presented its credential on the secure channel
+        if let Some(attributes) = msg.peer_attributes(&self.identities_attributes).await? {
+            println!("Sender attributes: {attributes}");
+        }
+        println!("Address: {}, Received: {:?}", ctx.address(), msg);
+
+        // Echo the message body back on its return_route.
+        ctx.send(msg.return_route(), msg.into_body()?).await
+    }
+}
 
 #[ockam::node]
 async fn main(ctx: Context) -> Result<()> {
@@ -86,7 +109,10 @@ async fn main(ctx: Context) -> Result<()> {
         &sc_listener_options.spawner_flow_control_id(),
     );
     let allow_production = AbacAccessControl::create(node.identities_attributes(), issuer, "cluster", "production");
-    node.start_worker_with_access_control(DefaultAddress::ECHO_SERVICE, Echoer, allow_production, AllowAll)
+    let echoer = Echoer {
+        identities_attributes: node.identities_attributes(),
+    };
+    node.start_worker_with_access_control(DefaultAddress::ECHO_SERVICE, echoer, allow_production, AllowAll)
         .await?;
 
     // Start a secure channel listener that only allows channels with
@@ -86,7 +86,11 @@ pub use ockam_transport_tcp::{
     TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
     TcpTransportExtension,
 };
-pub use relay_service::{RelayService, RelayServiceOptions};
+pub use relay_service::{
+    RelayAliasOwner, RelayAliasOwnership, RelayAliasRegistration, RelayAliasesMemoryRepository,
+    RelayAliasesRepository, RelayService, RelayServiceOptions, DEFAULT_RELAY_ALIAS_EXPIRATION,
+    RELAY_ADMIN_ATTRIBUTE, RELAY_DENIED_PREFIX, RELAY_RELEASE_PREFIX,
+};
 
 // ---
 
@@ -1,7 +1,9 @@
 mod options;
+mod ownership;
 mod relay;
 #[allow(clippy::module_inception)]
 mod relay_service;
 
 pub use options::*;
+pub use ownership::*;
 pub use relay_service::*;
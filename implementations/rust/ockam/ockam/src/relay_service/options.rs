@@ -3,12 +3,15 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl};
 
+use crate::relay_service::RelayAliasOwnership;
+
 /// Trust Options for a Forwarding Service
 pub struct RelayServiceOptions {
     pub(super) service_incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(super) relays_incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(super) consumer_service: Vec<FlowControlId>,
     pub(super) consumer_relay: Vec<FlowControlId>,
+    pub(super) alias_ownership: Option<RelayAliasOwnership>,
 }
 
 impl RelayServiceOptions {
@@ -19,6 +22,7 @@ impl RelayServiceOptions {
             relays_incoming_access_control: Arc::new(AllowAll),
             consumer_service: vec![],
             consumer_relay: vec![],
+            alias_ownership: None,
         }
     }
 
@@ -72,6 +76,13 @@ impl RelayServiceOptions {
         self
     }
 
+    /// Enforce the ownership of the registered aliases.
+    /// By default, any alias can be registered by anyone
+    pub fn with_alias_ownership(mut self, alias_ownership: RelayAliasOwnership) -> Self {
+        self.alias_ownership = Some(alias_ownership);
+        self
+    }
+
     pub(super) fn setup_flow_control_for_relay_service(
         &self,
         flow_controls: &FlowControls,
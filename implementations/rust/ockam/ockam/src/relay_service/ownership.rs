@@ -0,0 +1,219 @@
+use core::time::Duration;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{async_trait, Result};
+use ockam_identity::utils::now;
+use ockam_identity::{Identifier, IdentitiesAttributes, TimestampInSeconds};
+
+/// Attribute granting the right to register or release an alias owned by another identity.
+/// Its value must be `true`
+pub const RELAY_ADMIN_ATTRIBUTE: &str = "relay-admin";
+
+/// Prefix of the payload sent to a [`RelayService`](crate::RelayService) to release an alias
+pub const RELAY_RELEASE_PREFIX: &str = "release:";
+
+/// Prefix of the reply sent by a [`RelayService`](crate::RelayService) when an alias can't be released
+pub const RELAY_DENIED_PREFIX: &str = "denied:";
+
+/// Default time after which the ownership of an alias which is not registered again, and whose
+/// relay was stopped, expires
+pub const DEFAULT_RELAY_ALIAS_EXPIRATION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Identity owning a relay alias
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayAliasOwner {
+    /// Alias of the relay
+    pub alias: String,
+    /// Identity which registered the alias first
+    pub identifier: Identifier,
+    /// Time of the last registration of the alias by its owner
+    pub registered_at: TimestampInSeconds,
+}
+
+/// This trait supports the persistence of the owners of relay aliases
+#[async_trait]
+pub trait RelayAliasesRepository: Send + Sync + 'static {
+    /// Return the owner of an alias
+    async fn get_alias_owner(&self, alias: &str) -> Result<Option<RelayAliasOwner>>;
+
+    /// Set the owner of an alias, replacing the previous owner
+    async fn store_alias_owner(&self, owner: &RelayAliasOwner) -> Result<()>;
+
+    /// Remove the owner of an alias. Return false if the alias had no owner
+    async fn delete_alias_owner(&self, alias: &str) -> Result<bool>;
+
+    /// Return the owners of all the aliases, sorted by alias
+    async fn get_alias_owners(&self) -> Result<Vec<RelayAliasOwner>>;
+}
+
+/// Implementation of [`RelayAliasesRepository`] keeping the owners in memory
+#[derive(Clone, Default)]
+pub struct RelayAliasesMemoryRepository {
+    owners: Arc<Mutex<BTreeMap<String, RelayAliasOwner>>>,
+}
+
+impl RelayAliasesMemoryRepository {
+    /// Create a new empty repository
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RelayAliasesRepository for RelayAliasesMemoryRepository {
+    async fn get_alias_owner(&self, alias: &str) -> Result<Option<RelayAliasOwner>> {
+        Ok(self.owners.lock().unwrap().get(alias).cloned())
+    }
+
+    async fn store_alias_owner(&self, owner: &RelayAliasOwner) -> Result<()> {
+        self.owners
+            .lock()
+            .unwrap()
+            .insert(owner.alias.clone(), owner.clone());
+        Ok(())
+    }
+
+    async fn delete_alias_owner(&self, alias: &str) -> Result<bool> {
+        Ok(self.owners.lock().unwrap().remove(alias).is_some())
+    }
+
+    async fn get_alias_owners(&self) -> Result<Vec<RelayAliasOwner>> {
+        Ok(self.owners.lock().unwrap().values().cloned().collect())
+    }
+}
+
+/// Outcome of the registration of an alias
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayAliasRegistration {
+    /// The alias had no owner, the identity is now its owner
+    New,
+    /// The alias was registered again by its owner
+    Renewed,
+    /// The alias is now owned by the identity, instead of its previous owner.
+    /// This happens when the previous registration expired, or when the identity is an admin
+    TakenOver(Identifier),
+    /// The alias is owned by another identity
+    Denied(Identifier),
+}
+
+impl RelayAliasRegistration {
+    /// Return true if the alias can be used by the identity
+    pub fn is_allowed(&self) -> bool {
+        !matches!(self, RelayAliasRegistration::Denied(_))
+    }
+}
+
+/// Ownership of the aliases registered with a [`RelayService`](crate::RelayService).
+///
+/// The first identity registering an alias becomes its owner. Another identity can only
+/// register the same alias if:
+///
+///  - its attributes, attested by the authority, grant it the [`RELAY_ADMIN_ATTRIBUTE`]
+///  - the relay of the alias was stopped, and the owner did not register the alias again
+///    during the expiration period
+///  - the owner released the alias
+///
+#[derive(Clone)]
+pub struct RelayAliasOwnership {
+    repository: Arc<dyn RelayAliasesRepository>,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    authority: Identifier,
+    expiration: Duration,
+}
+
+impl RelayAliasOwnership {
+    /// Create the ownership of aliases, with admins attested by the given authority
+    pub fn new(
+        repository: Arc<dyn RelayAliasesRepository>,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        authority: Identifier,
+    ) -> Self {
+        Self {
+            repository,
+            identities_attributes,
+            authority,
+            expiration: DEFAULT_RELAY_ALIAS_EXPIRATION,
+        }
+    }
+
+    /// Set the time after which the ownership of an alias which is not registered again,
+    /// and whose relay was stopped, expires
+    pub fn with_expiration(mut self, expiration: Duration) -> Self {
+        self.expiration = expiration;
+        self
+    }
+
+    /// Register an alias for an identity. The identity becomes the owner of the alias,
+    /// unless the alias is owned by another identity.
+    ///
+    /// `relay_alive` must be true when the relay of the alias is still running: the ownership
+    /// of the alias doesn't expire then, since relays are not necessarily registered again
+    pub async fn register(
+        &self,
+        alias: &str,
+        identifier: &Identifier,
+        relay_alive: bool,
+    ) -> Result<RelayAliasRegistration> {
+        let now = now()?;
+        let registration = match self.repository.get_alias_owner(alias).await? {
+            None => RelayAliasRegistration::New,
+            Some(owner) if &owner.identifier == identifier => RelayAliasRegistration::Renewed,
+            Some(owner) => {
+                if (!relay_alive && self.is_expired(&owner, now))
+                    || self.is_admin(identifier).await?
+                {
+                    RelayAliasRegistration::TakenOver(owner.identifier)
+                } else {
+                    return Ok(RelayAliasRegistration::Denied(owner.identifier));
+                }
+            }
+        };
+
+        self.repository
+            .store_alias_owner(&RelayAliasOwner {
+                alias: alias.to_string(),
+                identifier: identifier.clone(),
+                registered_at: now,
+            })
+            .await?;
+        Ok(registration)
+    }
+
+    /// Release an alias so that it can be registered by another identity.
+    /// Return false if the alias is owned by another identity and the identity is not an admin
+    pub async fn release(&self, alias: &str, identifier: &Identifier) -> Result<bool> {
+        let owner = match self.repository.get_alias_owner(alias).await? {
+            Some(owner) => owner,
+            None => return Ok(true),
+        };
+        if &owner.identifier != identifier && !self.is_admin(identifier).await? {
+            return Ok(false);
+        }
+        self.repository.delete_alias_owner(alias).await?;
+        Ok(true)
+    }
+
+    /// Return the owners of all the aliases
+    pub async fn owners(&self) -> Result<Vec<RelayAliasOwner>> {
+        self.repository.get_alias_owners().await
+    }
+
+    /// Return true if the attributes of the identity grant the [`RELAY_ADMIN_ATTRIBUTE`]
+    async fn is_admin(&self, identifier: &Identifier) -> Result<bool> {
+        let attributes = self
+            .identities_attributes
+            .get_attributes(identifier, &self.authority)
+            .await?;
+        Ok(attributes
+            .and_then(|a| a.attrs().get(RELAY_ADMIN_ATTRIBUTE.as_bytes()).cloned())
+            .map(|value| value == b"true")
+            .unwrap_or(false))
+    }
+
+    fn is_expired(&self, owner: &RelayAliasOwner, now: TimestampInSeconds) -> bool {
+        owner.registered_at + self.expiration < now
+    }
+}
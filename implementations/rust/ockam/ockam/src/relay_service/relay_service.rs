@@ -1,9 +1,28 @@
 use crate::relay_service::relay::Relay;
+use crate::relay_service::{RelayAliasRegistration, RELAY_DENIED_PREFIX, RELAY_RELEASE_PREFIX};
 use crate::{Context, RelayServiceOptions};
 use core::str::from_utf8;
+use core::time::Duration;
 use ockam_core::compat::boxed::Box;
-use ockam_core::{Address, Any, DenyAll, Result, Routed, Worker};
+use ockam_core::compat::collections::BTreeSet;
+use ockam_core::compat::string::String;
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{
+    Address, AllowAll, AllowOnwardAddress, Any, DenyAll, Error, Mailboxes, OutgoingAccessControl,
+    Result, Route, Routed, Worker,
+};
+use ockam_identity::{Identifier, IdentitySecureChannelLocalInfo};
 use ockam_node::WorkerBuilder;
+#[cfg(feature = "std")]
+use ockam_node::{NodeEvent, NodeEventKind};
+use tracing::{info, warn};
+
+/// Interval between the checks that a stopped relay released its address
+const STOP_RELAY_CHECK_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Maximum number of checks that a stopped relay released its address
+const STOP_RELAY_MAX_CHECKS: usize = 500;
 
 /// Alias worker to register remote workers under local names.
 ///
@@ -13,6 +32,8 @@ use ockam_node::WorkerBuilder;
 #[non_exhaustive]
 pub struct RelayService {
     options: RelayServiceOptions,
+    /// Addresses of the relays created with an alias
+    relays: BTreeSet<Address>,
 }
 
 impl RelayService {
@@ -28,7 +49,10 @@ impl RelayService {
 
         let service_incoming_access_control = options.service_incoming_access_control.clone();
 
-        let s = Self { options };
+        let s = Self {
+            options,
+            relays: BTreeSet::new(),
+        };
 
         WorkerBuilder::new(s)
             .with_address(address)
@@ -52,18 +76,40 @@ impl Worker for RelayService {
         msg: Routed<Self::Message>,
     ) -> Result<()> {
         let forward_route = msg.return_route();
+        let identifier = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
         let payload = msg.payload();
 
-        let random_address = Address::random_tagged("Relay.service");
-
         // TODO: assume that the first byte is length, ignore it.
         // We have to improve this actually parse the payload.
-        let address = match payload.get(1..) {
-            Some(address) => match from_utf8(address) {
-                Ok(v) if v != "register" => Address::from_string(v),
-                _ => random_address,
-            },
-            None => random_address,
+        let alias = payload
+            .get(1..)
+            .and_then(|alias| from_utf8(alias).ok())
+            .filter(|alias| *alias != "register");
+
+        // Release requests are only handled when the ownership of the aliases is enforced,
+        // otherwise the payload is a regular alias
+        if let Some(alias) = alias
+            .filter(|_| self.options.alias_ownership.is_some())
+            .and_then(|a| a.strip_prefix(RELAY_RELEASE_PREFIX))
+        {
+            return self
+                .release(ctx, alias, identifier.as_ref(), forward_route)
+                .await;
+        }
+
+        let (address, is_alias) = match alias {
+            Some(alias) => {
+                if !self.register(ctx, alias, identifier.as_ref()).await? {
+                    // the relay is notified that its registration was denied
+                    return self
+                        .reply(ctx, forward_route, format!("{RELAY_DENIED_PREFIX}{alias}"))
+                        .await;
+                }
+                (Address::from_string(alias), true)
+            }
+            None => (Address::random_tagged("Relay.service"), false),
         };
 
         self.options
@@ -71,13 +117,138 @@ impl Worker for RelayService {
 
         Relay::create(
             ctx,
-            address,
+            address.clone(),
             forward_route,
             payload.to_vec(),
             self.options.relays_incoming_access_control.clone(),
         )
         .await?;
 
+        if is_alias {
+            self.relays.insert(address);
+        }
         Ok(())
     }
 }
+
+impl RelayService {
+    /// Check that the alias can be registered by the identity when the ownership of the
+    /// aliases is enforced. The relay of the alias is replaced by the new registration
+    async fn register(
+        &mut self,
+        ctx: &Context,
+        alias: &str,
+        identifier: Option<&Identifier>,
+    ) -> Result<bool> {
+        let alias_ownership = match self.options.alias_ownership.clone() {
+            Some(alias_ownership) => alias_ownership,
+            None => return Ok(true),
+        };
+        let identifier = match identifier {
+            Some(identifier) => identifier,
+            None => {
+                self.deny(
+                    ctx,
+                    alias,
+                    "the alias was not registered through a secure channel",
+                );
+                return Ok(false);
+            }
+        };
+
+        let address = Address::from_string(alias);
+        let relay_alive =
+            self.relays.contains(&address) && ctx.list_workers().await?.contains(&address);
+        match alias_ownership
+            .register(alias, identifier, relay_alive)
+            .await?
+        {
+            RelayAliasRegistration::Denied(owner) => {
+                self.deny(ctx, alias, &format!("the alias is owned by {owner}"));
+                return Ok(false);
+            }
+            RelayAliasRegistration::TakenOver(previous) => {
+                info!(%alias, %previous, %identifier, "The relay alias was taken over");
+            }
+            RelayAliasRegistration::New | RelayAliasRegistration::Renewed => {}
+        }
+        self.stop_relay(ctx, &address).await?;
+        Ok(true)
+    }
+
+    /// Release an alias, and stop its relay, if the identity is allowed to.
+    /// The reply is the release request, or a denial
+    async fn release(
+        &mut self,
+        ctx: &Context,
+        alias: &str,
+        identifier: Option<&Identifier>,
+        return_route: Route,
+    ) -> Result<()> {
+        let released = match (self.options.alias_ownership.clone(), identifier) {
+            (Some(alias_ownership), Some(identifier)) => {
+                alias_ownership.release(alias, identifier).await?
+            }
+            _ => false,
+        };
+
+        let reply = if released {
+            info!(%alias, "The relay alias was released");
+            self.stop_relay(ctx, &Address::from_string(alias)).await?;
+            format!("{RELAY_RELEASE_PREFIX}{alias}")
+        } else {
+            self.deny(ctx, alias, "the alias can't be released");
+            format!("{RELAY_DENIED_PREFIX}{alias}")
+        };
+        self.reply(ctx, return_route, reply).await
+    }
+
+    /// Send a reply to the sender of a request
+    async fn reply(&self, ctx: &Context, return_route: Route, reply: String) -> Result<()> {
+        // The service itself can't send messages, the reply is sent by a dedicated context
+        let outgoing_access_control: Arc<dyn OutgoingAccessControl> = if return_route.len() == 1 {
+            Arc::new(AllowAll)
+        } else {
+            Arc::new(AllowOnwardAddress(return_route.next()?.clone()))
+        };
+        let child_ctx = ctx
+            .new_detached_with_mailboxes(Mailboxes::main(
+                Address::random_tagged("Relay.service.reply"),
+                Arc::new(DenyAll),
+                outgoing_access_control,
+            ))
+            .await?;
+        child_ctx.send(return_route, reply).await
+    }
+
+    /// Stop the relay registered with an alias, if there is one, and wait until its
+    /// address can be used again
+    async fn stop_relay(&mut self, ctx: &Context, address: &Address) -> Result<()> {
+        // other workers of the node using the same address are never stopped
+        if !self.relays.remove(address) || ctx.stop_worker(address.clone()).await.is_err() {
+            return Ok(());
+        }
+        for _ in 0..STOP_RELAY_MAX_CHECKS {
+            if !ctx.list_workers().await?.contains(address) {
+                return Ok(());
+            }
+            ctx.sleep(STOP_RELAY_CHECK_INTERVAL).await;
+        }
+        Err(Error::new(
+            Origin::Ockam,
+            Kind::Timeout,
+            "the relay of the alias could not be stopped",
+        ))
+    }
+
+    fn deny(&self, ctx: &Context, alias: &str, reason: &str) {
+        warn!(%alias, "The relay alias was denied: {reason}");
+
+        #[cfg(feature = "std")]
+        ctx.node_events().publish(
+            NodeEvent::new(NodeEventKind::PolicyDenied, alias).with_detail("reason", reason),
+        );
+        #[cfg(not(feature = "std"))]
+        let _ = ctx;
+    }
+}
@@ -0,0 +1,96 @@
+use ockam_core::compat::vec::Vec;
+use ockam_core::Address;
+
+/// Restrict the services which can be reached through a [`RemoteRelay`](super::RemoteRelay).
+///
+/// The service of a relayed message is the first address of its onward route once the relay
+/// address has been removed. When some services are allowed, only those services can be
+/// reached. Denied services can never be reached. By default, all the services can be reached.
+///
+/// Only the first address is checked: a message sent through a secure channel is checked
+/// against the address of the secure channel, not against the service it is decrypted for.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteRelayFilter {
+    allowed_services: Vec<Address>,
+    denied_services: Vec<Address>,
+}
+
+impl RemoteRelayFilter {
+    /// Create a filter letting all the messages through
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a filter from lists of allowed and denied services
+    pub fn from_lists(allowed_services: Vec<Address>, denied_services: Vec<Address>) -> Self {
+        Self {
+            allowed_services,
+            denied_services,
+        }
+    }
+
+    /// Allow a service. Once a service is allowed, the services which are not allowed are denied
+    pub fn allow(mut self, service: impl Into<Address>) -> Self {
+        self.allowed_services.push(service.into());
+        self
+    }
+
+    /// Deny a service, even if it is also allowed
+    pub fn deny(mut self, service: impl Into<Address>) -> Self {
+        self.denied_services.push(service.into());
+        self
+    }
+
+    /// Services which can be reached. All the services can be reached if the list is empty
+    pub fn allowed_services(&self) -> &[Address] {
+        &self.allowed_services
+    }
+
+    /// Services which can't be reached
+    pub fn denied_services(&self) -> &[Address] {
+        &self.denied_services
+    }
+
+    /// Return true if the filter lets all the messages through
+    pub fn is_empty(&self) -> bool {
+        self.allowed_services.is_empty() && self.denied_services.is_empty()
+    }
+
+    /// Return true if a message can be forwarded to the given service
+    pub fn is_allowed(&self, service: &Address) -> bool {
+        (self.allowed_services.is_empty() || self.allowed_services.contains(service))
+            && !self.denied_services.contains(service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter() {
+        let api = Address::from_string("api");
+        let uppercase = Address::from_string("uppercase");
+        let node_manager = Address::from_string("_internal.nodemanager");
+
+        // all the services are allowed by default
+        let filter = RemoteRelayFilter::new();
+        assert!(filter.is_empty());
+        assert!(filter.is_allowed(&node_manager));
+
+        // only the allowed services can be reached
+        let filter = RemoteRelayFilter::new().allow("api").allow("uppercase");
+        assert!(filter.is_allowed(&api));
+        assert!(filter.is_allowed(&uppercase));
+        assert!(!filter.is_allowed(&node_manager));
+
+        // a denied service can't be reached, even if it is allowed
+        let filter = filter.deny("uppercase");
+        assert!(filter.is_allowed(&api));
+        assert!(!filter.is_allowed(&uppercase));
+
+        let filter = RemoteRelayFilter::new().deny("_internal.nodemanager");
+        assert!(filter.is_allowed(&api));
+        assert!(!filter.is_allowed(&node_manager));
+    }
+}
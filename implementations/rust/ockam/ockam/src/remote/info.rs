@@ -45,3 +45,12 @@ impl RemoteRelayInfo {
         &self.flow_control_id
     }
 }
+
+/// Outcome of the registration of a RemoteRelay, sent to the caller creating it
+#[derive(Serialize, Deserialize, Clone, Debug, Message)]
+pub(super) enum RemoteRelayRegistration {
+    /// The relay was registered
+    Registered(RemoteRelayInfo),
+    /// The relay service refused to register the alias
+    Denied,
+}
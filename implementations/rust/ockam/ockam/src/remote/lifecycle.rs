@@ -1,5 +1,8 @@
-use crate::remote::{Addresses, RemoteRelay, RemoteRelayInfo, RemoteRelayOptions};
-use crate::Context;
+use crate::remote::info::RemoteRelayRegistration;
+use crate::remote::{
+    Addresses, RemoteRelay, RemoteRelayFilter, RemoteRelayInfo, RemoteRelayOptions,
+};
+use crate::{Context, OckamError};
 use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{
@@ -72,6 +75,7 @@ impl RemoteRelay {
         flow_control_id: Option<FlowControlId>,
         heartbeat: Option<DelayedEvent<Vec<u8>>>,
         heartbeat_interval: Duration,
+        filter: RemoteRelayFilter,
     ) -> Self {
         Self {
             addresses,
@@ -81,6 +85,7 @@ impl RemoteRelay {
             flow_control_id,
             heartbeat,
             heartbeat_interval,
+            filter,
         }
     }
 
@@ -118,9 +123,11 @@ impl RemoteRelay {
             flow_control_id,
             Some(heartbeat),
             Duration::from_secs(5),
+            options.filter().clone(),
         );
 
         debug!("Starting static RemoteRelay at {}", &addresses.heartbeat);
+        let relay_address = addresses.main_internal.clone();
         let mailboxes = Self::mailboxes(
             addresses,
             Some(heartbeat_source_address),
@@ -130,8 +137,11 @@ impl RemoteRelay {
             .with_mailboxes(mailboxes)
             .start(ctx)
             .await?;
+        // the relay is stopped if its registration fails or is cancelled
+        let stop_on_drop = ctx.stop_worker_on_drop(relay_address);
 
-        let resp = child_ctx.receive::<RemoteRelayInfo>().await?.into_body()?;
+        let resp = Self::registration(&mut child_ctx).await?;
+        stop_on_drop.disarm();
 
         Ok(resp)
     }
@@ -166,22 +176,24 @@ impl RemoteRelay {
             flow_control_id,
             None,
             Duration::from_secs(10),
+            options.filter().clone(),
         );
 
         debug!(
             "Starting ephemeral RemoteRelay at {}",
             &addresses.main_internal
         );
+        let relay_address = addresses.main_internal.clone();
         let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
         WorkerBuilder::new(relay)
             .with_mailboxes(mailboxes)
             .start(ctx)
             .await?;
+        // the relay is stopped if its registration fails or is cancelled
+        let stop_on_drop = ctx.stop_worker_on_drop(relay_address);
 
-        let resp = callback_ctx
-            .receive::<RemoteRelayInfo>()
-            .await?
-            .into_body()?;
+        let resp = Self::registration(&mut callback_ctx).await?;
+        stop_on_drop.disarm();
 
         Ok(resp)
     }
@@ -220,23 +232,38 @@ impl RemoteRelay {
             flow_control_id,
             None,
             Duration::from_secs(10),
+            options.filter().clone(),
         );
 
         debug!(
             "Starting static RemoteRelay without heartbeats at {}",
             &addresses.main_internal
         );
+        let relay_address = addresses.main_internal.clone();
         let mailboxes = Self::mailboxes(addresses, None, outgoing_access_control);
         WorkerBuilder::new(relay)
             .with_mailboxes(mailboxes)
             .start(ctx)
             .await?;
+        // the relay is stopped if its registration fails or is cancelled
+        let stop_on_drop = ctx.stop_worker_on_drop(relay_address);
 
-        let resp = callback_ctx
-            .receive::<RemoteRelayInfo>()
-            .await?
-            .into_body()?;
+        let resp = Self::registration(&mut callback_ctx).await?;
+        stop_on_drop.disarm();
 
         Ok(resp)
     }
+
+    /// Wait for the outcome of the registration of the relay.
+    /// Return an [`OckamError::RelayAliasDenied`] error if the relay service refused it
+    async fn registration(callback_ctx: &mut Context) -> Result<RemoteRelayInfo> {
+        match callback_ctx
+            .receive::<RemoteRelayRegistration>()
+            .await?
+            .into_body()?
+        {
+            RemoteRelayRegistration::Registered(info) => Ok(info),
+            RemoteRelayRegistration::Denied => Err(OckamError::RelayAliasDenied)?,
+        }
+    }
 }
@@ -2,11 +2,13 @@
 //! which allows other nodes forward messages to local workers on this node using that alias.
 
 mod addresses;
+mod filter;
 mod info;
 mod lifecycle;
 mod options;
 mod worker;
 
+pub use filter::*;
 pub use info::*;
 pub use options::*;
 
@@ -28,4 +30,6 @@ pub struct RemoteRelay {
     // We only use Heartbeat for static RemoteRelay
     heartbeat: Option<DelayedEvent<Vec<u8>>>,
     heartbeat_interval: Duration,
+    /// Services which can be reached through this relay
+    filter: RemoteRelayFilter,
 }
@@ -1,10 +1,12 @@
-use crate::remote::Addresses;
+use crate::remote::{Addresses, RemoteRelayFilter};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, AllowAll, OutgoingAccessControl};
 
 /// Trust options for [`RemoteRelay`](super::RemoteRelay)
-pub struct RemoteRelayOptions {}
+pub struct RemoteRelayOptions {
+    filter: RemoteRelayFilter,
+}
 
 impl RemoteRelayOptions {
     /// Usually [`FlowControlId`] should be shared with the Producer that was used to create this
@@ -15,7 +17,20 @@ impl RemoteRelayOptions {
     /// through the [`RemoteRelay`](super::RemoteRelay) through the same Secure Channel.
     #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
-        Self {}
+        Self {
+            filter: RemoteRelayFilter::new(),
+        }
+    }
+
+    /// Restrict the services which can be reached through the relay
+    pub fn with_filter(mut self, filter: RemoteRelayFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Services which can be reached through the relay
+    pub fn filter(&self) -> &RemoteRelayFilter {
+        &self.filter
     }
 
     pub(super) fn setup_flow_control(
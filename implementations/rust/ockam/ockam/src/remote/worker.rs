@@ -1,12 +1,17 @@
+use crate::relay_service::RELAY_DENIED_PREFIX;
+use crate::remote::info::RemoteRelayRegistration;
 use crate::remote::{RemoteRelay, RemoteRelayInfo};
 use crate::{Context, OckamError};
+use ockam_core::api::{Error as ApiError, Id, RequestHeader, Response};
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use ockam_core::{Any, Decodable, Result, Routed, Worker};
-use tracing::{debug, info};
+use ockam_core::{Address, Any, Decodable, LocalMessage, Result, Routed, Worker};
+#[cfg(feature = "std")]
+use ockam_node::{NodeEvent, NodeEventKind};
+use tracing::{debug, info, warn};
 
 #[crate::worker]
 impl Worker for RemoteRelay {
@@ -60,6 +65,10 @@ impl Worker for RemoteRelay {
                         .map_err(|_| OckamError::InvalidHubResponse)?;
                     let payload =
                         String::from_utf8(payload).map_err(|_| OckamError::InvalidHubResponse)?;
+                    let denied = payload.strip_prefix(RELAY_DENIED_PREFIX);
+                    if denied == Some(self.registration_payload.as_str()) {
+                        return self.denied(ctx).await;
+                    }
                     // using ends_with() instead of == to allow for prefixes
                     if !payload.ends_with(&self.registration_payload) {
                         return Err(OckamError::InvalidHubResponse)?;
@@ -75,12 +84,12 @@ impl Worker for RemoteRelay {
 
                         ctx.send_from_address(
                             self.addresses.completion_callback.clone(),
-                            RemoteRelayInfo::new(
+                            RemoteRelayRegistration::Registered(RemoteRelayInfo::new(
                                 return_route,
                                 address,
                                 self.addresses.main_remote.clone(),
                                 self.flow_control_id.clone(),
-                            ),
+                            )),
                             self.addresses.main_remote.clone(),
                         )
                         .await?;
@@ -100,6 +109,10 @@ impl Worker for RemoteRelay {
                     // to exploit it in any way
                     return Err(OckamError::UnknownForwarderNextHopAddress)?;
                 }
+                Ok(next) if !self.filter.is_allowed(next) => {
+                    let service = next.clone();
+                    self.deny(ctx, &service, local_message).await
+                }
                 Ok(_) => {
                     // Forwarding the message
                     debug!("RemoteRelay received payload message");
@@ -122,3 +135,61 @@ impl Worker for RemoteRelay {
         }
     }
 }
+
+impl RemoteRelay {
+    /// Notify the caller that the relay service refused to register the alias.
+    /// A registration renewed by a heartbeat is not retried once denied
+    async fn denied(&mut self, ctx: &Context) -> Result<()> {
+        warn!(alias = %self.registration_payload, "The registration of the RemoteRelay was denied");
+        if let Some(heartbeat) = &mut self.heartbeat {
+            heartbeat.cancel();
+        }
+        if self.completion_msg_sent {
+            return Ok(());
+        }
+        self.completion_msg_sent = true;
+        ctx.send_from_address(
+            self.addresses.completion_callback.clone(),
+            RemoteRelayRegistration::Denied,
+            self.addresses.main_remote.clone(),
+        )
+        .await
+    }
+
+    /// Drop a message sent to a service which can't be reached through this relay,
+    /// and return a forbidden response to its sender
+    async fn deny(
+        &self,
+        ctx: &Context,
+        service: &Address,
+        local_message: LocalMessage,
+    ) -> Result<()> {
+        let return_route = local_message.return_route();
+        warn!(%service, %return_route, "RemoteRelay denied a message sent to a service which is not allowed");
+
+        #[cfg(feature = "std")]
+        ctx.node_events().publish(
+            NodeEvent::new(
+                NodeEventKind::PolicyDenied,
+                self.registration_payload.as_str(),
+            )
+            .with_detail("service", service)
+            .with_detail("source", &return_route),
+        );
+
+        // the request is answered with an API error when the message is a request
+        let message = format!("The service {service} can't be reached through this relay");
+        let request = Vec::<u8>::decode(local_message.payload_ref())
+            .ok()
+            .and_then(|body| minicbor::decode::<RequestHeader>(&body).ok());
+        let response = match request {
+            Some(request) => Response::forbidden(&request, &message).to_vec()?,
+            None => Response::forbidden_no_request(Id::default())
+                .body(ApiError::new_without_path().with_message(&message))
+                .to_vec()?,
+        };
+
+        ctx.send_from_address(return_route, response, self.addresses.main_remote.clone())
+            .await
+    }
+}
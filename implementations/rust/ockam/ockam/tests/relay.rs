@@ -1,10 +1,19 @@
-use ockam::identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
-use ockam::remote::{RemoteRelay, RemoteRelayOptions};
+use ockam::identity::{
+    secure_channels, AttributesEntry, Identifier, SecureChannel, SecureChannelListenerOptions,
+    SecureChannelOptions, SecureChannels,
+};
+use ockam::remote::{RemoteRelay, RemoteRelayFilter, RemoteRelayInfo, RemoteRelayOptions};
 use ockam::workers::Echoer;
-use ockam::{RelayService, RelayServiceOptions};
+use ockam::{
+    RelayAliasOwnership, RelayAliasesMemoryRepository, RelayService, RelayServiceOptions,
+    RELAY_ADMIN_ATTRIBUTE, RELAY_DENIED_PREFIX, RELAY_RELEASE_PREFIX,
+};
+use ockam_core::api::{Request, Response, Status};
+use ockam_core::errcode::Kind;
 use ockam_core::{route, AllowAll, Result};
-use ockam_node::{Context, MessageReceiveOptions};
+use ockam_node::{Context, MessageReceiveOptions, NodeEventKind};
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+use std::sync::Arc;
 use std::time::Duration;
 
 // Node creates a Relay service and a Remote Relay, Echoer is reached through the Relay. No flow control
@@ -250,3 +259,279 @@ async fn test4(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+// Node creates a Relay service and Remote Relays with and without a filter.
+// Only the allowed services can be reached through the filtered Relay
+#[ockam_macros::test]
+async fn test5(ctx: &mut Context) -> Result<()> {
+    RelayService::create(ctx, "forwarding_service", RelayServiceOptions::new()).await?;
+
+    ctx.start_worker("echoer", Echoer).await?;
+    ctx.start_worker("other", Echoer).await?;
+
+    // without a filter, all the services can be reached
+    let unfiltered = RemoteRelay::create(ctx, route![], RemoteRelayOptions::new()).await?;
+    for service in ["echoer", "other"] {
+        let resp = ctx
+            .send_and_receive::<String>(
+                route![unfiltered.remote_address(), service],
+                "Hello".to_string(),
+            )
+            .await?;
+        assert_eq!(resp, "Hello");
+    }
+
+    let options = RemoteRelayOptions::new().with_filter(RemoteRelayFilter::new().allow("echoer"));
+    let filtered = RemoteRelay::create(ctx, route![], options).await?;
+
+    // an allowed service can be reached
+    let resp = ctx
+        .send_and_receive::<String>(
+            route![filtered.remote_address(), "echoer"],
+            "Hello".to_string(),
+        )
+        .await?;
+    assert_eq!(resp, "Hello");
+
+    // a request to another service is answered with a forbidden response
+    let mut events = ctx.node_events().subscribe();
+    let request = Request::get("/");
+    let resp = ctx
+        .send_and_receive::<Vec<u8>>(
+            route![filtered.remote_address(), "other"],
+            request.to_vec()?,
+        )
+        .await?;
+    let (header, _) = Response::parse_response_header(&resp)?;
+    assert_eq!(header.status(), Some(Status::Forbidden));
+    assert_eq!(header.re(), request.header().id());
+
+    let event = events
+        .try_recv()
+        .expect("the denied message must be recorded");
+    assert_eq!(event.kind(), NodeEventKind::PolicyDenied);
+    assert_eq!(event.details().get("service").unwrap(), "0#other");
+
+    // any other message is answered with a forbidden response as well
+    let resp = ctx
+        .send_and_receive::<Vec<u8>>(
+            route![filtered.remote_address(), "other"],
+            "Hello".to_string(),
+        )
+        .await?;
+    let (header, _) = Response::parse_response_header(&resp)?;
+    assert_eq!(header.status(), Some(Status::Forbidden));
+    Ok(())
+}
+
+// Node creates a Relay service and a Remote Relay denying a service.
+// The other services can still be reached
+#[ockam_macros::test]
+async fn test6(ctx: &mut Context) -> Result<()> {
+    RelayService::create(ctx, "forwarding_service", RelayServiceOptions::new()).await?;
+
+    ctx.start_worker("echoer", Echoer).await?;
+    ctx.start_worker("other", Echoer).await?;
+
+    let options = RemoteRelayOptions::new().with_filter(RemoteRelayFilter::new().deny("other"));
+    let remote_info = RemoteRelay::create(ctx, route![], options).await?;
+
+    let resp = ctx
+        .send_and_receive::<String>(
+            route![remote_info.remote_address(), "echoer"],
+            "Hello".to_string(),
+        )
+        .await?;
+    assert_eq!(resp, "Hello");
+
+    let resp = ctx
+        .send_and_receive::<Vec<u8>>(
+            route![remote_info.remote_address(), "other"],
+            "Hello".to_string(),
+        )
+        .await?;
+    let (header, _) = Response::parse_response_header(&resp)?;
+    assert_eq!(header.status(), Some(Status::Forbidden));
+    Ok(())
+}
+
+// Node creates a Relay service enforcing the ownership of the aliases.
+// Identities register the same alias through secure channels:
+//  - the first identity owns the alias and can register it again
+//  - another identity can't register it
+//  - an admin can take it over, and release it
+#[ockam_macros::test]
+async fn test7(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let authority = identities_creation.create_identity().await?;
+
+    let listener_options = SecureChannelListenerOptions::new();
+    let alias_ownership = RelayAliasOwnership::new(
+        Arc::new(RelayAliasesMemoryRepository::new()),
+        identities.identities_attributes(),
+        authority.clone(),
+    );
+    let options = RelayServiceOptions::new()
+        .service_as_consumer(&listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&listener_options.spawner_flow_control_id())
+        .with_alias_ownership(alias_ownership.clone());
+    RelayService::create(ctx, "forwarding_service", options).await?;
+
+    let cloud = identities_creation.create_identity().await?;
+    secure_channels
+        .create_secure_channel_listener(ctx, &cloud, "cloud_listener", listener_options)
+        .await?;
+
+    // the first identity registering the alias owns it
+    let alice = identities_creation.create_identity().await?;
+    let alice_channel = create_channel(ctx, &secure_channels, &alice).await?;
+    register_shared_alias(ctx, &alice_channel).await?;
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![alice.clone()]);
+
+    // another identity can't register the alias
+    let bob = identities_creation.create_identity().await?;
+    let bob_channel = create_channel(ctx, &secure_channels, &bob).await?;
+    let mut events = ctx.node_events().subscribe();
+    assert_denied(register_shared_alias(ctx, &bob_channel).await);
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![alice.clone()]);
+    let event = events
+        .try_recv()
+        .expect("the denied registration must be recorded");
+    assert_eq!(event.kind(), NodeEventKind::PolicyDenied);
+
+    // nor release it
+    let reply = release_shared_alias(ctx, &bob_channel).await?;
+    assert_eq!(reply, format!("{RELAY_DENIED_PREFIX}shared"));
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![alice.clone()]);
+
+    // the owner can register the alias again
+    register_shared_alias(ctx, &alice_channel).await?;
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![alice.clone()]);
+
+    // an admin can take over the alias, and release it
+    let carol = identities_creation.create_identity().await?;
+    identities
+        .identities_attributes()
+        .put_attributes(
+            &carol,
+            AttributesEntry::single(
+                RELAY_ADMIN_ATTRIBUTE.as_bytes().to_vec(),
+                b"true".to_vec(),
+                None,
+                Some(authority.clone()),
+            )?,
+        )
+        .await?;
+    let carol_channel = create_channel(ctx, &secure_channels, &carol).await?;
+    register_shared_alias(ctx, &carol_channel).await?;
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![carol.clone()]);
+    assert!(ctx.list_workers().await?.contains(&"shared".into()));
+
+    let reply = release_shared_alias(ctx, &carol_channel).await?;
+    assert_eq!(reply, format!("{RELAY_RELEASE_PREFIX}shared"));
+    assert!(alias_owners(&alias_ownership).await?.is_empty());
+    assert!(!ctx.list_workers().await?.contains(&"shared".into()));
+
+    // once released, the alias can be registered by anyone
+    register_shared_alias(ctx, &bob_channel).await?;
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![bob]);
+    Ok(())
+}
+
+// Node creates a Relay service enforcing the ownership of the aliases, with a short expiration.
+// The ownership of an alias only expires once its relay is stopped
+#[ockam_macros::test]
+async fn test8(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let authority = identities_creation.create_identity().await?;
+
+    let listener_options = SecureChannelListenerOptions::new();
+    let alias_ownership = RelayAliasOwnership::new(
+        Arc::new(RelayAliasesMemoryRepository::new()),
+        identities.identities_attributes(),
+        authority,
+    )
+    .with_expiration(Duration::ZERO);
+    let options = RelayServiceOptions::new()
+        .service_as_consumer(&listener_options.spawner_flow_control_id())
+        .relay_as_consumer(&listener_options.spawner_flow_control_id())
+        .with_alias_ownership(alias_ownership.clone());
+    RelayService::create(ctx, "forwarding_service", options).await?;
+
+    let cloud = identities_creation.create_identity().await?;
+    secure_channels
+        .create_secure_channel_listener(ctx, &cloud, "cloud_listener", listener_options)
+        .await?;
+
+    let alice = identities_creation.create_identity().await?;
+    let alice_channel = create_channel(ctx, &secure_channels, &alice).await?;
+    register_shared_alias(ctx, &alice_channel).await?;
+
+    // the registration has expired but the relay of the owner is still running
+    ctx.sleep(Duration::from_millis(1100)).await;
+    let bob = identities_creation.create_identity().await?;
+    let bob_channel = create_channel(ctx, &secure_channels, &bob).await?;
+    assert_denied(register_shared_alias(ctx, &bob_channel).await);
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![alice]);
+
+    // once the relay is stopped, the alias can be taken over
+    ctx.stop_worker("shared").await?;
+    register_shared_alias(ctx, &bob_channel).await?;
+    assert_eq!(alias_owners(&alias_ownership).await?, vec![bob]);
+    Ok(())
+}
+
+/// HELPERS
+async fn create_channel(
+    ctx: &Context,
+    secure_channels: &SecureChannels,
+    identifier: &Identifier,
+) -> Result<SecureChannel> {
+    secure_channels
+        .create_secure_channel(
+            ctx,
+            identifier,
+            route!["cloud_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await
+}
+
+/// Register the alias `shared` through the secure channel
+async fn register_shared_alias(ctx: &Context, channel: &SecureChannel) -> Result<RemoteRelayInfo> {
+    RemoteRelay::create_static_without_heartbeats(
+        ctx,
+        channel.clone(),
+        "shared",
+        RemoteRelayOptions::new(),
+    )
+    .await
+}
+
+/// Check that the relay service refused to register the alias
+fn assert_denied(registration: Result<RemoteRelayInfo>) {
+    let err = registration.expect_err("the registration must be denied");
+    assert_eq!(err.code().kind, Kind::Conflict);
+}
+
+/// Release the alias `shared` through the secure channel and return the reply of the service
+async fn release_shared_alias(ctx: &Context, channel: &SecureChannel) -> Result<String> {
+    ctx.send_and_receive::<String>(
+        route![channel.clone(), "forwarding_service"],
+        format!("{RELAY_RELEASE_PREFIX}shared"),
+    )
+    .await
+}
+
+async fn alias_owners(alias_ownership: &RelayAliasOwnership) -> Result<Vec<Identifier>> {
+    Ok(alias_ownership
+        .owners()
+        .await?
+        .into_iter()
+        .map(|owner| owner.identifier)
+        .collect())
+}
@@ -8,11 +8,11 @@ use ockam_core::compat::str;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::vec;
 use ockam_core::Result;
-use ockam_core::{IncomingAccessControl, RelayMessage};
+use ockam_core::{DenyReason, IncomingAccessControl, RelayMessage};
 
 use crate::expr::str;
 use crate::Expr::*;
-use crate::{eval, Env, Expr};
+use crate::{eval, Env, EvalError, Expr};
 use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_identity::{Identifier, IdentitiesAttributes, IdentitySecureChannelLocalInfo};
@@ -34,7 +34,7 @@ pub const ABAC_IDENTIFIER_KEY: &str = "identifier";
 pub struct AbacAccessControl {
     identities_attributes: Arc<IdentitiesAttributes>,
     authority: Identifier,
-    policy_expression: Expr,
+    policy_expression: Arc<Expr>,
     environment: Env,
 }
 
@@ -47,17 +47,18 @@ impl Debug for AbacAccessControl {
 }
 
 impl AbacAccessControl {
-    /// Create a new AccessControl using a specific policy for checking attributes
+    /// Create a new AccessControl using a specific policy for checking attributes.
+    /// The policy expression can be shared with other access controls
     pub fn new(
         identities_attributes: Arc<IdentitiesAttributes>,
         authority: Identifier,
-        policy_expression: Expr,
+        policy_expression: impl Into<Arc<Expr>>,
         environment: Env,
     ) -> Self {
         Self {
             identities_attributes,
             authority,
-            policy_expression,
+            policy_expression: policy_expression.into(),
             environment,
         }
     }
@@ -84,13 +85,70 @@ impl AbacAccessControl {
         identities_attributes: Arc<IdentitiesAttributes>,
         authority: Identifier,
     ) -> AbacAccessControl {
-        AbacAccessControl::new(identities_attributes, authority, true.into(), Env::new())
+        AbacAccessControl::new(identities_attributes, authority, Bool(true), Env::new())
     }
 }
 
 impl AbacAccessControl {
     /// Returns true if the identity is authorized
     pub async fn is_identity_authorized(&self, id: Identifier) -> Result<bool> {
+        let environment = self.identity_environment(&id).await?;
+
+        // Finally, evaluate the expression and return the result:
+        match eval(&self.policy_expression, &environment) {
+            Ok(Expr::Bool(b)) => {
+                debug! {
+                    policy        = %self.policy_expression,
+                    id            = %id,
+                    is_authorized = %b,
+                    "policy evaluated"
+                }
+                Ok(b)
+            }
+            Ok(x) => {
+                warn! {
+                    policy = %self.policy_expression,
+                    id     = %id,
+                    expr   = %x,
+                    "evaluation did not yield a boolean result"
+                }
+                Ok(false)
+            }
+            Err(e) => {
+                warn! {
+                    policy = %self.policy_expression,
+                    id     = %id,
+                    err    = %e,
+                    "policy evaluation failed"
+                }
+                Ok(false)
+            }
+        }
+    }
+
+    /// Returns the reason why the identity is not authorized, if it is not authorized:
+    /// the first attribute used by the policy which the identity does not have, if any.
+    /// The policy expression is only logged locally since the reason is sent to the identity
+    pub async fn identity_deny_reason(&self, id: Identifier) -> Result<Option<DenyReason>> {
+        let environment = self.identity_environment(&id).await?;
+        let reason = match eval(&self.policy_expression, &environment) {
+            Ok(Expr::Bool(true)) => return Ok(None),
+            Err(EvalError::Unbound(attribute)) => {
+                DenyReason::default().with_missing_attribute(attribute)
+            }
+            _ => DenyReason::default(),
+        };
+        debug! {
+            policy  = %self.policy_expression,
+            id      = %id,
+            reason  = %reason,
+            "access denied"
+        }
+        Ok(Some(reason))
+    }
+
+    /// Return the environment used to evaluate the policy expression for a given identity
+    async fn identity_environment(&self, id: &Identifier) -> Result<Env> {
         let mut environment = self.environment.clone();
 
         // add the identifier itself as a subject parameter
@@ -103,7 +161,7 @@ impl AbacAccessControl {
         // Get identity attributes and populate the environment:
         match self
             .identities_attributes
-            .get_attributes(&id, &self.authority)
+            .get_attributes(id, &self.authority)
             .await?
         {
             Some(attrs) => {
@@ -164,37 +222,7 @@ impl AbacAccessControl {
                 );
             }
         }
-
-        // Finally, evaluate the expression and return the result:
-        match eval(&self.policy_expression, &environment) {
-            Ok(Expr::Bool(b)) => {
-                debug! {
-                    policy        = %self.policy_expression,
-                    id            = %id,
-                    is_authorized = %b,
-                    "policy evaluated"
-                }
-                Ok(b)
-            }
-            Ok(x) => {
-                warn! {
-                    policy = %self.policy_expression,
-                    id     = %id,
-                    expr   = %x,
-                    "evaluation did not yield a boolean result"
-                }
-                Ok(false)
-            }
-            Err(e) => {
-                warn! {
-                    policy = %self.policy_expression,
-                    id     = %id,
-                    err    = %e,
-                    "policy evaluation failed"
-                }
-                Ok(false)
-            }
-        }
+        Ok(environment)
     }
 }
 
@@ -215,4 +243,19 @@ impl IncomingAccessControl for AbacAccessControl {
 
         self.is_identity_authorized(id).await
     }
+
+    /// Returns the first attribute missing from the sender, without the policy expression
+    async fn deny_reason(&self, msg: &RelayMessage) -> Option<DenyReason> {
+        match IdentitySecureChannelLocalInfo::find_info(msg.local_message()) {
+            Ok(info) => self
+                .identity_deny_reason(info.their_identity_id())
+                .await
+                .ok()
+                .flatten(),
+            Err(_) => Some(
+                DenyReason::default()
+                    .with_missing_attribute(format!("{SUBJECT_KEY}.{ABAC_IDENTIFIER_KEY}")),
+            ),
+        }
+    }
 }
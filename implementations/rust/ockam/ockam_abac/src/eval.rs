@@ -3,6 +3,7 @@ use core::cmp::Ordering;
 use crate::env::Env;
 use crate::error::EvalError;
 use crate::expr::{unit, Expr};
+use ockam_core::compat::format;
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::vec::Vec;
 
@@ -22,7 +23,10 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
         Eq(usize),
         Gt(usize),
         Lt(usize),
+        Ge(usize),
+        Le(usize),
         Member,
+        In,
         Seq(usize),
     }
 
@@ -114,6 +118,20 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             ctrl.push(Op::Gt(nargs))
                         }
+                        "<=" => {
+                            if nargs < 2 {
+                                let msg = "'<=' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Le(nargs))
+                        }
+                        ">=" => {
+                            if nargs < 2 {
+                                let msg = "'>=' requires at least two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Ge(nargs))
+                        }
                         "=" => {
                             if nargs < 2 {
                                 let msg = "'=' requires at least two arguments";
@@ -132,6 +150,13 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             ctrl.push(Op::Member)
                         }
+                        "in" => {
+                            if nargs != 2 {
+                                let msg = "'in' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::In)
+                        }
                         "exists?" => {
                             let mut b = true;
                             for x in &xs[1 ..] {
@@ -228,12 +253,18 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                     }
                 }
             }
-            Op::Eq(n) => eval_predicate(n, &mut args, |x, y| x.equals(y))?,
+            Op::Eq(n) => eval_predicate(n, &mut args, equal_values)?,
             Op::Lt(n) => eval_predicate(n, &mut args, |x, y| {
-                x.compare(y).map(|o| o == Some(Ordering::Less))
+                compare_values(x, y).map(|o| o == Some(Ordering::Less))
             })?,
             Op::Gt(n) => eval_predicate(n, &mut args, |x, y| {
-                x.compare(y).map(|o| o == Some(Ordering::Greater))
+                compare_values(x, y).map(|o| o == Some(Ordering::Greater))
+            })?,
+            Op::Le(n) => eval_predicate(n, &mut args, |x, y| {
+                compare_values(x, y).map(|o| matches!(o, Some(Ordering::Less | Ordering::Equal)))
+            })?,
+            Op::Ge(n) => eval_predicate(n, &mut args, |x, y| {
+                compare_values(x, y).map(|o| matches!(o, Some(Ordering::Greater | Ordering::Equal)))
             })?,
             Op::Member => {
                 let s = pop(&mut args);
@@ -255,6 +286,21 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                     }
                 }
             }
+            Op::In => {
+                let s = pop(&mut args);
+                let y = pop(&mut args);
+                match s {
+                    // values of different types are never equal
+                    Expr::Seq(xs) => {
+                        let b = xs.iter().any(|x| equal_values(&y, x).unwrap_or(false));
+                        args.push(Expr::Bool(b))
+                    }
+                    other => {
+                        let msg = "'in' expects sequence as second argument";
+                        return Err(EvalError::InvalidType(other, msg))
+                    }
+                }
+            }
             Op::Seq(n) => {
                 let s = args.split_off(args.len() - n);
                 args.push(Expr::Seq(s))
@@ -266,6 +312,54 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
     Ok(pop(&mut args))
 }
 
+/// Check that an expression only uses known operators, with the number of arguments they
+/// require, without evaluating it.
+///
+/// This is used to reject a malformed policy expression before it is stored.
+#[rustfmt::skip]
+pub fn validate(expr: &Expr) -> Result<(), EvalError> {
+    let mut ctrl: Vec<&Expr> = Vec::new();
+    ctrl.push(expr);
+
+    while let Some(x) = ctrl.pop() {
+        match x {
+            Expr::List(xs) => match &xs[..] {
+                []                          => {}
+                [Expr::Ident(id), rest @ ..] => {
+                    let nargs = rest.len();
+                    let (valid, requirement) = match id.as_str() {
+                        "and" | "or" | "!="             => (true, ""),
+                        "not"                           => (nargs == 1, "one argument"),
+                        "if"                            => (nargs == 3, "three arguments"),
+                        "<" | ">" | "<=" | ">=" | "="   => (nargs >= 2, "at least two arguments"),
+                        "member?" | "in"                => (nargs == 2, "two arguments"),
+                        "exists?" => {
+                            if let Some(other) = rest.iter().find(|x| !x.is_ident()) {
+                                let msg = "'exists?' expects identifiers as arguments";
+                                return Err(EvalError::InvalidType(other.clone(), msg))
+                            }
+                            (true, "")
+                        }
+                        _ => return Err(EvalError::Unknown(id.to_string()))
+                    };
+                    if !valid {
+                        return Err(EvalError::malformed(format!("'{id}' requires {requirement}")))
+                    }
+                    ctrl.extend(rest)
+                }
+                [other, ..] => {
+                    let msg = "expected (op ...)";
+                    return Err(EvalError::InvalidType(other.clone(), msg))
+                }
+            }
+            Expr::Seq(xs) => ctrl.extend(xs),
+            _             => {}
+        }
+    }
+
+    Ok(())
+}
+
 /// Pop off the topmost stack value.
 ///
 /// # Panics
@@ -275,6 +369,31 @@ fn pop<T>(s: &mut Vec<T>) -> T {
     s.pop().expect("stack is not empty")
 }
 
+/// Compare two values like [`Expr::compare`].
+///
+/// Attribute values are strings, so a string compared to an integer is parsed as an integer.
+/// If it can't be parsed, the values are unordered and `None` is returned.
+fn compare_values(x: &Expr, y: &Expr) -> Result<Option<Ordering>, EvalError> {
+    match (x, y) {
+        (Expr::Int(a), Expr::Str(b)) => Ok(b.parse::<i64>().ok().map(|b| a.cmp(&b))),
+        (Expr::Str(a), Expr::Int(b)) => Ok(a.parse::<i64>().ok().map(|a| a.cmp(b))),
+        _ => x.compare(y),
+    }
+}
+
+/// Check if two values are equal like [`Expr::equals`].
+///
+/// A string compared to an integer is parsed as an integer, and is never equal to
+/// that integer if it can't be parsed.
+fn equal_values(x: &Expr, y: &Expr) -> Result<bool, EvalError> {
+    match (x, y) {
+        (Expr::Int(_), Expr::Str(_)) | (Expr::Str(_), Expr::Int(_)) => {
+            Ok(compare_values(x, y)? == Some(Ordering::Equal))
+        }
+        _ => x.equals(y),
+    }
+}
+
 /// Evaluate a predicate against the `n` topmost arguments.
 fn eval_predicate<F>(n: usize, args: &mut Vec<Expr>, f: F) -> Result<(), EvalError>
 where
@@ -296,7 +415,26 @@ where
 #[cfg(test)]
 mod tests {
     use crate::attribute_access_control::{ABAC_HAS_CREDENTIAL_KEY, SUBJECT_KEY};
-    use crate::{eval, Env, Expr};
+    use crate::{eval, parse, validate, Env, EvalError, Expr};
+
+    fn subject() -> Env {
+        let mut environment = Env::new();
+        environment.put("subject.identifier", Expr::Str("I2".into()));
+        environment.put("subject.level", Expr::Str("3".into()));
+        environment.put("subject.role", Expr::Str("admin".into()));
+        environment
+    }
+
+    fn eval_str(expression: &str, environment: &Env) -> Result<Expr, EvalError> {
+        eval(&parse(expression).unwrap().unwrap(), environment)
+    }
+
+    fn is_true(expression: &str) -> bool {
+        match eval_str(expression, &subject()) {
+            Ok(Expr::Bool(b)) => b,
+            other => panic!("unexpected result for {expression}: {other:?}"),
+        }
+    }
 
     #[test]
     fn test() {
@@ -324,4 +462,114 @@ mod tests {
         let res = eval(&check_credential_expression, &environment).unwrap();
         matches!(res, Expr::Bool(true));
     }
+
+    #[test]
+    fn test_identifier_membership() {
+        assert!(is_true(r#"(in subject.identifier ["I1" "I2" "I3"])"#));
+        assert!(!is_true(r#"(in subject.identifier ["I1" "I3"])"#));
+        assert!(!is_true(r#"(in subject.identifier [])"#));
+
+        // values of a different type are never members of the set
+        assert!(!is_true(r#"(in subject.identifier [1 2 3])"#));
+        assert!(is_true(r#"(in subject.level [1 2 3])"#));
+
+        // the set must be a sequence
+        assert!(eval_str(r#"(in subject.identifier "I2")"#, &subject()).is_err());
+    }
+
+    #[test]
+    fn test_numeric_comparisons() {
+        assert!(is_true("(>= subject.level 3)"));
+        assert!(is_true("(<= subject.level 3)"));
+        assert!(is_true("(> subject.level 2)"));
+        assert!(!is_true("(> subject.level 3)"));
+        assert!(is_true("(< 1 subject.level 4)"));
+        assert!(is_true("(<= 1 1 2)"));
+        assert!(!is_true("(>= 1 2 0)"));
+        assert!(is_true("(= subject.level 3)"));
+        assert!(is_true("(!= subject.level 4)"));
+
+        // strings are still compared as strings
+        assert!(is_true(r#"(< "10" "9")"#));
+        assert!(is_true(r#"(= subject.level "3")"#));
+    }
+
+    #[test]
+    fn test_type_mismatch() {
+        // an attribute which is not an integer can't be compared to an integer
+        assert!(!is_true("(>= subject.role 3)"));
+        assert!(!is_true("(< subject.role 3)"));
+        assert!(!is_true("(= subject.role 3)"));
+        assert!(is_true("(!= subject.role 3)"));
+
+        // other type mismatches are still errors
+        assert!(matches!(
+            eval_str("(< true 3)", &subject()),
+            Err(EvalError::TypeMismatch(_, _))
+        ));
+    }
+
+    #[test]
+    fn test_missing_attribute() {
+        assert!(eval_str("(>= subject.age 18)", &subject())
+            .unwrap_err()
+            .is_unbound());
+        assert!(!is_true("(and (exists? subject.age) (>= subject.age 18))"));
+        assert!(is_true(
+            "(or (not (exists? subject.age)) (>= subject.age 18))"
+        ));
+    }
+
+    #[test]
+    fn test_nested_combinations() {
+        let expression = r#"
+            (or (in subject.identifier ["I0" "I1"])
+                (and (= subject.role "admin")
+                     (>= subject.level 3)
+                     (not (in subject.identifier ["I9"]))))"#;
+        assert!(is_true(expression));
+
+        let mut environment = subject();
+        environment.put("subject.level", Expr::Str("2".into()));
+        let result = eval_str(expression, &environment).unwrap();
+        assert!(result.is_false());
+
+        // the combinators are lazy: the missing attribute is never evaluated
+        assert!(is_true("(or (>= subject.level 3) (>= subject.age 18))"));
+        assert!(!is_true("(and (> subject.level 3) (>= subject.age 18))"));
+        assert!(is_true(
+            "(if (exists? subject.age) (>= subject.age 18) (>= subject.level 3))"
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = [
+            "subject.has_credential",
+            r#"(= subject.role "admin")"#,
+            r#"(and (in subject.identifier ["I1" "I2"]) (>= subject.level 3) (<= subject.level 5))"#,
+            r#"(or (exists? subject.age) (member? subject.role ["a" "b"]))"#,
+            "(if (exists? subject.age) (>= subject.age 18) false)",
+            "(and)",
+        ];
+        for expression in valid {
+            let expr = parse(expression).unwrap().unwrap();
+            assert!(validate(&expr).is_ok(), "{expression}");
+        }
+
+        let invalid = [
+            "(>= subject.level)",
+            "(in subject.identifier)",
+            "(not true false)",
+            "(if true false)",
+            "(exists? \"subject.age\")",
+            "(matches? subject.role \"admin\")",
+            "(and (= subject.role) true)",
+            "(1 2)",
+        ];
+        for expression in invalid {
+            let expr = parse(expression).unwrap().unwrap();
+            assert!(validate(&expr).is_err(), "{expression}");
+        }
+    }
 }
@@ -23,9 +23,11 @@ pub mod resource;
 pub use attribute_access_control::AbacAccessControl;
 pub use env::Env;
 pub use error::{EvalError, ParseError};
-pub use eval::eval;
+pub use eval::{eval, validate};
 pub use expr::Expr;
 pub use policy::{storage::*, Policies, PolicyAccessControl, ResourcePolicy, ResourceTypePolicy};
+#[cfg(feature = "std")]
+pub use policy::{ExpressionCache, ExpressionCacheStats};
 pub use resource::{Resource, ResourceType};
 pub use types::{Action, ResourceName, Subject};
 
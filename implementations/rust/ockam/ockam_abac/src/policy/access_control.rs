@@ -2,9 +2,12 @@ use crate::{AbacAccessControl, Action, Env, Policies, Resource};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::format;
 use ockam_core::compat::sync::Arc;
-use ockam_core::{async_trait, IncomingAccessControl, RelayMessage};
+use ockam_core::{async_trait, DenyReason, IncomingAccessControl, RelayMessage};
 use ockam_identity::{Identifier, IdentitiesAttributes};
+#[cfg(feature = "std")]
+use ockam_node::{NodeEvent, NodeEventKind, NodeEvents};
 use tracing::debug;
 
 /// Evaluates a policy expression against an environment of attributes.
@@ -18,6 +21,8 @@ pub struct PolicyAccessControl {
     environment: Env,
     resource: Resource,
     action: Action,
+    #[cfg(feature = "std")]
+    node_events: Option<NodeEvents>,
 }
 
 /// Debug implementation writing out the resource, action and initial environment
@@ -53,13 +58,35 @@ impl PolicyAccessControl {
             environment: env,
             resource,
             action,
+            #[cfg(feature = "std")]
+            node_events: None,
         }
     }
-}
 
-#[async_trait]
-impl IncomingAccessControl for PolicyAccessControl {
-    async fn is_authorized(&self, msg: &RelayMessage) -> ockam_core::Result<bool> {
+    /// Publish a [`NodeEventKind::PolicyDenied`] event every time a message is rejected
+    #[cfg(feature = "std")]
+    pub fn with_node_events(mut self, node_events: NodeEvents) -> Self {
+        self.node_events = Some(node_events);
+        self
+    }
+
+    /// Returns true if the identity is authorized to perform the action on the resource
+    pub async fn is_identity_authorized(&self, id: Identifier) -> ockam_core::Result<bool> {
+        match self.abac_access_control().await? {
+            Some(access_control) => access_control.is_identity_authorized(id).await,
+            None => Ok(false),
+        }
+    }
+
+    async fn evaluate(&self, msg: &RelayMessage) -> ockam_core::Result<bool> {
+        match self.abac_access_control().await? {
+            Some(access_control) => access_control.is_authorized(msg).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Return an access control evaluating the current policy expression for the resource and action
+    async fn abac_access_control(&self) -> ockam_core::Result<Option<AbacAccessControl>> {
         // Load the policy expression for resource and action:
         let expression = if let Some(expr) = self
             .policies
@@ -74,16 +101,50 @@ impl IncomingAccessControl for PolicyAccessControl {
                 action   = %self.action,
                 "no policy found; access denied"
             }
-            return Ok(false);
+            return Ok(None);
         };
 
-        AbacAccessControl::new(
+        Ok(Some(AbacAccessControl::new(
             self.identities_attributes.clone(),
             self.authority.clone(),
             expression,
             self.environment.clone(),
-        )
-        .is_authorized(msg)
-        .await
+        )))
+    }
+}
+
+#[async_trait]
+impl IncomingAccessControl for PolicyAccessControl {
+    async fn is_authorized(&self, msg: &RelayMessage) -> ockam_core::Result<bool> {
+        let is_authorized = self.evaluate(msg).await?;
+
+        #[cfg(feature = "std")]
+        if let (false, Some(node_events)) = (is_authorized, &self.node_events) {
+            node_events.publish(
+                NodeEvent::new(
+                    NodeEventKind::PolicyDenied,
+                    self.resource.resource_name.as_str(),
+                )
+                .with_detail("resource_type", &self.resource.resource_type)
+                .with_detail("action", &self.action)
+                .with_detail("source", msg.source()),
+            );
+        }
+
+        Ok(is_authorized)
+    }
+
+    /// The policy is identified by its resource and action, its expression is not disclosed
+    async fn deny_reason(&self, msg: &RelayMessage) -> Option<DenyReason> {
+        let policy = format!("{}/{}", self.resource.resource_name, self.action);
+        match self.abac_access_control().await {
+            Ok(Some(access_control)) => access_control
+                .deny_reason(msg)
+                .await
+                .map(|reason| reason.with_policy(policy)),
+            // without a policy expression for the resource and action, all messages are denied
+            Ok(None) => Some(DenyReason::policy(policy)),
+            Err(_) => None,
+        }
     }
 }
@@ -0,0 +1,231 @@
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::Result;
+
+use crate::Expr;
+
+/// Bounded cache of parsed policy expressions, keyed by their string representation
+///
+/// The policy repositories store expressions as strings. With this cache, an expression is
+/// parsed once, and the same parsed expression is shared by all the resources using it,
+/// for example by all the outlets using the default policy.
+///
+/// When the cache is full, the least recently used expression is evicted. The entry of an
+/// expression is also removed when a policy using it is changed or deleted, so that the
+/// expressions which are not used anymore do not stay in memory.
+/// Clones share the same entries.
+#[derive(Clone)]
+pub struct ExpressionCache {
+    state: Arc<Mutex<CacheState>>,
+}
+
+struct CacheState {
+    capacity: usize,
+    entries: HashMap<String, CacheEntry>,
+    clock: u64,
+    stats: ExpressionCacheStats,
+}
+
+struct CacheEntry {
+    expression: Arc<Expr>,
+    last_used: u64,
+}
+
+/// Statistics of an [`ExpressionCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpressionCacheStats {
+    /// Number of expressions currently cached
+    pub size: usize,
+    /// Number of expressions which were found in the cache
+    pub hits: u64,
+    /// Number of expressions which had to be parsed
+    pub misses: u64,
+    /// Number of expressions removed because the cache was full
+    pub evicted: u64,
+    /// Number of expressions removed because a policy using them changed
+    pub invalidated: u64,
+}
+
+impl ExpressionCache {
+    /// Default maximum number of cached expressions
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Create a cache keeping up to `capacity` expressions.
+    /// A cache with a capacity of 0 parses every expression
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CacheState {
+                capacity,
+                entries: HashMap::new(),
+                clock: 0,
+                stats: ExpressionCacheStats::default(),
+            })),
+        }
+    }
+
+    /// Return the parsed expression for a given string, parsing it if it is not cached yet
+    pub fn get_or_parse(&self, expression: &str) -> Result<Arc<Expr>> {
+        if let Some(parsed) = self.get(expression) {
+            return Ok(parsed);
+        }
+        let parsed = Arc::new(Expr::try_from(expression)?);
+        Ok(self.insert(expression, parsed))
+    }
+
+    /// Return the shared handle for an expression, which is added to the cache if needed
+    pub fn intern(&self, expression: &Expr) -> Arc<Expr> {
+        let key = expression.to_string();
+        match self.get(&key) {
+            Some(interned) => interned,
+            None => self.insert(&key, Arc::new(expression.clone())),
+        }
+    }
+
+    /// Remove an expression from the cache
+    pub fn invalidate(&self, expression: &str) {
+        let mut state = self.state.lock().unwrap();
+        if state.entries.remove(expression).is_some() {
+            state.stats.invalidated += 1;
+            state.stats.size = state.entries.len();
+        }
+    }
+
+    /// Remove all the expressions from the cache
+    pub fn clear(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.stats.invalidated += state.entries.len() as u64;
+        state.entries.clear();
+        state.stats.size = 0;
+    }
+
+    /// Current statistics of the cache
+    pub fn stats(&self) -> ExpressionCacheStats {
+        self.state.lock().unwrap().stats
+    }
+
+    fn get(&self, key: &str) -> Option<Arc<Expr>> {
+        let mut state = self.state.lock().unwrap();
+        state.clock += 1;
+        let now = state.clock;
+        let found = state.entries.get_mut(key).map(|entry| {
+            entry.last_used = now;
+            entry.expression.clone()
+        });
+        if found.is_some() {
+            state.stats.hits += 1;
+        } else {
+            state.stats.misses += 1;
+        }
+        found
+    }
+
+    /// Add a parsed expression, unless another caller added it in the meantime,
+    /// and return the cached expression
+    fn insert(&self, key: &str, expression: Arc<Expr>) -> Arc<Expr> {
+        let mut state = self.state.lock().unwrap();
+        if state.capacity == 0 {
+            return expression;
+        }
+        if let Some(entry) = state.entries.get(key) {
+            return entry.expression.clone();
+        }
+        if state.entries.len() >= state.capacity {
+            let least_recently_used = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone());
+            if let Some(least_recently_used) = least_recently_used {
+                state.entries.remove(&least_recently_used);
+                state.stats.evicted += 1;
+            }
+        }
+        state.clock += 1;
+        let last_used = state.clock;
+        state.entries.insert(
+            key.to_string(),
+            CacheEntry {
+                expression: expression.clone(),
+                last_used,
+            },
+        );
+        state.stats.size = state.entries.len();
+        expression
+    }
+}
+
+impl Default for ExpressionCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identical_expressions_are_parsed_once() -> Result<()> {
+        let cache = ExpressionCache::default();
+        let expression1 = cache.get_or_parse("(= subject.component \"web\")")?;
+        let expression2 = cache.get_or_parse("(= subject.component \"web\")")?;
+        assert!(Arc::ptr_eq(&expression1, &expression2));
+
+        let interned = cache.intern(&expression1);
+        assert!(Arc::ptr_eq(&expression1, &interned));
+
+        let stats = cache.stats();
+        assert_eq!(stats.size, 1);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.hits, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_expressions_are_not_cached() {
+        let cache = ExpressionCache::default();
+        assert!(cache.get_or_parse("(= subject.component").is_err());
+        assert_eq!(cache.stats().size, 0);
+    }
+
+    #[test]
+    fn the_least_recently_used_expression_is_evicted() -> Result<()> {
+        let cache = ExpressionCache::new(2);
+        let first = cache.get_or_parse("(= subject.a \"1\")")?;
+        cache.get_or_parse("(= subject.b \"2\")")?;
+        // use the first expression again, the second one is now the least recently used
+        cache.get_or_parse("(= subject.a \"1\")")?;
+        cache.get_or_parse("(= subject.c \"3\")")?;
+
+        let stats = cache.stats();
+        assert_eq!(stats.size, 2);
+        assert_eq!(stats.evicted, 1);
+        assert!(Arc::ptr_eq(
+            &first,
+            &cache.get_or_parse("(= subject.a \"1\")")?
+        ));
+        let misses = cache.stats().misses;
+        cache.get_or_parse("(= subject.b \"2\")")?;
+        assert_eq!(cache.stats().misses, misses + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn invalidated_expressions_are_parsed_again() -> Result<()> {
+        let cache = ExpressionCache::default();
+        let expression = cache.get_or_parse("subject.has_credential")?;
+        cache.invalidate("subject.has_credential");
+        assert_eq!(cache.stats().size, 0);
+        assert_eq!(cache.stats().invalidated, 1);
+
+        let parsed_again = cache.get_or_parse("subject.has_credential")?;
+        assert!(!Arc::ptr_eq(&expression, &parsed_again));
+        assert_eq!(expression, parsed_again);
+
+        cache.clear();
+        assert_eq!(cache.stats().size, 0);
+        assert_eq!(cache.stats().invalidated, 2);
+        Ok(())
+    }
+}
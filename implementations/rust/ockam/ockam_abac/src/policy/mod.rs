@@ -1,10 +1,14 @@
 mod access_control;
+#[cfg(feature = "std")]
+mod expression_cache;
 mod policies;
 mod resource_policy;
 mod resource_type_policy;
 pub(crate) mod storage;
 
 pub use access_control::PolicyAccessControl;
+#[cfg(feature = "std")]
+pub use expression_cache::{ExpressionCache, ExpressionCacheStats};
 pub use policies::Policies;
 pub use resource_policy::ResourcePolicy;
 pub use resource_type_policy::ResourceTypePolicy;
@@ -1,8 +1,8 @@
 use crate::attribute_access_control::{ABAC_HAS_CREDENTIAL_KEY, SUBJECT_KEY};
 use crate::policy::ResourceTypePolicy;
 use crate::{
-    Action, Env, Expr, PolicyAccessControl, Resource, ResourceName, ResourcePoliciesRepository,
-    ResourcePolicy, ResourceType, ResourceTypePoliciesRepository,
+    Action, Env, Expr, PoliciesRepository, PolicyAccessControl, Resource, ResourceName,
+    ResourcePoliciesRepository, ResourcePolicy, ResourceType, ResourceTypePoliciesRepository,
 };
 use ockam_core::compat::format;
 use ockam_core::compat::sync::Arc;
@@ -29,6 +29,11 @@ impl Policies {
         }
     }
 
+    /// Create policies stored in a single repository, for both resources and resource types
+    pub fn from_repository<R: PoliciesRepository>(repository: Arc<R>) -> Self {
+        Self::new(repository.clone(), repository)
+    }
+
     #[instrument(skip_all, fields(resource = %resource, action = %action, env = %env, authority = %authority))]
     pub async fn make_policy_access_control(
         &self,
@@ -114,29 +119,27 @@ impl Policies {
             .await
     }
 
+    /// Return the expression of the policy applying to a resource and action.
+    /// The expression can be shared with other resources using the same policy
     pub async fn get_expression_for_resource(
         &self,
         resource: &Resource,
         action: &Action,
-    ) -> Result<Option<Expr>> {
+    ) -> Result<Option<Arc<Expr>>> {
         // Try to get a policy for the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_name(&resource.resource_name, action)
+        if let Some(expression) = self
+            .resources_policies_repository
+            .get_policy_expression(&resource.resource_name, action)
             .await?
         {
-            return Ok(Some(policy.expression));
+            return Ok(Some(expression));
         }
 
         // If there is no policy for the resource name, try to get
         // the policy for the resource type associated to the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_type(&resource.resource_type, action)
-            .await?
-        {
-            return Ok(Some(policy.expression));
-        }
-
-        Ok(None)
+        self.resource_types_policies_repository
+            .get_policy_expression(&resource.resource_type, action)
+            .await
     }
 }
 
@@ -193,3 +196,200 @@ impl Policies {
             .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ExpressionCache, ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase,
+        StaticPoliciesRepository,
+    };
+    use ockam_core::compat::collections::BTreeMap;
+    use ockam_identity::models::IDENTIFIER_LEN;
+    use ockam_identity::utils::now;
+    use ockam_identity::{AttributesEntry, IdentityAttributesSqlxDatabase};
+
+    const POLICIES: &str = r#"[
+        { "resource_type": "tcp-outlet", "action": "handle_message", "expression": "(= subject.component \"web\")" },
+        { "resource_name": "outlet-db", "action": "handle_message", "expression": "(= subject.component \"db\")" }
+    ]"#;
+
+    #[tokio::test]
+    async fn test_policy_enforcement_with_sql_policies() -> Result<()> {
+        let policies = Policies::new(
+            Arc::new(ResourcePolicySqlxDatabase::create().await?),
+            Arc::new(ResourceTypePolicySqlxDatabase::create().await?),
+        );
+        let document = StaticPoliciesRepository::from_json(POLICIES)?;
+        for policy in ResourcePoliciesRepository::get_policies(&document).await? {
+            policies
+                .store_policy_for_resource_name(
+                    &policy.resource_name,
+                    &policy.action,
+                    &policy.expression,
+                )
+                .await?;
+        }
+        for policy in ResourceTypePoliciesRepository::get_policies(&document).await? {
+            policies
+                .store_policy_for_resource_type(
+                    &policy.resource_type,
+                    &policy.action,
+                    &policy.expression,
+                )
+                .await?;
+        }
+
+        check_policy_enforcement(&policies).await
+    }
+
+    #[tokio::test]
+    async fn test_policy_enforcement_with_static_policies() -> Result<()> {
+        let policies =
+            Policies::from_repository(Arc::new(StaticPoliciesRepository::from_json(POLICIES)?));
+        check_policy_enforcement(&policies).await?;
+
+        // static policies are read-only
+        let action = Action::HandleMessage;
+        assert!(policies
+            .delete_policy_for_resource_name(&"outlet-db".into(), &action)
+            .await
+            .is_err());
+        assert!(policies
+            .store_policy_for_resource_type(&ResourceType::TcpInlet, &action, &true.into())
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shared_expressions_are_updated_with_their_policies() -> Result<()> {
+        let expressions = ExpressionCache::default();
+        let policies = Policies::new(
+            Arc::new(
+                ResourcePolicySqlxDatabase::create()
+                    .await?
+                    .with_expression_cache(expressions.clone()),
+            ),
+            Arc::new(
+                ResourceTypePolicySqlxDatabase::create()
+                    .await?
+                    .with_expression_cache(expressions.clone()),
+            ),
+        );
+        let action = Action::HandleMessage;
+        let web = Expr::try_from("(= subject.component \"web\")")?;
+        let db = Expr::try_from("(= subject.component \"db\")")?;
+        let outlet1 = Resource::new("outlet1", ResourceType::TcpOutlet);
+        let outlet2 = Resource::new("outlet2", ResourceType::TcpOutlet);
+
+        // the outlets without their own policy share the expression of their type
+        policies
+            .store_policy_for_resource_type(&ResourceType::TcpOutlet, &action, &web)
+            .await?;
+        let expression1 = policies
+            .get_expression_for_resource(&outlet1, &action)
+            .await?;
+        let expression2 = policies
+            .get_expression_for_resource(&outlet2, &action)
+            .await?;
+        assert!(Arc::ptr_eq(
+            expression1.as_ref().unwrap(),
+            expression2.as_ref().unwrap()
+        ));
+        assert_eq!(expression1.as_deref(), Some(&web));
+        assert_eq!(expressions.stats().misses, 1);
+
+        // an updated policy is used right away
+        policies
+            .store_policy_for_resource_name(&"outlet1".into(), &action, &db)
+            .await?;
+        let expression1 = policies
+            .get_expression_for_resource(&outlet1, &action)
+            .await?;
+        assert_eq!(expression1.as_deref(), Some(&db));
+
+        policies
+            .store_policy_for_resource_type(&ResourceType::TcpOutlet, &action, &db)
+            .await?;
+        assert_eq!(expressions.stats().invalidated, 1);
+        let expression2 = policies
+            .get_expression_for_resource(&outlet2, &action)
+            .await?;
+        assert_eq!(expression2.as_deref(), Some(&db));
+
+        // the expressions of deleted policies are not kept
+        policies
+            .delete_policy_for_resource_name(&"outlet1".into(), &action)
+            .await?;
+        policies
+            .delete_policy_for_resource_type(&ResourceType::TcpOutlet, &action)
+            .await?;
+        assert!(policies
+            .get_expression_for_resource(&outlet1, &action)
+            .await?
+            .is_none());
+        assert_eq!(expressions.stats().size, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_policies_document() {
+        for document in [
+            r#"[{ "action": "handle_message", "expression": "true" }]"#,
+            r#"[{ "resource_type": "unknown", "action": "handle_message", "expression": "true" }]"#,
+            r#"[{ "resource_name": "outlet", "action": "handle_message", "expression": "" }]"#,
+            r#"{ "resource_name": "outlet" }"#,
+        ] {
+            assert!(StaticPoliciesRepository::from_json(document).is_err());
+        }
+    }
+
+    /// Check that the access to outlets is enforced by the policies of `POLICIES`
+    async fn check_policy_enforcement(policies: &Policies) -> Result<()> {
+        let authority = Identifier([1; IDENTIFIER_LEN]);
+        let web = Identifier([2; IDENTIFIER_LEN]);
+        let db = Identifier([3; IDENTIFIER_LEN]);
+        let identities_attributes = Arc::new(IdentitiesAttributes::new(Arc::new(
+            IdentityAttributesSqlxDatabase::create().await?,
+        )));
+        for (identifier, component) in [(&web, "web"), (&db, "db")] {
+            let attributes = BTreeMap::from([(
+                "component".as_bytes().to_vec(),
+                component.as_bytes().to_vec(),
+            )]);
+            identities_attributes
+                .put_attributes(
+                    identifier,
+                    AttributesEntry::new(attributes, now()?, None, Some(authority.clone())),
+                )
+                .await?;
+        }
+
+        let access_control = |resource: Resource| {
+            policies.make_policy_access_control(
+                identities_attributes.clone(),
+                resource,
+                Action::HandleMessage,
+                Env::new(),
+                authority.clone(),
+            )
+        };
+
+        // the policy of the resource type applies to the outlets without their own policy
+        let outlet = access_control(Resource::new("outlet-web", ResourceType::TcpOutlet)).await?;
+        assert!(outlet.is_identity_authorized(web.clone()).await?);
+        assert!(!outlet.is_identity_authorized(db.clone()).await?);
+
+        // the policy of a resource name takes precedence over the policy of its type
+        let outlet = access_control(Resource::new("outlet-db", ResourceType::TcpOutlet)).await?;
+        assert!(!outlet.is_identity_authorized(web.clone()).await?);
+        assert!(outlet.is_identity_authorized(db.clone()).await?);
+
+        // the access is denied when there is no policy
+        let inlet = access_control(Resource::new("inlet", ResourceType::TcpInlet)).await?;
+        assert!(!inlet.is_identity_authorized(web).await?);
+        assert!(!inlet.is_identity_authorized(db).await?);
+        Ok(())
+    }
+}
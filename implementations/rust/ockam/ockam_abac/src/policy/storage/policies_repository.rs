@@ -0,0 +1,10 @@
+use crate::{ResourcePoliciesRepository, ResourceTypePoliciesRepository};
+
+/// This repository stores both the policies of resources and the policies of resource types.
+///
+/// It is implemented by any storage backend implementing the two repositories, in order to
+/// create [`Policies`](crate::Policies) from a single backend with
+/// [`Policies::from_repository`](crate::Policies::from_repository).
+pub trait PoliciesRepository: ResourcePoliciesRepository + ResourceTypePoliciesRepository {}
+
+impl<T: ResourcePoliciesRepository + ResourceTypePoliciesRepository> PoliciesRepository for T {}
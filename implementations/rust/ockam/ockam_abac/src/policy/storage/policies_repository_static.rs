@@ -0,0 +1,187 @@
+use core::str::FromStr;
+use serde::Deserialize;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+use crate::{
+    Action, Expr, ParseError, ResourceName, ResourcePoliciesRepository, ResourcePolicy,
+    ResourceType, ResourceTypePoliciesRepository, ResourceTypePolicy,
+};
+
+/// Read-only repository for policies, loaded from a static document.
+///
+/// This repository can be used by nodes which receive their policies from an external
+/// configuration system. The policies can not be modified once the node is started.
+///
+/// The document is a JSON list of policies, each policy being set either on a resource name or
+/// on a resource type:
+///
+/// ```json
+/// [
+///   { "resource_type": "tcp-outlet", "action": "handle_message", "expression": "(= subject.component \"web\")" },
+///   { "resource_name": "outlet-db", "action": "handle_message", "expression": "(= subject.component \"db\")" }
+/// ]
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StaticPoliciesRepository {
+    resource_policies: Vec<ResourcePolicy>,
+    resource_type_policies: Vec<ResourceTypePolicy>,
+}
+
+impl StaticPoliciesRepository {
+    /// Create a new repository for a fixed list of policies
+    pub fn new(
+        resource_policies: Vec<ResourcePolicy>,
+        resource_type_policies: Vec<ResourceTypePolicy>,
+    ) -> Self {
+        debug!("create a static repository for policies");
+        Self {
+            resource_policies,
+            resource_type_policies,
+        }
+    }
+
+    /// Create a new repository from a JSON list of policies
+    pub fn from_json(document: &str) -> Result<Self> {
+        let entries: Vec<PolicyDocumentEntry> = serde_json::from_str(document)
+            .map_err(|e| ParseError::message(format!("invalid policies document: {e}")))?;
+
+        let mut resource_policies = Vec::new();
+        let mut resource_type_policies = Vec::new();
+        for entry in entries {
+            let action = Action::from_str(&entry.action)?;
+            let expression = Expr::try_from(entry.expression.as_str())?;
+            match (entry.resource_name, entry.resource_type) {
+                (Some(resource_name), None) => resource_policies.push(ResourcePolicy::new(
+                    ResourceName::from(resource_name),
+                    action,
+                    expression,
+                )),
+                (None, Some(resource_type)) => {
+                    resource_type_policies.push(ResourceTypePolicy::new(
+                        ResourceType::from_str(&resource_type)?,
+                        action,
+                        expression,
+                    ))
+                }
+                _ => {
+                    return Err(ParseError::message(
+                        "a policy must be set either on a resource_name or on a resource_type",
+                    )
+                    .into())
+                }
+            }
+        }
+        Ok(Self::new(resource_policies, resource_type_policies))
+    }
+
+    fn read_only() -> Error {
+        Error::new(
+            Origin::Application,
+            Kind::Unsupported,
+            "the policies of this node are read-only",
+        )
+    }
+}
+
+#[async_trait]
+impl ResourcePoliciesRepository for StaticPoliciesRepository {
+    async fn store_policy(
+        &self,
+        _resource_name: &ResourceName,
+        _action: &Action,
+        _expression: &Expr,
+    ) -> Result<()> {
+        Err(Self::read_only())
+    }
+
+    async fn get_policy(
+        &self,
+        resource_name: &ResourceName,
+        action: &Action,
+    ) -> Result<Option<ResourcePolicy>> {
+        Ok(self
+            .resource_policies
+            .iter()
+            .find(|p| &p.resource_name == resource_name && &p.action == action)
+            .cloned())
+    }
+
+    async fn get_policies(&self) -> Result<Vec<ResourcePolicy>> {
+        Ok(self.resource_policies.clone())
+    }
+
+    async fn get_policies_by_resource_name(
+        &self,
+        resource_name: &ResourceName,
+    ) -> Result<Vec<ResourcePolicy>> {
+        Ok(self
+            .resource_policies
+            .iter()
+            .filter(|p| &p.resource_name == resource_name)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_policy(&self, _resource_name: &ResourceName, _action: &Action) -> Result<()> {
+        Err(Self::read_only())
+    }
+}
+
+#[async_trait]
+impl ResourceTypePoliciesRepository for StaticPoliciesRepository {
+    async fn store_policy(
+        &self,
+        _resource_type: &ResourceType,
+        _action: &Action,
+        _expression: &Expr,
+    ) -> Result<()> {
+        Err(Self::read_only())
+    }
+
+    async fn get_policy(
+        &self,
+        resource_type: &ResourceType,
+        action: &Action,
+    ) -> Result<Option<ResourceTypePolicy>> {
+        Ok(self
+            .resource_type_policies
+            .iter()
+            .find(|p| &p.resource_type == resource_type && &p.action == action)
+            .cloned())
+    }
+
+    async fn get_policies(&self) -> Result<Vec<ResourceTypePolicy>> {
+        Ok(self.resource_type_policies.clone())
+    }
+
+    async fn get_policies_by_resource_type(
+        &self,
+        resource_type: &ResourceType,
+    ) -> Result<Vec<ResourceTypePolicy>> {
+        Ok(self
+            .resource_type_policies
+            .iter()
+            .filter(|p| &p.resource_type == resource_type)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_policy(&self, _resource_type: &ResourceType, _action: &Action) -> Result<()> {
+        Err(Self::read_only())
+    }
+}
+
+/// Policy, as written in a policies document
+#[derive(Deserialize)]
+struct PolicyDocumentEntry {
+    resource_name: Option<String>,
+    resource_type: Option<String>,
+    action: String,
+    expression: String,
+}
@@ -1,6 +1,7 @@
 use crate::{Action, Expr, ResourceName, ResourcePolicy};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
@@ -24,6 +25,19 @@ pub trait ResourcePoliciesRepository: Send + Sync + 'static {
         action: &Action,
     ) -> Result<Option<ResourcePolicy>>;
 
+    /// Return the expression of the policy associated to a given resource and action.
+    /// Repositories can return the same expression for all the policies sharing it
+    async fn get_policy_expression(
+        &self,
+        resource_name: &ResourceName,
+        action: &Action,
+    ) -> Result<Option<Arc<Expr>>> {
+        Ok(self
+            .get_policy(resource_name, action)
+            .await?
+            .map(|policy| Arc::new(policy.expression)))
+    }
+
     /// Return the list of all the resource policies
     async fn get_policies(&self) -> Result<Vec<ResourcePolicy>>;
 
@@ -3,22 +3,35 @@ use sqlx::*;
 use tracing::debug;
 
 use ockam_core::async_trait;
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, SqlxType, ToSqlxType, ToVoid};
 
-use crate::{Action, Expr, ResourceName, ResourcePoliciesRepository, ResourcePolicy};
+use crate::{
+    Action, Expr, ExpressionCache, ResourceName, ResourcePoliciesRepository, ResourcePolicy,
+};
 
 #[derive(Clone)]
 pub struct ResourcePolicySqlxDatabase {
     database: SqlxDatabase,
+    expressions: ExpressionCache,
 }
 
 impl ResourcePolicySqlxDatabase {
     /// Create a new database for resource policies
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for resource policies");
-        Self { database }
+        Self {
+            database,
+            expressions: ExpressionCache::default(),
+        }
+    }
+
+    /// Parse the policy expressions with a cache shared with other repositories
+    pub fn with_expression_cache(mut self, expressions: ExpressionCache) -> Self {
+        self.expressions = expressions;
+        self
     }
 
     /// Create a new in-memory database for policies
@@ -37,6 +50,7 @@ impl ResourcePoliciesRepository for ResourcePolicySqlxDatabase {
         action: &Action,
         expression: &Expr,
     ) -> Result<()> {
+        self.invalidate_expression(resource_name, action).await?;
         let query = query(
             r#"INSERT OR REPLACE INTO resource_policy
             VALUES (?, ?, ?, ?)"#,
@@ -65,7 +79,18 @@ impl ResourcePoliciesRepository for ResourcePolicySqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.try_into()).transpose()?)
+        row.map(|r| r.into_policy(&self.expressions)).transpose()
+    }
+
+    async fn get_policy_expression(
+        &self,
+        resource_name: &ResourceName,
+        action: &Action,
+    ) -> Result<Option<Arc<Expr>>> {
+        self.get_expression(resource_name, action)
+            .await?
+            .map(|expression| self.expressions.get_or_parse(&expression))
+            .transpose()
     }
 
     async fn get_policies(&self) -> Result<Vec<ResourcePolicy>> {
@@ -77,7 +102,7 @@ impl ResourcePoliciesRepository for ResourcePolicySqlxDatabase {
         .bind(self.database.node_name()?.to_sql());
         let row: Vec<PolicyRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter()
-            .map(|r| r.try_into())
+            .map(|r| r.into_policy(&self.expressions))
             .collect::<Result<Vec<ResourcePolicy>>>()
     }
 
@@ -94,11 +119,12 @@ impl ResourcePoliciesRepository for ResourcePolicySqlxDatabase {
         .bind(resource_name.to_sql());
         let row: Vec<PolicyRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter()
-            .map(|r| r.try_into())
+            .map(|r| r.into_policy(&self.expressions))
             .collect::<Result<Vec<ResourcePolicy>>>()
     }
 
     async fn delete_policy(&self, resource_name: &ResourceName, action: &Action) -> Result<()> {
+        self.invalidate_expression(resource_name, action).await?;
         let query = query(
             r#"DELETE FROM resource_policy
             WHERE node_name=? and resource_name=? and action=?"#,
@@ -110,6 +136,37 @@ impl ResourcePoliciesRepository for ResourcePolicySqlxDatabase {
     }
 }
 
+impl ResourcePolicySqlxDatabase {
+    /// Return the expression of a policy, as it is stored
+    async fn get_expression(
+        &self,
+        resource_name: &ResourceName,
+        action: &Action,
+    ) -> Result<Option<String>> {
+        let query = query_scalar::<_, String>(
+            r#"SELECT expression
+            FROM resource_policy
+            WHERE node_name=$1 and resource_name=$2 and action=$3"#,
+        )
+        .bind(self.database.node_name()?.to_sql())
+        .bind(resource_name.to_sql())
+        .bind(action.to_sql());
+        query.fetch_optional(&*self.database.pool).await.into_core()
+    }
+
+    /// Remove the current expression of a policy from the cache before the policy is changed
+    async fn invalidate_expression(
+        &self,
+        resource_name: &ResourceName,
+        action: &Action,
+    ) -> Result<()> {
+        if let Some(expression) = self.get_expression(resource_name, action).await? {
+            self.expressions.invalidate(&expression);
+        }
+        Ok(())
+    }
+}
+
 // Database serialization / deserialization
 
 impl ToSqlxType for ResourceName {
@@ -142,19 +199,15 @@ impl PolicyRow {
         Ok(Action::from_str(&self.action)?)
     }
 
-    fn expression(&self) -> Result<Expr> {
-        Ok(Expr::try_from(self.expression.as_str())?)
+    fn expression(&self, expressions: &ExpressionCache) -> Result<Expr> {
+        Ok(expressions.get_or_parse(&self.expression)?.as_ref().clone())
     }
-}
-
-impl TryFrom<PolicyRow> for ResourcePolicy {
-    type Error = ockam_core::Error;
 
-    fn try_from(row: PolicyRow) -> Result<Self, Self::Error> {
+    fn into_policy(self, expressions: &ExpressionCache) -> Result<ResourcePolicy> {
         Ok(ResourcePolicy::new(
-            row.resource_name(),
-            row.action()?,
-            row.expression()?,
+            self.resource_name(),
+            self.action()?,
+            self.expression(expressions)?,
         ))
     }
 }
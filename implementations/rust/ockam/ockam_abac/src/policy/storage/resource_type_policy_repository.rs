@@ -2,6 +2,7 @@ use crate::policy::ResourceTypePolicy;
 use crate::{Action, Expr, ResourceType};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
@@ -25,6 +26,19 @@ pub trait ResourceTypePoliciesRepository: Send + Sync + 'static {
         action: &Action,
     ) -> Result<Option<ResourceTypePolicy>>;
 
+    /// Return the expression of the policy associated to a given resource type and action.
+    /// Repositories can return the same expression for all the policies sharing it
+    async fn get_policy_expression(
+        &self,
+        resource_type: &ResourceType,
+        action: &Action,
+    ) -> Result<Option<Arc<Expr>>> {
+        Ok(self
+            .get_policy(resource_type, action)
+            .await?
+            .map(|policy| Arc::new(policy.expression)))
+    }
+
     /// Return the list of all the resource type policies
     async fn get_policies(&self) -> Result<Vec<ResourceTypePolicy>>;
 
@@ -3,23 +3,34 @@ use sqlx::*;
 use tracing::debug;
 
 use ockam_core::async_trait;
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, SqlxType, ToSqlxType, ToVoid};
 
 use crate::policy::ResourceTypePolicy;
-use crate::{Action, Expr, ResourceType, ResourceTypePoliciesRepository};
+use crate::{Action, Expr, ExpressionCache, ResourceType, ResourceTypePoliciesRepository};
 
 #[derive(Clone)]
 pub struct ResourceTypePolicySqlxDatabase {
     database: SqlxDatabase,
+    expressions: ExpressionCache,
 }
 
 impl ResourceTypePolicySqlxDatabase {
     /// Create a new database for resource type policies
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for resource type policies");
-        Self { database }
+        Self {
+            database,
+            expressions: ExpressionCache::default(),
+        }
+    }
+
+    /// Parse the policy expressions with a cache shared with other repositories
+    pub fn with_expression_cache(mut self, expressions: ExpressionCache) -> Self {
+        self.expressions = expressions;
+        self
     }
 
     /// Create a new in-memory database for policies
@@ -38,6 +49,7 @@ impl ResourceTypePoliciesRepository for ResourceTypePolicySqlxDatabase {
         action: &Action,
         expression: &Expr,
     ) -> Result<()> {
+        self.invalidate_expression(resource_type, action).await?;
         let query = query(
             r#"INSERT OR REPLACE INTO
             resource_type_policy VALUES (?, ?, ?, ?)"#,
@@ -66,7 +78,18 @@ impl ResourceTypePoliciesRepository for ResourceTypePolicySqlxDatabase {
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.try_into()).transpose()?)
+        row.map(|r| r.into_policy(&self.expressions)).transpose()
+    }
+
+    async fn get_policy_expression(
+        &self,
+        resource_type: &ResourceType,
+        action: &Action,
+    ) -> Result<Option<Arc<Expr>>> {
+        self.get_expression(resource_type, action)
+            .await?
+            .map(|expression| self.expressions.get_or_parse(&expression))
+            .transpose()
     }
 
     async fn get_policies(&self) -> Result<Vec<ResourceTypePolicy>> {
@@ -77,7 +100,7 @@ impl ResourceTypePoliciesRepository for ResourceTypePolicySqlxDatabase {
         .bind(self.database.node_name()?.to_sql());
         let row: Vec<PolicyRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter()
-            .map(|r| r.try_into())
+            .map(|r| r.into_policy(&self.expressions))
             .collect::<Result<Vec<ResourceTypePolicy>>>()
     }
 
@@ -93,11 +116,12 @@ impl ResourceTypePoliciesRepository for ResourceTypePolicySqlxDatabase {
         .bind(resource_type.to_sql());
         let row: Vec<PolicyRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter()
-            .map(|r| r.try_into())
+            .map(|r| r.into_policy(&self.expressions))
             .collect::<Result<Vec<ResourceTypePolicy>>>()
     }
 
     async fn delete_policy(&self, resource_type: &ResourceType, action: &Action) -> Result<()> {
+        self.invalidate_expression(resource_type, action).await?;
         let query = query(
             r#"DELETE FROM resource_type_policy
             WHERE node_name=? and resource_type=? and action=?"#,
@@ -109,6 +133,37 @@ impl ResourceTypePoliciesRepository for ResourceTypePolicySqlxDatabase {
     }
 }
 
+impl ResourceTypePolicySqlxDatabase {
+    /// Return the expression of a policy, as it is stored
+    async fn get_expression(
+        &self,
+        resource_type: &ResourceType,
+        action: &Action,
+    ) -> Result<Option<String>> {
+        let query = query_scalar::<_, String>(
+            r#"SELECT expression
+            FROM resource_type_policy
+            WHERE node_name=$1 and resource_type=$2 and action=$3"#,
+        )
+        .bind(self.database.node_name()?.to_sql())
+        .bind(resource_type.to_sql())
+        .bind(action.to_sql());
+        query.fetch_optional(&*self.database.pool).await.into_core()
+    }
+
+    /// Remove the current expression of a policy from the cache before the policy is changed
+    async fn invalidate_expression(
+        &self,
+        resource_type: &ResourceType,
+        action: &Action,
+    ) -> Result<()> {
+        if let Some(expression) = self.get_expression(resource_type, action).await? {
+            self.expressions.invalidate(&expression);
+        }
+        Ok(())
+    }
+}
+
 /// Low-level representation of a row in the resource_type_policy table
 #[derive(FromRow)]
 struct PolicyRow {
@@ -126,19 +181,15 @@ impl PolicyRow {
         Ok(Action::from_str(&self.action)?)
     }
 
-    fn expression(&self) -> Result<Expr> {
-        Ok(Expr::try_from(self.expression.as_str())?)
+    fn expression(&self, expressions: &ExpressionCache) -> Result<Expr> {
+        Ok(expressions.get_or_parse(&self.expression)?.as_ref().clone())
     }
-}
-
-impl TryFrom<PolicyRow> for ResourceTypePolicy {
-    type Error = ockam_core::Error;
 
-    fn try_from(row: PolicyRow) -> Result<Self, Self::Error> {
+    fn into_policy(self, expressions: &ExpressionCache) -> Result<ResourceTypePolicy> {
         Ok(ResourceTypePolicy::new(
-            row.resource_type()?,
-            row.action()?,
-            row.expression()?,
+            self.resource_type()?,
+            self.action()?,
+            self.expression(expressions)?,
         ))
     }
 }
@@ -45,6 +45,9 @@ pub enum ResourceType {
     #[n(3)]
     #[strum(serialize = "echoer")]
     Echoer,
+    #[n(4)]
+    #[strum(serialize = "service-discovery")]
+    ServiceDiscovery,
 }
 
 impl ResourceType {
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+use ockam_abac::{eval, Env, Expr, ExpressionCache};
+
+/// This test serves as a benchmark for the evaluation of policy expressions,
+/// comparing the parsing of the expression on each evaluation with the use of a cache.
+/// In order for the result to be reliable, use the --profile release
+/// flag when running the test.
+/// `cargo test --test expression_cache --release -- --ignored --show-output`
+
+const EXPRESSION: &str = r#"(or (and (= subject.component "web") subject.has_credential) (and (= subject.component "db") (member? "admin" [subject.role resource.owner])))"#;
+const EVALUATIONS: u32 = 100_000;
+
+#[ignore]
+#[test]
+pub fn measure_expression_evaluation_with_cache() {
+    let mut env = Env::new();
+    env.put("subject.component", Expr::Str("db".into()))
+        .put("subject.has_credential", Expr::Bool(true))
+        .put("subject.role", Expr::Str("admin".into()))
+        .put("resource.owner", Expr::Str("ops".into()));
+
+    let parsed = measure(|| {
+        let expression = Expr::try_from(EXPRESSION).unwrap();
+        eval(&expression, &env).unwrap()
+    });
+
+    let cache = ExpressionCache::default();
+    let cached = measure(|| {
+        let expression = cache.get_or_parse(EXPRESSION).unwrap();
+        eval(&expression, &env).unwrap()
+    });
+
+    println!("evaluations: {EVALUATIONS}");
+    println!(
+        "parsed on each evaluation: {:?} per evaluation",
+        parsed / EVALUATIONS
+    );
+    println!("cached: {:?} per evaluation", cached / EVALUATIONS);
+    println!(
+        "speedup: {:.1}x",
+        parsed.as_secs_f64() / cached.as_secs_f64()
+    );
+    println!("cache: {:?}", cache.stats());
+
+    assert_eq!(cache.stats().misses, 1);
+    assert!(cached < parsed);
+}
+
+fn measure(f: impl Fn() -> Expr) -> Duration {
+    let start = Instant::now();
+    for _ in 0..EVALUATIONS {
+        assert_eq!(f(), Expr::Bool(true));
+    }
+    start.elapsed()
+}
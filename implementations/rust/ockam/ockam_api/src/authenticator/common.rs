@@ -1,5 +1,5 @@
 use crate::authenticator::direct::{OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY};
-use crate::authenticator::AuthorityMembersRepository;
+use crate::authenticator::{AuthorityMembersRepository, OCKAM_TICKET_TEMPLATE_ATTRIBUTE_KEY};
 use ockam::identity::Identifier;
 use ockam_core::Result;
 use std::collections::BTreeMap;
@@ -39,6 +39,15 @@ impl EnrollerAccessControlChecks {
         false
     }
 
+    /// Return the name of the attribute template an enroller is bound to, if any
+    pub(crate) fn bin_attributes_ticket_template(
+        attributes: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Option<String> {
+        attributes
+            .get(OCKAM_TICKET_TEMPLATE_ATTRIBUTE_KEY.as_bytes())
+            .and_then(|v| String::from_utf8(v.clone()).ok())
+    }
+
     pub(crate) async fn check_identifier(
         members: Arc<dyn AuthorityMembersRepository>,
         identifier: &Identifier,
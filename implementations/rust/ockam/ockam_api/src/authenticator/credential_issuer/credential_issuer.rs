@@ -1,9 +1,12 @@
 use core::time::Duration;
 
 use crate::authenticator::direct::AccountAuthorityInfo;
-use crate::authenticator::AuthorityMembersRepository;
+use crate::authenticator::events::AuthorityEvents;
+use crate::authenticator::{
+    AuthorityEvent, AuthorityEventKind, AuthorityMembersRepository, AuthorityRevocationsRepository,
+};
 use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
-use ockam::identity::utils::AttributesBuilder;
+use ockam::identity::utils::{now, AttributesBuilder};
 use ockam::identity::{Attributes, Credentials, Identifier};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
@@ -20,12 +23,14 @@ pub const DEFAULT_CREDENTIAL_VALIDITY: Duration = Duration::from_secs(30 * 24 *
 /// This struct runs as a Worker to issue credentials based on a request/response protocol
 pub struct CredentialIssuer {
     members: Arc<dyn AuthorityMembersRepository>,
+    revocations: Arc<dyn AuthorityRevocationsRepository>,
     credentials: Arc<Credentials>,
     issuer: Identifier,
     subject_attributes: Attributes,
     credential_ttl: Duration,
 
     account_authority: Option<AccountAuthorityInfo>,
+    events: AuthorityEvents,
 }
 
 impl CredentialIssuer {
@@ -33,11 +38,13 @@ impl CredentialIssuer {
     #[instrument(skip_all, fields(issuer = %issuer, project_identifier = project_identifier.clone(), credential_ttl = credential_ttl.map_or("n/a".to_string(), |d| d.as_secs().to_string())))]
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
         credentials: Arc<Credentials>,
         issuer: &Identifier,
         project_identifier: Option<String>, // Legacy value, should be removed when all clients are updated to the latest version
         credential_ttl: Option<Duration>,
         account_authority: Option<AccountAuthorityInfo>,
+        events: AuthorityEvents,
     ) -> Self {
         let subject_attributes = AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA);
         let subject_attributes = if let Some(project_identifier) = project_identifier {
@@ -53,11 +60,13 @@ impl CredentialIssuer {
 
         Self {
             members,
+            revocations,
             credentials,
             issuer: issuer.clone(),
             subject_attributes,
             credential_ttl: credential_ttl.unwrap_or(DEFAULT_CREDENTIAL_VALIDITY),
             account_authority,
+            events,
         }
     }
 
@@ -92,6 +101,7 @@ impl CredentialIssuer {
                         )
                         .await?;
                     info!("Successfully issued a credential for admin {}", subject);
+                    self.publish_credential_issued(subject).await?;
 
                     return Ok(Some(credential));
                 }
@@ -112,19 +122,52 @@ impl CredentialIssuer {
                 .insert(key.clone().into(), value.clone().into());
         }
 
+        // The time to live of the credential can be shortened by the enrollment token of the member
+        let credential_ttl = match member.credential_ttl() {
+            Some(member_ttl) => member_ttl.min(self.credential_ttl),
+            None => self.credential_ttl,
+        };
+
         let credential = self
             .credentials
+            .credentials_creation()
+            .issue_credential(&self.issuer, subject, subject_attributes, credential_ttl)
+            .await?;
+
+        info!(
+            "Successfully issued a credential for {}, valid for {}s",
+            subject,
+            credential_ttl.as_secs()
+        );
+        self.publish_credential_issued(subject).await?;
+
+        Ok(Some(credential))
+    }
+
+    /// Return the revocation list of the authority, signed as a credential issued to itself
+    #[instrument(skip_all)]
+    pub async fn revocation_list(&self) -> Result<CredentialAndPurposeKey> {
+        let revocation_list = self.revocations.get_revocation_list().await?;
+        self.credentials
             .credentials_creation()
             .issue_credential(
                 &self.issuer,
-                subject,
-                subject_attributes,
+                &self.issuer,
+                revocation_list.to_attributes(),
                 self.credential_ttl,
             )
-            .await?;
-
-        info!("Successfully issued a credential for {}", subject);
+            .await
+    }
 
-        Ok(Some(credential))
+    async fn publish_credential_issued(&self, subject: &Identifier) -> Result<()> {
+        self.events
+            .publish(AuthorityEvent::new(
+                AuthorityEventKind::CredentialIssued,
+                subject.clone(),
+                None,
+                now()?,
+            ))
+            .await;
+        Ok(())
     }
 }
@@ -4,7 +4,8 @@ use tracing::trace;
 
 use crate::authenticator::credential_issuer::CredentialIssuer;
 use crate::authenticator::direct::AccountAuthorityInfo;
-use crate::authenticator::AuthorityMembersRepository;
+use crate::authenticator::events::AuthorityEvents;
+use crate::authenticator::{AuthorityMembersRepository, AuthorityRevocationsRepository};
 use ockam::identity::{Credentials, Identifier, IdentitySecureChannelLocalInfo};
 use ockam_core::api::{Method, RequestHeader, Response};
 use ockam_core::compat::boxed::Box;
@@ -22,20 +23,24 @@ impl CredentialIssuerWorker {
     /// Create a new credentials issuer
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
         credentials: Arc<Credentials>,
         issuer: &Identifier,
         project_identifier: Option<String>, // Legacy value, should be removed when all clients are updated to the latest version
         credential_ttl: Option<Duration>,
         account_authority: Option<AccountAuthorityInfo>,
+        events: AuthorityEvents,
     ) -> Self {
         Self {
             credential_issuer: CredentialIssuer::new(
                 members,
+                revocations,
                 credentials,
                 issuer,
                 project_identifier,
                 credential_ttl,
                 account_authority,
+                events,
             ),
         }
     }
@@ -79,6 +84,12 @@ impl Worker for CredentialIssuerWorker {
                     Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
                 }
             }
+            (Some(Method::Get), "/revocations") => {
+                match self.credential_issuer.revocation_list().await {
+                    Ok(list) => Response::ok().with_headers(&req).body(list).to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
 
@@ -7,7 +7,7 @@ use ockam_core::api::Request;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, RevokeMember};
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
 
@@ -22,6 +22,13 @@ pub trait Members {
 
     async fn delete_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()>;
 
+    async fn revoke_member(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        reason: Option<String>,
+    ) -> miette::Result<()>;
+
     async fn list_member_ids(&self, ctx: &Context) -> miette::Result<Vec<Identifier>>;
 
     async fn list_members(
@@ -57,6 +64,22 @@ impl Members for AuthorityNodeClient {
             .into_diagnostic()
     }
 
+    async fn revoke_member(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        reason: Option<String>,
+    ) -> miette::Result<()> {
+        let req =
+            Request::post(format!("/members/{identifier}/revoke")).body(RevokeMember::new(reason));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
     async fn list_member_ids(&self, ctx: &Context) -> miette::Result<Vec<Identifier>> {
         let req = Request::get("/member_ids");
         self.get_secure_client()
@@ -9,7 +9,11 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
-use crate::authenticator::{AuthorityMember, AuthorityMembersRepository};
+use crate::authenticator::events::AuthorityEvents;
+use crate::authenticator::{
+    AuthorityEvent, AuthorityEventKind, AuthorityMember, AuthorityMembersRepository,
+    AuthorityRevocationsRepository,
+};
 
 /// Identity attribute key that indicates the role of the subject
 pub const OCKAM_ROLE_ATTRIBUTE_KEY: &str = "ockam-role";
@@ -24,7 +28,9 @@ pub type DirectAuthenticatorResult<T> = Either<T, DirectAuthenticatorError>;
 
 pub struct DirectAuthenticator {
     members: Arc<dyn AuthorityMembersRepository>,
+    revocations: Arc<dyn AuthorityRevocationsRepository>,
     account_authority: Option<AccountAuthorityInfo>,
+    events: AuthorityEvents,
 }
 #[derive(Clone)]
 pub struct AccountAuthorityInfo {
@@ -60,11 +66,15 @@ impl AccountAuthorityInfo {
 impl DirectAuthenticator {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
         account_authority: Option<AccountAuthorityInfo>,
+        events: AuthorityEvents,
     ) -> Self {
         Self {
             members,
+            revocations,
             account_authority,
+            events,
         }
     }
 
@@ -92,6 +102,21 @@ impl DirectAuthenticator {
             )));
         }
 
+        // Enrollers bound to an attribute template can only add members with enrollment tokens
+        if let Some(member) = self.members.get_member(enroller).await? {
+            if let Some(template) =
+                EnrollerAccessControlChecks::bin_attributes_ticket_template(member.attributes())
+            {
+                warn!(
+                    "Enroller {} bound to the template {} is trying to add member {} directly",
+                    enroller, template, identifier
+                );
+                return Ok(Either::Right(DirectAuthenticatorError(format!(
+                    "This enroller can only add members with enrollment tokens of the template {template}"
+                ))));
+            }
+        }
+
         let attrs = attributes
             .iter()
             .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
@@ -126,6 +151,14 @@ impl DirectAuthenticator {
             "Successfully added a member {} by {}. Attributes: {:?}",
             identifier, enroller, attributes
         );
+        self.events
+            .publish(AuthorityEvent::new(
+                AuthorityEventKind::MemberAdded,
+                identifier.clone(),
+                Some(enroller.clone()),
+                now()?,
+            ))
+            .await;
 
         Ok(Either::Left(()))
     }
@@ -170,6 +203,68 @@ impl DirectAuthenticator {
         &self,
         enroller: &Identifier,
         identifier: &Identifier,
+    ) -> Result<DirectAuthenticatorResult<()>> {
+        if let Either::Right(error) = self.check_can_delete_member(enroller, identifier).await? {
+            return Ok(Either::Right(error));
+        }
+
+        self.members.delete_member(identifier).await?;
+
+        info!("Successfully deleted member {}", identifier);
+        self.events
+            .publish(AuthorityEvent::new(
+                AuthorityEventKind::MemberDeleted,
+                identifier.clone(),
+                Some(enroller.clone()),
+                now()?,
+            ))
+            .await;
+
+        Ok(Either::Left(()))
+    }
+
+    /// Delete a member and add it to the revocation list published by the authority,
+    /// so that the credentials already issued to that member are rejected.
+    /// Return the new version of the revocation list
+    #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
+    pub async fn revoke_member(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
+        reason: Option<String>,
+    ) -> Result<DirectAuthenticatorResult<u64>> {
+        if let Either::Right(error) = self.check_can_delete_member(enroller, identifier).await? {
+            return Ok(Either::Right(error));
+        }
+
+        self.members.delete_member(identifier).await?;
+        let revoked_at = now()?;
+        let version = self
+            .revocations
+            .revoke(identifier, revoked_at, reason)
+            .await?;
+
+        info!(
+            "Successfully revoked member {}. Revocation list version: {}",
+            identifier, version
+        );
+        self.events
+            .publish(AuthorityEvent::new(
+                AuthorityEventKind::MemberRevoked,
+                identifier.clone(),
+                Some(enroller.clone()),
+                revoked_at,
+            ))
+            .await;
+
+        Ok(Either::Left(version))
+    }
+
+    /// Check that an enroller is allowed to delete, or revoke, a member
+    async fn check_can_delete_member(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
     ) -> Result<DirectAuthenticatorResult<()>> {
         let check_enroller = EnrollerAccessControlChecks::check_identifier(
             self.members.clone(),
@@ -215,10 +310,6 @@ impl DirectAuthenticator {
             )));
         }
 
-        self.members.delete_member(identifier).await?;
-
-        info!("Successfully deleted member {}", identifier);
-
         Ok(Either::Left(()))
     }
 }
@@ -8,9 +8,10 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, RevokeMember};
 use crate::authenticator::direct::DirectAuthenticator;
-use crate::authenticator::AuthorityMembersRepository;
+use crate::authenticator::events::AuthorityEvents;
+use crate::authenticator::{AuthorityMembersRepository, AuthorityRevocationsRepository};
 
 use super::AccountAuthorityInfo;
 
@@ -21,10 +22,17 @@ pub struct DirectAuthenticatorWorker {
 impl DirectAuthenticatorWorker {
     pub fn new(
         members: Arc<dyn AuthorityMembersRepository>,
+        revocations: Arc<dyn AuthorityRevocationsRepository>,
         account_authority: Option<AccountAuthorityInfo>,
+        events: AuthorityEvents,
     ) -> Self {
         Self {
-            authenticator: DirectAuthenticator::new(members, account_authority),
+            authenticator: DirectAuthenticator::new(
+                members,
+                revocations,
+                account_authority,
+                events,
+            ),
         }
     }
 }
@@ -101,6 +109,23 @@ impl Worker for DirectAuthenticatorWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Post), ["members", id, "revoke"]) => {
+                let identifier = Identifier::try_from(id.to_string())?;
+                let revoke: RevokeMember = if req.has_body() {
+                    dec.decode()?
+                } else {
+                    RevokeMember::default()
+                };
+                let res = self
+                    .authenticator
+                    .revoke_member(&from, &identifier, revoke.reason().cloned())
+                    .await?;
+
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
 
             _ => Response::unknown_path(&req).to_vec()?,
         };
@@ -1,3 +1,4 @@
+use crate::authenticator::AttributeConstraint;
 use minicbor::{Decode, Encode};
 use ockam::identity::Identifier;
 use std::collections::BTreeMap;
@@ -33,6 +34,23 @@ impl AddMember {
     }
 }
 
+#[derive(Debug, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevokeMember {
+    #[n(1)] reason: Option<String>,
+}
+
+impl RevokeMember {
+    pub fn new(reason: Option<String>) -> Self {
+        RevokeMember { reason }
+    }
+
+    pub fn reason(&self) -> Option<&String> {
+        self.reason.as_ref()
+    }
+}
+
 #[derive(Debug, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -40,6 +58,8 @@ pub struct CreateToken {
     #[b(1)] attributes: BTreeMap<String, String>,
     #[n(2)] ttl_secs: Option<u64>,
     #[n(3)] ttl_count: Option<u64>,
+    #[n(4)] credential_ttl_secs: Option<u64>,
+    #[n(5)] template: Option<String>,
 }
 
 impl CreateToken {
@@ -49,6 +69,8 @@ impl CreateToken {
             attributes: Default::default(),
             ttl_count: None,
             ttl_secs: None,
+            credential_ttl_secs: None,
+            template: None,
         }
     }
 
@@ -67,6 +89,18 @@ impl CreateToken {
         self
     }
 
+    /// Limit the time to live of the credentials issued to the members enrolled with the token
+    pub fn with_credential_ttl(mut self, credential_ttl: Option<Duration>) -> Self {
+        self.credential_ttl_secs = credential_ttl.map(|d| d.as_secs());
+        self
+    }
+
+    /// Constrain the attributes of the token with a template stored by the authority
+    pub fn with_template(mut self, template: Option<String>) -> Self {
+        self.template = template;
+        self
+    }
+
     pub fn into_owned_attributes(self) -> BTreeMap<String, String> {
         self.attributes.clone()
     }
@@ -78,4 +112,37 @@ impl CreateToken {
     pub fn ttl_secs(&self) -> Option<u64> {
         self.ttl_secs
     }
+
+    pub fn credential_ttl_secs(&self) -> Option<u64> {
+        self.credential_ttl_secs
+    }
+
+    pub fn template(&self) -> Option<&String> {
+        self.template.as_ref()
+    }
+}
+
+#[derive(Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CreateAttributeTemplate {
+    #[n(1)] name: String,
+    #[n(2)] attributes: BTreeMap<String, AttributeConstraint>,
+}
+
+impl CreateAttributeTemplate {
+    pub fn new(name: impl Into<String>, attributes: BTreeMap<String, AttributeConstraint>) -> Self {
+        CreateAttributeTemplate {
+            name: name.into(),
+            attributes,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn attributes(&self) -> &BTreeMap<String, AttributeConstraint> {
+        &self.attributes
+    }
 }
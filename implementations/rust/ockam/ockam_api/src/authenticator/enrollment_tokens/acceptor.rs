@@ -5,9 +5,11 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
 use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::events::AuthorityEvents;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
-    AuthorityEnrollmentTokenRepository, AuthorityMember, AuthorityMembersRepository,
+    AuthorityAttributeTemplatesRepository, AuthorityEnrollmentTokenRepository, AuthorityEvent,
+    AuthorityEventKind, AuthorityMember, AuthorityMembersRepository, EnrollmentToken,
 };
 
 pub struct EnrollmentTokenAcceptorError(pub String);
@@ -17,14 +19,23 @@ pub type EnrollmentTokenAcceptorResult<T> = Either<T, EnrollmentTokenAcceptorErr
 pub struct EnrollmentTokenAcceptor {
     pub(super) tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     pub(super) members: Arc<dyn AuthorityMembersRepository>,
+    pub(super) templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
+    pub(super) events: AuthorityEvents,
 }
 
 impl EnrollmentTokenAcceptor {
     pub fn new(
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
+        events: AuthorityEvents,
     ) -> Self {
-        Self { tokens, members }
+        Self {
+            tokens,
+            members,
+            templates,
+            events,
+        }
     }
 
     #[instrument(skip_all, fields(from = %from))]
@@ -64,6 +75,17 @@ impl EnrollmentTokenAcceptor {
             }
         };
 
+        // The attributes of the token are checked again, in case the token was altered after its issuance
+        if let Some(error) = self.check_template(&token).await? {
+            warn!(
+                "Enrollment token received from {} violates its template: {}. Reference: {}",
+                from,
+                error,
+                token.reference()
+            );
+            return Ok(Either::Right(EnrollmentTokenAcceptorError(error)));
+        }
+
         let reference = token.reference();
         let attrs = token
             .attrs
@@ -71,7 +93,9 @@ impl EnrollmentTokenAcceptor {
             .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
             .collect();
 
-        let member = AuthorityMember::new(from.clone(), attrs, token.issued_by, now()?, false);
+        let member =
+            AuthorityMember::new(from.clone(), attrs, token.issued_by.clone(), now()?, false)
+                .with_credential_ttl(token.credential_ttl);
 
         if let Err(err) = self.members.add_member(member).await {
             warn!(
@@ -87,7 +111,45 @@ impl EnrollmentTokenAcceptor {
             "Successfully accepted an enrollment token from {}. Reference: {}",
             from, reference
         );
+        self.events
+            .publish(
+                AuthorityEvent::new(
+                    AuthorityEventKind::EnrollmentTokenRedeemed,
+                    from.clone(),
+                    Some(token.issued_by),
+                    now()?,
+                )
+                .with_reference(token.reference),
+            )
+            .await;
 
         Ok(Either::Left(()))
     }
+
+    /// Check the attributes of a token against its template, and the template of its enroller.
+    /// Return an error message if the token is not valid
+    async fn check_template(&self, token: &EnrollmentToken) -> Result<Option<String>> {
+        let enroller_attributes = self
+            .members
+            .get_member(&token.issued_by)
+            .await?
+            .map(|m| m.attributes().clone())
+            .unwrap_or_default();
+
+        let bound =
+            EnrollerAccessControlChecks::bin_attributes_ticket_template(&enroller_attributes);
+        if bound.is_some() && bound != token.template {
+            return Ok(Some(
+                "The enrollment token was not issued with the template of its enroller".to_string(),
+            ));
+        }
+
+        let Some(name) = &token.template else {
+            return Ok(None);
+        };
+        match self.templates.get_template(name).await? {
+            Some(template) => Ok(template.check(&token.attrs, &enroller_attributes).err()),
+            None => Ok(Some(format!("Unknown attribute template {name}"))),
+        }
+    }
 }
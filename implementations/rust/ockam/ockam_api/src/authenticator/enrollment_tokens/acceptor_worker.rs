@@ -8,8 +8,12 @@ use ockam_node::Context;
 use tracing::trace;
 
 use crate::authenticator::enrollment_tokens::EnrollmentTokenAcceptor;
+use crate::authenticator::events::AuthorityEvents;
 use crate::authenticator::one_time_code::OneTimeCode;
-use crate::authenticator::{AuthorityEnrollmentTokenRepository, AuthorityMembersRepository};
+use crate::authenticator::{
+    AuthorityAttributeTemplatesRepository, AuthorityEnrollmentTokenRepository,
+    AuthorityMembersRepository,
+};
 
 pub struct EnrollmentTokenAcceptorWorker {
     pub(super) acceptor: EnrollmentTokenAcceptor,
@@ -19,9 +23,11 @@ impl EnrollmentTokenAcceptorWorker {
     pub fn new(
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
+        events: AuthorityEvents,
     ) -> Self {
         Self {
-            acceptor: EnrollmentTokenAcceptor::new(tokens, members),
+            acceptor: EnrollmentTokenAcceptor::new(tokens, members, templates, events),
         }
     }
 }
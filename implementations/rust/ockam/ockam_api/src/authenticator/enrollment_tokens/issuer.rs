@@ -13,6 +13,7 @@ use crate::authenticator::common::EnrollerAccessControlChecks;
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
+    AttributeConstraint, AuthorityAttributeTemplate, AuthorityAttributeTemplatesRepository,
     AuthorityEnrollmentTokenRepository, AuthorityMembersRepository, EnrollmentToken,
 };
 
@@ -25,6 +26,7 @@ pub type EnrollmentTokenIssuerResult<T> = Either<T, EnrollmentTokenIssuerError>;
 pub struct EnrollmentTokenIssuer {
     pub(super) tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     pub(super) members: Arc<dyn AuthorityMembersRepository>,
+    pub(super) templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
     pub(super) account_authority: Option<AccountAuthorityInfo>,
 }
 
@@ -32,22 +34,26 @@ impl EnrollmentTokenIssuer {
     pub fn new(
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             tokens,
             members,
+            templates,
             account_authority,
         }
     }
 
-    #[instrument(skip_all, fields(enroller = %enroller, token_duration = token_duration.map_or("n/a".to_string(), |d| d.as_secs().to_string()), ttl_count = ttl_count.map_or("n/a".to_string(), |t| t.to_string())))]
+    #[instrument(skip_all, fields(enroller = %enroller, token_duration = token_duration.map_or("n/a".to_string(), |d| d.as_secs().to_string()), ttl_count = ttl_count.map_or("n/a".to_string(), |t| t.to_string()), credential_ttl = credential_ttl.map_or("n/a".to_string(), |d| d.as_secs().to_string()), template = template.as_deref().unwrap_or("n/a")))]
     pub async fn issue_token(
         &self,
         enroller: &Identifier,
         attrs: BTreeMap<String, String>,
         token_duration: Option<Duration>,
         ttl_count: Option<u64>,
+        credential_ttl: Option<Duration>,
+        template: Option<String>,
     ) -> Result<EnrollmentTokenIssuerResult<OneTimeCode>> {
         let check = EnrollerAccessControlChecks::check_identifier(
             self.members.clone(),
@@ -66,6 +72,42 @@ impl EnrollmentTokenIssuer {
             )));
         }
 
+        // Complete and check the attributes with the template of the token
+        let enroller_attributes = self.enroller_attributes(enroller).await?;
+        if let Some(bound) =
+            EnrollerAccessControlChecks::bin_attributes_ticket_template(&enroller_attributes)
+        {
+            if template.as_ref() != Some(&bound) {
+                warn!(
+                    "Enroller {} is trying to issue an enrollment token without its template {}",
+                    enroller, bound
+                );
+                return Ok(Either::Right(EnrollmentTokenIssuerError(format!(
+                    "This enroller can only issue enrollment tokens with the template {bound}"
+                ))));
+            }
+        }
+        let attrs = match &template {
+            Some(name) => match self.templates.get_template(name).await? {
+                Some(template) => match template.apply(attrs, &enroller_attributes) {
+                    Ok(attrs) => attrs,
+                    Err(error) => {
+                        warn!(
+                            "Enroller {} is trying to issue an enrollment token violating its template: {}",
+                            enroller, error
+                        );
+                        return Ok(Either::Right(EnrollmentTokenIssuerError(error)));
+                    }
+                },
+                None => {
+                    return Ok(Either::Right(EnrollmentTokenIssuerError(format!(
+                        "Unknown attribute template {name}"
+                    ))));
+                }
+            },
+            None => attrs,
+        };
+
         // Check if we're trying to create an enroller
         if EnrollerAccessControlChecks::check_str_attributes_is_enroller(&attrs) {
             // Only pre-trusted identities will be able to add enrollers
@@ -98,6 +140,8 @@ impl EnrollmentTokenIssuer {
             expires_at,
             ttl_count,
             attrs,
+            credential_ttl,
+            template,
         };
         self.tokens.store_new_token(tkn).await?;
 
@@ -108,4 +152,79 @@ impl EnrollmentTokenIssuer {
 
         Ok(Either::Left(one_time_code))
     }
+
+    /// Create or replace an attribute template.
+    /// Only the admins which are not bound to a template can create templates
+    #[instrument(skip_all, fields(enroller = %enroller, name = %name))]
+    pub async fn create_template(
+        &self,
+        enroller: &Identifier,
+        name: &str,
+        attributes: BTreeMap<String, AttributeConstraint>,
+    ) -> Result<EnrollmentTokenIssuerResult<()>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+        let enroller_attributes = self.enroller_attributes(enroller).await?;
+
+        if !check.is_admin
+            || EnrollerAccessControlChecks::bin_attributes_ticket_template(&enroller_attributes)
+                .is_some()
+        {
+            warn!(
+                "Not admin {} is trying to create an attribute template",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "Not admin is trying to create an attribute template".to_string(),
+            )));
+        }
+
+        let template = AuthorityAttributeTemplate::new(name, attributes, enroller.clone(), now()?);
+        self.templates.store_template(&template).await?;
+        info!("Successfully created the attribute template {}", name);
+        Ok(Either::Left(()))
+    }
+
+    /// Return the attribute templates, if the caller is an enroller
+    #[instrument(skip_all, fields(enroller = %enroller))]
+    pub async fn list_templates(
+        &self,
+        enroller: &Identifier,
+    ) -> Result<EnrollmentTokenIssuerResult<Vec<AuthorityAttributeTemplate>>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to list the attribute templates",
+                enroller
+            );
+            return Ok(Either::Right(EnrollmentTokenIssuerError(
+                "Non-enroller is trying to list the attribute templates".to_string(),
+            )));
+        }
+
+        Ok(Either::Left(self.templates.get_templates().await?))
+    }
+
+    /// Return the attributes of the enroller, if it is a member
+    async fn enroller_attributes(
+        &self,
+        enroller: &Identifier,
+    ) -> Result<BTreeMap<Vec<u8>, Vec<u8>>> {
+        Ok(self
+            .members
+            .get_member(enroller)
+            .await?
+            .map(|m| m.attributes().clone())
+            .unwrap_or_default())
+    }
 }
@@ -6,8 +6,9 @@ use ockam_core::async_trait;
 use ockam_core::compat::time::Duration;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::types::{CreateAttributeTemplate, CreateToken};
 use crate::authenticator::one_time_code::OneTimeCode;
+use crate::authenticator::{AttributeConstraint, AuthorityAttributeTemplate};
 use crate::cloud::{AuthorityNodeClient, HasSecureClient};
 use crate::nodes::service::default_address::DefaultAddress;
 
@@ -19,22 +20,53 @@ pub trait TokenIssuer {
         attributes: BTreeMap<String, String>,
         duration: Option<Duration>,
         ttl_count: Option<u64>,
+        credential_ttl: Option<Duration>,
+    ) -> miette::Result<OneTimeCode> {
+        self.create_token_with_template(ctx, None, attributes, duration, ttl_count, credential_ttl)
+            .await
+    }
+
+    /// Create a token whose attributes are completed and constrained by an attribute template
+    async fn create_token_with_template(
+        &self,
+        ctx: &Context,
+        template: Option<String>,
+        attributes: BTreeMap<String, String>,
+        duration: Option<Duration>,
+        ttl_count: Option<u64>,
+        credential_ttl: Option<Duration>,
     ) -> miette::Result<OneTimeCode>;
+
+    async fn create_attribute_template(
+        &self,
+        ctx: &Context,
+        name: String,
+        attributes: BTreeMap<String, AttributeConstraint>,
+    ) -> miette::Result<()>;
+
+    async fn list_attribute_templates(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Vec<AuthorityAttributeTemplate>>;
 }
 
 #[async_trait]
 impl TokenIssuer for AuthorityNodeClient {
-    async fn create_token(
+    async fn create_token_with_template(
         &self,
         ctx: &Context,
+        template: Option<String>,
         attributes: BTreeMap<String, String>,
         duration: Option<Duration>,
         ttl_count: Option<u64>,
+        credential_ttl: Option<Duration>,
     ) -> miette::Result<OneTimeCode> {
         let body = CreateToken::new()
             .with_attributes(attributes)
             .with_ttl(duration)
-            .with_ttl_count(ttl_count);
+            .with_ttl_count(ttl_count)
+            .with_credential_ttl(credential_ttl)
+            .with_template(template);
 
         let req = Request::post("/").body(body);
         self.get_secure_client()
@@ -44,4 +76,32 @@ impl TokenIssuer for AuthorityNodeClient {
             .success()
             .into_diagnostic()
     }
+
+    async fn create_attribute_template(
+        &self,
+        ctx: &Context,
+        name: String,
+        attributes: BTreeMap<String, AttributeConstraint>,
+    ) -> miette::Result<()> {
+        let req = Request::post("/templates").body(CreateAttributeTemplate::new(name, attributes));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn list_attribute_templates(
+        &self,
+        ctx: &Context,
+    ) -> miette::Result<Vec<AuthorityAttributeTemplate>> {
+        let req = Request::get("/templates");
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::ENROLLMENT_TOKEN_ISSUER, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
 }
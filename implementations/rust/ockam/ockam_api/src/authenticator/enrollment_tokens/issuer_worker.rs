@@ -9,10 +9,13 @@ use ockam_core::compat::time::Duration;
 use ockam_core::{Result, Routed, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::CreateToken;
+use crate::authenticator::direct::types::{CreateAttributeTemplate, CreateToken};
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::enrollment_tokens::EnrollmentTokenIssuer;
-use crate::authenticator::{AuthorityEnrollmentTokenRepository, AuthorityMembersRepository};
+use crate::authenticator::{
+    AuthorityAttributeTemplatesRepository, AuthorityEnrollmentTokenRepository,
+    AuthorityMembersRepository,
+};
 
 pub struct EnrollmentTokenIssuerWorker {
     pub(super) issuer: EnrollmentTokenIssuer,
@@ -22,10 +25,11 @@ impl EnrollmentTokenIssuerWorker {
     pub fn new(
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
-            issuer: EnrollmentTokenIssuer::new(tokens, members, account_authority),
+            issuer: EnrollmentTokenIssuer::new(tokens, members, templates, account_authority),
         }
     }
 }
@@ -65,10 +69,19 @@ impl Worker for EnrollmentTokenIssuerWorker {
                 let att: CreateToken = dec.decode()?;
                 let duration = att.ttl_secs().map(Duration::from_secs);
                 let ttl_count = att.ttl_count();
+                let credential_ttl = att.credential_ttl_secs().map(Duration::from_secs);
+                let template = att.template().cloned();
 
                 let res = self
                     .issuer
-                    .issue_token(&from, att.into_owned_attributes(), duration, ttl_count)
+                    .issue_token(
+                        &from,
+                        att.into_owned_attributes(),
+                        duration,
+                        ttl_count,
+                        credential_ttl,
+                        template,
+                    )
                     .await?;
 
                 match res {
@@ -76,6 +89,28 @@ impl Worker for EnrollmentTokenIssuerWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Post), "/templates") => {
+                let create: CreateAttributeTemplate = dec.decode()?;
+                let res = self
+                    .issuer
+                    .create_template(&from, create.name(), create.attributes().clone())
+                    .await?;
+
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            (Some(Method::Get), "/templates") => {
+                let res = self.issuer.list_templates(&from).await?;
+
+                match res {
+                    Either::Left(templates) => {
+                        Response::ok().with_headers(&req).body(templates).to_vec()?
+                    }
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
         c.send(return_route, res).await
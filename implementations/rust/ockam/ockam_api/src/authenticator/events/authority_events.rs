@@ -0,0 +1,52 @@
+use ockam::identity::TimestampInSeconds;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+
+use crate::authenticator::events::AuthorityEventsWebhook;
+use crate::authenticator::{AuthorityEvent, AuthorityEventsRepository};
+
+/// This struct publishes the events produced by the Authority services.
+///
+/// Events are persisted, so that they can be listed later on, and are optionally
+/// sent to a webhook.
+#[derive(Clone)]
+pub struct AuthorityEvents {
+    repository: Arc<dyn AuthorityEventsRepository>,
+    webhook: Option<Arc<AuthorityEventsWebhook>>,
+}
+
+impl AuthorityEvents {
+    pub fn new(repository: Arc<dyn AuthorityEventsRepository>) -> Self {
+        Self {
+            repository,
+            webhook: None,
+        }
+    }
+
+    /// Also send the events to a webhook
+    pub fn with_webhook(mut self, webhook: AuthorityEventsWebhook) -> Self {
+        self.webhook = Some(Arc::new(webhook));
+        self
+    }
+
+    /// Persist an event and notify the webhook if there is one.
+    /// Failing to publish an event must not fail the operation which produced it,
+    /// so errors are only logged here.
+    #[instrument(skip_all, fields(kind = %event.kind, subject = %event.subject))]
+    pub async fn publish(&self, event: AuthorityEvent) {
+        if let Err(e) = self.repository.store_event(&event).await {
+            warn!("Error storing the authority event {}: {}", event.kind, e);
+        }
+        if let Some(webhook) = &self.webhook {
+            webhook.notify(event);
+        }
+    }
+
+    /// Return the events created at, or after, the given timestamp
+    pub async fn get_events(
+        &self,
+        since: Option<TimestampInSeconds>,
+    ) -> Result<Vec<AuthorityEvent>> {
+        self.repository.get_events(since).await
+    }
+}
@@ -0,0 +1,40 @@
+use miette::IntoDiagnostic;
+
+use ockam::identity::TimestampInSeconds;
+use ockam_core::api::Request;
+use ockam_core::async_trait;
+use ockam_node::Context;
+
+use crate::authenticator::AuthorityEvent;
+use crate::cloud::{AuthorityNodeClient, HasSecureClient};
+use crate::nodes::service::default_address::DefaultAddress;
+
+#[async_trait]
+pub trait Events {
+    /// Return the events produced by the authority, created at or after the `since` timestamp
+    async fn list_events(
+        &self,
+        ctx: &Context,
+        since: Option<TimestampInSeconds>,
+    ) -> miette::Result<Vec<AuthorityEvent>>;
+}
+
+#[async_trait]
+impl Events for AuthorityNodeClient {
+    async fn list_events(
+        &self,
+        ctx: &Context,
+        since: Option<TimestampInSeconds>,
+    ) -> miette::Result<Vec<AuthorityEvent>> {
+        let req = match since {
+            Some(since) => Request::get(format!("/since/{}", since.0)),
+            None => Request::get("/"),
+        };
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::AUTHORITY_EVENTS, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+}
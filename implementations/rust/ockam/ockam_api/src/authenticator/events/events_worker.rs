@@ -0,0 +1,104 @@
+use minicbor::Decoder;
+use tracing::trace;
+
+use ockam::identity::{IdentitySecureChannelLocalInfo, TimestampInSeconds};
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, Worker};
+use ockam_node::Context;
+
+use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::direct::AccountAuthorityInfo;
+use crate::authenticator::events::AuthorityEvents;
+use crate::authenticator::AuthorityMembersRepository;
+
+/// This worker lets enrollers list the events produced by the Authority
+pub struct AuthorityEventsWorker {
+    events: AuthorityEvents,
+    members: Arc<dyn AuthorityMembersRepository>,
+    account_authority: Option<AccountAuthorityInfo>,
+}
+
+impl AuthorityEventsWorker {
+    pub fn new(
+        events: AuthorityEvents,
+        members: Arc<dyn AuthorityMembersRepository>,
+        account_authority: Option<AccountAuthorityInfo>,
+    ) -> Self {
+        Self {
+            events,
+            members,
+            account_authority,
+        }
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for AuthorityEventsWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match IdentitySecureChannelLocalInfo::find_info(m.local_message())
+        {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = secure_channel_info.their_identity_id();
+        let return_route = m.return_route();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "authority_events",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+
+        let check = EnrollerAccessControlChecks::check_identifier(
+            self.members.clone(),
+            &from,
+            &self.account_authority,
+        )
+        .await?;
+        if !check.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to list the authority events",
+                from
+            );
+            let res =
+                Response::forbidden(&req, "Non-enroller is trying to list events").to_vec()?;
+            return c.send(return_route, res).await;
+        }
+
+        let path_segments = req.path_segments::<5>();
+        let res = match (req.method(), path_segments.as_slice()) {
+            (Some(Method::Get), [""]) => {
+                let events = self.events.get_events(None).await?;
+                Response::ok().with_headers(&req).body(events).to_vec()?
+            }
+            (Some(Method::Get), ["since", since]) => match since.parse::<u64>() {
+                Ok(since) => {
+                    let events = self
+                        .events
+                        .get_events(Some(TimestampInSeconds(since)))
+                        .await?;
+                    Response::ok().with_headers(&req).body(events).to_vec()?
+                }
+                Err(_) => Response::bad_request(&req, "invalid timestamp").to_vec()?,
+            },
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+
+        c.send(return_route, res).await
+    }
+}
@@ -0,0 +1,9 @@
+mod authority_events;
+mod client;
+mod events_worker;
+mod webhook;
+
+pub use authority_events::*;
+pub use client::*;
+pub use events_worker::*;
+pub use webhook::*;
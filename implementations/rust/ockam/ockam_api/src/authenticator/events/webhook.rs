@@ -0,0 +1,89 @@
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use reqwest::Url;
+
+use crate::authenticator::AuthorityEvent;
+
+/// Default number of attempts to deliver an event to the webhook
+pub const DEFAULT_WEBHOOK_MAX_ATTEMPTS: u32 = 5;
+
+/// Default delay before retrying to deliver an event. It is doubled after each failed attempt
+pub const DEFAULT_WEBHOOK_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Maximum delay between two attempts to deliver an event
+const MAX_WEBHOOK_BACKOFF: Duration = Duration::from_secs(60);
+
+/// This struct POSTs authority events, as JSON, to a configured URL
+pub struct AuthorityEventsWebhook {
+    url: Url,
+    client: reqwest::Client,
+    max_attempts: u32,
+    initial_backoff: Duration,
+}
+
+impl AuthorityEventsWebhook {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::new(),
+            max_attempts: DEFAULT_WEBHOOK_MAX_ATTEMPTS,
+            initial_backoff: DEFAULT_WEBHOOK_INITIAL_BACKOFF,
+        }
+    }
+
+    /// Set the number of delivery attempts and the delay before the first retry
+    pub fn with_retries(mut self, max_attempts: u32, initial_backoff: Duration) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Deliver the event in the background, so that a slow webhook does not delay the authority
+    pub(crate) fn notify(self: &Arc<Self>, event: AuthorityEvent) {
+        let webhook = self.clone();
+        tokio::spawn(async move { webhook.deliver(&event).await });
+    }
+
+    /// Try to deliver an event, with an exponential backoff between attempts.
+    /// Return true if the webhook accepted the event
+    pub(crate) async fn deliver(&self, event: &AuthorityEvent) -> bool {
+        let mut backoff = self.initial_backoff;
+        for attempt in 1..=self.max_attempts {
+            match self.client.post(self.url.clone()).json(event).send().await {
+                Ok(response) if response.status().is_success() => {
+                    debug!(url = %self.url, "delivered the authority event {}", event.kind);
+                    return true;
+                }
+                Ok(response) => warn!(
+                    url = %self.url,
+                    attempt,
+                    "the webhook rejected the authority event {}: {}",
+                    event.kind,
+                    response.status()
+                ),
+                Err(e) => warn!(
+                    url = %self.url,
+                    attempt,
+                    "failed to send the authority event {}: {}",
+                    event.kind,
+                    e
+                ),
+            }
+            if attempt < self.max_attempts {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(MAX_WEBHOOK_BACKOFF);
+            }
+        }
+        error!(
+            url = %self.url,
+            "giving up on delivering the authority event {} after {} attempts",
+            event.kind,
+            self.max_attempts
+        );
+        false
+    }
+}
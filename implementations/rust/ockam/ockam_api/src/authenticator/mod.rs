@@ -1,6 +1,7 @@
 pub mod credential_issuer;
 pub mod direct;
 pub mod enrollment_tokens;
+pub mod events;
 pub mod one_time_code;
 
 pub(crate) mod common;
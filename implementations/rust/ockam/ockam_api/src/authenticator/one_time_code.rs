@@ -10,12 +10,14 @@ use ockam_core::Error;
 use ockam_core::Result;
 use ockam_node::database::{SqlxType, ToSqlxType};
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use std::fmt::{Debug, Display, Formatter};
+
+use crate::redaction::REDACTED;
 
 /// A one-time code can be used to enroll
 /// a node with some authenticated attributes
 /// It can be retrieve with a command like `ockam project ticket --attribute component=control`
-#[derive(Debug, Clone, Decode, Encode, PartialEq, Eq)]
+#[derive(Clone, Decode, Encode, PartialEq, Eq)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct OneTimeCode {
@@ -50,6 +52,15 @@ impl From<[u8; 32]> for OneTimeCode {
     }
 }
 
+/// The code is a bearer secret, so it is never displayed in the logs
+impl Debug for OneTimeCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OneTimeCode")
+            .field("code", &REDACTED)
+            .finish()
+    }
+}
+
 impl FromStr for OneTimeCode {
     type Err = Error;
 
@@ -108,6 +119,12 @@ mod test {
     use quickcheck::{Arbitrary, Gen};
     use quickcheck_macros::quickcheck;
 
+    #[test]
+    fn test_debug_does_not_display_the_code() {
+        let one_time_code = OneTimeCode::new();
+        assert!(!format!("{one_time_code:?}").contains(&one_time_code.to_string()));
+    }
+
     #[quickcheck]
     fn test_from_to_string(one_time_code: OneTimeCode) -> bool {
         OneTimeCode::from_str(one_time_code.to_string().as_str()).ok() == Some(one_time_code)
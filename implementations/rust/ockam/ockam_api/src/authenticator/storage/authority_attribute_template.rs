@@ -0,0 +1,271 @@
+use minicbor::{Decode, Encode};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Member attribute binding an enroller to an attribute template.
+/// An enroller with this attribute can only issue enrollment tokens with the template named by its value,
+/// and can't create templates
+pub const OCKAM_TICKET_TEMPLATE_ATTRIBUTE_KEY: &str = "ockam-ticket-template";
+
+/// Named set of constraints on the attributes granted by an enrollment token.
+/// A token referencing a template can only grant the attributes listed in the template,
+/// with values allowed by their constraint
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthorityAttributeTemplate {
+    #[n(1)] pub name: String,
+    /// Allowed attributes and the constraint on their value
+    #[n(2)] pub attributes: BTreeMap<String, AttributeConstraint>,
+    /// Identity which created the template
+    #[n(3)] pub created_by: Identifier,
+    #[n(4)] pub created_at: TimestampInSeconds,
+}
+
+/// Constraint on the value of an attribute allowed by a template
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[serde(rename_all = "snake_case")]
+pub enum AttributeConstraint {
+    /// Any value is allowed
+    #[n(0)] Any,
+    /// The attribute always has this value
+    #[n(1)] Fixed(#[n(0)] String),
+    /// The value must be one of these values
+    #[n(2)] OneOf(#[n(0)] Vec<String>),
+    /// The attribute has the value of the same attribute of the enroller issuing the token
+    #[n(3)] Enroller,
+}
+
+impl AuthorityAttributeTemplate {
+    pub fn new(
+        name: impl Into<String>,
+        attributes: BTreeMap<String, AttributeConstraint>,
+        created_by: Identifier,
+        created_at: TimestampInSeconds,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            attributes,
+            created_by,
+            created_at,
+        }
+    }
+
+    /// Complete the attributes requested for a token with the values which are set by the template,
+    /// then check them against the template
+    pub fn apply(
+        &self,
+        mut attributes: BTreeMap<String, String>,
+        enroller_attributes: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<BTreeMap<String, String>, String> {
+        for (key, constraint) in &self.attributes {
+            if attributes.contains_key(key) {
+                continue;
+            }
+            let value = match constraint {
+                AttributeConstraint::Fixed(value) => Some(value.clone()),
+                AttributeConstraint::Enroller => enroller_value(enroller_attributes, key),
+                AttributeConstraint::Any | AttributeConstraint::OneOf(_) => None,
+            };
+            if let Some(value) = value {
+                attributes.insert(key.clone(), value);
+            }
+        }
+        self.check(&attributes, enroller_attributes)?;
+        Ok(attributes)
+    }
+
+    /// Check that the attributes of a token are allowed by the template.
+    /// The attributes with a fixed value, or the value of the enroller, must be present
+    pub fn check(
+        &self,
+        attributes: &BTreeMap<String, String>,
+        enroller_attributes: &BTreeMap<Vec<u8>, Vec<u8>>,
+    ) -> Result<(), String> {
+        for (key, value) in attributes {
+            let allowed = match self.attributes.get(key) {
+                None => {
+                    return Err(format!(
+                        "The attribute {key} is not allowed by the template {}",
+                        self.name
+                    ))
+                }
+                Some(AttributeConstraint::Any) => true,
+                Some(AttributeConstraint::Fixed(fixed)) => value == fixed,
+                Some(AttributeConstraint::OneOf(values)) => values.contains(value),
+                Some(AttributeConstraint::Enroller) => {
+                    enroller_value(enroller_attributes, key).as_ref() == Some(value)
+                }
+            };
+            if !allowed {
+                return Err(format!(
+                    "The value {value} of the attribute {key} is not allowed by the template {}",
+                    self.name
+                ));
+            }
+        }
+        for (key, constraint) in &self.attributes {
+            if matches!(
+                constraint,
+                AttributeConstraint::Fixed(_) | AttributeConstraint::Enroller
+            ) && !attributes.contains_key(key)
+            {
+                return Err(format!(
+                    "The attribute {key} is required by the template {}",
+                    self.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn enroller_value(enroller_attributes: &BTreeMap<Vec<u8>, Vec<u8>>, key: &str) -> Option<String> {
+    enroller_attributes
+        .get(key.as_bytes())
+        .and_then(|v| String::from_utf8(v.clone()).ok())
+}
+
+/// The textual representation of a constraint is:
+///  - `*` for any value
+///  - `@enroller` for the value of the enroller
+///  - `a|b|c` for a set of values
+///  - a single value for a fixed value
+impl Display for AttributeConstraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AttributeConstraint::Any => f.write_str("*"),
+            AttributeConstraint::Fixed(value) => f.write_str(value),
+            AttributeConstraint::OneOf(values) => f.write_str(&values.join("|")),
+            AttributeConstraint::Enroller => f.write_str("@enroller"),
+        }
+    }
+}
+
+impl FromStr for AttributeConstraint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "*" => Ok(AttributeConstraint::Any),
+            "@enroller" => Ok(AttributeConstraint::Enroller),
+            _ if s.contains('|') => Ok(AttributeConstraint::OneOf(
+                s.split('|').map(|v| v.to_string()).collect(),
+            )),
+            _ => Ok(AttributeConstraint::Fixed(s.to_string())),
+        }
+    }
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuthorityAttributeTemplateRow {
+    name: String,
+    attributes: Vec<u8>,
+    created_by: String,
+    created_at: i64,
+}
+
+impl TryFrom<AuthorityAttributeTemplateRow> for AuthorityAttributeTemplate {
+    type Error = Error;
+
+    fn try_from(value: AuthorityAttributeTemplateRow) -> Result<Self, Self::Error> {
+        Ok(AuthorityAttributeTemplate {
+            name: value.name,
+            attributes: minicbor::decode(&value.attributes)?,
+            created_by: Identifier::from_str(&value.created_by)?,
+            created_at: TimestampInSeconds(value.created_at as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_attributes_are_checked_against_the_template() {
+        let template = AuthorityAttributeTemplate::new(
+            "team-lead",
+            BTreeMap::from([
+                ("team".to_string(), AttributeConstraint::Enroller),
+                (
+                    "role".to_string(),
+                    AttributeConstraint::OneOf(vec!["dev".to_string(), "ops".to_string()]),
+                ),
+                (
+                    "env".to_string(),
+                    AttributeConstraint::Fixed("prod".to_string()),
+                ),
+                ("note".to_string(), AttributeConstraint::Any),
+            ]),
+            Identifier::from_str(
+                "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            )
+            .unwrap(),
+            TimestampInSeconds(0),
+        );
+        let enroller = BTreeMap::from([(b"team".to_vec(), b"blue".to_vec())]);
+        let attributes = |attrs: &[(&str, &str)]| {
+            attrs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        // the fixed values and the values of the enroller are set by the template
+        let applied = template
+            .apply(attributes(&[("role", "dev"), ("note", "x")]), &enroller)
+            .unwrap();
+        assert_eq!(
+            applied,
+            attributes(&[
+                ("env", "prod"),
+                ("note", "x"),
+                ("role", "dev"),
+                ("team", "blue")
+            ])
+        );
+        assert!(template.check(&applied, &enroller).is_ok());
+
+        assert!(template
+            .apply(attributes(&[("team", "red")]), &enroller)
+            .is_err());
+        assert!(template
+            .apply(attributes(&[("role", "admin")]), &enroller)
+            .is_err());
+        assert!(template
+            .apply(attributes(&[("env", "dev")]), &enroller)
+            .is_err());
+        assert!(template
+            .apply(attributes(&[("ockam-role", "enroller")]), &enroller)
+            .is_err());
+        // the enroller doesn't have a team
+        assert!(template.apply(attributes(&[]), &BTreeMap::new()).is_err());
+        // the values set by the template can't be left out of a token
+        assert!(template
+            .check(&attributes(&[("team", "blue")]), &enroller)
+            .is_err());
+    }
+
+    #[test]
+    fn constraints_are_parsed_from_their_textual_representation() {
+        for (s, constraint) in [
+            ("*", AttributeConstraint::Any),
+            ("@enroller", AttributeConstraint::Enroller),
+            ("prod", AttributeConstraint::Fixed("prod".to_string())),
+            (
+                "dev|ops",
+                AttributeConstraint::OneOf(vec!["dev".to_string(), "ops".to_string()]),
+            ),
+        ] {
+            assert_eq!(AttributeConstraint::from_str(s).unwrap(), constraint);
+            assert_eq!(constraint.to_string(), s);
+        }
+    }
+}
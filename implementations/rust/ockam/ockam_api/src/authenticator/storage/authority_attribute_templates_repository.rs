@@ -0,0 +1,19 @@
+use crate::authenticator::AuthorityAttributeTemplate;
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the attribute templates used to constrain the attributes
+/// granted by enrollment tokens
+#[async_trait]
+pub trait AuthorityAttributeTemplatesRepository: Send + Sync + 'static {
+    /// Store a template, replacing the template with the same name if it exists
+    async fn store_template(&self, template: &AuthorityAttributeTemplate) -> Result<()>;
+
+    /// Return the template with a given name
+    async fn get_template(&self, name: &str) -> Result<Option<AuthorityAttributeTemplate>>;
+
+    /// Return all the templates, sorted by name
+    async fn get_templates(&self) -> Result<Vec<AuthorityAttributeTemplate>>;
+}
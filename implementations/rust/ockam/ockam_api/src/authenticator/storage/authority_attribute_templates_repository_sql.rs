@@ -0,0 +1,134 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::authenticator::{
+    AuthorityAttributeTemplate, AuthorityAttributeTemplateRow,
+    AuthorityAttributeTemplatesRepository,
+};
+
+/// Implementation of [`AuthorityAttributeTemplatesRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct AuthorityAttributeTemplatesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityAttributeTemplatesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for authority attribute templates");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority attribute templates").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityAttributeTemplatesRepository for AuthorityAttributeTemplatesSqlxDatabase {
+    async fn store_template(&self, template: &AuthorityAttributeTemplate) -> Result<()> {
+        let query = query(
+            "INSERT OR REPLACE INTO authority_attribute_template (name, attributes, created_by, created_at) VALUES (?1, ?2, ?3, ?4)",
+        )
+        .bind(template.name.to_sql())
+        .bind(minicbor::to_vec(&template.attributes)?.to_sql())
+        .bind(template.created_by.to_sql())
+        .bind(template.created_at.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_template(&self, name: &str) -> Result<Option<AuthorityAttributeTemplate>> {
+        let query = query_as(
+            "SELECT name, attributes, created_by, created_at FROM authority_attribute_template WHERE name=?",
+        )
+        .bind(name.to_sql());
+        let row: Option<AuthorityAttributeTemplateRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.try_into()).transpose()
+    }
+
+    async fn get_templates(&self) -> Result<Vec<AuthorityAttributeTemplate>> {
+        let query = query_as(
+            "SELECT name, attributes, created_by, created_at FROM authority_attribute_template ORDER BY name",
+        );
+        let rows: Vec<AuthorityAttributeTemplateRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::AttributeConstraint;
+    use ockam::identity::{Identifier, TimestampInSeconds};
+    use ockam_core::compat::sync::Arc;
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_authority_attribute_templates_repository() -> Result<()> {
+        let repository = create_repository().await?;
+        assert!(repository.get_templates().await?.is_empty());
+
+        let admin = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let team_lead = AuthorityAttributeTemplate::new(
+            "team-lead",
+            BTreeMap::from([("team".to_string(), AttributeConstraint::Enroller)]),
+            admin.clone(),
+            TimestampInSeconds(100),
+        );
+        let developer = AuthorityAttributeTemplate::new(
+            "developer",
+            BTreeMap::from([(
+                "role".to_string(),
+                AttributeConstraint::Fixed("developer".to_string()),
+            )]),
+            admin.clone(),
+            TimestampInSeconds(200),
+        );
+        repository.store_template(&team_lead).await?;
+        repository.store_template(&developer).await?;
+
+        assert_eq!(
+            repository.get_templates().await?,
+            vec![developer.clone(), team_lead.clone()]
+        );
+        assert_eq!(
+            repository.get_template("team-lead").await?,
+            Some(team_lead.clone())
+        );
+        assert_eq!(repository.get_template("unknown").await?, None);
+
+        // a template can be replaced
+        let team_lead = AuthorityAttributeTemplate::new(
+            "team-lead",
+            BTreeMap::from([("team".to_string(), AttributeConstraint::Any)]),
+            admin,
+            TimestampInSeconds(300),
+        );
+        repository.store_template(&team_lead).await?;
+        assert_eq!(repository.get_template("team-lead").await?, Some(team_lead));
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository() -> Result<Arc<dyn AuthorityAttributeTemplatesRepository>> {
+        Ok(Arc::new(
+            AuthorityAttributeTemplatesSqlxDatabase::create().await?,
+        ))
+    }
+}
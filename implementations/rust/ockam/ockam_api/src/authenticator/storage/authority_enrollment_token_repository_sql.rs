@@ -50,7 +50,7 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
 
         let mut transaction = self.database.pool.begin().await.into_core()?;
 
-        let query2 = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes FROM authority_enrollment_token WHERE one_time_code=?")
+        let query2 = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes, credential_ttl, template FROM authority_enrollment_token WHERE one_time_code=?")
             .bind(one_time_code.to_sql());
         let row: Option<EnrollmentTokenRow> =
             query2.fetch_optional(&mut *transaction).await.into_core()?;
@@ -88,7 +88,7 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
 
     async fn store_new_token(&self, token: EnrollmentToken) -> Result<()> {
         let query = query(
-            "INSERT OR REPLACE INTO authority_enrollment_token (one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            "INSERT OR REPLACE INTO authority_enrollment_token (one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes, credential_ttl, template) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
         )
         .bind(token.one_time_code.to_sql())
         .bind(token.reference.map(|r| r.to_sql()))
@@ -96,7 +96,9 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
         .bind(token.created_at.to_sql())
         .bind(token.expires_at.to_sql())
         .bind(token.ttl_count.to_sql())
-        .bind(minicbor::to_vec(token.attrs)?.to_sql());
+        .bind(minicbor::to_vec(token.attrs)?.to_sql())
+        .bind(token.credential_ttl.map(|ttl| ttl.as_secs().to_sql()))
+        .bind(token.template.map(|t| t.to_sql()));
 
         query.execute(&*self.database.pool).await.void()
     }
@@ -137,6 +139,8 @@ mod tests {
             expires_at,
             ttl_count: 1,
             attrs: attrs.clone(),
+            credential_ttl: None,
+            template: None,
         };
 
         repository.store_new_token(token).await?;
@@ -184,6 +188,8 @@ mod tests {
             expires_at,
             ttl_count: 1,
             attrs: attrs.clone(),
+            credential_ttl: Some(Duration::from_secs(900)),
+            template: Some("team-lead".to_string()),
         };
 
         repository.store_new_token(token).await?;
@@ -198,6 +204,8 @@ mod tests {
         assert_eq!(token1.expires_at, expires_at);
         assert_eq!(token1.ttl_count, 1);
         assert_eq!(token1.attrs, attrs);
+        assert_eq!(token1.credential_ttl, Some(Duration::from_secs(900)));
+        assert_eq!(token1.template, Some("team-lead".to_string()));
 
         Ok(())
     }
@@ -227,6 +235,8 @@ mod tests {
             expires_at,
             ttl_count: 2,
             attrs: attrs.clone(),
+            credential_ttl: None,
+            template: None,
         };
 
         repository.store_new_token(token).await?;
@@ -279,6 +289,8 @@ mod tests {
             expires_at,
             ttl_count: 1,
             attrs: attrs.clone(),
+            credential_ttl: None,
+            template: None,
         };
 
         repository.store_new_token(token).await?;
@@ -0,0 +1,117 @@
+use minicbor::{Decode, Encode};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Event produced by the Authority node when the set of members,
+/// or the credentials given to those members, change
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthorityEvent {
+    /// Kind of event
+    #[n(1)] pub kind: AuthorityEventKind,
+    /// Identity which received a credential, redeemed a token or was added / deleted
+    #[n(2)] pub subject: Identifier,
+    /// Identity responsible for the event: the enroller adding a member or the issuer of a token
+    #[n(3)] pub actor: Option<Identifier>,
+    /// Reference of the redeemed enrollment token
+    #[n(4)] pub reference: Option<String>,
+    /// Time of the event
+    #[n(5)] pub created_at: TimestampInSeconds,
+}
+
+impl AuthorityEvent {
+    pub fn new(
+        kind: AuthorityEventKind,
+        subject: Identifier,
+        actor: Option<Identifier>,
+        created_at: TimestampInSeconds,
+    ) -> Self {
+        Self {
+            kind,
+            subject,
+            actor,
+            reference: None,
+            created_at,
+        }
+    }
+
+    pub fn with_reference(mut self, reference: Option<String>) -> Self {
+        self.reference = reference;
+        self
+    }
+}
+
+/// Kinds of events produced by the Authority node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorityEventKind {
+    #[n(0)] CredentialIssued,
+    #[n(1)] EnrollmentTokenRedeemed,
+    #[n(2)] MemberAdded,
+    #[n(3)] MemberDeleted,
+    #[n(4)] MemberRevoked,
+}
+
+impl Display for AuthorityEventKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            AuthorityEventKind::CredentialIssued => "credential_issued",
+            AuthorityEventKind::EnrollmentTokenRedeemed => "enrollment_token_redeemed",
+            AuthorityEventKind::MemberAdded => "member_added",
+            AuthorityEventKind::MemberDeleted => "member_deleted",
+            AuthorityEventKind::MemberRevoked => "member_revoked",
+        })
+    }
+}
+
+impl FromStr for AuthorityEventKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "credential_issued" => Ok(AuthorityEventKind::CredentialIssued),
+            "enrollment_token_redeemed" => Ok(AuthorityEventKind::EnrollmentTokenRedeemed),
+            "member_added" => Ok(AuthorityEventKind::MemberAdded),
+            "member_deleted" => Ok(AuthorityEventKind::MemberDeleted),
+            "member_revoked" => Ok(AuthorityEventKind::MemberRevoked),
+            _ => Err(Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("unknown authority event kind {s}"),
+            )),
+        }
+    }
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuthorityEventRow {
+    kind: String,
+    subject: String,
+    actor: Option<String>,
+    reference: Option<String>,
+    created_at: i64,
+}
+
+impl TryFrom<AuthorityEventRow> for AuthorityEvent {
+    type Error = Error;
+
+    fn try_from(value: AuthorityEventRow) -> Result<Self, Self::Error> {
+        Ok(AuthorityEvent {
+            kind: AuthorityEventKind::from_str(&value.kind)?,
+            subject: Identifier::from_str(&value.subject)?,
+            actor: value
+                .actor
+                .map(|actor| Identifier::from_str(&actor))
+                .transpose()?,
+            reference: value.reference,
+            created_at: TimestampInSeconds(value.created_at as u64),
+        })
+    }
+}
@@ -0,0 +1,16 @@
+use crate::authenticator::AuthorityEvent;
+use ockam::identity::TimestampInSeconds;
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the events produced by the Authority node
+#[async_trait]
+pub trait AuthorityEventsRepository: Send + Sync + 'static {
+    /// Store a new event. The oldest events are deleted when the maximum number of events is reached
+    async fn store_event(&self, event: &AuthorityEvent) -> Result<()>;
+
+    /// Return the events created at, or after, the given timestamp, oldest first
+    async fn get_events(&self, since: Option<TimestampInSeconds>) -> Result<Vec<AuthorityEvent>>;
+}
@@ -0,0 +1,160 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::TimestampInSeconds;
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::authenticator::{AuthorityEvent, AuthorityEventRow, AuthorityEventsRepository};
+
+/// Maximum number of events kept in the database
+pub const DEFAULT_MAX_AUTHORITY_EVENTS: u64 = 10_000;
+
+/// Implementation of [`AuthorityEventsRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct AuthorityEventsSqlxDatabase {
+    database: SqlxDatabase,
+    max_events: u64,
+}
+
+impl AuthorityEventsSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for authority events");
+        Self {
+            database,
+            max_events: DEFAULT_MAX_AUTHORITY_EVENTS,
+        }
+    }
+
+    /// Set the maximum number of events kept in the database
+    pub fn with_max_events(mut self, max_events: u64) -> Self {
+        self.max_events = max_events;
+        self
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority events").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityEventsRepository for AuthorityEventsSqlxDatabase {
+    async fn store_event(&self, event: &AuthorityEvent) -> Result<()> {
+        let mut transaction = self.database.pool.begin().await.into_core()?;
+
+        let query1 = query(
+            "INSERT INTO authority_events (kind, subject, actor, reference, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(event.kind.to_string().to_sql())
+        .bind(event.subject.to_sql())
+        .bind(event.actor.as_ref().map(|a| a.to_sql()))
+        .bind(event.reference.as_ref().map(|r| r.to_sql()))
+        .bind(event.created_at.to_sql());
+        query1.execute(&mut *transaction).await.void()?;
+
+        // Only keep the most recent events
+        let query2 = query(
+            "DELETE FROM authority_events WHERE id <= (SELECT MAX(id) FROM authority_events) - ?",
+        )
+        .bind(self.max_events as i64);
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_events(&self, since: Option<TimestampInSeconds>) -> Result<Vec<AuthorityEvent>> {
+        let since = since.unwrap_or(TimestampInSeconds(0));
+        let query = query_as("SELECT kind, subject, actor, reference, created_at FROM authority_events WHERE created_at >= ? ORDER BY id")
+            .bind(since.to_sql());
+        let rows: Vec<AuthorityEventRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::AuthorityEventKind;
+    use ockam::identity::Identifier;
+    use ockam_core::compat::sync::Arc;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_authority_events_repository() -> Result<()> {
+        let repository = create_repository(10).await?;
+
+        let subject = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let actor = Identifier::from_str(
+            "Ifedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210",
+        )
+        .ok();
+
+        let event1 = AuthorityEvent::new(
+            AuthorityEventKind::MemberAdded,
+            subject.clone(),
+            actor.clone(),
+            TimestampInSeconds(100),
+        );
+        let event2 = AuthorityEvent::new(
+            AuthorityEventKind::EnrollmentTokenRedeemed,
+            subject.clone(),
+            actor,
+            TimestampInSeconds(200),
+        )
+        .with_reference(Some("reference".to_string()));
+        repository.store_event(&event1).await?;
+        repository.store_event(&event2).await?;
+
+        let events = repository.get_events(None).await?;
+        assert_eq!(events, vec![event1, event2.clone()]);
+
+        let events = repository.get_events(Some(TimestampInSeconds(150))).await?;
+        assert_eq!(events, vec![event2]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authority_events_repository_is_bounded() -> Result<()> {
+        let repository = create_repository(3).await?;
+
+        let subject = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        for i in 0..5 {
+            let event = AuthorityEvent::new(
+                AuthorityEventKind::CredentialIssued,
+                subject.clone(),
+                None,
+                TimestampInSeconds(i),
+            );
+            repository.store_event(&event).await?;
+        }
+
+        let events = repository.get_events(None).await?;
+        let timestamps: Vec<u64> = events.iter().map(|e| e.created_at.0).collect();
+        assert_eq!(timestamps, vec![2, 3, 4]);
+
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository(max_events: u64) -> Result<Arc<dyn AuthorityEventsRepository>> {
+        Ok(Arc::new(
+            AuthorityEventsSqlxDatabase::create()
+                .await?
+                .with_max_events(max_events),
+        ))
+    }
+}
@@ -1,6 +1,7 @@
 use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::str::FromStr;
+use ockam_core::compat::time::Duration;
 use ockam_core::{Error, Result};
 
 /// Project member stored on the Authority node
@@ -13,6 +14,9 @@ pub struct AuthorityMember {
     // Was provided by TrustedIdentities argument during the Authority startup
     // pre-trusted identities can't be deleted using [`MembersStorage::delete_member()`]
     is_pre_trusted: bool,
+    // Maximum time to live of the credentials issued to this member,
+    // set when the member was enrolled with an enrollment token carrying that limit
+    credential_ttl: Option<Duration>,
 }
 
 impl AuthorityMember {
@@ -29,8 +33,16 @@ impl AuthorityMember {
             added_by,
             added_at,
             is_pre_trusted,
+            credential_ttl: None,
         }
     }
+
+    /// Limit the time to live of the credentials issued to this member
+    pub fn with_credential_ttl(mut self, credential_ttl: Option<Duration>) -> Self {
+        self.credential_ttl = credential_ttl;
+        self
+    }
+
     pub fn identifier(&self) -> &Identifier {
         &self.identifier
     }
@@ -46,6 +58,9 @@ impl AuthorityMember {
     pub fn is_pre_trusted(&self) -> bool {
         self.is_pre_trusted
     }
+    pub fn credential_ttl(&self) -> Option<Duration> {
+        self.credential_ttl
+    }
 }
 
 // Low-level representation of a table row
@@ -56,6 +71,7 @@ pub(crate) struct AuthorityMemberRow {
     added_by: String,
     added_at: i64,
     is_pre_trusted: bool,
+    credential_ttl: Option<i64>,
 }
 
 impl TryFrom<AuthorityMemberRow> for AuthorityMember {
@@ -68,6 +84,11 @@ impl TryFrom<AuthorityMemberRow> for AuthorityMember {
             Identifier::from_str(&value.added_by)?,
             TimestampInSeconds(value.added_at as u64),
             value.is_pre_trusted,
+        )
+        .with_credential_ttl(
+            value
+                .credential_ttl
+                .map(|ttl| Duration::from_secs(ttl as u64)),
         );
 
         Ok(member)
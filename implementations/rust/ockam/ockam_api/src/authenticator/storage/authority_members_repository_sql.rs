@@ -34,7 +34,7 @@ impl AuthorityMembersSqlxDatabase {
 #[async_trait]
 impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     async fn get_member(&self, identifier: &Identifier) -> Result<Option<AuthorityMember>> {
-        let query = query_as("SELECT identifier, attributes, added_by, added_at, is_pre_trusted FROM authority_member WHERE identifier=?")
+        let query = query_as("SELECT identifier, attributes, added_by, added_at, is_pre_trusted, credential_ttl FROM authority_member WHERE identifier=?")
             .bind(identifier.to_sql());
         let row: Option<AuthorityMemberRow> = query
             .fetch_optional(&*self.database.pool)
@@ -44,7 +44,7 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 
     async fn get_members(&self) -> Result<Vec<AuthorityMember>> {
-        let query = query_as("SELECT identifier, attributes, added_by, added_at, is_pre_trusted FROM authority_member");
+        let query = query_as("SELECT identifier, attributes, added_by, added_at, is_pre_trusted, credential_ttl FROM authority_member");
         let row: Vec<AuthorityMemberRow> =
             query.fetch_all(&*self.database.pool).await.into_core()?;
         row.into_iter().map(|r| r.try_into()).collect()
@@ -58,12 +58,13 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 
     async fn add_member(&self, member: AuthorityMember) -> Result<()> {
-        let query = query("INSERT OR REPLACE INTO authority_member VALUES (?1, ?2, ?3, ?4, ?5)")
+        let query = query("INSERT OR REPLACE INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes, credential_ttl) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
             .bind(member.identifier().to_sql())
             .bind(member.added_by().to_sql())
             .bind(member.added_at().to_sql())
             .bind(member.is_pre_trusted().to_sql())
-            .bind(minicbor::to_vec(member.attributes())?.to_sql());
+            .bind(minicbor::to_vec(member.attributes())?.to_sql())
+            .bind(member.credential_ttl().map(|ttl| ttl.as_secs().to_sql()));
 
         query.execute(&*self.database.pool).await.void()
     }
@@ -79,7 +80,7 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
 
         for (identifier, pre_trusted_identity) in pre_trusted_identities.deref() {
             let query2 =
-                query("INSERT OR REPLACE INTO authority_member VALUES (?1, ?2, ?3, ?4, ?5)")
+                query("INSERT OR REPLACE INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes) VALUES (?1, ?2, ?3, ?4, ?5)")
                     .bind(identifier.to_sql())
                     .bind(pre_trusted_identity.attested_by().to_sql())
                     .bind(pre_trusted_identity.added_at().to_sql())
@@ -104,6 +105,7 @@ mod tests {
     use ockam_core::compat::collections::BTreeMap;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::compat::sync::Arc;
+    use ockam_core::compat::time::Duration;
     use rand::thread_rng;
 
     fn random_identifier() -> Identifier {
@@ -151,7 +153,8 @@ mod tests {
             admin.clone(),
             timestamp2,
             false,
-        );
+        )
+        .with_credential_ttl(Some(Duration::from_secs(900)));
         repository.add_member(member2.clone()).await?;
 
         let members = repository.get_members().await?;
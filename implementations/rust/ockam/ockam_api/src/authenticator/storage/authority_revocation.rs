@@ -0,0 +1,43 @@
+use minicbor::{Decode, Encode};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Revocation of a member by the Authority node.
+/// The credentials issued to that member before the revocation are not accepted anymore
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuthorityRevocation {
+    /// Revoked identity
+    #[n(1)] pub identifier: Identifier,
+    /// Time of the revocation
+    #[n(2)] pub revoked_at: TimestampInSeconds,
+    /// Reason given for the revocation
+    #[n(3)] pub reason: Option<String>,
+    /// Version of the revocation list which introduced this revocation
+    #[n(4)] pub version: u64,
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct AuthorityRevocationRow {
+    identifier: String,
+    revoked_at: i64,
+    reason: Option<String>,
+    version: i64,
+}
+
+impl TryFrom<AuthorityRevocationRow> for AuthorityRevocation {
+    type Error = Error;
+
+    fn try_from(value: AuthorityRevocationRow) -> Result<Self, Self::Error> {
+        Ok(AuthorityRevocation {
+            identifier: Identifier::from_str(&value.identifier)?,
+            revoked_at: TimestampInSeconds(value.revoked_at as u64),
+            reason: value.reason,
+            version: value.version as u64,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+use crate::authenticator::AuthorityRevocation;
+use ockam::identity::{Identifier, RevocationList, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+/// This repository stores the members revoked by the Authority node
+#[async_trait]
+pub trait AuthorityRevocationsRepository: Send + Sync + 'static {
+    /// Revoke an identity and return the new version of the revocation list.
+    /// Revoking an identity again updates its revocation time
+    async fn revoke(
+        &self,
+        identifier: &Identifier,
+        revoked_at: TimestampInSeconds,
+        reason: Option<String>,
+    ) -> Result<u64>;
+
+    /// Return all the revocations, oldest first
+    async fn get_revocations(&self) -> Result<Vec<AuthorityRevocation>>;
+
+    /// Return the revocation list published by the Authority node
+    async fn get_revocation_list(&self) -> Result<RevocationList> {
+        let revocations = self.get_revocations().await?;
+        let version = revocations.iter().map(|r| r.version).max().unwrap_or(0);
+        Ok(RevocationList::new(
+            version,
+            revocations
+                .into_iter()
+                .map(|r| (r.identifier, r.revoked_at))
+                .collect(),
+        ))
+    }
+}
@@ -0,0 +1,143 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::authenticator::{
+    AuthorityRevocation, AuthorityRevocationRow, AuthorityRevocationsRepository,
+};
+
+/// Implementation of [`AuthorityRevocationsRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct AuthorityRevocationsSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityRevocationsSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for authority revocations");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority revocations").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityRevocationsRepository for AuthorityRevocationsSqlxDatabase {
+    async fn revoke(
+        &self,
+        identifier: &Identifier,
+        revoked_at: TimestampInSeconds,
+        reason: Option<String>,
+    ) -> Result<u64> {
+        let mut transaction = self.database.pool.begin().await.into_core()?;
+
+        let version: Option<i64> = query_scalar("SELECT MAX(version) FROM authority_revocation")
+            .fetch_one(&mut *transaction)
+            .await
+            .into_core()?;
+        let version = version.unwrap_or(0) as u64 + 1;
+
+        let query = query(
+            "INSERT INTO authority_revocation (identifier, revoked_at, reason, version) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (identifier)
+             DO UPDATE SET revoked_at = excluded.revoked_at, reason = excluded.reason, version = excluded.version",
+        )
+        .bind(identifier.to_sql())
+        .bind(revoked_at.to_sql())
+        .bind(reason.map(|r| r.to_sql()))
+        .bind(version.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()?;
+        Ok(version)
+    }
+
+    async fn get_revocations(&self) -> Result<Vec<AuthorityRevocation>> {
+        let query = query_as(
+            "SELECT identifier, revoked_at, reason, version FROM authority_revocation ORDER BY version",
+        );
+        let rows: Vec<AuthorityRevocationRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::sync::Arc;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_authority_revocations_repository() -> Result<()> {
+        let repository = create_repository().await?;
+        let list = repository.get_revocation_list().await?;
+        assert_eq!(list.version, 0);
+        assert!(list.revoked.is_empty());
+
+        let member1 = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let member2 = Identifier::from_str(
+            "Ifedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210",
+        )
+        .unwrap();
+
+        let version = repository
+            .revoke(&member1, TimestampInSeconds(100), Some("lost".to_string()))
+            .await?;
+        assert_eq!(version, 1);
+        let version = repository
+            .revoke(&member2, TimestampInSeconds(200), None)
+            .await?;
+        assert_eq!(version, 2);
+
+        // revoking a member again updates its revocation and the version of the list
+        let version = repository
+            .revoke(&member1, TimestampInSeconds(300), None)
+            .await?;
+        assert_eq!(version, 3);
+
+        let revocations = repository.get_revocations().await?;
+        assert_eq!(
+            revocations,
+            vec![
+                AuthorityRevocation {
+                    identifier: member2.clone(),
+                    revoked_at: TimestampInSeconds(200),
+                    reason: None,
+                    version: 2,
+                },
+                AuthorityRevocation {
+                    identifier: member1.clone(),
+                    revoked_at: TimestampInSeconds(300),
+                    reason: None,
+                    version: 3,
+                },
+            ]
+        );
+
+        let list = repository.get_revocation_list().await?;
+        assert_eq!(list.version, 3);
+        assert!(list.is_revoked(&member1, TimestampInSeconds(300)));
+        assert!(list.is_revoked(&member2, TimestampInSeconds(200)));
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository() -> Result<Arc<dyn AuthorityRevocationsRepository>> {
+        Ok(Arc::new(AuthorityRevocationsSqlxDatabase::create().await?))
+    }
+}
@@ -1,6 +1,7 @@
 use crate::authenticator::one_time_code::OneTimeCode;
 use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::compat::str::FromStr;
+use ockam_core::compat::time::Duration;
 use ockam_core::{Error, Result};
 use std::collections::BTreeMap;
 
@@ -23,6 +24,11 @@ pub struct EnrollmentToken {
     pub ttl_count: u64,
     /// Attributes that will be assigned to a member upon usage of that token
     pub attrs: BTreeMap<String, String>,
+    /// Maximum time to live of the credentials issued to the members enrolled with that token.
+    /// If it is not set, the time to live configured for the credential issuer is used
+    pub credential_ttl: Option<Duration>,
+    /// Name of the attribute template constraining the attributes of the token
+    pub template: Option<String>,
 }
 
 impl EnrollmentToken {
@@ -41,6 +47,8 @@ pub(crate) struct EnrollmentTokenRow {
     expires_at: i64,
     ttl_count: i64,
     attributes: Vec<u8>,
+    credential_ttl: Option<i64>,
+    template: Option<String>,
 }
 
 impl TryFrom<EnrollmentTokenRow> for EnrollmentToken {
@@ -55,6 +63,10 @@ impl TryFrom<EnrollmentTokenRow> for EnrollmentToken {
             expires_at: TimestampInSeconds(value.expires_at as u64),
             ttl_count: value.ttl_count as u64,
             attrs: minicbor::decode(&value.attributes)?,
+            credential_ttl: value
+                .credential_ttl
+                .map(|ttl| Duration::from_secs(ttl as u64)),
+            template: value.template,
         };
 
         Ok(member)
@@ -1,13 +1,31 @@
+mod authority_attribute_template;
+mod authority_attribute_templates_repository;
+mod authority_attribute_templates_repository_sql;
 mod authority_enrollment_token_repository;
 mod authority_enrollment_token_repository_sql;
+mod authority_event;
+mod authority_events_repository;
+mod authority_events_repository_sql;
 mod authority_member;
 mod authority_members_repository;
 mod authority_members_repository_sql;
+mod authority_revocation;
+mod authority_revocations_repository;
+mod authority_revocations_repository_sql;
 mod enrollment_token;
 
+pub use authority_attribute_template::*;
+pub use authority_attribute_templates_repository::*;
+pub use authority_attribute_templates_repository_sql::*;
 pub use authority_enrollment_token_repository::*;
 pub use authority_enrollment_token_repository_sql::*;
+pub use authority_event::*;
+pub use authority_events_repository::*;
+pub use authority_events_repository_sql::*;
 pub use authority_member::*;
 pub use authority_members_repository::*;
 pub use authority_members_repository_sql::*;
+pub use authority_revocation::*;
+pub use authority_revocations_repository::*;
+pub use authority_revocations_repository_sql::*;
 pub use enrollment_token::*;
@@ -7,9 +7,14 @@ use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWork
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
 };
+use crate::authenticator::events::{
+    AuthorityEvents, AuthorityEventsWebhook, AuthorityEventsWorker,
+};
 use crate::authenticator::{
+    AuthorityAttributeTemplatesRepository, AuthorityAttributeTemplatesSqlxDatabase,
     AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase,
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityEventsSqlxDatabase, AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityRevocationsRepository, AuthorityRevocationsSqlxDatabase,
 };
 use ockam::identity::{
     Identifier, Identities, SecureChannelListenerOptions, SecureChannels, TrustEveryonePolicy,
@@ -34,12 +39,16 @@ use crate::nodes::service::default_address::DefaultAddress;
 //   - a credential issuer
 //   - an enrollment token issuer
 //   - an enrollment token acceptor
+//   - an events service
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
     members: Arc<dyn AuthorityMembersRepository>,
+    revocations: Arc<dyn AuthorityRevocationsRepository>,
     tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
+    templates: Arc<dyn AuthorityAttributeTemplatesRepository>,
     account_authority: Option<AccountAuthorityInfo>,
+    events: AuthorityEvents,
 }
 
 /// Public functions to:
@@ -68,7 +77,20 @@ impl Authority {
         let database = SqlxDatabase::create_with_node_name(database_path, "authority").await?;
 
         let members = Arc::new(AuthorityMembersSqlxDatabase::new(database.clone()));
+        let revocations = Arc::new(AuthorityRevocationsSqlxDatabase::new(database.clone()));
         let tokens = Arc::new(AuthorityEnrollmentTokenSqlxDatabase::new(database.clone()));
+        let templates = Arc::new(AuthorityAttributeTemplatesSqlxDatabase::new(
+            database.clone(),
+        ));
+        let events =
+            AuthorityEvents::new(Arc::new(AuthorityEventsSqlxDatabase::new(database.clone())));
+        let events = match configuration.events_webhook.clone() {
+            Some(url) => {
+                info!(%url, "authority events will be sent to a webhook");
+                events.with_webhook(AuthorityEventsWebhook::new(url))
+            }
+            None => events,
+        };
 
         Self::bootstrap_repository(members.clone(), configuration).await?;
 
@@ -98,8 +120,11 @@ impl Authority {
             identifier,
             secure_channels,
             members,
+            revocations,
             tokens,
+            templates,
             account_authority,
+            events,
         })
     }
 
@@ -157,8 +182,12 @@ impl Authority {
             return Ok(());
         }
 
-        let direct =
-            DirectAuthenticatorWorker::new(self.members.clone(), self.account_authority.clone());
+        let direct = DirectAuthenticatorWorker::new(
+            self.members.clone(),
+            self.revocations.clone(),
+            self.account_authority.clone(),
+            self.events.clone(),
+        );
 
         let name = configuration.authenticator_name();
         ctx.flow_controls()
@@ -184,10 +213,15 @@ impl Authority {
         let issuer = EnrollmentTokenIssuerWorker::new(
             self.tokens.clone(),
             self.members.clone(),
+            self.templates.clone(),
             self.account_authority.clone(),
         );
-        let acceptor =
-            EnrollmentTokenAcceptorWorker::new(self.tokens.clone(), self.members.clone());
+        let acceptor = EnrollmentTokenAcceptorWorker::new(
+            self.tokens.clone(),
+            self.members.clone(),
+            self.templates.clone(),
+            self.events.clone(),
+        );
 
         // start an enrollment token issuer with an abac policy checking that
         // the caller is an enroller for the authority project
@@ -225,11 +259,13 @@ impl Authority {
         // create and start a credential issuer worker
         let issuer = CredentialIssuerWorker::new(
             self.members.clone(),
+            self.revocations.clone(),
             self.secure_channels.identities().credentials(),
             &self.identifier,
             Some(configuration.project_identifier()),
             ttl,
             self.account_authority.clone(),
+            self.events.clone(),
         );
 
         let address = DefaultAddress::CREDENTIAL_ISSUER.to_string();
@@ -265,6 +301,28 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the events service, to let enrollers list the events produced by the authority
+    pub async fn start_events_service(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let worker = AuthorityEventsWorker::new(
+            self.events.clone(),
+            self.members.clone(),
+            self.account_authority.clone(),
+        );
+
+        let address = DefaultAddress::AUTHORITY_EVENTS.to_string();
+        ctx.flow_controls()
+            .add_consumer(address.clone(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), worker).await?;
+
+        info!("started an events service at '{address}'");
+        Ok(())
+    }
+
     /// Start an echo service
     pub async fn start_echo_service(
         &self,
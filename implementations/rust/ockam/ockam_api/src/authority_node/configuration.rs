@@ -1,6 +1,7 @@
 use std::path::PathBuf;
 
 use ockam::identity::models::ChangeHistory;
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
 use ockam::identity::Identifier;
@@ -49,6 +50,9 @@ pub struct Configuration {
 
     /// Account Authority identity
     pub account_authority: Option<ChangeHistory>,
+
+    /// optional URL where the authority events are POSTed as JSON
+    pub events_webhook: Option<Url>,
 }
 
 /// Local and private functions for the authority configuration
@@ -41,6 +41,11 @@ pub async fn start_node(ctx: &Context, configuration: &Configuration) -> Result<
         .await?;
     debug!("okta service started");
 
+    authority
+        .start_events_service(ctx, &secure_channel_flow_control_id)
+        .await?;
+    debug!("events service started");
+
     // start an echo service so that the node can be queried as healthy
     authority
         .start_echo_service(ctx, &secure_channel_flow_control_id)
@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::task::JoinHandle;
+
+use ockam_node::database::{BackupManifest, NodeMigrationSet, SqlxDatabase};
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// Default number of snapshots kept in the backup directory of a node
+pub const DEFAULT_BACKUPS_TO_KEEP: usize = 4;
+
+/// Extension of the snapshot files created by periodic backups
+const BACKUP_FILE_EXTENSION: &str = "backup";
+
+/// Configuration of the periodic backups of a node database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeBackupSchedule {
+    interval: Duration,
+    dir: PathBuf,
+    keep: usize,
+}
+
+impl NodeBackupSchedule {
+    pub fn new(interval: Duration, dir: PathBuf, keep: usize) -> Self {
+        Self {
+            interval,
+            dir,
+            keep: keep.max(1),
+        }
+    }
+
+    pub fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn keep(&self) -> usize {
+        self.keep
+    }
+}
+
+/// The methods below support the backup of the database used by a node and its restoration.
+///
+/// Note that the database is shared by all the local nodes. This is why restoring a snapshot
+/// is only possible when none of the local nodes is running.
+impl CliState {
+    /// Write a snapshot of the database used by a node to a file.
+    /// This can be done while the node is running.
+    pub async fn backup_node(&self, node_name: &str, path: &Path) -> Result<BackupManifest> {
+        self.get_node(node_name).await?;
+        let mut database = self.database();
+        database.set_node_name(node_name);
+        Ok(database.backup_to(path).await?)
+    }
+
+    /// Restore the database used by a node from a snapshot file.
+    ///
+    /// The restoration is refused if:
+    ///  - the snapshot was taken for another node
+    ///  - the snapshot has a newer schema version than the one supported by this binary
+    ///  - a local node is running
+    pub async fn restore_node(&self, node_name: &str, path: &Path) -> Result<BackupManifest> {
+        let manifest = SqlxDatabase::read_backup_manifest(path).await?;
+        match &manifest.node_name {
+            Some(name) if name != node_name => {
+                return Err(CliStateError::InvalidOperation(format!(
+                    "The backup file {} was created for the node {name}, not for the node {node_name}",
+                    path.display()
+                )))
+            }
+            _ => (),
+        }
+
+        let running_nodes: Vec<String> = self
+            .get_nodes()
+            .await?
+            .iter()
+            .filter(|n| n.is_running())
+            .map(|n| n.name())
+            .collect();
+        if !running_nodes.is_empty() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The database is shared by all the local nodes and can only be restored when they are stopped. These nodes are still running: {}",
+                running_nodes.join(", ")
+            )));
+        }
+
+        Ok(self.database().restore_from(path, NodeMigrationSet).await?)
+    }
+
+    /// Start a task creating a snapshot of the node database at regular intervals.
+    /// Only the latest snapshots are kept in the backup directory.
+    pub fn start_periodic_node_backups(
+        &self,
+        node_name: &str,
+        schedule: NodeBackupSchedule,
+    ) -> JoinHandle<()> {
+        let cli_state = self.clone();
+        let node_name = node_name.to_string();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(schedule.interval());
+            // the first tick completes immediately, the first backup is done after one interval
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                match cli_state
+                    .backup_node_to_dir(&node_name, schedule.dir(), schedule.keep())
+                    .await
+                {
+                    Ok(path) => {
+                        info!(%node_name, "the node database has been backed up to {path:?}")
+                    }
+                    Err(e) => warn!(%node_name, "the node database could not be backed up: {e}"),
+                }
+            }
+        })
+    }
+
+    /// Create a new snapshot in a backup directory and only keep the `keep` latest snapshots
+    pub async fn backup_node_to_dir(
+        &self,
+        node_name: &str,
+        dir: &Path,
+        keep: usize,
+    ) -> Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let path = dir.join(format!(
+            "{node_name}-{timestamp:020}.{BACKUP_FILE_EXTENSION}"
+        ));
+        self.backup_node(node_name, &path).await?;
+
+        let mut backups = Self::list_node_backups(node_name, dir)?;
+        let to_delete = backups.len().saturating_sub(keep.max(1));
+        for old_backup in backups.drain(..to_delete) {
+            debug!(%node_name, "delete the old backup {old_backup:?}");
+            std::fs::remove_file(old_backup)?;
+        }
+        Ok(path)
+    }
+
+    /// Return the snapshots of a node in a backup directory, from the oldest to the newest
+    pub fn list_node_backups(node_name: &str, dir: &Path) -> Result<Vec<PathBuf>> {
+        let prefix = format!("{node_name}-");
+        let mut backups = vec![];
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            let is_node_backup = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with(&prefix))
+                .unwrap_or(false)
+                && path.extension().and_then(|e| e.to_str()) == Some(BACKUP_FILE_EXTENSION);
+            if is_node_backup {
+                backups.push(path);
+            }
+        }
+        // the file names contain a fixed-width timestamp
+        backups.sort();
+        Ok(backups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_backup_and_restore_node() -> Result<()> {
+        let cli = CliState::test().await?;
+        let node = cli.create_node("node").await?;
+        let backup_dir = TempDir::new().unwrap();
+        let backup_path = backup_dir.path().join("node.backup");
+
+        let manifest = cli.backup_node(&node.name(), &backup_path).await?;
+        assert_eq!(manifest.node_name, Some(node.name()));
+
+        // mutate the state
+        cli.create_identity_with_name("alice").await?;
+        cli.create_node("other").await?;
+
+        // the state before the mutation is restored
+        let expected = cli.get_node(&node.name()).await?;
+        cli.restore_node(&node.name(), &backup_path).await?;
+        assert!(cli.get_named_identity("alice").await.is_err());
+        assert!(cli.get_node("other").await.is_err());
+        assert_eq!(cli.get_node(&node.name()).await?, expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_node_is_refused() -> Result<()> {
+        let cli = CliState::test().await?;
+        let node = cli.create_node("node").await?;
+        let backup_dir = TempDir::new().unwrap();
+        let backup_path = backup_dir.path().join("node.backup");
+        cli.backup_node(&node.name(), &backup_path).await?;
+
+        // the backup was made for another node
+        cli.create_node("other").await?;
+        let result = cli.restore_node("other", &backup_path).await;
+        assert!(result.is_err());
+
+        // a node is still running
+        cli.set_node_pid(&node.name(), std::process::id()).await?;
+        let result = cli.restore_node(&node.name(), &backup_path).await;
+        assert!(result.is_err());
+        assert!(cli.get_node("other").await.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backups_rotation() -> Result<()> {
+        let cli = CliState::test().await?;
+        let node = cli.create_node("node").await?;
+        let backup_dir = TempDir::new().unwrap();
+
+        let mut created = vec![];
+        for _ in 0..3 {
+            created.push(
+                cli.backup_node_to_dir(&node.name(), backup_dir.path(), 2)
+                    .await?,
+            );
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+
+        // only the 2 latest backups are kept
+        let backups = CliState::list_node_backups(&node.name(), backup_dir.path())?;
+        assert_eq!(backups, created[1..].to_vec());
+        Ok(())
+    }
+}
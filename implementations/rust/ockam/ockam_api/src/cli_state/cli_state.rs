@@ -1,4 +1,6 @@
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use colorful::{Colorful, RGB};
 use rand::random;
@@ -49,6 +51,12 @@ pub struct CliState {
     /// Broadcast channel to be notified of major events during a process supported by the
     /// CliState API
     notifications: Sender<Notification>,
+    /// Warnings raised while running a command, which are displayed to the user once
+    /// the command completes
+    warnings: Arc<Mutex<Vec<String>>>,
+    /// Timeout given by the user for the requests sent to the nodes and to the controller.
+    /// When it is not set, each client uses its own default timeout
+    request_timeout: Option<Duration>,
 }
 
 pub fn color_primary(text: &str) -> String {
@@ -97,6 +105,23 @@ impl CliState {
         info!(notification);
         let _ = self.notifications.send(notification);
     }
+
+    /// Record a warning for the user. A warning which was already recorded is ignored
+    pub fn add_warning(&self, warning: String) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        }
+    }
+
+    /// Return the recorded warnings and clear them
+    pub fn take_warnings(&self) -> Vec<String> {
+        self.warnings
+            .lock()
+            .map(|mut warnings| std::mem::take(&mut *warnings))
+            .unwrap_or_default()
+    }
 }
 
 /// These functions allow to create and reset the local state
@@ -182,7 +207,7 @@ impl CliState {
     /// Create a new CliState where the data is stored at a given path
     pub async fn create(dir: PathBuf) -> Result<Self> {
         std::fs::create_dir_all(&dir)?;
-        let database = SqlxDatabase::create(Self::make_database_path(&dir)).await?;
+        let database = Self::open_nodes_database(&Self::make_database_path(&dir)).await?;
         let application_database = SqlxDatabase::create_with_migration(
             Self::make_application_database_path(&dir),
             ApplicationMigrationSet,
@@ -204,6 +229,8 @@ impl CliState {
             // is eventually used to trace user journeys.
             exporting_enabled: ExportingEnabled::Off,
             notifications,
+            warnings: Arc::new(Mutex::new(vec![])),
+            request_timeout: None,
         };
         Ok(state)
     }
@@ -223,6 +250,19 @@ impl CliState {
         }
     }
 
+    /// Return the timeout to use for the requests sent to the nodes and to the controller,
+    /// if it was set by the user
+    pub fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout
+    }
+
+    pub fn set_request_timeout(self, request_timeout: Option<Duration>) -> CliState {
+        CliState {
+            request_timeout,
+            ..self
+        }
+    }
+
     pub(super) fn make_database_path(root_path: &Path) -> PathBuf {
         root_path.join("database.sqlite3")
     }
@@ -268,6 +308,37 @@ pub fn random_name() -> String {
     petname::petname(2, "-").unwrap_or(hex::encode(random::<[u8; 4]>()))
 }
 
+/// Maximum length of the name of an identity, a node, a vault, etc...
+pub const MAX_NAME_LENGTH: usize = 64;
+
+/// Check that a name can be used for an identity, a node, a vault, etc...
+///
+/// A name starts with a letter or a digit, and only contains letters, digits, '-', '_' and '.'.
+/// Since the name of a node is also used for its directory, this excludes the path separators.
+/// The error is a message which can be displayed to the user.
+pub fn validate_name(name: &str) -> std::result::Result<(), String> {
+    if name.is_empty() {
+        return Err("The name can't be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!(
+            "The name can't be longer than {MAX_NAME_LENGTH} characters"
+        ));
+    }
+    if !name.starts_with(|c: char| c.is_ascii_alphanumeric()) {
+        return Err("The name must start with a letter or a digit".to_string());
+    }
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+    {
+        return Err(format!(
+            "The name can't contain '{c}'. Only letters, digits, '-', '_' and '.' are allowed"
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -275,6 +346,39 @@ mod tests {
     use std::fs;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn test_validate_name() {
+        for _ in 0..10 {
+            let name = random_name();
+            assert_eq!(validate_name(&name), Ok(()), "{name}");
+        }
+        for name in [
+            "n1",
+            "node_1",
+            "my.identity",
+            "1-node",
+            &"a".repeat(MAX_NAME_LENGTH),
+        ] {
+            assert_eq!(validate_name(name), Ok(()), "{name}");
+        }
+
+        for name in [
+            "",
+            "-node",
+            ".ockam",
+            "node/1",
+            "node 1",
+            "nœud",
+            &"a".repeat(65),
+        ] {
+            assert!(validate_name(name).is_err(), "{name}");
+        }
+        assert_eq!(
+            validate_name("node/1").unwrap_err(),
+            "The name can't contain '/'. Only letters, digits, '-', '_' and '.' are allowed"
+        );
+    }
+
     #[tokio::test]
     async fn test_reset() -> Result<()> {
         let db_file = NamedTempFile::new().unwrap();
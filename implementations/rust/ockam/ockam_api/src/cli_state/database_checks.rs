@@ -0,0 +1,56 @@
+use ockam_node::database::{DatabaseCheckReport, NodeMigrationSet};
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// The methods below check the integrity of the database used by a node and repair it.
+///
+/// Note that the database is shared by all the local nodes. This is why a repair
+/// is only possible when none of the local nodes is running.
+impl CliState {
+    /// Check the database used by a node.
+    /// A quick check skips the verification of the indexes, which is slower on large databases.
+    pub async fn check_node_database(
+        &self,
+        node_name: &str,
+        quick: bool,
+    ) -> Result<DatabaseCheckReport> {
+        self.get_node(node_name).await?;
+        Ok(self
+            .database()
+            .check_integrity(NodeMigrationSet, quick)
+            .await?)
+    }
+
+    /// Repair the database used by a node by rebuilding its indexes and compacting it.
+    /// If `delete_orphaned_rows` is true, the rows referencing identities which do not exist
+    /// anymore are deleted as well, and their number is returned.
+    pub async fn repair_node_database(
+        &self,
+        node_name: &str,
+        delete_orphaned_rows: bool,
+    ) -> Result<u64> {
+        self.get_node(node_name).await?;
+        let running_nodes: Vec<String> = self
+            .get_nodes()
+            .await?
+            .iter()
+            .filter(|n| n.is_running())
+            .map(|n| n.name())
+            .collect();
+        if !running_nodes.is_empty() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The database is shared by all the local nodes and can only be repaired when they are stopped. These nodes are still running: {}",
+                running_nodes.join(", ")
+            )));
+        }
+
+        let database = self.database();
+        let deleted = if delete_orphaned_rows {
+            database.delete_orphaned_rows().await?
+        } else {
+            0
+        };
+        database.reindex_and_vacuum().await?;
+        Ok(deleted)
+    }
+}
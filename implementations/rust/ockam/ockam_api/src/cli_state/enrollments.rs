@@ -1,14 +1,26 @@
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, Key, KeyInit, Nonce};
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
-use std::fmt::{Display, Formatter};
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use std::fmt::{Debug, Display, Formatter};
+use std::str::FromStr;
 use time::OffsetDateTime;
 
 use crate::authenticator::one_time_code::OneTimeCode;
+use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::Identifier;
+use ockam_abac::attribute_access_control::SUBJECT_KEY;
+use ockam_abac::expr::{and, eq, ident, str};
+use ockam_abac::Expr;
 
 use crate::cli_state::Result;
 use crate::cli_state::{CliState, CliStateError};
 use crate::cloud::project::models::ProjectModel;
 use crate::error::ApiError;
+use crate::redaction::REDACTED;
 
 /// The following CliState methods help keeping track of
 ///
@@ -97,6 +109,160 @@ impl CliState {
 
         Ok(true)
     }
+
+    /// Store a local copy of the attributes granted to an identity when it enrolled with a project:
+    /// the attributes of the redeemed enrollment ticket, and the attributes of the credential
+    /// issued at enrollment, which take precedence.
+    ///
+    /// This copy is only advisory, the project authority remains the source of truth.
+    #[instrument(skip_all, fields(identifier = %identifier, project_id = project_id))]
+    pub async fn store_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+        project_id: &str,
+        ticket_attributes: &BTreeMap<String, String>,
+        credential_attributes: &BTreeMap<String, String>,
+    ) -> Result<Vec<EnrollmentAttribute>> {
+        let mut attributes = BTreeMap::new();
+        for (source, source_attributes) in [
+            (EnrollmentAttributeSource::Ticket, ticket_attributes),
+            (EnrollmentAttributeSource::Credential, credential_attributes),
+        ] {
+            for (name, value) in source_attributes {
+                attributes.insert(
+                    name.clone(),
+                    EnrollmentAttribute {
+                        project_id: project_id.to_string(),
+                        name: name.clone(),
+                        value: value.clone(),
+                        source: source.clone(),
+                    },
+                );
+            }
+        }
+        let attributes: Vec<EnrollmentAttribute> = attributes.into_values().collect();
+        self.enrollment_repository()
+            .store_enrollment_attributes(identifier, project_id, &attributes)
+            .await?;
+        Ok(attributes)
+    }
+
+    /// Return the local copy of the attributes granted to an identity when it enrolled with projects
+    #[instrument(skip_all, fields(identifier = %identifier))]
+    pub async fn get_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<EnrollmentAttribute>> {
+        Ok(self
+            .enrollment_repository()
+            .get_enrollment_attributes(identifier)
+            .await?)
+    }
+
+    /// Return a policy expression requiring the subject to have the same values as an identity
+    /// for some of its enrollment attributes, for example `(= subject.role "ops")`
+    #[instrument(skip_all, fields(identity = identity_name.clone()))]
+    pub async fn expression_from_enrollment_attributes(
+        &self,
+        identity_name: &Option<String>,
+        names: &[String],
+    ) -> Result<Expr> {
+        let identifier = self.get_identifier_by_optional_name(identity_name).await?;
+        let attributes = self.get_enrollment_attributes(&identifier).await?;
+
+        let mut expressions = vec![];
+        for name in names {
+            let mut values: Vec<&str> = attributes
+                .iter()
+                .filter(|a| &a.name == name)
+                .map(|a| a.value.as_str())
+                .collect();
+            values.sort();
+            values.dedup();
+            let value = match values.as_slice() {
+                [value] => *value,
+                [] => Err(CliStateError::InvalidOperation(format!(
+                    "The identity {identifier} has no enrollment attribute named {name}. Its attributes can be displayed with 'ockam identity show --attributes'"
+                )))?,
+                _ => Err(CliStateError::InvalidOperation(format!(
+                    "The identity {identifier} has different values for the enrollment attribute {name} in its projects: {}",
+                    values.join(", ")
+                )))?,
+            };
+            expressions.push(eq([ident(format!("{SUBJECT_KEY}.{name}")), str(value)]));
+        }
+
+        match expressions.len() {
+            0 => Err(CliStateError::InvalidOperation(
+                "At least one attribute name is required to create a policy expression".into(),
+            )),
+            1 => Ok(expressions.remove(0)),
+            _ => Ok(and(expressions)),
+        }
+    }
+}
+
+/// Return the attributes of a credential, as strings
+pub fn credential_attributes(
+    credential: &CredentialAndPurposeKey,
+) -> Result<BTreeMap<String, String>> {
+    Ok(credential
+        .get_credential_data()?
+        .subject_attributes
+        .map
+        .iter()
+        .map(|(name, value)| {
+            (
+                String::from_utf8_lossy(name).to_string(),
+                String::from_utf8_lossy(value).to_string(),
+            )
+        })
+        .collect())
+}
+
+/// Attribute granted to a local identity when it enrolled with a project.
+///
+/// This is an advisory copy of the attribute: the project authority can change
+/// or revoke the attributes of its members at any time.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct EnrollmentAttribute {
+    pub project_id: String,
+    pub name: String,
+    pub value: String,
+    pub source: EnrollmentAttributeSource,
+}
+
+/// Origin of an enrollment attribute
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EnrollmentAttributeSource {
+    /// The attribute was embedded in the redeemed enrollment ticket
+    Ticket,
+    /// The attribute was attested by the credential issued at enrollment
+    Credential,
+}
+
+impl Display for EnrollmentAttributeSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnrollmentAttributeSource::Ticket => f.write_str("ticket"),
+            EnrollmentAttributeSource::Credential => f.write_str("credential"),
+        }
+    }
+}
+
+impl FromStr for EnrollmentAttributeSource {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "ticket" => Ok(EnrollmentAttributeSource::Ticket),
+            "credential" => Ok(EnrollmentAttributeSource::Credential),
+            _ => Err(ApiError::core(format!(
+                "Invalid enrollment attribute source: {s}"
+            ))),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -164,6 +330,10 @@ impl IdentityEnrollment {
 pub struct EnrollmentTicket {
     pub one_time_code: OneTimeCode,
     pub project: Option<ProjectModel>,
+    /// Attributes granted to the identity redeeming the ticket.
+    /// They are informative, the attributes are attested by the credential issued at enrollment
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attributes: BTreeMap<String, String>,
 }
 
 impl EnrollmentTicket {
@@ -171,15 +341,281 @@ impl EnrollmentTicket {
         Self {
             one_time_code,
             project,
+            attributes: BTreeMap::new(),
         }
     }
 
+    /// Attach the attributes granted to the identity redeeming the ticket
+    pub fn with_attributes(mut self, attributes: BTreeMap<String, String>) -> Self {
+        self.attributes = attributes;
+        self
+    }
+
     pub fn hex_encoded(&self) -> Result<String> {
         let serialized = serde_json::to_vec(&self)
             .map_err(|_err| ApiError::core("Failed to authenticate with Okta"))?;
         Ok(hex::encode(serialized))
     }
+
+    /// Encrypt the ticket with a key derived from a passphrase
+    pub fn encrypt(&self, passphrase: &TicketPassphrase) -> Result<EncryptedEnrollmentTicket> {
+        if passphrase.0.is_empty() {
+            Err(ApiError::core(
+                "The passphrase of an enrollment ticket can't be empty",
+            ))?
+        }
+        let plaintext = serde_json::to_vec(&self)
+            .map_err(|_err| ApiError::core("Failed to serialize the enrollment ticket"))?;
+
+        let mut salt = vec![0; TICKET_SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let mut nonce = vec![0; TICKET_NONCE_LENGTH];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let iterations = TICKET_KEY_DERIVATION_ITERATIONS;
+        let ciphertext = ticket_cipher(passphrase, &salt, iterations)
+            .encrypt(Nonce::from_slice(&nonce), plaintext.as_slice())
+            .map_err(|_err| ApiError::core("Failed to encrypt the enrollment ticket"))?;
+        Ok(EncryptedEnrollmentTicket {
+            salt,
+            iterations,
+            nonce,
+            ciphertext,
+        })
+    }
+}
+
+/// Number of PBKDF2 iterations used to derive the key of an encrypted enrollment ticket
+const TICKET_KEY_DERIVATION_ITERATIONS: u32 = 100_000;
+/// Maximum number of PBKDF2 iterations accepted when decrypting a ticket, so that a crafted
+/// ticket can't make the key derivation run for a very long time
+const TICKET_KEY_DERIVATION_MAX_ITERATIONS: u32 = 10 * TICKET_KEY_DERIVATION_ITERATIONS;
+const TICKET_SALT_LENGTH: usize = 16;
+const TICKET_NONCE_LENGTH: usize = 12;
+
+/// Return the cipher used to encrypt and decrypt an enrollment ticket
+fn ticket_cipher(passphrase: &TicketPassphrase, salt: &[u8], iterations: u32) -> Aes256Gcm {
+    let key = pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(passphrase.0.as_bytes(), salt, iterations);
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key))
+}
+
+/// Enrollment ticket encrypted with AES-256-GCM, using a key derived from a passphrase
+/// with PBKDF2-HMAC-SHA256.
+///
+/// Like a plain ticket, it is shared as a hex-encoded JSON document, which can be stored
+/// in a file without exposing the ticket itself.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EncryptedEnrollmentTicket {
+    #[serde(with = "hex")]
+    salt: Vec<u8>,
+    iterations: u32,
+    #[serde(with = "hex")]
+    nonce: Vec<u8>,
+    #[serde(with = "hex")]
+    ciphertext: Vec<u8>,
+}
+
+impl EncryptedEnrollmentTicket {
+    pub fn hex_encoded(&self) -> Result<String> {
+        let serialized = serde_json::to_vec(&self)
+            .map_err(|_err| ApiError::core("Failed to serialize the enrollment ticket"))?;
+        Ok(hex::encode(serialized))
+    }
+
+    /// Decrypt the ticket with the passphrase used to encrypt it
+    pub fn decrypt(&self, passphrase: &TicketPassphrase) -> Result<EnrollmentTicket> {
+        if self.nonce.len() != TICKET_NONCE_LENGTH
+            || !(TICKET_KEY_DERIVATION_ITERATIONS..=TICKET_KEY_DERIVATION_MAX_ITERATIONS)
+                .contains(&self.iterations)
+        {
+            Err(ApiError::core("The encrypted enrollment ticket is invalid"))?
+        }
+        let plaintext = ticket_cipher(passphrase, &self.salt, self.iterations)
+            .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_err| {
+                ApiError::core(
+                    "Failed to decrypt the enrollment ticket. Please check the passphrase",
+                )
+            })?;
+        Ok(serde_json::from_slice(&plaintext)
+            .map_err(|_err| ApiError::core("The decrypted enrollment ticket is invalid"))?)
+    }
+}
+
+/// An enrollment ticket, as given to a command redeeming it
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum EnrollmentTicketContents {
+    Plain(EnrollmentTicket),
+    Encrypted(EncryptedEnrollmentTicket),
+}
+
+impl EnrollmentTicketContents {
+    /// Return the ticket, decrypted with the passphrase if it is encrypted
+    pub fn ticket(&self, passphrase: Option<&TicketPassphrase>) -> Result<EnrollmentTicket> {
+        match (self, passphrase) {
+            (Self::Plain(ticket), _) => Ok(ticket.clone()),
+            (Self::Encrypted(encrypted), Some(passphrase)) => encrypted.decrypt(passphrase),
+            (Self::Encrypted(_), None) => Err(ApiError::core(
+                "This enrollment ticket is encrypted. Please provide the passphrase used to encrypt it",
+            ))?,
+        }
+    }
+}
+
+/// Passphrase used to encrypt an enrollment ticket.
+/// Like the ticket itself, it is never displayed in the logs
+#[derive(Clone, PartialEq, Eq)]
+pub struct TicketPassphrase(String);
+
+impl TicketPassphrase {
+    pub fn new(passphrase: impl Into<String>) -> Self {
+        Self(passphrase.into())
+    }
+}
+
+impl FromStr for TicketPassphrase {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl Debug for TicketPassphrase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(REDACTED)
+    }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypted_ticket_round_trip() -> Result<()> {
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        let passphrase = TicketPassphrase::new("correct horse battery staple");
+
+        let encrypted = ticket.encrypt(&passphrase)?;
+        let encrypted_hex = encrypted.hex_encoded()?;
+        assert!(!encrypted_hex.contains(&ticket.hex_encoded()?));
+        assert!(!encrypted_hex.contains(&ticket.one_time_code.to_string()));
+
+        // the encrypted ticket can be parsed like a plain one
+        let contents: EnrollmentTicketContents =
+            serde_json::from_slice(&hex::decode(encrypted_hex).unwrap()).unwrap();
+        assert_eq!(
+            contents,
+            EnrollmentTicketContents::Encrypted(encrypted.clone())
+        );
+        assert_eq!(contents.ticket(Some(&passphrase))?, ticket);
+
+        // it can't be used without the right passphrase
+        assert!(contents.ticket(None).is_err());
+        assert!(contents
+            .ticket(Some(&TicketPassphrase::new("wrong passphrase")))
+            .is_err());
+
+        // and the passphrase is never displayed
+        assert!(!format!("{passphrase:?}").contains("correct horse"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_plain_ticket_contents() -> Result<()> {
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        let contents: EnrollmentTicketContents =
+            serde_json::from_slice(&hex::decode(ticket.hex_encoded()?).unwrap()).unwrap();
+        assert_eq!(contents.ticket(None)?, ticket);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_enrollment_attributes() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let alice = Some("alice".to_string());
+
+        // a ticket created with `ockam project ticket --attribute role=ops --attribute team=payments`
+        let ticket =
+            EnrollmentTicket::new(OneTimeCode::new(), None).with_attributes(BTreeMap::from([
+                ("role".to_string(), "ops".to_string()),
+                ("team".to_string(), "payments".to_string()),
+            ]));
+        let contents: EnrollmentTicketContents =
+            serde_json::from_slice(&hex::decode(ticket.hex_encoded()?).unwrap()).unwrap();
+        let ticket = contents.ticket(None)?;
+
+        // the credential attributes take precedence over the ticket attributes
+        let credential_attributes = BTreeMap::from([("role".to_string(), "admin".to_string())]);
+        cli.store_enrollment_attributes(
+            &identity.identifier(),
+            "project_id",
+            &ticket.attributes,
+            &credential_attributes,
+        )
+        .await?;
+        let attribute = |name: &str, value: &str, source| EnrollmentAttribute {
+            project_id: "project_id".to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            source,
+        };
+        assert_eq!(
+            cli.get_enrollment_attributes(&identity.identifier())
+                .await?,
+            vec![
+                attribute("role", "admin", EnrollmentAttributeSource::Credential),
+                attribute("team", "payments", EnrollmentAttributeSource::Ticket),
+            ]
+        );
+
+        // the attributes can be used to create policy expressions
+        let expression = cli
+            .expression_from_enrollment_attributes(&alice, &["role".to_string()])
+            .await?;
+        assert_eq!(expression.to_string(), r#"(= subject.role "admin")"#);
+        let expression = cli
+            .expression_from_enrollment_attributes(
+                &alice,
+                &["role".to_string(), "team".to_string()],
+            )
+            .await?;
+        assert_eq!(
+            expression.to_string(),
+            r#"(and (= subject.role "admin") (= subject.team "payments"))"#
+        );
+        assert!(cli
+            .expression_from_enrollment_attributes(&alice, &["unknown".to_string()])
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_range_iterations_are_rejected() {
+        let passphrase = TicketPassphrase::new("correct horse battery staple");
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        let encrypted = ticket.encrypt(&passphrase).unwrap();
+        for iterations in [
+            1,
+            TICKET_KEY_DERIVATION_ITERATIONS - 1,
+            TICKET_KEY_DERIVATION_MAX_ITERATIONS + 1,
+            u32::MAX,
+        ] {
+            let tampered = EncryptedEnrollmentTicket {
+                iterations,
+                ..encrypted.clone()
+            };
+            assert!(tampered.decrypt(&passphrase).is_err());
+        }
+        assert_eq!(encrypted.decrypt(&passphrase).unwrap(), ticket);
+    }
+
+    #[test]
+    fn test_empty_passphrase_is_rejected() {
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        assert!(ticket.encrypt(&TicketPassphrase::new("")).is_err());
+    }
+}
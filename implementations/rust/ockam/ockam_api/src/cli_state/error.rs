@@ -45,6 +45,13 @@ pub enum CliStateError {
     #[diagnostic(code("OCK500"))]
     InvalidOperation(String),
 
+    #[error("Another ockam command is modifying the local state. Gave up waiting after {timeout}")]
+    #[diagnostic(
+        code("OCK409"),
+        help("Please wait for the other command to complete and try again, or increase the waiting time with the OCKAM_STATE_LOCK_TIMEOUT environment variable")
+    )]
+    StateLocked { timeout: String },
+
     #[error("Invalid configuration version '{0}'")]
     #[diagnostic(
         code("OCK500"),
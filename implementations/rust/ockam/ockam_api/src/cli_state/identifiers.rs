@@ -0,0 +1,252 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use ockam::identity::Identifier;
+use ockam_abac::attribute_access_control::{ABAC_IDENTIFIER_KEY, SUBJECT_KEY};
+use ockam_abac::Expr;
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// Minimum number of hexadecimal characters required to resolve an identifier from a prefix
+pub const MIN_IDENTIFIER_PREFIX_LEN: usize = 8;
+
+/// The methods below resolve identifiers which are given in a short form,
+/// for example `I84502ce0`, instead of the full 64 hexadecimal characters.
+///
+/// A short form is resolved against the identifiers known locally: the identities
+/// stored in the change history repository and the identities having attributes.
+impl CliState {
+    /// Return the identifier corresponding to a full identifier or to an unambiguous prefix.
+    ///
+    /// The leading `I` of the prefix is optional.
+    pub async fn resolve_identifier(&self, value: &str) -> Result<Identifier> {
+        if let Ok(identifier) = Identifier::from_str(value) {
+            return Ok(identifier);
+        }
+
+        let hex = value.strip_prefix('I').unwrap_or(value).to_lowercase();
+        if hex.len() < MIN_IDENTIFIER_PREFIX_LEN || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(CliStateError::InvalidData(format!(
+                "{value} is not a valid identifier. An identifier prefix must contain at least {MIN_IDENTIFIER_PREFIX_LEN} hexadecimal characters"
+            )));
+        }
+
+        let prefix = format!("I{hex}");
+        let mut candidates: BTreeSet<Identifier> = self
+            .change_history_repository()
+            .get_identifiers_by_prefix(&prefix)
+            .await?
+            .into_iter()
+            .collect();
+        candidates.extend(
+            self.identities_attributes()
+                .get_identifiers_by_prefix(&prefix)
+                .await?,
+        );
+
+        let mut candidates = candidates.into_iter();
+        match (candidates.next(), candidates.next()) {
+            (None, _) => Err(CliStateError::ResourceNotFound {
+                resource: "identifier".to_string(),
+                name: value.to_string(),
+            }),
+            (Some(identifier), None) => Ok(identifier),
+            (Some(first), Some(second)) => {
+                let candidates = [first, second]
+                    .into_iter()
+                    .chain(candidates)
+                    .map(|i| i.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                Err(CliStateError::InvalidData(format!(
+                    "The identifier prefix {value} is ambiguous. It matches: {candidates}"
+                )))
+            }
+        }
+    }
+
+    /// Resolve a list of full identifiers or identifier prefixes
+    pub async fn resolve_identifiers(&self, values: &[String]) -> Result<Vec<Identifier>> {
+        let mut identifiers = vec![];
+        for value in values {
+            identifiers.push(self.resolve_identifier(value).await?);
+        }
+        Ok(identifiers)
+    }
+
+    /// Return a copy of a policy expression where the identifiers compared
+    /// to `subject.identifier` are resolved to full identifiers
+    pub async fn resolve_identifiers_in_expression(&self, expression: &Expr) -> Result<Expr> {
+        let subject_identifier = Expr::Ident(format!("{SUBJECT_KEY}.{ABAC_IDENTIFIER_KEY}"));
+        let mut stack = vec![(expression, false)];
+        let mut values = vec![];
+
+        // collect the string values which are compared to `subject.identifier`
+        while let Some((expr, is_identifier)) = stack.pop() {
+            match expr {
+                Expr::Str(s) if is_identifier => values.push(s.clone()),
+                Expr::Seq(exprs) => stack.extend(exprs.iter().map(|e| (e, is_identifier))),
+                Expr::List(exprs) => {
+                    let is_identifier = exprs.contains(&subject_identifier);
+                    stack.extend(exprs.iter().map(|e| (e, is_identifier)))
+                }
+                _ => (),
+            }
+        }
+
+        let mut resolved = vec![];
+        for value in values {
+            let identifier = self.resolve_identifier(&value).await?;
+            resolved.push((value, identifier.to_string()));
+        }
+        Ok(replace_identifiers(
+            expression,
+            &subject_identifier,
+            &resolved,
+            false,
+        ))
+    }
+}
+
+fn replace_identifiers(
+    expression: &Expr,
+    subject_identifier: &Expr,
+    resolved: &[(String, String)],
+    is_identifier: bool,
+) -> Expr {
+    match expression {
+        Expr::Str(s) if is_identifier => resolved
+            .iter()
+            .find(|(value, _)| value == s)
+            .map(|(_, identifier)| Expr::Str(identifier.clone()))
+            .unwrap_or_else(|| expression.clone()),
+        Expr::Seq(exprs) => Expr::Seq(
+            exprs
+                .iter()
+                .map(|e| replace_identifiers(e, subject_identifier, resolved, is_identifier))
+                .collect(),
+        ),
+        Expr::List(exprs) => {
+            let is_identifier = exprs.contains(subject_identifier);
+            Expr::List(
+                exprs
+                    .iter()
+                    .map(|e| replace_identifiers(e, subject_identifier, resolved, is_identifier))
+                    .collect(),
+            )
+        }
+        _ => expression.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::models::IDENTIFIER_LEN;
+    use ockam_abac::parse;
+
+    #[tokio::test]
+    async fn test_resolve_full_identifier() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // a full identifier is returned as is, even if it is not known locally
+        let identifier = Identifier([1; IDENTIFIER_LEN]);
+        let result = cli.resolve_identifier(&identifier.to_string()).await?;
+        assert_eq!(result, identifier);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_unique_prefix() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let identifier = identity.identifier();
+        let full = identifier.to_string();
+
+        let result = cli.resolve_identifier(&full[..9]).await?;
+        assert_eq!(result, identifier);
+
+        // the leading I is optional and the prefix is not case-sensitive
+        let result = cli.resolve_identifier(&full[1..12].to_uppercase()).await?;
+        assert_eq!(result, identifier);
+
+        // a prefix which is too short is rejected
+        let result = cli.resolve_identifier(&full[..5]).await;
+        assert!(matches!(result, Err(CliStateError::InvalidData(_))));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_ambiguous_prefix() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let change_history = cli
+            .change_history_repository()
+            .get_change_history(&identity.identifier())
+            .await?
+            .unwrap();
+
+        // store two identifiers sharing the same 8 characters prefix
+        let mut bytes1 = [0xab; IDENTIFIER_LEN];
+        let mut bytes2 = [0xab; IDENTIFIER_LEN];
+        bytes1[IDENTIFIER_LEN - 1] = 1;
+        bytes2[IDENTIFIER_LEN - 1] = 2;
+        let (identifier1, identifier2) = (Identifier(bytes1), Identifier(bytes2));
+        for identifier in [&identifier1, &identifier2] {
+            cli.change_history_repository()
+                .store_change_history(identifier, change_history.clone())
+                .await?;
+        }
+
+        let result = cli.resolve_identifier("Iabababab").await;
+        match result {
+            Err(CliStateError::InvalidData(message)) => {
+                assert!(message.contains(&identifier1.to_string()));
+                assert!(message.contains(&identifier2.to_string()));
+            }
+            _ => panic!("the prefix must be ambiguous: {result:?}"),
+        }
+
+        // a longer prefix is not ambiguous anymore
+        let result = cli
+            .resolve_identifier(&identifier2.to_string()[..64])
+            .await?;
+        assert_eq!(result, identifier2);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_unknown_prefix() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        let result = cli.resolve_identifier("I0123456789").await;
+        assert!(matches!(
+            result,
+            Err(CliStateError::ResourceNotFound { .. })
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resolve_identifiers_in_expression() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let identifier = identity.identifier().to_string();
+        let prefix = &identifier[..10];
+
+        let expression = parse(&format!(
+            r#"(or (= subject.identifier "{prefix}") (and (= subject.name "{prefix}") (member? subject.identifier ["{prefix}"])))"#
+        ))
+        .unwrap()
+        .unwrap();
+        let expected = parse(&format!(
+            r#"(or (= subject.identifier "{identifier}") (and (= subject.name "{prefix}") (member? subject.identifier ["{identifier}"])))"#
+        ))
+        .unwrap()
+        .unwrap();
+
+        let result = cli.resolve_identifiers_in_expression(&expression).await?;
+        assert_eq!(result, expected);
+        Ok(())
+    }
+}
@@ -1,15 +1,36 @@
 use colorful::Colorful;
 use ockam::identity::models::ChangeHistory;
 use ockam::identity::{Identifier, Identity};
+use ockam_core::env::get_env;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
 use ockam_vault::{HandleToSecret, SigningSecretKeyHandle};
 
 use crate::{
-    cli_state::{random_name, CliState, Result},
+    cli_state::{random_name, CliState, CliStateError, Result},
     color_primary,
 };
 
+/// Environment variable used to select the identity of a command
+/// when no identity name is given as a command argument
+pub const OCKAM_IDENTITY: &str = "OCKAM_IDENTITY";
+
+/// Select the name of the identity to use for a command, by order of precedence:
+///
+///  1. the name given as a command argument (`--identity`)
+///  2. the name given with the `OCKAM_IDENTITY` environment variable
+///  3. the name of the default identity
+///
+/// All the commands creating secure channels, enrolling or signing must resolve their
+/// identity with this function (via [`CliState::resolve_identity_name`]).
+pub fn resolve_identity(
+    cmd_flag: Option<String>,
+    env: Option<String>,
+    default: Option<String>,
+) -> Option<String> {
+    cmd_flag.or(env).or(default)
+}
+
 /// The methods below allow the creation named identities.
 /// A NamedIdentity is an identity that is associated to a name in order to be more easily
 /// retrieved when necessary.
@@ -40,7 +61,7 @@ impl CliState {
         };
 
         let vault = self.get_named_vault(vault_name).await?;
-        let identities = self.make_identities(vault.vault().await?).await?;
+        let identities = self.make_identities(self.make_vault(&vault).await?).await?;
         let identity = identities.identities_creation().create_identity().await?;
 
         self.store_named_identity(&identity, name, &vault.name())
@@ -82,7 +103,7 @@ impl CliState {
         ));
 
         // create the identity
-        let identities = self.make_identities(vault.vault().await?).await?;
+        let identities = self.make_identities(self.make_vault(&vault).await?).await?;
         let identifier = identities
             .identities_creation()
             .identity_builder()
@@ -141,14 +162,53 @@ impl CliState {
         &mut self,
         name: &Option<String>,
     ) -> Result<NamedIdentity> {
-        match name {
+        match self.get_explicit_identity_name(name)? {
             // Identity specified.
-            Some(name) => self.get_named_identity(name).await,
+            Some(name) => self.get_named_identity(&name).await,
             // No identity specified.
             None => self.get_or_create_default_named_identity().await,
         }
     }
 
+    /// Return the name of the identity to use for a command, given the value of its
+    /// `--identity` argument. See [`resolve_identity`] for the order of precedence.
+    ///
+    /// This function creates the default identity if no identity is specified and
+    /// the default identity does not exist!
+    #[instrument(skip_all, fields(cmd_flag = cmd_flag.clone()))]
+    pub async fn resolve_identity_name(&self, cmd_flag: &Option<String>) -> Result<String> {
+        self.resolve_identity_name_with_env(cmd_flag, get_env::<String>(OCKAM_IDENTITY)?)
+            .await
+    }
+
+    /// Same as [`CliState::resolve_identity_name`] with an explicit value for
+    /// the `OCKAM_IDENTITY` environment variable
+    pub async fn resolve_identity_name_with_env(
+        &self,
+        cmd_flag: &Option<String>,
+        env: Option<String>,
+    ) -> Result<String> {
+        let default = self
+            .identities_repository()
+            .get_default_named_identity()
+            .await?
+            .map(|i| i.name());
+        match resolve_identity(cmd_flag.clone(), env, default) {
+            Some(name) => Ok(name),
+            None => self.get_default_identity_name().await,
+        }
+    }
+
+    /// Return the identity name explicitly selected for a command, either with
+    /// the `--identity` argument or the `OCKAM_IDENTITY` environment variable
+    pub fn get_explicit_identity_name(&self, cmd_flag: &Option<String>) -> Result<Option<String>> {
+        Ok(resolve_identity(
+            cmd_flag.clone(),
+            get_env::<String>(OCKAM_IDENTITY)?,
+            None,
+        ))
+    }
+
     /// Return the identifier of a named identity
     pub async fn get_identifier_by_name(&self, name: &str) -> Result<Identifier> {
         Ok(self.get_named_identity(name).await?.identifier())
@@ -162,6 +222,7 @@ impl CliState {
         name: &Option<String>,
     ) -> Result<Identifier> {
         let repository = self.identities_repository();
+        let name = &self.get_explicit_identity_name(name)?;
         let result = match name {
             Some(name) => repository.get_identifier(name).await?,
             None => repository
@@ -278,10 +339,7 @@ impl CliState {
     /// - or the name of the default identity (which is created if it does not already exist!)
     #[instrument(skip_all, fields(name = name.clone()))]
     pub async fn get_identity_name_or_default(&self, name: &Option<String>) -> Result<String> {
-        match name {
-            Some(name) => Ok(name.clone()),
-            None => self.get_default_identity_name().await,
-        }
+        self.resolve_identity_name(name).await
     }
 
     /// Return the named identity with the given identifier
@@ -325,6 +383,29 @@ impl CliState {
         Ok(self.identities_repository().set_as_default(name).await?)
     }
 
+    /// Rename an identity.
+    ///
+    /// Only the local name of the identity is changed: its identifier is the same,
+    /// so that the nodes, credentials and enrollments using that identity are not affected.
+    /// Return an error if the identity does not exist or if the new name is already taken.
+    #[instrument(skip_all, fields(name = %name, new_name = %new_name))]
+    pub async fn rename_identity(&self, name: &str, new_name: &str) -> Result<NamedIdentity> {
+        let named_identity = self.get_named_identity(name).await?;
+        if name == new_name {
+            return Ok(named_identity);
+        }
+
+        let repository = self.identities_repository();
+        if repository.get_named_identity(new_name).await?.is_some() {
+            return Err(CliStateError::AlreadyExists {
+                resource: "identity".to_string(),
+                name: new_name.to_string(),
+            });
+        }
+        repository.rename_identity(name, new_name).await?;
+        self.get_named_identity(new_name).await
+    }
+
     /// Delete an identity by name:
     ///
     ///  - check that the identity is not used by a node first
@@ -466,6 +547,54 @@ impl NamedIdentity {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_resolve_identity_precedence() {
+        let flag = Some("flag".to_string());
+        let env = Some("env".to_string());
+        let default = Some("default".to_string());
+
+        let cases = [
+            (&flag, &env, &default, Some("flag")),
+            (&flag, &None, &default, Some("flag")),
+            (&flag, &env, &None, Some("flag")),
+            (&None, &env, &default, Some("env")),
+            (&None, &env, &None, Some("env")),
+            (&None, &None, &default, Some("default")),
+            (&None, &None, &None, None),
+        ];
+        for (flag, env, default, expected) in cases {
+            assert_eq!(
+                resolve_identity(flag.clone(), env.clone(), default.clone()).as_deref(),
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_identity_name() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // the default identity is created if no identity is specified
+        let name = cli.resolve_identity_name_with_env(&None, None).await?;
+        let default = cli.get_or_create_default_named_identity().await?;
+        assert_eq!(name, default.name());
+
+        let flag = cli.create_identity_with_name("flag").await?;
+        let env = cli.create_identity_with_name("env").await?;
+        let cases = [
+            (Some(flag.name()), Some(env.name()), flag.identifier()),
+            (Some(flag.name()), None, flag.identifier()),
+            (None, Some(env.name()), env.identifier()),
+            (None, None, default.identifier()),
+        ];
+        for (cmd_flag, env, expected) in cases {
+            let name = cli.resolve_identity_name_with_env(&cmd_flag, env).await?;
+            assert_eq!(cli.get_identifier_by_name(&name).await?, expected);
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_identity_with_a_vault() -> Result<()> {
         let cli = CliState::test().await?;
@@ -552,4 +681,39 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_rename_identity() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("name").await?;
+        let other = cli.create_identity_with_name("other").await?;
+        let node = cli
+            .create_node_with_optional_values("node", &Some(identity.name()), &None)
+            .await?;
+
+        // the identity can't be renamed to an existing name
+        assert!(cli.rename_identity("name", "other").await.is_err());
+        assert!(cli.rename_identity("unknown", "new-name").await.is_err());
+
+        // when the identity is renamed its identifier is the same
+        let result = cli.rename_identity("name", "new-name").await?;
+        assert_eq!(result.identifier(), identity.identifier());
+        assert_eq!(result.name(), "new-name");
+        assert!(result.is_default());
+        assert!(cli.get_named_identity("name").await.is_err());
+        assert_eq!(
+            cli.get_named_identity("other").await?.identifier(),
+            other.identifier()
+        );
+
+        // the node using that identity can still access it
+        let result = cli.get_node(&node.name()).await?.identifier();
+        assert_eq!(result, identity.identifier());
+        let result = cli.get_named_identity_by_identifier(&result).await?;
+        assert_eq!(result.name(), "new-name");
+
+        // and the identity can't be deleted since it is still used by the node
+        assert!(cli.delete_identity_by_name("new-name").await.is_err());
+        Ok(())
+    }
 }
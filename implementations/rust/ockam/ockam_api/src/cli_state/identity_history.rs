@@ -0,0 +1,359 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use ockam::identity::models::{CredentialVerifyingKey, PurposePublicKey};
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, Identity, Purpose, TimestampInSeconds};
+use ockam_vault::VerifyingPublicKey;
+
+use crate::cli_state::{CliState, Result};
+
+/// The methods below support the display of the full history of an identity:
+/// the changes of its primary key and the purpose keys attested by that key.
+impl CliState {
+    /// Return the change history of an identity and its purpose keys.
+    /// The purpose keys are only known for the identities created locally.
+    pub async fn get_identity_history(&self, identity: &Identity) -> Result<IdentityHistory> {
+        let changes = identity.changes();
+        let latest = changes.len().saturating_sub(1);
+        let identity_changes: Vec<IdentityChange> = changes
+            .iter()
+            .enumerate()
+            .map(|(index, change)| {
+                let data = change.data();
+                IdentityChange {
+                    index,
+                    identifier: hex::encode(change.change_hash()),
+                    created_at: data.attestations_valid_from,
+                    valid_until: data.attestations_valid_until,
+                    primary_public_key: KeyDescription::from_verifying_key(
+                        change.primary_public_key(),
+                    ),
+                    revoke_all_purpose_keys: data.revoke_all_purpose_keys,
+                    status: if index == latest {
+                        ChangeStatus::Active
+                    } else {
+                        ChangeStatus::Rotated
+                    },
+                }
+            })
+            .collect();
+
+        let now = now()?;
+        let mut purpose_keys = vec![];
+        for purpose in [Purpose::SecureChannel, Purpose::Credentials] {
+            let attestation = self
+                .purpose_keys_repository()
+                .get_purpose_key(identity.identifier(), purpose)
+                .await?;
+            let Some(attestation) = attestation else {
+                continue;
+            };
+            let data = attestation.get_attestation_data()?;
+            let attested_by = changes
+                .iter()
+                .position(|c| c.change_hash() == &data.subject_latest_change_hash);
+            // the purpose keys attested before a change revoking them are not valid anymore
+            let is_revoked = match attested_by {
+                Some(index) => changes[index + 1..]
+                    .iter()
+                    .any(|c| c.data().revoke_all_purpose_keys),
+                None => true,
+            };
+            let status = if is_revoked {
+                PurposeKeyStatus::Revoked
+            } else if data.expires_at <= now {
+                PurposeKeyStatus::Expired
+            } else {
+                PurposeKeyStatus::Valid
+            };
+            purpose_keys.push(IdentityPurposeKey {
+                purpose: PurposeName(purpose),
+                public_key: KeyDescription::from_purpose_key(&data.public_key),
+                created_at: data.created_at,
+                expires_at: data.expires_at,
+                attested_by_change: attested_by,
+                status,
+            });
+        }
+
+        Ok(IdentityHistory {
+            identifier: identity.identifier().clone(),
+            changes: identity_changes,
+            purpose_keys,
+        })
+    }
+}
+
+/// Change history of an identity, and its purpose keys
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityHistory {
+    pub identifier: Identifier,
+    pub changes: Vec<IdentityChange>,
+    pub purpose_keys: Vec<IdentityPurposeKey>,
+}
+
+/// Change of the primary key of an identity
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityChange {
+    /// Position of the change in the history, starting at 0
+    pub index: usize,
+    /// Hex-encoded hash of the change
+    pub identifier: String,
+    pub created_at: TimestampInSeconds,
+    /// Time until which the key can attest purpose keys and sign the next change
+    pub valid_until: TimestampInSeconds,
+    pub primary_public_key: KeyDescription,
+    /// True if the change revokes the purpose keys attested by the previous keys
+    pub revoke_all_purpose_keys: bool,
+    pub status: ChangeStatus,
+}
+
+/// Status of the key of a change: only the key of the latest change is active
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeStatus {
+    Active,
+    Rotated,
+}
+
+/// Purpose key of an identity, with the validity window of its attestation
+#[derive(Serialize, Debug, Clone)]
+pub struct IdentityPurposeKey {
+    pub purpose: PurposeName,
+    pub public_key: KeyDescription,
+    pub created_at: TimestampInSeconds,
+    pub expires_at: TimestampInSeconds,
+    /// Index of the change whose key attested the purpose key, if it is part of the history
+    pub attested_by_change: Option<usize>,
+    pub status: PurposeKeyStatus,
+}
+
+/// Status of a purpose key attestation
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PurposeKeyStatus {
+    Valid,
+    Expired,
+    /// The attestation was revoked by a later change, or was not made by a key of the history
+    Revoked,
+}
+
+/// Purpose of a purpose key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PurposeName(pub Purpose);
+
+impl Display for PurposeName {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Purpose::SecureChannel => f.write_str("secure-channel"),
+            Purpose::Credentials => f.write_str("credential-signing"),
+        }
+    }
+}
+
+impl Serialize for PurposeName {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Type of a public key and its fingerprint: the first 8 bytes of the SHA-256 hash
+/// of the key, hex-encoded
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeyDescription {
+    pub key_type: String,
+    pub fingerprint: String,
+}
+
+impl KeyDescription {
+    fn new(key_type: &str, public_key: &[u8]) -> Self {
+        Self {
+            key_type: key_type.to_string(),
+            fingerprint: hex::encode(&Sha256::digest(public_key)[..8]),
+        }
+    }
+
+    fn from_verifying_key(public_key: &VerifyingPublicKey) -> Self {
+        match public_key {
+            VerifyingPublicKey::EdDSACurve25519(key) => Self::new("EdDSACurve25519", &key.0),
+            VerifyingPublicKey::ECDSASHA256CurveP256(key) => {
+                Self::new("ECDSASHA256CurveP256", &key.0)
+            }
+        }
+    }
+
+    fn from_purpose_key(public_key: &PurposePublicKey) -> Self {
+        match public_key {
+            PurposePublicKey::SecureChannelStatic(key) => Self::new("X25519", &key.0),
+            PurposePublicKey::CredentialSigning(CredentialVerifyingKey::EdDSACurve25519(key)) => {
+                Self::new("EdDSACurve25519", &key.0)
+            }
+            PurposePublicKey::CredentialSigning(CredentialVerifyingKey::ECDSASHA256CurveP256(
+                key,
+            )) => Self::new("ECDSASHA256CurveP256", &key.0),
+        }
+    }
+}
+
+impl Display for KeyDescription {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.key_type, self.fingerprint)
+    }
+}
+
+impl Display for IdentityHistory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Identifier: {}", self.identifier)?;
+        writeln!(f, "Changes:")?;
+        for change in &self.changes {
+            writeln!(f, "  Change[{}]: {}", change.index, change.status)?;
+            writeln!(f, "    identifier:              {}", change.identifier)?;
+            writeln!(
+                f,
+                "    created_at:              {}",
+                format_time(change.created_at)
+            )?;
+            writeln!(
+                f,
+                "    valid_until:             {}",
+                format_time(change.valid_until)
+            )?;
+            writeln!(
+                f,
+                "    primary_public_key:      {}",
+                change.primary_public_key
+            )?;
+            writeln!(
+                f,
+                "    revoke_all_purpose_keys: {}",
+                change.revoke_all_purpose_keys
+            )?;
+        }
+        if self.purpose_keys.is_empty() {
+            writeln!(f, "Purpose keys: none")?;
+        } else {
+            writeln!(f, "Purpose keys:")?;
+        }
+        for key in &self.purpose_keys {
+            writeln!(f, "  {}: {}", key.purpose, key.status)?;
+            writeln!(f, "    public_key:         {}", key.public_key)?;
+            writeln!(f, "    created_at:         {}", format_time(key.created_at))?;
+            writeln!(f, "    expires_at:         {}", format_time(key.expires_at))?;
+            match key.attested_by_change {
+                Some(index) => writeln!(f, "    attested_by_change: Change[{index}]")?,
+                None => writeln!(f, "    attested_by_change: unknown")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ChangeStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeStatus::Active => f.write_str("active"),
+            ChangeStatus::Rotated => f.write_str("rotated"),
+        }
+    }
+}
+
+impl Display for PurposeKeyStatus {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PurposeKeyStatus::Valid => f.write_str("valid"),
+            PurposeKeyStatus::Expired => f.write_str("expired"),
+            PurposeKeyStatus::Revoked => f.write_str("revoked"),
+        }
+    }
+}
+
+fn format_time(timestamp: TimestampInSeconds) -> String {
+    OffsetDateTime::from_unix_timestamp(timestamp.0 as i64)
+        .ok()
+        .and_then(|t| t.format(&Rfc3339).ok())
+        .unwrap_or_else(|| timestamp.0.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_identity_history() -> Result<()> {
+        let cli = CliState::test().await?;
+        let named_identity = cli.create_identity_with_name("alice").await?;
+        let identifier = named_identity.identifier();
+        let vault = cli
+            .make_vault(&cli.get_named_vault(&named_identity.vault_name()).await?)
+            .await?;
+        let identities = cli.make_identities(vault).await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .create_secure_channel_purpose_key(&identifier)
+            .await?;
+        identities
+            .identities_creation()
+            .rotate_identity(&identifier)
+            .await?;
+
+        let identity = cli.get_identity(&identifier).await?;
+        let history = cli.get_identity_history(&identity).await?;
+        assert_eq!(history.identifier, identifier);
+        assert_eq!(history.changes.len(), 2);
+        assert_eq!(history.changes[0].status, ChangeStatus::Rotated);
+        assert_eq!(history.changes[1].status, ChangeStatus::Active);
+        for (index, change) in history.changes.iter().enumerate() {
+            assert_eq!(change.index, index);
+            assert_eq!(
+                change.identifier,
+                hex::encode(identity.changes()[index].change_hash())
+            );
+            assert_eq!(change.primary_public_key.fingerprint.len(), 16);
+            assert!(change.created_at <= change.valid_until);
+        }
+        assert_ne!(
+            history.changes[0].primary_public_key,
+            history.changes[1].primary_public_key
+        );
+
+        // the secure channel key was attested by the first key
+        assert_eq!(history.purpose_keys.len(), 1);
+        let purpose_key = &history.purpose_keys[0];
+        assert_eq!(purpose_key.purpose, PurposeName(Purpose::SecureChannel));
+        assert_eq!(purpose_key.public_key.key_type, "X25519");
+        assert_eq!(purpose_key.attested_by_change, Some(0));
+        assert!(purpose_key.created_at < purpose_key.expires_at);
+
+        let displayed = history.to_string();
+        assert!(displayed.contains("Change[1]: active"));
+        assert!(displayed.contains("secure-channel:"));
+
+        // an identity imported without its private keys has no purpose keys
+        let other = CliState::test().await?;
+        let vault = other
+            .make_vault(&other.get_or_create_default_named_vault().await?)
+            .await?;
+        other
+            .make_identities(vault)
+            .await?
+            .identities_verification()
+            .import(Some(&identifier), &identity.export()?)
+            .await?;
+        let imported = other.get_identity(&identifier).await?;
+        let history = other.get_identity_history(&imported).await?;
+        assert_eq!(history.changes.len(), 2);
+        assert_eq!(history.changes[1].status, ChangeStatus::Active);
+        assert!(history.purpose_keys.is_empty());
+        assert!(history.to_string().contains("Purpose keys: none"));
+        Ok(())
+    }
+}
@@ -0,0 +1,175 @@
+use std::path::Path;
+
+use sqlx::query_scalar;
+
+use ockam::SqlxDatabase;
+use ockam_node::database::{FromSqlxError, MigrationSet, NodeMigrationSet};
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// The nodes database is migrated when it is opened, unless a node was created or started with
+/// `--auto-migrate false`. In that case the pending migrations must be applied explicitly with
+/// `ockam node migrate` and no node can start until they are applied.
+///
+/// Since the nodes database is shared by all the local nodes, disabling the automatic migrations
+/// for one node disables them for the whole database.
+impl CliState {
+    /// Set if the pending database migrations can be applied automatically when the node starts
+    pub async fn set_node_auto_migrate(&self, node_name: &str, auto_migrate: bool) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_auto_migrate(node_name, auto_migrate)
+            .await?)
+    }
+
+    /// Return true if the pending database migrations can be applied automatically for a node
+    pub async fn get_node_auto_migrate(&self, node_name: &str) -> Result<bool> {
+        Ok(self.nodes_repository().get_auto_migrate(node_name).await?)
+    }
+
+    /// Return the names of the migrations which have not been applied to the nodes database yet
+    pub async fn pending_migrations(&self) -> Result<Vec<String>> {
+        let status = NodeMigrationSet
+            .create_migrator()?
+            .status(&self.database().pool)
+            .await?;
+        Ok(status.pending_migrations)
+    }
+
+    /// Apply the pending migrations to the nodes database and return their names.
+    /// Each migration is applied in its own transaction
+    pub async fn migrate_database(&self) -> Result<Vec<String>> {
+        let pending_migrations = self.pending_migrations().await?;
+        if !pending_migrations.is_empty() {
+            NodeMigrationSet
+                .create_migrator()?
+                .migrate(&self.database().pool)
+                .await?;
+        }
+        Ok(pending_migrations)
+    }
+
+    /// Return an error listing the pending migrations if there are any, since a node
+    /// can not start before they are applied
+    pub async fn check_no_pending_migrations(&self, node_name: &str) -> Result<()> {
+        let pending_migrations = self.pending_migrations().await?;
+        if pending_migrations.is_empty() {
+            return Ok(());
+        }
+        Err(CliStateError::InvalidOperation(format!(
+            "The node {node_name} can not start because the database has pending migrations: {}. Please review them and run `ockam node migrate {node_name}` to apply them",
+            pending_migrations.join(", ")
+        )))
+    }
+
+    /// Open the nodes database and apply its pending migrations,
+    /// unless the automatic migrations have been disabled for one of the nodes
+    pub(super) async fn open_nodes_database(path: &Path) -> Result<SqlxDatabase> {
+        let database = SqlxDatabase::create_no_migration(path).await?;
+        let migrator = NodeMigrationSet.create_migrator()?;
+        let status = migrator.status(&database.pool).await?;
+        if status.pending_migrations.is_empty() {
+            return Ok(database);
+        }
+
+        if Self::is_auto_migrate_disabled(&database).await? {
+            warn!(
+                "the database at {path:?} has pending migrations which must be applied with `ockam node migrate`: {}",
+                status.pending_migrations.join(", ")
+            );
+        } else {
+            migrator.migrate(&database.pool).await?;
+        }
+        Ok(database)
+    }
+
+    /// Return true if a node disabled the automatic migrations.
+    /// The database might not have been migrated yet to support that setting.
+    async fn is_auto_migrate_disabled(database: &SqlxDatabase) -> Result<bool> {
+        let column: Option<String> =
+            query_scalar("SELECT name FROM pragma_table_info('node') WHERE name = 'auto_migrate'")
+                .fetch_optional(&*database.pool)
+                .await
+                .into_core()?;
+        if column.is_none() {
+            return Ok(false);
+        }
+
+        let count: i64 = query_scalar("SELECT COUNT(*) FROM node WHERE auto_migrate = 0")
+            .fetch_one(&*database.pool)
+            .await
+            .into_core()?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_node::database::ToVoid;
+
+    #[tokio::test]
+    async fn test_migrations_are_applied_automatically_by_default() -> Result<()> {
+        let dir = CliState::test_dir()?;
+        let cli = CliState::create(dir.clone()).await?;
+        cli.create_node("node").await?;
+        remove_node_events_migration(&cli).await?;
+
+        let cli = CliState::create(dir).await?;
+        assert!(cli.pending_migrations().await?.is_empty());
+        cli.check_no_pending_migrations("node").await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explicit_migration() -> Result<()> {
+        let dir = CliState::test_dir()?;
+        let cli = CliState::create(dir.clone()).await?;
+        cli.create_node("node").await?;
+        cli.set_node_auto_migrate("node", false).await?;
+        remove_node_events_migration(&cli).await?;
+
+        // the node can't start while there are pending migrations
+        let cli = CliState::create(dir.clone()).await?;
+        let pending_migrations = cli.pending_migrations().await?;
+        assert_eq!(
+            pending_migrations,
+            vec!["20240220100000_add_node_events".to_string()]
+        );
+        match cli.check_no_pending_migrations("node").await {
+            Err(CliStateError::InvalidOperation(message)) => {
+                assert!(message.contains("20240220100000_add_node_events"))
+            }
+            result => panic!("the node must not be able to start: {result:?}"),
+        }
+
+        // the migrations are applied explicitly
+        let applied_migrations = cli.migrate_database().await?;
+        assert_eq!(applied_migrations, pending_migrations);
+        cli.check_no_pending_migrations("node").await?;
+
+        // the node can start afterwards and the setting is kept
+        let cli = CliState::create(dir).await?;
+        cli.check_no_pending_migrations("node").await?;
+        assert!(!cli.get_node_auto_migrate("node").await?);
+        Ok(())
+    }
+
+    /// HELPERS
+
+    /// Revert the migration creating the node events table
+    /// in order to get a database with a pending migration
+    async fn remove_node_events_migration(cli: &CliState) -> Result<()> {
+        for statement in [
+            "DROP INDEX node_events_node_name_created_at_index",
+            "DROP TABLE node_events",
+            "DELETE FROM _sqlx_migrations WHERE version = 20240220100000",
+        ] {
+            sqlx::query(statement)
+                .execute(&*cli.database().pool)
+                .await
+                .void()?;
+        }
+        Ok(())
+    }
+}
@@ -1,29 +1,48 @@
+pub use backups::*;
 pub use cli_state::*;
 pub use enrollments::*;
 pub use error::*;
+pub use identifiers::*;
 pub use identities::*;
+pub use identity_history::*;
+pub use node_exits::*;
+pub use node_versions::*;
 pub use nodes::*;
 pub use notifications::*;
+pub use route_aliases::*;
+pub use state_lock::*;
 pub use storage::*;
+pub use vault_keys::*;
 pub use vaults::*;
 
+pub mod backups;
 #[allow(clippy::module_inception)]
 pub mod cli_state;
+mod database_checks;
 pub mod enrollments;
 pub mod error;
+pub mod identifiers;
 pub mod identities;
 mod identities_attributes;
+pub mod identity_history;
 pub mod journeys;
+mod migrations;
+mod node_credentials;
+pub mod node_exits;
+pub mod node_versions;
 pub mod nodes;
 pub mod notifications;
 pub mod policies;
 pub mod projects;
 pub mod repositories;
 mod resources;
+pub mod route_aliases;
 pub mod secure_channels;
 pub mod spaces;
+pub mod state_lock;
 pub mod storage;
 pub mod test_support;
 pub mod trust;
 pub mod users;
+pub mod vault_keys;
 pub mod vaults;
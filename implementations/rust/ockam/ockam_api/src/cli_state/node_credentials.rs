@@ -0,0 +1,44 @@
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::{CredentialRepository, CredentialSqlxDatabase};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+
+use crate::cli_state::{CliState, Result};
+use crate::credentials::CredentialScope;
+
+/// The methods below support the export of the credentials cached by a node
+impl CliState {
+    /// Return the credential currently cached by a node for a given scope
+    #[instrument(skip_all, fields(node_name = node_name, scope = %scope))]
+    pub async fn get_node_credential(
+        &self,
+        node_name: &str,
+        scope: CredentialScope,
+    ) -> Result<CredentialAndPurposeKey> {
+        let node = self.get_node(node_name).await?;
+        let issuer = match scope {
+            CredentialScope::ProjectMember => self
+                .get_node_project(node_name)
+                .await?
+                .authority_identifier()?,
+        };
+
+        let mut database = self.database();
+        database.set_node_name(node_name);
+        let credential = CredentialSqlxDatabase::new(database)
+            .get(&node.identifier(), &issuer)
+            .await?;
+
+        credential.ok_or_else(|| {
+            Error::new(
+                Origin::Api,
+                Kind::NotFound,
+                format!(
+                    "the node {node_name} has no {scope} credential issued by {issuer}. \
+                    The credential is retrieved when the node first connects to the project"
+                ),
+            )
+            .into()
+        })
+    }
+}
@@ -0,0 +1,207 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+use serde::Serialize;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
+
+use crate::cli_state::{CliState, Result};
+
+/// File receiving the stderr output of a background node
+const STDERR_FILE: &str = "stderr.log";
+
+/// File recording the exit status of a background node process, written when the process exits.
+/// It contains the exit code of the process and the time of the exit, in seconds
+const EXIT_FILE: &str = "exit";
+
+/// File marking a background node as stopped with `ockam node stop`.
+/// The exit of a stopped node is not recorded
+const STOPPED_FILE: &str = "stopped";
+
+/// The exit of a background node is recorded in the node directory, next to its logs:
+///
+///  - the stderr output of the node process is captured in a dedicated file
+///  - the process is started by a small shell watcher writing its exit status once it exits,
+///    unless the node was stopped on purpose
+///
+/// The exit is read the next time the command line shows the node.
+impl CliState {
+    /// Return the file receiving the stderr output of a background node
+    pub fn node_stderr_file(&self, node_name: &str) -> Result<PathBuf> {
+        Ok(self.create_node_dir(node_name)?.join(STDERR_FILE))
+    }
+
+    /// Return the file recording the exit status of a background node
+    pub fn node_exit_file(&self, node_name: &str) -> Result<PathBuf> {
+        Ok(self.create_node_dir(node_name)?.join(EXIT_FILE))
+    }
+
+    /// Return the file marking a background node as stopped
+    pub fn node_stopped_file(&self, node_name: &str) -> Result<PathBuf> {
+        Ok(self.create_node_dir(node_name)?.join(STOPPED_FILE))
+    }
+
+    /// Mark a node as stopped on purpose, so that the exit of its process is not recorded as a crash
+    pub fn mark_node_as_stopped(&self, node_name: &str) -> Result<()> {
+        std::fs::write(self.node_stopped_file(node_name)?, now()?.0.to_string())?;
+        Ok(())
+    }
+
+    /// Prepare the files of a node which is restarted in the background.
+    /// The record of a previous crash is archived with the time of the crash,
+    /// instead of being overwritten by the new process
+    pub fn archive_node_exit(&self, node_name: &str) -> Result<()> {
+        let exit_file = self.node_exit_file(node_name)?;
+        let stderr_file = self.node_stderr_file(node_name)?;
+        if let Some(exit) = self.get_node_exit(node_name)? {
+            let node_dir = self.node_dir(node_name);
+            let suffix = exit.exited_at.0;
+            std::fs::rename(&exit_file, node_dir.join(format!("{EXIT_FILE}-{suffix}")))?;
+            if stderr_file.exists() {
+                std::fs::rename(
+                    &stderr_file,
+                    node_dir.join(format!("{STDERR_FILE}-{suffix}")),
+                )?;
+            }
+            info!("The exit of the node {node_name} was archived: {exit}");
+        }
+        for file in [exit_file, stderr_file, self.node_stopped_file(node_name)?] {
+            if file.exists() {
+                std::fs::remove_file(file)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the last unexpected exit of a background node process, if it was recorded
+    pub fn get_node_exit(&self, node_name: &str) -> Result<Option<NodeExit>> {
+        let exit_file = self.node_exit_file(node_name)?;
+        let exit = match std::fs::read_to_string(exit_file) {
+            Ok(exit) => exit,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let stderr = std::fs::read_to_string(self.node_stderr_file(node_name)?).unwrap_or_default();
+        Ok(NodeExit::parse(&exit, &stderr))
+    }
+}
+
+/// Unexpected exit of a background node process
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeExit {
+    /// Exit code of the process, when it was not terminated by a signal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<i32>,
+    /// Signal terminating the process
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    /// First line of the panic message written by the process on stderr
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panic: Option<String>,
+    /// Time of the exit
+    pub exited_at: TimestampInSeconds,
+}
+
+impl NodeExit {
+    /// Parse the content of an exit file, `<exit status> <time of the exit>`, and the stderr output
+    /// of the process. A shell reports a process terminated by a signal with an exit status of
+    /// 128 + the signal number
+    pub fn parse(exit: &str, stderr: &str) -> Option<Self> {
+        let mut parts = exit.split_whitespace();
+        let status: i32 = parts.next()?.parse().ok()?;
+        let exited_at = TimestampInSeconds(parts.next()?.parse().ok()?);
+        let (code, signal) = if status > 128 {
+            (None, Some(status - 128))
+        } else {
+            (Some(status), None)
+        };
+        Some(Self {
+            code,
+            signal,
+            panic: Self::panic_message(stderr),
+            exited_at,
+        })
+    }
+
+    /// Return the first line of a panic message.
+    /// The message follows the line `thread '<name>' panicked at <location>:`
+    fn panic_message(stderr: &str) -> Option<String> {
+        let mut lines = stderr.lines();
+        let panicked = lines.find(|l| l.contains("panicked at"))?;
+        let message = match panicked.split_once("panicked at ") {
+            Some((_, location)) if !location.trim_end().ends_with(':') => location,
+            _ => lines.next().unwrap_or(panicked),
+        };
+        Some(message.trim().to_string())
+    }
+}
+
+impl Display for NodeExit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match (self.signal, self.code) {
+            (Some(signal), _) => write!(f, "signal {signal}")?,
+            (None, Some(code)) => write!(f, "exit code {code}")?,
+            (None, None) => write!(f, "unknown")?,
+        }
+        if let Some(panic) = &self.panic {
+            write!(f, " / panic: {panic}")?;
+        }
+        let exited_at = OffsetDateTime::from_unix_timestamp(self.exited_at.0 as i64)
+            .ok()
+            .and_then(|t| t.format(&Rfc3339).ok())
+            .unwrap_or_else(|| self.exited_at.0.to_string());
+        write!(f, " at {exited_at}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_node_exit() {
+        let stderr = "some log\nthread 'main' panicked at src/node.rs:10:5:\nthe node crashed\nnote: run with `RUST_BACKTRACE=1`\n";
+        let exit = NodeExit::parse("134 1700000000\n", stderr).unwrap();
+        assert_eq!(exit.signal, Some(6));
+        assert_eq!(exit.code, None);
+        assert_eq!(exit.panic, Some("the node crashed".to_string()));
+        assert_eq!(
+            exit.to_string(),
+            "signal 6 / panic: the node crashed at 2023-11-14T22:13:20Z"
+        );
+
+        let exit = NodeExit::parse("1 1700000000", "error: no credential").unwrap();
+        assert_eq!(exit.code, Some(1));
+        assert_eq!(exit.panic, None);
+
+        assert_eq!(NodeExit::parse("", ""), None);
+    }
+
+    #[tokio::test]
+    async fn test_node_exit_is_archived_on_restart() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.create_node("node").await?;
+        assert_eq!(cli.get_node_exit("node")?, None);
+
+        // simulate a node process writing a panic on stderr and exiting with an error
+        std::fs::write(
+            cli.node_stderr_file("node")?,
+            "thread 'main' panicked at src/node.rs:10:5:\nboom\n",
+        )?;
+        std::fs::write(cli.node_exit_file("node")?, "101 1700000000")?;
+        let exit = cli.get_node_exit("node")?.unwrap();
+        assert_eq!(exit.code, Some(101));
+        assert_eq!(exit.panic, Some("boom".to_string()));
+
+        // the crash record is archived when the node is restarted
+        cli.archive_node_exit("node")?;
+        assert_eq!(cli.get_node_exit("node")?, None);
+        let node_dir = cli.node_dir("node");
+        assert!(node_dir.join("exit-1700000000").exists());
+        assert!(node_dir.join("stderr.log-1700000000").exists());
+        Ok(())
+    }
+}
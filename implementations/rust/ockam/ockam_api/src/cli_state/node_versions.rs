@@ -0,0 +1,218 @@
+use std::fmt::{Display, Formatter};
+
+use serde::Serialize;
+
+use crate::cli_state::{CliState, Result};
+use crate::Version;
+
+/// The version of the binary which created, or last started, a node is recorded in the
+/// database. It is compared to the version of the command line every time the command line
+/// connects to the node, since a background node keeps running the code it was started with
+/// until it is restarted.
+impl CliState {
+    /// Record the version of the current binary for a node which is starting
+    pub async fn set_node_binary_version(&self, node_name: &str) -> Result<()> {
+        Ok(self
+            .nodes_repository()
+            .set_node_binary_version(node_name, &NodeBinaryVersion::current())
+            .await?)
+    }
+
+    /// Return the version of the binary which created, or last started, a node.
+    /// There is no version for nodes started by a binary older than this feature
+    pub async fn get_node_binary_version(
+        &self,
+        node_name: &str,
+    ) -> Result<Option<NodeBinaryVersion>> {
+        Ok(self
+            .nodes_repository()
+            .get_node_binary_version(node_name)
+            .await?)
+    }
+
+    /// Compare the version of a node to the version of the current binary.
+    /// If they differ by more than a patch version, a warning is recorded and the mismatch is returned
+    pub async fn check_node_binary_version(
+        &self,
+        node_name: &str,
+    ) -> Result<Option<NodeVersionMismatch>> {
+        let node_version = match self.get_node_binary_version(node_name).await? {
+            Some(node_version) => node_version,
+            None => return Ok(None),
+        };
+        let mismatch =
+            NodeVersionMismatch::check(node_name, &NodeBinaryVersion::current(), &node_version);
+        if let Some(mismatch) = &mismatch {
+            warn!("{mismatch}");
+            self.add_warning(mismatch.to_string());
+        }
+        Ok(mismatch)
+    }
+}
+
+/// Version of the binary running a node
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeBinaryVersion {
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_hash: Option<String>,
+}
+
+impl NodeBinaryVersion {
+    /// Create a new binary version. An empty git hash is considered as missing
+    pub fn new(version: impl Into<String>, git_hash: Option<String>) -> Self {
+        Self {
+            version: version.into(),
+            git_hash: git_hash.filter(|h| !h.is_empty()),
+        }
+    }
+
+    /// Return the version of the current binary
+    pub fn current() -> Self {
+        Self::new(
+            Version::crate_version(),
+            Some(Version::git_hash().to_string()),
+        )
+    }
+
+    /// Version number of the binary, for example 0.118.0
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Hash of the commit the binary was compiled from, if known
+    pub fn git_hash(&self) -> Option<&str> {
+        self.git_hash.as_deref()
+    }
+
+    /// Return true if the two versions only differ by their patch version.
+    /// Versions which can't be parsed are only compatible if they are equal
+    pub fn is_compatible_with(&self, other: &NodeBinaryVersion) -> bool {
+        match (self.major_minor(), other.major_minor()) {
+            (Some(this), Some(that)) => this == that,
+            _ => self.version == other.version,
+        }
+    }
+
+    /// Return the major and minor numbers of the version
+    fn major_minor(&self) -> Option<(u64, u64)> {
+        let mut numbers = self.version.trim_start_matches('v').split('.');
+        let major = numbers.next()?.parse().ok()?;
+        let minor = numbers.next()?.parse().ok()?;
+        Some((major, minor))
+    }
+}
+
+impl Display for NodeBinaryVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.git_hash {
+            Some(git_hash) => write!(f, "{} ({git_hash})", self.version),
+            None => write!(f, "{}", self.version),
+        }
+    }
+}
+
+/// A node running a version of the binary which is not compatible with the command line version
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct NodeVersionMismatch {
+    node_name: String,
+    cli_version: NodeBinaryVersion,
+    node_version: NodeBinaryVersion,
+}
+
+impl NodeVersionMismatch {
+    /// Return a mismatch if the two versions differ by more than a patch version
+    pub fn check(
+        node_name: &str,
+        cli_version: &NodeBinaryVersion,
+        node_version: &NodeBinaryVersion,
+    ) -> Option<Self> {
+        if cli_version.is_compatible_with(node_version) {
+            None
+        } else {
+            Some(Self {
+                node_name: node_name.to_string(),
+                cli_version: cli_version.clone(),
+                node_version: node_version.clone(),
+            })
+        }
+    }
+
+    pub fn node_name(&self) -> &str {
+        &self.node_name
+    }
+
+    pub fn cli_version(&self) -> &NodeBinaryVersion {
+        &self.cli_version
+    }
+
+    pub fn node_version(&self) -> &NodeBinaryVersion {
+        &self.node_version
+    }
+}
+
+impl Display for NodeVersionMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The node {} was started with the version {} of ockam but the current version is {}. Please restart it with `ockam node stop {} && ockam node start {}`",
+            self.node_name, self.node_version, self.cli_version, self.node_name, self.node_name
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_compatibility() {
+        let version = |v: &str| NodeBinaryVersion::new(v, None);
+
+        // patch versions are compatible
+        assert!(version("0.118.0").is_compatible_with(&version("0.118.3")));
+        assert!(version("1.2.3").is_compatible_with(&version("v1.2.0")));
+
+        // minor and major versions are not
+        assert!(!version("0.118.0").is_compatible_with(&version("0.117.0")));
+        assert!(!version("1.0.0").is_compatible_with(&version("0.118.0")));
+
+        // invalid versions are only compatible with themselves
+        assert!(version("dev").is_compatible_with(&version("dev")));
+        assert!(!version("dev").is_compatible_with(&version("0.118.0")));
+    }
+
+    #[tokio::test]
+    async fn test_node_started_with_an_older_binary() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.create_node("node").await?;
+
+        // the version of a node started by the current binary is compatible
+        cli.set_node_binary_version("node").await?;
+        assert_eq!(
+            cli.get_node_binary_version("node").await?,
+            Some(NodeBinaryVersion::current())
+        );
+        assert_eq!(cli.check_node_binary_version("node").await?, None);
+        assert!(cli.take_warnings().is_empty());
+
+        // simulate a node started by an older binary
+        let old_version = NodeBinaryVersion::new("0.1.0", Some("abcdef".to_string()));
+        cli.nodes_repository()
+            .set_node_binary_version("node", &old_version)
+            .await?;
+
+        let mismatch = cli.check_node_binary_version("node").await?.unwrap();
+        assert_eq!(mismatch.node_version(), &old_version);
+        assert_eq!(mismatch.cli_version(), &NodeBinaryVersion::current());
+
+        // a warning is surfaced only once, even if the node is contacted several times
+        cli.check_node_binary_version("node").await?;
+        let warnings = cli.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("0.1.0 (abcdef)"));
+        assert!(warnings[0].contains(Version::crate_version()));
+        assert!(cli.take_warnings().is_empty());
+        Ok(())
+    }
+}
@@ -36,6 +36,7 @@ impl CliState {
         let pid = process::id();
         self.set_node_pid(node_name, pid).await?;
         node = node.set_pid(pid);
+        self.set_node_binary_version(node_name).await?;
 
         if let Some(tcp_listener) = tcp_listener {
             let address = (*tcp_listener.socket_address()).into();
@@ -47,6 +48,52 @@ impl CliState {
         Ok(node)
     }
 
+    /// Create an ephemeral node, with some optional associated values, and start it.
+    ///
+    /// An ephemeral node is never set as the default node. Its state must be removed with
+    /// [`CliState::remove_node`] when it stops. If its process is killed before that, its state
+    /// is removed the next time the list of nodes is retrieved.
+    #[instrument(skip_all, fields(node_name = node_name, identity_name = identity_name.clone(), project_name = project_name.clone()))]
+    pub async fn start_ephemeral_node_with_optional_values(
+        &self,
+        node_name: &str,
+        identity_name: &Option<String>,
+        project_name: &Option<String>,
+        tcp_listener: Option<&TcpListener>,
+    ) -> Result<NodeInfo> {
+        // The state of an ephemeral node is deleted when it stops,
+        // so it must not reuse the state of an existing node
+        if let Some(node) = self.nodes_repository().get_node(node_name).await? {
+            if node.is_ephemeral() && !node.is_running() {
+                self.remove_node(node_name).await?;
+            } else {
+                Err(Error::new(
+                    Origin::Api,
+                    Kind::AlreadyExists,
+                    format!("A node with name {node_name} already exists"),
+                ))?
+            }
+        }
+
+        let identity = match self.get_explicit_identity_name(identity_name)? {
+            Some(name) => self.get_named_identity(&name).await?,
+            None => self.get_or_create_default_named_identity().await?,
+        };
+        let node = NodeInfo::new(
+            node_name.to_string(),
+            identity.identifier(),
+            0,
+            false,
+            false,
+            tcp_listener.map(|l| (*l.socket_address()).into()),
+            Some(process::id()),
+        )
+        .set_ephemeral();
+        self.nodes_repository().store_node(&node).await?;
+        self.set_node_project(node_name, project_name).await?;
+        Ok(node)
+    }
+
     /// Create a node, with some optional associated values:
     ///
     ///  - an identity name. That identity is used by the `NodeManager` to create secure channels
@@ -58,8 +105,8 @@ impl CliState {
         identity_name: &Option<String>,
         project_name: &Option<String>,
     ) -> Result<NodeInfo> {
-        let identity = match identity_name {
-            Some(name) => self.get_named_identity(name).await?,
+        let identity = match self.get_explicit_identity_name(identity_name)? {
+            Some(name) => self.get_named_identity(&name).await?,
             None => self.get_or_create_default_named_identity().await?,
         };
         let node = self
@@ -146,6 +193,7 @@ impl CliState {
     /// Remove a node:
     ///
     ///  - remove it from the repository
+    ///  - remove its events
     ///  - remove the node log files
     #[instrument(skip_all, fields(node_name = node_name))]
     pub async fn remove_node(&self, node_name: &str) -> Result<()> {
@@ -158,10 +206,13 @@ impl CliState {
         let repository = self.nodes_repository();
         let node_exists = repository.get_node(node_name).await.is_ok();
         repository.delete_node(node_name).await?;
+        self.node_events_repository()
+            .delete_events(node_name)
+            .await?;
         // set another node as the default node
         if node_exists {
             let other_nodes = repository.get_nodes().await?;
-            if let Some(other_node) = other_nodes.first() {
+            if let Some(other_node) = other_nodes.iter().find(|n| !n.is_ephemeral()) {
                 repository.set_default_node(&other_node.name()).await?;
             }
         }
@@ -172,6 +223,55 @@ impl CliState {
         Ok(())
     }
 
+    /// Rename a node:
+    ///
+    ///  - the node must exist and must be stopped
+    ///  - no other node must already use the new name
+    ///  - all the data stored for the node (project, credentials, policies, events, ...) is kept
+    ///  - the node directory, containing the node logs, is moved
+    #[instrument(skip_all, fields(node_name = node_name, new_node_name = new_node_name))]
+    pub async fn rename_node(&self, node_name: &str, new_node_name: &str) -> Result<NodeInfo> {
+        let node = self.get_node(node_name).await?;
+        if node_name == new_node_name {
+            return Ok(node);
+        }
+        if node.is_running() {
+            return Err(CliStateError::InvalidOperation(format!(
+                "The node {node_name} is running and cannot be renamed. Please stop it first with 'ockam node stop {node_name}'"
+            )));
+        }
+
+        let repository = self.nodes_repository();
+        if repository.get_node(new_node_name).await?.is_some() {
+            return Err(CliStateError::AlreadyExists {
+                resource: "node".to_string(),
+                name: new_node_name.to_string(),
+            });
+        }
+
+        // move the node directory first, so that nothing is changed if it can't be moved
+        let node_dir = self.node_dir(node_name);
+        let new_node_dir = self.node_dir(new_node_name);
+        if new_node_dir.exists() {
+            return Err(CliStateError::AlreadyExists {
+                resource: "node directory".to_string(),
+                name: format!("{new_node_dir:?}"),
+            });
+        }
+        if node_dir.exists() {
+            std::fs::rename(&node_dir, &new_node_dir)?;
+        }
+
+        if let Err(e) = repository.rename_node(node_name, new_node_name).await {
+            if new_node_dir.exists() {
+                let _ = std::fs::rename(&new_node_dir, &node_dir);
+            }
+            return Err(e)?;
+        }
+        debug!(name=%node_name, new_name=%new_node_name, "node renamed");
+        self.get_node(new_node_name).await
+    }
+
     /// Stop a background node
     ///
     ///  - if force is true, send a SIGKILL signal to the node process
@@ -187,6 +287,8 @@ impl CliState {
             if pid == process::id() {
                 return Ok(());
             }
+            // the exit of a process stopped on purpose is not reported as a crash
+            self.mark_node_as_stopped(node_name)?;
             nix::sys::signal::kill(
                 nix::unistd::Pid::from_raw(pid as i32),
                 if force {
@@ -217,6 +319,13 @@ impl CliState {
     /// Set a node as the default node
     #[instrument(skip_all, fields(node_name = node_name))]
     pub async fn set_default_node(&self, node_name: &str) -> Result<()> {
+        if self.get_node(node_name).await?.is_ephemeral() {
+            Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("The node {node_name} is ephemeral and cannot be the default node"),
+            ))?
+        }
         Ok(self.nodes_repository().set_default_node(node_name).await?)
     }
 
@@ -270,9 +379,20 @@ impl CliState {
     }
 
     /// Return all the created nodes
+    ///
+    /// Ephemeral nodes which are not running anymore were killed before they could remove
+    /// their state. They are removed instead of being returned.
     #[instrument(skip_all)]
     pub async fn get_nodes(&self) -> Result<Vec<NodeInfo>> {
-        Ok(self.nodes_repository().get_nodes().await?)
+        let mut nodes = vec![];
+        for node in self.nodes_repository().get_nodes().await? {
+            if node.is_ephemeral() && !node.is_running() {
+                self.remove_node(&node.name()).await?;
+            } else {
+                nodes.push(node);
+            }
+        }
+        Ok(nodes)
     }
 
     /// Return information about the default node (if there is one)
@@ -349,7 +469,11 @@ impl CliState {
     ) -> Result<NodeInfo> {
         let repository = self.nodes_repository();
         let is_default = repository.is_default_node(node_name).await?
-            || repository.get_nodes().await?.is_empty();
+            || repository
+                .get_nodes()
+                .await?
+                .iter()
+                .all(|n| n.is_ephemeral());
         let tcp_listener_address = repository.get_tcp_listener_address(node_name).await?;
         let node_info = NodeInfo::new(
             node_name.to_string(),
@@ -386,7 +510,7 @@ impl CliState {
     }
 
     /// Create a directory used to store files specific to a node
-    fn create_node_dir(&self, node_name: &str) -> Result<PathBuf> {
+    pub(super) fn create_node_dir(&self, node_name: &str) -> Result<PathBuf> {
         let path = self.node_dir(node_name);
         std::fs::create_dir_all(&path)?;
         Ok(path)
@@ -425,6 +549,7 @@ pub struct NodeInfo {
     is_authority: bool,
     tcp_listener_address: Option<InternetAddress>,
     pid: Option<u32>,
+    is_ephemeral: bool,
 }
 
 impl NodeInfo {
@@ -445,8 +570,10 @@ impl NodeInfo {
             is_authority,
             tcp_listener_address,
             pid,
+            is_ephemeral: false,
         }
     }
+
     pub fn name(&self) -> String {
         self.name.clone()
     }
@@ -474,6 +601,20 @@ impl NodeInfo {
         self.is_authority
     }
 
+    /// Return true if the node is only registered while its process is running
+    pub fn is_ephemeral(&self) -> bool {
+        self.is_ephemeral
+    }
+
+    /// Return a copy of this node with the is_ephemeral flag set to true.
+    /// An ephemeral node is never the default node
+    pub fn set_ephemeral(&self) -> Self {
+        let mut result = self.clone();
+        result.is_ephemeral = true;
+        result.is_default = false;
+        result
+    }
+
     pub fn tcp_listener_port(&self) -> Option<u16> {
         self.tcp_listener_address.as_ref().map(|t| t.port())
     }
@@ -542,6 +683,7 @@ impl NodeInfo {
 mod tests {
     use crate::cloud::project::models::ProjectModel;
     use crate::config::lookup::InternetAddress;
+    use ockam_abac::{Resource, ResourceType};
     use std::net::SocketAddr;
     use std::str::FromStr;
 
@@ -639,6 +781,52 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_rename_node() -> Result<()> {
+        let cli = CliState::test().await?;
+        let node_name = "node-1";
+        let node = cli.create_node(node_name).await?;
+        cli.nodes_repository()
+            .set_node_project_name(node_name, "project")
+            .await?;
+        let mut node_state = cli.clone();
+        node_state.set_node_name(node_name);
+        let resource = Resource::new("outlet", ResourceType::TcpOutlet);
+        node_state.store_resource(&resource).await?;
+        std::fs::create_dir_all(cli.node_dir(node_name))?;
+        std::fs::write(cli.node_dir(node_name).join("stdout"), "logs")?;
+
+        // a running node can't be renamed
+        assert!(cli.rename_node(node_name, "node-2").await.is_err());
+        cli.nodes_repository().set_no_node_pid(node_name).await?;
+
+        // the new name must not be used by another node
+        let _ = cli.create_node("other").await?;
+        assert!(cli.rename_node(node_name, "other").await.is_err());
+
+        // once renamed, the node keeps its identity, its data and its directory
+        let result = cli.rename_node(node_name, "node-2").await?;
+        assert_eq!(result.name(), "node-2");
+        assert_eq!(result.identifier(), node.identifier());
+        assert!(result.is_default());
+        assert!(cli.get_node(node_name).await.is_err());
+        assert_eq!(
+            cli.nodes_repository()
+                .get_node_project_name("node-2")
+                .await?,
+            Some("project".to_string())
+        );
+        node_state.set_node_name("node-2");
+        let result = node_state
+            .resources_repository()
+            .get_resource(&resource.resource_name)
+            .await?;
+        assert_eq!(result, Some(resource));
+        assert!(!cli.node_dir(node_name).exists());
+        assert!(cli.node_dir("node-2").join("stdout").exists());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_create_node_with_optional_values() -> Result<()> {
         let cli = CliState::test().await?;
@@ -693,4 +881,77 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_ephemeral_node_leaves_no_residue() -> Result<()> {
+        let cli = CliState::test().await?;
+        let default_node = cli.create_node("node-1").await?;
+
+        // start an ephemeral node and create some state for that node
+        let node_name = "ephemeral-node";
+        let node = cli
+            .start_ephemeral_node_with_optional_values(node_name, &None, &None, None)
+            .await?;
+        assert!(node.is_ephemeral());
+        assert!(!node.is_default());
+        assert!(cli.set_default_node(node_name).await.is_err());
+
+        let mut node_state = cli.clone();
+        node_state.set_node_name(node_name);
+        let resource = Resource::new("outlet", ResourceType::TcpOutlet);
+        node_state.store_resource(&resource).await?;
+        std::fs::create_dir_all(cli.node_dir(node_name))?;
+        std::fs::write(cli.node_dir(node_name).join("stdout"), "logs")?;
+
+        // the running ephemeral node is listed
+        let result = cli.get_nodes().await?;
+        assert_eq!(result.len(), 2);
+
+        // when the node stops, all its state is removed
+        cli.remove_node(node_name).await?;
+        assert_eq!(cli.get_nodes().await?, vec![default_node.clone()]);
+        assert!(!cli.node_dir(node_name).exists());
+        let result = node_state
+            .resources_repository()
+            .get_resource(&resource.resource_name)
+            .await?;
+        assert_eq!(result, None);
+
+        // an ephemeral node never becomes the default node
+        let _ = cli
+            .start_ephemeral_node_with_optional_values(node_name, &None, &None, None)
+            .await?;
+        cli.remove_node(&default_node.name()).await?;
+        assert!(cli.get_default_node().await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_killed_ephemeral_node_is_removed() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        // simulate an ephemeral node whose process was killed before it could remove its state
+        let node_name = "ephemeral-node";
+        let node = cli
+            .start_ephemeral_node_with_optional_values(node_name, &None, &None, None)
+            .await?;
+        let mut process = std::process::Command::new("true").spawn()?;
+        process.wait()?;
+        cli.nodes_repository()
+            .store_node(&node.set_pid(process.id()))
+            .await?;
+        std::fs::create_dir_all(cli.node_dir(node_name))?;
+
+        // the node is not listed anymore and its state is removed
+        assert!(cli.get_nodes().await?.is_empty());
+        assert!(cli.nodes_repository().get_node(node_name).await?.is_none());
+        assert!(!cli.node_dir(node_name).exists());
+
+        // and a new ephemeral node can be started with the same name
+        let node = cli
+            .start_ephemeral_node_with_optional_values(node_name, &None, &None, None)
+            .await?;
+        assert!(node.is_running());
+        Ok(())
+    }
 }
@@ -1,12 +1,22 @@
 use crate::cli_state::CliState;
-use ockam_abac::{Policies, ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase};
+use ockam_abac::{
+    ExpressionCache, Policies, ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase,
+};
 use std::sync::Arc;
 
 impl CliState {
     pub fn policies(&self) -> Policies {
+        // the resource and resource type policies often share the same expressions
+        let expressions = ExpressionCache::default();
         Policies::new(
-            Arc::new(ResourcePolicySqlxDatabase::new(self.database())),
-            Arc::new(ResourceTypePolicySqlxDatabase::new(self.database())),
+            Arc::new(
+                ResourcePolicySqlxDatabase::new(self.database())
+                    .with_expression_cache(expressions.clone()),
+            ),
+            Arc::new(
+                ResourceTypePolicySqlxDatabase::new(self.database())
+                    .with_expression_cache(expressions),
+            ),
         )
     }
 }
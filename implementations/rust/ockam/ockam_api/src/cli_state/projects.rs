@@ -49,40 +49,19 @@ impl Projects {
                 .await?;
         }
 
+        // The project is not marked as the default project here.
+        // A default project is either selected explicitly or it is the only project
         self.projects_repository
             .store_project(project.model())
             .await?;
 
-        // If there is no previous default project set this project as the default
-        let default_project = self.projects_repository.get_default_project().await?;
-        if default_project.is_none() {
-            self.projects_repository
-                .set_default_project(project.project_id())
-                .await?
-        };
-
         Ok(project)
     }
 
     #[instrument(skip_all, fields(project_id = project_id))]
     pub async fn delete_project(&self, project_id: &str) -> Result<()> {
-        // delete the project
-        let project_exists = self
-            .projects_repository
-            .get_project(project_id)
-            .await
-            .is_ok();
+        // another project is not selected as the default project when the default project is deleted
         self.projects_repository.delete_project(project_id).await?;
-
-        // set another project as the default project
-        if project_exists {
-            let other_projects = self.projects_repository.get_projects().await?;
-            if let Some(other_project) = other_projects.first() {
-                self.projects_repository
-                    .set_default_project(&other_project.id)
-                    .await?;
-            }
-        }
         Ok(())
     }
 
@@ -94,15 +73,47 @@ impl Projects {
         Ok(())
     }
 
+    /// Select the project used by default by the commands, see `ockam project use`
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn use_project(&self, name: &str) -> Result<Project> {
+        let project = self.get_project_by_name(name).await?;
+        self.set_default_project(project.project_id()).await?;
+        Ok(project)
+    }
+
+    /// Return the default project, which is:
+    ///
+    ///  - the project selected with `ockam project use`
+    ///  - otherwise the only existing project
+    ///
+    /// When several projects exist and none of them is selected, an error listing the projects
+    /// is returned, instead of picking one of them arbitrarily
     #[instrument(skip_all)]
     pub async fn get_default_project(&self) -> Result<Project> {
-        match self.projects_repository.get_default_project().await? {
-            Some(project) => Ok(Project::import(project).await?),
-            None => Err(Error::new(
+        if let Some(project) = self.projects_repository.get_default_project().await? {
+            return Ok(Project::import(project).await?);
+        }
+
+        let mut projects = self.projects_repository.get_projects().await?;
+        match projects.len() {
+            0 => Err(Error::new(
                 Origin::Api,
                 Kind::NotFound,
                 "there is no default project",
             ))?,
+            1 => Ok(Project::import(projects.remove(0)).await?),
+            _ => {
+                let mut names: Vec<String> = projects.into_iter().map(|p| p.name).collect();
+                names.sort();
+                Err(Error::new(
+                    Origin::Api,
+                    Kind::Conflict,
+                    format!(
+                        "there is no default project and several projects exist: {}. Please select the default project with `ockam project use <name>`",
+                        names.join(", ")
+                    ),
+                ))?
+            }
         }
     }
 
@@ -174,3 +185,73 @@ impl CliState {
         Projects::new(self.projects_repository(), identities_verification)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_project_is_the_default() -> Result<()> {
+        let cli = CliState::test().await?;
+        assert!(cli.projects().get_default_project().await.is_err());
+
+        store_project(&cli, "1", "p1").await?;
+        let result = cli.projects().get_default_project().await?;
+        assert_eq!(result.name(), "p1");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_several_projects_without_default() -> Result<()> {
+        let cli = CliState::test().await?;
+        store_project(&cli, "1", "p1").await?;
+        store_project(&cli, "2", "p2").await?;
+
+        // no project is picked arbitrarily
+        let error = cli
+            .projects()
+            .get_default_project()
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("p1, p2"), "{error}");
+        assert!(error.contains("ockam project use"), "{error}");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_use_project() -> Result<()> {
+        let cli = CliState::test().await?;
+        store_project(&cli, "1", "p1").await?;
+        store_project(&cli, "2", "p2").await?;
+
+        cli.projects().use_project("p2").await?;
+        let result = cli.projects().get_default_project().await?;
+        assert_eq!(result.name(), "p2");
+
+        // the selection is kept when the project is updated
+        store_project(&cli, "2", "p2").await?;
+        let result = cli.projects().get_default_project().await?;
+        assert_eq!(result.name(), "p2");
+
+        // an unknown project can't be selected
+        assert!(cli.projects().use_project("p3").await.is_err());
+
+        // when the default project is deleted, the remaining project is the default
+        cli.projects().delete_project("2").await?;
+        let result = cli.projects().get_default_project().await?;
+        assert_eq!(result.name(), "p1");
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn store_project(cli: &CliState, id: &str, name: &str) -> Result<Project> {
+        cli.projects()
+            .import_and_store_project(ProjectModel {
+                id: id.to_string(),
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .await
+    }
+}
@@ -42,6 +42,10 @@ impl CliState {
         Arc::new(NodesSqlxDatabase::new(self.database()))
     }
 
+    pub fn node_events_repository(&self) -> Arc<dyn NodeEventsRepository> {
+        Arc::new(NodeEventsSqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn projects_repository(&self) -> Arc<dyn ProjectsRepository> {
         Arc::new(ProjectsSqlxDatabase::new(self.database()))
     }
@@ -50,6 +54,10 @@ impl CliState {
         Arc::new(ResourcesSqlxDatabase::new(self.database()))
     }
 
+    pub(super) fn route_aliases_repository(&self) -> Arc<dyn RouteAliasesRepository> {
+        Arc::new(RouteAliasesSqlxDatabase::new(self.database()))
+    }
+
     pub(super) fn spaces_repository(&self) -> Arc<dyn SpacesRepository> {
         Arc::new(SpacesSqlxDatabase::new(self.database()))
     }
@@ -0,0 +1,123 @@
+use serde::Serialize;
+
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// Route aliases give a name to a route, so that the command line can accept `@<name>`
+/// wherever a route is expected.
+///
+/// The route of an alias is stored as given by the user. It is only resolved when the alias is
+/// used, since it can reference other aliases, which can be changed independently.
+impl CliState {
+    /// Create a route alias, or replace the route of an existing alias
+    #[instrument(skip_all, fields(name = name, route = route))]
+    pub async fn set_route_alias(&self, name: &str, route: &str) -> Result<RouteAlias> {
+        if !RouteAlias::is_valid_name(name) {
+            return Err(CliStateError::InvalidData(format!(
+                "The route alias name {name} is invalid. It can only contain letters, digits, '-', '_' and '.'"
+            )));
+        }
+        if route.trim().is_empty() {
+            return Err(CliStateError::InvalidData(format!(
+                "The route of the alias {name} is empty"
+            )));
+        }
+        let route_alias = RouteAlias {
+            name: name.to_string(),
+            route: route.trim().to_string(),
+            created_at: now()?,
+        };
+        self.route_aliases_repository()
+            .store_route_alias(&route_alias)
+            .await?;
+        Ok(route_alias)
+    }
+
+    /// Return the route alias with the given name
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn get_route_alias(&self, name: &str) -> Result<RouteAlias> {
+        self.route_aliases_repository()
+            .get_route_alias(name)
+            .await?
+            .ok_or_else(|| CliStateError::ResourceNotFound {
+                resource: "route alias".to_string(),
+                name: name.to_string(),
+            })
+    }
+
+    /// Return all the route aliases, sorted by name
+    #[instrument(skip_all)]
+    pub async fn get_route_aliases(&self) -> Result<Vec<RouteAlias>> {
+        Ok(self.route_aliases_repository().get_route_aliases().await?)
+    }
+
+    /// Delete a route alias. The aliases referencing it are kept, and fail to resolve
+    /// until the alias is created again
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn delete_route_alias(&self, name: &str) -> Result<()> {
+        let route_alias = self.get_route_alias(name).await?;
+        Ok(self
+            .route_aliases_repository()
+            .delete_route_alias(&route_alias.name)
+            .await?)
+    }
+}
+
+/// Named route
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct RouteAlias {
+    pub name: String,
+    /// Route, which can reference other aliases as `@<name>`
+    pub route: String,
+    pub created_at: TimestampInSeconds,
+}
+
+impl RouteAlias {
+    /// Return true if the name can be used in a route as `@<name>`
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_route_aliases() -> Result<()> {
+        let cli = CliState::test().await?;
+
+        cli.set_route_alias("prod", "/node/n1/secure/api").await?;
+        cli.set_route_alias("prod-db", "@prod/service/outlet")
+            .await?;
+        let names: Vec<String> = cli
+            .get_route_aliases()
+            .await?
+            .into_iter()
+            .map(|a| a.name)
+            .collect();
+        assert_eq!(names, vec!["prod", "prod-db"]);
+
+        // an alias can be replaced
+        cli.set_route_alias("prod", "/node/n2/secure/api").await?;
+        assert_eq!(
+            cli.get_route_alias("prod").await?.route,
+            "/node/n2/secure/api"
+        );
+
+        // invalid names and empty routes are rejected
+        assert!(cli.set_route_alias("prod/db", "/node/n1").await.is_err());
+        assert!(cli.set_route_alias("@db", "/node/n1").await.is_err());
+        assert!(cli.set_route_alias("db", " ").await.is_err());
+
+        cli.delete_route_alias("prod").await?;
+        assert!(cli.get_route_alias("prod").await.is_err());
+        assert!(cli.delete_route_alias("prod").await.is_err());
+        Ok(())
+    }
+}
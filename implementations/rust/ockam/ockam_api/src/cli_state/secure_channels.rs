@@ -8,7 +8,8 @@ use crate::cli_state::Result;
 impl CliState {
     pub async fn secure_channels(&self, node_name: &str) -> Result<Arc<SecureChannels>> {
         debug!("create the secure channels service");
-        let vault = self.get_node_vault(node_name).await?.vault().await?;
+        let named_vault = self.get_node_vault(node_name).await?;
+        let vault = self.make_vault(&named_vault).await?;
         let identities = Identities::create(self.database())
             .with_vault(vault)
             .build();
@@ -21,26 +21,47 @@ impl CliState {
             users: users.iter().map(|u| u.to_string()).collect(),
         };
 
+        // The space is not marked as the default space here.
+        // A default space is either selected explicitly or it is the only space
         repository.store_space(&space).await?;
 
-        // If there is no previous default space set this space as the default
-        let default_space = repository.get_default_space().await?;
-        if default_space.is_none() {
-            repository.set_default_space(&space.id).await?
-        };
-
         Ok(space)
     }
 
+    /// Return the default space, which is:
+    ///
+    ///  - the space selected with `ockam space use`
+    ///  - otherwise the only existing space
+    ///
+    /// When several spaces exist and none of them is selected, an error listing the spaces
+    /// is returned, instead of picking one of them arbitrarily
     #[instrument(skip_all)]
     pub async fn get_default_space(&self) -> Result<Space> {
-        match self.spaces_repository().get_default_space().await? {
-            Some(space) => Ok(space),
-            None => Err(Error::new(
+        let repository = self.spaces_repository();
+        if let Some(space) = repository.get_default_space().await? {
+            return Ok(space);
+        }
+
+        let mut spaces = repository.get_spaces().await?;
+        match spaces.len() {
+            0 => Err(Error::new(
                 Origin::Api,
                 Kind::NotFound,
                 "there is no default space",
             ))?,
+            1 => Ok(spaces.remove(0)),
+            _ => {
+                let mut names: Vec<String> = spaces.into_iter().map(|s| s.name).collect();
+                names.sort();
+                Err(Error::new(
+                    Origin::Api,
+                    Kind::Conflict,
+                    format!(
+                        "there is no default space and several spaces exist: {}. Please select the default space with `ockam space use <name>`",
+                        names.join(", ")
+                    ),
+                ))?
+            }
         }
     }
 
@@ -63,27 +84,22 @@ impl CliState {
 
     #[instrument(skip_all, fields(space_id = space_id))]
     pub async fn delete_space(&self, space_id: &str) -> Result<()> {
-        let repository = self.spaces_repository();
-        // delete the space
-        let space_exists = repository.get_space(space_id).await.is_ok();
-        repository.delete_space(space_id).await?;
-
-        // set another space as the default space
-        if space_exists {
-            let other_space = repository.get_spaces().await?;
-            if let Some(other_space) = other_space.first() {
-                repository
-                    .set_default_space(&other_space.space_id())
-                    .await?;
-            }
-        }
-        Ok(())
+        // another space is not selected as the default space when the default space is deleted
+        Ok(self.spaces_repository().delete_space(space_id).await?)
     }
 
     #[instrument(skip_all, fields(space_id = space_id))]
     pub async fn set_space_as_default(&self, space_id: &str) -> Result<()> {
         Ok(self.spaces_repository().set_default_space(space_id).await?)
     }
+
+    /// Select the space used by default by the commands, see `ockam space use`
+    #[instrument(skip_all, fields(name = name))]
+    pub async fn use_space(&self, name: &str) -> Result<Space> {
+        let space = self.get_space_by_name(name).await?;
+        self.set_space_as_default(&space.id).await?;
+        Ok(space)
+    }
 }
 
 #[cfg(test)]
@@ -108,4 +124,24 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_use_space() -> Result<()> {
+        let cli = CliState::test().await?;
+        cli.store_space("1", "name1", vec![]).await?;
+        let space2 = cli.store_space("2", "name2", vec![]).await?;
+
+        // no space is picked arbitrarily
+        let error = cli.get_default_space().await.unwrap_err().to_string();
+        assert!(error.contains("name1, name2"), "{error}");
+        assert!(error.contains("ockam space use"), "{error}");
+
+        // a space can be selected explicitly
+        cli.use_space("name2").await?;
+        let result = cli.get_default_space().await?;
+        assert_eq!(result, space2);
+        assert!(cli.use_space("name3").await.is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,135 @@
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fs2::FileExt;
+
+use ockam_core::env::get_env_with_default;
+
+use crate::cli_state::{CliState, CliStateError, Result};
+
+/// Name of the environment variable used to set the maximum duration a command waits for the
+/// state lock, for example "10s" or "500ms"
+pub const OCKAM_STATE_LOCK_TIMEOUT: &str = "OCKAM_STATE_LOCK_TIMEOUT";
+
+/// Default maximum duration a command waits for the state lock
+pub const DEFAULT_STATE_LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between two attempts to acquire the state lock
+const STATE_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Name of the file used to lock the state directory
+const STATE_LOCK_FILE_NAME: &str = "state.lock";
+
+/// Exclusive lock on the CLI state.
+///
+/// This is an advisory lock: it only coordinates the processes which try to acquire it.
+/// The commands modifying the state hold it for their whole duration so that the modifications
+/// made by two concurrent commands are not interleaved. Read-only commands don't take it.
+///
+/// The lock is released when this value is dropped, or when the process exits.
+#[derive(Debug)]
+pub struct CliStateLock {
+    file: File,
+}
+
+impl Drop for CliStateLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl CliState {
+    /// Acquire the exclusive state lock, waiting at most for the duration set with the
+    /// OCKAM_STATE_LOCK_TIMEOUT environment variable
+    pub fn lock_for_write(&self) -> Result<CliStateLock> {
+        let timeout = get_env_with_default(OCKAM_STATE_LOCK_TIMEOUT, DEFAULT_STATE_LOCK_TIMEOUT)?;
+        self.lock_for_write_with_timeout(timeout)
+    }
+
+    /// Acquire the exclusive state lock, waiting at most for the given duration.
+    ///
+    /// Return a StateLocked error if another process still holds the lock after that duration.
+    pub fn lock_for_write_with_timeout(&self, timeout: Duration) -> Result<CliStateLock> {
+        let path = self.state_lock_path();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let start = Instant::now();
+        loop {
+            match file.try_lock_exclusive() {
+                Ok(()) => {
+                    debug!(path = %path.display(), "acquired the state lock");
+                    return Ok(CliStateLock { file });
+                }
+                Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                    if start.elapsed() >= timeout {
+                        return Err(CliStateError::StateLocked {
+                            timeout: format!("{timeout:?}"),
+                        });
+                    }
+                    thread::sleep(STATE_LOCK_RETRY_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn state_lock_path(&self) -> PathBuf {
+        self.dir().join(STATE_LOCK_FILE_NAME)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[tokio::test]
+    async fn test_concurrent_state_modifications_are_serialized() -> Result<()> {
+        let cli = CliState::test().await?;
+        let counter = cli.dir().join("counter");
+        fs::write(&counter, "0")?;
+
+        // each thread reads the counter, waits a bit and writes it back incremented,
+        // which would lose some increments without the lock
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let cli = cli.clone();
+                let counter = counter.clone();
+                thread::spawn(move || -> Result<()> {
+                    let _lock = cli.lock_for_write_with_timeout(Duration::from_secs(10))?;
+                    let value: u32 = fs::read_to_string(&counter)?.parse().unwrap();
+                    thread::sleep(Duration::from_millis(20));
+                    fs::write(&counter, (value + 1).to_string())?;
+                    Ok(())
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap()?;
+        }
+
+        assert_eq!(fs::read_to_string(&counter)?, "8");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lock_timeout() -> Result<()> {
+        let cli = CliState::test().await?;
+        let lock = cli.lock_for_write_with_timeout(Duration::from_secs(1))?;
+
+        let result = cli.lock_for_write_with_timeout(Duration::from_millis(100));
+        assert!(matches!(result, Err(CliStateError::StateLocked { .. })));
+
+        // the lock can be acquired again once it is released
+        drop(lock);
+        let _lock = cli.lock_for_write_with_timeout(Duration::from_millis(100))?;
+        Ok(())
+    }
+}
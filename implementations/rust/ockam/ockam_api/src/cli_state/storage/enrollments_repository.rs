@@ -2,7 +2,7 @@ use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::cli_state::enrollments::IdentityEnrollment;
+use crate::cli_state::enrollments::{EnrollmentAttribute, IdentityEnrollment};
 
 /// This trait stores the enrollment status for local identities
 /// If an identity has been enrolled it is possible to retrieve:
@@ -28,4 +28,18 @@ pub trait EnrollmentsRepository: Send + Sync + 'static {
 
     /// Return true if the identity with the given name is enrolled
     async fn is_identity_enrolled(&self, name: &str) -> Result<bool>;
+
+    /// Replace the attributes granted to an identity when it enrolled with a project
+    async fn store_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+        project_id: &str,
+        attributes: &[EnrollmentAttribute],
+    ) -> Result<()>;
+
+    /// Get the attributes granted to an identity for all the projects it enrolled with
+    async fn get_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<EnrollmentAttribute>>;
 }
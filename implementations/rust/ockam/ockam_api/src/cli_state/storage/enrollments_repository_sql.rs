@@ -10,7 +10,9 @@ use ockam::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::cli_state::enrollments::IdentityEnrollment;
+use crate::cli_state::enrollments::{
+    EnrollmentAttribute, EnrollmentAttributeSource, IdentityEnrollment,
+};
 use crate::cli_state::EnrollmentsRepository;
 
 #[derive(Clone)]
@@ -124,6 +126,53 @@ impl EnrollmentsRepository for EnrollmentsSqlxDatabase {
             .into_core()?;
         Ok(result.map(|_| true).unwrap_or(false))
     }
+
+    async fn store_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+        project_id: &str,
+        attributes: &[EnrollmentAttribute],
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let query1 = query(
+            "DELETE FROM identity_enrollment_attribute WHERE identifier = ? AND project_id = ?",
+        )
+        .bind(identifier.to_sql())
+        .bind(project_id.to_sql());
+        query1.execute(&mut *transaction).await.void()?;
+
+        for attribute in attributes {
+            let query2 = query("INSERT INTO identity_enrollment_attribute VALUES (?, ?, ?, ?, ?)")
+                .bind(identifier.to_sql())
+                .bind(project_id.to_sql())
+                .bind(attribute.name.to_sql())
+                .bind(attribute.value.to_sql())
+                .bind(attribute.source.to_string().to_sql());
+            query2.execute(&mut *transaction).await.void()?;
+        }
+        transaction.commit().await.void()
+    }
+
+    async fn get_enrollment_attributes(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<Vec<EnrollmentAttribute>> {
+        let query = query_as(
+            r#"
+            SELECT project_id, attribute_name, attribute_value, source
+            FROM identity_enrollment_attribute
+            WHERE identifier = ?
+            ORDER BY project_id, attribute_name
+            "#,
+        )
+        .bind(identifier.to_sql());
+        let result: Vec<EnrollmentAttributeRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        result
+            .into_iter()
+            .map(|r| r.enrollment_attribute())
+            .collect::<Result<Vec<_>>>()
+    }
 }
 
 #[derive(FromRow)]
@@ -151,6 +200,25 @@ impl EnrollmentRow {
     }
 }
 
+#[derive(FromRow)]
+pub struct EnrollmentAttributeRow {
+    project_id: String,
+    attribute_name: String,
+    attribute_value: String,
+    source: String,
+}
+
+impl EnrollmentAttributeRow {
+    fn enrollment_attribute(self) -> Result<EnrollmentAttribute> {
+        Ok(EnrollmentAttribute {
+            project_id: self.project_id,
+            name: self.attribute_name,
+            value: self.attribute_value,
+            source: EnrollmentAttributeSource::from_str(&self.source)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::cli_state::{EnrollmentsRepository, IdentitiesRepository, IdentitiesSqlxDatabase};
@@ -193,6 +261,47 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_enrollment_attributes() -> Result<()> {
+        let db = create_database().await?;
+        let repository = create_repository(db.clone());
+        let identity = create_identity(db.clone(), "identity1").await?;
+        let attribute = |project_id: &str, name: &str, value: &str| EnrollmentAttribute {
+            project_id: project_id.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+            source: EnrollmentAttributeSource::Credential,
+        };
+
+        // attributes can be stored for different projects
+        let project1 = vec![
+            attribute("p1", "role", "ops"),
+            attribute("p1", "team", "payments"),
+        ];
+        let project2 = vec![attribute("p2", "role", "dev")];
+        repository
+            .store_enrollment_attributes(identity.identifier(), "p1", &project1)
+            .await?;
+        repository
+            .store_enrollment_attributes(identity.identifier(), "p2", &project2)
+            .await?;
+        let result = repository
+            .get_enrollment_attributes(identity.identifier())
+            .await?;
+        assert_eq!(result, [project1, project2.clone()].concat());
+
+        // enrolling again with a project replaces its attributes
+        let project1 = vec![attribute("p1", "role", "admin")];
+        repository
+            .store_enrollment_attributes(identity.identifier(), "p1", &project1)
+            .await?;
+        let result = repository
+            .get_enrollment_attributes(identity.identifier())
+            .await?;
+        assert_eq!(result, [project1, project2].concat());
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_identity(db: SqlxDatabase, name: &str) -> Result<Identity> {
         let identities = identities().await?;
@@ -26,6 +26,9 @@ pub trait IdentitiesRepository: Send + Sync + 'static {
         vault_name: &str,
     ) -> Result<NamedIdentity>;
 
+    /// Change the name of an identity. Its identifier is unchanged
+    async fn rename_identity(&self, name: &str, new_name: &str) -> Result<()>;
+
     /// Delete an identity given its name and return its identifier
     async fn delete_identity(&self, name: &str) -> Result<Option<Identifier>>;
 
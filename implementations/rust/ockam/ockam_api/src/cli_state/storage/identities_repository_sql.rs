@@ -63,6 +63,13 @@ impl IdentitiesRepository for IdentitiesSqlxDatabase {
         ))
     }
 
+    async fn rename_identity(&self, name: &str, new_name: &str) -> Result<()> {
+        let query = query("UPDATE named_identity SET name = ? WHERE name = ?")
+            .bind(new_name.to_sql())
+            .bind(name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
     async fn delete_identity(&self, name: &str) -> Result<Option<Identifier>> {
         let mut transaction = self.database.begin().await.into_core()?;
 
@@ -4,10 +4,14 @@ pub use identities_repository::*;
 pub use identities_repository_sql::*;
 pub use journeys_repository::*;
 pub use journeys_repository_sql::*;
+pub use node_events_repository::*;
+pub use node_events_repository_sql::*;
 pub use nodes_repository::*;
 pub use nodes_repository_sql::*;
 pub use projects_repository::*;
 pub use projects_repository_sql::*;
+pub use route_aliases_repository::*;
+pub use route_aliases_repository_sql::*;
 pub use spaces_repository::*;
 pub use spaces_repository_sql::*;
 pub use users_repository::*;
@@ -21,10 +25,14 @@ mod identities_repository;
 mod identities_repository_sql;
 mod journeys_repository;
 mod journeys_repository_sql;
+mod node_events_repository;
+mod node_events_repository_sql;
 mod nodes_repository;
 mod nodes_repository_sql;
 mod projects_repository;
 mod projects_repository_sql;
+mod route_aliases_repository;
+mod route_aliases_repository_sql;
 mod spaces_repository;
 mod spaces_repository_sql;
 mod users_repository;
@@ -0,0 +1,25 @@
+use ockam::identity::TimestampInSeconds;
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::{NodeEvent, NodeEventKind};
+
+use crate::nodes::models::node_events::NodeEventRecord;
+
+/// This repository stores the events emitted by the components of a node
+#[async_trait]
+pub trait NodeEventsRepository: Send + Sync + 'static {
+    /// Store a new event. The oldest events are deleted when the maximum number of events is reached
+    async fn store_event(&self, node_name: &str, event: &NodeEvent) -> Result<()>;
+
+    /// Return the events of a node created at, or after, the given timestamp, oldest first.
+    /// If a kind is given, only the events of that kind are returned
+    async fn get_events(
+        &self,
+        node_name: &str,
+        since: Option<TimestampInSeconds>,
+        kind: Option<NodeEventKind>,
+    ) -> Result<Vec<NodeEventRecord>>;
+
+    /// Delete all the events of a node
+    async fn delete_events(&self, node_name: &str) -> Result<()>;
+}
@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::TimestampInSeconds;
+use ockam_core::async_trait;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+use ockam_node::{NodeEvent, NodeEventKind};
+
+use crate::cli_state::NodeEventsRepository;
+use crate::nodes::models::node_events::NodeEventRecord;
+
+/// Maximum number of events kept in the database
+pub const DEFAULT_MAX_NODE_EVENTS: u64 = 10_000;
+
+/// Implementation of [`NodeEventsRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct NodeEventsSqlxDatabase {
+    database: SqlxDatabase,
+    max_events: u64,
+}
+
+impl NodeEventsSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for node events");
+        Self {
+            database,
+            max_events: DEFAULT_MAX_NODE_EVENTS,
+        }
+    }
+
+    /// Set the maximum number of events kept in the database
+    pub fn with_max_events(mut self, max_events: u64) -> Self {
+        self.max_events = max_events;
+        self
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(SqlxDatabase::in_memory("node events").await?))
+    }
+}
+
+#[async_trait]
+impl NodeEventsRepository for NodeEventsSqlxDatabase {
+    async fn store_event(&self, node_name: &str, event: &NodeEvent) -> Result<()> {
+        let details = serde_json::to_string(event.details())
+            .map_err(|e| Error::new(Origin::Api, Kind::Serialization, e))?;
+        let mut transaction = self.database.pool.begin().await.into_core()?;
+
+        let query1 = query(
+            "INSERT INTO node_events (node_name, kind, subject, details, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .bind(node_name.to_sql())
+        .bind(event.kind().to_string().to_sql())
+        .bind(event.subject().to_sql())
+        .bind(details.to_sql())
+        .bind(TimestampInSeconds(event.timestamp()).to_sql());
+        query1.execute(&mut *transaction).await.void()?;
+
+        // Only keep the most recent events
+        let query2 =
+            query("DELETE FROM node_events WHERE id <= (SELECT MAX(id) FROM node_events) - ?")
+                .bind(self.max_events as i64);
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_events(
+        &self,
+        node_name: &str,
+        since: Option<TimestampInSeconds>,
+        kind: Option<NodeEventKind>,
+    ) -> Result<Vec<NodeEventRecord>> {
+        let since = since.unwrap_or(TimestampInSeconds(0));
+        let query = query_as("SELECT kind, subject, details, created_at FROM node_events WHERE node_name = ? AND created_at >= ? AND (? IS NULL OR kind = ?) ORDER BY id")
+            .bind(node_name.to_sql())
+            .bind(since.to_sql())
+            .bind(kind.map(|k| k.to_string().to_sql()))
+            .bind(kind.map(|k| k.to_string().to_sql()));
+        let rows: Vec<NodeEventRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
+    async fn delete_events(&self, node_name: &str) -> Result<()> {
+        let query = query("DELETE FROM node_events WHERE node_name = ?").bind(node_name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct NodeEventRow {
+    kind: String,
+    subject: String,
+    details: String,
+    created_at: i64,
+}
+
+impl TryFrom<NodeEventRow> for NodeEventRecord {
+    type Error = Error;
+
+    fn try_from(value: NodeEventRow) -> Result<Self, Self::Error> {
+        let details: BTreeMap<String, String> = serde_json::from_str(&value.details)
+            .map_err(|e| Error::new(Origin::Api, Kind::Serialization, e))?;
+        Ok(NodeEventRecord {
+            kind: value.kind,
+            subject: value.subject,
+            details,
+            created_at: TimestampInSeconds(value.created_at as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::sync::Arc;
+
+    #[tokio::test]
+    async fn test_node_events_repository() -> Result<()> {
+        let repository = create_repository(10).await?;
+
+        let event1 = NodeEvent::new(NodeEventKind::ListenerBound, "127.0.0.1:4000")
+            .with_detail("transport", "tcp");
+        let event2 = NodeEvent::new(NodeEventKind::PolicyDenied, "outlet")
+            .with_detail("action", "handle_message");
+        repository.store_event("node1", &event1).await?;
+        repository.store_event("node1", &event2).await?;
+        repository.store_event("node2", &event2).await?;
+
+        let events = repository.get_events("node1", None, None).await?;
+        assert_eq!(
+            events,
+            vec![event1.clone().into(), NodeEventRecord::from(event2.clone())]
+        );
+
+        let events = repository
+            .get_events("node1", None, Some(NodeEventKind::PolicyDenied))
+            .await?;
+        assert_eq!(events, vec![NodeEventRecord::from(event2.clone())]);
+
+        let since = TimestampInSeconds(event1.timestamp() + 3600);
+        let events = repository.get_events("node1", Some(since), None).await?;
+        assert!(events.is_empty());
+
+        repository.delete_events("node1").await?;
+        assert!(repository.get_events("node1", None, None).await?.is_empty());
+        assert_eq!(repository.get_events("node2", None, None).await?.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_events_repository_is_bounded() -> Result<()> {
+        let repository = create_repository(3).await?;
+
+        for i in 0..5 {
+            let event = NodeEvent::new(NodeEventKind::RelayUp, format!("relay-{i}"));
+            repository.store_event("node", &event).await?;
+        }
+
+        let events = repository.get_events("node", None, None).await?;
+        let subjects: Vec<String> = events.into_iter().map(|e| e.subject).collect();
+        assert_eq!(subjects, vec!["relay-2", "relay-3", "relay-4"]);
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository(max_events: u64) -> Result<Arc<dyn NodeEventsRepository>> {
+        Ok(Arc::new(
+            NodeEventsSqlxDatabase::create()
+                .await?
+                .with_max_events(max_events),
+        ))
+    }
+}
@@ -2,7 +2,7 @@ use ockam::identity::Identifier;
 use ockam_core::async_trait;
 use ockam_core::Result;
 
-use crate::cli_state::NodeInfo;
+use crate::cli_state::{NodeBinaryVersion, NodeInfo};
 use crate::config::lookup::InternetAddress;
 
 /// This trait supports the storage of node data:
@@ -41,6 +41,9 @@ pub trait NodesRepository: Send + Sync + 'static {
     /// Delete a node given its name
     async fn delete_node(&self, node_name: &str) -> Result<()>;
 
+    /// Rename a node, together with all the data stored for that node
+    async fn rename_node(&self, node_name: &str, new_node_name: &str) -> Result<()>;
+
     /// Set the TCP listener of a node
     async fn set_tcp_listener_address(
         &self,
@@ -65,4 +68,20 @@ pub trait NodesRepository: Send + Sync + 'static {
 
     /// Return the name of the project associated to a node
     async fn get_node_project_name(&self, node_name: &str) -> Result<Option<String>>;
+
+    /// Set if the pending database migrations can be applied automatically for a node
+    async fn set_auto_migrate(&self, node_name: &str, auto_migrate: bool) -> Result<()>;
+
+    /// Return true if the pending database migrations can be applied automatically for a node
+    async fn get_auto_migrate(&self, node_name: &str) -> Result<bool>;
+
+    /// Set the version of the binary which created, or last started, a node
+    async fn set_node_binary_version(
+        &self,
+        node_name: &str,
+        version: &NodeBinaryVersion,
+    ) -> Result<()>;
+
+    /// Return the version of the binary which created, or last started, a node
+    async fn get_node_binary_version(&self, node_name: &str) -> Result<Option<NodeBinaryVersion>>;
 }
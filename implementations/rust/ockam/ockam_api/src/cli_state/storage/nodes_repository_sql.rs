@@ -3,13 +3,14 @@ use std::str::FromStr;
 use sqlx::sqlite::SqliteRow;
 use sqlx::*;
 
+use ockam::identity::utils::now;
 use ockam::identity::Identifier;
 use ockam::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
 use ockam_core::async_trait;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Result;
 
-use crate::cli_state::NodesRepository;
+use crate::cli_state::{NodeBinaryVersion, NodesRepository};
 use crate::config::lookup::InternetAddress;
 use crate::NodeInfo;
 
@@ -33,7 +34,13 @@ impl NodesSqlxDatabase {
 #[async_trait]
 impl NodesRepository for NodesSqlxDatabase {
     async fn store_node(&self, node_info: &NodeInfo) -> Result<()> {
-        let query = query("INSERT OR REPLACE INTO node VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")
+        // the auto_migrate column is not part of the node info and is kept when a node is updated
+        let query = query(
+            "INSERT INTO node (name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid, is_ephemeral)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (name)
+             DO UPDATE SET identifier = ?2, verbosity = ?3, is_default = ?4, is_authority = ?5, tcp_listener_address = ?6, pid = ?7, is_ephemeral = ?8",
+        )
             .bind(node_info.name().to_sql())
             .bind(node_info.identifier().to_sql())
             .bind(node_info.verbosity().to_sql())
@@ -45,18 +52,19 @@ impl NodesRepository for NodesSqlxDatabase {
                     .as_ref()
                     .map(|a| a.to_string().to_sql()),
             )
-            .bind(node_info.pid().map(|p| p.to_sql()));
+            .bind(node_info.pid().map(|p| p.to_sql()))
+            .bind(node_info.is_ephemeral().to_sql());
         Ok(query.execute(&*self.database.pool).await.void()?)
     }
 
     async fn get_nodes(&self) -> Result<Vec<NodeInfo>> {
-        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid FROM node");
+        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid, is_ephemeral FROM node");
         let rows: Vec<NodeRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.node_info()).collect()
     }
 
     async fn get_node(&self, node_name: &str) -> Result<Option<NodeInfo>> {
-        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid FROM node WHERE name = ?").bind(node_name.to_sql());
+        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid, is_ephemeral FROM node WHERE name = ?").bind(node_name.to_sql());
         let row: Option<NodeRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -65,13 +73,13 @@ impl NodesRepository for NodesSqlxDatabase {
     }
 
     async fn get_nodes_by_identifier(&self, identifier: &Identifier) -> Result<Vec<NodeInfo>> {
-        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid FROM node WHERE identifier = ?").bind(identifier.to_sql());
+        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid, is_ephemeral FROM node WHERE identifier = ?").bind(identifier.to_sql());
         let rows: Vec<NodeRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.node_info()).collect()
     }
 
     async fn get_default_node(&self) -> Result<Option<NodeInfo>> {
-        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid FROM node WHERE is_default = ?").bind(true.to_sql());
+        let query = query_as("SELECT name, identifier, verbosity, is_default, is_authority, tcp_listener_address, pid, is_ephemeral FROM node WHERE is_default = ?").bind(true.to_sql());
         let row: Option<NodeRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -129,10 +137,49 @@ impl NodesRepository for NodesSqlxDatabase {
             .bind(node_name.to_sql());
         query.execute(&mut *transaction).await.void()?;
 
+        let query =
+            sqlx::query("DELETE FROM revocation_list WHERE node_name=?").bind(node_name.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
         let query =
             sqlx::query("DELETE FROM node_project WHERE node_name=?").bind(node_name.to_sql());
         query.execute(&mut *transaction).await.void()?;
 
+        let query =
+            sqlx::query("DELETE FROM node_metadata WHERE node_name=?").bind(node_name.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn rename_node(&self, node_name: &str, new_node_name: &str) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        let query = query("UPDATE node SET name = ? WHERE name = ?")
+            .bind(new_node_name.to_sql())
+            .bind(node_name.to_sql());
+        query.execute(&mut *transaction).await.void()?;
+
+        // the node events are renamed as well, so that the history of the node is kept
+        for table in [
+            "credential",
+            "resource",
+            "resource_policy",
+            "resource_type_policy",
+            "identity_attributes",
+            "revocation_list",
+            "node_project",
+            "node_events",
+            "node_metadata",
+        ] {
+            let query = sqlx::query(&format!(
+                "UPDATE {table} SET node_name = ? WHERE node_name = ?"
+            ))
+            .bind(new_node_name.to_sql())
+            .bind(node_name.to_sql());
+            query.execute(&mut *transaction).await.void()?;
+        }
+
         transaction.commit().await.void()
     }
 
@@ -190,6 +237,47 @@ impl NodesRepository for NodesSqlxDatabase {
         let project_name: Option<String> = row.map(|r| r.get(0));
         Ok(project_name)
     }
+
+    async fn set_auto_migrate(&self, node_name: &str, auto_migrate: bool) -> Result<()> {
+        let query = query("UPDATE node SET auto_migrate = ? WHERE name = ?")
+            .bind(auto_migrate.to_sql())
+            .bind(node_name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_auto_migrate(&self, node_name: &str) -> Result<bool> {
+        let query = query_scalar::<_, bool>("SELECT auto_migrate FROM node WHERE name = ?")
+            .bind(node_name.to_sql());
+        let auto_migrate = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(auto_migrate.unwrap_or(true))
+    }
+
+    async fn set_node_binary_version(
+        &self,
+        node_name: &str,
+        version: &NodeBinaryVersion,
+    ) -> Result<()> {
+        let query = query("INSERT OR REPLACE INTO node_metadata VALUES (?, ?, ?, ?)")
+            .bind(node_name.to_sql())
+            .bind(version.version().to_sql())
+            .bind(version.git_hash().map(|h| h.to_sql()))
+            .bind(now()?.0.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_node_binary_version(&self, node_name: &str) -> Result<Option<NodeBinaryVersion>> {
+        let query =
+            query_as("SELECT binary_version, git_hash FROM node_metadata WHERE node_name = ?")
+                .bind(node_name.to_sql());
+        let row: Option<NodeMetadataRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| NodeBinaryVersion::new(r.binary_version, r.git_hash)))
+    }
 }
 
 // Database serialization / deserialization
@@ -203,6 +291,7 @@ pub(crate) struct NodeRow {
     is_authority: bool,
     tcp_listener_address: Option<String>,
     pid: Option<u32>,
+    is_ephemeral: bool,
 }
 
 impl NodeRow {
@@ -218,7 +307,7 @@ impl NodeRow {
             })?),
         };
 
-        Ok(NodeInfo::new(
+        let node_info = NodeInfo::new(
             self.name.clone(),
             Identifier::from_str(&self.identifier.clone())?,
             self.verbosity,
@@ -226,10 +315,21 @@ impl NodeRow {
             self.is_authority,
             tcp_listener_address,
             self.pid,
-        ))
+        );
+        Ok(if self.is_ephemeral {
+            node_info.set_ephemeral()
+        } else {
+            node_info
+        })
     }
 }
 
+#[derive(FromRow)]
+struct NodeMetadataRow {
+    binary_version: String,
+    git_hash: Option<String>,
+}
+
 #[cfg(test)]
 mod test {
     use ockam::identity::identities;
@@ -316,6 +416,21 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_ephemeral_node() -> Result<()> {
+        let repository = create_repository().await?;
+        let identifier = create_identity().await?;
+
+        // the ephemeral flag is persisted with the node
+        let node_info = create_node("node1", &identifier).set_ephemeral();
+        repository.store_node(&node_info).await?;
+
+        let result = repository.get_node("node1").await?;
+        assert_eq!(result, Some(node_info));
+        assert!(result.unwrap().is_ephemeral());
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_node_project() -> Result<()> {
         let repository = create_repository().await?;
@@ -330,6 +445,69 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_auto_migrate() -> Result<()> {
+        let repository = create_repository().await?;
+        let identifier = create_identity().await?;
+
+        // migrations are applied automatically by default
+        let node_info = create_node("node1", &identifier);
+        repository.store_node(&node_info).await?;
+        assert!(repository.get_auto_migrate("node1").await?);
+
+        // the setting is kept when the node is updated
+        repository.set_auto_migrate("node1", false).await?;
+        repository.store_node(&node_info.set_pid(5678)).await?;
+        assert!(!repository.get_auto_migrate("node1").await?);
+        assert_eq!(
+            repository.get_node("node1").await?.and_then(|n| n.pid()),
+            Some(5678)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_node_binary_version() -> Result<()> {
+        let repository = create_repository().await?;
+        let identifier = create_identity().await?;
+        repository
+            .store_node(&create_node("node1", &identifier))
+            .await?;
+
+        // no version is recorded for a node created by an older binary
+        assert_eq!(repository.get_node_binary_version("node1").await?, None);
+
+        // the version is replaced when the node is started again
+        let version = NodeBinaryVersion::new("0.1.0", Some("abcdef".to_string()));
+        repository
+            .set_node_binary_version("node1", &version)
+            .await?;
+        assert_eq!(
+            repository.get_node_binary_version("node1").await?,
+            Some(version)
+        );
+
+        let version = NodeBinaryVersion::new("0.2.0", None);
+        repository
+            .set_node_binary_version("node1", &version)
+            .await?;
+        assert_eq!(
+            repository.get_node_binary_version("node1").await?,
+            Some(version.clone())
+        );
+
+        // the version is kept when the node is renamed and removed when the node is deleted
+        repository.rename_node("node1", "node2").await?;
+        assert_eq!(repository.get_node_binary_version("node1").await?, None);
+        assert_eq!(
+            repository.get_node_binary_version("node2").await?,
+            Some(version)
+        );
+        repository.delete_node("node2").await?;
+        assert_eq!(repository.get_node_binary_version("node2").await?, None);
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn NodesRepository>> {
         Ok(Arc::new(NodesSqlxDatabase::create().await?))
@@ -0,0 +1,24 @@
+use ockam_core::async_trait;
+use ockam_core::Result;
+
+use crate::cli_state::RouteAlias;
+
+/// This trait supports the storage of named routes
+///
+///  - a route alias is identified by its name
+///  - the route of an alias is stored as given, and can reference other aliases
+///
+#[async_trait]
+pub trait RouteAliasesRepository: Send + Sync + 'static {
+    /// Store a route alias, replacing the route of an existing alias with the same name
+    async fn store_route_alias(&self, route_alias: &RouteAlias) -> Result<()>;
+
+    /// Return the route alias with the given name
+    async fn get_route_alias(&self, name: &str) -> Result<Option<RouteAlias>>;
+
+    /// Return all the route aliases, sorted by name
+    async fn get_route_aliases(&self) -> Result<Vec<RouteAlias>>;
+
+    /// Delete a route alias
+    async fn delete_route_alias(&self, name: &str) -> Result<()>;
+}
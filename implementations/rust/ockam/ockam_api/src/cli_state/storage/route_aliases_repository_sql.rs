@@ -0,0 +1,132 @@
+use sqlx::*;
+
+use ockam::identity::TimestampInSeconds;
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+use crate::cli_state::RouteAlias;
+
+use super::RouteAliasesRepository;
+
+#[derive(Clone)]
+pub struct RouteAliasesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl RouteAliasesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for route aliases");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(SqlxDatabase::in_memory("route aliases").await?))
+    }
+}
+
+#[async_trait]
+impl RouteAliasesRepository for RouteAliasesSqlxDatabase {
+    async fn store_route_alias(&self, route_alias: &RouteAlias) -> Result<()> {
+        let query = query("INSERT OR REPLACE INTO route_alias VALUES (?, ?, ?)")
+            .bind(route_alias.name.to_sql())
+            .bind(route_alias.route.to_sql())
+            .bind(route_alias.created_at.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_route_alias(&self, name: &str) -> Result<Option<RouteAlias>> {
+        let query = query_as("SELECT name, route, created_at FROM route_alias WHERE name=$1")
+            .bind(name.to_sql());
+        let row: Option<RouteAliasRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        Ok(row.map(|r| r.route_alias()))
+    }
+
+    async fn get_route_aliases(&self) -> Result<Vec<RouteAlias>> {
+        let query = query_as("SELECT name, route, created_at FROM route_alias ORDER BY name");
+        let rows: Vec<RouteAliasRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        Ok(rows.into_iter().map(|r| r.route_alias()).collect())
+    }
+
+    async fn delete_route_alias(&self, name: &str) -> Result<()> {
+        let query = query("DELETE FROM route_alias WHERE name=?").bind(name.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+//  Database serialization / deserialization
+
+/// Low-level representation of a row in the route_alias table
+#[derive(sqlx::FromRow)]
+struct RouteAliasRow {
+    name: String,
+    route: String,
+    created_at: i64,
+}
+
+impl RouteAliasRow {
+    fn route_alias(self) -> RouteAlias {
+        RouteAlias {
+            name: self.name,
+            route: self.route,
+            created_at: TimestampInSeconds(self.created_at as u64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        let repository = create_repository().await?;
+
+        // create and store 2 route aliases
+        let db = RouteAlias {
+            name: "prod-db".to_string(),
+            route: "@prod/service/outlet".to_string(),
+            created_at: TimestampInSeconds(1),
+        };
+        let mut prod = RouteAlias {
+            name: "prod".to_string(),
+            route: "/project/default/service/forward_to_db/secure/api".to_string(),
+            created_at: TimestampInSeconds(2),
+        };
+        repository.store_route_alias(&db).await?;
+        repository.store_route_alias(&prod).await?;
+
+        // retrieve them as a vector sorted by name, or by name
+        let result = repository.get_route_aliases().await?;
+        assert_eq!(result, vec![prod.clone(), db.clone()]);
+
+        let result = repository.get_route_alias("prod-db").await?;
+        assert_eq!(result, Some(db.clone()));
+
+        // the route of an alias can be replaced
+        prod.route = "/node/n1/secure/api".to_string();
+        repository.store_route_alias(&prod).await?;
+        let result = repository.get_route_alias("prod").await?;
+        assert_eq!(result, Some(prod.clone()));
+
+        // a route alias can be deleted
+        repository.delete_route_alias("prod-db").await?;
+        let result = repository.get_route_alias("prod-db").await?;
+        assert_eq!(result, None);
+
+        let result = repository.get_route_aliases().await?;
+        assert_eq!(result, vec![prod.clone()]);
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository() -> Result<Arc<dyn RouteAliasesRepository>> {
+        Ok(Arc::new(RouteAliasesSqlxDatabase::create().await?))
+    }
+}
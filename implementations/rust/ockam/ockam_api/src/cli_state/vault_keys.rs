@@ -0,0 +1,411 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+use ockam::identity::models::PurposePublicKey;
+use ockam::identity::{Identifier, Identity, Purpose, TimestampInSeconds, Vault};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_vault::storage::{StoredSecret, StoredSecretKeyHandle};
+use ockam_vault::{HandleToSecret, SigningSecretKeyHandle};
+
+use crate::cli_state::{CliState, NamedVault, Result};
+
+/// The methods below support the listing of the keys stored in a vault
+/// and the deletion of the keys which are not used anymore.
+///
+/// Keys are matched to the identities and purpose keys using them by computing
+/// the handles of their public keys, since those handles are derived from the public keys.
+impl CliState {
+    /// Return all the keys stored in a vault, with the data referencing them, if any
+    #[instrument(skip_all, fields(vault_name = vault_name))]
+    pub async fn get_vault_keys(&self, vault_name: &str) -> Result<Vec<VaultKey>> {
+        let named_vault = self.get_named_vault(vault_name).await?;
+        let secrets = self
+            .vault_secrets_repository(&named_vault)
+            .await?
+            .get_stored_secrets()
+            .await?;
+        let references = self.get_vault_key_references(&named_vault).await?;
+
+        Ok(secrets
+            .into_iter()
+            .map(|secret| {
+                let referenced_by = references.get(secret.handle.handle()).cloned();
+                VaultKey::new(secret, referenced_by)
+            })
+            .collect())
+    }
+
+    /// Delete a key which is not used by a local identity or one of its purpose keys.
+    ///
+    /// A key belonging to an identity which is known locally, but which is not a local named identity,
+    /// for example an exported identity, is only deleted when `force` is true.
+    #[instrument(skip_all, fields(vault_name = vault_name, key_id = key_id, force = force))]
+    pub async fn delete_orphaned_vault_key(
+        &self,
+        vault_name: &str,
+        key_id: &str,
+        force: bool,
+    ) -> Result<VaultKey> {
+        let key = self
+            .get_vault_keys(vault_name)
+            .await?
+            .into_iter()
+            .find(|k| k.key_id() == key_id)
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::NotFound,
+                    format!("there is no key {key_id} in the vault {vault_name}"),
+                )
+            })?;
+
+        if !key.can_be_deleted(force) {
+            let reason = match key.referenced_by() {
+                Some(reference) if !key.is_in_use() => format!(
+                    "it belongs to {reference}, which might still be used elsewhere. The deletion must be forced"
+                ),
+                Some(reference) => format!("it is used by {reference}"),
+                None => "it is used".to_string(),
+            };
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Conflict,
+                format!("the key {key_id} cannot be deleted: {reason}"),
+            ))?;
+        }
+
+        let named_vault = self.get_named_vault(vault_name).await?;
+        let repository = self.vault_secrets_repository(&named_vault).await?;
+        match key.handle() {
+            StoredSecretKeyHandle::Signing(handle) => {
+                repository.delete_signing_secret(handle).await?;
+            }
+            StoredSecretKeyHandle::X25519(handle) => {
+                repository.delete_x25519_secret(handle).await?;
+            }
+        };
+        Ok(key)
+    }
+
+    /// Return the handles of the keys referenced by identities and purpose keys
+    async fn get_vault_key_references(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<BTreeMap<HandleToSecret, VaultKeyReference>> {
+        let vault = self.make_vault(named_vault).await?;
+        let named_identities = self.get_named_identities().await?;
+        let mut references = BTreeMap::new();
+
+        // identities which are known locally without being named identities
+        let change_histories = self
+            .change_history_repository()
+            .get_change_histories()
+            .await?;
+        for change_history in change_histories {
+            let identity = Identity::create_from_change_history(&change_history).await?;
+            let identifier = identity.identifier().clone();
+            if named_identities
+                .iter()
+                .any(|i| i.identifier() == identifier)
+            {
+                continue;
+            }
+            for handle in Self::identity_key_handles(&vault, &identity).await? {
+                references.insert(
+                    handle,
+                    VaultKeyReference::KnownIdentity {
+                        identifier: identifier.clone(),
+                    },
+                );
+            }
+        }
+
+        // named identities using this vault, and their purpose keys
+        for named_identity in named_identities
+            .iter()
+            .filter(|i| i.vault_name() == named_vault.name())
+        {
+            let identifier = named_identity.identifier();
+            let identity = self.get_identity(&identifier).await?;
+            for handle in Self::identity_key_handles(&vault, &identity).await? {
+                references.insert(
+                    handle,
+                    VaultKeyReference::Identity {
+                        name: named_identity.name(),
+                        identifier: identifier.clone(),
+                    },
+                );
+            }
+
+            for purpose in [Purpose::SecureChannel, Purpose::Credentials] {
+                let attestation = self
+                    .purpose_keys_repository()
+                    .get_purpose_key(&identifier, purpose)
+                    .await?;
+                if let Some(attestation) = attestation {
+                    let handle = match attestation.get_attestation_data()?.public_key {
+                        PurposePublicKey::SecureChannelStatic(public_key) => {
+                            vault
+                                .secure_channel_vault
+                                .get_x25519_secret_key_handle(&public_key)
+                                .await?
+                                .0
+                        }
+                        PurposePublicKey::CredentialSigning(public_key) => vault
+                            .credential_vault
+                            .get_secret_key_handle(&public_key.into())
+                            .await?
+                            .handle()
+                            .clone(),
+                    };
+                    references.insert(
+                        handle,
+                        VaultKeyReference::PurposeKey {
+                            name: named_identity.name(),
+                            identifier: identifier.clone(),
+                            purpose,
+                        },
+                    );
+                }
+            }
+        }
+        Ok(references)
+    }
+
+    /// Return the handles of all the primary keys used by an identity over its history
+    async fn identity_key_handles(
+        vault: &Vault,
+        identity: &Identity,
+    ) -> Result<Vec<HandleToSecret>> {
+        let mut handles = vec![];
+        for change in identity.changes() {
+            let handle = vault
+                .identity_vault
+                .get_secret_key_handle(change.primary_public_key())
+                .await?;
+            handles.push(handle.handle().clone());
+        }
+        Ok(handles)
+    }
+}
+
+/// A key stored in a vault
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultKey {
+    handle: StoredSecretKeyHandle,
+    created_at: Option<TimestampInSeconds>,
+    referenced_by: Option<VaultKeyReference>,
+}
+
+impl VaultKey {
+    fn new(secret: StoredSecret, referenced_by: Option<VaultKeyReference>) -> Self {
+        Self {
+            handle: secret.handle,
+            created_at: secret.created_at.map(TimestampInSeconds),
+            referenced_by,
+        }
+    }
+
+    /// Return the key handle
+    pub fn handle(&self) -> &StoredSecretKeyHandle {
+        &self.handle
+    }
+
+    /// Return the key id, which is its hex-encoded handle
+    pub fn key_id(&self) -> String {
+        hex::encode(self.handle.handle().value())
+    }
+
+    /// Return the type of key
+    pub fn key_type(&self) -> &'static str {
+        match &self.handle {
+            StoredSecretKeyHandle::Signing(SigningSecretKeyHandle::EdDSACurve25519(_)) => {
+                "EdDSACurve25519"
+            }
+            StoredSecretKeyHandle::Signing(SigningSecretKeyHandle::ECDSASHA256CurveP256(_)) => {
+                "ECDSASHA256CurveP256"
+            }
+            StoredSecretKeyHandle::X25519(_) => "X25519",
+        }
+    }
+
+    /// Return the creation time of the key, if it is known
+    pub fn created_at(&self) -> Option<TimestampInSeconds> {
+        self.created_at
+    }
+
+    /// Return the identity or purpose key referencing this key, if any
+    pub fn referenced_by(&self) -> Option<&VaultKeyReference> {
+        self.referenced_by.as_ref()
+    }
+
+    /// Return true if the key is used by a local identity or one of its purpose keys
+    pub fn is_in_use(&self) -> bool {
+        matches!(
+            self.referenced_by,
+            Some(VaultKeyReference::Identity { .. }) | Some(VaultKeyReference::PurposeKey { .. })
+        )
+    }
+
+    /// Return true if the key is not used by a local identity or one of its purpose keys
+    pub fn is_orphaned(&self) -> bool {
+        !self.is_in_use()
+    }
+
+    /// Return true if the key can be deleted.
+    /// The keys of identities which are not local must be forced to be deleted
+    pub fn can_be_deleted(&self, force: bool) -> bool {
+        match self.referenced_by {
+            None => true,
+            Some(VaultKeyReference::KnownIdentity { .. }) => force,
+            Some(_) => false,
+        }
+    }
+}
+
+/// Data referencing a vault key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultKeyReference {
+    /// Primary key of a local named identity
+    Identity {
+        name: String,
+        identifier: Identifier,
+    },
+    /// Purpose key of a local named identity
+    PurposeKey {
+        name: String,
+        identifier: Identifier,
+        purpose: Purpose,
+    },
+    /// Primary key of an identity which is known locally, for example because it was exported,
+    /// but which is not a local named identity
+    KnownIdentity { identifier: Identifier },
+}
+
+impl Display for VaultKeyReference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VaultKeyReference::Identity { name, identifier } => {
+                write!(f, "the identity {name} ({identifier})")
+            }
+            VaultKeyReference::PurposeKey {
+                name,
+                identifier,
+                purpose,
+            } => {
+                let purpose = match purpose {
+                    Purpose::SecureChannel => "secure channel",
+                    Purpose::Credentials => "credentials",
+                };
+                write!(
+                    f,
+                    "the {purpose} purpose key of the identity {name} ({identifier})"
+                )
+            }
+            VaultKeyReference::KnownIdentity { identifier } => {
+                write!(
+                    f,
+                    "the identity {identifier}, which is not a local identity"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_keys_of_a_deleted_identity_are_orphaned() -> Result<()> {
+        let cli = CliState::test().await?;
+        let vault = cli.get_or_create_default_named_vault().await?;
+        let alice = cli.create_identity_with_name("alice").await?;
+        let bob = cli.create_identity_with_name("bob").await?;
+
+        // create a purpose key for alice
+        let identities = cli.make_identities(cli.make_vault(&vault).await?).await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .create_secure_channel_purpose_key(&alice.identifier())
+            .await?;
+
+        let keys = cli.get_vault_keys(&vault.name()).await?;
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|k| k.is_in_use()));
+        assert!(keys.iter().all(|k| k.created_at().is_some()));
+        let alice_keys: Vec<VaultKey> = keys
+            .into_iter()
+            .filter(|k| match k.referenced_by() {
+                Some(VaultKeyReference::Identity { name, .. })
+                | Some(VaultKeyReference::PurposeKey { name, .. }) => name == "alice",
+                _ => false,
+            })
+            .collect();
+        assert_eq!(alice_keys.len(), 2);
+
+        // the keys of a used identity cannot be deleted
+        let result = cli
+            .delete_orphaned_vault_key(&vault.name(), &alice_keys[0].key_id(), true)
+            .await;
+        assert!(result.is_err());
+
+        // once alice is deleted, her keys are orphaned and can be deleted
+        cli.delete_identity_by_name("alice").await?;
+        let orphaned: Vec<VaultKey> = cli
+            .get_vault_keys(&vault.name())
+            .await?
+            .into_iter()
+            .filter(|k| k.is_orphaned())
+            .collect();
+        assert_eq!(orphaned.len(), 2);
+        assert!(orphaned.iter().all(|k| k.referenced_by().is_none()));
+        for key in orphaned {
+            cli.delete_orphaned_vault_key(&vault.name(), &key.key_id(), false)
+                .await?;
+        }
+
+        // only the key of bob remains
+        let keys = cli.get_vault_keys(&vault.name()).await?;
+        assert_eq!(keys.len(), 1);
+        assert_eq!(
+            keys[0].referenced_by(),
+            Some(&VaultKeyReference::Identity {
+                name: "bob".to_string(),
+                identifier: bob.identifier()
+            })
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_keys_of_a_non_local_identity_must_be_forced_to_be_deleted() -> Result<()> {
+        let cli = CliState::test().await?;
+        let vault = cli.get_or_create_default_named_vault().await?;
+        let alice = cli.create_identity_with_name("alice").await?;
+
+        // alice is still known, for example because it was exported, but it is not a local identity anymore
+        cli.identities_repository().delete_identity("alice").await?;
+
+        let keys = cli.get_vault_keys(&vault.name()).await?;
+        assert_eq!(keys.len(), 1);
+        let key = &keys[0];
+        assert!(key.is_orphaned());
+        assert_eq!(
+            key.referenced_by(),
+            Some(&VaultKeyReference::KnownIdentity {
+                identifier: alice.identifier()
+            })
+        );
+
+        let result = cli
+            .delete_orphaned_vault_key(&vault.name(), &key.key_id(), false)
+            .await;
+        assert!(result.is_err());
+        cli.delete_orphaned_vault_key(&vault.name(), &key.key_id(), true)
+            .await?;
+        assert!(cli.get_vault_keys(&vault.name()).await?.is_empty());
+        Ok(())
+    }
+}
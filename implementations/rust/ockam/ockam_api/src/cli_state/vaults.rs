@@ -7,6 +7,7 @@ use std::sync::Arc;
 use ockam::identity::{Identities, Vault};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
+use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
 use ockam_vault_aws::AwsSigningVault;
 
 use crate::cli_state::{random_name, CliState, Result};
@@ -324,6 +325,42 @@ impl CliState {
         }
     }
 
+    /// Return the vault corresponding to a named vault.
+    /// The keys of the default vault are stored in the main database, so its pool of connections
+    /// is shared instead of opening another pool on the same file.
+    pub async fn make_vault(&self, named_vault: &NamedVault) -> Result<Vault> {
+        if !named_vault.is_kms() && named_vault.path() == self.database_path() {
+            Ok(Vault::create_with_database(self.database()))
+        } else {
+            named_vault.vault().await
+        }
+    }
+
+    /// Return the repository storing the secrets of a named vault.
+    /// The secrets of a KMS vault are not stored locally
+    pub(super) async fn vault_secrets_repository(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        if named_vault.is_kms() {
+            return Err(ockam_core::Error::new(
+                Origin::Api,
+                Kind::Unsupported,
+                format!(
+                    "The keys of the KMS vault {} cannot be listed",
+                    named_vault.name()
+                ),
+            ))?;
+        }
+        if named_vault.path() == self.database_path() {
+            Ok(self.secrets_repository())
+        } else {
+            Ok(Arc::new(SecretsSqlxDatabase::new(
+                named_vault.database().await?,
+            )))
+        }
+    }
+
     async fn get_named_vault_with_path(&self, path: &Path) -> Result<Option<NamedVault>> {
         Ok(self
             .vaults_repository()
@@ -382,7 +419,6 @@ impl NamedVault {
     }
 
     async fn database(&self) -> Result<SqlxDatabase> {
-        // FIXME: We should really have one instance of the SqlxDatabase per process
         Ok(SqlxDatabase::create(self.path.as_path()).await?)
     }
 }
@@ -1,5 +1,6 @@
+use crate::cloud::email_address::EmailAddress;
 use crate::cloud::operation::Operations;
-use crate::cloud::project::models::CreateProject;
+use crate::cloud::project::models::{AddProjectAdmin, CreateProject, ProjectAdmin};
 use crate::cloud::project::models::{OrchestratorVersionInfo, ProjectModel};
 use crate::cloud::{ControllerClient, HasSecureClient, ORCHESTRATOR_AWAIT_TIMEOUT};
 
@@ -10,7 +11,7 @@ use tokio_retry::strategy::FixedInterval;
 use tokio_retry::Retry;
 
 use crate::cloud::project::Project;
-use ockam_core::api::Request;
+use ockam_core::api::{Reply, Request, Status};
 use ockam_node::Context;
 
 impl ControllerClient {
@@ -82,6 +83,56 @@ impl ControllerClient {
             .miette_success("list projects")
     }
 
+    #[instrument(skip_all, fields(project_id = project_id))]
+    pub async fn list_project_admins(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+    ) -> miette::Result<Vec<ProjectAdmin>> {
+        trace!(target: TARGET, %project_id, "listing project admins");
+        let req = Request::get(format!("/v0/{project_id}/admins"));
+        self.get_secure_client()
+            .ask(ctx, "projects", req)
+            .await
+            .into_diagnostic()?
+            .miette_success("list project admins")
+    }
+
+    #[instrument(skip_all, fields(project_id = project_id, email = %email))]
+    pub async fn add_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<ProjectAdmin> {
+        trace!(target: TARGET, %project_id, %email, "adding project admin");
+        let req = Request::put(format!("/v0/{project_id}/admins"))
+            .body(AddProjectAdmin::new(email.clone()));
+        let reply = self
+            .get_secure_client()
+            .ask(ctx, "projects", req)
+            .await
+            .into_diagnostic()?;
+        project_admin_reply(reply, AdminRequest::Add, project_id, email)
+    }
+
+    #[instrument(skip_all, fields(project_id = project_id, email = %email))]
+    pub async fn delete_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()> {
+        trace!(target: TARGET, %project_id, %email, "deleting project admin");
+        let req = Request::delete(format!("/v0/{project_id}/admins/{email}"));
+        let reply = self
+            .get_secure_client()
+            .tell(ctx, "projects", req)
+            .await
+            .into_diagnostic()?;
+        project_admin_reply(reply, AdminRequest::Delete, project_id, email)
+    }
+
     pub async fn wait_until_project_creation_operation_is_complete(
         &self,
         ctx: &Context,
@@ -136,3 +187,105 @@ impl ControllerClient {
         .await
     }
 }
+
+/// Request made to the Controller to modify the administrators of a project
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AdminRequest {
+    Add,
+    Delete,
+}
+
+/// Map the errors returned by the Controller when adding or removing a project admin
+/// to messages which can be directly displayed to the user
+fn project_admin_reply<T>(
+    reply: Reply<T>,
+    request: AdminRequest,
+    project_id: &str,
+    email: &EmailAddress,
+) -> miette::Result<T> {
+    match (request, reply) {
+        (AdminRequest::Add, Reply::Failed(_, Some(Status::Conflict))) => Err(miette!(
+            "The user {email} is already an admin of the project {project_id}"
+        )),
+        (AdminRequest::Add, Reply::Failed(_, Some(Status::NotFound))) => Err(miette!(
+            "There is no Ockam user with the email {email}. The user must enroll before being made an admin of the project {project_id}"
+        )),
+        (AdminRequest::Delete, Reply::Failed(_, Some(Status::NotFound))) => Err(miette!(
+            "The user {email} is not an admin of the project {project_id}"
+        )),
+        (AdminRequest::Add, reply) => reply.miette_success("add project admin"),
+        (AdminRequest::Delete, reply) => reply.miette_success("delete project admin"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::api::Error;
+
+    #[test]
+    fn test_add_project_admin_errors() {
+        let email = EmailAddress::new_unsafe("test@ockam.io");
+
+        let result = project_admin_reply(
+            Reply::<ProjectAdmin>::Failed(Error::new_without_path(), Some(Status::Conflict)),
+            AdminRequest::Add,
+            "project-id",
+            &email,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "The user test@ockam.io is already an admin of the project project-id"
+        );
+
+        let result = project_admin_reply(
+            Reply::<ProjectAdmin>::Failed(Error::new_without_path(), Some(Status::NotFound)),
+            AdminRequest::Add,
+            "project-id",
+            &email,
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("There is no Ockam user with the email test@ockam.io"));
+
+        let admin = ProjectAdmin {
+            email: email.clone(),
+        };
+        let result = project_admin_reply(
+            Reply::Successful(admin.clone()),
+            AdminRequest::Add,
+            "project-id",
+            &email,
+        );
+        assert_eq!(result.unwrap(), admin);
+    }
+
+    #[test]
+    fn test_delete_project_admin_errors() {
+        let email = EmailAddress::new_unsafe("test@ockam.io");
+
+        let result = project_admin_reply(
+            Reply::<()>::Failed(Error::new_without_path(), Some(Status::NotFound)),
+            AdminRequest::Delete,
+            "project-id",
+            &email,
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "The user test@ockam.io is not an admin of the project project-id"
+        );
+
+        // other errors are reported as failed requests
+        let result = project_admin_reply(
+            Reply::<()>::Failed(Error::new_without_path(), Some(Status::Forbidden)),
+            AdminRequest::Delete,
+            "project-id",
+            &email,
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("delete project admin"));
+    }
+}
@@ -4,7 +4,9 @@ mod controller_client;
 mod operations;
 #[allow(clippy::module_inception)]
 mod project;
+mod project_export;
 mod projects_orchestrator_api;
 
 pub use project::*;
+pub use project_export::*;
 pub use projects_orchestrator_api::*;
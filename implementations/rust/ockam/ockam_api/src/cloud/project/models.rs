@@ -197,6 +197,35 @@ impl Display for ProjectUserRole {
     }
 }
 
+/// Administrator of a project, as returned by the Controller
+#[derive(Clone, Debug, Eq, PartialEq, Decode, Deserialize, Encode, Serialize)]
+#[cbor(map)]
+#[rustfmt::skip]
+pub struct ProjectAdmin {
+    #[n(1)] pub email: EmailAddress,
+}
+
+impl Display for ProjectAdmin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.email)
+    }
+}
+
+/// Request sent to the Controller to make a user an administrator of a project
+#[derive(Encode, Decode, Debug)]
+#[cfg_attr(test, derive(Clone))]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AddProjectAdmin {
+    #[n(1)] pub email: EmailAddress,
+}
+
+impl AddProjectAdmin {
+    pub fn new(email: EmailAddress) -> Self {
+        Self { email }
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct OktaAuth0 {
     pub tenant_base_url: Url,
@@ -241,6 +270,18 @@ mod tests {
         fn create_project(cp: CreateProject) -> TestResult {
             validate_with_schema("create_project", cp)
         }
+
+        fn project_admin(a: ProjectAdmin) -> TestResult {
+            validate_with_schema("project_admin", a)
+        }
+
+        fn project_admins(a: Vec<ProjectAdmin>) -> TestResult {
+            validate_with_schema("project_admins", a)
+        }
+
+        fn add_project_admin(a: AddProjectAdmin) -> TestResult {
+            validate_with_schema("add_project_admin", a)
+        }
     }
 
     /// HELPERS
@@ -293,4 +334,19 @@ mod tests {
             }
         }
     }
+    impl Arbitrary for ProjectAdmin {
+        fn arbitrary(g: &mut Gen) -> Self {
+            ProjectAdmin {
+                email: EmailAddress::arbitrary(g),
+            }
+        }
+    }
+
+    impl Arbitrary for AddProjectAdmin {
+        fn arbitrary(g: &mut Gen) -> Self {
+            AddProjectAdmin {
+                email: EmailAddress::arbitrary(g),
+            }
+        }
+    }
 }
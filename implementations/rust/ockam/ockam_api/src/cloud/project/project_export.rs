@@ -0,0 +1,197 @@
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::Identifier;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+use crate::cli_state::enrollments::EnrollmentTicket;
+use crate::cloud::project::models::ProjectModel;
+use crate::cloud::project::Project;
+
+/// Current version of the project export format
+pub const PROJECT_EXPORT_VERSION: u8 = 1;
+
+/// A project exported on a machine which can access the Orchestrator,
+/// so that it can be imported on a machine which can not access it.
+///
+/// The export contains everything needed to trust the project authority
+/// and, optionally, an enrollment ticket to become a member of the project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectExport {
+    /// Version of the export format
+    pub version: u8,
+    /// Project description, including the change history of the authority identity
+    pub project: ProjectModel,
+    /// Identifier of the project authority.
+    /// It must be the identifier derived from the authority change history
+    pub authority_identifier: Identifier,
+    /// Enrollment ticket which can be redeemed with the project authority
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrollment_ticket: Option<EnrollmentTicket>,
+}
+
+impl ProjectExport {
+    /// Create an export for a project which has an authority
+    pub fn new(project: &Project, enrollment_ticket: Option<EnrollmentTicket>) -> Result<Self> {
+        Ok(Self {
+            version: PROJECT_EXPORT_VERSION,
+            project: project.model().clone(),
+            authority_identifier: project.authority_identifier()?,
+            enrollment_ticket,
+        })
+    }
+
+    /// Validate the export and return the corresponding project.
+    ///
+    /// The authority change history must be correctly signed and must
+    /// correspond to the exported authority identifier. The project contained in
+    /// the enrollment ticket, if any, must have the same authority.
+    pub async fn import(&self) -> Result<Project> {
+        if self.version != PROJECT_EXPORT_VERSION {
+            return Err(invalid(format!(
+                "unsupported project export version {}, expected {}",
+                self.version, PROJECT_EXPORT_VERSION
+            )));
+        }
+
+        let authority_change_history =
+            self.project.authority_identity.as_ref().ok_or_else(|| {
+                invalid(format!(
+                    "the exported project {} has no authority identity",
+                    self.project.name
+                ))
+            })?;
+
+        // this verifies the signatures of the project and authority change histories
+        let project = Project::import(self.project.clone()).await?;
+
+        let authority_identifier = project.authority_identifier()?;
+        if authority_identifier != self.authority_identifier {
+            return Err(invalid(format!(
+                "the authority identity of the project {} is {}, but the export declares {}",
+                self.project.name, authority_identifier, self.authority_identifier
+            )));
+        }
+
+        if let Some(ticket_project) = self
+            .enrollment_ticket
+            .as_ref()
+            .and_then(|t| t.project.as_ref())
+        {
+            if ticket_project.id != self.project.id
+                || ticket_project.authority_identity.as_ref() != Some(authority_change_history)
+            {
+                return Err(invalid(format!(
+                    "the enrollment ticket was not issued for the project {}",
+                    self.project.name
+                )));
+            }
+        }
+
+        Ok(project)
+    }
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(Origin::Api, Kind::Invalid, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::one_time_code::OneTimeCode;
+    use crate::cli_state::CliState;
+    use crate::nodes::service::NodeManagerCredentialRetrieverOptions;
+
+    #[tokio::test]
+    async fn test_export_and_import_a_project() -> Result<()> {
+        let connected = CliState::test().await?;
+        let project = create_project(&connected, "project_id").await?;
+
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), Some(project.model().clone()));
+        let export = ProjectExport::new(&project, Some(ticket))?;
+        let exported = serde_json::to_string(&export).unwrap();
+
+        // import the project in another, disconnected, state
+        let disconnected = CliState::test().await?;
+        let export: ProjectExport = serde_json::from_str(&exported).unwrap();
+        let imported = export.import().await?;
+        disconnected.projects().store_project(imported).await?;
+
+        let imported = disconnected
+            .projects()
+            .get_project_by_name(project.name())
+            .await?;
+        assert_eq!(imported.model(), project.model());
+        assert_eq!(
+            imported.authority_identifier()?,
+            project.authority_identifier()?
+        );
+
+        // trust options can be created from the imported project
+        let trust_options = disconnected
+            .retrieve_trust_options(&Some(project.name().to_string()), &None, &None, false)
+            .await?;
+        assert_eq!(
+            trust_options.authority(),
+            Some(&project.authority_identifier()?)
+        );
+        assert!(matches!(
+            trust_options.credential_retriever_options(),
+            NodeManagerCredentialRetrieverOptions::Remote(_)
+        ));
+
+        let trust_options = disconnected
+            .retrieve_trust_options(&None, &imported.model().authority_identity, &None, true)
+            .await?;
+        assert!(matches!(
+            trust_options.credential_retriever_options(),
+            NodeManagerCredentialRetrieverOptions::CacheOnly(_)
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_reject_a_mismatched_authority() -> Result<()> {
+        let cli = CliState::test().await?;
+        let project = create_project(&cli, "project_id").await?;
+        let other_project = create_project(&cli, "other_project_id").await?;
+
+        // the declared authority identifier does not match the authority change history
+        let mut export = ProjectExport::new(&project, None)?;
+        export.authority_identifier = other_project.authority_identifier()?;
+        assert!(export.import().await.is_err());
+
+        // the authority change history has been tampered with
+        let mut export = ProjectExport::new(&project, None)?;
+        let mut change_history =
+            hex::decode(export.project.authority_identity.clone().unwrap()).unwrap();
+        let last = change_history.len() - 1;
+        change_history[last] ^= 0xff;
+        export.project.authority_identity = Some(hex::encode(change_history));
+        assert!(export.import().await.is_err());
+
+        // the enrollment ticket was issued for another project
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), Some(other_project.model().clone()));
+        let export = ProjectExport::new(&project, Some(ticket))?;
+        assert!(export.import().await.is_err());
+
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_project(cli: &CliState, project_id: &str) -> Result<Project> {
+        let authority = cli
+            .create_identity_with_name(&format!("{project_id}-authority"))
+            .await?;
+        let authority = cli.get_identity(&authority.identifier()).await?;
+        let model = ProjectModel {
+            id: project_id.to_string(),
+            name: format!("{project_id}-name"),
+            authority_access_route: Some("/dnsaddr/127.0.0.1/tcp/4000/service/api".to_string()),
+            authority_identity: Some(authority.export_as_string()?),
+            ..Default::default()
+        };
+        cli.projects().import_and_store_project(model).await
+    }
+}
@@ -1,4 +1,5 @@
-use crate::cloud::project::models::OrchestratorVersionInfo;
+use crate::cloud::email_address::EmailAddress;
+use crate::cloud::project::models::{OrchestratorVersionInfo, ProjectAdmin};
 use crate::cloud::project::Project;
 use ockam_core::async_trait;
 use ockam_node::Context;
@@ -48,6 +49,26 @@ pub trait ProjectsOrchestratorApi {
 
     async fn get_admin_projects(&self, ctx: &Context) -> miette::Result<Vec<Project>>;
 
+    async fn list_project_admins(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+    ) -> miette::Result<Vec<ProjectAdmin>>;
+
+    async fn add_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<ProjectAdmin>;
+
+    async fn delete_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()>;
+
     async fn wait_until_project_creation_operation_is_complete(
         &self,
         ctx: &Context,
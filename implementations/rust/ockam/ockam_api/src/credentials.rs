@@ -0,0 +1,233 @@
+//! Export and offline verification of the credentials presented by a node
+//!
+//! A credential can be exported as a file containing its CBOR encoding, together with the purpose key
+//! attestation used to sign it. That file can then be verified on a machine which has no access
+//! to the node or to the project, provided that the change history of the issuer is available.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::{
+    ChangeHistoryRepository, ChangeHistorySqlxDatabase, CredentialsVerification, Identifier,
+    Identity, PurposeKeyVerification, TimestampInSeconds, Vault,
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Kind of credential which can be exported from a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialScope {
+    /// Credential issued by the project authority to the identity of a node
+    ProjectMember,
+}
+
+impl Display for CredentialScope {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CredentialScope::ProjectMember => f.write_str("project-member"),
+        }
+    }
+}
+
+impl FromStr for CredentialScope {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "project-member" => Ok(CredentialScope::ProjectMember),
+            other => Err(format!(
+                "unknown credential scope '{other}'. The supported scopes are: project-member"
+            )),
+        }
+    }
+}
+
+/// Issuer which is expected to have signed a credential
+#[derive(Debug, Clone)]
+pub enum CredentialIssuer {
+    /// Issuer given by its identifier. Its change history must already be known locally
+    Identifier(Identifier),
+    /// Issuer given by its full change history
+    Identity(Identity),
+}
+
+impl CredentialIssuer {
+    /// Create an issuer from either an identifier or a hex-encoded change history
+    pub async fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        if let Ok(identifier) = Identifier::from_str(value) {
+            return Ok(CredentialIssuer::Identifier(identifier));
+        }
+        Identity::create(value)
+            .await
+            .map(CredentialIssuer::Identity)
+            .map_err(|e| {
+                invalid(format!(
+                    "the issuer must be an identifier or a change history: {e}"
+                ))
+            })
+    }
+
+    /// Return the identifier of the issuer
+    pub fn identifier(&self) -> Identifier {
+        match self {
+            CredentialIssuer::Identifier(identifier) => identifier.clone(),
+            CredentialIssuer::Identity(identity) => identity.identifier().clone(),
+        }
+    }
+
+    /// Return a repository containing the change history of the issuer.
+    ///
+    /// When the issuer is given by its change history, an in-memory repository is created
+    /// so that the local repository is not modified by a verification.
+    async fn change_history_repository(
+        &self,
+        local: Arc<dyn ChangeHistoryRepository>,
+    ) -> Result<Arc<dyn ChangeHistoryRepository>> {
+        match self {
+            CredentialIssuer::Identifier(_) => Ok(local),
+            CredentialIssuer::Identity(identity) => {
+                let repository = ChangeHistorySqlxDatabase::create().await?;
+                repository.update_identity(identity, false).await?;
+                Ok(Arc::new(repository))
+            }
+        }
+    }
+}
+
+/// Decode a credential exported either as CBOR bytes or as a hex-encoded string
+pub fn decode_credential(bytes: &[u8]) -> Result<CredentialAndPurposeKey> {
+    if let Ok(credential) = CredentialAndPurposeKey::decode_from_cbor_bytes(bytes) {
+        return Ok(credential);
+    }
+    let as_hex = std::str::from_utf8(bytes)
+        .map_err(|_| invalid("the credential is neither CBOR nor hex-encoded"))?;
+    CredentialAndPurposeKey::decode_from_string(as_hex.trim())
+        .map_err(|e| invalid(format!("the credential cannot be decoded: {e}")))
+}
+
+/// Verify a credential at a given time, without accessing a node.
+///
+/// The signatures of the credential and of its purpose key attestation are checked,
+/// as well as the issuer and the validity period of the credential.
+/// If the issuer is only given by its identifier, its change history is looked up in `local`.
+pub async fn verify_credential(
+    local: Arc<dyn ChangeHistoryRepository>,
+    issuer: &CredentialIssuer,
+    credential: &CredentialAndPurposeKey,
+    at: TimestampInSeconds,
+) -> Result<VerifiedCredential> {
+    let credential_data = credential.get_credential_data()?;
+    let purpose_key_data = credential.purpose_key_attestation.get_attestation_data()?;
+
+    // check the issuer and the dates first in order to return a precise error
+    let expected_issuer = issuer.identifier();
+    if purpose_key_data.subject != expected_issuer {
+        return Err(invalid(format!(
+            "the credential was issued by {}, not by {}",
+            purpose_key_data.subject, expected_issuer
+        )));
+    }
+    if credential_data.expires_at < at {
+        return Err(invalid(format!(
+            "the credential expired at {}, before {}",
+            credential_data.expires_at.0, at.0
+        )));
+    }
+    if !CredentialsVerification::is_valid_at(&credential_data, at) {
+        return Err(invalid(format!(
+            "the credential is only valid from {}, after {}",
+            credential_data.created_at.0, at.0
+        )));
+    }
+
+    let verifying_vault = Vault::create_verifying_vault();
+    let purpose_keys_verification = Arc::new(PurposeKeyVerification::new(
+        verifying_vault.clone(),
+        issuer.change_history_repository(local).await?,
+    ));
+    CredentialsVerification::verify_credential_at(
+        purpose_keys_verification,
+        verifying_vault,
+        None,
+        &[expected_issuer],
+        credential,
+        at,
+    )
+    .await
+    .map_err(|e| invalid(format!("the credential signature is invalid: {e}")))?;
+
+    VerifiedCredential::new(credential, at)
+}
+
+/// Content of a credential which has been successfully verified
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct VerifiedCredential {
+    pub subject: Identifier,
+    pub issuer: Identifier,
+    pub schema: u64,
+    pub attributes: BTreeMap<String, String>,
+    pub created_at: TimestampInSeconds,
+    pub expires_at: TimestampInSeconds,
+    pub verified_at: TimestampInSeconds,
+}
+
+impl VerifiedCredential {
+    fn new(credential: &CredentialAndPurposeKey, verified_at: TimestampInSeconds) -> Result<Self> {
+        let credential_data = credential.get_credential_data()?;
+        let purpose_key_data = credential.purpose_key_attestation.get_attestation_data()?;
+        let subject = credential_data
+            .subject
+            .ok_or_else(|| invalid("the credential subject is missing"))?;
+
+        // attributes which are not valid UTF-8 are displayed as hex-encoded strings
+        let attributes = credential_data
+            .subject_attributes
+            .map
+            .iter()
+            .map(|(k, v)| (display_bytes(k.as_slice()), display_bytes(v.as_slice())))
+            .collect();
+
+        Ok(Self {
+            subject,
+            issuer: purpose_key_data.subject,
+            schema: credential_data.subject_attributes.schema.0,
+            attributes,
+            created_at: credential_data.created_at,
+            expires_at: credential_data.expires_at,
+            verified_at,
+        })
+    }
+}
+
+impl Display for VerifiedCredential {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Subject:     {}", self.subject)?;
+        writeln!(f, "Issuer:      {}", self.issuer)?;
+        writeln!(f, "Schema:      {}", self.schema)?;
+        writeln!(f, "Created at:  {}", self.created_at.0)?;
+        writeln!(f, "Expires at:  {}", self.expires_at.0)?;
+        writeln!(f, "Verified at: {}", self.verified_at.0)?;
+        write!(f, "Attributes:")?;
+        if self.attributes.is_empty() {
+            write!(f, " none")?;
+        }
+        for (key, value) in &self.attributes {
+            write!(f, "\n  {key}: {value}")?;
+        }
+        Ok(())
+    }
+}
+
+fn display_bytes(bytes: &[u8]) -> String {
+    String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| hex::encode(bytes))
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::new(Origin::Api, Kind::Invalid, message.into())
+}
@@ -0,0 +1,811 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::io::ErrorKind;
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use hmac::{Hmac, Mac};
+use kafka_protocol::messages::{
+    ApiKey, ApiVersionsRequest, ApiVersionsResponse, RequestHeader, ResponseHeader,
+    SaslAuthenticateRequest, SaslAuthenticateResponse, SaslHandshakeRequest, SaslHandshakeResponse,
+};
+use kafka_protocol::protocol::{Decodable, Encodable, StrBytes};
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+use sha2::{Digest, Sha256, Sha512};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::rand::random_string;
+
+use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
+
+/// Default maximum duration of the validation of a Kafka broker
+pub const DEFAULT_KAFKA_BROKER_VALIDATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Client id sent to the broker during the validation
+const VALIDATION_CLIENT_ID: &str = "ockam-kafka-outlet-validation";
+
+/// Maximum size of a response accepted during the validation
+const MAX_RESPONSE_SIZE: usize = 1024 * 1024;
+
+const API_VERSIONS_VERSION: i16 = 0;
+const SASL_HANDSHAKE_VERSION: i16 = 1;
+const SASL_AUTHENTICATE_VERSION: i16 = 0;
+
+const UNSUPPORTED_SASL_MECHANISM: i16 = 33;
+const SASL_AUTHENTICATION_FAILED: i16 = 58;
+
+/// SASL mechanisms which can be validated
+#[derive(Clone, Copy, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum KafkaSaslMechanism {
+    #[n(0)] ScramSha256,
+    #[n(1)] ScramSha512,
+}
+
+impl Display for KafkaSaslMechanism {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KafkaSaslMechanism::ScramSha256 => write!(f, "SCRAM-SHA-256"),
+            KafkaSaslMechanism::ScramSha512 => write!(f, "SCRAM-SHA-512"),
+        }
+    }
+}
+
+impl FromStr for KafkaSaslMechanism {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().as_str() {
+            "SCRAM-SHA-256" => Ok(KafkaSaslMechanism::ScramSha256),
+            "SCRAM-SHA-512" => Ok(KafkaSaslMechanism::ScramSha512),
+            _ => Err(format!(
+                "unsupported SASL mechanism {s}. Use SCRAM-SHA-256 or SCRAM-SHA-512"
+            )),
+        }
+    }
+}
+
+impl KafkaSaslMechanism {
+    fn hmac(&self, key: &[u8], data: &[u8]) -> Vec<u8> {
+        match self {
+            KafkaSaslMechanism::ScramSha256 => {
+                let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any size");
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+            KafkaSaslMechanism::ScramSha512 => {
+                let mut mac = <Hmac<Sha512> as Mac>::new_from_slice(key)
+                    .expect("HMAC accepts keys of any size");
+                mac.update(data);
+                mac.finalize().into_bytes().to_vec()
+            }
+        }
+    }
+
+    fn hash(&self, data: &[u8]) -> Vec<u8> {
+        match self {
+            KafkaSaslMechanism::ScramSha256 => Sha256::digest(data).to_vec(),
+            KafkaSaslMechanism::ScramSha512 => Sha512::digest(data).to_vec(),
+        }
+    }
+
+    fn salted_password(&self, password: &str, salt: &[u8], iterations: u32) -> Vec<u8> {
+        match self {
+            KafkaSaslMechanism::ScramSha256 => {
+                pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(password.as_bytes(), salt, iterations)
+                    .to_vec()
+            }
+            KafkaSaslMechanism::ScramSha512 => {
+                pbkdf2::pbkdf2_hmac_array::<Sha512, 64>(password.as_bytes(), salt, iterations)
+                    .to_vec()
+            }
+        }
+    }
+}
+
+/// Credentials used to validate the SASL authentication with a broker.
+/// They are only used for the validation and never stored by the node.
+#[derive(Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KafkaSaslCredentials {
+    #[n(1)] mechanism: KafkaSaslMechanism,
+    #[n(2)] username: String,
+    #[n(3)] password: String,
+}
+
+impl KafkaSaslCredentials {
+    pub fn new(mechanism: KafkaSaslMechanism, username: String, password: String) -> Self {
+        Self {
+            mechanism,
+            username,
+            password,
+        }
+    }
+
+    pub fn mechanism(&self) -> KafkaSaslMechanism {
+        self.mechanism
+    }
+}
+
+impl Debug for KafkaSaslCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KafkaSaslCredentials")
+            .field("mechanism", &self.mechanism)
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Parameters of the validation of a Kafka broker, done before creating a Kafka outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct KafkaBrokerValidationRequest {
+    #[n(1)] timeout: Duration,
+    #[n(2)] sasl: Option<KafkaSaslCredentials>,
+}
+
+impl KafkaBrokerValidationRequest {
+    pub fn new(timeout: Duration, sasl: Option<KafkaSaslCredentials>) -> Self {
+        Self { timeout, sasl }
+    }
+
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn sasl(&self) -> Option<&KafkaSaslCredentials> {
+        self.sasl.as_ref()
+    }
+}
+
+/// Result of the validation of a Kafka broker
+#[derive(Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[serde(tag = "result", rename_all = "snake_case")]
+#[rustfmt::skip]
+pub enum KafkaBrokerValidation {
+    #[n(1)] Valid {
+        #[n(1)] api_keys_count: u32,
+        #[n(2)] sasl_mechanism: Option<KafkaSaslMechanism>,
+    },
+    #[n(2)] Unreachable {
+        #[n(1)] reason: String,
+    },
+    #[n(3)] Timeout {
+        #[n(1)] timeout_ms: u64,
+    },
+    #[n(4)] TlsMismatch,
+    #[n(5)] ConnectionClosed,
+    #[n(6)] SaslMechanismMismatch {
+        #[n(1)] requested: KafkaSaslMechanism,
+        #[n(2)] enabled: Vec<String>,
+    },
+    #[n(7)] SaslAuthenticationFailed {
+        #[n(1)] reason: String,
+    },
+    #[n(8)] ProtocolError {
+        #[n(1)] reason: String,
+    },
+}
+
+impl KafkaBrokerValidation {
+    pub fn is_valid(&self) -> bool {
+        matches!(self, KafkaBrokerValidation::Valid { .. })
+    }
+}
+
+impl Display for KafkaBrokerValidation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KafkaBrokerValidation::Valid {
+                api_keys_count,
+                sasl_mechanism,
+            } => {
+                write!(f, "the broker is reachable and supports {api_keys_count} APIs")?;
+                if let Some(mechanism) = sasl_mechanism {
+                    write!(f, ", the {mechanism} authentication succeeded")?;
+                }
+                Ok(())
+            }
+            KafkaBrokerValidation::Unreachable { reason } => {
+                write!(f, "the broker is unreachable: {reason}")
+            }
+            KafkaBrokerValidation::Timeout { timeout_ms } => {
+                write!(f, "the broker did not answer within {timeout_ms}ms")
+            }
+            KafkaBrokerValidation::TlsMismatch => write!(
+                f,
+                "the broker expects a TLS connection but the Kafka outlet connects without TLS"
+            ),
+            KafkaBrokerValidation::ConnectionClosed => write!(
+                f,
+                "the broker closed the connection. Its listener might require TLS or a different security protocol"
+            ),
+            KafkaBrokerValidation::SaslMechanismMismatch { requested, enabled } => {
+                if enabled.is_empty() {
+                    write!(f, "the broker does not support the {requested} mechanism")
+                } else {
+                    write!(
+                        f,
+                        "the broker does not support the {requested} mechanism. Enabled mechanisms: {}",
+                        enabled.join(", ")
+                    )
+                }
+            }
+            KafkaBrokerValidation::SaslAuthenticationFailed { reason } => {
+                write!(f, "the SASL authentication failed: {reason}")
+            }
+            KafkaBrokerValidation::ProtocolError { reason } => {
+                write!(f, "the broker answered with an unexpected message: {reason}")
+            }
+        }
+    }
+}
+
+/// Check that a Kafka broker can be used by a Kafka outlet:
+///
+///  - the broker is reachable and answers an ApiVersions request
+///  - if credentials are provided, the SASL handshake and authentication succeed
+///
+/// The whole validation is bounded by the request timeout.
+pub async fn validate_kafka_broker(
+    bootstrap_server_addr: SocketAddr,
+    request: &KafkaBrokerValidationRequest,
+) -> KafkaBrokerValidation {
+    match tokio::time::timeout(
+        request.timeout(),
+        validate(bootstrap_server_addr, request.sasl()),
+    )
+    .await
+    {
+        Ok(Ok(validation)) | Ok(Err(validation)) => validation,
+        Err(_) => KafkaBrokerValidation::Timeout {
+            timeout_ms: request.timeout().as_millis() as u64,
+        },
+    }
+}
+
+async fn validate(
+    bootstrap_server_addr: SocketAddr,
+    sasl: Option<&KafkaSaslCredentials>,
+) -> Result<KafkaBrokerValidation, KafkaBrokerValidation> {
+    let stream = TcpStream::connect(bootstrap_server_addr)
+        .await
+        .map_err(|e| KafkaBrokerValidation::Unreachable {
+            reason: e.to_string(),
+        })?;
+    let mut connection = BrokerConnection::new(stream);
+
+    let api_versions: ApiVersionsResponse = connection
+        .send(
+            ApiKey::ApiVersionsKey,
+            API_VERSIONS_VERSION,
+            &ApiVersionsRequest::default(),
+        )
+        .await?;
+    if api_versions.error_code != 0 {
+        return Err(protocol_error(format!(
+            "the ApiVersions request failed with the error code {}",
+            api_versions.error_code
+        )));
+    }
+
+    let sasl_mechanism = match sasl {
+        Some(credentials) => {
+            connection.authenticate(credentials).await?;
+            Some(credentials.mechanism())
+        }
+        None => None,
+    };
+
+    Ok(KafkaBrokerValidation::Valid {
+        api_keys_count: api_versions.api_keys.len() as u32,
+        sasl_mechanism,
+    })
+}
+
+/// Connection to a broker sending one request at a time
+struct BrokerConnection {
+    stream: TcpStream,
+    correlation_id: i32,
+}
+
+impl BrokerConnection {
+    fn new(stream: TcpStream) -> Self {
+        Self {
+            stream,
+            correlation_id: 0,
+        }
+    }
+
+    /// Send a request and wait for its response
+    async fn send<Req: Encodable, Resp: Decodable>(
+        &mut self,
+        api_key: ApiKey,
+        api_version: i16,
+        request: &Req,
+    ) -> Result<Resp, KafkaBrokerValidation> {
+        self.correlation_id += 1;
+        let mut header = RequestHeader::default();
+        header.request_api_key = api_key as i16;
+        header.request_api_version = api_version;
+        header.correlation_id = self.correlation_id;
+        header.client_id = Some(StrBytes::from_static_str(VALIDATION_CLIENT_ID));
+
+        let body = encode_request(&header, request, api_version, api_key)
+            .map_err(|_| protocol_error(format!("cannot encode the {api_key:?} request")))?;
+        let mut message = BytesMut::with_capacity(body.len() + 4);
+        message.extend_from_slice(&(body.len() as u32).to_be_bytes());
+        message.extend_from_slice(&body);
+        self.stream
+            .write_all(&message)
+            .await
+            .map_err(|_| KafkaBrokerValidation::ConnectionClosed)?;
+
+        let mut response = self.read_response().await?;
+        let response_header =
+            ResponseHeader::decode(&mut response, api_key.response_header_version(api_version))
+                .map_err(|_| protocol_error(format!("cannot decode the {api_key:?} response")))?;
+        if response_header.correlation_id != self.correlation_id {
+            return Err(protocol_error(format!(
+                "expected the correlation id {}, got {}",
+                self.correlation_id, response_header.correlation_id
+            )));
+        }
+        decode_body(&mut response, api_version)
+            .map_err(|_| protocol_error(format!("cannot decode the {api_key:?} response")))
+    }
+
+    async fn read_response(&mut self) -> Result<Bytes, KafkaBrokerValidation> {
+        let mut length = [0u8; 4];
+        self.stream.read_exact(&mut length).await.map_err(|e| {
+            if e.kind() == ErrorKind::UnexpectedEof || e.kind() == ErrorKind::ConnectionReset {
+                KafkaBrokerValidation::ConnectionClosed
+            } else {
+                protocol_error(e.to_string())
+            }
+        })?;
+
+        // a TLS listener answers a plaintext request with a TLS alert or handshake record
+        if (length[0] == 0x15 || length[0] == 0x16) && length[1] == 0x03 {
+            return Err(KafkaBrokerValidation::TlsMismatch);
+        }
+
+        let length = u32::from_be_bytes(length) as usize;
+        if length > MAX_RESPONSE_SIZE {
+            return Err(protocol_error(format!(
+                "the response size {length} exceeds {MAX_RESPONSE_SIZE} bytes"
+            )));
+        }
+        let mut response = vec![0u8; length];
+        self.stream
+            .read_exact(&mut response)
+            .await
+            .map_err(|_| KafkaBrokerValidation::ConnectionClosed)?;
+        Ok(Bytes::from(response))
+    }
+
+    /// Run the SASL handshake followed by a SCRAM authentication (RFC 5802)
+    async fn authenticate(
+        &mut self,
+        credentials: &KafkaSaslCredentials,
+    ) -> Result<(), KafkaBrokerValidation> {
+        let mechanism = credentials.mechanism();
+        let mut handshake = SaslHandshakeRequest::default();
+        handshake.mechanism = StrBytes::from_string(mechanism.to_string());
+        let response: SaslHandshakeResponse = self
+            .send(ApiKey::SaslHandshakeKey, SASL_HANDSHAKE_VERSION, &handshake)
+            .await?;
+        match response.error_code {
+            0 => (),
+            UNSUPPORTED_SASL_MECHANISM => {
+                return Err(KafkaBrokerValidation::SaslMechanismMismatch {
+                    requested: mechanism,
+                    enabled: response.mechanisms.iter().map(|m| m.to_string()).collect(),
+                })
+            }
+            code => {
+                return Err(protocol_error(format!(
+                    "the SASL handshake failed with the error code {code}"
+                )))
+            }
+        }
+
+        let client_nonce = random_string();
+        let client_first_bare = format!(
+            "n={},r={client_nonce}",
+            credentials.username.replace('=', "=3D").replace(',', "=2C")
+        );
+        let server_first = self
+            .sasl_authenticate(format!("n,,{client_first_bare}"))
+            .await?;
+
+        let server_first_fields = parse_scram_fields(&server_first);
+        let (server_nonce, salt, iterations) = match (
+            server_first_fields.get("r"),
+            server_first_fields
+                .get("s")
+                .and_then(|s| BASE64.decode(s).ok()),
+            server_first_fields
+                .get("i")
+                .and_then(|i| i.parse::<u32>().ok()),
+        ) {
+            (Some(nonce), Some(salt), Some(iterations)) if nonce.starts_with(&client_nonce) => {
+                (nonce.to_string(), salt, iterations)
+            }
+            _ => {
+                return Err(protocol_error(format!(
+                    "invalid SCRAM server message: {server_first}"
+                )))
+            }
+        };
+
+        let salted_password = mechanism.salted_password(&credentials.password, &salt, iterations);
+        let client_key = mechanism.hmac(&salted_password, b"Client Key");
+        let stored_key = mechanism.hash(&client_key);
+        let client_final_without_proof = format!("c=biws,r={server_nonce}");
+        let auth_message =
+            format!("{client_first_bare},{server_first},{client_final_without_proof}");
+        let client_signature = mechanism.hmac(&stored_key, auth_message.as_bytes());
+        let client_proof: Vec<u8> = client_key
+            .iter()
+            .zip(client_signature.iter())
+            .map(|(k, s)| k ^ s)
+            .collect();
+
+        let server_final = self
+            .sasl_authenticate(format!(
+                "{client_final_without_proof},p={}",
+                BASE64.encode(client_proof)
+            ))
+            .await?;
+
+        let server_final_fields = parse_scram_fields(&server_final);
+        if let Some(error) = server_final_fields.get("e") {
+            return Err(KafkaBrokerValidation::SaslAuthenticationFailed {
+                reason: error.to_string(),
+            });
+        }
+        let server_key = mechanism.hmac(&salted_password, b"Server Key");
+        let expected_server_signature = mechanism.hmac(&server_key, auth_message.as_bytes());
+        match server_final_fields
+            .get("v")
+            .and_then(|v| BASE64.decode(v).ok())
+        {
+            Some(server_signature) if server_signature == expected_server_signature => Ok(()),
+            _ => Err(KafkaBrokerValidation::SaslAuthenticationFailed {
+                reason: "the broker signature is invalid".to_string(),
+            }),
+        }
+    }
+
+    /// Send a SCRAM message and return the message sent back by the broker
+    async fn sasl_authenticate(
+        &mut self,
+        message: String,
+    ) -> Result<String, KafkaBrokerValidation> {
+        let mut request = SaslAuthenticateRequest::default();
+        request.auth_bytes = Bytes::from(message);
+        let response: SaslAuthenticateResponse = self
+            .send(
+                ApiKey::SaslAuthenticateKey,
+                SASL_AUTHENTICATE_VERSION,
+                &request,
+            )
+            .await?;
+        match response.error_code {
+            0 => String::from_utf8(response.auth_bytes.to_vec())
+                .map_err(|_| protocol_error("the SCRAM server message is not valid UTF-8")),
+            SASL_AUTHENTICATION_FAILED => Err(KafkaBrokerValidation::SaslAuthenticationFailed {
+                reason: response
+                    .error_message
+                    .map(|m| m.to_string())
+                    .unwrap_or_else(|| "invalid credentials".to_string()),
+            }),
+            code => Err(protocol_error(format!(
+                "the SASL authentication failed with the error code {code}"
+            ))),
+        }
+    }
+}
+
+/// Parse a SCRAM message made of comma-separated `key=value` attributes
+fn parse_scram_fields(message: &str) -> std::collections::HashMap<&str, &str> {
+    message
+        .split(',')
+        .filter_map(|field| field.split_once('='))
+        .collect()
+}
+
+fn protocol_error(reason: impl Into<String>) -> KafkaBrokerValidation {
+    KafkaBrokerValidation::ProtocolError {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kafka_protocol::messages::api_versions_response::ApiVersion;
+    use kafka_protocol::protocol::buf::ByteBuf;
+    use tokio::net::TcpListener;
+
+    const USERNAME: &str = "alice";
+    const PASSWORD: &str = "secret";
+    const SALT: &[u8] = b"salt";
+    const ITERATIONS: u32 = 4096;
+
+    /// Behaviour of the fake broker
+    #[derive(Clone)]
+    enum Script {
+        /// Answer the ApiVersions and SASL requests for the given mechanism
+        Broker { mechanism: KafkaSaslMechanism },
+        /// Answer with a TLS alert, as a TLS listener receiving a plaintext request
+        Tls,
+        /// Close the connection without answering
+        Close,
+        /// Accept the connection and never answer
+        Silent,
+    }
+
+    /// Start a fake broker speaking just enough of the Kafka protocol for the validation
+    async fn start_fake_broker(script: Script) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, script.clone()));
+            }
+        });
+        address
+    }
+
+    async fn serve(mut stream: TcpStream, script: Script) {
+        let mechanism = match script {
+            Script::Broker { mechanism } => mechanism,
+            Script::Tls => {
+                let _ = read_request(&mut stream).await;
+                let _ = stream
+                    .write_all(&[0x15, 0x03, 0x03, 0x00, 0x02, 0x02, 0x46])
+                    .await;
+                return;
+            }
+            Script::Close => return,
+            Script::Silent => {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                return;
+            }
+        };
+
+        let mut client_first_bare = String::new();
+        let mut server_first = String::new();
+        while let Some(mut request) = read_request(&mut stream).await {
+            let api_key =
+                ApiKey::try_from(request.peek_bytes(0..2).try_get_i16().unwrap()).unwrap();
+            let version = request.peek_bytes(2..4).try_get_i16().unwrap();
+            let header =
+                RequestHeader::decode(&mut request, api_key.request_header_version(version))
+                    .unwrap();
+            let mut response_header = ResponseHeader::default();
+            response_header.correlation_id = header.correlation_id;
+            let header_version = api_key.response_header_version(version);
+
+            let mut buffer = BytesMut::new();
+            response_header.encode(&mut buffer, header_version).unwrap();
+            match api_key {
+                ApiKey::ApiVersionsKey => {
+                    let mut response = ApiVersionsResponse::default();
+                    for key in [
+                        ApiKey::ApiVersionsKey,
+                        ApiKey::SaslHandshakeKey,
+                        ApiKey::SaslAuthenticateKey,
+                    ] {
+                        response.api_keys.insert(key as i16, ApiVersion::default());
+                    }
+                    response.encode(&mut buffer, version).unwrap();
+                }
+                ApiKey::SaslHandshakeKey => {
+                    let request = SaslHandshakeRequest::decode(&mut request, version).unwrap();
+                    let mut response = SaslHandshakeResponse::default();
+                    response.mechanisms = vec![StrBytes::from_string(mechanism.to_string())];
+                    if request.mechanism.to_string() != mechanism.to_string() {
+                        response.error_code = UNSUPPORTED_SASL_MECHANISM;
+                    }
+                    response.encode(&mut buffer, version).unwrap();
+                }
+                ApiKey::SaslAuthenticateKey => {
+                    let request = SaslAuthenticateRequest::decode(&mut request, version).unwrap();
+                    let message = String::from_utf8(request.auth_bytes.to_vec()).unwrap();
+                    let mut response = SaslAuthenticateResponse::default();
+                    if let Some(bare) = message.strip_prefix("n,,") {
+                        // client first message
+                        client_first_bare = bare.to_string();
+                        let client_nonce = parse_scram_fields(bare)["r"].to_string();
+                        server_first = format!(
+                            "r={client_nonce}server,s={},i={ITERATIONS}",
+                            BASE64.encode(SALT)
+                        );
+                        response.auth_bytes = Bytes::from(server_first.clone());
+                    } else {
+                        // client final message
+                        let (without_proof, proof) = message.rsplit_once(",p=").unwrap();
+                        let auth_message =
+                            format!("{client_first_bare},{server_first},{without_proof}");
+                        let salted_password = mechanism.salted_password(PASSWORD, SALT, ITERATIONS);
+                        let stored_key =
+                            mechanism.hash(&mechanism.hmac(&salted_password, b"Client Key"));
+                        let client_signature = mechanism.hmac(&stored_key, auth_message.as_bytes());
+                        let client_key: Vec<u8> = BASE64
+                            .decode(proof)
+                            .unwrap()
+                            .iter()
+                            .zip(client_signature.iter())
+                            .map(|(p, s)| p ^ s)
+                            .collect();
+                        if mechanism.hash(&client_key) == stored_key {
+                            let server_key = mechanism.hmac(&salted_password, b"Server Key");
+                            let server_signature =
+                                mechanism.hmac(&server_key, auth_message.as_bytes());
+                            response.auth_bytes =
+                                Bytes::from(format!("v={}", BASE64.encode(server_signature)));
+                        } else {
+                            response.error_code = SASL_AUTHENTICATION_FAILED;
+                            response.error_message = Some(StrBytes::from_static_str(
+                                "Authentication failed due to invalid credentials with SASL mechanism",
+                            ));
+                        }
+                    }
+                    response.encode(&mut buffer, version).unwrap();
+                }
+                _ => return,
+            }
+
+            let mut message = (buffer.len() as u32).to_be_bytes().to_vec();
+            message.extend_from_slice(&buffer);
+            if stream.write_all(&message).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    async fn read_request(stream: &mut TcpStream) -> Option<Bytes> {
+        let mut length = [0u8; 4];
+        stream.read_exact(&mut length).await.ok()?;
+        let mut request = vec![0u8; u32::from_be_bytes(length) as usize];
+        stream.read_exact(&mut request).await.ok()?;
+        Some(Bytes::from(request))
+    }
+
+    fn validation_request(sasl: Option<KafkaSaslCredentials>) -> KafkaBrokerValidationRequest {
+        KafkaBrokerValidationRequest::new(Duration::from_secs(2), sasl)
+    }
+
+    fn credentials(mechanism: KafkaSaslMechanism, password: &str) -> KafkaSaslCredentials {
+        KafkaSaslCredentials::new(mechanism, USERNAME.to_string(), password.to_string())
+    }
+
+    #[tokio::test]
+    async fn test_valid_broker() {
+        let address = start_fake_broker(Script::Broker {
+            mechanism: KafkaSaslMechanism::ScramSha256,
+        })
+        .await;
+
+        let validation = validate_kafka_broker(address, &validation_request(None)).await;
+        assert_eq!(
+            validation,
+            KafkaBrokerValidation::Valid {
+                api_keys_count: 3,
+                sasl_mechanism: None
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_valid_sasl_authentication() {
+        for mechanism in [
+            KafkaSaslMechanism::ScramSha256,
+            KafkaSaslMechanism::ScramSha512,
+        ] {
+            let address = start_fake_broker(Script::Broker { mechanism }).await;
+            let request = validation_request(Some(credentials(mechanism, PASSWORD)));
+
+            let validation = validate_kafka_broker(address, &request).await;
+            assert_eq!(
+                validation,
+                KafkaBrokerValidation::Valid {
+                    api_keys_count: 3,
+                    sasl_mechanism: Some(mechanism)
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_sasl_credentials() {
+        let mechanism = KafkaSaslMechanism::ScramSha256;
+        let address = start_fake_broker(Script::Broker { mechanism }).await;
+        let request = validation_request(Some(credentials(mechanism, "wrong")));
+
+        let validation = validate_kafka_broker(address, &request).await;
+        assert!(matches!(
+            validation,
+            KafkaBrokerValidation::SaslAuthenticationFailed { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sasl_mechanism_mismatch() {
+        let address = start_fake_broker(Script::Broker {
+            mechanism: KafkaSaslMechanism::ScramSha512,
+        })
+        .await;
+        let request =
+            validation_request(Some(credentials(KafkaSaslMechanism::ScramSha256, PASSWORD)));
+
+        let validation = validate_kafka_broker(address, &request).await;
+        assert_eq!(
+            validation,
+            KafkaBrokerValidation::SaslMechanismMismatch {
+                requested: KafkaSaslMechanism::ScramSha256,
+                enabled: vec!["SCRAM-SHA-512".to_string()]
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tls_mismatch() {
+        let address = start_fake_broker(Script::Tls).await;
+
+        let validation = validate_kafka_broker(address, &validation_request(None)).await;
+        assert_eq!(validation, KafkaBrokerValidation::TlsMismatch);
+    }
+
+    #[tokio::test]
+    async fn test_connection_closed() {
+        let address = start_fake_broker(Script::Close).await;
+
+        let validation = validate_kafka_broker(address, &validation_request(None)).await;
+        assert_eq!(validation, KafkaBrokerValidation::ConnectionClosed);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let address = start_fake_broker(Script::Silent).await;
+        let request = KafkaBrokerValidationRequest::new(Duration::from_millis(200), None);
+
+        let validation = validate_kafka_broker(address, &request).await;
+        assert_eq!(
+            validation,
+            KafkaBrokerValidation::Timeout { timeout_ms: 200 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_broker() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        drop(listener);
+
+        let validation = validate_kafka_broker(address, &validation_request(None)).await;
+        assert!(matches!(
+            validation,
+            KafkaBrokerValidation::Unreachable { .. }
+        ));
+    }
+
+    #[test]
+    fn test_credentials_are_not_printed() {
+        let credentials = credentials(KafkaSaslMechanism::ScramSha256, PASSWORD);
+        assert!(!format!("{credentials:?}").contains(PASSWORD));
+    }
+}
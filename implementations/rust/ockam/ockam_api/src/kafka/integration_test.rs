@@ -97,6 +97,7 @@ mod test {
                 "127.0.0.1:0",
                 route![listener_address.clone(), outlet_address.clone()],
                 TcpInletOptions::new(),
+                None,
             )
             .await?;
 
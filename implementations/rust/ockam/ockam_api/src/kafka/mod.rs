@@ -1,6 +1,7 @@
 //!This service allows encrypted transparent communication from the kafka producer
 //! to the kafka consumer without any modification in the existing application.
 
+mod broker_validation;
 mod inlet_controller;
 mod integration_test;
 mod length_delimited;
@@ -11,6 +12,10 @@ mod portal_worker;
 mod protocol_aware;
 mod secure_channel_map;
 
+pub use broker_validation::{
+    validate_kafka_broker, KafkaBrokerValidation, KafkaBrokerValidationRequest,
+    KafkaSaslCredentials, KafkaSaslMechanism, DEFAULT_KAFKA_BROKER_VALIDATION_TIMEOUT,
+};
 pub(crate) use inlet_controller::KafkaInletController;
 use ockam::identity::Identifier;
 use ockam_abac::attribute_access_control::{
@@ -127,7 +127,9 @@ impl Worker for KafkaPortalWorker {
                     context.stop_worker(context.address()).await?;
                 }
             }
-            PortalMessage::Ping => self.forward(context, routed_message).await?,
+            PortalMessage::Ping | PortalMessage::PortalPing | PortalMessage::PortalPingReply(_) => {
+                self.forward(context, routed_message).await?
+            }
 
             PortalMessage::Pong => {
                 match self.receiving {
@@ -24,6 +24,7 @@ pub mod authenticator;
 pub mod cli_state;
 pub mod cloud;
 pub mod config;
+pub mod credentials;
 pub mod echoer;
 pub mod enroll;
 pub mod error;
@@ -33,6 +34,7 @@ pub mod minicbor_url;
 pub mod nodes;
 pub mod okta;
 pub mod port_range;
+pub mod redaction;
 pub mod uppercase;
 mod version;
 
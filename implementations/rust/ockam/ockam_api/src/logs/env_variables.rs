@@ -21,7 +21,7 @@ pub(crate) const OCKAM_LOG_MAX_SIZE_MB: &str = "OCKAM_LOG_MAX_SIZE_MB";
 /// Maximum number of log files created per node
 pub(crate) const OCKAM_LOG_MAX_FILES: &str = "OCKAM_LOG_MAX_FILES";
 
-/// Log format. Accepted values, see LogFormat. For example: pretty, json, default
+/// Log format. Accepted values, see LogFormat. For example: pretty, compact, json, default
 pub(crate) const OCKAM_LOG_FORMAT: &str = "OCKAM_LOG_FORMAT";
 
 /// Filter for log messages based on crate names. Accepted values: 'all', 'default', 'comma-separated strings'. For example: ockam_core,ockam_api
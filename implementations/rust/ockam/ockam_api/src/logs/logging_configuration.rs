@@ -120,6 +120,24 @@ impl LoggingConfiguration {
         }
     }
 
+    /// Set a specific log format
+    pub fn set_format(self, format: LogFormat) -> LoggingConfiguration {
+        LoggingConfiguration { format, ..self }
+    }
+
+    /// Enable logging, regardless of the environment variables
+    pub fn set_enabled(self) -> LoggingConfiguration {
+        LoggingConfiguration {
+            enabled: LoggingEnabled::On,
+            ..self
+        }
+    }
+
+    /// Return true if the log records are structured records written to the console
+    pub fn is_structured_on_console(&self) -> bool {
+        self.is_enabled() && self.log_dir.is_none() && self.format == LogFormat::Json
+    }
+
     /// Set some specific crates
     pub fn set_crates(self, crates: &[&str]) -> LoggingConfiguration {
         LoggingConfiguration {
@@ -300,7 +318,7 @@ pub fn logging_enabled() -> ockam_core::Result<LoggingEnabled> {
 fn log_level(preferred_log_level: Option<Level>) -> ockam_core::Result<Level> {
     let is_legacy_variable_set = is_set::<LevelVar>(OCKAM_LOG)?;
     let env_variable = if is_legacy_variable_set {
-        println!("The OCKAM_LOG variable is deprecated. Please use: OCKAM_LOGGING=true OCKAM_LOG_LEVEL=trace (with the desired level) instead.");
+        eprintln!("The OCKAM_LOG variable is deprecated. Please use: OCKAM_LOGGING=true OCKAM_LOG_LEVEL=trace (with the desired level) instead.");
         OCKAM_LOG
     } else {
         OCKAM_LOG_LEVEL
@@ -72,6 +72,7 @@ pub enum Colored {
 pub enum LogFormat {
     Default,
     Pretty,
+    Compact,
     Json,
 }
 
@@ -79,6 +80,7 @@ impl FromString for LogFormat {
     fn from_string(s: &str) -> ockam_core::Result<Self> {
         match s {
             "pretty" => Ok(LogFormat::Pretty),
+            "compact" => Ok(LogFormat::Compact),
             "json" => Ok(LogFormat::Json),
             _ => Ok(LogFormat::Default),
         }
@@ -90,6 +92,7 @@ impl std::fmt::Display for LogFormat {
         match self {
             LogFormat::Default => write!(f, "default"),
             LogFormat::Pretty => write!(f, "pretty"),
+            LogFormat::Compact => write!(f, "compact"),
             LogFormat::Json => write!(f, "json"),
         }
     }
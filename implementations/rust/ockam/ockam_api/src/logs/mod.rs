@@ -8,6 +8,7 @@
 //      - In a log file for a background node.
 //      - In the console for other commands.
 //   - If OCKAM_TRACING=true then, _additionally_, the spans and logs messages are sent to an OpenTelemetry collector.
+//   - With the `telemetry` feature, the metrics recorded by transports and secure channels are sent to the same collector.
 ///
 mod current_span;
 mod default_values;
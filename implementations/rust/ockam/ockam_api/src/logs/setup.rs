@@ -6,6 +6,12 @@ use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::export::logs::LogExporter;
 use opentelemetry_sdk::export::trace::SpanExporter;
 use opentelemetry_sdk::logs::{BatchLogProcessor, LoggerProvider};
+#[cfg(feature = "telemetry")]
+use opentelemetry_sdk::metrics::exporter::PushMetricsExporter;
+#[cfg(feature = "telemetry")]
+use opentelemetry_sdk::metrics::reader::{DefaultAggregationSelector, DefaultTemporalitySelector};
+#[cfg(feature = "telemetry")]
+use opentelemetry_sdk::metrics::{PeriodicReader, SdkMeterProvider};
 use opentelemetry_sdk::propagation::TraceContextPropagator;
 use opentelemetry_sdk::trace::{BatchConfig, BatchConfigBuilder, BatchSpanProcessor};
 use opentelemetry_sdk::{self as sdk};
@@ -26,6 +32,8 @@ use tracing_subscriber::{fmt, layer::SubscriberExt, registry};
 
 use ockam_node::Executor;
 
+#[cfg(feature = "telemetry")]
+use crate::journeys::APPLICATION_EVENT_NODE_NAME;
 use crate::journeys::APP_NAME;
 use crate::logs::tracing_guard::TracingGuard;
 use crate::logs::{ExportingConfiguration, GlobalErrorHandler, LoggingConfiguration};
@@ -45,27 +53,79 @@ impl LoggingTracing {
         app_name: &str,
         node_name: Option<String>,
     ) -> TracingGuard {
-        if exporting_configuration.is_enabled() && logging_configuration.is_enabled() {
-            // set-up logging and tracing
-            Self::setup_with_exporters(
-                create_span_exporter(exporting_configuration),
-                create_log_exporter(exporting_configuration),
-                logging_configuration,
-                exporting_configuration,
-                app_name,
-                node_name,
-            )
-        } else if exporting_configuration.is_enabled() {
-            Self::setup_tracing_only(
-                create_span_exporter(exporting_configuration),
-                logging_configuration,
+        let tracing_guard =
+            if exporting_configuration.is_enabled() && logging_configuration.is_enabled() {
+                // set-up logging and tracing
+                Self::setup_with_exporters(
+                    create_span_exporter(exporting_configuration),
+                    create_log_exporter(exporting_configuration),
+                    logging_configuration,
+                    exporting_configuration,
+                    app_name,
+                    node_name.clone(),
+                )
+            } else if exporting_configuration.is_enabled() {
+                Self::setup_tracing_only(
+                    create_span_exporter(exporting_configuration),
+                    logging_configuration,
+                    exporting_configuration,
+                    app_name,
+                    node_name.clone(),
+                )
+            } else {
+                Self::setup_local_logging_only(logging_configuration)
+            };
+
+        // metrics are exported with the same pipeline as spans
+        #[cfg(feature = "telemetry")]
+        if exporting_configuration.is_enabled() {
+            let meter_provider = Self::setup_metrics(
+                create_metrics_exporter(exporting_configuration),
                 exporting_configuration,
                 app_name,
                 node_name,
-            )
-        } else {
-            Self::setup_local_logging_only(logging_configuration)
+            );
+            return tracing_guard.with_meter_provider(meter_provider);
         }
+        tracing_guard
+    }
+
+    /// Setup the export of the metrics recorded by the transports and secure channels
+    /// and return the meter provider, which can be used to force the export of metrics.
+    ///
+    /// The node name, if any, is set as a resource attribute on all the metrics.
+    /// This must be called before starting nodes since the instruments are created
+    /// from the global meter provider when the workers start.
+    #[cfg(feature = "telemetry")]
+    pub fn setup_metrics<M: PushMetricsExporter>(
+        metrics_exporter: M,
+        exporting_configuration: &ExportingConfiguration,
+        app_name: &str,
+        node_name: Option<String>,
+    ) -> SdkMeterProvider {
+        let app = app_name.to_string();
+        let export_interval = exporting_configuration.span_export_scheduled_delay();
+        let export_timeout = exporting_configuration.span_export_timeout();
+        Executor::execute_future(async move {
+            let reader = PeriodicReader::builder(metrics_exporter, sdk::runtime::Tokio)
+                .with_interval(export_interval)
+                .with_timeout(export_timeout)
+                .build();
+            let resource = match node_name {
+                Some(node_name) => make_resource(app).merge(&Resource::new(vec![KeyValue::new(
+                    APPLICATION_EVENT_NODE_NAME.clone(),
+                    node_name,
+                )])),
+                None => make_resource(app),
+            };
+            let meter_provider = SdkMeterProvider::builder()
+                .with_reader(reader)
+                .with_resource(resource)
+                .build();
+            global::set_meter_provider(meter_provider.clone());
+            meter_provider
+        })
+        .expect("Failed to build the meter provider")
     }
 
     /// Setup the tracing and logging with some specific exporters
@@ -106,6 +166,7 @@ impl LoggingTracing {
 
         let result = match logging_configuration.format() {
             LogFormat::Pretty => layers.with(appender.pretty()).try_init(),
+            LogFormat::Compact => layers.with(appender.compact()).try_init(),
             LogFormat::Json => layers.with(appender.json()).try_init(),
             LogFormat::Default => layers.with(appender).try_init(),
         };
@@ -127,6 +188,7 @@ impl LoggingTracing {
             let layers = registry().with(logging_configuration.env_filter());
             let result = match logging_configuration.format() {
                 LogFormat::Pretty => layers.with(appender.pretty()).try_init(),
+                LogFormat::Compact => layers.with(appender.compact()).try_init(),
                 LogFormat::Json => layers.with(appender.json()).try_init(),
                 LogFormat::Default => layers.with(appender).try_init(),
             };
@@ -215,6 +277,30 @@ fn create_span_exporter(
     .expect("can't create a span exporter")
 }
 
+/// Create a metrics exporter
+// Metrics are sent to an OpenTelemetry collector using gRPC
+#[cfg(feature = "telemetry")]
+fn create_metrics_exporter(
+    exporting_configuration: &ExportingConfiguration,
+) -> opentelemetry_otlp::MetricsExporter {
+    let metrics_export_timeout = exporting_configuration.span_export_timeout();
+    let endpoint = exporting_configuration.opentelemetry_endpoint().to_string();
+
+    Executor::execute_future(async move {
+        opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(endpoint)
+            .with_timeout(metrics_export_timeout)
+            .with_metadata(get_otlp_headers())
+            .build_metrics_exporter(
+                Box::new(DefaultAggregationSelector::new()),
+                Box::new(DefaultTemporalitySelector::new()),
+            )
+            .expect("failed to create the metrics exporter")
+    })
+    .expect("can't create a metrics exporter")
+}
+
 /// Create the tracing layer for OpenTelemetry
 /// Spans are exported in batches
 fn create_opentelemetry_tracing_layer<
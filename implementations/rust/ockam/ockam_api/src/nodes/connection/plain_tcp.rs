@@ -3,6 +3,7 @@ use crate::nodes::connection::{Changes, ConnectionBuilder, Instantiator};
 use crate::{multiaddr_to_route, route_to_multiaddr};
 use std::sync::Arc;
 
+use crate::nodes::service::{release_on_cancel, CreatedResource};
 use crate::nodes::NodeManager;
 use ockam_core::{async_trait, Error, Route};
 use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Tcp};
@@ -60,6 +61,9 @@ impl Instantiator for PlainTcpInstantiator {
             .tcp_connection
             .take()
             .ok_or_else(|| ApiError::core("TCP connection should be set"))?;
+        release_on_cancel(CreatedResource::TcpConnection(
+            tcp_connection.sender_address().clone(),
+        ));
 
         Ok(Changes {
             current_multiaddr,
@@ -1,10 +1,14 @@
 pub(crate) mod connection;
 pub mod models;
 pub mod registry;
+pub mod relay_chain;
 pub mod service;
+pub mod storage;
+pub mod topology;
 
 pub use service::background_node_client::*;
 pub use service::in_memory_node::*;
+pub use service::plugins::*;
 pub use service::policy::*;
 /// The main node-manager service running on remote nodes
 pub use service::{IdentityOverride, NodeManager, NodeManagerWorker};
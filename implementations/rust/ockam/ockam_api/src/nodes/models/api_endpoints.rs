@@ -0,0 +1,36 @@
+//! Endpoints of the node manager API
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+/// Endpoint of the node manager API, with the statistics of the requests it handled
+/// since the node started
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ApiEndpoint {
+    #[n(1)] pub method: String,
+    /// Path pattern of the requests. The path parameters are prefixed with ':'
+    #[n(2)] pub path: String,
+    /// Name of the handler of the requests
+    #[n(3)] pub name: String,
+    #[n(4)] pub requests: u64,
+    /// Number of requests which failed or were answered with an error status
+    #[n(5)] pub errors: u64,
+    /// Average time spent handling a request, in microseconds
+    #[n(6)] pub average_latency_us: u64,
+}
+
+/// Response body for listing the endpoints of the node manager API
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ApiEndpointList {
+    #[n(1)] pub list: Vec<ApiEndpoint>,
+}
+
+impl ApiEndpointList {
+    pub fn new(list: Vec<ApiEndpoint>) -> Self {
+        Self { list }
+    }
+}
@@ -0,0 +1,170 @@
+//! Node manager API version and capabilities
+
+use std::fmt::{Display, Formatter};
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+/// Version of the node manager API implemented by this crate.
+///
+/// The minor version is incremented when requests or capabilities are added,
+/// the major version when existing requests change in an incompatible way.
+pub const NODE_API_VERSION: ApiVersion = ApiVersion::new(0, 27, 0);
+
+/// Names of the optional features supported by a node manager API
+pub struct NodeCapability;
+
+impl NodeCapability {
+    /// Policies can be set for resource types, and not only for resources
+    pub const POLICIES_V2: &'static str = "policies-v2";
+    /// Relays can be re-created at failover addresses
+    pub const RELAY_FAILOVER: &'static str = "relay-failover";
+    /// The size of the messages handled by the node workers can be recorded
+    pub const TRAFFIC_ACCOUNTING: &'static str = "traffic-accounting";
+    /// The node state can be exported for diagnostics purposes
+    pub const DIAGNOSTICS: &'static str = "diagnostics";
+    /// Workers are listed with the type of worker owning their addresses
+    pub const WORKER_OWNERS: &'static str = "worker-owners";
+    /// The bandwidth used by inlets and outlets can be limited
+    pub const PORTAL_BANDWIDTH: &'static str = "portal-bandwidth";
+    /// Application-defined services can be started with a configuration
+    pub const SERVICE_PLUGINS: &'static str = "service-plugins";
+    /// Secure channel listeners can require attributes in the credentials of the initiators
+    pub const REQUIRED_ATTRIBUTES: &'static str = "required-attributes";
+    /// Outlets can establish connections to their peer in advance
+    pub const OUTLET_CONNECTION_POOL: &'static str = "outlet-connection-pool";
+    /// Inlets can be created with the name of a project relay, instead of a full route
+    pub const INLET_VIA_RELAY: &'static str = "inlet-via-relay";
+    /// The events emitted by the node components can be queried
+    pub const NODE_EVENTS: &'static str = "node-events";
+    /// Secure channel listeners can limit the rate of messages accepted from each initiator
+    pub const LISTENER_RATE_LIMIT: &'static str = "listener-rate-limit";
+    /// The individual connections of an inlet can be listed and closed
+    pub const PORTAL_CONNECTIONS: &'static str = "portal-connections";
+    /// The keys stored in the vault of the node can be listed with the data referencing them
+    pub const VAULT_KEYS: &'static str = "vault-keys";
+    /// The authority and credential retriever of a running node can be read and updated
+    pub const TRUST_OPTIONS: &'static str = "trust-options";
+    /// The outcome and timings of the components started with the node can be queried
+    pub const STARTUP_REPORT: &'static str = "startup-report";
+    /// Relays can be renamed
+    pub const RELAY_RENAME: &'static str = "relay-rename";
+    /// Relays can restrict the services reachable through them
+    pub const RELAY_SERVICE_FILTER: &'static str = "relay-service-filter";
+    /// Relay aliases can be released, and their owners listed
+    pub const RELAY_ALIAS_OWNERSHIP: &'static str = "relay-alias-ownership";
+    /// Raw messages can be sent to the workers of the node by the developer commands
+    pub const INJECT_MESSAGE: &'static str = "inject-message";
+    /// The endpoints of the node manager API can be listed with their request statistics
+    pub const API_ENDPOINTS: &'static str = "api-endpoints";
+    /// Inlets can check the health of the outlet before accepting local connections
+    pub const INLET_PRE_CHECK: &'static str = "inlet-pre-check";
+    /// Outlets can pass the identifier of the peer of each connection to their target
+    pub const OUTLET_IDENTITY_FORWARDING: &'static str = "outlet-identity-forwarding";
+    /// Long-running requests can be cancelled while they are in flight
+    pub const REQUEST_CANCELLATION: &'static str = "request-cancellation";
+    /// The secure channels, relays, portals and transports of a node can be described as a graph
+    pub const NODE_TOPOLOGY: &'static str = "node-topology";
+    /// Inlets can rewrite the HTTP requests sent by their clients
+    pub const INLET_HTTP_REWRITE: &'static str = "inlet-http-rewrite";
+    /// Services can be advertised, and listed by remote nodes through a discovery service
+    pub const SERVICE_DISCOVERY: &'static str = "service-discovery";
+    /// Inlets and outlets can stop accepting new connections without being deleted
+    pub const PORTAL_PAUSE: &'static str = "portal-pause";
+
+    /// All the capabilities supported by this version of the node manager API
+    pub fn all() -> Vec<String> {
+        [
+            Self::POLICIES_V2,
+            Self::RELAY_FAILOVER,
+            Self::TRAFFIC_ACCOUNTING,
+            Self::DIAGNOSTICS,
+            Self::WORKER_OWNERS,
+            Self::PORTAL_BANDWIDTH,
+            Self::SERVICE_PLUGINS,
+            Self::REQUIRED_ATTRIBUTES,
+            Self::OUTLET_CONNECTION_POOL,
+            Self::INLET_VIA_RELAY,
+            Self::NODE_EVENTS,
+            Self::LISTENER_RATE_LIMIT,
+            Self::PORTAL_CONNECTIONS,
+            Self::VAULT_KEYS,
+            Self::TRUST_OPTIONS,
+            Self::STARTUP_REPORT,
+            Self::RELAY_RENAME,
+            Self::RELAY_SERVICE_FILTER,
+            Self::RELAY_ALIAS_OWNERSHIP,
+            Self::INJECT_MESSAGE,
+            Self::API_ENDPOINTS,
+            Self::INLET_PRE_CHECK,
+            Self::OUTLET_IDENTITY_FORWARDING,
+            Self::REQUEST_CANCELLATION,
+            Self::NODE_TOPOLOGY,
+            Self::INLET_HTTP_REWRITE,
+            Self::SERVICE_DISCOVERY,
+            Self::PORTAL_PAUSE,
+        ]
+        .iter()
+        .map(|c| c.to_string())
+        .collect()
+    }
+}
+
+/// Semantic version of the node manager API
+#[derive(Debug, Clone, Copy, Decode, Encode, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ApiVersion {
+    #[n(1)] pub major: u16,
+    #[n(2)] pub minor: u16,
+    #[n(3)] pub patch: u16,
+}
+
+impl ApiVersion {
+    pub const fn new(major: u16, minor: u16, patch: u16) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl Display for ApiVersion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Response body to a `GET /node/api_version` request
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeApiInfo {
+    #[n(1)] pub version: ApiVersion,
+    #[n(2)] pub capabilities: Vec<String>,
+}
+
+impl NodeApiInfo {
+    /// Version and capabilities of the node manager API implemented by this crate
+    pub fn current() -> Self {
+        Self {
+            version: NODE_API_VERSION,
+            capabilities: NodeCapability::all(),
+        }
+    }
+
+    /// Version and capabilities assumed for nodes created before the
+    /// `GET /node/api_version` request was introduced
+    pub fn legacy() -> Self {
+        Self {
+            version: ApiVersion::new(0, 3, 0),
+            capabilities: vec![],
+        }
+    }
+
+    /// Return true if the node supports a given capability
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|c| c == capability)
+    }
+}
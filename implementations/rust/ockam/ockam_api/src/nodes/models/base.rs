@@ -1,11 +1,13 @@
 //! Nodemanager API types
 
 use minicbor::{Decode, Encode};
+use serde::Serialize;
+use std::fmt::{self, Display};
 
 ///////////////////-!  RESPONSE BODIES
 
 /// Response body for a node status
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct NodeStatus {
@@ -13,6 +15,7 @@ pub struct NodeStatus {
     #[n(2)] pub status: String,
     #[n(3)] pub workers: u32,
     #[n(4)] pub pid: i32,
+    #[n(5)] pub credentials: Option<CredentialsStatus>,
 }
 
 impl NodeStatus {
@@ -27,6 +30,72 @@ impl NodeStatus {
             status: status.into(),
             workers,
             pid,
+            credentials: None,
         }
     }
+
+    pub fn with_credentials(mut self, credentials: CredentialsStatus) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Return true if the node is running without the credential it was expected to pre-fetch
+    pub fn is_degraded(&self) -> bool {
+        self.credentials
+            .as_ref()
+            .map(|c| c.state == CredentialsState::Degraded)
+            .unwrap_or(false)
+    }
+}
+
+/// State of the credential pre-fetched when the node starts
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "lowercase")]
+pub enum CredentialsState {
+    /// The credential is retrieved lazily, when it is first needed
+    #[n(0)] Disabled,
+    /// The credential is being retrieved
+    #[n(1)] Pending,
+    /// The credential has been retrieved
+    #[n(2)] Ready,
+    /// The credential could not be retrieved in time, the node runs without it
+    #[n(3)] Degraded,
+}
+
+impl Display for CredentialsState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Disabled => "disabled",
+            Self::Pending => "pending",
+            Self::Ready => "ready",
+            Self::Degraded => "degraded",
+        })
+    }
+}
+
+/// Outcome of the credential pre-fetch, reported in the node status
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CredentialsStatus {
+    #[n(1)] pub state: CredentialsState,
+    #[n(2)] pub attempts: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(3)] pub last_error: Option<String>,
+}
+
+impl CredentialsStatus {
+    pub fn new(state: CredentialsState) -> Self {
+        Self {
+            state,
+            attempts: 0,
+            last_error: None,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.state == CredentialsState::Ready
+    }
 }
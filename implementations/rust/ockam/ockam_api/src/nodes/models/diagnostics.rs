@@ -0,0 +1,27 @@
+//! Diagnostics request/response types
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::portal::{InletStatus, OutletStatus};
+use crate::nodes::models::relay::RelayInfo;
+
+/// Response body gathering the state of a node for diagnostics purposes
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeDiagnostics {
+    #[n(1)] pub status: NodeStatus,
+    /// Version of the latest migration applied to the node database
+    #[n(2)] pub schema_version: Option<i64>,
+    /// Migrations not applied yet to the node database
+    #[n(3)] pub pending_migrations: Vec<String>,
+    #[n(4)] pub relays: Vec<RelayInfo>,
+    #[n(5)] pub inlets: Vec<InletStatus>,
+    #[n(6)] pub outlets: Vec<OutletStatus>,
+    /// Addresses of the secure channels encryptors
+    #[n(7)] pub secure_channels: Vec<String>,
+    /// Addresses of the secure channel listeners
+    #[n(8)] pub secure_channel_listeners: Vec<String>,
+}
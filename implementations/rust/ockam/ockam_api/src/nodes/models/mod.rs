@@ -2,13 +2,22 @@
 ///
 /// This module is only a type facade and should not have any logic of
 /// its own
+pub mod api_endpoints;
+pub mod api_version;
 pub mod base;
 pub mod credentials;
+pub mod diagnostics;
 pub mod flow_controls;
+pub mod node_events;
 pub mod policies;
 pub mod portal;
 pub mod relay;
 pub mod secure_channel;
 pub mod services;
+pub mod startup;
+pub mod topology;
+pub mod traffic;
 pub mod transport;
+pub mod trust;
+pub mod vault;
 pub mod workers;
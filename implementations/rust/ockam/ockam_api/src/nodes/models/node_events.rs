@@ -0,0 +1,48 @@
+//! Node events request/response types
+
+use std::collections::BTreeMap;
+
+use minicbor::{Decode, Encode};
+use ockam::identity::TimestampInSeconds;
+use ockam_node::NodeEvent;
+use serde::Serialize;
+
+/// Request body to query the events emitted by a node
+#[derive(Debug, Clone, Default, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct GetNodeEvents {
+    /// Only return the events emitted at, or after, this time
+    #[n(1)] pub since: Option<TimestampInSeconds>,
+    /// Only return the events of this kind, for example "policy-denied"
+    #[n(2)] pub kind: Option<String>,
+}
+
+impl GetNodeEvents {
+    pub fn new(since: Option<TimestampInSeconds>, kind: Option<String>) -> Self {
+        Self { since, kind }
+    }
+}
+
+/// Event stored in the event log of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeEventRecord {
+    #[n(1)] pub kind: String,
+    /// Worker address, socket address or resource name concerned by the event
+    #[n(2)] pub subject: String,
+    #[n(3)] pub details: BTreeMap<String, String>,
+    #[n(4)] pub created_at: TimestampInSeconds,
+}
+
+impl From<NodeEvent> for NodeEventRecord {
+    fn from(event: NodeEvent) -> Self {
+        Self {
+            kind: event.kind().to_string(),
+            subject: event.subject().to_string(),
+            details: event.details().clone(),
+            created_at: TimestampInSeconds(event.timestamp()),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 //! Inlets and outlet request/response types
 
+use std::fmt::{Display, Formatter};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -10,6 +11,10 @@ use ockam::route;
 use ockam_abac::Expr;
 use ockam_core::{Address, IncomingAccessControl, Route};
 use ockam_multiaddr::MultiAddr;
+use ockam_transport_tcp::{
+    HttpRewrite, TcpInletPreCheck, TcpOutletConnectionPool, TcpOutletIdentityFormat,
+    TcpPortalBandwidthLimiter, TcpPortalConnectionInfo, TcpPortalHealth, TcpPortalPause,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::error::ApiError;
@@ -47,6 +52,17 @@ pub struct CreateInlet {
     #[n(8)] pub(crate) policy_expression: Option<Expr>,
     /// Create the inlet and wait for the outlet to connect
     #[n(9)] pub(crate) wait_connection: bool,
+    /// The maximum number of bytes per second read from the inlet connections
+    #[n(10)] pub(crate) bandwidth_limit: Option<u64>,
+    /// The name of a relay of the default project.
+    /// If set, `outlet_addr` is the name of the outlet service, and the node resolves the full
+    /// route to the outlet through that relay
+    #[n(11)] pub(crate) via: Option<String>,
+    /// Check the health of the outlet and its target when a local client connects,
+    /// and reset the connection if they can't be reached. Not set by older clients
+    #[n(12)] pub(crate) pre_check: Option<bool>,
+    /// Rewrite the HTTP requests sent by the clients of the inlet
+    #[n(13)] pub(crate) http_rewrite: Option<InletHttpRewrite>,
 }
 
 impl CreateInlet {
@@ -68,6 +84,10 @@ impl CreateInlet {
             wait_for_outlet_duration: None,
             policy_expression: None,
             wait_connection,
+            bandwidth_limit: None,
+            via: None,
+            pre_check: None,
+            http_rewrite: None,
         }
     }
 
@@ -90,6 +110,10 @@ impl CreateInlet {
             wait_for_outlet_duration: None,
             policy_expression: None,
             wait_connection,
+            bandwidth_limit: None,
+            via: None,
+            pre_check: None,
+            http_rewrite: None,
         }
     }
 
@@ -101,6 +125,22 @@ impl CreateInlet {
         self.policy_expression = Some(expression);
     }
 
+    pub fn set_bandwidth_limit(&mut self, bytes_per_second: u64) {
+        self.bandwidth_limit = Some(bytes_per_second);
+    }
+
+    pub fn set_via(&mut self, relay: String) {
+        self.via = Some(relay);
+    }
+
+    pub fn set_pre_check(&mut self, pre_check: bool) {
+        self.pre_check = Some(pre_check);
+    }
+
+    pub fn set_http_rewrite(&mut self, http_rewrite: InletHttpRewrite) {
+        self.http_rewrite = Some(http_rewrite);
+    }
+
     pub fn listen_addr(&self) -> String {
         self.listen_addr.clone()
     }
@@ -128,6 +168,48 @@ impl CreateInlet {
     pub fn wait_for_outlet_duration(&self) -> Option<Duration> {
         self.wait_for_outlet_duration
     }
+
+    pub fn bandwidth_limit(&self) -> Option<u64> {
+        self.bandwidth_limit
+    }
+
+    pub fn pre_check(&self) -> bool {
+        self.pre_check.unwrap_or(false)
+    }
+
+    pub fn via(&self) -> Option<&str> {
+        self.via.as_deref()
+    }
+
+    pub fn http_rewrite(&self) -> Option<&InletHttpRewrite> {
+        self.http_rewrite.as_ref()
+    }
+}
+
+/// Rewriting of the HTTP requests sent by the clients of an inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletHttpRewrite {
+    /// The value replacing the Host header of each request
+    #[n(1)] pub host: Option<String>,
+    /// The headers added to each request, as names and values.
+    /// The headers with the same names sent by the clients are removed
+    #[n(2)] pub headers: Vec<(String, String)>,
+}
+
+impl InletHttpRewrite {
+    pub fn new(host: Option<String>, headers: Vec<(String, String)>) -> Self {
+        Self { host, headers }
+    }
+}
+
+impl TryFrom<InletHttpRewrite> for HttpRewrite {
+    type Error = ockam_core::Error;
+
+    fn try_from(http_rewrite: InletHttpRewrite) -> Result<Self, Self::Error> {
+        HttpRewrite::new(http_rewrite.host, http_rewrite.headers)
+    }
 }
 
 /// Request body to create an outlet
@@ -146,6 +228,12 @@ pub struct CreateOutlet {
     /// If not set, the policy set for the [TCP outlet resource type](ockam_abac::ResourceType::TcpOutlet)
     /// will be used.
     #[n(4)] pub policy_expression: Option<Expr>,
+    /// The maximum number of bytes per second read from the outlet connections
+    #[n(5)] pub bandwidth_limit: Option<u64>,
+    /// The number of connections to the outlet peer established in advance
+    #[n(6)] pub connection_pool_size: Option<usize>,
+    /// How the identifier of the peer of each connection is passed to the outlet peer
+    #[n(7)] pub identity_forwarding: Option<OutletIdentityForwarding>,
 }
 
 impl CreateOutlet {
@@ -159,12 +247,92 @@ impl CreateOutlet {
             worker_addr,
             reachable_from_default_secure_channel,
             policy_expression: None,
+            bandwidth_limit: None,
+            connection_pool_size: None,
+            identity_forwarding: None,
         }
     }
 
     pub fn set_policy_expression(&mut self, expression: Expr) {
         self.policy_expression = Some(expression);
     }
+
+    pub fn set_bandwidth_limit(&mut self, bytes_per_second: u64) {
+        self.bandwidth_limit = Some(bytes_per_second);
+    }
+
+    pub fn set_connection_pool_size(&mut self, size: usize) {
+        self.connection_pool_size = Some(size);
+    }
+
+    pub fn set_identity_forwarding(&mut self, identity_forwarding: OutletIdentityForwarding) {
+        self.identity_forwarding = Some(identity_forwarding);
+    }
+}
+
+/// Header used by an outlet to pass the identifier of the peer of each connection,
+/// authenticated by a secure channel, to the outlet peer
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq, Eq)]
+#[rustfmt::skip]
+pub enum OutletIdentityForwarding {
+    /// An HTTP header with the given name, added to the first request of each connection
+    #[n(1)] HttpHeader(#[n(1)] String),
+    /// A PROXY protocol v2 header, sent before the payload of each connection
+    #[n(2)] ProxyProtocolV2,
+}
+
+impl Display for OutletIdentityForwarding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OutletIdentityForwarding::HttpHeader(name) => write!(f, "HTTP header {name}"),
+            OutletIdentityForwarding::ProxyProtocolV2 => f.write_str("PROXY protocol v2"),
+        }
+    }
+}
+
+impl From<OutletIdentityForwarding> for TcpOutletIdentityFormat {
+    fn from(identity_forwarding: OutletIdentityForwarding) -> Self {
+        match identity_forwarding {
+            OutletIdentityForwarding::HttpHeader(name) => TcpOutletIdentityFormat::HttpHeader(name),
+            OutletIdentityForwarding::ProxyProtocolV2 => TcpOutletIdentityFormat::ProxyProtocolV2,
+        }
+    }
+}
+
+/// Request body to change the bandwidth limit of an inlet or an outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetBandwidthLimit {
+    /// The maximum number of bytes per second, no limit if not set
+    #[n(1)] pub limit: Option<u64>,
+}
+
+impl SetBandwidthLimit {
+    pub fn new(limit: Option<u64>) -> Self {
+        Self { limit }
+    }
+}
+
+/// Request body to stop accepting new connections on an inlet or an outlet
+#[derive(Clone, Debug, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PausePortal {
+    /// If set, the connections still open after this number of milliseconds are closed
+    #[n(1)] pub drain_after_ms: Option<u64>,
+}
+
+impl PausePortal {
+    pub fn new(drain_after: Option<Duration>) -> Self {
+        Self {
+            drain_after_ms: drain_after.map(|d| d.as_millis() as u64),
+        }
+    }
+
+    pub fn drain_after(&self) -> Option<Duration> {
+        self.drain_after_ms.map(Duration::from_millis)
+    }
 }
 
 /// Response body when interacting with a portal endpoint
@@ -180,6 +348,15 @@ pub struct InletStatus {
     #[n(5)] pub outlet_route: Option<String>,
     #[n(6)] pub status: ConnectionStatus,
     #[n(7)] pub outlet_addr: String,
+    /// The maximum number of bytes per second read from the inlet connections
+    #[n(8)] pub bandwidth_limit: Option<u64>,
+    /// The number of bytes per second currently read from the inlet connections
+    #[n(9)] pub throughput: Option<u64>,
+    /// The last known health of the outlet and its target, if the inlet checks them
+    /// before accepting local connections
+    #[n(10)] pub far_side: Option<InletFarSideStatus>,
+    /// Time of the pause, in seconds since the Unix epoch, if the inlet refuses new connections
+    #[n(11)] pub paused_at: Option<u64>,
 }
 
 impl InletStatus {
@@ -201,6 +378,61 @@ impl InletStatus {
             outlet_route: outlet_route.into(),
             status,
             outlet_addr: outlet_addr.into(),
+            bandwidth_limit: None,
+            throughput: None,
+            far_side: None,
+            paused_at: None,
+        }
+    }
+
+    /// Add the bandwidth limit and the current throughput of the inlet
+    pub fn with_bandwidth(mut self, bandwidth: &TcpPortalBandwidthLimiter) -> Self {
+        self.bandwidth_limit = bandwidth.limit();
+        self.throughput = Some(bandwidth.throughput());
+        self
+    }
+
+    /// Add the last known health of the far side, if the inlet checks it
+    pub fn with_pre_check(mut self, pre_check: Option<&TcpInletPreCheck>) -> Self {
+        self.far_side = pre_check.map(InletFarSideStatus::from);
+        self
+    }
+
+    /// Add the time of the pause, if the inlet refuses new connections
+    pub fn with_pause(mut self, pause: &TcpPortalPause) -> Self {
+        self.paused_at = pause.paused_at();
+        self
+    }
+}
+
+/// Health of the far side of an inlet, as of the last local connection
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InletFarSideStatus {
+    /// `healthy`, `upstream-down`, `outlet-unreachable`,
+    /// or `unknown` if no local client connected yet
+    #[n(1)] pub health: String,
+    /// Time of the last check, in seconds since the Unix epoch
+    #[n(2)] pub checked_at: Option<u64>,
+}
+
+impl From<&TcpInletPreCheck> for InletFarSideStatus {
+    fn from(pre_check: &TcpInletPreCheck) -> Self {
+        match pre_check.last_status() {
+            Some(status) => Self {
+                health: match status.health {
+                    TcpPortalHealth::Healthy => "healthy",
+                    TcpPortalHealth::UpstreamDown => "upstream-down",
+                    TcpPortalHealth::OutletUnreachable => "outlet-unreachable",
+                }
+                .to_string(),
+                checked_at: Some(status.checked_at),
+            },
+            None => Self {
+                health: "unknown".to_string(),
+                checked_at: None,
+            },
         }
     }
 }
@@ -214,6 +446,46 @@ pub struct OutletStatus {
     #[n(2)] pub worker_addr: Address,
     /// An optional status payload
     #[n(3)] pub payload: Option<String>,
+    /// The maximum number of bytes per second read from the outlet connections
+    #[n(4)] pub bandwidth_limit: Option<u64>,
+    /// The number of bytes per second currently read from the outlet connections
+    #[n(5)] pub throughput: Option<u64>,
+    /// The statistics of the connections established in advance, if the outlet uses a pool
+    #[n(6)] pub connection_pool: Option<OutletConnectionPoolStatus>,
+    /// How the identifier of the peer of each connection is passed to the outlet peer
+    #[n(7)] pub identity_forwarding: Option<OutletIdentityForwarding>,
+    /// Time of the pause, in seconds since the Unix epoch, if the outlet refuses new connections
+    #[n(8)] pub paused_at: Option<u64>,
+}
+
+/// Statistics of the connections established in advance by an outlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct OutletConnectionPoolStatus {
+    /// The maximum number of connections established in advance
+    #[n(1)] pub size: usize,
+    /// The number of connections ready to be used
+    #[n(2)] pub available: usize,
+    /// The number of portal connections which used a connection from the pool
+    #[n(3)] pub hits: u64,
+    /// The number of portal connections which had to connect to the peer
+    #[n(4)] pub misses: u64,
+    /// The number of connections closed because they were stale
+    #[n(5)] pub evicted: u64,
+}
+
+impl From<&TcpOutletConnectionPool> for OutletConnectionPoolStatus {
+    fn from(pool: &TcpOutletConnectionPool) -> Self {
+        let stats = pool.stats();
+        Self {
+            size: stats.size,
+            available: stats.available,
+            hits: stats.hits,
+            misses: stats.misses,
+            evicted: stats.evicted,
+        }
+    }
 }
 
 impl OutletStatus {
@@ -226,9 +498,42 @@ impl OutletStatus {
             socket_addr,
             worker_addr,
             payload: payload.into(),
+            bandwidth_limit: None,
+            throughput: None,
+            connection_pool: None,
+            identity_forwarding: None,
+            paused_at: None,
         }
     }
 
+    /// Add the bandwidth limit and the current throughput of the outlet
+    pub fn with_bandwidth(mut self, bandwidth: &TcpPortalBandwidthLimiter) -> Self {
+        self.bandwidth_limit = bandwidth.limit();
+        self.throughput = Some(bandwidth.throughput());
+        self
+    }
+
+    /// Add the statistics of the connection pool of the outlet
+    pub fn with_connection_pool(mut self, pool: Option<&TcpOutletConnectionPool>) -> Self {
+        self.connection_pool = pool.map(OutletConnectionPoolStatus::from);
+        self
+    }
+
+    /// Add the format used to pass the identifier of the peers to the outlet peer
+    pub fn with_identity_forwarding(
+        mut self,
+        identity_forwarding: Option<OutletIdentityForwarding>,
+    ) -> Self {
+        self.identity_forwarding = identity_forwarding;
+        self
+    }
+
+    /// Add the time of the pause, if the outlet refuses new connections
+    pub fn with_pause(mut self, pause: &TcpPortalPause) -> Self {
+        self.paused_at = pause.paused_at();
+        self
+    }
+
     pub fn worker_address(&self) -> Result<MultiAddr, ockam_core::Error> {
         route_to_multiaddr(&route![self.worker_addr.to_string()])
             .ok_or_else(|| ApiError::core("Invalid Worker Address"))
@@ -271,6 +576,52 @@ impl OutletList {
     }
 }
 
+/// Response body describing an individual connection of an inlet
+#[derive(Clone, Debug, Decode, Encode, Serialize, Deserialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalConnectionStatus {
+    /// Identifier of the connection, used to close it
+    #[n(1)] pub id: String,
+    /// Alias of the inlet accepting the connection
+    #[n(2)] pub alias: String,
+    /// Socket address of the local TCP client
+    #[n(3)] pub peer_addr: SocketAddr,
+    /// Unix timestamp, in seconds, of the connection creation
+    #[n(4)] pub established_at: u64,
+    /// Number of bytes read from the TCP client
+    #[n(5)] pub bytes_read: u64,
+    /// Number of bytes written to the TCP client
+    #[n(6)] pub bytes_written: u64,
+}
+
+impl PortalConnectionStatus {
+    pub fn new(alias: impl Into<String>, connection: &TcpPortalConnectionInfo) -> Self {
+        Self {
+            id: connection.id().address().to_string(),
+            alias: alias.into(),
+            peer_addr: connection.peer(),
+            established_at: connection.established_at(),
+            bytes_read: connection.bytes_read(),
+            bytes_written: connection.bytes_written(),
+        }
+    }
+}
+
+/// Response body when returning the connections of an inlet
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PortalConnectionList {
+    #[n(1)] pub list: Vec<PortalConnectionStatus>
+}
+
+impl PortalConnectionList {
+    pub fn new(list: Vec<PortalConnectionStatus>) -> Self {
+        Self { list }
+    }
+}
+
 #[derive(Debug)]
 pub enum OutletAccessControl {
     IncomingAccessControl(Arc<dyn IncomingAccessControl>),
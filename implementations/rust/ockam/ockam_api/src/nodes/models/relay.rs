@@ -1,14 +1,21 @@
 use minicbor::{Decode, Encode};
 
-use ockam::identity::Identifier;
-use ockam::remote::RemoteRelayInfo;
-use ockam::route;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::remote::{RemoteRelayFilter, RemoteRelayInfo};
+use ockam::{route, RelayAliasOwner};
 use ockam_core::flow_control::FlowControlId;
-use ockam_multiaddr::MultiAddr;
+use ockam_core::Address;
+use ockam_multiaddr::proto::{Project, Secure, Service};
+use ockam_multiaddr::{MultiAddr, Protocol};
 
+use crate::cli_state::CliState;
 use crate::error::ApiError;
+use crate::nodes::service::default_address::DefaultAddress;
 use crate::{route_to_multiaddr, ConnectionStatus};
 
+/// Prefix added by the Orchestrator to the name of the relays created in a project
+pub const PROJECT_RELAY_PREFIX: &str = "forward_to_";
+
 /// Request body when instructing a node to create a relay
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
@@ -26,6 +33,16 @@ pub struct CreateRelay {
     #[n(4)] pub(crate) authorized: Option<Identifier>,
     /// Relay address.
     #[n(5)] pub(crate) relay_address: Option<String>,
+    /// Additional addresses to create the relay at, in order of preference,
+    /// when the relay can't be re-established at the previous ones. Not set by older clients
+    #[n(6)] pub(crate) failover_addresses: Option<Vec<MultiAddr>>,
+    /// Move the relay back to `address` when it can be reached again. Not set by older clients
+    #[n(7)] pub(crate) failback: Option<bool>,
+    /// Services which can be reached through the relay. All the services can be reached if empty.
+    /// Not set by older clients
+    #[n(8)] pub(crate) allowed_services: Option<Vec<String>>,
+    /// Services which can't be reached through the relay. Not set by older clients
+    #[n(9)] pub(crate) denied_services: Option<Vec<String>>,
 }
 
 impl CreateRelay {
@@ -42,9 +59,30 @@ impl CreateRelay {
             at_rust_node,
             authorized: auth,
             relay_address,
+            failover_addresses: None,
+            failback: None,
+            allowed_services: None,
+            denied_services: None,
         }
     }
 
+    pub fn with_failover_addresses(mut self, failover_addresses: Vec<MultiAddr>) -> Self {
+        self.failover_addresses = Some(failover_addresses);
+        self
+    }
+
+    pub fn with_failback(mut self, failback: bool) -> Self {
+        self.failback = Some(failback);
+        self
+    }
+
+    pub fn with_filter(mut self, filter: &RemoteRelayFilter) -> Self {
+        let (allowed_services, denied_services) = filter_to_lists(filter);
+        self.allowed_services = Some(allowed_services);
+        self.denied_services = Some(denied_services);
+        self
+    }
+
     pub fn address(&self) -> &MultiAddr {
         &self.address
     }
@@ -64,6 +102,100 @@ impl CreateRelay {
     pub fn relay_address(&self) -> Option<&str> {
         self.relay_address.as_deref()
     }
+
+    pub fn failover_addresses(&self) -> &[MultiAddr] {
+        self.failover_addresses.as_deref().unwrap_or_default()
+    }
+
+    pub fn failback(&self) -> bool {
+        self.failback.unwrap_or(false)
+    }
+
+    /// Services which can be reached through the relay
+    pub fn filter(&self) -> RemoteRelayFilter {
+        RemoteRelayFilter::from_lists(
+            self.allowed_services
+                .iter()
+                .flatten()
+                .map(Address::from_string)
+                .collect(),
+            self.denied_services
+                .iter()
+                .flatten()
+                .map(Address::from_string)
+                .collect(),
+        )
+    }
+}
+
+/// Return the names of the allowed and denied services of a relay filter
+fn filter_to_lists(filter: &RemoteRelayFilter) -> (Vec<String>, Vec<String>) {
+    let names = |addresses: &[Address]| addresses.iter().map(|a| a.address().to_string()).collect();
+    (
+        names(filter.allowed_services()),
+        names(filter.denied_services()),
+    )
+}
+
+/// Request body to rename a relay
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RenameRelay {
+    /// New alias of the relay
+    #[n(1)] pub new_alias: String,
+}
+
+impl RenameRelay {
+    pub fn new(new_alias: impl Into<String>) -> Self {
+        Self {
+            new_alias: new_alias.into(),
+        }
+    }
+}
+
+/// Request body to release an alias registered with the relay service of another node,
+/// so that it can be registered by another identity
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ReleaseRelayAlias {
+    /// Address of the node running the relay service
+    #[n(1)] pub address: MultiAddr,
+    /// Alias to release
+    #[n(2)] pub alias: String,
+}
+
+impl ReleaseRelayAlias {
+    pub fn new(address: MultiAddr, alias: impl Into<String>) -> Self {
+        Self {
+            address,
+            alias: alias.into(),
+        }
+    }
+}
+
+/// Response body when listing the owners of the aliases registered with the relay service of a node
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode, serde::Serialize, serde::Deserialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RelayAliasOwnerInfo {
+    /// Alias of the relay
+    #[n(1)] pub alias: String,
+    /// Identity owning the alias
+    #[n(2)] pub identifier: Identifier,
+    /// Time of the last registration of the alias by its owner
+    #[n(3)] pub registered_at: TimestampInSeconds,
+}
+
+impl From<RelayAliasOwner> for RelayAliasOwnerInfo {
+    fn from(owner: RelayAliasOwner) -> Self {
+        Self {
+            alias: owner.alias,
+            identifier: owner.identifier,
+            registered_at: owner.registered_at,
+        }
+    }
 }
 
 /// Response body when creating a relay
@@ -80,6 +212,14 @@ pub struct RelayInfo {
     #[n(7)] alias: String,
     #[n(8)] at_rust_node: bool,
     #[n(9)] last_failure: Option<String>,
+    /// Not set by older nodes
+    #[n(10)] failover_addresses: Option<Vec<MultiAddr>>,
+    #[n(11)] active_destination_address: Option<MultiAddr>,
+    #[n(12)] last_destination_change: Option<String>,
+    /// Not set by older nodes
+    #[n(13)] allowed_services: Option<Vec<String>>,
+    /// Not set by older nodes
+    #[n(14)] denied_services: Option<Vec<String>>,
 }
 
 impl RelayInfo {
@@ -99,6 +239,11 @@ impl RelayInfo {
             flow_control_id: None,
             connection_status,
             last_failure: None,
+            failover_addresses: None,
+            active_destination_address: None,
+            last_destination_change: None,
+            allowed_services: None,
+            denied_services: None,
         }
     }
 
@@ -108,25 +253,37 @@ impl RelayInfo {
             remote_address: Some(remote_relay_info.remote_address().into()),
             worker_address: Some(remote_relay_info.worker_address().to_string()),
             flow_control_id: remote_relay_info.flow_control_id().clone(),
-            connection_status: self.connection_status,
-            destination_address: self.destination_address,
-            alias: self.alias,
-            at_rust_node: self.at_rust_node,
-            last_failure: self.last_failure,
+            ..self
         }
     }
 
     pub fn with_last_failure(self, last_failure: String) -> Self {
         Self {
-            forwarding_route: self.forwarding_route,
-            remote_address: self.remote_address,
-            worker_address: self.worker_address,
-            flow_control_id: self.flow_control_id,
-            connection_status: self.connection_status,
-            destination_address: self.destination_address,
-            alias: self.alias,
-            at_rust_node: self.at_rust_node,
             last_failure: Some(last_failure),
+            ..self
+        }
+    }
+
+    pub fn with_destinations(
+        self,
+        failover_addresses: Vec<MultiAddr>,
+        active_destination_address: Option<MultiAddr>,
+        last_destination_change: Option<String>,
+    ) -> Self {
+        Self {
+            failover_addresses: Some(failover_addresses),
+            active_destination_address,
+            last_destination_change,
+            ..self
+        }
+    }
+
+    pub fn with_filter(self, filter: &RemoteRelayFilter) -> Self {
+        let (allowed_services, denied_services) = filter_to_lists(filter);
+        Self {
+            allowed_services: Some(allowed_services),
+            denied_services: Some(denied_services),
+            ..self
         }
     }
 
@@ -146,6 +303,31 @@ impl RelayInfo {
         self.at_rust_node
     }
 
+    /// Addresses the relay can fail over to when `destination_address` is unreachable
+    pub fn failover_addresses(&self) -> &[MultiAddr] {
+        self.failover_addresses.as_deref().unwrap_or_default()
+    }
+
+    /// Address the relay is currently created at
+    pub fn active_destination_address(&self) -> Option<&MultiAddr> {
+        self.active_destination_address.as_ref()
+    }
+
+    /// Description of the last time the relay switched to a different destination
+    pub fn last_destination_change(&self) -> Option<&str> {
+        self.last_destination_change.as_deref()
+    }
+
+    /// Services which can be reached through the relay. All the services can be reached if empty
+    pub fn allowed_services(&self) -> &[String] {
+        self.allowed_services.as_deref().unwrap_or_default()
+    }
+
+    /// Services which can't be reached through the relay
+    pub fn denied_services(&self) -> &[String] {
+        self.denied_services.as_deref().unwrap_or_default()
+    }
+
     pub fn forwarding_route(&self) -> &Option<String> {
         &self.forwarding_route
     }
@@ -178,3 +360,149 @@ impl RelayInfo {
         }
     }
 }
+
+/// Route to a service of a node reachable through a relay created in a project:
+/// `/project/<project>/service/forward_to_<relay>/secure/api/service/<service>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProjectRelayRoute {
+    project: String,
+    relay: String,
+    service: String,
+}
+
+impl ProjectRelayRoute {
+    /// Create a route from the names of its segments, checking that each one is valid
+    pub fn new(
+        project: impl Into<String>,
+        relay: impl Into<String>,
+        service: impl Into<String>,
+    ) -> Result<Self, ockam_core::Error> {
+        let route = Self {
+            project: project.into(),
+            relay: relay.into(),
+            service: service.into(),
+        };
+        Self::check_segment("project", &route.project)?;
+        Self::check_segment("relay", &route.relay)?;
+        Self::check_segment("service", &route.service)?;
+        Ok(route)
+    }
+
+    /// Create a route to a service, through a relay of the default project.
+    /// The service is either a name, like `outlet`, or a multiaddr, like `/service/outlet`
+    pub async fn resolve(
+        cli_state: &CliState,
+        relay: &str,
+        service: &str,
+    ) -> Result<Self, ockam_core::Error> {
+        let project = cli_state
+            .projects()
+            .get_default_project()
+            .await
+            .map_err(|e| {
+                ApiError::core(format!(
+                    "The project segment of the route could not be resolved: {e}"
+                ))
+            })?;
+        Self::new(project.name(), relay, Self::service_name(service)?)
+    }
+
+    /// Name of the project hosting the relay
+    pub fn project(&self) -> &str {
+        &self.project
+    }
+
+    /// Name of the relay, as given when the relay was created
+    pub fn relay(&self) -> &str {
+        &self.relay
+    }
+
+    /// Name of the service reachable through the relay
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// Route to the secure channel listener of the node which created the relay
+    pub fn relay_multiaddr(&self) -> Result<MultiAddr, ockam_core::Error> {
+        let mut multiaddr = MultiAddr::default();
+        multiaddr.push_back(Project::new(self.project.as_str()))?;
+        multiaddr.push_back(Service::new(format!(
+            "{PROJECT_RELAY_PREFIX}{}",
+            self.relay
+        )))?;
+        multiaddr.push_back(Secure::new(DefaultAddress::SECURE_CHANNEL_LISTENER))?;
+        Ok(multiaddr)
+    }
+
+    /// Full route to the service
+    pub fn multiaddr(&self) -> Result<MultiAddr, ockam_core::Error> {
+        let mut multiaddr = self.relay_multiaddr()?;
+        multiaddr.push_back(Service::new(self.service.as_str()))?;
+        Ok(multiaddr)
+    }
+
+    fn service_name(service: &str) -> Result<String, ockam_core::Error> {
+        if !service.starts_with('/') {
+            return Ok(service.to_string());
+        }
+        let invalid = || {
+            ApiError::core(format!(
+                "The service segment of the route could not be resolved: '{service}' is not a service name, like 'outlet' or '/service/outlet'"
+            ))
+        };
+        let multiaddr = MultiAddr::try_from(service).map_err(|_| invalid())?;
+        match multiaddr.first() {
+            Some(p) if multiaddr.len() == 1 && p.code() == Service::CODE => p
+                .cast::<Service>()
+                .map(|s| s.to_string())
+                .ok_or_else(invalid),
+            _ => Err(invalid()),
+        }
+    }
+
+    fn check_segment(segment: &str, name: &str) -> Result<(), ockam_core::Error> {
+        if name.is_empty() || name.contains('/') {
+            return Err(ApiError::core(format!(
+                "The {segment} segment of the route could not be resolved: '{name}' is not a valid {segment} name"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn the_project_relay_route_is_the_canonical_route() -> ockam_core::Result<()> {
+        let route = ProjectRelayRoute::new("default", "x", "outlet")?;
+        assert_eq!(
+            route.multiaddr()?,
+            MultiAddr::from_str("/project/default/service/forward_to_x/secure/api/service/outlet")
+                .unwrap()
+        );
+        assert_eq!(
+            route.relay_multiaddr()?,
+            MultiAddr::from_str("/project/default/service/forward_to_x/secure/api").unwrap()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_segments_are_reported() {
+        let error = ProjectRelayRoute::new("default", "x/y", "outlet").unwrap_err();
+        assert!(error.to_string().contains("The relay segment"), "{error}");
+
+        let error = ProjectRelayRoute::new("default", "x", "").unwrap_err();
+        assert!(error.to_string().contains("The service segment"), "{error}");
+
+        assert_eq!(
+            ProjectRelayRoute::service_name("/service/db").unwrap(),
+            "db"
+        );
+        let error = ProjectRelayRoute::service_name("/node/n1/service/db").unwrap_err();
+        assert!(error.to_string().contains("The service segment"), "{error}");
+    }
+}
@@ -1,11 +1,12 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use minicbor::{Decode, Encode};
 use serde::Serialize;
 
-use ockam::identity::{Identifier, SecureChannel, DEFAULT_TIMEOUT};
+use ockam::identity::{ClockSkew, Identifier, SecureChannel, DEFAULT_TIMEOUT};
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::{route, Address, Result};
+use ockam_core::{route, Address, RateLimit, Result};
 use ockam_multiaddr::MultiAddr;
 
 use crate::error::ApiError;
@@ -140,6 +141,8 @@ pub struct CreateSecureChannelListenerRequest {
     #[n(1)] pub addr: Address,
     #[n(2)] pub authorized_identifiers: Option<Vec<Identifier>>,
     #[n(3)] pub identity_name: Option<String>,
+    #[n(4)] pub required_attributes: Option<BTreeMap<String, String>>,
+    #[n(5)] pub rate_limit: Option<RateLimit>,
 }
 
 impl CreateSecureChannelListenerRequest {
@@ -152,8 +155,25 @@ impl CreateSecureChannelListenerRequest {
             addr: addr.to_owned(),
             authorized_identifiers,
             identity_name,
+            required_attributes: None,
+            rate_limit: None,
         }
     }
+
+    /// Only accept initiators presenting a credential with all these attributes
+    pub fn with_required_attributes(
+        mut self,
+        required_attributes: BTreeMap<String, String>,
+    ) -> Self {
+        self.required_attributes = Some(required_attributes);
+        self
+    }
+
+    /// Limit the rate of messages accepted from each initiator
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
 }
 
 /// Response body when deleting a Secure Channel Listener
@@ -177,13 +197,22 @@ impl DeleteSecureChannelListenerResponse {
 pub struct ShowSecureChannelListenerResponse {
     #[n(1)] pub addr: Address,
     #[n(2)] pub flow_control_id: FlowControlId,
+    #[n(3)] pub rate_limit: Option<RateLimit>,
+    #[n(4)] pub denied_messages_count: Option<u64>,
+    /// Number of handshakes torn down because they were not completed in time
+    #[n(5)] pub abandoned_handshakes: Option<u64>,
 }
 
 impl ShowSecureChannelListenerResponse {
     pub(crate) fn new(info: &SecureChannelListenerInfo) -> Self {
+        let rate_limiting_access_control = info.rate_limiting_access_control();
         Self {
             addr: info.listener().address().to_string().into(),
             flow_control_id: info.listener().flow_control_id().clone(),
+            rate_limit: rate_limiting_access_control.map(|ac| ac.rate_limit()),
+            denied_messages_count: rate_limiting_access_control
+                .map(|ac| ac.denied_messages_count()),
+            abandoned_handshakes: Some(info.listener().statistics().abandoned_handshakes()),
         }
     }
 }
@@ -211,6 +240,16 @@ pub struct ShowSecureChannelResponse {
     #[n(2)] pub route: Option<String>,
     #[n(3)] pub authorized_identifiers: Option<Vec<String>>,
     #[n(4)] pub flow_control_id: Option<FlowControlId>,
+    /// Number of seconds the other side clock is ahead of ours (negative if it is behind)
+    #[n(5)] pub clock_skew: Option<i64>,
+    /// Number of keys renewals done to encrypt the messages sent to the other side
+    #[n(6)] pub encryptor_rekeys: Option<u64>,
+    /// Number of keys renewals done to decrypt the messages received from the other side
+    #[n(7)] pub decryptor_rekeys: Option<u64>,
+    /// True if the channel was established before the trust options of the node were updated
+    #[n(8)] pub previous_trust_options: Option<bool>,
+    /// Maximum size, in bytes, of the payload of the messages, negotiated with the other side
+    #[n(9)] pub max_plaintext_size: Option<u32>,
 }
 
 impl ShowSecureChannelResponse {
@@ -229,8 +268,34 @@ impl ShowSecureChannelResponse {
                 })
                 .unwrap_or(None),
             flow_control_id: info.map(|info| info.sc().flow_control_id().clone()),
+            clock_skew: None,
+            encryptor_rekeys: None,
+            decryptor_rekeys: None,
+            previous_trust_options: None,
+            max_plaintext_size: None,
         }
     }
+
+    pub fn with_previous_trust_options(mut self, previous_trust_options: bool) -> Self {
+        self.previous_trust_options = Some(previous_trust_options);
+        self
+    }
+
+    pub fn with_clock_skew(mut self, clock_skew: Option<ClockSkew>) -> Self {
+        self.clock_skew = clock_skew.map(|skew| skew.seconds());
+        self
+    }
+
+    pub fn with_rekeys(mut self, encryptor_rekeys: u64, decryptor_rekeys: u64) -> Self {
+        self.encryptor_rekeys = Some(encryptor_rekeys);
+        self.decryptor_rekeys = Some(decryptor_rekeys);
+        self
+    }
+
+    pub fn with_max_plaintext_size(mut self, max_plaintext_size: u32) -> Self {
+        self.max_plaintext_size = Some(max_plaintext_size);
+        self
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
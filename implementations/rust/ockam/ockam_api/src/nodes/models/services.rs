@@ -5,6 +5,8 @@ use ockam_multiaddr::MultiAddr;
 
 use serde::Serialize;
 
+use crate::kafka::{KafkaBrokerValidation, KafkaBrokerValidationRequest};
+
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -52,18 +54,28 @@ impl DeleteServiceRequest {
 #[cbor(map)]
 pub struct StartKafkaOutletRequest {
     #[n(1)] pub bootstrap_server_addr: SocketAddr,
+    #[n(2)] validation: Option<KafkaBrokerValidationRequest>,
 }
 
 impl StartKafkaOutletRequest {
-    pub fn new(bootstrap_server_addr: SocketAddr) -> Self {
+    pub fn new(
+        bootstrap_server_addr: SocketAddr,
+        validation: Option<KafkaBrokerValidationRequest>,
+    ) -> Self {
         Self {
             bootstrap_server_addr,
+            validation,
         }
     }
 
     pub fn bootstrap_server_addr(&self) -> &SocketAddr {
         &self.bootstrap_server_addr
     }
+
+    /// If present, the broker is validated before the outlet is created
+    pub fn validation(&self) -> Option<&KafkaBrokerValidationRequest> {
+        self.validation.as_ref()
+    }
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -138,17 +150,51 @@ impl StartKafkaDirectRequest {
     }
 }
 
+/// Advertisement of a service by the discovery service of its node
+#[derive(Debug, Clone, Default, PartialEq, Eq, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ServiceAdvertisement {
+    /// Only the advertised services are listed by the discovery service
+    #[n(1)] pub advertise: bool,
+    /// Human readable description of the service
+    #[n(2)] pub description: Option<String>,
+}
+
+impl ServiceAdvertisement {
+    /// Advertise a service, with an optional description
+    pub fn advertised(description: Option<String>) -> Self {
+        Self {
+            advertise: true,
+            description,
+        }
+    }
+}
+
 /// Request body when instructing a node to start an Uppercase service
 #[derive(Debug, Clone, Decode, Encode)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct StartUppercaseServiceRequest {
     #[n(1)] pub addr: String,
+    #[n(2)] pub advertisement: Option<ServiceAdvertisement>,
 }
 
 impl StartUppercaseServiceRequest {
     pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+        Self {
+            addr: addr.into(),
+            advertisement: None,
+        }
+    }
+
+    pub fn with_advertisement(mut self, advertisement: ServiceAdvertisement) -> Self {
+        self.advertisement = Some(advertisement);
+        self
+    }
+
+    pub fn advertisement(&self) -> ServiceAdvertisement {
+        self.advertisement.clone().unwrap_or_default()
     }
 }
 
@@ -158,11 +204,24 @@ impl StartUppercaseServiceRequest {
 #[cbor(map)]
 pub struct StartEchoerServiceRequest {
     #[n(1)] pub addr: String,
+    #[n(2)] pub advertisement: Option<ServiceAdvertisement>,
 }
 
 impl StartEchoerServiceRequest {
     pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+        Self {
+            addr: addr.into(),
+            advertisement: None,
+        }
+    }
+
+    pub fn with_advertisement(mut self, advertisement: ServiceAdvertisement) -> Self {
+        self.advertisement = Some(advertisement);
+        self
+    }
+
+    pub fn advertisement(&self) -> ServiceAdvertisement {
+        self.advertisement.clone().unwrap_or_default()
     }
 }
 
@@ -172,11 +231,24 @@ impl StartEchoerServiceRequest {
 #[cbor(map)]
 pub struct StartHopServiceRequest {
     #[n(1)] pub addr: String,
+    #[n(2)] pub advertisement: Option<ServiceAdvertisement>,
 }
 
 impl StartHopServiceRequest {
     pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+        Self {
+            addr: addr.into(),
+            advertisement: None,
+        }
+    }
+
+    pub fn with_advertisement(mut self, advertisement: ServiceAdvertisement) -> Self {
+        self.advertisement = Some(advertisement);
+        self
+    }
+
+    pub fn advertisement(&self) -> ServiceAdvertisement {
+        self.advertisement.clone().unwrap_or_default()
     }
 }
 
@@ -186,6 +258,7 @@ impl StartHopServiceRequest {
 pub struct ServiceStatus {
     #[n(2)] pub addr: String,
     #[n(3)] pub service_type: String,
+    #[n(4)] pub validation: Option<KafkaBrokerValidation>,
 }
 
 impl ServiceStatus {
@@ -193,8 +266,15 @@ impl ServiceStatus {
         Self {
             addr: addr.into(),
             service_type: service_type.into(),
+            validation: None,
         }
     }
+
+    /// Set the result of the validation done when the service was created
+    pub fn with_validation(mut self, validation: Option<KafkaBrokerValidation>) -> Self {
+        self.validation = validation;
+        self
+    }
 }
 
 /// Response body for listing services
@@ -210,3 +290,125 @@ impl ServiceList {
         Self { list }
     }
 }
+
+/// Request body when instructing a node to start a discovery service
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartServiceDiscoveryRequest {
+    #[n(1)] pub addr: String,
+}
+
+impl StartServiceDiscoveryRequest {
+    pub fn new(addr: impl Into<String>) -> Self {
+        Self { addr: addr.into() }
+    }
+}
+
+/// Service listed by the discovery service of a node
+#[derive(Debug, Clone, Serialize, Decode, Encode, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AdvertisedService {
+    #[n(1)] pub addr: String,
+    #[n(2)] pub service_type: String,
+    #[n(3)] pub description: Option<String>,
+}
+
+impl AdvertisedService {
+    pub fn new(
+        addr: impl Into<String>,
+        service_type: impl Into<String>,
+        description: Option<String>,
+    ) -> Self {
+        Self {
+            addr: addr.into(),
+            service_type: service_type.into(),
+            description,
+        }
+    }
+}
+
+/// Response body of a discovery service, listing the services advertised by its node
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AdvertisedServiceList {
+    #[n(1)] pub list: Vec<AdvertisedService>
+}
+
+impl AdvertisedServiceList {
+    pub fn new(list: Vec<AdvertisedService>) -> Self {
+        Self { list }
+    }
+}
+
+/// Request body when instructing a node to start a service plugin
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartServicePluginRequest {
+    #[n(1)] pub name: String,
+    /// JSON configuration passed to the plugin
+    #[n(2)] pub config: Option<String>,
+    #[n(3)] pub advertisement: Option<ServiceAdvertisement>,
+}
+
+impl StartServicePluginRequest {
+    pub fn new(name: impl Into<String>, config: Option<&serde_json::Value>) -> Self {
+        Self {
+            name: name.into(),
+            config: config.map(|c| c.to_string()),
+            advertisement: None,
+        }
+    }
+
+    pub fn with_advertisement(mut self, advertisement: ServiceAdvertisement) -> Self {
+        self.advertisement = Some(advertisement);
+        self
+    }
+
+    pub fn advertisement(&self) -> ServiceAdvertisement {
+        self.advertisement.clone().unwrap_or_default()
+    }
+
+    /// Return the parsed plugin configuration
+    pub fn config(&self) -> serde_json::Result<Option<serde_json::Value>> {
+        self.config
+            .as_ref()
+            .map(|c| serde_json::from_str(c))
+            .transpose()
+    }
+}
+
+/// Status of a service plugin started on a node
+#[derive(Debug, Clone, Serialize, Decode, Encode, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ServicePluginStatus {
+    #[n(1)] pub name: String,
+    #[n(2)] pub addresses: Vec<String>,
+}
+
+impl ServicePluginStatus {
+    pub fn new(name: impl Into<String>, addresses: Vec<Address>) -> Self {
+        Self {
+            name: name.into(),
+            addresses: addresses.iter().map(|a| a.address().to_string()).collect(),
+        }
+    }
+}
+
+/// Response body for listing service plugins
+#[derive(Debug, Clone, Serialize, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ServicePluginList {
+    #[n(1)] pub list: Vec<ServicePluginStatus>
+}
+
+impl ServicePluginList {
+    pub fn new(list: Vec<ServicePluginStatus>) -> Self {
+        Self { list }
+    }
+}
@@ -0,0 +1,88 @@
+//! Node startup report types
+
+use std::fmt::{self, Display};
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+/// Outcome of a unit started when the node starts
+#[derive(Copy, Clone, Debug, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+#[serde(rename_all = "lowercase")]
+pub enum StartupUnitStatus {
+    /// The unit has been started
+    #[n(0)] Started,
+    /// The unit failed to start
+    #[n(1)] Failed,
+    /// The unit was not started because one of its dependencies was not started
+    #[n(2)] Skipped,
+}
+
+impl Display for StartupUnitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Started => "started",
+            Self::Failed => "failed",
+            Self::Skipped => "skipped",
+        })
+    }
+}
+
+/// Startup outcome and timings of a single unit
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartupUnitReport {
+    #[n(1)] pub name: String,
+    #[n(2)] pub dependencies: Vec<String>,
+    #[n(3)] pub status: StartupUnitStatus,
+    /// Reason of the failure or of the skip
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(4)] pub reason: Option<String>,
+    /// Time spent waiting for the dependencies, since the start of the plan
+    #[n(5)] pub waited_ms: u64,
+    /// Time spent starting the unit itself
+    #[n(6)] pub duration_ms: u64,
+}
+
+/// Response body to a `GET /node/startup` request: the outcome of the node startup plan
+#[derive(Debug, Clone, Default, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct StartupReport {
+    #[n(1)] pub units: Vec<StartupUnitReport>,
+    #[n(2)] pub duration_ms: u64,
+}
+
+impl StartupReport {
+    /// Return the report of a unit
+    pub fn unit(&self, name: &str) -> Option<&StartupUnitReport> {
+        self.units.iter().find(|u| u.name == name)
+    }
+
+    /// Return true if all the units have been started
+    pub fn is_complete(&self) -> bool {
+        self.units
+            .iter()
+            .all(|u| u.status == StartupUnitStatus::Started)
+    }
+}
+
+impl Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Startup completed in {}ms", self.duration_ms)?;
+        for unit in &self.units {
+            write!(
+                f,
+                "  {}: {} (waited {}ms, took {}ms)",
+                unit.name, unit.status, unit.waited_ms, unit.duration_ms
+            )?;
+            if let Some(reason) = &unit.reason {
+                write!(f, ": {reason}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
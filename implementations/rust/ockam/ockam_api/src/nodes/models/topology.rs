@@ -0,0 +1,72 @@
+//! Topology request/response types
+
+use minicbor::{Decode, Encode};
+use serde::Serialize;
+
+use ockam::identity::Identifier;
+
+use crate::nodes::models::portal::OutletStatus;
+use crate::nodes::models::transport::TransportStatus;
+
+/// Response body describing the connections of a node to other nodes
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct NodeTopology {
+    #[n(1)] pub node_name: String,
+    /// Identifier of the node identity
+    #[n(2)] pub identifier: Identifier,
+    #[n(3)] pub secure_channels: Vec<TopologySecureChannel>,
+    #[n(4)] pub relays: Vec<TopologyRelay>,
+    #[n(5)] pub inlets: Vec<TopologyInlet>,
+    #[n(6)] pub outlets: Vec<OutletStatus>,
+    #[n(7)] pub transports: Vec<TransportStatus>,
+}
+
+/// A secure channel, created or accepted by the node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopologySecureChannel {
+    /// Address of the local encryptor
+    #[n(1)] pub encryptor_address: String,
+    /// Address of the local decryptor
+    #[n(2)] pub decryptor_address: String,
+    /// Address of the decryptor of the other side.
+    /// It is equal to the `decryptor_address` of the same channel seen from the other side
+    #[n(3)] pub their_decryptor_address: String,
+    #[n(4)] pub my_identifier: Identifier,
+    #[n(5)] pub their_identifier: Identifier,
+    /// True if the channel was created by this node
+    #[n(6)] pub is_initiator: bool,
+    /// Route to the other side, when the channel was created with the node manager API
+    #[n(7)] pub route: Option<String>,
+}
+
+/// A relay created by the node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopologyRelay {
+    #[n(1)] pub alias: String,
+    /// Address where the relay is currently created
+    #[n(2)] pub destination: String,
+    /// Identifier of the node hosting the relay, if it is reached through a secure channel
+    #[n(3)] pub destination_identifier: Option<Identifier>,
+    #[n(4)] pub remote_address: Option<String>,
+}
+
+/// An inlet created by the node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TopologyInlet {
+    #[n(1)] pub alias: String,
+    #[n(2)] pub bind_address: String,
+    /// Address of the outlet, as given when the inlet was created
+    #[n(3)] pub outlet_address: String,
+    /// Current route to the outlet
+    #[n(4)] pub outlet_route: Option<String>,
+    /// Identifier of the node hosting the outlet, if it is reached through a secure channel
+    #[n(5)] pub outlet_identifier: Option<Identifier>,
+}
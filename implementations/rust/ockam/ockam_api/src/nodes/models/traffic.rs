@@ -0,0 +1,59 @@
+//! Message size accounting request/response types
+
+use minicbor::{Decode, Encode};
+use ockam_node::{MessageDirection, MessageSizeStats};
+use serde::Serialize;
+
+/// Request body to enable or disable the accounting of message sizes
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetTrafficAccounting {
+    #[n(1)] pub enabled: bool,
+}
+
+impl SetTrafficAccounting {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+/// Response body listing the message sizes recorded by the workers of a node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrafficStats {
+    #[n(1)] pub enabled: bool,
+    #[n(2)] pub workers: Vec<WorkerTraffic>,
+}
+
+/// Sizes, in bytes, of the messages handled by a worker in one direction
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct WorkerTraffic {
+    #[n(1)] pub address: String,
+    /// Either "inbound" or "outbound"
+    #[n(2)] pub direction: String,
+    #[n(3)] pub count: u64,
+    #[n(4)] pub p50: u64,
+    #[n(5)] pub p95: u64,
+    #[n(6)] pub max: u64,
+}
+
+impl From<MessageSizeStats> for WorkerTraffic {
+    fn from(stats: MessageSizeStats) -> Self {
+        let direction = match stats.direction {
+            MessageDirection::Inbound => "inbound",
+            MessageDirection::Outbound => "outbound",
+        };
+        Self {
+            address: stats.address.to_string(),
+            direction: direction.to_string(),
+            count: stats.count as u64,
+            p50: stats.p50 as u64,
+            p95: stats.p95 as u64,
+            max: stats.max as u64,
+        }
+    }
+}
@@ -26,6 +26,13 @@ pub struct TransportStatus {
     #[n(5)] pub processor_address: String,
     /// Corresponding flow control id
     #[n(6)] pub flow_control_id: FlowControlId,
+    /// Number of connections accepted by a listener
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(7)] pub accepted_connections: Option<u64>,
+    /// Number of connections closed by a listener right after being accepted,
+    /// because of its rate, source or filter limits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[n(8)] pub rejected_connections: Option<u64>,
 }
 
 impl TransportStatus {
@@ -60,6 +67,8 @@ impl From<ApiTransport> for TransportStatus {
             worker_addr: value.worker_address.clone(),
             processor_address: value.processor_address.clone(),
             flow_control_id: value.flow_control_id,
+            accepted_connections: None,
+            rejected_connections: None,
         }
     }
 }
@@ -73,12 +82,15 @@ impl From<TcpSenderInfo> for TransportStatus {
             worker_addr: value.address().to_string(),
             processor_address: value.receiver_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            accepted_connections: None,
+            rejected_connections: None,
         }
     }
 }
 
 impl From<TcpListenerInfo> for TransportStatus {
     fn from(value: TcpListenerInfo) -> Self {
+        let stats = value.stats();
         Self {
             tt: TransportType::Tcp,
             tm: TransportMode::Listen,
@@ -86,6 +98,8 @@ impl From<TcpListenerInfo> for TransportStatus {
             worker_addr: "<none>".into(),
             processor_address: value.address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            accepted_connections: Some(stats.accepted),
+            rejected_connections: Some(stats.rejected()),
         }
     }
 }
@@ -99,6 +113,8 @@ impl From<TcpConnection> for TransportStatus {
             worker_addr: value.sender_address().to_string(),
             processor_address: value.receiver_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            accepted_connections: None,
+            rejected_connections: None,
         }
     }
 }
@@ -112,6 +128,8 @@ impl From<TcpListener> for TransportStatus {
             worker_addr: "<none>".into(),
             processor_address: value.processor_address().to_string(),
             flow_control_id: value.flow_control_id().clone(),
+            accepted_connections: None,
+            rejected_connections: None,
         }
     }
 }
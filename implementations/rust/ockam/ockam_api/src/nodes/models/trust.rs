@@ -0,0 +1,68 @@
+//! Node trust options request/response types
+
+use minicbor::{Decode, Encode};
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::Identifier;
+use ockam_multiaddr::MultiAddr;
+use serde::Serialize;
+
+/// Options used by a running node to retrieve its own credential
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+pub enum CredentialRetrieverRequest {
+    /// The node doesn't present any credential
+    #[n(0)] None,
+    /// The node reads a credential issued by the given authority from its credentials cache
+    #[n(1)] CacheOnly(#[n(0)] Identifier),
+    /// The node requests a credential from the authority node at the given address
+    #[n(2)] Remote(#[n(0)] Identifier, #[n(1)] MultiAddr),
+    /// The node always presents the given credential
+    #[n(3)] InMemory(#[n(0)] CredentialAndPurposeKey),
+}
+
+/// Request body to update the trust options of a running node.
+///
+/// Only the new secure channels use the updated options.
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct UpdateTrustOptions {
+    /// New options to retrieve the node credential, if they must be changed
+    #[n(1)] pub credential_retriever: Option<CredentialRetrieverRequest>,
+    /// New project authority, if it must be changed
+    #[n(2)] pub authority: Option<Identifier>,
+    /// Changing the authority changes which identities are trusted by the node,
+    /// so the change must be explicitly confirmed
+    #[n(3)] pub confirm_authority_change: bool,
+}
+
+impl UpdateTrustOptions {
+    pub fn new(
+        credential_retriever: Option<CredentialRetrieverRequest>,
+        authority: Option<Identifier>,
+        confirm_authority_change: bool,
+    ) -> Self {
+        Self {
+            credential_retriever,
+            authority,
+            confirm_authority_change,
+        }
+    }
+}
+
+/// Response body describing the trust options currently used by a node
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct TrustOptionsStatus {
+    /// Project authority trusted by the node
+    #[n(1)] pub authority: Option<Identifier>,
+    /// Way the node credential is retrieved: "none", "cache-only", "remote" or "in-memory"
+    #[n(2)] pub credential_retriever: String,
+    /// Authority issuing the node credential, when it is retrieved from the cache or from a remote node
+    #[n(3)] pub credential_issuer: Option<Identifier>,
+    /// Route to the authority node, when the credential is retrieved from a remote node
+    #[n(4)] pub credential_issuer_route: Option<String>,
+    /// Incremented each time the trust options are updated
+    #[n(5)] pub version: u64,
+}
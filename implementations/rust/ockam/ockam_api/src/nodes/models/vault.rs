@@ -0,0 +1,55 @@
+//! Vault keys request/response types
+
+use minicbor::{Decode, Encode};
+use ockam::identity::TimestampInSeconds;
+use serde::Serialize;
+
+use crate::cli_state::VaultKey;
+
+/// Key stored in the vault of a node, with the data referencing it
+#[derive(Debug, Clone, Decode, Encode, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VaultKeyStatus {
+    /// Hex-encoded handle of the key
+    #[n(1)] pub key_id: String,
+    /// Type of key, for example "X25519"
+    #[n(2)] pub key_type: String,
+    /// Creation time of the key, unknown for keys created by older versions
+    #[n(3)] pub created_at: Option<TimestampInSeconds>,
+    /// Description of the identity or purpose key using this key, if any
+    #[n(4)] pub referenced_by: Option<String>,
+    /// True if the key is used by a local identity or one of its purpose keys
+    #[n(5)] pub in_use: bool,
+}
+
+impl From<&VaultKey> for VaultKeyStatus {
+    fn from(key: &VaultKey) -> Self {
+        Self {
+            key_id: key.key_id(),
+            key_type: key.key_type().to_string(),
+            created_at: key.created_at(),
+            referenced_by: key.referenced_by().map(|r| r.to_string()),
+            in_use: key.is_in_use(),
+        }
+    }
+}
+
+/// Response body when returning the keys of a vault
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct VaultKeyList {
+    /// Name of the vault storing the keys
+    #[n(1)] pub vault_name: String,
+    #[n(2)] pub list: Vec<VaultKeyStatus>,
+}
+
+impl VaultKeyList {
+    pub fn new(vault_name: impl Into<String>, list: Vec<VaultKeyStatus>) -> Self {
+        Self {
+            vault_name: vault_name.into(),
+            list,
+        }
+    }
+}
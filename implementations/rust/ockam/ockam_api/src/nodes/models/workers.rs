@@ -1,20 +1,33 @@
+use std::time::Duration;
+
 use minicbor::{Decode, Encode};
+use serde::Serialize;
 
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct WorkerStatus {
     #[n(2)] pub addr: String,
+    /// Type name of the worker registering this address
+    #[n(3)] pub owner: Option<String>,
 }
 
 impl WorkerStatus {
     pub fn new(addr: impl Into<String>) -> Self {
-        Self { addr: addr.into() }
+        Self {
+            addr: addr.into(),
+            owner: None,
+        }
+    }
+
+    pub fn with_owner(mut self, owner: impl Into<String>) -> Self {
+        self.owner = Some(owner.into());
+        self
     }
 }
 
 /// Response body for listing workers
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
 #[rustfmt::skip]
 #[cbor(map)]
 pub struct WorkerList {
@@ -26,3 +39,42 @@ impl WorkerList {
         Self { list }
     }
 }
+
+/// Request body to send a raw payload to a worker of the node
+#[derive(Debug, Clone, Decode, Encode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InjectMessage {
+    #[n(1)] pub address: String,
+    #[n(2)] pub payload: Vec<u8>,
+    /// Time to wait for a reply, in milliseconds. No reply is expected if it is not set
+    #[n(3)] pub reply_timeout_ms: Option<u64>,
+}
+
+impl InjectMessage {
+    pub fn new(address: impl Into<String>, payload: Vec<u8>) -> Self {
+        Self {
+            address: address.into(),
+            payload,
+            reply_timeout_ms: None,
+        }
+    }
+
+    pub fn with_reply_timeout(mut self, timeout: Duration) -> Self {
+        self.reply_timeout_ms = Some(timeout.as_millis() as u64);
+        self
+    }
+
+    pub fn reply_timeout(&self) -> Option<Duration> {
+        self.reply_timeout_ms.map(Duration::from_millis)
+    }
+}
+
+/// Response body for a message sent to a worker of the node
+#[derive(Debug, Clone, Decode, Encode, Serialize)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct InjectedMessageReply {
+    /// Payload of the reply, if a reply was expected
+    #[n(1)] pub reply: Option<Vec<u8>>,
+}
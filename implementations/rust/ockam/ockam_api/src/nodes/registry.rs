@@ -1,15 +1,24 @@
+use crate::kafka::KafkaBrokerValidation;
+use crate::nodes::models::portal::OutletIdentityForwarding;
 use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::models::services::ServiceAdvertisement;
 use crate::session::sessions::{ReplacerOutputKind, Session};
 use crate::{random_name, DefaultAddress};
+use chrono::Utc;
 use ockam::identity::Identifier;
 use ockam::identity::{SecureChannel, SecureChannelListener};
-use ockam_core::compat::collections::BTreeMap;
-use ockam_core::{Address, Route};
+use ockam::remote::RemoteRelayFilter;
+use ockam_core::compat::collections::{BTreeMap, BTreeSet};
+use ockam_core::{Address, RateLimitingAccessControl, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::compat::asynchronous::RwLock;
+use ockam_transport_tcp::{
+    TcpInletPreCheck, TcpOutletConnectionPool, TcpPortalBandwidthLimiter, TcpPortalPause,
+};
 use std::borrow::Borrow;
 use std::fmt::Display;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 
 #[derive(Default)]
 pub(crate) struct SecureChannelRegistry {
@@ -30,9 +39,13 @@ impl SecureChannelRegistry {
         route: Route,
         sc: SecureChannel,
         authorized_identifiers: Option<Vec<Identifier>>,
+        trust_options_version: u64,
     ) {
         let mut channels = self.channels.write().await;
-        channels.push(SecureChannelInfo::new(route, sc, authorized_identifiers))
+        channels.push(
+            SecureChannelInfo::new(route, sc, authorized_identifiers)
+                .with_trust_options_version(trust_options_version),
+        )
     }
 
     pub async fn remove_by_addr(&self, addr: &Address) {
@@ -52,6 +65,8 @@ pub struct SecureChannelInfo {
     route: Route,
     sc: SecureChannel,
     authorized_identifiers: Option<Vec<Identifier>>,
+    // Version of the node trust options used to create the channel
+    trust_options_version: u64,
 }
 
 impl SecureChannelInfo {
@@ -64,9 +79,15 @@ impl SecureChannelInfo {
             route,
             sc,
             authorized_identifiers,
+            trust_options_version: 0,
         }
     }
 
+    pub fn with_trust_options_version(mut self, trust_options_version: u64) -> Self {
+        self.trust_options_version = trust_options_version;
+        self
+    }
+
     pub fn route(&self) -> &Route {
         &self.route
     }
@@ -78,34 +99,122 @@ impl SecureChannelInfo {
     pub fn authorized_identifiers(&self) -> Option<&Vec<Identifier>> {
         self.authorized_identifiers.as_ref()
     }
+
+    /// Return the version of the node trust options used to create the channel
+    pub fn trust_options_version(&self) -> u64 {
+        self.trust_options_version
+    }
 }
 
 #[derive(Clone)]
 pub struct SecureChannelListenerInfo {
     listener: SecureChannelListener,
+    rate_limiting_access_control: Option<Arc<RateLimitingAccessControl>>,
 }
 
 impl SecureChannelListenerInfo {
     pub fn new(listener: SecureChannelListener) -> Self {
-        Self { listener }
+        Self {
+            listener,
+            rate_limiting_access_control: None,
+        }
+    }
+
+    pub fn with_rate_limiting_access_control(
+        mut self,
+        rate_limiting_access_control: Option<Arc<RateLimitingAccessControl>>,
+    ) -> Self {
+        self.rate_limiting_access_control = rate_limiting_access_control;
+        self
     }
 
     pub fn listener(&self) -> &SecureChannelListener {
         &self.listener
     }
+
+    pub fn rate_limiting_access_control(&self) -> Option<&Arc<RateLimitingAccessControl>> {
+        self.rate_limiting_access_control.as_ref()
+    }
 }
 
 #[derive(Default, Clone)]
 pub(crate) struct OktaIdentityProviderServiceInfo {}
 
 #[derive(Default, Clone)]
-pub(crate) struct UppercaseServiceInfo {}
+pub(crate) struct UppercaseServiceInfo {
+    advertisement: ServiceAdvertisement,
+}
+
+impl UppercaseServiceInfo {
+    pub fn new(advertisement: ServiceAdvertisement) -> Self {
+        Self { advertisement }
+    }
+
+    pub fn advertisement(&self) -> &ServiceAdvertisement {
+        &self.advertisement
+    }
+}
 
 #[derive(Default, Clone)]
-pub(crate) struct EchoerServiceInfo {}
+pub(crate) struct EchoerServiceInfo {
+    advertisement: ServiceAdvertisement,
+}
+
+impl EchoerServiceInfo {
+    pub fn new(advertisement: ServiceAdvertisement) -> Self {
+        Self { advertisement }
+    }
+
+    pub fn advertisement(&self) -> &ServiceAdvertisement {
+        &self.advertisement
+    }
+}
 
 #[derive(Default, Clone)]
-pub(crate) struct HopServiceInfo {}
+pub(crate) struct HopServiceInfo {
+    advertisement: ServiceAdvertisement,
+}
+
+impl HopServiceInfo {
+    pub fn new(advertisement: ServiceAdvertisement) -> Self {
+        Self { advertisement }
+    }
+
+    pub fn advertisement(&self) -> &ServiceAdvertisement {
+        &self.advertisement
+    }
+}
+
+#[derive(Default, Clone)]
+pub(crate) struct ServiceDiscoveryInfo {}
+
+#[derive(Clone)]
+pub(crate) struct ServicePluginInfo {
+    addresses: Vec<Address>,
+    advertisement: ServiceAdvertisement,
+}
+
+impl ServicePluginInfo {
+    pub fn new(addresses: Vec<Address>) -> Self {
+        Self {
+            addresses,
+            advertisement: ServiceAdvertisement::default(),
+        }
+    }
+
+    pub fn with_advertisement(mut self, advertisement: ServiceAdvertisement) -> Self {
+        self.advertisement = advertisement;
+        self
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.addresses.clone()
+    }
+
+    pub fn advertisement(&self) -> &ServiceAdvertisement {
+        &self.advertisement
+    }
+}
 
 #[derive(Eq, PartialEq, Clone)]
 pub enum KafkaServiceKind {
@@ -129,16 +238,30 @@ impl Display for KafkaServiceKind {
 #[derive(Clone)]
 pub(crate) struct KafkaServiceInfo {
     kind: KafkaServiceKind,
+    validation: Option<KafkaBrokerValidation>,
 }
 
 impl KafkaServiceInfo {
     pub fn new(kind: KafkaServiceKind) -> Self {
-        Self { kind }
+        Self {
+            kind,
+            validation: None,
+        }
+    }
+
+    pub fn with_validation(mut self, validation: Option<KafkaBrokerValidation>) -> Self {
+        self.validation = validation;
+        self
     }
 
     pub fn kind(&self) -> &KafkaServiceKind {
         &self.kind
     }
+
+    /// Result of the broker validation done when the service was created
+    pub fn validation(&self) -> Option<&KafkaBrokerValidation> {
+        self.validation.as_ref()
+    }
 }
 
 #[derive(Clone)]
@@ -146,14 +269,27 @@ pub(crate) struct InletInfo {
     pub(crate) bind_addr: String,
     pub(crate) outlet_addr: MultiAddr,
     pub(crate) session: Session,
+    pub(crate) bandwidth: TcpPortalBandwidthLimiter,
+    pub(crate) pre_check: Option<TcpInletPreCheck>,
+    pub(crate) pause: TcpPortalPause,
 }
 
 impl InletInfo {
-    pub(crate) fn new(bind_addr: &str, outlet_addr: MultiAddr, session: Session) -> Self {
+    pub(crate) fn new(
+        bind_addr: &str,
+        outlet_addr: MultiAddr,
+        session: Session,
+        bandwidth: TcpPortalBandwidthLimiter,
+        pre_check: Option<TcpInletPreCheck>,
+        pause: TcpPortalPause,
+    ) -> Self {
         Self {
             bind_addr: bind_addr.to_owned(),
             outlet_addr,
             session,
+            bandwidth,
+            pre_check,
+            pause,
         }
     }
 }
@@ -162,10 +298,18 @@ impl InletInfo {
 pub struct OutletInfo {
     pub(crate) socket_addr: SocketAddr,
     pub(crate) worker_addr: Address,
+    pub(crate) bandwidth: TcpPortalBandwidthLimiter,
+    pub(crate) pool: Option<TcpOutletConnectionPool>,
+    pub(crate) identity_forwarding: Option<OutletIdentityForwarding>,
+    pub(crate) pause: TcpPortalPause,
 }
 
 impl OutletInfo {
-    pub(crate) fn new(socket_addr: &SocketAddr, worker_addr: Option<&Address>) -> Self {
+    pub(crate) fn new(
+        socket_addr: &SocketAddr,
+        worker_addr: Option<&Address>,
+        bandwidth: TcpPortalBandwidthLimiter,
+    ) -> Self {
         let worker_addr = match worker_addr {
             Some(addr) => addr.clone(),
             None => Address::from_string(""),
@@ -173,18 +317,88 @@ impl OutletInfo {
         Self {
             socket_addr: *socket_addr,
             worker_addr,
+            bandwidth,
+            pool: None,
+            identity_forwarding: None,
+            pause: TcpPortalPause::new(),
         }
     }
+
+    pub(crate) fn with_pause(mut self, pause: TcpPortalPause) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    pub(crate) fn with_connection_pool(mut self, pool: Option<TcpOutletConnectionPool>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    pub(crate) fn with_identity_forwarding(
+        mut self,
+        identity_forwarding: Option<OutletIdentityForwarding>,
+    ) -> Self {
+        self.identity_forwarding = identity_forwarding;
+        self
+    }
 }
 
 #[derive(Clone)]
 pub struct RegistryRelayInfo {
     pub(crate) destination_address: MultiAddr,
+    pub(crate) failover_addresses: Vec<MultiAddr>,
+    pub(crate) destination_status: RelayDestinationStatus,
     pub(crate) alias: String,
     pub(crate) at_rust_node: bool,
+    // the parameters below are kept to be able to create the relay again when it is renamed
+    pub(crate) authorized: Option<Identifier>,
+    pub(crate) relay_address: Option<String>,
+    pub(crate) failback: bool,
+    pub(crate) filter: RemoteRelayFilter,
     pub(crate) session: Session,
 }
 
+/// Keeps track of the destination a relay is currently created at.
+/// It is shared between the registry and the session replacer of the relay,
+/// so that a failover performed by the replacer is visible when showing the relay.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RelayDestinationStatus {
+    inner: Arc<Mutex<RelayDestinationState>>,
+}
+
+#[derive(Debug, Default)]
+struct RelayDestinationState {
+    active: Option<MultiAddr>,
+    last_change: Option<String>,
+}
+
+impl RelayDestinationStatus {
+    pub(crate) fn active(&self) -> Option<MultiAddr> {
+        self.inner.lock().unwrap().active.clone()
+    }
+
+    pub(crate) fn last_change(&self) -> Option<String> {
+        self.inner.lock().unwrap().last_change.clone()
+    }
+
+    /// Set the destination the relay is currently created at and record
+    /// a status change event if it differs from the previous one
+    pub(crate) fn set_active(&self, destination: &MultiAddr) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(previous) = inner.active.as_ref() {
+            if previous != destination {
+                let event = format!(
+                    "{} switched from {previous} to {destination}",
+                    Utc::now().to_rfc3339()
+                );
+                info!(%previous, %destination, "relay destination changed");
+                inner.last_change = Some(event);
+            }
+        }
+        inner.active = Some(destination.clone());
+    }
+}
+
 impl From<RegistryRelayInfo> for RelayInfo {
     fn from(registry_relay_info: RegistryRelayInfo) -> Self {
         let relay_info = RelayInfo::new(
@@ -192,7 +406,13 @@ impl From<RegistryRelayInfo> for RelayInfo {
             registry_relay_info.alias.clone(),
             registry_relay_info.at_rust_node,
             registry_relay_info.session.connection_status(),
-        );
+        )
+        .with_destinations(
+            registry_relay_info.failover_addresses.clone(),
+            registry_relay_info.destination_status.active(),
+            registry_relay_info.destination_status.last_change(),
+        )
+        .with_filter(&registry_relay_info.filter);
 
         let current_relay_status =
             registry_relay_info
@@ -221,11 +441,47 @@ pub(crate) struct Registry {
     pub(crate) echoer_services: RegistryOf<Address, EchoerServiceInfo>,
     pub(crate) kafka_services: RegistryOf<Address, KafkaServiceInfo>,
     pub(crate) hop_services: RegistryOf<Address, HopServiceInfo>,
+    pub(crate) service_plugins: RegistryOf<String, ServicePluginInfo>,
+    pub(crate) service_discoveries: RegistryOf<Address, ServiceDiscoveryInfo>,
     pub(crate) relays: RegistryOf<String, RegistryRelayInfo>,
+    pub(crate) relay_reservations: RelayAliasReservations,
     pub(crate) inlets: RegistryOf<String, InletInfo>,
     pub(crate) outlets: RegistryOf<Address, OutletInfo>,
 }
 
+/// Aliases of the relays which are being created.
+/// An alias is reserved until its relay is registered, so that two relays can't be created
+/// concurrently with the same alias
+#[derive(Default)]
+pub(crate) struct RelayAliasReservations {
+    aliases: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl RelayAliasReservations {
+    /// Reserve an alias, or return None if it is already reserved
+    pub fn reserve(&self, alias: &str) -> Option<RelayAliasReservation> {
+        if !self.aliases.lock().unwrap().insert(alias.to_string()) {
+            return None;
+        }
+        Some(RelayAliasReservation {
+            alias: alias.to_string(),
+            aliases: self.aliases.clone(),
+        })
+    }
+}
+
+/// Reservation of a relay alias, released when dropped
+pub(crate) struct RelayAliasReservation {
+    alias: String,
+    aliases: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl Drop for RelayAliasReservation {
+    fn drop(&mut self) {
+        self.aliases.lock().unwrap().remove(&self.alias);
+    }
+}
+
 pub(crate) struct RegistryOf<K, V> {
     map: RwLock<BTreeMap<K, V>>,
 }
@@ -386,6 +642,10 @@ mod tests {
     }
 
     fn outlet_info(worker_addr: Address) -> OutletInfo {
-        OutletInfo::new(&SocketAddr::from(([127, 0, 0, 1], 0)), Some(&worker_addr))
+        OutletInfo::new(
+            &SocketAddr::from(([127, 0, 0, 1], 0)),
+            Some(&worker_addr),
+            TcpPortalBandwidthLimiter::unlimited(),
+        )
     }
 }
@@ -0,0 +1,386 @@
+//! Chains of relays, making a node reachable through several intermediate nodes.
+//!
+//! A chain is created from the node to reach outwards: the first node creates a relay at the
+//! second one, the second node creates a relay at the third one, and so on. The relay created at
+//! the last hop uses the alias chosen by the user, the other relays use generated aliases.
+//!
+//! A client reaches the first node by sending its messages to the forwarding address of the last
+//! relay, followed by the forwarding addresses of the previous relays:
+//! `<last hop>/service/forward_to_<alias>/service/forward_to_<generated alias>/...`.
+//!
+//! Once all the relays are created, the chain is verified by sending a message to the echo service
+//! of the first node, through the chain. If a relay can't be created, or the chain can't be
+//! verified, the relays which were already created are deleted, unless the partial chain must be
+//! kept.
+
+use std::time::Duration;
+
+use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use ockam::remote::RemoteRelayFilter;
+use ockam_core::api::Request;
+use ockam_core::async_trait;
+use ockam_core::compat::rand::random_string;
+use ockam_multiaddr::proto::Project;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+
+use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::service::messages::Messages;
+use crate::nodes::service::relay::Relays;
+use crate::nodes::{BackgroundNodeClient, InMemoryNode};
+use crate::DefaultAddress;
+
+/// Default duration after which the verification of a chain fails
+pub const DEFAULT_RELAY_CHAIN_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Node on which a relay of a chain can be created
+#[async_trait]
+pub trait RelayChainNode: Send + Sync {
+    /// Name of the node
+    fn chain_node_name(&self) -> String;
+
+    /// Create a relay at the given route, without failover addresses nor service filter
+    async fn create_chain_relay(
+        &self,
+        ctx: &Context,
+        at: &MultiAddr,
+        alias: String,
+    ) -> miette::Result<RelayInfo>;
+
+    /// Delete a relay created by [`RelayChainNode::create_chain_relay`]
+    async fn delete_chain_relay(&self, ctx: &Context, alias: &str) -> miette::Result<()>;
+
+    /// Send a message from this node and return the reply
+    async fn send_probe(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Duration,
+    ) -> miette::Result<Vec<u8>>;
+}
+
+/// Relay of a chain, to create on `node` at the route `at`
+pub struct RelayChainHop<'a> {
+    pub node: &'a dyn RelayChainNode,
+    pub at: MultiAddr,
+}
+
+impl<'a> RelayChainHop<'a> {
+    pub fn new(node: &'a dyn RelayChainNode, at: MultiAddr) -> Self {
+        Self { node, at }
+    }
+}
+
+/// Options of the creation of a chain of relays
+#[derive(Debug, Clone)]
+pub struct RelayChainOptions {
+    keep_partial: bool,
+    probe_timeout: Duration,
+}
+
+impl Default for RelayChainOptions {
+    fn default() -> Self {
+        Self {
+            keep_partial: false,
+            probe_timeout: DEFAULT_RELAY_CHAIN_PROBE_TIMEOUT,
+        }
+    }
+}
+
+impl RelayChainOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the relays already created when the chain can't be completed
+    pub fn with_keep_partial(mut self, keep_partial: bool) -> Self {
+        self.keep_partial = keep_partial;
+        self
+    }
+
+    /// Set the duration after which the verification of the chain fails
+    pub fn with_probe_timeout(mut self, probe_timeout: Duration) -> Self {
+        self.probe_timeout = probe_timeout;
+        self
+    }
+}
+
+/// Chain of relays, from the node to reach to the last hop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelayChain {
+    /// Alias of the relay created at the last hop
+    pub alias: String,
+    /// Route a client must use to reach the first node of the chain
+    pub address: MultiAddr,
+    pub relays: Vec<RelayChainRelay>,
+}
+
+/// Relay created for a chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RelayChainRelay {
+    /// Name of the node which created the relay
+    pub node: String,
+    /// Route at which the relay was created
+    pub at: MultiAddr,
+    pub alias: String,
+    /// Forwarding address of the relay, on the node at `at`
+    pub remote_address: MultiAddr,
+}
+
+/// Create a chain of relays, starting with the relay of the node to reach.
+///
+/// The relays are created in order, then the chain is verified with a message sent by
+/// the first node to its own echo service, through the chain.
+pub async fn create_relay_chain(
+    ctx: &Context,
+    alias: &str,
+    hops: &[RelayChainHop<'_>],
+    options: &RelayChainOptions,
+) -> miette::Result<RelayChain> {
+    let (first, last) = match (hops.first(), hops.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Err(miette!("A chain of relays needs at least one hop")),
+    };
+
+    let mut created: Vec<(&dyn RelayChainNode, RelayChainRelay)> = vec![];
+    for (index, hop) in hops.iter().enumerate() {
+        let relay_alias = if index == hops.len() - 1 {
+            alias.to_string()
+        } else {
+            format!("{alias}-{}", random_string().to_lowercase())
+        };
+        let node_name = hop.node.chain_node_name();
+        debug!(node = %node_name, at = %hop.at, alias = %relay_alias, "creating a chained relay");
+
+        let relay = match hop
+            .node
+            .create_chain_relay(ctx, &hop.at, relay_alias.clone())
+            .await
+            .and_then(|relay| remote_address(&relay))
+        {
+            Ok(remote_address) => RelayChainRelay {
+                node: node_name,
+                at: hop.at.clone(),
+                alias: relay_alias,
+                remote_address,
+            },
+            Err(err) => {
+                let cause = format!(
+                    "The relay of the node {node_name} could not be created at {}: {err}",
+                    hop.at
+                );
+                return Err(rollback(ctx, created, options, cause).await);
+            }
+        };
+        created.push((hop.node, relay));
+    }
+
+    let mut address = last.at.clone();
+    for (_, relay) in created.iter().rev() {
+        address
+            .concat_mut(&relay.remote_address)
+            .into_diagnostic()?;
+    }
+
+    let probe = address
+        .clone()
+        .concat(&secure_echo_address()?)
+        .into_diagnostic()?;
+    let message = random_string().into_bytes();
+    let verified = match first
+        .node
+        .send_probe(ctx, &probe, message.clone(), options.probe_timeout)
+        .await
+    {
+        Ok(reply) if reply == message => Ok(()),
+        Ok(_) => Err("the reply differs from the message".to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+    if let Err(err) = verified {
+        let cause =
+            format!("The chain could not be verified by sending a message to {probe}: {err}");
+        return Err(rollback(ctx, created, options, cause).await);
+    }
+
+    Ok(RelayChain {
+        alias: alias.to_string(),
+        address,
+        relays: created.into_iter().map(|(_, relay)| relay).collect(),
+    })
+}
+
+/// Delete the relays created for a chain which could not be completed, in the reverse order
+/// of their creation, and return the error describing the failure
+async fn rollback(
+    ctx: &Context,
+    created: Vec<(&dyn RelayChainNode, RelayChainRelay)>,
+    options: &RelayChainOptions,
+    cause: String,
+) -> miette::Report {
+    if created.is_empty() {
+        return miette!(cause);
+    }
+    let relays = created
+        .iter()
+        .map(|(_, relay)| format!("{} on {}", relay.alias, relay.node))
+        .collect::<Vec<_>>()
+        .join(", ");
+    if options.keep_partial {
+        return miette!("{cause}. The relays already created were kept: {relays}");
+    }
+
+    let mut not_deleted = vec![];
+    for (node, relay) in created.iter().rev() {
+        if let Err(err) = node.delete_chain_relay(ctx, &relay.alias).await {
+            warn!(node = %relay.node, alias = %relay.alias, %err, "the chained relay could not be deleted");
+            not_deleted.push(format!("{} on {}", relay.alias, relay.node));
+        }
+    }
+    if not_deleted.is_empty() {
+        miette!("{cause}. The relays already created were deleted: {relays}")
+    } else {
+        miette!(
+            "{cause}. Some of the relays already created could not be deleted: {}",
+            not_deleted.join(", ")
+        )
+    }
+}
+
+fn remote_address(relay: &RelayInfo) -> miette::Result<MultiAddr> {
+    relay
+        .remote_address_ma()
+        .into_diagnostic()?
+        .ok_or_else(|| miette!("The relay {} has no remote address", relay.alias()))
+}
+
+fn secure_echo_address() -> miette::Result<MultiAddr> {
+    format!(
+        "/secure/{}/service/{}",
+        DefaultAddress::SECURE_CHANNEL_LISTENER,
+        DefaultAddress::ECHO_SERVICE
+    )
+    .parse()
+    .into_diagnostic()
+}
+
+/// Relays at projects are registered at the project relay service, the other relays
+/// are registered at the relay service of a Rust node
+fn at_rust_node(at: &MultiAddr) -> bool {
+    !at.starts_with(Project::CODE)
+}
+
+/// Forwarding address registered for a relay created on a Rust node
+fn relay_address(alias: &str, at: &MultiAddr) -> String {
+    if at_rust_node(at) {
+        format!("forward_to_{alias}")
+    } else {
+        alias.to_string()
+    }
+}
+
+#[async_trait]
+impl RelayChainNode for InMemoryNode {
+    fn chain_node_name(&self) -> String {
+        self.node_name()
+    }
+
+    async fn create_chain_relay(
+        &self,
+        ctx: &Context,
+        at: &MultiAddr,
+        alias: String,
+    ) -> miette::Result<RelayInfo> {
+        let relay_address = relay_address(&alias, at);
+        self.create_relay(
+            ctx,
+            at,
+            alias,
+            at_rust_node(at),
+            None,
+            Some(relay_address),
+            vec![],
+            false,
+            RemoteRelayFilter::new(),
+        )
+        .await
+        .into_diagnostic()
+    }
+
+    async fn delete_chain_relay(&self, _ctx: &Context, alias: &str) -> miette::Result<()> {
+        self.delete_relay(alias).await.into_diagnostic()
+    }
+
+    async fn send_probe(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Duration,
+    ) -> miette::Result<Vec<u8>> {
+        self.node_manager
+            .send_message(ctx, to, message, Some(timeout))
+            .await
+    }
+}
+
+#[async_trait]
+impl RelayChainNode for BackgroundNodeClient {
+    fn chain_node_name(&self) -> String {
+        self.node_name()
+    }
+
+    async fn create_chain_relay(
+        &self,
+        ctx: &Context,
+        at: &MultiAddr,
+        alias: String,
+    ) -> miette::Result<RelayInfo> {
+        let relay_address = relay_address(&alias, at);
+        Relays::create_relay(
+            self,
+            ctx,
+            at,
+            alias,
+            None,
+            Some(relay_address),
+            at_rust_node(at),
+            vec![],
+            false,
+            RemoteRelayFilter::new(),
+        )
+        .await
+    }
+
+    async fn delete_chain_relay(&self, ctx: &Context, alias: &str) -> miette::Result<()> {
+        self.tell(ctx, Request::delete(format!("/node/relay/{alias}")))
+            .await
+    }
+
+    async fn send_probe(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        timeout: Duration,
+    ) -> miette::Result<Vec<u8>> {
+        self.send_message(ctx, to, message, Some(timeout)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn relays_at_projects_use_their_alias_as_forwarding_address() {
+        let node = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4000").unwrap();
+        let project = MultiAddr::from_str("/project/p1").unwrap();
+        assert_eq!(relay_address("edge", &node), "forward_to_edge");
+        assert_eq!(relay_address("edge", &project), "edge");
+    }
+}
@@ -7,24 +7,24 @@ use std::path::PathBuf;
 use std::time::Duration;
 
 use miette::IntoDiagnostic;
-use minicbor::{Decoder, Encode};
+use minicbor::{Decode, Decoder, Encode};
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{
-    CachedCredentialRetrieverCreator, CredentialRetrieverCreator, MemoryCredentialRetrieverCreator,
-    RemoteCredentialRetrieverCreator, RemoteCredentialRetrieverInfo,
-};
-use ockam::identity::{Identifier, SecureChannels};
-use ockam::{
-    Address, Context, RelayService, RelayServiceOptions, Result, Routed, TcpTransport, Worker,
-};
+use ockam::identity::{CredentialRetrieverCreator, RemoteCredentialRetrieverInfo};
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo, SecureChannels};
+use ockam::{Address, Context, Result, Routed, TcpTransport, Worker};
 use ockam_abac::expr::str;
-use ockam_abac::{Action, Env, Expr, Resource};
-use ockam_core::api::{Method, RequestHeader, Response};
-use ockam_core::compat::{string::String, sync::Arc};
+use ockam_abac::{Action, Env, Expr, Policies, Resource};
+use ockam_core::api::{Method, RequestHeader, Response, Status};
+use ockam_core::compat::{
+    string::String,
+    sync::{Arc, RwLock},
+};
+use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
-use ockam_core::{AllowAll, AsyncTryClone, IncomingAccessControl};
+use ockam_core::{AllowAll, IncomingAccessControl, MessagePriority};
 use ockam_multiaddr::MultiAddr;
+use ockam_node::{NodeEvent, NodeEventKind, NodeEvents};
 
 use crate::cli_state::CliState;
 use crate::cloud::{AuthorityNodeClient, CredentialsEnabled, ProjectNodeClient};
@@ -32,31 +32,46 @@ use crate::nodes::connection::{
     Connection, ConnectionBuilder, PlainTcpInstantiator, ProjectInstantiator,
     SecureChannelInstantiator,
 };
-use crate::nodes::models::policies::SetPolicyRequest;
+use crate::nodes::models::api_version::NODE_API_VERSION;
 use crate::nodes::models::portal::{OutletList, OutletStatus};
+use crate::nodes::models::startup::StartupReport;
 use crate::nodes::models::transport::{TransportMode, TransportType};
-use crate::nodes::registry::KafkaServiceKind;
-use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::credentials_prefetch::{
+    CredentialPrefetch, DEFAULT_CREDENTIAL_PREFETCH_INITIAL_BACKOFF,
+    DEFAULT_CREDENTIAL_PREFETCH_TIMEOUT,
+};
+use crate::nodes::service::requests::InFlightRequests;
+use crate::nodes::service::routing_table::{ApiRequest, RoutingTable};
+use crate::nodes::service::trust::NodeManagerTrust;
 use crate::nodes::{InMemoryNode, NODEMANAGER_ADDR};
 use crate::session::MedicHandle;
 
 use super::registry::Registry;
 
 pub(crate) mod background_node_client;
+pub mod credentials_prefetch;
 pub mod default_address;
+pub mod discovery;
 mod flow_controls;
 pub(crate) mod in_memory_node;
 pub mod kafka_services;
 pub mod messages;
 mod node_services;
+pub mod plugins;
 pub(crate) mod policy;
 pub mod portals;
 mod projects;
 pub mod relay;
+mod requests;
+mod routing_table;
 mod secure_channel;
+pub mod startup;
 mod transport;
+mod trust;
 pub mod workers;
 
+pub(crate) use requests::{release_on_cancel, CreatedResource};
+
 const TARGET: &str = "ockam_api::nodemanager::service";
 
 /// Generate a new alias for some user created extension
@@ -78,6 +93,27 @@ pub(crate) fn encode_response<T: Encode<()>>(
     Ok(v)
 }
 
+/// Decode the body of a request.
+/// A body which can not be decoded was most likely encoded by a client using
+/// another version of the node manager API, so the request is reported as unsupported.
+fn decode_body<'a, T: Decode<'a, ()>>(dec: &mut Decoder<'a>) -> Result<T> {
+    dec.decode()
+        .map_err(|e| ockam_core::Error::new(Origin::Api, Kind::Unsupported, e))
+}
+
+/// Create a response for a request which is not supported by this version of the node manager API.
+/// The response contains the request method and path, and the unknown part of the request.
+pub(crate) fn unsupported_request(
+    req: &RequestHeader,
+    unknown: &str,
+) -> Response<ockam_core::api::Error> {
+    Response::error(
+        req,
+        &format!("unsupported request for node API {NODE_API_VERSION}: {unknown}"),
+        Status::NotImplemented,
+    )
+}
+
 /// Node manager provides high-level operations to
 ///  - send messages
 ///  - create secure channels, inlet, outlet
@@ -90,10 +126,13 @@ pub struct NodeManager {
     api_transport_flow_control_id: FlowControlId,
     pub(crate) tcp_transport: TcpTransport,
     pub(crate) secure_channels: Arc<SecureChannels>,
-    pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
-    authority: Option<Identifier>,
+    pub(crate) credential_prefetch: CredentialPrefetch,
+    trust: RwLock<NodeManagerTrust>,
     pub(crate) registry: Arc<Registry>,
     pub(crate) medic_handle: MedicHandle,
+    pub(crate) node_events: NodeEvents,
+    pub(crate) policies: Policies,
+    startup_report: StartupReport,
 }
 
 impl NodeManager {
@@ -113,11 +152,15 @@ impl NodeManager {
     }
 
     pub fn credential_retriever_creator(&self) -> Option<Arc<dyn CredentialRetrieverCreator>> {
-        self.credential_retriever_creator.clone()
+        self.trust
+            .read()
+            .unwrap()
+            .credential_retriever_creator
+            .clone()
     }
 
     pub fn authority(&self) -> Option<Identifier> {
-        self.authority.clone()
+        self.trust.read().unwrap().authority.clone()
     }
 
     pub fn node_name(&self) -> String {
@@ -137,6 +180,9 @@ impl NodeManager {
                 .iter()
                 .map(|(_, info)| {
                     OutletStatus::new(info.socket_addr, info.worker_addr.clone(), None)
+                        .with_bandwidth(&info.bandwidth)
+                        .with_connection_pool(info.pool.as_ref())
+                        .with_identity_forwarding(info.identity_forwarding.clone())
                 })
                 .collect(),
         )
@@ -194,11 +240,17 @@ impl NodeManager {
 #[derive(Clone)]
 pub struct NodeManagerWorker {
     pub node_manager: Arc<InMemoryNode>,
+    routing_table: Arc<RoutingTable>,
+    requests: Arc<InFlightRequests>,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: Arc<InMemoryNode>) -> Self {
-        NodeManagerWorker { node_manager }
+        NodeManagerWorker {
+            node_manager,
+            routing_table: Arc::new(Self::routing_table()),
+            requests: Arc::new(InFlightRequests::default()),
+        }
     }
 
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
@@ -231,7 +283,7 @@ impl NodeManager {
             env.put("action.id", str(action_str));
 
             // Store policy for the given resource and action
-            let policies = self.cli_state.policies();
+            let policies = self.policies.clone();
             if let Some(expression) = expression {
                 policies
                     .store_policy_for_resource_name(&resource.resource_name, &action, &expression)
@@ -248,7 +300,8 @@ impl NodeManager {
                     env,
                     authority,
                 )
-                .await?;
+                .await?
+                .with_node_events(self.node_events.clone());
 
             cfg_if::cfg_if! {
                 if #[cfg(feature = "std")] {
@@ -277,6 +330,7 @@ pub struct NodeManagerGeneralOptions {
     node_name: String,
     start_default_services: bool,
     persistent: bool,
+    policies: Option<Policies>,
 }
 
 impl NodeManagerGeneralOptions {
@@ -291,8 +345,17 @@ impl NodeManagerGeneralOptions {
             node_name,
             start_default_services,
             persistent,
+            policies: None,
         }
     }
+
+    /// Use the given policies instead of the policies stored in the CLI state.
+    /// This allows an embedded node to use read-only policies, for example loaded
+    /// with [`ockam_abac::StaticPoliciesRepository`]
+    pub fn with_policies(mut self, policies: Policies) -> Self {
+        self.policies = Some(policies);
+        self
+    }
 }
 
 #[derive(Clone)]
@@ -327,7 +390,7 @@ impl NodeManagerTransportOptions {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum NodeManagerCredentialRetrieverOptions {
     None,
     CacheOnly(Identifier),
@@ -338,6 +401,7 @@ pub enum NodeManagerCredentialRetrieverOptions {
 pub struct NodeManagerTrustOptions {
     credential_retriever_options: NodeManagerCredentialRetrieverOptions,
     authority: Option<Identifier>,
+    eager_credentials: bool,
 }
 
 impl NodeManagerTrustOptions {
@@ -348,8 +412,30 @@ impl NodeManagerTrustOptions {
         Self {
             credential_retriever_options,
             authority,
+            eager_credentials: false,
         }
     }
+
+    /// Retrieve the node credential when the node starts, instead of when it is first needed
+    pub fn with_eager_credentials(mut self, eager_credentials: bool) -> Self {
+        self.eager_credentials = eager_credentials;
+        self
+    }
+
+    /// Return the options used to retrieve the node credential
+    pub fn credential_retriever_options(&self) -> &NodeManagerCredentialRetrieverOptions {
+        &self.credential_retriever_options
+    }
+
+    /// Return the identifier of the authority trusted by the node
+    pub fn authority(&self) -> Option<&Identifier> {
+        self.authority.as_ref()
+    }
+
+    /// Return true if the node credential must be retrieved when the node starts
+    pub fn eager_credentials(&self) -> bool {
+        self.eager_credentials
+    }
 }
 
 impl NodeManager {
@@ -386,33 +472,36 @@ impl NodeManager {
             .await?
             .identifier();
 
-        debug!("create default resource type policies");
-        cli_state
-            .policies()
-            .store_default_resource_type_policies()
-            .await?;
+        // the default resource type policies are only stored in the CLI state, provided
+        // policies are used as they are
+        let policies = match general_options.policies {
+            Some(policies) => policies,
+            None => {
+                debug!("create default resource type policies");
+                let policies = cli_state.policies();
+                policies.store_default_resource_type_policies().await?;
+                policies
+            }
+        };
 
-        let credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>> =
-            match trust_options.credential_retriever_options {
-                NodeManagerCredentialRetrieverOptions::None => None,
-                NodeManagerCredentialRetrieverOptions::CacheOnly(issuer) => {
-                    Some(Arc::new(CachedCredentialRetrieverCreator::new(
-                        issuer.clone(),
-                        secure_channels.identities().cached_credentials_repository(),
-                    )))
-                }
-                NodeManagerCredentialRetrieverOptions::Remote(info) => {
-                    Some(Arc::new(RemoteCredentialRetrieverCreator::new(
-                        ctx.async_try_clone().await?,
-                        Arc::new(transport_options.tcp_transport.clone()),
-                        secure_channels.clone(),
-                        info.clone(),
-                    )))
-                }
-                NodeManagerCredentialRetrieverOptions::InMemory(credential) => {
-                    Some(Arc::new(MemoryCredentialRetrieverCreator::new(credential)))
-                }
-            };
+        let credential_retriever_creator = Self::make_credential_retriever_creator(
+            ctx,
+            &transport_options.tcp_transport,
+            &secure_channels,
+            &trust_options.credential_retriever_options,
+        )
+        .await?;
+
+        let prefetch_credentials =
+            credential_retriever_creator.is_some() && trust_options.eager_credentials;
+        let credential_prefetch = if prefetch_credentials {
+            CredentialPrefetch::new(
+                DEFAULT_CREDENTIAL_PREFETCH_TIMEOUT,
+                DEFAULT_CREDENTIAL_PREFETCH_INITIAL_BACKOFF,
+            )
+        } else {
+            CredentialPrefetch::disabled()
+        };
 
         let mut s = Self {
             cli_state,
@@ -421,73 +510,39 @@ impl NodeManager {
             api_transport_flow_control_id: transport_options.api_transport_flow_control_id,
             tcp_transport: transport_options.tcp_transport,
             secure_channels,
-            credential_retriever_creator,
-            authority: trust_options.authority,
+            credential_prefetch,
+            trust: RwLock::new(NodeManagerTrust::new(
+                trust_options.credential_retriever_options,
+                credential_retriever_creator,
+                trust_options.authority,
+            )),
             registry,
             medic_handle,
+            node_events: ctx.node_events().clone(),
+            policies,
+            startup_report: StartupReport::default(),
         };
 
-        debug!("retrieve the node identifier");
-        s.initialize_services(ctx, general_options.start_default_services)
-            .await?;
+        debug!("persist the node events");
+        s.persist_node_events();
+
+        debug!("start the node services");
+        s.startup_report = s
+            .run_startup_plan(
+                ctx,
+                general_options.start_default_services,
+                prefetch_credentials,
+            )
+            .await;
+        s.node_events.publish(NodeEvent::new(
+            NodeEventKind::NodeStarted,
+            s.node_name.as_str(),
+        ));
         info!("created a node manager for the node: {}", s.node_name);
 
         Ok(s)
     }
 
-    async fn initialize_default_services(
-        &self,
-        ctx: &Context,
-        api_flow_control_id: &FlowControlId,
-    ) -> Result<()> {
-        // Start services
-        ctx.flow_controls()
-            .add_consumer(DefaultAddress::UPPERCASE_SERVICE, api_flow_control_id);
-        self.start_uppercase_service_impl(ctx, DefaultAddress::UPPERCASE_SERVICE.into())
-            .await?;
-
-        RelayService::create(
-            ctx,
-            DefaultAddress::RELAY_SERVICE,
-            RelayServiceOptions::new()
-                .service_as_consumer(api_flow_control_id)
-                .relay_as_consumer(api_flow_control_id),
-        )
-        .await?;
-
-        self.create_secure_channel_listener(
-            DefaultAddress::SECURE_CHANNEL_LISTENER.into(),
-            None, // Not checking identifiers here in favor of credential check
-            None,
-            ctx,
-        )
-        .await?;
-
-        Ok(())
-    }
-
-    async fn initialize_services(
-        &mut self,
-        ctx: &Context,
-        start_default_services: bool,
-    ) -> Result<()> {
-        let api_flow_control_id = self.api_transport_flow_control_id.clone();
-
-        if start_default_services {
-            self.initialize_default_services(ctx, &api_flow_control_id)
-                .await?;
-        }
-
-        // Always start the echoer service as ockam_api::Medic assumes it will be
-        // started unconditionally on every node. It's used for liveliness checks.
-        ctx.flow_controls()
-            .add_consumer(DefaultAddress::ECHO_SERVICE, &api_flow_control_id);
-        self.start_echoer_service(ctx, DefaultAddress::ECHO_SERVICE.into())
-            .await?;
-
-        Ok(())
-    }
-
     pub async fn make_connection(
         &self,
         ctx: Arc<Context>,
@@ -546,12 +601,40 @@ impl NodeManager {
 impl NodeManagerWorker {
     //////// Request matching and response handling ////////
 
-    #[instrument(skip_all, fields(method = ?req.method(), path = req.path()))]
+    /// Register the handlers of the requests of all the node manager services
+    fn routing_table() -> RoutingTable {
+        let mut routes = RoutingTable::default();
+        node_services::add_routes(&mut routes);
+        startup::add_routes(&mut routes);
+        trust::add_routes(&mut routes);
+        transport::add_routes(&mut routes);
+        secure_channel::add_routes(&mut routes);
+        kafka_services::add_routes(&mut routes);
+        plugins::add_routes(&mut routes);
+        discovery::add_routes(&mut routes);
+        relay::add_routes(&mut routes);
+        portals::add_routes(&mut routes);
+        flow_controls::add_routes(&mut routes);
+        workers::add_routes(&mut routes);
+        policy::add_routes(&mut routes);
+        messages::add_routes(&mut routes);
+        requests::add_routes(&mut routes);
+        routes.add(
+            Method::Get,
+            "/node/api",
+            "list_api_endpoints",
+            |w, _ctx, r| Box::pin(async move { r.respond(w.list_api_endpoints()) }),
+        );
+        routes
+    }
+
+    #[instrument(skip_all, fields(method = ?req.method(), path = req.path(), handler = tracing::field::Empty))]
     async fn handle_request(
         &mut self,
         ctx: &mut Context,
         req: &RequestHeader,
-        dec: &mut Decoder<'_>,
+        caller: Option<&Identifier>,
+        body: &[u8],
     ) -> Result<Vec<u8>> {
         debug! {
             target: TARGET,
@@ -562,196 +645,67 @@ impl NodeManagerWorker {
             "request"
         }
 
-        use Method::*;
         let path = req.path();
-        let path_segments = req.path_segments::<5>();
         let method = match req.method() {
             Some(m) => m,
-            None => todo!(),
-        };
-
-        let r = match (method, path_segments.as_slice()) {
-            // ==*== Basic node information ==*==
-            // TODO: create, delete, destroy remote nodes
-            (Get, ["node"]) => encode_response(req, self.get_node_status(ctx).await)?,
-
-            // ==*== Tcp Connection ==*==
-            (Get, ["node", "tcp", "connection"]) => self.get_tcp_connections(req).await.to_vec()?,
-            (Get, ["node", "tcp", "connection", address]) => {
-                encode_response(req, self.get_tcp_connection(address.to_string()).await)?
-            }
-            (Post, ["node", "tcp", "connection"]) => {
-                encode_response(req, self.create_tcp_connection(ctx, dec.decode()?).await)?
-            }
-            (Delete, ["node", "tcp", "connection"]) => {
-                encode_response(req, self.delete_tcp_connection(dec.decode()?).await)?
-            }
-
-            // ==*== Tcp Listeners ==*==
-            (Get, ["node", "tcp", "listener"]) => self.get_tcp_listeners(req).await.to_vec()?,
-            (Get, ["node", "tcp", "listener", address]) => {
-                encode_response(req, self.get_tcp_listener(address.to_string()).await)?
-            }
-            (Post, ["node", "tcp", "listener"]) => {
-                encode_response(req, self.create_tcp_listener(dec.decode()?).await)?
-            }
-            (Delete, ["node", "tcp", "listener"]) => {
-                encode_response(req, self.delete_tcp_listener(dec.decode()?).await)?
+            None => {
+                warn!(%path, "Called an endpoint without a method");
+                return unsupported_request(req, &format!("missing method for {path}")).to_vec();
             }
+        };
 
-            // ==*== Secure channels ==*==
-            (Get, ["node", "secure_channel"]) => {
-                encode_response(req, self.list_secure_channels().await)?
-            }
-            (Get, ["node", "secure_channel_listener"]) => {
-                encode_response(req, self.list_secure_channel_listener().await)?
-            }
-            (Post, ["node", "secure_channel"]) => {
-                encode_response(req, self.create_secure_channel(dec.decode()?, ctx).await)?
-            }
-            (Delete, ["node", "secure_channel"]) => {
-                encode_response(req, self.delete_secure_channel(dec.decode()?, ctx).await)?
-            }
-            (Get, ["node", "show_secure_channel"]) => {
-                encode_response(req, self.show_secure_channel(dec.decode()?).await)?
-            }
-            (Post, ["node", "secure_channel_listener"]) => encode_response(
-                req,
-                self.create_secure_channel_listener(dec.decode()?, ctx)
-                    .await,
-            )?,
-            (Delete, ["node", "secure_channel_listener"]) => encode_response(
-                req,
-                self.delete_secure_channel_listener(dec.decode()?, ctx)
-                    .await,
-            )?,
-            (Get, ["node", "show_secure_channel_listener"]) => {
-                encode_response(req, self.show_secure_channel_listener(dec.decode()?).await)?
-            }
-
-            // ==*== Services ==*==
-            (Post, ["node", "services", DefaultAddress::UPPERCASE_SERVICE]) => {
-                encode_response(req, self.start_uppercase_service(ctx, dec.decode()?).await)?
-            }
-            (Post, ["node", "services", DefaultAddress::ECHO_SERVICE]) => {
-                encode_response(req, self.start_echoer_service(ctx, dec.decode()?).await)?
-            }
-            (Post, ["node", "services", DefaultAddress::HOP_SERVICE]) => {
-                encode_response(req, self.start_hop_service(ctx, dec.decode()?).await)?
-            }
-            (Post, ["node", "services", DefaultAddress::KAFKA_OUTLET]) => encode_response(
-                req,
-                self.start_kafka_outlet_service(ctx, dec.decode()?).await,
-            )?,
-            (Delete, ["node", "services", DefaultAddress::KAFKA_OUTLET]) => encode_response(
-                req,
-                self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Outlet)
-                    .await,
-            )?,
-            (Post, ["node", "services", DefaultAddress::KAFKA_CONSUMER]) => encode_response(
-                req,
-                self.start_kafka_consumer_service(ctx, dec.decode()?).await,
-            )?,
-            (Delete, ["node", "services", DefaultAddress::KAFKA_CONSUMER]) => encode_response(
-                req,
-                self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Consumer)
-                    .await,
-            )?,
-            (Post, ["node", "services", DefaultAddress::KAFKA_PRODUCER]) => encode_response(
-                req,
-                self.start_kafka_producer_service(ctx, dec.decode()?).await,
-            )?,
-            (Delete, ["node", "services", DefaultAddress::KAFKA_PRODUCER]) => encode_response(
-                req,
-                self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Producer)
-                    .await,
-            )?,
-            (Post, ["node", "services", DefaultAddress::KAFKA_DIRECT]) => encode_response(
-                req,
-                self.start_kafka_direct_service(ctx, dec.decode()?).await,
-            )?,
-            (Delete, ["node", "services", DefaultAddress::KAFKA_DIRECT]) => encode_response(
-                req,
-                self.delete_kafka_service(ctx, dec.decode()?, KafkaServiceKind::Direct)
-                    .await,
-            )?,
-            (Get, ["node", "services"]) => encode_response(req, self.list_services().await)?,
-            (Get, ["node", "services", service_type]) => {
-                encode_response(req, self.list_services_of_type(service_type).await)?
-            }
-
-            // ==*== Relay commands ==*==
-            (Get, ["node", "relay", alias]) => {
-                encode_response(req, self.show_relay(req, alias).await)?
-            }
-            (Get, ["node", "relay"]) => encode_response(req, self.get_relays(req).await)?,
-            (Delete, ["node", "relay", alias]) => {
-                encode_response(req, self.delete_relay(req, alias).await)?
-            }
-            (Post, ["node", "relay"]) => {
-                encode_response(req, self.create_relay(ctx, req, dec.decode()?).await)?
-            }
-
-            // ==*== Inlets & Outlets ==*==
-            (Get, ["node", "inlet"]) => encode_response(req, self.get_inlets().await)?,
-            (Get, ["node", "inlet", alias]) => encode_response(req, self.show_inlet(alias).await)?,
-            (Get, ["node", "outlet"]) => self.get_outlets(req).await.to_vec()?,
-            (Get, ["node", "outlet", addr]) => {
-                let addr: Address = addr.to_string().into();
-                encode_response(req, self.show_outlet(&addr).await)?
-            }
-            (Post, ["node", "inlet"]) => {
-                encode_response(req, self.create_inlet(ctx, dec.decode()?).await)?
-            }
-            (Post, ["node", "outlet"]) => {
-                encode_response(req, self.create_outlet(ctx, dec.decode()?).await)?
-            }
-            (Delete, ["node", "outlet", addr]) => {
-                let addr: Address = addr.to_string().into();
-                encode_response(req, self.delete_outlet(&addr).await)?
-            }
-            (Delete, ["node", "inlet", alias]) => {
-                encode_response(req, self.delete_inlet(alias).await)?
-            }
-            (Delete, ["node", "portal"]) => todo!(),
-
-            // ==*== Flow Controls ==*==
-            (Post, ["node", "flow_controls", "add_consumer"]) => {
-                encode_response(req, self.add_consumer(ctx, dec.decode()?).await)?
+        let routing_table = self.routing_table.clone();
+        let (endpoint, params) = match routing_table.find(method, path) {
+            Some(found) => found,
+            // ==*== Catch-all for Unimplemented APIs ==*==
+            None => {
+                warn!(%method, %path, "Called invalid endpoint");
+                return unsupported_request(req, &format!("unknown endpoint {method} {path}"))
+                    .to_vec();
             }
+        };
+        tracing::Span::current().record("handler", endpoint.name());
 
-            // ==*== Workers ==*==
-            (Get, ["node", "workers"]) => encode_response(req, self.list_workers(ctx).await)?,
-
-            // ==*== Policies ==*==
-            (Post, ["policy", action]) => {
-                let payload: SetPolicyRequest = dec.decode()?;
-                encode_response(
-                    req,
-                    self.add_policy(action, payload.resource, payload.expression)
-                        .await,
-                )?
-            }
-            (Get, ["policy", action]) => {
-                encode_response(req, self.get_policy(action, dec.decode()?).await)?
-            }
-            (Get, ["policy"]) => encode_response(req, self.list_policies(dec.decode()?).await)?,
-            (Delete, ["policy", action]) => {
-                encode_response(req, self.delete_policy(action, dec.decode()?).await)?
-            }
+        let request = ApiRequest::new(req, caller, params, body);
+        endpoint.handle(self, ctx, &request).await
+    }
 
-            // ==*== Messages ==*==
-            (Post, ["v0", "message"]) => {
-                encode_response(req, self.send_message(ctx, dec.decode()?).await)?
+    /// Return the encoded response of a request, or an error response if it could not be handled
+    fn encode_result(req: &RequestHeader, result: Result<Vec<u8>>) -> Result<Vec<u8>> {
+        let r = match result {
+            Ok(r) => r,
+            Err(err) if err.code().kind == Kind::Unsupported => {
+                warn! {
+                    target: TARGET,
+                    re     = %req.id(),
+                    method = ?req.method(),
+                    path   = %req.path(),
+                    cause  = %err,
+                    "unsupported request"
+                }
+                unsupported_request(req, &format!("invalid request body ({err})")).to_vec()?
             }
-
-            // ==*== Catch-all for Unimplemented APIs ==*==
-            _ => {
-                warn!(%method, %path, "Called invalid endpoint");
-                Response::bad_request(req, &format!("Invalid endpoint: {} {}", method, path))
+            Err(err) => {
+                error! {
+                    target: TARGET,
+                    re     = %req.id(),
+                    method = ?req.method(),
+                    path   = %req.path(),
+                    code   = %err.code(),
+                    cause  = ?err.source(),
+                    "failed to handle request"
+                }
+                Response::internal_error(req, &format!("failed to handle request: {err} {req:?}"))
                     .to_vec()?
             }
         };
+        debug! {
+            target: TARGET,
+            re     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            "responding"
+        }
         Ok(r)
     }
 }
@@ -767,6 +721,10 @@ impl Worker for NodeManagerWorker {
 
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
         let return_route = msg.return_route();
+        // identity of the caller, when the request is received through a secure channel
+        let caller = IdentitySecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| info.their_identity_id());
         let body = msg.into_body()?;
         let mut dec = Decoder::new(&body);
         let req: RequestHeader = match dec.decode() {
@@ -776,30 +734,27 @@ impl Worker for NodeManagerWorker {
                 return Ok(());
             }
         };
-
-        let r = match self.handle_request(ctx, &req, &mut dec).await {
-            Ok(r) => r,
-            Err(err) => {
-                error! {
-                    target: TARGET,
-                    re     = %req.id(),
-                    method = ?req.method(),
-                    path   = %req.path(),
-                    code   = %err.code(),
-                    cause  = ?err.source(),
-                    "failed to handle request"
-                }
-                Response::internal_error(&req, &format!("failed to handle request: {err} {req:?}"))
-                    .to_vec()?
-            }
-        };
-        debug! {
-            target: TARGET,
-            re     = %req.id(),
-            method = ?req.method(),
-            path   = %req.path(),
-            "responding"
+        let request_body = &body[dec.position()..];
+
+        // long-running requests are handled in their own task, so that they can be cancelled
+        let is_cancellable = req
+            .method()
+            .and_then(|method| self.routing_table.find(method, req.path()))
+            .map(|(endpoint, _)| endpoint.is_cancellable())
+            .unwrap_or(false);
+        if is_cancellable {
+            return self
+                .spawn_cancellable_request(ctx, req, caller, request_body.to_vec(), return_route)
+                .await;
         }
-        ctx.send(return_route, r).await
+
+        self.requests.add_not_cancellable(req.id());
+        let result = self
+            .handle_request(ctx, &req, caller.as_ref(), request_body)
+            .await;
+        let r = Self::encode_result(&req, result)?;
+        // responses are control messages, they are handled before data messages
+        ctx.send_with_priority(return_route, r, MessagePriority::High)
+            .await
     }
 }
@@ -1,16 +1,18 @@
-use std::sync::Arc;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use miette::{miette, IntoDiagnostic};
 use minicbor::{Decode, Encode};
 
-use ockam_core::api::{Reply, Request};
+use ockam_core::api::{Id, Reply, Request, Status};
 use ockam_core::Route;
 use ockam_node::api::Client;
 use ockam_node::Context;
 use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TcpTransport};
 
 use crate::cli_state::CliState;
+use crate::nodes::models::api_version::NodeApiInfo;
 use crate::nodes::NODEMANAGER_ADDR;
 
 /// This struct represents a Client to a node that has been started
@@ -25,6 +27,10 @@ pub struct BackgroundNodeClient {
     to: Route,
     timeout: Option<Duration>,
     tcp_transport: Arc<TcpTransport>,
+    /// Version and capabilities of the node manager API, cached per node name
+    api_info: Arc<Mutex<BTreeMap<String, NodeApiInfo>>>,
+    /// If true, the request in flight is cancelled when the user presses Ctrl-C
+    cancel_on_interrupt: bool,
 }
 
 impl BackgroundNodeClient {
@@ -63,6 +69,12 @@ impl BackgroundNodeClient {
         BackgroundNodeClient::new(tcp, cli_state, node_name)
     }
 
+    /// Default timeout for the requests sent to the node, when no timeout is set on the CliState
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Maximum time to wait for the node to acknowledge the cancellation of a request
+    pub const CANCELLATION_TIMEOUT: Duration = Duration::from_secs(3);
+
     /// Create a new client to send requests to a running background node
     pub fn new(
         tcp_transport: &TcpTransport,
@@ -73,8 +85,10 @@ impl BackgroundNodeClient {
             cli_state: cli_state.clone(),
             node_name: node_name.to_string(),
             to: NODEMANAGER_ADDR.into(),
-            timeout: Some(Duration::from_secs(30)),
+            timeout: Some(cli_state.request_timeout().unwrap_or(Self::DEFAULT_TIMEOUT)),
             tcp_transport: Arc::new(tcp_transport.clone()),
+            api_info: Default::default(),
+            cancel_on_interrupt: false,
         })
     }
 
@@ -103,20 +117,80 @@ impl BackgroundNodeClient {
         Self { timeout, ..self }
     }
 
+    /// Ask the node to cancel the request in flight when the user presses Ctrl-C,
+    /// before returning an error. This is used by the commands sending long-running requests.
+    ///
+    /// Note that once a request has been sent, Ctrl-C does not terminate the process by itself
+    /// anymore: the command is expected to exit when the request is cancelled
+    pub fn cancel_on_interrupt(self) -> Self {
+        Self {
+            cancel_on_interrupt: true,
+            ..self
+        }
+    }
+
     pub fn cli_state(&self) -> &CliState {
         &self.cli_state
     }
 
+    /// Return the version and capabilities of the node manager API of the node.
+    /// They are only requested once per node for the lifetime of this client
+    pub async fn api_info(&self, ctx: &Context) -> miette::Result<NodeApiInfo> {
+        if let Some(api_info) = self.api_info.lock().unwrap().get(&self.node_name) {
+            return Ok(api_info.clone());
+        }
+
+        let api_info = match self
+            .ask_and_get_reply(ctx, Request::get("/node/api_version"))
+            .await?
+        {
+            Reply::Successful(api_info) => api_info,
+            // nodes created before the API was versioned reject the request as an invalid endpoint
+            Reply::Failed(_, Some(Status::BadRequest)) => NodeApiInfo::legacy(),
+            reply => reply.success().into_diagnostic()?,
+        };
+        debug!(node = %self.node_name, version = %api_info.version, capabilities = ?api_info.capabilities, "retrieved the node API version");
+
+        self.api_info
+            .lock()
+            .unwrap()
+            .insert(self.node_name.clone(), api_info.clone());
+        Ok(api_info)
+    }
+
+    /// Return true if the node manager API of the node supports a given capability
+    pub async fn supports(&self, ctx: &Context, capability: &str) -> miette::Result<bool> {
+        Ok(self.api_info(ctx).await?.supports(capability))
+    }
+
+    /// Return an error if the node manager API of the node doesn't support a given capability.
+    /// The feature is a description of what requires that capability.
+    pub async fn require_capability(
+        &self,
+        ctx: &Context,
+        capability: &str,
+        feature: &str,
+    ) -> miette::Result<()> {
+        let api_info = self.api_info(ctx).await?;
+        if api_info.supports(capability) {
+            Ok(())
+        } else {
+            Err(miette!(
+                "node API {} does not support {feature}, upgrade the node {}",
+                api_info.version,
+                self.node_name
+            ))
+        }
+    }
+
     /// Send a request and expect a decodable response
     pub async fn ask<T, R>(&self, ctx: &Context, req: Request<T>) -> miette::Result<R>
     where
         T: Encode<()>,
         R: for<'b> Decode<'b, ()>,
     {
-        self.ask_and_get_reply(ctx, req)
-            .await?
-            .success()
-            .into_diagnostic()
+        let reply = self.ask_and_get_reply(ctx, req).await?;
+        self.success(reply)
     }
 
     /// Send a request and expect a decodable response and use a specific timeout
@@ -135,9 +209,8 @@ impl BackgroundNodeClient {
         let res = client
             .ask(ctx, req)
             .await
-            .into_diagnostic()?
-            .success()
-            .into_diagnostic();
+            .into_diagnostic()
+            .and_then(|reply| self.success(reply));
 
         _ = tcp_connection.stop(ctx).await;
         res
@@ -155,12 +228,47 @@ impl BackgroundNodeClient {
         R: for<'b> Decode<'b, ()>,
     {
         let (tcp_connection, client) = self.make_client().await?;
-        let res = client.ask(ctx, req).await.into_diagnostic();
+        let res = if self.cancel_on_interrupt {
+            let id = req.header().id();
+            tokio::select! {
+                reply = client.ask(ctx, req) => reply.into_diagnostic(),
+                _ = tokio::signal::ctrl_c() => match self.cancel_request(ctx, id).await {
+                    Ok(true) => Err(miette!("The request was cancelled")),
+                    // the request completed already, or the node does not support cancellations
+                    Ok(false) => Err(miette!("The command was interrupted before the node {} replied", self.node_name)),
+                    Err(e) => Err(miette!(
+                        "The command was interrupted, but the request could not be cancelled on the node {}: {e}",
+                        self.node_name
+                    )),
+                },
+            }
+        } else {
+            client.ask(ctx, req).await.into_diagnostic()
+        };
 
         _ = tcp_connection.stop(ctx).await;
         res
     }
 
+    /// Ask the node to cancel a request which is in flight.
+    /// Return false if the request already completed
+    pub async fn cancel_request(&self, ctx: &Context, id: Id) -> miette::Result<bool> {
+        let (tcp_connection, client) = self
+            .make_client_with_timeout(Some(Self::CANCELLATION_TIMEOUT))
+            .await?;
+        let reply = client
+            .tell(ctx, Request::delete(format!("/node/requests/{id}")))
+            .await
+            .into_diagnostic();
+
+        _ = tcp_connection.stop(ctx).await;
+        match reply? {
+            Reply::Successful(_) => Ok(true),
+            Reply::Failed(_, Some(Status::NotFound)) => Ok(false),
+            reply => self.success(reply).map(|_| true),
+        }
+    }
+
     /// Send a request but don't decode the response
     pub async fn tell<T>(&self, ctx: &Context, req: Request<T>) -> miette::Result<()>
     where
@@ -170,9 +278,8 @@ impl BackgroundNodeClient {
         let res = client
             .tell(ctx, req)
             .await
-            .into_diagnostic()?
-            .success()
-            .into_diagnostic();
+            .into_diagnostic()
+            .and_then(|reply| self.success(reply));
 
         _ = tcp_connection.stop(ctx).await;
         res
@@ -194,6 +301,19 @@ impl BackgroundNodeClient {
         res
     }
 
+    /// Return the value of a successful reply.
+    /// A request which is not supported by the node returns an error asking to upgrade the node
+    fn success<R>(&self, reply: Reply<R>) -> miette::Result<R> {
+        match reply {
+            Reply::Failed(error, Some(Status::NotImplemented)) => Err(miette!(
+                "{}, upgrade the node {}",
+                error.message().unwrap_or("unsupported request"),
+                self.node_name
+            )),
+            reply => reply.success().into_diagnostic(),
+        }
+    }
+
     /// This method succeeds if a TCP connection can be established with the node
     pub async fn is_accessible(&self, ctx: &Context) -> miette::Result<()> {
         self.create_tcp_connection()
@@ -217,6 +337,16 @@ impl BackgroundNodeClient {
     /// Create a TCP connection to the node
     async fn create_tcp_connection(&self) -> miette::Result<TcpConnection> {
         let node_info = self.cli_state.get_node(&self.node_name).await?;
+
+        // a mismatch is only reported as a warning, the node might still support the request
+        if let Err(e) = self
+            .cli_state
+            .check_node_binary_version(&self.node_name)
+            .await
+        {
+            debug!(node = %self.node_name, "cannot check the version of the node: {e}");
+        }
+
         let tcp_listener_address = node_info
             .tcp_listener_address()
             .ok_or(miette!(
@@ -0,0 +1,247 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use ockam::identity::{CredentialRetrieverCreator, Identifier};
+use ockam::Result;
+
+use crate::nodes::models::base::{CredentialsState, CredentialsStatus};
+
+/// Default duration during which the node tries to retrieve its credential when it starts
+pub const DEFAULT_CREDENTIAL_PREFETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Default delay before retrying to retrieve the credential. It is doubled after each failed attempt
+pub const DEFAULT_CREDENTIAL_PREFETCH_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Maximum delay between two attempts to retrieve the credential
+const MAX_CREDENTIAL_PREFETCH_BACKOFF: Duration = Duration::from_secs(10);
+
+/// This struct retrieves the node credential eagerly, when the node starts,
+/// instead of waiting for the first secure channel needing it.
+///
+/// The retrieval is retried, with an exponential backoff, for a bounded time.
+/// If the credential still can't be retrieved the node keeps running in a degraded mode
+/// and the credential will be retrieved lazily, as if the pre-fetch was disabled.
+#[derive(Clone, Debug)]
+pub struct CredentialPrefetch {
+    status: Arc<Mutex<CredentialsStatus>>,
+    timeout: Duration,
+    initial_backoff: Duration,
+}
+
+impl CredentialPrefetch {
+    pub fn new(timeout: Duration, initial_backoff: Duration) -> Self {
+        Self {
+            status: Arc::new(Mutex::new(CredentialsStatus::new(
+                CredentialsState::Pending,
+            ))),
+            timeout,
+            initial_backoff,
+        }
+    }
+
+    /// Pre-fetch which is never started, the credential is retrieved lazily
+    pub fn disabled() -> Self {
+        let prefetch = Self::new(
+            DEFAULT_CREDENTIAL_PREFETCH_TIMEOUT,
+            DEFAULT_CREDENTIAL_PREFETCH_INITIAL_BACKOFF,
+        );
+        prefetch.set_state(CredentialsState::Disabled);
+        prefetch
+    }
+
+    /// Return the current outcome of the pre-fetch
+    pub fn status(&self) -> CredentialsStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Retrieve the credential in the background, so that the node can accept requests in the meantime
+    pub(crate) fn start(&self, creator: Arc<dyn CredentialRetrieverCreator>, subject: Identifier) {
+        let prefetch = self.clone();
+        tokio::spawn(async move { prefetch.run(creator.as_ref(), &subject).await });
+    }
+
+    /// Try to retrieve the credential until it succeeds or the timeout elapses.
+    /// Return the final status of the pre-fetch
+    pub(crate) async fn run(
+        &self,
+        creator: &dyn CredentialRetrieverCreator,
+        subject: &Identifier,
+    ) -> CredentialsStatus {
+        let started_at = Instant::now();
+        let mut backoff = self.initial_backoff;
+        loop {
+            let attempts = {
+                let mut status = self.status.lock().unwrap();
+                status.attempts += 1;
+                status.attempts
+            };
+            match Self::retrieve(creator, subject).await {
+                Ok(()) => {
+                    info!(%subject, attempts, "the node credential has been retrieved");
+                    self.set_state(CredentialsState::Ready);
+                    break;
+                }
+                Err(e) => {
+                    warn!(%subject, attempts, "failed to retrieve the node credential: {e}");
+                    self.status.lock().unwrap().last_error = Some(e.to_string());
+                }
+            }
+            let elapsed = started_at.elapsed();
+            if elapsed >= self.timeout {
+                error!(
+                    %subject,
+                    "the node credential could not be retrieved after {attempts} attempts, the node runs in degraded mode"
+                );
+                self.set_state(CredentialsState::Degraded);
+                break;
+            }
+            tokio::time::sleep(backoff.min(self.timeout - elapsed)).await;
+            backoff = (backoff * 2).min(MAX_CREDENTIAL_PREFETCH_BACKOFF);
+        }
+        self.status()
+    }
+
+    async fn retrieve(
+        creator: &dyn CredentialRetrieverCreator,
+        subject: &Identifier,
+    ) -> Result<()> {
+        let retriever = creator.create(subject).await?;
+        retriever.initialize().await?;
+        retriever.retrieve().await?;
+        Ok(())
+    }
+
+    fn set_state(&self, state: CredentialsState) {
+        self.status.lock().unwrap().state = state;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use ockam::identity::models::CredentialAndPurposeKey;
+    use ockam::identity::utils::AttributesBuilder;
+    use ockam::identity::{identities, CredentialRetriever, MemoryCredentialRetriever};
+    use ockam_core::errcode::{Kind, Origin};
+    use ockam_core::{async_trait, Address};
+
+    use super::*;
+    use crate::authenticator::credential_issuer::{
+        DEFAULT_CREDENTIAL_VALIDITY, PROJECT_MEMBER_SCHEMA,
+    };
+
+    /// Retriever failing its first retrievals, then returning a credential
+    struct FlakyCredentialRetriever {
+        failures: Arc<AtomicU32>,
+        credential: MemoryCredentialRetriever,
+    }
+
+    #[async_trait]
+    impl CredentialRetriever for FlakyCredentialRetriever {
+        async fn initialize(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn retrieve(&self) -> Result<CredentialAndPurposeKey> {
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Unavailable,
+                    "the authority is not reachable",
+                ));
+            }
+            self.credential.retrieve().await
+        }
+
+        fn subscribe(&self, _address: &Address) -> Result<()> {
+            Ok(())
+        }
+
+        fn unsubscribe(&self, _address: &Address) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FlakyCredentialRetrieverCreator {
+        failures: Arc<AtomicU32>,
+        credential: CredentialAndPurposeKey,
+    }
+
+    #[async_trait]
+    impl CredentialRetrieverCreator for FlakyCredentialRetrieverCreator {
+        async fn create(&self, _subject: &Identifier) -> Result<Arc<dyn CredentialRetriever>> {
+            Ok(Arc::new(FlakyCredentialRetriever {
+                failures: self.failures.clone(),
+                credential: MemoryCredentialRetriever::new(self.credential.clone()),
+            }))
+        }
+    }
+
+    async fn creator(failures: u32) -> Result<(FlakyCredentialRetrieverCreator, Identifier)> {
+        let identities = identities().await?;
+        let identifier = identities.identities_creation().create_identity().await?;
+        let credential = identities
+            .credentials()
+            .credentials_creation()
+            .issue_credential(
+                &identifier,
+                &identifier,
+                AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA).build(),
+                DEFAULT_CREDENTIAL_VALIDITY,
+            )
+            .await?;
+        let creator = FlakyCredentialRetrieverCreator {
+            failures: Arc::new(AtomicU32::new(failures)),
+            credential,
+        };
+        Ok((creator, identifier))
+    }
+
+    #[tokio::test]
+    async fn the_credential_is_retrieved_at_the_first_attempt() -> Result<()> {
+        let (creator, identifier) = creator(0).await?;
+        let prefetch = CredentialPrefetch::new(Duration::from_secs(5), Duration::from_millis(10));
+
+        let status = prefetch.run(&creator, &identifier).await;
+        assert_eq!(status.state, CredentialsState::Ready);
+        assert_eq!(status.attempts, 1);
+        assert_eq!(status.last_error, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn the_credential_is_retrieved_after_a_transient_failure() -> Result<()> {
+        let (creator, identifier) = creator(2).await?;
+        let prefetch = CredentialPrefetch::new(Duration::from_secs(5), Duration::from_millis(10));
+
+        let status = prefetch.run(&creator, &identifier).await;
+        assert_eq!(status.state, CredentialsState::Ready);
+        assert_eq!(status.attempts, 3);
+        assert!(status.last_error.unwrap().contains("not reachable"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn the_node_is_degraded_when_the_credential_cannot_be_retrieved() -> Result<()> {
+        let (creator, identifier) = creator(u32::MAX).await?;
+        let prefetch =
+            CredentialPrefetch::new(Duration::from_millis(100), Duration::from_millis(10));
+
+        let status = prefetch.run(&creator, &identifier).await;
+        assert_eq!(status.state, CredentialsState::Degraded);
+        assert!(status.attempts > 1);
+        assert!(status.last_error.is_some());
+        assert_eq!(prefetch.status(), status);
+        Ok(())
+    }
+
+    #[test]
+    fn a_disabled_prefetch_is_reported_as_such() {
+        let status = CredentialPrefetch::disabled().status();
+        assert_eq!(status.state, CredentialsState::Disabled);
+        assert_eq!(status.attempts, 0);
+    }
+}
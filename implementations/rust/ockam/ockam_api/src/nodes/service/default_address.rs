@@ -6,11 +6,13 @@ impl DefaultAddress {
     pub const UPPERCASE_SERVICE: &'static str = "uppercase";
     pub const ECHO_SERVICE: &'static str = "echo";
     pub const HOP_SERVICE: &'static str = "hop";
+    pub const SERVICE_DISCOVERY: &'static str = "discovery";
     pub const SECURE_CHANNEL_LISTENER: &'static str = "api";
     pub const DIRECT_AUTHENTICATOR: &'static str = "direct_authenticator";
     pub const CREDENTIAL_ISSUER: &'static str = "credential_issuer";
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
+    pub const AUTHORITY_EVENTS: &'static str = "authority_events";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
     pub const KAFKA_OUTLET: &'static str = "kafka_outlet";
     pub const KAFKA_CONSUMER: &'static str = "kafka_consumer";
@@ -22,11 +24,13 @@ impl DefaultAddress {
             | Self::UPPERCASE_SERVICE
             | Self::ECHO_SERVICE
             | Self::HOP_SERVICE
+            | Self::SERVICE_DISCOVERY
             | Self::SECURE_CHANNEL_LISTENER
             | Self::DIRECT_AUTHENTICATOR
             | Self::CREDENTIAL_ISSUER
             | Self::ENROLLMENT_TOKEN_ISSUER
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
+            | Self::AUTHORITY_EVENTS
             | Self::OKTA_IDENTITY_PROVIDER
             | Self::KAFKA_CONSUMER
             | Self::KAFKA_PRODUCER
@@ -41,11 +45,13 @@ impl DefaultAddress {
             Self::UPPERCASE_SERVICE,
             Self::ECHO_SERVICE,
             Self::HOP_SERVICE,
+            Self::SERVICE_DISCOVERY,
             Self::SECURE_CHANNEL_LISTENER,
             Self::DIRECT_AUTHENTICATOR,
             Self::CREDENTIAL_ISSUER,
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
+            Self::AUTHORITY_EVENTS,
             Self::OKTA_IDENTITY_PROVIDER,
             Self::KAFKA_CONSUMER,
             Self::KAFKA_PRODUCER,
@@ -69,6 +75,7 @@ mod test {
         assert!(DefaultAddress::is_valid(DefaultAddress::UPPERCASE_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::ECHO_SERVICE));
         assert!(DefaultAddress::is_valid(DefaultAddress::HOP_SERVICE));
+        assert!(DefaultAddress::is_valid(DefaultAddress::SERVICE_DISCOVERY));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::SECURE_CHANNEL_LISTENER
         ));
@@ -82,6 +89,7 @@ mod test {
         assert!(DefaultAddress::is_valid(
             DefaultAddress::ENROLLMENT_TOKEN_ACCEPTOR
         ));
+        assert!(DefaultAddress::is_valid(DefaultAddress::AUTHORITY_EVENTS));
         assert!(DefaultAddress::is_valid(
             DefaultAddress::OKTA_IDENTITY_PROVIDER
         ));
@@ -0,0 +1,246 @@
+//! Discovery of the services of a node by remote nodes
+//!
+//! A node can start a discovery service, which lists the services started with an
+//! advertisement. The other services of the node are never listed.
+//!
+//! The discovery service is protected by the access control of its resource type, like the
+//! other services of the node, and is meant to be reached through a secure channel, for example
+//! at `/node/n1/secure/api/service/discovery`.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use miette::IntoDiagnostic;
+use minicbor::Decoder;
+use tracing::trace;
+
+use ockam::{Address, Context, Result, Routed, Worker};
+use ockam_abac::{Action, Resource, ResourceType};
+use ockam_core::api::{Error, Method, Request, RequestHeader, Response};
+use ockam_core::async_trait;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::WorkerBuilder;
+
+use crate::error::ApiError;
+use crate::nodes::models::services::{
+    AdvertisedService, AdvertisedServiceList, StartServiceDiscoveryRequest,
+};
+use crate::nodes::registry::{Registry, ServiceDiscoveryInfo};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::messages::Messages;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::NodeManager;
+
+use super::NodeManagerWorker;
+
+/// Worker answering the requests of remote nodes for the services advertised by its node
+pub struct ServiceDiscovery {
+    registry: Arc<Registry>,
+}
+
+impl ServiceDiscovery {
+    pub(crate) fn new(registry: Arc<Registry>) -> Self {
+        Self { registry }
+    }
+}
+
+#[ockam::worker]
+impl Worker for ServiceDiscovery {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
+        let return_route = msg.return_route();
+        let body = msg.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = match dec.decode() {
+            Ok(req) => req,
+            Err(e) => {
+                let res = Response::bad_request_no_request(&e.to_string()).to_vec()?;
+                return ctx.send(return_route, res).await;
+            }
+        };
+        trace! {
+            target: "service_discovery",
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            "request"
+        }
+
+        let path_segments = req.path_segments::<2>();
+        let res = match (req.method(), path_segments.as_slice()) {
+            (Some(Method::Get), ["services"]) => {
+                let services = self.registry.advertised_services().await;
+                Response::ok()
+                    .with_headers(&req)
+                    .body(AdvertisedServiceList::new(services))
+                    .to_vec()?
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+        ctx.send(return_route, res).await
+    }
+}
+
+impl Registry {
+    /// Return the services started with an advertisement
+    pub(crate) async fn advertised_services(&self) -> Vec<AdvertisedService> {
+        let mut list = vec![];
+        for (addr, info) in self.uppercase_services.entries().await {
+            let advertisement = info.advertisement();
+            if advertisement.advertise {
+                list.push(AdvertisedService::new(
+                    addr.address(),
+                    DefaultAddress::UPPERCASE_SERVICE,
+                    advertisement.description.clone(),
+                ));
+            }
+        }
+        for (addr, info) in self.echoer_services.entries().await {
+            let advertisement = info.advertisement();
+            if advertisement.advertise {
+                list.push(AdvertisedService::new(
+                    addr.address(),
+                    DefaultAddress::ECHO_SERVICE,
+                    advertisement.description.clone(),
+                ));
+            }
+        }
+        for (addr, info) in self.hop_services.entries().await {
+            let advertisement = info.advertisement();
+            if advertisement.advertise {
+                list.push(AdvertisedService::new(
+                    addr.address(),
+                    DefaultAddress::HOP_SERVICE,
+                    advertisement.description.clone(),
+                ));
+            }
+        }
+        for (name, info) in self.service_plugins.entries().await {
+            let advertisement = info.advertisement();
+            if advertisement.advertise {
+                for addr in info.addresses() {
+                    list.push(AdvertisedService::new(
+                        addr.address(),
+                        name.clone(),
+                        advertisement.description.clone(),
+                    ));
+                }
+            }
+        }
+        list
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) async fn start_service_discovery(
+        &self,
+        ctx: &Context,
+        request: StartServiceDiscoveryRequest,
+    ) -> Result<Response, Response<Error>> {
+        match self
+            .node_manager
+            .start_service_discovery(ctx, request.addr.into())
+            .await
+        {
+            Ok(_) => Ok(Response::ok()),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+}
+
+impl NodeManager {
+    /// Start a discovery service, listing the advertised services of this node.
+    ///
+    /// The service can be reached through the secure channel listeners of the node, and only
+    /// accepts the messages allowed by the policies of the `service-discovery` resource type.
+    pub async fn start_service_discovery(&self, ctx: &Context, addr: Address) -> Result<()> {
+        if self.registry.service_discoveries.contains_key(&addr).await {
+            return Err(ApiError::core("Discovery service exists at this address"));
+        }
+
+        let ac = self
+            .access_control(
+                self.authority(),
+                Resource::new(addr.address(), ResourceType::ServiceDiscovery),
+                Action::HandleMessage,
+                None,
+            )
+            .await?;
+
+        // the listeners created later add the default address as a consumer when they start
+        for listener in self.registry.secure_channel_listeners.values().await {
+            ctx.flow_controls()
+                .add_consumer(addr.clone(), listener.listener().flow_control_id());
+        }
+
+        WorkerBuilder::new(ServiceDiscovery::new(self.registry.clone()))
+            .with_address(addr.clone())
+            .with_incoming_access_control_arc(ac)
+            .start(ctx)
+            .await?;
+
+        self.registry
+            .service_discoveries
+            .insert(addr, ServiceDiscoveryInfo::default())
+            .await;
+
+        Ok(())
+    }
+}
+
+/// Register the handlers of the requests for the discovery service
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::SERVICE_DISCOVERY),
+        "start_service_discovery",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_service_discovery(ctx, r.body()?).await) })
+        },
+    );
+}
+
+/// Request sent to a discovery service to list the services advertised by its node
+pub fn list_advertised_services_request() -> Request {
+    Request::get("/services")
+}
+
+/// List the services advertised by a remote node
+#[async_trait]
+pub trait AdvertisedServices {
+    /// Send a request to the discovery service of the node reached by `at`,
+    /// for example `/node/n1/secure/api`, and return the services advertised by that node
+    async fn list_advertised_services(
+        &self,
+        ctx: &Context,
+        at: &MultiAddr,
+        timeout: Option<Duration>,
+    ) -> miette::Result<Vec<AdvertisedService>>;
+}
+
+#[async_trait]
+impl<T: Messages + Send + Sync> AdvertisedServices for T {
+    async fn list_advertised_services(
+        &self,
+        ctx: &Context,
+        at: &MultiAddr,
+        timeout: Option<Duration>,
+    ) -> miette::Result<Vec<AdvertisedService>> {
+        let to = at.clone().concat(&discovery_address()?).into_diagnostic()?;
+        let request = list_advertised_services_request()
+            .to_vec()
+            .into_diagnostic()?;
+        let reply = self.send_message(ctx, &to, request, timeout).await?;
+        let services: AdvertisedServiceList =
+            Response::parse_response_body(&reply).into_diagnostic()?;
+        Ok(services.list)
+    }
+}
+
+fn discovery_address() -> miette::Result<MultiAddr> {
+    format!("/service/{}", DefaultAddress::SERVICE_DISCOVERY)
+        .parse()
+        .into_diagnostic()
+}
@@ -1,4 +1,4 @@
-use ockam_core::api::{Error, Response};
+use ockam_core::api::{Error, Method, Response};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Result;
 use ockam_multiaddr::MultiAddr;
@@ -6,6 +6,7 @@ use ockam_node::Context;
 
 use crate::local_multiaddr_to_route;
 use crate::nodes::models::flow_controls::AddConsumer;
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::NodeManager;
 
 use super::NodeManagerWorker;
@@ -69,3 +70,13 @@ impl ToString for AddConsumerError {
         }
     }
 }
+
+/// Register the handlers of the requests for the flow controls
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Post,
+        "/node/flow_controls/add_consumer",
+        "add_consumer",
+        |w, ctx, r| Box::pin(async move { r.respond(w.add_consumer(ctx, r.body()?).await) }),
+    );
+}
@@ -9,6 +9,7 @@ use ockam::{Context, Result, TcpTransport};
 use ockam_core::compat::{string::String, sync::Arc};
 use ockam_core::errcode::Kind;
 use ockam_multiaddr::MultiAddr;
+use ockam_node::{NodeEvent, NodeEventKind};
 use ockam_transport_tcp::TcpListenerOptions;
 
 use crate::cli_state::random_name;
@@ -170,7 +171,7 @@ impl InMemoryNode {
         }
         CurrentSpan::set_attribute(NODE_NAME, &self.node_manager.node_name);
 
-        self.create_controller_client(self.timeout)
+        self.create_controller_client(self.timeout.or(self.cli_state.request_timeout()))
             .await
             .into_diagnostic()
     }
@@ -181,6 +182,8 @@ impl InMemoryNode {
     }
 
     pub async fn stop(&self, ctx: &Context) -> Result<()> {
+        self.node_events
+            .publish(NodeEvent::new(NodeEventKind::NodeStopped, self.node_name()));
         self.medic_handle.stop_medic(ctx).await?;
         for addr in DefaultAddress::iter() {
             let result = ctx.stop_worker(addr).await;
@@ -1,7 +1,7 @@
 use std::net::IpAddr;
 
 use ockam::{Address, Context, Result};
-use ockam_core::api::{Error, Response};
+use ockam_core::api::{Error, Method, Response};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::rand::random_string;
 use ockam_core::route;
@@ -11,9 +11,10 @@ use ockam_multiaddr::MultiAddr;
 use super::NodeManagerWorker;
 use crate::error::ApiError;
 use crate::kafka::{
-    kafka_default_policy_expression, kafka_policy_expression, ConsumerNodeAddr,
-    KafkaInletController, KafkaPortalListener, KafkaSecureChannelControllerImpl,
-    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    kafka_default_policy_expression, kafka_policy_expression, validate_kafka_broker,
+    ConsumerNodeAddr, KafkaBrokerValidationRequest, KafkaInletController, KafkaPortalListener,
+    KafkaSecureChannelControllerImpl, KAFKA_OUTLET_BOOTSTRAP_ADDRESS,
+    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
 };
 use crate::kafka::{OutletManagerService, PrefixRelayService};
 use crate::nodes::models::portal::OutletAccessControl;
@@ -23,6 +24,7 @@ use crate::nodes::models::services::{
 };
 use crate::nodes::registry::{KafkaServiceInfo, KafkaServiceKind};
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::InMemoryNode;
 use crate::nodes::NodeManager;
 use crate::port_range::PortRange;
@@ -40,6 +42,7 @@ impl NodeManagerWorker {
                 context,
                 Address::from_string(body.address()),
                 body.request().bootstrap_server_addr,
+                body.request().validation(),
             )
             .await
         {
@@ -172,8 +175,7 @@ impl InMemoryNode {
             })?;
 
         let authority_identifier = self
-            .authority
-            .clone()
+            .authority()
             .ok_or(ApiError::core("NodeManager has no authority"))?;
 
         let default_policy_expression = kafka_default_policy_expression();
@@ -206,6 +208,9 @@ impl InMemoryNode {
             Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into()),
             false,
             OutletAccessControl::PolicyExpression(outlet_policy_expression.clone()),
+            None,
+            None,
+            None,
         )
         .await?;
 
@@ -247,6 +252,9 @@ impl InMemoryNode {
             None,
             None,
             false,
+            None,
+            false,
+            None,
         )
         .await?;
 
@@ -288,8 +296,7 @@ impl InMemoryNode {
         );
 
         let authority_identifier = self
-            .authority
-            .clone()
+            .authority()
             .ok_or(ApiError::core("NodeManager has no authority"))?;
 
         let secure_channels = self.secure_channels.clone();
@@ -345,6 +352,9 @@ impl InMemoryNode {
             None,
             None,
             false,
+            None,
+            false,
+            None,
         )
         .await?;
 
@@ -373,7 +383,24 @@ impl NodeManager {
         context: &Context,
         service_address: Address,
         bootstrap_server_addr: SocketAddr,
+        validation_request: Option<&KafkaBrokerValidationRequest>,
     ) -> Result<()> {
+        // the credentials of the validation request are only used here and never stored
+        let validation = match validation_request {
+            Some(validation_request) => {
+                let validation =
+                    validate_kafka_broker(bootstrap_server_addr, validation_request).await;
+                if !validation.is_valid() {
+                    return Err(ApiError::core(format!(
+                        "The Kafka broker at {bootstrap_server_addr} failed the validation: {validation}"
+                    )));
+                }
+                info!(%bootstrap_server_addr, "the Kafka broker is valid: {validation}");
+                Some(validation)
+            }
+            None => None,
+        };
+
         let default_secure_channel_listener_flow_control_id = context
             .flow_controls()
             .get_flow_control_with_spawner(&DefaultAddress::SECURE_CHANNEL_LISTENER.into())
@@ -388,8 +415,7 @@ impl NodeManager {
         .await?;
 
         let authority_id = self
-            .authority
-            .clone()
+            .authority()
             .ok_or(ApiError::core("NodeManager has no authority"))?;
         let outlet_policy_expression = None;
 
@@ -409,6 +435,9 @@ impl NodeManager {
                 Some(KAFKA_OUTLET_BOOTSTRAP_ADDRESS.into()),
                 false,
                 OutletAccessControl::PolicyExpression(outlet_policy_expression),
+                None,
+                None,
+                None,
             )
             .await
         {
@@ -420,7 +449,7 @@ impl NodeManager {
                 .kafka_services
                 .insert(
                     service_address,
-                    KafkaServiceInfo::new(KafkaServiceKind::Outlet),
+                    KafkaServiceInfo::new(KafkaServiceKind::Outlet).with_validation(validation),
                 )
                 .await;
         }
@@ -468,3 +497,91 @@ pub enum DeleteKafkaServiceResult {
         kind: KafkaServiceKind,
     },
 }
+
+/// Register the handlers of the requests for the Kafka services
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::KAFKA_OUTLET),
+        "start_kafka_outlet_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_kafka_outlet_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        format!("/node/services/{}", DefaultAddress::KAFKA_OUTLET),
+        "delete_kafka_outlet_service",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.delete_kafka_service(ctx, r.body()?, KafkaServiceKind::Outlet)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::KAFKA_CONSUMER),
+        "start_kafka_consumer_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_kafka_consumer_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        format!("/node/services/{}", DefaultAddress::KAFKA_CONSUMER),
+        "delete_kafka_consumer_service",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.delete_kafka_service(ctx, r.body()?, KafkaServiceKind::Consumer)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::KAFKA_PRODUCER),
+        "start_kafka_producer_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_kafka_producer_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        format!("/node/services/{}", DefaultAddress::KAFKA_PRODUCER),
+        "delete_kafka_producer_service",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.delete_kafka_service(ctx, r.body()?, KafkaServiceKind::Producer)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::KAFKA_DIRECT),
+        "start_kafka_direct_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_kafka_direct_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        format!("/node/services/{}", DefaultAddress::KAFKA_DIRECT),
+        "delete_kafka_direct_service",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.delete_kafka_service(ctx, r.body()?, KafkaServiceKind::Direct)
+                        .await,
+                )
+            })
+        },
+    );
+}
@@ -1,17 +1,20 @@
 use miette::IntoDiagnostic;
+use std::future::Future;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::trace;
 
 use minicbor::{Decode, Encode};
+use serde::Serialize;
 
-use ockam_core::api::{Error, Request, Response};
-use ockam_core::{self, async_trait, AsyncTryClone, Result};
+use ockam_core::api::{Error, Method, Request, Response};
+use ockam_core::{self, async_trait, AsyncTryClone, Result, Route};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::{Context, MessageSendReceiveOptions};
 
 use crate::error::ApiError;
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::{BackgroundNodeClient, NodeManager, NodeManagerWorker};
 
 const TARGET: &str = "ockam_api::message";
@@ -25,6 +28,116 @@ pub trait Messages {
         message: Vec<u8>,
         timeout: Option<Duration>,
     ) -> miette::Result<Vec<u8>>;
+
+    /// Send `count` messages to an echo service, one every `interval`, and return the round trip
+    /// of each message. A message which is not echoed back before the timeout is counted as lost.
+    #[allow(clippy::too_many_arguments)]
+    async fn send_messages(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        count: usize,
+        interval: Duration,
+        timeout: Option<Duration>,
+        on_round_trip: &(dyn Fn(&MessageRoundTrip) + Send + Sync),
+    ) -> miette::Result<Vec<MessageRoundTrip>> {
+        Ok(
+            send_repeatedly(message, count, interval, on_round_trip, |message| {
+                self.send_message(ctx, to, message, timeout)
+            })
+            .await,
+        )
+    }
+}
+
+/// Round trip of a message sent to an echo service
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageRoundTrip {
+    /// Sequence number of the message, starting at 1
+    pub sequence: usize,
+    /// Size of the message payload
+    pub payload_size: usize,
+    /// Time elapsed until the reply was received, if it was received
+    pub round_trip_time: Option<Duration>,
+    /// Reason why the message was lost
+    pub error: Option<String>,
+}
+
+impl MessageRoundTrip {
+    fn new(
+        sequence: usize,
+        message: &[u8],
+        reply: std::result::Result<Vec<u8>, String>,
+        started_at: Instant,
+    ) -> Self {
+        let round_trip_time = started_at.elapsed();
+        let (round_trip_time, error) = match reply {
+            Ok(reply) if reply == message => (Some(round_trip_time), None),
+            Ok(_) => (None, Some("the reply differs from the message".to_string())),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            sequence,
+            payload_size: message.len(),
+            round_trip_time,
+            error,
+        }
+    }
+
+    pub fn is_lost(&self) -> bool {
+        self.round_trip_time.is_none()
+    }
+}
+
+/// Statistics of the round trips of a series of messages. The times are in milliseconds
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct MessageRoundTripStats {
+    pub sent: usize,
+    pub received: usize,
+    pub lost: usize,
+    pub loss_percent: f64,
+    pub min_ms: Option<f64>,
+    pub avg_ms: Option<f64>,
+    pub p95_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+impl MessageRoundTripStats {
+    pub fn new(round_trips: &[MessageRoundTrip]) -> Self {
+        let mut times: Vec<f64> = round_trips
+            .iter()
+            .filter_map(|r| r.round_trip_time)
+            .map(|t| t.as_secs_f64() * 1000.0)
+            .collect();
+        times.sort_by(|a, b| a.total_cmp(b));
+
+        let sent = round_trips.len();
+        let received = times.len();
+        let lost = sent - received;
+        let loss_percent = if sent == 0 {
+            0.0
+        } else {
+            lost as f64 * 100.0 / sent as f64
+        };
+        // nearest-rank percentile
+        let p95_ms = if received == 0 {
+            None
+        } else {
+            let rank = (received as f64 * 0.95).ceil() as usize;
+            Some(times[rank.max(1) - 1])
+        };
+        Self {
+            sent,
+            received,
+            lost,
+            loss_percent,
+            min_ms: times.first().copied(),
+            avg_ms: (received > 0).then(|| times.iter().sum::<f64>() / received as f64),
+            p95_ms,
+            max_ms: times.last().copied(),
+        }
+    }
 }
 
 #[async_trait]
@@ -46,18 +159,79 @@ impl Messages for NodeManager {
         let route = connection.route().into_diagnostic()?;
 
         trace!(route = %route, msg_l = %msg_length, "sending message");
-        let options = if let Some(timeout) = timeout {
-            MessageSendReceiveOptions::new().with_timeout(timeout)
-        } else {
-            MessageSendReceiveOptions::new()
-        };
-        Ok(ctx
-            .send_and_receive_extended::<Vec<u8>>(route, message, options)
+        send_on_route(ctx, route, message, timeout).await
+    }
+
+    /// The connection is only made once, so that the round trip times don't include its creation
+    #[instrument(skip_all)]
+    async fn send_messages(
+        &self,
+        ctx: &Context,
+        to: &MultiAddr,
+        message: Vec<u8>,
+        count: usize,
+        interval: Duration,
+        timeout: Option<Duration>,
+        on_round_trip: &(dyn Fn(&MessageRoundTrip) + Send + Sync),
+    ) -> miette::Result<Vec<MessageRoundTrip>> {
+        let connection_ctx = Arc::new(ctx.async_try_clone().await.into_diagnostic()?);
+        let connection = self
+            .make_connection(connection_ctx, to, self.identifier(), None, timeout)
             .await
-            .into_diagnostic()?
-            .into_body()
-            .into_diagnostic()?)
+            .into_diagnostic()?;
+        let route = connection.route().into_diagnostic()?;
+
+        Ok(
+            send_repeatedly(message, count, interval, on_round_trip, |message| {
+                send_on_route(ctx, route.clone(), message, timeout)
+            })
+            .await,
+        )
+    }
+}
+
+/// Send a message `count` times, waiting for each reply, and record the round trips
+async fn send_repeatedly<F, Fut>(
+    message: Vec<u8>,
+    count: usize,
+    interval: Duration,
+    on_round_trip: &(dyn Fn(&MessageRoundTrip) + Send + Sync),
+    send: F,
+) -> Vec<MessageRoundTrip>
+where
+    F: Fn(Vec<u8>) -> Fut,
+    Fut: Future<Output = miette::Result<Vec<u8>>>,
+{
+    let mut round_trips = vec![];
+    for sequence in 1..=count {
+        let started_at = Instant::now();
+        let reply = send(message.clone()).await.map_err(|e| e.to_string());
+        let round_trip = MessageRoundTrip::new(sequence, &message, reply, started_at);
+        on_round_trip(&round_trip);
+        round_trips.push(round_trip);
+        if sequence < count {
+            tokio::time::sleep(interval.saturating_sub(started_at.elapsed())).await;
+        }
     }
+    round_trips
+}
+
+async fn send_on_route(
+    ctx: &Context,
+    route: Route,
+    message: Vec<u8>,
+    timeout: Option<Duration>,
+) -> miette::Result<Vec<u8>> {
+    let options = if let Some(timeout) = timeout {
+        MessageSendReceiveOptions::new().with_timeout(timeout)
+    } else {
+        MessageSendReceiveOptions::new()
+    };
+    ctx.send_and_receive_extended::<Vec<u8>>(route, message, options)
+        .await
+        .into_diagnostic()?
+        .into_body()
+        .into_diagnostic()
 }
 
 #[async_trait]
@@ -122,3 +296,103 @@ impl SendMessage {
             .map_err(|_err| ApiError::core(format!("Invalid route: {}", self.route)))
     }
 }
+
+/// Register the handlers of the requests for the messages
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(Method::Post, "/v0/message", "send_message", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.send_message(ctx, r.body()?).await) })
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::echoer::Echoer;
+    use crate::test_utils::start_manager_for_tests;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[ockam_macros::test]
+    async fn send_messages_with_stats(context: &mut Context) -> ockam::Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        context.start_worker("test_echo", Echoer).await?;
+        let node_manager = handle.node_manager.clone();
+
+        let replies = AtomicUsize::new(0);
+        let on_round_trip = |round_trip: &MessageRoundTrip| {
+            assert!(!round_trip.is_lost(), "{:?}", round_trip.error);
+            replies.fetch_add(1, Ordering::Relaxed);
+        };
+        let mut round_trips = node_manager
+            .send_messages(
+                context,
+                &MultiAddr::from_str("/service/test_echo").unwrap(),
+                vec![1; 1024],
+                3,
+                Duration::from_millis(10),
+                Some(Duration::from_secs(5)),
+                &on_round_trip,
+            )
+            .await
+            .unwrap();
+        assert_eq!(replies.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            round_trips.iter().map(|r| r.sequence).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(round_trips.iter().all(|r| r.payload_size == 1024));
+
+        // the final message can not be routed, it is counted as lost
+        let lost = node_manager
+            .send_messages(
+                context,
+                &MultiAddr::from_str("/service/unknown").unwrap(),
+                vec![1; 1024],
+                1,
+                Duration::from_millis(10),
+                Some(Duration::from_millis(500)),
+                &|_| {},
+            )
+            .await
+            .unwrap();
+        assert!(lost[0].is_lost());
+        assert!(lost[0].error.is_some());
+        round_trips.extend(lost);
+
+        let stats = MessageRoundTripStats::new(&round_trips);
+        assert_eq!(stats.sent, 4);
+        assert_eq!(stats.received, 3);
+        assert_eq!(stats.lost, 1);
+        assert_eq!(stats.loss_percent, 25.0);
+        let (min, avg, p95, max) = (
+            stats.min_ms.unwrap(),
+            stats.avg_ms.unwrap(),
+            stats.p95_ms.unwrap(),
+            stats.max_ms.unwrap(),
+        );
+        assert!(min <= avg && avg <= max);
+        assert_eq!(p95, max);
+
+        context.stop().await
+    }
+
+    #[test]
+    fn stats_without_replies() {
+        let round_trips = vec![MessageRoundTrip {
+            sequence: 1,
+            payload_size: 10,
+            round_trip_time: None,
+            error: Some("timeout".to_string()),
+        }];
+        let stats = MessageRoundTripStats::new(&round_trips);
+        assert_eq!(stats.lost, 1);
+        assert_eq!(stats.loss_percent, 100.0);
+        assert_eq!(stats.min_ms, None);
+        assert_eq!(stats.avg_ms, None);
+        assert_eq!(stats.p95_ms, None);
+        assert_eq!(stats.max_ms, None);
+
+        let stats = MessageRoundTripStats::new(&[]);
+        assert_eq!(stats.sent, 0);
+        assert_eq!(stats.loss_percent, 0.0);
+    }
+}
@@ -1,21 +1,38 @@
+use std::str::FromStr;
+
 use either::Either;
 
-use ockam::{Address, Context, Result};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::{Address, Context, Result, Route};
 use ockam_abac::{Action, Resource, ResourceType};
-use ockam_core::api::{Error, Response};
-use ockam_node::WorkerBuilder;
+use ockam_core::api::{Error, Method, Response};
+use ockam_node::database::{MigrationSet, NodeMigrationSet};
+use ockam_node::{NodeEventKind, WorkerBuilder};
 
 use crate::echoer::Echoer;
 use crate::error::ApiError;
 use crate::hop::Hop;
+use crate::nodes::models::api_version::NodeApiInfo;
 use crate::nodes::models::base::NodeStatus;
+use crate::nodes::models::diagnostics::NodeDiagnostics;
+use crate::nodes::models::node_events::{GetNodeEvents, NodeEventRecord};
 use crate::nodes::models::services::{
-    ServiceList, ServiceStatus, StartEchoerServiceRequest, StartHopServiceRequest,
-    StartUppercaseServiceRequest,
+    ServiceAdvertisement, ServiceList, ServiceStatus, StartEchoerServiceRequest,
+    StartHopServiceRequest, StartUppercaseServiceRequest,
+};
+use crate::nodes::models::topology::{
+    NodeTopology, TopologyInlet, TopologyRelay, TopologySecureChannel,
+};
+use crate::nodes::models::traffic::{SetTrafficAccounting, TrafficStats, WorkerTraffic};
+use crate::nodes::models::transport::TransportStatus;
+use crate::nodes::models::vault::{VaultKeyList, VaultKeyStatus};
+use crate::nodes::registry::{
+    EchoerServiceInfo, HopServiceInfo, KafkaServiceKind, UppercaseServiceInfo,
 };
-use crate::nodes::registry::KafkaServiceKind;
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::NodeManager;
+use crate::session::sessions::ReplacerOutputKind;
 use crate::uppercase::Uppercase;
 
 use super::NodeManagerWorker;
@@ -28,7 +45,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_uppercase_service_impl(ctx, request.addr.into())
+            .start_uppercase_service_impl(ctx, request.addr.clone().into(), request.advertisement())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -43,7 +60,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_echoer_service(ctx, request.addr.into())
+            .start_echoer_service(ctx, request.addr.clone().into(), request.advertisement())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -58,7 +75,7 @@ impl NodeManagerWorker {
     ) -> Result<Response, Response<Error>> {
         match self
             .node_manager
-            .start_hop_service(ctx, request.addr.into())
+            .start_hop_service(ctx, request.addr.clone().into(), request.advertisement())
             .await
         {
             Ok(_) => Ok(Response::ok()),
@@ -94,6 +111,68 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    pub(super) fn get_api_version(&self) -> Result<Response<NodeApiInfo>, Response<Error>> {
+        Ok(Response::ok().body(NodeApiInfo::current()))
+    }
+
+    pub(super) async fn get_traffic(
+        &self,
+        context: &Context,
+    ) -> Result<Response<TrafficStats>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.get_traffic(context)))
+    }
+
+    pub(super) async fn set_traffic_accounting(
+        &self,
+        context: &Context,
+        request: SetTrafficAccounting,
+    ) -> Result<Response<TrafficStats>, Response<Error>> {
+        self.node_manager
+            .set_traffic_accounting(context, request.enabled);
+        Ok(Response::ok().body(self.node_manager.get_traffic(context)))
+    }
+
+    pub(super) async fn get_node_events(
+        &self,
+        request: GetNodeEvents,
+    ) -> Result<Response<Vec<NodeEventRecord>>, Response<Error>> {
+        let kind = match request.kind.as_deref().map(NodeEventKind::from_str) {
+            Some(Err(e)) => return Err(Response::bad_request_no_request(&e.to_string())),
+            Some(Ok(kind)) => Some(kind),
+            None => None,
+        };
+        match self.node_manager.get_node_events(request.since, kind).await {
+            Ok(events) => Ok(Response::ok().body(events)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn get_node_diagnostics(
+        &self,
+        context: &Context,
+    ) -> Result<Response<NodeDiagnostics>, Response<Error>> {
+        match self.node_manager.get_node_diagnostics(context).await {
+            Ok(diagnostics) => Ok(Response::ok().body(diagnostics)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn get_node_topology(
+        &self,
+    ) -> Result<Response<NodeTopology>, Response<Error>> {
+        match self.node_manager.get_node_topology().await {
+            Ok(topology) => Ok(Response::ok().body(topology)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn get_vault_keys(&self) -> Result<Response<VaultKeyList>, Response<Error>> {
+        match self.node_manager.get_vault_keys().await {
+            Ok(keys) => Ok(Response::ok().body(keys)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
 }
 
 impl NodeManager {
@@ -150,30 +229,45 @@ impl NodeManager {
                     DefaultAddress::HOP_SERVICE,
                 ))
             });
+        self.registry
+            .service_discoveries
+            .keys()
+            .await
+            .iter()
+            .for_each(|addr| {
+                list.push(ServiceStatus::new(
+                    addr.address(),
+                    DefaultAddress::SERVICE_DISCOVERY,
+                ))
+            });
         self.registry
             .kafka_services
             .entries()
             .await
             .iter()
             .for_each(|(address, info)| {
-                list.push(ServiceStatus::new(
-                    address.address(),
-                    match info.kind() {
-                        KafkaServiceKind::Consumer => DefaultAddress::KAFKA_CONSUMER,
-                        KafkaServiceKind::Producer => DefaultAddress::KAFKA_PRODUCER,
-                        KafkaServiceKind::Outlet => DefaultAddress::KAFKA_OUTLET,
-                        KafkaServiceKind::Direct => DefaultAddress::KAFKA_DIRECT,
-                    },
-                ))
+                list.push(
+                    ServiceStatus::new(
+                        address.address(),
+                        match info.kind() {
+                            KafkaServiceKind::Consumer => DefaultAddress::KAFKA_CONSUMER,
+                            KafkaServiceKind::Producer => DefaultAddress::KAFKA_PRODUCER,
+                            KafkaServiceKind::Outlet => DefaultAddress::KAFKA_OUTLET,
+                            KafkaServiceKind::Direct => DefaultAddress::KAFKA_DIRECT,
+                        },
+                    )
+                    .with_validation(info.validation().cloned()),
+                )
             });
 
         Ok(list)
     }
 
-    pub(super) async fn start_uppercase_service_impl(
+    pub async fn start_uppercase_service_impl(
         &self,
         ctx: &Context,
         addr: Address,
+        advertisement: ServiceAdvertisement,
     ) -> Result<()> {
         if self.registry.uppercase_services.contains_key(&addr).await {
             return Err(ApiError::core("Uppercase service exists at this address"));
@@ -183,13 +277,18 @@ impl NodeManager {
 
         self.registry
             .uppercase_services
-            .insert(addr.clone(), Default::default())
+            .insert(addr.clone(), UppercaseServiceInfo::new(advertisement))
             .await;
 
         Ok(())
     }
 
-    pub(super) async fn start_echoer_service(&self, ctx: &Context, addr: Address) -> Result<()> {
+    pub async fn start_echoer_service(
+        &self,
+        ctx: &Context,
+        addr: Address,
+        advertisement: ServiceAdvertisement,
+    ) -> Result<()> {
         if self.registry.echoer_services.contains_key(&addr).await {
             return Err(ApiError::core("Echoer service exists at this address"));
         }
@@ -211,13 +310,18 @@ impl NodeManager {
 
         self.registry
             .echoer_services
-            .insert(addr, Default::default())
+            .insert(addr, EchoerServiceInfo::new(advertisement))
             .await;
 
         Ok(())
     }
 
-    pub(super) async fn start_hop_service(&self, ctx: &Context, addr: Address) -> Result<()> {
+    pub async fn start_hop_service(
+        &self,
+        ctx: &Context,
+        addr: Address,
+        advertisement: ServiceAdvertisement,
+    ) -> Result<()> {
         if self.registry.hop_services.contains_key(&addr).await {
             return Err(ApiError::core("Hop service exists at this address"));
         }
@@ -229,7 +333,7 @@ impl NodeManager {
 
         self.registry
             .hop_services
-            .insert(addr, Default::default())
+            .insert(addr, HopServiceInfo::new(advertisement))
             .await;
 
         Ok(())
@@ -241,6 +345,482 @@ impl NodeManager {
             "Running",
             ctx.list_workers().await?.len() as u32,
             std::process::id() as i32,
+        )
+        .with_credentials(self.credential_prefetch.status()))
+    }
+
+    /// Enable or disable the accounting of message sizes on this node
+    pub fn set_traffic_accounting(&self, ctx: &Context, enabled: bool) {
+        ctx.message_sizes().set_enabled(enabled)
+    }
+
+    /// Return the message sizes recorded by the transports and secure channels of this node
+    pub fn get_traffic(&self, ctx: &Context) -> TrafficStats {
+        let message_sizes = ctx.message_sizes();
+        TrafficStats {
+            enabled: message_sizes.is_enabled(),
+            workers: message_sizes
+                .stats()
+                .into_iter()
+                .map(WorkerTraffic::from)
+                .collect(),
+        }
+    }
+
+    /// Store the events published by the components of this node in its event log.
+    /// Events are stored by a background task so that publishers are never blocked
+    pub(super) fn persist_node_events(&self) {
+        let mut subscriber = self.node_events.subscribe();
+        let repository = self.cli_state.node_events_repository();
+        let node_name = self.node_name.clone();
+        tokio::spawn(async move {
+            while let Some(event) = subscriber.recv().await {
+                if let Err(e) = repository.store_event(&node_name, &event).await {
+                    warn!(%e, "cannot store the node event {}", event.kind());
+                }
+            }
+        });
+    }
+
+    /// Return the events of this node created at, or after, the given timestamp, oldest first
+    pub async fn get_node_events(
+        &self,
+        since: Option<TimestampInSeconds>,
+        kind: Option<NodeEventKind>,
+    ) -> Result<Vec<NodeEventRecord>> {
+        self.cli_state
+            .node_events_repository()
+            .get_events(&self.node_name, since, kind)
+            .await
+    }
+
+    /// Gather the state of this node for diagnostics purposes
+    pub async fn get_node_diagnostics(&self, ctx: &Context) -> Result<NodeDiagnostics> {
+        let migration_status = NodeMigrationSet
+            .create_migrator()?
+            .status(&self.cli_state.database().pool)
+            .await?;
+        Ok(NodeDiagnostics {
+            status: self.get_node_status(ctx).await?,
+            schema_version: migration_status.current_version,
+            pending_migrations: migration_status.pending_migrations,
+            relays: self.get_relays().await,
+            inlets: self.list_inlets().await.list,
+            outlets: self.list_outlets().await.list,
+            secure_channels: self.list_secure_channels().await,
+            secure_channel_listeners: self
+                .list_secure_channel_listeners()
+                .await
+                .iter()
+                .map(|info| info.listener().address().to_string())
+                .collect(),
+        })
+    }
+
+    /// Describe the secure channels, relays, portals and transports of this node,
+    /// so that its connections to other nodes can be drawn
+    pub async fn get_node_topology(&self) -> Result<NodeTopology> {
+        let channels = self
+            .secure_channels
+            .secure_channel_registry()
+            .get_channel_list();
+        let routes: Vec<(Address, Route)> = self
+            .registry
+            .secure_channels
+            .list()
+            .await
+            .iter()
+            .map(|info| (info.sc().encryptor_address().clone(), info.route().clone()))
+            .collect();
+
+        // The other side of a route is the identity at the end of its last secure channel
+        let far_side = |route: Option<Route>| -> Option<Identifier> {
+            let route = route?;
+            let hops: Vec<&Address> = route.iter().collect();
+            hops.into_iter().rev().find_map(|hop| {
+                channels
+                    .iter()
+                    .find(|channel| channel.encryptor_messaging_address() == hop)
+                    .map(|channel| channel.their_id().clone())
+            })
+        };
+
+        let secure_channels = channels
+            .iter()
+            .map(|channel| TopologySecureChannel {
+                encryptor_address: channel.encryptor_messaging_address().to_string(),
+                decryptor_address: channel.decryptor_messaging_address().to_string(),
+                their_decryptor_address: channel.their_decryptor_address().to_string(),
+                my_identifier: channel.my_id().clone(),
+                their_identifier: channel.their_id().clone(),
+                is_initiator: channel.is_initiator(),
+                route: routes
+                    .iter()
+                    .find(|(address, _)| address == channel.encryptor_messaging_address())
+                    .map(|(_, route)| route.to_string()),
+            })
+            .collect();
+
+        let relays = self
+            .registry
+            .relays
+            .values()
+            .await
+            .into_iter()
+            .map(|info| TopologyRelay {
+                alias: info.alias.clone(),
+                destination: info
+                    .destination_status
+                    .active()
+                    .unwrap_or_else(|| info.destination_address.clone())
+                    .to_string(),
+                destination_identifier: far_side(info.session.ping_route()),
+                remote_address: info.session.status().and_then(|status| match status.kind {
+                    ReplacerOutputKind::Relay(relay) => Some(relay.remote_address().to_string()),
+                    ReplacerOutputKind::Inlet(_) => None,
+                }),
+            })
+            .collect();
+
+        let inlets = self
+            .registry
+            .inlets
+            .entries()
+            .await
+            .into_iter()
+            .map(|(alias, info)| TopologyInlet {
+                alias,
+                bind_address: info.bind_addr.clone(),
+                outlet_address: info.outlet_addr.to_string(),
+                outlet_route: info.session.status().and_then(|status| match status.kind {
+                    ReplacerOutputKind::Inlet(inlet) => Some(inlet.route.to_string()),
+                    ReplacerOutputKind::Relay(_) => None,
+                }),
+                outlet_identifier: far_side(info.session.ping_route()),
+            })
+            .collect();
+
+        let tcp_registry = self.tcp_transport.registry();
+        let transports = tcp_registry
+            .get_all_listeners()
+            .into_iter()
+            .map(TransportStatus::from)
+            .chain(
+                tcp_registry
+                    .get_all_sender_workers()
+                    .into_iter()
+                    .map(TransportStatus::from),
+            )
+            .collect();
+
+        Ok(NodeTopology {
+            node_name: self.node_name(),
+            identifier: self.identifier(),
+            secure_channels,
+            relays,
+            inlets,
+            outlets: self.list_outlets().await.list,
+            transports,
+        })
+    }
+
+    /// Return the keys stored in the vault of the node identity, classified as in-use or orphaned
+    pub async fn get_vault_keys(&self) -> Result<VaultKeyList> {
+        let vault_name = self
+            .cli_state
+            .get_named_identity_by_identifier(&self.identifier())
+            .await?
+            .vault_name();
+        let keys = self.cli_state.get_vault_keys(&vault_name).await?;
+        Ok(VaultKeyList::new(
+            vault_name,
+            keys.iter().map(VaultKeyStatus::from).collect(),
         ))
     }
 }
+
+/// Register the handlers of the requests for the node information and services
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(Method::Get, "/node", "get_node_status", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.get_node_status(ctx).await) })
+    });
+    routes.add(
+        Method::Get,
+        "/node/api_version",
+        "get_api_version",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_api_version()) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/diagnostics",
+        "get_node_diagnostics",
+        |w, ctx, r| Box::pin(async move { r.respond(w.get_node_diagnostics(ctx).await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/topology",
+        "get_node_topology",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_node_topology().await) }),
+    );
+    routes.add(Method::Get, "/node/traffic", "get_traffic", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.get_traffic(ctx).await) })
+    });
+    routes.add(
+        Method::Post,
+        "/node/traffic",
+        "set_traffic_accounting",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.set_traffic_accounting(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/vault/keys",
+        "get_vault_keys",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_vault_keys().await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/events",
+        "get_node_events",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_node_events(r.body()?).await) }),
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::UPPERCASE_SERVICE),
+        "start_uppercase_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_uppercase_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::ECHO_SERVICE),
+        "start_echoer_service",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_echoer_service(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Post,
+        format!("/node/services/{}", DefaultAddress::HOP_SERVICE),
+        "start_hop_service",
+        |w, ctx, r| Box::pin(async move { r.respond(w.start_hop_service(ctx, r.body()?).await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/services",
+        "list_services",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.list_services().await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/services/:service_type",
+        "list_services_of_type",
+        |w, _ctx, r| {
+            Box::pin(
+                async move { r.respond(w.list_services_of_type(r.param("service_type")).await) },
+            )
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::nodes::models::api_version::{NodeApiInfo, NodeCapability};
+    use crate::nodes::models::base::{CredentialsState, NodeStatus};
+    use crate::nodes::models::node_events::{GetNodeEvents, NodeEventRecord};
+    use crate::nodes::models::vault::VaultKeyList;
+    use crate::nodes::service::default_address::DefaultAddress;
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::test_utils::start_manager_for_tests;
+    use ockam_core::api::{Method, Reply, Request, Response, Status};
+    use ockam_core::route;
+    use ockam_multiaddr::MultiAddr;
+    use ockam_node::api::Client;
+    use ockam_node::Context;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    /// Requests recorded from a client which doesn't know about API versions
+    ///
+    /// GET /node, without a body
+    const GET_NODE: &str = "a4010102652f6e6f6465030004f4";
+    /// GET /node/forwarder, an endpoint which doesn't exist anymore
+    const GET_FORWARDER: &str = "a40102026f2f6e6f64652f666f72776172646572030004f4";
+    /// POST /node/services/echo, with a body where the address is wrapped in an unknown CBOR tag
+    const POST_TAGGED_ECHO: &str =
+        "a4010302732f6e6f64652f73657276696365732f6563686f030104f5a101d903e86178";
+
+    #[ockam_macros::test]
+    async fn get_api_version(context: &mut Context) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        let api_info: NodeApiInfo = client
+            .ask(context, Request::get("/node/api_version"))
+            .await?
+            .success()?;
+        assert_eq!(api_info, NodeApiInfo::current());
+        assert!(api_info.supports(NodeCapability::RELAY_FAILOVER));
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn vault_keys_can_be_listed(context: &mut Context) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        let keys: VaultKeyList = client
+            .ask(context, Request::get("/node/vault/keys"))
+            .await?
+            .success()?;
+        // the keys of the node identity are in use
+        assert!(!keys.list.is_empty());
+        assert!(keys.list.iter().any(|k| k.in_use));
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn old_client_requests(context: &mut Context) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+
+        // a request which is still supported gets a successful response
+        let reply: Reply<NodeStatus> = send_recorded_request(context, GET_NODE).await?;
+        let status = reply.success()?;
+        assert_eq!(status.status, "Running");
+
+        // an unknown endpoint is reported as an unsupported request
+        let reply: Reply<()> = send_recorded_request(context, GET_FORWARDER).await?;
+        let error = assert_unsupported(reply, "/node/forwarder", Method::Get);
+        assert!(
+            error.contains("unknown endpoint GET /node/forwarder"),
+            "{error}"
+        );
+
+        // a body which can't be decoded is reported as an unsupported request
+        let reply: Reply<()> = send_recorded_request(context, POST_TAGGED_ECHO).await?;
+        let error = assert_unsupported(reply, "/node/services/echo", Method::Post);
+        assert!(error.contains("invalid request body"), "{error}");
+        assert!(error.contains("tag"), "{error}");
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn node_status_reports_the_credential_prefetch(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        let status: NodeStatus = client
+            .ask(context, Request::get("/node"))
+            .await?
+            .success()?;
+        let credentials = status.credentials.clone().unwrap();
+        assert_eq!(credentials.state, CredentialsState::Disabled);
+        assert!(!status.is_degraded());
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn node_events_can_be_queried(context: &mut Context) -> ockam::Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = handle.node_manager.clone();
+
+        // A message without credentials is denied by the policy of the echoer service
+        context
+            .send(route![DefaultAddress::ECHO_SERVICE], "hello".to_string())
+            .await?;
+
+        // A secure channel is established with the node's own listener
+        let listener = handle
+            .cli_state
+            .get_node(&node_manager.node_name())
+            .await?
+            .tcp_listener_multi_address()?;
+        let secure_channel_address = listener
+            .concat(&MultiAddr::from_str("/service/api").unwrap())
+            .unwrap();
+        node_manager
+            .create_secure_channel(context, secure_channel_address, None, None, None)
+            .await?;
+
+        let denied = get_node_events(context, "policy-denied", 1).await?;
+        assert_eq!(denied[0].subject, DefaultAddress::ECHO_SERVICE);
+        assert_eq!(denied[0].details.get("action").unwrap(), "handle_message");
+
+        // both sides of the secure channel are reported
+        let established = get_node_events(context, "secure-channel-established", 2).await?;
+        assert!(established
+            .iter()
+            .any(|e| e.details.get("role").unwrap() == "initiator"));
+
+        // an unknown kind is rejected
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        let reply: Reply<Vec<NodeEventRecord>> = client
+            .ask(
+                context,
+                Request::get("/node/events")
+                    .body(GetNodeEvents::new(None, Some("unknown".to_string()))),
+            )
+            .await?;
+        assert!(matches!(reply, Reply::Failed(_, Some(Status::BadRequest))));
+
+        context.stop().await
+    }
+
+    /// HELPERS
+    async fn get_node_events(
+        context: &Context,
+        kind: &str,
+        expected: usize,
+    ) -> ockam::Result<Vec<NodeEventRecord>> {
+        // events are persisted in the background
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        for _ in 0..50 {
+            let events: Vec<NodeEventRecord> = client
+                .ask(
+                    context,
+                    Request::get("/node/events")
+                        .body(GetNodeEvents::new(None, Some(kind.to_string()))),
+                )
+                .await?
+                .success()?;
+            if events.len() >= expected {
+                assert!(events.iter().all(|e| e.kind == kind));
+                return Ok(events);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("no {kind} events were found");
+    }
+
+    async fn send_recorded_request<R>(context: &Context, request: &str) -> ockam::Result<Reply<R>>
+    where
+        R: for<'a> minicbor::Decode<'a, ()>,
+    {
+        let response: Vec<u8> = context
+            .send_and_receive(route![NODEMANAGER_ADDR], hex::decode(request).unwrap())
+            .await?;
+        Response::parse_response_reply::<R>(response.as_slice())
+    }
+
+    fn assert_unsupported<R>(reply: Reply<R>, path: &str, method: Method) -> String {
+        match reply {
+            Reply::Failed(error, Some(Status::NotImplemented)) => {
+                assert_eq!(error.path(), Some(path));
+                assert_eq!(
+                    error.method().map(|m| m.to_string()),
+                    Some(method.to_string())
+                );
+                error.message().unwrap_or_default().to_string()
+            }
+            _ => panic!("the request {method} {path} should be unsupported"),
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! Application-defined services which can be started on a node
+//!
+//! A custom build of the `ockam` binary can register some [`NodeServicePlugin`]s
+//! before starting a node, then start them by name with an optional configuration.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+use ockam::{Address, Context, Result};
+use ockam_core::api::{Error, Method, Response};
+use ockam_core::async_trait;
+
+use crate::error::ApiError;
+use crate::nodes::models::services::{
+    ServiceAdvertisement, ServicePluginList, ServicePluginStatus, StartServicePluginRequest,
+};
+use crate::nodes::registry::ServicePluginInfo;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::NodeManager;
+use crate::uppercase::Uppercase;
+
+use super::NodeManagerWorker;
+
+/// A service which is not part of Ockam but can be started on a node, by name.
+#[async_trait]
+pub trait NodeServicePlugin: Send + Sync + 'static {
+    /// Name of the plugin, used to reference it in the node configuration
+    fn name(&self) -> &str;
+
+    /// Start the workers of the plugin on the node.
+    ///
+    /// The configuration is the blob declared for this plugin in the node configuration, if any.
+    /// The addresses of the started workers are returned in order to be displayed in the node status.
+    async fn start(
+        &self,
+        ctx: &Context,
+        node_manager: &NodeManager,
+        config: Option<serde_json::Value>,
+    ) -> Result<Vec<Address>>;
+}
+
+static NODE_SERVICE_PLUGINS: Lazy<Mutex<BTreeMap<String, Arc<dyn NodeServicePlugin>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Process-wide registry of the plugins which can be started on a node
+pub struct NodeServicePlugins;
+
+impl NodeServicePlugins {
+    /// Register a plugin. This must be done before the node is started,
+    /// for example at the beginning of the `main` function of a custom binary.
+    /// A plugin registered with the same name is replaced.
+    pub fn register(plugin: impl NodeServicePlugin) {
+        let mut plugins = NODE_SERVICE_PLUGINS.lock().unwrap();
+        plugins.insert(plugin.name().to_string(), Arc::new(plugin));
+    }
+
+    /// Return the plugin registered with a given name
+    pub fn get(name: &str) -> Option<Arc<dyn NodeServicePlugin>> {
+        NODE_SERVICE_PLUGINS.lock().unwrap().get(name).cloned()
+    }
+
+    /// Return the names of all the registered plugins
+    pub fn names() -> Vec<String> {
+        NODE_SERVICE_PLUGINS
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Example of a plugin starting an Uppercase worker.
+///
+/// The worker address can be configured with `{ "address": "my_address" }`,
+/// and defaults to the plugin name.
+pub struct UppercasePlugin;
+
+impl UppercasePlugin {
+    pub const NAME: &'static str = "uppercase-echo";
+}
+
+#[async_trait]
+impl NodeServicePlugin for UppercasePlugin {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    async fn start(
+        &self,
+        ctx: &Context,
+        _node_manager: &NodeManager,
+        config: Option<serde_json::Value>,
+    ) -> Result<Vec<Address>> {
+        let address = config
+            .as_ref()
+            .and_then(|c| c.get("address"))
+            .and_then(|a| a.as_str())
+            .unwrap_or(Self::NAME);
+        let address = Address::from_string(address);
+        ctx.start_worker(address.clone(), Uppercase).await?;
+        Ok(vec![address])
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) async fn start_service_plugin(
+        &self,
+        ctx: &Context,
+        request: StartServicePluginRequest,
+    ) -> Result<Response<ServicePluginStatus>, Response<Error>> {
+        let config = match request.config() {
+            Ok(config) => config,
+            Err(e) => return Err(Response::bad_request_no_request(&e.to_string())),
+        };
+        match self
+            .node_manager
+            .start_service_plugin(ctx, &request.name, config, request.advertisement())
+            .await
+        {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn list_service_plugins(
+        &self,
+    ) -> Result<Response<ServicePluginList>, Response<Error>> {
+        Ok(Response::ok().body(ServicePluginList::new(
+            self.node_manager.list_service_plugins().await,
+        )))
+    }
+}
+
+impl NodeManager {
+    /// Start a plugin registered in [`NodeServicePlugins`]
+    pub async fn start_service_plugin(
+        &self,
+        ctx: &Context,
+        name: &str,
+        config: Option<serde_json::Value>,
+        advertisement: ServiceAdvertisement,
+    ) -> Result<ServicePluginStatus> {
+        if self.registry.service_plugins.contains_key(name).await {
+            return Err(ApiError::core(format!(
+                "The plugin {name} is already started"
+            )));
+        }
+        let plugin = NodeServicePlugins::get(name).ok_or_else(|| {
+            ApiError::core(format!(
+                "The plugin {name} is not registered. Registered plugins: [{}]",
+                NodeServicePlugins::names().join(", ")
+            ))
+        })?;
+
+        debug!(%name, "starting the service plugin");
+        let addresses = plugin.start(ctx, self, config).await?;
+        let info = ServicePluginInfo::new(addresses).with_advertisement(advertisement);
+        self.registry
+            .service_plugins
+            .insert(name.to_string(), info.clone())
+            .await;
+        Ok(ServicePluginStatus::new(name, info.addresses()))
+    }
+
+    /// Return the plugins started on this node
+    pub async fn list_service_plugins(&self) -> Vec<ServicePluginStatus> {
+        self.registry
+            .service_plugins
+            .entries()
+            .await
+            .into_iter()
+            .map(|(name, info)| ServicePluginStatus::new(name, info.addresses()))
+            .collect()
+    }
+}
+
+/// Register the handlers of the requests for the service plugins
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Post,
+        "/node/plugins",
+        "start_service_plugin",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.start_service_plugin(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/plugins",
+        "list_service_plugins",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.list_service_plugins().await) }),
+    );
+}
@@ -1,10 +1,12 @@
 use ockam_abac::{Action, Expr};
-use ockam_core::api::{Error, Request, Response};
+use ockam_core::api::{Error, Method, Request, Response};
 use ockam_core::{async_trait, Result};
 use ockam_node::Context;
 use std::str::FromStr;
 
+use crate::nodes::models::api_version::NodeCapability;
 use crate::nodes::models::policies::{PoliciesList, Policy, ResourceTypeOrName, SetPolicyRequest};
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::{BackgroundNodeClient, NodeManagerWorker};
 
 use super::NodeManager;
@@ -70,14 +72,12 @@ impl NodeManager {
         let action = Action::from_str(action)?;
         match resource {
             ResourceTypeOrName::Type(resource_type) => {
-                self.cli_state
-                    .policies()
+                self.policies
                     .store_policy_for_resource_type(&resource_type, &action, &expression)
                     .await
             }
             ResourceTypeOrName::Name(resource_name) => {
-                self.cli_state
-                    .policies()
+                self.policies
                     .store_policy_for_resource_name(&resource_name, &action, &expression)
                     .await
             }
@@ -93,14 +93,12 @@ impl NodeManager {
         let action = Action::from_str(action)?;
         Ok(match resource {
             ResourceTypeOrName::Type(resource_type) => self
-                .cli_state
-                .policies()
+                .policies
                 .get_policy_for_resource_type(&resource_type, &action)
                 .await?
                 .map(|p| p.into()),
             ResourceTypeOrName::Name(resource_name) => self
-                .cli_state
-                .policies()
+                .policies
                 .get_policy_for_resource_name(&resource_name, &action)
                 .await?
                 .map(|p| p.into()),
@@ -112,16 +110,14 @@ impl NodeManager {
             Some(resource) => match resource {
                 ResourceTypeOrName::Type(resource_type) => {
                     let resource_type_policies = self
-                        .cli_state
-                        .policies()
+                        .policies
                         .get_policies_for_resource_type(&resource_type)
                         .await?;
                     Ok(PoliciesList::new(vec![], resource_type_policies))
                 }
                 ResourceTypeOrName::Name(resource_name) => {
                     let resource_policies = self
-                        .cli_state
-                        .policies()
+                        .policies
                         .get_policies_for_resource_name(&resource_name)
                         .await?;
                     Ok(PoliciesList::new(resource_policies, vec![]))
@@ -129,7 +125,7 @@ impl NodeManager {
             },
             None => {
                 let (resource_policies, resource_type_policies) =
-                    self.cli_state.policies().get_policies().await?;
+                    self.policies.get_policies().await?;
                 Ok(PoliciesList::new(resource_policies, resource_type_policies))
             }
         }
@@ -139,14 +135,12 @@ impl NodeManager {
         let action = Action::from_str(action)?;
         match resource {
             ResourceTypeOrName::Type(resource_type) => {
-                self.cli_state
-                    .policies()
+                self.policies
                     .delete_policy_for_resource_type(&resource_type, &action)
                     .await
             }
             ResourceTypeOrName::Name(resource_name) => {
-                self.cli_state
-                    .policies()
+                self.policies
                     .delete_policy_for_resource_name(&resource_name, &action)
                     .await
             }
@@ -198,6 +192,7 @@ impl Policies for BackgroundNodeClient {
         action: &Action,
         expression: &Expr,
     ) -> miette::Result<()> {
+        self.require_policies_capability(ctx, resource).await?;
         let payload = SetPolicyRequest::new(resource.clone(), expression.clone());
         let request = Request::post(policy_path(action)).body(payload);
         self.tell(ctx, request).await?;
@@ -210,6 +205,7 @@ impl Policies for BackgroundNodeClient {
         resource: &ResourceTypeOrName,
         action: &Action,
     ) -> miette::Result<Policy> {
+        self.require_policies_capability(ctx, resource).await?;
         let request = Request::get(policy_path(action)).body(resource);
         self.ask(ctx, request).await
     }
@@ -219,6 +215,9 @@ impl Policies for BackgroundNodeClient {
         ctx: &Context,
         resource: Option<&ResourceTypeOrName>,
     ) -> miette::Result<PoliciesList> {
+        if let Some(resource) = resource {
+            self.require_policies_capability(ctx, resource).await?;
+        }
         let request = Request::get("/policy").body(resource);
         self.ask(ctx, request).await
     }
@@ -229,8 +228,67 @@ impl Policies for BackgroundNodeClient {
         resource: &ResourceTypeOrName,
         action: &Action,
     ) -> miette::Result<()> {
+        self.require_policies_capability(ctx, resource).await?;
         let request = Request::delete(policy_path(action)).body(resource);
         self.tell(ctx, request).await?;
         Ok(())
     }
 }
+
+impl BackgroundNodeClient {
+    /// Policies for resource types require a node supporting the `policies-v2` capability
+    async fn require_policies_capability(
+        &self,
+        ctx: &Context,
+        resource: &ResourceTypeOrName,
+    ) -> miette::Result<()> {
+        match resource {
+            ResourceTypeOrName::Type(_) => {
+                self.require_capability(
+                    ctx,
+                    NodeCapability::POLICIES_V2,
+                    "policies for resource types",
+                )
+                .await
+            }
+            ResourceTypeOrName::Name(_) => Ok(()),
+        }
+    }
+}
+
+/// Register the handlers of the requests for the policies
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Post,
+        "/policy/:action",
+        "add_policy",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                let payload: SetPolicyRequest = r.body()?;
+                r.respond(
+                    w.add_policy(r.param("action"), payload.resource, payload.expression)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/policy/:action",
+        "get_policy",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.get_policy(r.param("action"), r.body()?).await) })
+        },
+    );
+    routes.add(Method::Get, "/policy", "list_policies", |w, _ctx, r| {
+        Box::pin(async move { r.respond(w.list_policies(r.body()?).await) })
+    });
+    routes.add(
+        Method::Delete,
+        "/policy/:action",
+        "delete_policy",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.delete_policy(r.param("action"), r.body()?).await) })
+        },
+    );
+}
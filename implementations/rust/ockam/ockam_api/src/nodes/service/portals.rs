@@ -1,30 +1,37 @@
-use std::net::SocketAddr;
+use std::net::{SocketAddr, TcpListener};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::time::timeout;
 
-use crate::address::get_free_address_for;
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, IdentitySecureChannelLocalInfo};
 use ockam::{Address, Result};
 use ockam_abac::{Action, Expr, Resource, ResourceType};
-use ockam_core::api::{Error, Reply, Request, RequestHeader, Response};
+use ockam_core::api::{Error, Method, Reply, Request, RequestHeader, Response};
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, route, AsyncTryClone, Route};
+use ockam_core::{async_trait, route, AsyncTryClone, LocalInfo, Route};
 use ockam_multiaddr::proto::Project as ProjectProto;
 use ockam_multiaddr::{MultiAddr, Protocol};
 use ockam_node::Context;
-use ockam_transport_tcp::{TcpInletOptions, TcpOutletOptions};
+use ockam_transport_tcp::{
+    HttpRewrite, TcpInletOptions, TcpInletPreCheck, TcpOutletConnectionPool,
+    TcpOutletIdentityForwarding, TcpOutletOptions, TcpPortalBandwidthLimiter, TcpPortalPause,
+    TcpPortalPeerIdentifier, TcpTransport,
+};
 
 use crate::error::ApiError;
 use crate::nodes::connection::Connection;
+use crate::nodes::models::api_version::NodeCapability;
 use crate::nodes::models::portal::{
-    CreateInlet, CreateOutlet, InletList, InletStatus, OutletAccessControl, OutletList,
-    OutletStatus,
+    CreateInlet, CreateOutlet, InletHttpRewrite, InletList, InletStatus, OutletAccessControl,
+    OutletIdentityForwarding, OutletList, OutletStatus, PausePortal, PortalConnectionList,
+    PortalConnectionStatus, SetBandwidthLimit,
 };
+use crate::nodes::models::relay::ProjectRelayRoute;
 use crate::nodes::registry::{InletInfo, OutletInfo};
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::{BackgroundNodeClient, InMemoryNode};
 use crate::session::sessions::{
     ConnectionStatus, CurrentInletStatus, ReplacerOutcome, ReplacerOutputKind, Session,
@@ -34,6 +41,10 @@ use crate::session::MedicHandle;
 
 use super::{NodeManager, NodeManagerWorker};
 
+/// Maximum time to wait for the project, then the relay, to be reachable
+/// when validating a relay route
+const RELAY_VALIDATION_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// INLETS
 impl NodeManagerWorker {
     pub(super) async fn get_inlets(&self) -> Result<Response<InletList>, Response<Error>> {
@@ -57,7 +68,22 @@ impl NodeManagerWorker {
             wait_for_outlet_duration,
             policy_expression,
             wait_connection,
+            bandwidth_limit,
+            via,
+            pre_check,
+            http_rewrite,
         } = create_inlet;
+        let outlet_addr = match via {
+            Some(relay) => match self
+                .node_manager
+                .resolve_relay_route(ctx, &relay, &outlet_addr)
+                .await
+            {
+                Ok(outlet_addr) => outlet_addr,
+                Err(e) => return Err(Response::bad_request_no_request(&e.to_string())),
+            },
+            None => outlet_addr,
+        };
         match self
             .node_manager
             .create_inlet(
@@ -71,6 +97,9 @@ impl NodeManagerWorker {
                 wait_for_outlet_duration,
                 authorized,
                 wait_connection,
+                bandwidth_limit,
+                pre_check.unwrap_or(false),
+                http_rewrite,
             )
             .await
         {
@@ -100,6 +129,71 @@ impl NodeManagerWorker {
             ))),
         }
     }
+
+    pub(super) async fn set_inlet_bandwidth_limit(
+        &self,
+        alias: &str,
+        set_bandwidth_limit: SetBandwidthLimit,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .set_inlet_bandwidth_limit(alias, set_bandwidth_limit.limit)
+            .await
+        {
+            Some(inlet) => Ok(Response::ok().body(inlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn pause_inlet(
+        &self,
+        alias: &str,
+        pause_portal: PausePortal,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .pause_inlet(alias, pause_portal.drain_after())
+            .await
+        {
+            Some(inlet) => Ok(Response::ok().body(inlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn resume_inlet(
+        &self,
+        alias: &str,
+    ) -> Result<Response<InletStatus>, Response<Error>> {
+        match self.node_manager.resume_inlet(alias).await {
+            Some(inlet) => Ok(Response::ok().body(inlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn get_inlet_connections(
+        &self,
+        alias: &str,
+    ) -> Result<Response<PortalConnectionList>, Response<Error>> {
+        match self.node_manager.list_inlet_connections(alias).await {
+            Some(connections) => Ok(Response::ok().body(connections)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Inlet with alias {alias} not found"
+            ))),
+        }
+    }
+
+    pub(super) fn close_portal_connection(&self, id: &str) -> Result<Response, Response<Error>> {
+        match self.node_manager.close_portal_connection(id) {
+            Ok(()) => Ok(Response::ok()),
+            Err(e) => Err(Response::not_found_no_request(&e.to_string())),
+        }
+    }
 }
 
 /// OUTLETS
@@ -115,6 +209,9 @@ impl NodeManagerWorker {
             worker_addr,
             reachable_from_default_secure_channel,
             policy_expression,
+            bandwidth_limit,
+            connection_pool_size,
+            identity_forwarding,
         } = create_outlet;
 
         match self
@@ -125,6 +222,9 @@ impl NodeManagerWorker {
                 worker_addr,
                 reachable_from_default_secure_channel,
                 OutletAccessControl::PolicyExpression(policy_expression),
+                bandwidth_limit,
+                connection_pool_size,
+                identity_forwarding,
             )
             .await
         {
@@ -139,11 +239,17 @@ impl NodeManagerWorker {
     ) -> Result<Response<OutletStatus>, Response<Error>> {
         match self.node_manager.delete_outlet(worker_addr).await {
             Ok(res) => match res {
-                Some(outlet_info) => Ok(Response::ok().body(OutletStatus::new(
-                    outlet_info.socket_addr,
-                    outlet_info.worker_addr.clone(),
-                    None,
-                ))),
+                Some(outlet_info) => Ok(Response::ok().body(
+                    OutletStatus::new(
+                        outlet_info.socket_addr,
+                        outlet_info.worker_addr.clone(),
+                        None,
+                    )
+                    .with_bandwidth(&outlet_info.bandwidth)
+                    .with_connection_pool(outlet_info.pool.as_ref())
+                    .with_identity_forwarding(outlet_info.identity_forwarding.clone())
+                    .with_pause(&outlet_info.pause),
+                )),
                 None => Err(Response::bad_request_no_request(&format!(
                     "Outlet with address {worker_addr} not found"
                 ))),
@@ -164,6 +270,52 @@ impl NodeManagerWorker {
         }
     }
 
+    pub(super) async fn set_outlet_bandwidth_limit(
+        &self,
+        worker_addr: &Address,
+        set_bandwidth_limit: SetBandwidthLimit,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .set_outlet_bandwidth_limit(worker_addr, set_bandwidth_limit.limit)
+            .await
+        {
+            Some(outlet) => Ok(Response::ok().body(outlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn pause_outlet(
+        &self,
+        worker_addr: &Address,
+        pause_portal: PausePortal,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self
+            .node_manager
+            .pause_outlet(worker_addr, pause_portal.drain_after())
+            .await
+        {
+            Some(outlet) => Ok(Response::ok().body(outlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
+    pub(super) async fn resume_outlet(
+        &self,
+        worker_addr: &Address,
+    ) -> Result<Response<OutletStatus>, Response<Error>> {
+        match self.node_manager.resume_outlet(worker_addr).await {
+            Some(outlet) => Ok(Response::ok().body(outlet)),
+            None => Err(Response::not_found_no_request(&format!(
+                "Outlet with address {worker_addr} not found"
+            ))),
+        }
+    }
+
     pub(super) async fn get_outlets(&self, req: &RequestHeader) -> Response<OutletList> {
         Response::ok()
             .with_headers(req)
@@ -183,6 +335,9 @@ impl NodeManager {
         worker_addr: Option<Address>,
         reachable_from_default_secure_channel: bool,
         access_control: OutletAccessControl,
+        bandwidth_limit: Option<u64>,
+        connection_pool_size: Option<usize>,
+        identity_forwarding: Option<OutletIdentityForwarding>,
     ) -> Result<OutletStatus> {
         let worker_addr = self
             .registry
@@ -218,8 +373,30 @@ impl NodeManager {
             }
         };
 
+        // The limiter is always set, in order to measure the throughput of the outlet
+        let bandwidth = TcpPortalBandwidthLimiter::new(bandwidth_limit);
+        let pool = connection_pool_size
+            .filter(|size| *size > 0)
+            .map(TcpOutletConnectionPool::new);
+        let pause = TcpPortalPause::new();
         let options = {
-            let options = TcpOutletOptions::new().with_incoming_access_control(access_control);
+            let options = TcpOutletOptions::new()
+                .with_incoming_access_control(access_control)
+                .with_bandwidth_limiter(bandwidth.clone())
+                .with_pause(pause.clone());
+            let options = match pool.clone() {
+                Some(pool) => options.with_connection_pool(pool),
+                None => options,
+            };
+            let options = match identity_forwarding.clone() {
+                Some(identity_forwarding) => {
+                    options.with_identity_forwarding(TcpOutletIdentityForwarding::new(
+                        identity_forwarding.into(),
+                        Arc::new(SecureChannelPeerIdentifier),
+                    )?)
+                }
+                None => options,
+            };
             let options = if self.authority().is_none() {
                 options.as_consumer(&self.api_transport_flow_control_id)
             } else {
@@ -252,11 +429,17 @@ impl NodeManager {
                     .outlets
                     .insert(
                         worker_addr.clone(),
-                        OutletInfo::new(&socket_addr, Some(&worker_addr)),
+                        OutletInfo::new(&socket_addr, Some(&worker_addr), bandwidth.clone())
+                            .with_connection_pool(pool.clone())
+                            .with_identity_forwarding(identity_forwarding.clone())
+                            .with_pause(pause),
                     )
                     .await;
 
                 OutletStatus::new(socket_addr, worker_addr, None)
+                    .with_bandwidth(&bandwidth)
+                    .with_connection_pool(pool.as_ref())
+                    .with_identity_forwarding(identity_forwarding)
             }
             Err(e) => {
                 warn!(at = %socket_addr, err = %e, "Failed to create TCP outlet");
@@ -298,16 +481,100 @@ impl NodeManager {
         info!(%worker_addr, "Handling request to show outlet portal");
         if let Some(outlet_to_show) = self.registry.outlets.get(worker_addr).await {
             debug!(%worker_addr, "Outlet not found in node registry");
-            Some(OutletStatus::new(
-                outlet_to_show.socket_addr,
-                outlet_to_show.worker_addr.clone(),
-                None,
-            ))
+            Some(
+                OutletStatus::new(
+                    outlet_to_show.socket_addr,
+                    outlet_to_show.worker_addr.clone(),
+                    None,
+                )
+                .with_bandwidth(&outlet_to_show.bandwidth)
+                .with_connection_pool(outlet_to_show.pool.as_ref())
+                .with_identity_forwarding(outlet_to_show.identity_forwarding.clone())
+                .with_pause(&outlet_to_show.pause),
+            )
         } else {
             error!(%worker_addr, "Outlet not found in the node registry");
             None
         }
     }
+
+    /// Change the bandwidth limit of an outlet, without interrupting its connections
+    pub async fn set_outlet_bandwidth_limit(
+        &self,
+        worker_addr: &Address,
+        bandwidth_limit: Option<u64>,
+    ) -> Option<OutletStatus> {
+        info!(%worker_addr, ?bandwidth_limit, "Handling request to change the bandwidth limit of an outlet");
+        let outlet = self.registry.outlets.get(worker_addr).await?;
+        outlet.bandwidth.set_limit(bandwidth_limit);
+        Some(
+            OutletStatus::new(outlet.socket_addr, outlet.worker_addr.clone(), None)
+                .with_bandwidth(&outlet.bandwidth)
+                .with_connection_pool(outlet.pool.as_ref())
+                .with_identity_forwarding(outlet.identity_forwarding.clone())
+                .with_pause(&outlet.pause),
+        )
+    }
+
+    /// Stop accepting new connections on an outlet, without deleting it.
+    /// The inlets are refused the new connections, the existing connections are kept open
+    /// unless `drain_after` is set, in which case they are closed after that grace period
+    pub async fn pause_outlet(
+        &self,
+        worker_addr: &Address,
+        drain_after: Option<Duration>,
+    ) -> Option<OutletStatus> {
+        info!(%worker_addr, ?drain_after, "Handling request to pause an outlet");
+        let outlet = self.registry.outlets.get(worker_addr).await?;
+        outlet.pause.pause();
+        if let Some(drain_after) = drain_after {
+            drain_portal_connections(
+                self.tcp_transport.clone(),
+                outlet.worker_addr.clone(),
+                outlet.pause.clone(),
+                drain_after,
+            );
+        }
+        self.show_outlet(worker_addr).await
+    }
+
+    /// Accept new connections again on a paused outlet
+    pub async fn resume_outlet(&self, worker_addr: &Address) -> Option<OutletStatus> {
+        info!(%worker_addr, "Handling request to resume an outlet");
+        let outlet = self.registry.outlets.get(worker_addr).await?;
+        outlet.pause.resume();
+        self.show_outlet(worker_addr).await
+    }
+}
+
+/// Close the remaining connections of a paused inlet or outlet after a grace period.
+/// Nothing is closed if the portal was resumed, or paused again, in the meantime
+fn drain_portal_connections(
+    tcp_transport: TcpTransport,
+    listener_address: Address,
+    pause: TcpPortalPause,
+    drain_after: Duration,
+) {
+    let paused_at = pause.paused_at();
+    tokio::spawn(async move {
+        tokio::time::sleep(drain_after).await;
+        if pause.paused_at() == paused_at {
+            let closed = tcp_transport.close_portal_connections(&listener_address);
+            info!(%listener_address, %closed, "drained the connections of a paused portal");
+        }
+    });
+}
+
+/// Identify the peers of an outlet with the identity authenticated by their secure channel
+#[derive(Debug)]
+struct SecureChannelPeerIdentifier;
+
+impl TcpPortalPeerIdentifier for SecureChannelPeerIdentifier {
+    fn peer_identifier(&self, local_info: &[LocalInfo]) -> Option<String> {
+        IdentitySecureChannelLocalInfo::find_info_from_list(local_info)
+            .ok()
+            .map(|info| info.their_identity_id().to_string())
+    }
 }
 
 /// INLETS
@@ -326,6 +593,9 @@ impl NodeManager {
         wait_for_outlet_duration: Option<Duration>,
         authorized: Option<Identifier>,
         wait_connection: bool,
+        bandwidth_limit: Option<u64>,
+        pre_check: bool,
+        http_rewrite: Option<InletHttpRewrite>,
     ) -> Result<InletStatus> {
         info!("Handling request to create inlet portal");
         debug! {
@@ -337,16 +607,22 @@ impl NodeManager {
             "Creating inlet portal"
         }
 
-        // the port could be zero, to simplify the following code we
-        // resolve the address to a full socket address
-        let listen_addr = if listen_addr.ends_with(":0") {
+        // The port could be zero. In that case the OS assigns a port, which stays bound
+        // until the inlet is created, possibly much later when the outlet is available, and
+        // the bound listener is then handed over to the inlet.
+        // This way the inlet is always reported with the address it will listen on
+        let (listen_addr, reserved_listener) = if listen_addr.ends_with(":0") {
             let socket_addr = SocketAddr::from_str(&listen_addr)
                 .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?;
-            get_free_address_for(&socket_addr.ip().to_string())
-                .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?
-                .to_string()
+            let listener = TcpListener::bind(socket_addr)
+                .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?;
+            let assigned_addr = listener
+                .local_addr()
+                .map_err(|err| ockam_core::Error::new(Origin::Transport, Kind::Invalid, err))?;
+            debug!(%assigned_addr, "assigned a port to the inlet");
+            (assigned_addr.to_string(), Some(listener))
         } else {
-            listen_addr
+            (listen_addr, None)
         };
 
         // Check registry for duplicated alias or bind address
@@ -380,6 +656,17 @@ impl NodeManager {
             }
         }
 
+        // The limiter is shared by the successive inlets created by the session replacer
+        // and is always set, in order to measure the throughput of the inlet
+        let bandwidth = TcpPortalBandwidthLimiter::new(bandwidth_limit);
+        // The last status of the far side is also shared by the successive inlets
+        let pre_check = pre_check.then(TcpInletPreCheck::new);
+        // The pause is kept when the inlet is re-created
+        let pause = TcpPortalPause::new();
+        let http_rewrite = http_rewrite
+            .map(HttpRewrite::try_from)
+            .transpose()?
+            .map(Arc::new);
         let replacer = InletSessionReplacer {
             node_manager: self.clone(),
             context: Arc::new(ctx.async_try_clone().await?),
@@ -391,8 +678,13 @@ impl NodeManager {
             wait_for_outlet_duration: wait_for_outlet_duration.unwrap_or(MAX_CONNECT_TIME),
             resource: Resource::new(alias.clone(), ResourceType::TcpInlet),
             policy_expression,
+            bandwidth: bandwidth.clone(),
+            pre_check: pre_check.clone(),
+            pause: pause.clone(),
+            http_rewrite,
             connection: None,
             inlet_address: None,
+            reserved_listener,
         };
 
         let mut session = Session::new(replacer);
@@ -422,7 +714,14 @@ impl NodeManager {
             .inlets
             .insert(
                 alias.clone(),
-                InletInfo::new(&listen_addr, outlet_addr.clone(), session),
+                InletInfo::new(
+                    &listen_addr,
+                    outlet_addr.clone(),
+                    session,
+                    bandwidth.clone(),
+                    pre_check.clone(),
+                    pause.clone(),
+                ),
             )
             .await;
 
@@ -437,7 +736,66 @@ impl NodeManager {
                 .map(|s| s.connection_status)
                 .unwrap_or(ConnectionStatus::Down),
             outlet_addr.to_string(),
-        ))
+        )
+        .with_bandwidth(&bandwidth)
+        .with_pre_check(pre_check.as_ref())
+        .with_pause(&pause))
+    }
+
+    /// Return the route to an outlet service reachable through a relay of the default project.
+    ///
+    /// If the project can be reached, check that the relay exists by connecting to the
+    /// node which created it. Otherwise, the route is returned as is, and the inlet will
+    /// keep trying to connect to the outlet.
+    pub async fn resolve_relay_route(
+        &self,
+        ctx: &Context,
+        relay: &str,
+        service: &MultiAddr,
+    ) -> Result<MultiAddr> {
+        let route =
+            ProjectRelayRoute::resolve(&self.cli_state, relay, &service.to_string()).await?;
+        let ctx = Arc::new(ctx.async_try_clone().await?);
+
+        let mut project = MultiAddr::default();
+        project.push_back(ProjectProto::new(route.project()))?;
+        let project_connection = match self
+            .make_connection(
+                ctx.clone(),
+                &project,
+                self.identifier(),
+                None,
+                Some(RELAY_VALIDATION_TIMEOUT),
+            )
+            .await
+        {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(project = %route.project(), "the relay {relay} can't be validated, the project is not reachable: {e}");
+                return route.multiaddr();
+            }
+        };
+        let _ = project_connection.close(&ctx, self).await;
+
+        match self
+            .make_connection(
+                ctx.clone(),
+                &route.relay_multiaddr()?,
+                self.identifier(),
+                None,
+                Some(RELAY_VALIDATION_TIMEOUT),
+            )
+            .await
+        {
+            Ok(connection) => {
+                let _ = connection.close(&ctx, self).await;
+                route.multiaddr()
+            }
+            Err(e) => Err(ApiError::core(format!(
+                "The relay segment of the route could not be resolved: the relay '{relay}' was not found in the project '{}' ({e})",
+                route.project()
+            ))),
+        }
     }
 
     pub async fn delete_inlet(&self, alias: &str) -> Result<InletStatus> {
@@ -454,7 +812,10 @@ impl NodeManager {
                 None,
                 ConnectionStatus::Down,
                 inlet_to_delete.outlet_addr.to_string(),
-            ))
+            )
+            .with_bandwidth(&inlet_to_delete.bandwidth)
+            .with_pre_check(inlet_to_delete.pre_check.as_ref())
+            .with_pause(&inlet_to_delete.pause))
         } else {
             error!(%alias, "Inlet not found in the node registry");
             let message = format!("Inlet with alias {alias} not found");
@@ -469,9 +830,9 @@ impl NodeManager {
     pub async fn show_inlet(&self, alias: &str) -> Option<InletStatus> {
         info!(%alias, "Handling request to show inlet portal");
         if let Some(inlet_info) = self.registry.inlets.get(alias).await {
-            if let Some(status) = inlet_info.session.status() {
+            let inlet_status = if let Some(status) = inlet_info.session.status() {
                 if let ReplacerOutputKind::Inlet(status) = &status.kind {
-                    Some(InletStatus::new(
+                    InletStatus::new(
                         inlet_info.bind_addr.to_string(),
                         status.worker.address().to_string(),
                         alias,
@@ -479,12 +840,12 @@ impl NodeManager {
                         status.route.to_string(),
                         status.connection_status,
                         inlet_info.outlet_addr.to_string(),
-                    ))
+                    )
                 } else {
                     panic!("Unexpected outcome: {:?}", status.kind)
                 }
             } else {
-                Some(InletStatus::new(
+                InletStatus::new(
                     inlet_info.bind_addr.to_string(),
                     None,
                     alias,
@@ -492,8 +853,14 @@ impl NodeManager {
                     None,
                     ConnectionStatus::Down,
                     inlet_info.outlet_addr.to_string(),
-                ))
-            }
+                )
+            };
+            Some(
+                inlet_status
+                    .with_bandwidth(&inlet_info.bandwidth)
+                    .with_pre_check(inlet_info.pre_check.as_ref())
+                    .with_pause(&inlet_info.pause),
+            )
         } else {
             error!(%alias, "Inlet not found in the node registry");
             None
@@ -508,7 +875,7 @@ impl NodeManager {
                 .await
                 .iter()
                 .map(|(alias, info)| {
-                    if let Some(status) = info.session.status().as_ref() {
+                    let inlet_status = if let Some(status) = info.session.status().as_ref() {
                         match &status.kind {
                             ReplacerOutputKind::Inlet(status) => InletStatus::new(
                                 &info.bind_addr,
@@ -533,11 +900,85 @@ impl NodeManager {
                             ConnectionStatus::Down,
                             info.outlet_addr.to_string(),
                         )
-                    }
+                    };
+                    inlet_status
+                        .with_bandwidth(&info.bandwidth)
+                        .with_pre_check(info.pre_check.as_ref())
+                        .with_pause(&info.pause)
                 })
                 .collect(),
         )
     }
+
+    /// Change the bandwidth limit of an inlet, without interrupting its connections
+    pub async fn set_inlet_bandwidth_limit(
+        &self,
+        alias: &str,
+        bandwidth_limit: Option<u64>,
+    ) -> Option<InletStatus> {
+        info!(%alias, ?bandwidth_limit, "Handling request to change the bandwidth limit of an inlet");
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        inlet_info.bandwidth.set_limit(bandwidth_limit);
+        self.show_inlet(alias).await
+    }
+
+    /// Stop accepting new connections on an inlet, without deleting it.
+    /// The inlet keeps listening and resets the new connections, the existing connections are
+    /// kept open unless `drain_after` is set, in which case they are closed after that grace period
+    pub async fn pause_inlet(
+        &self,
+        alias: &str,
+        drain_after: Option<Duration>,
+    ) -> Option<InletStatus> {
+        info!(%alias, ?drain_after, "Handling request to pause an inlet");
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        inlet_info.pause.pause();
+        if let Some(drain_after) = drain_after {
+            // The inlet listener is re-created by the session, drain the current one
+            if let Some(ReplacerOutputKind::Inlet(status)) =
+                inlet_info.session.status().map(|s| s.kind)
+            {
+                drain_portal_connections(
+                    self.tcp_transport.clone(),
+                    status.worker,
+                    inlet_info.pause.clone(),
+                    drain_after,
+                );
+            }
+        }
+        self.show_inlet(alias).await
+    }
+
+    /// Accept new connections again on a paused inlet
+    pub async fn resume_inlet(&self, alias: &str) -> Option<InletStatus> {
+        info!(%alias, "Handling request to resume an inlet");
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        inlet_info.pause.resume();
+        self.show_inlet(alias).await
+    }
+
+    /// Return the connections currently accepted by an inlet
+    pub async fn list_inlet_connections(&self, alias: &str) -> Option<PortalConnectionList> {
+        let inlet_info = self.registry.inlets.get(alias).await?;
+        // The inlet listener is re-created by the session, use the current one
+        let connections = match inlet_info.session.status().map(|s| s.kind) {
+            Some(ReplacerOutputKind::Inlet(status)) => self
+                .tcp_transport
+                .portal_connections(&status.worker)
+                .iter()
+                .map(|c| PortalConnectionStatus::new(alias, c))
+                .collect(),
+            _ => vec![],
+        };
+        Some(PortalConnectionList::new(connections))
+    }
+
+    /// Close a single portal connection, leaving the other connections of its portal untouched
+    pub fn close_portal_connection(&self, id: &str) -> Result<()> {
+        info!(%id, "Handling request to close a portal connection");
+        let id: Address = id.to_string().into();
+        self.tcp_transport.close_portal_connection(&id)
+    }
 }
 
 impl InMemoryNode {
@@ -555,6 +996,9 @@ impl InMemoryNode {
         wait_for_outlet_duration: Option<Duration>,
         authorized: Option<Identifier>,
         wait_connection: bool,
+        bandwidth_limit: Option<u64>,
+        pre_check: bool,
+        http_rewrite: Option<InletHttpRewrite>,
     ) -> Result<InletStatus> {
         self.node_manager
             .create_inlet(
@@ -568,6 +1012,9 @@ impl InMemoryNode {
                 wait_for_outlet_duration,
                 authorized,
                 wait_connection,
+                bandwidth_limit,
+                pre_check,
+                http_rewrite,
             )
             .await
     }
@@ -584,10 +1031,16 @@ struct InletSessionReplacer {
     wait_for_outlet_duration: Duration,
     resource: Resource,
     policy_expression: Option<Expr>,
+    bandwidth: TcpPortalBandwidthLimiter,
+    pre_check: Option<TcpInletPreCheck>,
+    pause: TcpPortalPause,
+    http_rewrite: Option<Arc<HttpRewrite>>,
 
     // current status
     connection: Option<Connection>,
     inlet_address: Option<Address>,
+    /// Listener bound to the port assigned by the OS, handed over to the first inlet
+    reserved_listener: Option<TcpListener>,
 }
 
 #[async_trait]
@@ -658,15 +1111,33 @@ impl SessionReplacer for InletSessionReplacer {
                 connection_route,
                 self.suffix_route.clone()
             ];
-            let options = TcpInletOptions::new().with_incoming_access_control(access_control);
+            let mut options = TcpInletOptions::new()
+                .with_incoming_access_control(access_control)
+                .with_bandwidth_limiter(self.bandwidth.clone())
+                .with_pause(self.pause.clone());
+            if let Some(pre_check) = &self.pre_check {
+                options = options.with_pre_check(pre_check.clone());
+            }
+            if let Some(http_rewrite) = &self.http_rewrite {
+                options = options.with_interceptor(http_rewrite.clone());
+            }
 
-            // Finally, attempt to create a new inlet using the new route:
-            let inlet_address = self
-                .node_manager
-                .tcp_transport
-                .create_inlet(self.listen_addr.clone(), normalized_route.clone(), options)
-                .await?
-                .1;
+            // Finally, attempt to create a new inlet using the new route,
+            // with the listener of the reserved port if there is one
+            let tcp_transport = &self.node_manager.tcp_transport;
+            let (socket_addr, inlet_address) = match self.reserved_listener.take() {
+                Some(listener) => {
+                    tcp_transport
+                        .create_inlet_with_listener(listener, normalized_route.clone(), options)
+                        .await?
+                }
+                None => {
+                    tcp_transport
+                        .create_inlet(self.listen_addr.clone(), normalized_route.clone(), options)
+                        .await?
+                }
+            };
+            debug!(%socket_addr, "the inlet is listening");
             self.inlet_address = Some(inlet_address.clone());
 
             Ok(ReplacerOutcome {
@@ -728,11 +1199,39 @@ pub trait Inlets {
         policy_expression: &Option<Expr>,
         wait_for_outlet_timeout: Duration,
         validate: bool,
+        bandwidth_limit: Option<u64>,
+        via: Option<&str>,
+        pre_check: bool,
+        http_rewrite: Option<InletHttpRewrite>,
     ) -> miette::Result<Reply<InletStatus>>;
 
     async fn show_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<Reply<InletStatus>>;
 
+    async fn set_inlet_bandwidth_limit(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        bandwidth_limit: Option<u64>,
+    ) -> miette::Result<Reply<InletStatus>>;
+
+    async fn pause_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        drain_after: Option<Duration>,
+    ) -> miette::Result<Reply<InletStatus>>;
+
+    async fn resume_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<Reply<InletStatus>>;
+
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>>;
+
+    async fn list_inlet_connections(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Reply<PortalConnectionList>>;
+
+    async fn close_portal_connection(&self, ctx: &Context, id: &str) -> miette::Result<Reply<()>>;
 }
 
 #[async_trait]
@@ -747,9 +1246,33 @@ impl Inlets for BackgroundNodeClient {
         policy_expression: &Option<Expr>,
         wait_for_outlet_timeout: Duration,
         wait_connection: bool,
+        bandwidth_limit: Option<u64>,
+        via: Option<&str>,
+        pre_check: bool,
+        http_rewrite: Option<InletHttpRewrite>,
     ) -> miette::Result<Reply<InletStatus>> {
+        // older nodes would silently ignore the bandwidth limit
+        if bandwidth_limit.is_some() {
+            self.require_capability(ctx, NodeCapability::PORTAL_BANDWIDTH, "bandwidth limits")
+                .await?;
+        }
+        // older nodes would use the service name as a local route
+        if via.is_some() {
+            self.require_capability(ctx, NodeCapability::INLET_VIA_RELAY, "inlets via a relay")
+                .await?;
+        }
+        // older nodes would accept the connections without checking the outlet
+        if pre_check {
+            self.require_capability(ctx, NodeCapability::INLET_PRE_CHECK, "inlet pre-checks")
+                .await?;
+        }
+        // older nodes would forward the requests unchanged
+        if http_rewrite.is_some() {
+            self.require_capability(ctx, NodeCapability::INLET_HTTP_REWRITE, "HTTP rewriting")
+                .await?;
+        }
         let request = {
-            let via_project = outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
+            let via_project = via.is_some() || outlet_addr.matches(0, &[ProjectProto::CODE.into()]);
             let mut payload = if via_project {
                 CreateInlet::via_project(
                     listen_addr.into(),
@@ -773,6 +1296,18 @@ impl Inlets for BackgroundNodeClient {
             if let Some(e) = policy_expression.as_ref() {
                 payload.set_policy_expression(e.clone())
             }
+            if let Some(bandwidth_limit) = bandwidth_limit {
+                payload.set_bandwidth_limit(bandwidth_limit)
+            }
+            if let Some(relay) = via {
+                payload.set_via(relay.to_string())
+            }
+            if pre_check {
+                payload.set_pre_check(true)
+            }
+            if let Some(http_rewrite) = http_rewrite {
+                payload.set_http_rewrite(http_rewrite)
+            }
             payload.set_wait_ms(wait_for_outlet_timeout.as_millis() as u64);
             Request::post("/node/inlet").body(payload)
         };
@@ -784,10 +1319,61 @@ impl Inlets for BackgroundNodeClient {
         self.ask_and_get_reply(ctx, request).await
     }
 
+    async fn set_inlet_bandwidth_limit(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        bandwidth_limit: Option<u64>,
+    ) -> miette::Result<Reply<InletStatus>> {
+        self.require_capability(ctx, NodeCapability::PORTAL_BANDWIDTH, "bandwidth limits")
+            .await?;
+        let request = Request::post(format!("/node/inlet/{alias}/bandwidth"))
+            .body(SetBandwidthLimit::new(bandwidth_limit));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn pause_inlet(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        drain_after: Option<Duration>,
+    ) -> miette::Result<Reply<InletStatus>> {
+        self.require_capability(ctx, NodeCapability::PORTAL_PAUSE, "portal pauses")
+            .await?;
+        let request =
+            Request::post(format!("/node/inlet/{alias}/pause")).body(PausePortal::new(drain_after));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn resume_inlet(&self, ctx: &Context, alias: &str) -> miette::Result<Reply<InletStatus>> {
+        self.require_capability(ctx, NodeCapability::PORTAL_PAUSE, "portal pauses")
+            .await?;
+        let request = Request::post(format!("/node/inlet/{alias}/resume"));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
     async fn delete_inlet(&self, ctx: &Context, inlet_alias: &str) -> miette::Result<Reply<()>> {
         let request = Request::delete(format!("/node/inlet/{inlet_alias}"));
         self.tell_and_get_reply(ctx, request).await
     }
+
+    async fn list_inlet_connections(
+        &self,
+        ctx: &Context,
+        alias: &str,
+    ) -> miette::Result<Reply<PortalConnectionList>> {
+        self.require_capability(ctx, NodeCapability::PORTAL_CONNECTIONS, "inlet connections")
+            .await?;
+        let request = Request::get(format!("/node/inlet/{alias}/connections"));
+        self.ask_and_get_reply(ctx, request).await
+    }
+
+    async fn close_portal_connection(&self, ctx: &Context, id: &str) -> miette::Result<Reply<()>> {
+        self.require_capability(ctx, NodeCapability::PORTAL_CONNECTIONS, "inlet connections")
+            .await?;
+        let request = Request::delete(format!("/node/portal/connection/{id}"));
+        self.tell_and_get_reply(ctx, request).await
+    }
 }
 
 #[async_trait]
@@ -798,6 +1384,29 @@ pub trait Outlets {
         to: &SocketAddr,
         from: Option<&Address>,
         policy_expression: Option<Expr>,
+        bandwidth_limit: Option<u64>,
+        connection_pool_size: Option<usize>,
+        identity_forwarding: Option<OutletIdentityForwarding>,
+    ) -> miette::Result<OutletStatus>;
+
+    async fn set_outlet_bandwidth_limit(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        bandwidth_limit: Option<u64>,
+    ) -> miette::Result<OutletStatus>;
+
+    async fn pause_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        drain_after: Option<Duration>,
+    ) -> miette::Result<OutletStatus>;
+
+    async fn resume_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
     ) -> miette::Result<OutletStatus>;
 }
 
@@ -810,13 +1419,214 @@ impl Outlets for BackgroundNodeClient {
         to: &SocketAddr,
         from: Option<&Address>,
         policy_expression: Option<Expr>,
+        bandwidth_limit: Option<u64>,
+        connection_pool_size: Option<usize>,
+        identity_forwarding: Option<OutletIdentityForwarding>,
     ) -> miette::Result<OutletStatus> {
         let mut payload = CreateOutlet::new(*to, from.cloned(), true);
         if let Some(policy_expression) = policy_expression {
             payload.set_policy_expression(policy_expression);
         }
+        // older nodes would silently ignore the bandwidth limit
+        if let Some(bandwidth_limit) = bandwidth_limit {
+            self.require_capability(ctx, NodeCapability::PORTAL_BANDWIDTH, "bandwidth limits")
+                .await?;
+            payload.set_bandwidth_limit(bandwidth_limit);
+        }
+        if let Some(connection_pool_size) = connection_pool_size {
+            self.require_capability(
+                ctx,
+                NodeCapability::OUTLET_CONNECTION_POOL,
+                "outlet connection pools",
+            )
+            .await?;
+            payload.set_connection_pool_size(connection_pool_size);
+        }
+        if let Some(identity_forwarding) = identity_forwarding {
+            self.require_capability(
+                ctx,
+                NodeCapability::OUTLET_IDENTITY_FORWARDING,
+                "outlet identity forwarding",
+            )
+            .await?;
+            payload.set_identity_forwarding(identity_forwarding);
+        }
         let req = Request::post("/node/outlet").body(payload);
         let result: OutletStatus = self.ask(ctx, req).await?;
         Ok(result)
     }
+
+    async fn set_outlet_bandwidth_limit(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        bandwidth_limit: Option<u64>,
+    ) -> miette::Result<OutletStatus> {
+        self.require_capability(ctx, NodeCapability::PORTAL_BANDWIDTH, "bandwidth limits")
+            .await?;
+        let req = Request::post(format!("/node/outlet/{}/bandwidth", worker_addr.address()))
+            .body(SetBandwidthLimit::new(bandwidth_limit));
+        let result: OutletStatus = self.ask(ctx, req).await?;
+        Ok(result)
+    }
+
+    async fn pause_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+        drain_after: Option<Duration>,
+    ) -> miette::Result<OutletStatus> {
+        self.require_capability(ctx, NodeCapability::PORTAL_PAUSE, "portal pauses")
+            .await?;
+        let req = Request::post(format!("/node/outlet/{}/pause", worker_addr.address()))
+            .body(PausePortal::new(drain_after));
+        let result: OutletStatus = self.ask(ctx, req).await?;
+        Ok(result)
+    }
+
+    async fn resume_outlet(
+        &self,
+        ctx: &Context,
+        worker_addr: &Address,
+    ) -> miette::Result<OutletStatus> {
+        self.require_capability(ctx, NodeCapability::PORTAL_PAUSE, "portal pauses")
+            .await?;
+        let req = Request::post(format!("/node/outlet/{}/resume", worker_addr.address()));
+        let result: OutletStatus = self.ask(ctx, req).await?;
+        Ok(result)
+    }
+}
+
+/// Register the handlers of the requests for the inlets and outlets
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(Method::Get, "/node/inlet", "get_inlets", |w, _ctx, r| {
+        Box::pin(async move { r.respond(w.get_inlets().await) })
+    });
+    routes.add(
+        Method::Get,
+        "/node/inlet/:alias",
+        "show_inlet",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.show_inlet(r.param("alias")).await) }),
+    );
+    routes.add(Method::Get, "/node/outlet", "get_outlets", |w, _ctx, r| {
+        Box::pin(async move { w.get_outlets(r.header).await.to_vec() })
+    });
+    routes.add(
+        Method::Get,
+        "/node/outlet/:address",
+        "show_outlet",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(w.show_outlet(&r.param("address").to_string().into()).await)
+            })
+        },
+    );
+    routes.add(Method::Post, "/node/inlet", "create_inlet", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.create_inlet(ctx, r.body()?).await) })
+    });
+    routes.add(
+        Method::Post,
+        "/node/outlet",
+        "create_outlet",
+        |w, ctx, r| Box::pin(async move { r.respond(w.create_outlet(ctx, r.body()?).await) }),
+    );
+    routes.add(
+        Method::Delete,
+        "/node/outlet/:address",
+        "delete_outlet",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.delete_outlet(&r.param("address").to_string().into())
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/inlet/:alias/connections",
+        "get_inlet_connections",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.get_inlet_connections(r.param("alias")).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        "/node/portal/connection/:id",
+        "close_portal_connection",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.close_portal_connection(r.param("id"))) }),
+    );
+    routes.add(
+        Method::Post,
+        "/node/inlet/:alias/bandwidth",
+        "set_inlet_bandwidth_limit",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.set_inlet_bandwidth_limit(r.param("alias"), r.body()?)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/outlet/:address/bandwidth",
+        "set_outlet_bandwidth_limit",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.set_outlet_bandwidth_limit(&r.param("address").to_string().into(), r.body()?)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/inlet/:alias/pause",
+        "pause_inlet",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.pause_inlet(r.param("alias"), r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/inlet/:alias/resume",
+        "resume_inlet",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.resume_inlet(r.param("alias")).await) }),
+    );
+    routes.add(
+        Method::Post,
+        "/node/outlet/:address/pause",
+        "pause_outlet",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.pause_outlet(&r.param("address").to_string().into(), r.body()?)
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/outlet/:address/resume",
+        "resume_outlet",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.resume_outlet(&r.param("address").to_string().into())
+                        .await,
+                )
+            })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        "/node/inlet/:alias",
+        "delete_inlet",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.delete_inlet(r.param("alias")).await) }),
+    );
 }
@@ -2,7 +2,8 @@ use miette::IntoDiagnostic;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
-use crate::cloud::project::models::OrchestratorVersionInfo;
+use crate::cloud::email_address::EmailAddress;
+use crate::cloud::project::models::{OrchestratorVersionInfo, ProjectAdmin};
 use crate::cloud::project::{Project, ProjectsOrchestratorApi};
 use crate::nodes::InMemoryNode;
 
@@ -164,6 +165,44 @@ impl ProjectsOrchestratorApi for InMemoryNode {
             .collect::<Vec<_>>())
     }
 
+    #[instrument(skip_all, fields(project_id = project_id))]
+    async fn list_project_admins(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+    ) -> miette::Result<Vec<ProjectAdmin>> {
+        self.create_controller()
+            .await?
+            .list_project_admins(ctx, project_id)
+            .await
+    }
+
+    #[instrument(skip_all, fields(project_id = project_id, email = %email))]
+    async fn add_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<ProjectAdmin> {
+        self.create_controller()
+            .await?
+            .add_project_admin(ctx, project_id, email)
+            .await
+    }
+
+    #[instrument(skip_all, fields(project_id = project_id, email = %email))]
+    async fn delete_project_admin(
+        &self,
+        ctx: &Context,
+        project_id: &str,
+        email: &EmailAddress,
+    ) -> miette::Result<()> {
+        self.create_controller()
+            .await?
+            .delete_project_admin(ctx, project_id, email)
+            .await
+    }
+
     /// Wait until the operation associated with the project creation is complete
     /// At this stage the project node must be up and running
     #[instrument(skip_all, fields(project_id = project.project_id()))]
@@ -1,29 +1,51 @@
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use miette::IntoDiagnostic;
 
 use ockam::identity::Identifier;
-use ockam::remote::{RemoteRelay, RemoteRelayOptions};
-use ockam::Result;
-use ockam_core::api::{Error, Request, RequestHeader, Response};
+use ockam::remote::{RemoteRelay, RemoteRelayFilter, RemoteRelayOptions};
+use ockam::{
+    RelayAliasOwnership, RelayAliasesRepository, Result, RELAY_DENIED_PREFIX, RELAY_RELEASE_PREFIX,
+};
+use ockam_core::api::{Error, Method, Request, RequestHeader, Response};
+use ockam_core::env::get_env_with_default;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Address, AsyncTryClone};
+use ockam_core::{async_trait, route, Address, AsyncTryClone, NeutralMessage};
 use ockam_multiaddr::MultiAddr;
-use ockam_node::Context;
+use ockam_node::{Context, MessageSendReceiveOptions, NodeEvent, NodeEventKind};
 
 use crate::nodes::connection::Connection;
-use crate::nodes::models::relay::{CreateRelay, RelayInfo};
+use crate::nodes::models::api_version::NodeCapability;
+use crate::nodes::models::relay::{
+    CreateRelay, RelayAliasOwnerInfo, RelayInfo, ReleaseRelayAlias, RenameRelay,
+    PROJECT_RELAY_PREFIX,
+};
 use crate::nodes::models::secure_channel::{
     CreateSecureChannelRequest, CreateSecureChannelResponse,
 };
-use crate::nodes::registry::RegistryRelayInfo;
+use crate::nodes::registry::{RegistryRelayInfo, RelayDestinationStatus};
+use crate::nodes::service::default_address::DefaultAddress;
 use crate::nodes::service::in_memory_node::InMemoryNode;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::storage::RelayAliasesSqlxDatabase;
 use crate::nodes::BackgroundNodeClient;
 use crate::session::sessions::{ReplacerOutcome, ReplacerOutputKind, Session, SessionReplacer};
 use crate::session::MedicHandle;
 
-use super::{NodeManager, NodeManagerWorker};
+use super::{release_on_cancel, CreatedResource, NodeManager, NodeManagerWorker};
+
+/// Set this variable to `true` to enforce the ownership of the aliases registered with the relay
+/// service of a node. The node must have an authority, which attests the relay admins
+pub const OCKAM_RELAY_ALIAS_OWNERSHIP: &str = "OCKAM_RELAY_ALIAS_OWNERSHIP";
+
+/// Interval between the checks of the primary destination of a relay created at a failover
+/// destination, when the relay fails back
+const RELAY_FAILBACK_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Maximum time to connect to the primary destination of a relay and get an echo back
+const RELAY_FAILBACK_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
 
 impl NodeManagerWorker {
     pub async fn create_relay(
@@ -32,12 +54,16 @@ impl NodeManagerWorker {
         req: &RequestHeader,
         create_relay: CreateRelay,
     ) -> Result<Response<RelayInfo>, Response<Error>> {
+        let filter = create_relay.filter();
+        let failover_addresses = create_relay.failover_addresses().to_vec();
+        let failback = create_relay.failback();
         let CreateRelay {
             address,
             alias,
             at_rust_node,
             authorized,
             relay_address,
+            ..
         } = create_relay;
         match self
             .node_manager
@@ -48,14 +74,24 @@ impl NodeManagerWorker {
                 at_rust_node,
                 authorized,
                 relay_address,
+                failover_addresses,
+                failback,
+                filter,
             )
             .await
         {
             Ok(body) => Ok(Response::ok().with_headers(req).body(body)),
-            Err(err) => Err(Response::internal_error(
-                req,
-                &format!("Failed to create relay: {}", err),
-            )),
+            Err(err) => match err.code().kind {
+                // the relay service refused to register the alias
+                Kind::Conflict => Err(Response::forbidden(
+                    req,
+                    &format!("Failed to create relay: {}", err),
+                )),
+                _ => Err(Response::internal_error(
+                    req,
+                    &format!("Failed to create relay: {}", err),
+                )),
+            },
         }
     }
 
@@ -89,6 +125,31 @@ impl NodeManagerWorker {
         self.node_manager.show_relay(req, alias).await
     }
 
+    pub async fn rename_relay(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        alias: &str,
+        rename_relay: RenameRelay,
+    ) -> Result<Response<RelayInfo>, Response<Error>> {
+        debug!(%alias, new_alias = %rename_relay.new_alias, "Handling RenameRelay request");
+        match self
+            .node_manager
+            .rename_relay(ctx, alias, rename_relay.new_alias)
+            .await
+        {
+            Ok(body) => Ok(Response::ok().with_headers(req).body(body)),
+            Err(err) => match err.code().kind {
+                Kind::NotFound => Err(Response::not_found(req, &err.to_string())),
+                Kind::AlreadyExists => Err(Response::bad_request(req, &err.to_string())),
+                _ => Err(Response::internal_error(
+                    req,
+                    &format!("Failed to rename relay {alias}: {err}"),
+                )),
+            },
+        }
+    }
+
     pub async fn get_relays(
         &self,
         req: &RequestHeader,
@@ -98,6 +159,44 @@ impl NodeManagerWorker {
             .with_headers(req)
             .body(self.node_manager.get_relays().await))
     }
+
+    pub async fn get_relay_alias_owners(
+        &self,
+        req: &RequestHeader,
+    ) -> Result<Response<Vec<RelayAliasOwnerInfo>>, Response<Error>> {
+        debug!("Handling GetRelayAliasOwners request");
+        match self.node_manager.get_relay_alias_owners().await {
+            Ok(owners) => Ok(Response::ok().with_headers(req).body(owners)),
+            Err(err) => Err(Response::internal_error(
+                req,
+                &format!("Failed to list the relay alias owners: {err}"),
+            )),
+        }
+    }
+
+    pub async fn release_relay_alias(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        release: ReleaseRelayAlias,
+    ) -> Result<Response<()>, Response<Error>> {
+        let ReleaseRelayAlias { address, alias } = release;
+        debug!(%alias, %address, "Handling ReleaseRelayAlias request");
+        match self
+            .node_manager
+            .release_relay_alias(ctx, &address, &alias)
+            .await
+        {
+            Ok(()) => Ok(Response::ok().with_headers(req).body(())),
+            Err(err) => match err.code().kind {
+                Kind::Conflict => Err(Response::forbidden(req, &err.to_string())),
+                _ => Err(Response::internal_error(
+                    req,
+                    &format!("Failed to release the relay alias {alias}: {err}"),
+                )),
+            },
+        }
+    }
 }
 
 impl NodeManager {
@@ -119,7 +218,15 @@ impl NodeManager {
     /// Create a new Relay
     /// The Connection encapsulates the list of workers required on the relay route.
     /// This route is monitored in the `InMemoryNode` and the workers are restarted if necessary
-    /// when the route is unresponsive
+    /// when the route is unresponsive.
+    ///
+    /// When the route can't be re-established at `addr` the relay is created at the next
+    /// address of `failover_addresses`. If `failback` is set, `addr` is checked periodically
+    /// while the relay is at a failover address, and the relay returns to `addr` once it has
+    /// recovered.
+    ///
+    /// The `filter` restricts the services of this node which can be reached through the relay.
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_relay(
         self: &Arc<Self>,
         ctx: &Context,
@@ -128,8 +235,14 @@ impl NodeManager {
         at_rust_node: bool,
         authorized: Option<Identifier>,
         relay_address: Option<String>,
+        failover_addresses: Vec<MultiAddr>,
+        failback: bool,
+        filter: RemoteRelayFilter,
     ) -> Result<RelayInfo> {
-        if self.registry.relays.contains_key(&alias).await {
+        // the alias stays reserved until the relay is registered,
+        // so that concurrent requests can't create two relays with the same alias
+        let reservation = self.registry.relay_reservations.reserve(&alias);
+        if reservation.is_none() || self.registry.relays.contains_key(&alias).await {
             let message = format!("A relay with the name '{alias}' already exists");
             return Err(ockam_core::Error::new(
                 Origin::Node,
@@ -138,18 +251,28 @@ impl NodeManager {
             ));
         }
 
+        let destination_status = RelayDestinationStatus::default();
+        let mut addresses = vec![addr.clone()];
+        addresses.extend(failover_addresses.iter().cloned());
         let replacer = RelaySessionReplacer {
+            alias: alias.clone(),
             node_manager: self.clone(),
             context: Arc::new(ctx.async_try_clone().await?),
-            addr: addr.clone(),
+            addresses,
+            active: 0,
+            failback,
+            failback_probe: None,
+            destination_status: destination_status.clone(),
             at_rust_node,
-            relay_address,
+            relay_address: relay_address.clone(),
             connection: None,
             relay_worker_address: None,
-            authorized,
+            authorized: authorized.clone(),
+            filter: filter.clone(),
         };
 
         let mut session = Session::new(replacer);
+        release_on_cancel(CreatedResource::Relay(alias.clone(), session.clone()));
         let relay_info =
             MedicHandle::connect(&mut session)
                 .await
@@ -162,8 +285,14 @@ impl NodeManager {
 
         let registry_relay_info = RegistryRelayInfo {
             destination_address: addr.clone(),
+            failover_addresses,
+            destination_status,
             alias: alias.clone(),
             at_rust_node,
+            authorized,
+            relay_address,
+            failback,
+            filter,
             session,
         };
 
@@ -208,6 +337,170 @@ impl NodeManager {
         }
     }
 
+    /// Rename a relay.
+    ///
+    /// When the forwarding address of the relay is its alias, possibly with the project relay
+    /// prefix, the forwarding address is renamed as well. In that case the relay is first
+    /// registered at its new forwarding address, then the previous registration is removed,
+    /// so that the relay is still reachable if the new registration fails.
+    ///
+    /// Otherwise the relay is closed and created again under its new alias. If that fails,
+    /// the relay is restored with its previous alias.
+    pub async fn rename_relay(
+        self: &Arc<Self>,
+        ctx: &Context,
+        alias: &str,
+        new_alias: String,
+    ) -> Result<RelayInfo> {
+        let relay = match self.registry.relays.get(alias).await {
+            Some(relay) => relay,
+            None => {
+                return Err(ockam_core::Error::new(
+                    Origin::Node,
+                    Kind::NotFound,
+                    format!("Relay with alias {alias} not found."),
+                ))
+            }
+        };
+        if alias == new_alias {
+            return Ok(relay.into());
+        }
+        if self.registry.relays.contains_key(&new_alias).await {
+            return Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                format!("A relay with the name '{new_alias}' already exists"),
+            ));
+        }
+
+        let relay_address = match relay.relay_address.as_deref() {
+            Some(address) if address == alias => Some(new_alias.clone()),
+            Some(address) if address == format!("{PROJECT_RELAY_PREFIX}{alias}") => {
+                Some(format!("{PROJECT_RELAY_PREFIX}{new_alias}"))
+            }
+            _ => relay.relay_address.clone(),
+        };
+        let renames_relay_address = relay_address != relay.relay_address;
+
+        if !renames_relay_address {
+            self.delete_relay_impl(alias).await?;
+        }
+        let result = self
+            .create_relay(
+                ctx,
+                &relay.destination_address,
+                new_alias.clone(),
+                relay.at_rust_node,
+                relay.authorized.clone(),
+                relay_address,
+                relay.failover_addresses.clone(),
+                relay.failback,
+                relay.filter.clone(),
+            )
+            .await;
+
+        match result {
+            Ok(relay_info) => {
+                if renames_relay_address {
+                    if let Err(err) = self.delete_relay_impl(alias).await {
+                        warn!(%alias, %new_alias, %err, "Failed to delete the relay after renaming it");
+                    }
+                }
+                info!(%alias, %new_alias, "Relay renamed");
+                Ok(relay_info)
+            }
+            Err(err) => {
+                if !renames_relay_address {
+                    let restored = self
+                        .create_relay(
+                            ctx,
+                            &relay.destination_address,
+                            alias.to_string(),
+                            relay.at_rust_node,
+                            relay.authorized.clone(),
+                            relay.relay_address.clone(),
+                            relay.failover_addresses.clone(),
+                            relay.failback,
+                            relay.filter.clone(),
+                        )
+                        .await;
+                    if let Err(restore_err) = restored {
+                        error!(%alias, %restore_err, "Failed to restore the relay after a failed rename");
+                    }
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Return the ownership of the aliases registered with the relay service of this node,
+    /// if it is enforced with the [`OCKAM_RELAY_ALIAS_OWNERSHIP`] variable
+    pub(super) fn relay_alias_ownership(&self) -> Result<Option<RelayAliasOwnership>> {
+        if !get_env_with_default(OCKAM_RELAY_ALIAS_OWNERSHIP, false)? {
+            return Ok(None);
+        }
+        let authority = match self.authority() {
+            Some(authority) => authority,
+            None => {
+                warn!("The ownership of the relay aliases can't be enforced without an authority");
+                return Ok(None);
+            }
+        };
+        Ok(Some(RelayAliasOwnership::new(
+            self.relay_aliases_repository(),
+            self.secure_channels.identities().identities_attributes(),
+            authority,
+        )))
+    }
+
+    /// Return the owners of the aliases registered with the relay service of this node
+    pub async fn get_relay_alias_owners(&self) -> Result<Vec<RelayAliasOwnerInfo>> {
+        Ok(self
+            .relay_aliases_repository()
+            .get_alias_owners()
+            .await?
+            .into_iter()
+            .map(RelayAliasOwnerInfo::from)
+            .collect())
+    }
+
+    /// Release an alias registered with the relay service of another node by the identity of
+    /// this node, so that the alias can be registered by another identity
+    pub async fn release_relay_alias(
+        &self,
+        ctx: &Context,
+        address: &MultiAddr,
+        alias: &str,
+    ) -> Result<()> {
+        let connection_ctx = Arc::new(ctx.async_try_clone().await?);
+        let connection = self
+            .make_connection(connection_ctx, address, self.identifier(), None, None)
+            .await?;
+        let route = route![connection.route()?, DefaultAddress::RELAY_SERVICE];
+        let reply = ctx
+            .send_and_receive::<String>(route, format!("{RELAY_RELEASE_PREFIX}{alias}"))
+            .await;
+        if let Err(err) = connection.close(ctx, self).await {
+            warn!(%alias, %err, "Failed to close the connection used to release a relay alias");
+        }
+
+        match reply?.strip_prefix(RELAY_DENIED_PREFIX) {
+            Some(_) => Err(ockam_core::Error::new(
+                Origin::Node,
+                Kind::Conflict,
+                format!("The relay alias {alias} is owned by another identity"),
+            )),
+            None => {
+                info!(%alias, %address, "Relay alias released");
+                Ok(())
+            }
+        }
+    }
+
+    fn relay_aliases_repository(&self) -> Arc<dyn RelayAliasesRepository> {
+        Arc::new(RelayAliasesSqlxDatabase::new(self.cli_state.database()))
+    }
+
     /// This function finds an existing relay and returns its configuration
     pub(super) async fn show_relay(
         &self,
@@ -228,6 +521,7 @@ impl NodeManager {
 }
 
 impl InMemoryNode {
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_relay(
         &self,
         ctx: &Context,
@@ -236,39 +530,170 @@ impl InMemoryNode {
         at_rust_node: bool,
         authorized: Option<Identifier>,
         relay_address: Option<String>,
+        failover_addresses: Vec<MultiAddr>,
+        failback: bool,
+        filter: RemoteRelayFilter,
     ) -> Result<RelayInfo> {
         self.node_manager
-            .create_relay(ctx, address, alias, at_rust_node, authorized, relay_address)
+            .create_relay(
+                ctx,
+                address,
+                alias,
+                at_rust_node,
+                authorized,
+                relay_address,
+                failover_addresses,
+                failback,
+                filter,
+            )
             .await
     }
 
     pub async fn delete_relay(&self, remote_address: &str) -> Result<()> {
         self.node_manager.delete_relay_impl(remote_address).await
     }
+
+    pub async fn rename_relay(
+        &self,
+        ctx: &Context,
+        alias: &str,
+        new_alias: String,
+    ) -> Result<RelayInfo> {
+        self.node_manager.rename_relay(ctx, alias, new_alias).await
+    }
 }
 
 struct RelaySessionReplacer {
+    alias: String,
     node_manager: Arc<NodeManager>,
     context: Arc<Context>,
     relay_address: Option<String>,
 
+    // destinations, in order of preference
+    addresses: Vec<MultiAddr>,
+    failback: bool,
+    /// Set to stop the periodic check of the primary destination
+    failback_probe: Option<Arc<AtomicBool>>,
+    destination_status: RelayDestinationStatus,
+
     // current status
     connection: Option<Connection>,
     relay_worker_address: Option<Address>,
-    addr: MultiAddr,
+    active: usize,
     at_rust_node: bool,
     authorized: Option<Identifier>,
+    filter: RemoteRelayFilter,
 }
 
 #[async_trait]
 impl SessionReplacer for RelaySessionReplacer {
     async fn create(&mut self) -> std::result::Result<ReplacerOutcome, ockam_core::Error> {
-        debug!(addr = self.addr.to_string(), relay_address = ?self.relay_address, at_rust_node = ?self.at_rust_node, "Handling CreateRelay request");
+        // Start from the primary destination when failing back, otherwise keep using the
+        // currently active destination and only move on to the next ones when it fails
+        let start = if self.failback { 0 } else { self.active };
+        let count = self.addresses.len();
+        let mut last_error = None;
+        for index in (0..count).map(|i| (start + i) % count) {
+            match self.create_at(index).await {
+                Ok(outcome) => {
+                    self.active = index;
+                    self.destination_status.set_active(&self.addresses[index]);
+                    self.context.node_events().publish(
+                        NodeEvent::new(NodeEventKind::RelayUp, self.alias.as_str())
+                            .with_detail("destination", &self.addresses[index]),
+                    );
+                    if self.failback && index != 0 {
+                        self.start_failback_probe();
+                    }
+                    return Ok(outcome);
+                }
+                Err(err) => {
+                    warn!(addr = %self.addresses[index], %err, "Failed to create relay");
+                    self.close().await;
+                    last_error = Some(err);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ockam_core::Error::new(Origin::Api, Kind::Invalid, "No relay destination provided")
+        }))
+    }
+
+    async fn close(&mut self) {
+        if let Some(stop) = self.failback_probe.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+
+        if let Some(connection) = self.connection.take() {
+            let result = connection.close(&self.context, &self.node_manager).await;
+            if let Err(err) = result {
+                error!(?err, "Failed to close connection");
+            }
+        }
+
+        if let Some(relay_address) = self.relay_worker_address.take() {
+            self.context.node_events().publish(
+                NodeEvent::new(NodeEventKind::RelayDown, self.alias.as_str())
+                    .with_detail("destination", &self.addresses[self.active]),
+            );
+            match self.context.stop_worker(relay_address.clone()).await {
+                Ok(_) => {
+                    debug!(%relay_address, "Successfully stopped relay");
+                }
+                Err(err) => {
+                    error!(%relay_address, ?err, "Failed to stop relay address {relay_address}");
+                }
+            }
+        }
+    }
+}
+
+impl RelaySessionReplacer {
+    /// Check the primary destination periodically, while the relay is created at a failover
+    /// destination, and re-create the relay once the primary destination answers again.
+    /// The check stops when the relay is closed
+    fn start_failback_probe(&mut self) {
+        let stop = Arc::new(AtomicBool::new(false));
+        self.failback_probe = Some(stop.clone());
+        let node_manager = self.node_manager.clone();
+        let context = self.context.clone();
+        let alias = self.alias.clone();
+        let primary = self.addresses[0].clone();
+        let authorized = self.authorized.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(RELAY_FAILBACK_PROBE_INTERVAL).await;
+                if stop.load(Ordering::Relaxed) {
+                    return;
+                }
+                if !probe_destination(&node_manager, &context, &primary, authorized.clone()).await
+                    || stop.load(Ordering::Relaxed)
+                {
+                    continue;
+                }
+                info!(%alias, %primary, "The primary destination of the relay has recovered");
+                if let Some(relay) = node_manager.registry.relays.get(&alias).await {
+                    if let Err(err) = relay.session.replace().await {
+                        warn!(%alias, %err, "Failed to move the relay back to its primary destination");
+                    }
+                }
+                return;
+            }
+        });
+    }
+
+    /// Create the relay at the destination with the given index
+    async fn create_at(
+        &mut self,
+        index: usize,
+    ) -> std::result::Result<ReplacerOutcome, ockam_core::Error> {
+        let addr = self.addresses[index].clone();
+        debug!(addr = addr.to_string(), relay_address = ?self.relay_address, at_rust_node = ?self.at_rust_node, "Handling CreateRelay request");
         let connection = self
             .node_manager
             .make_connection(
                 self.context.clone(),
-                &self.addr.clone(),
+                &addr,
                 self.node_manager.identifier(),
                 self.authorized.clone(),
                 None,
@@ -283,7 +708,7 @@ impl SessionReplacer for RelaySessionReplacer {
         }
 
         let route = connection.route()?;
-        let options = RemoteRelayOptions::new();
+        let options = RemoteRelayOptions::new().with_filter(self.filter.clone());
 
         let relay_info = if self.at_rust_node {
             if let Some(relay_address) = self.relay_address.as_ref() {
@@ -310,29 +735,47 @@ impl SessionReplacer for RelaySessionReplacer {
             kind: ReplacerOutputKind::Relay(relay_info),
         })
     }
+}
 
-    async fn close(&mut self) {
-        if let Some(connection) = self.connection.take() {
-            let result = connection.close(&self.context, &self.node_manager).await;
-            if let Err(err) = result {
-                error!(?err, "Failed to close connection");
-            }
-        }
-
-        if let Some(relay_address) = self.relay_worker_address.take() {
-            match self.context.stop_worker(relay_address.clone()).await {
-                Ok(_) => {
-                    debug!(%relay_address, "Successfully stopped relay");
-                }
-                Err(err) => {
-                    error!(%relay_address, ?err, "Failed to stop relay address {relay_address}");
-                }
-            }
+/// Return true if a secure channel can be established with the node at `addr`
+/// and its echo service answers
+async fn probe_destination(
+    node_manager: &Arc<NodeManager>,
+    context: &Arc<Context>,
+    addr: &MultiAddr,
+    authorized: Option<Identifier>,
+) -> bool {
+    let connection = match node_manager
+        .make_connection(
+            context.clone(),
+            addr,
+            node_manager.identifier(),
+            authorized,
+            Some(RELAY_FAILBACK_PROBE_TIMEOUT),
+        )
+        .await
+    {
+        Ok(connection) => connection,
+        Err(err) => {
+            debug!(%addr, %err, "The relay destination is still unreachable");
+            return false;
         }
+    };
+    let echo = context
+        .send_and_receive_extended::<NeutralMessage>(
+            route![connection.transport_route(), DefaultAddress::ECHO_SERVICE],
+            NeutralMessage::from(vec![]),
+            MessageSendReceiveOptions::new().with_timeout(RELAY_FAILBACK_PROBE_TIMEOUT),
+        )
+        .await;
+    if let Err(err) = connection.close(context, node_manager).await {
+        debug!(%addr, %err, "Failed to close the connection used to check a relay destination");
     }
+    echo.is_ok()
 }
 
 #[async_trait]
+#[allow(clippy::too_many_arguments)]
 pub trait Relays {
     async fn create_relay(
         &self,
@@ -342,10 +785,14 @@ pub trait Relays {
         authorized: Option<Identifier>,
         relay_address: Option<String>,
         at_rust_node: bool,
+        failover_addresses: Vec<MultiAddr>,
+        failback: bool,
+        filter: RemoteRelayFilter,
     ) -> miette::Result<RelayInfo>;
 }
 
 #[async_trait]
+#[allow(clippy::too_many_arguments)]
 impl Relays for BackgroundNodeClient {
     async fn create_relay(
         &self,
@@ -355,14 +802,34 @@ impl Relays for BackgroundNodeClient {
         authorized: Option<Identifier>,
         relay_address: Option<String>,
         at_rust_node: bool,
+        failover_addresses: Vec<MultiAddr>,
+        failback: bool,
+        filter: RemoteRelayFilter,
     ) -> miette::Result<RelayInfo> {
+        // older nodes would silently ignore the failover configuration
+        if !failover_addresses.is_empty() || failback {
+            self.require_capability(ctx, NodeCapability::RELAY_FAILOVER, "relay failover")
+                .await?;
+        }
+        // or let all the messages through
+        if !filter.is_empty() {
+            self.require_capability(
+                ctx,
+                NodeCapability::RELAY_SERVICE_FILTER,
+                "relay service filters",
+            )
+            .await?;
+        }
         let body = CreateRelay::new(
             address.clone(),
             alias,
             at_rust_node,
             authorized,
             relay_address,
-        );
+        )
+        .with_failover_addresses(failover_addresses)
+        .with_failback(failback)
+        .with_filter(&filter);
         self.ask(ctx, Request::post("/node/relay").body(body)).await
     }
 }
@@ -423,3 +890,58 @@ impl SecureChannelsCreation for BackgroundNodeClient {
         Ok(response.addr)
     }
 }
+
+/// Register the handlers of the requests for the relays
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Get,
+        "/node/relay-aliases",
+        "get_relay_alias_owners",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_relay_alias_owners(r.header).await) }),
+    );
+    routes.add(
+        Method::Post,
+        "/node/relay-aliases/release",
+        "release_relay_alias",
+        |w, ctx, r| {
+            Box::pin(
+                async move { r.respond(w.release_relay_alias(ctx, r.header, r.body()?).await) },
+            )
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/relay/:alias",
+        "show_relay",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.show_relay(r.header, r.param("alias")).await) })
+        },
+    );
+    routes.add(Method::Get, "/node/relay", "get_relays", |w, _ctx, r| {
+        Box::pin(async move { r.respond(w.get_relays(r.header).await) })
+    });
+    routes.add(
+        Method::Delete,
+        "/node/relay/:alias",
+        "delete_relay",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.delete_relay(r.header, r.param("alias")).await) })
+        },
+    );
+    routes.add_cancellable(Method::Post, "/node/relay", "create_relay", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.create_relay(ctx, r.header, r.body()?).await) })
+    });
+    routes.add(
+        Method::Post,
+        "/node/relay/:alias/rename",
+        "rename_relay",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(
+                    w.rename_relay(ctx, r.header, r.param("alias"), r.body()?)
+                        .await,
+                )
+            })
+        },
+    );
+}
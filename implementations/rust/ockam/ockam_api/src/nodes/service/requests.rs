@@ -0,0 +1,424 @@
+//! Cancellation of the requests handled by the node manager.
+//!
+//! The node manager handles its requests one at a time. The handlers of long-running requests,
+//! for example the creation of a secure channel to an unreachable peer, are registered with
+//! [`RoutingTable::add_cancellable`] and run in their own task, so that the node manager
+//! can receive a cancellation request while they are in flight.
+//!
+//! Cancelling a request aborts its task: the handler future is dropped, which releases
+//! the temporary address used to send its messages, and stops the workers which were started
+//! but not ready yet, like the handshake worker of a secure channel.
+//! Then the resources which the handler created before being cancelled, registered with
+//! [`release_on_cancel`], are released: TCP connections, secure channels and relays.
+//! Finally the original caller receives a response with the [`Status::Cancelled`] status.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use tokio::task::JoinHandle;
+
+use ockam::identity::Identifier;
+use ockam::{Address, Context, Result, Route};
+use ockam_core::api::{Error, Id, Method, RequestHeader, Response, Status};
+use ockam_core::{AllowAll, DenyAll, MessagePriority};
+
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+use crate::session::sessions::Session;
+
+/// Number of requests handled by endpoints which can not be cancelled, which are remembered
+/// in order to answer their cancellation
+const MAX_NOT_CANCELLABLE_REQUESTS: usize = 64;
+
+/// Reason why a request could not be cancelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CancellationFailure {
+    /// The request was handled by an endpoint which can not be cancelled
+    NotCancellable,
+    /// There is no request in flight with this identifier, it might have completed already
+    NotFound,
+}
+
+tokio::task_local! {
+    /// Resources created by the cancellable request handled by the current task
+    static CREATED_RESOURCES: Arc<CreatedResources>;
+}
+
+/// Resource created by a request, which must be released if the request is cancelled
+pub(crate) enum CreatedResource {
+    /// TCP connection, identified by the address of its sender worker
+    TcpConnection(Address),
+    /// Secure channel, identified by its encryptor address
+    SecureChannel(Address),
+    /// Relay registered with its alias
+    Relay(String, Session),
+}
+
+/// Resources created by a cancellable request
+#[derive(Default)]
+struct CreatedResources {
+    resources: Mutex<Vec<CreatedResource>>,
+}
+
+/// Register a resource created while handling a cancellable request, so that it is released
+/// if the request is cancelled. This has no effect outside of a cancellable request
+pub(crate) fn release_on_cancel(resource: CreatedResource) {
+    let _ = CREATED_RESOURCES.try_with(|created| created.resources.lock().unwrap().push(resource));
+}
+
+impl CreatedResources {
+    /// Release the resources, in the reverse order of their creation.
+    /// Some resources might have been released already, for example the secure channels of a
+    /// relay which is closed, so the failures are only logged
+    async fn release(&self, ctx: &Context, node_manager: &NodeManager) {
+        let resources = std::mem::take(&mut *self.resources.lock().unwrap());
+        for resource in resources.into_iter().rev() {
+            match resource {
+                CreatedResource::TcpConnection(address) => {
+                    if let Err(e) = node_manager.tcp_transport.disconnect(address.clone()).await {
+                        debug!(%address, "cannot disconnect the tcp connection: {e}");
+                    }
+                }
+                CreatedResource::SecureChannel(address) => {
+                    if let Err(e) = node_manager
+                        .secure_channels
+                        .stop_secure_channel(ctx, &address)
+                        .await
+                    {
+                        debug!(%address, "cannot stop the secure channel: {e}");
+                    }
+                    node_manager
+                        .registry
+                        .secure_channels
+                        .remove_by_addr(&address)
+                        .await;
+                }
+                CreatedResource::Relay(alias, session) => {
+                    // the relay is only removed from the registry if it was not replaced since
+                    let registered = node_manager.registry.relays.get(&alias).await;
+                    if registered.is_some_and(|relay| relay.session.key() == session.key()) {
+                        node_manager.registry.relays.remove(&alias).await;
+                    }
+                    if let Err(e) = session.close().await {
+                        debug!(%alias, "cannot close the relay: {e}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Request handled in its own task
+struct InFlightRequest {
+    header: RequestHeader,
+    return_route: Route,
+    task: JoinHandle<()>,
+    created: Arc<CreatedResources>,
+}
+
+#[derive(Default)]
+struct InFlightState {
+    cancellable: BTreeMap<Id, InFlightRequest>,
+    not_cancellable: VecDeque<Id>,
+}
+
+/// Requests of the node manager which are currently handled, and can be cancelled
+#[derive(Default)]
+pub(crate) struct InFlightRequests {
+    state: Mutex<InFlightState>,
+}
+
+impl InFlightRequests {
+    /// Register a request handled by an endpoint which can not be cancelled
+    pub(super) fn add_not_cancellable(&self, id: Id) {
+        let mut state = self.state.lock().unwrap();
+        if state.not_cancellable.len() == MAX_NOT_CANCELLABLE_REQUESTS {
+            state.not_cancellable.pop_front();
+        }
+        state.not_cancellable.push_back(id);
+    }
+
+    /// Remove a request which completed.
+    /// Return false if the request was cancelled, in which case its response must not be sent
+    fn complete(&self, id: Id) -> bool {
+        self.state.lock().unwrap().cancellable.remove(&id).is_some()
+    }
+
+    /// Abort a request, and return the request when it was still in flight
+    fn cancel(&self, id: Id) -> std::result::Result<InFlightRequest, CancellationFailure> {
+        let mut state = self.state.lock().unwrap();
+        match state.cancellable.remove(&id) {
+            Some(request) => {
+                request.task.abort();
+                Ok(request)
+            }
+            None if state.not_cancellable.contains(&id) => Err(CancellationFailure::NotCancellable),
+            None => Err(CancellationFailure::NotFound),
+        }
+    }
+}
+
+impl NodeManagerWorker {
+    /// Handle a request in its own task, so that it can be cancelled while it is in flight.
+    ///
+    /// The handler uses a detached context, with a temporary address, to send its messages.
+    /// This address is released when the handler completes, or when it is cancelled.
+    /// The resources created by the handler are recorded, to be released on cancellation.
+    pub(super) async fn spawn_cancellable_request(
+        &self,
+        ctx: &Context,
+        header: RequestHeader,
+        caller: Option<Identifier>,
+        body: Vec<u8>,
+        return_route: Route,
+    ) -> Result<()> {
+        let mut worker = self.clone();
+        let mut request_ctx = ctx
+            .new_detached(
+                Address::random_tagged("NodeManagerWorker.request"),
+                DenyAll,
+                AllowAll,
+            )
+            .await?;
+        let requests = self.requests.clone();
+        let id = header.id();
+        let created = Arc::new(CreatedResources::default());
+
+        // the request is registered before its task can complete
+        let mut state = requests.state.lock().unwrap();
+        let task_header = header.clone();
+        let task_return_route = return_route.clone();
+        let task_created = created.clone();
+        let task = tokio::spawn(async move {
+            let result = CREATED_RESOURCES
+                .scope(
+                    task_created,
+                    worker.handle_request(&mut request_ctx, &task_header, caller.as_ref(), &body),
+                )
+                .await;
+            if !worker.requests.complete(task_header.id()) {
+                return;
+            }
+            let response = match Self::encode_result(&task_header, result) {
+                Ok(response) => response,
+                Err(e) => {
+                    error!(re = %task_header.id(), "cannot encode the response: {e}");
+                    return;
+                }
+            };
+            if let Err(e) = request_ctx
+                .send_with_priority(task_return_route, response, MessagePriority::High)
+                .await
+            {
+                warn!(re = %task_header.id(), "cannot send the response: {e}");
+            }
+        });
+        state.cancellable.insert(
+            id,
+            InFlightRequest {
+                header,
+                return_route,
+                task,
+                created,
+            },
+        );
+        Ok(())
+    }
+
+    /// Cancel a request which is in flight, and release the resources it created.
+    /// The original caller receives a response with the [`Status::Cancelled`] status
+    pub(super) async fn cancel_request(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        id: &str,
+    ) -> Result<std::result::Result<Response, Response<Error>>> {
+        let id: Id = match id.parse() {
+            Ok(id) => id,
+            Err(e) => return Ok(Err(Response::bad_request(req, &e.to_string()))),
+        };
+        match self.requests.cancel(id) {
+            Ok(request) => {
+                // wait for the task to stop, so that it can't create resources anymore
+                let _ = request.task.await;
+                request.created.release(ctx, &self.node_manager).await;
+                debug!(re = %id, path = %request.header.path(), "cancelled a request");
+                ctx.send_with_priority(
+                    request.return_route,
+                    Response::cancelled(&request.header).to_vec()?,
+                    MessagePriority::High,
+                )
+                .await?;
+                Ok(Ok(Response::ok()))
+            }
+            Err(CancellationFailure::NotCancellable) => Ok(Err(Response::error(
+                req,
+                &format!("The request {id} is not cancellable"),
+                Status::Conflict,
+            ))),
+            Err(CancellationFailure::NotFound) => Ok(Err(Response::not_found(
+                req,
+                &format!("There is no request {id} in flight"),
+            ))),
+        }
+    }
+}
+
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Delete,
+        "/node/requests/:id",
+        "cancel_request",
+        |w, ctx, r| {
+            Box::pin(
+                async move { r.respond(w.cancel_request(ctx, r.header, r.param("id")).await?) },
+            )
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::models::relay::{CreateRelay, RelayInfo};
+    use crate::nodes::models::secure_channel::CreateSecureChannelRequest;
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::test_utils::start_manager_for_tests;
+    use minicbor::Encode;
+    use ockam::{Any, Routed, Worker};
+    use ockam_core::api::{Reply, Request};
+    use ockam_core::route;
+    use ockam_multiaddr::MultiAddr;
+    use ockam_node::api::Client;
+    use std::collections::BTreeSet;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    /// Worker which never replies, so that a secure channel handshake never completes
+    struct Unresponsive;
+
+    #[ockam::worker]
+    impl Worker for Unresponsive {
+        type Context = Context;
+        type Message = Any;
+
+        async fn handle_message(&mut self, _ctx: &mut Context, _msg: Routed<Any>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Send a request, cancel it while it is in flight, and check that the original caller
+    /// is notified of the cancellation
+    async fn send_and_cancel<T: Encode<()>>(
+        context: &Context,
+        client: &Client,
+        request: Request<T>,
+    ) -> ockam::Result<Id> {
+        let id = request.header().id();
+        let cancel = async {
+            // leave some time for the request to be in flight
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            client
+                .tell(context, Request::delete(format!("/node/requests/{id}")))
+                .await
+        };
+        let (created, cancelled) = tokio::join!(client.tell(context, request), cancel);
+
+        match created? {
+            Reply::Failed(_, status) => assert_eq!(status, Some(Status::Cancelled)),
+            Reply::Successful(_) => panic!("the request should have been cancelled"),
+        }
+        assert!(matches!(cancelled?, Reply::Successful(_)));
+        Ok(id)
+    }
+
+    /// Wait until the workers of the node are the expected ones, since the workers of a
+    /// cancelled request are stopped asynchronously
+    async fn assert_workers(context: &Context, expected: &BTreeSet<Address>) -> ockam::Result<()> {
+        let mut workers = BTreeSet::new();
+        for _ in 0..50 {
+            workers = context.list_workers().await?.into_iter().collect();
+            if &workers == expected {
+                return Ok(());
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("the workers {workers:?} should be {expected:?}");
+    }
+
+    #[ockam_macros::test]
+    async fn cancel_in_flight_requests(context: &mut Context) -> ockam::Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        context.start_worker("unresponsive", Unresponsive).await?;
+        let client = Client::new(&route![NODEMANAGER_ADDR], Some(Duration::from_secs(10)));
+        let workers: BTreeSet<Address> = context.list_workers().await?.into_iter().collect();
+        let unresponsive = MultiAddr::from_str("/service/unresponsive").unwrap();
+
+        // the handshake of the secure channel never completes
+        let create = Request::post("/node/secure_channel").body(CreateSecureChannelRequest::new(
+            &unresponsive,
+            None,
+            None,
+        ));
+        let id = send_and_cancel(context, &client, create).await?;
+
+        // nothing is left from the request: no worker, no secure channel, no connection
+        assert_workers(context, &workers).await?;
+        let secure_channels: Vec<String> = client
+            .ask(context, Request::get("/node/secure_channel"))
+            .await?
+            .success()?;
+        assert!(secure_channels.is_empty());
+        assert!(handle.tcp.registry().get_all_sender_workers().is_empty());
+
+        // the registration of the relay is never acknowledged
+        let create = Request::post("/node/relay").body(CreateRelay::new(
+            unresponsive.clone(),
+            "cancelled".to_string(),
+            true,
+            None,
+            Some("cancelled".to_string()),
+        ));
+        send_and_cancel(context, &client, create).await?;
+        assert_workers(context, &workers).await?;
+        let relays: Vec<RelayInfo> = client
+            .ask(context, Request::get("/node/relay"))
+            .await?
+            .success()?;
+        assert!(relays.is_empty());
+
+        // the alias of the cancelled relay is not reserved anymore
+        assert!(handle
+            .node_manager
+            .registry
+            .relay_reservations
+            .reserve("cancelled")
+            .is_some());
+
+        // the request is not in flight anymore
+        match client
+            .tell(context, Request::delete(format!("/node/requests/{id}")))
+            .await?
+        {
+            Reply::Failed(_, status) => assert_eq!(status, Some(Status::NotFound)),
+            Reply::Successful(_) => panic!("the request should not be found"),
+        }
+
+        // the requests handled by other endpoints can not be cancelled
+        let list = Request::get("/node/secure_channel");
+        let list_id = list.header().id();
+        client.tell(context, list).await?.success()?;
+        match client
+            .tell(
+                context,
+                Request::delete(format!("/node/requests/{list_id}")),
+            )
+            .await?
+        {
+            Reply::Failed(_, status) => assert_eq!(status, Some(Status::Conflict)),
+            Reply::Successful(_) => panic!("the request should not be cancellable"),
+        }
+
+        context.stop().await
+    }
+}
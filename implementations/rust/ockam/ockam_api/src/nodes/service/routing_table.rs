@@ -0,0 +1,468 @@
+//! Routing of the requests sent to the node manager.
+//!
+//! Each service of the node manager registers the handlers of its requests in a [`RoutingTable`],
+//! with the method and the path pattern of the requests they handle.
+
+use std::cmp::Reverse;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use minicbor::{Decode, Decoder, Encode};
+
+use ockam::identity::Identifier;
+use ockam::{Context, Result};
+use ockam_core::api::{Error, Method, RequestHeader, Response, ResponseHeader, Segments};
+
+use crate::nodes::models::api_endpoints::{ApiEndpoint, ApiEndpointList};
+use crate::nodes::service::{decode_body, encode_response};
+use crate::nodes::NodeManagerWorker;
+
+/// Maximum number of segments of a request path.
+/// The last segment of a longer path contains the rest of the path
+const MAX_PATH_SEGMENTS: usize = 5;
+
+/// Future returned by a request handler
+pub(crate) type HandlerFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + 'a>>;
+
+/// Function handling the requests of an endpoint and returning the encoded response
+pub(crate) type Handler =
+    for<'a> fn(&'a mut NodeManagerWorker, &'a mut Context, &'a ApiRequest<'a>) -> HandlerFuture<'a>;
+
+/// Request received by a handler
+pub(crate) struct ApiRequest<'a> {
+    pub(crate) header: &'a RequestHeader,
+    /// Identity of the caller, when the request is received through a secure channel
+    pub(crate) caller: Option<&'a Identifier>,
+    params: Vec<(&'a str, &'a str)>,
+    body: &'a [u8],
+}
+
+impl<'a> ApiRequest<'a> {
+    pub(crate) fn new(
+        header: &'a RequestHeader,
+        caller: Option<&'a Identifier>,
+        params: Vec<(&'a str, &'a str)>,
+        body: &'a [u8],
+    ) -> Self {
+        Self {
+            header,
+            caller,
+            params,
+            body,
+        }
+    }
+
+    /// Return the path segment matching a parameter of the endpoint pattern.
+    /// The parameter must be declared by the pattern
+    pub(crate) fn param(&self, name: &str) -> &'a str {
+        self.params
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, value)| *value)
+            .unwrap_or_else(|| panic!("the endpoint pattern has no parameter named {name}"))
+    }
+
+    /// Decode the body of the request
+    pub(crate) fn body<T: Decode<'a, ()>>(&self) -> Result<T> {
+        decode_body(&mut Decoder::new(self.body))
+    }
+
+    /// Append the request header to the response and encode it
+    pub(crate) fn respond<T: Encode<()>>(
+        &self,
+        res: std::result::Result<Response<T>, Response<Error>>,
+    ) -> Result<Vec<u8>> {
+        encode_response(self.header, res)
+    }
+}
+
+/// Segment of a path pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PatternSegment {
+    Literal(String),
+    /// Any segment, named after the ':' prefix of the pattern
+    Param(String),
+}
+
+/// Path pattern of an endpoint, for example `/node/inlet/:alias`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PathPattern(Vec<PatternSegment>);
+
+impl PathPattern {
+    pub(crate) fn parse(pattern: &str) -> Self {
+        Self(
+            pattern
+                .trim_start_matches('/')
+                .split('/')
+                .map(|s| match s.strip_prefix(':') {
+                    Some(name) => PatternSegment::Param(name.to_string()),
+                    None => PatternSegment::Literal(s.to_string()),
+                })
+                .collect(),
+        )
+    }
+
+    /// Return the values of the parameters if the path segments match the pattern
+    fn matches<'t, 'p>(&'t self, segments: &[&'p str]) -> Option<Vec<(&'t str, &'p str)>> {
+        if segments.len() != self.0.len() {
+            return None;
+        }
+        let mut params = vec![];
+        for (pattern, segment) in self.0.iter().zip(segments) {
+            match pattern {
+                PatternSegment::Literal(literal) if literal == segment => (),
+                PatternSegment::Literal(_) => return None,
+                PatternSegment::Param(name) => params.push((name.as_str(), *segment)),
+            }
+        }
+        Some(params)
+    }
+
+    /// Positions of the literal segments. When several patterns match a path, the pattern
+    /// with a literal segment where the others have a parameter is selected
+    fn specificity(&self) -> Vec<bool> {
+        self.0
+            .iter()
+            .map(|s| matches!(s, PatternSegment::Literal(_)))
+            .collect()
+    }
+}
+
+impl Display for PathPattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for segment in self.0.iter() {
+            match segment {
+                PatternSegment::Literal(literal) => write!(f, "/{literal}")?,
+                PatternSegment::Param(name) => write!(f, "/:{name}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of requests handled by an endpoint
+#[derive(Debug, Default)]
+struct EndpointStatistics {
+    requests: AtomicU64,
+    /// Requests which failed, or were answered with an error status
+    errors: AtomicU64,
+    total_latency_us: AtomicU64,
+}
+
+/// Handler of the requests with a given method and path pattern
+pub(crate) struct Endpoint {
+    method: Method,
+    pattern: PathPattern,
+    name: &'static str,
+    handler: Handler,
+    /// True if the requests are handled in their own task, and can be cancelled
+    cancellable: bool,
+    statistics: EndpointStatistics,
+}
+
+impl Endpoint {
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub(crate) fn is_cancellable(&self) -> bool {
+        self.cancellable
+    }
+
+    /// Handle a request and record its latency and outcome
+    pub(crate) async fn handle(
+        &self,
+        worker: &mut NodeManagerWorker,
+        ctx: &mut Context,
+        request: &ApiRequest<'_>,
+    ) -> Result<Vec<u8>> {
+        let started_at = Instant::now();
+        let result = (self.handler)(worker, ctx, request).await;
+        self.record(started_at.elapsed(), &result);
+        result
+    }
+
+    fn record(&self, latency: Duration, result: &Result<Vec<u8>>) {
+        let statistics = &self.statistics;
+        statistics.requests.fetch_add(1, Ordering::Relaxed);
+        statistics
+            .total_latency_us
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        let is_error = match result {
+            Ok(response) => !Decoder::new(response)
+                .decode::<ResponseHeader>()
+                .map(|header| header.is_ok())
+                .unwrap_or(false),
+            Err(_) => true,
+        };
+        if is_error {
+            statistics.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn to_api_endpoint(&self) -> ApiEndpoint {
+        let requests = self.statistics.requests.load(Ordering::Relaxed);
+        let total_latency_us = self.statistics.total_latency_us.load(Ordering::Relaxed);
+        ApiEndpoint {
+            method: self.method.to_string(),
+            path: self.pattern.to_string(),
+            name: self.name.to_string(),
+            requests,
+            errors: self.statistics.errors.load(Ordering::Relaxed),
+            average_latency_us: total_latency_us.checked_div(requests).unwrap_or(0),
+        }
+    }
+}
+
+/// Endpoints of the node manager API
+#[derive(Default)]
+pub(crate) struct RoutingTable {
+    endpoints: Vec<Endpoint>,
+}
+
+impl RoutingTable {
+    /// Register the handler of the requests with a method and a path pattern.
+    /// The parameters of the pattern are the segments prefixed with ':'
+    pub(crate) fn add(
+        &mut self,
+        method: Method,
+        pattern: impl AsRef<str>,
+        name: &'static str,
+        handler: Handler,
+    ) {
+        self.push(method, pattern.as_ref(), name, handler, false)
+    }
+
+    /// Register the handler of long-running requests, which can be cancelled while they
+    /// are in flight. These requests are handled in their own task, concurrently with the
+    /// other requests of the node manager
+    pub(crate) fn add_cancellable(
+        &mut self,
+        method: Method,
+        pattern: impl AsRef<str>,
+        name: &'static str,
+        handler: Handler,
+    ) {
+        self.push(method, pattern.as_ref(), name, handler, true)
+    }
+
+    fn push(
+        &mut self,
+        method: Method,
+        pattern: &str,
+        name: &'static str,
+        handler: Handler,
+        cancellable: bool,
+    ) {
+        self.endpoints.push(Endpoint {
+            method,
+            pattern: PathPattern::parse(pattern),
+            name,
+            handler,
+            cancellable,
+            statistics: EndpointStatistics::default(),
+        })
+    }
+
+    /// Return the endpoint handling a request, with the values of its parameters.
+    ///
+    /// When several endpoints match the request, the endpoint with the most specific pattern
+    /// is returned, then the endpoint registered first
+    pub(crate) fn find<'t, 'p>(
+        &'t self,
+        method: Method,
+        path: &'p str,
+    ) -> Option<(&'t Endpoint, Vec<(&'t str, &'p str)>)> {
+        let segments = Segments::<MAX_PATH_SEGMENTS>::parse(path);
+        self.endpoints
+            .iter()
+            .filter(|endpoint| endpoint.method == method)
+            .filter_map(|endpoint| {
+                endpoint
+                    .pattern
+                    .matches(segments.as_slice())
+                    .map(|params| (endpoint, params))
+            })
+            .min_by_key(|(endpoint, _)| Reverse(endpoint.pattern.specificity()))
+    }
+
+    /// Return all the endpoints, with the statistics of their requests
+    pub(crate) fn list(&self) -> ApiEndpointList {
+        ApiEndpointList::new(
+            self.endpoints
+                .iter()
+                .map(|endpoint| endpoint.to_api_endpoint())
+                .collect(),
+        )
+    }
+}
+
+impl NodeManagerWorker {
+    /// Return the endpoints of the node manager API
+    pub(super) fn list_api_endpoints(
+        &self,
+    ) -> std::result::Result<Response<ApiEndpointList>, Response<Error>> {
+        Ok(Response::ok().body(self.routing_table.list()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::nodes::models::portal::{InletList, InletStatus};
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::test_utils::start_manager_for_tests;
+    use ockam_core::api::{Reply, Request};
+    use ockam_core::route;
+    use ockam_node::api::Client;
+    use Method::*;
+
+    fn unused_handler<'a>(
+        _worker: &'a mut NodeManagerWorker,
+        _ctx: &'a mut Context,
+        _request: &'a ApiRequest<'a>,
+    ) -> HandlerFuture<'a> {
+        Box::pin(async { Ok(vec![]) })
+    }
+
+    fn found(routes: &RoutingTable, method: Method, path: &str) -> Option<(String, Vec<String>)> {
+        routes.find(method, path).map(|(endpoint, params)| {
+            (
+                endpoint.name().to_string(),
+                params
+                    .iter()
+                    .map(|(name, value)| format!("{name}={value}"))
+                    .collect(),
+            )
+        })
+    }
+
+    #[test]
+    fn the_most_specific_pattern_is_selected() {
+        let mut routes = RoutingTable::default();
+        routes.add(
+            Get,
+            "/node/services/:service_type",
+            "list_services_of_type",
+            unused_handler,
+        );
+        routes.add(
+            Get,
+            "/node/services/echo",
+            "show_echo_service",
+            unused_handler,
+        );
+        routes.add(
+            Post,
+            "/node/services/:service_type",
+            "start_service",
+            unused_handler,
+        );
+        routes.add(Get, "/node/services", "list_services", unused_handler);
+        routes.add(Get, "/node/:a/:b", "two_params", unused_handler);
+        routes.add(Get, "/node/relay/:alias", "show_relay", unused_handler);
+        routes.add(Get, "/node/:name/relay", "show_node_relay", unused_handler);
+        routes.add(Get, "/node/relay/:other", "shadowed", unused_handler);
+
+        // a literal segment is preferred to a parameter, whatever the registration order
+        assert_eq!(
+            found(&routes, Get, "/node/services/echo"),
+            Some(("show_echo_service".to_string(), vec![]))
+        );
+        assert_eq!(
+            found(&routes, Get, "/node/services/kafka"),
+            Some((
+                "list_services_of_type".to_string(),
+                vec!["service_type=kafka".to_string()]
+            ))
+        );
+        // the method is part of the match
+        assert_eq!(
+            found(&routes, Post, "/node/services/echo"),
+            Some((
+                "start_service".to_string(),
+                vec!["service_type=echo".to_string()]
+            ))
+        );
+        // the number of segments is part of the match
+        assert_eq!(
+            found(&routes, Get, "node/services"),
+            Some(("list_services".to_string(), vec![]))
+        );
+        assert_eq!(
+            found(&routes, Get, "/node/inlet/alias"),
+            Some((
+                "two_params".to_string(),
+                vec!["a=inlet".to_string(), "b=alias".to_string()]
+            ))
+        );
+        // the first literal segment decides, then the registration order
+        assert_eq!(
+            found(&routes, Get, "/node/relay/relay"),
+            Some(("show_relay".to_string(), vec!["alias=relay".to_string()]))
+        );
+        assert_eq!(found(&routes, Get, "/node/services/echo/more"), None);
+        assert_eq!(found(&routes, Delete, "/node/services"), None);
+    }
+
+    #[test]
+    fn patterns_are_displayed_with_their_parameters() {
+        let pattern = PathPattern::parse("/node/inlet/:alias/bandwidth");
+        assert_eq!(pattern.to_string(), "/node/inlet/:alias/bandwidth");
+        assert_eq!(
+            PathPattern::parse("node/inlet"),
+            PathPattern::parse("/node/inlet")
+        );
+    }
+
+    #[ockam_macros::test]
+    async fn the_api_endpoints_are_listed_with_their_statistics(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+
+        let inlet: Reply<InletStatus> = client
+            .ask(context, Request::get("/node/inlet/unknown"))
+            .await?;
+        assert!(inlet.found()?.is_none());
+        let _: InletList = client
+            .ask(context, Request::get("/node/inlet"))
+            .await?
+            .success()?;
+
+        let endpoints: ApiEndpointList = client
+            .ask(context, Request::get("/node/api"))
+            .await?
+            .success()?;
+
+        let endpoint = |name: &str| {
+            endpoints
+                .list
+                .iter()
+                .find(|e| e.name == name)
+                .cloned()
+                .unwrap_or_else(|| panic!("the endpoint {name} is not listed"))
+        };
+        let show_inlet = endpoint("show_inlet");
+        assert_eq!(show_inlet.method, "GET");
+        assert_eq!(show_inlet.path, "/node/inlet/:alias");
+        assert_eq!(show_inlet.requests, 1);
+        assert_eq!(show_inlet.errors, 1);
+
+        let get_inlets = endpoint("get_inlets");
+        assert_eq!(get_inlets.requests, 1);
+        assert_eq!(get_inlets.errors, 0);
+
+        // the handler names are unique
+        let mut names: Vec<String> = endpoints.list.iter().map(|e| e.name.clone()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 use ockam::identity::TrustEveryonePolicy;
@@ -6,15 +7,16 @@ use ockam::identity::{
     Identifier, Identities, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
     TrustMultiIdentifiersPolicy,
 };
-use ockam::identity::{SecureChannel, SecureChannelListener};
+use ockam::identity::{SecureChannel, SecureChannelListener, SecureChannelRegistryEntry};
 use ockam::{Address, Result, Route};
-use ockam_core::api::{Error, Response};
+use ockam_core::api::{Error, Method, Response};
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::AsyncTryClone;
+use ockam_core::{AsyncTryClone, RateLimit};
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 
+use crate::error::ApiError;
 use crate::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
 use crate::nodes::models::secure_channel::CreateSecureChannelRequest;
 use crate::nodes::models::secure_channel::DeleteSecureChannelListenerRequest;
@@ -28,6 +30,8 @@ use crate::nodes::models::secure_channel::{
 };
 use crate::nodes::registry::{SecureChannelInfo, SecureChannelListenerInfo};
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::service::{release_on_cancel, CreatedResource};
 use crate::nodes::{NodeManager, NodeManagerWorker};
 
 /// SECURE CHANNELS
@@ -87,7 +91,20 @@ impl NodeManagerWorker {
                 .get_secure_channel(&address)
                 .await
                 .map(|secure_channel| {
-                    Response::ok().body(ShowSecureChannelResponse::new(Some(secure_channel)))
+                    let entry = self
+                        .node_manager
+                        .get_secure_channel_registry_entry(secure_channel.sc().encryptor_address());
+                    let previous_trust_options = secure_channel.trust_options_version()
+                        < self.node_manager.get_trust_options().version;
+                    let mut response = ShowSecureChannelResponse::new(Some(secure_channel))
+                        .with_previous_trust_options(previous_trust_options);
+                    if let Some(entry) = entry {
+                        response = response
+                            .with_clock_skew(entry.their_clock_skew())
+                            .with_rekeys(entry.encryptor_rekeys(), entry.decryptor_rekeys())
+                            .with_max_plaintext_size(entry.max_plaintext_size());
+                    }
+                    Response::ok().body(response)
                 })?;
 
         Ok(response)
@@ -105,12 +122,20 @@ impl NodeManagerWorker {
             addr,
             authorized_identifiers,
             identity_name,
-            ..
+            required_attributes,
+            rate_limit,
         } = create_secure_channel_listener;
 
         let response = self
             .node_manager
-            .create_secure_channel_listener(addr, authorized_identifiers, identity_name, ctx)
+            .create_secure_channel_listener(
+                addr,
+                authorized_identifiers,
+                required_attributes.unwrap_or_default(),
+                rate_limit,
+                identity_name,
+                ctx,
+            )
             .await
             .map(|_| Response::ok())?;
         Ok(response)
@@ -194,6 +219,8 @@ impl NodeManager {
         timeout: Option<Duration>,
     ) -> Result<SecureChannel> {
         debug!(%sc_route, "Creating secure channel");
+        // the trust options can be updated while the channel is created
+        let trust = self.current_trust();
         let options = SecureChannelOptions::new();
 
         let options = if let Some(timeout) = timeout {
@@ -202,15 +229,15 @@ impl NodeManager {
             options
         };
 
-        let options = match self.authority() {
+        let options = match trust.authority {
             Some(authority) => options.with_authority(authority),
             None => options,
         };
 
-        let options = match self.credential_retriever_creator.as_ref() {
+        let options = match trust.credential_retriever_creator {
             None => options,
             Some(credential_retriever_creator) => {
-                options.with_credential_retriever_creator(credential_retriever_creator.clone())?
+                options.with_credential_retriever_creator(credential_retriever_creator)?
             }
         };
 
@@ -225,10 +252,13 @@ impl NodeManager {
             .await?;
 
         debug!(%sc_route, %sc, "Created secure channel");
+        release_on_cancel(CreatedResource::SecureChannel(
+            sc.encryptor_address().clone(),
+        ));
 
         self.registry
             .secure_channels
-            .insert(sc_route, sc.clone(), authorized_identifiers)
+            .insert(sc_route, sc.clone(), authorized_identifiers, trust.version)
             .await;
 
         Ok(sc)
@@ -261,6 +291,16 @@ impl NodeManager {
             ))
     }
 
+    /// Return the registry entry of a secure channel, with the clock skew and keys renewals counters
+    pub fn get_secure_channel_registry_entry(
+        &self,
+        addr: &Address,
+    ) -> Option<SecureChannelRegistryEntry> {
+        self.secure_channels
+            .secure_channel_registry()
+            .get_channel_by_encryptor_address(addr)
+    }
+
     pub async fn list_secure_channels(&self) -> Vec<String> {
         let registry = &self.registry.secure_channels;
         let secure_channel_list = registry.list().await;
@@ -277,6 +317,8 @@ impl NodeManager {
         &self,
         address: Address,
         authorized_identifiers: Option<Vec<Identifier>>,
+        required_attributes: BTreeMap<String, String>,
+        rate_limit: Option<RateLimit>,
         identity_name: Option<String>,
         ctx: &Context,
     ) -> Result<SecureChannelListener> {
@@ -312,16 +354,33 @@ impl NodeManager {
 
         let options = match self.authority() {
             Some(authority) => options.with_authority(authority),
+            None if !required_attributes.is_empty() => {
+                return Err(ApiError::core(
+                    "Required attributes can only be checked by a node with a trusted authority",
+                ));
+            }
+            None => options,
+        };
+
+        let options = required_attributes
+            .iter()
+            .fold(options, |options, (key, value)| {
+                options.with_required_attribute(key, value)
+            });
+
+        let options = match rate_limit {
+            Some(rate_limit) => options.with_rate_limit(rate_limit),
             None => options,
         };
 
-        let options = match self.credential_retriever_creator.as_ref() {
+        let options = match self.credential_retriever_creator() {
             None => options,
             Some(credential_retriever_creator) => {
-                options.with_credential_retriever_creator(credential_retriever_creator.clone())?
+                options.with_credential_retriever_creator(credential_retriever_creator)?
             }
         };
 
+        let rate_limiting_access_control = options.rate_limiting_access_control();
         let listener = secure_channels
             .create_secure_channel_listener(ctx, &identifier, address.clone(), options)
             .await?;
@@ -330,7 +389,8 @@ impl NodeManager {
             .secure_channel_listeners
             .insert(
                 address.clone(),
-                SecureChannelListenerInfo::new(listener.clone()),
+                SecureChannelListenerInfo::new(listener.clone())
+                    .with_rate_limiting_access_control(rate_limiting_access_control),
             )
             .await;
 
@@ -344,6 +404,11 @@ impl NodeManager {
             listener.flow_control_id(),
         );
 
+        ctx.flow_controls().add_consumer(
+            DefaultAddress::SERVICE_DISCOVERY,
+            listener.flow_control_id(),
+        );
+
         Ok(listener)
     }
 
@@ -399,3 +464,69 @@ impl NodeManager {
         )))
     }
 }
+
+/// Register the handlers of the requests for the secure channels and secure channel listeners
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Get,
+        "/node/secure_channel",
+        "list_secure_channels",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.list_secure_channels().await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/secure_channel_listener",
+        "list_secure_channel_listener",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.list_secure_channel_listener().await) }),
+    );
+    routes.add_cancellable(
+        Method::Post,
+        "/node/secure_channel",
+        "create_secure_channel",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.create_secure_channel(r.body()?, ctx).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        "/node/secure_channel",
+        "delete_secure_channel",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.delete_secure_channel(r.body()?, ctx).await) })
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/show_secure_channel",
+        "show_secure_channel",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.show_secure_channel(r.body()?).await) }),
+    );
+    routes.add(
+        Method::Post,
+        "/node/secure_channel_listener",
+        "create_secure_channel_listener",
+        |w, ctx, r| {
+            Box::pin(
+                async move { r.respond(w.create_secure_channel_listener(r.body()?, ctx).await) },
+            )
+        },
+    );
+    routes.add(
+        Method::Delete,
+        "/node/secure_channel_listener",
+        "delete_secure_channel_listener",
+        |w, ctx, r| {
+            Box::pin(
+                async move { r.respond(w.delete_secure_channel_listener(r.body()?, ctx).await) },
+            )
+        },
+    );
+    routes.add(
+        Method::Get,
+        "/node/show_secure_channel_listener",
+        "show_secure_channel_listener",
+        |w, _ctx, r| {
+            Box::pin(async move { r.respond(w.show_secure_channel_listener(r.body()?).await) })
+        },
+    );
+}
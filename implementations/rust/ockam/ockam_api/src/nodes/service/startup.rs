@@ -0,0 +1,474 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+
+use futures::future::join_all;
+use tokio::sync::watch;
+
+use ockam::{Context, RelayService, RelayServiceOptions, Result};
+use ockam_core::api::{Error, Method, Response};
+use ockam_core::errcode::{Kind, Origin};
+
+use crate::nodes::models::services::ServiceAdvertisement;
+use crate::nodes::models::startup::{StartupReport, StartupUnitReport, StartupUnitStatus};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+
+/// Name of the unit starting the secure channel listener of the node
+pub const SECURE_CHANNEL_LISTENER_UNIT: &str = "secure-channel-listener";
+/// Name of the unit starting the relay service of the node
+pub const RELAY_SERVICE_UNIT: &str = "relay-service";
+/// Name of the unit starting the uppercase service of the node
+pub const UPPERCASE_SERVICE_UNIT: &str = "uppercase-service";
+/// Name of the unit starting the echo service of the node
+pub const ECHO_SERVICE_UNIT: &str = "echo-service";
+/// Name of the unit checking that the API transport listener is available
+pub const TRANSPORT_LISTENER_UNIT: &str = "transport-listener";
+/// Name of the unit starting the pre-fetch of the node credential
+pub const CREDENTIAL_PREFETCH_UNIT: &str = "credential-prefetch";
+
+type StartFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+
+/// A component started when the node starts, with the names of the units it depends on
+struct StartupUnit<'a> {
+    name: String,
+    dependencies: Vec<String>,
+    start: StartFuture<'a>,
+}
+
+/// This struct starts the components of a node while respecting their dependencies.
+///
+/// Each unit is started as soon as all its dependencies have been started, so independent
+/// units start concurrently. If a unit fails, or depends on an unknown unit or on a dependency cycle,
+/// all the units depending on it, directly or not, are skipped.
+///
+/// The outcome and the timings of each unit are returned as a [`StartupReport`].
+#[derive(Default)]
+pub struct StartupPlan<'a> {
+    units: Vec<StartupUnit<'a>>,
+}
+
+impl<'a> StartupPlan<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a unit to the plan. The unit is only started when the plan is run
+    pub fn add_unit(
+        &mut self,
+        name: impl Into<String>,
+        dependencies: &[&str],
+        start: impl Future<Output = Result<()>> + Send + 'a,
+    ) -> &mut Self {
+        self.units.push(StartupUnit {
+            name: name.into(),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            start: Box::pin(start),
+        });
+        self
+    }
+
+    /// Start all the units and return the outcome of each unit, in the order they were added
+    pub async fn run(self) -> StartupReport {
+        let plan_started_at = Instant::now();
+        let not_startable = self.not_startable_units();
+
+        let mut senders = BTreeMap::new();
+        let mut receivers = BTreeMap::new();
+        for unit in &self.units {
+            let (sender, receiver) = watch::channel(None);
+            senders.insert(unit.name.clone(), sender);
+            receivers.insert(unit.name.clone(), receiver);
+        }
+
+        let units = self.units.into_iter().map(|unit| {
+            let sender = senders.remove(&unit.name);
+            let dependencies: Vec<_> = unit
+                .dependencies
+                .iter()
+                .map(|d| (d.clone(), receivers.get(d).cloned()))
+                .collect();
+            let not_startable = not_startable.get(&unit.name).cloned();
+            async move {
+                let report =
+                    Self::run_unit(unit, dependencies, not_startable, plan_started_at).await;
+                if let Some(sender) = sender {
+                    let _ = sender.send(Some(report.status));
+                }
+                report
+            }
+        });
+        let units = join_all(units).await;
+
+        StartupReport {
+            units,
+            duration_ms: plan_started_at.elapsed().as_millis() as u64,
+        }
+    }
+
+    /// Wait for the dependencies of a unit, then start it unless one of them was not started
+    async fn run_unit(
+        unit: StartupUnit<'a>,
+        dependencies: Vec<(String, Option<watch::Receiver<Option<StartupUnitStatus>>>)>,
+        not_startable: Option<String>,
+        plan_started_at: Instant,
+    ) -> StartupUnitReport {
+        let mut skip_reason = not_startable;
+        if skip_reason.is_none() {
+            for (dependency, receiver) in dependencies {
+                let status = match receiver {
+                    Some(mut receiver) => receiver
+                        .wait_for(|s| s.is_some())
+                        .await
+                        .ok()
+                        .and_then(|s| *s),
+                    None => None,
+                };
+                match status {
+                    Some(StartupUnitStatus::Started) => continue,
+                    Some(status) => {
+                        skip_reason = Some(format!("the dependency {dependency} was {status}"));
+                    }
+                    None => skip_reason = Some(format!("the dependency {dependency} is unknown")),
+                }
+                break;
+            }
+        }
+        let waited_ms = plan_started_at.elapsed().as_millis() as u64;
+
+        let (status, reason, duration_ms) = match skip_reason {
+            Some(reason) => {
+                warn!(unit = %unit.name, "startup unit skipped: {reason}");
+                (StartupUnitStatus::Skipped, Some(reason), 0)
+            }
+            None => {
+                debug!(unit = %unit.name, "start unit");
+                let started_at = Instant::now();
+                let result = unit.start.await;
+                let duration_ms = started_at.elapsed().as_millis() as u64;
+                match result {
+                    Ok(()) => (StartupUnitStatus::Started, None, duration_ms),
+                    Err(e) => {
+                        error!(unit = %unit.name, "startup unit failed: {e}");
+                        (StartupUnitStatus::Failed, Some(e.to_string()), duration_ms)
+                    }
+                }
+            }
+        };
+
+        StartupUnitReport {
+            name: unit.name,
+            dependencies: unit.dependencies,
+            status,
+            reason,
+            waited_ms,
+            duration_ms,
+        }
+    }
+
+    /// Return the units which can never be started, because they are part of a dependency cycle
+    /// or have the same name as another unit, with the reason why
+    fn not_startable_units(&self) -> BTreeMap<String, String> {
+        let mut result = BTreeMap::new();
+        let mut names = BTreeSet::new();
+        for unit in &self.units {
+            if !names.insert(unit.name.as_str()) {
+                result.insert(
+                    unit.name.clone(),
+                    format!("another unit is named {}", unit.name),
+                );
+            }
+        }
+
+        // remove the units which can be ordered, the remaining ones are part of a cycle
+        // or depend on a cycle
+        let mut remaining: BTreeMap<&str, Vec<&str>> = self
+            .units
+            .iter()
+            .map(|u| {
+                let dependencies = u
+                    .dependencies
+                    .iter()
+                    .map(|d| d.as_str())
+                    .filter(|d| names.contains(d))
+                    .collect();
+                (u.name.as_str(), dependencies)
+            })
+            .collect();
+        loop {
+            let ordered: Vec<&str> = remaining
+                .iter()
+                .filter(|(_, dependencies)| dependencies.iter().all(|d| !remaining.contains_key(d)))
+                .map(|(name, _)| *name)
+                .collect();
+            if ordered.is_empty() {
+                break;
+            }
+            for name in ordered {
+                remaining.remove(name);
+            }
+        }
+        for name in remaining.keys() {
+            result.entry(name.to_string()).or_insert_with(|| {
+                "the unit is part of, or depends on, a dependency cycle".to_string()
+            });
+        }
+        result
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) fn get_startup_report(&self) -> Result<Response<StartupReport>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.startup_report()))
+    }
+}
+
+impl NodeManager {
+    /// Return the outcome and timings of the units started when the node started
+    pub fn startup_report(&self) -> StartupReport {
+        self.startup_report.clone()
+    }
+
+    /// Start the services of the node, and the credential pre-fetch if it is enabled
+    pub(super) async fn run_startup_plan(
+        &self,
+        ctx: &Context,
+        start_default_services: bool,
+        prefetch_credentials: bool,
+    ) -> StartupReport {
+        let api_flow_control_id = self.api_transport_flow_control_id.clone();
+        let mut plan = StartupPlan::new();
+
+        // the services below consume the messages received by the API transport listener
+        plan.add_unit(TRANSPORT_LISTENER_UNIT, &[], async {
+            let is_listening = self
+                .tcp_transport
+                .registry()
+                .get_all_listeners()
+                .iter()
+                .any(|l| l.flow_control_id() == &api_flow_control_id);
+            if is_listening {
+                Ok(())
+            } else {
+                Err(ockam_core::Error::new(
+                    Origin::Transport,
+                    Kind::NotFound,
+                    "the API transport listener is not running",
+                ))
+            }
+        });
+
+        if start_default_services {
+            plan.add_unit(UPPERCASE_SERVICE_UNIT, &[TRANSPORT_LISTENER_UNIT], async {
+                ctx.flow_controls()
+                    .add_consumer(DefaultAddress::UPPERCASE_SERVICE, &api_flow_control_id);
+                self.start_uppercase_service_impl(
+                    ctx,
+                    DefaultAddress::UPPERCASE_SERVICE.into(),
+                    ServiceAdvertisement::default(),
+                )
+                .await
+            });
+            plan.add_unit(RELAY_SERVICE_UNIT, &[TRANSPORT_LISTENER_UNIT], async {
+                let mut options = RelayServiceOptions::new()
+                    .service_as_consumer(&api_flow_control_id)
+                    .relay_as_consumer(&api_flow_control_id);
+                if let Some(alias_ownership) = self.relay_alias_ownership()? {
+                    options = options.with_alias_ownership(alias_ownership);
+                }
+                RelayService::create(ctx, DefaultAddress::RELAY_SERVICE, options).await
+            });
+            plan.add_unit(
+                SECURE_CHANNEL_LISTENER_UNIT,
+                &[TRANSPORT_LISTENER_UNIT],
+                async {
+                    self.create_secure_channel_listener(
+                        DefaultAddress::SECURE_CHANNEL_LISTENER.into(),
+                        None, // Not checking identifiers here in favor of credential check
+                        BTreeMap::new(),
+                        None,
+                        None,
+                        ctx,
+                    )
+                    .await
+                    .map(|_| ())
+                },
+            );
+        }
+
+        // Always start the echoer service as ockam_api::Medic assumes it will be
+        // started unconditionally on every node. It's used for liveliness checks.
+        plan.add_unit(ECHO_SERVICE_UNIT, &[TRANSPORT_LISTENER_UNIT], async {
+            ctx.flow_controls()
+                .add_consumer(DefaultAddress::ECHO_SERVICE, &api_flow_control_id);
+            self.start_echoer_service(
+                ctx,
+                DefaultAddress::ECHO_SERVICE.into(),
+                ServiceAdvertisement::default(),
+            )
+            .await
+        });
+
+        if prefetch_credentials {
+            plan.add_unit(CREDENTIAL_PREFETCH_UNIT, &[], async {
+                match self.credential_retriever_creator() {
+                    Some(creator) => {
+                        debug!("pre-fetch the node credential");
+                        self.credential_prefetch
+                            .start(creator, self.node_identifier.clone());
+                        Ok(())
+                    }
+                    None => Err(ockam_core::Error::new(
+                        Origin::Node,
+                        Kind::NotFound,
+                        "the node has no credential retriever",
+                    )),
+                }
+            });
+        }
+
+        let report = plan.run().await;
+        if !report.is_complete() {
+            warn!(
+                "some components of the node {} were not started\n{report}",
+                self.node_name
+            );
+        }
+        report
+    }
+}
+
+/// Register the handlers of the requests for the startup report
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Get,
+        "/node/startup",
+        "get_startup_report",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_startup_report()) }),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::*;
+
+    fn failure(message: &str) -> ockam_core::Error {
+        ockam_core::Error::new(Origin::Api, Kind::Internal, message.to_string())
+    }
+
+    #[tokio::test]
+    async fn dependents_wait_for_a_slow_dependency() {
+        let events = Arc::new(Mutex::new(vec![]));
+        let mut plan = StartupPlan::new();
+
+        let e = events.clone();
+        plan.add_unit("relay", &["listener"], async move {
+            e.lock().unwrap().push("relay");
+            Ok(())
+        });
+        let e = events.clone();
+        plan.add_unit("listener", &[], async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            e.lock().unwrap().push("listener");
+            Ok(())
+        });
+        let e = events.clone();
+        plan.add_unit("echo", &[], async move {
+            e.lock().unwrap().push("echo");
+            Ok(())
+        });
+
+        let report = plan.run().await;
+
+        assert!(report.is_complete());
+        // the independent unit is not delayed by the slow one
+        assert_eq!(*events.lock().unwrap(), vec!["echo", "listener", "relay"]);
+        let relay = report.unit("relay").unwrap();
+        assert!(relay.waited_ms >= 100);
+        assert!(report.unit("echo").unwrap().waited_ms < 100);
+        // the units are reported in the order they were added
+        let names: Vec<_> = report.units.iter().map(|u| u.name.as_str()).collect();
+        assert_eq!(names, vec!["relay", "listener", "echo"]);
+    }
+
+    #[tokio::test]
+    async fn a_failure_skips_all_the_dependents() {
+        let started = Arc::new(Mutex::new(vec![]));
+        let mut plan = StartupPlan::new();
+
+        plan.add_unit("listener", &[], async { Err(failure("port in use")) });
+        let s = started.clone();
+        plan.add_unit("relay", &["listener"], async move {
+            s.lock().unwrap().push("relay");
+            Ok(())
+        });
+        let s = started.clone();
+        plan.add_unit("inlet", &["relay"], async move {
+            s.lock().unwrap().push("inlet");
+            Ok(())
+        });
+        let s = started.clone();
+        plan.add_unit("echo", &[], async move {
+            s.lock().unwrap().push("echo");
+            Ok(())
+        });
+
+        let report = plan.run().await;
+
+        assert!(!report.is_complete());
+        assert_eq!(*started.lock().unwrap(), vec!["echo"]);
+
+        let listener = report.unit("listener").unwrap();
+        assert_eq!(listener.status, StartupUnitStatus::Failed);
+        assert!(listener.reason.as_ref().unwrap().contains("port in use"));
+
+        let relay = report.unit("relay").unwrap();
+        assert_eq!(relay.status, StartupUnitStatus::Skipped);
+        assert_eq!(
+            relay.reason.as_deref(),
+            Some("the dependency listener was failed")
+        );
+
+        let inlet = report.unit("inlet").unwrap();
+        assert_eq!(inlet.status, StartupUnitStatus::Skipped);
+        assert_eq!(
+            inlet.reason.as_deref(),
+            Some("the dependency relay was skipped")
+        );
+
+        assert_eq!(
+            report.unit("echo").unwrap().status,
+            StartupUnitStatus::Started
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_dependencies_and_cycles_are_skipped() {
+        let mut plan = StartupPlan::new();
+        plan.add_unit("a", &["b"], async { Ok(()) });
+        plan.add_unit("b", &["a"], async { Ok(()) });
+        plan.add_unit("c", &["a"], async { Ok(()) });
+        plan.add_unit("d", &["missing"], async { Ok(()) });
+
+        let report = tokio::time::timeout(Duration::from_secs(5), plan.run())
+            .await
+            .unwrap();
+
+        for name in ["a", "b", "c", "d"] {
+            assert_eq!(
+                report.unit(name).unwrap().status,
+                StartupUnitStatus::Skipped,
+                "{name}"
+            );
+        }
+        assert_eq!(
+            report.unit("d").unwrap().reason.as_deref(),
+            Some("the dependency missing is unknown")
+        );
+    }
+}
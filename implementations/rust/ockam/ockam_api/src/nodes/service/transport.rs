@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 
 use ockam::Result;
-use ockam_core::api::{Error, RequestHeader, Response};
+use ockam_core::api::{Error, Method, RequestHeader, Response};
 use ockam_node::Context;
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions};
 
@@ -9,6 +9,7 @@ use super::{NodeManager, NodeManagerWorker};
 use crate::nodes::models::transport::{
     CreateTcpConnection, CreateTcpListener, DeleteTransport, TransportList, TransportStatus,
 };
+use crate::nodes::service::routing_table::RoutingTable;
 
 impl NodeManager {
     fn get_tcp_connections(&self) -> TransportList {
@@ -49,15 +50,15 @@ impl NodeManager {
         ctx: &Context,
     ) -> Result<TransportStatus> {
         let options = TcpConnectionOptions::new();
+        let connection = self.tcp_transport.connect(address, options).await?;
 
         // Add all Hop workers as consumers for Demo purposes
         // Production nodes should not run any Hop workers
         for hop in self.registry.hop_services.keys().await {
             ctx.flow_controls()
-                .add_consumer(hop.clone(), &options.flow_control_id());
+                .add_consumer(hop.clone(), connection.flow_control_id());
         }
 
-        let connection = self.tcp_transport.connect(address, options).await?;
         Ok(connection.into())
     }
 
@@ -202,3 +203,65 @@ impl NodeManagerWorker {
             .map_err(|msg| Response::bad_request_no_request(&msg))
     }
 }
+
+/// Register the handlers of the requests for the TCP connections and listeners
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Get,
+        "/node/tcp/connection",
+        "get_tcp_connections",
+        |w, _ctx, r| Box::pin(async move { w.get_tcp_connections(r.header).await.to_vec() }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/tcp/connection/:address",
+        "get_tcp_connection",
+        |w, _ctx, r| {
+            Box::pin(async move {
+                r.respond(w.get_tcp_connection(r.param("address").to_string()).await)
+            })
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/tcp/connection",
+        "create_tcp_connection",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.create_tcp_connection(ctx, r.body()?).await) })
+        },
+    );
+    routes.add(
+        Method::Delete,
+        "/node/tcp/connection",
+        "delete_tcp_connection",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.delete_tcp_connection(r.body()?).await) }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/tcp/listener",
+        "get_tcp_listeners",
+        |w, _ctx, r| Box::pin(async move { w.get_tcp_listeners(r.header).await.to_vec() }),
+    );
+    routes.add(
+        Method::Get,
+        "/node/tcp/listener/:address",
+        "get_tcp_listener",
+        |w, _ctx, r| {
+            Box::pin(
+                async move { r.respond(w.get_tcp_listener(r.param("address").to_string()).await) },
+            )
+        },
+    );
+    routes.add(
+        Method::Post,
+        "/node/tcp/listener",
+        "create_tcp_listener",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.create_tcp_listener(r.body()?).await) }),
+    );
+    routes.add(
+        Method::Delete,
+        "/node/tcp/listener",
+        "delete_tcp_listener",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.delete_tcp_listener(r.body()?).await) }),
+    );
+}
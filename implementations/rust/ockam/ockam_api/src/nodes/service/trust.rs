@@ -0,0 +1,452 @@
+use ockam::identity::{
+    CachedCredentialRetrieverCreator, CredentialRetrieverCreator, Identifier,
+    MemoryCredentialRetrieverCreator, RemoteCredentialRetrieverCreator,
+    RemoteCredentialRetrieverInfo, SecureChannels,
+};
+use ockam::{Context, Result, TcpTransport};
+use ockam_core::api::{Error, Method, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::AsyncTryClone;
+
+use crate::multiaddr_to_transport_route;
+use crate::nodes::models::trust::{
+    CredentialRetrieverRequest, TrustOptionsStatus, UpdateTrustOptions,
+};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::nodes::service::routing_table::RoutingTable;
+use crate::nodes::service::NodeManagerCredentialRetrieverOptions;
+use crate::nodes::{NodeManager, NodeManagerWorker};
+
+/// Trust options currently used by a node manager.
+///
+/// They can be updated while the node is running. In that case the existing secure channels
+/// are kept as they are, and only the secure channels created afterwards use the new options.
+#[derive(Clone)]
+pub(crate) struct NodeManagerTrust {
+    pub(crate) credential_retriever_options: NodeManagerCredentialRetrieverOptions,
+    pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
+    pub(crate) authority: Option<Identifier>,
+    /// Incremented each time the trust options are updated
+    pub(crate) version: u64,
+}
+
+impl NodeManagerTrust {
+    pub(crate) fn new(
+        credential_retriever_options: NodeManagerCredentialRetrieverOptions,
+        credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
+        authority: Option<Identifier>,
+    ) -> Self {
+        Self {
+            credential_retriever_options,
+            credential_retriever_creator,
+            authority,
+            version: 0,
+        }
+    }
+
+    fn status(&self) -> TrustOptionsStatus {
+        let (credential_retriever, credential_issuer, credential_issuer_route) =
+            match &self.credential_retriever_options {
+                NodeManagerCredentialRetrieverOptions::None => ("none", None, None),
+                NodeManagerCredentialRetrieverOptions::CacheOnly(issuer) => {
+                    ("cache-only", Some(issuer.clone()), None)
+                }
+                NodeManagerCredentialRetrieverOptions::Remote(info) => (
+                    "remote",
+                    Some(info.issuer.clone()),
+                    Some(info.route.to_string()),
+                ),
+                NodeManagerCredentialRetrieverOptions::InMemory(_) => ("in-memory", None, None),
+            };
+        TrustOptionsStatus {
+            authority: self.authority.clone(),
+            credential_retriever: credential_retriever.to_string(),
+            credential_issuer,
+            credential_issuer_route,
+            version: self.version,
+        }
+    }
+}
+
+impl NodeManagerWorker {
+    pub(super) fn get_trust_options(
+        &self,
+    ) -> Result<Response<TrustOptionsStatus>, Response<Error>> {
+        Ok(Response::ok().body(self.node_manager.get_trust_options()))
+    }
+
+    pub(super) async fn update_trust_options(
+        &self,
+        ctx: &Context,
+        request: UpdateTrustOptions,
+    ) -> Result<Response<TrustOptionsStatus>, Response<Error>> {
+        match self.node_manager.update_trust_options(ctx, request).await {
+            Ok(status) => Ok(Response::ok().body(status)),
+            Err(e) => match e.code().kind {
+                Kind::Invalid => Err(Response::bad_request_no_request(&e.to_string())),
+                _ => Err(Response::internal_error_no_request(&e.to_string())),
+            },
+        }
+    }
+}
+
+impl NodeManager {
+    /// Return the trust options currently used to create secure channels
+    pub(crate) fn current_trust(&self) -> NodeManagerTrust {
+        self.trust.read().unwrap().clone()
+    }
+
+    /// Return a description of the trust options currently used by the node
+    pub fn get_trust_options(&self) -> TrustOptionsStatus {
+        self.trust.read().unwrap().status()
+    }
+
+    /// Update the credential retriever options and/or the authority of the node.
+    ///
+    /// Changing the authority must be confirmed since it changes the identities trusted by the node.
+    /// The existing secure channels are not modified.
+    pub async fn update_trust_options(
+        &self,
+        ctx: &Context,
+        request: UpdateTrustOptions,
+    ) -> Result<TrustOptionsStatus> {
+        let current = self.current_trust();
+        if let Some(authority) = &request.authority {
+            if current.authority.as_ref() != Some(authority) && !request.confirm_authority_change {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!(
+                        "Changing the authority to {authority} changes which identities are trusted by the node. The change must be confirmed"
+                    ),
+                ));
+            }
+        }
+
+        let credential_retriever = match request.credential_retriever {
+            Some(credential_retriever) => {
+                let options = Self::credential_retriever_options(credential_retriever)?;
+                let creator = Self::make_credential_retriever_creator(
+                    ctx,
+                    &self.tcp_transport,
+                    &self.secure_channels,
+                    &options,
+                )
+                .await?;
+                Some((options, creator))
+            }
+            None => None,
+        };
+
+        let status = {
+            let mut trust = self.trust.write().unwrap();
+            if let Some((options, creator)) = credential_retriever {
+                trust.credential_retriever_options = options;
+                trust.credential_retriever_creator = creator;
+            }
+            if let Some(authority) = request.authority {
+                trust.authority = Some(authority);
+            }
+            trust.version += 1;
+            trust.status()
+        };
+        info!(
+            "the trust options of the node {} have been updated: {status:?}",
+            self.node_name
+        );
+        Ok(status)
+    }
+
+    /// Create the component used by secure channels to retrieve the node credential
+    pub(super) async fn make_credential_retriever_creator(
+        ctx: &Context,
+        tcp_transport: &TcpTransport,
+        secure_channels: &Arc<SecureChannels>,
+        options: &NodeManagerCredentialRetrieverOptions,
+    ) -> Result<Option<Arc<dyn CredentialRetrieverCreator>>> {
+        Ok(match options {
+            NodeManagerCredentialRetrieverOptions::None => None,
+            NodeManagerCredentialRetrieverOptions::CacheOnly(issuer) => {
+                Some(Arc::new(CachedCredentialRetrieverCreator::new(
+                    issuer.clone(),
+                    secure_channels.identities().cached_credentials_repository(),
+                )))
+            }
+            NodeManagerCredentialRetrieverOptions::Remote(info) => {
+                Some(Arc::new(RemoteCredentialRetrieverCreator::new(
+                    ctx.async_try_clone().await?,
+                    Arc::new(tcp_transport.clone()),
+                    secure_channels.clone(),
+                    info.clone(),
+                )))
+            }
+            NodeManagerCredentialRetrieverOptions::InMemory(credential) => Some(Arc::new(
+                MemoryCredentialRetrieverCreator::new(credential.clone()),
+            )),
+        })
+    }
+
+    fn credential_retriever_options(
+        request: CredentialRetrieverRequest,
+    ) -> Result<NodeManagerCredentialRetrieverOptions> {
+        Ok(match request {
+            CredentialRetrieverRequest::None => NodeManagerCredentialRetrieverOptions::None,
+            CredentialRetrieverRequest::CacheOnly(issuer) => {
+                NodeManagerCredentialRetrieverOptions::CacheOnly(issuer)
+            }
+            CredentialRetrieverRequest::Remote(issuer, authority_route) => {
+                let route = multiaddr_to_transport_route(&authority_route).ok_or_else(|| {
+                    ockam_core::Error::new(
+                        Origin::Api,
+                        Kind::Invalid,
+                        format!("Invalid authority route: {authority_route}"),
+                    )
+                })?;
+                NodeManagerCredentialRetrieverOptions::Remote(RemoteCredentialRetrieverInfo::new(
+                    issuer,
+                    route,
+                    DefaultAddress::CREDENTIAL_ISSUER.into(),
+                ))
+            }
+            CredentialRetrieverRequest::InMemory(credential) => {
+                NodeManagerCredentialRetrieverOptions::InMemory(credential)
+            }
+        })
+    }
+}
+
+/// Register the handlers of the requests for the trust options
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(
+        Method::Get,
+        "/node/trust",
+        "get_trust_options",
+        |w, _ctx, r| Box::pin(async move { r.respond(w.get_trust_options()) }),
+    );
+    routes.add(
+        Method::Post,
+        "/node/trust",
+        "update_trust_options",
+        |w, ctx, r| {
+            Box::pin(async move { r.respond(w.update_trust_options(ctx, r.body()?).await) })
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use core::time::Duration;
+
+    use ockam::identity::{
+        CredentialRetrievalOutcome, CredentialRetrieverCreator, MemoryCredentialRetrieverCreator,
+        NoOpCredentialRetrieverCreator, RecordingCredentialRetrieverCreator,
+    };
+    use ockam_core::api::{Reply, Request, Status};
+    use ockam_core::compat::sync::Arc;
+    use ockam_core::errcode::Kind;
+    use ockam_core::route;
+    use ockam_multiaddr::MultiAddr;
+    use ockam_node::api::Client;
+    use ockam_node::Context;
+
+    use crate::nodes::models::secure_channel::{
+        ShowSecureChannelRequest, ShowSecureChannelResponse,
+    };
+    use crate::nodes::models::trust::{
+        CredentialRetrieverRequest, TrustOptionsStatus, UpdateTrustOptions,
+    };
+    use crate::nodes::service::NodeManagerCredentialRetrieverOptions;
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::test_utils::start_manager_for_tests;
+
+    #[ockam_macros::test]
+    async fn trust_options_can_be_updated(context: &mut Context) -> ockam::Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = handle.node_manager.clone();
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+
+        let status: TrustOptionsStatus = client
+            .ask(context, Request::get("/node/trust"))
+            .await?
+            .success()?;
+        assert_eq!(status.credential_retriever, "in-memory");
+        assert_eq!(status.version, 0);
+        let authority = status.authority.unwrap();
+
+        // A secure channel is established with the node's own listener,
+        // presenting the in-memory credential
+        let listener = handle
+            .cli_state
+            .get_node(&node_manager.node_name())
+            .await?
+            .tcp_listener_multi_address()?;
+        let secure_channel_address = listener
+            .concat(&MultiAddr::from_str("/service/api").unwrap())
+            .unwrap();
+        let previous_channel = node_manager
+            .create_secure_channel(
+                context,
+                secure_channel_address.clone(),
+                None,
+                None,
+                Some(Duration::from_secs(5)),
+            )
+            .await?;
+
+        // The credential is now only read from the cache, which is empty
+        let status: TrustOptionsStatus = client
+            .ask(
+                context,
+                Request::post("/node/trust").body(UpdateTrustOptions::new(
+                    Some(CredentialRetrieverRequest::CacheOnly(authority.clone())),
+                    None,
+                    false,
+                )),
+            )
+            .await?
+            .success()?;
+        assert_eq!(status.credential_retriever, "cache-only");
+        assert_eq!(status.credential_issuer, Some(authority.clone()));
+        assert_eq!(status.authority, Some(authority));
+        assert_eq!(status.version, 1);
+
+        // so the next secure channel can not present any credential
+        let result = node_manager
+            .create_secure_channel(
+                context,
+                secure_channel_address,
+                None,
+                None,
+                Some(Duration::from_secs(5)),
+            )
+            .await;
+        assert!(result.is_err());
+
+        // the existing channel is kept, but flagged
+        let response: ShowSecureChannelResponse = client
+            .ask(
+                context,
+                Request::get("/node/show_secure_channel").body(ShowSecureChannelRequest::new(
+                    previous_channel.encryptor_address(),
+                )),
+            )
+            .await?
+            .success()?;
+        assert_eq!(response.previous_trust_options, Some(true));
+
+        // changing the authority must be confirmed
+        let other_authority = handle
+            .cli_state
+            .create_identity_with_name("other-authority")
+            .await?
+            .identifier();
+        let reply: Reply<TrustOptionsStatus> = client
+            .ask(
+                context,
+                Request::post("/node/trust").body(UpdateTrustOptions::new(
+                    None,
+                    Some(other_authority.clone()),
+                    false,
+                )),
+            )
+            .await?;
+        assert!(matches!(reply, Reply::Failed(_, Some(Status::BadRequest))));
+
+        let status: TrustOptionsStatus = client
+            .ask(
+                context,
+                Request::post("/node/trust").body(UpdateTrustOptions::new(
+                    None,
+                    Some(other_authority.clone()),
+                    true,
+                )),
+            )
+            .await?
+            .success()?;
+        assert_eq!(status.authority, Some(other_authority));
+        assert_eq!(status.version, 2);
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn a_failing_credential_retriever_can_be_replaced(
+        context: &mut Context,
+    ) -> ockam::Result<()> {
+        let handle = start_manager_for_tests(context, None, None).await?;
+        let node_manager = handle.node_manager.clone();
+        let credential = match node_manager.current_trust().credential_retriever_options {
+            NodeManagerCredentialRetrieverOptions::InMemory(credential) => credential,
+            _ => panic!("the test node should use an in-memory credential"),
+        };
+        let listener = handle
+            .cli_state
+            .get_node(&node_manager.node_name())
+            .await?
+            .tcp_listener_multi_address()?;
+        let secure_channel_address = listener
+            .concat(&MultiAddr::from_str("/service/api").unwrap())
+            .unwrap();
+
+        // the first retriever never finds a credential
+        let failing = RecordingCredentialRetrieverCreator::new(Arc::new(
+            NoOpCredentialRetrieverCreator::with_error_kind(Kind::NotFound),
+        ));
+        let failing_log = failing.log();
+        set_credential_retriever_creator(&node_manager, Arc::new(failing));
+
+        let result = node_manager
+            .create_secure_channel(
+                context,
+                secure_channel_address.clone(),
+                None,
+                None,
+                Some(Duration::from_secs(5)),
+            )
+            .await;
+        assert!(result.is_err());
+        let failed_retrievals = failing_log.records().len();
+        assert_eq!(
+            failing_log.outcomes(),
+            vec![CredentialRetrievalOutcome::Failed(Kind::NotFound); failed_retrievals]
+        );
+        assert!(failed_retrievals > 0);
+        assert_eq!(failing_log.records()[0].subject, node_manager.identifier());
+
+        // a secure channel can be created once the retriever is replaced by a working one
+        let working = RecordingCredentialRetrieverCreator::new(Arc::new(
+            MemoryCredentialRetrieverCreator::new(credential),
+        ));
+        let working_log = working.log();
+        set_credential_retriever_creator(&node_manager, Arc::new(working));
+
+        node_manager
+            .create_secure_channel(
+                context,
+                secure_channel_address,
+                None,
+                None,
+                Some(Duration::from_secs(5)),
+            )
+            .await?;
+        assert_eq!(
+            working_log.outcomes(),
+            vec![CredentialRetrievalOutcome::Retrieved]
+        );
+        // the failing retriever is not used anymore
+        assert_eq!(failing_log.records().len(), failed_retrievals);
+
+        context.stop().await
+    }
+
+    fn set_credential_retriever_creator(
+        node_manager: &crate::nodes::InMemoryNode,
+        creator: Arc<dyn CredentialRetrieverCreator>,
+    ) {
+        let mut trust = node_manager.trust.write().unwrap();
+        trust.credential_retriever_creator = Some(creator);
+        trust.version += 1;
+    }
+}
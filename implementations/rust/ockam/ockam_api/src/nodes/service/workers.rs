@@ -1,25 +1,226 @@
-use crate::nodes::models::workers::{WorkerList, WorkerStatus};
+use crate::nodes::models::workers::{
+    InjectMessage, InjectedMessageReply, WorkerList, WorkerStatus,
+};
+use crate::nodes::service::routing_table::RoutingTable;
 use crate::nodes::NodeManagerWorker;
-use ockam_core::api::{Error, Response};
-use ockam_core::Result;
-use ockam_node::Context;
+use ockam::identity::Identifier;
+use ockam_core::api::{Error, Method, RequestHeader, Response};
+use ockam_core::env::get_env_with_default;
+use ockam_core::{route, Address, AllowOnwardAddress, DenyAll, NeutralMessage, Result};
+use ockam_node::{Context, MessageSendReceiveOptions};
+
+/// Set this variable to `true` to let a node accept the raw messages sent to its workers by
+/// the `ockam worker send` developer command. They bypass the typed API of the node
+pub const OCKAM_ALLOW_DEV_COMMANDS: &str = "OCKAM_ALLOW_DEV_COMMANDS";
+
+/// Return true if the developer commands are allowed with the [`OCKAM_ALLOW_DEV_COMMANDS`] variable
+pub fn dev_commands_allowed() -> bool {
+    get_env_with_default(OCKAM_ALLOW_DEV_COMMANDS, false).unwrap_or(false)
+}
 
 impl NodeManagerWorker {
-    /// Return the current list of workers
+    /// Return the current list of registered addresses, with the type of the worker owning them
     pub async fn list_workers(
         &self,
         ctx: &Context,
     ) -> Result<Response<WorkerList>, Response<Error>> {
-        let workers = match ctx.list_workers().await {
+        let workers = match ctx.list_workers_info().await {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
             Ok(workers) => Ok(workers),
         }?;
 
         let list = workers
-            .into_iter()
-            .map(|addr| WorkerStatus::new(addr.address()))
+            .iter()
+            .flat_map(|worker| {
+                worker
+                    .addresses()
+                    .iter()
+                    .map(|addr| WorkerStatus::new(addr.address()).with_owner(worker.owner()))
+            })
             .collect();
 
         Ok(Response::ok().body(WorkerList::new(list)))
     }
+
+    /// Send a raw payload to a worker of this node, and wait for its reply if a timeout is set.
+    ///
+    /// The request is only accepted if the developer commands are allowed, and if it comes from
+    /// the local CLI: either through the local API listener, or through a secure channel
+    /// established with the identity of this node
+    pub(super) async fn inject_message(
+        &self,
+        ctx: &Context,
+        req: &RequestHeader,
+        caller: Option<&Identifier>,
+        inject_message: InjectMessage,
+    ) -> Result<Response<InjectedMessageReply>, Response<Error>> {
+        if !dev_commands_allowed() {
+            let message = format!(
+                "the developer commands are not allowed, start the node with {OCKAM_ALLOW_DEV_COMMANDS}=true"
+            );
+            return Err(Response::forbidden(req, &message));
+        }
+        if let Some(caller) = caller {
+            if caller != &self.node_manager.identifier() {
+                warn!(%caller, "a message can only be sent to a worker by the identity of the node");
+                return Err(Response::forbidden(
+                    req,
+                    "a message can only be sent to a worker by the identity of the node",
+                ));
+            }
+        }
+
+        let address = Address::from_string(&inject_message.address);
+        let workers = ctx
+            .list_workers()
+            .await
+            .map_err(|e| Response::internal_error_no_request(&e.to_string()))?;
+        if !workers.contains(&address) {
+            return Err(Response::not_found(
+                req,
+                &format!("there is no worker at the address {address}"),
+            ));
+        }
+
+        let timeout = inject_message.reply_timeout();
+        let payload = NeutralMessage::from(inject_message.payload);
+        let reply = match timeout {
+            Some(timeout) => {
+                let reply = ctx
+                    .send_and_receive_extended::<NeutralMessage>(
+                        route![address.clone()],
+                        payload,
+                        MessageSendReceiveOptions::new().with_timeout(timeout),
+                    )
+                    .await
+                    .map_err(|e| {
+                        Response::internal_error_no_request(&format!(
+                            "no reply was received from {address}: {e}"
+                        ))
+                    })?;
+                Some(
+                    reply
+                        .into_body()
+                        .map_err(|e| Response::internal_error_no_request(&e.to_string()))?
+                        .into_vec(),
+                )
+            }
+            None => {
+                // the message is sent from a temporary address, so that a reply is not
+                // received as a request by the node manager
+                let sender = ctx
+                    .new_detached(
+                        Address::random_tagged("NodeManager.inject_message"),
+                        DenyAll,
+                        AllowOnwardAddress(address.clone()),
+                    )
+                    .await
+                    .map_err(|e| Response::internal_error_no_request(&e.to_string()))?;
+                sender
+                    .send(route![address], payload)
+                    .await
+                    .map_err(|e| Response::internal_error_no_request(&e.to_string()))?;
+                None
+            }
+        };
+        Ok(Response::ok().body(InjectedMessageReply { reply }))
+    }
+}
+
+/// Register the handlers of the requests for the workers
+pub(super) fn add_routes(routes: &mut RoutingTable) {
+    routes.add(Method::Get, "/node/workers", "list_workers", |w, ctx, r| {
+        Box::pin(async move { r.respond(w.list_workers(ctx).await) })
+    });
+    routes.add(
+        Method::Post,
+        "/node/workers/message",
+        "inject_message",
+        |w, ctx, r| {
+            Box::pin(async move {
+                r.respond(w.inject_message(ctx, r.header, r.caller, r.body()?).await)
+            })
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OCKAM_ALLOW_DEV_COMMANDS;
+    use crate::echoer::Echoer;
+    use crate::nodes::models::workers::{InjectMessage, InjectedMessageReply, WorkerList};
+    use crate::nodes::NODEMANAGER_ADDR;
+    use crate::test_utils::start_manager_for_tests;
+    use core::time::Duration;
+    use ockam_core::api::{Reply, Request, Status};
+    use ockam_core::route;
+    use ockam_node::api::Client;
+    use ockam_node::Context;
+
+    #[ockam_macros::test]
+    async fn list_workers_with_their_owner_type(context: &mut Context) -> ockam::Result<()> {
+        let _handle = start_manager_for_tests(context, None, None).await?;
+
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+        let workers: WorkerList = client
+            .ask(context, Request::get("/node/workers"))
+            .await?
+            .success()?;
+
+        let node_manager = workers
+            .list
+            .iter()
+            .find(|w| w.addr == NODEMANAGER_ADDR)
+            .unwrap();
+        assert_eq!(
+            node_manager.owner.as_deref(),
+            Some(core::any::type_name::<crate::nodes::NodeManagerWorker>())
+        );
+
+        context.stop().await
+    }
+
+    #[ockam_macros::test]
+    async fn inject_a_message_into_a_worker(context: &mut Context) -> ockam::Result<()> {
+        std::env::set_var(OCKAM_ALLOW_DEV_COMMANDS, "true");
+        let _handle = start_manager_for_tests(context, None, None).await?;
+        context.start_worker("test_echo", Echoer).await?;
+        let client = Client::new(&route![NODEMANAGER_ADDR], None);
+
+        // the reply of the echo worker is returned
+        let reply: InjectedMessageReply = client
+            .ask(
+                context,
+                Request::post("/node/workers/message").body(
+                    InjectMessage::new("test_echo", vec![0, 1, 2, 255])
+                        .with_reply_timeout(Duration::from_secs(5)),
+                ),
+            )
+            .await?
+            .success()?;
+        assert_eq!(reply.reply, Some(vec![0, 1, 2, 255]));
+
+        // without a timeout the reply is not expected
+        let reply: InjectedMessageReply = client
+            .ask(
+                context,
+                Request::post("/node/workers/message")
+                    .body(InjectMessage::new("test_echo", b"hello".to_vec())),
+            )
+            .await?
+            .success()?;
+        assert_eq!(reply.reply, None);
+
+        // a message can't be sent to an address without a worker
+        let reply: Reply<InjectedMessageReply> = client
+            .ask(
+                context,
+                Request::post("/node/workers/message")
+                    .body(InjectMessage::new("nonexistent", b"hello".to_vec())),
+            )
+            .await?;
+        assert!(matches!(reply, Reply::Failed(_, Some(Status::NotFound))));
+
+        context.stop().await
+    }
 }
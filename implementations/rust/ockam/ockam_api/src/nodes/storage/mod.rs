@@ -0,0 +1,3 @@
+mod relay_aliases_repository_sql;
+
+pub use relay_aliases_repository_sql::*;
@@ -0,0 +1,157 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::{RelayAliasOwner, RelayAliasesRepository};
+use ockam_core::async_trait;
+use ockam_core::compat::str::FromStr;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType, ToVoid};
+
+/// Implementation of [`RelayAliasesRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RelayAliasesSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl RelayAliasesSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for relay aliases");
+        Self { database }
+    }
+
+    /// Create a new in-memory database, passing a node name to isolate data between nodes
+    pub async fn create_with_node_name(node_name: &str) -> Result<Self> {
+        let mut db = SqlxDatabase::in_memory("relay aliases").await?;
+        db.set_node_name(node_name);
+        Ok(Self::new(db))
+    }
+}
+
+#[async_trait]
+impl RelayAliasesRepository for RelayAliasesSqlxDatabase {
+    async fn get_alias_owner(&self, alias: &str) -> Result<Option<RelayAliasOwner>> {
+        let query = query_as(
+            "SELECT alias, identifier, registered_at FROM relay_alias WHERE alias=$1 AND node_name=$2",
+        )
+        .bind(alias.to_sql())
+        .bind(self.database.node_name()?.to_sql());
+        let row: Option<RelayAliasRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.relay_alias_owner()).transpose()
+    }
+
+    async fn store_alias_owner(&self, owner: &RelayAliasOwner) -> Result<()> {
+        let query = query(
+            "INSERT INTO relay_alias (alias, identifier, registered_at, node_name) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (alias, node_name)
+             DO UPDATE SET identifier = excluded.identifier, registered_at = excluded.registered_at",
+        )
+        .bind(owner.alias.to_sql())
+        .bind(owner.identifier.to_sql())
+        .bind(owner.registered_at.to_sql())
+        .bind(self.database.node_name()?.to_sql());
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_alias_owner(&self, alias: &str) -> Result<bool> {
+        let query = query("DELETE FROM relay_alias WHERE alias=$1 AND node_name=$2")
+            .bind(alias.to_sql())
+            .bind(self.database.node_name()?.to_sql());
+        let result = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_alias_owners(&self) -> Result<Vec<RelayAliasOwner>> {
+        let query = query_as(
+            "SELECT alias, identifier, registered_at FROM relay_alias WHERE node_name=$1 ORDER BY alias",
+        )
+        .bind(self.database.node_name()?.to_sql());
+        let rows: Vec<RelayAliasRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.relay_alias_owner()).collect()
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RelayAliasRow {
+    alias: String,
+    identifier: String,
+    registered_at: i64,
+}
+
+impl RelayAliasRow {
+    fn relay_alias_owner(&self) -> Result<RelayAliasOwner> {
+        Ok(RelayAliasOwner {
+            alias: self.alias.clone(),
+            identifier: Identifier::from_str(&self.identifier)?,
+            registered_at: TimestampInSeconds(self.registered_at as u64),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::sync::Arc;
+
+    #[tokio::test]
+    async fn test_relay_aliases_repository() -> Result<()> {
+        let repository = create_repository().await?;
+        assert_eq!(repository.get_alias_owner("alias1").await?, None);
+
+        let owner1 = RelayAliasOwner {
+            alias: "alias1".to_string(),
+            identifier: Identifier::from_str(
+                "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+            )?,
+            registered_at: TimestampInSeconds(100),
+        };
+        let owner2 = RelayAliasOwner {
+            alias: "alias2".to_string(),
+            identifier: Identifier::from_str(
+                "Ifedcba9876543210fedcba9876543210fedcba9876543210fedcba9876543210",
+            )?,
+            registered_at: TimestampInSeconds(200),
+        };
+        repository.store_alias_owner(&owner2).await?;
+        repository.store_alias_owner(&owner1).await?;
+        assert_eq!(
+            repository.get_alias_owner("alias1").await?,
+            Some(owner1.clone())
+        );
+        assert_eq!(
+            repository.get_alias_owners().await?,
+            vec![owner1.clone(), owner2.clone()]
+        );
+
+        // the owner of an alias can be replaced
+        let owner1 = RelayAliasOwner {
+            identifier: owner2.identifier.clone(),
+            registered_at: TimestampInSeconds(300),
+            ..owner1
+        };
+        repository.store_alias_owner(&owner1).await?;
+        assert_eq!(
+            repository.get_alias_owner("alias1").await?,
+            Some(owner1.clone())
+        );
+
+        // and deleted
+        assert!(repository.delete_alias_owner("alias1").await?);
+        assert!(!repository.delete_alias_owner("alias1").await?);
+        assert_eq!(repository.get_alias_owners().await?, vec![owner2]);
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository() -> Result<Arc<dyn RelayAliasesRepository>> {
+        Ok(Arc::new(
+            RelayAliasesSqlxDatabase::create_with_node_name("node").await?,
+        ))
+    }
+}
@@ -0,0 +1,351 @@
+//! Graph of the connections between nodes, built from the [`NodeTopology`] of each node.
+//!
+//! The vertices of the graph are the identities of the nodes, their inlets and outlets, and
+//! the addresses which could not be attributed to a known identity. The edges are the secure
+//! channels, portals, relays and TCP connections between them.
+//!
+//! When the topologies of several nodes are merged, the secure channels seen from both sides
+//! are drawn once, and the inlets are connected to the outlets of the other nodes by matching
+//! the identifier at the other end of their route.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use ockam::identity::Identifier;
+use ockam_multiaddr::proto::Service;
+use ockam_multiaddr::MultiAddr;
+
+use crate::nodes::models::topology::NodeTopology;
+use crate::nodes::models::transport::TransportMode;
+
+/// Version of the JSON representation of a [`TopologyGraph`].
+/// It is incremented when a field is removed or changes meaning
+pub const TOPOLOGY_GRAPH_VERSION: u32 = 1;
+
+/// Graph of the connections between a set of nodes.
+/// The vertices and edges are sorted, so that the same topology is always rendered the same way
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TopologyGraph {
+    pub version: u32,
+    pub vertices: Vec<TopologyVertex>,
+    pub edges: Vec<TopologyEdge>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TopologyVertex {
+    /// Unique identifier of the vertex, for example `identity:I1234...` or `inlet:n1/db`
+    pub id: String,
+    pub kind: TopologyVertexKind,
+    pub label: String,
+    /// Name of the node owning this vertex, if it is one of the described nodes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<Identifier>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyVertexKind {
+    Identity,
+    Inlet,
+    Outlet,
+    Address,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TopologyEdge {
+    pub from: String,
+    pub to: String,
+    pub kind: TopologyEdgeKind,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopologyEdgeKind {
+    /// A node identity hosts an inlet or an outlet
+    Hosts,
+    SecureChannel,
+    Portal,
+    Relay,
+    Tcp,
+}
+
+impl TopologyGraph {
+    /// Build the graph of one or several nodes
+    pub fn new(topologies: &[NodeTopology]) -> Self {
+        let mut builder = GraphBuilder::default();
+        for topology in topologies {
+            builder.add_identity(&topology.identifier, Some(&topology.node_name));
+        }
+        for topology in topologies {
+            builder.add_node(topology, topologies);
+        }
+        builder.build()
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Render the graph in the Graphviz DOT language
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::new();
+        let _ = writeln!(dot, "digraph ockam {{");
+        let _ = writeln!(dot, "  rankdir=LR;");
+        for vertex in &self.vertices {
+            let shape = match vertex.kind {
+                TopologyVertexKind::Identity => "box",
+                TopologyVertexKind::Inlet | TopologyVertexKind::Outlet => "ellipse",
+                TopologyVertexKind::Address => "plaintext",
+            };
+            let _ = writeln!(
+                dot,
+                "  {} [label={}, shape={shape}];",
+                quote(&vertex.id),
+                quote(&vertex.label)
+            );
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                TopologyEdgeKind::Hosts => "dashed",
+                TopologyEdgeKind::SecureChannel => "bold",
+                TopologyEdgeKind::Portal => "solid",
+                TopologyEdgeKind::Relay => "dotted",
+                TopologyEdgeKind::Tcp => "solid, color=gray",
+            };
+            let _ = writeln!(
+                dot,
+                "  {} -> {} [label={}, style={style}];",
+                quote(&edge.from),
+                quote(&edge.to),
+                quote(&edge.label)
+            );
+        }
+        let _ = writeln!(dot, "}}");
+        dot
+    }
+}
+
+/// Quote a DOT identifier, keeping the line breaks of labels
+fn quote(s: &str) -> String {
+    format!(
+        "\"{}\"",
+        s.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    )
+}
+
+#[derive(Default)]
+struct GraphBuilder {
+    vertices: BTreeMap<String, TopologyVertex>,
+    edges: BTreeSet<TopologyEdge>,
+    /// Secure channels indexed by their initiator and the decryptor address of their responder,
+    /// which identifies a channel on both sides
+    secure_channels: BTreeMap<(Identifier, String), TopologyEdge>,
+}
+
+impl GraphBuilder {
+    fn build(mut self) -> TopologyGraph {
+        self.edges.extend(self.secure_channels.into_values());
+        TopologyGraph {
+            version: TOPOLOGY_GRAPH_VERSION,
+            vertices: self.vertices.into_values().collect(),
+            edges: self.edges.into_iter().collect(),
+        }
+    }
+
+    fn add_identity(&mut self, identifier: &Identifier, node_name: Option<&str>) -> String {
+        let id = format!("identity:{identifier}");
+        let vertex = self
+            .vertices
+            .entry(id.clone())
+            .or_insert_with(|| TopologyVertex {
+                id: id.clone(),
+                kind: TopologyVertexKind::Identity,
+                label: identifier.to_string(),
+                node: None,
+                identifier: Some(identifier.clone()),
+            });
+        if let (None, Some(node_name)) = (&vertex.node, node_name) {
+            vertex.node = Some(node_name.to_string());
+            vertex.label = format!("{node_name}\n{identifier}");
+        }
+        id
+    }
+
+    fn add_vertex(
+        &mut self,
+        id: String,
+        kind: TopologyVertexKind,
+        label: String,
+        node: Option<&str>,
+    ) -> String {
+        self.vertices
+            .entry(id.clone())
+            .or_insert_with(|| TopologyVertex {
+                id: id.clone(),
+                kind,
+                label,
+                node: node.map(|n| n.to_string()),
+                identifier: None,
+            });
+        id
+    }
+
+    fn add_address(&mut self, address: &str) -> String {
+        self.add_vertex(
+            format!("address:{address}"),
+            TopologyVertexKind::Address,
+            address.to_string(),
+            None,
+        )
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, kind: TopologyEdgeKind, label: impl Into<String>) {
+        self.edges.insert(TopologyEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            kind,
+            label: label.into(),
+        });
+    }
+
+    fn add_node(&mut self, topology: &NodeTopology, all: &[NodeTopology]) {
+        let node_name = topology.node_name.as_str();
+        let node = self.add_identity(&topology.identifier, Some(node_name));
+
+        for channel in &topology.secure_channels {
+            let (initiator, responder, responder_decryptor) = if channel.is_initiator {
+                (
+                    &channel.my_identifier,
+                    &channel.their_identifier,
+                    &channel.their_decryptor_address,
+                )
+            } else {
+                (
+                    &channel.their_identifier,
+                    &channel.my_identifier,
+                    &channel.decryptor_address,
+                )
+            };
+            let from = self.add_identity(initiator, None);
+            let to = self.add_identity(responder, None);
+            let label = match &channel.route {
+                Some(route) => format!("secure channel\n{route}"),
+                None => "secure channel".to_string(),
+            };
+            let edge = TopologyEdge {
+                from,
+                to,
+                kind: TopologyEdgeKind::SecureChannel,
+                label,
+            };
+            // keep the label with a route when the channel is seen from both sides
+            let key = (initiator.clone(), responder_decryptor.clone());
+            match self.secure_channels.get(&key) {
+                Some(existing) if existing.label.len() >= edge.label.len() => {}
+                _ => {
+                    self.secure_channels.insert(key, edge);
+                }
+            }
+        }
+
+        for outlet in &topology.outlets {
+            let worker = outlet.worker_addr.address();
+            let outlet_vertex = self.add_vertex(
+                outlet_vertex_id(node_name, worker),
+                TopologyVertexKind::Outlet,
+                format!("outlet {worker}\n{}", outlet.socket_addr),
+                Some(node_name),
+            );
+            self.add_edge(&node, &outlet_vertex, TopologyEdgeKind::Hosts, "outlet");
+        }
+
+        for inlet in &topology.inlets {
+            let inlet_vertex = self.add_vertex(
+                format!("inlet:{node_name}/{}", inlet.alias),
+                TopologyVertexKind::Inlet,
+                format!("inlet {}\n{}", inlet.alias, inlet.bind_address),
+                Some(node_name),
+            );
+            self.add_edge(&node, &inlet_vertex, TopologyEdgeKind::Hosts, "inlet");
+
+            let outlet_service = last_service(&inlet.outlet_address);
+            let target = match &inlet.outlet_identifier {
+                Some(identifier) => {
+                    let outlet = all
+                        .iter()
+                        .filter(|t| &t.identifier == identifier)
+                        .find_map(|t| {
+                            t.outlets
+                                .iter()
+                                .find(|o| {
+                                    Some(o.worker_addr.address()) == outlet_service.as_deref()
+                                })
+                                .map(|o| outlet_vertex_id(&t.node_name, o.worker_addr.address()))
+                        });
+                    match outlet {
+                        Some(outlet) => outlet,
+                        None => self.add_identity(identifier, None),
+                    }
+                }
+                None => self.add_address(&inlet.outlet_address),
+            };
+            self.add_edge(&inlet_vertex, &target, TopologyEdgeKind::Portal, "portal");
+        }
+
+        for relay in &topology.relays {
+            let target = match &relay.destination_identifier {
+                Some(identifier) => self.add_identity(identifier, None),
+                None => self.add_address(&relay.destination),
+            };
+            self.add_edge(
+                &node,
+                &target,
+                TopologyEdgeKind::Relay,
+                format!("relay {}", relay.alias),
+            );
+        }
+
+        // only the outgoing connections are drawn, the incoming ones are their other side
+        for transport in &topology.transports {
+            if transport.tm != TransportMode::Outgoing {
+                continue;
+            }
+            let listener = all.iter().find(|t| {
+                t.transports.iter().any(|l| {
+                    l.tm == TransportMode::Listen && l.socket_addr == transport.socket_addr
+                })
+            });
+            let target = match listener {
+                Some(listener) => self.add_identity(&listener.identifier, None),
+                None => self.add_address(&transport.socket_addr),
+            };
+            self.add_edge(
+                &node,
+                &target,
+                TopologyEdgeKind::Tcp,
+                format!("tcp {}", transport.socket_addr),
+            );
+        }
+    }
+}
+
+fn outlet_vertex_id(node_name: &str, worker: &str) -> String {
+    format!("outlet:{node_name}/{worker}")
+}
+
+/// Return the last service of a multiaddr, which is the address of the outlet for an inlet
+fn last_service(address: &str) -> Option<String> {
+    let address = MultiAddr::from_str(address).ok()?;
+    address
+        .iter()
+        .filter_map(|p| p.cast::<Service>().map(|s| s.to_string()))
+        .last()
+}
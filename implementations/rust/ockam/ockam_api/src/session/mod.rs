@@ -420,6 +420,8 @@ mod tests {
                     bind_addr: "127.0.0.1:10000".to_string(),
                     outlet_addr: MultiAddr::default(),
                     session: session.clone(),
+                    bandwidth: ockam_transport_tcp::TcpPortalBandwidthLimiter::unlimited(),
+                    pre_check: None,
                 },
             )
             .await;
@@ -217,6 +217,21 @@ impl Session {
         Ok(())
     }
 
+    /// Re-create the session right away, for example to move it back to a preferred route
+    pub(crate) async fn replace(&self) -> Result<(), Error> {
+        self.degraded();
+        match self.replacer().recreate().await {
+            Ok(outcome) => {
+                self.up(outcome);
+                Ok(())
+            }
+            Err(err) => {
+                self.down();
+                Err(err)
+            }
+        }
+    }
+
     pub(super) fn replacer(&self) -> Arc<InnerSessionReplacer> {
         let inner = self.inner.lock().unwrap();
         inner.replacer.clone()
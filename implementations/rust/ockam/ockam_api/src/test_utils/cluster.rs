@@ -0,0 +1,380 @@
+use std::future::Future;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use tokio::time::timeout;
+
+use ockam::identity::utils::AttributesBuilder;
+use ockam::identity::Identifier;
+use ockam::remote::RemoteRelayFilter;
+use ockam::Result;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{route, Address, AllowAll, Error};
+use ockam_multiaddr::MultiAddr;
+
+use crate::authenticator::credential_issuer::{DEFAULT_CREDENTIAL_VALIDITY, PROJECT_MEMBER_SCHEMA};
+use crate::cli_state::CliState;
+use crate::nodes::models::portal::{InletStatus, OutletAccessControl, OutletStatus};
+use crate::nodes::models::relay::RelayInfo;
+use crate::nodes::service::{NodeManagerCredentialRetrieverOptions, NodeManagerTrustOptions};
+use crate::test_utils::TestNode;
+
+/// Default duration after which a cluster test fails
+const DEFAULT_TEST_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Builder for a [`TestCluster`].
+///
+/// ```ignore
+/// TestCluster::builder().with_nodes(2).run(|cluster| async move {
+///     let echo_server = start_tcp_echo_server().await;
+///     let inlet = cluster.create_portal(0, 1, echo_server.chosen_addr).await?;
+///     assert_tcp_echo(&inlet.bind_addr, b"hello").await;
+///     Ok(())
+/// });
+/// ```
+pub struct TestClusterBuilder {
+    nodes: usize,
+    authority: bool,
+    member_attributes: Vec<(String, String)>,
+    timeout: Duration,
+}
+
+impl TestClusterBuilder {
+    /// Set the number of nodes of the cluster, not counting the authority node
+    pub fn with_nodes(mut self, nodes: usize) -> Self {
+        self.nodes = nodes;
+        self
+    }
+
+    /// Start an additional node acting as an authority.
+    /// The other nodes trust that authority and present a credential issued by it.
+    pub fn with_authority(mut self) -> Self {
+        self.authority = true;
+        self
+    }
+
+    /// Add an attribute to the credentials issued by the authority to the other nodes
+    pub fn with_member_attribute(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.authority = true;
+        self.member_attributes.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the duration after which the test fails
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Start the cluster on a new runtime, then run the test body.
+    ///
+    /// All the nodes are stopped when the test body returns, and the test panics if
+    /// the test body returns an error or doesn't complete before the timeout.
+    pub fn run<F, Fut>(self, test: F)
+    where
+        F: FnOnce(TestCluster) -> Fut,
+        Fut: Future<Output = Result<()>>,
+    {
+        std::env::set_var("OCKAM_LOG", "none");
+        let runtime = Arc::new(Runtime::new().unwrap());
+        let handle = runtime.handle();
+        let runtime_cloned = runtime.clone();
+        let test_timeout = self.timeout;
+
+        let result: Result<()> = handle.block_on(async move {
+            let test_body = async move {
+                let cluster = self.start(runtime_cloned).await?;
+                let result = test(cluster.clone()).await;
+                cluster.stop().await;
+                result
+            };
+
+            timeout(test_timeout, test_body)
+                .await
+                .unwrap_or_else(|_| Err(Error::new(Origin::Node, Kind::Timeout, "Test timed out")))
+        });
+
+        result.unwrap();
+    }
+
+    async fn start(self, runtime: Arc<Runtime>) -> Result<TestCluster> {
+        let authority = if self.authority {
+            Some(Arc::new(TestNode::create(runtime.clone(), None).await))
+        } else {
+            None
+        };
+
+        let mut nodes = vec![];
+        for _ in 0..self.nodes {
+            let node = match &authority {
+                Some(authority) => {
+                    Self::start_member(runtime.clone(), authority, &self.member_attributes).await?
+                }
+                None => TestNode::create(runtime.clone(), None).await,
+            };
+            nodes.push(Arc::new(node));
+        }
+
+        Ok(TestCluster { nodes, authority })
+    }
+
+    /// Start a node with a credential issued by the authority node
+    async fn start_member(
+        runtime: Arc<Runtime>,
+        authority: &TestNode,
+        attributes: &[(String, String)],
+    ) -> Result<TestNode> {
+        // the identity of the node must exist before the credential can be issued
+        let cli_state = CliState::test().await?;
+        let identifier = cli_state
+            .get_or_create_default_named_identity()
+            .await?
+            .identifier();
+
+        let authority_identifier = authority.node_manager.identifier();
+        let mut builder = AttributesBuilder::with_schema(PROJECT_MEMBER_SCHEMA);
+        for (key, value) in attributes {
+            builder = builder.with_attribute(key.as_str(), value.as_str());
+        }
+        let credential = authority
+            .secure_channels
+            .identities()
+            .credentials()
+            .credentials_creation()
+            .issue_credential(
+                &authority_identifier,
+                &identifier,
+                builder.build(),
+                DEFAULT_CREDENTIAL_VALIDITY,
+            )
+            .await?;
+
+        Ok(TestNode::create_with_cli_state(
+            runtime,
+            None,
+            cli_state,
+            NodeManagerTrustOptions::new(
+                NodeManagerCredentialRetrieverOptions::InMemory(credential),
+                Some(authority_identifier),
+            ),
+        )
+        .await)
+    }
+}
+
+/// A set of in-memory nodes running in the same process.
+///
+/// Each node has its own database and identity and listens on an ephemeral TCP port.
+/// Nodes are referred to by their index, in the order of their creation.
+#[derive(Clone)]
+pub struct TestCluster {
+    nodes: Vec<Arc<TestNode>>,
+    authority: Option<Arc<TestNode>>,
+}
+
+impl TestCluster {
+    /// Create a builder for a cluster with 2 nodes and no authority
+    pub fn builder() -> TestClusterBuilder {
+        TestClusterBuilder {
+            nodes: 2,
+            authority: false,
+            member_attributes: vec![],
+            timeout: DEFAULT_TEST_TIMEOUT,
+        }
+    }
+
+    /// Return the node with the given index
+    pub fn node(&self, index: usize) -> &TestNode {
+        self.nodes
+            .get(index)
+            .unwrap_or_else(|| panic!("the cluster has no node {index}"))
+    }
+
+    /// Return the authority node. This panics if the cluster was built without an authority
+    pub fn authority(&self) -> &TestNode {
+        self.authority
+            .as_deref()
+            .expect("the cluster was built without an authority")
+    }
+
+    /// Return the identifier of the authority node, if there is one
+    pub fn authority_identifier(&self) -> Option<Identifier> {
+        self.authority
+            .as_ref()
+            .map(|authority| authority.node_manager.identifier())
+    }
+
+    /// Return the address of the node with the given index, as a multiaddr
+    pub async fn address(&self, index: usize) -> Result<MultiAddr> {
+        self.node(index).listen_address().await.multi_addr()
+    }
+
+    /// Return the address of the default secure channel listener of a node
+    pub async fn secure_api_address(&self, index: usize) -> Result<MultiAddr> {
+        self.address(index)
+            .await?
+            .concat(&MultiAddr::from_str("/secure/api")?)
+    }
+
+    /// Create a relay named `alias` on the node `from`, at the node `at`.
+    /// The relay worker is registered with the address `forward_to_<alias>`.
+    pub async fn create_relay(&self, from: usize, at: usize, alias: &str) -> Result<RelayInfo> {
+        self.create_relay_with_filter(from, at, alias, RemoteRelayFilter::new())
+            .await
+    }
+
+    /// Create a relay named `alias` on the node `from`, at the node `at`,
+    /// restricting the services of the node `from` which can be reached through the relay
+    pub async fn create_relay_with_filter(
+        &self,
+        from: usize,
+        at: usize,
+        alias: &str,
+        filter: RemoteRelayFilter,
+    ) -> Result<RelayInfo> {
+        let node = self.node(from);
+        node.node_manager
+            .create_relay(
+                &node.context,
+                &self.secure_api_address(at).await?,
+                alias.to_string(),
+                true,
+                None,
+                Some(format!("forward_to_{alias}")),
+                vec![],
+                false,
+                filter,
+            )
+            .await
+    }
+
+    /// Create an outlet on a node, forwarding to `to`, accessible to any identity
+    pub async fn create_outlet(
+        &self,
+        index: usize,
+        to: SocketAddr,
+        address: &str,
+    ) -> Result<OutletStatus> {
+        let node = self.node(index);
+        node.node_manager
+            .create_outlet(
+                &node.context,
+                to,
+                Some(Address::from_string(address)),
+                true,
+                OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                None,
+                None,
+                None,
+            )
+            .await
+    }
+
+    /// Create an inlet on the node `from`, on an ephemeral port, for the outlet
+    /// `outlet_address` of the node `to`. The inlet connects through a secure channel.
+    pub async fn create_inlet(
+        &self,
+        from: usize,
+        to: usize,
+        outlet_address: &str,
+        alias: &str,
+    ) -> Result<InletStatus> {
+        let outlet = self
+            .secure_api_address(to)
+            .await?
+            .concat(&MultiAddr::from_str(&format!("/service/{outlet_address}"))?)?;
+        self.create_inlet_to(from, outlet, alias).await
+    }
+
+    /// Create an inlet on the node `from`, on an ephemeral port, for an outlet reached with
+    /// the given route, for example through a relay
+    pub async fn create_inlet_to(
+        &self,
+        from: usize,
+        outlet: MultiAddr,
+        alias: &str,
+    ) -> Result<InletStatus> {
+        let node = self.node(from);
+        node.node_manager
+            .create_inlet(
+                &node.context,
+                "127.0.0.1:0".to_string(),
+                route![],
+                route![],
+                outlet,
+                alias.to_string(),
+                None,
+                None,
+                None,
+                true,
+                None,
+                false,
+                None,
+            )
+            .await
+    }
+
+    /// Create a portal from an inlet on the node `from` to `target`, through an outlet on the node `to`
+    pub async fn create_portal(
+        &self,
+        from: usize,
+        to: usize,
+        target: SocketAddr,
+    ) -> Result<InletStatus> {
+        self.create_outlet(to, target, "outlet").await?;
+        self.create_inlet(from, to, "outlet", "inlet").await
+    }
+
+    /// Stop a node. The node is still part of the cluster, but can't be reached anymore
+    pub async fn stop_node(&self, index: usize) -> Result<()> {
+        self.node(index).context.stop().await
+    }
+
+    /// Stop all the nodes. Nodes which were already stopped are skipped
+    pub async fn stop(&self) {
+        for node in self.nodes.iter().chain(self.authority.iter()) {
+            let _ = node.context.stop().await;
+        }
+    }
+}
+
+/// Send a payload to a TCP address and assert that it is echoed back
+pub async fn assert_tcp_echo(address: &str, payload: &[u8]) {
+    let mut socket = TcpStream::connect(address)
+        .await
+        .unwrap_or_else(|e| panic!("cannot connect to {address}: {e}"));
+    socket.write_all(payload).await.unwrap();
+
+    let mut buffer = vec![0u8; payload.len()];
+    socket.read_exact(&mut buffer).await.unwrap();
+    assert_eq!(buffer, payload);
+}
+
+/// Poll a condition until it holds, or fail after a timeout
+pub async fn eventually<F, Fut>(timeout_duration: Duration, mut condition: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let wait = async {
+        while !condition().await {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+    };
+    timeout(timeout_duration, wait).await.map_err(|_| {
+        Error::new(
+            Origin::Node,
+            Kind::Timeout,
+            "The condition was not met before the timeout",
+        )
+    })
+}
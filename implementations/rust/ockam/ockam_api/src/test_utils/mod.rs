@@ -26,6 +26,10 @@ use crate::nodes::service::{NodeManagerGeneralOptions, NodeManagerTransportOptio
 use crate::nodes::InMemoryNode;
 use crate::nodes::{NodeManagerWorker, NODEMANAGER_ADDR};
 
+mod cluster;
+
+pub use cluster::*;
+
 /// This struct is used by tests, it has two responsibilities:
 /// - guard to delete the cli state at the end of the test, the cli state
 ///   is comprised by some files within the file system, created in a
@@ -52,6 +56,19 @@ pub async fn start_manager_for_tests(
     context: &mut Context,
     bind_addr: Option<&str>,
     trust_options: Option<NodeManagerTrustOptions>,
+) -> Result<NodeManagerHandle> {
+    let cli_state = CliState::test().await?;
+    start_manager_for_tests_with_cli_state(context, cli_state, bind_addr, trust_options).await
+}
+
+/// Starts a local node manager using an existing cli state.
+///
+/// The node uses the default identity of the cli state, which is created if it doesn't exist yet.
+pub async fn start_manager_for_tests_with_cli_state(
+    context: &mut Context,
+    cli_state: CliState,
+    bind_addr: Option<&str>,
+    trust_options: Option<NodeManagerTrustOptions>,
 ) -> Result<NodeManagerHandle> {
     let tcp = TcpTransport::create(context).await?;
     let tcp_listener = tcp
@@ -61,8 +78,6 @@ pub async fn start_manager_for_tests(
         )
         .await?;
 
-    let cli_state = CliState::test().await?;
-
     let node_name = random_name();
     cli_state
         .start_node_with_optional_values(&node_name, &None, &None, Some(&tcp_listener))
@@ -197,17 +212,32 @@ pub struct TestNode {
 
 impl TestNode {
     pub async fn create(runtime: Arc<Runtime>, listen_addr: Option<&str>) -> Self {
+        let cli_state = CliState::test().await.expect("cannot create cli state");
+        Self::create_with_cli_state(
+            runtime,
+            listen_addr,
+            cli_state,
+            NodeManagerTrustOptions::new(NodeManagerCredentialRetrieverOptions::None, None),
+        )
+        .await
+    }
+
+    /// Create a node using an existing cli state and some specific trust options
+    pub async fn create_with_cli_state(
+        runtime: Arc<Runtime>,
+        listen_addr: Option<&str>,
+        cli_state: CliState,
+        trust_options: NodeManagerTrustOptions,
+    ) -> Self {
         let (mut context, mut executor) = NodeBuilder::new().with_runtime(runtime.clone()).build();
         runtime.spawn(async move {
             executor.start_router().await.expect("cannot start router");
         });
-        let node_manager_handle = start_manager_for_tests(
+        let node_manager_handle = start_manager_for_tests_with_cli_state(
             &mut context,
+            cli_state,
             listen_addr,
-            Some(NodeManagerTrustOptions::new(
-                NodeManagerCredentialRetrieverOptions::None,
-                None,
-            )),
+            Some(trust_options),
         )
         .await
         .expect("cannot start node manager");
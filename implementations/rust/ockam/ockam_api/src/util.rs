@@ -1,4 +1,5 @@
 use std::net::{SocketAddrV4, SocketAddrV6};
+use std::str::FromStr;
 
 use miette::miette;
 
@@ -103,8 +104,8 @@ pub async fn multiaddr_to_route(
                 let port = it.next()?.cast::<Tcp>()?;
                 let socket_addr = SocketAddrV4::new(*ip4, *port);
 
-                let options = TcpConnectionOptions::new();
-                flow_control_id = Some(options.flow_control_id().clone());
+                // the connections to the same peer are shared, each user keeps its own flow control
+                let options = TcpConnectionOptions::new().shared();
 
                 let connection = match tcp.connect(socket_addr.to_string(), options).await {
                     Ok(c) => c,
@@ -114,6 +115,7 @@ pub async fn multiaddr_to_route(
                     }
                 };
 
+                flow_control_id = Some(connection.flow_control_id().clone());
                 number_of_tcp_hops += 1;
                 rb = rb.append(connection.sender_address().clone());
 
@@ -128,8 +130,7 @@ pub async fn multiaddr_to_route(
                 let port = it.next()?.cast::<Tcp>()?;
                 let socket_addr = SocketAddrV6::new(*ip6, *port, 0, 0);
 
-                let options = TcpConnectionOptions::new();
-                flow_control_id = Some(options.flow_control_id().clone());
+                let options = TcpConnectionOptions::new().shared();
 
                 let connection = match tcp.connect(socket_addr.to_string(), options).await {
                     Ok(c) => c,
@@ -139,6 +140,7 @@ pub async fn multiaddr_to_route(
                     }
                 };
 
+                flow_control_id = Some(connection.flow_control_id().clone());
                 number_of_tcp_hops += 1;
                 rb = rb.append(connection.sender_address().clone());
 
@@ -154,8 +156,7 @@ pub async fn multiaddr_to_route(
                     if p.code() == Tcp::CODE {
                         let port = p.cast::<Tcp>()?;
 
-                        let options = TcpConnectionOptions::new();
-                        flow_control_id = Some(options.flow_control_id().clone());
+                        let options = TcpConnectionOptions::new().shared();
                         let peer = format!("{}:{}", &*host, *port);
 
                         let connection = match tcp.connect(&peer, options).await {
@@ -166,6 +167,7 @@ pub async fn multiaddr_to_route(
                             }
                         };
 
+                        flow_control_id = Some(connection.flow_control_id().clone());
                         number_of_tcp_hops += 1;
                         rb = rb.append(connection.sender_address().clone());
 
@@ -308,6 +310,25 @@ pub fn addr_to_multiaddr<T: Into<Address>>(a: T) -> Option<MultiAddr> {
     route_to_multiaddr(&r)
 }
 
+/// Display a route as a MultiAddr when possible.
+///
+/// Otherwise fall back to the route format, for example `1#127.0.0.1:4000 => 0#api`,
+/// so that all the addresses of the route can be inspected for diagnostics.
+pub fn route_to_multiaddr_or_route_string(r: &Route) -> String {
+    route_to_multiaddr(r)
+        .map(|ma| ma.to_string())
+        .unwrap_or_else(|| r.to_string())
+}
+
+/// Parse a string returned by [`route_to_multiaddr_or_route_string`] back to a route
+pub fn multiaddr_or_route_string_to_route(s: &str) -> Result<Route> {
+    if let Ok(ma) = MultiAddr::from_str(s) {
+        return multiaddr_to_transport_route(&ma)
+            .ok_or_else(|| ApiError::core(format!("could not convert {ma} to a route")));
+    }
+    Ok(Route::from_str(s)?)
+}
+
 /// Tells whether the input MultiAddr references a local node or a remote node.
 ///
 /// This should be called before cleaning the MultiAddr.
@@ -371,3 +392,30 @@ pub fn local_worker(code: &Code) -> Result<bool> {
         _ => Err(ApiError::core(format!("unknown transport type: {code}"))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    #[test]
+    fn route_string_fallback() {
+        let local = route!["secure", "service"];
+        let displayed = route_to_multiaddr_or_route_string(&local);
+        assert_eq!(displayed, "/service/secure/service/service");
+        assert_eq!(
+            multiaddr_or_route_string_to_route(&displayed).unwrap(),
+            local
+        );
+
+        let remote = route![Address::new(TCP, "127.0.0.1:4000"), "my worker"];
+        let displayed = route_to_multiaddr_or_route_string(&remote);
+        assert_eq!(displayed, "1#127.0.0.1:4000 => 0#my%20worker");
+        assert_eq!(
+            multiaddr_or_route_string_to_route(&displayed).unwrap(),
+            remote
+        );
+
+        assert!(multiaddr_or_route_string_to_route("0#a => => 0#b").is_err());
+    }
+}
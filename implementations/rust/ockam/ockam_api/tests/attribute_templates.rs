@@ -0,0 +1,254 @@
+use crate::common::common::{
+    change_client_identifier, default_configuration, start_authority_with_configuration,
+    AuthorityClient, AuthorityInfo,
+};
+use ockam::identity::utils::now;
+use ockam::identity::{secure_channels, Identifier, SecureChannels};
+use ockam_api::authenticator::direct::{
+    Members, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
+};
+use ockam_api::authenticator::enrollment_tokens::{TokenAcceptor, TokenIssuer};
+use ockam_api::authenticator::one_time_code::OneTimeCode;
+use ockam_api::authenticator::{
+    AttributeConstraint, AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase,
+    EnrollmentToken, OCKAM_TICKET_TEMPLATE_ATTRIBUTE_KEY,
+};
+use ockam_api::cloud::AuthorityNodeClient;
+use ockam_core::Result;
+use ockam_node::database::SqlxDatabase;
+use ockam_node::Context;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+mod common;
+
+#[ockam_macros::test]
+async fn template_violations_are_rejected_when_the_ticket_is_created(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let setup = setup(ctx, secure_channels.clone()).await?;
+    let team_lead = &setup.team_lead.client;
+
+    // a team lead must use its template
+    let res = team_lead
+        .create_token(ctx, attributes(&[("role", "developer")]), None, None, None)
+        .await;
+    assert!(res.is_err());
+
+    // the attributes must be allowed by the template
+    for attrs in [
+        attributes(&[("role", "admin")]),
+        attributes(&[("role", "developer"), ("team", "red")]),
+        attributes(&[("role", "developer"), ("location", "sf")]),
+        attributes(&[(
+            OCKAM_ROLE_ATTRIBUTE_KEY,
+            OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE,
+        )]),
+    ] {
+        let res = create_token(ctx, team_lead, Some("team-lead"), attrs).await;
+        assert!(res.is_err());
+    }
+    let res = create_token(ctx, team_lead, Some("unknown"), Default::default()).await;
+    assert!(res.is_err());
+
+    // a team lead can't add members directly, nor create templates
+    let res = team_lead
+        .add_member(
+            ctx,
+            create_identity(&secure_channels).await?,
+            attributes(&[("role", "developer")]),
+        )
+        .await;
+    assert!(res.is_err());
+    let res = team_lead
+        .create_attribute_template(ctx, "team-lead".to_string(), Default::default())
+        .await;
+    assert!(res.is_err());
+
+    // a valid ticket grants the team of the team lead
+    let otc = create_token(
+        ctx,
+        team_lead,
+        Some("team-lead"),
+        attributes(&[("role", "developer")]),
+    )
+    .await
+    .unwrap();
+    let member = create_identity(&secure_channels).await?;
+    change_client_identifier(team_lead, &member, None)
+        .present_token(ctx, otc)
+        .await
+        .unwrap();
+
+    let members = setup.admin.client.list_members(ctx).await.unwrap();
+    assert_eq!(
+        members.get(&member).unwrap().attrs(),
+        &binary_attributes(&[("role", "developer"), ("team", "blue")])
+    );
+
+    // the templates can be listed by an enroller
+    let templates = team_lead.list_attribute_templates(ctx).await.unwrap();
+    assert_eq!(templates.len(), 1);
+    assert_eq!(templates[0].name, "team-lead");
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn forged_tickets_are_rejected_when_they_are_redeemed(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let setup = setup(ctx, secure_channels.clone()).await?;
+
+    // tokens are stored directly in the authority database, as if they had been altered
+    let tokens = AuthorityEnrollmentTokenSqlxDatabase::new(
+        SqlxDatabase::create(&setup.database_path).await?,
+    );
+    let forged_tokens = [
+        // an attribute which is not in the template
+        (
+            Some("team-lead"),
+            attributes(&[("role", "developer"), ("team", "blue"), ("admin", "true")]),
+        ),
+        // another team
+        (
+            Some("team-lead"),
+            attributes(&[("role", "developer"), ("team", "red")]),
+        ),
+        // no template
+        (None, attributes(&[("role", "developer"), ("team", "red")])),
+    ];
+
+    for (template, attrs) in forged_tokens {
+        let otc = OneTimeCode::new();
+        let now = now()?;
+        tokens
+            .store_new_token(EnrollmentToken {
+                one_time_code: otc.clone(),
+                reference: None,
+                issued_by: setup.team_lead.identifier.clone(),
+                created_at: now,
+                expires_at: now + 600,
+                ttl_count: 1,
+                attrs,
+                credential_ttl: None,
+                template: template.map(|t| t.to_string()),
+            })
+            .await?;
+
+        let member = create_identity(&secure_channels).await?;
+        let res = change_client_identifier(&setup.team_lead.client, &member, None)
+            .present_token(ctx, otc)
+            .await;
+        assert!(res.is_err());
+
+        let members = setup.admin.client.list_member_ids(ctx).await.unwrap();
+        assert!(!members.contains(&member));
+    }
+
+    Ok(())
+}
+
+/// HELPERS
+struct Setup {
+    database_path: PathBuf,
+    admin: AuthorityClient,
+    team_lead: AuthorityClient,
+}
+
+/// Start an authority with a `team-lead` template, and enroll a team lead bound to that template
+async fn setup(ctx: &Context, secure_channels: Arc<SecureChannels>) -> Result<Setup> {
+    let configuration = default_configuration().await?;
+    let database_path = configuration.database_path.clone();
+    let AuthorityInfo { mut admins, .. } =
+        start_authority_with_configuration(ctx, secure_channels.clone(), 1, configuration).await?;
+    let admin = admins.remove(0);
+
+    admin
+        .client
+        .create_attribute_template(
+            ctx,
+            "team-lead".to_string(),
+            BTreeMap::from([
+                ("team".to_string(), AttributeConstraint::Enroller),
+                (
+                    "role".to_string(),
+                    AttributeConstraint::OneOf(vec!["developer".to_string(), "tester".to_string()]),
+                ),
+            ]),
+        )
+        .await
+        .unwrap();
+
+    let otc = admin
+        .client
+        .create_token(
+            ctx,
+            attributes(&[
+                (
+                    OCKAM_ROLE_ATTRIBUTE_KEY,
+                    OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE,
+                ),
+                (OCKAM_TICKET_TEMPLATE_ATTRIBUTE_KEY, "team-lead"),
+                ("team", "blue"),
+            ]),
+            None,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    let team_lead = create_identity(&secure_channels).await?;
+    let team_lead_client = change_client_identifier(&admin.client, &team_lead, None);
+    team_lead_client.present_token(ctx, otc).await.unwrap();
+
+    Ok(Setup {
+        database_path,
+        admin,
+        team_lead: AuthorityClient {
+            identifier: team_lead,
+            client: team_lead_client,
+        },
+    })
+}
+
+async fn create_token(
+    ctx: &Context,
+    client: &AuthorityNodeClient,
+    template: Option<&str>,
+    attributes: BTreeMap<String, String>,
+) -> miette::Result<OneTimeCode> {
+    client
+        .create_token_with_template(
+            ctx,
+            template.map(|t| t.to_string()),
+            attributes,
+            None,
+            None,
+            None,
+        )
+        .await
+}
+
+async fn create_identity(secure_channels: &SecureChannels) -> Result<Identifier> {
+    secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await
+}
+
+fn attributes(attrs: &[(&str, &str)]) -> BTreeMap<String, String> {
+    attrs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn binary_attributes(attrs: &[(&str, &str)]) -> BTreeMap<Vec<u8>, Vec<u8>> {
+    attrs
+        .iter()
+        .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+        .collect()
+}
@@ -0,0 +1,135 @@
+use crate::common::common::{
+    change_client_identifier, default_configuration, start_authority_with_configuration,
+    AuthorityInfo,
+};
+use ockam::identity::secure_channels;
+use ockam_api::authenticator::enrollment_tokens::{TokenAcceptor, TokenIssuer};
+use ockam_api::authenticator::events::Events;
+use ockam_api::authenticator::{
+    AuthorityEventKind, AuthorityEventsRepository, AuthorityEventsSqlxDatabase,
+};
+use ockam_core::Result;
+use ockam_node::database::SqlxDatabase;
+use ockam_node::Context;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+mod common;
+
+#[ockam_macros::test]
+async fn redeemed_token_is_recorded_and_sent_to_webhook(ctx: &mut Context) -> Result<()> {
+    let (webhook_url, mut payloads) = start_webhook_stub().await;
+
+    let mut configuration = default_configuration().await?;
+    configuration.events_webhook = Some(webhook_url.parse().unwrap());
+    let database_path = configuration.database_path.clone();
+
+    let secure_channels = secure_channels().await?;
+    let AuthorityInfo { admins, .. } =
+        start_authority_with_configuration(ctx, secure_channels.clone(), 1, configuration).await?;
+    let admin = &admins[0];
+
+    let otc = admin
+        .client
+        .create_token(ctx, Default::default(), None, None, None)
+        .await
+        .unwrap();
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    member_client.present_token(ctx, otc).await.unwrap();
+
+    // the event is persisted in the authority database
+    let database = SqlxDatabase::create(&database_path).await?;
+    let events = AuthorityEventsSqlxDatabase::new(database)
+        .get_events(None)
+        .await?;
+    let redeemed = events
+        .iter()
+        .find(|e| e.kind == AuthorityEventKind::EnrollmentTokenRedeemed)
+        .expect("the token redemption must be recorded");
+    assert_eq!(redeemed.subject, member);
+    assert_eq!(redeemed.actor, Some(admin.identifier.clone()));
+
+    // the event can be listed by an enroller
+    let listed = admin.client.list_events(ctx, None).await.unwrap();
+    assert!(listed.contains(redeemed));
+    let listed = admin
+        .client
+        .list_events(ctx, Some((*redeemed.created_at + 3600).into()))
+        .await
+        .unwrap();
+    assert!(listed.is_empty());
+
+    // a member which is not an enroller cannot list the events
+    assert!(member_client.list_events(ctx, None).await.is_err());
+
+    // the event is sent to the webhook
+    let payload = tokio::time::timeout(Duration::from_secs(10), async {
+        while let Some(payload) = payloads.recv().await {
+            if payload["kind"] == "enrollment_token_redeemed" {
+                return Some(payload);
+            }
+        }
+        None
+    })
+    .await
+    .expect("the webhook must be called")
+    .unwrap();
+    assert_eq!(payload["subject"], member.to_string());
+
+    Ok(())
+}
+
+/// Start a minimal HTTP server accepting POST requests and
+/// returning their JSON bodies on a channel
+async fn start_webhook_stub() -> (String, mpsc::UnboundedReceiver<serde_json::Value>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/events", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::unbounded_channel();
+
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 4096];
+                let body = loop {
+                    let n = stream.read(&mut buffer).await.unwrap_or(0);
+                    if n == 0 {
+                        return;
+                    }
+                    request.extend_from_slice(&buffer[..n]);
+                    let text = String::from_utf8_lossy(&request).to_string();
+                    if let Some((headers, body)) = text.split_once("\r\n\r\n") {
+                        let content_length = headers
+                            .lines()
+                            .find_map(|l| {
+                                let (name, value) = l.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if body.len() >= content_length {
+                            break body[..content_length].to_string();
+                        }
+                    }
+                };
+                let _ = stream
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .await;
+                if let Ok(payload) = serde_json::from_str(&body) {
+                    let _ = sender.send(payload);
+                }
+            });
+        }
+    });
+
+    (url, receiver)
+}
@@ -38,6 +38,7 @@ pub async fn default_configuration() -> Result<Configuration> {
         no_token_enrollment: true,
         okta: None,
         account_authority: None,
+        events_webhook: None,
     };
 
     // Hack to create Authority Identity using the same vault and storage
@@ -71,8 +72,17 @@ pub async fn start_authority(
     secure_channels: Arc<SecureChannels>,
     number_of_admins: usize,
 ) -> Result<AuthorityInfo> {
-    let mut configuration = default_configuration().await?;
+    let configuration = default_configuration().await?;
+    start_authority_with_configuration(ctx, secure_channels, number_of_admins, configuration).await
+}
 
+// Start an Authority with a given configuration and number of freshly generated Admins
+pub async fn start_authority_with_configuration(
+    ctx: &Context,
+    secure_channels: Arc<SecureChannels>,
+    number_of_admins: usize,
+    mut configuration: Configuration,
+) -> Result<AuthorityInfo> {
     let account_authority = secure_channels
         .identities()
         .identities_creation()
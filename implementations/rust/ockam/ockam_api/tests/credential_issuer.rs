@@ -7,8 +7,10 @@ use ockam::identity::{
 };
 use ockam::route;
 use ockam_api::authenticator::credential_issuer::CredentialIssuerWorker;
+use ockam_api::authenticator::events::AuthorityEvents;
 use ockam_api::authenticator::{
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase, PreTrustedIdentity,
+    AuthorityEventsSqlxDatabase, AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityRevocationsSqlxDatabase, PreTrustedIdentity,
 };
 use ockam_core::api::Request;
 use ockam_core::compat::collections::BTreeMap;
@@ -68,11 +70,13 @@ async fn credential(ctx: &mut Context) -> Result<()> {
         .add_consumer(auth_worker_addr.clone(), &sc_flow_control_id);
     let auth = CredentialIssuerWorker::new(
         members,
+        Arc::new(AuthorityRevocationsSqlxDatabase::create().await?),
         identities.credentials(),
         &auth_identifier,
         None,
         None,
         None,
+        AuthorityEvents::new(Arc::new(AuthorityEventsSqlxDatabase::create().await?)),
     );
     ctx.start_worker(auth_worker_addr.clone(), auth).await?;
 
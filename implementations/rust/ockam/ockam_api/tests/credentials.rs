@@ -0,0 +1,148 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
+use ockam::identity::utils::{now, AttributesBuilder};
+use ockam::identity::{identities, Identifier, Identities, TimestampInSeconds};
+use ockam_api::credentials::{decode_credential, verify_credential, CredentialIssuer};
+use ockam_core::Result;
+
+#[tokio::test]
+async fn verify_valid_credential() -> Result<()> {
+    let fixture = Fixture::create().await?;
+
+    let verified = verify_credential(
+        fixture.identities.change_history_repository(),
+        &CredentialIssuer::Identifier(fixture.issuer.clone()),
+        &fixture.credential,
+        now()?,
+    )
+    .await?;
+
+    assert_eq!(verified.subject, fixture.subject);
+    assert_eq!(verified.issuer, fixture.issuer);
+    assert_eq!(verified.schema, 1);
+    assert_eq!(
+        verified.attributes.get("role"),
+        Some(&"auditor".to_string())
+    );
+    assert!(verified.created_at <= verified.verified_at);
+    assert!(verified.verified_at < verified.expires_at);
+    Ok(())
+}
+
+#[tokio::test]
+async fn verify_credential_offline_with_the_issuer_change_history() -> Result<()> {
+    let fixture = Fixture::create().await?;
+    let issuer = fixture.identities.get_identity(&fixture.issuer).await?;
+    let issuer = CredentialIssuer::parse(&issuer.export_as_string()?).await?;
+
+    // the verification must not rely on the change histories known locally
+    let other_identities = identities().await?;
+    let verified = verify_credential(
+        other_identities.change_history_repository(),
+        &issuer,
+        &fixture.credential,
+        now()?,
+    )
+    .await?;
+    assert_eq!(verified.issuer, fixture.issuer);
+
+    // without the change history the issuer is unknown
+    let result = verify_credential(
+        other_identities.change_history_repository(),
+        &CredentialIssuer::Identifier(fixture.issuer.clone()),
+        &fixture.credential,
+        now()?,
+    )
+    .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn reject_expired_credential() -> Result<()> {
+    let fixture = Fixture::create().await?;
+    let expires_at = fixture.credential.get_expires_at()?;
+
+    let result = verify_credential(
+        fixture.identities.change_history_repository(),
+        &CredentialIssuer::Identifier(fixture.issuer.clone()),
+        &fixture.credential,
+        TimestampInSeconds(expires_at.0 + 1),
+    )
+    .await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains("expired"), "{error}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn reject_credential_from_another_issuer() -> Result<()> {
+    let fixture = Fixture::create().await?;
+    let other_issuer = fixture
+        .identities
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    let result = verify_credential(
+        fixture.identities.change_history_repository(),
+        &CredentialIssuer::Identifier(other_issuer.clone()),
+        &fixture.credential,
+        now()?,
+    )
+    .await;
+
+    let error = result.unwrap_err().to_string();
+    assert!(error.contains(&other_issuer.to_string()), "{error}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn decode_cbor_and_hex_credentials() -> Result<()> {
+    let fixture = Fixture::create().await?;
+
+    let from_cbor = decode_credential(&fixture.credential.encode_as_cbor_bytes()?)?;
+    assert_eq!(from_cbor, fixture.credential);
+
+    let as_hex = format!("{}\n", fixture.credential.encode_as_string()?);
+    let from_hex = decode_credential(as_hex.as_bytes())?;
+    assert_eq!(from_hex, fixture.credential);
+
+    assert!(decode_credential(b"not a credential").is_err());
+    Ok(())
+}
+
+/// HELPERS
+struct Fixture {
+    identities: Arc<Identities>,
+    issuer: Identifier,
+    subject: Identifier,
+    credential: CredentialAndPurposeKey,
+}
+
+impl Fixture {
+    async fn create() -> Result<Self> {
+        let identities = identities().await?;
+        let issuer = identities.identities_creation().create_identity().await?;
+        let subject = identities.identities_creation().create_identity().await?;
+
+        let attributes = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute(b"role".to_vec(), b"auditor".to_vec())
+            .build();
+        let credential = identities
+            .credentials()
+            .credentials_creation()
+            .issue_credential(&issuer, &subject, attributes, Duration::from_secs(3600))
+            .await?;
+
+        Ok(Self {
+            identities,
+            issuer,
+            subject,
+            credential,
+        })
+    }
+}
@@ -0,0 +1,80 @@
+use ockam_api::nodes::models::services::{AdvertisedService, ServiceAdvertisement};
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::service::discovery::AdvertisedServices;
+use ockam_api::test_utils::start_manager_for_tests;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[ockam_macros::test]
+async fn advertised_services_are_listed_over_a_secure_channel(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    node_manager
+        .start_uppercase_service_impl(
+            context,
+            "advertised_uppercase".into(),
+            ServiceAdvertisement::advertised(None),
+        )
+        .await?;
+    node_manager
+        .start_echoer_service(
+            context,
+            "advertised_echo".into(),
+            ServiceAdvertisement::advertised(Some("Echo of the messages".to_string())),
+        )
+        .await?;
+    node_manager
+        .start_hop_service(
+            context,
+            "hidden_hop".into(),
+            ServiceAdvertisement::default(),
+        )
+        .await?;
+    node_manager
+        .start_service_discovery(context, DefaultAddress::SERVICE_DISCOVERY.into())
+        .await?;
+
+    // only the advertised services are listed, the default services are not advertised
+    let services = node_manager
+        .list_advertised_services(
+            context,
+            &MultiAddr::from_str("/secure/api")?,
+            Some(Duration::from_secs(5)),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        services,
+        vec![
+            AdvertisedService::new(
+                "advertised_uppercase",
+                DefaultAddress::UPPERCASE_SERVICE,
+                None
+            ),
+            AdvertisedService::new(
+                "advertised_echo",
+                DefaultAddress::ECHO_SERVICE,
+                Some("Echo of the messages".to_string())
+            ),
+        ]
+    );
+
+    // the discovery service is protected by the node policies, which require a credential
+    let result = node_manager
+        .list_advertised_services(context, &MultiAddr::default(), Some(Duration::from_secs(1)))
+        .await;
+    assert!(result.is_err());
+
+    // a discovery service can only be started once at a given address
+    let result = node_manager
+        .start_service_discovery(context, DefaultAddress::SERVICE_DISCOVERY.into())
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
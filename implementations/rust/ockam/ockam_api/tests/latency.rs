@@ -133,6 +133,9 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -154,6 +157,9 @@ pub fn measure_buffer_latency_two_nodes_portal() {
                     None,
                     None,
                     true,
+                    None,
+                    false,
+                    None,
                 )
                 .await?;
 
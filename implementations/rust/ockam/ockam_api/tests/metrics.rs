@@ -0,0 +1,148 @@
+#![cfg(feature = "telemetry")]
+
+use ockam::identity::{SecureChannelListenerOptions, SecureChannelOptions};
+use ockam::node;
+use ockam_api::echoer::Echoer;
+use ockam_api::journeys::APPLICATION_EVENT_NODE_NAME;
+use ockam_api::logs::{ExportingConfiguration, LoggingTracing};
+use ockam_core::{route, AsyncTryClone};
+use ockam_node::telemetry::{SECURE_CHANNEL_TRANSPORT_TYPE, TRANSPORT_TYPE_ATTRIBUTE};
+use ockam_node::{Context, NodeBuilder};
+use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransportExtension};
+use opentelemetry_sdk::metrics::data::{ResourceMetrics, Sum};
+use opentelemetry_sdk::testing::metrics::InMemoryMetricsExporter;
+
+/// This test needs to be an integration test
+/// It needs to run in isolation because
+/// it sets up a global meter provider that might interact with other tests
+#[test]
+fn test_transport_and_secure_channel_metrics() {
+    let metrics_exporter = InMemoryMetricsExporter::default();
+    let meter_provider = LoggingTracing::setup_metrics(
+        metrics_exporter.clone(),
+        &ExportingConfiguration::foreground(true).unwrap(),
+        "test",
+        Some("metrics-node".to_string()),
+    );
+
+    let (ctx, mut executor) = NodeBuilder::new().build();
+    let received = executor
+        .execute_no_abort(async move { send_echo_messages(ctx, 3).await })
+        .unwrap()
+        .unwrap();
+    assert_eq!(received, 3);
+
+    meter_provider.force_flush().unwrap();
+    let exported = metrics_exporter.get_finished_metrics().unwrap();
+    let metrics = exported.last().expect("metrics must have been exported");
+
+    // the node name is set on all the metrics
+    assert_eq!(
+        metrics
+            .resource
+            .get(APPLICATION_EVENT_NODE_NAME.clone())
+            .map(|v| v.to_string()),
+        Some("metrics-node".to_string())
+    );
+
+    // the messages of both nodes are counted in each direction
+    for transport_type in ["tcp", SECURE_CHANNEL_TRANSPORT_TYPE] {
+        for name in [
+            "ockam.messages.in",
+            "ockam.messages.out",
+            "ockam.bytes.in",
+            "ockam.bytes.out",
+        ] {
+            assert!(
+                counter_value(metrics, name, transport_type) > 0,
+                "{name} must have been increased for {transport_type}"
+            );
+        }
+    }
+    // 3 messages and their replies went through the secure channel
+    assert!(counter_value(metrics, "ockam.messages.out", SECURE_CHANNEL_TRANSPORT_TYPE) >= 6);
+    assert_eq!(
+        counter_value(metrics, "ockam.decode_failures", "tcp"),
+        0,
+        "there must be no decode failures"
+    );
+
+    // both sides of the secure channel recorded the duration of the handshake
+    assert!(metrics
+        .scope_metrics
+        .iter()
+        .flat_map(|s| s.metrics.iter())
+        .any(|m| m.name == "ockam.secure_channel.handshake.duration"));
+}
+
+/// Start 2 nodes connected with a secure channel over TCP and
+/// send some messages from one node to an echoer on the other node
+async fn send_echo_messages(ctx: Context, count: usize) -> ockam_core::Result<usize> {
+    let node1 = node(ctx.async_try_clone().await?).await?;
+    let identity1 = node1.create_identity().await?;
+    let tcp1 = node1.create_tcp_transport().await?;
+    node1.start_worker("echoer", Echoer).await?;
+    let listener = tcp1
+        .listen("127.0.0.1:0", TcpListenerOptions::new())
+        .await?;
+    let secure_channel_listener = node1
+        .create_secure_channel_listener(
+            &identity1,
+            "secure_channel_listener",
+            SecureChannelListenerOptions::new().as_consumer(listener.flow_control_id()),
+        )
+        .await?;
+    node1
+        .flow_controls()
+        .add_consumer("echoer", secure_channel_listener.flow_control_id());
+
+    let node2 = node(ctx.async_try_clone().await?).await?;
+    let identity2 = node2.create_identity().await?;
+    let tcp2 = node2.create_tcp_transport().await?;
+    let connection = tcp2
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    let channel = node2
+        .create_secure_channel(
+            &identity2,
+            route![connection, secure_channel_listener.address().clone()],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut received = 0;
+    for i in 0..count {
+        let message = format!("hello {i}");
+        let reply = node2
+            .context()
+            .send_and_receive::<String>(
+                route![channel.encryptor_address().clone(), "echoer"],
+                message.clone(),
+            )
+            .await?;
+        if reply == message {
+            received += 1;
+        }
+    }
+
+    ctx.stop().await?;
+    Ok(received)
+}
+
+/// Return the sum of the values of a counter for a given transport type
+fn counter_value(metrics: &ResourceMetrics, name: &str, transport_type: &str) -> u64 {
+    metrics
+        .scope_metrics
+        .iter()
+        .flat_map(|s| s.metrics.iter())
+        .filter(|m| m.name == name)
+        .filter_map(|m| m.data.as_any().downcast_ref::<Sum<u64>>())
+        .flat_map(|sum| sum.data_points.iter())
+        .filter(|data_point| {
+            data_point.attributes.iter().any(|(key, value)| {
+                key.as_str() == TRANSPORT_TYPE_ATTRIBUTE && value.as_str() == transport_type
+            })
+        })
+        .map(|data_point| data_point.value)
+        .sum()
+}
@@ -0,0 +1,89 @@
+use ockam_api::logs::{
+    Colored, CratesFilter, GlobalErrorHandler, LogFormat, LoggingConfiguration, LoggingEnabled,
+    LoggingTracing,
+};
+use ockam_api::random_name;
+use ockam_core::{async_trait, Result, Routed, Worker};
+use ockam_node::{Context, NodeBuilder, PANIC_LOG_TARGET};
+use serde_json::Value;
+use std::fs;
+use std::time::Duration;
+use tempfile::NamedTempFile;
+use tracing_core::Level;
+
+/// This test needs to be an integration test
+/// It needs to run in isolation because it sets up a global subscriber and a panic hook
+#[test]
+fn worker_panics_are_logged_as_structured_records() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let log_directory = temp_file.path().parent().unwrap().join(random_name());
+    let configuration = LoggingConfiguration::new(
+        LoggingEnabled::On,
+        Level::INFO,
+        GlobalErrorHandler::Off,
+        100,
+        60,
+        LogFormat::Json,
+        Colored::Off,
+        Some(log_directory.clone()),
+        CratesFilter::All,
+    );
+    let guard = LoggingTracing::setup_local_logging_only(&configuration);
+
+    let (ctx, mut executor) = NodeBuilder::new()
+        .no_logging()
+        .no_exit_on_panic()
+        .no_panic_output()
+        .build();
+    executor
+        .execute(async move {
+            ctx.start_worker("panicking", PanickingWorker).await?;
+            ctx.send("panicking", "hello".to_string()).await?;
+            // leave some time for the worker to panic
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            ctx.stop_timeout(1).await
+        })
+        .unwrap()
+        .unwrap();
+
+    // flush the log records
+    drop(guard);
+
+    let records: Vec<Value> = fs::read_dir(&log_directory)
+        .unwrap()
+        .map(|file| fs::read_to_string(file.unwrap().path()).unwrap())
+        .flat_map(|contents| {
+            contents
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("log records must be JSON"))
+                .collect::<Vec<Value>>()
+        })
+        .collect();
+
+    let panic = records
+        .iter()
+        .find(|r| r["target"] == PANIC_LOG_TARGET)
+        .unwrap_or_else(|| panic!("the panic must be logged: {records:?}"));
+    assert_eq!(panic["level"], "ERROR");
+    let fields = &panic["fields"];
+    assert!(fields["worker"].as_str().unwrap().ends_with("panicking"));
+    assert!(fields["message_type"].as_str().unwrap().ends_with("String"));
+    assert!(fields["location"].as_str().unwrap().contains("panics.rs"));
+    assert!(fields["message"]
+        .as_str()
+        .unwrap()
+        .contains("cannot handle hello"));
+}
+
+/// HELPERS
+struct PanickingWorker;
+
+#[async_trait]
+impl Worker for PanickingWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        panic!("cannot handle {}", msg.into_body()?)
+    }
+}
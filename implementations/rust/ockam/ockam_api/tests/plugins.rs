@@ -0,0 +1,55 @@
+use ockam_api::nodes::models::services::{ServiceAdvertisement, ServicePluginStatus};
+use ockam_api::nodes::{NodeServicePlugins, UppercasePlugin};
+use ockam_api::test_utils::start_manager_for_tests;
+use ockam_core::{route, Address};
+use ockam_node::Context;
+use serde_json::json;
+
+#[ockam_macros::test]
+async fn service_plugin_can_be_started(context: &mut Context) -> ockam::Result<()> {
+    NodeServicePlugins::register(UppercasePlugin);
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    let status = node_manager
+        .start_service_plugin(
+            context,
+            UppercasePlugin::NAME,
+            Some(json!({ "address": "plugin_uppercase" })),
+            ServiceAdvertisement::default(),
+        )
+        .await?;
+    assert_eq!(
+        status,
+        ServicePluginStatus::new(
+            UppercasePlugin::NAME,
+            vec![Address::from_string("plugin_uppercase")]
+        )
+    );
+    assert_eq!(node_manager.list_service_plugins().await, vec![status]);
+
+    // the plugin worker is reachable
+    let reply: String = context
+        .send_and_receive(route!["plugin_uppercase"], "hello".to_string())
+        .await?;
+    assert_eq!(reply, "HELLO");
+
+    // a plugin can only be started once
+    let result = node_manager
+        .start_service_plugin(
+            context,
+            UppercasePlugin::NAME,
+            None,
+            ServiceAdvertisement::default(),
+        )
+        .await;
+    assert!(result.is_err());
+
+    // only registered plugins can be started
+    let result = node_manager
+        .start_service_plugin(context, "unknown", None, ServiceAdvertisement::default())
+        .await;
+    assert!(result.is_err());
+
+    Ok(())
+}
@@ -1,7 +1,10 @@
 use ockam_api::config::lookup::InternetAddress;
-use ockam_api::nodes::models::portal::OutletAccessControl;
+use ockam_api::nodes::models::portal::{
+    InletHttpRewrite, OutletAccessControl, OutletIdentityForwarding,
+};
 use ockam_api::test_utils::{
-    start_manager_for_tests, start_passthrough_server, start_tcp_echo_server, Disruption, TestNode,
+    assert_tcp_echo, start_manager_for_tests, start_passthrough_server, start_tcp_echo_server,
+    Disruption, TestCluster, TestNode,
 };
 use ockam_api::ConnectionStatus;
 use ockam_core::compat::rand::RngCore;
@@ -32,6 +35,9 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
             Some(Address::from_string("outlet")),
             true,
             OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            None,
         )
         .await?;
 
@@ -51,6 +57,9 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
             None,
             None,
             true,
+            None,
+            false,
+            None,
         )
         .await?;
 
@@ -71,6 +80,479 @@ async fn inlet_outlet_local_successful(context: &mut Context) -> ockam::Result<(
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn inlet_on_an_assigned_port_is_reported_before_connecting(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    // the outlet doesn't exist yet, the inlet is created without waiting for the connection
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            Some(Duration::from_millis(100)),
+            None,
+            false,
+            None,
+            false,
+            None,
+        )
+        .await?;
+    assert_ne!(inlet_status.bind_addr, "127.0.0.1:0");
+
+    // the assigned port stays reserved for the inlet
+    assert!(std::net::TcpListener::bind(&inlet_status.bind_addr).is_err());
+
+    let shown = node_manager.show_inlet("alias").await.unwrap();
+    assert_eq!(shown.bind_addr, inlet_status.bind_addr);
+    let listed = node_manager.list_inlets().await.list;
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].bind_addr, inlet_status.bind_addr);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_outlet_bandwidth_limit_can_be_changed(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    let outlet_status = node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            Some(1_000_000),
+            None,
+            None,
+        )
+        .await?;
+    assert_eq!(outlet_status.bandwidth_limit, Some(1_000_000));
+
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            Some(1_000_000),
+            false,
+            None,
+        )
+        .await?;
+    assert_eq!(inlet_status.bandwidth_limit, Some(1_000_000));
+
+    // change the limits while the portal is running
+    let inlet_status = node_manager
+        .set_inlet_bandwidth_limit("alias", Some(500_000))
+        .await
+        .unwrap();
+    assert_eq!(inlet_status.bandwidth_limit, Some(500_000));
+    let outlet_status = node_manager
+        .set_outlet_bandwidth_limit(&Address::from_string("outlet"), None)
+        .await
+        .unwrap();
+    assert_eq!(outlet_status.bandwidth_limit, None);
+
+    // the portal still works and the traffic is accounted for
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+
+    let mut buf = [0u8; 5];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let inlet_status = node_manager.show_inlet("alias").await.unwrap();
+    assert_eq!(inlet_status.bandwidth_limit, Some(500_000));
+    assert!(inlet_status.throughput.unwrap() > 0);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_pre_check_reports_the_far_side_status(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            true,
+            None,
+        )
+        .await?;
+    // no client connected yet
+    assert_eq!(inlet_status.far_side.unwrap().health, "unknown");
+
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+    let mut buf = [0u8; 5];
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    let far_side = node_manager.show_inlet("alias").await.unwrap().far_side;
+    let far_side = far_side.unwrap();
+    assert_eq!(far_side.health, "healthy");
+    assert!(far_side.checked_at.is_some());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn outlet_passes_the_inlet_identifier_in_an_http_header(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    let outlet_status = node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            Some(OutletIdentityForwarding::HttpHeader(
+                "X-Ockam-Identifier".to_string(),
+            )),
+        )
+        .await?;
+    assert_eq!(
+        outlet_status.identity_forwarding,
+        Some(OutletIdentityForwarding::HttpHeader(
+            "X-Ockam-Identifier".to_string()
+        ))
+    );
+
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            None,
+        )
+        .await?;
+
+    // the echo server returns the request received from the outlet
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nX-Ockam-Identifier: Ifake\r\n\r\n")
+        .await
+        .unwrap();
+
+    let expected = format!(
+        "GET / HTTP/1.1\r\nX-Ockam-Identifier: {}\r\nHost: localhost\r\n\r\n",
+        node_manager.identifier()
+    );
+    let mut received = vec![0u8; expected.len()];
+    socket.read_exact(&mut received).await.unwrap();
+    assert_eq!(String::from_utf8(received).unwrap(), expected);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_rewrites_the_http_requests_of_its_clients(
+    context: &mut Context,
+) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    // an invalid header is refused when the inlet is created
+    let invalid = InletHttpRewrite::new(None, vec![("X Tenant".to_string(), "1".to_string())]);
+    let result = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "invalid".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            Some(invalid),
+        )
+        .await;
+    assert!(result.is_err());
+
+    let http_rewrite = InletHttpRewrite::new(
+        Some("api.internal".to_string()),
+        vec![("X-Tenant".to_string(), "acme".to_string())],
+    );
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            Some(http_rewrite),
+        )
+        .await?;
+
+    // the echo server returns the request received from the outlet
+    let mut socket = TcpStream::connect(inlet_status.bind_addr).await.unwrap();
+    socket
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost:5000\r\nX-Tenant: other\r\n\r\n")
+        .await
+        .unwrap();
+
+    let expected = "GET / HTTP/1.1\r\nHost: api.internal\r\nX-Tenant: acme\r\n\r\n";
+    let mut received = vec![0u8; expected.len()];
+    socket.read_exact(&mut received).await.unwrap();
+    assert_eq!(String::from_utf8(received).unwrap(), expected);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_connection_can_be_closed_by_id(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            None,
+        )
+        .await?;
+
+    let mut buf = [0u8; 5];
+    let mut socket1 = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket1.write_all(b"hello").await.unwrap();
+    socket1.read_exact(&mut buf).await.unwrap();
+    let mut socket2 = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket2.write_all(b"hello").await.unwrap();
+    socket2.read_exact(&mut buf).await.unwrap();
+
+    let connections = node_manager.list_inlet_connections("alias").await.unwrap();
+    assert_eq!(connections.list.len(), 2);
+    let connection1 = connections
+        .list
+        .iter()
+        .find(|c| c.peer_addr == socket1.local_addr().unwrap())
+        .unwrap();
+    assert_eq!(connection1.alias, "alias");
+    assert_eq!(connection1.bytes_read, 5);
+
+    node_manager.close_portal_connection(&connection1.id)?;
+
+    // the closed connection is dropped, the other one keeps working
+    assert_eq!(socket1.read(&mut buf).await.unwrap(), 0);
+    socket2.write_all(b"world").await.unwrap();
+    socket2.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"world");
+
+    assert!(node_manager
+        .list_inlet_connections("unknown")
+        .await
+        .is_none());
+    assert!(node_manager.close_portal_connection("unknown").is_err());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn inlet_can_be_paused_and_drained(context: &mut Context) -> ockam::Result<()> {
+    let echo_server_handle = start_tcp_echo_server().await;
+    let node_manager_handle = start_manager_for_tests(context, None, None).await?;
+    let node_manager = &node_manager_handle.node_manager;
+
+    node_manager
+        .create_outlet(
+            context,
+            echo_server_handle.chosen_addr,
+            Some(Address::from_string("outlet")),
+            true,
+            OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+            None,
+            None,
+            None,
+        )
+        .await?;
+
+    let inlet_status = node_manager
+        .create_inlet(
+            context,
+            "127.0.0.1:0".to_string(),
+            route![],
+            route![],
+            MultiAddr::from_str("/secure/api/service/outlet")?,
+            "alias".to_string(),
+            None,
+            None,
+            None,
+            true,
+            None,
+            false,
+            None,
+        )
+        .await?;
+    assert_eq!(inlet_status.paused_at, None);
+
+    let mut buf = [0u8; 5];
+    let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+    socket.read_exact(&mut buf).await.unwrap();
+
+    let inlet_status = node_manager
+        .pause_inlet("alias", Some(Duration::from_millis(500)))
+        .await
+        .unwrap();
+    assert!(inlet_status.paused_at.is_some());
+
+    // the new connections are refused, the existing one works until it is drained
+    let mut refused = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    assert!(!matches!(refused.read(&mut buf).await, Ok(n) if n > 0));
+    socket.write_all(b"world").await.unwrap();
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"world");
+    assert_eq!(socket.read(&mut buf).await.unwrap(), 0);
+
+    let inlet_status = node_manager.resume_inlet("alias").await.unwrap();
+    assert_eq!(inlet_status.paused_at, None);
+    let mut socket = TcpStream::connect(&inlet_status.bind_addr).await.unwrap();
+    socket.write_all(b"hello").await.unwrap();
+    socket.read_exact(&mut buf).await.unwrap();
+    assert_eq!(&buf, b"hello");
+
+    // outlets can be paused and resumed as well
+    let outlet_status = node_manager
+        .pause_outlet(&Address::from_string("outlet"), None)
+        .await
+        .unwrap();
+    assert!(outlet_status.paused_at.is_some());
+    let outlet_status = node_manager
+        .resume_outlet(&Address::from_string("outlet"))
+        .await
+        .unwrap();
+    assert_eq!(outlet_status.paused_at, None);
+
+    assert!(node_manager.pause_inlet("unknown", None).await.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn portal_between_members_of_the_same_authority() {
+    // the two nodes present a credential issued by the authority node
+    TestCluster::builder()
+        .with_nodes(2)
+        .with_authority()
+        .run(|cluster| async move {
+            let echo_server_handle = start_tcp_echo_server().await;
+            let inlet_status = cluster
+                .create_portal(0, 1, echo_server_handle.chosen_addr)
+                .await?;
+            assert_eq!(inlet_status.status, ConnectionStatus::Up);
+
+            assert_tcp_echo(&inlet_status.bind_addr, b"hello").await;
+            Ok(())
+        });
+}
+
 #[test]
 fn portal_node_goes_down_reconnect() {
     // in this test we manually create three nodes with a shared runtime, then:
@@ -100,6 +582,9 @@ fn portal_node_goes_down_reconnect() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -121,6 +606,9 @@ fn portal_node_goes_down_reconnect() {
                     None,
                     None,
                     true,
+                    None,
+                    false,
+                    None,
                 )
                 .await?;
 
@@ -164,6 +652,9 @@ fn portal_node_goes_down_reconnect() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -236,6 +727,9 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -269,6 +763,9 @@ fn portal_low_bandwidth_connection_keep_working_for_60s() {
                     None,
                     None,
                     true,
+                    None,
+                    false,
+                    None,
                 )
                 .await?;
 
@@ -349,6 +846,9 @@ fn portal_heavy_load_exchanged() {
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -375,6 +875,9 @@ fn portal_heavy_load_exchanged() {
                     None,
                     None,
                     true,
+                    None,
+                    false,
+                    None,
                 )
                 .await?;
 
@@ -487,6 +990,9 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                     Some(Address::from_string("outlet")),
                     true,
                     OutletAccessControl::IncomingAccessControl(Arc::new(AllowAll)),
+                    None,
+                    None,
+                    None,
                 )
                 .await?;
 
@@ -520,6 +1026,9 @@ fn test_portal_payload_transfer(outgoing_disruption: Disruption, incoming_disrup
                     None,
                     None,
                     true,
+                    None,
+                    false,
+                    None,
                 )
                 .await?;
 
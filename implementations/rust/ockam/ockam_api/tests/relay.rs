@@ -0,0 +1,337 @@
+use ockam::remote::RemoteRelayFilter;
+use ockam_api::cloud::project::models::ProjectModel;
+use ockam_api::cloud::project::Project;
+use ockam_api::nodes::relay_chain::{create_relay_chain, RelayChainHop, RelayChainOptions};
+use ockam_api::nodes::NODEMANAGER_ADDR;
+use ockam_api::test_utils::{assert_tcp_echo, eventually, start_tcp_echo_server, TestCluster};
+use ockam_api::ConnectionStatus;
+use ockam_core::errcode::Kind;
+use ockam_multiaddr::MultiAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[test]
+fn relay_fails_over_to_next_destination() {
+    // in this test we create three nodes, then:
+    //  - create a relay on the first node, at the second node, with the third node as failover
+    //  - bring down the second node
+    //  - verify that the relay is re-created at the third node
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            let primary = cluster.secure_api_address(1).await?;
+            let failover = cluster.secure_api_address(2).await?;
+
+            let first_node = cluster.node(0);
+            let relay_info = first_node
+                .node_manager
+                .create_relay(
+                    &first_node.context,
+                    &primary,
+                    "relay_alias".to_string(),
+                    true,
+                    None,
+                    Some("relay_alias".to_string()),
+                    vec![failover.clone()],
+                    false,
+                    RemoteRelayFilter::new(),
+                )
+                .await?;
+
+            assert_eq!(relay_info.connection_status(), ConnectionStatus::Up);
+            assert_eq!(relay_info.active_destination_address(), Some(&primary));
+            assert_eq!(relay_info.failover_addresses(), &[failover.clone()]);
+            assert!(relay_info.last_destination_change().is_none());
+
+            cluster.stop_node(1).await?;
+
+            // now let's verify the relay has moved to the failover destination
+            let failover = &failover;
+            eventually(Duration::from_secs(60), || async move {
+                let relay_info = first_node
+                    .node_manager
+                    .get_relays()
+                    .await
+                    .into_iter()
+                    .find(|r| r.alias() == "relay_alias")
+                    .unwrap();
+                relay_info.connection_status() == ConnectionStatus::Up
+                    && relay_info.active_destination_address() == Some(failover)
+                    && relay_info.last_destination_change().is_some()
+            })
+            .await
+        });
+}
+
+#[test]
+fn inlet_route_is_resolved_through_a_project_relay() {
+    // in this test we create three nodes:
+    //  - the first node plays the role of the project
+    //  - the second node creates a relay named "x" at the first node
+    //  - the third node resolves the route to an outlet through that relay
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            cluster.create_relay(1, 0, "x").await?;
+            store_project(&cluster, 0, 2).await?;
+
+            // the resolved route is the route users used to write by hand
+            let inlet_node = cluster.node(2);
+            let outlet = MultiAddr::from_str("/service/outlet")?;
+            let resolved = inlet_node
+                .node_manager
+                .resolve_relay_route(&inlet_node.context, "x", &outlet)
+                .await?;
+            assert_eq!(
+                resolved,
+                MultiAddr::from_str("/project/p1/service/forward_to_x/secure/api/service/outlet")?
+            );
+
+            // an unknown relay is reported as such
+            let error = inlet_node
+                .node_manager
+                .resolve_relay_route(&inlet_node.context, "unknown", &outlet)
+                .await
+                .unwrap_err();
+            assert!(
+                error.to_string().contains(
+                    "The relay segment of the route could not be resolved: the relay 'unknown' was not found in the project 'p1'"
+                ),
+                "{error}"
+            );
+
+            Ok(())
+        });
+}
+
+#[test]
+fn renamed_relay_is_reachable_with_its_new_name() {
+    // in this test we create three nodes:
+    //  - the first node plays the role of the project
+    //  - the second node creates a relay named "x" at the first node, then renames it "y"
+    //  - the third node can reach the relay with its new name only
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            cluster.create_relay(1, 0, "x").await?;
+            cluster.create_relay(1, 0, "z").await?;
+
+            // a relay can't be renamed with the name of another relay
+            let outlet_node = cluster.node(1);
+            let error = outlet_node
+                .node_manager
+                .rename_relay(&outlet_node.context, "x", "z".to_string())
+                .await
+                .unwrap_err();
+            assert_eq!(error.code().kind, Kind::AlreadyExists);
+
+            let relay_info = outlet_node
+                .node_manager
+                .rename_relay(&outlet_node.context, "x", "y".to_string())
+                .await?;
+            assert_eq!(relay_info.alias(), "y");
+            assert_eq!(relay_info.connection_status(), ConnectionStatus::Up);
+            let aliases: Vec<String> = outlet_node
+                .node_manager
+                .get_relays()
+                .await
+                .iter()
+                .map(|r| r.alias().to_string())
+                .collect();
+            assert!(aliases.contains(&"y".to_string()));
+            assert!(!aliases.contains(&"x".to_string()));
+
+            // the relay is registered with its new name only
+            store_project(&cluster, 0, 2).await?;
+            let inlet_node = cluster.node(2);
+            let outlet = MultiAddr::from_str("/service/outlet")?;
+            let resolved = inlet_node
+                .node_manager
+                .resolve_relay_route(&inlet_node.context, "y", &outlet)
+                .await?;
+            assert_eq!(
+                resolved,
+                MultiAddr::from_str("/project/p1/service/forward_to_y/secure/api/service/outlet")?
+            );
+            let result = inlet_node
+                .node_manager
+                .resolve_relay_route(&inlet_node.context, "x", &outlet)
+                .await;
+            assert!(result.is_err());
+
+            Ok(())
+        });
+}
+
+#[test]
+fn relay_filter_restricts_the_reachable_services() {
+    // in this test we create three nodes:
+    //  - the first node plays the role of the project
+    //  - the second node creates a relay named "x" at the first node, denying its node manager
+    //  - the third node still reaches an outlet of the second node through the relay
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            let filter = RemoteRelayFilter::new().deny(NODEMANAGER_ADDR);
+            let relay_info = cluster.create_relay_with_filter(1, 0, "x", filter).await?;
+            assert!(relay_info.allowed_services().is_empty());
+            assert_eq!(
+                relay_info.denied_services(),
+                &[NODEMANAGER_ADDR.to_string()]
+            );
+
+            // the filter is kept when the relay is renamed
+            let outlet_node = cluster.node(1);
+            let relay_info = outlet_node
+                .node_manager
+                .rename_relay(&outlet_node.context, "x", "y".to_string())
+                .await?;
+            assert_eq!(
+                relay_info.denied_services(),
+                &[NODEMANAGER_ADDR.to_string()]
+            );
+
+            // the secure channel listener of the node can still be reached through the relay
+            let echo_server = start_tcp_echo_server().await;
+            cluster
+                .create_outlet(1, echo_server.chosen_addr, "outlet")
+                .await?;
+            let outlet = cluster.address(0).await?.concat(&MultiAddr::from_str(
+                "/service/forward_to_y/secure/api/service/outlet",
+            )?)?;
+            let inlet = cluster.create_inlet_to(2, outlet, "inlet").await?;
+            assert_tcp_echo(&inlet.bind_addr, b"hello").await;
+
+            Ok(())
+        });
+}
+
+#[test]
+fn relay_chain_reaches_the_first_node_through_all_the_hops() {
+    // in this test we create three nodes:
+    //  - the first node creates a relay at the second node
+    //  - the second node creates a relay named "edge" at the third node
+    //  - an outlet of the first node is reached through the third node, then the second one
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            let edge_node = cluster.node(0);
+            let gateway_node = cluster.node(1);
+            let hops = vec![
+                RelayChainHop::new(
+                    &*edge_node.node_manager,
+                    cluster.secure_api_address(1).await?,
+                ),
+                RelayChainHop::new(
+                    &*gateway_node.node_manager,
+                    cluster.secure_api_address(2).await?,
+                ),
+            ];
+            let chain =
+                create_relay_chain(&edge_node.context, "edge", &hops, &RelayChainOptions::new())
+                    .await
+                    .unwrap();
+
+            // the relay at the last hop uses the given alias, the other one a generated alias
+            assert_eq!(chain.relays.len(), 2);
+            let generated_alias = chain.relays[0].alias.clone();
+            assert!(generated_alias.starts_with("edge-"));
+            assert_eq!(chain.relays[1].alias, "edge");
+            assert_eq!(
+                chain.address,
+                cluster
+                    .secure_api_address(2)
+                    .await?
+                    .concat(&MultiAddr::from_str(&format!(
+                        "/service/forward_to_edge/service/forward_to_{generated_alias}"
+                    ))?)?
+            );
+
+            // a client of the third node reaches an outlet of the first node
+            let echo_server = start_tcp_echo_server().await;
+            cluster
+                .create_outlet(0, echo_server.chosen_addr, "outlet")
+                .await?;
+            let outlet = chain
+                .address
+                .concat(&MultiAddr::from_str("/secure/api/service/outlet")?)?;
+            let inlet = cluster.create_inlet_to(2, outlet, "inlet").await?;
+            assert_tcp_echo(&inlet.bind_addr, b"hello").await;
+
+            Ok(())
+        });
+}
+
+#[test]
+fn relay_chain_is_rolled_back_when_the_last_hop_fails() {
+    // in this test we create three nodes:
+    //  - the first node creates a relay at the second node
+    //  - the second node fails to create its relay, at an unreachable address
+    //  - the relay of the first node is deleted, unless the partial chain must be kept
+    TestCluster::builder()
+        .with_nodes(3)
+        .run(|cluster| async move {
+            let edge_node = cluster.node(0);
+            let gateway_node = cluster.node(1);
+            let unreachable = MultiAddr::from_str("/ip4/127.0.0.1/tcp/1/secure/api")?;
+            let hops = vec![
+                RelayChainHop::new(
+                    &*edge_node.node_manager,
+                    cluster.secure_api_address(1).await?,
+                ),
+                RelayChainHop::new(&*gateway_node.node_manager, unreachable),
+            ];
+
+            let error =
+                create_relay_chain(&edge_node.context, "edge", &hops, &RelayChainOptions::new())
+                    .await
+                    .unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("The relays already created were deleted"),
+                "{error}"
+            );
+            assert!(edge_node.node_manager.get_relays().await.is_empty());
+            assert!(gateway_node.node_manager.get_relays().await.is_empty());
+
+            // the relay of the first node is kept when requested
+            let error = create_relay_chain(
+                &edge_node.context,
+                "edge",
+                &hops,
+                &RelayChainOptions::new().with_keep_partial(true),
+            )
+            .await
+            .unwrap_err();
+            assert!(
+                error
+                    .to_string()
+                    .contains("The relays already created were kept"),
+                "{error}"
+            );
+            let relays = edge_node.node_manager.get_relays().await;
+            assert_eq!(relays.len(), 1);
+            assert!(relays[0].alias().starts_with("edge-"));
+
+            Ok(())
+        });
+}
+
+/// Store a project named "p1", served by the node `project`, in the state of the node `at`
+async fn store_project(cluster: &TestCluster, project: usize, at: usize) -> ockam::Result<()> {
+    let project = Project::import(ProjectModel {
+        name: "p1".to_string(),
+        access_route: cluster.address(project).await?.to_string(),
+        identity: Some(cluster.node(project).node_manager.identifier()),
+        ..Default::default()
+    })
+    .await?;
+    cluster
+        .node(at)
+        .cli_state
+        .projects()
+        .store_project(project)
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ockam::identity::models::CredentialAndPurposeKey;
+use ockam::identity::secure_channels;
+use ockam::identity::utils::now;
+use ockam::identity::{SecureChannelListenerOptions, SecureChannelOptions};
+use ockam::route;
+use ockam_api::authenticator::credential_issuer::CredentialIssuerWorker;
+use ockam_api::authenticator::events::AuthorityEvents;
+use ockam_api::authenticator::{
+    AuthorityEventsSqlxDatabase, AuthorityMember, AuthorityMembersRepository,
+    AuthorityMembersSqlxDatabase, AuthorityRevocationsRepository, AuthorityRevocationsSqlxDatabase,
+};
+use ockam_core::api::Request;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::{Address, Result};
+use ockam_node::api::Client;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn revoked_credentials_are_rejected(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let authority = identities.identities_creation().create_identity().await?;
+    let member = identities.identities_creation().create_identity().await?;
+    let verifier = identities.identities_creation().create_identity().await?;
+
+    let members = Arc::new(AuthorityMembersSqlxDatabase::create().await?);
+    members
+        .add_member(AuthorityMember::new(
+            member.clone(),
+            BTreeMap::from([(b"role".to_vec(), b"member".to_vec())]),
+            authority.clone(),
+            now()?,
+            false,
+        ))
+        .await?;
+    let revocations = Arc::new(AuthorityRevocationsSqlxDatabase::create().await?);
+
+    // Start the credential issuer of the authority
+    let issuer_listener = Address::random_local();
+    let issuer_address = Address::random_local();
+    let options = SecureChannelListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer(issuer_address.clone(), &options.spawner_flow_control_id());
+    secure_channels
+        .create_secure_channel_listener(ctx, &authority, issuer_listener.clone(), options)
+        .await?;
+    let issuer = CredentialIssuerWorker::new(
+        members,
+        revocations.clone(),
+        identities.credentials(),
+        &authority,
+        None,
+        None,
+        None,
+        AuthorityEvents::new(Arc::new(AuthorityEventsSqlxDatabase::create().await?)),
+    );
+    ctx.start_worker(issuer_address.clone(), issuer).await?;
+
+    // The member gets a credential and presents it on its secure channel listener
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &member,
+            route![issuer_listener.clone()],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = Client::new(&route![channel, issuer_address.clone()], None);
+    let credential: CredentialAndPurposeKey =
+        client.ask(ctx, Request::post("/")).await?.success()?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &member,
+            "member_listener",
+            SecureChannelListenerOptions::new().with_credential(credential)?,
+        )
+        .await?;
+
+    // The verifier accepts the credential of the member
+    let connect = || {
+        secure_channels.create_secure_channel(
+            ctx,
+            &verifier,
+            route!["member_listener"],
+            SecureChannelOptions::new()
+                .with_authority(authority.clone())
+                .with_timeout(Duration::from_millis(500)),
+        )
+    };
+    assert!(connect().await.is_ok());
+
+    // The authority revokes the member, and the verifier retrieves the new revocation list
+    let version = revocations
+        .revoke(&member, now()?, Some("lost device".to_string()))
+        .await?;
+    assert_eq!(version, 1);
+
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &verifier,
+            route![issuer_listener],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = Client::new(&route![channel, issuer_address], None);
+    let revocation_list: CredentialAndPurposeKey = client
+        .ask(ctx, Request::get("/revocations"))
+        .await?
+        .success()?;
+    let credentials_verification = identities.credentials().credentials_verification();
+    assert!(
+        credentials_verification
+            .receive_revocation_list(&authority, &revocation_list)
+            .await?
+    );
+    // the same version is not stored twice
+    assert!(
+        !credentials_verification
+            .receive_revocation_list(&authority, &revocation_list)
+            .await?
+    );
+
+    // The same credential is now rejected by the verifier
+    assert!(connect().await.is_err());
+    Ok(())
+}
@@ -1,11 +1,16 @@
 use crate::common::common::{change_client_identifier, start_authority, AuthorityInfo};
+use ockam::identity::models::CredentialAndPurposeKey;
 use ockam::identity::secure_channels;
 use ockam::identity::utils::now;
+use ockam_api::authenticator::credential_issuer::DEFAULT_CREDENTIAL_VALIDITY;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
 use ockam_api::authenticator::enrollment_tokens::{TokenAcceptor, TokenIssuer};
+use ockam_api::cloud::HasSecureClient;
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_core::api::Request;
 use ockam_core::Result;
 use ockam_node::Context;
 use std::collections::BTreeMap;
@@ -22,7 +27,7 @@ async fn admin_can_issue_token(ctx: &mut Context) -> Result<()> {
 
     admin
         .client
-        .create_token(ctx, Default::default(), None, None)
+        .create_token(ctx, Default::default(), None, None, None)
         .await
         .unwrap();
 
@@ -30,19 +35,25 @@ async fn admin_can_issue_token(ctx: &mut Context) -> Result<()> {
     attributes.insert("KEY".to_string(), "VALUE".to_string());
     admin
         .client
-        .create_token(ctx, attributes, None, None)
+        .create_token(ctx, attributes, None, None, None)
         .await
         .unwrap();
 
     admin
         .client
-        .create_token(ctx, Default::default(), Some(Duration::from_secs(30)), None)
+        .create_token(
+            ctx,
+            Default::default(),
+            Some(Duration::from_secs(30)),
+            None,
+            None,
+        )
         .await
         .unwrap();
 
     admin
         .client
-        .create_token(ctx, Default::default(), None, Some(5))
+        .create_token(ctx, Default::default(), None, Some(5), None)
         .await
         .unwrap();
 
@@ -58,7 +69,7 @@ async fn admin_can_accept_token(ctx: &mut Context) -> Result<()> {
 
     let otc = admin
         .client
-        .create_token(ctx, Default::default(), None, None)
+        .create_token(ctx, Default::default(), None, None, None)
         .await
         .unwrap();
 
@@ -84,7 +95,7 @@ async fn admin_can_add_enroller(ctx: &mut Context) -> Result<()> {
     );
     let otc = admin
         .client
-        .create_token(ctx, attributes.clone(), None, None)
+        .create_token(ctx, attributes.clone(), None, None, None)
         .await
         .unwrap();
 
@@ -132,7 +143,7 @@ async fn admin_can_add_member(ctx: &mut Context) -> Result<()> {
     attributes.insert("KEY".to_string(), "VALUE".to_string());
     let otc = admin
         .client
-        .create_token(ctx, attributes.clone(), None, None)
+        .create_token(ctx, attributes.clone(), None, None, None)
         .await
         .unwrap();
 
@@ -183,7 +194,7 @@ async fn enroller_can_add_member(ctx: &mut Context) -> Result<()> {
     );
     let otc = admin
         .client
-        .create_token(ctx, attributes.clone(), None, None)
+        .create_token(ctx, attributes.clone(), None, None, None)
         .await
         .unwrap();
 
@@ -200,7 +211,7 @@ async fn enroller_can_add_member(ctx: &mut Context) -> Result<()> {
     attributes_member.insert("KEY".to_string(), "VALUE".to_string());
 
     let otc = enroller_client
-        .create_token(ctx, attributes_member.clone(), None, None)
+        .create_token(ctx, attributes_member.clone(), None, None, None)
         .await
         .unwrap();
 
@@ -250,7 +261,7 @@ async fn enroller_cant_add_enroller(ctx: &mut Context) -> Result<()> {
     );
     let otc = admin
         .client
-        .create_token(ctx, attributes.clone(), None, None)
+        .create_token(ctx, attributes.clone(), None, None, None)
         .await
         .unwrap();
 
@@ -264,7 +275,7 @@ async fn enroller_cant_add_enroller(ctx: &mut Context) -> Result<()> {
     enroller_client.present_token(ctx, otc).await.unwrap();
 
     let res = enroller_client
-        .create_token(ctx, attributes.clone(), None, None)
+        .create_token(ctx, attributes.clone(), None, None, None)
         .await;
 
     assert!(res.is_err());
@@ -287,6 +298,7 @@ async fn token_expiration(ctx: &mut Context) -> Result<()> {
             Default::default(),
             Some(Duration::from_secs(ttl)),
             None,
+            None,
         )
         .await
         .unwrap();
@@ -318,7 +330,7 @@ async fn usage_count_default(ctx: &mut Context) -> Result<()> {
 
     let otc = admin
         .client
-        .create_token(ctx, Default::default(), None, None)
+        .create_token(ctx, Default::default(), None, None, None)
         .await
         .unwrap();
 
@@ -360,7 +372,7 @@ async fn usage_count2(ctx: &mut Context) -> Result<()> {
 
     let otc = admin
         .client
-        .create_token(ctx, Default::default(), None, Some(2))
+        .create_token(ctx, Default::default(), None, Some(2), None)
         .await
         .unwrap();
 
@@ -404,3 +416,73 @@ async fn usage_count2(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn credential_ttl_of_the_token_limits_the_issued_credentials(
+    ctx: &mut Context,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let credential_ttl = Duration::from_secs(15 * 60);
+    let otc_with_ttl = admin
+        .client
+        .create_token(ctx, Default::default(), None, None, Some(credential_ttl))
+        .await
+        .unwrap();
+    let otc_without_ttl = admin
+        .client
+        .create_token(ctx, Default::default(), None, None, None)
+        .await
+        .unwrap();
+
+    let member1 = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_client1 = change_client_identifier(&admin.client, &member1, None);
+    member_client1
+        .present_token(ctx, otc_with_ttl)
+        .await
+        .unwrap();
+
+    let member2 = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let member_client2 = change_client_identifier(&admin.client, &member2, None);
+    member_client2
+        .present_token(ctx, otc_without_ttl)
+        .await
+        .unwrap();
+
+    // The credential of the member enrolled with the first token expires within the hinted time to live
+    let credential: CredentialAndPurposeKey = member_client1
+        .get_secure_client()
+        .ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, Request::post("/"))
+        .await?
+        .success()?;
+    let data = credential.credential.get_credential_data()?;
+    assert_eq!(
+        data.expires_at.0 - data.created_at.0,
+        credential_ttl.as_secs()
+    );
+
+    // The other member gets a credential with the default time to live
+    let credential: CredentialAndPurposeKey = member_client2
+        .get_secure_client()
+        .ask(ctx, DefaultAddress::CREDENTIAL_ISSUER, Request::post("/"))
+        .await?
+        .success()?;
+    let data = credential.credential.get_credential_data()?;
+    assert_eq!(
+        data.expires_at.0 - data.created_at.0,
+        DEFAULT_CREDENTIAL_VALIDITY.as_secs()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use ockam_api::nodes::topology::{TopologyEdge, TopologyEdgeKind, TopologyGraph};
+use ockam_api::test_utils::{start_tcp_echo_server, TestCluster};
+use ockam_api::ConnectionStatus;
+
+#[test]
+fn topology_of_a_portal_between_two_nodes() {
+    TestCluster::builder()
+        .with_nodes(2)
+        .run(|cluster| async move {
+            let echo_server_handle = start_tcp_echo_server().await;
+            let inlet_status = cluster
+                .create_portal(0, 1, echo_server_handle.chosen_addr)
+                .await?;
+            assert_eq!(inlet_status.status, ConnectionStatus::Up);
+
+            let mut topologies = vec![];
+            for index in 0..2 {
+                topologies.push(cluster.node(index).node_manager.get_node_topology().await?);
+            }
+            let inlet_node = topologies[0].node_name.clone();
+            let outlet_node = topologies[1].node_name.clone();
+            let inlet_identity = format!("identity:{}", topologies[0].identifier);
+            let outlet_identity = format!("identity:{}", topologies[1].identifier);
+
+            // the graph is checked through its JSON representation
+            let json = TopologyGraph::new(&topologies).to_json().unwrap();
+            let graph: TopologyGraph = serde_json::from_str(&json).unwrap();
+            let edges = |kind: TopologyEdgeKind| -> Vec<(String, String)> {
+                graph
+                    .edges
+                    .iter()
+                    .filter(|e| e.kind == kind)
+                    .map(|TopologyEdge { from, to, .. }| (from.clone(), to.clone()))
+                    .collect()
+            };
+
+            assert_eq!(
+                edges(TopologyEdgeKind::Portal),
+                vec![(
+                    format!("inlet:{inlet_node}/inlet"),
+                    format!("outlet:{outlet_node}/outlet")
+                )]
+            );
+            // the secure channel is seen from both nodes, but drawn once
+            assert_eq!(
+                edges(TopologyEdgeKind::SecureChannel),
+                vec![(inlet_identity.clone(), outlet_identity.clone())]
+            );
+            assert!(
+                edges(TopologyEdgeKind::Tcp).contains(&(inlet_identity, outlet_identity)),
+                "{json}"
+            );
+            Ok(())
+        });
+}
@@ -6,9 +6,15 @@ use ockam::identity::{SecureChannelListenerOptions, SecureChannelOptions};
 use ockam::node;
 use ockam_api::echoer::Echoer;
 use ockam_core::{route, Address, AsyncTryClone, OpenTelemetryContext, Routed, Worker};
+#[cfg(feature = "tracing_context")]
+use ockam_core::{TransportMessage, HOP, HOP_NODE_NAME, ONWARD_ROUTE};
 use ockam_node::Context;
 use ockam_transport_core::Transport;
 use ockam_transport_tcp::{TcpListenerOptions, TcpTransportExtension, TCP};
+#[cfg(feature = "tracing_context")]
+use opentelemetry::Key;
+#[cfg(feature = "tracing_context")]
+use opentelemetry_sdk::export::trace::SpanData;
 use std::time::Duration;
 use tonic::async_trait;
 use tracing::instrument;
@@ -157,8 +163,64 @@ root
     pretty_assertions::assert_eq!(format!("\n{actual}"), expected);
 }
 
+/// This test checks that the spans of a transport message record its onward route
+/// after each hop modifying that route
+#[cfg(feature = "tracing_context")]
+#[test]
+fn test_transport_message_spans_record_the_route_of_each_hop() {
+    let (_, spans) = trace_code(|_| async {
+        let message = TransportMessage::v1(route!["node1", "node2", "echoer"], route![], vec![])
+            .start_new_tracing_context(OpenTelemetryContext::current());
+        let message = message.update_tracing_for_hop("node1", route!["node2", "echoer"]);
+        message.update_tracing_for_hop("node2", route!["echoer"])
+    });
+
+    let mut hops: Vec<&SpanData> = spans
+        .iter()
+        .filter(|s| s.name == "TransportMessage::start_trace" || s.name == "TransportMessage::hop")
+        .collect();
+    hops.sort_by_key(|s| span_attribute(s, HOP));
+    assert_eq!(hops.len(), 3);
+
+    let expected = [
+        (None, route!["node1", "node2", "echoer"]),
+        (Some("node1"), route!["node2", "echoer"]),
+        (Some("node2"), route!["echoer"]),
+    ];
+    for (hop, (span, (node_name, onward_route))) in hops.iter().zip(expected).enumerate() {
+        assert_eq!(span_attribute(span, HOP), Some(hop.to_string()));
+        assert_eq!(
+            span_attribute(span, ONWARD_ROUTE),
+            Some(onward_route.to_string())
+        );
+        assert_eq!(
+            span_attribute(span, HOP_NODE_NAME),
+            node_name.map(|n| n.to_string())
+        );
+    }
+
+    // each hop is a child of the previous one, in the trace started for the transport message,
+    // which is linked to the previous trace
+    for (previous, hop) in hops.iter().tuple_windows() {
+        assert_eq!(hop.parent_span_id, previous.span_context.span_id());
+        assert_eq!(
+            hop.span_context.trace_id(),
+            previous.span_context.trace_id()
+        );
+    }
+}
+
 /// HELPERS
 
+/// Return the value of a span attribute as a string
+#[cfg(feature = "tracing_context")]
+fn span_attribute(span: &SpanData, key: &Key) -> Option<String> {
+    span.attributes
+        .iter()
+        .find(|kv| &kv.key == key)
+        .map(|kv| kv.value.to_string())
+}
+
 /// Start 2 nodes:
 ///
 ///  - 1 node with a MessageSender worker
@@ -169,6 +169,8 @@ impl AppState {
                     .await?
             }
         };
+        // set the selected space as the default one
+        self.state().await.set_space_as_default(&space.id).await?;
 
         Ok(space)
     }
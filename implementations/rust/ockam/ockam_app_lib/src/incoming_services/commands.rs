@@ -221,6 +221,10 @@ impl AppState {
                 &Some(expr),
                 Duration::from_secs(5),
                 true,
+                None,
+                None,
+                false,
+                None,
             )
             .await
             .map_err(|err| {
@@ -42,6 +42,7 @@ impl AppState {
                 BTreeMap::from([("invitation_email".to_string(), invitation_email.to_string())]),
                 Some(Duration::from_secs(60 * 60 * 24 * 14)),
                 None,
+                None,
             )
             .await?;
         Ok(EnrollmentTicket::new(otc, Some(project.model().clone())))
@@ -4,6 +4,7 @@ use std::sync::Arc;
 use miette::IntoDiagnostic;
 use tracing::{debug, info, trace, warn};
 
+use ockam::remote::RemoteRelayFilter;
 use ockam::Context;
 use ockam_api::cli_state::CliState;
 use ockam_api::nodes::models::relay::RelayInfo;
@@ -91,6 +92,9 @@ impl AppState {
                             false,
                             None,
                             Some(relay_alias),
+                            vec![],
+                            false,
+                            RemoteRelayFilter::new(),
                         )
                         .await
                         .into_diagnostic()?;
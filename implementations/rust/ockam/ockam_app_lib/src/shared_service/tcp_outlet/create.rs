@@ -35,6 +35,9 @@ impl AppState {
                 OutletAccessControl::IncomingAccessControl(
                     self.create_invitations_access_control(worker_addr).await?,
                 ),
+                None,
+                None,
+                None,
             )
             .await
         {
@@ -58,6 +58,9 @@ impl AppState {
                     Some(tcp_outlet.worker_addr.clone()),
                     true,
                     OutletAccessControl::IncomingAccessControl(access_control),
+                    None,
+                    None,
+                    None,
                 )
                 .await
                 .map_err(|e| {
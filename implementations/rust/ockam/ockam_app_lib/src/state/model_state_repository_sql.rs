@@ -112,11 +112,11 @@ impl TcpOutletStatusRow {
         let socket_addr = SocketAddr::from_str(&self.socket_addr)
             .map_err(|e| Error::new(Origin::Application, Kind::Serialization, e.to_string()))?;
         let worker_addr = Address::from_string(&self.worker_addr);
-        Ok(OutletStatus {
+        Ok(OutletStatus::new(
             socket_addr,
             worker_addr,
-            payload: self.payload.clone(),
-        })
+            self.payload.clone(),
+        ))
     }
 }
 
@@ -1,3 +1,8 @@
+use ockam_api::redaction::{redact_text, REDACTED};
+
+/// Options whose values are secrets
+const SECRET_OPTIONS: &[&str] = &["--passphrase"];
+
 /// Return true if the list of arguments contains a help flag
 pub fn has_help_flag(input: &[String]) -> bool {
     input.contains(&"-h".to_string()) || input.contains(&"--help".to_string())
@@ -47,3 +52,62 @@ pub fn replace_hyphen_with_stdin(s: String) -> String {
         s
     }
 }
+
+/// Return the arguments without the secrets they contain, so that they can be logged:
+/// the values of the secret options are removed, as well as the inlined tickets and keys
+pub fn redact_arguments(arguments: &[String]) -> Vec<String> {
+    let mut redacted = Vec::with_capacity(arguments.len());
+    let mut is_secret_value = false;
+    for argument in arguments {
+        if is_secret_value {
+            redacted.push(REDACTED.to_string());
+            is_secret_value = false;
+            continue;
+        }
+        match SECRET_OPTIONS
+            .iter()
+            .find(|option| argument.starts_with(&format!("{option}=")))
+        {
+            Some(option) => redacted.push(format!("{option}={REDACTED}")),
+            None => {
+                is_secret_value = SECRET_OPTIONS.contains(&argument.as_str());
+                redacted.push(redact_text(argument));
+            }
+        }
+    }
+    redacted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::authenticator::one_time_code::OneTimeCode;
+    use ockam_api::EnrollmentTicket;
+
+    #[test]
+    fn secrets_are_removed_from_the_arguments() {
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None)
+            .hex_encoded()
+            .unwrap();
+        let arguments: Vec<String> = [
+            "ockam",
+            "project",
+            "enroll",
+            &ticket,
+            "--passphrase",
+            "s3cr3t",
+            "--passphrase=s3cr3t",
+            "--identity",
+            "alice",
+        ]
+        .iter()
+        .map(|a| a.to_string())
+        .collect();
+
+        let redacted = redact_arguments(&arguments).join(" ");
+        assert!(!redacted.contains(&ticket));
+        assert!(!redacted.contains("s3cr3t"));
+        assert!(redacted.starts_with("ockam project enroll"));
+        assert!(redacted.ends_with("--identity alice"));
+    }
+}
@@ -2,6 +2,7 @@ use std::fmt::{Display, Formatter};
 
 use clap::Args;
 use miette::{miette, IntoDiagnostic};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
 
 use ockam::identity::utils::now;
@@ -16,10 +17,10 @@ use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::fmt;
 
 use crate::node::util::run_ockam;
+use crate::util::embedded_node_that_is_not_stopped;
 use crate::util::parsers::internet_address_parser;
 use crate::util::{async_cmd, local_cmd};
-use crate::util::{embedded_node_that_is_not_stopped, exitcode};
-use crate::{docs, CommandGlobalOpts, Result};
+use crate::{docs, CommandGlobalOpts, ErrorKind, Result};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -100,6 +101,11 @@ pub struct CreateCommand {
     /// for account and project administrator credentials.
     #[arg(long, value_name = "ACCOUNT_AUTHORITY_CHANGE_HISTORY", default_value = None)]
     account_authority: Option<String>,
+
+    /// URL where the authority events (issued credentials, redeemed enrollment tickets,
+    /// added and deleted members) are POSTed as JSON
+    #[arg(long, value_name = "URL", default_value = None)]
+    events_webhook: Option<Url>,
 }
 
 impl CreateCommand {
@@ -185,9 +191,13 @@ impl CreateCommand {
             args.push("--account-authority".to_string());
             args.push(acc_auth_identity.clone());
         }
+        if let Some(events_webhook) = &self.events_webhook {
+            args.push("--events-webhook".to_string());
+            args.push(events_webhook.to_string());
+        }
         args.push(self.node_name.to_string());
 
-        run_ockam(args).await
+        run_ockam(opts, &self.node_name, args).await
     }
 }
 
@@ -196,7 +206,7 @@ impl CreateCommand {
         if self.foreground {
             // Create a new node in the foreground (i.e. in this OS process)
             local_cmd(embedded_node_that_is_not_stopped(
-                opts.rt.clone(),
+                opts.node_builder(),
                 |ctx| async move { self.start_authority_node(&ctx, opts).await },
             ))
         } else {
@@ -310,6 +320,7 @@ impl CreateCommand {
             no_token_enrollment: self.no_token_enrollment,
             okta: okta_configuration,
             account_authority,
+            events_webhook: self.events_webhook.clone(),
         };
 
         authority_node::start_node(ctx, &configuration)
@@ -338,7 +349,7 @@ impl CreateCommand {
 fn parse_trusted_identities(values: &str) -> Result<TrustedIdentities> {
     serde_json::from_str::<TrustedIdentities>(values).map_err(|e| {
         crate::Error::new(
-            exitcode::CONFIG,
+            ErrorKind::InvalidInput,
             miette!("Cannot parse the trusted identities: {}", e),
         )
     })
@@ -0,0 +1,180 @@
+use clap::{Args, Subcommand};
+use miette::miette;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use ockam::identity::TimestampInSeconds;
+use ockam::Context;
+use ockam_api::authenticator::events::Events;
+use ockam_api::authenticator::AuthorityEvent;
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::output::Output;
+use crate::project_member::{create_authority_client, get_project};
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts, ErrorKind, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/events/long_about.txt");
+const LIST_LONG_ABOUT: &str = include_str!("./static/events/list/long_about.txt");
+
+/// Inspect the events produced by an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct EventsCommand {
+    #[command(subcommand)]
+    subcommand: EventsSubcommand,
+}
+
+impl EventsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            EventsSubcommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            EventsSubcommand::List(c) => c.name(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum EventsSubcommand {
+    #[command(display_order = 800)]
+    List(ListCommand),
+}
+
+/// List the events produced by an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LIST_LONG_ABOUT),
+)]
+pub struct ListCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Route to the project whose authority events are requested
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+
+    /// Only list the events created at or after this time.
+    /// Either a RFC 3339 date, for example 2024-02-15T10:00:00Z, or a number of seconds since the Unix epoch
+    #[arg(long, value_name = "TIMESTAMP", value_parser = parse_since)]
+    since: Option<TimestampInSeconds>,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "authority events list".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = get_project(&opts.state, &self.to).await?;
+
+        let node = InMemoryNode::start_with_project_name(
+            ctx,
+            &opts.state,
+            Some(project.name().to_string()),
+        )
+        .await?;
+
+        let authority_node_client =
+            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
+
+        let events = authority_node_client
+            .list_events(ctx, self.since)
+            .await?
+            .into_iter()
+            .map(EventOutput)
+            .collect::<Vec<_>>();
+
+        let plain = opts.terminal.build_list(
+            &events,
+            "Events",
+            "No events found on that Authority node.",
+        )?;
+        let json = events.iter().map(|e| &e.0).collect::<Vec<_>>();
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json!(&json))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+struct EventOutput(AuthorityEvent);
+
+impl Output for EventOutput {
+    fn output(&self) -> Result<String> {
+        let event = &self.0;
+        let created_at = OffsetDateTime::from_unix_timestamp(*event.created_at as i64)
+            .ok()
+            .and_then(|t| t.format(&Rfc3339).ok())
+            .unwrap_or_else(|| event.created_at.0.to_string());
+        let mut output = format!("{} {} {}", created_at, event.kind, event.subject);
+        if let Some(actor) = &event.actor {
+            output.push_str(&format!(" by {actor}"));
+        }
+        if let Some(reference) = &event.reference {
+            output.push_str(&format!(" (reference: {reference})"));
+        }
+        Ok(output)
+    }
+}
+
+/// Parse a RFC 3339 date or a number of seconds since the Unix epoch
+fn parse_since(value: &str) -> Result<TimestampInSeconds> {
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Ok(TimestampInSeconds(seconds));
+    }
+    let date = OffsetDateTime::parse(value, &Rfc3339).map_err(|e| {
+        crate::Error::new(
+            ErrorKind::InvalidInput,
+            miette!("Invalid timestamp '{value}': {e}"),
+        )
+    })?;
+    u64::try_from(date.unix_timestamp())
+        .map(TimestampInSeconds)
+        .map_err(|_| {
+            crate::Error::new(
+                ErrorKind::InvalidInput,
+                miette!("The timestamp '{value}' is before the Unix epoch"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_since_accepts_seconds_and_dates() {
+        assert_eq!(
+            parse_since("1708000000").unwrap(),
+            TimestampInSeconds(1708000000)
+        );
+        assert_eq!(
+            parse_since("2024-02-15T12:26:40Z").unwrap(),
+            TimestampInSeconds(1708000000)
+        );
+        assert!(parse_since("yesterday").is_err());
+        assert!(parse_since("1960-01-01T00:00:00Z").is_err());
+    }
+}
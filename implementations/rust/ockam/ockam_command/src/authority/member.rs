@@ -0,0 +1,109 @@
+use clap::{Args, Subcommand};
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::authenticator::direct::Members;
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::project_member::{create_authority_client, get_project};
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/member/long_about.txt");
+const REVOKE_LONG_ABOUT: &str = include_str!("./static/member/revoke/long_about.txt");
+const REVOKE_AFTER_LONG_HELP: &str = include_str!("./static/member/revoke/after_long_help.txt");
+
+/// Manage the members of an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct MemberCommand {
+    #[command(subcommand)]
+    subcommand: MemberSubcommand,
+}
+
+impl MemberCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            MemberSubcommand::Revoke(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            MemberSubcommand::Revoke(c) => c.name(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum MemberSubcommand {
+    #[command(display_order = 800)]
+    Revoke(RevokeCommand),
+}
+
+/// Revoke a member, so that its credentials are rejected by the other members
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(REVOKE_LONG_ABOUT),
+after_long_help = docs::after_help(REVOKE_AFTER_LONG_HELP),
+)]
+pub struct RevokeCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Route to the project whose member is revoked
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+
+    /// Reason of the revocation, recorded by the Authority node
+    #[arg(long, value_name = "REASON")]
+    reason: Option<String>,
+
+    /// Identifier of the member, or an unambiguous prefix of at least 8 characters
+    #[arg(value_name = "IDENTIFIER")]
+    member: String,
+}
+
+impl RevokeCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "authority member revoke".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = get_project(&opts.state, &self.to).await?;
+        let member = opts.state.resolve_identifier(&self.member).await?;
+
+        let node = InMemoryNode::start_with_project_name(
+            ctx,
+            &opts.state,
+            Some(project.name().to_string()),
+        )
+        .await?;
+
+        let authority_node_client =
+            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
+
+        authority_node_client
+            .revoke_member(ctx, member.clone(), self.reason.clone())
+            .await?;
+
+        opts.terminal.stdout().plain(fmt_ok!(
+            "Identifier {} has been revoked. Its credentials will be rejected by the members which retrieved the latest revocation list",
+            member
+        ));
+
+        Ok(())
+    }
+}
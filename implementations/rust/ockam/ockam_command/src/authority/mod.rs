@@ -2,8 +2,14 @@ use crate::{docs, CommandGlobalOpts};
 use clap::Args;
 use clap::Subcommand;
 use create::CreateCommand;
+use events::EventsCommand;
+use member::MemberCommand;
+use template::TemplateCommand;
 
 mod create;
+mod events;
+mod member;
+mod template;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -23,12 +29,18 @@ impl AuthorityCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         match self.subcommand {
             AuthoritySubcommand::Create(c) => c.run(opts),
+            AuthoritySubcommand::Events(c) => c.run(opts),
+            AuthoritySubcommand::Member(c) => c.run(opts),
+            AuthoritySubcommand::Template(c) => c.run(opts),
         }
     }
 
     pub fn name(&self) -> String {
         match &self.subcommand {
             AuthoritySubcommand::Create(c) => c.name(),
+            AuthoritySubcommand::Events(c) => c.name(),
+            AuthoritySubcommand::Member(c) => c.name(),
+            AuthoritySubcommand::Template(c) => c.name(),
         }
     }
 }
@@ -37,4 +49,10 @@ impl AuthorityCommand {
 pub enum AuthoritySubcommand {
     #[command(display_order = 800)]
     Create(CreateCommand),
+    #[command(display_order = 800)]
+    Events(EventsCommand),
+    #[command(display_order = 800)]
+    Member(MemberCommand),
+    #[command(display_order = 800)]
+    Template(TemplateCommand),
 }
@@ -0,0 +1,238 @@
+use clap::{Args, Subcommand};
+use colorful::Colorful;
+use miette::miette;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ockam::Context;
+use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
+use ockam_api::authenticator::{AttributeConstraint, AuthorityAttributeTemplate};
+use ockam_api::nodes::InMemoryNode;
+use ockam_multiaddr::MultiAddr;
+
+use crate::output::Output;
+use crate::project_member::{create_authority_client, get_project};
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts, ErrorKind, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/template/long_about.txt");
+const CREATE_LONG_ABOUT: &str = include_str!("./static/template/create/long_about.txt");
+const CREATE_AFTER_LONG_HELP: &str = include_str!("./static/template/create/after_long_help.txt");
+const LIST_LONG_ABOUT: &str = include_str!("./static/template/list/long_about.txt");
+
+/// Manage the attribute templates of an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct TemplateCommand {
+    #[command(subcommand)]
+    subcommand: TemplateSubcommand,
+}
+
+impl TemplateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            TemplateSubcommand::Create(c) => c.run(opts),
+            TemplateSubcommand::List(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            TemplateSubcommand::Create(c) => c.name(),
+            TemplateSubcommand::List(c) => c.name(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TemplateSubcommand {
+    #[command(display_order = 800)]
+    Create(CreateCommand),
+    #[command(display_order = 800)]
+    List(ListCommand),
+}
+
+/// Create or replace an attribute template
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(CREATE_LONG_ABOUT),
+after_long_help = docs::after_help(CREATE_AFTER_LONG_HELP),
+)]
+pub struct CreateCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Route to the project whose authority stores the template
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+
+    /// Name of the template
+    #[arg(value_name = "NAME")]
+    template: String,
+
+    /// Attributes allowed by the template, in `key=constraint` format. You can specify this option multiple times for multiple attributes
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE", value_parser = parse_attribute, required = true)]
+    attributes: Vec<(String, AttributeConstraint)>,
+}
+
+impl CreateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "authority template create".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = get_project(&opts.state, &self.to).await?;
+
+        let node = InMemoryNode::start_with_project_name(
+            ctx,
+            &opts.state,
+            Some(project.name().to_string()),
+        )
+        .await?;
+
+        let authority_node_client =
+            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
+
+        let attributes: BTreeMap<String, AttributeConstraint> =
+            self.attributes.iter().cloned().collect();
+        authority_node_client
+            .create_attribute_template(ctx, self.template.clone(), attributes)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The attribute template {} has been created. Use it with {}",
+                self.template,
+                format!("ockam project ticket --template {}", self.template)
+            ))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+/// List the attribute templates of an Authority node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LIST_LONG_ABOUT),
+)]
+pub struct ListCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Route to the project whose authority templates are requested
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "authority template list".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = get_project(&opts.state, &self.to).await?;
+
+        let node = InMemoryNode::start_with_project_name(
+            ctx,
+            &opts.state,
+            Some(project.name().to_string()),
+        )
+        .await?;
+
+        let authority_node_client =
+            create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
+
+        let templates = authority_node_client
+            .list_attribute_templates(ctx)
+            .await?
+            .into_iter()
+            .map(TemplateOutput)
+            .collect::<Vec<_>>();
+
+        let plain = opts.terminal.build_list(
+            &templates,
+            "Attribute templates",
+            "No attribute templates found on that Authority node.",
+        )?;
+        let json = templates.iter().map(|t| &t.0).collect::<Vec<_>>();
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json!(&json))
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+struct TemplateOutput(AuthorityAttributeTemplate);
+
+impl Output for TemplateOutput {
+    fn output(&self) -> Result<String> {
+        let template = &self.0;
+        let attributes = template
+            .attributes
+            .iter()
+            .map(|(k, c)| format!("{k}={c}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        Ok(format!("{}: {}", template.name, attributes))
+    }
+}
+
+/// Parse an attribute of a template, given as `key=constraint`
+fn parse_attribute(value: &str) -> Result<(String, AttributeConstraint)> {
+    let (key, constraint) = value
+        .split_once('=')
+        .filter(|(key, constraint)| !key.is_empty() && !constraint.is_empty())
+        .ok_or_else(|| {
+            crate::Error::new(
+                ErrorKind::InvalidInput,
+                miette!("Invalid attribute '{value}', expected 'key=constraint'"),
+            )
+        })?;
+    Ok((key.to_string(), AttributeConstraint::from_str(constraint)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_attribute_reads_the_constraint() {
+        assert_eq!(
+            parse_attribute("team=@enroller").unwrap(),
+            ("team".to_string(), AttributeConstraint::Enroller)
+        );
+        assert_eq!(
+            parse_attribute("role=developer|tester").unwrap(),
+            (
+                "role".to_string(),
+                AttributeConstraint::OneOf(vec!["developer".to_string(), "tester".to_string()])
+            )
+        );
+        assert!(parse_attribute("team").is_err());
+        assert!(parse_attribute("team=").is_err());
+    }
+}
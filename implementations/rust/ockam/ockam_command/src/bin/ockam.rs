@@ -2,12 +2,12 @@
 // binary names. The issue is that we need to avoid the `ockam` binary colliding
 // with the `ockam` crate.
 
-use ockam_command::util::exitcode;
+use ockam_command::ErrorKind;
 
 fn main() {
     if let Err(e) = ockam_command::entry_point::run() {
-        // initialization errors are displayed here
+        // errors are displayed here and mapped to a stable exit code
         eprintln!("{:?}", e);
-        std::process::exit(exitcode::SOFTWARE);
+        std::process::exit(ErrorKind::from_report(&e).exit_code());
     }
 }
@@ -1,18 +1,22 @@
+use crate::arguments::redact_arguments;
 use crate::command_events::{add_command_error_event, add_command_event};
 use crate::command_global_opts::CommandGlobalOpts;
 use crate::docs;
+use crate::error::ErrorOutput;
 use crate::fmt_warn;
 use crate::global_args::GlobalArgs;
+use crate::output::OutputFormat;
 use crate::subcommand::OckamSubcommand;
 use crate::upgrade::check_if_an_upgrade_is_available;
 use crate::version::Version;
 
 use clap::Parser;
 use colorful::Colorful;
-use miette::GraphicalReportHandler;
+use miette::{GraphicalReportHandler, IntoDiagnostic};
 use ockam_core::OCKAM_TRACER_NAME;
 use opentelemetry::trace::{Link, SpanBuilder, TraceContextExt, Tracer};
 use opentelemetry::{global, Context};
+use std::process::exit;
 use tracing::{instrument, warn};
 
 const ABOUT: &str = include_str!("./static/about.txt");
@@ -54,6 +58,10 @@ impl OckamCommand {
             return Ok(());
         }
 
+        // The arguments are only logged and recorded in the journey events,
+        // so the secrets they might contain are removed first
+        let arguments = redact_arguments(&arguments);
+
         // Sets a hook using our own Error Report Handler
         // This allows us to customize how we
         // format the error messages and their content.
@@ -67,6 +75,13 @@ impl OckamCommand {
         }));
         let options = CommandGlobalOpts::new(&arguments, &self.global_args, &self.subcommand)?;
 
+        // Commands modifying the local state are serialized, the lock is released when the command ends
+        let _state_lock = if self.subcommand.requires_state_lock() {
+            Some(options.state.lock_for_write()?)
+        } else {
+            None
+        };
+
         if let Err(err) = check_if_an_upgrade_is_available(&options) {
             warn!("Failed to check for upgrade, error={err}");
             options
@@ -107,6 +122,14 @@ impl OckamCommand {
             )?
         };
         options.shutdown();
+        if let Err(ref e) = result {
+            // With a JSON output, the error is displayed as JSON as well so that it can be parsed
+            if options.global_args.output_format == OutputFormat::Json {
+                let output = ErrorOutput::from_report(e);
+                eprintln!("{}", serde_json::to_string(&output).into_diagnostic()?);
+                exit(output.code);
+            }
+        }
         result
     }
 
@@ -7,6 +7,7 @@ use tokio::runtime::Runtime;
 use tracing::{debug, info};
 use tracing_core::Level;
 
+use ockam::NodeBuilder;
 use ockam_api::logs::{
     crates_filter, logging_configuration, Colored, ExportingConfiguration, LoggingConfiguration,
     LoggingTracing, TracingGuard,
@@ -47,8 +48,18 @@ impl CommandGlobalOpts {
         cmd: &OckamSubcommand,
     ) -> miette::Result<Self> {
         let terminal = Terminal::from(global_args);
-        let logging_configuration =
-            Self::make_logging_configuration(global_args, cmd, terminal.is_tty())?;
+        let logging_configuration = Self::make_logging_configuration(
+            global_args,
+            cmd,
+            terminal.is_tty() && terminal.colors_enabled(),
+        )?;
+        // When the standard output only contains structured log records,
+        // the other messages are written to the standard error
+        let terminal = if logging_configuration.is_structured_on_console() {
+            terminal.set_structured_logs()
+        } else {
+            terminal
+        };
         let tracing_configuration = Self::make_tracing_configuration(global_args, cmd)?;
         let tracing_guard =
             Self::setup_logging_tracing(cmd, &logging_configuration, &tracing_configuration);
@@ -62,7 +73,9 @@ impl CommandGlobalOpts {
         );
 
         let state = match CliState::with_default_dir() {
-            Ok(state) => state.set_tracing_enabled(tracing_configuration.is_enabled()),
+            Ok(state) => state
+                .set_tracing_enabled(tracing_configuration.is_enabled())
+                .set_request_timeout(global_args.timeout),
             Err(err) => {
                 // If the user is trying to run `ockam reset` and the local state is corrupted,
                 // we can try to hard reset the local state.
@@ -95,7 +108,7 @@ impl CommandGlobalOpts {
                 terminal
                     .write_line(format!("\n{:?}", miette!(err.to_string())))
                     .unwrap();
-                exit(exitcode::SOFTWARE);
+                exit(exitcode::INTERNAL);
             }
         };
         Ok(Self {
@@ -136,7 +149,7 @@ impl CommandGlobalOpts {
     fn make_logging_configuration(
         global_args: &GlobalArgs,
         cmd: &OckamSubcommand,
-        is_tty: bool,
+        colored: bool,
     ) -> miette::Result<LoggingConfiguration> {
         if global_args.quiet {
             return LoggingConfiguration::off().into_diagnostic();
@@ -144,20 +157,20 @@ impl CommandGlobalOpts {
 
         let log_path = cmd.log_path();
         let crates = crates_filter().into_diagnostic()?;
-        if cmd.is_background_node() {
-            Ok(LoggingConfiguration::background(log_path, crates).into_diagnostic()?)
+        let configuration = if cmd.is_background_node() {
+            LoggingConfiguration::background(log_path, crates).into_diagnostic()?
         } else {
             let preferred_log_level = verbose_log_level(global_args.verbose);
-            let colored = if !global_args.no_color && is_tty {
-                Colored::On
-            } else {
-                Colored::Off
-            };
-            Ok(
-                logging_configuration(preferred_log_level, colored, log_path, crates)
-                    .into_diagnostic()?,
-            )
-        }
+            let colored = if colored { Colored::On } else { Colored::Off };
+            logging_configuration(preferred_log_level, colored, log_path, crates)
+                .into_diagnostic()?
+        };
+
+        // A log format selected on the command line enables logging
+        Ok(match cmd.log_format() {
+            Some(format) => configuration.set_format(format).set_enabled(),
+            None => configuration,
+        })
     }
 
     /// Create the tracing configuration, depending on the command to execute
@@ -190,6 +203,19 @@ impl CommandGlobalOpts {
         debug!("{:#?}", tracing_configuration);
     }
 
+    /// Return a builder for the node executing the command.
+    /// When the standard output only contains structured log records, panics are only logged
+    pub fn node_builder(&self) -> NodeBuilder {
+        let builder = NodeBuilder::new()
+            .no_logging()
+            .with_runtime(self.rt.clone());
+        if self.terminal.has_structured_logs() {
+            builder.no_panic_output()
+        } else {
+            builder
+        }
+    }
+
     pub fn set_quiet(&self) -> Self {
         let mut clone = self.clone();
         clone.global_args = clone.global_args.set_quiet();
@@ -220,7 +246,8 @@ impl CommandGlobalOpts {
             global_args.no_color,
             global_args.no_input,
             global_args.output_format.clone(),
-        );
+        )
+        .with_output_fields(global_args.output_fields.clone());
         Self {
             global_args,
             state,
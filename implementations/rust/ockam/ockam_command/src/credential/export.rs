@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam_api::credentials::CredentialScope;
+
+use crate::node::NodeOpts;
+use crate::util::async_cmd;
+use crate::{fmt_ok, CommandGlobalOpts};
+
+/// Export the credential presented by a node, with its purpose key attestation
+#[derive(Clone, Debug, Args)]
+pub struct ExportCommand {
+    #[command(flatten)]
+    pub node_opts: NodeOpts,
+
+    /// Scope of the credential to export. Supported scopes: project-member
+    #[arg(long, value_name = "SCOPE", default_value = "project-member")]
+    pub scope: CredentialScope,
+
+    /// Path of the file to write the CBOR-encoded credential to. The credential is written to stdout, hex-encoded, if this is not specified
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+impl ExportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "credential export".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node_name = opts
+            .state
+            .get_node_or_default(&self.node_opts.at_node)
+            .await?
+            .name();
+        let credential = opts
+            .state
+            .get_node_credential(&node_name, self.scope)
+            .await?;
+
+        match &self.output {
+            Some(path) => {
+                let bytes = credential.encode_as_cbor_bytes().into_diagnostic()?;
+                std::fs::write(path, bytes).into_diagnostic()?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "Exported the {} credential of the node {} to {}",
+                        self.scope,
+                        node_name,
+                        path.display()
+                    ))
+                    .json(serde_json::json!({ "node": node_name, "path": path }))
+                    .write_line()?;
+            }
+            None => {
+                let encoded = credential.encode_as_string().into_diagnostic()?;
+                opts.terminal
+                    .stdout()
+                    .plain(&encoded)
+                    .machine(&encoded)
+                    .json(serde_json::json!({ "node": node_name, "credential": encoded }))
+                    .write_line()?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,6 +3,7 @@ use colorful::core::StrMarker;
 use colorful::Colorful;
 use serde_json::json;
 
+pub(crate) use export::ExportCommand;
 pub(crate) use issue::IssueCommand;
 use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
 use ockam::identity::{Identifier, TimestampInSeconds};
@@ -11,10 +12,11 @@ pub(crate) use store::StoreCommand;
 pub(crate) use verify::VerifyCommand;
 
 use crate::credential::list::ListCommand;
-use crate::error::Error;
+use crate::error::{Error, ErrorKind};
 use crate::output::Output;
 use crate::{CommandGlobalOpts, Result};
 
+pub(crate) mod export;
 pub(crate) mod issue;
 pub(crate) mod list;
 pub(crate) mod store;
@@ -33,6 +35,7 @@ pub enum CredentialSubcommand {
     #[command(display_order = 900)]
     List(ListCommand),
     Issue(IssueCommand),
+    Export(ExportCommand),
     Store(StoreCommand),
     Verify(VerifyCommand),
 }
@@ -42,6 +45,7 @@ impl CredentialSubcommand {
         match &self {
             CredentialSubcommand::List(c) => c.name(),
             CredentialSubcommand::Issue(c) => c.name(),
+            CredentialSubcommand::Export(c) => c.name(),
             CredentialSubcommand::Store(c) => c.name(),
             CredentialSubcommand::Verify(c) => c.name(),
         }
@@ -53,6 +57,7 @@ impl CredentialCommand {
         match self.subcommand {
             CredentialSubcommand::List(c) => c.run(opts),
             CredentialSubcommand::Issue(c) => c.run(opts),
+            CredentialSubcommand::Export(c) => c.run(opts),
             CredentialSubcommand::Store(c) => c.run(opts),
             CredentialSubcommand::Verify(c) => c.run(opts),
         }
@@ -82,7 +87,7 @@ impl CredentialOutput {
 
         let subject = credential_data.subject.ok_or(Error::InternalError {
             error_message: "credential subject is missing".to_str(),
-            exit_code: 1,
+            kind: ErrorKind::Internal,
         })?;
 
         let mut attributes = HashMap::<String, String>::default();
@@ -128,6 +133,7 @@ impl Output for CredentialOutput {
             \tis_verified: {is_verified}\n\
             \tcreated_at:  {created_at}\n\
             \texpires_at:  {expires_at}\n\
+            \tttl:         {ttl}s\n\
             \tschema:      {schema}\n\
             \tattributes:  {attributes}\n\
             \tbinary:      {credential}",
@@ -136,6 +142,7 @@ impl Output for CredentialOutput {
             is_verified = is_verified,
             created_at = self.created_at.0,
             expires_at = self.expires_at.0,
+            ttl = self.expires_at.0.saturating_sub(self.created_at.0),
             schema = self.schema.0,
             attributes = attributes,
             credential = self.credential
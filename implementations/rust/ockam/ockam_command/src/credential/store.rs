@@ -2,12 +2,14 @@ use std::path::PathBuf;
 
 use clap::Args;
 use colorful::Colorful;
-use miette::miette;
+use miette::{miette, IntoDiagnostic};
 use tokio::sync::Mutex;
 use tokio::try_join;
 
+use ockam::identity::utils::now;
 use ockam::identity::{CredentialRepository, CredentialSqlxDatabase, Identifier};
 use ockam::Context;
+use ockam_api::credentials::CredentialIssuer;
 
 use crate::credential::verify::verify_credential;
 use crate::node::util::initialize_default_node;
@@ -65,13 +67,14 @@ impl StoreCommand {
         let send_req = async {
             let credential = match verify_credential(
                 &opts,
-                &self.issuer,
+                &CredentialIssuer::Identifier(self.issuer.clone()),
                 &self.credential,
                 &self.credential_path,
+                now().into_diagnostic()?,
             )
             .await
             {
-                Ok(credential) => credential,
+                Ok((credential, _)) => credential,
                 Err(_err) => {
                     *is_finished.lock().await = true;
                     return Err(miette!("Credential is not verified"))?;
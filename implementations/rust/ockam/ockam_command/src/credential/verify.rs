@@ -1,5 +1,4 @@
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
 
 use clap::Args;
 use colorful::Colorful;
@@ -7,26 +6,37 @@ use miette::{miette, IntoDiagnostic};
 use tokio::{sync::Mutex, try_join};
 
 use ockam::identity::models::CredentialAndPurposeKey;
-use ockam::identity::{
-    ChangeHistoryRepository, ChangeHistorySqlxDatabase, CredentialsVerification, Identifier,
-    PurposeKeyVerification,
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
+use ockam_api::credentials::{
+    decode_credential, verify_credential as verify_credential_at, CredentialIssuer,
+    VerifiedCredential,
 };
-use ockam_vault::{SoftwareVaultForVerifyingSignatures, VaultForVerifyingSignatures};
 
 use crate::util::async_cmd;
-use crate::util::parsers::identity_identifier_parser;
 use crate::{fmt_err, fmt_log, fmt_ok, CommandGlobalOpts};
 
+/// Verify a credential offline: its signatures, its issuer and its validity period
 #[derive(Clone, Debug, Args)]
 pub struct VerifyCommand {
-    #[arg(long = "issuer", value_name = "IDENTIFIER", value_parser = identity_identifier_parser)]
-    pub issuer: Identifier,
-
-    #[arg(group = "credential_value", value_name = "CREDENTIAL_STRING", long)]
+    /// Identifier of the expected issuer, or path to a file containing its change history
+    #[arg(long = "issuer", value_name = "IDENTIFIER_OR_FILE")]
+    pub issuer: String,
+
+    /// Hex-encoded credential, or path to a file containing the credential, as CBOR or hex
+    #[arg(
+        group = "credential_value",
+        value_name = "CREDENTIAL_STRING_OR_FILE",
+        long
+    )]
     pub credential: Option<String>,
 
     #[arg(group = "credential_value", value_name = "CREDENTIAL_FILE", long)]
     pub credential_path: Option<PathBuf>,
+
+    /// Time at which the credential must be valid, in seconds since the Unix epoch. The local time is used if this is not specified
+    #[arg(long = "at", value_name = "TIMESTAMP")]
+    pub at: Option<u64>,
 }
 
 impl VerifyCommand {
@@ -40,30 +50,33 @@ impl VerifyCommand {
         "credential verify".into()
     }
 
-    pub fn issuer(&self) -> &Identifier {
-        &self.issuer
-    }
-
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let (is_valid, plain_text) = match verify_credential(
-            &opts,
-            self.issuer(),
-            &self.credential,
-            &self.credential_path,
-        )
-        .await
-        {
-            Ok(_) => (true, fmt_ok!("Credential is valid")),
-            Err(e) => (
-                false,
-                fmt_err!("Credential is not valid\n") + &fmt_log!("{}", e),
-            ),
+        let at = match self.at {
+            Some(at) => TimestampInSeconds(at),
+            None => now().into_diagnostic()?,
         };
+        let issuer = parse_issuer(&self.issuer).await?;
+
+        let (is_valid, plain_text, json) =
+            match verify_credential(&opts, &issuer, &self.credential, &self.credential_path, at)
+                .await
+            {
+                Ok((_, verified)) => (
+                    true,
+                    fmt_ok!("Credential is valid\n") + &fmt_log!("{}", verified),
+                    serde_json::json!({ "is_valid": true, "credential": verified }),
+                ),
+                Err(e) => (
+                    false,
+                    fmt_err!("Credential is not valid\n") + &fmt_log!("{}", e),
+                    serde_json::json!({ "is_valid": false, "error": e.to_string() }),
+                ),
+            };
 
         opts.terminal
             .stdout()
             .plain(plain_text)
-            .json(serde_json::json!({ "is_valid": is_valid }))
+            .json(json)
             .machine(is_valid.to_string())
             .write_line()?;
 
@@ -71,45 +84,50 @@ impl VerifyCommand {
     }
 }
 
+/// Parse an issuer given as an identifier, a hex-encoded change history, or a file containing a change history
+pub async fn parse_issuer(value: &str) -> miette::Result<CredentialIssuer> {
+    let value = if Path::new(value).is_file() {
+        tokio::fs::read_to_string(value).await.into_diagnostic()?
+    } else {
+        value.to_string()
+    };
+    CredentialIssuer::parse(&value).await.into_diagnostic()
+}
+
 pub async fn verify_credential(
     opts: &CommandGlobalOpts,
-    issuer: &Identifier,
+    issuer: &CredentialIssuer,
     credential: &Option<String>,
     credential_path: &Option<PathBuf>,
-) -> miette::Result<CredentialAndPurposeKey> {
+    at: TimestampInSeconds,
+) -> miette::Result<(CredentialAndPurposeKey, VerifiedCredential)> {
     opts.terminal
         .write_line(&fmt_log!("Verifying credential...\n"))?;
 
     let is_finished: Mutex<bool> = Mutex::new(false);
 
     let send_req = async {
-        let credential_as_str = match (&credential, &credential_path) {
-            (_, Some(credential_path)) => tokio::fs::read_to_string(credential_path)
-                .await
-                .into_diagnostic()?
-                .trim()
-                .to_string(),
-            (Some(credential), _) => credential.clone(),
-            _ => {
+        let credential = match read_credential(credential, credential_path).await {
+            Ok(credential) => credential,
+            Err(e) => {
                 *is_finished.lock().await = true;
-                return Err(miette!(
-                    "Credential or Credential Path argument must be provided"
-                ));
+                return Err(e);
             }
         };
 
-        let change_history_repository = ChangeHistorySqlxDatabase::new(opts.state.database());
-
-        let result = validate_encoded_credential(
-            Arc::new(change_history_repository),
-            SoftwareVaultForVerifyingSignatures::create(),
+        let result = verify_credential_at(
+            opts.state.change_history_repository(),
             issuer,
-            &credential_as_str,
+            &credential,
+            at,
         )
-        .await;
+        .await
+        .into_diagnostic();
 
         *is_finished.lock().await = true;
-        result.map_err(|e| e.wrap_err("Credential is invalid"))
+        result
+            .map(|verified| (credential, verified))
+            .map_err(|e| e.wrap_err("Credential is invalid"))
     };
 
     let output_messages = vec!["Verifying credential...".to_string()];
@@ -118,30 +136,27 @@ pub async fn verify_credential(
         .terminal
         .progress_output(&output_messages, &is_finished);
 
-    let (credential_and_purpose_key, _) = try_join!(send_req, progress_output)?;
+    let (result, _) = try_join!(send_req, progress_output)?;
 
-    Ok(credential_and_purpose_key)
+    Ok(result)
 }
 
-async fn validate_encoded_credential(
-    change_history_repository: Arc<dyn ChangeHistoryRepository>,
-    verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
-    issuer: &Identifier,
-    credential_as_str: &str,
+/// Read a credential given either as a hex-encoded string or as a file
+async fn read_credential(
+    credential: &Option<String>,
+    credential_path: &Option<PathBuf>,
 ) -> miette::Result<CredentialAndPurposeKey> {
-    let credential_and_purpose_key: CredentialAndPurposeKey =
-        minicbor::decode(&hex::decode(credential_as_str).into_diagnostic()?).into_diagnostic()?;
-    CredentialsVerification::verify_credential_static(
-        Arc::new(PurposeKeyVerification::new(
-            verifying_vault.clone(),
-            change_history_repository,
-        )),
-        verifying_vault,
-        None,
-        &[issuer.clone()],
-        &credential_and_purpose_key,
-    )
-    .await
-    .into_diagnostic()?;
-    Ok(credential_and_purpose_key)
+    let bytes = match (credential, credential_path) {
+        (_, Some(credential_path)) => tokio::fs::read(credential_path).await.into_diagnostic()?,
+        (Some(credential), _) if Path::new(credential).is_file() => {
+            tokio::fs::read(credential).await.into_diagnostic()?
+        }
+        (Some(credential), _) => credential.trim().as_bytes().to_vec(),
+        _ => {
+            return Err(miette!(
+                "Credential or Credential Path argument must be provided"
+            ))
+        }
+    };
+    decode_credential(&bytes).into_diagnostic()
 }
@@ -3,6 +3,7 @@ use std::process;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use clap::builder::BoolishValueParser;
 use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, WrapErr};
@@ -11,6 +12,9 @@ use tokio::try_join;
 use tracing::{error, info, instrument, warn};
 
 use ockam::Context;
+use ockam_api::cli_state::enrollments::{
+    credential_attributes, EnrollmentTicket, EnrollmentTicketContents, TicketPassphrase,
+};
 use ockam_api::cli_state::random_name;
 use ockam_api::cloud::enroll::auth0::*;
 use ockam_api::cloud::project::Project;
@@ -31,6 +35,7 @@ use crate::progress_display::ProgressDisplay;
 use crate::project::util::check_project_readiness;
 use crate::terminal::{color_primary, color_uri, OckamColor};
 use crate::util::async_cmd;
+use crate::value_parsers::parse_enrollment_ticket_contents;
 use crate::{docs, fmt_heading, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, Result};
 
 use r3bl_rs_utils_core::UnicodeString;
@@ -60,9 +65,22 @@ pub struct EnrollCommand {
     /// activation, and PKCE (Proof Key for Code Exchange) authorization flow. Please be
     /// careful with this option since it will open your default system browser. This
     /// option might be useful if you have already enrolled and want to re-enroll using
-    /// the same account information
-    #[arg(long)]
-    pub authorization_code_flow: bool,
+    /// the same account information.
+    ///
+    /// Use `--authorization-code-flow=off`, together with `--token`, to enroll without any
+    /// interaction, for example in a CI environment
+    #[arg(long, value_name = "on|off", num_args = 0..=1, require_equals = true, default_missing_value = "on", value_parser = BoolishValueParser::new())]
+    pub authorization_code_flow: Option<bool>,
+
+    /// Enrollment ticket created by a project administrator with `ockam project ticket`.
+    /// When it is set, the identity is enrolled with the project of the ticket without any prompt
+    /// and without opening a browser
+    #[arg(long, value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket_contents)]
+    pub token: Option<EnrollmentTicketContents>,
+
+    /// Passphrase used to encrypt the enrollment ticket with `ockam project ticket --passphrase`
+    #[arg(long, value_name = "PASSPHRASE", requires = "token")]
+    pub passphrase: Option<TicketPassphrase>,
 
     /// By default this command skips the enrollment process if the Identity you specified
     /// (using `--identity`), or the default Identity, is already enrolled, by checking
@@ -90,13 +108,21 @@ impl EnrollCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if self.is_non_interactive() {
+            return self.run_non_interactive(ctx, opts).await;
+        }
         if opts.global_args.output_format == OutputFormat::Json {
             return Err(miette::miette!(
             "This command is interactive and requires you to open a web browser to complete enrollment. \
             Please try running it again without '--output json'."
         ));
         }
-        self.run_impl(ctx, opts.clone()).await?;
+        // Resolve the identity once, so that the same identity is checked and enrolled
+        let cmd = Self {
+            identity: opts.state.get_explicit_identity_name(&self.identity)?,
+            ..self.clone()
+        };
+        cmd.run_impl(ctx, opts.clone()).await?;
         Ok(())
     }
 
@@ -105,7 +131,7 @@ impl EnrollCommand {
     skip_all, // Drop all args that passed in, as Context doesn't play nice
     fields(
         enroller = ? self.identity, // https://docs.rs/tracing/latest/tracing/
-        authorization_code_flow = % self.authorization_code_flow,
+        authorization_code_flow = ? self.authorization_code_flow,
         force = % self.force,
         skip_orchestrator_resources_creation = % self.skip_orchestrator_resources_creation,
     ))]
@@ -199,6 +225,109 @@ impl EnrollCommand {
         Ok(())
     }
 
+    /// Return true if the enrollment must be performed without any prompt or browser interaction
+    fn is_non_interactive(&self) -> bool {
+        self.authorization_code_flow == Some(false) || self.token.is_some()
+    }
+
+    /// Enroll the identity with the project of an enrollment ticket, without any user interaction.
+    /// This fails, instead of prompting the user, if some input is missing
+    #[instrument(skip_all, fields(enroller = ? self.identity))]
+    async fn run_non_interactive(
+        &self,
+        ctx: &Context,
+        mut opts: CommandGlobalOpts,
+    ) -> miette::Result<()> {
+        let ticket = match (&self.token, self.authorization_code_flow) {
+            (Some(_), Some(true)) => {
+                return Err(miette!(
+                    "The {} option cannot be used with the authorization code flow. Please use {} to enroll without any interaction",
+                    color_primary("--token"),
+                    color_primary("--authorization-code-flow=off")
+                ))
+            }
+            (Some(contents), _) => contents.ticket(self.passphrase.as_ref())?,
+            (None, _) => {
+                return Err(miette!(
+                    "An enrollment ticket is required to enroll without any interaction. Please pass it with {}. \
+                    It can be created by a project administrator with {}",
+                    color_primary("--token"),
+                    color_primary("ockam project ticket")
+                ))
+            }
+        };
+        let project = ticket.project.clone().ok_or(miette!(
+            "The enrollment ticket does not contain a project. Please ask a project administrator for a new ticket"
+        ))?;
+
+        // the user is never prompted, even if the command is run from a terminal
+        opts.terminal = opts.terminal.set_no_input();
+
+        let identity = opts
+            .state
+            .get_named_identity_or_default(&self.identity)
+            .await?;
+        let project = opts
+            .state
+            .projects()
+            .import_and_store_project(project)
+            .await?;
+        opts.state
+            .projects()
+            .set_default_project(project.project_id())
+            .await?;
+
+        let node = InMemoryNode::start_with_project_name(
+            ctx,
+            &opts.state,
+            Some(project.name().to_string()),
+        )
+        .await?;
+        let authority_node_client = node
+            .create_authority_client(
+                &project.authority_identifier().into_diagnostic()?,
+                project.authority_multiaddr().into_diagnostic()?,
+                Some(identity.name()),
+                None,
+            )
+            .await?;
+        authority_node_client
+            .present_token(ctx, &ticket.one_time_code)
+            .await
+            .wrap_err("Failed to enroll your Identity with the project authority")?;
+        let credential = authority_node_client
+            .issue_credential(ctx)
+            .await
+            .wrap_err("Failed to retrieve a credential from the project authority")?;
+        opts.state
+            .store_enrollment_attributes(
+                &identity.identifier(),
+                project.project_id(),
+                &ticket.attributes,
+                &credential_attributes(&credential)?,
+            )
+            .await?;
+
+        let identifier = identity.identifier();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Your Identity {}, with Identifier {} is now enrolled with the Project {}.",
+                color_primary(identity.name()),
+                color_primary(identifier.to_string()),
+                color_primary(project.name())
+            ))
+            .machine(identifier.to_string())
+            .json(serde_json::json!({
+                "identity": identity.name(),
+                "identifier": identifier.to_string(),
+                "project_id": project.project_id(),
+                "project_name": project.name(),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+
     /// Check if the identity is already enrolled and display a message to the user.
     async fn is_already_enrolled(
         &self,
@@ -269,7 +398,7 @@ impl EnrollCommand {
 
         // Run OIDC service
         let oidc_service = OidcService::default();
-        let token = if self.authorization_code_flow {
+        let token = if self.authorization_code_flow == Some(true) {
             oidc_service.get_token_with_pkce().await.into_diagnostic()?
         } else {
             oidc_service.get_token_interactively(opts).await?
@@ -463,6 +592,7 @@ async fn get_user_space(
             space.clone()
         }
     };
+    opts.state.set_space_as_default(&space.id).await?;
     opts.terminal.write_line(&fmt_ok!(
         "Marked {} as your default Space, {}.\n",
         color_primary(space.name.clone()),
@@ -4,6 +4,10 @@ use crate::{fmt_heading, fmt_log};
 use colorful::Colorful;
 use miette::miette;
 use miette::Diagnostic;
+use ockam_api::cli_state::CliStateError;
+use ockam_api::error::ApiError;
+use ockam_core::errcode::{Kind, Origin};
+use serde::Serialize;
 use std::fmt::{Debug, Formatter};
 
 pub type Result<T> = miette::Result<T, Error>;
@@ -63,7 +67,7 @@ pub enum Error {
     #[error("{error_message}")]
     InternalError {
         error_message: String,
-        exit_code: ExitCode,
+        kind: ErrorKind,
     },
 
     // Unavailable
@@ -82,11 +86,10 @@ pub enum Error {
 
 impl Error {
     #[track_caller]
-    pub fn new(code: ExitCode, err: miette::ErrReport) -> Self {
-        assert_ne!(code, 0, "Error's exit code can't be OK");
+    pub fn new(kind: ErrorKind, err: miette::ErrReport) -> Self {
         Error::InternalError {
             error_message: err.to_string(),
-            exit_code: code,
+            kind,
         }
     }
 
@@ -94,22 +97,192 @@ impl Error {
     pub fn arg_validation<T: Debug>(arg: &str, value: T, err: Option<&str>) -> Self {
         let err = err.map(|e| format!(": {e}")).unwrap_or_default();
         let msg = format!("invalid value '({value:?})' for '{arg}' {err}");
-        Self::new(exitcode::USAGE, miette!(msg))
+        Self::new(ErrorKind::InvalidInput, miette!(msg))
     }
 
     #[track_caller]
     pub fn new_internal_error(msg: &str) -> Self {
-        Self::new(exitcode::SOFTWARE, miette!(msg.to_string()))
+        Self::new(ErrorKind::Internal, miette!(msg.to_string()))
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::NotFound { .. } => ErrorKind::NotFound,
+            Error::Unauthorized { .. } => ErrorKind::PermissionDenied,
+            Error::NotEnrolled => ErrorKind::PermissionDenied,
+            Error::Conflict { .. } => ErrorKind::Conflict,
+            Error::InternalError { kind, .. } => *kind,
+            Error::Unavailable { .. } => ErrorKind::Network,
+        }
     }
 
     pub fn code(&self) -> ExitCode {
+        self.kind().exit_code()
+    }
+}
+
+/// Kind of error returned by a command.
+///
+/// Each kind is associated to a stable exit code, and to a stable name
+/// which is used in the JSON output of errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Internal,
+    NotFound,
+    PermissionDenied,
+    Network,
+    InvalidInput,
+    Conflict,
+}
+
+impl ErrorKind {
+    pub fn exit_code(&self) -> ExitCode {
         match self {
-            Error::NotFound { .. } => exitcode::SOFTWARE,
-            Error::Unauthorized { .. } => exitcode::NOPERM,
-            Error::NotEnrolled => exitcode::NOPERM,
-            Error::Conflict { .. } => exitcode::SOFTWARE,
-            Error::InternalError { exit_code, .. } => *exit_code,
-            Error::Unavailable { .. } => exitcode::UNAVAILABLE,
+            ErrorKind::Internal => exitcode::INTERNAL,
+            ErrorKind::NotFound => exitcode::NOT_FOUND,
+            ErrorKind::PermissionDenied => exitcode::PERMISSION_DENIED,
+            ErrorKind::Network => exitcode::NETWORK,
+            ErrorKind::InvalidInput => exitcode::INVALID_INPUT,
+            ErrorKind::Conflict => exitcode::CONFLICT,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorKind::Internal => "internal",
+            ErrorKind::NotFound => "not_found",
+            ErrorKind::PermissionDenied => "permission_denied",
+            ErrorKind::Network => "network",
+            ErrorKind::InvalidInput => "invalid_input",
+            ErrorKind::Conflict => "conflict",
+        }
+    }
+
+    /// Classify an error returned by a command.
+    ///
+    /// The chain of causes is inspected first, so that the most specific
+    /// error determines the kind. Otherwise the diagnostic code of the
+    /// report is used (`OCK404`, `404 NotFound`, etc...).
+    pub fn from_report(report: &miette::Report) -> Self {
+        report
+            .chain()
+            .find_map(Self::from_error)
+            .or_else(|| {
+                report
+                    .code()
+                    .and_then(|code| Self::from_diagnostic_code(&code.to_string()))
+            })
+            .unwrap_or(ErrorKind::Internal)
+    }
+
+    fn from_error(error: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        if let Some(e) = error.downcast_ref::<Error>() {
+            Some(e.kind())
+        } else if let Some(e) = error.downcast_ref::<CliStateError>() {
+            Self::from_cli_state_error(e)
+        } else if let Some(e) = error.downcast_ref::<ApiError>() {
+            Self::from_api_error(e)
+        } else if let Some(e) = error.downcast_ref::<ockam_core::Error>() {
+            Self::from_ockam_error(e)
+        } else if let Some(e) = error.downcast_ref::<std::io::Error>() {
+            Self::from_io_error(e)
+        } else if error.is::<ockam_multiaddr::Error>() || error.is::<std::net::AddrParseError>() {
+            Some(ErrorKind::InvalidInput)
+        } else {
+            None
+        }
+    }
+
+    fn from_cli_state_error(error: &CliStateError) -> Option<Self> {
+        match error {
+            CliStateError::Io(e) => Self::from_io_error(e),
+            CliStateError::Ockam(e) => Self::from_ockam_error(e),
+            CliStateError::AlreadyExists { .. } | CliStateError::StateLocked { .. } => {
+                Some(ErrorKind::Conflict)
+            }
+            CliStateError::ResourceNotFound { .. } => Some(ErrorKind::NotFound),
+            CliStateError::InvalidPath(_) | CliStateError::EmptyPath => {
+                Some(ErrorKind::InvalidInput)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_api_error(error: &ApiError) -> Option<Self> {
+        match error {
+            ApiError::Core(e) => Self::from_ockam_error(e),
+            ApiError::MultiAddr(_) | ApiError::Parse(_) => Some(ErrorKind::InvalidInput),
+            ApiError::Io(e) => Self::from_io_error(e),
+            ApiError::Reqwest(_) => Some(ErrorKind::Network),
+        }
+    }
+
+    fn from_ockam_error(error: &ockam_core::Error) -> Option<Self> {
+        let code = error.code();
+        match code.kind {
+            Kind::NotFound => Some(ErrorKind::NotFound),
+            Kind::AlreadyExists | Kind::Conflict => Some(ErrorKind::Conflict),
+            Kind::Invalid | Kind::Misuse | Kind::Unsupported | Kind::Serialization => {
+                Some(ErrorKind::InvalidInput)
+            }
+            Kind::Timeout | Kind::Protocol => Some(ErrorKind::Network),
+            Kind::Io if code.origin == Origin::Transport => Some(ErrorKind::Network),
+            Kind::Internal => Some(ErrorKind::Internal),
+            _ => None,
+        }
+    }
+
+    fn from_io_error(error: &std::io::Error) -> Option<Self> {
+        use std::io::ErrorKind as Io;
+        match error.kind() {
+            Io::NotFound => Some(ErrorKind::NotFound),
+            Io::PermissionDenied => Some(ErrorKind::PermissionDenied),
+            Io::AlreadyExists | Io::AddrInUse => Some(ErrorKind::Conflict),
+            Io::InvalidInput | Io::InvalidData => Some(ErrorKind::InvalidInput),
+            Io::ConnectionRefused
+            | Io::ConnectionReset
+            | Io::ConnectionAborted
+            | Io::NotConnected
+            | Io::AddrNotAvailable
+            | Io::BrokenPipe
+            | Io::TimedOut => Some(ErrorKind::Network),
+            _ => None,
+        }
+    }
+
+    /// Map diagnostic codes, which follow the HTTP status codes, to an error kind
+    fn from_diagnostic_code(code: &str) -> Option<Self> {
+        let status = code
+            .trim_start_matches("OCK")
+            .get(..3)?
+            .parse::<u16>()
+            .ok()?;
+        match status {
+            401 | 403 => Some(ErrorKind::PermissionDenied),
+            404 => Some(ErrorKind::NotFound),
+            409 => Some(ErrorKind::Conflict),
+            400..=499 => Some(ErrorKind::InvalidInput),
+            502..=504 => Some(ErrorKind::Network),
+            _ => None,
+        }
+    }
+}
+
+/// JSON representation of an error, displayed when `--output json` is used
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    pub code: ExitCode,
+    pub error_kind: &'static str,
+    pub message: String,
+}
+
+impl ErrorOutput {
+    pub fn from_report(report: &miette::Report) -> Self {
+        let kind = ErrorKind::from_report(report);
+        Self {
+            code: kind.exit_code(),
+            error_kind: kind.as_str(),
+            message: report.to_string(),
         }
     }
 }
@@ -221,30 +394,109 @@ impl miette::ReportHandler for ErrorReportHandler {
 }
 
 macro_rules! gen_from_impl {
-    ($t:ty, $c:ident) => {
+    ($t:ty, $k:ident) => {
+        impl From<$t> for Error {
+            #[track_caller]
+            fn from(e: $t) -> Self {
+                use miette::miette;
+                Error::new(ErrorKind::$k, miette!(e.to_string()))
+            }
+        }
+    };
+}
+
+gen_from_impl!(std::fmt::Error, Internal);
+gen_from_impl!(std::net::AddrParseError, InvalidInput);
+gen_from_impl!(hex::FromHexError, InvalidInput);
+gen_from_impl!(serde_bare::error::Error, InvalidInput);
+gen_from_impl!(serde_json::Error, InvalidInput);
+gen_from_impl!(serde_yaml::Error, InvalidInput);
+gen_from_impl!(
+    minicbor::encode::Error<std::convert::Infallible>,
+    InvalidInput
+);
+gen_from_impl!(minicbor::decode::Error, InvalidInput);
+gen_from_impl!(ockam_multiaddr::Error, InvalidInput);
+gen_from_impl!(time::error::Parse, InvalidInput);
+gen_from_impl!(dialoguer::Error, InvalidInput);
+
+/// The kind of these errors depends on their content
+macro_rules! gen_from_impl_classified {
+    ($t:ty, $f:ident) => {
         impl From<$t> for Error {
             #[track_caller]
             fn from(e: $t) -> Self {
                 use miette::miette;
-                Error::new(exitcode::$c, miette!(e.to_string()))
+                let kind = ErrorKind::$f(&e).unwrap_or(ErrorKind::Internal);
+                Error::new(kind, miette!(e.to_string()))
             }
         }
     };
 }
 
-gen_from_impl!(std::io::Error, IOERR);
-gen_from_impl!(std::fmt::Error, SOFTWARE);
-gen_from_impl!(std::net::AddrParseError, DATAERR);
-gen_from_impl!(hex::FromHexError, DATAERR);
-gen_from_impl!(serde_bare::error::Error, DATAERR);
-gen_from_impl!(serde_json::Error, DATAERR);
-gen_from_impl!(serde_yaml::Error, DATAERR);
-gen_from_impl!(minicbor::encode::Error<std::convert::Infallible>, DATAERR);
-gen_from_impl!(minicbor::decode::Error, DATAERR);
-gen_from_impl!(ockam::Error, SOFTWARE);
-gen_from_impl!(ockam_api::cli_state::CliStateError, SOFTWARE);
-gen_from_impl!(ockam_api::error::ApiError, SOFTWARE);
-gen_from_impl!(ockam_multiaddr::Error, SOFTWARE);
-gen_from_impl!(miette::ErrReport, SOFTWARE);
-gen_from_impl!(time::error::Parse, DATAERR);
-gen_from_impl!(dialoguer::Error, DATAERR);
+gen_from_impl_classified!(std::io::Error, from_io_error);
+gen_from_impl_classified!(ockam::Error, from_ockam_error);
+gen_from_impl_classified!(CliStateError, from_cli_state_error);
+gen_from_impl_classified!(ApiError, from_api_error);
+
+impl From<miette::ErrReport> for Error {
+    #[track_caller]
+    fn from(e: miette::ErrReport) -> Self {
+        let kind = ErrorKind::from_report(&e);
+        Error::new(kind, e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::IntoDiagnostic;
+
+    #[test]
+    fn classify_errors() {
+        let not_found: miette::Report = CliStateError::Ockam(ockam_core::Error::new(
+            Origin::Api,
+            Kind::NotFound,
+            "There is no node with name n1",
+        ))
+        .into();
+        assert_eq!(ErrorKind::from_report(&not_found), ErrorKind::NotFound);
+        assert_eq!(ErrorKind::from_report(&not_found).exit_code(), 2);
+
+        let wrapped = not_found.wrap_err("Failed to show the node");
+        assert_eq!(ErrorKind::from_report(&wrapped), ErrorKind::NotFound);
+
+        let unauthorized: miette::Report = Error::NotEnrolled.into();
+        assert_eq!(ErrorKind::from_report(&unauthorized).exit_code(), 3);
+
+        let refused: Error = std::io::Error::from(std::io::ErrorKind::ConnectionRefused).into();
+        assert_eq!(refused.code(), 4);
+
+        let invalid: Error = "/unknown/proto"
+            .parse::<ockam_multiaddr::MultiAddr>()
+            .unwrap_err()
+            .into();
+        assert_eq!(invalid.code(), 5);
+
+        let untyped = Err::<(), _>(std::fmt::Error).into_diagnostic().unwrap_err();
+        assert_eq!(ErrorKind::from_report(&untyped), ErrorKind::Internal);
+    }
+
+    #[test]
+    fn json_error_output() {
+        let report: miette::Report = Error::NotFound {
+            resource: "node".to_string(),
+            resource_name: "n1".to_string(),
+        }
+        .into();
+        let output = serde_json::to_value(ErrorOutput::from_report(&report)).unwrap();
+        assert_eq!(
+            output,
+            serde_json::json!({
+                "code": 2,
+                "error_kind": "not_found",
+                "message": "Unable to find node named n1",
+            })
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use clap::ArgAction;
 use clap::Args;
 use ockam_core::env::get_env_with_default;
+use std::time::Duration;
 
 use crate::docs;
-use crate::output::OutputFormat;
+use crate::output::{OutputField, OutputFormat};
+use crate::terminal::no_color_env;
+use crate::util::duration::duration_parser;
 
 /// Those arguments are common to all commands
 #[derive(Debug, Clone, Args)]
@@ -52,6 +55,21 @@ pub struct GlobalArgs {
     )]
     pub output_format: OutputFormat,
 
+    /// Only display some fields of the JSON output, for example `changes[0].identifier`.
+    /// A single field is displayed as plain lines, several fields as a JSON object
+    #[arg(
+    hide = docs::hide(),
+    global = true,
+    long = "output-field",
+    value_name = "PATH"
+    )]
+    pub output_fields: Vec<OutputField>,
+
+    /// Maximum time to wait for the answer of a node or of the Orchestrator, for example `500ms`, `10s` or `2m`.
+    /// A number without unit is a number of seconds
+    #[arg(global = true, long, value_name = "DURATION", value_parser = duration_parser)]
+    pub timeout: Option<Duration>,
+
     // if test_argument_parser is true, command arguments are checked
     // but the command is not executed.
     #[arg(global = true, long, hide = true)]
@@ -63,7 +81,7 @@ fn quiet_default_value() -> bool {
 }
 
 fn no_color_default_value() -> bool {
-    get_env_with_default("NO_COLOR", false).unwrap_or(false)
+    no_color_env()
 }
 
 fn no_input_default_value() -> bool {
@@ -79,6 +97,8 @@ impl Default for GlobalArgs {
             no_color: no_color_default_value(),
             no_input: no_input_default_value(),
             output_format: OutputFormat::Plain,
+            output_fields: vec![],
+            timeout: None,
             test_argument_parser: false,
         }
     }
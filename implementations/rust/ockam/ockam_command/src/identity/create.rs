@@ -7,7 +7,7 @@ use colorful::Colorful;
 use miette::IntoDiagnostic;
 use ockam::identity::models::ChangeHistory;
 use ockam::identity::IdentitiesVerification;
-use ockam_api::cli_state::random_name;
+use ockam_api::cli_state::{random_name, validate_name};
 use ockam_api::{color_primary, NamedVault};
 use ockam_node::Context;
 use ockam_vault::SoftwareVaultForVerifyingSignatures;
@@ -22,7 +22,9 @@ long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct CreateCommand {
-    #[arg(hide_default_value = true, default_value_t = random_name())]
+    /// Name of the identity.
+    /// If it is omitted, you are asked for a name, with a random name as the default value.
+    #[arg(hide_default_value = true, default_value = "")]
     pub name: String,
 
     /// Vault name to store the identity key
@@ -42,7 +44,14 @@ pub struct CreateCommand {
 impl Command for CreateCommand {
     const NAME: &'static str = "identity create";
 
-    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(mut self, _ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let name = Some(self.name.clone()).filter(|n| !n.is_empty());
+        self.name = opts.terminal.validated_value_or_prompt(
+            name,
+            "Enter a name for the identity",
+            random_name(),
+            validate_name,
+        )?;
         let _progress_display = ProgressDisplay::start(&opts);
         let vault = match &self.vault {
             Some(vault_name) => opts.state.get_or_create_named_vault(vault_name).await?,
@@ -2,7 +2,7 @@ use clap::Args;
 use colorful::Colorful;
 use console::Term;
 
-use crate::terminal::tui::DeleteCommandTui;
+use crate::terminal::tui::{DeleteCommandTui, DEFAULT_DELETE_CONCURRENCY};
 use crate::terminal::PluralTerm;
 use crate::util::async_cmd;
 use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor, Terminal, TerminalStream};
@@ -26,6 +26,10 @@ pub struct DeleteCommand {
 
     #[arg(long, short)]
     all: bool,
+
+    /// Maximum number of identities deleted at the same time
+    #[arg(display_order = 901, long, value_name = "COUNT", default_value_t = DEFAULT_DELETE_CONCURRENCY)]
+    concurrency: usize,
 }
 
 impl DeleteCommand {
@@ -87,9 +91,16 @@ impl DeleteCommandTui for DeleteTui {
             .collect())
     }
 
+    fn delete_concurrency(&self) -> usize {
+        self.cmd.concurrency
+    }
+
+    async fn delete_item(&self, item_name: &str) -> miette::Result<()> {
+        Ok(self.opts.state.delete_identity_by_name(item_name).await?)
+    }
+
     async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
-        let state = &self.opts.state;
-        state.delete_identity_by_name(item_name).await?;
+        self.delete_item(item_name).await?;
         self.terminal()
             .stdout()
             .plain(fmt_ok!(
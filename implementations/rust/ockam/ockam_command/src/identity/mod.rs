@@ -6,12 +6,14 @@ pub(crate) use list::ListCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::identity::default::DefaultCommand;
+use crate::identity::rename::RenameCommand;
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
 mod default;
 mod delete;
 mod list;
+mod rename;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -35,6 +37,7 @@ pub enum IdentitySubcommand {
     List(ListCommand),
     Default(DefaultCommand),
     Delete(DeleteCommand),
+    Rename(RenameCommand),
 }
 
 impl IdentityCommand {
@@ -45,6 +48,7 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.run(opts),
             IdentitySubcommand::Delete(c) => c.run(opts),
             IdentitySubcommand::Default(c) => c.run(opts),
+            IdentitySubcommand::Rename(c) => c.run(opts),
         }
     }
 
@@ -55,6 +59,7 @@ impl IdentityCommand {
             IdentitySubcommand::List(c) => c.name(),
             IdentitySubcommand::Delete(c) => c.name(),
             IdentitySubcommand::Default(c) => c.name(),
+            IdentitySubcommand::Rename(c) => c.name(),
         }
         .to_string()
     }
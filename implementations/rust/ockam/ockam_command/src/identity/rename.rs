@@ -0,0 +1,55 @@
+use clap::Args;
+use colorful::Colorful;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rename/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename an identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RenameCommand {
+    /// Current name of the identity
+    name: String,
+
+    /// New name of the identity
+    new_name: String,
+}
+
+impl RenameCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "identity rename".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let identity = opts
+            .state
+            .rename_identity(&self.name, &self.new_name)
+            .await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The identity named '{}' has been renamed to '{}'",
+                &self.name,
+                identity.name()
+            ))
+            .machine(identity.name())
+            .json(serde_json::json!({
+                "name": identity.name(),
+                "identifier": identity.identifier().to_string(),
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
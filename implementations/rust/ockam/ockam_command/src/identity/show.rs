@@ -1,16 +1,15 @@
-use std::fmt::Display;
-
 use crate::identity::list::IdentityListOutput;
-use crate::output::{EncodeFormat, IdentifierDisplay, Output, VerifyingPublicKeyDisplay};
+use crate::output::{EncodeFormat, IdentifierDisplay};
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts};
 use clap::Args;
 use miette::IntoDiagnostic;
-use ockam::identity::verified_change::VerifiedChange;
-use ockam::identity::{Identifier, Identity};
+use ockam::identity::Identifier;
+use ockam_api::cli_state::enrollments::EnrollmentAttribute;
 use ockam_api::NamedIdentity;
 use serde::Serialize;
 use serde_json::{json, to_string_pretty};
+use std::fmt::{Display, Formatter};
 
 const LONG_ABOUT: &str = include_str!("./static/show/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
@@ -27,10 +26,15 @@ pub struct ShowCommand {
     #[arg()]
     name: Option<String>,
 
-    /// Show the full identity history, and not just the identifier or the name
+    /// Show the full identity history: the changes of its primary key and its purpose keys
     #[arg(short, long)]
     full: bool,
 
+    /// Show the attributes granted to the identity when it enrolled with projects.
+    /// This is a local copy, the attributes are attested by the project authorities
+    #[arg(long, conflicts_with = "full")]
+    attributes: bool,
+
     //TODO: see if it make sense to have a --encoding argument shared across commands.
     //      note the only reason this is here right now is that project.json expect the
     //      authority' identity change history to be in hex format.  This only applies
@@ -50,6 +54,9 @@ impl ShowCommand {
     }
 
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if self.attributes {
+            return ShowCommand::show_attributes(&opts, &self.name).await;
+        }
         if self.name.is_some() || !opts.terminal.can_ask_for_user_input() {
             ShowCommand::show_single_identity(&opts, &self.name, self.full, self.encoding.clone())
                 .await?;
@@ -117,8 +124,8 @@ impl ShowCommand {
                 let json = to_string_pretty(&json!({"encoded": &encoded}));
                 (encoded, json)
             } else {
-                let identity: ShowIdentity = identity.into();
-                (identity.to_string(), to_string_pretty(&identity))
+                let history = opts.state.get_identity_history(&identity).await?;
+                (history.to_string(), to_string_pretty(&history))
             }
         } else {
             let identifier_display = IdentifierDisplay(identity.identifier().clone());
@@ -138,6 +145,27 @@ impl ShowCommand {
         Ok(())
     }
 
+    async fn show_attributes(
+        opts: &CommandGlobalOpts,
+        name: &Option<String>,
+    ) -> miette::Result<()> {
+        let identifier = opts.state.get_identifier_by_optional_name(name).await?;
+        let attributes = opts.state.get_enrollment_attributes(&identifier).await?;
+        let output = EnrollmentAttributesOutput {
+            identifier,
+            advisory: true,
+            attributes,
+        };
+
+        opts.terminal
+            .clone()
+            .stdout()
+            .plain(output.to_string())
+            .json(to_string_pretty(&output).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+
     async fn show_identity_list(
         opts: &CommandGlobalOpts,
         selected_names: Vec<String>,
@@ -170,61 +198,93 @@ impl ShowCommand {
     }
 }
 
+/// Local copy of the attributes granted to an identity when it enrolled with projects
 #[derive(Serialize)]
-struct ShowIdentity {
+struct EnrollmentAttributesOutput {
     identifier: Identifier,
-    changes: Vec<Change>,
-}
-
-impl From<Identity> for ShowIdentity {
-    fn from(value: Identity) -> Self {
-        Self {
-            identifier: value.identifier().to_owned(),
-            changes: value.changes().iter().cloned().map(Change::from).collect(),
-        }
-    }
+    /// The attributes are only advisory, the project authorities are the source of truth
+    advisory: bool,
+    attributes: Vec<EnrollmentAttribute>,
 }
 
-impl Display for ShowIdentity {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Identifier: {}", self.identifier)?;
-        for (i_num, change) in self.changes.iter().enumerate() {
-            writeln!(f, "  Change[{}]:", i_num)?;
-            writeln!(f, "    identifier:              {}", change.identifier)?;
-            writeln!(
+impl Display for EnrollmentAttributesOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.attributes.is_empty() {
+            return write!(
                 f,
-                "    primary_public_key:      {}",
-                change.primary_public_key
-            )?;
+                "The identity {} has no known enrollment attributes",
+                self.identifier
+            );
+        }
+        writeln!(
+            f,
+            "Enrollment attributes of the identity {}",
+            self.identifier
+        )?;
+        writeln!(
+            f,
+            "(local copy: the attributes are attested by the project authority, which can change them at any time)"
+        )?;
+        for attribute in &self.attributes {
             writeln!(
                 f,
-                "    revoke_all_purpose_keys: {}",
-                change.revoke_all_purpose_keys
+                "  {}={} (project {}, from the {})",
+                attribute.name, attribute.value, attribute.project_id, attribute.source
             )?;
         }
         Ok(())
     }
 }
 
-impl Output for ShowIdentity {
-    fn output(&self) -> crate::error::Result<String> {
-        Ok(self.to_string())
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_api::cli_state::enrollments::EnrollmentAttributeSource;
+    use ockam_api::CliState;
+    use std::collections::BTreeMap;
 
-#[derive(Serialize)]
-struct Change {
-    pub identifier: String,
-    pub primary_public_key: VerifyingPublicKeyDisplay,
-    pub revoke_all_purpose_keys: bool,
-}
+    #[tokio::test]
+    async fn test_show_enrollment_attributes() -> ockam_api::cli_state::Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        cli.store_enrollment_attributes(
+            &identity.identifier(),
+            "project_id",
+            &BTreeMap::from([
+                ("role".to_string(), "ops".to_string()),
+                ("team".to_string(), "payments".to_string()),
+            ]),
+            &BTreeMap::from([("role".to_string(), "ops".to_string())]),
+        )
+        .await?;
 
-impl From<VerifiedChange> for Change {
-    fn from(value: VerifiedChange) -> Self {
-        Self {
-            identifier: hex::encode(value.change_hash()),
-            primary_public_key: VerifyingPublicKeyDisplay(value.primary_public_key().to_owned()),
-            revoke_all_purpose_keys: value.data().revoke_all_purpose_keys,
-        }
+        let output = EnrollmentAttributesOutput {
+            identifier: identity.identifier(),
+            advisory: true,
+            attributes: cli
+                .get_enrollment_attributes(&identity.identifier())
+                .await?,
+        };
+        assert_eq!(
+            output.attributes[1].source,
+            EnrollmentAttributeSource::Ticket
+        );
+
+        let plain = output.to_string();
+        assert!(plain.contains("local copy"), "{plain}");
+        assert!(
+            plain.contains("role=ops (project project_id, from the credential)"),
+            "{plain}"
+        );
+        assert!(
+            plain.contains("team=payments (project project_id, from the ticket)"),
+            "{plain}"
+        );
+
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["advisory"], true);
+        assert_eq!(json["attributes"][0]["name"], "role");
+        assert_eq!(json["attributes"][0]["source"], "credential");
+        Ok(())
     }
 }
@@ -1,10 +1,15 @@
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use clap::{command, Args};
 use colorful::Colorful;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::Context;
+use ockam_api::kafka::{
+    KafkaBrokerValidationRequest, KafkaSaslCredentials, KafkaSaslMechanism,
+    DEFAULT_KAFKA_BROKER_VALIDATION_TIMEOUT,
+};
 use ockam_api::nodes::models::services::StartKafkaOutletRequest;
 use ockam_api::nodes::models::services::StartServiceRequest;
 use ockam_api::nodes::BackgroundNodeClient;
@@ -12,6 +17,7 @@ use ockam_core::api::Request;
 
 use crate::node::util::initialize_default_node;
 use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
 use crate::{
     fmt_log, fmt_ok,
     kafka::{kafka_default_outlet_addr, kafka_default_outlet_server},
@@ -32,6 +38,26 @@ pub struct CreateCommand {
     /// The address of the kafka bootstrap broker
     #[arg(long, default_value_t = kafka_default_outlet_server())]
     bootstrap_server: SocketAddr,
+    /// Check that the bootstrap broker is reachable, and accepts the SASL credentials if provided,
+    /// before creating the outlet
+    #[arg(long)]
+    validate: bool,
+    /// Maximum duration of the broker validation, for example 5s
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser, requires = "validate")]
+    validate_timeout: Option<Duration>,
+    /// SASL mechanism used to validate the broker credentials: SCRAM-SHA-256 or SCRAM-SHA-512
+    #[arg(
+        long,
+        value_name = "MECHANISM",
+        requires_all = ["validate", "validate_sasl_username", "validate_sasl_password"]
+    )]
+    validate_sasl_mechanism: Option<KafkaSaslMechanism>,
+    /// SASL username used to validate the broker credentials. It is not stored by the node
+    #[arg(long, value_name = "USERNAME", requires = "validate_sasl_mechanism")]
+    validate_sasl_username: Option<String>,
+    /// SASL password used to validate the broker credentials. It is not stored by the node
+    #[arg(long, value_name = "PASSWORD", requires = "validate_sasl_mechanism")]
+    validate_sasl_password: Option<String>,
 }
 
 impl CreateCommand {
@@ -51,7 +77,8 @@ impl CreateCommand {
             .write_line(&fmt_log!("Creating KafkaOutlet service"))?;
         let is_finished = Mutex::new(false);
         let send_req = async {
-            let payload = StartKafkaOutletRequest::new(self.bootstrap_server);
+            let payload =
+                StartKafkaOutletRequest::new(self.bootstrap_server, self.validation_request());
             let payload = StartServiceRequest::new(payload, &self.addr);
             let req = Request::post("/node/services/kafka_outlet").body(payload);
             let node =
@@ -95,4 +122,26 @@ impl CreateCommand {
 
         Ok(())
     }
+
+    fn validation_request(&self) -> Option<KafkaBrokerValidationRequest> {
+        if !self.validate {
+            return None;
+        }
+        let sasl =
+            match (
+                self.validate_sasl_mechanism,
+                &self.validate_sasl_username,
+                &self.validate_sasl_password,
+            ) {
+                (Some(mechanism), Some(username), Some(password)) => Some(
+                    KafkaSaslCredentials::new(mechanism, username.clone(), password.clone()),
+                ),
+                _ => None,
+            };
+        Some(KafkaBrokerValidationRequest::new(
+            self.validate_timeout
+                .unwrap_or(DEFAULT_KAFKA_BROKER_VALIDATION_TIMEOUT),
+            sasl,
+        ))
+    }
 }
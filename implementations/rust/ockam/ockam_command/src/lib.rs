@@ -47,6 +47,7 @@ mod project;
 mod project_member;
 mod relay;
 mod reset;
+mod route;
 mod run;
 mod secure_channel;
 mod service;
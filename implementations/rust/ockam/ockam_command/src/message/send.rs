@@ -1,14 +1,15 @@
 use core::time::Duration;
 
 use clap::Args;
+use colorful::Colorful;
 use miette::{Context as _, IntoDiagnostic};
 use tracing::info;
 
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::service::messages::Messages;
+use ockam_api::nodes::service::messages::{MessageRoundTrip, MessageRoundTripStats, Messages};
 use ockam_api::nodes::BackgroundNodeClient;
-use ockam_api::nodes::InMemoryNode;
+use ockam_api::nodes::{InMemoryNode, NodeManager};
 use ockam_multiaddr::MultiAddr;
 
 use crate::project::util::{
@@ -16,12 +17,16 @@ use crate::project::util::{
 };
 use crate::util::api::{IdentityOpts, TrustOpts};
 use crate::util::duration::duration_parser;
+use crate::util::route_aliases::resolve_route;
 use crate::util::{async_cmd, clean_nodes_multiaddr};
-use crate::{docs, CommandGlobalOpts};
+use crate::{docs, fmt_log, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
 
 const LONG_ABOUT: &str = include_str!("./static/send/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/send/after_long_help.txt");
 
+/// Default time to wait for a reply, when no `--timeout` is given
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Send a message to an Ockam node
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -34,19 +39,28 @@ pub struct SendCommand {
     #[arg(short, long, value_name = "NODE", value_parser = extract_address_value)]
     from: Option<String>,
 
-    /// The route to send the message to
+    /// The route to send the message to. It can use route aliases, for example `@prod/service/echo`
     #[arg(short, long, value_name = "ROUTE")]
-    pub to: MultiAddr,
+    pub to: String,
 
     /// Flag to indicate that the message is hex encoded
     #[arg(long)]
     pub hex: bool,
 
-    /// Override default timeout
-    #[arg(long, value_name = "TIMEOUT", default_value = "10s", value_parser = duration_parser)]
-    pub timeout: Duration,
+    /// Send the message N times to an echo service and print the round trip statistics
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub repeat: Option<u64>,
+
+    /// Interval between two messages sent with --repeat
+    #[arg(long, value_name = "INTERVAL", default_value = "1s", value_parser = duration_parser, requires = "repeat")]
+    pub interval: Duration,
 
-    pub message: String,
+    /// Send a payload of this size in bytes instead of a message
+    #[arg(long, value_name = "BYTES", conflicts_with = "message")]
+    pub payload_size: Option<usize>,
+
+    #[arg(required_unless_present = "payload_size")]
+    pub message: Option<String>,
 
     #[command(flatten)]
     identity_opts: IdentityOpts,
@@ -68,25 +82,28 @@ impl SendCommand {
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         // Process `--to` Multiaddr
-        let (to, meta) = clean_nodes_multiaddr(&self.to, &opts.state)
+        let to = resolve_route(&opts.state, &self.to)
+            .await
+            .context("Argument '--to' is invalid")?;
+        let (to, meta) = clean_nodes_multiaddr(&to, &opts.state)
             .await
             .context("Argument '--to' is invalid")?;
 
-        let msg_bytes = if self.hex {
-            hex::decode(self.message.clone())
+        let msg_bytes = match (&self.message, self.payload_size) {
+            (_, Some(payload_size)) => vec![b'x'; payload_size],
+            (Some(message), None) if self.hex => hex::decode(message)
                 .into_diagnostic()
-                .context("The message is not a valid hex string")?
-        } else {
-            self.message.as_bytes().to_vec()
+                .context("The message is not a valid hex string")?,
+            (Some(message), None) => message.as_bytes().to_vec(),
+            (None, None) => return Err(miette::miette!("A message or a payload size is required")),
         };
 
         // Setup environment depending on whether we are sending the message from a background node
         // or an in-memory node
-        let response: Vec<u8> = if let Some(node) = &self.from {
-            BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str())
-                .await?
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout))
-                .await?
+        if let Some(node) = &self.from {
+            let node =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, node.as_str()).await?;
+            self.send(ctx, &opts, &node, &to, msg_bytes).await
         } else {
             let identity_name = opts
                 .state
@@ -113,16 +130,33 @@ impl SendCommand {
                 &node_manager,
                 &meta,
                 Some(identity_name),
-                Some(self.timeout),
+                Some(reply_timeout(&opts)),
             )
             .await?;
             let to = clean_projects_multiaddr(to, projects_sc)?;
             info!("sending to {to}");
-            node_manager
-                .send_message(ctx, &to, msg_bytes, Some(self.timeout))
-                .await?
-        };
+            let node_manager: &NodeManager = &node_manager;
+            self.send(ctx, &opts, node_manager, &to, msg_bytes).await
+        }
+    }
 
+    async fn send(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        messages: &(impl Messages + Sync),
+        to: &MultiAddr,
+        msg_bytes: Vec<u8>,
+    ) -> miette::Result<()> {
+        if let Some(repeat) = self.repeat {
+            return self
+                .send_repeatedly(ctx, opts, messages, to, msg_bytes, repeat as usize)
+                .await;
+        }
+
+        let response = messages
+            .send_message(ctx, to, msg_bytes, Some(reply_timeout(opts)))
+            .await?;
         let result = if self.hex {
             hex::encode(response)
         } else {
@@ -134,4 +168,116 @@ impl SendCommand {
         opts.terminal.stdout().plain(result).write_line()?;
         Ok(())
     }
+
+    /// Send messages to an echo service, printing a line for each round trip in interactive mode,
+    /// then print the round trip statistics
+    async fn send_repeatedly(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        messages: &(impl Messages + Sync),
+        to: &MultiAddr,
+        msg_bytes: Vec<u8>,
+        repeat: usize,
+    ) -> miette::Result<()> {
+        let on_round_trip = |round_trip: &MessageRoundTrip| {
+            let line = match (&round_trip.round_trip_time, &round_trip.error) {
+                (Some(time), _) => fmt_log!(
+                    "{} bytes from {to}: seq={} time={:.3}ms",
+                    round_trip.payload_size,
+                    round_trip.sequence,
+                    time.as_secs_f64() * 1000.0
+                ),
+                (None, error) => fmt_warn!(
+                    "No reply from {to}: seq={} {}",
+                    round_trip.sequence,
+                    error.clone().unwrap_or_default()
+                ),
+            };
+            let _ = opts.terminal.write_line(line);
+        };
+        let round_trips = messages
+            .send_messages(
+                ctx,
+                to,
+                msg_bytes,
+                repeat,
+                self.interval,
+                Some(reply_timeout(opts)),
+                &on_round_trip,
+            )
+            .await?;
+
+        let stats = MessageRoundTripStats::new(&round_trips);
+        let format_ms = |ms: Option<f64>| {
+            ms.map(|ms| format!("{ms:.3}"))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let plain = fmt_ok!(
+            "{} messages sent, {} received, {} lost\n",
+            stats.sent,
+            stats.received,
+            format!("{:.1}%", stats.loss_percent).color(OckamColor::PrimaryResource.color())
+        ) + &fmt_log!(
+            "round trip min/avg/p95/max = {}/{}/{}/{} ms",
+            format_ms(stats.min_ms),
+            format_ms(stats.avg_ms),
+            format_ms(stats.p95_ms),
+            format_ms(stats.max_ms)
+        );
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&stats).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Time to wait for a reply, set with the global `--timeout` option
+fn reply_timeout(opts: &CommandGlobalOpts) -> Duration {
+    opts.global_args.timeout.unwrap_or(DEFAULT_TIMEOUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message::MessageSubcommand;
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::OckamSubcommand;
+    use ockam_api::CliState;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn the_route_of_an_alias_is_used_by_message_send() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        state
+            .set_route_alias("relay", "/node/n1/service/forward_to_n2")
+            .await?;
+        state.set_route_alias("n2", "@relay/secure/api").await?;
+
+        let cmd = parse_cmd_from_args(
+            "message send",
+            &[
+                "--to".to_string(),
+                "@n2/service/echo".to_string(),
+                "hello".to_string(),
+            ],
+        )?;
+        let cmd = match cmd {
+            OckamSubcommand::Message(c) => match c.subcommand {
+                MessageSubcommand::Send(c) => c,
+            },
+            _ => panic!("unexpected command"),
+        };
+        assert_eq!(
+            resolve_route(&state, &cmd.to).await?,
+            MultiAddr::from_str("/node/n1/service/forward_to_n2/secure/api/service/echo").unwrap()
+        );
+
+        // an unknown alias is an error
+        state.delete_route_alias("relay").await?;
+        assert!(resolve_route(&state, &cmd.to).await.is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/backup/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/backup/after_long_help.txt");
+
+/// Back up the database of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct BackupCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// Path of the backup file to create
+    #[arg(long, value_name = "PATH")]
+    to: PathBuf,
+}
+
+impl BackupCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node backup".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let manifest = opts.state.backup_node(&self.node_name, &self.to).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The database of the node {} has been backed up to {} (schema version {})",
+                self.node_name
+                    .as_str()
+                    .color(OckamColor::PrimaryResource.color()),
+                self.to
+                    .display()
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                manifest.schema_version
+            ))
+            .machine(self.to.display().to_string())
+            .json(serde_json::json!({
+                "path": self.to,
+                "schema_version": manifest.schema_version,
+                "crate_version": manifest.crate_version,
+                "created_at": manifest.created_at,
+            }))
+            .write_line()?;
+        Ok(())
+    }
+}
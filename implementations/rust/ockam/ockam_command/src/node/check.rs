@@ -0,0 +1,119 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use ockam_node::database::{DatabaseCheckReport, Severity};
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_err, fmt_info, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/check/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/check/after_long_help.txt");
+
+/// Check the integrity of the database of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CheckCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// Skip the verification of the indexes, which is slow on large databases
+    #[arg(long)]
+    quick: bool,
+
+    /// Repair the database: rebuild the indexes, compact the database
+    /// and delete the rows referencing identities which do not exist
+    #[arg(long)]
+    repair: bool,
+
+    /// Confirm the deletion of the rows referencing identities which do not exist without prompting
+    #[arg(long, short, requires = "repair")]
+    yes: bool,
+}
+
+impl CheckCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node check".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let mut report = opts
+            .state
+            .check_node_database(&self.node_name, self.quick)
+            .await?;
+
+        if self.repair {
+            self.write_report(&opts, &report)?;
+            let delete_orphaned_rows = report.orphaned_rows > 0
+                && opts.terminal.confirmed_with_flag_or_prompt(
+                    self.yes,
+                    format!(
+                        "Are you sure you want to delete the {} row(s) referencing identities which do not exist?",
+                        report.orphaned_rows
+                    ),
+                )?;
+            let deleted = opts
+                .state
+                .repair_node_database(&self.node_name, delete_orphaned_rows)
+                .await?;
+            opts.terminal.write_line(fmt_info!(
+                "The indexes have been rebuilt and {deleted} orphaned row(s) have been deleted"
+            ))?;
+            report = opts
+                .state
+                .check_node_database(&self.node_name, self.quick)
+                .await?;
+        }
+        self.write_report(&opts, &report)?;
+
+        if report.is_corrupted() {
+            return Err(miette!(
+                "The database of the node {} is corrupted",
+                self.node_name
+            ));
+        }
+        Ok(())
+    }
+
+    fn write_report(
+        &self,
+        opts: &CommandGlobalOpts,
+        report: &DatabaseCheckReport,
+    ) -> miette::Result<()> {
+        let node_name = self
+            .node_name
+            .as_str()
+            .color(OckamColor::PrimaryResource.color());
+        let plain = if report.findings.is_empty() {
+            fmt_ok!("The database of the node {node_name} has no issues")
+        } else {
+            let findings = report
+                .findings
+                .iter()
+                .map(|f| match f.severity {
+                    Severity::Error => fmt_err!("[{}] {}", f.check, f.message),
+                    Severity::Warning => fmt_warn!("[{}] {}", f.check, f.message),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            fmt_info!("The database of the node {node_name} has the following issues:\n")
+                + &findings
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::json!(report))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use std::{path::PathBuf, str::FromStr};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use clap::Args;
 use miette::Context as _;
@@ -9,17 +9,20 @@ use opentelemetry::KeyValue;
 use tracing::instrument;
 use url::Url;
 
-use ockam_api::cli_state::random_name;
-use ockam_api::EnrollmentTicket;
+use ockam_api::cli_state::enrollments::{EnrollmentTicketContents, TicketPassphrase};
+use ockam_api::cli_state::{random_name, validate_name, DEFAULT_BACKUPS_TO_KEEP};
+use ockam_api::logs::LogFormat;
 use ockam_core::{opentelemetry_context_parser, AsyncTryClone, OpenTelemetryContext};
 use ockam_node::Context;
+use ockam_transport_tcp::{IpCidr, TcpListenerOptions};
 
 use crate::node::util::NodeManagerDefaults;
 use crate::service::config::Config;
 use crate::util::api::TrustOpts;
+use crate::util::duration::duration_parser;
 use crate::util::embedded_node_that_is_not_stopped;
 use crate::util::{async_cmd, local_cmd};
-use crate::value_parsers::{parse_enrollment_ticket, parse_key_val};
+use crate::value_parsers::{parse_enrollment_ticket_contents, parse_key_val, parse_log_format};
 use crate::{docs, Command, CommandGlobalOpts, Result};
 
 pub mod background;
@@ -37,7 +40,8 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct CreateCommand {
     /// Name of the node or path to a config file.
-    #[arg(hide_default_value = true, default_value_t = random_name())]
+    /// If it is omitted, you are asked for a name, with a random name as the default value.
+    #[arg(hide_default_value = true, default_value = "")]
     pub name: String,
 
     /// Run the node in foreground.
@@ -53,6 +57,29 @@ pub struct CreateCommand {
     #[arg(display_order = 900, long, short)]
     pub exit_on_eof: bool,
 
+    /// Run the node in foreground for the duration of the command only.
+    /// The node is never set as the default node and all its state is removed when it stops.
+    #[arg(
+        display_order = 900,
+        long,
+        requires = "foreground",
+        conflicts_with = "child_process"
+    )]
+    pub ephemeral: bool,
+
+    /// Format of the logs of a foreground node: pretty, compact or json.
+    /// Logging is enabled when this argument is set. With the json format, the standard output
+    /// only contains log records, and the other messages are written to the standard error
+    #[arg(
+        display_order = 900,
+        long,
+        alias = "foreground-log-format",
+        value_name = "FORMAT",
+        requires = "foreground",
+        value_parser = parse_log_format
+    )]
+    pub log_format: Option<LogFormat>,
+
     /// TCP listener address
     #[arg(
         display_order = 900,
@@ -63,6 +90,25 @@ pub struct CreateCommand {
     )]
     pub tcp_listener_address: String,
 
+    /// Maximum number of connections accepted per second by the TCP listener.
+    /// The connections exceeding that rate are closed right away
+    #[arg(display_order = 900, long, value_name = "RATE")]
+    pub tcp_listener_max_accept_rate: Option<u32>,
+
+    /// Maximum number of open connections to the TCP listener from a single IP address
+    #[arg(display_order = 900, long, value_name = "COUNT")]
+    pub tcp_listener_max_connections_per_ip: Option<usize>,
+
+    /// Only accept the TCP connections coming from this range of addresses, for example 10.0.0.0/8.
+    /// This argument can be repeated
+    #[arg(display_order = 900, long, value_name = "CIDR")]
+    pub tcp_listener_allow: Vec<IpCidr>,
+
+    /// Close the TCP connections coming from this range of addresses, for example 192.168.0.0/16.
+    /// This argument can be repeated
+    #[arg(display_order = 900, long, value_name = "CIDR")]
+    pub tcp_listener_deny: Vec<IpCidr>,
+
     /// `node create` started a child process to run this node in foreground.
     #[arg(long, hide = true)]
     pub child_process: bool,
@@ -82,17 +128,49 @@ pub struct CreateCommand {
     #[command(flatten)]
     pub trust_opts: TrustOpts,
 
+    /// Retrieve the node credential when the node starts, instead of when it is first needed.
+    /// If the credential can't be retrieved in time, the node keeps running in a degraded mode
+    #[arg(long, display_order = 900)]
+    pub eager_credentials: bool,
+
     /// Serialized opentelemetry context
     #[arg(long, hide = true, value_parser = opentelemetry_context_parser)]
     pub opentelemetry_context: Option<OpenTelemetryContext>,
 
     /// Path, URL or inlined hex-encoded enrollment ticket
-    #[arg(long, value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket)]
-    pub enrollment_ticket: Option<EnrollmentTicket>,
+    #[arg(long, value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket_contents)]
+    pub enrollment_ticket: Option<EnrollmentTicketContents>,
+
+    /// Passphrase used to encrypt the enrollment ticket with `ockam project ticket --passphrase`
+    #[arg(long, value_name = "PASSPHRASE", requires = "enrollment_ticket")]
+    pub passphrase: Option<TicketPassphrase>,
 
     /// Key-value pairs defining environment variables used by the config file.
     #[arg(long = "variable", value_name = "VARIABLE", value_parser = parse_key_val::<String, String>)]
     pub variables: Vec<(String, String)>,
+
+    /// Back up the node database at regular intervals, for example 6h
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser, requires = "backup_dir", display_order = 900)]
+    pub backup_interval: Option<Duration>,
+
+    /// Directory where the periodic backups of the node database are written
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "backup_interval",
+        display_order = 900
+    )]
+    pub backup_dir: Option<PathBuf>,
+
+    /// Set to false to prevent the database migrations from being applied automatically
+    /// when a new version of the command is used. The node then refuses to start until
+    /// the pending migrations are applied with `ockam node migrate`
+    #[arg(long, value_name = "BOOL", display_order = 900)]
+    pub auto_migrate: Option<bool>,
+
+    /// Number of periodic backups to keep in the backup directory
+    #[arg(long, value_name = "COUNT", default_value_t = DEFAULT_BACKUPS_TO_KEEP, value_parser = clap::value_parser!(u64).range(1..).map(|n| n as usize), display_order = 900)]
+    pub backup_keep: usize,
 }
 
 impl Default for CreateCommand {
@@ -102,15 +180,27 @@ impl Default for CreateCommand {
             skip_is_running_check: false,
             name: random_name(),
             exit_on_eof: false,
+            ephemeral: false,
+            log_format: None,
             tcp_listener_address: node_manager_defaults.tcp_listener_address,
+            tcp_listener_max_accept_rate: None,
+            tcp_listener_max_connections_per_ip: None,
+            tcp_listener_allow: vec![],
+            tcp_listener_deny: vec![],
             foreground: false,
             child_process: false,
             launch_config: None,
             identity: None,
             trust_opts: node_manager_defaults.trust_opts,
+            eager_credentials: false,
             opentelemetry_context: None,
             enrollment_ticket: None,
+            passphrase: None,
             variables: vec![],
+            backup_interval: None,
+            backup_dir: None,
+            backup_keep: DEFAULT_BACKUPS_TO_KEEP,
+            auto_migrate: None,
         }
     }
 }
@@ -119,7 +209,8 @@ impl Default for CreateCommand {
 impl Command for CreateCommand {
     const NAME: &'static str = "node create";
 
-    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(mut self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        self.resolve_name(&opts)?;
         let ctx = ctx.async_try_clone().await.into_diagnostic()?;
         if self.has_name_arg() {
             if self.foreground {
@@ -133,7 +224,8 @@ impl Command for CreateCommand {
     }
 
     #[instrument(skip_all)]
-    fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+    fn run(mut self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        self.resolve_name(&opts)?;
         if self.has_name_arg() {
             if self.foreground {
                 if self.child_process {
@@ -142,7 +234,7 @@ impl Command for CreateCommand {
                         .set_attribute(KeyValue::new("background", "true"));
                 }
                 local_cmd(embedded_node_that_is_not_stopped(
-                    opts.rt.clone(),
+                    opts.node_builder(),
                     |ctx| async move { self.foreground_mode(&ctx, opts).await },
                 ))
             } else {
@@ -173,10 +265,46 @@ impl CreateCommand {
         Ok(())
     }
 
+    /// Validate the node name, or ask the user for one if it was not provided.
+    /// A config file path or URL is kept as it is, and so is the name of a node
+    /// which is started as a child process, since it was already validated.
+    fn resolve_name(&mut self, opts: &CommandGlobalOpts) -> Result<()> {
+        if self.child_process || (!self.name.is_empty() && !self.has_name_arg()) {
+            return Ok(());
+        }
+        let name = Some(self.name.clone()).filter(|n| !n.is_empty());
+        self.name = opts.terminal.validated_value_or_prompt(
+            name,
+            "Enter a name for the node",
+            random_name(),
+            validate_name,
+        )?;
+        Ok(())
+    }
+
     // Return true if the `name` argument is a node name, false if it's a config file path or URL
     fn has_name_arg(&self) -> bool {
         Url::parse(&self.name).is_err() && std::fs::metadata(&self.name).is_err()
     }
+
+    /// Options of the node TCP listener, with the protections against connection floods.
+    /// The accept rate allows bursts of one second worth of connections
+    pub fn tcp_listener_options(&self) -> TcpListenerOptions {
+        let mut options = TcpListenerOptions::new();
+        if let Some(rate) = self.tcp_listener_max_accept_rate {
+            options = options.with_max_accept_rate(rate, rate);
+        }
+        if let Some(max) = self.tcp_listener_max_connections_per_ip {
+            options = options.with_max_connections_per_source(max);
+        }
+        for source in &self.tcp_listener_allow {
+            options = options.allow_source(*source);
+        }
+        for source in &self.tcp_listener_deny {
+            options = options.deny_source(*source);
+        }
+        options
+    }
 }
 
 pub fn parse_launch_config(config_or_path: &str) -> Result<Config> {
@@ -33,6 +33,7 @@ impl CreateCommand {
         if let Some(identity_name) = &self.identity {
             opts.state.get_named_identity(identity_name).await?;
         }
+        opts.state.check_no_pending_migrations(&self.name).await?;
 
         let node_name = self.name.clone();
         CurrentSpan::set_attribute(NODE_NAME, node_name.as_str());
@@ -87,19 +88,32 @@ impl CreateCommand {
             .add_journey_event(JourneyEvent::NodeCreated, attributes)
             .await?;
 
+        // The port of the listener is the one assigned by the OS when it was set to 0
+        let listener_address = opts
+            .state
+            .get_node(&node_name)
+            .await?
+            .tcp_listener_address()
+            .map(|address| address.to_string())
+            .unwrap_or("N/A".into());
+
         opts.clone()
             .terminal
             .stdout()
             .plain(
                 fmt_ok!(
-                    "Node {} created successfully\n\n",
+                    "Node {} created successfully\n",
                     node_name.color(OckamColor::PrimaryResource.color())
+                ) + &fmt_log!(
+                    "The node is listening at {}\n\n",
+                    listener_address.color(OckamColor::PrimaryResource.color())
                 ) + &fmt_log!("To see more details on this node, run:\n")
                     + &fmt_log!(
                         "{}",
                         "ockam node show".color(OckamColor::PrimaryResource.color())
                     ),
             )
+            .machine(listener_address)
             .write_line()?;
 
         Ok(())
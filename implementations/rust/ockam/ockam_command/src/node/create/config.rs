@@ -39,6 +39,8 @@ pub struct NodeConfig {
     pub tcp_inlets: TcpInlets,
     #[serde(flatten)]
     pub relays: Relays,
+    #[serde(flatten)]
+    pub plugins: Plugins,
 }
 
 impl ConfigParser<'_> for NodeConfig {}
@@ -59,8 +61,11 @@ impl NodeConfig {
 
         // Set the enrollment ticket from the cli command
         // overriding the one from the config file.
-        if let Some(ticket) = &cli_args.enrollment_ticket {
+        // An encrypted ticket is decrypted here, the passphrase is not passed along
+        if let Some(contents) = &cli_args.enrollment_ticket {
+            let ticket = contents.ticket(cli_args.passphrase.as_ref())?;
             self.project_enroll.ticket = Some(ticket.hex_encoded()?);
+            self.project_enroll.passphrase = None;
         }
 
         // Merge the node arguments from the config with the cli command args.
@@ -99,6 +104,7 @@ impl NodeConfig {
         let commands: Vec<ParsedCommands> = vec![
             self.project_enroll.parse_commands(overrides)?.into(),
             self.node.parse_commands(overrides)?.into(),
+            self.plugins.parse_commands(overrides)?.into(),
             self.relays.parse_commands(overrides)?.into(),
             self.policies.parse_commands(overrides)?.into(),
             self.tcp_outlets.parse_commands(overrides)?.into(),
@@ -117,6 +123,7 @@ impl NodeConfig {
 mod tests {
     use super::*;
     use ockam_api::authenticator::one_time_code::OneTimeCode;
+    use ockam_api::cli_state::enrollments::{EnrollmentTicketContents, TicketPassphrase};
     use ockam_api::EnrollmentTicket;
 
     #[test]
@@ -145,7 +152,7 @@ mod tests {
 
         let cli_args = CreateCommand {
             tcp_listener_address: "127.0.0.1:1234".to_string(),
-            enrollment_ticket: Some(enrollment_ticket.clone()),
+            enrollment_ticket: Some(EnrollmentTicketContents::Plain(enrollment_ticket.clone())),
             ..Default::default()
         };
 
@@ -189,4 +196,31 @@ mod tests {
             Some(enrollment_ticket_hex.clone())
         );
     }
+
+    #[test]
+    fn merge_config_with_encrypted_ticket() {
+        let enrollment_ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        let passphrase = TicketPassphrase::new("correct horse battery staple");
+        let encrypted = enrollment_ticket.encrypt(&passphrase).unwrap();
+
+        // the ticket can't be used without its passphrase
+        let cli_args = CreateCommand {
+            enrollment_ticket: Some(EnrollmentTicketContents::Encrypted(encrypted)),
+            ..Default::default()
+        };
+        let mut config = NodeConfig::parse("").unwrap();
+        assert!(config.merge(cli_args.clone()).is_err());
+
+        // the decrypted ticket is passed to the enrollment
+        let cli_args = CreateCommand {
+            passphrase: Some(passphrase),
+            ..cli_args
+        };
+        let mut config = NodeConfig::parse("").unwrap();
+        config.merge(cli_args).unwrap();
+        assert_eq!(
+            config.project_enroll.ticket,
+            Some(enrollment_ticket.hex_encoded().unwrap())
+        );
+    }
 }
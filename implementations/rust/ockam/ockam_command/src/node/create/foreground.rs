@@ -3,16 +3,18 @@ use std::sync::Arc;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic};
 use tokio::time::{sleep, Duration};
-use tracing::{debug, instrument};
+use tracing::{debug, info, instrument, warn};
 
-use ockam::{Address, AsyncTryClone, TcpListenerOptions};
+use ockam::Address;
 use ockam::{Context, TcpTransport};
+use ockam_api::cli_state::{CliState, NodeBackupSchedule};
 use ockam_api::nodes::InMemoryNode;
 use ockam_api::nodes::{
     service::{NodeManagerGeneralOptions, NodeManagerTransportOptions},
     NodeManagerWorker, NODEMANAGER_ADDR,
 };
 use ockam_core::{route, LOCAL};
+use ockam_transport_tcp::TcpListener;
 
 use crate::fmt_ok;
 use crate::node::CreateCommand;
@@ -32,6 +34,7 @@ impl CreateCommand {
 
         let node_name = self.name.clone();
         debug!("create node {node_name} in foreground mode");
+        opts.state.check_no_pending_migrations(&node_name).await?;
 
         if opts
             .state
@@ -45,7 +48,7 @@ impl CreateCommand {
         };
 
         let tcp = TcpTransport::create(ctx).await.into_diagnostic()?;
-        let options = TcpListenerOptions::new();
+        let options = self.tcp_listener_options();
         let listener = tcp
             .listen(&self.tcp_listener_address, options)
             .await
@@ -60,16 +63,59 @@ impl CreateCommand {
         let mut state = opts.state.clone();
         state.set_node_name(&node_name);
 
-        let node_info = state
-            .start_node_with_optional_values(
-                &node_name,
-                &self.identity,
-                &self.trust_opts.project_name,
-                Some(&listener),
-            )
-            .await?;
+        let node_info = if self.ephemeral {
+            state
+                .start_ephemeral_node_with_optional_values(
+                    &node_name,
+                    &self.identity,
+                    &self.trust_opts.project_name,
+                    Some(&listener),
+                )
+                .await?
+        } else {
+            state
+                .start_node_with_optional_values(
+                    &node_name,
+                    &self.identity,
+                    &self.trust_opts.project_name,
+                    Some(&listener),
+                )
+                .await?
+        };
         debug!("created node {node_info:?}");
+        if let Some(auto_migrate) = self.auto_migrate {
+            state
+                .set_node_auto_migrate(&node_name, auto_migrate)
+                .await?;
+        }
+
+        let result = self.run_node(ctx, &opts, state, tcp, listener).await;
+
+        if self.ephemeral {
+            // The node has been stopped and its workers have been drained,
+            // so the state created for the node can be safely removed
+            if let Err(e) = opts.state.remove_node(&node_name).await {
+                warn!("the state of the ephemeral node {node_name} could not be removed: {e}");
+            }
+        }
+        result?;
+
+        opts.terminal
+            .write_line(fmt_ok!("Node stopped successfully"))?;
+
+        Ok(())
+    }
 
+    /// Start the node manager and wait for a signal to stop the node
+    async fn run_node(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        state: CliState,
+        tcp: TcpTransport,
+        listener: TcpListener,
+    ) -> miette::Result<()> {
+        let node_name = self.name.clone();
         let trust_options = opts
             .state
             .retrieve_trust_options(
@@ -79,7 +125,8 @@ impl CreateCommand {
                 self.trust_opts.expect_cached_credential,
             )
             .await
-            .into_diagnostic()?;
+            .into_diagnostic()?
+            .with_eager_credentials(self.eager_credentials);
 
         let node_man = InMemoryNode::new(
             ctx,
@@ -89,10 +136,7 @@ impl CreateCommand {
                 self.launch_config.is_none(),
                 true,
             ),
-            NodeManagerTransportOptions::new(
-                listener.flow_control_id().clone(),
-                tcp.async_try_clone().await.into_diagnostic()?,
-            ),
+            NodeManagerTransportOptions::new(listener.flow_control_id().clone(), tcp),
             trust_options,
         )
         .await
@@ -121,6 +165,14 @@ impl CreateCommand {
             }
         }
 
+        let periodic_backups = match (self.backup_interval, &self.backup_dir) {
+            (Some(interval), Some(dir)) => Some(opts.state.start_periodic_node_backups(
+                &node_name,
+                NodeBackupSchedule::new(interval, dir.clone(), self.backup_keep),
+            )),
+            _ => None,
+        };
+
         // Create a channel for communicating back to the main thread
         let (tx, mut rx) = tokio::sync::mpsc::channel(2);
         shutdown::wait(
@@ -133,14 +185,13 @@ impl CreateCommand {
         .await?;
 
         opts.shutdown();
+        if let Some(periodic_backups) = periodic_backups {
+            periodic_backups.abort();
+        }
 
         // Try to stop node; it might have already been stopped or deleted (e.g. when running `node delete --all`)
         let _ = opts.state.stop_node(&node_name, true).await;
         ctx.stop().await.into_diagnostic()?;
-
-        opts.terminal
-            .write_line(fmt_ok!("Node stopped successfully"))?;
-
         Ok(())
     }
 }
@@ -159,7 +210,7 @@ async fn start_services(ctx: &Context, cfg: &Config) -> miette::Result<()> {
             let adr = Address::from((LOCAL, cfg.address));
             let ids = cfg.authorized_identifiers;
             let identity = cfg.identity;
-            println!("starting secure-channel listener ...");
+            info!("starting secure-channel listener ...");
             secure_channel_listener::create_listener(ctx, adr, ids, identity, route![]).await?;
         }
     }
@@ -2,7 +2,7 @@ use clap::Args;
 use colorful::Colorful;
 use console::Term;
 
-use crate::terminal::tui::DeleteCommandTui;
+use crate::terminal::tui::{DeleteCommandTui, DEFAULT_DELETE_CONCURRENCY};
 use crate::terminal::PluralTerm;
 use crate::util::async_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts, Terminal, TerminalStream};
@@ -32,6 +32,10 @@ pub struct DeleteCommand {
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
+
+    /// Maximum number of nodes deleted at the same time
+    #[arg(display_order = 901, long, value_name = "COUNT", default_value_t = DEFAULT_DELETE_CONCURRENCY)]
+    concurrency: usize,
 }
 
 impl DeleteCommand {
@@ -93,11 +97,20 @@ impl DeleteCommandTui for DeleteTui {
             .collect())
     }
 
-    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
-        self.opts
+    fn delete_concurrency(&self) -> usize {
+        self.cmd.concurrency
+    }
+
+    async fn delete_item(&self, item_name: &str) -> miette::Result<()> {
+        Ok(self
+            .opts
             .state
             .delete_node(item_name, self.cmd.force)
-            .await?;
+            .await?)
+    }
+
+    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        self.delete_item(item_name).await?;
         self.terminal()
             .stdout()
             .plain(fmt_ok!(
@@ -0,0 +1,494 @@
+//! Start and stop several local nodes, respecting the dependencies between them.
+//!
+//! A node depends on another local node when one of its relays was created at that node.
+//! The relay destinations are read from the `relay-up` events persisted for each node,
+//! so only the relays which were successfully created at least once are taken into account.
+//! The destinations of the inlets are not persisted and can't be used to order the nodes.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use colorful::Colorful;
+use futures::stream::{self, StreamExt};
+use miette::miette;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam_api::cli_state::{CliState, NodeInfo};
+use ockam_api::config::lookup::InternetAddress;
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Node, Tcp};
+use ockam_multiaddr::{MultiAddr, Protocol};
+use ockam_node::NodeEventKind;
+
+use crate::terminal::Table;
+use crate::{color, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
+
+/// Default maximum number of nodes started, or stopped, at the same time
+pub const DEFAULT_NODES_CONCURRENCY: usize = 4;
+
+/// Name of the detail of a `relay-up` event containing the relay destination
+const RELAY_DESTINATION_DETAIL: &str = "destination";
+
+/// Operation run on several nodes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeOperation {
+    Start,
+    Stop,
+}
+
+impl NodeOperation {
+    fn name(&self) -> &'static str {
+        match self {
+            NodeOperation::Start => "start",
+            NodeOperation::Stop => "stop",
+        }
+    }
+
+    fn past_participle(&self) -> &'static str {
+        match self {
+            NodeOperation::Start => "started",
+            NodeOperation::Stop => "stopped",
+        }
+    }
+}
+
+/// Outcome of the start, or stop, of a node by [`run_in_dependency_order`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeOperationResult {
+    pub name: String,
+    pub status: NodeOperationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeOperationStatus {
+    Succeeded,
+    Failed,
+    /// The operation was not attempted because a previous operation failed
+    Skipped,
+}
+
+impl NodeOperationResult {
+    fn new(name: String, error: Option<String>) -> Self {
+        let status = if error.is_none() {
+            NodeOperationStatus::Succeeded
+        } else {
+            NodeOperationStatus::Failed
+        };
+        Self {
+            name,
+            status,
+            error,
+        }
+    }
+
+    fn skipped(name: String) -> Self {
+        Self {
+            name,
+            status: NodeOperationStatus::Skipped,
+            error: None,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        self.status == NodeOperationStatus::Failed
+    }
+
+    /// Line displayed when the operation completes
+    pub fn plain_output(&self, operation: NodeOperation) -> String {
+        match &self.error {
+            None => fmt_ok!(
+                "The node {} has been {}",
+                color!(self.name, OckamColor::PrimaryResource),
+                operation.past_participle()
+            ),
+            Some(error) => fmt_warn!(
+                "Failed to {} the node {}: {}",
+                operation.name(),
+                color!(self.name, OckamColor::PrimaryResource),
+                error
+            ),
+        }
+    }
+}
+
+/// Return the local nodes each node depends on, keyed by node name.
+/// Nodes without dependencies are not part of the returned map
+pub async fn relay_dependencies(
+    state: &CliState,
+    nodes: &[NodeInfo],
+) -> miette::Result<BTreeMap<String, BTreeSet<String>>> {
+    let listeners: BTreeMap<String, InternetAddress> = nodes
+        .iter()
+        .filter_map(|node| {
+            node.tcp_listener_address()
+                .map(|address| (node.name(), address))
+        })
+        .collect();
+
+    let repository = state.node_events_repository();
+    let mut dependencies = BTreeMap::new();
+    for node in nodes {
+        let events = repository
+            .get_events(&node.name(), None, Some(NodeEventKind::RelayUp))
+            .await
+            .into_diagnostic()?;
+
+        // the events are sorted from the oldest to the newest, so only
+        // the latest destination of each relay is kept
+        let mut destinations = BTreeMap::new();
+        for event in events {
+            if let Some(destination) = event.details.get(RELAY_DESTINATION_DETAIL) {
+                destinations.insert(event.subject, destination.clone());
+            }
+        }
+
+        let node_dependencies: BTreeSet<String> = destinations
+            .values()
+            .filter_map(|destination| MultiAddr::from_str(destination).ok())
+            .filter_map(|destination| resolve_local_node(&destination, &listeners))
+            .filter(|name| name != &node.name())
+            .collect();
+        if !node_dependencies.is_empty() {
+            dependencies.insert(node.name(), node_dependencies);
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Return the name of the local node reached with a relay destination, if any.
+/// The destination is either a `/node/<name>` address or the TCP listener address of a node
+pub fn resolve_local_node(
+    destination: &MultiAddr,
+    listeners: &BTreeMap<String, InternetAddress>,
+) -> Option<String> {
+    let mut protocols = destination.iter();
+    let first = protocols.next()?;
+    let host = match first.code() {
+        Node::CODE => return Some((*first.cast::<Node>()?).to_string()),
+        Ip4::CODE => first.cast::<Ip4>()?.0.to_string(),
+        Ip6::CODE => first.cast::<Ip6>()?.0.to_string(),
+        DnsAddr::CODE => (*first.cast::<DnsAddr>()?).to_string(),
+        _ => return None,
+    };
+    let port = protocols.next()?.cast::<Tcp>()?.0;
+
+    listeners
+        .iter()
+        .find(|(_, listener)| listener.port() == port && same_host(&listener_host(listener), &host))
+        .map(|(name, _)| name.clone())
+}
+
+fn listener_host(listener: &InternetAddress) -> String {
+    match listener {
+        InternetAddress::Dns(host, _) => host.clone(),
+        InternetAddress::V4(v4) => v4.ip().to_string(),
+        InternetAddress::V6(v6) => v6.ip().to_string(),
+    }
+}
+
+/// Two hosts are the same if they are equal or if they both designate the local machine
+fn same_host(host1: &str, host2: &str) -> bool {
+    host1 == host2 || (is_local_host(host1) && is_local_host(host2))
+}
+
+fn is_local_host(host: &str) -> bool {
+    host == "localhost"
+        || IpAddr::from_str(host)
+            .map(|ip| ip.is_loopback() || ip.is_unspecified())
+            .unwrap_or(false)
+}
+
+/// Split the nodes in successive waves, so that each node comes after the nodes it depends on.
+///
+/// The nodes of a wave don't depend on each other and can be started at the same time.
+/// Dependencies on nodes which are not part of the list are ignored, and the nodes
+/// which are part of a dependency cycle are all put in the last wave.
+pub fn dependency_waves(
+    nodes: &[String],
+    dependencies: &BTreeMap<String, BTreeSet<String>>,
+) -> Vec<Vec<String>> {
+    let mut remaining: BTreeSet<String> = nodes.iter().cloned().collect();
+    let mut waves = vec![];
+    while !remaining.is_empty() {
+        let mut wave: Vec<String> = remaining
+            .iter()
+            .filter(|node| match dependencies.get(*node) {
+                Some(node_dependencies) => node_dependencies
+                    .iter()
+                    .all(|dependency| dependency == *node || !remaining.contains(dependency)),
+                None => true,
+            })
+            .cloned()
+            .collect();
+        if wave.is_empty() {
+            wave = remaining.iter().cloned().collect();
+        }
+        for node in &wave {
+            remaining.remove(node);
+        }
+        waves.push(wave);
+    }
+    waves
+}
+
+/// Run an operation on each node, one wave after the other, with at most `concurrency`
+/// operations running at the same time within a wave.
+///
+/// When an operation fails, the current wave is completed. Then the next waves are run
+/// only if `continue_on_error` is true, otherwise their nodes are reported as skipped.
+/// `on_completed` is called as soon as an operation completes, so that progress can be reported.
+pub async fn run_in_dependency_order<F, Fut>(
+    waves: Vec<Vec<String>>,
+    concurrency: usize,
+    continue_on_error: bool,
+    operation: F,
+    mut on_completed: impl FnMut(&NodeOperationResult),
+) -> Vec<NodeOperationResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = miette::Result<()>>,
+{
+    let mut results = vec![];
+    let mut aborted = false;
+    for wave in waves {
+        if aborted {
+            results.extend(wave.into_iter().map(NodeOperationResult::skipped));
+            continue;
+        }
+        let mut wave_results: Vec<NodeOperationResult> = stream::iter(wave)
+            .map(|name| {
+                let run = operation(name.clone());
+                async move {
+                    let error = run.await.err().map(|e| e.to_string());
+                    NodeOperationResult::new(name, error)
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .inspect(|result| on_completed(result))
+            .collect()
+            .await;
+        wave_results.sort_by(|r1, r2| r1.name.cmp(&r2.name));
+        aborted = !continue_on_error && wave_results.iter().any(|r| r.is_failed());
+        results.extend(wave_results);
+    }
+    results
+}
+
+/// Table displaying the outcome of each operation, in the order of the operations
+pub fn results_table(results: &[NodeOperationResult], operation: NodeOperation) -> Table {
+    let mut table = Table::new(&["NAME", "STATUS", "ERROR"]);
+    for result in results {
+        let status = match result.status {
+            NodeOperationStatus::Succeeded => {
+                color!(operation.past_participle(), OckamColor::Success)
+            }
+            NodeOperationStatus::Failed => color!("failed", OckamColor::Failure),
+            NodeOperationStatus::Skipped => color!("skipped", OckamColor::FmtWARNBackground),
+        };
+        table.add_row(vec![
+            color!(result.name, OckamColor::PrimaryResource),
+            status,
+            result.error.clone().unwrap_or_default(),
+        ]);
+    }
+    table
+}
+
+/// Display the table of results and return an error if any operation failed,
+/// so that the command exits with a non-zero status
+pub fn write_results(
+    opts: &CommandGlobalOpts,
+    results: &[NodeOperationResult],
+    operation: NodeOperation,
+) -> miette::Result<()> {
+    let plain = opts
+        .terminal
+        .build_table(&results_table(results, operation), "No nodes found.");
+    let succeeded: Vec<&str> = results
+        .iter()
+        .filter(|r| r.status == NodeOperationStatus::Succeeded)
+        .map(|r| r.name.as_str())
+        .collect();
+    opts.terminal
+        .clone()
+        .stdout()
+        .plain(plain)
+        .machine(succeeded.join("\n"))
+        .json(serde_json::to_string(results).into_diagnostic()?)
+        .write_line()?;
+
+    let failed = results.iter().filter(|r| r.is_failed()).count();
+    if failed > 0 {
+        return Err(miette!(
+            "{failed} of {} nodes failed to {}. You can check their logs with `ockam node logs`",
+            results.len(),
+            operation.name()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use miette::miette;
+    use std::sync::{Arc, Mutex};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    /// n1 has a relay at n2, and n2 has a relay at n3
+    fn stub_nodes() -> (Vec<String>, BTreeMap<String, BTreeSet<String>>) {
+        let listeners: BTreeMap<String, InternetAddress> = [
+            ("n1", "127.0.0.1:4001"),
+            ("n2", "127.0.0.1:4002"),
+            ("n3", "localhost:4003"),
+        ]
+        .into_iter()
+        .map(|(name, address)| (name.to_string(), InternetAddress::new(address).unwrap()))
+        .collect();
+
+        let n1_relay = MultiAddr::from_str("/ip4/127.0.0.1/tcp/4002/secure/api").unwrap();
+        let n2_relay = MultiAddr::from_str("/dnsaddr/localhost/tcp/4003/secure/api").unwrap();
+        let mut dependencies = BTreeMap::new();
+        for (node, relay) in [("n1", n1_relay), ("n2", n2_relay)] {
+            let dependency = resolve_local_node(&relay, &listeners).unwrap();
+            dependencies.insert(node.to_string(), BTreeSet::from([dependency]));
+        }
+        (names(&["n1", "n2", "n3"]), dependencies)
+    }
+
+    #[test]
+    fn relay_destinations_are_resolved_to_local_nodes() {
+        let listeners = BTreeMap::from([(
+            "n1".to_string(),
+            InternetAddress::new("0.0.0.0:4001").unwrap(),
+        )]);
+        let resolve = |destination: &str| {
+            resolve_local_node(&MultiAddr::from_str(destination).unwrap(), &listeners)
+        };
+        assert_eq!(
+            resolve("/ip4/127.0.0.1/tcp/4001/secure/api"),
+            Some("n1".into())
+        );
+        assert_eq!(resolve("/dnsaddr/localhost/tcp/4001"), Some("n1".into()));
+        assert_eq!(resolve("/node/n2"), Some("n2".into()));
+        assert_eq!(resolve("/ip4/127.0.0.1/tcp/5000"), None);
+        assert_eq!(resolve("/dnsaddr/example.com/tcp/4001"), None);
+        assert_eq!(resolve("/project/default"), None);
+    }
+
+    #[test]
+    fn nodes_are_ordered_after_their_dependencies() {
+        let (nodes, dependencies) = stub_nodes();
+        assert_eq!(
+            dependency_waves(&nodes, &dependencies),
+            vec![names(&["n3"]), names(&["n2"]), names(&["n1"])]
+        );
+
+        // dependencies on nodes which are not started are ignored
+        assert_eq!(
+            dependency_waves(&names(&["n1", "n3"]), &dependencies),
+            vec![names(&["n1", "n3"])]
+        );
+
+        // the nodes of a cycle are started together, after the other nodes
+        let mut cycle = dependencies.clone();
+        cycle.insert("n2".to_string(), BTreeSet::from(["n1".to_string()]));
+        assert_eq!(
+            dependency_waves(&nodes, &cycle),
+            vec![names(&["n3"]), names(&["n1", "n2"])]
+        );
+    }
+
+    #[tokio::test]
+    async fn operations_run_in_dependency_order() {
+        let (nodes, dependencies) = stub_nodes();
+        let started = Arc::new(Mutex::new(vec![]));
+        let results = run_in_dependency_order(
+            dependency_waves(&nodes, &dependencies),
+            4,
+            false,
+            |name| {
+                let started = started.clone();
+                async move {
+                    started.lock().unwrap().push(name);
+                    Ok(())
+                }
+            },
+            |_| {},
+        )
+        .await;
+
+        assert_eq!(*started.lock().unwrap(), names(&["n3", "n2", "n1"]));
+        assert!(results
+            .iter()
+            .all(|r| r.status == NodeOperationStatus::Succeeded));
+    }
+
+    #[tokio::test]
+    async fn a_failure_is_reported_and_skips_the_dependent_nodes() {
+        let (nodes, dependencies) = stub_nodes();
+        let operation = |name: String| async move {
+            if name == "n2" {
+                Err(miette!("the node could not be started"))
+            } else {
+                Ok(())
+            }
+        };
+
+        let results = run_in_dependency_order(
+            dependency_waves(&nodes, &dependencies),
+            4,
+            false,
+            operation,
+            |_| {},
+        )
+        .await;
+        let statuses: Vec<(&str, NodeOperationStatus)> = results
+            .iter()
+            .map(|r| (r.name.as_str(), r.status))
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("n3", NodeOperationStatus::Succeeded),
+                ("n2", NodeOperationStatus::Failed),
+                ("n1", NodeOperationStatus::Skipped),
+            ]
+        );
+        assert_eq!(
+            results[1].error.as_deref(),
+            Some("the node could not be started")
+        );
+
+        // with continue_on_error, the remaining nodes are still started
+        let mut completed = vec![];
+        let results = run_in_dependency_order(
+            dependency_waves(&nodes, &dependencies),
+            4,
+            true,
+            operation,
+            |result| completed.push(result.name.clone()),
+        )
+        .await;
+        assert_eq!(completed, names(&["n3", "n2", "n1"]));
+        let failed: Vec<&str> = results
+            .iter()
+            .filter(|r| r.is_failed())
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(failed, vec!["n2"]);
+        assert_eq!(
+            results[2].status,
+            NodeOperationStatus::Succeeded,
+            "n1 is started even though n2 failed"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use clap::Args;
+use miette::IntoDiagnostic;
+use serde_json::json;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use ockam::identity::utils::now;
+use ockam::identity::TimestampInSeconds;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::node_events::{GetNodeEvents, NodeEventRecord};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::{Context, NodeEventKind};
+
+use crate::output::Output;
+use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
+use crate::{docs, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/events/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/events/after_long_help.txt");
+
+/// List the events emitted by the components of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct EventsCommand {
+    /// Name of the node to list the events of
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    node: Option<String>,
+
+    /// Only list the events emitted during this duration, for example 10m or 2h
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    since: Option<Duration>,
+
+    /// Only list the events of this kind, for example policy-denied
+    #[arg(long, value_name = "KIND", value_parser = parse_kind)]
+    kind: Option<NodeEventKind>,
+}
+
+impl EventsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node events".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node).await?;
+        node.require_capability(ctx, NodeCapability::NODE_EVENTS, "node events")
+            .await?;
+
+        let since = match self.since {
+            Some(since) => Some(TimestampInSeconds(
+                now().into_diagnostic()?.0.saturating_sub(since.as_secs()),
+            )),
+            None => None,
+        };
+        let request = Request::get("/node/events")
+            .body(GetNodeEvents::new(since, self.kind.map(|k| k.to_string())));
+        let events: Vec<NodeEventRecord> = node.ask(ctx, request).await?;
+        let events = events.into_iter().map(EventOutput).collect::<Vec<_>>();
+
+        let plain = opts.terminal.build_list(
+            &events,
+            "Events",
+            &format!("No events found on the node {}.", node.node_name()),
+        )?;
+        let json = events.iter().map(|e| &e.0).collect::<Vec<_>>();
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json!(&json))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+struct EventOutput(NodeEventRecord);
+
+impl Output for EventOutput {
+    fn output(&self) -> Result<String> {
+        let event = &self.0;
+        let created_at = OffsetDateTime::from_unix_timestamp(*event.created_at as i64)
+            .ok()
+            .and_then(|t| t.format(&Rfc3339).ok())
+            .unwrap_or_else(|| event.created_at.0.to_string());
+        let mut output = format!("{} {} {}", created_at, event.kind, event.subject);
+        if !event.details.is_empty() {
+            let details = event
+                .details
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            output.push_str(&format!(" ({details})"));
+        }
+        Ok(output)
+    }
+}
+
+fn parse_kind(value: &str) -> std::result::Result<NodeEventKind, String> {
+    value.parse().map_err(|_| {
+        let kinds = NodeEventKind::all()
+            .iter()
+            .map(|k| k.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("unknown event kind, expected one of: {kinds}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn an_event_is_displayed_with_its_details() {
+        let event = EventOutput(NodeEventRecord {
+            kind: "policy-denied".to_string(),
+            subject: "outlet".to_string(),
+            details: BTreeMap::from([("action".to_string(), "handle_message".to_string())]),
+            created_at: TimestampInSeconds(1708000000),
+        });
+        assert_eq!(
+            event.output().unwrap(),
+            "2024-02-15T12:26:40Z policy-denied outlet (action=handle_message)"
+        );
+        assert_eq!(
+            parse_kind("policy-denied").unwrap(),
+            NodeEventKind::PolicyDenied
+        );
+        assert!(parse_kind("policy").is_err());
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use clap::Args;
+use colorful::Colorful;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use miette::IntoDiagnostic;
+use serde_json::{json, Value};
+
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::diagnostics::NodeDiagnostics;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::redaction::{redact_json, redact_text};
+use ockam_api::NodeInfo;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::async_cmd;
+use crate::version::Version;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export_diagnostics/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export_diagnostics/after_long_help.txt");
+
+/// Export the logs, state and versions of a node into an archive, for support
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportDiagnosticsCommand {
+    /// Name of the node to export the diagnostics of.
+    node_name: Option<String>,
+
+    /// Path of the tar.gz archive to create
+    #[arg(long, short, value_name = "PATH")]
+    output: PathBuf,
+
+    /// Only keep the last megabytes of each log file
+    #[arg(long, value_name = "MEGABYTES")]
+    max_log_size: Option<u64>,
+}
+
+impl ExportDiagnosticsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node export-diagnostics".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = opts.state.get_node_or_default(&self.node_name).await?;
+        let node_name = node.name();
+        let mut archive = DiagnosticsArchive::default();
+
+        archive.add_json("versions.json", versions());
+        archive.add_json("configuration.json", configuration(&node));
+
+        // The state of the node is retrieved from the node itself, when it is running
+        let diagnostics = if node.is_running() {
+            match self.get_node_diagnostics(ctx, &opts, &node_name).await {
+                Ok(diagnostics) => serde_json::to_value(diagnostics).into_diagnostic()?,
+                Err(e) => json!({ "error": e.to_string() }),
+            }
+        } else {
+            json!({ "error": format!("the node {node_name} is not running") })
+        };
+        archive.add_json("node.json", diagnostics);
+
+        for log_file in self.log_files(&opts, &node_name)? {
+            let name = log_file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let content = read_tail(&log_file, self.max_log_size)?;
+            archive.add_text(&format!("logs/{name}"), &content);
+        }
+
+        let file = File::create(&self.output).into_diagnostic()?;
+        archive.write(file)?;
+
+        let output = self.output.display().to_string();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The diagnostics of the node {node_name} have been exported to {output}"
+            ))
+            .machine(&output)
+            .json(json!({ "path": output }))
+            .write_line()?;
+        Ok(())
+    }
+
+    async fn get_node_diagnostics(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        node_name: &str,
+    ) -> miette::Result<NodeDiagnostics> {
+        let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, node_name).await?;
+        node.require_capability(ctx, NodeCapability::DIAGNOSTICS, "diagnostics")
+            .await?;
+        node.ask(ctx, Request::get("/node/diagnostics")).await
+    }
+
+    /// Return the log files of a node, the most recent last
+    fn log_files(&self, opts: &CommandGlobalOpts, node_name: &str) -> miette::Result<Vec<PathBuf>> {
+        let node_dir = opts.state.node_dir(node_name);
+        if !node_dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut files: Vec<_> = std::fs::read_dir(node_dir)
+            .into_diagnostic()?
+            .flatten()
+            .filter(|entry| {
+                entry.file_name().to_string_lossy().starts_with("stdout")
+                    && entry.metadata().map(|m| m.is_file()).unwrap_or(false)
+            })
+            .map(|entry| entry.path())
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+/// Version of the command and its enabled features
+fn versions() -> Value {
+    let mut features = vec![];
+    if cfg!(feature = "orchestrator") {
+        features.push("orchestrator");
+    }
+    json!({
+        "version": clap::crate_version!(),
+        "long_version": Version::long(),
+        "features": features,
+    })
+}
+
+/// Configuration of the node, along with the `OCKAM_` environment variables
+fn configuration(node: &NodeInfo) -> Value {
+    let environment: serde_json::Map<String, Value> = std::env::vars()
+        .filter(|(name, _)| name.starts_with("OCKAM_"))
+        .map(|(name, value)| (name, Value::String(value)))
+        .collect();
+    json!({
+        "node": {
+            "name": node.name(),
+            "identifier": node.identifier().to_string(),
+            "verbosity": node.verbosity(),
+            "is_default": node.is_default(),
+            "is_authority": node.is_authority_node(),
+            "tcp_listener_address": node.tcp_listener_address().map(|a| a.to_string()),
+            "pid": node.pid(),
+            "status": node.status(),
+        },
+        "environment": environment,
+    })
+}
+
+/// Read a file, keeping only its last `max_size` megabytes if specified
+fn read_tail(path: &Path, max_size: Option<u64>) -> miette::Result<String> {
+    let mut file = File::open(path).into_diagnostic()?;
+    if let Some(max_size) = max_size {
+        let max_bytes = max_size.saturating_mul(1024 * 1024);
+        let len = file.metadata().into_diagnostic()?.len();
+        if len > max_bytes {
+            file.seek(SeekFrom::Start(len - max_bytes))
+                .into_diagnostic()?;
+        }
+    }
+    let mut content = vec![];
+    file.read_to_end(&mut content).into_diagnostic()?;
+    Ok(String::from_utf8_lossy(&content).into_owned())
+}
+
+/// Files of a diagnostics archive
+///
+/// Secrets are redacted from every file when it is added to the archive.
+#[derive(Default)]
+struct DiagnosticsArchive {
+    files: Vec<(String, Vec<u8>)>,
+}
+
+impl DiagnosticsArchive {
+    fn add_json(&mut self, name: &str, mut value: Value) {
+        redact_json(&mut value);
+        let content = serde_json::to_vec_pretty(&value).unwrap_or_default();
+        self.files.push((name.to_string(), content));
+    }
+
+    fn add_text(&mut self, name: &str, text: &str) {
+        self.files
+            .push((name.to_string(), redact_text(text).into_bytes()));
+    }
+
+    /// Write the files as a tar.gz archive
+    fn write(&self, writer: impl Write) -> miette::Result<()> {
+        let mtime = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let mut builder = tar::Builder::new(GzEncoder::new(writer, Compression::default()));
+        for (name, content) in &self.files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(mtime);
+            header.set_cksum();
+            builder
+                .append_data(
+                    &mut header,
+                    format!("diagnostics/{name}"),
+                    content.as_slice(),
+                )
+                .into_diagnostic()?;
+        }
+        builder
+            .into_inner()
+            .into_diagnostic()?
+            .finish()
+            .into_diagnostic()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    const SECRET: &str = "s3cr3t-marker";
+
+    #[test]
+    fn secrets_are_absent_from_the_archive() {
+        let mut archive = DiagnosticsArchive::default();
+        archive.add_json(
+            "configuration.json",
+            json!({
+                "node": { "name": "n1" },
+                "environment": { "OCKAM_ENROLLMENT_TOKEN": SECRET, "OCKAM_LOG": "info" },
+                "credential": { "payload": SECRET },
+            }),
+        );
+        archive.add_text(
+            "logs/stdout.log",
+            &format!(
+                "INFO node n1 started\nDEBUG private_key={SECRET}\nDEBUG credential {}\n",
+                "0f".repeat(48)
+            ),
+        );
+
+        let mut bytes = vec![];
+        archive.write(&mut bytes).unwrap();
+
+        let mut entries = vec![];
+        let mut tar = tar::Archive::new(GzDecoder::new(bytes.as_slice()));
+        for entry in tar.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry.path().unwrap().display().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            entries.push((name, content));
+        }
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].0, "diagnostics/configuration.json");
+        assert_eq!(entries[1].0, "diagnostics/logs/stdout.log");
+        for (_, content) in &entries {
+            assert!(!content.contains(SECRET));
+            assert!(!content.contains(&"0f".repeat(48)));
+        }
+        assert!(entries[0].1.contains("OCKAM_LOG"));
+        assert!(entries[1].1.contains("node n1 started"));
+    }
+}
@@ -1,6 +1,5 @@
 use clap::Args;
 use colorful::Colorful;
-use indoc::formatdoc;
 use miette::IntoDiagnostic;
 use serde::Serialize;
 use tokio::sync::Mutex;
@@ -9,8 +8,7 @@ use tokio::try_join;
 use ockam_api::cli_state::nodes::NodeInfo;
 use ockam_api::NodeProcessStatus;
 
-use crate::output::Output;
-use crate::terminal::OckamColor;
+use crate::terminal::{OckamColor, Table};
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts, Result};
 
@@ -93,9 +91,13 @@ pub fn print_nodes_info(
     opts: &CommandGlobalOpts,
     nodes: Vec<NodeListOutput>,
 ) -> miette::Result<()> {
+    let mut table = Table::new(&["NAME", "STATUS", "PID", "DEFAULT", "EPHEMERAL"]);
+    for node in &nodes {
+        table.add_row(node.table_row());
+    }
     let plain = opts
         .terminal
-        .build_list(&nodes, "Nodes", "No nodes found on this system.")?;
+        .build_table(&table, "No nodes found on this system.");
 
     let json = serde_json::to_string_pretty(&nodes).into_diagnostic()?;
 
@@ -115,6 +117,7 @@ pub struct NodeListOutput {
     pub status: NodeProcessStatus,
     pub pid: Option<u32>,
     pub is_default: bool,
+    pub is_ephemeral: bool,
 }
 
 impl NodeListOutput {
@@ -123,12 +126,14 @@ impl NodeListOutput {
         status: NodeProcessStatus,
         pid: Option<u32>,
         is_default: bool,
+        is_ephemeral: bool,
     ) -> Self {
         Self {
             node_name,
             status,
             pid,
             is_default,
+            is_ephemeral,
         }
     }
 
@@ -138,47 +143,29 @@ impl NodeListOutput {
             node_info.status(),
             node_info.pid(),
             node_info.is_default(),
+            node_info.is_ephemeral(),
         )
     }
 }
 
-impl Output for NodeListOutput {
-    fn output(&self) -> Result<String> {
-        let (status, process) = match self.status {
-            NodeProcessStatus::Running(pid) => (
-                "UP".color(OckamColor::Success.color()),
-                format!(
-                    "Process id {}",
-                    pid.to_string().color(OckamColor::PrimaryResource.color())
-                ),
-            ),
-            NodeProcessStatus::Zombie(pid) => (
-                "ZOMBIE".color(OckamColor::Failure.color()),
-                format!(
-                    "Process id {}",
-                    pid.to_string().color(OckamColor::PrimaryResource.color())
-                ),
-            ),
-            NodeProcessStatus::Stopped => (
-                "DOWN".color(OckamColor::Failure.color()),
-                "No process running".to_string(),
-            ),
-        };
-
-        let default = match self.is_default {
-            true => " (default)".to_string(),
-            false => "".to_string(),
-        };
-
-        let output = formatdoc! {"
-        Node {node_name}{default} {status}
-        {process}",
-        node_name = self
-            .node_name
-            .to_string()
-            .color(OckamColor::PrimaryResource.color()),
+impl NodeListOutput {
+    /// Values displayed for this node in the table of nodes
+    fn table_row(&self) -> Vec<String> {
+        let status = match self.status {
+            NodeProcessStatus::Running(_) => "UP".color(OckamColor::Success.color()),
+            NodeProcessStatus::Zombie(_) => "ZOMBIE".color(OckamColor::Failure.color()),
+            NodeProcessStatus::Stopped => "DOWN".color(OckamColor::Failure.color()),
         };
-
-        Ok(output)
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+        vec![
+            self.node_name
+                .as_str()
+                .color(OckamColor::PrimaryResource.color())
+                .to_string(),
+            status.to_string(),
+            self.pid.map(|pid| pid.to_string()).unwrap_or("-".into()),
+            yes_no(self.is_default),
+            yes_no(self.is_ephemeral),
+        ]
     }
 }
@@ -0,0 +1,94 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_info, fmt_log, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/migrate/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/migrate/after_long_help.txt");
+
+/// Apply the pending migrations of the database of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct MigrateCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// Only list the pending migrations, without applying them
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl MigrateCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node migrate".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = opts.state.get_node(&self.node_name).await?;
+        let pending_migrations = opts.state.pending_migrations().await?;
+        if pending_migrations.is_empty() {
+            opts.terminal
+                .stdout()
+                .plain(fmt_info!(
+                    "The database of the node {} has no pending migrations",
+                    self.node_name
+                        .as_str()
+                        .color(OckamColor::PrimaryResource.color())
+                ))
+                .json(serde_json::json!([]))
+                .write_line()?;
+            return Ok(());
+        }
+
+        let migrations_list = pending_migrations
+            .iter()
+            .map(|m| fmt_log!("{}", m.as_str().color(OckamColor::PrimaryResource.color())))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if self.dry_run {
+            opts.terminal
+                .stdout()
+                .plain(fmt_info!("The following migrations are pending:\n") + &migrations_list)
+                .json(serde_json::json!(pending_migrations))
+                .write_line()?;
+            return Ok(());
+        }
+
+        if node.is_running() {
+            return Err(miette!(
+                "The node {} is running. Please stop it with `ockam node stop {}` before migrating its database",
+                self.node_name,
+                self.node_name
+            ));
+        }
+
+        let applied_migrations = opts.state.migrate_database().await?;
+        opts.terminal
+            .stdout()
+            .plain(
+                fmt_ok!(
+                    "The following migrations have been applied to the database of the node {}:\n",
+                    self.node_name
+                        .as_str()
+                        .color(OckamColor::PrimaryResource.color())
+                ) + &migrations_list,
+            )
+            .json(serde_json::json!(applied_migrations))
+            .write_line()?;
+        Ok(())
+    }
+}
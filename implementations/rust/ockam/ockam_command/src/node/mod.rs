@@ -1,27 +1,48 @@
 use clap::{Args, Subcommand};
 use ockam_api::address::extract_address_value;
 
+use backup::BackupCommand;
+use check::CheckCommand;
 pub use create::CreateCommand;
 pub use create::*;
 use default::DefaultCommand;
 use delete::DeleteCommand;
+use events::EventsCommand;
+use export_diagnostics::ExportDiagnosticsCommand;
 use list::ListCommand;
 use logs::LogCommand;
+use migrate::MigrateCommand;
+use rename::RenameCommand;
+use restore::RestoreCommand;
 use show::ShowCommand;
 use start::StartCommand;
+use startup::StartupCommand;
 use stop::StopCommand;
+use topology::TopologyCommand;
+use traffic::TrafficCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
 
+mod backup;
+mod check;
 mod create;
 mod default;
 mod delete;
+mod dependencies;
+mod events;
+mod export_diagnostics;
 mod list;
 mod logs;
+mod migrate;
 mod models;
+mod rename;
+mod restore;
 mod show;
 mod start;
+mod startup;
 mod stop;
+mod topology;
+mod traffic;
 pub mod util;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -64,6 +85,26 @@ pub enum NodeSubcommand {
     Stop(StopCommand),
     #[command(display_order = 800)]
     Default(DefaultCommand),
+    #[command(display_order = 800)]
+    Rename(RenameCommand),
+    #[command(display_order = 800)]
+    ExportDiagnostics(ExportDiagnosticsCommand),
+    #[command(display_order = 800)]
+    Traffic(TrafficCommand),
+    #[command(display_order = 800)]
+    Events(EventsCommand),
+    #[command(display_order = 800)]
+    Topology(TopologyCommand),
+    #[command(display_order = 800)]
+    Startup(StartupCommand),
+    #[command(display_order = 800)]
+    Backup(BackupCommand),
+    #[command(display_order = 800)]
+    Restore(RestoreCommand),
+    #[command(display_order = 800)]
+    Migrate(MigrateCommand),
+    #[command(display_order = 800)]
+    Check(CheckCommand),
 }
 
 impl NodeSubcommand {
@@ -77,6 +118,16 @@ impl NodeSubcommand {
             NodeSubcommand::Start(c) => c.name(),
             NodeSubcommand::Stop(c) => c.name(),
             NodeSubcommand::Default(c) => c.name(),
+            NodeSubcommand::Rename(c) => c.name(),
+            NodeSubcommand::ExportDiagnostics(c) => c.name(),
+            NodeSubcommand::Traffic(c) => c.name(),
+            NodeSubcommand::Events(c) => c.name(),
+            NodeSubcommand::Topology(c) => c.name(),
+            NodeSubcommand::Startup(c) => c.name(),
+            NodeSubcommand::Backup(c) => c.name(),
+            NodeSubcommand::Restore(c) => c.name(),
+            NodeSubcommand::Migrate(c) => c.name(),
+            NodeSubcommand::Check(c) => c.name(),
         }
     }
 }
@@ -92,6 +143,16 @@ impl NodeCommand {
             NodeSubcommand::Stop(c) => c.run(opts),
             NodeSubcommand::Logs(c) => c.run(opts),
             NodeSubcommand::Default(c) => c.run(opts),
+            NodeSubcommand::Rename(c) => c.run(opts),
+            NodeSubcommand::ExportDiagnostics(c) => c.run(opts),
+            NodeSubcommand::Traffic(c) => c.run(opts),
+            NodeSubcommand::Events(c) => c.run(opts),
+            NodeSubcommand::Topology(c) => c.run(opts),
+            NodeSubcommand::Startup(c) => c.run(opts),
+            NodeSubcommand::Backup(c) => c.run(opts),
+            NodeSubcommand::Restore(c) => c.run(opts),
+            NodeSubcommand::Migrate(c) => c.run(opts),
+            NodeSubcommand::Check(c) => c.run(opts),
         }
     }
 }
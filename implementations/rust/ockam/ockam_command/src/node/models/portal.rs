@@ -23,7 +23,7 @@ impl From<InletStatus> for ShowInletStatus {
             listen_address: value.bind_addr,
             route_to_outlet: value
                 .outlet_route
-                .and_then(Route::parse)
+                .and_then(|r| r.parse::<Route>().ok())
                 .and_then(|r| route_to_multiaddr(&r)),
         }
     }
@@ -1,4 +1,6 @@
-use ockam_api::{addr_to_multiaddr, nodes::models::services::ServiceStatus};
+use ockam_api::addr_to_multiaddr;
+use ockam_api::kafka::KafkaBrokerValidation;
+use ockam_api::nodes::models::services::{ServicePluginStatus, ServiceStatus};
 use ockam_multiaddr::MultiAddr;
 use serde::Serialize;
 
@@ -9,6 +11,8 @@ pub struct ShowServiceStatus {
     pub address: Option<MultiAddr>,
     #[serde(rename = "type")]
     pub service_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validation: Option<KafkaBrokerValidation>,
 }
 
 impl From<ServiceStatus> for ShowServiceStatus {
@@ -16,6 +20,27 @@ impl From<ServiceStatus> for ShowServiceStatus {
         Self {
             address: addr_to_multiaddr(value.addr),
             service_type: value.service_type,
+            validation: value.validation,
+        }
+    }
+}
+
+/// Information to display of the service plugins in the `ockam node show` command
+#[derive(Debug, Serialize)]
+pub struct ShowServicePluginStatus {
+    pub name: String,
+    pub addresses: Vec<MultiAddr>,
+}
+
+impl From<ServicePluginStatus> for ShowServicePluginStatus {
+    fn from(value: ServicePluginStatus) -> Self {
+        Self {
+            name: value.name,
+            addresses: value
+                .addresses
+                .into_iter()
+                .filter_map(addr_to_multiaddr)
+                .collect(),
         }
     }
 }
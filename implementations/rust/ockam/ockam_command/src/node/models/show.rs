@@ -2,6 +2,8 @@ use std::fmt::Display;
 
 use colorful::Colorful;
 
+use ockam_api::cli_state::{NodeBinaryVersion, NodeExit};
+use ockam_api::nodes::models::base::{CredentialsState, CredentialsStatus};
 use ockam_multiaddr::{
     proto::{DnsAddr, Node, Tcp},
     MultiAddr,
@@ -13,7 +15,7 @@ use crate::output::Output;
 use super::{
     portal::{ShowInletStatus, ShowOutletStatus},
     secure_channel::ShowSecureChannelListener,
-    services::ShowServiceStatus,
+    services::{ShowServicePluginStatus, ShowServiceStatus},
     transport::ShowTransportStatus,
 };
 
@@ -24,14 +26,28 @@ pub struct ShowNodeResponse {
     pub name: String,
     pub is_up: bool,
     pub node_pid: Option<u32>,
+    /// Last unexpected exit of the node process, when the node is down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_exit: Option<NodeExit>,
+    /// Version of the command line showing the node
+    pub cli_version: NodeBinaryVersion,
+    /// Version of the binary which started the node, if it was recorded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_version: Option<NodeBinaryVersion>,
+    /// True if the node must be restarted to run the same version as the command line
+    pub version_mismatch: bool,
     pub route: RouteToNode,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub identity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<CredentialsStatus>,
     pub transports: Vec<ShowTransportStatus>,
     pub secure_channel_listeners: Vec<ShowSecureChannelListener>,
     pub inlets: Vec<ShowInletStatus>,
     pub outlets: Vec<ShowOutletStatus>,
     pub services: Vec<ShowServiceStatus>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub plugins: Vec<ShowServicePluginStatus>,
 }
 #[derive(Debug, Serialize)]
 pub struct RouteToNode {
@@ -67,15 +83,30 @@ impl ShowNodeResponse {
             name: name.to_owned(),
             is_up,
             node_pid,
+            last_exit: None,
+            cli_version: NodeBinaryVersion::current(),
+            node_version: None,
+            version_mismatch: false,
             route: RouteToNode { short, verbose },
             identity: None,
+            credentials: None,
             transports: Default::default(),
             secure_channel_listeners: Default::default(),
             inlets: Default::default(),
             outlets: Default::default(),
             services: Default::default(),
+            plugins: Default::default(),
         }
     }
+
+    /// Set the version of the binary which started the node
+    pub fn set_node_version(&mut self, node_version: Option<NodeBinaryVersion>) {
+        self.version_mismatch = node_version
+            .as_ref()
+            .map(|v| !self.cli_version.is_compatible_with(v))
+            .unwrap_or(false);
+        self.node_version = node_version;
+    }
 }
 
 impl Display for ShowNodeResponse {
@@ -101,6 +132,23 @@ impl Display for ShowNodeResponse {
             writeln!(buffer, "  PID: {}", node_pid)?;
         }
 
+        if let Some(last_exit) = &self.last_exit {
+            writeln!(buffer, "  Last Exit: {}", last_exit.to_string().light_red())?;
+        }
+
+        writeln!(buffer, "  Version:")?;
+        writeln!(buffer, "    CLI: {}", self.cli_version)?;
+        match &self.node_version {
+            Some(node_version) if self.version_mismatch => writeln!(
+                buffer,
+                "    Node: {} {}",
+                node_version.to_string().light_red(),
+                "(restart the node to use the CLI version)".light_red()
+            )?,
+            Some(node_version) => writeln!(buffer, "    Node: {node_version}")?,
+            None => writeln!(buffer, "    Node: unknown")?,
+        }
+
         writeln!(buffer, "  Route To Node:")?;
         if let Some(short) = &self.route.short {
             writeln!(buffer, "    Short: {short}")?;
@@ -113,6 +161,22 @@ impl Display for ShowNodeResponse {
             writeln!(buffer, "  Identity: {}", identity)?;
         }
 
+        if let Some(credentials) = &self.credentials {
+            let state = credentials.state.to_string();
+            match credentials.state {
+                CredentialsState::Ready => {
+                    writeln!(buffer, "  Credentials: {}", state.light_green())?
+                }
+                CredentialsState::Degraded => {
+                    writeln!(buffer, "  Credentials: {}", state.light_red())?;
+                    if let Some(error) = &credentials.last_error {
+                        writeln!(buffer, "    Last Error: {error}")?;
+                    }
+                }
+                _ => writeln!(buffer, "  Credentials: {state}")?,
+            }
+        }
+
         writeln!(buffer, "  Transports:")?;
         for e in &self.transports {
             writeln!(buffer, "    Transport:")?;
@@ -157,6 +221,20 @@ impl Display for ShowNodeResponse {
             if let Some(ma) = &e.address {
                 writeln!(buffer, "      Address: {ma}")?;
             }
+            if let Some(validation) = &e.validation {
+                writeln!(buffer, "      Validation: {validation}")?;
+            }
+        }
+
+        if !self.plugins.is_empty() {
+            writeln!(buffer, "  Plugins:")?;
+            for e in &self.plugins {
+                writeln!(buffer, "    Plugin:")?;
+                writeln!(buffer, "      Name: {}", e.name)?;
+                for ma in &e.addresses {
+                    writeln!(buffer, "      Address: {ma}")?;
+                }
+            }
         }
 
         Ok(())
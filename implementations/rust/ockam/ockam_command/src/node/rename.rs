@@ -0,0 +1,52 @@
+use clap::Args;
+use colorful::Colorful;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rename/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename a stopped node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RenameCommand {
+    /// Current name of the node
+    node_name: String,
+
+    /// New name of the node
+    new_node_name: String,
+}
+
+impl RenameCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node rename".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = opts
+            .state
+            .rename_node(&self.node_name, &self.new_node_name)
+            .await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The node '{}' has been renamed to '{}'",
+                &self.node_name,
+                node.name()
+            ))
+            .machine(node.name())
+            .json(serde_json::json!({ "name": node.name() }))
+            .write_line()?;
+        Ok(())
+    }
+}
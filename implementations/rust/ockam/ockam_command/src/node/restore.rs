@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use clap::Args;
+use colorful::Colorful;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/restore/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/restore/after_long_help.txt");
+
+/// Restore the database of a node from a backup
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    before_help = docs::before_help(PREVIEW_TAG),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RestoreCommand {
+    /// Name of the node
+    node_name: String,
+
+    /// Path of the backup file to restore
+    #[arg(long, value_name = "PATH")]
+    from: PathBuf,
+}
+
+impl RestoreCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node restore".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let manifest = opts.state.restore_node(&self.node_name, &self.from).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The database of the node {} has been restored from {} (created by version {})",
+                self.node_name
+                    .as_str()
+                    .color(OckamColor::PrimaryResource.color()),
+                self.from
+                    .display()
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                manifest.crate_version
+            ))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -7,9 +7,10 @@ use std::time::Duration;
 use tokio_retry::strategy::FibonacciBackoff;
 use tracing::{info, trace, warn};
 
+use ockam_api::nodes::models::api_version::NodeCapability;
 use ockam_api::nodes::models::base::NodeStatus;
 use ockam_api::nodes::models::portal::{InletList, OutletList};
-use ockam_api::nodes::models::services::ServiceList;
+use ockam_api::nodes::models::services::{ServiceList, ServicePluginList};
 use ockam_api::nodes::models::transport::TransportList;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::AsyncTryClone;
@@ -22,7 +23,7 @@ use crate::{docs, CommandGlobalOpts, Result, Terminal, TerminalStream};
 
 use super::models::portal::{ShowInletStatus, ShowOutletStatus};
 use super::models::secure_channel::ShowSecureChannelListener;
-use super::models::services::ShowServiceStatus;
+use super::models::services::{ShowServicePluginStatus, ShowServiceStatus};
 use super::models::show::ShowNodeResponse;
 use super::models::transport::ShowTransportStatus;
 
@@ -136,7 +137,7 @@ pub async fn print_query_status(
     let node_name = node.node_name();
     let node_info = cli_state.get_node(&node_name).await?;
 
-    let show_node = if !is_node_up(ctx, node, wait_until_ready).await? {
+    let mut show_node = if !is_node_up(ctx, node, wait_until_ready).await? {
         // it is expected to not be able to open an arbitrary TCP connection on an authority node
         // so in that case we display an UP status
         let is_authority_node = cli_state
@@ -146,13 +147,15 @@ pub async fn print_query_status(
             .map(|n| n.is_authority_node())
             .unwrap_or(false);
 
-        ShowNodeResponse::new(
+        let mut show_node = ShowNodeResponse::new(
             node_info.is_default(),
             &node_name,
             is_authority_node,
             node_info.tcp_listener_port(),
             node_info.pid(),
-        )
+        );
+        show_node.last_exit = cli_state.get_node_exit(&node_name)?;
+        show_node
     } else {
         let mut show_node = ShowNodeResponse::new(
             node_info.is_default(),
@@ -161,6 +164,10 @@ pub async fn print_query_status(
             node_info.tcp_listener_port(),
             node_info.pid(),
         );
+        // Get the outcome of the credential pre-fetch
+        let status: NodeStatus = node.ask(ctx, api::query_status()).await?;
+        show_node.credentials = status.credentials;
+
         // Get list of services for the node
         let services: ServiceList = node.ask(ctx, api::list_services()).await?;
         show_node.services = services
@@ -169,6 +176,21 @@ pub async fn print_query_status(
             .map(ShowServiceStatus::from)
             .collect();
 
+        // Get list of service plugins, if the node supports them
+        if node
+            .api_info(ctx)
+            .await
+            .map(|info| info.supports(NodeCapability::SERVICE_PLUGINS))
+            .unwrap_or(false)
+        {
+            let plugins: ServicePluginList = node.ask(ctx, api::list_service_plugins()).await?;
+            show_node.plugins = plugins
+                .list
+                .into_iter()
+                .map(ShowServicePluginStatus::from)
+                .collect();
+        }
+
         // Get list of TCP listeners for node
         let transports: TransportList = node.ask(ctx, api::list_tcp_listeners()).await?;
         show_node.transports = transports
@@ -200,6 +222,7 @@ pub async fn print_query_status(
 
         show_node
     };
+    show_node.set_node_version(cli_state.get_node_binary_version(&node_name).await?);
 
     opts.terminal
         .clone()
@@ -216,8 +239,8 @@ pub async fn print_query_status(
 ///
 /// If `wait_until_ready` is `true` and the node does not
 /// appear to be 'up', retry the test at time intervals up to
-/// a maximum number of retries. A use case for this is to
-/// allow a node time to start up and become ready.
+/// a maximum time, which is the `--timeout` given by the user if any.
+/// A use case for this is to allow a node time to start up and become ready.
 pub async fn is_node_up(
     ctx: &Context,
     node_client: &mut BackgroundNodeClient,
@@ -244,10 +267,14 @@ async fn is_node_accessible(
     let retries = FibonacciBackoff::from_millis(IS_NODE_ACCESSIBLE_TIME_BETWEEN_CHECKS_MS);
 
     let node_name = node_client.node_name();
+    let max_time = node_client
+        .cli_state()
+        .request_timeout()
+        .unwrap_or(IS_NODE_ACCESSIBLE_TIMEOUT);
 
     let mut total_time = Duration::from_secs(0);
     for timeout_duration in retries {
-        if total_time >= IS_NODE_ACCESSIBLE_TIMEOUT || !wait_until_ready && !total_time.is_zero() {
+        if total_time >= max_time || !wait_until_ready && !total_time.is_zero() {
             return Ok(false);
         };
         if node_client.is_accessible(ctx).await.is_ok() {
@@ -270,10 +297,14 @@ async fn is_node_ready(
     let retries = FibonacciBackoff::from_millis(IS_NODE_READY_TIME_BETWEEN_CHECKS_MS);
 
     let node_name = node_client.node_name();
+    let max_time = node_client
+        .cli_state()
+        .request_timeout()
+        .unwrap_or(IS_NODE_READY_TIMEOUT);
     let now = std::time::Instant::now();
     let mut total_time = Duration::from_secs(0);
     for timeout_duration in retries {
-        if total_time >= IS_NODE_READY_TIMEOUT || !wait_until_ready && !total_time.is_zero() {
+        if total_time >= max_time || !wait_until_ready && !total_time.is_zero() {
             return Ok(false);
         };
         // Test if node is ready
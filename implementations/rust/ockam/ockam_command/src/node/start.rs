@@ -4,6 +4,10 @@ use colorful::Colorful;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_node::Context;
 
+use crate::node::dependencies::{
+    dependency_waves, relay_dependencies, run_in_dependency_order, write_results, NodeOperation,
+    DEFAULT_NODES_CONCURRENCY,
+};
 use crate::node::show::print_query_status;
 use crate::node::util::spawn_node;
 use crate::node::CreateCommand;
@@ -23,7 +27,27 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct StartCommand {
     /// Name of the node to be started
+    #[arg(group = "nodes")]
     node_name: Option<String>,
+
+    /// Start all the nodes which are not running.
+    /// A node having a relay at another local node is started after that node
+    #[arg(long, group = "nodes")]
+    all: bool,
+
+    /// With --all, keep starting the remaining nodes when a node fails to start
+    #[arg(display_order = 901, long, requires = "all")]
+    continue_on_error: bool,
+
+    /// With --all, maximum number of nodes started at the same time
+    #[arg(display_order = 901, long, value_name = "COUNT", default_value_t = DEFAULT_NODES_CONCURRENCY)]
+    concurrency: usize,
+
+    /// Set to false to prevent the database migrations from being applied automatically
+    /// when a new version of the command is used. The node then refuses to start until
+    /// the pending migrations are applied with `ockam node migrate`
+    #[arg(long, value_name = "BOOL")]
+    auto_migrate: Option<bool>,
 }
 
 impl StartCommand {
@@ -38,13 +62,17 @@ impl StartCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if self.all {
+            return self.start_all_nodes(ctx, &opts).await;
+        }
+
         if self.node_name.is_some() || !opts.terminal.can_ask_for_user_input() {
             let node_name = opts
                 .state
                 .get_node_or_default(&self.node_name)
                 .await?
                 .name();
-            start_single_node(&node_name, opts, ctx).await?;
+            start_single_node(&node_name, self.auto_migrate, opts, ctx).await?;
             return Ok(());
         }
 
@@ -59,7 +87,7 @@ impl StartCommand {
                     .write_line()?;
             }
             1 => {
-                start_single_node(&inactive_nodes[0], opts, ctx).await?;
+                start_single_node(&inactive_nodes[0], self.auto_migrate, opts, ctx).await?;
             }
             _ => {
                 let selected_nodes = opts
@@ -72,7 +100,9 @@ impl StartCommand {
                             .plain(fmt_info!("No node selected, exiting gratefully!"))
                             .write_line()?;
                     }
-                    1 => start_single_node(&selected_nodes[0], opts, ctx).await?,
+                    1 => {
+                        start_single_node(&selected_nodes[0], self.auto_migrate, opts, ctx).await?
+                    }
                     _ => {
                         if !opts.terminal.confirm_interactively(format!(
                             "You are about to start the given nodes:[ {} ]. Confirm?",
@@ -86,7 +116,8 @@ impl StartCommand {
                         }
 
                         let formatted_starts_result =
-                            start_multiple_nodes(ctx, &opts, &selected_nodes).await?;
+                            start_multiple_nodes(ctx, &opts, &selected_nodes, self.auto_migrate)
+                                .await?;
 
                         opts.terminal
                             .stdout()
@@ -98,11 +129,50 @@ impl StartCommand {
         }
         Ok(())
     }
+
+    /// Start all the inactive nodes, concurrently, after the nodes they depend on
+    async fn start_all_nodes(&self, ctx: &Context, opts: &CommandGlobalOpts) -> miette::Result<()> {
+        let nodes = opts.state.get_nodes().await?;
+        let inactive_nodes: Vec<String> = nodes
+            .iter()
+            .filter(|node| !node.is_running())
+            .map(|node| node.name())
+            .collect();
+        if inactive_nodes.is_empty() {
+            opts.terminal
+                .stdout()
+                .plain(fmt_info!(
+                    "All the nodes are already started, nothing to do. Exiting gratefully"
+                ))
+                .write_line()?;
+            return Ok(());
+        }
+
+        let dependencies = relay_dependencies(&opts.state, &nodes).await?;
+        let results = run_in_dependency_order(
+            dependency_waves(&inactive_nodes, &dependencies),
+            self.concurrency,
+            self.continue_on_error,
+            |node_name| async move {
+                run_node(&node_name, self.auto_migrate, ctx, opts)
+                    .await
+                    .map(|_| ())
+            },
+            |result| {
+                let _ = opts
+                    .terminal
+                    .write_line(result.plain_output(NodeOperation::Start));
+            },
+        )
+        .await;
+        write_results(opts, &results, NodeOperation::Start)
+    }
 }
 
 /// Starts a single node and display the output on the console
 async fn start_single_node(
     node_name: &str,
+    auto_migrate: Option<bool>,
     mut opts: CommandGlobalOpts,
     ctx: &Context,
 ) -> miette::Result<()> {
@@ -122,7 +192,7 @@ async fn start_single_node(
         return Ok(());
     }
 
-    let mut node: BackgroundNodeClient = run_node(node_name, ctx, &opts).await?;
+    let mut node: BackgroundNodeClient = run_node(node_name, auto_migrate, ctx, &opts).await?;
     print_query_status(&opts, ctx, &mut node, true).await?;
     Ok(())
 }
@@ -133,11 +203,12 @@ async fn start_multiple_nodes(
     ctx: &Context,
     opts: &CommandGlobalOpts,
     node_selected: &[String],
+    auto_migrate: Option<bool>,
 ) -> miette::Result<Vec<String>> {
     let mut node_error_flag: bool = false;
     let mut node_starts_output: Vec<String> = vec![];
     for node_name in node_selected {
-        match run_node(node_name, ctx, opts).await {
+        match run_node(node_name, auto_migrate, ctx, opts).await {
             Ok(_) => node_starts_output.push(fmt_ok!("{node_name}")),
             Err(_) => {
                 node_error_flag = true;
@@ -154,10 +225,17 @@ async fn start_multiple_nodes(
 /// Run a single node. Return the BackgroundNode instance of the created node or error
 async fn run_node(
     node_name: &str,
+    auto_migrate: Option<bool>,
     ctx: &Context,
     opts: &CommandGlobalOpts,
 ) -> miette::Result<BackgroundNodeClient> {
     let node_info = opts.state.get_node(node_name).await?;
+    if let Some(auto_migrate) = auto_migrate {
+        opts.state
+            .set_node_auto_migrate(node_name, auto_migrate)
+            .await?;
+    }
+    opts.state.check_no_pending_migrations(node_name).await?;
     opts.state.stop_node(node_name, false).await?;
     let node_address = node_info
         .tcp_listener_address()
@@ -0,0 +1,54 @@
+use clap::Args;
+
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::startup::StartupReport;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/startup/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/startup/after_long_help.txt");
+
+/// Show the outcome and timings of the components started with a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct StartupCommand {
+    /// Name of the node to show the startup of
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    node: Option<String>,
+}
+
+impl StartupCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node startup".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node).await?;
+        node.require_capability(ctx, NodeCapability::STARTUP_REPORT, "startup report")
+            .await?;
+        let report: StartupReport = node.ask(ctx, Request::get("/node/startup")).await?;
+
+        opts.terminal
+            .stdout()
+            .plain(&report)
+            .json(serde_json::to_value(&report).unwrap_or_default())
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
+use crate::node::dependencies::{
+    dependency_waves, relay_dependencies, run_in_dependency_order, write_results, NodeOperation,
+    DEFAULT_NODES_CONCURRENCY,
+};
 use crate::util::async_cmd;
 use crate::{color, docs, fmt_info, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor};
 
@@ -5,6 +9,8 @@ use clap::Args;
 use colorful::Colorful;
 use miette::miette;
 
+use ockam_api::cli_state::NodeInfo;
+
 const LONG_ABOUT: &str = include_str!("./static/stop/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/stop/after_long_help.txt");
@@ -18,11 +24,25 @@ const AFTER_LONG_HELP: &str = include_str!("./static/stop/after_long_help.txt");
 )]
 pub struct StopCommand {
     /// Name of the node.
+    #[arg(group = "nodes")]
     node_name: Option<String>,
 
+    /// Stop all the running nodes.
+    /// A node having a relay at another local node is stopped before that node
+    #[arg(long, group = "nodes")]
+    all: bool,
+
     /// Whether to use the SIGTERM or SIGKILL signal to stop the node
     #[arg(short, long)]
     force: bool,
+
+    /// With --all, keep stopping the remaining nodes when a node fails to stop
+    #[arg(display_order = 901, long, requires = "all")]
+    continue_on_error: bool,
+
+    /// With --all, maximum number of nodes stopped at the same time
+    #[arg(display_order = 901, long, value_name = "COUNT", default_value_t = DEFAULT_NODES_CONCURRENCY)]
+    concurrency: usize,
 }
 
 impl StopCommand {
@@ -37,10 +57,8 @@ impl StopCommand {
     }
 
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let running_nodes = opts
-            .state
-            .get_nodes()
-            .await?
+        let nodes = opts.state.get_nodes().await?;
+        let running_nodes = nodes
             .iter()
             .filter(|node| node.is_running())
             .map(|node| node.name())
@@ -53,6 +71,10 @@ impl StopCommand {
             return Ok(());
         }
 
+        if self.all {
+            return self.stop_all_nodes(&opts, &nodes, running_nodes).await;
+        }
+
         if self.node_name.is_some() || !opts.terminal.can_ask_for_user_input() {
             let node_name = opts
                 .state
@@ -103,6 +125,36 @@ impl StopCommand {
         }
         Ok(())
     }
+
+    /// Stop all the running nodes, concurrently, before the nodes they depend on
+    async fn stop_all_nodes(
+        &self,
+        opts: &CommandGlobalOpts,
+        nodes: &[NodeInfo],
+        running_nodes: Vec<String>,
+    ) -> miette::Result<()> {
+        let dependencies = relay_dependencies(&opts.state, nodes).await?;
+        let mut waves = dependency_waves(&running_nodes, &dependencies);
+        waves.reverse();
+        let results = run_in_dependency_order(
+            waves,
+            self.concurrency,
+            self.continue_on_error,
+            |node_name| async move {
+                opts.state
+                    .stop_node(&node_name, self.force)
+                    .await
+                    .map_err(miette::Report::from)
+            },
+            |result| {
+                let _ = opts
+                    .terminal
+                    .write_line(result.plain_output(NodeOperation::Stop));
+            },
+        )
+        .await;
+        write_results(opts, &results, NodeOperation::Stop)
+    }
 }
 
 async fn stop_node(opts: CommandGlobalOpts, node_name: &str, force: bool) -> miette::Result<()> {
@@ -0,0 +1,71 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::topology::NodeTopology;
+use ockam_api::nodes::topology::TopologyGraph;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/topology/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/topology/after_long_help.txt");
+
+/// Export the connections of one or several nodes as a DOT or JSON graph
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TopologyCommand {
+    /// Name of the node to export the topology of
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value, conflicts_with = "cluster")]
+    node: Option<String>,
+
+    /// Names of the nodes to export a merged topology of, separated by commas
+    #[arg(long, value_name = "NODE_NAMES", value_delimiter = ',', num_args = 1..)]
+    cluster: Vec<String>,
+}
+
+impl TopologyCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node topology".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node_names = if self.cluster.is_empty() {
+            vec![opts.state.get_node_or_default(&self.node).await?.name()]
+        } else {
+            self.cluster.clone()
+        };
+
+        let mut topologies = vec![];
+        for node_name in node_names {
+            let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, &node_name).await?;
+            node.require_capability(ctx, NodeCapability::NODE_TOPOLOGY, "node topology")
+                .await?;
+            let topology: NodeTopology = node.ask(ctx, Request::get("/node/topology")).await?;
+            topologies.push(topology);
+        }
+
+        let graph = TopologyGraph::new(&topologies);
+        opts.terminal
+            .stdout()
+            .plain(graph.to_dot())
+            .json(graph.to_json().into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::traffic::{SetTrafficAccounting, TrafficStats, WorkerTraffic};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_node::Context;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_info, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/traffic/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/traffic/after_long_help.txt");
+
+/// Show the sizes of the messages handled by the transports and secure channels of a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct TrafficCommand {
+    /// Name of the node to show the message sizes of
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    node: Option<String>,
+
+    /// Start recording the message sizes, discarding the previous records
+    #[arg(long, conflicts_with = "disable")]
+    enable: bool,
+
+    /// Stop recording the message sizes
+    #[arg(long)]
+    disable: bool,
+}
+
+impl TrafficCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "node traffic".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node).await?;
+        node.require_capability(
+            ctx,
+            NodeCapability::TRAFFIC_ACCOUNTING,
+            "message size accounting",
+        )
+        .await?;
+        let traffic: TrafficStats = if self.enable || self.disable {
+            let request =
+                Request::post("/node/traffic").body(SetTrafficAccounting::new(self.enable));
+            node.ask(ctx, request).await?
+        } else {
+            node.ask(ctx, Request::get("/node/traffic")).await?
+        };
+
+        let plain = if self.enable {
+            fmt_ok!(
+                "Message sizes are now recorded on the node {}",
+                node.node_name()
+            )
+        } else if self.disable {
+            fmt_ok!(
+                "Message sizes are not recorded anymore on the node {}",
+                node.node_name()
+            )
+        } else if !traffic.enabled && traffic.workers.is_empty() {
+            fmt_info!(
+                "Message sizes are not recorded on the node {}. Use --enable to start recording them",
+                node.node_name()
+            )
+        } else {
+            render_table(&traffic.workers)
+        };
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_value(&traffic).unwrap_or_default())
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Render the message sizes as a table, one row per worker address and direction
+fn render_table(workers: &[WorkerTraffic]) -> String {
+    let address_width = workers
+        .iter()
+        .map(|w| w.address.len())
+        .chain(["ADDRESS".len()])
+        .max()
+        .unwrap_or_default();
+    let mut table = format!(
+        "{:<address_width$}  {:<9}  {:>8}  {:>8}  {:>8}  {:>8}\n",
+        "ADDRESS", "DIRECTION", "COUNT", "P50", "P95", "MAX"
+    );
+    for worker in workers {
+        table.push_str(&format!(
+            "{:<address_width$}  {:<9}  {:>8}  {:>8}  {:>8}  {:>8}\n",
+            worker.address, worker.direction, worker.count, worker.p50, worker.p95, worker.max
+        ));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_has_one_row_per_worker_and_direction() {
+        let workers = vec![
+            WorkerTraffic {
+                address: "0#encryptor".to_string(),
+                direction: "inbound".to_string(),
+                count: 10,
+                p50: 100,
+                p95: 100,
+                max: 100,
+            },
+            WorkerTraffic {
+                address: "0#encryptor".to_string(),
+                direction: "outbound".to_string(),
+                count: 10,
+                p50: 135,
+                p95: 135,
+                max: 135,
+            },
+        ];
+        let table = render_table(&workers);
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("ADDRESS"));
+        assert!(lines[2].starts_with("0#encryptor  outbound"));
+        assert!(lines[2].ends_with("135"));
+    }
+}
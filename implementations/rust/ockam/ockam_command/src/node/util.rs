@@ -61,6 +61,54 @@ pub async fn initialize_default_node(
     Ok(())
 }
 
+/// Make sure that the node used by a command runs with the identity selected for that command.
+///
+/// When an identity is explicitly selected, either with `--identity` or with the
+/// `OCKAM_IDENTITY` environment variable:
+///
+///  - the default node is created with that identity if it doesn't exist yet
+///  - an error is returned if the node (the given one or the default one) uses another identity
+///
+/// Otherwise this behaves like [`initialize_default_node`].
+pub async fn initialize_node_for_identity(
+    ctx: &Context,
+    opts: &CommandGlobalOpts,
+    node_name: &Option<String>,
+    identity_name: &Option<String>,
+) -> miette::Result<()> {
+    let identity_name = match opts.state.get_explicit_identity_name(identity_name)? {
+        Some(identity_name) => identity_name,
+        None => return initialize_default_node(ctx, opts).await,
+    };
+    let identifier = opts.state.get_identifier_by_name(&identity_name).await?;
+
+    let node = match node_name {
+        Some(node_name) => Some(opts.state.get_node(node_name).await?),
+        None => opts.state.get_default_node().await.ok(),
+    };
+    match node {
+        Some(node) if node.identifier() != identifier => Err(miette!(
+            "The node {} uses the identity {}, not the identity {} ({}). Please use a node created with `ockam node create --identity {}`",
+            node.name(),
+            node.identifier(),
+            identity_name,
+            identifier,
+            identity_name
+        )),
+        Some(_) => Ok(()),
+        None => {
+            let mut cmd = CreateCommand::default();
+            cmd.identity = Some(identity_name);
+            let node_name = cmd.name.clone();
+            cmd.spawn_background_node(opts).await?;
+            let mut node =
+                BackgroundNodeClient::create_to_node(ctx, &opts.state, &node_name).await?;
+            is_node_up(ctx, &mut node, true).await?;
+            Ok(())
+        }
+    }
+}
+
 /// A utility function to spawn a new node into foreground mode
 #[allow(clippy::too_many_arguments)]
 pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette::Result<()> {
@@ -69,9 +117,18 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         name,
         identity: identity_name,
         tcp_listener_address: address,
+        tcp_listener_max_accept_rate,
+        tcp_listener_max_connections_per_ip,
+        tcp_listener_allow,
+        tcp_listener_deny,
         launch_config,
         trust_opts,
+        eager_credentials,
         opentelemetry_context,
+        backup_interval,
+        backup_dir,
+        backup_keep,
+        auto_migrate,
         ..
     } = cmd;
     let TrustOpts {
@@ -94,10 +151,34 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         "--child-process".to_string(),
     ];
 
+    if let Some(rate) = tcp_listener_max_accept_rate {
+        args.push("--tcp-listener-max-accept-rate".to_string());
+        args.push(rate.to_string());
+    }
+
+    if let Some(max) = tcp_listener_max_connections_per_ip {
+        args.push("--tcp-listener-max-connections-per-ip".to_string());
+        args.push(max.to_string());
+    }
+
+    for source in tcp_listener_allow {
+        args.push("--tcp-listener-allow".to_string());
+        args.push(source.to_string());
+    }
+
+    for source in tcp_listener_deny {
+        args.push("--tcp-listener-deny".to_string());
+        args.push(source.to_string());
+    }
+
     if expect_cached_credential {
         args.push("--expect-cached-credential".to_string());
     }
 
+    if eager_credentials {
+        args.push("--eager-credentials".to_string());
+    }
+
     if skip_is_running_check {
         args.push("--skip-is-running-check".to_string());
     }
@@ -131,6 +212,20 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
         args.push(authority_route.to_string());
     }
 
+    if let (Some(backup_interval), Some(backup_dir)) = (backup_interval, backup_dir) {
+        args.push("--backup-interval".to_string());
+        args.push(format!("{}ms", backup_interval.as_millis()));
+        args.push("--backup-dir".to_string());
+        args.push(backup_dir.to_string_lossy().to_string());
+        args.push("--backup-keep".to_string());
+        args.push(backup_keep.to_string());
+    }
+
+    if let Some(auto_migrate) = auto_migrate {
+        args.push("--auto-migrate".to_string());
+        args.push(auto_migrate.to_string());
+    }
+
     if let Some(opentelemetry_context) = opentelemetry_context {
         args.push("--opentelemetry-context".to_string());
         args.push(opentelemetry_context.to_string());
@@ -138,11 +233,31 @@ pub async fn spawn_node(opts: &CommandGlobalOpts, cmd: CreateCommand) -> miette:
 
     args.push(name.to_owned());
 
-    run_ockam(args).await
+    run_ockam(opts, &name, args).await
 }
 
-/// Run the ockam command line with specific arguments
-pub async fn run_ockam(args: Vec<String>) -> miette::Result<()> {
+/// Shell script running a background node process and recording its exit status and time,
+/// unless the node was stopped on purpose.
+/// Its arguments are: the stderr file, the exit file, the stopped file, the ockam executable
+/// and the arguments of the executable
+#[cfg(unix)]
+const NODE_WATCHER_SCRIPT: &str = r#"stderr_file=$1; exit_file=$2; stopped_file=$3; shift 3
+"$@" 2>"$stderr_file"
+status=$?
+if [ "$status" -ne 0 ] && [ ! -f "$stopped_file" ]; then
+  echo "$status $(date +%s)" > "$exit_file"
+fi"#;
+
+/// Run the ockam command line with specific arguments to start a background node.
+///
+/// The stderr output of the node process is captured in a file of the node directory.
+/// On Unix systems, a shell watcher records the exit status of the process when it exits,
+/// so that `ockam node show` can display the reason of a crash
+pub async fn run_ockam(
+    opts: &CommandGlobalOpts,
+    node_name: &str,
+    args: Vec<String>,
+) -> miette::Result<()> {
     // On systems with non-obvious path setups (or during
     // development) re-executing the current binary is a more
     // deterministic way of starting a node.
@@ -151,13 +266,88 @@ pub async fn run_ockam(args: Vec<String>) -> miette::Result<()> {
             .unwrap()
             .into()
     });
-    Command::new(ockam_exe)
-        .args(args)
+
+    // keep the record of a previous crash before starting the new process
+    opts.state.archive_node_exit(node_name)?;
+    let stderr_file = opts.state.node_stderr_file(node_name)?;
+
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg(NODE_WATCHER_SCRIPT)
+            .arg("ockam-node-watcher")
+            .arg(stderr_file)
+            .arg(opts.state.node_exit_file(node_name)?)
+            .arg(opts.state.node_stopped_file(node_name)?)
+            .arg(ockam_exe)
+            .args(args)
+            .stderr(Stdio::null());
+        command
+    };
+    #[cfg(not(unix))]
+    let mut command = {
+        let mut command = Command::new(ockam_exe);
+        command
+            .args(args)
+            .stderr(std::fs::File::create(stderr_file).into_diagnostic()?);
+        command
+    };
+
+    command
         .stdout(Stdio::null())
-        .stderr(Stdio::null())
         .stdin(Stdio::null())
         .spawn()
         .into_diagnostic()
         .context("failed to spawn node")?;
     Ok(())
 }
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use ockam_api::cli_state::NodeExit;
+    use tempfile::tempdir;
+
+    #[test]
+    fn the_watcher_records_the_exit_of_a_crashed_process() {
+        let dir = tempdir().unwrap();
+        let stderr_file = dir.path().join("stderr.log");
+        let exit_file = dir.path().join("exit");
+        let stopped_file = dir.path().join("stopped");
+        let run_watcher = |process_script: &str| {
+            Command::new("sh")
+                .arg("-c")
+                .arg(NODE_WATCHER_SCRIPT)
+                .arg("ockam-node-watcher")
+                .arg(&stderr_file)
+                .arg(&exit_file)
+                .arg(&stopped_file)
+                .args(["sh", "-c", process_script])
+                .status()
+                .unwrap()
+        };
+
+        // a process writing a panic on stderr and exiting with an error
+        run_watcher("echo \"thread 'main' panicked at src/main.rs:1:1:\nboom\" >&2; exit 101");
+        let exit = NodeExit::parse(
+            &std::fs::read_to_string(&exit_file).unwrap(),
+            &std::fs::read_to_string(&stderr_file).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(exit.code, Some(101));
+        assert_eq!(exit.panic, Some("boom".to_string()));
+
+        // a process terminated by a signal
+        run_watcher("kill -ABRT $$");
+        let exit = NodeExit::parse(&std::fs::read_to_string(&exit_file).unwrap(), "").unwrap();
+        assert_eq!(exit.signal, Some(6));
+
+        // the exit of a process stopped on purpose is not recorded
+        std::fs::remove_file(&exit_file).unwrap();
+        std::fs::write(&stopped_file, "").unwrap();
+        run_watcher("exit 1");
+        assert!(!exit_file.exists());
+    }
+}
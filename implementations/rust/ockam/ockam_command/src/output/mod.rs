@@ -1,8 +1,10 @@
 mod encode_format;
 #[allow(clippy::module_inception)]
 pub(crate) mod output;
+mod output_fields;
 mod output_format;
 
 pub use encode_format::*;
 pub use output::*;
+pub use output_fields::*;
 pub use output_format::*;
@@ -16,11 +16,12 @@ use ockam::identity::{Credential, Identifier, Identity, TimestampInSeconds};
 use ockam_api::cli_state::vaults::NamedVault;
 use ockam_api::cloud::project::Project;
 use ockam_api::cloud::space::Space;
-use ockam_api::nodes::models::portal::{InletStatus, OutletStatus};
+use ockam_api::nodes::models::portal::{InletStatus, OutletStatus, PortalConnectionStatus};
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelResponse, ShowSecureChannelResponse,
 };
-use ockam_api::route_to_multiaddr;
+use ockam_api::nodes::models::vault::VaultKeyStatus;
+use ockam_api::{route_to_multiaddr, route_to_multiaddr_or_route_string};
 use ockam_core::api::Reply;
 use ockam_core::{route, Route};
 use ockam_vault::{
@@ -187,7 +188,7 @@ impl Output for ShowSecureChannelResponse {
     fn output(&self) -> Result<String> {
         let s = match &self.channel {
             Some(addr) => {
-                format!(
+                let mut s = format!(
                     "\n  Secure Channel:\n{} {}\n{} {}\n{} {}",
                     "  •         At: ".light_magenta(),
                     route_to_multiaddr(&route![addr.to_string()])
@@ -204,7 +205,39 @@ impl Output for ShowSecureChannelResponse {
                         .map(|id| id.clone().light_yellow().to_string())
                         .collect::<Vec<String>>()
                         .join("\n\t")
-                )
+                );
+                if let Some(clock_skew) = self.clock_skew {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  • Clock skew: ".light_magenta(),
+                        format!("{clock_skew:+}s").light_yellow()
+                    ));
+                }
+                if let (Some(encryptor_rekeys), Some(decryptor_rekeys)) =
+                    (self.encryptor_rekeys, self.decryptor_rekeys)
+                {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •     Rekeys: ".light_magenta(),
+                        format!("{encryptor_rekeys} sent, {decryptor_rekeys} received")
+                            .light_yellow()
+                    ));
+                }
+                if let Some(max_plaintext_size) = self.max_plaintext_size {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •   Max size: ".light_magenta(),
+                        format!("{max_plaintext_size} bytes").light_yellow()
+                    ));
+                }
+                if self.previous_trust_options == Some(true) {
+                    s.push_str(&format!(
+                        "\n{} {}",
+                        "  •      Trust: ".light_magenta(),
+                        "established under previous trust options".light_yellow()
+                    ));
+                }
+                s
             }
             None => format!("{}", "Channel not found".red()),
         };
@@ -230,9 +263,14 @@ Outlet:
 
     fn list_output(&self) -> Result<String> {
         let output = format!(
-            r#"From address {} to TCP server {}"#,
+            r#"From address {} to TCP server {}{}"#,
             color_primary(self.worker_address()?.to_string()),
             color_primary(self.socket_addr.to_string()),
+            if self.paused_at.is_some() {
+                " (paused)"
+            } else {
+                ""
+            },
         );
 
         Ok(output)
@@ -250,9 +288,8 @@ impl Output for InletStatus {
         let outlet = self
             .outlet_route
             .as_ref()
-            .and_then(Route::parse)
-            .and_then(|r| route_to_multiaddr(&r))
-            .map(|addr| addr.to_string())
+            .and_then(|r| r.parse::<Route>().ok())
+            .map(|r| route_to_multiaddr_or_route_string(&r))
             .unwrap_or("N/A".to_string());
 
         let output = format!(
@@ -285,11 +322,16 @@ Inlet
 
     fn list_output(&self) -> Result<String> {
         let output = format!(
-            r#"Inlet {}
+            r#"Inlet {}{}
 From {} to {}"#,
             self.alias
                 .to_string()
                 .color(OckamColor::PrimaryResource.color()),
+            if self.paused_at.is_some() {
+                " (paused)"
+            } else {
+                ""
+            },
             self.bind_addr
                 .to_string()
                 .color(OckamColor::PrimaryResource.color()),
@@ -304,6 +346,50 @@ From {} to {}"#,
     }
 }
 
+impl Output for PortalConnectionStatus {
+    fn output(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(output, "Connection {}", color_primary(&self.id))?;
+        writeln!(output, "    Inlet: {}", color_primary(&self.alias))?;
+        writeln!(output, "    Client Address: {}", self.peer_addr)?;
+        writeln!(
+            output,
+            "    Established At: {}",
+            human_readable_time(TimestampInSeconds(self.established_at))
+        )?;
+        writeln!(output, "    Bytes Read: {}", self.bytes_read)?;
+        write!(output, "    Bytes Written: {}", self.bytes_written)?;
+        Ok(output)
+    }
+}
+
+impl Output for VaultKeyStatus {
+    fn output(&self) -> Result<String> {
+        let mut output = String::new();
+        writeln!(output, "Key {}", color_primary(&self.key_id))?;
+        writeln!(output, "    Type: {}", self.key_type)?;
+        writeln!(
+            output,
+            "    Created At: {}",
+            self.created_at
+                .map(human_readable_time)
+                .unwrap_or_else(|| "unknown".to_string())
+        )?;
+        write!(
+            output,
+            "    Referenced By: {}",
+            match &self.referenced_by {
+                Some(reference) => reference.clone(),
+                None => "none (orphaned)".to_string(),
+            }
+        )?;
+        if self.referenced_by.is_some() && !self.in_use {
+            write!(output, " (orphaned)")?;
+        }
+        Ok(output)
+    }
+}
+
 impl Output for NamedVault {
     fn output(&self) -> Result<String> {
         let mut output = String::new();
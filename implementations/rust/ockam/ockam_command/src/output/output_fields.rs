@@ -0,0 +1,294 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use miette::miette;
+use serde_json::{Map, Value};
+
+use crate::Result;
+
+/// Path to a field of the JSON output of a command, for example `changes[0].identifier`.
+///
+/// A path is made of keys separated by dots, where each key can be followed by some array indices.
+/// Indices can also be written as keys: `changes.0.identifier`.
+/// When a key is applied to an array, it is applied to each element of the array,
+/// so that `node_name` selects the name of every node in the output of `ockam node list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputField {
+    path: String,
+    segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+impl Display for OutputField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl FromStr for OutputField {
+    type Err = String;
+
+    fn from_str(path: &str) -> std::result::Result<Self, Self::Err> {
+        let path = path.trim().trim_start_matches('.');
+        if path.is_empty() {
+            return Err("the field path can not be empty".to_string());
+        }
+        let mut segments = vec![];
+        for part in path.split('.') {
+            let (key, mut indices) = match part.find('[') {
+                Some(start) => part.split_at(start),
+                None => (part, ""),
+            };
+            if !key.is_empty() {
+                match key.parse::<usize>() {
+                    Ok(index) => segments.push(PathSegment::Index(index)),
+                    Err(_) => segments.push(PathSegment::Key(key.to_string())),
+                }
+            } else if indices.is_empty() {
+                return Err(format!("the field path `{path}` contains an empty key"));
+            }
+            while !indices.is_empty() {
+                let index = indices
+                    .strip_prefix('[')
+                    .and_then(|rest| rest.split_once(']'))
+                    .and_then(|(index, rest)| index.parse::<usize>().ok().map(|i| (i, rest)));
+                match index {
+                    Some((index, rest)) => {
+                        segments.push(PathSegment::Index(index));
+                        indices = rest;
+                    }
+                    None => {
+                        return Err(format!(
+                            "the field path `{path}` contains an invalid array index in `{part}`"
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            path: path.to_string(),
+            segments,
+        })
+    }
+}
+
+impl OutputField {
+    /// Select the value of this field in a JSON value
+    fn select(&self, root: &Value) -> Result<Value> {
+        Ok(
+            Self::select_segments(root, &self.segments).map_err(|reason| {
+                miette!(
+                    "Invalid output field `{}`: {reason}. Available top-level keys: [{}]",
+                    self.path,
+                    available_keys(root).join(", ")
+                )
+            })?,
+        )
+    }
+
+    fn select_segments(
+        value: &Value,
+        segments: &[PathSegment],
+    ) -> std::result::Result<Value, String> {
+        let (segment, rest) = match segments.split_first() {
+            Some(split) => split,
+            None => return Ok(value.clone()),
+        };
+        match (segment, value) {
+            (PathSegment::Key(key), Value::Object(map)) => match map.get(key) {
+                Some(value) => Self::select_segments(value, rest),
+                None => Err(format!("there is no key `{key}`")),
+            },
+            // a key applied to a list selects that key in every element of the list
+            (PathSegment::Key(key), Value::Array(items)) => {
+                if !items.is_empty() && !items.iter().any(|item| item.get(key).is_some()) {
+                    return Err(format!("there is no key `{key}` in the listed items"));
+                }
+                items
+                    .iter()
+                    .map(|item| match item.get(key) {
+                        Some(value) => Self::select_segments(value, rest),
+                        None => Ok(Value::Null),
+                    })
+                    .collect::<std::result::Result<Vec<_>, _>>()
+                    .map(Value::Array)
+            }
+            (PathSegment::Index(index), Value::Array(items)) => match items.get(*index) {
+                Some(value) => Self::select_segments(value, rest),
+                None => Err(format!(
+                    "the index {index} is out of bounds for a list of {} items",
+                    items.len()
+                )),
+            },
+            (PathSegment::Key(key), _) => Err(format!("`{key}` can not be selected in `{value}`")),
+            (PathSegment::Index(index), _) => Err(format!(
+                "`{value}` is not a list and can not be indexed with {index}"
+            )),
+        }
+    }
+}
+
+/// Project the JSON output of a command on a list of fields.
+///
+/// A single field is displayed as plain lines: one line for a scalar value, one line per element for a list.
+/// Several fields are displayed as a JSON object where each field path is associated to its value.
+pub fn project_output_fields(json: &str, fields: &[OutputField]) -> Result<String> {
+    let root: Value =
+        serde_json::from_str(json).map_err(|e| miette!("The output is not valid JSON: {e}"))?;
+    match fields {
+        [field] => Ok(plain_lines(&field.select(&root)?)),
+        _ => {
+            let mut projection = Map::new();
+            for field in fields {
+                projection.insert(field.path.clone(), field.select(&root)?);
+            }
+            Ok(serde_json::to_string_pretty(&Value::Object(projection))?)
+        }
+    }
+}
+
+fn plain_lines(value: &Value) -> String {
+    match value {
+        Value::Array(items) => items.iter().map(plain_line).collect::<Vec<_>>().join("\n"),
+        Value::Object(_) => serde_json::to_string_pretty(value).unwrap_or(value.to_string()),
+        _ => plain_line(value),
+    }
+}
+
+fn plain_line(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Keys of the output if it is an object, or keys of its elements if it is a list
+fn available_keys(root: &Value) -> Vec<String> {
+    let mut keys: Vec<String> = vec![];
+    let objects = match root {
+        Value::Array(items) => items.iter().collect(),
+        value => vec![value],
+    };
+    for key in objects
+        .into_iter()
+        .filter_map(|o| o.as_object())
+        .flat_map(|o| o.keys())
+    {
+        if !keys.contains(key) {
+            keys.push(key.clone());
+        }
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(paths: &[&str]) -> Vec<OutputField> {
+        paths.iter().map(|p| p.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_paths() {
+        let field: OutputField = "changes[0].identifier".parse().unwrap();
+        assert_eq!(
+            field.segments,
+            vec![
+                PathSegment::Key("changes".into()),
+                PathSegment::Index(0),
+                PathSegment::Key("identifier".into())
+            ]
+        );
+        let same: OutputField = "changes.0.identifier".parse().unwrap();
+        assert_eq!(field.segments, same.segments);
+
+        let field: OutputField = "[1].status.pid".parse().unwrap();
+        assert_eq!(field.segments[0], PathSegment::Index(1));
+        let field: OutputField = "matrix[1][2]".parse().unwrap();
+        assert_eq!(field.segments.len(), 3);
+
+        assert!("".parse::<OutputField>().is_err());
+        assert!("a..b".parse::<OutputField>().is_err());
+        assert!("a[x]".parse::<OutputField>().is_err());
+        assert!("a[0".parse::<OutputField>().is_err());
+    }
+
+    #[test]
+    fn project_single_field() {
+        let output = json!({
+            "identifier": "I123",
+            "changes": [
+                { "identifier": "c1", "revoke_all_purpose_keys": false },
+                { "identifier": "c2", "revoke_all_purpose_keys": true }
+            ]
+        })
+        .to_string();
+
+        let projected = project_output_fields(&output, &fields(&["identifier"])).unwrap();
+        assert_eq!(projected, "I123");
+
+        let projected =
+            project_output_fields(&output, &fields(&["changes[1].revoke_all_purpose_keys"]))
+                .unwrap();
+        assert_eq!(projected, "true");
+
+        let projected = project_output_fields(&output, &fields(&["changes.identifier"])).unwrap();
+        assert_eq!(projected, "c1\nc2");
+    }
+
+    #[test]
+    fn project_list() {
+        let output = json!([
+            { "node_name": "n1", "status": { "status": "running", "pid": 12 } },
+            { "node_name": "n2", "status": { "status": "stopped" } }
+        ])
+        .to_string();
+
+        let projected = project_output_fields(&output, &fields(&["node_name"])).unwrap();
+        assert_eq!(projected, "n1\nn2");
+
+        let projected = project_output_fields(&output, &fields(&["[0].status.pid"])).unwrap();
+        assert_eq!(projected, "12");
+
+        let projected = project_output_fields(&output, &fields(&["status.pid"])).unwrap();
+        assert_eq!(projected, "12\nnull");
+    }
+
+    #[test]
+    fn project_several_fields() {
+        let output = json!({ "name": "n1", "status": { "pid": 12 }, "other": 1 }).to_string();
+        let projected = project_output_fields(&output, &fields(&["name", "status.pid"])).unwrap();
+        let projected: Value = serde_json::from_str(&projected).unwrap();
+        assert_eq!(projected, json!({ "name": "n1", "status.pid": 12 }));
+    }
+
+    #[test]
+    fn invalid_paths_list_the_available_keys() {
+        let output = json!({ "name": "n1", "status": { "pid": 12 } }).to_string();
+
+        let error = project_output_fields(&output, &fields(&["unknown"]))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("there is no key `unknown`"), "{error}");
+        assert!(error.contains("[name, status]"), "{error}");
+
+        let error = project_output_fields(&output, &fields(&["name[3]"]))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("is not a list"), "{error}");
+
+        let output = json!([{ "node_name": "n1" }]).to_string();
+        let error = project_output_fields(&output, &fields(&["[1]"]))
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("out of bounds"), "{error}");
+        assert!(error.contains("[node_name]"), "{error}");
+    }
+}
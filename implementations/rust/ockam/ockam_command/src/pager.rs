@@ -17,7 +17,8 @@ pub fn render_help(help: clap::Error) {
         }
         // The pager binary was not found, so we just print the help without pagination
         Err(_) => {
-            help.exit();
+            let _ = help.print();
+            process::exit(help_exit_code(&help));
         }
     }
 }
@@ -50,10 +51,15 @@ fn paginate_with(pager_binary_path: PathBuf, help: clap::Error) -> miette::Resul
             .into_diagnostic()?;
     }
     let _ = pager_process.wait();
-    let code = if help.use_stderr() {
-        exitcode::USAGE
+    process::exit(help_exit_code(&help));
+}
+
+/// Clap uses the exit code 2 for parsing errors, which would be
+/// confused with a resource that is not found
+fn help_exit_code(help: &clap::Error) -> exitcode::ExitCode {
+    if help.use_stderr() {
+        exitcode::INVALID_INPUT
     } else {
         exitcode::OK
-    };
-    process::exit(code);
+    }
 }
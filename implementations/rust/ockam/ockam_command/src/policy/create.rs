@@ -9,7 +9,7 @@ use ockam_abac::{Action, Expr, ResourceName, ResourceType};
 use ockam_api::nodes::models::policies::ResourceTypeOrName;
 use ockam_api::nodes::{BackgroundNodeClient, Policies};
 
-use super::resource_type_parser;
+use super::{policy_expression_parser, resource_type_parser};
 use crate::node::util::initialize_default_node;
 use crate::terminal::color_primary;
 
@@ -30,8 +30,30 @@ pub struct CreateCommand {
     #[arg(long)]
     pub resource: Option<ResourceName>,
 
-    #[arg(long)]
-    pub expression: Expr,
+    /// Policy expression. The identifiers compared to `subject.identifier`
+    /// can be given as unambiguous prefixes of at least 8 characters.
+    /// Attribute values are compared as integers with `<`, `>`, `<=`, `>=` and `=`
+    /// when the other value is an integer, e.g. `(>= subject.level 3)`, and a set of
+    /// identifiers can be checked with `(in subject.identifier ["I1..." "I2..."])`
+    #[arg(
+        long,
+        value_parser = policy_expression_parser,
+        required_unless_present = "expression_from_my_attributes"
+    )]
+    pub expression: Option<Expr>,
+
+    /// Create an expression requiring the subject to have the same values as the default
+    /// identity for the given attributes, for example `--expression-from-my-attributes role`
+    /// creates `(= subject.role "ops")` if the default identity was enrolled with the attribute
+    /// `role=ops`. The attributes are read from the local copy shown by `ockam identity show --attributes`
+    #[arg(
+        long,
+        value_name = "ATTRIBUTE_NAME",
+        value_delimiter = ',',
+        num_args = 1..,
+        conflicts_with = "expression"
+    )]
+    pub expression_from_my_attributes: Vec<String>,
 }
 
 #[async_trait]
@@ -57,8 +79,24 @@ impl Command for CreateCommand {
         let resource = ResourceTypeOrName::new(self.resource_type.as_ref(), self.resource.as_ref())
             .into_diagnostic()?;
 
+        let expression = match &self.expression {
+            Some(expression) => {
+                opts.state
+                    .resolve_identifiers_in_expression(expression)
+                    .await?
+            }
+            None => {
+                opts.state
+                    .expression_from_enrollment_attributes(
+                        &None,
+                        &self.expression_from_my_attributes,
+                    )
+                    .await?
+            }
+        };
+
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
-        node.add_policy(ctx, &resource, &Action::HandleMessage, &self.expression)
+        node.add_policy(ctx, &resource, &Action::HandleMessage, &expression)
             .await?;
         opts.terminal
             .stdout()
@@ -87,4 +125,18 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn command_can_use_the_enrollment_attributes() {
+        let cmd = parse_cmd_from_args(
+            CreateCommand::NAME,
+            &[
+                "--resource-type".to_string(),
+                "tcp-outlet".to_string(),
+                "--expression-from-my-attributes".to_string(),
+                "role,team".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
 }
@@ -1,6 +1,6 @@
 use clap::{Args, Subcommand};
 use miette::miette;
-use ockam_abac::ResourceType;
+use ockam_abac::{validate, Expr, ResourceType};
 use std::str::FromStr;
 
 pub use crate::policy::create::CreateCommand;
@@ -55,6 +55,14 @@ impl PolicyCommand {
     }
 }
 
+/// Parse a policy expression and check that it only uses known operators
+pub(crate) fn policy_expression_parser(input: &str) -> miette::Result<Expr> {
+    let expression =
+        Expr::from_str(input).map_err(|e| miette!("Invalid policy expression: {e}"))?;
+    validate(&expression).map_err(|e| miette!("Invalid policy expression: {e}"))?;
+    Ok(expression)
+}
+
 pub(crate) fn resource_type_parser(input: &str) -> miette::Result<ResourceType> {
     ResourceType::from_str(input).map_err(|_| {
         let valid_values = ResourceType::join_enum_values_as_string();
@@ -0,0 +1,68 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::cloud::email_address::EmailAddress;
+use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/add/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/add/after_long_help.txt");
+
+/// Make a user an administrator of a Project
+#[derive(Clone, Debug, Args)]
+#[command(
+arg_required_else_help = true,
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct AddCommand {
+    /// Email address of the user
+    #[arg(value_parser = EmailAddress::parse)]
+    email: EmailAddress,
+
+    /// Name of the project. The default project is used if it is not set
+    #[arg(long = "project", value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+}
+
+impl AddCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "project admin add".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = InMemoryNode::start(ctx, &opts.state).await?;
+        let project = node
+            .get_project_by_name_or_default(ctx, &self.project_name)
+            .await?;
+        let admin = node
+            .add_project_admin(ctx, project.project_id(), &self.email)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The user {} is now an admin of the project {}",
+                color!(&admin.email, OckamColor::PrimaryResource),
+                color!(project.name(), OckamColor::PrimaryResource)
+            ))
+            .machine(admin.email.to_string())
+            .json(serde_json::json!({ "email": admin.email, "project": project.name() }))
+            .write_line()?;
+        Ok(())
+    }
+}
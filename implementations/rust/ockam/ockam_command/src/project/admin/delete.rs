@@ -0,0 +1,148 @@
+use clap::Args;
+use colorful::Colorful;
+use console::Term;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cloud::email_address::EmailAddress;
+use ockam_api::cloud::project::{Project, ProjectsOrchestratorApi};
+use ockam_api::nodes::InMemoryNode;
+use ockam_core::AsyncTryClone;
+
+use crate::terminal::tui::DeleteCommandTui;
+use crate::terminal::PluralTerm;
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor, Terminal, TerminalStream};
+
+const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Remove an administrator from a Project
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct DeleteCommand {
+    /// Email address of the administrator to remove
+    #[arg(display_order = 1001, value_parser = EmailAddress::parse)]
+    email: Option<EmailAddress>,
+
+    /// Name of the project. The default project is used if it is not set
+    #[arg(long = "project", value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+impl DeleteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "project admin delete".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        DeleteTui::run(
+            ctx.async_try_clone().await.into_diagnostic()?,
+            opts,
+            self.clone(),
+        )
+        .await
+    }
+}
+
+pub struct DeleteTui {
+    ctx: Context,
+    opts: CommandGlobalOpts,
+    node: InMemoryNode,
+    project: Project,
+    cmd: DeleteCommand,
+}
+
+impl DeleteTui {
+    pub async fn run(
+        ctx: Context,
+        opts: CommandGlobalOpts,
+        cmd: DeleteCommand,
+    ) -> miette::Result<()> {
+        let node = InMemoryNode::start(&ctx, &opts.state).await?;
+        let project = node
+            .get_project_by_name_or_default(&ctx, &cmd.project_name)
+            .await?;
+        let tui = Self {
+            ctx,
+            opts,
+            node,
+            project,
+            cmd,
+        };
+        tui.delete().await
+    }
+}
+
+#[ockam_core::async_trait]
+impl DeleteCommandTui for DeleteTui {
+    const ITEM_NAME: PluralTerm = PluralTerm::Admin;
+
+    fn cmd_arg_item_name(&self) -> Option<String> {
+        self.cmd.email.as_ref().map(|e| e.to_string())
+    }
+
+    fn cmd_arg_delete_all(&self) -> bool {
+        false
+    }
+
+    fn cmd_arg_confirm_deletion(&self) -> bool {
+        self.cmd.yes
+    }
+
+    fn terminal(&self) -> Terminal<TerminalStream<Term>> {
+        self.opts.terminal.clone()
+    }
+
+    async fn list_items_names(&self) -> miette::Result<Vec<String>> {
+        let admins = self
+            .node
+            .list_project_admins(&self.ctx, self.project.project_id())
+            .await?;
+        // email addresses are compared without case, so use the
+        // email address given as an argument when it is one of the admins
+        Ok(admins
+            .into_iter()
+            .map(|a| match &self.cmd.email {
+                Some(email) if email == &a.email => email.to_string(),
+                _ => a.email.to_string(),
+            })
+            .collect())
+    }
+
+    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        let email = EmailAddress::parse(item_name).into_diagnostic()?;
+        self.node
+            .delete_project_admin(&self.ctx, self.project.project_id(), &email)
+            .await?;
+
+        self.terminal()
+            .stdout()
+            .plain(fmt_ok!(
+                "The user {} is no longer an admin of the project {}",
+                color!(item_name, OckamColor::PrimaryResource),
+                color!(self.project.name(), OckamColor::PrimaryResource)
+            ))
+            .machine(item_name)
+            .json(serde_json::json!({ "email": item_name, "project": self.project.name() }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+use clap::Args;
+use console::Term;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::cloud::project::models::ProjectAdmin;
+use ockam_api::cloud::project::ProjectsOrchestratorApi;
+use ockam_api::nodes::InMemoryNode;
+use ockam_core::AsyncTryClone;
+
+use crate::output::Output;
+use crate::terminal::tui::ShowCommandTui;
+use crate::terminal::PluralTerm;
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts, Result, Terminal, TerminalStream};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the administrators of a Project
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ListCommand {
+    /// Name of the project. The default project is used if it is not set
+    #[arg(long = "project", value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+}
+
+impl ListCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "project admin list".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        ListTui::run(
+            ctx.async_try_clone().await.into_diagnostic()?,
+            opts,
+            self.clone(),
+        )
+        .await
+    }
+}
+
+/// The project whose admins are listed is either given as an argument,
+/// or selected interactively among the projects of the user
+pub struct ListTui {
+    ctx: Context,
+    opts: CommandGlobalOpts,
+    node: InMemoryNode,
+    cmd: ListCommand,
+}
+
+impl ListTui {
+    pub async fn run(
+        ctx: Context,
+        opts: CommandGlobalOpts,
+        cmd: ListCommand,
+    ) -> miette::Result<()> {
+        let node = InMemoryNode::start(&ctx, &opts.state).await?;
+        let tui = Self {
+            ctx,
+            opts,
+            node,
+            cmd,
+        };
+        tui.show().await
+    }
+}
+
+#[ockam_core::async_trait]
+impl ShowCommandTui for ListTui {
+    const ITEM_NAME: PluralTerm = PluralTerm::Project;
+
+    fn cmd_arg_item_name(&self) -> Option<String> {
+        self.cmd.project_name.clone()
+    }
+
+    fn terminal(&self) -> Terminal<TerminalStream<Term>> {
+        self.opts.terminal.clone()
+    }
+
+    async fn get_arg_item_name_or_default(&self) -> miette::Result<String> {
+        Ok(self
+            .opts
+            .state
+            .projects()
+            .get_project_by_name_or_default(&self.cmd.project_name)
+            .await?
+            .name()
+            .to_string())
+    }
+
+    async fn list_items_names(&self) -> miette::Result<Vec<String>> {
+        Ok(self
+            .node
+            .get_admin_projects(&self.ctx)
+            .await?
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect())
+    }
+
+    async fn show_single(&self, item_name: &str) -> miette::Result<()> {
+        let project = self.node.get_project_by_name(&self.ctx, item_name).await?;
+        let admins: Vec<AdminOutput> = self
+            .node
+            .list_project_admins(&self.ctx, project.project_id())
+            .await?
+            .into_iter()
+            .map(AdminOutput)
+            .collect();
+
+        let plain = self.terminal().build_list(
+            &admins,
+            &format!("Admins of the project {}", project.name()),
+            "No admins found for this project.",
+        )?;
+        let admins: Vec<ProjectAdmin> = admins.into_iter().map(|a| a.0).collect();
+        self.terminal()
+            .stdout()
+            .plain(plain)
+            .machine(
+                admins
+                    .iter()
+                    .map(|a| a.email.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .json(serde_json::to_string_pretty(&admins).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+struct AdminOutput(ProjectAdmin);
+
+impl Output for AdminOutput {
+    fn output(&self) -> Result<String> {
+        Ok(self.0.email.to_string())
+    }
+}
@@ -0,0 +1,44 @@
+use clap::{Args, Subcommand};
+
+pub use add::AddCommand;
+pub use delete::DeleteCommand;
+pub use list::ListCommand;
+
+use crate::CommandGlobalOpts;
+
+mod add;
+mod delete;
+mod list;
+
+/// Manage the administrators of a Project
+#[derive(Clone, Debug, Args)]
+#[command(arg_required_else_help = true, subcommand_required = true)]
+pub struct AdminCommand {
+    #[command(subcommand)]
+    subcommand: AdminSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AdminSubcommand {
+    List(ListCommand),
+    Add(AddCommand),
+    Delete(DeleteCommand),
+}
+
+impl AdminCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            AdminSubcommand::List(c) => c.run(opts),
+            AdminSubcommand::Add(c) => c.run(opts),
+            AdminSubcommand::Delete(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            AdminSubcommand::List(c) => c.name(),
+            AdminSubcommand::Add(c) => c.name(),
+            AdminSubcommand::Delete(c) => c.name(),
+        }
+    }
+}
@@ -7,7 +7,9 @@ use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
 
 use ockam::Context;
-use ockam_api::cli_state::enrollments::EnrollmentTicket;
+use ockam_api::cli_state::enrollments::{
+    credential_attributes, EnrollmentTicket, EnrollmentTicketContents, TicketPassphrase,
+};
 use ockam_api::cloud::project::models::OktaAuth0;
 use ockam_api::cloud::project::Project;
 use ockam_api::enroll::enrollment::Enrollment;
@@ -18,7 +20,7 @@ use ockam_api::nodes::InMemoryNode;
 use crate::enroll::OidcServiceExt;
 use crate::output::{CredentialAndPurposeKeyDisplay, OutputFormat};
 use crate::util::api::{IdentityOpts, TrustOpts};
-use crate::value_parsers::parse_enrollment_ticket;
+use crate::value_parsers::parse_enrollment_ticket_contents;
 use crate::{color_primary, docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Result};
 
 const LONG_ABOUT: &str = include_str!("./static/enroll/long_about.txt");
@@ -32,8 +34,17 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct EnrollCommand {
     /// Path, URL or inlined hex-encoded enrollment ticket
-    #[arg(display_order = 800, group = "authentication_method", value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket)]
-    pub enrollment_ticket: Option<EnrollmentTicket>,
+    #[arg(display_order = 800, group = "authentication_method", value_name = "ENROLLMENT TICKET", value_parser = parse_enrollment_ticket_contents)]
+    pub enrollment_ticket: Option<EnrollmentTicketContents>,
+
+    /// Passphrase used to encrypt the enrollment ticket with `ockam project ticket --passphrase`
+    #[arg(
+        display_order = 801,
+        long,
+        value_name = "PASSPHRASE",
+        requires = "enrollment_ticket"
+    )]
+    pub passphrase: Option<TicketPassphrase>,
 
     #[command(flatten)]
     pub identity_opts: IdentityOpts,
@@ -62,7 +73,10 @@ impl Command for EnrollCommand {
             .state
             .get_named_identity_or_default(&self.identity_opts.identity)
             .await?;
-        let project = self.store_project(&opts).await?;
+        let enrollment_ticket = self.enrollment_ticket()?;
+        let project = self
+            .store_project(&opts, enrollment_ticket.as_ref())
+            .await?;
 
         // Create secure channel to the project's authority node
         let node = InMemoryNode::start_with_project_name(
@@ -81,7 +95,7 @@ impl Command for EnrollCommand {
             .await?;
 
         // Enroll
-        if let Some(tkn) = self.enrollment_ticket.as_ref() {
+        if let Some(tkn) = enrollment_ticket.as_ref() {
             authority_node_client
                 .present_token(ctx, &tkn.one_time_code)
                 .await?;
@@ -104,6 +118,19 @@ impl Command for EnrollCommand {
         // Issue credential
         let credential = authority_node_client.issue_credential(ctx).await?;
 
+        // Keep a local copy of the attributes granted to the identity
+        let ticket_attributes = enrollment_ticket
+            .map(|ticket| ticket.attributes)
+            .unwrap_or_default();
+        opts.state
+            .store_enrollment_attributes(
+                &identity.identifier(),
+                project.project_id(),
+                &ticket_attributes,
+                &credential_attributes(&credential)?,
+            )
+            .await?;
+
         // Get the project name to display to the user.
         let project_name = {
             let project = opts
@@ -138,9 +165,21 @@ impl Command for EnrollCommand {
 }
 
 impl EnrollCommand {
-    async fn store_project(&self, opts: &CommandGlobalOpts) -> Result<Project> {
+    /// Return the enrollment ticket, decrypted with the passphrase if it is encrypted
+    pub fn enrollment_ticket(&self) -> Result<Option<EnrollmentTicket>> {
+        match &self.enrollment_ticket {
+            Some(contents) => Ok(Some(contents.ticket(self.passphrase.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn store_project(
+        &self,
+        opts: &CommandGlobalOpts,
+        enrollment_ticket: Option<&EnrollmentTicket>,
+    ) -> Result<Project> {
         // Retrieve project info from the enrollment ticket or project.json in the case of okta auth
-        let project = if let Some(ticket) = &self.enrollment_ticket {
+        let project = if let Some(ticket) = enrollment_ticket {
             let project = ticket
                 .project
                 .as_ref()
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
+use ockam_api::cli_state::enrollments::EnrollmentTicket;
+use ockam_api::cloud::project::ProjectExport;
+use ockam_api::nodes::InMemoryNode;
+
+use crate::project_member::create_member_attributes;
+use crate::util::api::IdentityOpts;
+use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
+use crate::{docs, fmt_ok, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export a Project, and an enrollment ticket, to enroll a machine which can not access the Orchestrator
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct ExportCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// Name of the Project to export. The default Project is used if this is not specified
+    #[arg(long, value_name = "PROJECT_NAME")]
+    project: Option<String>,
+
+    /// Path of the file to write the export to. The export is written to stdout if this is not specified
+    #[arg(long, value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// Don't include an enrollment ticket in the export
+    #[arg(long, conflicts_with_all = ["attributes", "expires_in", "usage_count"])]
+    no_ticket: bool,
+
+    /// Attributes in `key=value` format to be attached to the member using the enrollment ticket
+    #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
+    attributes: Vec<String>,
+
+    /// Duration for which the enrollment ticket is valid. Examples: 10000ms, 600s, 600, 10m, 1h, 1d
+    #[arg(long = "expires-in", value_name = "DURATION", value_parser = duration_parser)]
+    expires_in: Option<Duration>,
+
+    /// Number of times the enrollment ticket can be used to enroll, the default is 1
+    #[arg(long = "usage-count", value_name = "USAGE_COUNT")]
+    usage_count: Option<u64>,
+}
+
+impl ExportCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "project export".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let project = opts
+            .state
+            .projects()
+            .get_project_by_name_or_default(&self.project)
+            .await?;
+
+        let enrollment_ticket = if self.no_ticket {
+            None
+        } else {
+            let node = InMemoryNode::start_with_project_name(
+                ctx,
+                &opts.state,
+                Some(project.name().to_string()),
+            )
+            .await?;
+            let identity = opts
+                .state
+                .get_identity_name_or_default(&self.identity_opts.identity)
+                .await?;
+            let authority_node_client = node
+                .create_authority_client(
+                    &project.authority_identifier().into_diagnostic()?,
+                    project.authority_multiaddr().into_diagnostic()?,
+                    Some(identity),
+                    None,
+                )
+                .await?;
+
+            let attributes = create_member_attributes(&self.attributes, &None, false)?;
+            let token = authority_node_client
+                .create_token(ctx, attributes, self.expires_in, self.usage_count, None)
+                .await?;
+            Some(EnrollmentTicket::new(token, Some(project.model().clone())))
+        };
+
+        let export = ProjectExport::new(&project, enrollment_ticket).into_diagnostic()?;
+        let json = serde_json::to_string_pretty(&export).into_diagnostic()?;
+
+        match &self.output_file {
+            Some(path) => {
+                std::fs::write(path, json).into_diagnostic()?;
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!(
+                        "Exported the project {} to {}",
+                        project.name(),
+                        path.display()
+                    ))
+                    .write_line()?;
+            }
+            None => opts.terminal.stdout().machine(json).write_line()?,
+        }
+        Ok(())
+    }
+}
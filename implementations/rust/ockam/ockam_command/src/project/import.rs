@@ -1,11 +1,11 @@
-use clap::ArgGroup;
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{miette, IntoDiagnostic};
 use ockam_api::cloud::project::models::ProjectModel;
+use ockam_api::cloud::project::ProjectExport;
 
 use crate::util::async_cmd;
-use crate::{docs, fmt_err, fmt_ok, CommandGlobalOpts};
+use crate::{color_primary, docs, fmt_log, fmt_ok, CommandGlobalOpts};
 
 const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
@@ -16,11 +16,14 @@ const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt"
 long_about = docs::about(LONG_ABOUT),
 after_long_help = docs::after_help(AFTER_LONG_HELP),
 )]
-#[clap(group(ArgGroup::new("detailed").required(false)))]
 pub struct ImportCommand {
-    /// Project file
-    #[arg(long, value_name = "PATH")]
-    pub project_file: String,
+    /// Project file, created with `ockam project export` or `ockam project show --output json`
+    #[arg(value_name = "PATH", required_unless_present = "project_file")]
+    pub path: Option<String>,
+
+    /// Project file. Deprecated, pass the path as an argument instead
+    #[arg(long, value_name = "PATH", hide = true, conflicts_with = "path")]
+    pub project_file: Option<String>,
 }
 
 impl ImportCommand {
@@ -35,30 +38,50 @@ impl ImportCommand {
     }
 
     async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let file_content = std::fs::read_to_string(&self.project_file).into_diagnostic()?;
+        let path = self
+            .path
+            .as_ref()
+            .or(self.project_file.as_ref())
+            .ok_or(miette!("A project file must be specified"))?;
+        let file_content = std::fs::read_to_string(path).into_diagnostic()?;
+
+        // A file created by `ockam project export` is validated before being imported
+        if let Ok(export) = serde_json::from_str::<ProjectExport>(&file_content) {
+            let project = export
+                .import()
+                .await
+                .map_err(|e| miette!("The project file {path} could not be imported: {e}"))?;
+            let project = opts.state.projects().store_project(project).await?;
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!("Successfully imported project {}", project.name()))
+                .write_line()?;
+
+            if let Some(ticket) = export.enrollment_ticket {
+                let ticket = ticket.hex_encoded().into_diagnostic()?;
+                opts.terminal.write_line(&fmt_log!(
+                    "The project file contains an enrollment ticket, which can be used with {} once the project authority is reachable",
+                    color_primary("ockam project enroll <TICKET>")
+                ))?;
+                opts.terminal
+                    .stdout()
+                    .plain(&ticket)
+                    .machine(&ticket)
+                    .write_line()?;
+            }
+            return Ok(());
+        }
+
         let project: ProjectModel = serde_json::from_str(&file_content).into_diagnostic()?;
-        let result = opts
-            .state
+        opts.state
             .projects()
             .import_and_store_project(project.clone())
-            .await;
-
-        match result {
-            Ok(_) => opts
-                .terminal
-                .stdout()
-                .plain(fmt_ok!("Successfully imported project {}", &project.name))
-                .write_line()?,
-            Err(e) => opts
-                .terminal
-                .stdout()
-                .plain(fmt_err!(
-                    "The project {} could not be imported: {}",
-                    &self.project_file,
-                    e.to_string()
-                ))
-                .write_line()?,
-        };
+            .await
+            .map_err(|e| miette!("The project {path} could not be imported: {e}"))?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!("Successfully imported project {}", &project.name))
+            .write_line()?;
         Ok(())
     }
 }
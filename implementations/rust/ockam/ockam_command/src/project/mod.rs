@@ -2,27 +2,33 @@ use crate::{docs, Command};
 use clap::{Args, Subcommand};
 
 pub use addon::AddonCommand;
+pub use admin::AdminCommand;
 pub use create::CreateCommand;
 pub use delete::DeleteCommand;
 pub use enroll::EnrollCommand;
+pub use export::ExportCommand;
 pub use import::ImportCommand;
 pub use info::InfoCommand;
 pub use list::ListCommand;
 pub use show::ShowCommand;
 pub use ticket::TicketCommand;
+pub use use_project::UseCommand;
 pub use version::VersionCommand;
 
 use crate::CommandGlobalOpts;
 
 mod addon;
+mod admin;
 mod create;
 mod delete;
 pub(crate) mod enroll;
+mod export;
 mod import;
 mod info;
 mod list;
 mod show;
 mod ticket;
+mod use_project;
 pub mod util;
 mod version;
 
@@ -44,6 +50,7 @@ pub struct ProjectCommand {
 pub enum ProjectSubcommand {
     Create(CreateCommand),
     Import(ImportCommand),
+    Export(ExportCommand),
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
@@ -51,7 +58,9 @@ pub enum ProjectSubcommand {
     Information(InfoCommand),
     Ticket(TicketCommand),
     Addon(AddonCommand),
+    Admin(AdminCommand),
     Enroll(Box<EnrollCommand>),
+    Use(UseCommand),
 }
 
 impl ProjectCommand {
@@ -59,6 +68,7 @@ impl ProjectCommand {
         match self.subcommand {
             ProjectSubcommand::Create(c) => c.run(opts),
             ProjectSubcommand::Import(c) => c.run(opts),
+            ProjectSubcommand::Export(c) => c.run(opts),
             ProjectSubcommand::Delete(c) => c.run(opts),
             ProjectSubcommand::List(c) => c.run(opts),
             ProjectSubcommand::Show(c) => c.run(opts),
@@ -66,7 +76,9 @@ impl ProjectCommand {
             ProjectSubcommand::Ticket(c) => c.run(opts),
             ProjectSubcommand::Information(c) => c.run(opts),
             ProjectSubcommand::Addon(c) => c.run(opts),
+            ProjectSubcommand::Admin(c) => c.run(opts),
             ProjectSubcommand::Enroll(c) => c.run(opts),
+            ProjectSubcommand::Use(c) => c.run(opts),
         }
     }
 
@@ -77,11 +89,14 @@ impl ProjectCommand {
             ProjectSubcommand::List(c) => c.name(),
             ProjectSubcommand::Show(c) => c.name(),
             ProjectSubcommand::Import(c) => c.name(),
+            ProjectSubcommand::Export(c) => c.name(),
             ProjectSubcommand::Version(c) => c.name(),
             ProjectSubcommand::Information(c) => c.name(),
             ProjectSubcommand::Ticket(c) => c.name(),
             ProjectSubcommand::Addon(c) => c.name(),
+            ProjectSubcommand::Admin(c) => c.name(),
             ProjectSubcommand::Enroll(c) => c.name(),
+            ProjectSubcommand::Use(c) => c.name(),
         }
     }
 }
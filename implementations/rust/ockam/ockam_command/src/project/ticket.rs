@@ -1,4 +1,7 @@
 use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use clap::Args;
@@ -11,15 +14,15 @@ use ockam_api::authenticator::direct::{
     Members, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
 use ockam_api::authenticator::enrollment_tokens::TokenIssuer;
-use ockam_api::cli_state::enrollments::EnrollmentTicket;
+use ockam_api::cli_state::enrollments::{EnrollmentTicket, TicketPassphrase};
 use ockam_api::cli_state::CliState;
 use ockam_api::cloud::project::Project;
 use ockam_api::nodes::InMemoryNode;
 use ockam_multiaddr::{proto, MultiAddr, Protocol};
 
-use crate::fmt_ok;
 use crate::util::async_cmd;
 use crate::{docs, CommandGlobalOpts, Result};
+use crate::{fmt_ok, fmt_warn};
 use crate::{
     output::OutputFormat,
     util::api::{IdentityOpts, TrustOpts},
@@ -53,14 +56,10 @@ pub struct TicketCommand {
     #[arg(value_name = "IDENTIFIER", long, short, conflicts_with = "expires_in")]
     member: Option<Identifier>,
 
-    /// The Project name from this option is used to create the enrollment ticket. This takes precedence over `--project`
-    #[arg(
-        long,
-        short,
-        default_value = "/project/default",
-        value_name = "ROUTE_TO_PROJECT"
-    )]
-    to: MultiAddr,
+    /// The Project name from this option is used to create the enrollment ticket. This takes precedence over `--project`.
+    /// If neither is given, the default Project is used
+    #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
+    to: Option<MultiAddr>,
 
     /// Attributes in `key=value` format to be attached to the member. You can specify this option multiple times for multiple attributes
     #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
@@ -79,6 +78,10 @@ pub struct TicketCommand {
     )]
     usage_count: Option<u64>,
 
+    /// Maximum time to live of the credentials issued to the identities enrolled with this ticket. The authority uses the shortest of this duration and its own configured duration. Examples: 10000ms, 600s, 600, 15m, 1h, 1d. If you don't specify a length sigil, it is assumed to be seconds
+    #[arg(long = "credential-ttl", value_name = "DURATION", conflicts_with = "member", value_parser = duration_parser)]
+    credential_ttl: Option<Duration>,
+
     /// Name of the relay that the identity using the ticket will be allowed to create. This name is transformed into attributes to prevent collisions when creating relay names. For example: `--relay foo` is shorthand for `--attribute ockam-relay=foo`
     #[arg(long = "relay", value_name = "ENROLLEE_ALLOWED_RELAY_NAME")]
     allowed_relay_name: Option<String>,
@@ -86,6 +89,18 @@ pub struct TicketCommand {
     /// Add the enroller role to your ticket. If you specify it, this flag is transformed into the attributes `--attribute ockam-role=enroller`. This role allows the Identity using the ticket to enroll other Identities into the Project, typically something that only admins can do
     #[arg(long = "enroller")]
     enroller: bool,
+
+    /// Name of an attribute template of the Project's Authority, as created with `ockam authority template create`. The attributes of the ticket are completed and checked by the template, when the ticket is created and when it is redeemed
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "member")]
+    template: Option<String>,
+
+    /// Write the enrollment ticket to this file, only readable by the current user, instead of printing it. The ticket is a secret which should not end up in the shell history or in CI logs
+    #[arg(long, value_name = "PATH", conflicts_with = "member")]
+    output_file: Option<PathBuf>,
+
+    /// Encrypt the enrollment ticket with this passphrase. The same passphrase must be given to `ockam project enroll --passphrase` to use the ticket
+    #[arg(long, value_name = "PASSPHRASE", conflicts_with = "member")]
+    passphrase: Option<TicketPassphrase>,
 }
 
 impl TicketCommand {
@@ -127,16 +142,29 @@ impl TicketCommand {
         ));
         }
 
+        let project = match &self.to {
+            Some(to) => get_project(&opts.state, to).await?,
+            None => Some(
+                opts.state
+                    .projects()
+                    .get_project_by_name_or_default(&self.trust_opts.project_name)
+                    .await?,
+            ),
+        };
+
         let node = InMemoryNode::start_with_project_name(
             ctx,
             &opts.state,
-            self.trust_opts.project_name.clone(),
+            project
+                .as_ref()
+                .map(|p| p.name().to_string())
+                .or(self.trust_opts.project_name.clone()),
         )
         .await?;
 
         let project_model: Option<ProjectModel>;
 
-        let authority_node_client = if let Some(p) = get_project(&opts.state, &self.to).await? {
+        let authority_node_client = if let Some(p) = project {
             let identity = opts
                 .state
                 .get_identity_name_or_default(&self.identity_opts.identity)
@@ -165,17 +193,47 @@ impl TicketCommand {
                 .await?
         } else {
             let token = authority_node_client
-                .create_token(ctx, attributes, self.expires_in, self.usage_count)
+                .create_token_with_template(
+                    ctx,
+                    self.template.clone(),
+                    attributes.clone(),
+                    self.expires_in,
+                    self.usage_count,
+                    self.credential_ttl,
+                )
                 .await?;
 
-            let ticket = EnrollmentTicket::new(token, project_model);
-            let ticket_serialized = ticket.hex_encoded().into_diagnostic()?;
+            let ticket = EnrollmentTicket::new(token, project_model).with_attributes(attributes);
+            let ticket_serialized = match &self.passphrase {
+                Some(passphrase) => ticket
+                    .encrypt(passphrase)
+                    .and_then(|encrypted| encrypted.hex_encoded())
+                    .into_diagnostic()?,
+                None => ticket.hex_encoded().into_diagnostic()?,
+            };
+
+            if let Some(output_file) = &self.output_file {
+                write_secret_file(output_file, &ticket_serialized)?;
+                opts.terminal.write_line(&fmt_ok!(
+                    "Created enrollment ticket in {}. You can use it to enroll another machine using: {}",
+                    color_primary(output_file.display().to_string()),
+                    color_primary("ockam project enroll")
+                ))?;
+                return Ok(());
+            }
 
             opts.terminal.write_line(&fmt_ok!(
                 "{}: {}",
                 "Created enrollment ticket. You can use it to enroll another machine using",
                 color_primary("ockam project enroll")
             ))?;
+            opts.terminal.write(format!(
+                "{}\n",
+                fmt_warn!(
+                    "The enrollment ticket is a secret. Use {} to keep it out of your shell history and logs",
+                    color_primary("--output-file")
+                )
+            ))?;
 
             opts.terminal
                 .clone()
@@ -188,6 +246,25 @@ impl TicketCommand {
     }
 }
 
+/// Write a secret to a file which can only be read and written by the current user
+fn write_secret_file(path: &Path, contents: &str) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        // the mode is only applied to new files
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+                .into_diagnostic()?;
+        }
+    }
+    let mut file = options.open(path).into_diagnostic()?;
+    file.write_all(contents.as_bytes()).into_diagnostic()?;
+    Ok(())
+}
+
 /// Get the project authority from the first address protocol.
 ///
 /// If the first protocol is a `/project`, look up the project's config.
@@ -208,3 +285,61 @@ async fn get_project(cli_state: &CliState, input: &MultiAddr) -> Result<Option<P
         Ok(None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::project::{EnrollCommand, ProjectSubcommand};
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::{Command, OckamSubcommand};
+    use ockam_api::authenticator::one_time_code::OneTimeCode;
+    use tempfile::tempdir;
+
+    fn parse_enroll_command(args: &[String]) -> EnrollCommand {
+        match parse_cmd_from_args(EnrollCommand::NAME, args).unwrap() {
+            OckamSubcommand::Project(cmd) => match cmd.subcommand {
+                ProjectSubcommand::Enroll(cmd) => *cmd,
+                _ => panic!("expected a project enroll command"),
+            },
+            _ => panic!("expected a project command"),
+        }
+    }
+
+    #[test]
+    fn an_encrypted_ticket_file_can_be_redeemed() {
+        let ticket = EnrollmentTicket::new(OneTimeCode::new(), None);
+        let passphrase = "correct horse battery staple";
+        let encrypted = ticket
+            .encrypt(&TicketPassphrase::new(passphrase))
+            .unwrap()
+            .hex_encoded()
+            .unwrap();
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("my.ticket");
+        write_secret_file(&path, &encrypted).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let args = [
+            path.to_str().unwrap().to_string(),
+            "--passphrase".to_string(),
+            passphrase.to_string(),
+        ];
+        let cmd = parse_enroll_command(&args);
+        assert_eq!(cmd.enrollment_ticket().unwrap(), Some(ticket.clone()));
+
+        // the parsed command is logged, without the ticket nor the passphrase
+        let logged = format!("{cmd:?}");
+        assert!(!logged.contains(&ticket.one_time_code.to_string()));
+        assert!(!logged.contains(passphrase));
+
+        // the passphrase is required
+        let cmd = parse_enroll_command(&args[..1]);
+        assert!(cmd.enrollment_ticket().is_err());
+    }
+}
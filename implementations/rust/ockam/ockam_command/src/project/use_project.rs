@@ -0,0 +1,46 @@
+use clap::Args;
+use colorful::Colorful;
+
+use crate::util::async_cmd;
+use crate::{docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/use/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/use/after_long_help.txt");
+
+/// Select the default project
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct UseCommand {
+    /// Name of the project to use by default
+    pub name: String,
+}
+
+impl UseCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "project use".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        opts.state.projects().use_project(&self.name).await?;
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The project {} is now the default project",
+                self.name
+                    .as_str()
+                    .color(OckamColor::PrimaryResource.color())
+            ))
+            .machine(&self.name)
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -1,7 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
 
-use ockam::identity::Identifier;
 use ockam::Context;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::nodes::InMemoryNode;
@@ -33,8 +32,9 @@ pub struct AddCommand {
     #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
     to: Option<MultiAddr>,
 
+    /// Identifier of the member, or an unambiguous prefix of at least 8 characters
     #[arg(value_name = "IDENTIFIER")]
-    member: Identifier,
+    member: String,
 
     /// Attributes in `key=value` format to be attached to the member. You can specify this option multiple times for multiple attributes
     #[arg(short, long = "attribute", value_name = "ATTRIBUTE")]
@@ -62,6 +62,7 @@ impl AddCommand {
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let project = get_project(&opts.state, &self.to).await?;
+        let member = opts.state.resolve_identifier(&self.member).await?;
 
         let node = InMemoryNode::start_with_project_name(
             ctx,
@@ -76,7 +77,7 @@ impl AddCommand {
         authority_node_client
             .add_member(
                 ctx,
-                self.member.clone(),
+                member.clone(),
                 create_member_attributes(
                     &self.attributes,
                     &self.allowed_relay_name,
@@ -87,7 +88,7 @@ impl AddCommand {
 
         opts.terminal.stdout().plain(fmt_ok!(
             "Identifier {} is now a Project member. It can get a credential and access Project resources, like portals of other members",
-            self.member
+            member
         ));
 
         Ok(())
@@ -1,7 +1,6 @@
 use clap::Args;
 use colorful::Colorful;
 
-use ockam::identity::Identifier;
 use ockam::Context;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::nodes::InMemoryNode;
@@ -29,8 +28,9 @@ pub struct DeleteCommand {
     #[arg(long, short, value_name = "ROUTE_TO_PROJECT")]
     to: Option<MultiAddr>,
 
+    /// Identifier of the member, or an unambiguous prefix of at least 8 characters
     #[arg(value_name = "IDENTIFIER")]
-    member: Identifier,
+    member: String,
 }
 
 impl DeleteCommand {
@@ -46,6 +46,7 @@ impl DeleteCommand {
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let project = get_project(&opts.state, &self.to).await?;
+        let member = opts.state.resolve_identifier(&self.member).await?;
 
         let node = InMemoryNode::start_with_project_name(
             ctx,
@@ -58,12 +59,12 @@ impl DeleteCommand {
             create_authority_client(&node, &opts.state, &self.identity_opts, &project).await?;
 
         authority_node_client
-            .delete_member(ctx, self.member.clone())
+            .delete_member(ctx, member.clone())
             .await?;
 
         opts.terminal.stdout().plain(fmt_ok!(
             "Identifier {} is no longer a member of the Project. It won't be able to get a credential and access Project resources, like portals of other members",
-            self.member
+            member
         ));
 
         Ok(())
@@ -9,20 +9,24 @@ use tokio::try_join;
 use tracing::info;
 
 use ockam::identity::Identifier;
+use ockam::remote::RemoteRelayFilter;
 use ockam::Context;
 use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::relay::RelayInfo;
 use ockam_api::nodes::service::relay::Relays;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::CliState;
+use ockam_core::Address;
 use ockam_multiaddr::proto::Project;
 use ockam_multiaddr::{MultiAddr, Protocol};
 
 use crate::output::Output;
 use crate::terminal::OckamColor;
+use crate::util::api::IdentityOpts;
+use crate::util::route_aliases::expand_route;
 use crate::util::{colorize_connection_status, process_nodes_multiaddr};
 use crate::{docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
-use crate::{node::util::initialize_default_node, terminal::color_primary};
+use crate::{node::util::initialize_node_for_identity, terminal::color_primary};
 
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -43,14 +47,30 @@ pub struct CreateCommand {
     #[arg(long, id = "NODE_NAME", value_parser = extract_address_value)]
     pub to: Option<String>,
 
-    /// Route to the node at which to create the relay
-    #[arg(long, id = "ROUTE", default_value_t = default_at_addr())]
-    pub at: String,
+    /// Route to the node at which to create the relay. It can use route aliases, for example `@hub`.
+    /// Can be repeated: the first route is the primary one, the next ones are tried in order
+    /// when the previous routes are unreachable.
+    #[arg(long, id = "ROUTE", default_values_t = [default_at_addr()])]
+    pub at: Vec<String>,
+
+    /// Move the relay back to the first `--at` route once it is reachable again
+    #[arg(long)]
+    pub failback: bool,
 
     /// Authorized identity for secure channel connection
     #[arg(long, id = "AUTHORIZED")]
     pub authorized: Option<Identifier>,
 
+    /// Address of a service of the node which can be reached through the relay.
+    /// Can be repeated; when set, the other services can't be reached.
+    #[arg(long = "allow-service", value_name = "SERVICE_ADDRESS")]
+    pub allow_service: Vec<String>,
+
+    /// Address of a service of the node which can't be reached through the relay.
+    /// Can be repeated.
+    #[arg(long = "deny-service", value_name = "SERVICE_ADDRESS")]
+    pub deny_service: Vec<String>,
+
     /// Relay address to use. By default, inherits the relay name.
     #[arg(long)]
     relay_address: Option<String>,
@@ -59,10 +79,16 @@ pub struct CreateCommand {
     /// By default, this information will be inferred from the `--at` argument.
     #[arg(long)]
     project_relay: bool,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
 }
 
+/// Placeholder replaced by the name of the default project in the relay routes
+pub(super) const DEFAULT_PROJECT_NAME_PLACEHOLDER: &str = "$DEFAULT_PROJECT_NAME";
+
 pub fn default_at_addr() -> String {
-    "/project/$DEFAULT_PROJECT_NAME".to_string()
+    format!("/project/{DEFAULT_PROJECT_NAME_PLACEHOLDER}")
 }
 
 #[async_trait]
@@ -70,15 +96,19 @@ impl Command for CreateCommand {
     const NAME: &'static str = "relay create";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        initialize_default_node(ctx, &opts).await?;
+        initialize_node_for_identity(ctx, &opts, &self.to, &self.identity_opts.identity).await?;
         let cmd = self.parse_args(&opts).await?;
-        let at = cmd.at();
+        let at = cmd.at()?;
+        let failover_at = cmd.failover_at()?;
+        let filter = cmd.filter();
         let alias = cmd.relay_name();
 
         opts.terminal.write_line(&fmt_log!("Creating Relay...\n"))?;
         let is_finished: Mutex<bool> = Mutex::new(false);
 
-        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.to).await?;
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.to)
+            .await?
+            .cancel_on_interrupt();
         let get_relay_info = async {
             let relay_info = {
                 if at.starts_with(Project::CODE) && cmd.authorized.is_some() {
@@ -94,6 +124,9 @@ impl Command for CreateCommand {
                     cmd.authorized,
                     Some(cmd.relay_address.unwrap_or(alias)),
                     !cmd.project_relay,
+                    failover_at,
+                    cmd.failback,
+                    filter,
                 )
                 .await?
             };
@@ -153,8 +186,30 @@ impl Command for CreateCommand {
 }
 
 impl CreateCommand {
-    fn at(&self) -> MultiAddr {
-        MultiAddr::from_str(&self.at).unwrap()
+    fn at(&self) -> Result<MultiAddr> {
+        let at = self
+            .at
+            .first()
+            .ok_or(Error::arg_validation("at", "", None))?;
+        MultiAddr::from_str(at).map_err(|_| Error::arg_validation("at", at, None))
+    }
+
+    fn failover_at(&self) -> Result<Vec<MultiAddr>> {
+        self.at
+            .iter()
+            .skip(1)
+            .map(|at| MultiAddr::from_str(at).map_err(|_| Error::arg_validation("at", at, None)))
+            .collect()
+    }
+
+    fn filter(&self) -> RemoteRelayFilter {
+        RemoteRelayFilter::from_lists(
+            self.allow_service
+                .iter()
+                .map(Address::from_string)
+                .collect(),
+            self.deny_service.iter().map(Address::from_string).collect(),
+        )
     }
 
     fn relay_name(&self) -> String {
@@ -162,41 +217,80 @@ impl CreateCommand {
     }
 
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> Result<Self> {
-        let default_project_name = &opts
-            .state
-            .projects()
-            .get_default_project()
-            .await
-            .ok()
-            .map(|p| p.name().to_string());
-        let at = Self::parse_arg_at(&opts.state, self.at, default_project_name.as_deref()).await?;
-        self.project_relay |= at.starts_with(Project::CODE);
+        // The default project is only resolved when a route refers to it, and it must not be
+        // ambiguous when several projects exist
+        let default_project_name = match self
+            .at
+            .iter()
+            .find(|at| at.contains(DEFAULT_PROJECT_NAME_PLACEHOLDER))
+        {
+            Some(at) => {
+                let project = opts
+                    .state
+                    .projects()
+                    .get_default_project()
+                    .await
+                    .map_err(|e| Error::arg_validation("at", at, Some(&e.to_string())))?;
+                Some(project.name().to_string())
+            }
+            None => None,
+        };
+        let mut routes: Vec<MultiAddr> = vec![];
+        for addr in self.at {
+            let addr =
+                Self::parse_arg_at(&opts.state, addr, default_project_name.as_deref()).await?;
+            if let Some(primary) = routes.first() {
+                if addr.starts_with(Project::CODE) != primary.starts_with(Project::CODE) {
+                    return Err(Error::arg_validation(
+                        "at",
+                        addr.to_string(),
+                        Some("all the relay routes must either be projects or nodes"),
+                    ));
+                }
+            }
+            routes.push(addr);
+        }
+        let primary = routes
+            .first()
+            .ok_or(Error::arg_validation("at", "", None))?;
+        self.project_relay |= primary.starts_with(Project::CODE);
+        if self.failback && routes.len() < 2 {
+            return Err(Error::arg_validation(
+                "failback",
+                "true",
+                Some("--failback requires several --at routes"),
+            ));
+        }
         let relay_name = Self::parse_arg_relay_name(self.relay_name, !self.project_relay)?;
-        self.at = at.to_string();
+        self.at = routes.iter().map(|at| at.to_string()).collect();
         self.relay_name = relay_name;
         Ok(self)
     }
 
-    async fn parse_arg_at(
+    pub(super) async fn parse_arg_at(
         state: &CliState,
         at: impl Into<String>,
         default_project_name: Option<&str>,
     ) -> Result<MultiAddr> {
-        let mut at = at.into();
+        // The address uses route aliases, expand them
+        let mut at = expand_route(state, &at.into()).await?;
         // The address is a node name
         if !at.contains('/') {
             at = format!("/node/{at}");
         }
         // The address is a project, parse it.
-        else if at.starts_with("/project/") {
+        else if at.contains(DEFAULT_PROJECT_NAME_PLACEHOLDER) {
             let project_name = default_project_name.ok_or(Error::NotEnrolled)?;
-            at = at.replace("$DEFAULT_PROJECT_NAME", project_name);
+            at = at.replace(DEFAULT_PROJECT_NAME_PLACEHOLDER, project_name);
         }
         let ma = MultiAddr::from_str(&at).map_err(|_| Error::arg_validation("at", at, None))?;
         process_nodes_multiaddr(&ma, state).await
     }
 
-    fn parse_arg_relay_name(relay_name: impl Into<String>, at_rust_node: bool) -> Result<String> {
+    pub(super) fn parse_arg_relay_name(
+        relay_name: impl Into<String>,
+        at_rust_node: bool,
+    ) -> Result<String> {
         let relay_name = relay_name.into();
         if at_rust_node {
             Ok(format!("forward_to_{relay_name}"))
@@ -276,6 +370,14 @@ mod tests {
             .to_string();
         assert_eq!(res, addr);
 
+        // The user provides a route alias
+        state.set_route_alias("hub", addr).await.unwrap();
+        let res = CreateCommand::parse_arg_at(&state, "@hub", default_project_name)
+            .await
+            .unwrap()
+            .to_string();
+        assert_eq!(res, addr);
+
         // The user provides the name of a node
         let node = InMemoryNode::start(ctx, &state).await.unwrap();
         let res = CreateCommand::parse_arg_at(&state, &node.node_name(), default_project_name)
@@ -0,0 +1,157 @@
+use async_trait::async_trait;
+use std::path::PathBuf;
+
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::relay_chain::{
+    create_relay_chain, RelayChain, RelayChainHop, RelayChainOptions,
+};
+use ockam_api::nodes::BackgroundNodeClient;
+
+use super::CreateCommand;
+use crate::output::Output;
+use crate::terminal::color_primary;
+use crate::{docs, fmt_log, fmt_ok, Command, CommandGlobalOpts, Error, Result};
+
+const AFTER_LONG_HELP: &str = include_str!("./static/create_chain/after_long_help.txt");
+const LONG_ABOUT: &str = include_str!("./static/create_chain/long_about.txt");
+
+/// Create a chain of Relays through several nodes
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CreateChainCommand {
+    /// Node to reach through the chain. If not provided, the default node will be used
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    pub to: Option<String>,
+
+    /// Comma-separated list of the hops of the chain, from the closest to the node to reach.
+    /// All the hops but the last one must be local nodes, since a relay is created on them.
+    /// The last hop can be a node name or a route, for example a project route.
+    #[arg(long, value_name = "HOPS", value_delimiter = ',', required = true)]
+    pub through: Vec<String>,
+
+    /// Name of the relay created at the last hop.
+    /// The relays created at the other hops get a name derived from it.
+    #[arg(long, value_name = "RELAY_NAME")]
+    pub alias: String,
+
+    /// Keep the relays already created when the chain can't be completed
+    #[arg(long)]
+    pub keep_partial: bool,
+
+    /// Path of a file where the route to the node is written
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for CreateChainCommand {
+    const NAME: &'static str = "relay create-chain";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let (last_hop, intermediate_hops) = self
+            .through
+            .split_last()
+            .ok_or_else(|| Error::arg_validation("through", "", Some("no hop was given")))?;
+
+        // a relay is created on the node to reach, and on each intermediate hop
+        let mut nodes = vec![BackgroundNodeClient::create(ctx, &opts.state, &self.to).await?];
+        for hop in intermediate_hops {
+            if hop.contains('/') {
+                return Err(Error::arg_validation(
+                    "through",
+                    hop,
+                    Some("only the last hop can be a route, the other hops must be local nodes"),
+                )
+                .into());
+            }
+            nodes.push(BackgroundNodeClient::create_to_node(ctx, &opts.state, hop).await?);
+        }
+        let mut addresses = vec![];
+        for hop in &self.through {
+            addresses.push(CreateCommand::parse_arg_at(&opts.state, hop, None).await?);
+        }
+        let hops = nodes
+            .iter()
+            .zip(addresses)
+            .map(|(node, at)| RelayChainHop::new(node, at))
+            .collect::<Vec<_>>();
+
+        opts.terminal.write_line(&fmt_log!(
+            "Creating a chain of relays from {} through {}...\n",
+            color_primary(nodes[0].node_name()),
+            color_primary(self.through.join(", "))
+        ))?;
+        let options = RelayChainOptions::new().with_keep_partial(self.keep_partial);
+        let chain = create_relay_chain(ctx, &self.alias, &hops, &options).await?;
+
+        if let Some(path) = &self.output_file {
+            std::fs::write(path, chain.address.to_string()).into_diagnostic()?;
+        }
+
+        let mut plain = fmt_ok!(
+            "The node {} can be reached at {} through {}\n",
+            color_primary(nodes[0].node_name()),
+            color_primary(chain.address.to_string()),
+            color_primary(last_hop)
+        );
+        plain.push_str(&chain.output()?);
+        if let Some(path) = &self.output_file {
+            plain.push_str(&fmt_log!(
+                "The route has been written to {}",
+                color_primary(path.display().to_string())
+            ));
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(chain.address.to_string())
+            .json(serde_json::to_string_pretty(&chain).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+impl Output for RelayChain {
+    fn output(&self) -> Result<String> {
+        Ok(self
+            .relays
+            .iter()
+            .map(|relay| {
+                fmt_log!(
+                    "Relay {} created by {} at {}\n",
+                    color_primary(&relay.alias),
+                    color_primary(&relay.node),
+                    color_primary(relay.at.to_string())
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            CreateChainCommand::NAME,
+            &[
+                "--through".to_string(),
+                "n1,n2".to_string(),
+                "--alias".to_string(),
+                "edge".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -1,19 +1,22 @@
 use clap::Args;
 use colorful::Colorful;
 use miette::IntoDiagnostic;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 use tokio::sync::Mutex;
 use tokio::try_join;
 use tracing::trace;
 
 use ockam::Context;
 use ockam_api::address::extract_address_value;
-use ockam_api::nodes::models::relay::RelayInfo;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::relay::{RelayAliasOwnerInfo, RelayInfo};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 
-use crate::terminal::OckamColor;
-use crate::util::async_cmd;
-use crate::{docs, CommandGlobalOpts};
+use crate::terminal::{OckamColor, Table};
+use crate::util::{async_cmd, colorize_connection_status};
+use crate::{docs, CommandGlobalOpts, Result};
 
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
@@ -31,6 +34,10 @@ pub struct ListCommand {
     /// Get the list of Relays at the given node
     #[arg(global = true, long, value_name = "NODE", value_parser = extract_address_value)]
     pub to: Option<String>,
+
+    /// List the owners of the Relay names registered at the given node, instead of its Relays
+    #[arg(long)]
+    pub owners: bool,
 }
 
 impl ListCommand {
@@ -46,6 +53,9 @@ impl ListCommand {
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.to).await?;
+        if self.owners {
+            return self.list_owners(ctx, opts, &node).await;
+        }
         let is_finished: Mutex<bool> = Mutex::new(false);
 
         let get_relays = async {
@@ -66,11 +76,14 @@ impl ListCommand {
         let (relays, _) = try_join!(get_relays, progress_output)?;
         trace!(?relays, "Relays retrieved");
 
-        let plain = opts.terminal.build_list(
-            &relays,
-            &format!("Relays on Node {}", node.node_name()),
+        let mut table = Table::new(&["ALIAS", "STATUS", "REMOTE ADDRESS"]);
+        for relay in &relays {
+            table.add_row(relay_table_row(relay)?);
+        }
+        let plain = opts.terminal.build_table(
+            &table,
             &format!("No Relays found on node {}.", node.node_name()),
-        )?;
+        );
         let json = serde_json::to_string_pretty(&relays).into_diagnostic()?;
 
         opts.terminal
@@ -81,3 +94,66 @@ impl ListCommand {
         Ok(())
     }
 }
+
+impl ListCommand {
+    async fn list_owners(
+        &self,
+        ctx: &Context,
+        opts: CommandGlobalOpts,
+        node: &BackgroundNodeClient,
+    ) -> miette::Result<()> {
+        node.require_capability(
+            ctx,
+            NodeCapability::RELAY_ALIAS_OWNERSHIP,
+            "relay names owners",
+        )
+        .await?;
+        let owners: Vec<RelayAliasOwnerInfo> =
+            node.ask(ctx, Request::get("/node/relay-aliases")).await?;
+        trace!(?owners, "Relay owners retrieved");
+
+        let mut table = Table::new(&["ALIAS", "OWNER", "REGISTERED AT"]);
+        for owner in &owners {
+            let registered_at = OffsetDateTime::from_unix_timestamp(*owner.registered_at as i64)
+                .ok()
+                .and_then(|t| t.format(&Rfc3339).ok())
+                .unwrap_or_else(|| owner.registered_at.0.to_string());
+            table.add_row(vec![
+                owner
+                    .alias
+                    .as_str()
+                    .color(OckamColor::PrimaryResource.color())
+                    .to_string(),
+                owner.identifier.to_string(),
+                registered_at,
+            ]);
+        }
+        let plain = opts.terminal.build_table(
+            &table,
+            &format!("No Relay names are owned on node {}.", node.node_name()),
+        );
+        let json = serde_json::to_string_pretty(&owners).into_diagnostic()?;
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Values displayed for a relay in the table of relays
+fn relay_table_row(relay: &RelayInfo) -> Result<Vec<String>> {
+    Ok(vec![
+        relay
+            .alias()
+            .color(OckamColor::PrimaryResource.color())
+            .to_string(),
+        colorize_connection_status(relay.connection_status()).to_string(),
+        relay
+            .remote_address_ma()?
+            .map(|address| address.to_string())
+            .unwrap_or("N/A".into()),
+    ])
+}
@@ -1,15 +1,21 @@
 use clap::{Args, Subcommand};
 
 pub(crate) use create::CreateCommand;
+pub(crate) use create_chain::CreateChainCommand;
 pub(crate) use delete::DeleteCommand;
 pub(crate) use list::ListCommand;
+pub(crate) use release::ReleaseCommand;
+pub(crate) use rename::RenameCommand;
 pub(crate) use show::ShowCommand;
 
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
+mod create_chain;
 mod delete;
 mod list;
+mod release;
+mod rename;
 mod show;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -31,27 +37,36 @@ pub struct RelayCommand {
 #[derive(Clone, Debug, Subcommand)]
 pub enum RelaySubCommand {
     Create(CreateCommand),
+    CreateChain(CreateChainCommand),
     List(ListCommand),
     Show(ShowCommand),
     Delete(DeleteCommand),
+    Rename(RenameCommand),
+    Release(ReleaseCommand),
 }
 
 impl RelayCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         match self.subcommand {
             RelaySubCommand::Create(c) => c.run(opts),
+            RelaySubCommand::CreateChain(c) => c.run(opts),
             RelaySubCommand::List(c) => c.run(opts),
             RelaySubCommand::Show(c) => c.run(opts),
             RelaySubCommand::Delete(c) => c.run(opts),
+            RelaySubCommand::Rename(c) => c.run(opts),
+            RelaySubCommand::Release(c) => c.run(opts),
         }
     }
 
     pub fn name(&self) -> String {
         match &self.subcommand {
             RelaySubCommand::Create(c) => c.name(),
+            RelaySubCommand::CreateChain(c) => c.name(),
             RelaySubCommand::List(c) => c.name(),
             RelaySubCommand::Show(c) => c.name(),
             RelaySubCommand::Delete(c) => c.name(),
+            RelaySubCommand::Rename(c) => c.name(),
+            RelaySubCommand::Release(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::relay::ReleaseRelayAlias;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+use ockam_multiaddr::proto::Project;
+
+use crate::relay::create::{default_at_addr, DEFAULT_PROJECT_NAME_PLACEHOLDER};
+use crate::relay::CreateCommand;
+use crate::{color, docs, fmt_ok, Command, CommandGlobalOpts, Error, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/release/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/release/after_long_help.txt");
+
+/// Release the name of a Relay so that it can be used by another identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ReleaseCommand {
+    /// Name of the Relay
+    #[arg(value_name = "RELAY_NAME")]
+    relay_name: String,
+
+    /// Node which registered the Relay. If not provided, the default node will be used
+    #[arg(long, value_name = "NODE", value_parser = extract_address_value)]
+    pub to: Option<String>,
+
+    /// Route to the node at which the Relay was registered
+    #[arg(long, id = "ROUTE", default_value_t = default_at_addr())]
+    pub at: String,
+}
+
+#[async_trait]
+impl Command for ReleaseCommand {
+    const NAME: &'static str = "relay release";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let default_project_name = if self.at.contains(DEFAULT_PROJECT_NAME_PLACEHOLDER) {
+            let project = opts
+                .state
+                .projects()
+                .get_default_project()
+                .await
+                .map_err(|e| Error::arg_validation("at", &self.at, Some(&e.to_string())))?;
+            Some(project.name().to_string())
+        } else {
+            None
+        };
+        let at =
+            CreateCommand::parse_arg_at(&opts.state, &self.at, default_project_name.as_deref())
+                .await?;
+        let alias =
+            CreateCommand::parse_arg_relay_name(&self.relay_name, !at.starts_with(Project::CODE))?;
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.to).await?;
+        node.require_capability(ctx, NodeCapability::RELAY_ALIAS_OWNERSHIP, "relay release")
+            .await?;
+        node.tell(
+            ctx,
+            Request::post("/node/relay-aliases/release")
+                .body(ReleaseRelayAlias::new(at.clone(), &alias)),
+        )
+        .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The Relay name {} has been released at {}",
+                color!(&self.relay_name, OckamColor::PrimaryResource),
+                color!(at.to_string(), OckamColor::PrimaryResource)
+            ))
+            .machine(&alias)
+            .json(serde_json::json!({ "alias": alias, "at": at.to_string() }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::relay::{RelayInfo, RenameRelay};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const LONG_ABOUT: &str = include_str!("./static/rename/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rename/after_long_help.txt");
+
+/// Rename a Relay
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RenameCommand {
+    /// Current name of the Relay
+    #[arg(value_name = "RELAY_NAME")]
+    relay_name: String,
+
+    /// New name of the Relay
+    #[arg(value_name = "NEW_RELAY_NAME")]
+    new_relay_name: String,
+
+    /// Node on which the Relay was created. If not provided, the default node will be used
+    #[arg(long, value_name = "NODE", value_parser = extract_address_value)]
+    pub at: Option<String>,
+}
+
+impl RenameCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "relay rename".into()
+    }
+
+    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        node.require_capability(ctx, NodeCapability::RELAY_RENAME, "relay rename")
+            .await?;
+        let relay: RelayInfo = node
+            .ask(
+                ctx,
+                Request::post(format!("/node/relay/{}/rename", self.relay_name))
+                    .body(RenameRelay::new(&self.new_relay_name)),
+            )
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "Relay with name {} on Node {} has been renamed to {}",
+                color!(&self.relay_name, OckamColor::PrimaryResource),
+                color!(node.node_name(), OckamColor::PrimaryResource),
+                color!(relay.alias(), OckamColor::PrimaryResource)
+            ))
+            .machine(relay.alias())
+            .json(serde_json::to_value(&relay).unwrap_or_default())
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -131,6 +131,11 @@ impl ShowCommandTui for ShowTui {
 struct RelayShowOutput {
     pub alias: String,
     pub destination: MultiAddr,
+    pub failover_destinations: Vec<MultiAddr>,
+    pub active_destination: Option<MultiAddr>,
+    pub last_destination_change: Option<String>,
+    pub allowed_services: Vec<String>,
+    pub denied_services: Vec<String>,
     pub connection_status: ConnectionStatus,
     pub relay_route: Option<String>,
     pub remote_address: Option<MultiAddr>,
@@ -142,6 +147,11 @@ impl From<RelayInfo> for RelayShowOutput {
         Self {
             alias: r.alias().to_string(),
             destination: r.destination_address().clone(),
+            failover_destinations: r.failover_addresses().to_vec(),
+            active_destination: r.active_destination_address().cloned(),
+            last_destination_change: r.last_destination_change().map(|c| c.to_string()),
+            allowed_services: r.allowed_services().to_vec(),
+            denied_services: r.denied_services().to_vec(),
             connection_status: r.connection_status(),
             relay_route: r.forwarding_route().clone(),
             remote_address: r.remote_address_ma().into_diagnostic().unwrap(),
@@ -152,11 +162,32 @@ impl From<RelayInfo> for RelayShowOutput {
 
 impl Output for RelayShowOutput {
     fn output(&self) -> crate::error::Result<String> {
+        let failover_destinations = if self.failover_destinations.is_empty() {
+            "N/A".to_string()
+        } else {
+            self.failover_destinations
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let services = |services: &[String], empty: &str| {
+            if services.is_empty() {
+                empty.to_string()
+            } else {
+                services.join(", ")
+            }
+        };
         Ok(formatdoc!(
             r#"
         Relay:
             Alias: {alias}
             Destination: {destination_address}
+            Failover Destinations: {failover_destinations}
+            Active Destination: {active_destination}
+            Last Destination Change: {last_destination_change}
+            Allowed Services: {allowed_services}
+            Denied Services: {denied_services}
             Status: {connection_status}
             Relay Route: {route}
             Remote Address: {remote_addr}
@@ -165,6 +196,14 @@ impl Output for RelayShowOutput {
             alias = self.alias,
             connection_status = colorize_connection_status(self.connection_status),
             destination_address = self.destination.to_string(),
+            active_destination = self
+                .active_destination
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or("N/A".into()),
+            last_destination_change = self.last_destination_change.as_deref().unwrap_or("N/A"),
+            allowed_services = services(&self.allowed_services, "all"),
+            denied_services = services(&self.denied_services, "N/A"),
             route = self.relay_route.as_deref().unwrap_or("N/A"),
             remote_addr = self
                 .remote_address
@@ -0,0 +1,103 @@
+use async_trait::async_trait;
+use clap::Args;
+use console::Term;
+
+use ockam::Context;
+
+use crate::terminal::tui::DeleteCommandTui;
+use crate::terminal::{color_primary, PluralTerm};
+use crate::util::route_aliases::ROUTE_ALIAS_PREFIX;
+use crate::{docs, fmt_ok, Command, CommandGlobalOpts, Terminal, TerminalStream};
+
+const LONG_ABOUT: &str = include_str!("./static/delete/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/delete/after_long_help.txt");
+
+/// Delete route aliases
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DeleteCommand {
+    /// Name of the alias
+    pub name: Option<String>,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+
+    /// Delete all the route aliases
+    #[arg(long, short)]
+    all: bool,
+}
+
+#[async_trait]
+impl Command for DeleteCommand {
+    const NAME: &'static str = "route alias delete";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        DeleteTui::run(opts, self).await
+    }
+}
+
+struct DeleteTui {
+    opts: CommandGlobalOpts,
+    cmd: DeleteCommand,
+}
+
+impl DeleteTui {
+    async fn run(opts: CommandGlobalOpts, cmd: DeleteCommand) -> miette::Result<()> {
+        let tui = Self { opts, cmd };
+        tui.delete().await
+    }
+}
+
+#[ockam_core::async_trait]
+impl DeleteCommandTui for DeleteTui {
+    const ITEM_NAME: PluralTerm = PluralTerm::RouteAlias;
+
+    fn cmd_arg_item_name(&self) -> Option<String> {
+        // the alias can be given as it is used in a route
+        self.cmd
+            .name
+            .as_deref()
+            .map(|name| name.trim_start_matches(ROUTE_ALIAS_PREFIX).to_string())
+    }
+
+    fn cmd_arg_delete_all(&self) -> bool {
+        self.cmd.all
+    }
+
+    fn cmd_arg_confirm_deletion(&self) -> bool {
+        self.cmd.yes
+    }
+
+    fn terminal(&self) -> Terminal<TerminalStream<Term>> {
+        self.opts.terminal.clone()
+    }
+
+    async fn list_items_names(&self) -> miette::Result<Vec<String>> {
+        Ok(self
+            .opts
+            .state
+            .get_route_aliases()
+            .await?
+            .into_iter()
+            .map(|alias| alias.name)
+            .collect())
+    }
+
+    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        self.opts.state.delete_route_alias(item_name).await?;
+        self.terminal()
+            .stdout()
+            .plain(fmt_ok!(
+                "The route alias {} has been deleted",
+                color_primary(format!("{ROUTE_ALIAS_PREFIX}{item_name}"))
+            ))
+            .machine(item_name)
+            .json(serde_json::json!({ "name": &item_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+
+use ockam::Context;
+use ockam_api::cli_state::RouteAlias;
+
+use crate::output::Output;
+use crate::terminal::color_primary;
+use crate::util::route_aliases::{resolve_route, ROUTE_ALIAS_PREFIX};
+use crate::{docs, Command, CommandGlobalOpts, Result};
+
+const LONG_ABOUT: &str = include_str!("./static/list/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/list/after_long_help.txt");
+
+/// List the route aliases
+#[derive(Clone, Debug, Args)]
+#[command(
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ListCommand;
+
+#[async_trait]
+impl Command for ListCommand {
+    const NAME: &'static str = "route alias list";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let mut aliases = vec![];
+        for alias in opts.state.get_route_aliases().await? {
+            let resolved =
+                resolve_route(&opts.state, &format!("{ROUTE_ALIAS_PREFIX}{}", alias.name))
+                    .await
+                    .map(|route| route.to_string())
+                    .map_err(|e| e.to_string());
+            aliases.push(RouteAliasOutput::new(alias, resolved));
+        }
+
+        let plain =
+            opts.terminal
+                .build_list(&aliases, "Route aliases", "No route aliases found")?;
+        let json = serde_json::to_string_pretty(&aliases).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// A route alias with the result of its resolution
+#[derive(Serialize)]
+struct RouteAliasOutput {
+    #[serde(flatten)]
+    alias: RouteAlias,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolved_route: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl RouteAliasOutput {
+    fn new(alias: RouteAlias, resolved: std::result::Result<String, String>) -> Self {
+        let (resolved_route, error) = match resolved {
+            Ok(route) => (Some(route), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            alias,
+            resolved_route,
+            error,
+        }
+    }
+}
+
+impl Output for RouteAliasOutput {
+    fn output(&self) -> Result<String> {
+        let mut output = format!(
+            "Alias: {}\nRoute: {}",
+            color_primary(format!("{ROUTE_ALIAS_PREFIX}{}", self.alias.name)),
+            color_primary(&self.alias.route)
+        );
+        match (&self.resolved_route, &self.error) {
+            (Some(route), _) if route != &self.alias.route => {
+                output.push_str(&format!("\nResolved route: {}", color_primary(route)))
+            }
+            (_, Some(error)) => output.push_str(&format!("\nError: {error}")),
+            _ => {}
+        }
+        Ok(output)
+    }
+}
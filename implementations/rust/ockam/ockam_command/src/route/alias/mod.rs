@@ -0,0 +1,51 @@
+use clap::{Args, Subcommand};
+
+pub(crate) use delete::DeleteCommand;
+pub(crate) use list::ListCommand;
+pub(crate) use set::SetCommand;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+mod delete;
+mod list;
+mod set;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage route aliases
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct RouteAliasCommand {
+    #[command(subcommand)]
+    pub subcommand: RouteAliasSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RouteAliasSubcommand {
+    #[command(display_order = 800)]
+    Set(SetCommand),
+    List(ListCommand),
+    Delete(DeleteCommand),
+}
+
+impl RouteAliasCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            RouteAliasSubcommand::Set(c) => c.run(opts),
+            RouteAliasSubcommand::List(c) => c.run(opts),
+            RouteAliasSubcommand::Delete(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            RouteAliasSubcommand::Set(c) => c.name(),
+            RouteAliasSubcommand::List(c) => c.name(),
+            RouteAliasSubcommand::Delete(c) => c.name(),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+
+use crate::terminal::color_primary;
+use crate::util::route_aliases::{resolve_route, ROUTE_ALIAS_PREFIX};
+use crate::{docs, fmt_ok, fmt_warn, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/set/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/set/after_long_help.txt");
+
+/// Create a route alias, or change the route of an existing one
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    long_about = docs::about(LONG_ABOUT),
+    after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SetCommand {
+    /// Name of the alias. It can only contain letters, digits, '-', '_' and '.'
+    pub name: String,
+
+    /// Route of the alias. It can reference other aliases, for example `@relay/secure/api`
+    pub route: String,
+}
+
+#[async_trait]
+impl Command for SetCommand {
+    const NAME: &'static str = "route alias set";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let alias = opts.state.set_route_alias(&self.name, &self.route).await?;
+
+        // the aliases are only resolved when they are used, warn about a route
+        // which can't be resolved yet, for example because it uses an alias to be created later
+        let mut plain = fmt_ok!(
+            "The route alias {} now stands for {}\n",
+            color_primary(format!("{ROUTE_ALIAS_PREFIX}{}", alias.name)),
+            color_primary(&alias.route)
+        );
+        if let Err(e) =
+            resolve_route(&opts.state, &format!("{ROUTE_ALIAS_PREFIX}{}", alias.name)).await
+        {
+            plain.push_str(&fmt_warn!("The route can't be resolved yet: {e}\n"));
+        }
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .machine(&alias.name)
+            .json(serde_json::to_string_pretty(&alias).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            SetCommand::NAME,
+            &[
+                "prod-db".to_string(),
+                "/project/default/service/forward_to_db/secure/api/service/outlet".to_string(),
+            ],
+        );
+        assert!(cmd.is_ok());
+    }
+}
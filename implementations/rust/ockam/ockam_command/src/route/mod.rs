@@ -0,0 +1,39 @@
+use clap::{Args, Subcommand};
+
+use crate::route::alias::RouteAliasCommand;
+use crate::{docs, CommandGlobalOpts};
+
+mod alias;
+
+const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
+
+/// Manage routes
+#[derive(Clone, Debug, Args)]
+#[command(
+    arg_required_else_help = true,
+    subcommand_required = true,
+    long_about = docs::about(LONG_ABOUT),
+)]
+pub struct RouteCommand {
+    #[command(subcommand)]
+    pub subcommand: RouteSubcommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum RouteSubcommand {
+    Alias(RouteAliasCommand),
+}
+
+impl RouteCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        match self.subcommand {
+            RouteSubcommand::Alias(c) => c.run(opts),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match &self.subcommand {
+            RouteSubcommand::Alias(c) => c.name(),
+        }
+    }
+}
@@ -167,6 +167,7 @@ mod tests {
             },
             project_enroll: ProjectEnroll {
                 ticket: Some("./path/to/ticket".to_string()),
+                passphrase: None,
             },
             nodes: Nodes {
                 nodes: Some(ResourcesContainer::List(vec![
@@ -290,6 +291,7 @@ mod tests {
             identities: Identities { identities: None },
             project_enroll: ProjectEnroll {
                 ticket: Some("./path/to/ticket".to_string()),
+                passphrase: None,
             },
             nodes: Nodes {
                 nodes: Some(ResourcesContainer::List(vec![
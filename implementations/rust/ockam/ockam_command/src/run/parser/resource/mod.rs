@@ -1,6 +1,7 @@
 mod identities;
 mod node;
 mod nodes;
+mod plugins;
 mod policies;
 mod project_enroll;
 mod relays;
@@ -13,6 +14,7 @@ mod vaults;
 pub use identities::Identities;
 pub use node::Node;
 pub use nodes::Nodes;
+pub use plugins::Plugins;
 pub use policies::Policies;
 pub use project_enroll::ProjectEnroll;
 pub use relays::Relays;
@@ -0,0 +1,90 @@
+use crate::run::parser::resource::traits::CommandsParser;
+use crate::run::parser::resource::utils::parse_cmd_from_args;
+use crate::run::parser::resource::ValuesOverrides;
+use crate::service::start::StartCommand;
+use crate::service::ServiceSubcommand;
+use crate::{color_primary, Command, OckamSubcommand};
+use async_trait::async_trait;
+use miette::{miette, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Service plugins to start on the node, with their optional configuration.
+/// The plugins must be registered in the ockam binary running the node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Plugins {
+    #[serde(alias = "plugin")]
+    pub plugins: Option<BTreeMap<String, Option<serde_json::Value>>>,
+}
+
+impl Plugins {
+    fn get_subcommand(args: &[String]) -> Result<StartCommand> {
+        if let OckamSubcommand::Service(cmd) = parse_cmd_from_args(StartCommand::NAME, args)? {
+            if let ServiceSubcommand::Start(c) = cmd.subcommand {
+                return Ok(c);
+            }
+        }
+        Err(miette!(format!(
+            "Failed to parse {} command",
+            color_primary(StartCommand::NAME)
+        )))
+    }
+}
+
+#[async_trait]
+impl CommandsParser<StartCommand> for Plugins {
+    fn parse_commands(self, overrides: &ValuesOverrides) -> Result<Vec<StartCommand>> {
+        let mut cmds = vec![];
+        for (name, config) in self.plugins.unwrap_or_default() {
+            let mut args = vec!["plugin".to_string(), name];
+            if let Some(config) = config {
+                args.push("--config".to_string());
+                args.push(config.to_string());
+            }
+            if let Some(node_name) = overrides.override_node_name.as_ref() {
+                args.push("--at".to_string());
+                args.push(node_name.clone());
+            }
+            cmds.push(Self::get_subcommand(&args)?);
+        }
+        Ok(cmds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::service::start::StartSubCommand;
+    use serde_json::json;
+
+    #[test]
+    fn plugins_config() {
+        let config = r#"
+            plugins:
+              uppercase-echo:
+                address: my_uppercase
+              other-plugin:
+        "#;
+        let parsed: Plugins = serde_yaml::from_str(config).unwrap();
+        let cmds = parsed
+            .parse_commands(&ValuesOverrides::default().with_override_node_name("n1"))
+            .unwrap();
+        assert_eq!(cmds.len(), 2);
+
+        assert_eq!(cmds[0].node_opts.at_node.as_ref().unwrap(), "n1");
+        match &cmds[0].create_subcommand {
+            StartSubCommand::Plugin { name, config, .. } => {
+                assert_eq!(name, "other-plugin");
+                assert!(config.is_none());
+            }
+            _ => panic!("expected a plugin command"),
+        }
+        match &cmds[1].create_subcommand {
+            StartSubCommand::Plugin { name, config, .. } => {
+                assert_eq!(name, "uppercase-echo");
+                assert_eq!(config, &Some(json!({ "address": "my_uppercase" })));
+            }
+            _ => panic!("expected a plugin command"),
+        }
+    }
+}
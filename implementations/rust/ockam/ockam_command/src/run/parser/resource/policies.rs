@@ -56,7 +56,7 @@ mod tests {
         assert_eq!(cmds[0].at.as_ref().unwrap(), "n1");
         assert_eq!(cmds[0].resource.as_ref().unwrap().as_str(), "r1");
         assert_eq!(
-            &cmds[0].expression.to_string(),
+            &cmds[0].expression.as_ref().unwrap().to_string(),
             "(= subject.component \"c1\")"
         );
     }
@@ -82,7 +82,7 @@ mod tests {
         assert_eq!(cmds[0].at.as_ref().unwrap(), "n1");
         assert_eq!(cmds[0].resource.as_ref().unwrap().as_str(), "r1");
         assert_eq!(
-            &cmds[0].expression.to_string(),
+            &cmds[0].expression.as_ref().unwrap().to_string(),
             "(= subject.component \"c1\")"
         );
 
@@ -92,7 +92,7 @@ mod tests {
             "tcp-outlet"
         );
         assert_eq!(
-            &cmds[1].expression.to_string(),
+            &cmds[1].expression.as_ref().unwrap().to_string(),
             "(= subject.component \"c2\")"
         );
 
@@ -102,7 +102,7 @@ mod tests {
             "tcp-inlet"
         );
         assert_eq!(
-            &cmds[2].expression.to_string(),
+            &cmds[2].expression.as_ref().unwrap().to_string(),
             "(= subject.component \"c3\")"
         );
     }
@@ -12,6 +12,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProjectEnroll {
     pub ticket: Option<String>,
+    /// Passphrase of an encrypted ticket
+    pub passphrase: Option<String>,
 }
 
 impl ProjectEnroll {
@@ -32,7 +34,13 @@ impl ProjectEnroll {
 impl CommandsParser<EnrollCommand> for ProjectEnroll {
     fn parse_commands(self, _overrides: &ValuesOverrides) -> Result<Vec<EnrollCommand>> {
         match self.ticket {
-            Some(path_or_contents) => Ok(vec![Self::get_subcommand(&[path_or_contents])?]),
+            Some(path_or_contents) => {
+                let mut args = vec![path_or_contents];
+                if let Some(passphrase) = self.passphrase {
+                    args.extend(["--passphrase".to_string(), passphrase]);
+                }
+                Ok(vec![Self::get_subcommand(&args)?])
+            }
             None => Ok(vec![]),
         }
     }
@@ -74,6 +82,7 @@ impl ParsedCommand for ParsedEnrollCommand {
 mod tests {
     use super::*;
     use ockam_api::authenticator::one_time_code::OneTimeCode;
+    use ockam_api::cli_state::enrollments::TicketPassphrase;
     use ockam_api::EnrollmentTicket;
     use std::fs::File;
     use std::io::Write;
@@ -90,8 +99,8 @@ mod tests {
         let cmds = parsed.parse_commands(&ValuesOverrides::default()).unwrap();
         assert_eq!(cmds.len(), 1);
         assert_eq!(
-            cmds[0].enrollment_ticket.as_ref().unwrap(),
-            &enrollment_ticket
+            cmds[0].enrollment_ticket().unwrap().unwrap(),
+            enrollment_ticket
         );
 
         // As path
@@ -104,8 +113,23 @@ mod tests {
         let cmds = parsed.parse_commands(&ValuesOverrides::default()).unwrap();
         assert_eq!(cmds.len(), 1);
         assert_eq!(
-            cmds[0].enrollment_ticket.as_ref().unwrap(),
-            &enrollment_ticket
+            cmds[0].enrollment_ticket().unwrap().unwrap(),
+            enrollment_ticket
+        );
+
+        // Encrypted, with its passphrase
+        let encrypted = enrollment_ticket
+            .encrypt(&TicketPassphrase::new("passphrase"))
+            .unwrap()
+            .hex_encoded()
+            .unwrap();
+        let config = format!("ticket: {encrypted}\npassphrase: passphrase");
+        let parsed: ProjectEnroll = serde_yaml::from_str(&config).unwrap();
+        let cmds = parsed.parse_commands(&ValuesOverrides::default()).unwrap();
+        assert_eq!(cmds.len(), 1);
+        assert_eq!(
+            cmds[0].enrollment_ticket().unwrap().unwrap(),
+            enrollment_ticket
         );
     }
 }
@@ -5,7 +5,7 @@ use serde_json::json;
 use tokio::{sync::Mutex, try_join};
 
 use ockam::identity::DEFAULT_TIMEOUT;
-use ockam::{identity::Identifier, route, Context};
+use ockam::{route, Context};
 use ockam_api::address::extract_address_value;
 use ockam_api::nodes::models::secure_channel::{
     CreateSecureChannelRequest, CreateSecureChannelResponse,
@@ -20,9 +20,14 @@ use crate::project::util::{
     clean_projects_multiaddr, get_projects_secure_channels_from_config_lookup,
 };
 use crate::util::api::IdentityOpts;
+use crate::util::route_aliases::resolve_route;
 use crate::util::{async_cmd, clean_nodes_multiaddr};
 use crate::{
-    docs, error::Error, fmt_log, fmt_ok, terminal::OckamColor, util::exitcode, CommandGlobalOpts,
+    docs,
+    error::{Error, ErrorKind},
+    fmt_log, fmt_ok,
+    terminal::OckamColor,
+    CommandGlobalOpts,
 };
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
@@ -40,13 +45,14 @@ pub struct CreateCommand {
     #[arg(value_name = "NODE", long, display_order = 800, value_parser = extract_address_value)]
     pub from: String,
 
-    /// Route to a secure channel listener
+    /// Route to a secure channel listener. It can use route aliases, for example `@n2/service/api`
     #[arg(value_name = "ROUTE", long, display_order = 800)]
-    pub to: MultiAddr,
+    pub to: String,
 
-    /// Identifiers authorized to be presented by the listener
+    /// Identifiers authorized to be presented by the listener.
+    /// Unambiguous prefixes of at least 8 characters are accepted
     #[arg(value_name = "IDENTIFIER", long, short, display_order = 801)]
-    pub authorized: Option<Vec<Identifier>>,
+    pub authorized: Option<Vec<String>>,
 
     #[command(flatten)]
     identity_opts: IdentityOpts,
@@ -71,7 +77,10 @@ impl CreateCommand {
         ctx: &Context,
         node: &BackgroundNodeClient,
     ) -> miette::Result<MultiAddr> {
-        let (to, meta) = clean_nodes_multiaddr(&self.to, &opts.state)
+        let to = resolve_route(&opts.state, &self.to)
+            .await
+            .wrap_err(format!("Could not convert {} into route", &self.to))?;
+        let (to, meta) = clean_nodes_multiaddr(&to, &opts.state)
             .await
             .wrap_err(format!("Could not convert {} into route", &self.to))?;
         let identity_name = opts
@@ -95,7 +104,9 @@ impl CreateCommand {
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         initialize_default_node(ctx, &opts).await?;
-        let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, &self.from).await?;
+        let node = BackgroundNodeClient::create_to_node(ctx, &opts.state, &self.from)
+            .await?
+            .cancel_on_interrupt();
 
         opts.terminal
             .write_line(&fmt_log!("Creating Secure Channel...\n"))?;
@@ -103,7 +114,10 @@ impl CreateCommand {
         // Delegate the request to create a secure channel to the from node.
         let is_finished: Mutex<bool> = Mutex::new(false);
         let to = self.parse_to_route(&opts, ctx, &node).await?;
-        let authorized_identifiers = self.authorized.clone();
+        let authorized_identifiers = match &self.authorized {
+            Some(authorized) => Some(opts.state.resolve_identifiers(authorized).await?),
+            None => None,
+        };
 
         let create_secure_channel = async {
             let identity_name = opts
@@ -129,7 +143,7 @@ impl CreateCommand {
         let route = &route![secure_channel.to_string()];
         let multi_addr = route_to_multiaddr(route).ok_or_else(|| {
             Error::new(
-                exitcode::PROTOCOL,
+                ErrorKind::Internal,
                 miette!("Failed to convert route {route} to multi-address"),
             )
         })?;
@@ -146,9 +160,7 @@ impl CreateCommand {
                 ) + &fmt_log!(
                     "From {} to {}",
                     from.color(OckamColor::PrimaryResource.color()),
-                    self.to
-                        .to_string()
-                        .color(OckamColor::PrimaryResource.color())
+                    self.to.clone().color(OckamColor::PrimaryResource.color())
                 ),
             )
             .machine(multi_addr.to_string())
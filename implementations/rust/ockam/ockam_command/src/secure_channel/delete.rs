@@ -80,7 +80,7 @@ impl DeleteCommand {
                             && !options.global_args.quiet
                             && options.global_args.output_format == OutputFormat::Plain
                         {
-                            if options.global_args.no_color {
+                            if !options.terminal.colors_enabled() {
                                 eprintln!("\n  Deleted Secure Channel:");
                                 eprintln!("  •        At: /node/{}", &node_name);
                                 eprintln!("  •   Address: {}", &self.address);
@@ -109,9 +109,9 @@ impl DeleteCommand {
                             );
                         }
 
-                        // return the exitcode::PROTOCOL since if things are going as expected
+                        // return an internal error since if things are going as expected
                         // a route in the response should be convertible to multiaddr.
-                        std::process::exit(exitcode::PROTOCOL);
+                        std::process::exit(exitcode::INTERNAL);
                     }
                 }
             }
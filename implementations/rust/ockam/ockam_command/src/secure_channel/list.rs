@@ -79,7 +79,14 @@ impl ListCommand {
                 .join("")
         };
 
-        Ok(SecureChannelListOutput { from, to, at })
+        let previous_trust_options = show_response.previous_trust_options.unwrap_or(false);
+
+        Ok(SecureChannelListOutput {
+            from,
+            to,
+            at,
+            previous_trust_options,
+        })
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
@@ -141,6 +148,7 @@ pub struct SecureChannelListOutput {
     pub from: String,
     pub to: String,
     pub at: String,
+    pub previous_trust_options: bool,
 }
 
 impl Output for SecureChannelListOutput {
@@ -163,6 +171,9 @@ impl Output for SecureChannelListOutput {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        if self.previous_trust_options {
+            write!(output, " (established under previous trust options)")?;
+        }
 
         Ok(output)
     }
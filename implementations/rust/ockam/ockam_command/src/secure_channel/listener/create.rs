@@ -1,16 +1,21 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
 use clap::Args;
 use colorful::Colorful;
 use miette::{miette, IntoDiagnostic, WrapErr};
 
 use ockam::identity::Identifier;
 use ockam::Context;
+use ockam_api::nodes::models::api_version::NodeCapability;
 use ockam_api::nodes::models::secure_channel::CreateSecureChannelListenerRequest;
 use ockam_api::nodes::{BackgroundNodeClient, NODEMANAGER_ADDR};
 use ockam_core::api::{Request, Status};
-use ockam_core::{Address, Route};
+use ockam_core::{Address, RateLimit, Route};
 
 use crate::node::util::initialize_default_node;
 use crate::node::NodeOpts;
+use crate::util::duration::duration_parser;
 use crate::util::{api, async_cmd, exitcode};
 use crate::{docs, fmt_log, fmt_ok, terminal::OckamColor, CommandGlobalOpts};
 
@@ -31,14 +36,30 @@ pub struct CreateCommand {
     /// Address for this listener
     address: Address,
 
-    /// Authorized Identifiers of secure channel initiators
+    /// Authorized Identifiers of secure channel initiators.
+    /// Unambiguous prefixes of at least 8 characters are accepted
     #[arg(short, long, value_name = "IDENTIFIERS")]
-    authorized: Option<Vec<Identifier>>,
+    authorized: Option<Vec<String>>,
 
     /// Name of the Identity that the secure-channel listener will use
     /// If it is different from the default node identity
     #[arg(value_name = "IDENTITY_NAME", long)]
     identity: Option<String>,
+
+    /// Attribute in `key=value` format which must be present in the credential
+    /// presented by secure channel initiators. Can be used several times
+    #[arg(long = "required-attribute", value_name = "ATTRIBUTE")]
+    required_attributes: Vec<String>,
+
+    /// Maximum number of messages accepted from each secure channel initiator
+    /// over the rate limit window. Further messages are denied
+    #[arg(long, value_name = "MESSAGES")]
+    rate_limit: Option<u32>,
+
+    /// Length of the sliding window used to enforce the rate limit.
+    /// Examples: 500ms, 1s, 1m. The default is 1 second
+    #[arg(long, value_name = "DURATION", default_value = "1s", value_parser = duration_parser, requires = "rate_limit")]
+    rate_limit_window: Duration,
 }
 
 impl CreateCommand {
@@ -52,16 +73,47 @@ impl CreateCommand {
         "create secure channel listener".into()
     }
 
+    fn required_attributes(&self) -> miette::Result<BTreeMap<String, String>> {
+        let mut attributes = BTreeMap::new();
+        for attr in &self.required_attributes {
+            let mut parts = attr.splitn(2, '=');
+            let key = parts.next().ok_or(miette!("key expected"))?;
+            let value = parts
+                .next()
+                .ok_or(miette!("value expected for the attribute {key}"))?;
+            attributes.insert(key.to_string(), value.to_string());
+        }
+        Ok(attributes)
+    }
+
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         initialize_default_node(ctx, &opts).await?;
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
-        let req = Request::post("/node/secure_channel_listener").body(
-            CreateSecureChannelListenerRequest::new(
-                &self.address,
-                self.authorized.clone(),
-                self.identity.clone(),
-            ),
+        let required_attributes = self.required_attributes()?;
+        let authorized = match &self.authorized {
+            Some(authorized) => Some(opts.state.resolve_identifiers(authorized).await?),
+            None => None,
+        };
+        let mut payload = CreateSecureChannelListenerRequest::new(
+            &self.address,
+            authorized,
+            self.identity.clone(),
         );
+        if !required_attributes.is_empty() {
+            node.require_capability(
+                ctx,
+                NodeCapability::REQUIRED_ATTRIBUTES,
+                "required attributes",
+            )
+            .await?;
+            payload = payload.with_required_attributes(required_attributes);
+        }
+        if let Some(max_messages) = self.rate_limit {
+            node.require_capability(ctx, NodeCapability::LISTENER_RATE_LIMIT, "rate limits")
+                .await?;
+            payload = payload.with_rate_limit(RateLimit::new(max_messages, self.rate_limit_window));
+        }
+        let req = Request::post("/node/secure_channel_listener").body(payload);
         let result = node.tell(ctx, req).await;
         match result {
             Ok(_) => {
@@ -117,7 +169,7 @@ pub async fn create_listener(
         }
         _ => {
             eprintln!("An error occurred while creating the secure channel listener",);
-            std::process::exit(exitcode::CANTCREAT)
+            std::process::exit(exitcode::INTERNAL)
         }
     }
 }
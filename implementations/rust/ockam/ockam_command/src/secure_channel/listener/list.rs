@@ -93,6 +93,22 @@ impl Output for ShowSecureChannelListenerResponse {
         }
         .color(OckamColor::PrimaryResource.color());
 
-        Ok(format!("Address {addr}"))
+        let mut output = format!("Address {addr}");
+        if let (Some(rate_limit), Some(denied_messages_count)) =
+            (self.rate_limit, self.denied_messages_count)
+        {
+            output.push_str(&format!(
+                "\n    Rate limit {} messages per {:?}, {} denied",
+                rate_limit.max_messages(),
+                rate_limit.window(),
+                denied_messages_count
+            ));
+        }
+        if let Some(abandoned_handshakes) = self.abandoned_handshakes.filter(|n| *n > 0) {
+            output.push_str(&format!(
+                "\n    {abandoned_handshakes} abandoned handshakes"
+            ));
+        }
+        Ok(output)
     }
 }
@@ -1,26 +1,39 @@
 use std::fmt::Write;
+use std::str::FromStr;
 
 use clap::Args;
 use colorful::Colorful;
-use miette::IntoDiagnostic;
+use miette::{Context as _, IntoDiagnostic};
 use tokio::sync::Mutex;
 use tokio::try_join;
 
 use ockam::Context;
-use ockam_api::nodes::models::services::{ServiceList, ServiceStatus};
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::services::{AdvertisedService, ServiceList, ServiceStatus};
+use ockam_api::nodes::service::discovery::AdvertisedServices;
 use ockam_api::nodes::BackgroundNodeClient;
+use ockam_api::CliState;
+use ockam_multiaddr::MultiAddr;
 
-use crate::node::NodeOpts;
 use crate::output::Output;
 use crate::terminal::OckamColor;
-use crate::util::{api, async_cmd};
+use crate::util::route_aliases::expand_route;
+use crate::util::{api, async_cmd, clean_nodes_multiaddr};
 use crate::CommandGlobalOpts;
 
 /// List service(s) of a given node
 #[derive(Clone, Debug, Args)]
 pub struct ListCommand {
-    #[command(flatten)]
-    pub node_opts: NodeOpts,
+    /// Node to list the services of. A route to a remote node, for example `/node/n1/secure/api`
+    /// or a route alias like `@n1`, lists the services advertised by the discovery service of that node
+    #[arg(long, value_name = "NODE_NAME_OR_ROUTE")]
+    pub at: Option<String>,
+
+    /// Node sending the request to the remote node given by `--at`.
+    /// If not provided, the default node will be used
+    #[arg(long, value_name = "NODE_NAME", value_parser = extract_address_value)]
+    pub from: Option<String>,
 }
 
 impl ListCommand {
@@ -35,7 +48,16 @@ impl ListCommand {
     }
 
     async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        if let Some(at) = self.remote_route(&opts.state).await? {
+            return self.list_advertised_services(ctx, opts, &at).await;
+        }
+        let at_node = self
+            .at
+            .as_deref()
+            .map(extract_address_value)
+            .transpose()
+            .into_diagnostic()?;
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &at_node).await?;
         let is_finished: Mutex<bool> = Mutex::new(false);
 
         let get_services = async {
@@ -69,6 +91,100 @@ impl ListCommand {
 
         Ok(())
     }
+
+    /// Return the route given by `--at` if it leads to a remote node, and not to a local node
+    async fn remote_route(&self, state: &CliState) -> miette::Result<Option<MultiAddr>> {
+        let at = match &self.at {
+            Some(at) => Some(expand_route(state, at).await?),
+            None => None,
+        };
+        match at {
+            Some(at) if at.contains('/') => {
+                let route = MultiAddr::from_str(&at)
+                    .into_diagnostic()
+                    .context("Argument '--at' is invalid")?;
+                Ok(if route.len() > 1 { Some(route) } else { None })
+            }
+            _ => Ok(None),
+        }
+    }
+
+    async fn list_advertised_services(
+        &self,
+        ctx: &Context,
+        opts: CommandGlobalOpts,
+        at: &MultiAddr,
+    ) -> miette::Result<()> {
+        let (to, _) = clean_nodes_multiaddr(at, &opts.state)
+            .await
+            .context("Argument '--at' is invalid")?;
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.from).await?;
+        node.require_capability(ctx, NodeCapability::SERVICE_DISCOVERY, "service discovery")
+            .await?;
+        let is_finished: Mutex<bool> = Mutex::new(false);
+
+        let get_services = async {
+            let services = node
+                .list_advertised_services(ctx, &to, opts.global_args.timeout)
+                .await?;
+            *is_finished.lock().await = true;
+            Ok(services)
+        };
+
+        let output_messages = vec![format!(
+            "Listing the Services advertised at {}...\n",
+            at.to_string().color(OckamColor::PrimaryResource.color())
+        )];
+
+        let progress_output = opts
+            .terminal
+            .progress_output(&output_messages, &is_finished);
+
+        let (services, _) = try_join!(get_services, progress_output)?;
+
+        let plain = opts.terminal.build_list(
+            &services,
+            &format!("Services advertised at {at}"),
+            &format!("No services advertised at {at}"),
+        )?;
+        let json = serde_json::to_string_pretty(&services).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+
+        Ok(())
+    }
+}
+
+impl Output for AdvertisedService {
+    fn output(&self) -> crate::Result<String> {
+        let mut output = String::new();
+
+        writeln!(
+            output,
+            "Service {}",
+            self.service_type
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        write!(
+            output,
+            "Address {}{}",
+            "/service/"
+                .to_string()
+                .color(OckamColor::PrimaryResource.color()),
+            self.addr
+                .to_string()
+                .color(OckamColor::PrimaryResource.color())
+        )?;
+        if let Some(description) = &self.description {
+            write!(output, "\nDescription {description}")?;
+        }
+
+        Ok(output)
+    }
 }
 
 impl Output for ServiceStatus {
@@ -92,6 +208,9 @@ impl Output for ServiceStatus {
                 .to_string()
                 .color(OckamColor::PrimaryResource.color())
         )?;
+        if let Some(validation) = &self.validation {
+            write!(output, "\nValidation {validation}")?;
+        }
 
         Ok(output)
     }
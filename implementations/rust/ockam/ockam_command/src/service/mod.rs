@@ -6,7 +6,7 @@ pub(crate) mod util;
 pub(crate) use start::StartCommand;
 
 use crate::docs;
-use crate::CommandGlobalOpts;
+use crate::{Command, CommandGlobalOpts};
 use clap::{Args, Subcommand};
 
 use list::ListCommand;
@@ -15,7 +15,7 @@ use list::ListCommand;
 #[command(hide = docs::hide())]
 pub struct ServiceCommand {
     #[command(subcommand)]
-    subcommand: ServiceSubcommand,
+    pub subcommand: ServiceSubcommand,
 }
 
 #[derive(Clone, Debug, Subcommand)]
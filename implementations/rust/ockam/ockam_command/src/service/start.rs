@@ -1,17 +1,20 @@
+use async_trait::async_trait;
 use clap::{Args, Subcommand};
 use colorful::Colorful;
 use miette::miette;
 use minicbor::Encode;
 
 use ockam::Context;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::services::{ServiceAdvertisement, ServicePluginStatus};
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::api::Request;
 
 use crate::node::NodeOpts;
 use crate::terminal::OckamColor;
-use crate::util::{api, async_cmd};
-use crate::{fmt_ok, CommandGlobalOpts};
+use crate::util::api;
+use crate::{fmt_ok, Command, CommandGlobalOpts};
 use crate::{fmt_warn, Result};
 
 /// Start a specified service
@@ -28,40 +31,104 @@ pub enum StartSubCommand {
     Hop {
         #[arg(long, default_value_t = hop_default_addr())]
         addr: String,
+        #[command(flatten)]
+        advertise_opts: AdvertiseOpts,
     },
+    /// Start a service plugin registered in a custom build of the ockam binary
+    Plugin {
+        /// Name of the plugin
+        name: String,
+        /// JSON configuration passed to the plugin
+        #[arg(long, value_parser = parse_plugin_config)]
+        config: Option<serde_json::Value>,
+        #[command(flatten)]
+        advertise_opts: AdvertiseOpts,
+    },
+    /// Start a discovery service, listing the advertised services of the node to remote nodes
+    Discovery {
+        #[arg(long, default_value_t = discovery_default_addr())]
+        addr: String,
+    },
+}
+
+#[derive(Clone, Debug, Args)]
+pub struct AdvertiseOpts {
+    /// Advertise the service, so that it is listed by the discovery service of the node
+    #[arg(long)]
+    pub advertise: bool,
+
+    /// Description of the service listed by the discovery service
+    #[arg(long, value_name = "DESCRIPTION", requires = "advertise")]
+    pub description: Option<String>,
+}
+
+impl AdvertiseOpts {
+    fn advertisement(&self) -> ServiceAdvertisement {
+        if self.advertise {
+            ServiceAdvertisement::advertised(self.description.clone())
+        } else {
+            ServiceAdvertisement::default()
+        }
+    }
+}
+
+fn parse_plugin_config(config: &str) -> Result<serde_json::Value> {
+    serde_json::from_str(config)
+        .map_err(|e| miette!("The plugin configuration is not valid JSON: {e}").into())
 }
 
 fn hop_default_addr() -> String {
     DefaultAddress::HOP_SERVICE.to_string()
 }
 
-impl StartCommand {
-    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
-        async_cmd(&self.name(), opts.clone(), |ctx| async move {
-            self.async_run(&ctx, opts).await
-        })
-    }
+fn discovery_default_addr() -> String {
+    DefaultAddress::SERVICE_DISCOVERY.to_string()
+}
 
-    pub fn name(&self) -> String {
-        "service start".into()
-    }
+#[async_trait]
+impl Command for StartCommand {
+    const NAME: &'static str = "service start";
 
-    async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
         let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
-        let addr = match &self.create_subcommand {
-            StartSubCommand::Hop { addr, .. } => {
-                start_hop_service(ctx, &node, addr).await?;
+        let addresses = match &self.create_subcommand {
+            StartSubCommand::Hop {
+                addr,
+                advertise_opts,
+            } => {
+                start_hop_service(ctx, &node, addr, advertise_opts.advertisement()).await?;
                 opts.terminal.write_line(&fmt_warn!(
                     "SECURITY WARNING: Don't use Hop service in production nodes"
                 ))?;
-                addr
+                vec![addr.clone()]
+            }
+            StartSubCommand::Plugin {
+                name,
+                config,
+                advertise_opts,
+            } => {
+                start_service_plugin(
+                    ctx,
+                    &node,
+                    name,
+                    config.as_ref(),
+                    advertise_opts.advertisement(),
+                )
+                .await?
+                .addresses
+            }
+            StartSubCommand::Discovery { addr } => {
+                start_service_discovery(ctx, &node, addr).await?;
+                vec![addr.clone()]
             }
         };
 
-        opts.terminal.write_line(&fmt_ok!(
-            "Service started at address {}",
-            addr.to_string().color(OckamColor::PrimaryResource.color())
-        ))?;
+        for addr in addresses {
+            opts.terminal.write_line(&fmt_ok!(
+                "Service started at address {}",
+                addr.color(OckamColor::PrimaryResource.color())
+            ))?;
+        }
 
         Ok(())
     }
@@ -88,7 +155,45 @@ pub async fn start_hop_service(
     ctx: &Context,
     node: &BackgroundNodeClient,
     serv_addr: &str,
+    advertisement: ServiceAdvertisement,
 ) -> Result<()> {
-    let req = api::start_hop_service(serv_addr);
+    if advertisement.advertise {
+        node.require_capability(ctx, NodeCapability::SERVICE_DISCOVERY, "service discovery")
+            .await?;
+    }
+    let req = api::start_hop_service(serv_addr, advertisement);
     start_service_impl(ctx, node, "Hop", req).await
 }
+
+/// Start a discovery service, listing the advertised services of the node
+pub async fn start_service_discovery(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    serv_addr: &str,
+) -> Result<()> {
+    node.require_capability(ctx, NodeCapability::SERVICE_DISCOVERY, "service discovery")
+        .await?;
+    let req = api::start_service_discovery(serv_addr);
+    start_service_impl(ctx, node, "Discovery", req).await
+}
+
+/// Start a service plugin and return its status
+pub async fn start_service_plugin(
+    ctx: &Context,
+    node: &BackgroundNodeClient,
+    name: &str,
+    config: Option<&serde_json::Value>,
+    advertisement: ServiceAdvertisement,
+) -> Result<ServicePluginStatus> {
+    node.require_capability(ctx, NodeCapability::SERVICE_PLUGINS, "service plugins")
+        .await?;
+    if advertisement.advertise {
+        node.require_capability(ctx, NodeCapability::SERVICE_DISCOVERY, "service discovery")
+            .await?;
+    }
+    let req = api::start_service_plugin(name, config, advertisement);
+    Ok(node
+        .ask(ctx, req)
+        .await
+        .map_err(|e| miette!("Failed to start the {name} plugin: {e:?}"))?)
+}
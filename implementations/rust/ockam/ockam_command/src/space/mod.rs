@@ -4,6 +4,7 @@ pub use create::CreateCommand;
 pub use delete::DeleteCommand;
 pub use list::ListCommand;
 pub use show::ShowCommand;
+pub use use_space::UseCommand;
 
 use crate::{docs, CommandGlobalOpts};
 
@@ -11,6 +12,7 @@ mod create;
 mod delete;
 mod list;
 mod show;
+mod use_space;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -36,6 +38,8 @@ pub enum SpaceSubcommand {
     List(ListCommand),
     #[command(display_order = 800)]
     Show(ShowCommand),
+    #[command(display_order = 800)]
+    Use(UseCommand),
 }
 
 impl SpaceCommand {
@@ -45,6 +49,7 @@ impl SpaceCommand {
             SpaceSubcommand::Delete(c) => c.run(opts),
             SpaceSubcommand::List(c) => c.run(opts),
             SpaceSubcommand::Show(c) => c.run(opts),
+            SpaceSubcommand::Use(c) => c.run(opts),
         }
     }
 
@@ -54,6 +59,7 @@ impl SpaceCommand {
             SpaceSubcommand::Delete(c) => c.name(),
             SpaceSubcommand::List(c) => c.name(),
             SpaceSubcommand::Show(c) => c.name(),
+            SpaceSubcommand::Use(c) => c.name(),
         }
     }
 }
@@ -12,7 +12,7 @@ use ockam_api::cloud::project::models::OrchestratorVersionInfo;
 use ockam_api::nodes::models::base::NodeStatus as NodeStatusModel;
 use ockam_api::nodes::{BackgroundNodeClient, InMemoryNode};
 
-use crate::util::{api, async_cmd, duration::duration_parser};
+use crate::util::{api, async_cmd};
 use crate::CommandGlobalOpts;
 use crate::Result;
 
@@ -22,12 +22,11 @@ pub struct StatusCommand {
     /// Show status for all identities; default: enrolled only
     #[arg(long, short)]
     all: bool,
-
-    /// Override the default timeout
-    #[arg(long, default_value = "5", value_parser = duration_parser)]
-    timeout: Duration,
 }
 
+/// Default time to wait for the answer of the Orchestrator, when no `--timeout` is given
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl StatusCommand {
     pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
         async_cmd(&self.name(), opts.clone(), |ctx| async move {
@@ -45,7 +44,7 @@ impl StatusCommand {
 
         let node = InMemoryNode::start(ctx, &opts.state)
             .await?
-            .with_timeout(self.timeout);
+            .with_timeout(opts.global_args.timeout.unwrap_or(DEFAULT_TIMEOUT));
         let controller = node.create_controller().await?;
         let orchestrator_version = controller
             .get_orchestrator_version_info(ctx)
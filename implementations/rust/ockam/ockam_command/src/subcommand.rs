@@ -2,6 +2,7 @@ use async_trait::async_trait;
 use clap::Subcommand;
 use std::path::PathBuf;
 
+use ockam_api::logs::LogFormat;
 use ockam_api::CliState;
 use ockam_core::OpenTelemetryContext;
 use ockam_node::Context;
@@ -30,6 +31,7 @@ use crate::project::ProjectCommand;
 use crate::project_member::ProjectMemberCommand;
 use crate::relay::RelayCommand;
 use crate::reset::ResetCommand;
+use crate::route::RouteCommand;
 use crate::run::RunCommand;
 use crate::secure_channel::listener::SecureChannelListenerCommand;
 use crate::secure_channel::SecureChannelCommand;
@@ -67,6 +69,7 @@ pub enum OckamSubcommand {
     Service(ServiceCommand),
     Message(MessageCommand),
     Relay(RelayCommand),
+    Route(RouteCommand),
 
     TcpListener(TcpListenerCommand),
     TcpConnection(TcpConnectionCommand),
@@ -120,6 +123,7 @@ impl OckamSubcommand {
             OckamSubcommand::Service(c) => c.run(opts),
             OckamSubcommand::Message(c) => c.run(opts),
             OckamSubcommand::Relay(c) => c.run(opts),
+            OckamSubcommand::Route(c) => c.run(opts),
 
             OckamSubcommand::KafkaOutlet(c) => c.run(opts),
             OckamSubcommand::TcpListener(c) => c.run(opts),
@@ -178,11 +182,50 @@ impl OckamSubcommand {
             },
             OckamSubcommand::Authority(cmd) => match &cmd.subcommand {
                 AuthoritySubcommand::Create(cmd) => cmd.child_process,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Return true if this command runs a node in the current process until it is stopped
+    fn is_long_running(&self) -> bool {
+        match self {
+            OckamSubcommand::Node(cmd) => match &cmd.subcommand {
+                NodeSubcommand::Create(cmd) => cmd.child_process || cmd.foreground,
+                _ => false,
             },
+            OckamSubcommand::Authority(cmd) => match &cmd.subcommand {
+                AuthoritySubcommand::Create(cmd) => cmd.child_process || cmd.foreground,
+                _ => false,
+            },
+            OckamSubcommand::Run(cmd) => cmd.blocking,
             _ => false,
         }
     }
 
+    /// Return true if this command only reads the local state
+    fn is_read_only(&self) -> bool {
+        match self {
+            OckamSubcommand::Status(_)
+            | OckamSubcommand::Completion(_)
+            | OckamSubcommand::Markdown(_)
+            | OckamSubcommand::Manpages(_)
+            | OckamSubcommand::Environment(_) => true,
+            _ => matches!(
+                self.name().split_whitespace().last(),
+                Some("list" | "show" | "logs")
+            ),
+        }
+    }
+
+    /// Return true if this command must hold the state lock while it is executed.
+    ///
+    /// Long-running commands don't take it, otherwise they would block all the other commands.
+    pub fn requires_state_lock(&self) -> bool {
+        !self.is_read_only() && !self.is_long_running()
+    }
+
     /// Return the node name for an ockam node create command
     pub fn node_name(&self) -> Option<String> {
         match self {
@@ -204,6 +247,7 @@ impl OckamSubcommand {
                         None
                     }
                 }
+                _ => None,
             },
             _ => None,
         }
@@ -230,6 +274,18 @@ impl OckamSubcommand {
                         None
                     }
                 }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Return the log format selected for a foreground node
+    pub fn log_format(&self) -> Option<LogFormat> {
+        match self {
+            OckamSubcommand::Node(cmd) => match &cmd.subcommand {
+                NodeSubcommand::Create(cmd) => cmd.log_format.clone(),
+                _ => None,
             },
             _ => None,
         }
@@ -251,6 +307,7 @@ impl OckamSubcommand {
             OckamSubcommand::Service(c) => c.name(),
             OckamSubcommand::Message(c) => c.name(),
             OckamSubcommand::Relay(c) => c.name(),
+            OckamSubcommand::Route(c) => c.name(),
             OckamSubcommand::TcpListener(c) => c.name(),
             OckamSubcommand::TcpConnection(c) => c.name(),
             OckamSubcommand::TcpOutlet(c) => c.name(),
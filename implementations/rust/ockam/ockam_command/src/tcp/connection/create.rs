@@ -67,7 +67,7 @@ impl CreateCommand {
                     .await?
                     .name();
                 let to = response.socket_addr().into_diagnostic()?;
-                if opts.global_args.no_color {
+                if !opts.terminal.colors_enabled() {
                     println!("\n  TCP Connection:");
                     println!("    From: /node/{from}");
                     println!("    To: {} (/ip4/{}/tcp/{})", to, to.ip(), to.port());
@@ -105,6 +105,21 @@ impl Output for TransportStatus {
                 .color(OckamColor::PrimaryResource.color())
         )?;
 
+        if let (Some(accepted), Some(rejected)) =
+            (self.accepted_connections, self.rejected_connections)
+        {
+            write!(
+                output,
+                "\nConnections {} accepted, {} rejected",
+                accepted
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color()),
+                rejected
+                    .to_string()
+                    .color(OckamColor::PrimaryResource.color())
+            )?;
+        }
+
         Ok(output)
     }
 }
@@ -0,0 +1,64 @@
+use clap::Args;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::util::async_cmd;
+use crate::{docs, CommandGlobalOpts};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/connections/after_long_help.txt");
+
+/// List the connections accepted by a TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ConnectionsCommand {
+    /// Name of the inlet
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ConnectionsCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "tcp-inlet connections".into()
+    }
+
+    pub async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let connections = node
+            .list_inlet_connections(ctx, &self.alias)
+            .await?
+            .success()
+            .into_diagnostic()?;
+
+        let plain = opts.terminal.build_list(
+            &connections.list,
+            "Connections",
+            &format!("No connections found for the TCP Inlet {}", self.alias),
+        )?;
+        let json = serde_json::to_string_pretty(&connections.list).into_diagnostic()?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(json)
+            .write_line()?;
+
+        Ok(())
+    }
+}
@@ -20,7 +20,8 @@ use ockam_api::journeys::{
     JourneyEvent, NODE_NAME, TCP_INLET_ALIAS, TCP_INLET_AT, TCP_INLET_CONNECTION_STATUS,
     TCP_INLET_FROM, TCP_INLET_TO,
 };
-use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::models::portal::{InletHttpRewrite, InletStatus};
+use ockam_api::nodes::models::relay::ProjectRelayRoute;
 use ockam_api::nodes::service::portals::Inlets;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{random_name, ConnectionStatus};
@@ -28,11 +29,13 @@ use ockam_core::api::{Reply, Status};
 use ockam_multiaddr::proto;
 use ockam_multiaddr::{MultiAddr, Protocol as _};
 
-use crate::node::util::initialize_default_node;
-use crate::tcp::util::alias_parser;
+use crate::node::util::initialize_node_for_identity;
+use crate::tcp::util::{alias_parser, bandwidth_parser, http_header_parser, http_host_parser};
 use crate::terminal::OckamColor;
+use crate::util::api::IdentityOpts;
 use crate::util::duration::duration_parser;
 use crate::util::parsers::socket_addr_parser;
+use crate::util::route_aliases::expand_route;
 use crate::util::{find_available_port, port_is_free_guard, process_nodes_multiaddr};
 use crate::{docs, fmt_info, fmt_log, fmt_ok, fmt_warn, Command, CommandGlobalOpts, Error};
 
@@ -57,6 +60,7 @@ pub struct CreateCommand {
     /// If you are connecting to a remote node through a relay in the Orchestrator you can either
     /// provide the full route to the TCP Outlet as `/project/myproject/service/forward_to_myrelay/secure/api/service/outlet`,
     /// or just the name of the service as `outlet` or `/service/outlet`.
+    /// The route can use route aliases, for example `@prod-db/service/outlet`.
     /// If you are passing just the service name, consider using `--via` to specify the
     /// relay name (e.g. `ockam tcp-inlet create --to outlet --via myrelay`).
     #[arg(long, display_order = 900, id = "ROUTE", default_value_t = default_to_addr())]
@@ -66,10 +70,16 @@ pub struct CreateCommand {
     ///
     /// Use this flag when you are using `--to` to specify the service name of a TCP Outlet
     /// that is reachable through a relay in the Orchestrator.
+    /// The route to the TCP Outlet is then built from the default project, the relay name and
+    /// the service name, and the node checks that the relay exists if the project is reachable.
     /// If you don't provide it, the default relay name will be used, if necessary.
     #[arg(long, display_order = 900, id = "RELAY_NAME")]
     pub via: Option<String>,
 
+    /// Route to the TCP Outlet through the relay given with `--via`
+    #[arg(skip)]
+    relay_route: Option<ProjectRelayRoute>,
+
     /// Authorized identity for secure channel connection
     #[arg(long, name = "AUTHORIZED", display_order = 900)]
     pub authorized: Option<Identifier>,
@@ -93,13 +103,36 @@ pub struct CreateCommand {
     #[arg(long, display_order = 900, id = "RETRY", default_value = "20s", value_parser = duration_parser)]
     pub retry_wait: Duration,
 
-    /// Override default timeout
-    #[arg(long, value_parser = duration_parser)]
-    pub timeout: Option<Duration>,
-
     /// Create the TCP Inlet without waiting for the TCP Outlet to connect
     #[arg(long, default_value = "false")]
     no_connection_wait: bool,
+
+    /// Maximum bandwidth used to read from the TCP connections of the TCP Inlet,
+    /// in bps, kbps, mbps or gbps. For example `5mbps`.
+    /// When the limit is reached, the TCP clients are slowed down.
+    #[arg(long, display_order = 900, id = "BANDWIDTH", value_parser = bandwidth_parser)]
+    pub max_bandwidth: Option<u64>,
+
+    /// Check that the TCP Outlet and its target are reachable when a TCP client connects.
+    /// If they are not, the connection of the client is reset right away,
+    /// instead of timing out through the portal.
+    #[arg(long, display_order = 900)]
+    pub pre_check: bool,
+
+    /// Replace the Host header of the HTTP requests sent by the TCP clients with the given value,
+    /// for example `api.internal:8080`. The header is added to the requests without one.
+    /// Connections which don't send HTTP requests are closed
+    #[arg(long, display_order = 900, id = "HOST", value_parser = http_host_parser)]
+    pub http_host: Option<String>,
+
+    /// Add an HTTP header, given as `Name: value`, to the HTTP requests sent by the TCP clients.
+    /// A header with the same name sent by a client is removed. Can be used several times.
+    /// Connections which don't send HTTP requests are closed
+    #[arg(long = "http-header", display_order = 900, id = "HEADER", value_parser = http_header_parser)]
+    pub http_headers: Vec<(String, String)>,
+
+    #[command(flatten)]
+    pub identity_opts: IdentityOpts,
 }
 
 pub(crate) fn default_from_addr() -> SocketAddr {
@@ -116,7 +149,7 @@ impl Command for CreateCommand {
     const NAME: &'static str = "tcp-inlet create";
 
     async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
-        initialize_default_node(ctx, &opts).await?;
+        initialize_node_for_identity(ctx, &opts, &self.at, &self.identity_opts.identity).await?;
         let cmd = self.parse_args(&opts).await?;
         opts.terminal.write_line(&fmt_log!(
             "Creating TCP Inlet at {}...\n",
@@ -125,8 +158,7 @@ impl Command for CreateCommand {
                 .color(OckamColor::PrimaryResource.color())
         ))?;
 
-        let mut node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
-        cmd.timeout.map(|t| node.set_timeout_mut(t));
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &cmd.at).await?;
 
         let is_finished: Mutex<bool> = Mutex::new(false);
         let progress_bar = opts.terminal.progress_spinner();
@@ -138,17 +170,22 @@ impl Command for CreateCommand {
                 ))?;
             }
 
+            let outlet_addr = cmd.outlet_addr()?;
             let inlet = loop {
                 let result: Reply<InletStatus> = node
                     .create_inlet(
                         ctx,
                         &cmd.from.to_string(),
-                        &cmd.to(),
+                        &outlet_addr,
                         &cmd.alias,
                         &cmd.authorized,
                         &cmd.policy_expression,
                         cmd.connection_wait,
                         !cmd.no_connection_wait,
+                        cmd.max_bandwidth,
+                        cmd.relay_route.as_ref().map(|r| r.relay()),
+                        cmd.pre_check,
+                        cmd.http_rewrite(),
                     )
                     .await?;
 
@@ -157,10 +194,16 @@ impl Command for CreateCommand {
                         *is_finished.lock().await = true;
                         break inlet_status;
                     }
-                    Reply::Failed(_, s) => {
+                    Reply::Failed(e, s) => {
                         if let Some(status) = s {
                             if status == Status::BadRequest {
-                                Err(miette!("Bad request when creating an inlet"))?
+                                // the errors of the relay route resolution are reported as is
+                                match e.message() {
+                                    Some(message) if cmd.relay_route.is_some() => {
+                                        Err(miette!(message.to_string()))?
+                                    }
+                                    _ => Err(miette!("Bad request when creating an inlet"))?,
+                                }
                             }
                         };
                         trace!("the inlet creation returned a non-OK status: {s:?}");
@@ -219,8 +262,8 @@ impl Command for CreateCommand {
             .plain(if cmd.no_connection_wait {
                 fmt_ok!(
                     "The inlet {} on node {} will automatically connect when the outlet at {} is available\n",
-                    &cmd.from
-                        .to_string()
+                    &inlet
+                        .bind_addr
                         .color(OckamColor::PrimaryResource.color()),
                     &node.node_name().color(OckamColor::PrimaryResource.color()),
                     &cmd.to
@@ -230,8 +273,8 @@ impl Command for CreateCommand {
             } else if inlet.status == ConnectionStatus::Up {
                 fmt_ok!(
                     "TCP inlet {} on node {} is now sending traffic\n",
-                    &cmd.from
-                        .to_string()
+                    &inlet
+                        .bind_addr
                         .color(OckamColor::PrimaryResource.color()),
                     &node.node_name().color(OckamColor::PrimaryResource.color())
                 ) + &fmt_log!(
@@ -243,8 +286,8 @@ impl Command for CreateCommand {
             } else {
                 fmt_warn!(
                     "TCP inlet {} on node {} failed to connect to the outlet at {}\n",
-                    &cmd.from
-                        .to_string()
+                    &inlet
+                        .bind_addr
                         .color(OckamColor::PrimaryResource.color()),
                     &node.node_name().color(OckamColor::PrimaryResource.color()),
                     &cmd.to
@@ -265,6 +308,29 @@ impl CreateCommand {
         MultiAddr::from_str(&self.to).unwrap()
     }
 
+    /// Address sent to the node: the full route to the TCP Outlet,
+    /// or only the name of the outlet service if the node resolves the route via a relay
+    fn outlet_addr(&self) -> miette::Result<MultiAddr> {
+        match &self.relay_route {
+            Some(relay_route) => {
+                MultiAddr::from_str(&format!("/service/{}", relay_route.service()))
+                    .into_diagnostic()
+            }
+            None => Ok(self.to()),
+        }
+    }
+
+    /// Rewriting of the HTTP requests, if the HTTP host or headers are set
+    fn http_rewrite(&self) -> Option<InletHttpRewrite> {
+        if self.http_host.is_none() && self.http_headers.is_empty() {
+            return None;
+        }
+        Some(InletHttpRewrite::new(
+            self.http_host.clone(),
+            self.http_headers.clone(),
+        ))
+    }
+
     async fn add_inlet_created_event(
         &self,
         opts: &CommandGlobalOpts,
@@ -273,7 +339,7 @@ impl CreateCommand {
     ) -> miette::Result<()> {
         let mut attributes = HashMap::new();
         attributes.insert(TCP_INLET_AT, node_name.to_string());
-        attributes.insert(TCP_INLET_FROM, self.from.to_string());
+        attributes.insert(TCP_INLET_FROM, inlet.bind_addr.clone());
         attributes.insert(TCP_INLET_TO, self.to.clone());
         attributes.insert(TCP_INLET_ALIAS, inlet.alias.clone());
         attributes.insert(TCP_INLET_CONNECTION_STATUS, inlet.status.to_string());
@@ -285,43 +351,75 @@ impl CreateCommand {
     }
 
     async fn parse_args(mut self, opts: &CommandGlobalOpts) -> miette::Result<Self> {
-        self.to = Self::parse_arg_to(&opts.state, self.to, self.via.as_ref()).await?;
+        if let Some(via) = self.via.as_ref() {
+            let relay_route = Self::parse_arg_via(&opts.state, &self.to, via).await?;
+            self.to = relay_route.multiaddr().into_diagnostic()?.to_string();
+            self.relay_route = Some(relay_route);
+        } else {
+            self.to = Self::parse_arg_to(&opts.state, self.to, None).await?;
+        }
         Ok(self)
     }
 
+    /// Resolve the route to the TCP Outlet service when a relay name is given with `--via`
+    async fn parse_arg_via(
+        state: &CliState,
+        to: &str,
+        via: &str,
+    ) -> miette::Result<ProjectRelayRoute> {
+        let service = if to == default_to_addr() {
+            "outlet"
+        } else {
+            to
+        };
+
+        // "via" can't be passed if the user provides a full route for "to"
+        if let Ok(to) = MultiAddr::from_str(service) {
+            let is_service = to.len() == 1
+                && to
+                    .first()
+                    .map(|p| p.code() == proto::Service::CODE)
+                    .unwrap_or(false);
+            if !is_service {
+                return Err(Error::arg_validation(
+                    "to",
+                    via,
+                    Some("'via' can't be passed if 'to' is a route"),
+                ))?;
+            }
+        }
+
+        Ok(ProjectRelayRoute::resolve(state, via, service)
+            .await
+            .map_err(|e| Error::arg_validation("via", via, Some(&e.to_string())))?)
+    }
+
     async fn parse_arg_to(
         state: &CliState,
         to: impl Into<String>,
         via: Option<&String>,
     ) -> miette::Result<String> {
-        let mut to = to.into();
-        let to_is_default = to == default_to_addr();
+        // "to" uses route aliases, expand them
+        let mut to = expand_route(state, &to.into()).await?;
+        if let Some(via) = via {
+            let relay_route = Self::parse_arg_via(state, &to, via).await?;
+            return Ok(relay_route.multiaddr().into_diagnostic()?.to_string());
+        }
+
         let mut service_name = "outlet".to_string();
-        let relay_name = via.cloned().unwrap_or("default".to_string());
 
         match MultiAddr::from_str(&to) {
             // "to" is a valid multiaddr
             Ok(to) => {
                 // check whether it's a full route or a single service
                 if let Some(proto) = to.first() {
-                    // "to" refers to the service name
+                    // "to" refers to the service name, otherwise it's a full route
                     if proto.code() == proto::Service::CODE && to.len() == 1 {
                         service_name = proto
                             .cast::<proto::Service>()
                             .ok_or_else(|| Error::arg_validation("to", via, None))?
                             .to_string();
                     }
-                    // "to" is a full route
-                    else {
-                        // "via" can't be passed if the user provides a value for "to"
-                        if !to_is_default && via.is_some() {
-                            return Err(Error::arg_validation(
-                                "to",
-                                via,
-                                Some("'via' can't be passed if 'to' is a route"),
-                            ))?;
-                        }
-                    }
                 }
             }
             // If it's not
@@ -340,11 +438,10 @@ impl CreateCommand {
                 .get_default_project()
                 .await
                 .map(|p| p.name().to_string())
-                .ok()
-                .ok_or(Error::arg_validation("to", via, Some("No projects found")))?;
+                .map_err(|e| Error::arg_validation("to", via, Some(&e.to_string())))?;
             to = to.replace("<default_project_name>", &project_name);
         }
-        to = to.replace("<default_relay_name>", &relay_name);
+        to = to.replace("<default_relay_name>", "default");
         to = to.replace("<default_service_name>", &service_name);
 
         // Parse "to" as a multiaddr again with all the values in place
@@ -367,6 +464,24 @@ mod tests {
         assert!(cmd.is_ok());
     }
 
+    #[test]
+    fn http_rewrite_can_be_parsed() {
+        let parse = |args: &[&str]| {
+            let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+            parse_cmd_from_args(CreateCommand::NAME, &args)
+        };
+        assert!(parse(&["--http-host", "api.internal:8080"]).is_ok());
+        assert!(parse(&[
+            "--http-header",
+            "X-Tenant: acme",
+            "--http-header",
+            "X-Env: prod"
+        ])
+        .is_ok());
+        assert!(parse(&["--http-header", "X-Tenant"]).is_err());
+        assert!(parse(&["--http-host", "api internal"]).is_err());
+    }
+
     #[ockam_macros::test]
     async fn parse_arg_to(ctx: &mut Context) -> ockam_core::Result<()> {
         // Setup
@@ -431,6 +546,19 @@ mod tests {
             "/project/p1/service/forward_to_default/secure/api/service/myoutlet".to_string()
         );
 
+        // "to" argument accepts a route alias
+        state
+            .set_route_alias("db", "/project/p2/service/forward_to_n1/secure/api")
+            .await
+            .unwrap();
+        let res = CreateCommand::parse_arg_to(&state, "@db/service/outlet", None)
+            .await
+            .unwrap();
+        assert_eq!(
+            res,
+            "/project/p2/service/forward_to_n1/secure/api/service/outlet".to_string()
+        );
+
         // "via" argument is used to replace the relay name
         let cases = [
             (
@@ -459,4 +587,64 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn parse_arg_via() -> ockam_core::Result<()> {
+        let state = CliState::test().await.unwrap();
+
+        // the project segment can't be resolved without a default project
+        let error = CreateCommand::parse_arg_via(&state, "outlet", "myrelay")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("The project segment"), "{error}");
+
+        let project = Project::import(ProjectModel {
+            name: "p1".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        state.projects().store_project(project).await.unwrap();
+
+        // the generated route is the route users used to write by hand
+        for to in ["myoutlet", "/service/myoutlet"] {
+            let relay_route = CreateCommand::parse_arg_via(&state, to, "myrelay")
+                .await
+                .unwrap();
+            assert_eq!(
+                relay_route.multiaddr()?,
+                MultiAddr::from_str(
+                    "/project/p1/service/forward_to_myrelay/secure/api/service/myoutlet"
+                )
+                .unwrap()
+            );
+        }
+
+        // invalid relay names are reported
+        let error = CreateCommand::parse_arg_via(&state, "outlet", "my/relay")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("The relay segment"), "{error}");
+
+        // the default project must be selected when there are several projects
+        let project = Project::import(ProjectModel {
+            id: "2".to_string(),
+            name: "p2".to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+        state.projects().store_project(project).await.unwrap();
+        let error = CreateCommand::parse_arg_via(&state, "outlet", "myrelay")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("ockam project use"), "{error}");
+
+        state.projects().use_project("p2").await.unwrap();
+        let relay_route = CreateCommand::parse_arg_via(&state, "outlet", "myrelay")
+            .await
+            .unwrap();
+        assert_eq!(relay_route.project(), "p2");
+        Ok(())
+    }
 }
@@ -0,0 +1,61 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/disconnect/after_long_help.txt");
+
+/// Close a single connection of a TCP Inlet, without stopping the inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct DisconnectCommand {
+    /// Id of the connection, as returned by `ockam tcp-inlet connections`
+    #[arg(display_order = 900, required = true, id = "ID")]
+    id: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl DisconnectCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "tcp-inlet disconnect".into()
+    }
+
+    pub async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        node.close_portal_connection(ctx, &self.id)
+            .await?
+            .success()
+            .into_diagnostic()?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The connection {} on Node {} has been closed",
+                color!(&self.id, OckamColor::PrimaryResource),
+                color!(node.node_name(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!({ "id": self.id }))
+            .write_line()?;
+
+        Ok(())
+    }
+}
@@ -1,13 +1,21 @@
+mod connections;
 pub(crate) mod create;
 mod delete;
+mod disconnect;
 mod list;
+mod pause;
+mod resume;
 mod show;
 
 use crate::{docs, Command, CommandGlobalOpts};
 use clap::{Args, Subcommand};
+use connections::ConnectionsCommand;
 use create::CreateCommand;
 use delete::DeleteCommand;
+use disconnect::DisconnectCommand;
 pub(crate) use list::ListCommand;
+use pause::PauseCommand;
+use resume::ResumeCommand;
 pub(crate) use show::ShowCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -32,6 +40,10 @@ pub enum TcpInletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Connections(ConnectionsCommand),
+    Disconnect(DisconnectCommand),
+    Pause(PauseCommand),
+    Resume(ResumeCommand),
 }
 
 impl TcpInletCommand {
@@ -41,6 +53,10 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.run(opts),
             TcpInletSubCommand::List(c) => c.run(opts),
             TcpInletSubCommand::Show(c) => c.run(opts),
+            TcpInletSubCommand::Connections(c) => c.run(opts),
+            TcpInletSubCommand::Disconnect(c) => c.run(opts),
+            TcpInletSubCommand::Pause(c) => c.run(opts),
+            TcpInletSubCommand::Resume(c) => c.run(opts),
         }
     }
 
@@ -50,6 +66,10 @@ impl TcpInletCommand {
             TcpInletSubCommand::Delete(c) => c.name(),
             TcpInletSubCommand::List(c) => c.name(),
             TcpInletSubCommand::Show(c) => c.name(),
+            TcpInletSubCommand::Connections(c) => c.name(),
+            TcpInletSubCommand::Disconnect(c) => c.name(),
+            TcpInletSubCommand::Pause(c) => c.name(),
+            TcpInletSubCommand::Resume(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,64 @@
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_api::nodes::models::portal::InletStatus;
+use ockam_api::nodes::service::portals::Inlets;
+use ockam_api::nodes::BackgroundNodeClient;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/resume/after_long_help.txt");
+
+/// Accept new connections again on a paused TCP Inlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ResumeCommand {
+    /// Alias of the inlet to resume
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the inlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ResumeCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "tcp-inlet resume".into()
+    }
+
+    pub async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let inlet: InletStatus = node
+            .resume_inlet(ctx, &self.alias)
+            .await?
+            .success()
+            .into_diagnostic()?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The TCP Inlet {} on Node {} accepts new connections",
+                color!(&self.alias, OckamColor::PrimaryResource),
+                color!(node.node_name(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!(&inlet))
+            .write_line()?;
+
+        Ok(())
+    }
+}
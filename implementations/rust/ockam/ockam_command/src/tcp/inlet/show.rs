@@ -2,6 +2,8 @@ use clap::Args;
 use colorful::Colorful;
 use indoc::formatdoc;
 use miette::IntoDiagnostic;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use ockam::Context;
 use ockam_api::nodes::models::portal::InletStatus;
@@ -9,7 +11,7 @@ use ockam_api::nodes::service::portals::Inlets;
 use ockam_api::nodes::BackgroundNodeClient;
 
 use crate::node::NodeOpts;
-use crate::tcp::util::alias_parser;
+use crate::tcp::util::{alias_parser, fmt_bandwidth, fmt_paused};
 use crate::util::async_cmd;
 use crate::{docs, fmt_ok, CommandGlobalOpts};
 
@@ -57,10 +59,30 @@ impl ShowCommand {
             outlet_route,
             status,
             outlet_addr,
+            bandwidth_limit,
+            throughput,
+            far_side,
+            paused_at,
             ..
         } = inlet_status;
 
         let outlet_route = outlet_route.unwrap_or("N/A".to_string());
+        let bandwidth_limit = bandwidth_limit
+            .map(fmt_bandwidth)
+            .unwrap_or("unlimited".to_string());
+        let throughput = throughput.map(fmt_bandwidth).unwrap_or("N/A".to_string());
+        let far_side = match far_side {
+            Some(far_side) => match far_side.checked_at.and_then(|t| {
+                OffsetDateTime::from_unix_timestamp(t as i64)
+                    .ok()
+                    .and_then(|t| t.format(&Rfc3339).ok())
+            }) {
+                Some(checked_at) => format!("{} (checked at {checked_at})", far_side.health),
+                None => far_side.health,
+            },
+            None => "not checked".to_string(),
+        };
+        let paused = fmt_paused(paused_at);
         let plain = formatdoc! {r#"
         Inlet:
           Alias: {alias}
@@ -68,6 +90,10 @@ impl ShowCommand {
           TCP Address: {bind_addr}
           Outlet Route: {outlet_route}
           Outlet Destination: {outlet_addr}
+          Bandwidth Limit: {bandwidth_limit}
+          Throughput: {throughput}
+          Far Side: {far_side}
+          Paused: {paused}
     "#};
         let machine = bind_addr;
         opts.terminal
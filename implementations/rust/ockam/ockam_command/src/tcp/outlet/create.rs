@@ -13,11 +13,14 @@ use ockam::Context;
 use ockam_abac::Expr;
 use ockam_api::address::extract_address_value;
 use ockam_api::journeys::{JourneyEvent, NODE_NAME, TCP_OUTLET_AT, TCP_OUTLET_FROM, TCP_OUTLET_TO};
+use ockam_api::nodes::models::portal::OutletIdentityForwarding;
 use ockam_api::nodes::service::portals::Outlets;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_core::Address;
+use ockam_transport_tcp::TcpOutletIdentityFormat;
 
 use crate::node::util::initialize_default_node;
+use crate::tcp::util::{bandwidth_parser, http_header_name_parser};
 
 use crate::util::parsers::socket_addr_parser;
 use crate::{docs, fmt_info, fmt_ok, Command, CommandGlobalOpts};
@@ -57,6 +60,32 @@ pub struct CreateCommand {
     /// You can check the fallback policy with `ockam policy show --resource-type tcp-outlet`.
     #[arg(hide = true, long = "allow", display_order = 904, id = "EXPRESSION")]
     pub policy_expression: Option<Expr>,
+
+    /// Maximum bandwidth used to read from the TCP connections of the TCP Outlet,
+    /// in bps, kbps, mbps or gbps. For example `5mbps`.
+    /// When the limit is reached, the TCP server is slowed down.
+    #[arg(long, display_order = 905, id = "BANDWIDTH", value_parser = bandwidth_parser)]
+    pub max_bandwidth: Option<u64>,
+
+    /// Number of connections to the TCP server established in advance by the TCP Outlet,
+    /// so that new TCP Inlet connections don't wait for a TCP handshake with the server.
+    /// Idle connections are closed after 60 seconds and replaced.
+    /// By default, no connections are established in advance
+    #[arg(long, display_order = 906, id = "SIZE")]
+    pub connection_pool_size: Option<usize>,
+
+    /// Pass the identifier of the identity which created each connection to the TCP server,
+    /// in an HTTP header added to the first request of the connection. The header is named
+    /// X-Ockam-Identifier if no name is given, and a header with the same name sent by the
+    /// client is removed. Connections which are not made through a secure channel are refused
+    #[arg(long, display_order = 907, id = "HEADER_NAME", num_args = 0..=1, require_equals = true, default_missing_value = TcpOutletIdentityFormat::DEFAULT_HTTP_HEADER, value_parser = http_header_name_parser, conflicts_with = "PROXY_PROTOCOL_VERSION")]
+    pub identity_header: Option<String>,
+
+    /// Pass the identifier of the identity which created each connection to the TCP server,
+    /// in a PROXY protocol header sent before the data of the connection. The identifier is
+    /// in a TLV of type 0xE0. Connections which are not made through a secure channel are refused
+    #[arg(long, display_order = 908, id = "PROXY_PROTOCOL_VERSION", value_parser = ["v2"])]
+    pub proxy_protocol: Option<String>,
 }
 
 #[async_trait]
@@ -69,10 +98,19 @@ impl Command for CreateCommand {
         let node_name = node.node_name();
         let is_finished: Mutex<bool> = Mutex::new(false);
 
+        let identity_forwarding = self.identity_forwarding();
         let send_req = async {
             let from = self.from.map(Address::from);
             let res = node
-                .create_outlet(ctx, &self.to, from.as_ref(), self.policy_expression)
+                .create_outlet(
+                    ctx,
+                    &self.to,
+                    from.as_ref(),
+                    self.policy_expression,
+                    self.max_bandwidth,
+                    self.connection_pool_size,
+                    identity_forwarding,
+                )
                 .await?;
             *is_finished.lock().await = true;
             Ok(res)
@@ -134,6 +172,16 @@ impl Command for CreateCommand {
     }
 }
 
+impl CreateCommand {
+    fn identity_forwarding(&self) -> Option<OutletIdentityForwarding> {
+        match (&self.identity_header, &self.proxy_protocol) {
+            (Some(header), _) => Some(OutletIdentityForwarding::HttpHeader(header.clone())),
+            (None, Some(_)) => Some(OutletIdentityForwarding::ProxyProtocolV2),
+            (None, None) => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,4 +195,22 @@ mod tests {
         );
         assert!(cmd.is_ok());
     }
+
+    #[test]
+    fn identity_forwarding_can_be_parsed() {
+        let parse = |args: &[&str]| {
+            let args: Vec<String> = ["--to", "127.0.0.1:5000"]
+                .iter()
+                .chain(args)
+                .map(|a| a.to_string())
+                .collect();
+            parse_cmd_from_args(CreateCommand::NAME, &args)
+        };
+        assert!(parse(&["--identity-header"]).is_ok());
+        assert!(parse(&["--identity-header=X-Client-Id"]).is_ok());
+        assert!(parse(&["--identity-header=X-Client-Id:"]).is_err());
+        assert!(parse(&["--proxy-protocol", "v2"]).is_ok());
+        assert!(parse(&["--proxy-protocol", "v1"]).is_err());
+        assert!(parse(&["--identity-header", "--proxy-protocol", "v2"]).is_err());
+    }
 }
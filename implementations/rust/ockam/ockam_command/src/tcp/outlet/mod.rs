@@ -1,6 +1,8 @@
 pub mod create;
 mod delete;
 pub mod list;
+mod pause;
+mod resume;
 mod show;
 
 use crate::{docs, Command, CommandGlobalOpts};
@@ -8,6 +10,8 @@ use clap::{Args, Subcommand};
 use create::CreateCommand;
 use delete::DeleteCommand;
 use list::ListCommand;
+use pause::PauseCommand;
+use resume::ResumeCommand;
 use show::ShowCommand;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
@@ -32,6 +36,8 @@ pub enum TcpOutletSubCommand {
     Delete(DeleteCommand),
     List(ListCommand),
     Show(ShowCommand),
+    Pause(PauseCommand),
+    Resume(ResumeCommand),
 }
 
 impl TcpOutletCommand {
@@ -41,6 +47,8 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Delete(c) => c.run(opts),
             TcpOutletSubCommand::List(c) => c.run(opts),
             TcpOutletSubCommand::Show(c) => c.run(opts),
+            TcpOutletSubCommand::Pause(c) => c.run(opts),
+            TcpOutletSubCommand::Resume(c) => c.run(opts),
         }
     }
 
@@ -50,6 +58,8 @@ impl TcpOutletCommand {
             TcpOutletSubCommand::Delete(c) => c.name(),
             TcpOutletSubCommand::List(c) => c.name(),
             TcpOutletSubCommand::Show(c) => c.name(),
+            TcpOutletSubCommand::Pause(c) => c.name(),
+            TcpOutletSubCommand::Resume(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::models::portal::OutletStatus;
+use ockam_api::nodes::service::portals::Outlets;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::Address;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::util::async_cmd;
+use crate::util::duration::duration_parser;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/pause/after_long_help.txt");
+
+/// Stop accepting new connections on a TCP Outlet, without deleting it
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct PauseCommand {
+    /// Alias of the outlet to pause
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Close the connections still open after this grace period, for example 30s.
+    /// The existing connections are kept open if it is not set
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    drain_after: Option<Duration>,
+
+    /// Node on which the outlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl PauseCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "tcp-outlet pause".into()
+    }
+
+    pub async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let outlet: OutletStatus = node
+            .pause_outlet(ctx, &Address::from_string(&self.alias), self.drain_after)
+            .await?;
+
+        let drain = match self.drain_after {
+            Some(drain_after) => format!(
+                ", its connections will be closed in {}",
+                color!(format!("{drain_after:?}"), OckamColor::PrimaryResource)
+            ),
+            None => String::new(),
+        };
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The TCP Outlet {} on Node {} refuses new connections{drain}",
+                color!(&self.alias, OckamColor::PrimaryResource),
+                color!(node.node_name(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!(&outlet))
+            .write_line()?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use clap::Args;
+use colorful::Colorful;
+
+use ockam::Context;
+use ockam_api::nodes::models::portal::OutletStatus;
+use ockam_api::nodes::service::portals::Outlets;
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::Address;
+
+use crate::node::NodeOpts;
+use crate::tcp::util::alias_parser;
+use crate::util::async_cmd;
+use crate::{color, docs, fmt_ok, CommandGlobalOpts, OckamColor};
+
+const PREVIEW_TAG: &str = include_str!("../../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/resume/after_long_help.txt");
+
+/// Accept new connections again on a paused TCP Outlet
+#[derive(Clone, Debug, Args)]
+#[command(
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP))]
+pub struct ResumeCommand {
+    /// Alias of the outlet to resume
+    #[arg(display_order = 900, required = true, id = "ALIAS", value_parser = alias_parser)]
+    alias: String,
+
+    /// Node on which the outlet was started
+    #[command(flatten)]
+    node_opts: NodeOpts,
+}
+
+impl ResumeCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |ctx| async move {
+            self.async_run(&ctx, opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "tcp-outlet resume".into()
+    }
+
+    pub async fn async_run(&self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.node_opts.at_node).await?;
+        let outlet: OutletStatus = node
+            .resume_outlet(ctx, &Address::from_string(&self.alias))
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The TCP Outlet {} on Node {} accepts new connections",
+                color!(&self.alias, OckamColor::PrimaryResource),
+                color!(node.node_name(), OckamColor::PrimaryResource)
+            ))
+            .json(serde_json::json!(&outlet))
+            .write_line()?;
+
+        Ok(())
+    }
+}
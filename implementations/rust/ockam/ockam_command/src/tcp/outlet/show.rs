@@ -10,14 +10,16 @@ use ockam::Context;
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::{
     address::extract_address_value,
-    nodes::models::portal::{OutletList, OutletStatus},
+    nodes::models::portal::{
+        OutletConnectionPoolStatus, OutletIdentityForwarding, OutletList, OutletStatus,
+    },
 };
 use ockam_core::api::Request;
 use ockam_core::AsyncTryClone;
 use ockam_multiaddr::MultiAddr;
 
 use crate::output::Output;
-use crate::tcp::util::alias_parser;
+use crate::tcp::util::{alias_parser, fmt_paused};
 use crate::terminal::tui::ShowCommandTui;
 use crate::terminal::PluralTerm;
 use crate::util::async_cmd;
@@ -70,6 +72,12 @@ struct OutletInformation {
     node_name: String,
     worker_addr: MultiAddr,
     socket_addr: SocketAddr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connection_pool: Option<OutletConnectionPoolStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    identity_forwarding: Option<OutletIdentityForwarding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paused_at: Option<u64>,
 }
 
 impl Output for OutletInformation {
@@ -79,6 +87,18 @@ impl Output for OutletInformation {
         write!(w, "\n  On Node: {}", self.node_name)?;
         write!(w, "\n  From address: {}", self.worker_addr)?;
         write!(w, "\n  To TCP server: {}", self.socket_addr)?;
+        if let Some(pool) = &self.connection_pool {
+            write!(w, "\n  Connection pool:")?;
+            write!(w, "\n    Size: {}", pool.size)?;
+            write!(w, "\n    Available: {}", pool.available)?;
+            write!(w, "\n    Hits: {}", pool.hits)?;
+            write!(w, "\n    Misses: {}", pool.misses)?;
+            write!(w, "\n    Evicted: {}", pool.evicted)?;
+        }
+        if let Some(identity_forwarding) = &self.identity_forwarding {
+            write!(w, "\n  Identity forwarding: {identity_forwarding}")?;
+        }
+        write!(w, "\n  Paused: {}", fmt_paused(self.paused_at))?;
         Ok(w)
     }
 }
@@ -155,6 +175,9 @@ impl ShowCommandTui for ShowTui {
             node_name: self.node.node_name().to_string(),
             worker_addr: outlet_status.worker_address().into_diagnostic()?,
             socket_addr: outlet_status.socket_addr,
+            connection_pool: outlet_status.connection_pool,
+            identity_forwarding: outlet_status.identity_forwarding,
+            paused_at: outlet_status.paused_at,
         };
         self.terminal()
             .stdout()
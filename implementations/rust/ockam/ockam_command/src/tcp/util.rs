@@ -1,5 +1,8 @@
 use crate::Result;
 use miette::miette;
+use ockam_transport_tcp::TcpOutletIdentityFormat;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 pub fn alias_parser(arg: &str) -> Result<String> {
     if arg.contains(':') {
@@ -8,3 +11,143 @@ pub fn alias_parser(arg: &str) -> Result<String> {
         Ok(arg.to_string())
     }
 }
+
+/// Parse the name of an HTTP header
+pub fn http_header_name_parser(arg: &str) -> Result<String> {
+    if TcpOutletIdentityFormat::is_valid_http_header_name(arg) {
+        Ok(arg.to_string())
+    } else {
+        Err(miette!("'{arg}' is not a valid HTTP header name"))?
+    }
+}
+
+/// Parse the value of an HTTP Host header
+pub fn http_host_parser(arg: &str) -> Result<String> {
+    if arg.is_empty() || arg.contains(char::is_whitespace) {
+        Err(miette!("'{arg}' is not a valid HTTP host"))?
+    }
+    Ok(arg.to_string())
+}
+
+/// Parse an HTTP header given as `Name: value`
+pub fn http_header_parser(arg: &str) -> Result<(String, String)> {
+    let (name, value) = arg
+        .split_once(':')
+        .ok_or_else(|| miette!("the HTTP header {arg} must be given as 'Name: value'"))?;
+    let name = http_header_name_parser(name.trim())?;
+    let value = value.trim();
+    if value.contains(['\r', '\n']) {
+        Err(miette!(
+            "the value of the HTTP header {name} must be on a single line"
+        ))?
+    }
+    Ok((name, value.to_string()))
+}
+
+/// Parse a bandwidth expressed in bits per second, like `800kbps` or `5mbps`,
+/// and return it in bytes per second
+pub fn bandwidth_parser(arg: &str) -> Result<u64> {
+    let normalized = arg.trim().to_lowercase();
+    let (value, multiplier) = [
+        ("gbps", 1_000_000_000f64),
+        ("mbps", 1_000_000f64),
+        ("kbps", 1_000f64),
+        ("bps", 1f64),
+    ]
+    .iter()
+    .find_map(|(unit, multiplier)| {
+        normalized
+            .strip_suffix(unit)
+            .map(|value| (value.trim().to_string(), *multiplier))
+    })
+    .ok_or_else(|| {
+        miette!(
+            "the bandwidth {arg} must be expressed in bps, kbps, mbps or gbps, for example 5mbps"
+        )
+    })?;
+    let bits_per_second = value
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v > 0.0)
+        .ok_or_else(|| miette!("the bandwidth {arg} must be a positive number"))?
+        * multiplier;
+    let bytes_per_second = (bits_per_second / 8.0) as u64;
+    if bytes_per_second == 0 {
+        Err(miette!("the bandwidth {arg} must be at least 8bps"))?
+    }
+    Ok(bytes_per_second)
+}
+
+/// Display a number of bytes per second as a bandwidth in bits per second
+pub fn fmt_bandwidth(bytes_per_second: u64) -> String {
+    let bits_per_second = bytes_per_second as f64 * 8.0;
+    let (value, unit) = if bits_per_second >= 1_000_000_000.0 {
+        (bits_per_second / 1_000_000_000.0, "Gbps")
+    } else if bits_per_second >= 1_000_000.0 {
+        (bits_per_second / 1_000_000.0, "Mbps")
+    } else if bits_per_second >= 1_000.0 {
+        (bits_per_second / 1_000.0, "kbps")
+    } else {
+        (bits_per_second, "bps")
+    };
+    format!("{} {unit}", (value * 100.0).round() / 100.0)
+}
+
+/// Display whether an inlet or an outlet is paused, and since when
+pub fn fmt_paused(paused_at: Option<u64>) -> String {
+    match paused_at {
+        Some(paused_at) => match OffsetDateTime::from_unix_timestamp(paused_at as i64)
+            .ok()
+            .and_then(|t| t.format(&Rfc3339).ok())
+        {
+            Some(paused_at) => format!("yes, since {paused_at}"),
+            None => "yes".to_string(),
+        },
+        None => "no".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bandwidth_parser() {
+        assert_eq!(bandwidth_parser("5mbps").unwrap(), 625_000);
+        assert_eq!(bandwidth_parser("5Mbps").unwrap(), 625_000);
+        assert_eq!(bandwidth_parser("2.5 kbps").unwrap(), 312);
+        assert_eq!(bandwidth_parser("1gbps").unwrap(), 125_000_000);
+        assert_eq!(bandwidth_parser("800bps").unwrap(), 100);
+
+        assert!(bandwidth_parser("5").is_err());
+        assert!(bandwidth_parser("5mb").is_err());
+        assert!(bandwidth_parser("-5mbps").is_err());
+        assert!(bandwidth_parser("1bps").is_err());
+    }
+
+    #[test]
+    fn test_http_header_parser() {
+        assert_eq!(
+            http_header_parser("X-Tenant: acme").unwrap(),
+            ("X-Tenant".to_string(), "acme".to_string())
+        );
+        assert_eq!(
+            http_header_parser("Authorization:Bearer a:b").unwrap(),
+            ("Authorization".to_string(), "Bearer a:b".to_string())
+        );
+
+        assert!(http_header_parser("X-Tenant").is_err());
+        assert!(http_header_parser("X Tenant: acme").is_err());
+        assert!(http_header_parser("X-Tenant: a\nb").is_err());
+
+        assert!(http_host_parser("api.internal:8080").is_ok());
+        assert!(http_host_parser("api internal").is_err());
+    }
+
+    #[test]
+    fn test_fmt_bandwidth() {
+        assert_eq!(fmt_bandwidth(625_000), "5 Mbps");
+        assert_eq!(fmt_bandwidth(312), "2.5 kbps");
+        assert_eq!(fmt_bandwidth(0), "0 bps");
+    }
+}
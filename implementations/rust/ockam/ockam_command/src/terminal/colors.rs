@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use colorful::{core::color_string::CString, Colorful, RGB};
 use colors_transform::{Color, Rgb};
 use r3bl_rs_utils_core::UnicodeString;
@@ -46,10 +48,42 @@ impl OckamColor {
     }
 }
 
+/// Colors are enabled unless the terminal created for the command disables them
+static COLORS_ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable colors for all the messages formatted by the command
+pub fn set_colors_enabled(enabled: bool) {
+    COLORS_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Return true if the messages formatted by the command can contain colors
+pub fn colors_enabled() -> bool {
+    COLORS_ENABLED.load(Ordering::Relaxed)
+}
+
+/// Remove the ANSI escape codes from a message if colors are disabled
+pub fn strip_colors_if_disabled(msg: String) -> String {
+    if colors_enabled() {
+        return msg;
+    }
+    String::from_utf8(strip_ansi_escapes::strip(&msg)).unwrap_or(msg)
+}
+
+/// Return true if the `NO_COLOR` environment variable is set to a non-empty value.
+/// See https://no-color.org. A value of `false`, `0` or `no` keeps the colors enabled
+pub fn no_color_env() -> bool {
+    match std::env::var("NO_COLOR") {
+        Ok(value) => !matches!(value.to_lowercase().as_str(), "" | "false" | "0" | "no"),
+        Err(_) => false,
+    }
+}
+
 #[macro_export]
 macro_rules! color {
     ($text:expr, $color:expr) => {
-        $text.to_string().color($color.color())
+        $crate::terminal::strip_colors_if_disabled(
+            $text.to_string().color($color.color()).to_string(),
+        )
     };
 }
 
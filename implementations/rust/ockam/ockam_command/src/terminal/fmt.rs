@@ -1,137 +1,137 @@
 #[macro_export]
 macro_rules! fmt_log {
     ($input:expr) => {
-        format!("{} {}", "      ", format!($input))
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}", "      ", format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}", "      ", format!($input, $($args),+))
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}", "      ", format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_ok {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     ✔"
             .color($crate::terminal::OckamColor::FmtOKBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     ✔"
             .color($crate::terminal::OckamColor::FmtOKBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_para {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     │"
             .color($crate::terminal::OckamColor::FmtINFOBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     │"
             .color($crate::terminal::OckamColor::FmtINFOBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_list {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     │"
             .color($crate::terminal::OckamColor::FmtLISTBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     │"
             .color($crate::terminal::OckamColor::FmtLISTBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_heading {
     ($input:expr) => {
-        format!("{}{}\n{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{}{}\n{} {}",
         "       ",
         "─".repeat(85).dim().dark_gray(),
         "      "
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{}{}\n{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{}{}\n{} {}",
         "       ",
         "─".repeat(85).dim().dark_gray(),
         "      "
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_info {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     >"
             .color($crate::terminal::OckamColor::FmtINFOBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     >"
             .color($crate::terminal::OckamColor::FmtINFOBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_warn {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     !"
             .color($crate::terminal::OckamColor::FmtWARNBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     !"
             .color($crate::terminal::OckamColor::FmtWARNBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
 
 #[macro_export]
 macro_rules! fmt_err {
     ($input:expr) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     ✗"
             .color($crate::terminal::OckamColor::FmtERRORBackground.color())
             .bold(),
-        format!($input))
+        format!($input)))
     };
     ($input:expr, $($args:expr),+) => {
-        format!("{} {}",
+        $crate::terminal::strip_colors_if_disabled(format!("{} {}",
         "     ✗"
             .color($crate::terminal::OckamColor::FmtERRORBackground.color())
             .bold(),
-        format!($input, $($args),+))
+        format!($input, $($args),+)))
     };
 }
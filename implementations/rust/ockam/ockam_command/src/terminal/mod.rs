@@ -15,25 +15,34 @@ pub use colors::*;
 use mode::*;
 use ockam_core::env::{get_env, get_env_with_default, FromString};
 use ockam_core::errcode::Kind;
+use prompt::TerminalInput;
 use r3bl_rs_utils_core::*;
 use r3bl_tuify::*;
 use tracing::warn;
 
-use crate::output::OutputFormat;
+use crate::output::{project_output_fields, OutputField, OutputFormat};
 use crate::{fmt_info, fmt_list, fmt_log, fmt_warn, GlobalArgs, Result};
 pub mod colors;
 pub mod fmt;
+pub mod prompt;
+pub mod table;
 pub mod term;
 pub mod tui;
 
+pub use table::Table;
+
 /// A terminal abstraction to handle commands' output and messages styling.
 #[derive(Clone, Debug)]
 pub struct Terminal<T: TerminalWriter + Debug, WriteMode = ToStdErr> {
     stdout: T,
     stderr: T,
     quiet: bool,
+    /// The standard output only contains structured log records
+    structured_logs: bool,
+    no_color: bool,
     no_input: bool,
     output_format: OutputFormat,
+    output_fields: Vec<OutputField>,
     mode: WriteMode,
     max_width_col_count: usize,
     max_height_row_count: usize,
@@ -43,16 +52,30 @@ impl<T: TerminalWriter + Debug, W> Terminal<T, W> {
     pub fn is_quiet(&self) -> bool {
         self.quiet
     }
+
+    /// Return true if the standard output only contains structured log records
+    pub fn has_structured_logs(&self) -> bool {
+        self.structured_logs
+    }
+
+    /// Return true if the output of this terminal can contain colors
+    pub fn colors_enabled(&self) -> bool {
+        !self.no_color
+    }
 }
 
 impl From<&GlobalArgs> for Terminal<TerminalStream<Term>> {
     fn from(global_args: &GlobalArgs) -> Self {
-        Terminal::new(
+        let terminal = Terminal::new(
             global_args.quiet,
             global_args.no_color,
             global_args.no_input,
             global_args.output_format.clone(),
         )
+        .with_output_fields(global_args.output_fields.clone());
+        // the messages formatted outside of the terminal follow the same decision
+        set_colors_enabled(terminal.colors_enabled());
+        terminal
     }
 }
 
@@ -209,7 +232,7 @@ pub trait TerminalWriter: Clone {
 // Core functions
 impl<W: TerminalWriter + Debug> Terminal<W> {
     pub fn new(quiet: bool, no_color: bool, no_input: bool, output_format: OutputFormat) -> Self {
-        let no_color = Self::should_disable_color(no_color);
+        let no_color = Self::should_disable_color(no_color, W::stdout(no_color).is_tty());
         let no_input = Self::should_disable_user_input(no_input);
         let stdout = W::stdout(no_color);
         let stderr = W::stderr(no_color);
@@ -218,14 +241,29 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
             stdout,
             stderr,
             quiet,
+            structured_logs: false,
+            no_color,
             no_input,
             output_format,
+            output_fields: vec![],
             mode: ToStdErr,
             max_width_col_count,
             max_height_row_count: 5,
         }
     }
 
+    /// Only display the given fields of the JSON output of a command
+    pub fn with_output_fields(mut self, output_fields: Vec<OutputField>) -> Self {
+        self.output_fields = output_fields;
+        self
+    }
+
+    /// Use a given number of columns instead of the detected width of the terminal
+    pub fn with_max_width(mut self, max_width_col_count: usize) -> Self {
+        self.max_width_col_count = max_width_col_count;
+        self
+    }
+
     pub fn is_tty(&self) -> bool {
         self.stderr.is_tty()
     }
@@ -248,6 +286,50 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         ))
     }
 
+    /// Prompt the user for a value. An empty answer selects the default value,
+    /// which is also returned when the user can't be asked for input.
+    pub fn prompt_with_default(&self, msg: impl AsRef<str>, default: &str) -> Result<String> {
+        if !self.can_ask_for_user_input() {
+            return Ok(default.to_string());
+        }
+        prompt::prompt_validated(&mut TerminalInput, msg.as_ref(), Some(default), |_| Ok(()))
+    }
+
+    /// Prompt the user for a value until it is accepted by the validator.
+    /// The validator returns the error displayed to the user when a value is rejected.
+    pub fn prompt_validated(
+        &self,
+        msg: impl AsRef<str>,
+        validator: impl Fn(&str) -> std::result::Result<(), String>,
+    ) -> Result<String> {
+        if !self.can_ask_for_user_input() {
+            return Err(miette!(
+                "A value is required for: {}. Please provide it as an argument",
+                msg.as_ref()
+            ))?;
+        }
+        prompt::prompt_validated(&mut TerminalInput, msg.as_ref(), None, validator)
+    }
+
+    /// Validate a value provided as an argument or, if there is none, prompt the user for it.
+    /// When the user can't be asked for input, the default value is used.
+    pub fn validated_value_or_prompt(
+        &self,
+        value: Option<String>,
+        msg: impl AsRef<str>,
+        default: String,
+        validator: impl Fn(&str) -> std::result::Result<(), String>,
+    ) -> Result<String> {
+        prompt::validated_value_or_prompt(
+            &mut TerminalInput,
+            self.can_ask_for_user_input(),
+            value,
+            msg.as_ref(),
+            default,
+            validator,
+        )
+    }
+
     pub fn confirmed_with_flag_or_prompt(
         &self,
         flag: bool,
@@ -307,10 +389,11 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         !self.no_input && self.stderr.is_tty() && !self.quiet
     }
 
-    fn should_disable_color(no_color: bool) -> bool {
-        // If global argument `--no-color` is passed or the `NO_COLOR` env var is set, colors
-        // will be stripped out from output messages. Otherwise, let the terminal decide.
-        no_color || get_env_with_default("NO_COLOR", false).unwrap_or(false)
+    fn should_disable_color(no_color: bool, is_tty: bool) -> bool {
+        // If global argument `--no-color` is passed, the `NO_COLOR` env var is set, or the
+        // standard output is redirected, colors will be stripped out from output messages.
+        // Otherwise, let the terminal decide.
+        no_color || no_color_env() || !is_tty
     }
 
     fn should_disable_user_input(no_input: bool) -> bool {
@@ -324,6 +407,21 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         clone.quiet = true;
         clone
     }
+
+    /// Return a terminal which writes its output to the standard error,
+    /// because the standard output only contains structured log records
+    pub fn set_structured_logs(&self) -> Self {
+        let mut clone = self.clone();
+        clone.structured_logs = true;
+        clone
+    }
+
+    /// Return a terminal which never asks for user input, even when it is attached to a TTY
+    pub fn set_no_input(&self) -> Self {
+        let mut clone = self.clone();
+        clone.no_input = true;
+        clone
+    }
 }
 
 // Logging mode
@@ -343,7 +441,11 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
     }
 
     pub fn write_line(&self, msg: impl AsRef<str>) -> Result<&Self> {
-        if self.quiet || !self.stdout.is_tty() || self.output_format != OutputFormat::Plain {
+        if self.quiet
+            || !self.stdout.is_tty()
+            || self.output_format != OutputFormat::Plain
+            || !self.output_fields.is_empty()
+        {
             return Ok(self);
         }
 
@@ -403,13 +505,24 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdErr> {
         Ok(output)
     }
 
+    /// Render a table fitting the width of the terminal, or a message if the table is empty
+    pub fn build_table(&self, table: &Table, empty_message: &str) -> String {
+        if table.is_empty() {
+            return fmt_info!("{}", empty_message);
+        }
+        table.render(self.max_width_col_count)
+    }
+
     pub fn stdout(self) -> Terminal<W, ToStdOut> {
         Terminal {
             stdout: self.stdout,
             stderr: self.stderr,
             quiet: self.quiet,
+            structured_logs: self.structured_logs,
+            no_color: self.no_color,
             no_input: self.no_input,
             output_format: self.output_format,
+            output_fields: self.output_fields,
             mode: ToStdOut {
                 output: Output::new(),
             },
@@ -440,6 +553,16 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
         self
     }
 
+    /// Return the stream receiving the output of a command.
+    /// This is the standard error when the standard output is reserved for structured log records
+    fn output_stream(&self) -> &W {
+        if self.structured_logs {
+            &self.stderr
+        } else {
+            &self.stdout
+        }
+    }
+
     pub fn write_line(self) -> Result<()> {
         // Check that there is at least one output format defined
         if self.mode.output.plain.is_none()
@@ -453,6 +576,15 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
         let machine = self.mode.output.machine.as_ref();
         let json = self.mode.output.json.as_ref();
 
+        // The projection on some fields applies to the JSON output, whatever the output format
+        if !self.output_fields.is_empty() {
+            let json = json.ok_or(miette!(
+                "The --output-field option is not supported by this command"
+            ))?;
+            let projection = project_output_fields(json, &self.output_fields)?;
+            return self.output_stream().write_line(projection);
+        }
+
         let msg = match self.output_format {
             OutputFormat::Plain => {
                 // If interactive, use the following priority: Plain -> Machine -> JSON
@@ -483,7 +615,7 @@ impl<W: TerminalWriter + Debug> Terminal<W, ToStdOut> {
                 }
             },
         };
-        self.stdout.write_line(msg)
+        self.output_stream().write_line(msg)
     }
 }
 
@@ -563,6 +695,9 @@ pub enum PluralTerm {
     Inlet,
     Outlet,
     Policy,
+    Admin,
+    Key,
+    RouteAlias,
 }
 
 impl PluralTerm {
@@ -577,6 +712,9 @@ impl PluralTerm {
             PluralTerm::Inlet => "inlet",
             PluralTerm::Outlet => "outlet",
             PluralTerm::Policy => "policy",
+            PluralTerm::Admin => "admin",
+            PluralTerm::Key => "key",
+            PluralTerm::RouteAlias => "route alias",
         }
     }
 
@@ -591,6 +729,9 @@ impl PluralTerm {
             PluralTerm::Inlet => "inlets",
             PluralTerm::Outlet => "outlets",
             PluralTerm::Policy => "policies",
+            PluralTerm::Admin => "admins",
+            PluralTerm::Key => "keys",
+            PluralTerm::RouteAlias => "route aliases",
         }
     }
 }
@@ -0,0 +1,215 @@
+//! Prompts asking the user for a value, with a default value and a validation of the answer
+
+use std::io::ErrorKind;
+
+use console::Term;
+use miette::miette;
+
+use crate::{fmt_warn, Result};
+
+/// Source of the answers given to the prompts
+pub trait PromptInput {
+    /// Display the prompt and return the answer of the user, or `None` if the user aborted
+    fn read_answer(&mut self, prompt: &str, default: Option<&str>) -> Result<Option<String>>;
+
+    /// Display the reason why an answer was rejected, before prompting again
+    fn reject_answer(&mut self, error: &str) -> Result<()>;
+}
+
+/// Answers typed by the user in the terminal
+pub struct TerminalInput;
+
+impl PromptInput for TerminalInput {
+    fn read_answer(&mut self, prompt: &str, default: Option<&str>) -> Result<Option<String>> {
+        let mut input = dialoguer::Input::<String>::new()
+            .with_prompt(prompt)
+            .allow_empty(true);
+        if let Some(default) = default {
+            input = input.default(default.to_string()).show_default(true);
+        }
+        match input.interact_text_on(&Term::stderr()) {
+            Ok(answer) => Ok(Some(answer)),
+            Err(dialoguer::Error::IO(e)) if e.kind() == ErrorKind::Interrupted => Ok(None),
+            Err(e) => Err(e)?,
+        }
+    }
+
+    fn reject_answer(&mut self, error: &str) -> Result<()> {
+        Term::stderr().write_line(&fmt_warn!("{error}"))?;
+        Ok(())
+    }
+}
+
+/// Prompt the user until the answer is accepted by the validator.
+/// An empty answer selects the default value, if there is one.
+pub fn prompt_validated(
+    input: &mut impl PromptInput,
+    msg: &str,
+    default: Option<&str>,
+    validator: impl Fn(&str) -> std::result::Result<(), String>,
+) -> Result<String> {
+    loop {
+        let answer = match input.read_answer(msg, default)? {
+            Some(answer) => answer.trim().to_string(),
+            None => return Err(miette!("No value was entered for: {msg}"))?,
+        };
+        let answer = match default {
+            Some(default) if answer.is_empty() => default.to_string(),
+            _ => answer,
+        };
+        match validator(&answer) {
+            Ok(()) => return Ok(answer),
+            Err(error) => input.reject_answer(&error)?,
+        }
+    }
+}
+
+/// Return the value given as a command argument, after validating it.
+///
+/// Without a value, the user is asked for one, with a default value. If the user can't be asked
+/// for input, the default value is returned.
+pub fn validated_value_or_prompt(
+    input: &mut impl PromptInput,
+    can_ask_for_user_input: bool,
+    value: Option<String>,
+    msg: &str,
+    default: String,
+    validator: impl Fn(&str) -> std::result::Result<(), String>,
+) -> Result<String> {
+    match value {
+        Some(value) => match validator(&value) {
+            Ok(()) => Ok(value),
+            Err(error) => Err(miette!("Invalid value '{value}': {error}"))?,
+        },
+        None if can_ask_for_user_input => prompt_validated(input, msg, Some(&default), validator),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Answers given in advance. The prompt is aborted when there are no more answers
+    #[derive(Default)]
+    struct ScriptedInput {
+        answers: VecDeque<String>,
+        prompts: Vec<String>,
+        errors: Vec<String>,
+    }
+
+    impl ScriptedInput {
+        fn new(answers: &[&str]) -> Self {
+            Self {
+                answers: answers.iter().map(|a| a.to_string()).collect(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl PromptInput for ScriptedInput {
+        fn read_answer(&mut self, prompt: &str, default: Option<&str>) -> Result<Option<String>> {
+            self.prompts.push(match default {
+                Some(default) => format!("{prompt} [{default}]"),
+                None => prompt.to_string(),
+            });
+            Ok(self.answers.pop_front())
+        }
+
+        fn reject_answer(&mut self, error: &str) -> Result<()> {
+            self.errors.push(error.to_string());
+            Ok(())
+        }
+    }
+
+    fn no_spaces(answer: &str) -> std::result::Result<(), String> {
+        if answer.contains(' ') {
+            Err("spaces are not allowed".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn the_user_is_prompted_until_the_answer_is_valid() {
+        let mut input = ScriptedInput::new(&["my node", "  ", "my-node"]);
+        let answer = prompt_validated(&mut input, "Node name", None, no_spaces).unwrap();
+        assert_eq!(answer, "my-node");
+        assert_eq!(input.prompts, vec!["Node name"; 3]);
+        // an empty answer without a default value is passed to the validator
+        assert_eq!(input.errors, vec!["spaces are not allowed"]);
+    }
+
+    #[test]
+    fn an_empty_answer_selects_the_default_value() {
+        let mut input = ScriptedInput::new(&["invalid name", ""]);
+        let answer = prompt_validated(&mut input, "Node name", Some("n1"), no_spaces).unwrap();
+        assert_eq!(answer, "n1");
+        assert_eq!(input.prompts, vec!["Node name [n1]"; 2]);
+        assert_eq!(input.errors.len(), 1);
+    }
+
+    #[test]
+    fn the_user_can_abort_the_prompt() {
+        let mut input = ScriptedInput::new(&["invalid name"]);
+        let error = prompt_validated(&mut input, "Node name", Some("n1"), no_spaces).unwrap_err();
+        assert!(error.to_string().contains("Node name"), "{error}");
+    }
+
+    #[test]
+    fn an_argument_value_is_validated_without_prompting() {
+        let mut input = ScriptedInput::new(&[]);
+        for can_ask_for_user_input in [true, false] {
+            let value = validated_value_or_prompt(
+                &mut input,
+                can_ask_for_user_input,
+                Some("n2".to_string()),
+                "Node name",
+                "n1".to_string(),
+                no_spaces,
+            )
+            .unwrap();
+            assert_eq!(value, "n2");
+
+            let error = validated_value_or_prompt(
+                &mut input,
+                can_ask_for_user_input,
+                Some("node 2".to_string()),
+                "Node name",
+                "n1".to_string(),
+                no_spaces,
+            )
+            .unwrap_err();
+            assert!(error.to_string().contains("spaces are not allowed"));
+        }
+        assert!(input.prompts.is_empty());
+    }
+
+    #[test]
+    fn the_default_value_is_used_when_the_user_cannot_be_asked() {
+        let mut input = ScriptedInput::new(&["n2"]);
+        let value = validated_value_or_prompt(
+            &mut input,
+            false,
+            None,
+            "Node name",
+            "n1".to_string(),
+            no_spaces,
+        )
+        .unwrap();
+        assert_eq!(value, "n1");
+        assert!(input.prompts.is_empty());
+
+        let value = validated_value_or_prompt(
+            &mut input,
+            true,
+            None,
+            "Node name",
+            "n1".to_string(),
+            no_spaces,
+        )
+        .unwrap();
+        assert_eq!(value, "n2");
+    }
+}
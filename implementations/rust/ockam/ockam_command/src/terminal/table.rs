@@ -0,0 +1,278 @@
+//! Rendering of tables adapted to the width of the terminal
+
+use colorful::Colorful;
+use console::{measure_text_width, pad_str, Alignment};
+
+/// Tables narrower than this width are displayed as blocks of `header: value` lines
+const MIN_TABLE_WIDTH: usize = 40;
+
+/// Columns are not shrunk below this width. If the table still doesn't fit,
+/// it is displayed as blocks of `header: value` lines
+const MIN_COLUMN_WIDTH: usize = 6;
+
+/// Space between two columns
+const COLUMN_SEPARATOR: &str = "  ";
+
+/// Indentation of each line, aligned with the messages formatted with `fmt_log!`
+const INDENTATION: &str = "       ";
+
+/// Suffix of the values truncated to fit in their column
+const ELLIPSIS: &str = "…";
+
+/// A table displaying a list of items, one row per item and one column per field.
+///
+/// The columns are sized to their content. When the table is wider than the terminal,
+/// the widest columns are shrunk and their values are truncated with an ellipsis.
+/// In narrow terminals, each row is displayed as a block of `header: value` lines instead.
+#[derive(Clone, Debug, Default)]
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: &[&str]) -> Self {
+        Self {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    /// Add a row, with one value per header. Missing values are displayed as empty cells
+    pub fn add_row(&mut self, mut row: Vec<String>) {
+        row.resize(self.headers.len(), String::new());
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Render the table so that it fits in the given number of columns
+    pub fn render(&self, width: usize) -> String {
+        match self.column_widths(width) {
+            Some(column_widths) => self.render_columns(&column_widths),
+            None => self.render_blocks(),
+        }
+    }
+
+    /// Return the width of each column, or None if the columns can't fit in the given width
+    fn column_widths(&self, width: usize) -> Option<Vec<usize>> {
+        if width < MIN_TABLE_WIDTH {
+            return None;
+        }
+        let mut column_widths: Vec<usize> = self
+            .headers
+            .iter()
+            .map(|header| measure_text_width(header))
+            .collect();
+        for row in &self.rows {
+            for (column_width, value) in column_widths.iter_mut().zip(row) {
+                *column_width = (*column_width).max(measure_text_width(value));
+            }
+        }
+
+        let separators = COLUMN_SEPARATOR.len() * column_widths.len().saturating_sub(1);
+        let available = width.saturating_sub(INDENTATION.len() + separators);
+        while column_widths.iter().sum::<usize>() > available {
+            let widest = column_widths.iter_mut().max()?;
+            if *widest <= MIN_COLUMN_WIDTH {
+                return None;
+            }
+            *widest -= 1;
+        }
+        Some(column_widths)
+    }
+
+    fn render_columns(&self, column_widths: &[usize]) -> String {
+        let headers: Vec<String> = self
+            .headers
+            .iter()
+            .zip(column_widths)
+            .map(|(header, width)| {
+                let header = pad_str(header, *width, Alignment::Left, Some(ELLIPSIS));
+                header.to_string().bold().to_string()
+            })
+            .collect();
+        let mut lines = vec![Self::render_line(&headers)];
+        for row in &self.rows {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(column_widths)
+                .map(|(value, width)| {
+                    pad_str(value, *width, Alignment::Left, Some(ELLIPSIS)).to_string()
+                })
+                .collect();
+            lines.push(Self::render_line(&cells));
+        }
+        lines.join("\n")
+    }
+
+    fn render_line(cells: &[String]) -> String {
+        format!("{INDENTATION}{}", cells.join(COLUMN_SEPARATOR))
+            .trim_end()
+            .to_string()
+    }
+
+    fn render_blocks(&self) -> String {
+        let headers_width = self
+            .headers
+            .iter()
+            .map(|header| measure_text_width(header))
+            .max()
+            .unwrap_or_default();
+        let blocks: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                self.headers
+                    .iter()
+                    .zip(row)
+                    .map(|(header, value)| {
+                        let header = pad_str(header, headers_width, Alignment::Left, None);
+                        format!("{INDENTATION}{}: {value}", header.to_string().bold())
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+            .collect();
+        blocks.join("\n\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::output::OutputFormat;
+    use crate::terminal::{Terminal, TerminalStream, TerminalWriter};
+    use crate::OckamColor;
+
+    /// A terminal writing into a shared buffer
+    #[derive(Clone, Debug, Default)]
+    struct FakeTerminal(Arc<Mutex<Vec<u8>>>);
+
+    impl FakeTerminal {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl Write for FakeTerminal {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl TerminalWriter for TerminalStream<FakeTerminal> {
+        fn stdout(no_color: bool) -> Self {
+            Self {
+                writer: FakeTerminal::default(),
+                no_color,
+            }
+        }
+
+        fn stderr(no_color: bool) -> Self {
+            Self::stdout(no_color)
+        }
+
+        fn is_tty(&self) -> bool {
+            true
+        }
+
+        fn write(&mut self, s: impl AsRef<str>) -> crate::Result<()> {
+            let s = self.prepare_msg(s)?;
+            self.writer.write_all(s.as_bytes())?;
+            Ok(())
+        }
+
+        fn rewrite(&mut self, s: impl AsRef<str>) -> crate::Result<()> {
+            self.write(s)
+        }
+
+        fn write_line(&self, s: impl AsRef<str>) -> crate::Result<()> {
+            let s = self.prepare_msg(s)?;
+            self.writer.clone().write_all(format!("{s}\n").as_bytes())?;
+            Ok(())
+        }
+    }
+
+    fn nodes_table() -> Table {
+        let mut table = Table::new(&["NAME", "STATUS", "ROUTE"]);
+        table.add_row(vec![
+            "n1".to_string(),
+            "UP".color(OckamColor::Success.color()).to_string(),
+            "/node/n1/service/a-very-long-service-name-which-does-not-fit".to_string(),
+        ]);
+        table.add_row(vec!["n2".to_string(), "DOWN".to_string()]);
+        table
+    }
+
+    fn render_in_terminal(no_color: bool, width: usize) -> String {
+        let terminal: Terminal<TerminalStream<FakeTerminal>> =
+            Terminal::new(false, no_color, true, OutputFormat::Plain).with_max_width(width);
+        let stdout = terminal.stdout.writer.clone();
+        let plain = terminal.build_table(&nodes_table(), "No nodes");
+        terminal.stdout().plain(plain).write_line().unwrap();
+        stdout.contents()
+    }
+
+    #[test]
+    fn columns_are_sized_to_their_content() {
+        let output = nodes_table().render(120);
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("NAME"), "{output}");
+        assert!(lines[1].ends_with("a-very-long-service-name-which-does-not-fit"));
+        assert!(!output.contains(ELLIPSIS), "{output}");
+    }
+
+    #[test]
+    fn columns_are_truncated_to_fit_the_terminal() {
+        let output = nodes_table().render(50);
+        assert!(output.contains(ELLIPSIS), "{output}");
+        for line in output.lines() {
+            assert!(measure_text_width(line) <= 50, "{line}");
+        }
+    }
+
+    #[test]
+    fn narrow_terminals_display_blocks() {
+        let output = nodes_table().render(30);
+        let output = String::from_utf8(strip_ansi_escapes::strip(output)).unwrap();
+        assert!(output.contains("NAME  : n1"), "{output}");
+        assert!(output.contains("STATUS: DOWN"), "{output}");
+        assert!(
+            output.lines().any(|line| line.trim() == "ROUTE :"),
+            "{output}"
+        );
+    }
+
+    #[test]
+    fn no_ansi_codes_when_colors_are_off() {
+        let output = render_in_terminal(true, 50);
+        assert!(output.contains("n1"), "{output}");
+        assert!(!output.contains('\x1b'), "{output:?}");
+
+        let output = render_in_terminal(true, 30);
+        assert!(output.contains("NAME  : n1"), "{output}");
+        assert!(!output.contains('\x1b'), "{output:?}");
+
+        let output = render_in_terminal(false, 50);
+        assert!(output.contains('\x1b'), "{output:?}");
+    }
+
+    #[test]
+    fn empty_tables_display_a_message() {
+        let terminal: Terminal<TerminalStream<FakeTerminal>> =
+            Terminal::new(false, true, true, OutputFormat::Plain);
+        let plain = terminal.build_table(&Table::new(&["NAME"]), "No nodes");
+        assert!(plain.contains("No nodes"));
+    }
+}
@@ -1,10 +1,14 @@
 use crate::terminal::PluralTerm;
-use crate::{color, fmt_info, fmt_warn, OckamColor, Terminal, TerminalStream};
+use crate::{color, fmt_info, fmt_ok, fmt_warn, OckamColor, Terminal, TerminalStream};
 use colorful::Colorful;
 use console::Term;
+use futures::stream::{self, StreamExt};
 use miette::{miette, IntoDiagnostic};
+use serde::Serialize;
+use std::future::Future;
 
-use super::color_primary;
+/// Default number of items deleted at the same time by [`DeleteCommandTui::delete_multiple`]
+pub const DEFAULT_DELETE_CONCURRENCY: usize = 4;
 
 #[ockam_core::async_trait]
 pub trait ShowCommandTui {
@@ -87,7 +91,7 @@ pub trait ShowCommandTui {
                                     .plain(fmt_warn!(
                                         "Failed to show {} {}",
                                         Self::ITEM_NAME.singular(),
-                                        color_primary(item_name)
+                                        color!(item_name, OckamColor::PrimaryResource)
                                     ))
                                     .write_line()?;
                             }
@@ -102,7 +106,10 @@ pub trait ShowCommandTui {
 
 pub fn get_opt_node_name_message(node_name: Option<&str>) -> String {
     if let Some(node_name) = node_name {
-        format!(" on node {}", color_primary(node_name))
+        format!(
+            " on node {}",
+            color!(node_name, OckamColor::PrimaryResource)
+        )
     } else {
         "".to_string()
     }
@@ -119,19 +126,73 @@ pub trait DeleteCommandTui {
 
     async fn list_items_names(&self) -> miette::Result<Vec<String>>;
     async fn delete_single(&self, item_name: &str) -> miette::Result<()>;
+
+    /// Delete an item without writing anything to the terminal.
+    /// This is used when several items are deleted concurrently, in which case the output
+    /// is written by [`DeleteCommandTui::delete_multiple`]
+    async fn delete_item(&self, item_name: &str) -> miette::Result<()> {
+        self.delete_single(item_name).await
+    }
+
+    /// Maximum number of items deleted at the same time.
+    /// The items are deleted sequentially, with [`DeleteCommandTui::delete_single`], by default
+    fn delete_concurrency(&self) -> usize {
+        1
+    }
+
     async fn delete_multiple(&self, items_names: Vec<String>) -> miette::Result<()> {
-        for item_name in items_names {
-            if self.delete_single(&item_name).await.is_err() {
-                self.terminal()
-                    .stdout()
-                    .plain(fmt_warn!(
-                        "Failed to delete {} {}",
-                        Self::ITEM_NAME.singular(),
-                        color!(item_name, OckamColor::PrimaryResource)
-                    ))
-                    .write_line()?;
+        let concurrency = self.delete_concurrency();
+        if concurrency <= 1 {
+            for item_name in items_names {
+                if self.delete_single(&item_name).await.is_err() {
+                    self.terminal()
+                        .stdout()
+                        .plain(fmt_warn!(
+                            "Failed to delete {} {}",
+                            Self::ITEM_NAME.singular(),
+                            color!(item_name, OckamColor::PrimaryResource)
+                        ))
+                        .write_line()?;
+                }
             }
+            return Ok(());
         }
+
+        let terminal = self.terminal();
+        let results = delete_concurrently(
+            items_names,
+            concurrency,
+            |item_name| async move { self.delete_item(&item_name).await },
+            |result| {
+                let _ = terminal.write_line(result.plain_output(Self::ITEM_NAME));
+            },
+        )
+        .await;
+
+        let deleted: Vec<&str> = results
+            .iter()
+            .filter(|r| r.deleted)
+            .map(|r| r.name.as_str())
+            .collect();
+        let mut plain = fmt_ok!(
+            "{} {} deleted",
+            deleted.len(),
+            if deleted.len() == 1 {
+                Self::ITEM_NAME.singular()
+            } else {
+                Self::ITEM_NAME.plural()
+            }
+        );
+        for failed in results.iter().filter(|r| !r.deleted) {
+            plain.push('\n');
+            plain.push_str(&failed.plain_output(Self::ITEM_NAME));
+        }
+        terminal
+            .stdout()
+            .plain(plain)
+            .machine(deleted.join("\n"))
+            .json(serde_json::to_string(&results).into_diagnostic()?)
+            .write_line()?;
         Ok(())
     }
 
@@ -174,7 +235,7 @@ pub trait DeleteCommandTui {
                     return Err(miette!(
                         "The {} {} was not found",
                         Self::ITEM_NAME.singular(),
-                        color_primary(&item_name)
+                        color!(item_name, OckamColor::PrimaryResource)
                     ));
                 }
                 if terminal.confirmed_with_flag_or_prompt(
@@ -244,3 +305,111 @@ pub trait DeleteCommandTui {
         Ok(())
     }
 }
+
+/// Outcome of the deletion of an item by [`delete_concurrently`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DeletionResult {
+    pub name: String,
+    pub deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl DeletionResult {
+    fn plain_output(&self, item_name: PluralTerm) -> String {
+        match &self.error {
+            None => fmt_ok!(
+                "The {} {} has been deleted",
+                item_name.singular(),
+                color!(self.name, OckamColor::PrimaryResource)
+            ),
+            Some(error) => fmt_warn!(
+                "Failed to delete {} {}: {}",
+                item_name.singular(),
+                color!(self.name, OckamColor::PrimaryResource),
+                error
+            ),
+        }
+    }
+}
+
+/// Delete items with at most `concurrency` deletions running at the same time.
+///
+/// `on_completed` is called as soon as a deletion completes, so that progress can be reported,
+/// while the returned results are sorted by item name to keep the final output deterministic.
+pub async fn delete_concurrently<F, Fut>(
+    items_names: Vec<String>,
+    concurrency: usize,
+    delete: F,
+    mut on_completed: impl FnMut(&DeletionResult),
+) -> Vec<DeletionResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = miette::Result<()>>,
+{
+    let mut results: Vec<DeletionResult> = stream::iter(items_names)
+        .map(|name| {
+            let deletion = delete(name.clone());
+            async move {
+                let error = deletion.await.err().map(|e| e.to_string());
+                DeletionResult {
+                    name,
+                    deleted: error.is_none(),
+                    error,
+                }
+            }
+        })
+        .buffer_unordered(concurrency.max(1))
+        .inspect(|result| on_completed(result))
+        .collect()
+        .await;
+    results.sort_by(|r1, r2| r1.name.cmp(&r2.name));
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[tokio::test]
+    async fn items_are_deleted_concurrently() {
+        let items_names: Vec<String> = (0..8).rev().map(|i| format!("n{i}")).collect();
+        let delay = Duration::from_millis(100);
+        let mut completed = vec![];
+
+        let started_at = Instant::now();
+        let results = delete_concurrently(
+            items_names,
+            4,
+            |name| async move {
+                tokio::time::sleep(delay).await;
+                if name == "n3" {
+                    Err(miette!("the node is not responding"))
+                } else {
+                    Ok(())
+                }
+            },
+            |result| completed.push(result.name.clone()),
+        )
+        .await;
+        let elapsed = started_at.elapsed();
+
+        // 8 items taking 100ms each would take 800ms if they were deleted sequentially
+        assert!(elapsed < delay * 6, "{elapsed:?}");
+        assert_eq!(completed.len(), 8);
+
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["n0", "n1", "n2", "n3", "n4", "n5", "n6", "n7"]);
+
+        let failed: Vec<&DeletionResult> = results.iter().filter(|r| !r.deleted).collect();
+        assert_eq!(
+            failed,
+            vec![&DeletionResult {
+                name: "n3".to_string(),
+                deleted: false,
+                error: Some("the node is not responding".to_string()),
+            }]
+        );
+    }
+}
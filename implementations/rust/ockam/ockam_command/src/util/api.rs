@@ -7,7 +7,10 @@ use regex::Regex;
 
 use ockam::identity::Identifier;
 use ockam_api::nodes::models::flow_controls::AddConsumer;
-use ockam_api::nodes::models::services::StartHopServiceRequest;
+use ockam_api::nodes::models::services::{
+    ServiceAdvertisement, StartHopServiceRequest, StartServiceDiscoveryRequest,
+    StartServicePluginRequest,
+};
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_api::nodes::*;
 use ockam_core::api::Request;
@@ -35,6 +38,11 @@ pub(crate) fn list_services() -> Request<()> {
     Request::get("/node/services")
 }
 
+/// Construct a request to print a list of the service plugins started on the given node
+pub(crate) fn list_service_plugins() -> Request<()> {
+    Request::get("/node/plugins")
+}
+
 /// Construct a request to print a list of inlets for the given node
 pub(crate) fn list_inlets() -> Request<()> {
     Request::get("/node/inlet")
@@ -109,11 +117,30 @@ pub(crate) fn show_secure_channel_listener(
 }
 
 /// Construct a request to start a Hop Service
-pub(crate) fn start_hop_service(addr: &str) -> Request<StartHopServiceRequest> {
-    let payload = StartHopServiceRequest::new(addr);
+pub(crate) fn start_hop_service(
+    addr: &str,
+    advertisement: ServiceAdvertisement,
+) -> Request<StartHopServiceRequest> {
+    let payload = StartHopServiceRequest::new(addr).with_advertisement(advertisement);
     Request::post(node_service(DefaultAddress::HOP_SERVICE)).body(payload)
 }
 
+/// Construct a request to start a discovery service
+pub(crate) fn start_service_discovery(addr: &str) -> Request<StartServiceDiscoveryRequest> {
+    let payload = StartServiceDiscoveryRequest::new(addr);
+    Request::post(node_service(DefaultAddress::SERVICE_DISCOVERY)).body(payload)
+}
+
+/// Construct a request to start a service plugin
+pub(crate) fn start_service_plugin(
+    name: &str,
+    config: Option<&serde_json::Value>,
+    advertisement: ServiceAdvertisement,
+) -> Request<StartServicePluginRequest> {
+    let payload = StartServicePluginRequest::new(name, config).with_advertisement(advertisement);
+    Request::post("/node/plugins").body(payload)
+}
+
 pub(crate) fn add_consumer(id: FlowControlId, address: MultiAddr) -> Request<AddConsumer> {
     let payload = AddConsumer::new(id, address);
     Request::post("/node/flow_controls/add_consumer").body(payload)
@@ -136,7 +163,8 @@ pub(crate) fn parse_create_secure_channel_listener_response(resp: &[u8]) -> Resu
 
 #[derive(Clone, Debug, Args)]
 pub struct IdentityOpts {
-    /// Run the command as the given Identity name
+    /// Run the command as the given Identity name.
+    /// Defaults to the value of the OCKAM_IDENTITY environment variable, then to the default Identity
     #[arg(global = true, value_name = "IDENTITY_NAME", long)]
     pub identity: Option<String>,
 }
@@ -174,7 +202,62 @@ pub(crate) fn validate_cloud_resource_name(s: &str) -> miette::Result<()> {
 
 #[cfg(test)]
 mod test {
+    use crate::relay::RelaySubCommand;
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+    use crate::tcp::inlet::TcpInletSubCommand;
     use crate::util::api::validate_cloud_resource_name;
+    use crate::OckamSubcommand;
+    use ockam_api::CliState;
+
+    /// Return the value of the `--identity` argument of a parsed command
+    fn identity_arg(cmd: OckamSubcommand) -> Option<String> {
+        match cmd {
+            OckamSubcommand::Enroll(c) => c.identity,
+            OckamSubcommand::Relay(c) => match c.subcommand {
+                RelaySubCommand::Create(c) => c.identity_opts.identity,
+                _ => panic!("unexpected relay command"),
+            },
+            OckamSubcommand::TcpInlet(c) => match c.subcommand {
+                TcpInletSubCommand::Create(c) => c.identity_opts.identity,
+                _ => panic!("unexpected tcp-inlet command"),
+            },
+            _ => panic!("unexpected command"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_identity_resolution_for_commands() -> miette::Result<()> {
+        let state = CliState::test().await?;
+        let default = state.get_or_create_default_named_identity().await?;
+        let flag = state.create_identity_with_name("flag").await?;
+        let env = state.create_identity_with_name("env").await?;
+
+        for cmd_name in ["enroll", "relay create", "tcp-inlet create"] {
+            let cases = [
+                (true, Some(env.name()), flag.identifier()),
+                (true, None, flag.identifier()),
+                (false, Some(env.name()), env.identifier()),
+                (false, None, default.identifier()),
+            ];
+            for (use_flag, env_value, expected) in cases {
+                let args = if use_flag {
+                    vec!["--identity".to_string(), flag.name()]
+                } else {
+                    vec![]
+                };
+                let cmd = parse_cmd_from_args(cmd_name, &args)?;
+                let name = state
+                    .resolve_identity_name_with_env(&identity_arg(cmd), env_value)
+                    .await?;
+                assert_eq!(
+                    state.get_identifier_by_name(&name).await?,
+                    expected,
+                    "wrong identity for `{cmd_name}` with args {args:?}"
+                );
+            }
+        }
+        Ok(())
+    }
 
     #[test]
     fn test_validate_cloud_resource_name() {
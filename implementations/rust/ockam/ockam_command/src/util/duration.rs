@@ -1,7 +1,85 @@
 use clap::error::{Error, ErrorKind};
-use ockam_core::env::parse_duration;
 use std::time::Duration;
 
+/// Parse a duration given on the command line: a number followed by an optional unit,
+/// `ms`, `s`, `m`, `h` or `d`. A number without unit is a number of seconds.
+///
+/// For example: `500ms`, `10s`, `10`, `2m`, `1h`.
 pub(crate) fn duration_parser(arg: &str) -> Result<Duration, clap::Error> {
-    parse_duration(arg).map_err(|_| Error::raw(ErrorKind::InvalidValue, "Invalid duration."))
+    parse_duration(arg).ok_or_else(|| {
+        Error::raw(
+            ErrorKind::InvalidValue,
+            format!(
+                "Invalid duration '{arg}'. Use a number followed by an optional unit: ms, s, m, h or d (for example 500ms, 10s or 2m). A number without unit is a number of seconds."
+            ),
+        )
+    })
+}
+
+fn parse_duration(arg: &str) -> Option<Duration> {
+    let arg = arg.trim();
+    let unit_start = arg.find(|c: char| !c.is_ascii_digit()).unwrap_or(arg.len());
+    let (value, unit) = arg.split_at(unit_start);
+    if value.is_empty() {
+        return None;
+    }
+    let value: u64 = value.parse().ok()?;
+    match unit {
+        "ms" => Some(Duration::from_millis(value)),
+        "" | "s" => Some(Duration::from_secs(value)),
+        "m" => value.checked_mul(60).map(Duration::from_secs),
+        "h" => value.checked_mul(60 * 60).map(Duration::from_secs),
+        "d" => value.checked_mul(24 * 60 * 60).map(Duration::from_secs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations_with_units() {
+        assert_eq!(
+            duration_parser("500ms").unwrap(),
+            Duration::from_millis(500)
+        );
+        assert_eq!(duration_parser("10s").unwrap(), Duration::from_secs(10));
+        assert_eq!(duration_parser("2m").unwrap(), Duration::from_secs(120));
+        assert_eq!(duration_parser("1h").unwrap(), Duration::from_secs(3600));
+        assert_eq!(duration_parser("1d").unwrap(), Duration::from_secs(86400));
+        assert_eq!(duration_parser("0s").unwrap(), Duration::ZERO);
+    }
+
+    #[test]
+    fn parse_durations_without_unit_as_seconds() {
+        assert_eq!(duration_parser("10").unwrap(), Duration::from_secs(10));
+        assert_eq!(duration_parser(" 5 ").unwrap(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn reject_invalid_durations() {
+        for invalid in [
+            "",
+            " ",
+            "s",
+            "ms",
+            "-1s",
+            "1.5s",
+            "10 s",
+            "10sec",
+            "10S",
+            "x10s",
+            "10s10",
+            "1y",
+            // overflows
+            "18446744073709551616",
+            "18446744073709551615d",
+        ] {
+            assert!(
+                duration_parser(invalid).is_err(),
+                "'{invalid}' should not be a valid duration"
+            );
+        }
+    }
 }
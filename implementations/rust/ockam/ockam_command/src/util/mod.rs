@@ -1,6 +1,5 @@
 use colorful::core::color_string::CString;
 use colorful::Colorful;
-use std::sync::Arc;
 use std::{
     net::{SocketAddr, TcpListener},
     path::Path,
@@ -9,7 +8,6 @@ use std::{
 use miette::Context as _;
 use miette::{miette, IntoDiagnostic};
 use opentelemetry::trace::FutureExt;
-use tokio::runtime::Runtime;
 use tracing::{debug, error};
 
 use ockam::{Address, Context, NodeBuilder};
@@ -27,6 +25,7 @@ pub mod api;
 pub mod duration;
 pub mod exitcode;
 pub mod parsers;
+pub mod route_aliases;
 
 /// A simple wrapper for shutting down the local embedded node (for
 /// the client side of the CLI).  Swallows errors and turns them into
@@ -56,6 +55,11 @@ where
     let res = embedded_node(opts.clone(), |ctx| {
         async move { f(ctx).await }.with_context(OpenTelemetryContext::current_context())
     });
+    // display the warnings raised while running the command, for example
+    // when a node runs a different version of the binary
+    for warning in opts.state.take_warnings() {
+        let _ = opts.terminal.write_line(fmt_warn!("{warning}"));
+    }
     local_cmd(res)
 }
 
@@ -66,10 +70,7 @@ where
     T: Send + 'static,
     E: Send + Sync + From<CliStateError> + 'static,
 {
-    let (ctx, mut executor) = NodeBuilder::new()
-        .no_logging()
-        .with_runtime(opts.rt)
-        .build();
+    let (ctx, mut executor) = opts.node_builder().build();
     let res = executor.execute(
         async move {
             let child_ctx = ctx
@@ -93,13 +94,16 @@ where
     }
 }
 
-pub fn embedded_node_that_is_not_stopped<F, Fut, T>(rt: Arc<Runtime>, f: F) -> miette::Result<T>
+pub fn embedded_node_that_is_not_stopped<F, Fut, T>(
+    node_builder: NodeBuilder,
+    f: F,
+) -> miette::Result<T>
 where
     F: FnOnce(Context) -> Fut + Send + Sync + 'static,
     Fut: core::future::Future<Output = miette::Result<T>> + Send + 'static,
     T: Send + 'static,
 {
-    let (ctx, mut executor) = NodeBuilder::new().no_logging().with_runtime(rt).build();
+    let (ctx, mut executor) = node_builder.build();
     let res = executor.execute(async move {
         let child_ctx = ctx
             .new_detached(
@@ -243,6 +247,9 @@ pub fn colorize_connection_status(status: ConnectionStatus) -> CString {
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
+    use std::sync::Arc;
+
+    use tokio::runtime::Runtime;
 
     use super::*;
 
@@ -289,7 +296,9 @@ mod tests {
     #[test]
     fn test_execute_error() {
         let result = embedded_node_that_is_not_stopped(
-            Arc::new(Runtime::new().unwrap()),
+            NodeBuilder::new()
+                .no_logging()
+                .with_runtime(Arc::new(Runtime::new().unwrap())),
             |ctx| async move { function_returning_an_error(ctx, 1).await },
         );
         assert!(result.is_err());
@@ -302,7 +311,9 @@ mod tests {
     #[test]
     fn test_execute_error_() {
         let result = embedded_node_that_is_not_stopped(
-            Arc::new(Runtime::new().unwrap()),
+            NodeBuilder::new()
+                .no_logging()
+                .with_runtime(Arc::new(Runtime::new().unwrap())),
             |ctx| async move { function_returning_an_error_and_stopping_the_context(ctx, 1).await },
         );
         assert!(result.is_err());
@@ -0,0 +1,226 @@
+//! Resolution of the route aliases used as `@<name>` in the routes given on the command line
+//!
+//! An alias is expanded when a command uses it, so an alias referencing other aliases
+//! always uses their latest routes.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use miette::miette;
+
+use ockam_api::cli_state::CliState;
+use ockam_multiaddr::MultiAddr;
+
+/// Maximum number of nested aliases expanded to resolve a route
+pub const MAX_ROUTE_ALIAS_DEPTH: usize = 8;
+
+/// Prefix of the route segments referencing an alias
+pub const ROUTE_ALIAS_PREFIX: char = '@';
+
+/// Return true if the input references at least one route alias
+pub fn has_route_alias(input: &str) -> bool {
+    input
+        .split('/')
+        .any(|segment| segment.starts_with(ROUTE_ALIAS_PREFIX))
+}
+
+/// Expand the route aliases of the input, and return the expanded input.
+/// The input is returned as it is if it doesn't reference any alias.
+pub async fn expand_route(state: &CliState, input: &str) -> miette::Result<String> {
+    if !has_route_alias(input) {
+        return Ok(input.to_string());
+    }
+    let aliases = state
+        .get_route_aliases()
+        .await?
+        .into_iter()
+        .map(|alias| (alias.name, alias.route))
+        .collect();
+    expand_route_aliases(input, &aliases)
+}
+
+/// Expand the route aliases of the input and parse it as a route
+pub async fn resolve_route(state: &CliState, input: &str) -> miette::Result<MultiAddr> {
+    let route = expand_route(state, input).await?;
+    MultiAddr::from_str(&route).map_err(|e| miette!("Invalid route {route}: {e}"))
+}
+
+/// Replace each `@<name>` segment of the input with the route of the alias `name`.
+///
+/// The routes of the aliases are expanded as well, up to [`MAX_ROUTE_ALIAS_DEPTH`] nested
+/// aliases. An alias referencing itself, directly or not, is an error.
+pub fn expand_route_aliases(
+    input: &str,
+    aliases: &BTreeMap<String, String>,
+) -> miette::Result<String> {
+    let mut expanding = vec![];
+    expand(input, aliases, &mut expanding)
+}
+
+fn expand(
+    input: &str,
+    aliases: &BTreeMap<String, String>,
+    expanding: &mut Vec<String>,
+) -> miette::Result<String> {
+    let mut expanded = String::new();
+    for segment in input.split('/').filter(|s| !s.is_empty()) {
+        let name = match segment.strip_prefix(ROUTE_ALIAS_PREFIX) {
+            Some(name) => name,
+            None => {
+                expanded.push('/');
+                expanded.push_str(segment);
+                continue;
+            }
+        };
+        if expanding.iter().any(|n| n == name) {
+            let cycle = expanding
+                .iter()
+                .chain([&name.to_string()])
+                .map(|n| format!("{ROUTE_ALIAS_PREFIX}{n}"))
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            return Err(miette!("The route alias {name} references itself: {cycle}"));
+        }
+        if expanding.len() >= MAX_ROUTE_ALIAS_DEPTH {
+            return Err(miette!(
+                "The route alias {name} can't be resolved, more than {MAX_ROUTE_ALIAS_DEPTH} nested aliases are used to resolve {ROUTE_ALIAS_PREFIX}{}",
+                expanding[0]
+            ));
+        }
+        let route = aliases
+            .get(name)
+            .ok_or_else(|| unknown_route_alias(name, aliases))?;
+        expanding.push(name.to_string());
+        expanded.push_str(&expand(route, aliases, expanding)?);
+        expanding.pop();
+    }
+    Ok(expanded)
+}
+
+fn unknown_route_alias(name: &str, aliases: &BTreeMap<String, String>) -> miette::Report {
+    let close_matches = close_matches(name, aliases.keys());
+    if close_matches.is_empty() {
+        miette!("The route alias {name} doesn't exist. Run `ockam route alias list` to see the existing aliases")
+    } else {
+        miette!(
+            "The route alias {name} doesn't exist. Did you mean {}?",
+            close_matches
+                .iter()
+                .map(|n| format!("{ROUTE_ALIAS_PREFIX}{n}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
+/// Return the names close to the given name, from the closest to the farthest
+fn close_matches<'a>(name: &str, names: impl Iterator<Item = &'a String>) -> Vec<&'a String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    let mut matches = names
+        .map(|n| (edit_distance(name, n), n))
+        .filter(|(distance, n)| *distance <= max_distance || n.starts_with(name))
+        .collect::<Vec<_>>();
+    matches.sort();
+    matches.into_iter().map(|(_, n)| n).collect()
+}
+
+/// Number of characters to insert, delete or substitute to change a string into another one
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(list: &[(&str, &str)]) -> BTreeMap<String, String> {
+        list.iter()
+            .map(|(name, route)| (name.to_string(), route.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn routes_without_aliases_are_unchanged() {
+        let aliases = aliases(&[("db", "/node/n1")]);
+        assert_eq!(
+            expand_route_aliases("/node/n1/service/echo", &aliases).unwrap(),
+            "/node/n1/service/echo"
+        );
+        assert!(!has_route_alias("/node/n1/service/echo"));
+        assert!(has_route_alias("@db/service/echo"));
+    }
+
+    #[test]
+    fn nested_aliases_are_expanded() {
+        let aliases = aliases(&[
+            ("project", "/project/default"),
+            ("db-relay", "@project/service/forward_to_db"),
+            ("prod-db", "@db-relay/secure/api/service/outlet"),
+        ]);
+        assert_eq!(
+            expand_route_aliases("@prod-db", &aliases).unwrap(),
+            "/project/default/service/forward_to_db/secure/api/service/outlet"
+        );
+        assert_eq!(
+            expand_route_aliases("/node/n1/@db-relay/service/echo", &aliases).unwrap(),
+            "/node/n1/project/default/service/forward_to_db/service/echo"
+        );
+    }
+
+    #[test]
+    fn cycles_are_detected() {
+        let aliases = aliases(&[("a", "@b/service/echo"), ("b", "/node/n1/@a"), ("c", "@c")]);
+        let error = expand_route_aliases("@a", &aliases).unwrap_err();
+        assert!(error.to_string().contains("@a -> @b -> @a"));
+        let error = expand_route_aliases("@c", &aliases).unwrap_err();
+        assert!(error.to_string().contains("@c -> @c"));
+    }
+
+    #[test]
+    fn the_nesting_depth_is_limited() {
+        let list = (0..MAX_ROUTE_ALIAS_DEPTH + 1)
+            .map(|i| (format!("a{i}"), format!("@a{}", i + 1)))
+            .chain([(
+                format!("a{}", MAX_ROUTE_ALIAS_DEPTH + 1),
+                "/node/n1".to_string(),
+            )])
+            .collect::<BTreeMap<_, _>>();
+        let error = expand_route_aliases("@a0", &list).unwrap_err();
+        assert!(error.to_string().contains("nested aliases"));
+        assert_eq!(expand_route_aliases("@a2", &list).unwrap(), "/node/n1");
+    }
+
+    #[test]
+    fn unknown_aliases_list_close_matches() {
+        let aliases = aliases(&[
+            ("prod-db", "/node/n1"),
+            ("prod-dbs", "/node/n2"),
+            ("staging", "/node/n3"),
+        ]);
+        let error = expand_route_aliases("@prod-bd", &aliases).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("Did you mean @prod-db, @prod-dbs?"));
+
+        let error = expand_route_aliases("@unknown", &aliases).unwrap_err();
+        assert!(error.to_string().contains("ockam route alias list"));
+    }
+
+    #[test]
+    fn edit_distance_is_computed() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("prod", "prod"), 0);
+        assert_eq!(edit_distance("prod", "prd"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+}
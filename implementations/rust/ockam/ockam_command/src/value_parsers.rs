@@ -1,5 +1,6 @@
 use miette::{miette, Context, IntoDiagnostic};
-use ockam_api::EnrollmentTicket;
+use ockam_api::logs::LogFormat;
+use ockam_api::EnrollmentTicketContents;
 use std::str::FromStr;
 use url::Url;
 
@@ -20,8 +21,20 @@ where
     ))
 }
 
-/// Parse an enrollment ticket given a path, a URL or hex-encoded string
-pub fn parse_enrollment_ticket(value: &str) -> miette::Result<EnrollmentTicket> {
+/// Parse the format of the logs of a foreground node
+pub fn parse_log_format(value: &str) -> miette::Result<LogFormat> {
+    match value {
+        "pretty" => Ok(LogFormat::Pretty),
+        "compact" => Ok(LogFormat::Compact),
+        "json" => Ok(LogFormat::Json),
+        _ => Err(miette!(
+            "invalid log format `{value}`, expected one of: pretty, compact, json"
+        )),
+    }
+}
+
+/// Parse an enrollment ticket, which might be encrypted, given a path, a URL or hex-encoded string
+pub fn parse_enrollment_ticket_contents(value: &str) -> miette::Result<EnrollmentTicketContents> {
     let contents = parse_string_or_path_or_url(value)?;
     // Try to deserialize the contents as JSON
     if let Ok(enrollment_ticket) = serde_json::from_str(&contents) {
@@ -2,6 +2,7 @@ mod create;
 mod delete;
 mod list;
 mod move_vault;
+mod prune;
 mod show;
 mod util;
 
@@ -9,6 +10,7 @@ pub use crate::vault::create::CreateCommand;
 use crate::vault::delete::DeleteCommand;
 use crate::vault::list::ListCommand;
 use crate::vault::move_vault::MoveCommand;
+use crate::vault::prune::PruneCommand;
 use crate::vault::show::ShowCommand;
 use crate::{docs, Command, CommandGlobalOpts};
 
@@ -35,6 +37,7 @@ pub enum VaultSubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Prune(PruneCommand),
 }
 
 impl VaultCommand {
@@ -45,6 +48,7 @@ impl VaultCommand {
             VaultSubcommand::Show(cmd) => cmd.run(opts),
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
+            VaultSubcommand::Prune(cmd) => cmd.run(opts),
         }
     }
 
@@ -55,6 +59,7 @@ impl VaultCommand {
             VaultSubcommand::Show(c) => c.name(),
             VaultSubcommand::Delete(c) => c.name(),
             VaultSubcommand::List(c) => c.name(),
+            VaultSubcommand::Prune(c) => c.name(),
         }
     }
 }
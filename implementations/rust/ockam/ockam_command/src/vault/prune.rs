@@ -0,0 +1,160 @@
+use clap::Args;
+use colorful::Colorful;
+use console::Term;
+
+use crate::terminal::tui::DeleteCommandTui;
+use crate::terminal::PluralTerm;
+use crate::util::async_cmd;
+use crate::{
+    color, docs, fmt_ok, fmt_warn, CommandGlobalOpts, OckamColor, Terminal, TerminalStream,
+};
+
+const LONG_ABOUT: &str = include_str!("./static/prune/long_about.txt");
+const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/prune/after_long_help.txt");
+
+/// Delete the keys of a vault which are not used by any identity
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+before_help = docs::before_help(PREVIEW_TAG),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct PruneCommand {
+    /// Id of the orphaned key to delete, as displayed by `ockam vault show --keys`
+    #[arg(required_unless_present = "orphaned")]
+    key_id: Option<String>,
+
+    /// Name of the vault. The default vault is used if it is not set
+    #[arg(long = "vault", value_name = "VAULT_NAME")]
+    vault_name: Option<String>,
+
+    /// Delete all the orphaned keys of the vault
+    #[arg(long)]
+    orphaned: bool,
+
+    /// Also delete the keys of identities which are known locally without being local identities,
+    /// for example exported identities
+    #[arg(long)]
+    force: bool,
+
+    /// Confirm the deletion without prompting
+    #[arg(display_order = 901, long, short)]
+    yes: bool,
+}
+
+impl PruneCommand {
+    pub fn run(self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        async_cmd(&self.name(), opts.clone(), |_ctx| async move {
+            self.async_run(opts).await
+        })
+    }
+
+    pub fn name(&self) -> String {
+        "vault prune".into()
+    }
+
+    async fn async_run(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        PruneTui::run(opts, self.clone()).await
+    }
+}
+
+pub struct PruneTui {
+    opts: CommandGlobalOpts,
+    cmd: PruneCommand,
+    vault_name: String,
+}
+
+impl PruneTui {
+    pub async fn run(opts: CommandGlobalOpts, cmd: PruneCommand) -> miette::Result<()> {
+        let vault_name = match &cmd.vault_name {
+            Some(vault_name) => vault_name.clone(),
+            None => opts.state.get_or_create_default_named_vault().await?.name(),
+        };
+        let tui = Self {
+            opts,
+            cmd,
+            vault_name,
+        };
+        tui.warn_about_skipped_keys().await?;
+        tui.delete().await
+    }
+
+    /// Warn the user when some orphaned keys are only deleted with `--force`
+    async fn warn_about_skipped_keys(&self) -> miette::Result<()> {
+        if self.cmd.force {
+            return Ok(());
+        }
+        let keys = self.opts.state.get_vault_keys(&self.vault_name).await?;
+        for key in keys
+            .iter()
+            .filter(|k| k.is_orphaned() && !k.can_be_deleted(false))
+        {
+            if let Some(reference) = key.referenced_by() {
+                self.terminal()
+                    .stdout()
+                    .plain(fmt_warn!(
+                        "The key {} belongs to {reference}. Use --force to delete it",
+                        color!(key.key_id(), OckamColor::PrimaryResource)
+                    ))
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[ockam_core::async_trait]
+impl DeleteCommandTui for PruneTui {
+    const ITEM_NAME: PluralTerm = PluralTerm::Key;
+
+    fn cmd_arg_item_name(&self) -> Option<String> {
+        self.cmd.key_id.clone()
+    }
+
+    fn cmd_arg_delete_all(&self) -> bool {
+        self.cmd.orphaned
+    }
+
+    fn cmd_arg_confirm_deletion(&self) -> bool {
+        self.cmd.yes
+    }
+
+    fn terminal(&self) -> Terminal<TerminalStream<Term>> {
+        self.opts.terminal.clone()
+    }
+
+    async fn list_items_names(&self) -> miette::Result<Vec<String>> {
+        // when a key is given, it is checked by the deletion, which explains why it can't be deleted
+        let key_is_given = self.cmd.key_id.is_some();
+        Ok(self
+            .opts
+            .state
+            .get_vault_keys(&self.vault_name)
+            .await?
+            .iter()
+            .filter(|k| key_is_given || k.can_be_deleted(self.cmd.force))
+            .map(|k| k.key_id())
+            .collect())
+    }
+
+    async fn delete_single(&self, item_name: &str) -> miette::Result<()> {
+        let key = self
+            .opts
+            .state
+            .delete_orphaned_vault_key(&self.vault_name, item_name, self.cmd.force)
+            .await?;
+        self.terminal()
+            .stdout()
+            .plain(fmt_ok!(
+                "The {} key {} has been deleted from the vault {}",
+                key.key_type(),
+                color!(item_name, OckamColor::PrimaryResource),
+                color!(&self.vault_name, OckamColor::PrimaryResource)
+            ))
+            .machine(item_name)
+            .json(serde_json::json!({ "key_id": &item_name, "vault": &self.vault_name }))
+            .write_line()?;
+        Ok(())
+    }
+}
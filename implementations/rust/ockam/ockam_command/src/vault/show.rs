@@ -1,6 +1,7 @@
 use clap::Args;
 use console::Term;
 use miette::IntoDiagnostic;
+use ockam_api::nodes::models::vault::VaultKeyStatus;
 
 use crate::output::Output;
 use crate::terminal::tui::ShowCommandTui;
@@ -23,6 +24,10 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 pub struct ShowCommand {
     /// Name of the vault
     pub name: Option<String>,
+
+    /// List the keys stored in the vault, with the identities and purpose keys using them
+    #[arg(long)]
+    pub keys: bool,
 }
 
 impl ShowCommand {
@@ -44,6 +49,7 @@ impl ShowCommand {
 pub struct ShowTui {
     opts: CommandGlobalOpts,
     vault_name: Option<String>,
+    keys: bool,
 }
 
 impl ShowTui {
@@ -51,6 +57,7 @@ impl ShowTui {
         let tui = Self {
             opts,
             vault_name: cmd.name,
+            keys: cmd.keys,
         };
         tui.show().await
     }
@@ -92,6 +99,9 @@ impl ShowCommandTui for ShowTui {
     }
 
     async fn show_single(&self, item_name: &str) -> miette::Result<()> {
+        if self.keys {
+            return self.show_keys(item_name).await;
+        }
         let vault = VaultOutput::new(&self.opts.state.get_named_vault(item_name).await?);
         self.terminal()
             .stdout()
@@ -102,3 +112,33 @@ impl ShowCommandTui for ShowTui {
         Ok(())
     }
 }
+
+impl ShowTui {
+    async fn show_keys(&self, vault_name: &str) -> miette::Result<()> {
+        let keys: Vec<VaultKeyStatus> = self
+            .opts
+            .state
+            .get_vault_keys(vault_name)
+            .await?
+            .iter()
+            .map(VaultKeyStatus::from)
+            .collect();
+        let plain = self.terminal().build_list(
+            &keys,
+            &format!("Keys of the vault {vault_name}"),
+            &format!("No keys found in the vault {vault_name}"),
+        )?;
+        self.terminal()
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&keys).into_diagnostic()?)
+            .machine(
+                keys.iter()
+                    .map(|k| k.key_id.clone())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+            .write_line()?;
+        Ok(())
+    }
+}
@@ -26,7 +26,7 @@ after_long_help = docs::after_help(AFTER_LONG_HELP)
 )]
 pub struct ListCommand {
     /// Node at which to lookup workers
-    #[arg(value_name = "NODE_NAME", long, display_order = 800, value_parser = extract_address_value)]
+    #[arg(value_name = "NODE_NAME", long, visible_alias = "node", display_order = 800, value_parser = extract_address_value)]
     at: Option<String>,
 }
 
@@ -67,7 +67,11 @@ impl ListCommand {
             &format!("Workers on {}", node.node_name()),
             &format!("No workers found on {}.", node.node_name()),
         )?;
-        opts.terminal.stdout().plain(list).write_line()?;
+        opts.terminal
+            .stdout()
+            .plain(list)
+            .json(serde_json::json!(&workers.list))
+            .write_line()?;
 
         Ok(())
     }
@@ -75,11 +79,13 @@ impl ListCommand {
 
 impl Output for WorkerStatus {
     fn output(&self) -> crate::Result<String> {
-        Ok(format!(
-            "Worker {}",
-            self.addr
-                .to_string()
-                .color(OckamColor::PrimaryResource.color())
-        ))
+        let addr = self
+            .addr
+            .to_string()
+            .color(OckamColor::PrimaryResource.color());
+        match &self.owner {
+            Some(owner) => Ok(format!("Worker {addr} ({owner})")),
+            None => Ok(format!("Worker {addr}")),
+        }
     }
 }
@@ -0,0 +1,104 @@
+use core::time::Duration;
+
+use async_trait::async_trait;
+use clap::Args;
+use miette::{miette, Context as _, IntoDiagnostic};
+
+use ockam::Context;
+use ockam_api::address::extract_address_value;
+use ockam_api::nodes::models::api_version::NodeCapability;
+use ockam_api::nodes::models::workers::{InjectMessage, InjectedMessageReply};
+use ockam_api::nodes::service::workers::{dev_commands_allowed, OCKAM_ALLOW_DEV_COMMANDS};
+use ockam_api::nodes::BackgroundNodeClient;
+use ockam_core::api::Request;
+
+use crate::{docs, fmt_ok, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/send/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/send/after_long_help.txt");
+
+/// Time given to the node to handle the request, in addition to the time waiting for a reply
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default time to wait for a reply, when no `--timeout` is given
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Send a raw message to a worker of a node (developer command)
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct SendCommand {
+    /// Node running the worker
+    #[arg(value_name = "NODE_NAME", long, visible_alias = "node", value_parser = extract_address_value)]
+    at: Option<String>,
+
+    /// Local address of the worker
+    #[arg(long, value_name = "ADDRESS")]
+    to: String,
+
+    /// Payload of the message, hex encoded
+    #[arg(long, value_name = "BYTES")]
+    payload_hex: String,
+
+    /// Wait for a reply from the worker and print it
+    #[arg(long)]
+    expect_reply: bool,
+}
+
+#[async_trait]
+impl Command for SendCommand {
+    const NAME: &'static str = "worker send";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> miette::Result<()> {
+        if !dev_commands_allowed() {
+            return Err(miette!(
+                "This is a developer command, set {OCKAM_ALLOW_DEV_COMMANDS}=true to use it"
+            ));
+        }
+        let payload = hex::decode(&self.payload_hex)
+            .into_diagnostic()
+            .context("The payload is not a valid hex string")?;
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        node.require_capability(ctx, NodeCapability::INJECT_MESSAGE, "worker send")
+            .await?;
+
+        let mut inject_message = InjectMessage::new(&self.to, payload);
+        let mut request_timeout = REQUEST_TIMEOUT;
+        if self.expect_reply {
+            let reply_timeout = opts.global_args.timeout.unwrap_or(REPLY_TIMEOUT);
+            inject_message = inject_message.with_reply_timeout(reply_timeout);
+            request_timeout += reply_timeout;
+        }
+        let reply: InjectedMessageReply = node
+            .set_timeout(Some(request_timeout))
+            .ask(
+                ctx,
+                Request::post("/node/workers/message").body(inject_message),
+            )
+            .await?;
+
+        match reply.reply {
+            Some(reply) => {
+                let hex = hex::encode(&reply);
+                let utf8 = String::from_utf8(reply).ok();
+                opts.terminal
+                    .stdout()
+                    .plain(utf8.clone().unwrap_or_else(|| hex.clone()))
+                    .machine(&hex)
+                    .json(serde_json::json!({ "hex": hex, "utf8": utf8 }))
+                    .write_line()?;
+            }
+            None => {
+                opts.terminal
+                    .stdout()
+                    .plain(fmt_ok!("The message has been sent to {}", self.to))
+                    .json(serde_json::json!({ "to": self.to }))
+                    .write_line()?;
+            }
+        }
+        Ok(())
+    }
+}
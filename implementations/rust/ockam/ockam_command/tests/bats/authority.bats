@@ -185,6 +185,58 @@ EOF
   assert_output --partial "m3_member"
 }
 
+@test "authority - non-interactive enrollment with a ticket" {
+  port="$(random_port)"
+
+  run "$OCKAM" identity create authority
+  run "$OCKAM" identity create enroller
+  run "$OCKAM" identity create ci
+
+  enroller_identifier=$($OCKAM identity show enroller)
+  ci_identifier=$($OCKAM identity show ci)
+  authority_identity_full=$($OCKAM identity show --full --encoding hex authority)
+
+  # An enrollment ticket is required
+  run "$OCKAM" enroll --authorization-code-flow=off --identity ci
+  assert_failure
+  assert_output --partial "--token"
+
+  # Start the authority node.
+  trusted="{\"$enroller_identifier\": {\"ockam-role\": \"enroller\"}}"
+  run_success "$OCKAM" authority create --tcp-listener-address="127.0.0.1:$port" --project-identifier 1 --trusted-identities "$trusted"
+  sleep 1 # wait for authority to start TCP listener
+
+  cat <<EOF >>"$OCKAM_HOME/project.json"
+{
+  "id": "1",
+  "name": "default",
+  "space_name": "together-porgy",
+  "access_route": "/dnsaddr/127.0.0.1/tcp/4000/service/api",
+  "users": [],
+  "space_id": "1",
+  "identity": "I6c20e814b56579306f55c64e8747e6c1b4a53d9aa1b2c3d4e5f6a6b5c4d3e2f1",
+  "authority_access_route": "/dnsaddr/127.0.0.1/tcp/$port/service/api",
+  "authority_identity": "$authority_identity_full",
+  "version": "605c4632ded93eb17edeeef31fa3860db225b3ab-2023-12-05",
+  "running": false,
+  "operation_id": null,
+  "user_roles": []
+}
+EOF
+
+  run_success bash -c "$OCKAM project import --project-file $OCKAM_HOME/project.json"
+
+  # The enrollment doesn't prompt for anything and returns the enrolled identifier and project
+  token=$($OCKAM project ticket --identity enroller --attribute sample_attr=ci_member)
+  result=$($OCKAM enroll --authorization-code-flow=off --token "$token" --identity ci --output json)
+  assert_equal "$(echo "$result" | jq -r .identifier)" "$ci_identifier"
+  assert_equal "$(echo "$result" | jq -r .project_id)" "1"
+
+  # The identity is now a member of the project
+  run_success "$OCKAM" project enroll --identity ci
+  assert_output --partial "ci_member"
+}
+
 @test "local authority - test api commands" {
   port="$(random_port)"
 
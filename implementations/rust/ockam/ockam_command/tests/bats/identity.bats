@@ -24,6 +24,7 @@ teardown() {
   assert_output --partial "Change[0]:"
   assert_output --partial "Identifier: "
   assert_output --partial "primary_public_key: "
+  assert_output --partial "Purpose keys:"
 }
 
 @test "identity - CRUD" {
@@ -51,3 +51,23 @@ teardown() {
               | $OCKAM message send $msg --from /node/n1 --to -/service/echo"
   assert_output "$msg"
 }
+
+@test "worker - send a raw message to a local worker" {
+  # the developer command is refused by default
+  run_success "$OCKAM" node create n1
+  run_failure "$OCKAM" worker send --at n1 --to echo --payload-hex 68656c6c6f
+
+  # the node must also be started with the developer commands allowed
+  export OCKAM_ALLOW_DEV_COMMANDS=true
+  run_failure "$OCKAM" worker send --at n1 --to echo --payload-hex 68656c6c6f
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" worker send --at n2 --to echo --payload-hex 68656c6c6f --expect-reply --timeout 5s
+  assert_output "hello"
+  run_success "$OCKAM" worker send --at n2 --to echo --payload-hex 0102ff --expect-reply --output json
+  assert_output --partial "0102ff"
+  run_success "$OCKAM" worker send --at n2 --to echo --payload-hex 68656c6c6f
+
+  run_failure "$OCKAM" worker send --at n2 --to nonexistent --payload-hex 68656c6c6f
+  unset OCKAM_ALLOW_DEV_COMMANDS
+}
@@ -46,6 +46,16 @@ force_kill_node() {
   assert_output --partial "/service/uppercase"
 }
 
+@test "node - create with a listener on a port assigned by the OS" {
+  n="$(random_str)"
+  run_success "$OCKAM" node create "$n" --tcp-listener-address "127.0.0.1:0"
+  assert_output --partial "The node is listening at 127.0.0.1:"
+  refute_output --partial "127.0.0.1:0"
+
+  run_success "$OCKAM" node show "$n" --output json
+  refute_output --partial "/tcp/0/"
+}
+
 @test "node - start services" {
   run_success "$OCKAM" node create n1
 
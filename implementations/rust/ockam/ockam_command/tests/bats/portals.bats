@@ -155,6 +155,25 @@ teardown() {
   run_success curl --fail --head --max-time 10 "127.0.0.1:$port"
 }
 
+@test "portals - create an inlet on a port assigned by the OS" {
+  run_success "$OCKAM" node create n1
+  run_success "$OCKAM" node create n2
+
+  run_success "$OCKAM" tcp-outlet create --at /node/n1 --to 127.0.0.1:$PYTHON_SERVER_PORT
+  inlet="$($OCKAM tcp-inlet create --at /node/n2 --from "127.0.0.1:0" --to /node/n1/service/outlet --alias "assigned" --output json)"
+  bind_addr="$(echo "$inlet" | jq -r .bind_addr)"
+  port="${bind_addr##*:}"
+  assert [ "$port" != "0" ]
+
+  # the assigned address is reported by show and list
+  run_success "$OCKAM" tcp-inlet show assigned --at /node/n2 --output json
+  assert_output --partial "\"bind_addr\":\"$bind_addr\""
+  run_success "$OCKAM" tcp-inlet list --at /node/n2 --output json
+  assert_output --partial "\"bind_addr\":\"$bind_addr\""
+
+  run_success curl --fail --head --max-time 10 "127.0.0.1:$port"
+}
+
 @test "portals - create an inlet/outlet pair with relay through a relay and move tcp traffic through it" {
   port="$(random_port)"
   run_success "$OCKAM" node create relay
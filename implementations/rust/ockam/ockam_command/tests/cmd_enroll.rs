@@ -27,5 +27,36 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
     cmd.args(args);
     cmd.assert().success();
 
+    let args = [
+        "--test-argument-parser",
+        "enroll",
+        "--authorization-code-flow",
+    ];
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(args);
+    cmd.assert().success();
+
+    let enrollment_ticket = include_str!("./fixtures/user.enrollment.ticket").trim();
+    let args = [
+        "--test-argument-parser",
+        "enroll",
+        "--authorization-code-flow=off",
+        "--token",
+        enrollment_ticket,
+    ];
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(args);
+    cmd.assert().success();
+
+    // the flow can only be turned on or off
+    let args = [
+        "--test-argument-parser",
+        "enroll",
+        "--authorization-code-flow=maybe",
+    ];
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.args(args);
+    cmd.assert().failure();
+
     Ok(())
 }
@@ -0,0 +1,84 @@
+use assert_cmd::prelude::*;
+use std::process::Command;
+
+/// Create a command running in its own local state
+fn ockam_command(ockam_home: &tempfile::TempDir) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_HOME", ockam_home.path())
+        .env("OCKAM_DISABLE_UPGRADE_CHECK", "true")
+        .env("OCKAM_OPENTELEMETRY_EXPORT", "false")
+        .env("PAGER", "no-pager");
+    Ok(cmd)
+}
+
+#[test]
+fn show_identity_with_output_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let ockam_home = tempfile::tempdir()?;
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("identity").arg("create").arg("alice");
+    cmd.assert().success();
+
+    let show_identity =
+        |fields: &[&str]| -> Result<std::process::Output, Box<dyn std::error::Error>> {
+            let mut cmd = ockam_command(&ockam_home)?;
+            cmd.arg("identity").arg("show").arg("alice").arg("--full");
+            for field in fields {
+                cmd.arg("--output-field").arg(field);
+            }
+            Ok(cmd.output()?)
+        };
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("identity")
+        .arg("show")
+        .arg("alice")
+        .arg("--full")
+        .arg("--output")
+        .arg("json");
+    let identity: serde_json::Value = serde_json::from_slice(&cmd.output()?.stdout)?;
+
+    let output = show_identity(&["identifier"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?.trim(),
+        identity["identifier"].as_str().unwrap()
+    );
+
+    // nested field in an array element
+    let output = show_identity(&["changes[0].revoke_all_purpose_keys"])?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "false");
+
+    // the history of the change and the fingerprint of its key
+    let output = show_identity(&["changes[0].status"])?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "active");
+    let output = show_identity(&["changes[0].primary_public_key.fingerprint"])?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim().len(), 16);
+    assert!(identity["purpose_keys"].is_array());
+
+    // a key applied to an array is applied to each element
+    let output = show_identity(&["changes.identifier"])?;
+    assert_eq!(
+        String::from_utf8(output.stdout)?.trim(),
+        identity["changes"][0]["identifier"].as_str().unwrap()
+    );
+
+    // several fields
+    let output = show_identity(&["identifier", "changes.0.identifier"])?;
+    let projection: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        projection,
+        serde_json::json!({
+            "identifier": identity["identifier"],
+            "changes.0.identifier": identity["changes"][0]["identifier"],
+        })
+    );
+
+    // invalid paths
+    let output = show_identity(&["changes[3]"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("out of bounds"), "{stderr}");
+    assert!(stderr.contains("changes"), "{stderr}");
+
+    Ok(())
+}
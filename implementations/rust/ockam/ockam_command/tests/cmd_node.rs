@@ -19,5 +19,242 @@ fn valid_arguments() -> Result<(), Box<dyn std::error::Error>> {
         .arg("node-name");
     cmd.assert().success();
 
+    // check node success
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("check")
+        .arg("node-name")
+        .arg("--repair")
+        .arg("--yes");
+    cmd.assert().success();
+
+    // the confirmation flag is only valid for a repair
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.arg("--test-argument-parser")
+        .arg("node")
+        .arg("check")
+        .arg("node-name")
+        .arg("--yes");
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+/// Create a command running in its own local state
+fn ockam_command(ockam_home: &tempfile::TempDir) -> Result<Command, Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("ockam")?;
+    cmd.env("OCKAM_HOME", ockam_home.path())
+        .env("OCKAM_DISABLE_UPGRADE_CHECK", "true")
+        .env("OCKAM_OPENTELEMETRY_EXPORT", "false")
+        // Display parsing errors directly instead of using a pager
+        .env("PAGER", "no-pager");
+    Ok(cmd)
+}
+
+#[test]
+fn show_missing_node() -> Result<(), Box<dyn std::error::Error>> {
+    let ockam_home = tempfile::tempdir()?;
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node").arg("show").arg("missing-node");
+    cmd.assert().code(2);
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node")
+        .arg("show")
+        .arg("missing-node")
+        .arg("--output")
+        .arg("json");
+    let output = cmd.output()?;
+    assert_eq!(output.status.code(), Some(2));
+    let stderr = String::from_utf8(output.stderr)?;
+    let error: serde_json::Value = serde_json::from_str(stderr.lines().last().unwrap())?;
+    assert_eq!(error["code"], 2);
+    assert_eq!(error["error_kind"], "not_found");
+
+    Ok(())
+}
+
+#[test]
+fn invalid_multiaddr() -> Result<(), Box<dyn std::error::Error>> {
+    let ockam_home = tempfile::tempdir()?;
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("message")
+        .arg("send")
+        .arg("hello")
+        .arg("--to")
+        .arg("/unknown/protocol");
+    cmd.assert().code(5);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn ephemeral_node_leaves_no_residue() -> Result<(), Box<dyn std::error::Error>> {
+    use nix::sys::signal::{kill, Signal};
+    use nix::unistd::Pid;
+    use std::time::{Duration, Instant};
+
+    let ockam_home = tempfile::tempdir()?;
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node")
+        .arg("create")
+        .arg("ephemeral-node")
+        .arg("--foreground")
+        .arg("--ephemeral")
+        .arg("--tcp-listener-address")
+        .arg("127.0.0.1:0");
+    let mut child = cmd.spawn()?;
+
+    // wait for the node to be listed as a running ephemeral node
+    let list_nodes = || -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+        let mut cmd = ockam_command(&ockam_home)?;
+        cmd.arg("node").arg("list").arg("--output").arg("json");
+        let output = cmd.output()?;
+        Ok(serde_json::from_slice(&output.stdout).unwrap_or(serde_json::Value::Null))
+    };
+    let started = Instant::now();
+    loop {
+        let nodes = list_nodes()?;
+        if let Some(node) = nodes.as_array().and_then(|nodes| nodes.first()) {
+            assert_eq!(node["node_name"], "ephemeral-node");
+            assert_eq!(node["is_ephemeral"], true);
+            assert_eq!(node["is_default"], false);
+            break;
+        }
+        assert!(
+            started.elapsed() < Duration::from_secs(30),
+            "the ephemeral node was not started"
+        );
+        std::thread::sleep(Duration::from_millis(200));
+    }
+
+    // stop the node with a signal and check that its state has been removed
+    kill(Pid::from_raw(child.id() as i32), Signal::SIGTERM)?;
+    child.wait()?;
+
+    assert_eq!(list_nodes()?, serde_json::json!([]));
+    assert!(!ockam_home
+        .path()
+        .join("nodes")
+        .join("ephemeral-node")
+        .exists());
+
+    Ok(())
+}
+
+#[test]
+fn list_nodes_with_output_fields() -> Result<(), Box<dyn std::error::Error>> {
+    let ockam_home = tempfile::tempdir()?;
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node").arg("create").arg("n1");
+    cmd.assert().success();
+
+    let list_nodes = |fields: &[&str]| -> Result<std::process::Output, Box<dyn std::error::Error>> {
+        let mut cmd = ockam_command(&ockam_home)?;
+        cmd.arg("node").arg("list");
+        for field in fields {
+            cmd.arg("--output-field").arg(field);
+        }
+        Ok(cmd.output()?)
+    };
+
+    // a single field is displayed as plain lines
+    let output = list_nodes(&["node_name"])?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "n1");
+
+    // nested fields can be selected in a list item
+    let output = list_nodes(&["[0].status.status"])?;
+    assert_eq!(String::from_utf8(output.stdout)?.trim(), "running");
+
+    // several fields are displayed as a JSON object
+    let output = list_nodes(&["node_name", "[0].is_default"])?;
+    let projection: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(
+        projection,
+        serde_json::json!({ "node_name": ["n1"], "[0].is_default": true })
+    );
+
+    // an invalid path lists the available keys
+    let output = list_nodes(&["unknown"])?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("node_name"), "{stderr}");
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node").arg("delete").arg("--all").arg("--yes");
+    cmd.assert().success();
+
+    Ok(())
+}
+
+#[test]
+fn requests_to_a_non_responding_node_time_out() -> Result<(), Box<dyn std::error::Error>> {
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    let ockam_home = tempfile::tempdir()?;
+
+    // create a node and stop it, keeping its address in the local state
+    let address = TcpListener::bind("127.0.0.1:0")?.local_addr()?;
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node")
+        .arg("create")
+        .arg("n1")
+        .arg("--tcp-listener-address")
+        .arg(address.to_string());
+    cmd.assert().success();
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node").arg("stop").arg("n1");
+    cmd.assert().success();
+
+    // replace the node with a stub accepting connections but never answering
+    let stub = TcpListener::bind(address)?;
+    std::thread::spawn(move || {
+        let mut connections = vec![];
+        for connection in stub.incoming() {
+            connections.push(connection);
+        }
+    });
+
+    let started = Instant::now();
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("--timeout")
+        .arg("1s")
+        .arg("worker")
+        .arg("list")
+        .arg("--at")
+        .arg("n1");
+    cmd.assert().failure();
+    // the default timeout of the requests to a node is 30 seconds
+    assert!(
+        started.elapsed() < Duration::from_secs(15),
+        "the request did not time out after 1s: {:?}",
+        started.elapsed()
+    );
+
+    // an invalid timeout is rejected
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("worker")
+        .arg("list")
+        .arg("--at")
+        .arg("n1")
+        .arg("--timeout")
+        .arg("1.5s");
+    let output = cmd.output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr)?;
+    assert!(stderr.contains("Invalid duration '1.5s'"), "{stderr}");
+
+    let mut cmd = ockam_command(&ockam_home)?;
+    cmd.arg("node").arg("delete").arg("--all").arg("--yes");
+    cmd.assert().success();
+
     Ok(())
 }
@@ -1,6 +1,8 @@
 use crate::compat::boxed::Box;
+use crate::compat::string::String;
 use crate::{async_trait, RelayMessage, Result};
-use core::fmt::Debug;
+use core::fmt::{self, Debug, Display, Formatter};
+use minicbor::{Decode, Encode};
 
 /// Defines the interface for incoming message flow authorization.
 ///
@@ -31,6 +33,65 @@ pub trait IncomingAccessControl: Debug + Send + Sync + 'static {
     // TODO: Consider &mut self
     /// Return true if the message is allowed to pass, and false if not.
     async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool>;
+
+    /// Explain why a message is not allowed to pass.
+    ///
+    /// This function is only called for a denied message whose sender asked to be notified
+    /// of the denial, see [`crate::api::RequestHeader::notify_on_deny`].
+    /// It must return `None` if this access control allows the message, and must not have
+    /// side effects, like consuming a rate limit, since the message was already evaluated.
+    /// The reason is sent to the sender, so it must not disclose the policy itself.
+    /// By default, no reason is given.
+    async fn deny_reason(&self, _relay_msg: &RelayMessage) -> Option<DenyReason> {
+        None
+    }
+}
+
+/// Reason of the denial of a message by an [`IncomingAccessControl`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct DenyReason {
+    /// Identifier of the policy which was evaluated, for example its resource and action.
+    /// The policy expression itself is never disclosed
+    #[n(1)] pub policy: Option<String>,
+    /// Attribute used by the policy but missing from the attributes of the sender
+    #[n(2)] pub missing_attribute: Option<String>,
+}
+
+impl DenyReason {
+    /// Create a reason for the denial of a message by the policy with the given identifier
+    pub fn policy(policy: impl Into<String>) -> Self {
+        Self {
+            policy: Some(policy.into()),
+            missing_attribute: None,
+        }
+    }
+
+    /// Set the identifier of the policy which denied the message
+    pub fn with_policy(mut self, policy: impl Into<String>) -> Self {
+        self.policy = Some(policy.into());
+        self
+    }
+
+    /// Add the attribute missing from the attributes of the sender
+    pub fn with_missing_attribute(mut self, missing_attribute: impl Into<String>) -> Self {
+        self.missing_attribute = Some(missing_attribute.into());
+        self
+    }
+}
+
+impl Display for DenyReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (&self.policy, &self.missing_attribute) {
+            (Some(policy), Some(missing)) => {
+                write!(f, "the policy {policy} requires the attribute {missing}")
+            }
+            (Some(policy), None) => write!(f, "the policy {policy} is not satisfied"),
+            (None, Some(missing)) => write!(f, "the attribute {missing} is missing"),
+            (None, None) => write!(f, "no reason given"),
+        }
+    }
 }
 
 /// Defines the interface for outgoing message flow authorization.
@@ -71,6 +132,8 @@ mod any;
 mod cache;
 mod deny_all;
 mod onward;
+#[cfg(feature = "std")]
+mod rate_limiting;
 mod source;
 
 pub use all::*;
@@ -80,4 +143,6 @@ pub use any::*;
 pub use cache::*;
 pub use deny_all::*;
 pub use onward::*;
+#[cfg(feature = "std")]
+pub use rate_limiting::*;
 pub use source::*;
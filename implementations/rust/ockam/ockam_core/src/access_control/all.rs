@@ -1,4 +1,4 @@
-use crate::access_control::{IncomingAccessControl, OutgoingAccessControl};
+use crate::access_control::{DenyReason, IncomingAccessControl, OutgoingAccessControl};
 use crate::compat::sync::Arc;
 use crate::compat::vec::Vec;
 use crate::{async_trait, compat::boxed::Box, RelayMessage, Result};
@@ -25,6 +25,17 @@ impl IncomingAccessControl for AllIncomingAccessControl {
 
         crate::allow()
     }
+
+    /// Return the reason given by the first access control denying the message.
+    /// The access controls are not evaluated again since they can have side effects
+    async fn deny_reason(&self, relay_msg: &RelayMessage) -> Option<DenyReason> {
+        for ac in &self.0 {
+            if let Some(reason) = ac.deny_reason(relay_msg).await {
+                return Some(reason);
+            }
+        }
+        None
+    }
 }
 
 /// Allows message that are allowed by all [`OutgoingAccessControl`]s
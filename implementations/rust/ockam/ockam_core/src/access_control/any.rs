@@ -1,4 +1,4 @@
-use crate::access_control::{IncomingAccessControl, OutgoingAccessControl};
+use crate::access_control::{DenyReason, IncomingAccessControl, OutgoingAccessControl};
 use crate::compat::sync::Arc;
 use crate::compat::vec::Vec;
 use crate::{async_trait, compat::boxed::Box, RelayMessage, Result};
@@ -25,6 +25,16 @@ impl IncomingAccessControl for AnyIncomingAccessControl {
 
         crate::deny()
     }
+
+    /// Return the first reason given by the access controls, which all denied the message
+    async fn deny_reason(&self, relay_msg: &RelayMessage) -> Option<DenyReason> {
+        for ac in &self.0 {
+            if let Some(reason) = ac.deny_reason(relay_msg).await {
+                return Some(reason);
+            }
+        }
+        None
+    }
 }
 
 /// Allows message that are allowed by any of [`OutgoingAccessControl`]s
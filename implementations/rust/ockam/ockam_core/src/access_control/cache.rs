@@ -1,6 +1,7 @@
 use crate::compat::sync::Mutex;
 use crate::{
-    Address, IncomingAccessControl, LocalInfo, OutgoingAccessControl, RelayMessage, Route,
+    Address, DenyReason, IncomingAccessControl, LocalInfo, OutgoingAccessControl, RelayMessage,
+    Route,
 };
 use alloc::vec::Vec;
 use async_trait::async_trait;
@@ -127,6 +128,10 @@ impl IncomingAccessControl for CachedIncomingAccessControl {
             crate::deny()
         }
     }
+
+    async fn deny_reason(&self, relay_msg: &RelayMessage) -> Option<DenyReason> {
+        self.access_control.deny_reason(relay_msg).await
+    }
 }
 
 /// A wrapper for an outgoing access control that caches successful authorizations.
@@ -0,0 +1,328 @@
+use crate::compat::boxed::Box;
+use crate::compat::collections::{BTreeMap, VecDeque};
+use crate::compat::string::{String, ToString};
+use crate::compat::sync::{Arc, Mutex};
+use crate::compat::vec::Vec;
+use crate::{async_trait, Address, IncomingAccessControl, RelayMessage, Result};
+use core::fmt::{Debug, Formatter};
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+use minicbor::{Decode, Encode};
+use std::time::Instant;
+
+/// Above this number of tracked senders, the senders without any message in the current
+/// window are forgotten
+const MAX_TRACKED_SENDERS: usize = 1024;
+
+/// Maximum number of messages accepted from a given sender over a sliding window
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RateLimit {
+    #[n(1)] max_messages: u32,
+    #[n(2)] window: Duration,
+}
+
+impl RateLimit {
+    /// Accept at most `max_messages` messages from a sender over any period of time of length `window`
+    pub fn new(max_messages: u32, window: Duration) -> Self {
+        Self {
+            max_messages,
+            window,
+        }
+    }
+
+    /// Maximum number of messages accepted over the window
+    pub fn max_messages(&self) -> u32 {
+        self.max_messages
+    }
+
+    /// Length of the sliding window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+}
+
+/// Sender of a message, as identified by the rate limiting access control
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Sender {
+    /// The sender is identified by the data of a `LocalInfo` added to the message,
+    /// for example the identifier of the other side of a secure channel
+    LocalInfo(Vec<u8>),
+    /// The sender is identified by the source address of the message
+    Address(Address),
+}
+
+/// A wrapper for an incoming access control that denies messages from a sender once it has sent
+/// more messages than allowed by a [`RateLimit`] over a sliding window.
+///
+/// Senders are identified by the data of a `LocalInfo` with a configurable type identifier when
+/// that `LocalInfo` is present on the message (for example the identifier of the other side of
+/// a secure channel) and by the message source address otherwise.
+///
+/// The rate is checked before the wrapped access control, so that messages from an abusive
+/// sender are denied without being evaluated further. Every denied message because of the rate
+/// increments a counter which can be retrieved for diagnostics.
+pub struct RateLimitingAccessControl {
+    access_control: Arc<dyn IncomingAccessControl>,
+    rate_limit: RateLimit,
+    sender_local_info_type: Option<String>,
+    received: Mutex<BTreeMap<Sender, VecDeque<Instant>>>,
+    denied_messages_count: AtomicU64,
+}
+
+impl Debug for RateLimitingAccessControl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("RateLimitingAccessControl")
+            .field("access_control", &self.access_control)
+            .field("rate_limit", &self.rate_limit)
+            .field("sender_local_info_type", &self.sender_local_info_type)
+            .field("denied_messages_count", &self.denied_messages_count())
+            .finish()
+    }
+}
+
+impl RateLimitingAccessControl {
+    /// Wrap an incoming access control with a rate limit
+    pub fn new(access_control: Arc<dyn IncomingAccessControl>, rate_limit: RateLimit) -> Self {
+        Self {
+            access_control,
+            rate_limit,
+            sender_local_info_type: None,
+            received: Mutex::new(BTreeMap::new()),
+            denied_messages_count: AtomicU64::new(0),
+        }
+    }
+
+    /// Identify senders with the data of the `LocalInfo` having this type identifier,
+    /// when present on a message
+    pub fn with_sender_local_info(mut self, type_identifier: &str) -> Self {
+        self.sender_local_info_type = Some(type_identifier.to_string());
+        self
+    }
+
+    /// Rate limit applied to each sender
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limit
+    }
+
+    /// Number of messages denied so far because their sender exceeded the rate limit
+    pub fn denied_messages_count(&self) -> u64 {
+        self.denied_messages_count.load(Ordering::Relaxed)
+    }
+
+    fn sender(&self, relay_msg: &RelayMessage) -> Sender {
+        if let Some(type_identifier) = &self.sender_local_info_type {
+            if let Some(local_info) = relay_msg
+                .local_message()
+                .local_info_ref()
+                .iter()
+                .find(|local_info| local_info.type_identifier() == type_identifier)
+            {
+                return Sender::LocalInfo(local_info.data().to_vec());
+            }
+        }
+        Sender::Address(relay_msg.source().clone())
+    }
+
+    /// Record a message from the sender at the given time and return true
+    /// if it is within the rate limit
+    fn accept(&self, sender: Sender, now: Instant) -> bool {
+        let window = self.rate_limit.window;
+        let is_in_window = |received_at: &Instant| now.duration_since(*received_at) < window;
+
+        let mut received = self.received.lock().unwrap();
+        if received.len() > MAX_TRACKED_SENDERS {
+            received.retain(|_, timestamps| timestamps.iter().any(is_in_window));
+        }
+
+        let timestamps = received.entry(sender).or_default();
+        while let Some(oldest) = timestamps.front() {
+            if is_in_window(oldest) {
+                break;
+            }
+            timestamps.pop_front();
+        }
+
+        if timestamps.len() >= self.rate_limit.max_messages as usize {
+            self.denied_messages_count.fetch_add(1, Ordering::Relaxed);
+            false
+        } else {
+            timestamps.push_back(now);
+            true
+        }
+    }
+}
+
+#[async_trait]
+impl IncomingAccessControl for RateLimitingAccessControl {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        let sender = self.sender(relay_msg);
+        if !self.accept(sender, Instant::now()) {
+            warn!(
+                "the sender of a message to {} exceeded the rate limit of {} messages per {:?}",
+                relay_msg.destination(),
+                self.rate_limit.max_messages,
+                self.rate_limit.window
+            );
+            return crate::deny();
+        }
+        self.access_control.is_authorized(relay_msg).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, AllowAll, DenyAll, LocalInfo, LocalMessage};
+
+    const IDENTIFIER_TYPE: &str = "IDENTIFIER";
+
+    #[tokio::test]
+    async fn test_burst_over_and_under_the_limit() -> Result<()> {
+        let access_control = RateLimitingAccessControl::new(
+            Arc::new(AllowAll),
+            RateLimit::new(3, Duration::from_millis(300)),
+        );
+        let source = Address::random_local();
+
+        // a burst under the limit is accepted
+        for _ in 0..3 {
+            assert!(
+                access_control
+                    .is_authorized(&message(&source, None))
+                    .await?
+            );
+        }
+        assert_eq!(access_control.denied_messages_count(), 0);
+
+        // a burst over the limit is denied
+        for _ in 0..2 {
+            assert!(
+                !access_control
+                    .is_authorized(&message(&source, None))
+                    .await?
+            );
+        }
+        assert_eq!(access_control.denied_messages_count(), 2);
+
+        // another sender is not affected
+        let other_source = Address::random_local();
+        assert!(
+            access_control
+                .is_authorized(&message(&other_source, None))
+                .await?
+        );
+
+        // once the window has passed, messages are accepted again
+        tokio::time::sleep(Duration::from_millis(350)).await;
+        for _ in 0..3 {
+            assert!(
+                access_control
+                    .is_authorized(&message(&source, None))
+                    .await?
+            );
+        }
+        assert!(
+            !access_control
+                .is_authorized(&message(&source, None))
+                .await?
+        );
+        assert_eq!(access_control.denied_messages_count(), 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let access_control = RateLimitingAccessControl::new(
+            Arc::new(AllowAll),
+            RateLimit::new(2, Duration::from_secs(10)),
+        );
+        let sender = Sender::Address(Address::random_local());
+        let start = Instant::now();
+
+        assert!(access_control.accept(sender.clone(), start));
+        assert!(access_control.accept(sender.clone(), start + Duration::from_secs(6)));
+        assert!(!access_control.accept(sender.clone(), start + Duration::from_secs(9)));
+        // the first message leaves the window but not the second one
+        assert!(access_control.accept(sender.clone(), start + Duration::from_secs(10)));
+        assert!(!access_control.accept(sender.clone(), start + Duration::from_secs(15)));
+        assert!(access_control.accept(sender, start + Duration::from_secs(16)));
+        assert_eq!(access_control.denied_messages_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_senders_identified_by_local_info() -> Result<()> {
+        let access_control = RateLimitingAccessControl::new(
+            Arc::new(AllowAll),
+            RateLimit::new(1, Duration::from_secs(60)),
+        )
+        .with_sender_local_info(IDENTIFIER_TYPE);
+
+        // the same sender using different source addresses is limited
+        let alice = LocalInfo::new(IDENTIFIER_TYPE.into(), b"alice".to_vec());
+        assert!(
+            access_control
+                .is_authorized(&message(&Address::random_local(), Some(alice.clone())))
+                .await?
+        );
+        assert!(
+            !access_control
+                .is_authorized(&message(&Address::random_local(), Some(alice)))
+                .await?
+        );
+
+        // different senders using the same source address are not
+        let source = Address::random_local();
+        let bob = LocalInfo::new(IDENTIFIER_TYPE.into(), b"bob".to_vec());
+        assert!(
+            access_control
+                .is_authorized(&message(&source, Some(bob)))
+                .await?
+        );
+        assert!(
+            access_control
+                .is_authorized(&message(&source, None))
+                .await?
+        );
+        assert_eq!(access_control.denied_messages_count(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_composed_with_another_access_control() -> Result<()> {
+        let access_control = RateLimitingAccessControl::new(
+            Arc::new(DenyAll),
+            RateLimit::new(1, Duration::from_secs(60)),
+        );
+        let source = Address::random_local();
+
+        // the wrapped access control still denies messages within the rate limit
+        assert!(
+            !access_control
+                .is_authorized(&message(&source, None))
+                .await?
+        );
+        assert_eq!(access_control.denied_messages_count(), 0);
+
+        // but messages are counted against the rate limit before being evaluated
+        assert!(
+            !access_control
+                .is_authorized(&message(&source, None))
+                .await?
+        );
+        assert_eq!(access_control.denied_messages_count(), 1);
+        Ok(())
+    }
+
+    fn message(source: &Address, local_info: Option<LocalInfo>) -> RelayMessage {
+        RelayMessage::new(
+            source.clone(),
+            Address::random_local(),
+            LocalMessage::new()
+                .with_onward_route(route!["service"])
+                .with_return_route(route![source.clone()])
+                .with_local_info(local_info.into_iter().collect()),
+        )
+    }
+}
@@ -15,6 +15,8 @@ use crate::compat::rand;
 use crate::compat::string::String;
 use crate::compat::vec::Vec;
 use crate::errcode::{Kind, Origin};
+#[cfg(feature = "routing-full")]
+use crate::DenyReason;
 use crate::Result;
 
 /// A request header.
@@ -34,6 +36,9 @@ pub struct RequestHeader {
     #[n(3)] method: Option<Method>,
     /// Indicator if a request body is expected after this header.
     #[n(4)] has_body: bool,
+    /// Indicator if the sender expects an access denied response when the request
+    /// is denied by the access control of its destination, instead of no response.
+    #[n(5)] notify_on_deny: Option<bool>,
 }
 
 impl RequestHeader {
@@ -43,6 +48,7 @@ impl RequestHeader {
             method: Some(method),
             path: path.into(),
             has_body,
+            notify_on_deny: None,
         }
     }
 
@@ -161,11 +167,7 @@ impl<T> Reply<T> {
     pub fn success(self) -> Result<T> {
         match self {
             Reply::Successful(t) => Ok(t),
-            Reply::Failed(e, _) => Err(crate::Error::new(
-                Origin::Api,
-                Kind::Invalid,
-                e.message().unwrap_or("no message defined for this error"),
-            )),
+            Reply::Failed(e, status) => Err(Self::failure_error(e, status)),
         }
     }
 
@@ -186,6 +188,21 @@ impl<T> Reply<T> {
         }
     }
 
+    /// Return the error corresponding to a failed request.
+    /// A request which was forbidden is reported as an authorization error
+    #[track_caller]
+    fn failure_error(e: Error, status: Option<Status>) -> crate::Error {
+        let origin = match status {
+            Some(Status::Forbidden) => Origin::Authorization,
+            _ => Origin::Api,
+        };
+        crate::Error::new(
+            origin,
+            Kind::Invalid,
+            e.message().unwrap_or("no message defined for this error"),
+        )
+    }
+
     /// Return the value T as an option if it has been found .
     /// Any failure indicated by a non-OK or not-NotFound status is interpreted as an error
     #[track_caller]
@@ -193,11 +210,7 @@ impl<T> Reply<T> {
         match self {
             Reply::Successful(t) => Ok(Some(t)),
             Reply::Failed(_, Some(Status::NotFound)) => Ok(None),
-            Reply::Failed(e, _) => Err(crate::Error::new(
-                Origin::Api,
-                Kind::Invalid,
-                e.message().unwrap_or("no message defined for this error"),
-            )),
+            Reply::Failed(e, status) => Err(Self::failure_error(e, status)),
         }
     }
 }
@@ -208,7 +221,7 @@ impl<T> Reply<T> {
 pub struct Id(#[n(0)] u32);
 
 /// Request methods.
-#[derive(Debug, Copy, Clone, Encode, Decode)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
 #[rustfmt::skip]
 #[cbor(index_only)]
 pub enum Method {
@@ -244,6 +257,8 @@ pub enum Status {
     #[n(404)] NotFound,
     #[n(409)] Conflict,
     #[n(405)] MethodNotAllowed,
+    /// The request was cancelled before it could complete
+    #[n(499)] Cancelled,
     #[n(500)] InternalServerError,
     #[n(501)] NotImplemented,
 }
@@ -258,6 +273,7 @@ impl Display for Status {
             Status::NotFound => "404 NotFound",
             Status::Conflict => "409 Conflict",
             Status::MethodNotAllowed => "405 MethodNotAllowed",
+            Status::Cancelled => "499 Cancelled",
             Status::InternalServerError => "500 InternalServerError",
             Status::NotImplemented => "501 NotImplemented",
         })
@@ -277,6 +293,21 @@ impl From<Id> for u32 {
     }
 }
 
+/// Parse an identifier displayed as 8 hexadecimal characters
+impl core::str::FromStr for Id {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        u32::from_str_radix(s, 16).map(Id).map_err(|_| {
+            crate::Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!("invalid request identifier {s}"),
+            )
+        })
+    }
+}
+
 impl Display for Id {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:08x}", self.0)
@@ -303,6 +334,11 @@ impl RequestHeader {
     pub fn has_body(&self) -> bool {
         self.has_body
     }
+
+    /// Return true if the sender must be notified when the request is denied by an access control
+    pub fn notify_on_deny(&self) -> bool {
+        self.notify_on_deny.unwrap_or(false)
+    }
 }
 
 impl ResponseHeader {
@@ -536,6 +572,12 @@ impl<T> Request<T> {
         self
     }
 
+    /// Ask for an access denied response if the request is denied by an access control
+    pub fn notify_on_deny(mut self) -> Self {
+        self.header.notify_on_deny = Some(true);
+        self
+    }
+
     pub fn header(&self) -> &RequestHeader {
         &self.header
     }
@@ -712,6 +754,21 @@ impl Response {
         Response::builder(r.id(), Status::Forbidden).body(e)
     }
 
+    /// Create an error response for a request which was cancelled before it could complete.
+    pub fn cancelled(r: &RequestHeader) -> Response<Error> {
+        Self::error(r, "the request was cancelled", Status::Cancelled)
+    }
+
+    /// Create an error response for a request denied by an access control.
+    #[cfg(feature = "routing-full")]
+    pub fn access_denied(r: &RequestHeader, reason: Option<&DenyReason>) -> Response<Error> {
+        let message = match reason {
+            Some(reason) => format!("access denied: {reason}"),
+            None => "access denied".to_string(),
+        };
+        Self::forbidden(r, &message)
+    }
+
     pub fn internal_error_no_request(msg: &str) -> Response<Error> {
         let e = Error::new_without_path().with_message(msg);
         Response::builder(Id::default(), Status::InternalServerError).body(e)
@@ -931,6 +988,7 @@ mod tests {
         Status::BadRequest,
         Status::NotFound,
         Status::MethodNotAllowed,
+        Status::Cancelled,
         Status::InternalServerError,
         Status::NotImplemented,
     ];
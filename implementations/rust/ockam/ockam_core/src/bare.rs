@@ -1,19 +1,53 @@
 //! Primitives to encode and decode Binary Application Record Encoding (BARE).
 //!
 //! These primitives are used to encode and decode most performance-sensitive messages
-//! in Ockam without using `std`, and not are not meant to support all possible use cases.
+//! in Ockam without using `std`, and are not meant to support all possible use cases.
+//! They can be reused by custom message types, for example in third-party transports,
+//! in order to stay compatible with the encoding of [`TransportMessage`](crate::TransportMessage).
+//!
+//! The following forms are supported:
+//!
+//!  - fixed width integers (`u8`, `u16`, `u32`, `u64`), encoded in little-endian order
+//!  - variable length integers, encoded as ULEB128
+//!  - fixed length data, encoded as is
+//!  - variable length data and strings, prefixed by their length as a variable length integer
+//!
+//! Every `write_*` function appends the encoded value to a buffer, and has a `size_of_*`
+//! counterpart returning the number of bytes which will be written.
+//!
+//! Every `read_*` function reads a value from a slice starting at a given index and advances that
+//! index past the value. The reads are bounds-checked: they return `None`, and never panic,
+//! when the slice is too short or the index is out of the slice. In that case the index is left
+//! unchanged. Use [`read_prefix`] to read a value at the beginning of a slice and get the number
+//! of bytes which were consumed.
+//!
+//! This module is not dependent on std or any other crate.
 
-/// Defines a set of functions to encode and decode bare encoding
-///
-/// This module is not dependent on std or any other crate
 use crate::compat::vec::Vec;
 
-/// Read a dynamically sized slize from the given cursor
-pub fn read_slice<'de>(slice: &'de [u8], index: &mut usize) -> Option<&'de [u8]> {
-    let length: usize = read_variable_length_integer(slice, index)?
-        .try_into()
-        .ok()?;
-    if slice.len() - *index >= length {
+/// Read a value at the beginning of the given slice with one of the `read_*` functions.
+/// Return the value and the number of bytes which were consumed.
+pub fn read_prefix<'de, T>(
+    slice: &'de [u8],
+    read: impl FnOnce(&'de [u8], &mut usize) -> Option<T>,
+) -> Option<(T, usize)> {
+    let mut index = 0;
+    let value = read(slice, &mut index)?;
+    Some((value, index))
+}
+
+/// Return the number of bytes left in the slice after the given index
+fn remaining(slice: &[u8], index: usize) -> Option<usize> {
+    slice.len().checked_sub(index)
+}
+
+/// Read data with a known length from the given cursor
+pub fn read_fixed_slice<'de>(
+    slice: &'de [u8],
+    index: &mut usize,
+    length: usize,
+) -> Option<&'de [u8]> {
+    if remaining(slice, *index)? >= length {
         let result = &slice[*index..(*index + length)];
         *index += length;
         Some(result)
@@ -22,6 +56,68 @@ pub fn read_slice<'de>(slice: &'de [u8], index: &mut usize) -> Option<&'de [u8]>
     }
 }
 
+/// Write data with a known length to the given buffer.
+/// The length is not written, it must be known when the data is read.
+pub fn write_fixed_slice(destination: &mut Vec<u8>, buffer: &[u8]) {
+    destination.extend_from_slice(buffer);
+}
+
+/// Read a `u8` from the given cursor
+pub fn read_u8(slice: &[u8], index: &mut usize) -> Option<u8> {
+    let byte = *slice.get(*index)?;
+    *index += 1;
+    Some(byte)
+}
+
+/// Write a `u8` to the given buffer
+pub fn write_u8(destination: &mut Vec<u8>, value: u8) {
+    destination.push(value);
+}
+
+/// Read a little-endian `u16` from the given cursor
+pub fn read_u16(slice: &[u8], index: &mut usize) -> Option<u16> {
+    let bytes = read_fixed_slice(slice, index, 2)?;
+    Some(u16::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Write a `u16` to the given buffer, in little-endian order
+pub fn write_u16(destination: &mut Vec<u8>, value: u16) {
+    destination.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Read a little-endian `u32` from the given cursor
+pub fn read_u32(slice: &[u8], index: &mut usize) -> Option<u32> {
+    let bytes = read_fixed_slice(slice, index, 4)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Write a `u32` to the given buffer, in little-endian order
+pub fn write_u32(destination: &mut Vec<u8>, value: u32) {
+    destination.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Read a little-endian `u64` from the given cursor
+pub fn read_u64(slice: &[u8], index: &mut usize) -> Option<u64> {
+    let bytes = read_fixed_slice(slice, index, 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Write a `u64` to the given buffer, in little-endian order
+pub fn write_u64(destination: &mut Vec<u8>, value: u64) {
+    destination.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Read a dynamically sized slice from the given cursor
+pub fn read_slice<'de>(slice: &'de [u8], index: &mut usize) -> Option<&'de [u8]> {
+    let mut cursor = *index;
+    let length: usize = read_variable_length_integer(slice, &mut cursor)?
+        .try_into()
+        .ok()?;
+    let result = read_fixed_slice(slice, &mut cursor, length)?;
+    *index = cursor;
+    Some(result)
+}
+
 /// Returns the size of the encoded slice in bytes
 pub fn size_of_slice(slice: &[u8]) -> usize {
     size_of_variable_length(slice.len() as u64) + slice.len()
@@ -35,8 +131,11 @@ pub fn write_slice(destination: &mut Vec<u8>, buffer: &[u8]) {
 
 /// Reads a string from the given cursor
 pub fn read_str<'de>(slice: &'de [u8], index: &mut usize) -> Option<&'de str> {
-    let buffer = read_slice(slice, index)?;
-    core::str::from_utf8(buffer).ok()
+    let mut cursor = *index;
+    let buffer = read_slice(slice, &mut cursor)?;
+    let result = core::str::from_utf8(buffer).ok()?;
+    *index = cursor;
+    Some(result)
 }
 
 /// Writes a string to the given buffer
@@ -44,6 +143,11 @@ pub fn write_str(destination: &mut Vec<u8>, string: &str) {
     write_slice(destination, string.as_bytes());
 }
 
+/// Returns the size of the encoded string in bytes
+pub fn size_of_str(string: &str) -> usize {
+    size_of_slice(string.as_bytes())
+}
+
 /// Returns the size in bytes of the given variable length integer
 pub fn size_of_variable_length(value: u64) -> usize {
     let mut result = 0;
@@ -61,11 +165,12 @@ pub fn size_of_variable_length(value: u64) -> usize {
 /// Read a variable length integer from the given cursor (ULEB128)
 /// returns None if the buffer is too short
 pub fn read_variable_length_integer(slice: &[u8], index: &mut usize) -> Option<u64> {
+    let mut cursor = *index;
     let mut result = 0;
     let mut shift = 0;
     loop {
-        let byte = slice.get(*index)?;
-        *index += 1;
+        let byte = slice.get(cursor)?;
+        cursor += 1;
 
         let current = ((byte & 0b0111_1111) as u64) << shift;
         if shift == 63 && *byte != 0b0000_0001 {
@@ -78,6 +183,7 @@ pub fn read_variable_length_integer(slice: &[u8], index: &mut usize) -> Option<u
         shift += 7;
     }
 
+    *index = cursor;
     Some(result)
 }
 
@@ -98,6 +204,7 @@ pub fn write_variable_length_integer(destination: &mut Vec<u8>, mut value: u64)
 #[cfg(test)]
 mod test {
     use crate::bare::read_variable_length_integer;
+    use proptest::prelude::*;
     use rand::rngs::StdRng;
     use rand::{Rng, SeedableRng};
 
@@ -241,4 +348,113 @@ mod test {
             assert_eq!(value.as_slice(), result, "seed: {seed}");
         }
     }
+
+    #[test]
+    fn test_fixed_width_integers() {
+        let mut buffer = Vec::new();
+        super::write_u8(&mut buffer, 0xAB);
+        super::write_u16(&mut buffer, 0x0102);
+        super::write_u32(&mut buffer, 0x0102_0304);
+        super::write_u64(&mut buffer, 0x0102_0304_0506_0708);
+        assert_eq!(
+            buffer,
+            vec![
+                0xAB, 0x02, 0x01, 0x04, 0x03, 0x02, 0x01, 0x08, 0x07, 0x06, 0x05, 0x04, 0x03, 0x02,
+                0x01
+            ]
+        );
+
+        let index = &mut 0;
+        assert_eq!(super::read_u8(&buffer, index), Some(0xAB));
+        assert_eq!(super::read_u16(&buffer, index), Some(0x0102));
+        assert_eq!(super::read_u32(&buffer, index), Some(0x0102_0304));
+        assert_eq!(super::read_u64(&buffer, index), Some(0x0102_0304_0506_0708));
+        assert_eq!(*index, buffer.len());
+    }
+
+    #[test]
+    fn reads_out_of_bounds_return_none() {
+        let mut buffer = Vec::new();
+        super::write_slice(&mut buffer, b"hello");
+
+        // the index is past the end of the slice
+        let mut index = buffer.len() + 1;
+        assert_eq!(super::read_slice(&buffer, &mut index), None);
+        assert_eq!(super::read_u8(&buffer, &mut index), None);
+        assert_eq!(super::read_u64(&buffer, &mut index), None);
+        assert_eq!(read_variable_length_integer(&buffer, &mut index), None);
+        assert_eq!(index, buffer.len() + 1);
+
+        // the slice is truncated
+        let truncated = &buffer[..buffer.len() - 1];
+        let mut index = 0;
+        assert_eq!(super::read_slice(truncated, &mut index), None);
+        assert_eq!(super::read_u32(truncated, &mut index), Some(0x6C65_6805));
+        assert_eq!(super::read_u16(truncated, &mut index), None);
+        assert_eq!(index, 4);
+    }
+
+    #[test]
+    fn read_prefix_returns_the_consumed_bytes() {
+        let mut buffer = Vec::new();
+        super::write_str(&mut buffer, "hello");
+        buffer.extend_from_slice(b"trailing bytes");
+
+        let (value, consumed) = super::read_prefix(&buffer, super::read_str).unwrap();
+        assert_eq!(value, "hello");
+        assert_eq!(consumed, super::size_of_str("hello"));
+        assert_eq!(consumed, 6);
+    }
+
+    proptest! {
+        #[test]
+        fn test_variable_length_round_trip(value in any::<u64>()) {
+            let mut buffer = Vec::new();
+            super::write_variable_length_integer(&mut buffer, value);
+            prop_assert_eq!(buffer.len(), super::size_of_variable_length(value));
+            let decoded = super::read_prefix(&buffer, read_variable_length_integer);
+            prop_assert_eq!(decoded, Some((value, buffer.len())));
+        }
+
+        #[test]
+        fn test_fixed_width_round_trip(a in any::<u8>(), b in any::<u16>(), c in any::<u32>(), d in any::<u64>()) {
+            let mut buffer = Vec::new();
+            super::write_u8(&mut buffer, a);
+            super::write_u16(&mut buffer, b);
+            super::write_u32(&mut buffer, c);
+            super::write_u64(&mut buffer, d);
+            prop_assert_eq!(buffer.len(), 15);
+
+            let index = &mut 0;
+            prop_assert_eq!(super::read_u8(&buffer, index), Some(a));
+            prop_assert_eq!(super::read_u16(&buffer, index), Some(b));
+            prop_assert_eq!(super::read_u32(&buffer, index), Some(c));
+            prop_assert_eq!(super::read_u64(&buffer, index), Some(d));
+        }
+
+        #[test]
+        fn test_slice_and_str_round_trip(data in proptest::collection::vec(any::<u8>(), 0..1024), string in "\\PC*") {
+            let mut buffer = Vec::new();
+            super::write_slice(&mut buffer, &data);
+            super::write_str(&mut buffer, &string);
+            super::write_fixed_slice(&mut buffer, &data);
+            prop_assert_eq!(buffer.len(), super::size_of_slice(&data) + super::size_of_str(&string) + data.len());
+
+            let index = &mut 0;
+            prop_assert_eq!(super::read_slice(&buffer, index), Some(data.as_slice()));
+            prop_assert_eq!(super::read_str(&buffer, index), Some(string.as_str()));
+            prop_assert_eq!(super::read_fixed_slice(&buffer, index, data.len()), Some(data.as_slice()));
+            prop_assert_eq!(*index, buffer.len());
+        }
+
+        #[test]
+        fn test_truncated_slice_is_rejected(data in proptest::collection::vec(any::<u8>(), 1..256), cut in any::<prop::sample::Index>()) {
+            let mut buffer = Vec::new();
+            super::write_slice(&mut buffer, &data);
+            let truncated = &buffer[..cut.index(buffer.len())];
+            let mut index = 0;
+            prop_assert_eq!(super::read_slice(truncated, &mut index), None);
+            prop_assert_eq!(index, 0);
+        }
+    }
 }
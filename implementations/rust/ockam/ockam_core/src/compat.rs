@@ -9,6 +9,14 @@
 //!      possible. (e.g. `std::sync::Arc` -> `ockam_core::compat::sync::Arc`)
 //!   3. if you need to add new items to compat, follow the originating
 //!      namespace. (e.g. `compat::vec::Vec` and not `compat::Vec`)
+//!
+//! The types enabled by the `"routing-codec"` feature only use the
+//! `alloc` based modules of this facade (`vec`, `string`, `str`,
+//! `collections`, `fmt`), together with `error` and `rand`. The `sync`
+//! and `time` modules are only needed by `"routing-full"`.
+
+/// Provides the delays between the attempts of a retried operation.
+pub mod backoff;
 
 /// Provides `std::borrow` for `alloc` targets.
 #[cfg(feature = "alloc")]
@@ -0,0 +1,289 @@
+//! Delays between the attempts of an operation which is retried after a failure.
+//!
+//! A [`Backoff`] describes the delays: they start at an initial delay, are multiplied
+//! after each failed attempt and are capped by a maximum delay. With "full jitter",
+//! each delay is a random duration between zero and that capped value, so that
+//! many clients failing at the same time don't retry at the same time.
+//!
+//! The delays are produced by [`BackoffDelays::next_delay`], which is available on `no_std`
+//! targets. On `std` targets, the [`retry`] function runs an asynchronous operation until it
+//! succeeds or until the maximum number of attempts is reached.
+
+use crate::compat::rand::{thread_rng, Rng};
+use crate::compat::time::Duration;
+
+/// Policy for the delays between the attempts of a retried operation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Backoff {
+    initial_delay: Duration,
+    multiplier: f64,
+    max_delay: Duration,
+    max_attempts: Option<u32>,
+    jitter: bool,
+}
+
+impl Backoff {
+    /// Default multiplier applied to the delay after each failed attempt
+    pub const DEFAULT_MULTIPLIER: f64 = 2.0;
+
+    /// Create a backoff starting at `initial_delay` and doubling after each attempt,
+    /// up to `max_delay`. The number of attempts is not limited and there is no jitter
+    pub const fn new(initial_delay: Duration, max_delay: Duration) -> Self {
+        // `Ord::max` can't be used in a const function
+        let max_delay = if max_delay.as_nanos() < initial_delay.as_nanos() {
+            initial_delay
+        } else {
+            max_delay
+        };
+        Self {
+            initial_delay,
+            multiplier: Self::DEFAULT_MULTIPLIER,
+            max_delay,
+            max_attempts: None,
+            jitter: false,
+        }
+    }
+
+    /// Set the multiplier applied to the delay after each failed attempt.
+    /// Multipliers smaller than 1 are replaced by 1, which gives a constant delay
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = if multiplier >= 1.0 { multiplier } else { 1.0 };
+        self
+    }
+
+    /// Limit the total number of attempts, including the first one.
+    /// With a limit of `n` attempts, at most `n - 1` delays are produced
+    pub const fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Replace each delay by a random duration between zero and that delay
+    pub const fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Delay before the first retry
+    pub fn initial_delay(&self) -> Duration {
+        self.initial_delay
+    }
+
+    /// Multiplier applied to the delay after each failed attempt
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Upper bound of the delays
+    pub fn max_delay(&self) -> Duration {
+        self.max_delay
+    }
+
+    /// Maximum number of attempts, if limited
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    /// Return true if the delays are randomized
+    pub fn has_jitter(&self) -> bool {
+        self.jitter
+    }
+
+    /// Delay before the retry following the failed attempt number `attempt`, starting at 0,
+    /// before any jitter is applied
+    pub fn capped_delay(&self, attempt: u32) -> Duration {
+        // the delay is multiplied step by step since `f64::powi` is not available on no_std targets
+        let max_delay = self.max_delay.as_secs_f64();
+        let mut delay = self.initial_delay.as_secs_f64();
+        for _ in 0..attempt {
+            if delay == 0.0 || delay >= max_delay || self.multiplier <= 1.0 {
+                break;
+            }
+            delay *= self.multiplier;
+        }
+        Duration::try_from_secs_f64(delay)
+            .map(|delay| delay.min(self.max_delay))
+            .unwrap_or(self.max_delay)
+    }
+
+    /// Return the successive delays of this policy
+    pub fn delays(&self) -> BackoffDelays {
+        BackoffDelays {
+            backoff: *self,
+            attempt: 0,
+        }
+    }
+}
+
+/// Successive delays of a [`Backoff`]
+#[derive(Clone, Debug)]
+pub struct BackoffDelays {
+    backoff: Backoff,
+    attempt: u32,
+}
+
+impl BackoffDelays {
+    /// Return the delay to wait for before the next attempt,
+    /// or `None` if the maximum number of attempts is reached
+    pub fn next_delay(&mut self) -> Option<Duration> {
+        if let Some(max_attempts) = self.backoff.max_attempts {
+            if self.attempt.saturating_add(1) >= max_attempts {
+                return None;
+            }
+        }
+        let delay = self.backoff.capped_delay(self.attempt);
+        self.attempt = self.attempt.saturating_add(1);
+
+        if self.backoff.jitter {
+            let nanos = u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX);
+            Some(Duration::from_nanos(thread_rng().gen_range(0..=nanos)))
+        } else {
+            Some(delay)
+        }
+    }
+
+    /// Number of delays returned so far
+    pub fn attempts(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Start again from the initial delay, for example after a successful attempt
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+impl Iterator for BackoffDelays {
+    type Item = Duration;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_delay()
+    }
+}
+
+/// Run an operation until it succeeds, waiting between the attempts as specified by `backoff`.
+/// The error of the last attempt is returned if the maximum number of attempts is reached
+#[cfg(feature = "tokio")]
+pub async fn retry<T, E, F, Fut>(backoff: &Backoff, mut operation: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: core::future::Future<Output = Result<T, E>>,
+    E: core::fmt::Display,
+{
+    let mut delays = backoff.delays();
+    loop {
+        match operation().await {
+            Ok(result) => return Ok(result),
+            Err(error) => match delays.next_delay() {
+                Some(delay) => {
+                    tracing::debug!(
+                        attempt = delays.attempts(),
+                        "retrying in {delay:?} after an error: {error}"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                None => return Err(error),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compat::vec::Vec;
+    #[cfg(feature = "tokio")]
+    use core::sync::atomic::{AtomicU32, Ordering};
+    use proptest::prelude::*;
+
+    #[test]
+    fn delays_are_multiplied_and_capped() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        let delays: Vec<u128> = backoff.delays().take(6).map(|d| d.as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+
+        let backoff = backoff.with_multiplier(1.5).with_max_attempts(4);
+        let delays: Vec<u128> = backoff.delays().map(|d| d.as_millis()).collect();
+        assert_eq!(delays, vec![100, 150, 225]);
+    }
+
+    #[test]
+    fn delays_can_be_reset() {
+        let backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1));
+        let mut delays = backoff.delays();
+        delays.next_delay();
+        delays.next_delay();
+        assert_eq!(delays.attempts(), 2);
+        delays.reset();
+        assert_eq!(delays.next_delay(), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn a_large_number_of_attempts_does_not_overflow() {
+        let backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+        assert_eq!(backoff.capped_delay(u32::MAX), Duration::from_secs(60));
+    }
+
+    proptest! {
+        #[test]
+        fn delays_stay_within_bounds(
+            initial_ms in 0u64..10_000,
+            max_ms in 0u64..100_000,
+            multiplier in 0.0f64..10.0,
+            max_attempts in 0u32..20,
+            jitter: bool,
+        ) {
+            let backoff = Backoff::new(Duration::from_millis(initial_ms), Duration::from_millis(max_ms))
+                .with_multiplier(multiplier)
+                .with_max_attempts(max_attempts)
+                .with_jitter(jitter);
+
+            let delays: Vec<Duration> = backoff.delays().collect();
+            prop_assert_eq!(delays.len() as u32, max_attempts.saturating_sub(1));
+
+            let mut previous = Duration::ZERO;
+            for (attempt, delay) in delays.iter().enumerate() {
+                let capped = backoff.capped_delay(attempt as u32);
+                prop_assert!(capped <= backoff.max_delay());
+                prop_assert!(capped >= previous);
+                previous = capped;
+                if jitter {
+                    prop_assert!(*delay <= capped);
+                } else {
+                    prop_assert_eq!(*delay, capped);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn an_operation_is_retried_until_it_succeeds() {
+        let backoff = Backoff::new(Duration::from_millis(1), Duration::from_millis(5));
+        let attempts = AtomicU32::new(0);
+        let result: Result<u32, &str> = retry(&backoff, || async {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            if attempt < 3 {
+                Err("not yet")
+            } else {
+                Ok(attempt)
+            }
+        })
+        .await;
+        assert_eq!(result, Ok(3));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn the_last_error_is_returned_after_the_maximum_number_of_attempts() {
+        let backoff =
+            Backoff::new(Duration::from_millis(1), Duration::from_millis(5)).with_max_attempts(3);
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), u32> = retry(&backoff, || async {
+            Err(attempts.fetch_add(1, Ordering::SeqCst))
+        })
+        .await;
+        assert_eq!(result, Err(2));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+}
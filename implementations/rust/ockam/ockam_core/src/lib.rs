@@ -22,6 +22,20 @@
 //! feature enabled whether or not your direct dependency on `ockam_core`
 //! has `default-features = false`.
 //!
+//! The routing types are split in two features, both enabled by `"std"`:
+//!
+//! - `"routing-codec"` only provides the types needed to encode and decode messages:
+//!   `Address`, `Route`, `TransportType` and `TransportMessage`.
+//! - `"routing-full"` provides the rest of the routing module and the types which depend
+//!   on it: local messages, mailboxes, workers, processors, access control and flow control.
+//!
+//! An embedded target which only needs to encode and decode messages can use
+//!
+//! ```toml
+//! [dependencies]
+//! ockam_core = { version = "<current version>" , default-features = false, features = ["no_std", "routing-codec"] }
+//! ```
+//!
 #![deny(unsafe_code)]
 #![warn(
     missing_docs,
@@ -62,12 +76,15 @@ pub use ockam_macros::{AsyncTryClone, Message};
 extern crate futures_util;
 
 /// Access control
+#[cfg(feature = "routing-full")]
 pub mod access_control;
 pub mod api;
 pub mod compat;
 
 /// Debugger
+#[cfg(feature = "routing-full")]
 pub mod debugger;
+#[cfg(feature = "routing-full")]
 pub mod flow_control;
 
 /// Encoding
@@ -81,18 +98,27 @@ pub mod bare;
 mod cbor;
 mod error;
 mod message;
+#[cfg(feature = "routing-full")]
 mod processor;
+#[cfg(feature = "routing-codec")]
 mod routing;
+mod typed_payload;
 mod uint;
+#[cfg(feature = "routing-full")]
 mod worker;
 
+#[cfg(feature = "routing-full")]
 pub use access_control::*;
 pub use cbor::*;
 pub use error::*;
 pub use message::*;
+#[cfg(feature = "routing-full")]
 pub use processor::*;
+#[cfg(feature = "routing-codec")]
 pub use routing::*;
+pub use typed_payload::*;
 pub use uint::*;
+#[cfg(feature = "routing-full")]
 pub use worker::*;
 
 #[cfg(all(not(feature = "std"), feature = "alloc"))]
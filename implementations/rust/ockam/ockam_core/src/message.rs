@@ -1,12 +1,19 @@
+#[cfg(all(feature = "std", feature = "routing-full"))]
+use crate::LocalBody;
 use crate::{
     compat::{
         string::{String, ToString},
         vec::Vec,
     },
     errcode::{Kind, Origin},
-    Address, Error, LocalMessage, Result, Route,
+    Error, Result,
 };
-use core::fmt::{self, Debug, Display, Formatter};
+#[cfg(feature = "routing-full")]
+use crate::{Address, IngressInfo, LocalMessage, Route};
+#[cfg(feature = "routing-full")]
+use core::fmt::Debug;
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "routing-full")]
 use core::marker::PhantomData;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_bare::ser::{Serializer, VecWrite};
@@ -187,6 +194,7 @@ impl<E: Display> From<minicbor::encode::Error<E>> for Error {
 ///
 /// See `ockam_node::WorkerRelay` for a usage example.
 ///
+#[cfg(feature = "routing-full")]
 pub struct Routed<M: Message> {
     /// Phantom field to keep track of the message type.
     phantom: PhantomData<M>,
@@ -197,8 +205,13 @@ pub struct Routed<M: Message> {
     src_addr: Address,
     /// A `LocalMessage` that contains routing information for the wrapped message.
     local_msg: LocalMessage,
+    /// Body moved from a worker of the same node. When it is set, the payload of
+    /// `local_msg` is empty and the body is only encoded if the payload is accessed
+    #[cfg(feature = "std")]
+    local_body: Option<LocalBody>,
 }
 
+#[cfg(feature = "routing-full")]
 impl<M: Message> Routed<M> {
     /// Create a new `Routed` message wrapper from the given message,
     /// message address and a local message that contains routing
@@ -209,9 +222,18 @@ impl<M: Message> Routed<M> {
             msg_addr,
             src_addr,
             local_msg,
+            #[cfg(feature = "std")]
+            local_body: None,
         }
     }
 
+    /// Set the body moved from a worker of the same node
+    #[cfg(feature = "std")]
+    pub(crate) fn with_local_body(mut self, local_body: Option<LocalBody>) -> Self {
+        self.local_body = local_body;
+        self
+    }
+
     /// Return a copy of the message address.
     #[inline]
     pub fn msg_addr(&self) -> Address {
@@ -244,34 +266,53 @@ impl<M: Message> Routed<M> {
     /// Consume the message wrapper and return the original message.
     #[inline]
     pub fn into_body(self) -> Result<M> {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = self.local_body {
+            return local_body.into_body();
+        }
         M::decode(&self.into_payload())
     }
 
     /// Consume the message wrapper and return the underlying local message.
     #[inline]
     pub fn into_local_message(self) -> LocalMessage {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = self.local_body {
+            return local_body.into_local_message(self.local_msg);
+        }
         self.local_msg
     }
 
     /// Return a reference to the underlying local message.
     #[inline]
     pub fn local_message(&self) -> &LocalMessage {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = &self.local_body {
+            return local_body.local_message(&self.local_msg);
+        }
         &self.local_msg
     }
 
+    /// Return the description of the transport connection on which the message entered the node
+    #[inline]
+    pub fn ingress(&self) -> Option<IngressInfo> {
+        self.local_msg.ingress()
+    }
+
     /// Return a reference to the underlying transport message's binary payload.
     #[inline]
     pub fn payload(&self) -> &[u8] {
-        self.local_msg.payload_ref()
+        self.local_message().payload_ref()
     }
 
     /// Consume the message wrapper and return the underlying transport message's binary payload.
     #[inline]
     pub fn into_payload(self) -> Vec<u8> {
-        self.local_msg.into_payload()
+        self.into_local_message().into_payload()
     }
 }
 
+#[cfg(feature = "routing-full")]
 impl<M: Message + Debug> Debug for Routed<M> {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         f.debug_struct("Routed")
@@ -29,6 +29,25 @@ use serde::{Deserialize, Serialize};
 /// * `"0#alice"` represents a local worker with the address: `alice`.
 /// * `"1#carol"` represents a remote worker with the address `carol`, reachable over TCP transport.
 ///
+/// ## Escaping
+///
+/// An address is displayed as `<transport type>#<data>` and parsing the displayed string
+/// returns the same address. For this to work, the following bytes of the data are displayed
+/// as `%XX`, where `XX` is the byte value in uppercase hexadecimal:
+///
+/// * the `%` and `#` characters,
+/// * whitespace and control characters,
+/// * the `=` character when it is followed by `>`, so that `=>` only separates the addresses of a [`Route`](crate::Route),
+/// * bytes which are not part of a valid UTF-8 sequence.
+///
+/// For example, the data `my address#1` is displayed as `0#my%20address%231`.
+///
+/// ## Wire stability
+///
+/// The encoding of an address is stable since it is part of a [`Route`](crate::Route).
+/// With BARE, it is encoded as its transport type followed by its variable length data.
+/// With CBOR, it is encoded as the map `{1: transport type, 2: data as an array of bytes}`.
+///
 #[derive(Serialize, Deserialize, Decode, Encode, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
 #[rustfmt::skip]
 #[cbor(map)] // TODO: Switch to an array eventually
@@ -150,40 +169,94 @@ impl core::str::FromStr for Address {
     ///
     /// See type documentation for more detail.
     fn from_str(s: &str) -> Result<Address, Self::Err> {
-        let buf: String = s.into();
-        let mut vec: Vec<_> = buf.split('#').collect();
-
-        // If after the split we only have one element, there was no
-        // `#` separator, so the type needs to be implicitly `= 0`
-        if vec.len() == 1 {
-            Ok(Address {
-                tt: LOCAL,
-                inner: vec.remove(0).as_bytes().to_vec(),
-            })
+        // If there is no `#` separator, the type is implicitly `= 0`
+        let (tt, data, data_offset) = match s.split_once('#') {
+            None => (LOCAL, s, 0),
+            Some((tt_str, data)) => match str::parse(tt_str) {
+                Ok(tt) => (TransportType::new(tt), data, tt_str.len() + 1),
+                Err(e) => {
+                    return Err(AddressParseError::new(AddressParseErrorKind::InvalidType(
+                        e,
+                    )))
+                }
+            },
+        };
+
+        if let Some(position) = data.find('#') {
+            return Err(AddressParseError::new(AddressParseErrorKind::MultipleSep)
+                .with_offset(data_offset + position));
         }
-        // If after the split we have 2 elements, we extract the type
-        // value from the string, and use the rest as the address
-        else if vec.len() == 2 {
-            match str::parse(vec.remove(0)) {
-                Ok(tt) => Ok(Address {
-                    tt: TransportType::new(tt),
-                    inner: vec.remove(0).as_bytes().to_vec(),
-                }),
-                Err(e) => Err(AddressParseError::new(AddressParseErrorKind::InvalidType(
-                    e,
-                ))),
+
+        let inner = unescape(data).map_err(|position| {
+            AddressParseError::new(AddressParseErrorKind::InvalidEscape)
+                .with_offset(data_offset + position)
+        })?;
+        Ok(Address { tt, inner })
+    }
+}
+
+impl Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}#", self.tt)?;
+        write_escaped(f, &self.inner)
+    }
+}
+
+/// Write the data of an address, escaping the bytes which could not be parsed back
+fn write_escaped(f: &mut fmt::Formatter, data: &[u8]) -> fmt::Result {
+    let mut rest = data;
+    while !rest.is_empty() {
+        let (valid, invalid) = match from_utf8(rest) {
+            Ok(valid) => (valid, [].as_slice()),
+            Err(e) => {
+                let (valid, after) = rest.split_at(e.valid_up_to());
+                let invalid_len = e.error_len().unwrap_or(after.len());
+                (from_utf8(valid).unwrap_or_default(), &after[..invalid_len])
+            }
+        };
+        rest = &rest[valid.len() + invalid.len()..];
+
+        let mut chars = valid.chars().peekable();
+        while let Some(c) = chars.next() {
+            let is_separator = c == '=' && chars.peek() == Some(&'>');
+            if c == '%' || c == '#' || c.is_whitespace() || c.is_control() || is_separator {
+                let mut buffer = [0; 4];
+                for b in c.encode_utf8(&mut buffer).bytes() {
+                    write!(f, "%{:02X}", b)?;
+                }
+            } else {
+                write!(f, "{}", c)?;
             }
-        } else {
-            Err(AddressParseError::new(AddressParseErrorKind::MultipleSep))
+        }
+        for b in invalid {
+            write!(f, "%{:02X}", b)?;
         }
     }
+    Ok(())
 }
 
-impl Display for Address {
-    fn fmt<'a>(&'a self, f: &mut fmt::Formatter) -> fmt::Result {
-        let inner: &'a str = from_utf8(self.inner.as_slice()).unwrap_or("Invalid UTF-8");
-        write!(f, "{}#{}", self.tt, inner)
+/// Decode the `%XX` sequences of an address.
+/// Return the position of the first invalid sequence in case of an error.
+fn unescape(data: &str) -> Result<Vec<u8>, usize> {
+    let bytes = data.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let byte = bytes
+                .get(i + 1..i + 3)
+                .and_then(|hex| from_utf8(hex).ok())
+                .filter(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                .ok_or(i)?;
+            result.push(byte);
+            i += 3;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
     }
+    Ok(result)
 }
 
 impl Debug for Address {
@@ -294,13 +367,10 @@ impl Address {
         1 + crate::bare::size_of_slice(&self.inner)
     }
     pub(crate) fn manually_decode(slice: &[u8], index: &mut usize) -> Option<Address> {
-        if slice.len() - *index < 2 {
-            return None;
-        }
-        let tt = slice[*index];
-        *index += 1;
-
-        let inner = crate::bare::read_slice(slice, index)?;
+        let mut cursor = *index;
+        let tt = crate::bare::read_u8(slice, &mut cursor)?;
+        let inner = crate::bare::read_slice(slice, &mut cursor)?;
+        *index = cursor;
         Some(Address {
             tt: TransportType::new(tt),
             inner: inner.to_vec(),
@@ -359,4 +429,25 @@ mod test {
     fn parse_addr_invalid_multiple_separators() {
         let _ = Address::from_string("1#invalid#");
     }
+
+    #[test]
+    fn display_escapes_special_characters() {
+        let address = Address::new(TransportType::new(1), "my address#1=>%");
+        assert_eq!(address.to_string(), "1#my%20address%231%3D>%25");
+        assert_eq!(address.to_string().parse::<Address>().unwrap(), address);
+
+        let address = Address::from((LOCAL, vec![b'a', 0xff, b'=', b'b']));
+        assert_eq!(address.to_string(), "0#a%FF=b");
+        assert_eq!(address.to_string().parse::<Address>().unwrap(), address);
+    }
+
+    #[test]
+    fn parse_addr_invalid_escape() {
+        let error = "1#abc%4".parse::<Address>().unwrap_err();
+        assert_eq!(error.kind(), &AddressParseErrorKind::InvalidEscape);
+        assert_eq!(error.offset(), 5);
+
+        let error = "1#a%+1".parse::<Address>().unwrap_err();
+        assert_eq!(error.offset(), 3);
+    }
 }
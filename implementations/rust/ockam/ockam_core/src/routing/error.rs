@@ -1,4 +1,5 @@
 use crate::{
+    compat::string::{String, ToString},
     errcode::{Kind, Origin},
     Error,
 };
@@ -34,6 +35,7 @@ impl Display for RouteError {
 #[derive(Debug)]
 pub struct AddressParseError {
     kind: AddressParseErrorKind,
+    offset: usize,
 }
 
 /// Enum to store the cause of an address parsing failure.
@@ -44,22 +46,32 @@ pub enum AddressParseErrorKind {
     InvalidType(core::num::ParseIntError),
     /// Address string has more than one '#' separator.
     MultipleSep,
+    /// A '%' character is not followed by two hexadecimal digits.
+    InvalidEscape,
 }
 
 impl AddressParseError {
     /// Create new address parse error instance.
     pub fn new(kind: AddressParseErrorKind) -> Self {
-        Self { kind }
+        Self { kind, offset: 0 }
+    }
+    /// Set the offset of the invalid character in the address string.
+    pub fn with_offset(self, offset: usize) -> Self {
+        Self { offset, ..self }
     }
     /// Return the cause of the address parsing failure.
     pub fn kind(&self) -> &AddressParseErrorKind {
         &self.kind
     }
+    /// Return the offset of the invalid character in the address string.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
 }
 
-impl Display for AddressParseError {
+impl Display for AddressParseErrorKind {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.kind {
+        match self {
             AddressParseErrorKind::InvalidType(e) => {
                 write!(f, "Failed to parse address type: '{}'", e)
             }
@@ -69,8 +81,95 @@ impl Display for AddressParseError {
                     "Invalid address string: more than one '#' separator found"
                 )
             }
+            AddressParseErrorKind::InvalidEscape => {
+                write!(
+                    f,
+                    "Invalid address string: '%' must be followed by two hexadecimal digits"
+                )
+            }
         }
     }
 }
 
+impl Display for AddressParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at offset {})", self.kind, self.offset)
+    }
+}
+
 impl crate::compat::error::Error for AddressParseError {}
+
+/// An error which is returned when route parsing from string fails.
+#[derive(Debug)]
+pub struct RouteParseError {
+    kind: RouteParseErrorKind,
+    segment_index: usize,
+    segment: String,
+    offset: usize,
+}
+
+/// Enum to store the cause of a route parsing failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RouteParseErrorKind {
+    /// A segment between two '=>' separators is empty.
+    EmptySegment,
+    /// A segment is not a valid address.
+    InvalidAddress(AddressParseErrorKind),
+}
+
+impl RouteParseError {
+    /// Create new route parse error instance.
+    pub fn new(
+        kind: RouteParseErrorKind,
+        segment_index: usize,
+        segment: &str,
+        offset: usize,
+    ) -> Self {
+        Self {
+            kind,
+            segment_index,
+            segment: segment.to_string(),
+            offset,
+        }
+    }
+    /// Return the cause of the route parsing failure.
+    pub fn kind(&self) -> &RouteParseErrorKind {
+        &self.kind
+    }
+    /// Return the index of the invalid segment, starting at 0.
+    pub fn segment_index(&self) -> usize {
+        self.segment_index
+    }
+    /// Return the invalid segment, without its surrounding whitespace.
+    pub fn segment(&self) -> &str {
+        &self.segment
+    }
+    /// Return the offset of the invalid character in the route string.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl From<RouteParseError> for Error {
+    #[track_caller]
+    fn from(err: RouteParseError) -> Self {
+        Error::new(Origin::Core, Kind::Invalid, err)
+    }
+}
+
+impl Display for RouteParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Invalid route segment {} '{}' at offset {}: ",
+            self.segment_index, self.segment, self.offset
+        )?;
+        match &self.kind {
+            RouteParseErrorKind::EmptySegment => write!(f, "the segment is empty"),
+            RouteParseErrorKind::InvalidAddress(kind) => write!(f, "{}", kind),
+        }
+    }
+}
+
+impl crate::compat::error::Error for RouteParseError {}
@@ -1,6 +1,6 @@
 use crate::access_control::IncomingAccessControl;
 use crate::compat::{sync::Arc, vec::Vec};
-use crate::{debugger, Address, DenyAll, OutgoingAccessControl, RelayMessage, Result};
+use crate::{debugger, Address, DenyAll, DenyReason, OutgoingAccessControl, RelayMessage, Result};
 use core::cmp::Ordering;
 use core::fmt::{self, Debug};
 
@@ -178,6 +178,15 @@ impl Mailboxes {
         }
     }
 
+    /// Return the reason why the given [`RelayMessage`]
+    /// is not authorized to be received by this [`Mailboxes`], if one is known
+    pub async fn incoming_deny_reason(&self, relay_msg: &RelayMessage) -> Option<DenyReason> {
+        match self.find_mailbox(relay_msg.destination()) {
+            Some(mailbox) => mailbox.incoming.deny_reason(relay_msg).await,
+            None => None,
+        }
+    }
+
     /// Return `true` if the given [`RelayMessage`]
     /// is authorized to be sent by this [`Mailboxes`]
     pub async fn is_outgoing_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+
+use crate::compat::string::{String, ToString};
+use crate::compat::vec::Vec;
+use crate::{Decodable, Encodable, LocalInfo, LocalMessage, Result, TransportType};
+
+/// Identifier of the [`LocalInfo`] entry storing the [`IngressInfo`] of a [`LocalMessage`]
+pub const INGRESS_INFO_IDENTIFIER: &str = "INGRESS_INFO";
+
+/// Description of the transport connection on which a message entered the node.
+///
+/// This information is attached by the transport receivers (TCP, UDP) to the messages they
+/// receive, and is kept when a message is decrypted by a secure channel. A worker can use it,
+/// for example, to log the peer of a request or to limit the rate of messages per source.
+///
+/// Like the other [`LocalInfo`] entries, it is never sent to another node: a message forwarded
+/// over another transport only carries the ingress information of the next node.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IngressInfo {
+    transport_type: TransportType,
+    local_address: String,
+    remote_address: String,
+    connection_id: String,
+}
+
+impl IngressInfo {
+    /// Create a new [`IngressInfo`]
+    pub fn new(
+        transport_type: TransportType,
+        local_address: impl Into<String>,
+        remote_address: impl Into<String>,
+        connection_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            transport_type,
+            local_address: local_address.into(),
+            remote_address: remote_address.into(),
+            connection_id: connection_id.into(),
+        }
+    }
+
+    /// Type of the transport which received the message
+    pub fn transport_type(&self) -> TransportType {
+        self.transport_type
+    }
+
+    /// Local address of the socket which received the message
+    pub fn local_address(&self) -> &str {
+        &self.local_address
+    }
+
+    /// Address of the peer which sent the message
+    pub fn remote_address(&self) -> &str {
+        &self.remote_address
+    }
+
+    /// Identifier of the connection within its transport, for example the address
+    /// of the worker sending messages back on that connection
+    pub fn connection_id(&self) -> &str {
+        &self.connection_id
+    }
+
+    /// Encode the ingress information as a [`LocalInfo`] entry
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(
+            INGRESS_INFO_IDENTIFIER.to_string(),
+            self.clone().encode()?,
+        ))
+    }
+
+    /// Return the ingress information stored in a list of [`LocalInfo`] entries, if any
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> Option<IngressInfo> {
+        local_info
+            .iter()
+            .find(|info| info.type_identifier() == INGRESS_INFO_IDENTIFIER)
+            .and_then(|info| IngressInfo::decode(info.data()).ok())
+    }
+
+    /// Mark a list of [`LocalInfo`] entries with some ingress information,
+    /// replacing any pre-existing entry
+    pub fn mark(mut local_info: Vec<LocalInfo>, ingress: &IngressInfo) -> Result<Vec<LocalInfo>> {
+        local_info.retain(|info| info.type_identifier() != INGRESS_INFO_IDENTIFIER);
+        local_info.push(ingress.to_local_info()?);
+        Ok(local_info)
+    }
+}
+
+impl LocalMessage {
+    /// Return the description of the transport connection on which the message entered the node.
+    /// There is no such information for messages created within the node.
+    pub fn ingress(&self) -> Option<IngressInfo> {
+        IngressInfo::find_info_from_list(self.local_info_ref())
+    }
+
+    /// Set the ingress information of the message, replacing any pre-existing one
+    pub fn with_ingress(mut self, ingress: &IngressInfo) -> Result<Self> {
+        let local_info = self.local_info_mut();
+        local_info.retain(|info| info.type_identifier() != INGRESS_INFO_IDENTIFIER);
+        local_info.push(ingress.to_local_info()?);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route;
+
+    #[test]
+    fn test_ingress_info() {
+        let message = LocalMessage::new()
+            .with_onward_route(route!["worker"])
+            .with_payload(b"hello".to_vec());
+        assert!(message.ingress().is_none());
+
+        let ingress = IngressInfo::new(
+            TransportType::new(1),
+            "127.0.0.1:4000",
+            "127.0.0.1:5000",
+            "connection",
+        );
+        let message = message.with_ingress(&ingress).unwrap();
+        assert_eq!(message.ingress(), Some(ingress.clone()));
+
+        // marking a message again replaces the existing entry
+        let other = IngressInfo::new(
+            TransportType::new(1),
+            "127.0.0.1:4000",
+            "127.0.0.1:6000",
+            "other_connection",
+        );
+        let message = message.with_ingress(&other).unwrap();
+        assert_eq!(message.ingress(), Some(other));
+        assert_eq!(message.local_info_ref().len(), 1);
+
+        // the ingress information is not part of the message sent to other nodes
+        let transport_message = message.into_transport_message();
+        let received = LocalMessage::from_transport_message(transport_message);
+        assert!(received.ingress().is_none());
+    }
+}
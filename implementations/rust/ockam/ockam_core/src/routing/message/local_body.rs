@@ -0,0 +1,211 @@
+use crate::compat::boxed::Box;
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{Error, LocalMessage, Message, Result};
+use core::any::{Any, TypeId};
+use core::fmt::{self, Debug, Formatter};
+use std::sync::{Mutex, OnceLock};
+
+/// Body of a message sent to a worker of the same node.
+///
+/// When the next hop of a message is the last address of its route, and that address is a
+/// [`LOCAL`](crate::LOCAL) worker address, the message is moved to the worker instead of being
+/// encoded by the sender and decoded by the receiver.
+///
+/// The body is only encoded when the receiver needs it as bytes:
+///
+///  - when the receiver expects another type of message. In that case the body is encoded and
+///    decoded again, exactly like a message coming from another node
+///  - when the receiver accesses the payload of the message, or its [`LocalMessage`]
+///
+/// Note that a message type whose encoding loses some information, for example because some
+/// of its fields are skipped, is received as it was sent.
+pub struct LocalBody {
+    type_id: TypeId,
+    type_name: &'static str,
+    body: Mutex<Option<Box<dyn AnyMessage>>>,
+    encoded: OnceLock<LocalMessage>,
+}
+
+/// Message which can either be downcast to its type or encoded
+trait AnyMessage: Send {
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send>;
+    fn encode_boxed(self: Box<Self>) -> Result<Vec<u8>>;
+}
+
+impl<M: Message> AnyMessage for M {
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send> {
+        self
+    }
+
+    fn encode_boxed(self: Box<Self>) -> Result<Vec<u8>> {
+        (*self).encode()
+    }
+}
+
+impl LocalBody {
+    /// Create a body for a message which is moved to a worker of the same node
+    pub fn new<M: Message>(body: M) -> Self {
+        Self {
+            type_id: TypeId::of::<M>(),
+            type_name: core::any::type_name::<M>(),
+            body: Mutex::new(Some(Box::new(body))),
+            encoded: OnceLock::new(),
+        }
+    }
+
+    /// Return true if the body has the type `M` and has not been encoded yet
+    pub fn is<M: Message>(&self) -> bool {
+        self.type_id == TypeId::of::<M>() && self.encoded.get().is_none()
+    }
+
+    /// Return the given local message with the encoded body as its payload.
+    /// The body is encoded the first time this function is called.
+    ///
+    /// If the body can't be encoded, the error is logged and the payload is empty,
+    /// since the sender of the message can't be notified anymore.
+    pub fn local_message(&self, local_msg: &LocalMessage) -> &LocalMessage {
+        self.encoded.get_or_init(|| {
+            let payload = Self::encode(self.type_name, self.take_body());
+            local_msg.clone().set_payload(payload)
+        })
+    }
+
+    /// Return the given local message with the encoded body as its payload.
+    /// As with [`LocalBody::local_message`], the payload is empty if the body can't be encoded
+    pub fn into_local_message(self, local_msg: LocalMessage) -> LocalMessage {
+        let Self {
+            type_name,
+            body,
+            encoded,
+            ..
+        } = self;
+        match encoded.into_inner() {
+            Some(encoded) => encoded,
+            None => local_msg.set_payload(Self::encode(type_name, Self::into_inner(body))),
+        }
+    }
+
+    /// Return the body if it has the type `M`. Otherwise it is encoded and decoded as an `M`
+    pub fn into_body<M: Message>(self) -> Result<M> {
+        let Self {
+            type_id,
+            body,
+            encoded,
+            ..
+        } = self;
+        if let Some(encoded) = encoded.into_inner() {
+            return M::decode(encoded.payload_ref());
+        }
+
+        let body = Self::into_inner(body)?;
+        if type_id == TypeId::of::<M>() {
+            if let Ok(body) = body.into_any().downcast::<M>() {
+                return Ok(*body);
+            }
+            return Err(Error::new(
+                Origin::Core,
+                Kind::Internal,
+                "the local message body doesn't have the expected type",
+            ));
+        }
+        M::decode(&body.encode_boxed()?)
+    }
+
+    fn encode(type_name: &str, body: Result<Box<dyn AnyMessage>>) -> Vec<u8> {
+        match body.and_then(|body| body.encode_boxed()) {
+            Ok(payload) => payload,
+            Err(e) => {
+                error!("cannot encode a local message of type {type_name}: {e}");
+                Vec::new()
+            }
+        }
+    }
+
+    fn take_body(&self) -> Result<Box<dyn AnyMessage>> {
+        self.body
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .take()
+            .ok_or_else(Self::missing_body)
+    }
+
+    fn into_inner(body: Mutex<Option<Box<dyn AnyMessage>>>) -> Result<Box<dyn AnyMessage>> {
+        body.into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .ok_or_else(Self::missing_body)
+    }
+
+    fn missing_body() -> Error {
+        Error::new(
+            Origin::Core,
+            Kind::Internal,
+            "the local message body was already taken",
+        )
+    }
+}
+
+impl Debug for LocalBody {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalBody")
+            .field("type", &self.type_name)
+            .field("encoded", &self.encoded.get().is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{route, Encodable, NeutralMessage};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, crate::Message)]
+    struct Request {
+        id: u32,
+        items: Vec<String>,
+    }
+
+    fn request() -> Request {
+        Request {
+            id: 1,
+            items: vec!["a".to_string(), "b".to_string()],
+        }
+    }
+
+    #[test]
+    fn a_body_with_the_expected_type_is_moved() -> Result<()> {
+        let body = LocalBody::new(request());
+        assert!(body.is::<Request>());
+        assert!(!body.is::<NeutralMessage>());
+        assert_eq!(body.into_body::<Request>()?, request());
+        Ok(())
+    }
+
+    #[test]
+    fn a_body_with_another_type_is_encoded_and_decoded() -> Result<()> {
+        let body = LocalBody::new(request());
+        let bytes = body.into_body::<NeutralMessage>()?.into_vec();
+        assert_eq!(bytes, request().encode()?);
+        Ok(())
+    }
+
+    #[test]
+    fn the_payload_of_a_body_is_the_encoded_body() -> Result<()> {
+        let local_msg = LocalMessage::new().with_onward_route(route!["worker"]);
+
+        let body = LocalBody::new(request());
+        let encoded = body.local_message(&local_msg);
+        assert_eq!(encoded.payload_ref(), request().encode()?);
+        assert_eq!(encoded.onward_route_ref(), local_msg.onward_route_ref());
+
+        // once it is encoded, the body is decoded from its payload
+        assert!(!body.is::<Request>());
+        assert_eq!(body.into_body::<Request>()?, request());
+
+        let body = LocalBody::new(request());
+        let encoded = body.into_local_message(local_msg.clone());
+        assert_eq!(encoded.payload_ref(), request().encode()?);
+        Ok(())
+    }
+}
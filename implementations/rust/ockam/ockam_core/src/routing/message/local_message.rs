@@ -38,6 +38,11 @@ use serde::{Deserialize, Serialize};
 ///
 /// self.pop_front_onward_route()?.prepend_front_return_route(&new_route)
 ///
+/// # Wire stability
+///
+/// A [`LocalMessage`] never leaves a node, only its [`TransportMessage`] does. Its encoding is
+/// internal and can change between releases.
+///
 #[derive(Serialize, Deserialize, Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Message)]
 pub struct LocalMessage {
     /// Onward message route.
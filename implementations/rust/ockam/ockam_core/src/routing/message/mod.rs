@@ -1,13 +1,31 @@
+#[cfg(feature = "routing-full")]
+mod ingress;
+#[cfg(all(feature = "std", feature = "routing-full"))]
+mod local_body;
+#[cfg(feature = "routing-full")]
 mod local_info;
+#[cfg(feature = "routing-full")]
 mod local_message;
 #[cfg(feature = "std")]
 mod opentelemetry;
+#[cfg(feature = "routing-full")]
+mod priority;
+#[cfg(feature = "routing-full")]
 mod relay_message;
 mod transport_message;
 
+#[cfg(feature = "routing-full")]
+pub use ingress::*;
+#[cfg(all(feature = "std", feature = "routing-full"))]
+pub use local_body::*;
+#[cfg(feature = "routing-full")]
 pub use local_info::*;
+#[cfg(feature = "routing-full")]
 pub use local_message::*;
 #[cfg(feature = "std")]
 pub use opentelemetry::*;
+#[cfg(feature = "routing-full")]
+pub use priority::*;
+#[cfg(feature = "routing-full")]
 pub use relay_message::*;
 pub use transport_message::*;
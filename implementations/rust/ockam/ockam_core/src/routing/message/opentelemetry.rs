@@ -2,7 +2,7 @@ use crate::errcode::{Kind, Origin};
 use core::fmt::{Display, Formatter};
 use core::str::FromStr;
 use opentelemetry::propagation::{Extractor, Injector};
-use opentelemetry::{global, Context};
+use opentelemetry::{global, Context, Key};
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::collections::HashMap;
@@ -16,6 +16,17 @@ const TRACE_CONTEXT_PROPAGATION_SPAN: &str = "trace context propagation";
 /// Name of the global Ockam tracer
 pub const OCKAM_TRACER_NAME: &str = "ockam";
 
+/// Attribute of the transport message spans: onward route of the message
+pub const ONWARD_ROUTE: &Key = &Key::from_static_str("ockam.onward_route");
+/// Attribute of the transport message spans: index of the hop, starting at 0 when the message is sent
+pub const HOP: &Key = &Key::from_static_str("ockam.hop");
+/// Attribute of the transport message spans: name of the node handling a hop
+pub const HOP_NODE_NAME: &Key = &Key::from_static_str("ockam.hop.node_name");
+
+/// Key of the hop index in a serialized OpenTelemetryContext.
+/// It is ignored by the OpenTelemetry propagators
+const HOP_KEY: &str = "ockam-hop";
+
 /// Serializable data type to hold the opentelemetry propagation context.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct OpenTelemetryContext(HashMap<String, String>);
@@ -144,6 +155,21 @@ impl OpenTelemetryContext {
         Self(HashMap::new())
     }
 
+    /// Return the index of the hop of the transport message carrying this context.
+    /// The index is 0 if the context was not propagated over several hops
+    pub fn hop(&self) -> u32 {
+        self.0
+            .get(HOP_KEY)
+            .and_then(|hop| hop.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Set the index of the hop of the transport message carrying this context
+    pub fn with_hop(mut self, hop: u32) -> Self {
+        self.0.insert(HOP_KEY.to_string(), hop.to_string());
+        self
+    }
+
     /// Return the keys and values for testing
     pub fn as_map(&self) -> HashMap<String, String> {
         self.0.clone()
@@ -0,0 +1,87 @@
+use crate::compat::string::ToString;
+use crate::{LocalInfo, LocalMessage};
+
+/// Identifier of the [`LocalInfo`] entry storing the priority of a [`LocalMessage`]
+pub const MESSAGE_PRIORITY_IDENTIFIER: &str = "MESSAGE_PRIORITY";
+
+/// Priority of a [`LocalMessage`] in the mailbox of the worker receiving it.
+///
+/// Workers started with priority lanes handle the [`MessagePriority::High`] messages
+/// (secure channel handshakes, node manager requests, etc...) before the
+/// [`MessagePriority::Normal`] ones (data messages). The other workers handle all the messages
+/// in the order they were received.
+///
+/// The priority is only used within a node, it is not transmitted to other nodes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MessagePriority {
+    /// Data messages
+    #[default]
+    Normal,
+    /// Control messages
+    High,
+}
+
+impl MessagePriority {
+    /// Return true for the high priority
+    pub fn is_high(&self) -> bool {
+        *self == MessagePriority::High
+    }
+
+    /// Encode the priority as a [`LocalInfo`] entry
+    pub fn to_local_info(&self) -> LocalInfo {
+        let data = match self {
+            MessagePriority::Normal => 0,
+            MessagePriority::High => 1,
+        };
+        LocalInfo::new(MESSAGE_PRIORITY_IDENTIFIER.to_string(), vec![data])
+    }
+
+    /// Return the priority stored in a list of [`LocalInfo`] entries.
+    /// The priority is [`MessagePriority::Normal`] if there is no such entry.
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> MessagePriority {
+        match local_info
+            .iter()
+            .find(|info| info.type_identifier() == MESSAGE_PRIORITY_IDENTIFIER)
+            .map(|info| info.data())
+        {
+            Some([1]) => MessagePriority::High,
+            _ => MessagePriority::Normal,
+        }
+    }
+}
+
+impl LocalMessage {
+    /// Return the priority of the message
+    pub fn priority(&self) -> MessagePriority {
+        MessagePriority::find_info_from_list(self.local_info_ref())
+    }
+
+    /// Set the priority of the message
+    pub fn with_priority(mut self, priority: MessagePriority) -> Self {
+        let local_info = self.local_info_mut();
+        local_info.retain(|info| info.type_identifier() != MESSAGE_PRIORITY_IDENTIFIER);
+        if priority.is_high() {
+            local_info.push(priority.to_local_info());
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_priority() {
+        let message = LocalMessage::new();
+        assert_eq!(message.priority(), MessagePriority::Normal);
+
+        let message = message.with_priority(MessagePriority::High);
+        assert_eq!(message.priority(), MessagePriority::High);
+        assert_eq!(message.local_info_ref().len(), 1);
+
+        let message = message.with_priority(MessagePriority::Normal);
+        assert_eq!(message.priority(), MessagePriority::Normal);
+        assert!(message.local_info_ref().is_empty());
+    }
+}
@@ -1,12 +1,17 @@
-use crate::{Address, LocalMessage, Route};
+#[cfg(feature = "std")]
+use crate::LocalBody;
+use crate::{Address, LocalMessage, Message, Route, Routed};
 
 /// A message addressed to the relay responsible for delivery of the
 /// wrapped [`LocalMessage`]
-#[derive(Clone, Debug)]
+#[derive(Debug)]
 pub struct RelayMessage {
     source: Address,
     destination: Address,
     local_msg: LocalMessage,
+    /// Body moved to a worker of the same node, see [`LocalBody`]
+    #[cfg(feature = "std")]
+    local_body: Option<LocalBody>,
 }
 
 impl RelayMessage {
@@ -16,9 +21,25 @@ impl RelayMessage {
             source,
             destination,
             local_msg,
+            #[cfg(feature = "std")]
+            local_body: None,
         }
     }
 
+    /// Attach a body which is moved to the destination worker instead of being encoded.
+    /// The payload of the local message is ignored
+    #[cfg(feature = "std")]
+    pub fn with_local_body(mut self, local_body: LocalBody) -> Self {
+        self.local_body = Some(local_body);
+        self
+    }
+
+    /// Return true if the body of the message is moved to the destination worker
+    #[cfg(feature = "std")]
+    pub fn has_local_body(&self) -> bool {
+        self.local_body.is_some()
+    }
+
     /// The sender address of the wrapped `LocalMessage`
     /// Note that this may be different from the first hop in the return_route
     /// This address is always equal to the address of the `Context` instance used to
@@ -46,18 +67,57 @@ impl RelayMessage {
         self.local_msg.return_route_ref()
     }
 
-    /// Payload
+    /// Payload. A body moved to the destination worker is encoded when this function is called
     pub fn payload(&self) -> &[u8] {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = &self.local_body {
+            return local_body.local_message(&self.local_msg).payload_ref();
+        }
         self.local_msg.payload_ref()
     }
 
     /// Local message
+    ///
+    /// The payload of the local message is empty if its body is moved to the destination worker.
+    /// Use [`RelayMessage::payload`] to access the payload in all cases.
     pub fn local_message(&self) -> &LocalMessage {
         &self.local_msg
     }
 
-    /// Take local message
+    /// Take local message. A body moved to the destination worker is encoded as its payload
     pub fn into_local_message(self) -> LocalMessage {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = self.local_body {
+            return local_body.into_local_message(self.local_msg);
+        }
         self.local_msg
     }
+
+    /// Wrap the message for a worker expecting messages of type `M`
+    pub fn into_routed<M: Message>(self) -> Routed<M> {
+        let routed = Routed::new(self.destination, self.source, self.local_msg);
+        #[cfg(feature = "std")]
+        let routed = routed.with_local_body(self.local_body);
+        routed
+    }
+}
+
+impl Clone for RelayMessage {
+    /// A body moved to the destination worker can't be cloned, so it is encoded as
+    /// the payload of the cloned message
+    fn clone(&self) -> Self {
+        #[cfg(feature = "std")]
+        if let Some(local_body) = &self.local_body {
+            return Self::new(
+                self.source.clone(),
+                self.destination.clone(),
+                local_body.local_message(&self.local_msg).clone(),
+            );
+        }
+        Self::new(
+            self.source.clone(),
+            self.destination.clone(),
+            self.local_msg.clone(),
+        )
+    }
 }
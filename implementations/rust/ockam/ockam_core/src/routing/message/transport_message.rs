@@ -1,16 +1,16 @@
 use crate::errcode::{Kind, Origin};
 #[cfg(feature = "std")]
 use crate::OpenTelemetryContext;
-#[cfg(feature = "tracing_context")]
-use crate::OCKAM_TRACER_NAME;
 use crate::{compat::vec::Vec, Decodable, Encodable, Encoded, Message, Route};
+#[cfg(feature = "tracing_context")]
+use crate::{HOP, HOP_NODE_NAME, OCKAM_TRACER_NAME, ONWARD_ROUTE};
 use cfg_if::cfg_if;
 use core::fmt::{self, Display, Formatter};
 #[cfg(feature = "tracing_context")]
 use opentelemetry::{
     global,
     trace::{Link, SpanBuilder, TraceContextExt, Tracer},
-    Context,
+    Context, KeyValue,
 };
 
 /// A generic transport message type.
@@ -26,6 +26,28 @@ use opentelemetry::{
 ///
 /// See `ockam_transport_tcp::workers::sender::TcpSendWorker` for a usage example.
 ///
+/// # Encoding
+///
+/// A transport message is encoded with the [`bare`](crate::bare) primitives as:
+///
+///  - the version, as a `u8`
+///  - the onward route and the return route, each encoded as a variable length number of
+///    addresses, followed by the addresses. An address is encoded as its transport type (`u8`)
+///    followed by its variable length data
+///  - the payload, as variable length data
+///
+/// All the lengths are encoded as variable length integers (ULEB128), since version 1.
+///
+/// With the `tracing_context` feature, the payload is followed by a tracing section: a `u8` set
+/// to `1` when a tracing context is present, followed by the context as a variable length string.
+/// Nodes built without that feature ignore any bytes following the payload.
+///
+/// # Wire stability
+///
+/// This encoding is shared by all the nodes reachable over a transport and must not change
+/// for a given version. The canonical encodings are checked against the fixtures of
+/// `ockam_core/tests/fixtures/wire`.
+///
 #[derive(Debug, Clone, Eq, PartialEq, Message)]
 pub struct TransportMessage {
     /// The transport protocol version.
@@ -70,6 +92,8 @@ impl TransportMessage {
     /// message that would leave the same node for example.
     ///
     /// We can still navigate the two created traces as one thanks to their link.
+    ///
+    /// The first span of the new trace records the onward route of the message, as hop 0.
     #[cfg(feature = "std")]
     pub fn start_new_tracing_context(self, _tracing_context: OpenTelemetryContext) -> Self {
         cfg_if! {
@@ -77,7 +101,11 @@ impl TransportMessage {
                 // start a new trace for this transport message, and link it to the previous trace, via the current tracing context
                 let tracer = global::tracer(OCKAM_TRACER_NAME);
                 let span_builder = SpanBuilder::from_name("TransportMessage::start_trace")
-                      .with_links(vec![Link::new(_tracing_context.extract().span().span_context().clone(), vec![])]);
+                      .with_links(vec![Link::new(_tracing_context.extract().span().span_context().clone(), vec![])])
+                      .with_attributes(vec![
+                          KeyValue::new(ONWARD_ROUTE.clone(), self.onward_route.to_string()),
+                          KeyValue::new(HOP.clone(), 0_i64),
+                      ]);
                 let span = tracer.build_with_context(span_builder, &Context::default());
                 let cx = Context::current_with_span(span);
 
@@ -87,7 +115,7 @@ impl TransportMessage {
                 let _ = tracer.build_with_context(span_builder, &_tracing_context.extract());
 
                 // create the new opentelemetry context
-                let tracing_context = OpenTelemetryContext::inject(&cx);
+                let tracing_context = OpenTelemetryContext::inject(&cx).with_hop(0);
 
                 Self {
                     tracing_context: Some(tracing_context.to_string()),
@@ -99,6 +127,49 @@ impl TransportMessage {
         }
     }
 
+    /// Return a TransportMessage with a new onward route, once a transport has modified
+    /// the route of the message in order to forward it.
+    ///
+    /// With the `tracing_context` feature, a child span of the current tracing context of the message
+    /// records the node handling the hop, the new onward route and the index of the hop.
+    /// That span belongs to the trace started by [`TransportMessage::start_new_tracing_context`], so
+    /// the link to the previous trace is kept.
+    pub fn update_tracing_for_hop(self, _node_name: &str, onward_route: Route) -> Self {
+        cfg_if! {
+            if #[cfg(feature = "tracing_context")] {
+                let tracing_context = self
+                    .tracing_context
+                    .as_deref()
+                    .and_then(|tracing_context| OpenTelemetryContext::try_from(tracing_context).ok())
+                    .unwrap_or_else(OpenTelemetryContext::current);
+                let hop = tracing_context.hop() + 1;
+                let parent_cx = tracing_context.extract();
+
+                let tracer = global::tracer(OCKAM_TRACER_NAME);
+                let span_builder = SpanBuilder::from_name("TransportMessage::hop")
+                    .with_attributes(vec![
+                        KeyValue::new(HOP_NODE_NAME.clone(), _node_name.to_string()),
+                        KeyValue::new(ONWARD_ROUTE.clone(), onward_route.to_string()),
+                        KeyValue::new(HOP.clone(), hop as i64),
+                    ]);
+                let span = tracer.build_with_context(span_builder, &parent_cx);
+                let cx = parent_cx.with_span(span);
+                let tracing_context = OpenTelemetryContext::inject(&cx).with_hop(hop);
+
+                Self {
+                    onward_route,
+                    tracing_context: Some(tracing_context.to_string()),
+                    ..self
+                }
+            } else {
+                Self {
+                    onward_route,
+                    ..self
+                }
+            }
+        }
+    }
+
     /// Return the tracing context
     #[cfg(feature = "tracing_context")]
     pub fn tracing_context(&self) -> OpenTelemetryContext {
@@ -129,8 +200,8 @@ impl Encodable for TransportMessage {
     fn encode(self) -> crate::Result<Encoded> {
         cfg_if! {
             if #[cfg(feature = "tracing_context")] {
-                let tracing = if let Some(tracing_context) = self.tracing_context {
-                    1 + crate::bare::size_of_slice(tracing_context)
+                let tracing = if let Some(tracing_context) = &self.tracing_context {
+                    1 + crate::bare::size_of_str(tracing_context)
                 } else {
                     1
                 };
@@ -152,7 +223,7 @@ impl Encodable for TransportMessage {
         cfg_if! {
             if #[cfg(feature = "tracing_context")] {
                 if let Some(tracing_context) = self.tracing_context {
-                    vec.push(1);
+                    encoded.push(1);
                     crate::bare::write_str(&mut encoded, &tracing_context);
                 }
                 else {
@@ -189,7 +260,7 @@ impl TransportMessage {
         cfg_if! {
             if #[cfg(feature = "tracing_context")] {
                 // ignore if missing, keep compatibility with older messages
-                let present = slice.get(index).unwrap_or(0);
+                let present = slice.get(index).copied().unwrap_or(0);
                 index += 1;
                 let tracing_context = if present == 1 {
                     crate::bare::read_str(slice, &mut index).map(|s| s.to_string())
@@ -219,7 +290,8 @@ impl TransportMessage {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{route, Decodable, Encodable};
+    use crate::{route, Address, Decodable, Encodable};
+    use proptest::prelude::*;
     use serde::Serialize;
 
     #[derive(Debug, Clone, Eq, PartialEq, Serialize)]
@@ -239,7 +311,8 @@ mod test {
 
     #[test]
     fn encode_decode_transport_message() {
-        let msg = TransportMessage::v1(
+        #[allow(unused_mut)]
+        let mut msg = TransportMessage::v1(
             route!["onward", "route!"],
             route!["return", "route!"],
             "hello".as_bytes().to_vec(),
@@ -255,6 +328,73 @@ mod test {
         assert_eq!(msg, decoded);
     }
 
+    #[test]
+    fn can_decode_v1_bytes() {
+        #[rustfmt::skip]
+        let encoded = vec![
+            // version
+            1,
+            // onward route: 1 address, transport type 0, "a"
+            1, 0, 1, b'a',
+            // return route: 2 addresses, transport type 1, "bc", transport type 0, ""
+            2, 1, 2, b'b', b'c', 0, 0,
+            // payload
+            5, b'h', b'e', b'l', b'l', b'o',
+        ];
+
+        let decoded = TransportMessage::decode(&encoded).unwrap();
+        assert_eq!(decoded.version, 1);
+        assert_eq!(decoded.onward_route, route!["a"]);
+        assert_eq!(
+            decoded.return_route,
+            route![
+                Address::from((crate::TransportType::new(1), b"bc".to_vec())),
+                Address::from((crate::TransportType::new(0), Vec::new()))
+            ]
+        );
+        assert_eq!(decoded.payload, b"hello".to_vec());
+
+        // the same bytes are produced when encoding the message again
+        let msg = TransportMessage::v1(decoded.onward_route, decoded.return_route, decoded.payload);
+        assert!(msg.encode().unwrap().starts_with(&encoded));
+    }
+
+    #[test]
+    fn truncated_messages_are_rejected() {
+        let msg = TransportMessage::v1(
+            route!["onward", "route!"],
+            route!["return", "route!"],
+            "hello".as_bytes().to_vec(),
+        );
+        let encoded = msg.encode().unwrap();
+        // the tracing context is optional, a message without it is not truncated
+        let tracing = if cfg!(feature = "tracing_context") {
+            1
+        } else {
+            0
+        };
+        for length in 0..encoded.len() - tracing {
+            assert!(TransportMessage::decode(&encoded[..length]).is_err());
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn transport_message_round_trip(
+            onward in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..16), 0..4),
+            return_route in proptest::collection::vec(proptest::collection::vec(any::<u8>(), 0..16), 0..4),
+            payload in proptest::collection::vec(any::<u8>(), 0..2048),
+        ) {
+            let msg = TransportMessage::v1(
+                Route::create(onward.into_iter().map(Address::from).collect()),
+                Route::create(return_route.into_iter().map(Address::from).collect()),
+                payload,
+            );
+            let encoded = msg.clone().encode().unwrap();
+            prop_assert_eq!(TransportMessage::decode(&encoded).unwrap(), msg);
+        }
+    }
+
     #[test]
     fn can_decode_older_serialized_version() {
         let msg = TransportMessageWithoutTracing {
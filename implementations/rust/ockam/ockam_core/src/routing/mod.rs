@@ -14,7 +14,9 @@ pub use message::*;
 
 mod macros;
 
+#[cfg(feature = "routing-full")]
 mod mailbox;
+#[cfg(feature = "routing-full")]
 pub use mailbox::*;
 
 mod transport_type;
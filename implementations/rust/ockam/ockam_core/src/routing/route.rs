@@ -1,12 +1,19 @@
 use crate::{
     compat::{collections::VecDeque, string::String, vec::Vec},
-    Address, Result, RouteError, TransportType,
+    Address, Result, RouteError, RouteParseError, RouteParseErrorKind, TransportType,
 };
 use core::fmt::{self, Display};
 use minicbor::{Decode, Encode};
 use serde::{Deserialize, Serialize};
 
 /// A full route to a peer.
+///
+/// # Wire stability
+///
+/// Routes are sent to other nodes in transport messages and in the messages of secure channels.
+/// Both their BARE encoding (a variable length number of addresses followed by the addresses)
+/// and their CBOR encoding (an array of addresses) are stable and are checked against the
+/// fixtures of `ockam_core/tests/fixtures/wire`.
 #[derive(Serialize, Deserialize, Decode, Encode, Debug, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
 #[rustfmt::skip]
 #[cbor(transparent)]
@@ -268,6 +275,48 @@ impl Display for Route {
     }
 }
 
+impl core::str::FromStr for Route {
+    type Err = RouteParseError;
+
+    /// Parse a route from its displayed form, for example `1#127.0.0.1:4000 => 0#bob`.
+    ///
+    /// Addresses are separated by `=>` and their special characters must be escaped,
+    /// as described in the [`Address`] documentation. An empty string is an empty route.
+    fn from_str(s: &str) -> Result<Route, Self::Err> {
+        let mut route = Route::new();
+        if s.trim().is_empty() {
+            return Ok(route.into());
+        }
+
+        let mut segment_start = 0;
+        for (index, segment) in s.split("=>").enumerate() {
+            let trimmed = segment.trim_start();
+            let offset = segment_start + segment.len() - trimmed.len();
+            let trimmed = trimmed.trim_end();
+            segment_start += segment.len() + "=>".len();
+
+            if trimmed.is_empty() {
+                return Err(RouteParseError::new(
+                    RouteParseErrorKind::EmptySegment,
+                    index,
+                    trimmed,
+                    offset,
+                ));
+            }
+            let address = trimmed.parse::<Address>().map_err(|e| {
+                RouteParseError::new(
+                    RouteParseErrorKind::InvalidAddress(e.kind().clone()),
+                    index,
+                    trimmed,
+                    offset + e.offset(),
+                )
+            })?;
+            route = route.append(address);
+        }
+        Ok(route.into())
+    }
+}
+
 /// Convert a `RouteBuilder` into a `Route`.
 impl From<RouteBuilder<'_>> for Route {
     fn from(RouteBuilder { ref inner, .. }: RouteBuilder) -> Self {
@@ -502,7 +551,10 @@ impl Route {
 
     pub(crate) fn manual_decode(slice: &[u8], index: &mut usize) -> Option<Route> {
         let number_of_addresses = crate::bare::read_variable_length_integer(slice, index)?;
-        let mut addresses = VecDeque::with_capacity(number_of_addresses as usize);
+        // each address takes at least 2 bytes, don't trust the decoded number for the allocation
+        let max_addresses = slice.len().saturating_sub(*index) / 2;
+        let mut addresses =
+            VecDeque::with_capacity((number_of_addresses as usize).min(max_addresses));
 
         for _ in 0..number_of_addresses {
             let addr = Address::manually_decode(slice, index)?;
@@ -515,7 +567,11 @@ impl Route {
 
 #[cfg(test)]
 mod tests {
-    use crate::{route, Address, Encodable, Error, Route};
+    use crate::{
+        route, Address, AddressParseErrorKind, Encodable, Error, Route, RouteParseErrorKind,
+        TransportType,
+    };
+    use proptest::prelude::*;
 
     #[test]
     fn encode_and_maually_decode_route() {
@@ -613,4 +669,72 @@ mod tests {
         assert!(matches!(r.contains_route(&route!["a", "c"]), Ok(false)));
         assert!(matches!(r.contains_route(&route!["x"]), Ok(false)));
     }
+
+    #[test]
+    fn test_route_from_str() {
+        let route: Route = "1#127.0.0.1:4000 => 0#bob".parse().unwrap();
+        assert_eq!(
+            route,
+            route![Address::new(TransportType::new(1), "127.0.0.1:4000"), "bob"]
+        );
+
+        let route: Route = " alice=>0#my%20worker%23%3D> ".parse().unwrap();
+        assert_eq!(route, route!["alice", "my worker#=>"]);
+        assert_eq!(route.to_string(), "0#alice => 0#my%20worker%23%3D>");
+
+        let route: Route = "".parse().unwrap();
+        assert_eq!(route, route![]);
+    }
+
+    #[test]
+    fn test_route_from_str_errors() {
+        let error = "0#alice => => 0#bob".parse::<Route>().unwrap_err();
+        assert_eq!(error.kind(), &RouteParseErrorKind::EmptySegment);
+        assert_eq!(error.segment_index(), 1);
+        assert_eq!(error.offset(), 11);
+
+        let error = "0#alice => 0#b%2Gob".parse::<Route>().unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &RouteParseErrorKind::InvalidAddress(AddressParseErrorKind::InvalidEscape)
+        );
+        assert_eq!(error.segment_index(), 1);
+        assert_eq!(error.segment(), "0#b%2Gob");
+        assert_eq!(error.offset(), 14);
+        assert_eq!(
+            error.to_string(),
+            "Invalid route segment 1 '0#b%2Gob' at offset 14: \
+            Invalid address string: '%' must be followed by two hexadecimal digits"
+        );
+
+        let error = "0#alice => 0#b#ob".parse::<Route>().unwrap_err();
+        assert_eq!(
+            error.kind(),
+            &RouteParseErrorKind::InvalidAddress(AddressParseErrorKind::MultipleSep)
+        );
+        assert_eq!(error.offset(), 14);
+    }
+
+    fn arbitrary_address() -> impl Strategy<Value = Address> {
+        (
+            any::<u8>(),
+            prop_oneof![
+                "\\PC*".prop_map(|s| s.into_bytes()),
+                "[ =>#%a-z]*".prop_map(|s| s.into_bytes()),
+                proptest::collection::vec(any::<u8>(), 0..32),
+            ],
+        )
+            .prop_map(|(tt, data)| Address::from((TransportType::new(tt), data)))
+    }
+
+    proptest! {
+        #[test]
+        fn test_route_display_from_str_round_trip(
+            addresses in proptest::collection::vec(arbitrary_address(), 0..8)
+        ) {
+            let route = Route::create(addresses);
+            let parsed: Route = route.to_string().parse().unwrap();
+            prop_assert_eq!(parsed, route);
+        }
+    }
 }
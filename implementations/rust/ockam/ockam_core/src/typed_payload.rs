@@ -0,0 +1,284 @@
+use crate::compat::boxed::Box;
+use crate::compat::collections::BTreeMap;
+use crate::compat::string::{String, ToString};
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{Decodable, Encodable, Error, Message, Result};
+use core::fmt::{self, Display, Formatter};
+use serde::{Deserialize, Serialize};
+
+/// A message type identified by a schema and a version.
+///
+/// Workers exchanging a [`TypedMessage`] wrapped in a [`TypedPayload`] can detect
+/// that they don't compile the same version of the message type, instead of failing
+/// with an opaque decoding error.
+pub trait TypedMessage: Message {
+    /// Identifier of the schema, shared by all the versions of the message type
+    const SCHEMA: &'static str;
+    /// Version of the schema encoded by this message type
+    const VERSION: u32;
+}
+
+/// A self-describing envelope: the encoded body of a [`TypedMessage`],
+/// prefixed with its schema identifier and version.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, crate::Message)]
+pub struct TypedPayload {
+    schema: String,
+    version: u32,
+    body: Vec<u8>,
+}
+
+impl TypedPayload {
+    /// Encode a message and wrap it with its schema identifier and version
+    pub fn new<M: TypedMessage>(msg: M) -> Result<Self> {
+        Ok(Self {
+            schema: M::SCHEMA.to_string(),
+            version: M::VERSION,
+            body: msg.encode()?,
+        })
+    }
+
+    /// Schema identifier of the wrapped message
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    /// Schema version of the wrapped message
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Encoded body of the wrapped message
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Decode the wrapped message if it has the schema and version of `M`
+    pub fn decode_typed<M: TypedMessage>(self) -> Result<M> {
+        self.decode_typed_with(&PayloadMigrationRegistry::default())
+    }
+
+    /// Decode the wrapped message, migrating it first if it was encoded
+    /// with an older version of the schema of `M`
+    pub fn decode_typed_with<M: TypedMessage>(
+        self,
+        migrations: &dyn PayloadMigrations,
+    ) -> Result<M> {
+        let mismatch = || SchemaMismatch::new::<M>(&self.schema, self.version);
+        if self.schema != M::SCHEMA || self.version > M::VERSION {
+            return Err(mismatch().into());
+        }
+
+        let mut body = self.body.clone();
+        for version in self.version..M::VERSION {
+            body = match migrations.migrate(&self.schema, version, &body) {
+                Some(migrated) => migrated?,
+                None => return Err(mismatch().into()),
+            };
+        }
+        M::decode(&body)
+    }
+}
+
+/// Error returned when a [`TypedPayload`] doesn't contain the expected message type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaMismatch {
+    /// Schema identifier of the expected message type
+    pub expected_schema: String,
+    /// Schema version of the expected message type
+    pub expected_version: u32,
+    /// Schema identifier of the received payload
+    pub received_schema: String,
+    /// Schema version of the received payload
+    pub received_version: u32,
+}
+
+impl SchemaMismatch {
+    fn new<M: TypedMessage>(received_schema: &str, received_version: u32) -> Self {
+        Self {
+            expected_schema: M::SCHEMA.to_string(),
+            expected_version: M::VERSION,
+            received_schema: received_schema.to_string(),
+            received_version,
+        }
+    }
+}
+
+impl Display for SchemaMismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "expected a message with the schema {} v{}, received {} v{}",
+            self.expected_schema,
+            self.expected_version,
+            self.received_schema,
+            self.received_version
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchemaMismatch {}
+
+impl From<SchemaMismatch> for Error {
+    #[track_caller]
+    fn from(e: SchemaMismatch) -> Self {
+        Error::new(Origin::Core, Kind::Invalid, e)
+    }
+}
+
+/// Migrations of payloads encoded with older versions of a schema
+pub trait PayloadMigrations: Send + Sync {
+    /// Migrate the body of a payload from the given version of a schema to the next one.
+    /// Return `None` if there is no migration for that schema and version
+    fn migrate(&self, schema: &str, from_version: u32, body: &[u8]) -> Option<Result<Vec<u8>>>;
+}
+
+/// Function migrating an encoded body to the next version of its schema
+pub type PayloadMigration = Box<dyn Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync>;
+
+/// [`PayloadMigrations`] registered by an application, for each schema and version
+#[derive(Default)]
+pub struct PayloadMigrationRegistry {
+    migrations: BTreeMap<(String, u32), PayloadMigration>,
+}
+
+impl PayloadMigrationRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the migration of a schema from a version to the next one
+    pub fn register(
+        &mut self,
+        schema: &str,
+        from_version: u32,
+        migration: impl Fn(&[u8]) -> Result<Vec<u8>> + Send + Sync + 'static,
+    ) {
+        self.migrations
+            .insert((schema.to_string(), from_version), Box::new(migration));
+    }
+
+    /// Register the migration of a message type from the previous version of its schema.
+    /// The migration decodes the old message type and converts it to the new one
+    pub fn register_message<Old, New>(
+        &mut self,
+        migration: impl Fn(Old) -> New + Send + Sync + 'static,
+    ) where
+        Old: TypedMessage,
+        New: TypedMessage,
+    {
+        self.register(New::SCHEMA, Old::VERSION, move |body| {
+            migration(Old::decode(body)?).encode()
+        })
+    }
+}
+
+impl PayloadMigrations for PayloadMigrationRegistry {
+    fn migrate(&self, schema: &str, from_version: u32, body: &[u8]) -> Option<Result<Vec<u8>>> {
+        self.migrations
+            .get(&(schema.to_string(), from_version))
+            .map(|migration| migration(body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, crate::Message)]
+    struct GreetingV1 {
+        name: String,
+    }
+
+    impl TypedMessage for GreetingV1 {
+        const SCHEMA: &'static str = "greeting";
+        const VERSION: u32 = 1;
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, crate::Message)]
+    struct GreetingV2 {
+        name: String,
+        polite: bool,
+    }
+
+    impl TypedMessage for GreetingV2 {
+        const SCHEMA: &'static str = "greeting";
+        const VERSION: u32 = 2;
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, crate::Message)]
+    struct Farewell(String);
+
+    impl TypedMessage for Farewell {
+        const SCHEMA: &'static str = "farewell";
+        const VERSION: u32 = 1;
+    }
+
+    fn greeting() -> GreetingV2 {
+        GreetingV2 {
+            name: "alice".to_string(),
+            polite: true,
+        }
+    }
+
+    fn assert_mismatch(error: Error, expected: &str) {
+        assert_eq!(error.code().kind, Kind::Invalid);
+        assert!(error.to_string().contains(expected), "{error}");
+    }
+
+    #[test]
+    fn a_matching_payload_is_decoded() -> Result<()> {
+        let payload = TypedPayload::new(greeting())?;
+        assert_eq!(payload.schema(), "greeting");
+        assert_eq!(payload.version(), 2);
+
+        let payload = TypedPayload::decode(&payload.encode()?)?;
+        assert_eq!(payload.decode_typed::<GreetingV2>()?, greeting());
+        Ok(())
+    }
+
+    #[test]
+    fn a_payload_with_another_schema_is_rejected() -> Result<()> {
+        let payload = TypedPayload::new(Farewell("bob".to_string()))?;
+        let error = payload.decode_typed::<GreetingV2>().unwrap_err();
+        assert_mismatch(
+            error,
+            "expected a message with the schema greeting v2, received farewell v1",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn a_payload_with_another_version_is_rejected_without_migration() -> Result<()> {
+        let old = TypedPayload::new(GreetingV1 {
+            name: "alice".to_string(),
+        })?;
+        let error = old.decode_typed::<GreetingV2>().unwrap_err();
+        assert_mismatch(error, "schema greeting v2, received greeting v1");
+
+        let new = TypedPayload::new(greeting())?;
+        let error = new.decode_typed::<GreetingV1>().unwrap_err();
+        assert_mismatch(error, "schema greeting v1, received greeting v2");
+        Ok(())
+    }
+
+    #[test]
+    fn an_older_payload_is_migrated() -> Result<()> {
+        let mut migrations = PayloadMigrationRegistry::new();
+        migrations.register_message(|old: GreetingV1| GreetingV2 {
+            name: old.name,
+            polite: true,
+        });
+
+        let old = TypedPayload::new(GreetingV1 {
+            name: "alice".to_string(),
+        })?;
+        assert_eq!(
+            old.decode_typed_with::<GreetingV2>(&migrations)?,
+            greeting()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+//! Golden fixtures for the messages exchanged between nodes.
+//!
+//! The files in `tests/fixtures/wire` contain the hex-encoded canonical encoding of:
+//!
+//!  - a v1 [`TransportMessage`], with and without a tracing section
+//!  - a [`Route`] containing addresses of various transport types, encoded with BARE and with CBOR
+//!
+//! These encodings are wire-stable: the current code must decode the fixtures and produce
+//! byte-identical encodings. Other types, like `LocalMessage`, never leave a node and are internal.
+//!
+//! If a format is changed on purpose, the fixtures can be regenerated with:
+//!
+//! ```sh
+//! OCKAM_REGENERATE_WIRE_FIXTURES=1 cargo test -p ockam_core --test wire_compatibility -- --ignored
+//! ```
+//!
+//! The regeneration is refused on CI, so that a fixture can only be changed by a reviewed commit.
+
+use ockam_core::{route, Address, Decodable, Encodable, Route, TransportMessage, TransportType};
+use std::path::PathBuf;
+
+const REGENERATE_ENV_VAR: &str = "OCKAM_REGENERATE_WIRE_FIXTURES";
+
+#[test]
+fn transport_message_v1() {
+    let fixture = read_fixture("transport_message_v1");
+    let decoded = TransportMessage::decode(&fixture).unwrap();
+    assert_eq!(decoded, transport_message());
+
+    // with the tracing_context feature, an empty tracing section is appended to the payload
+    let mut expected = fixture;
+    if cfg!(feature = "tracing_context") {
+        expected.push(0);
+    }
+    assert_eq!(transport_message().encode().unwrap(), expected);
+}
+
+#[test]
+fn transport_message_v1_with_tracing_context() {
+    let fixture = read_fixture("transport_message_v1_tracing_context");
+    let decoded = TransportMessage::decode(&fixture).unwrap();
+
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "tracing_context")] {
+            assert_eq!(decoded.tracing_context.as_deref(), Some(TRACING_CONTEXT));
+            assert_eq!(decoded.encode().unwrap(), fixture);
+        } else {
+            // the tracing section is ignored by nodes which don't support it
+            assert_eq!(decoded, transport_message());
+            assert!(fixture.starts_with(&decoded.encode().unwrap()));
+        }
+    }
+}
+
+#[test]
+fn route_bare() {
+    let fixture = read_fixture("route.bare");
+    assert_eq!(Route::decode(&fixture).unwrap(), test_route());
+    assert_eq!(test_route().encode().unwrap(), fixture);
+}
+
+#[test]
+fn route_cbor() {
+    let fixture = read_fixture("route.cbor");
+    assert_eq!(minicbor::decode::<Route>(&fixture).unwrap(), test_route());
+    assert_eq!(minicbor::to_vec(test_route()).unwrap(), fixture);
+}
+
+#[test]
+#[ignore]
+fn regenerate_fixtures() {
+    check_regeneration_is_allowed();
+
+    let mut with_tracing_context = transport_message_v1_bytes();
+    with_tracing_context.push(1);
+    ockam_core::bare::write_str(&mut with_tracing_context, TRACING_CONTEXT);
+
+    write_fixture("transport_message_v1", &transport_message_v1_bytes());
+    write_fixture(
+        "transport_message_v1_tracing_context",
+        &with_tracing_context,
+    );
+    write_fixture("route.bare", &test_route().encode().unwrap());
+    write_fixture("route.cbor", &minicbor::to_vec(test_route()).unwrap());
+}
+
+/// Tracing context used in the fixture containing a tracing section
+const TRACING_CONTEXT: &str =
+    r#"{"traceparent":"00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"}"#;
+
+fn transport_message() -> TransportMessage {
+    TransportMessage::v1(
+        route![
+            Address::from((TransportType::new(1), b"127.0.0.1:4000".to_vec())),
+            "api"
+        ],
+        route!["app"],
+        b"hello".to_vec(),
+    )
+}
+
+/// Return the encoding of the test transport message, without any tracing section
+fn transport_message_v1_bytes() -> Vec<u8> {
+    let mut encoded = transport_message().encode().unwrap();
+    if cfg!(feature = "tracing_context") {
+        encoded.pop();
+    }
+    encoded
+}
+
+/// Route with a local address, a TCP address, a non UTF-8 address and an address
+/// whose length is encoded with more than one byte
+fn test_route() -> Route {
+    route![
+        "app",
+        Address::from((TransportType::new(1), b"127.0.0.1:4000".to_vec())),
+        Address::from((TransportType::new(5), vec![0xff, 0x00, 0x80])),
+        Address::from((TransportType::new(2), (0..200).collect::<Vec<u8>>()))
+    ]
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/wire")
+        .join(format!("{name}.hex"))
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = fixture_path(name);
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read the fixture {}: {e}", path.display()));
+    hex::decode(content.trim())
+        .unwrap_or_else(|e| panic!("the fixture {} is not valid hex: {e}", path.display()))
+}
+
+fn write_fixture(name: &str, bytes: &[u8]) {
+    std::fs::write(fixture_path(name), format!("{}\n", hex::encode(bytes))).unwrap();
+}
+
+fn check_regeneration_is_allowed() {
+    if std::env::var(REGENERATE_ENV_VAR).as_deref() != Ok("1") {
+        panic!("set {REGENERATE_ENV_VAR}=1 to regenerate the wire fixtures");
+    }
+    if std::env::var_os("CI").is_some() {
+        panic!("the wire fixtures must not be regenerated on CI");
+    }
+}
@@ -2,9 +2,10 @@ use ockam_core::compat::sync::Arc;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
 use crate::models::{CredentialData, PurposeKeyAttestationData};
+use crate::utils::TimeSource;
 use crate::{
     CredentialsCreation, CredentialsVerification, IdentitiesCreation, IdentityAttributesRepository,
-    PurposeKeys,
+    PurposeKeys, RevocationListRepository,
 };
 
 /// Structure with both [`CredentialData`] and [`PurposeKeyAttestationData`] that we get
@@ -24,6 +25,8 @@ pub struct Credentials {
     purpose_keys: Arc<PurposeKeys>,
     identities_creation: Arc<IdentitiesCreation>,
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    time_source: Arc<dyn TimeSource>,
 }
 
 impl Credentials {
@@ -34,6 +37,8 @@ impl Credentials {
         purpose_keys: Arc<PurposeKeys>,
         identities_creation: Arc<IdentitiesCreation>,
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        time_source: Arc<dyn TimeSource>,
     ) -> Self {
         Self {
             credential_vault,
@@ -41,6 +46,8 @@ impl Credentials {
             purpose_keys,
             identities_creation,
             identity_attributes_repository,
+            revocation_list_repository,
+            time_source,
         }
     }
 
@@ -56,6 +63,7 @@ impl Credentials {
             self.credential_vault.clone(),
             self.verifying_vault.clone(),
             self.identities_creation.identities_verification(),
+            self.time_source.clone(),
         ))
     }
 
@@ -65,6 +73,8 @@ impl Credentials {
             self.purpose_keys.purpose_keys_verification(),
             self.verifying_vault.clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
+            self.time_source.clone(),
         ))
     }
 }
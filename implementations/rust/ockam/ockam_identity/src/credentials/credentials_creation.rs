@@ -5,7 +5,7 @@ use ockam_core::Result;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
 use crate::models::{Attributes, Credential, CredentialAndPurposeKey, CredentialData, Identifier};
-use crate::utils::now;
+use crate::utils::TimeSource;
 use crate::{IdentitiesVerification, PurposeKeyCreation, TimestampInSeconds};
 
 /// Service for managing [`Credential`]s
@@ -14,6 +14,7 @@ pub struct CredentialsCreation {
     credential_vault: Arc<dyn VaultForSigning>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_verification: Arc<IdentitiesVerification>,
+    time_source: Arc<dyn TimeSource>,
 }
 
 impl CredentialsCreation {
@@ -23,12 +24,14 @@ impl CredentialsCreation {
         credential_vault: Arc<dyn VaultForSigning>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_verification: Arc<IdentitiesVerification>,
+        time_source: Arc<dyn TimeSource>,
     ) -> Self {
         Self {
             purpose_keys_creation,
             verifying_vault,
             credential_vault,
             identities_verification,
+            time_source,
         }
     }
 }
@@ -50,7 +53,7 @@ impl CredentialsCreation {
 
         let subject_identity = self.identities_verification.get_identity(subject).await?;
 
-        let created_at = now()?;
+        let created_at = self.time_source.now()?;
         let expires_at = created_at + TimestampInSeconds(ttl.as_secs());
 
         let credential_data = CredentialData {
@@ -10,10 +10,10 @@ use crate::identities::AttributesEntry;
 use crate::models::{
     CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey, VersionedData,
 };
-use crate::utils::now;
+use crate::utils::{now, TimeSource};
 use crate::{
     CredentialAndPurposeKeyData, IdentityAttributesRepository, IdentityError,
-    PurposeKeyVerification, TimestampInSeconds,
+    PurposeKeyVerification, RevocationList, RevocationListRepository, TimestampInSeconds,
 };
 
 /// We allow Credentials to be created in the future related to this machine's time due to
@@ -25,6 +25,8 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    time_source: Arc<dyn TimeSource>,
 }
 
 impl CredentialsVerification {
@@ -33,11 +35,15 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        time_source: Arc<dyn TimeSource>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_attributes_repository,
+            revocation_list_repository,
+            time_source,
         }
     }
 }
@@ -50,12 +56,13 @@ impl CredentialsVerification {
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
-        Self::verify_credential_static(
+        Self::verify_credential_at(
             self.purpose_keys_verification.clone(),
             self.verifying_vault.clone(),
             expected_subject,
             authorities,
             credential_and_purpose_key,
+            self.time_source.now()?,
         )
         .await
     }
@@ -67,12 +74,44 @@ impl CredentialsVerification {
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
+    ) -> Result<CredentialAndPurposeKeyData> {
+        Self::verify_credential_at(
+            purpose_keys_verification,
+            verifying_vault,
+            expected_subject,
+            authorities,
+            credential_and_purpose_key,
+            now()?,
+        )
+        .await
+    }
+
+    /// Return true if the validity period of a [`Credential`] contains the given time,
+    /// taking into account the maximum allowed time drift
+    pub fn is_valid_at(credential_data: &CredentialData, now: TimestampInSeconds) -> bool {
+        let created_in_the_future = credential_data.created_at > now
+            && credential_data.created_at - now > MAX_ALLOWED_TIME_DRIFT;
+        let expired = credential_data.expires_at < now;
+        !created_in_the_future && !expired
+    }
+
+    /// Verify a [`Credential`] at a given time.
+    ///
+    /// The validity period of the purpose key attestation is checked against that time as well.
+    pub async fn verify_credential_at(
+        purpose_keys_verification: Arc<PurposeKeyVerification>,
+        verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
+        expected_subject: Option<&Identifier>,
+        authorities: &[Identifier],
+        credential_and_purpose_key: &CredentialAndPurposeKey,
+        now: TimestampInSeconds,
     ) -> Result<CredentialAndPurposeKeyData> {
         debug!("verify purpose key attestation");
         let purpose_key_data = purpose_keys_verification
-            .verify_purpose_key_attestation(
+            .verify_purpose_key_attestation_at(
                 None,
                 &credential_and_purpose_key.purpose_key_attestation,
+                now,
             )
             .await?;
 
@@ -149,17 +188,8 @@ impl CredentialsVerification {
             return Err(IdentityError::CredentialVerificationFailed)?;
         }
 
-        let now = now()?;
-
-        if credential_data.created_at > now
-            && credential_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
-        {
-            // Credential can't be created in the future
-            return Err(IdentityError::CredentialVerificationFailed)?;
-        }
-
-        if credential_data.expires_at < now {
-            // Credential expired
+        if !Self::is_valid_at(&credential_data, now) {
+            // Credential can't be created in the future and must not be expired
             return Err(IdentityError::CredentialVerificationFailed)?;
         }
 
@@ -200,6 +230,18 @@ impl CredentialsVerification {
             )
             .await?;
 
+        let issuer = &credential_data.purpose_key_data.subject;
+        if let Some(revocation_list) = self
+            .revocation_list_repository
+            .get_revocation_list(issuer)
+            .await?
+        {
+            if revocation_list.is_revoked(subject, credential_data.credential_data.created_at) {
+                warn!("the credential of {subject} has been revoked by {issuer}");
+                return Err(IdentityError::CredentialRevoked)?;
+            }
+        }
+
         let map = credential_data.credential_data.subject_attributes.map;
         let map: BTreeMap<_, _> = map
             .into_iter()
@@ -211,7 +253,7 @@ impl CredentialsVerification {
                 subject,
                 AttributesEntry::new(
                     map,
-                    now()?,
+                    self.time_source.now()?,
                     Some(credential_data.credential_data.expires_at),
                     Some(credential_data.purpose_key_data.subject),
                 ),
@@ -220,4 +262,22 @@ impl CredentialsVerification {
 
         Ok(())
     }
+
+    /// Receive the revocation list signed by an authority: verify it and store it
+    /// if it is more recent than the last list received from that authority.
+    /// Return true if the list has been stored
+    pub async fn receive_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: &CredentialAndPurposeKey,
+    ) -> Result<bool> {
+        let data = self
+            .verify_credential(Some(authority), &[authority.clone()], revocation_list)
+            .await?;
+        let revocation_list =
+            RevocationList::from_attributes(&data.credential_data.subject_attributes)?;
+        self.revocation_list_repository
+            .store_revocation_list(authority, &revocation_list)
+            .await
+    }
 }
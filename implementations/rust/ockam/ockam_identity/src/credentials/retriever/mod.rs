@@ -3,8 +3,12 @@ mod cache_retriever;
 mod credential_retriever;
 mod memory_retriever;
 mod remote_retriever;
+#[cfg(feature = "test-support")]
+mod test_support;
 
 pub use cache_retriever::*;
 pub use credential_retriever::*;
 pub use memory_retriever::*;
 pub use remote_retriever::*;
+#[cfg(feature = "test-support")]
+pub use test_support::*;
@@ -0,0 +1,362 @@
+use core::sync::atomic::{AtomicBool, Ordering};
+use minicbor::{Decode, Encode};
+use tracing::{debug, error};
+
+use ockam_core::api::{Reply, Request};
+use ockam_core::compat::collections::{BTreeMap, BTreeSet};
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::time::Duration;
+use ockam_core::compat::vec::Vec;
+use ockam_core::{route, Address, Result};
+use ockam_node::api::Client;
+use ockam_node::compat::asynchronous::Mutex as AsyncMutex;
+use ockam_node::Context;
+use ockam_transport_core::Transport;
+
+use crate::utils::now;
+use crate::{
+    Identifier, RemoteCredentialRetriever, RemoteCredentialRetrieverInfo,
+    RemoteCredentialRetrieverTimingOptions, SecureChannel, SecureChannels, SecureClient,
+    TimestampInSeconds,
+};
+
+/// Connections to the credential issuers, shared by all the [`RemoteCredentialRetriever`]s
+/// created with the same [`SecureChannels`].
+///
+/// There is one connection per issuer identity and route, whatever the number of
+/// [`RemoteCredentialRetrieverCreator`](crate::RemoteCredentialRetrieverCreator)s using them.
+#[derive(Clone, Default)]
+pub struct CredentialIssuerConnections {
+    connections: Arc<Mutex<BTreeMap<(Identifier, String), Arc<CredentialIssuerConnection>>>>,
+}
+
+impl CredentialIssuerConnections {
+    /// Return the connection to an issuer, creating it if necessary
+    pub fn get_or_create(
+        &self,
+        info: &RemoteCredentialRetrieverInfo,
+        transport: Arc<dyn Transport>,
+    ) -> Arc<CredentialIssuerConnection> {
+        self.connections
+            .lock()
+            .unwrap()
+            .entry((info.issuer.clone(), info.route.to_string()))
+            .or_insert_with(|| Arc::new(CredentialIssuerConnection::new(info.clone(), transport)))
+            .clone()
+    }
+}
+
+/// Connection to a credential issuer, shared by the retrievers of all the subjects
+/// retrieving their credentials from that issuer.
+///
+///  - The requests made at the same time for the same subject go through the same secure
+///    channel. The secure channel is stopped once no request uses it anymore
+///  - The credentials are refreshed by a single background task, which wakes up at the earliest
+///    refresh time. The credentials which will have to be refreshed soon are refreshed in the
+///    same round, so that all the refreshes of a subject use the same secure channel
+///  - The revocation list of the issuer is only retrieved once per refresh interval
+///
+pub struct CredentialIssuerConnection {
+    info: RemoteCredentialRetrieverInfo,
+    transport: Arc<dyn Transport>,
+    /// Secure channels to the issuer, per subject
+    secure_channels: AsyncMutex<BTreeMap<Identifier, SharedSecureChannel>>,
+    refreshes: Mutex<ScheduledRefreshes>,
+    is_revocation_list_refreshed: AtomicBool,
+}
+
+/// Secure channel used by several requests
+#[derive(Default)]
+struct SharedSecureChannel {
+    secure_channel: Option<OpenSecureChannel>,
+    /// Number of requests, or refresh rounds, using the secure channel
+    users: usize,
+}
+
+struct OpenSecureChannel {
+    secure_channel: SecureChannel,
+    transport_address: Option<Address>,
+    secure_channels: Arc<SecureChannels>,
+}
+
+#[derive(Default)]
+struct ScheduledRefreshes {
+    /// Next refresh of each retriever, by address of the retriever context
+    retrievers: BTreeMap<Address, ScheduledRefresh>,
+    /// Time at which the refresh task wakes up, and the generation of that wake-up.
+    /// A wake-up is superseded when an earlier one is scheduled
+    wake_up: Option<(TimestampInSeconds, u64)>,
+    generation: u64,
+}
+
+struct ScheduledRefresh {
+    retriever: RemoteCredentialRetriever,
+    refresh_at: TimestampInSeconds,
+    /// The credential can be refreshed earlier, together with other credentials
+    earliest_refresh_at: TimestampInSeconds,
+    is_retry: bool,
+}
+
+impl CredentialIssuerConnection {
+    fn new(info: RemoteCredentialRetrieverInfo, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            info,
+            transport,
+            secure_channels: Default::default(),
+            refreshes: Default::default(),
+            is_revocation_list_refreshed: AtomicBool::new(false),
+        }
+    }
+
+    /// Information about the issuer
+    pub fn info(&self) -> &RemoteCredentialRetrieverInfo {
+        &self.info
+    }
+
+    /// Send a request to the credential issuer service, using the secure channel of the subject
+    pub(super) async fn ask<T, R>(
+        &self,
+        ctx: &Context,
+        secure_channels: Arc<SecureChannels>,
+        subject: &Identifier,
+        timing_options: &RemoteCredentialRetrieverTimingOptions,
+        req: Request<T>,
+    ) -> Result<Reply<R>>
+    where
+        T: Encode<()>,
+        R: for<'a> Decode<'a, ()>,
+    {
+        let secure_channel = self
+            .acquire_secure_channel(ctx, secure_channels, subject, timing_options)
+            .await?;
+        let route = route![secure_channel, self.info.service_address.clone()];
+        let client = Client::new(&route, Some(timing_options.request_timeout));
+        let reply = client.ask(ctx, req).await;
+        self.release_secure_channel(ctx, subject).await;
+        // we delay the unwrapping of the reply to make sure that the secure channel is
+        // released first
+        reply
+    }
+
+    /// Return the secure channel of the subject, creating it if it is not used by another request
+    async fn acquire_secure_channel(
+        &self,
+        ctx: &Context,
+        secure_channels: Arc<SecureChannels>,
+        subject: &Identifier,
+        timing_options: &RemoteCredentialRetrieverTimingOptions,
+    ) -> Result<SecureChannel> {
+        let mut shared_secure_channels = self.secure_channels.lock().await;
+        let shared = shared_secure_channels.entry(subject.clone()).or_default();
+        shared.users += 1;
+        if let Some(open) = &shared.secure_channel {
+            return Ok(open.secure_channel.clone());
+        }
+
+        let client = SecureClient::new(
+            secure_channels.clone(),
+            None,
+            self.transport.clone(),
+            self.info.route.clone(),
+            &self.info.issuer,
+            subject,
+            timing_options.secure_channel_creation_timeout,
+            timing_options.request_timeout,
+        );
+        match client.create_secure_channel(ctx).await {
+            Ok((secure_channel, transport_address)) => {
+                debug!(
+                    "Created a secure channel to the credential issuer {} for {}",
+                    self.info.issuer, subject
+                );
+                shared.secure_channel = Some(OpenSecureChannel {
+                    secure_channel: secure_channel.clone(),
+                    transport_address,
+                    secure_channels,
+                });
+                Ok(secure_channel)
+            }
+            Err(e) => {
+                shared.users -= 1;
+                if shared.users == 0 {
+                    shared_secure_channels.remove(subject);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Keep the secure channel of the subject open until it is released, even if it is not
+    /// created yet
+    async fn reserve_secure_channel(&self, subject: &Identifier) {
+        self.secure_channels
+            .lock()
+            .await
+            .entry(subject.clone())
+            .or_default()
+            .users += 1;
+    }
+
+    /// Stop the secure channel of the subject if it is not used anymore
+    async fn release_secure_channel(&self, ctx: &Context, subject: &Identifier) {
+        let mut shared_secure_channels = self.secure_channels.lock().await;
+        let is_unused = match shared_secure_channels.get_mut(subject) {
+            Some(shared) => {
+                shared.users = shared.users.saturating_sub(1);
+                shared.users == 0
+            }
+            None => false,
+        };
+        if !is_unused {
+            return;
+        }
+
+        if let Some(SharedSecureChannel {
+            secure_channel: Some(open),
+            ..
+        }) = shared_secure_channels.remove(subject)
+        {
+            debug!(
+                "Stopping the secure channel to the credential issuer {} for {}",
+                self.info.issuer, subject
+            );
+            let _ = open
+                .secure_channels
+                .stop_secure_channel(ctx, open.secure_channel.encryptor_address())
+                .await;
+            if let Some(transport_address) = open.transport_address {
+                let _ = self.transport.disconnect(transport_address).await;
+            }
+        }
+    }
+}
+
+impl CredentialIssuerConnection {
+    /// Schedule the refresh of the credential of a retriever, replacing its previously
+    /// scheduled refresh
+    pub(super) fn schedule_refresh(
+        self: &Arc<Self>,
+        retriever: RemoteCredentialRetriever,
+        now: TimestampInSeconds,
+        refresh_in: Duration,
+        is_retry: bool,
+    ) {
+        let refresh_at = now + refresh_in;
+        // a retry is never made earlier than its backoff delay
+        let earliest_refresh_at = if is_retry {
+            refresh_at
+        } else {
+            TimestampInSeconds(
+                refresh_at
+                    .0
+                    .saturating_sub(retriever.timing_options.proactive_refresh_gap.0),
+            )
+        };
+        let ctx = retriever.ctx.clone();
+        self.refreshes.lock().unwrap().retrievers.insert(
+            retriever.ctx.address(),
+            ScheduledRefresh {
+                retriever,
+                refresh_at,
+                earliest_refresh_at,
+                is_retry,
+            },
+        );
+        self.wake_up_for_next_refresh(ctx);
+    }
+
+    /// Make sure that the refresh task wakes up at the time of the earliest scheduled refresh
+    fn wake_up_for_next_refresh(self: &Arc<Self>, ctx: Arc<Context>) {
+        let mut refreshes = self.refreshes.lock().unwrap();
+        let next_refresh_at = match refreshes.retrievers.values().map(|r| r.refresh_at).min() {
+            Some(next_refresh_at) => next_refresh_at,
+            None => return,
+        };
+        if let Some((wake_up_at, _)) = refreshes.wake_up {
+            if wake_up_at <= next_refresh_at {
+                return;
+            }
+        }
+        refreshes.generation += 1;
+        let generation = refreshes.generation;
+        refreshes.wake_up = Some((next_refresh_at, generation));
+        drop(refreshes);
+
+        let connection = self.clone();
+        ockam_node::spawn(async move {
+            ctx.sleep_long_until(*next_refresh_at).await;
+            if !connection.is_current_wake_up(next_refresh_at, generation) {
+                return;
+            }
+            connection.refresh_credentials(&ctx).await;
+            connection.wake_up_for_next_refresh(ctx);
+        });
+    }
+
+    /// Return true if the wake-up was not superseded by an earlier one.
+    /// In that case the refresh task is now considered awake
+    fn is_current_wake_up(&self, wake_up_at: TimestampInSeconds, generation: u64) -> bool {
+        let mut refreshes = self.refreshes.lock().unwrap();
+        if refreshes.wake_up != Some((wake_up_at, generation)) {
+            return false;
+        }
+        refreshes.wake_up = None;
+        true
+    }
+
+    /// Refresh all the credentials which can be refreshed now.
+    /// The secure channel of each subject stays open until all its credentials are refreshed
+    async fn refresh_credentials(&self, ctx: &Context) {
+        let now = match now() {
+            Ok(now) => now,
+            Err(e) => {
+                error!(
+                    "Cannot refresh the credentials from {}: {}",
+                    self.info.issuer, e
+                );
+                return;
+            }
+        };
+        let scheduled: Vec<ScheduledRefresh> = {
+            let mut refreshes = self.refreshes.lock().unwrap();
+            let addresses: Vec<Address> = refreshes
+                .retrievers
+                .iter()
+                .filter(|(_, r)| r.earliest_refresh_at <= now)
+                .map(|(address, _)| address.clone())
+                .collect();
+            addresses
+                .iter()
+                .filter_map(|address| refreshes.retrievers.remove(address))
+                .collect()
+        };
+        debug!(
+            "Refreshing {} credential(s) from {}",
+            scheduled.len(),
+            self.info.issuer
+        );
+
+        let subjects: BTreeSet<Identifier> = scheduled
+            .iter()
+            .map(|r| r.retriever.subject.clone())
+            .collect();
+        for subject in subjects.iter() {
+            self.reserve_secure_channel(subject).await;
+        }
+        for refresh in scheduled {
+            refresh.retriever.refresh_credential(refresh.is_retry).await;
+        }
+        for subject in subjects.iter() {
+            self.release_secure_channel(ctx, subject).await;
+        }
+    }
+
+    /// Return true the first time it is called, when the periodic retrieval of the
+    /// revocation list of the issuer must be started
+    pub(super) fn start_revocation_list_refresh(&self) -> bool {
+        !self
+            .is_revocation_list_refreshed
+            .swap(true, Ordering::SeqCst)
+    }
+}
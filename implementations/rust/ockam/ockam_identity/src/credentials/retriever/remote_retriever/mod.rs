@@ -1,9 +1,11 @@
 mod info;
+mod issuer_connection;
 #[allow(clippy::module_inception)]
 mod remote_retriever;
 mod remote_retriever_creator;
 mod remote_retriever_trait_impl;
 
 pub use info::*;
+pub use issuer_connection::*;
 pub use remote_retriever::*;
 pub use remote_retriever_creator::*;
@@ -2,6 +2,7 @@ use core::cmp::max;
 use tracing::{debug, error, info, trace, warn};
 
 use ockam_core::api::Request;
+use ockam_core::compat::backoff::{Backoff, BackoffDelays};
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::time::Duration;
 use ockam_core::compat::vec::Vec;
@@ -13,8 +14,9 @@ use ockam_transport_core::Transport;
 use crate::models::CredentialAndPurposeKey;
 use crate::utils::now;
 use crate::{
-    CachedCredentialRetriever, Identifier, RemoteCredentialRetrieverInfo, SecureChannels,
-    SecureClient, TimestampInSeconds, DEFAULT_CREDENTIAL_CLOCK_SKEW_GAP,
+    CachedCredentialRetriever, CredentialIssuerConnection, Identifier,
+    RemoteCredentialRetrieverInfo, SecureChannels, TimestampInSeconds,
+    DEFAULT_CREDENTIAL_CLOCK_SKEW_GAP,
 };
 
 /// This is the default interval before a credential expiration when we'll query for
@@ -25,6 +27,19 @@ pub const DEFAULT_PROACTIVE_REFRESH_CREDENTIAL_TIME_GAP: TimestampInSeconds =
 /// Default minimal interval before 2 refreshed in case we retry the refresh.
 pub const DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL: Duration = Duration::from_secs(10);
 
+/// Default maximal interval before 2 refreshes in case we retry the refresh.
+pub const DEFAULT_MAX_REFRESH_CREDENTIAL_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Default delays between the retries of a failed credential refresh: they start at
+/// [`DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL`] and double up to [`DEFAULT_MAX_REFRESH_CREDENTIAL_INTERVAL`].
+/// The jitter spreads the retries of the nodes which lost their connection to the Authority
+/// at the same time
+pub const DEFAULT_REFRESH_CREDENTIAL_RETRY_BACKOFF: Backoff = Backoff::new(
+    DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL,
+    DEFAULT_MAX_REFRESH_CREDENTIAL_INTERVAL,
+)
+.with_jitter(true);
+
 /// Default timeout for requesting credential from the authority
 pub const DEFAULT_CREDENTIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
 
@@ -34,6 +49,9 @@ pub const DEFAULT_CREDENTIAL_SECURE_CHANNEL_CREATION_TIMEOUT: Duration = Duratio
 /// Start refresh in the background before it expires
 pub const DEFAULT_CREDENTIAL_PROACTIVE_REFRESH_GAP: TimestampInSeconds = TimestampInSeconds(60);
 
+/// Default interval between two retrievals of the revocation list published by the authority
+pub const DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
 /// Timing options for retrieving remote credentials
 #[derive(Clone, Copy)]
 pub struct RemoteCredentialRetrieverTimingOptions {
@@ -41,13 +59,16 @@ pub struct RemoteCredentialRetrieverTimingOptions {
     pub request_timeout: Duration,
     /// Timeout for creating secure channel to the Authority node
     pub secure_channel_creation_timeout: Duration,
-    /// Minimum interval before refresh requests to the Authority node
-    pub min_refresh_interval: Duration,
+    /// Delays between the retries of a failed refresh request to the Authority node.
+    /// The maximum number of attempts is ignored: the refresh is retried until it succeeds
+    pub refresh_retry_backoff: Backoff,
     /// Time gap used to request a new credential before the old one actually expires
     pub proactive_refresh_gap: TimestampInSeconds,
     /// Time gap used to consider credential expired before its actual expiration
     /// to account for time errors on different machines
     pub clock_skew_gap: TimestampInSeconds,
+    /// Interval between two retrievals of the revocation list published by the Authority node
+    pub revocation_list_refresh_interval: Duration,
 }
 
 impl Default for RemoteCredentialRetrieverTimingOptions {
@@ -55,9 +76,10 @@ impl Default for RemoteCredentialRetrieverTimingOptions {
         Self {
             request_timeout: DEFAULT_CREDENTIAL_REQUEST_TIMEOUT,
             secure_channel_creation_timeout: DEFAULT_CREDENTIAL_SECURE_CHANNEL_CREATION_TIMEOUT,
-            min_refresh_interval: DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL,
+            refresh_retry_backoff: DEFAULT_REFRESH_CREDENTIAL_RETRY_BACKOFF,
             proactive_refresh_gap: DEFAULT_PROACTIVE_REFRESH_CREDENTIAL_TIME_GAP,
             clock_skew_gap: DEFAULT_CREDENTIAL_CLOCK_SKEW_GAP,
+            revocation_list_refresh_interval: DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL,
         }
     }
 }
@@ -68,17 +90,22 @@ pub(super) struct LastPresentedCredential {
     pub(super) expires_at: TimestampInSeconds,
 }
 
-/// Credentials retriever for credentials located on a different node
+/// Credentials retriever for credentials located on a different node.
+///
+/// The retrievers using the same issuer share a [`CredentialIssuerConnection`], which refreshes
+/// their credentials and keeps their secure channels to the issuer.
 #[derive(Clone)]
 pub struct RemoteCredentialRetriever {
-    ctx: Arc<Context>,
-    transport: Arc<dyn Transport>,
+    pub(super) ctx: Arc<Context>,
+    connection: Arc<CredentialIssuerConnection>,
     secure_channels: Arc<SecureChannels>,
     pub(super) issuer_info: RemoteCredentialRetrieverInfo,
     pub(super) subject: Identifier,
     pub(super) timing_options: RemoteCredentialRetrieverTimingOptions,
 
     is_initialized: Arc<Mutex<bool>>,
+    /// Delays before the next retries of the refresh, reset when a credential is retrieved
+    refresh_retry_delays: Arc<RwLock<BackoffDelays>>,
     pub(super) last_presented_credential: Arc<RwLock<Option<LastPresentedCredential>>>,
     /// Subscribers addresses that we will notify when credential is refreshed
     pub(super) subscribers: Arc<RwLock<Vec<Address>>>,
@@ -99,14 +126,21 @@ impl RemoteCredentialRetriever {
             subject, issuer_info.issuer
         );
 
+        let connection = secure_channels
+            .credential_issuer_connections()
+            .get_or_create(&issuer_info, transport);
+
         Self {
             ctx: Arc::new(ctx),
-            transport,
+            connection,
             secure_channels,
             issuer_info,
             subject,
             timing_options,
             is_initialized: Arc::new(Mutex::new(false)),
+            refresh_retry_delays: Arc::new(RwLock::new(
+                timing_options.refresh_retry_backoff.delays(),
+            )),
             last_presented_credential: Arc::new(RwLock::new(None)),
             subscribers: Default::default(),
         }
@@ -160,7 +194,13 @@ impl RemoteCredentialRetriever {
             self.get_new_credential().await?;
         } else {
             // We still have a valid credential - schedule refresh in the background
-            self.schedule_credentials_refresh_impl(refresh_in.duration, false);
+            self.schedule_credentials_refresh_impl(now, refresh_in.duration, false);
+        }
+
+        // The last revocation list is cached, so it is only refreshed periodically,
+        // once for all the retrievers using the same issuer
+        if self.connection.start_revocation_list_refresh() {
+            self.refresh_revocation_list_in_background();
         }
 
         *is_initialized = true;
@@ -206,9 +246,15 @@ impl RemoteCredentialRetriever {
         let refresh_in = Duration::from(refresh_in);
 
         let refresh_in = if is_retry {
-            // Avoid too many request to the credential_retriever, the refresh can't be sooner than
-            // self.min_credential_refresh_interval if it's a retry
-            max(self.timing_options.min_refresh_interval, refresh_in)
+            // Avoid too many request to the credential_retriever, the refresh is delayed
+            // a bit more after each failed attempt
+            let retry_in = self
+                .refresh_retry_delays
+                .write()
+                .unwrap()
+                .next_delay()
+                .unwrap_or(self.timing_options.refresh_retry_backoff.max_delay());
+            max(retry_in, refresh_in)
         } else {
             refresh_in
         };
@@ -225,7 +271,7 @@ impl RemoteCredentialRetriever {
     fn schedule_credentials_refresh(&self, now: TimestampInSeconds, is_retry: bool) {
         let refresh_in = self.compute_refresh_duration(now, is_retry);
 
-        self.schedule_credentials_refresh_impl(refresh_in.duration, is_retry);
+        self.schedule_credentials_refresh_impl(now, refresh_in.duration, is_retry);
     }
 
     async fn notify_subscribers(&self) -> Result<()> {
@@ -250,10 +296,14 @@ impl RemoteCredentialRetriever {
         Ok(())
     }
 
-    /// Schedule a DelayedEvent that will at specific point in time put a message
-    /// into EncryptorWorker's own internal mailbox which it will use as a trigger to get a new
-    /// credential and present it to the other side.
-    fn schedule_credentials_refresh_impl(&self, refresh_in: Duration, is_retry: bool) {
+    /// Schedule the refresh of the credential with the connection to the issuer, which refreshes
+    /// the credentials of all its retrievers in the background
+    fn schedule_credentials_refresh_impl(
+        &self,
+        now: TimestampInSeconds,
+        refresh_in: Duration,
+        is_retry: bool,
+    ) {
         let is_retry_str = if is_retry { " retry " } else { " " };
         info!(
             "Scheduling background credentials refresh{}from {} in {} seconds",
@@ -262,7 +312,8 @@ impl RemoteCredentialRetriever {
             refresh_in.as_secs()
         );
 
-        self.request_new_credential_in_background(refresh_in, is_retry);
+        self.connection
+            .schedule_refresh(self.clone(), now, refresh_in, is_retry);
     }
 }
 
@@ -273,19 +324,15 @@ impl RemoteCredentialRetriever {
             .identities
             .cached_credentials_repository();
 
-        let client = SecureClient::new(
-            self.secure_channels.clone(),
-            None,
-            self.transport.clone(),
-            self.issuer_info.route.clone(),
-            &self.issuer_info.issuer,
-            &self.subject,
-            self.timing_options.secure_channel_creation_timeout,
-            self.timing_options.request_timeout,
-        );
-
-        let credential = client
-            .ask(&self.ctx, "credential_issuer", Request::post("/"))
+        let credential = self
+            .connection
+            .ask(
+                &self.ctx,
+                self.secure_channels.clone(),
+                &self.subject,
+                &self.timing_options,
+                Request::post("/"),
+            )
             .await?
             .success()?;
 
@@ -308,6 +355,7 @@ impl RemoteCredentialRetriever {
         let expires_at = credential_and_purpose_key_data.credential_data.expires_at;
 
         trace!("The retrieved credential is valid");
+        self.refresh_retry_delays.write().unwrap().reset();
 
         *self.last_presented_credential.write().unwrap() = Some(LastPresentedCredential {
             credential: credential.clone(),
@@ -338,33 +386,73 @@ impl RemoteCredentialRetriever {
         Ok(())
     }
 
-    fn request_new_credential_in_background(&self, wait: Duration, is_retry: bool) {
-        let s = self.clone();
-        ockam_node::spawn(async move {
-            let is_retry_str = if is_retry { " retry " } else { " " };
-            info!(
-                "Scheduled background credentials refresh{}from {} in {} seconds",
-                is_retry_str,
-                s.issuer_info.issuer,
-                wait.as_secs()
-            );
-            s.ctx
-                .sleep_long_until(*now().unwrap() + wait.as_secs())
-                .await;
+    /// Get the revocation list published by the authority and store it if it is more recent
+    /// than the cached one.
+    async fn get_revocation_list(&self) -> Result<()> {
+        let revocation_list = self
+            .connection
+            .ask(
+                &self.ctx,
+                self.secure_channels.clone(),
+                &self.subject,
+                &self.timing_options,
+                Request::get("/revocations"),
+            )
+            .await?
+            .success()?;
+
+        let updated = self
+            .secure_channels
+            .identities()
+            .credentials()
+            .credentials_verification()
+            .receive_revocation_list(&self.issuer_info.issuer, &revocation_list)
+            .await?;
+
+        if updated {
             info!(
-                "Executing background credentials refresh{}from {}",
-                is_retry_str, s.issuer_info.issuer,
+                "Retrieved a new revocation list from {}",
+                self.issuer_info.issuer
             );
-            let res = s.get_new_credential().await;
-
-            if let Some(err) = res.err() {
-                error!(
-                    "Error refreshing credential for {} in the background: {}",
-                    s.subject, err
-                );
+        }
+        Ok(())
+    }
 
-                s.schedule_credentials_refresh(now().unwrap(), true);
+    fn refresh_revocation_list_in_background(&self) {
+        let s = self.clone();
+        ockam_node::spawn(async move {
+            loop {
+                let wait = s.timing_options.revocation_list_refresh_interval;
+                s.ctx
+                    .sleep_long_until(*now().unwrap() + wait.as_secs())
+                    .await;
+                // Authorities which don't publish a revocation list are still supported
+                if let Some(err) = s.get_revocation_list().await.err() {
+                    warn!(
+                        "Error refreshing the revocation list from {}: {}",
+                        s.issuer_info.issuer, err
+                    );
+                }
             }
         });
     }
+
+    /// Refresh the credential now, and schedule a retry if the refresh fails
+    pub(super) async fn refresh_credential(&self, is_retry: bool) {
+        let is_retry_str = if is_retry { " retry " } else { " " };
+        info!(
+            "Executing background credentials refresh{}from {}",
+            is_retry_str, self.issuer_info.issuer,
+        );
+        let res = self.get_new_credential().await;
+
+        if let Some(err) = res.err() {
+            error!(
+                "Error refreshing credential for {} in the background: {}",
+                self.subject, err
+            );
+
+            self.schedule_credentials_refresh(now().unwrap(), true);
+        }
+    }
 }
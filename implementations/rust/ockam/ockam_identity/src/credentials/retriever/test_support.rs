@@ -0,0 +1,234 @@
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Address, Error, Result};
+
+use crate::models::CredentialAndPurposeKey;
+use crate::utils::now;
+use crate::{CredentialRetriever, CredentialRetrieverCreator, Identifier, TimestampInSeconds};
+
+/// Credentials retriever which never has a credential available.
+/// It can be used to test how a missing credential is handled.
+pub struct NoOpCredentialRetriever {
+    kind: Kind,
+}
+
+impl NoOpCredentialRetriever {
+    /// Create a retriever failing with an error of the given kind
+    pub fn new(kind: Kind) -> Self {
+        Self { kind }
+    }
+}
+
+#[async_trait]
+impl CredentialRetriever for NoOpCredentialRetriever {
+    async fn initialize(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn retrieve(&self) -> Result<CredentialAndPurposeKey> {
+        Err(Error::new(
+            Origin::Identity,
+            self.kind,
+            "no credential available",
+        ))
+    }
+
+    fn subscribe(&self, _address: &Address) -> Result<()> {
+        Ok(())
+    }
+
+    fn unsubscribe(&self, _address: &Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Creator for [`NoOpCredentialRetriever`]
+pub struct NoOpCredentialRetrieverCreator {
+    kind: Kind,
+}
+
+impl NoOpCredentialRetrieverCreator {
+    /// Create retrievers failing with a [`Kind::NotFound`] error
+    pub fn new() -> Self {
+        Self::with_error_kind(Kind::NotFound)
+    }
+
+    /// Create retrievers failing with an error of the given kind
+    pub fn with_error_kind(kind: Kind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Default for NoOpCredentialRetrieverCreator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl CredentialRetrieverCreator for NoOpCredentialRetrieverCreator {
+    async fn create(&self, _subject: &Identifier) -> Result<Arc<dyn CredentialRetriever>> {
+        Ok(Arc::new(NoOpCredentialRetriever::new(self.kind)))
+    }
+}
+
+/// Result of a credential retrieval recorded by a [`RecordingCredentialRetriever`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialRetrievalOutcome {
+    /// A credential was returned
+    Retrieved,
+    /// The retrieval failed with an error of the given kind
+    Failed(Kind),
+}
+
+/// Credential retrieval recorded by a [`RecordingCredentialRetriever`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CredentialRetrievalRecord {
+    /// Identity for which a credential was requested
+    pub subject: Identifier,
+    /// Time of the request
+    pub requested_at: TimestampInSeconds,
+    /// Result of the request
+    pub outcome: CredentialRetrievalOutcome,
+}
+
+/// Log of the credential retrievals, shared by a [`RecordingCredentialRetrieverCreator`]
+/// and all the retrievers it creates
+#[derive(Debug, Clone, Default)]
+pub struct CredentialRetrievalLog {
+    records: Arc<Mutex<Vec<CredentialRetrievalRecord>>>,
+}
+
+impl CredentialRetrievalLog {
+    /// Return all the recorded retrievals, in the order of the requests
+    pub fn records(&self) -> Vec<CredentialRetrievalRecord> {
+        self.records.lock().unwrap().clone()
+    }
+
+    /// Return the outcomes of the recorded retrievals, in the order of the requests
+    pub fn outcomes(&self) -> Vec<CredentialRetrievalOutcome> {
+        self.records().into_iter().map(|r| r.outcome).collect()
+    }
+
+    /// Remove all the recorded retrievals
+    pub fn clear(&self) {
+        self.records.lock().unwrap().clear()
+    }
+
+    fn record(
+        &self,
+        subject: &Identifier,
+        requested_at: TimestampInSeconds,
+        result: &Result<CredentialAndPurposeKey>,
+    ) {
+        let outcome = match result {
+            Ok(_) => CredentialRetrievalOutcome::Retrieved,
+            Err(e) => CredentialRetrievalOutcome::Failed(e.code().kind),
+        };
+        self.records
+            .lock()
+            .unwrap()
+            .push(CredentialRetrievalRecord {
+                subject: subject.clone(),
+                requested_at,
+                outcome,
+            })
+    }
+}
+
+/// Credentials retriever recording each retrieval made with another retriever
+pub struct RecordingCredentialRetriever {
+    subject: Identifier,
+    retriever: Arc<dyn CredentialRetriever>,
+    log: CredentialRetrievalLog,
+}
+
+#[async_trait]
+impl CredentialRetriever for RecordingCredentialRetriever {
+    async fn initialize(&self) -> Result<()> {
+        self.retriever.initialize().await
+    }
+
+    async fn retrieve(&self) -> Result<CredentialAndPurposeKey> {
+        let requested_at = now()?;
+        let result = self.retriever.retrieve().await;
+        self.log.record(&self.subject, requested_at, &result);
+        result
+    }
+
+    fn subscribe(&self, address: &Address) -> Result<()> {
+        self.retriever.subscribe(address)
+    }
+
+    fn unsubscribe(&self, address: &Address) -> Result<()> {
+        self.retriever.unsubscribe(address)
+    }
+}
+
+/// Creator for [`RecordingCredentialRetriever`], wrapping another creator
+pub struct RecordingCredentialRetrieverCreator {
+    creator: Arc<dyn CredentialRetrieverCreator>,
+    log: CredentialRetrievalLog,
+}
+
+impl RecordingCredentialRetrieverCreator {
+    /// Record the retrievals made with the retrievers of the given creator
+    pub fn new(creator: Arc<dyn CredentialRetrieverCreator>) -> Self {
+        Self {
+            creator,
+            log: CredentialRetrievalLog::default(),
+        }
+    }
+
+    /// Log of the retrievals made by all the created retrievers
+    pub fn log(&self) -> CredentialRetrievalLog {
+        self.log.clone()
+    }
+}
+
+#[async_trait]
+impl CredentialRetrieverCreator for RecordingCredentialRetrieverCreator {
+    async fn create(&self, subject: &Identifier) -> Result<Arc<dyn CredentialRetriever>> {
+        Ok(Arc::new(RecordingCredentialRetriever {
+            subject: subject.clone(),
+            retriever: self.creator.create(subject).await?,
+            log: self.log.clone(),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identities;
+
+    #[tokio::test]
+    async fn retrievals_are_recorded() -> Result<()> {
+        let identities = identities().await?;
+        let subject = identities.identities_creation().create_identity().await?;
+
+        let creator = RecordingCredentialRetrieverCreator::new(Arc::new(
+            NoOpCredentialRetrieverCreator::with_error_kind(Kind::Unsupported),
+        ));
+        let retriever = creator.create(&subject).await?;
+        retriever.initialize().await?;
+        let error = retriever.retrieve().await.unwrap_err();
+        assert_eq!(error.code().kind, Kind::Unsupported);
+        assert!(retriever.retrieve().await.is_err());
+
+        let records = creator.log().records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].subject, subject);
+        assert!(records[0].requested_at <= records[1].requested_at);
+        assert_eq!(
+            creator.log().outcomes(),
+            vec![CredentialRetrievalOutcome::Failed(Kind::Unsupported); 2]
+        );
+
+        creator.log().clear();
+        assert!(creator.log().records().is_empty());
+        Ok(())
+    }
+}
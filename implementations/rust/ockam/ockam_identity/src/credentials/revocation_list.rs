@@ -0,0 +1,129 @@
+use core::str::FromStr;
+use minicbor::{Decode, Encode};
+
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+use crate::models::{Attributes, CredentialSchemaIdentifier, Identifier};
+use crate::utils::AttributesBuilder;
+use crate::{IdentityError, TimestampInSeconds};
+
+/// Schema of the credential used by an authority to sign its revocation list
+pub const REVOCATION_LIST_SCHEMA: CredentialSchemaIdentifier = CredentialSchemaIdentifier(2);
+
+/// Attribute containing the version of the revocation list
+const REVOCATION_LIST_VERSION_KEY: &str = "ockam-revocation-list-version";
+
+/// Prefix of the attributes containing a revoked identifier. Their value is the revocation time
+const REVOKED_IDENTIFIER_KEY_PREFIX: &str = "ockam-revoked:";
+
+/// List of the identities revoked by an authority.
+///
+/// The authority increments the version of the list every time a member is revoked,
+/// and signs the list by issuing it as a credential to itself.
+/// A credential presented by a revoked identity is rejected if it was created before the revocation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct RevocationList {
+    /// Version of the list, monotonically increasing
+    #[n(1)] pub version: u64,
+    /// Revoked identities and their revocation time
+    #[n(2)] pub revoked: BTreeMap<Identifier, TimestampInSeconds>,
+}
+
+impl RevocationList {
+    /// Constructor
+    pub fn new(version: u64, revoked: BTreeMap<Identifier, TimestampInSeconds>) -> Self {
+        Self { version, revoked }
+    }
+
+    /// Return true if a credential issued to the subject at the given time has been revoked
+    pub fn is_revoked(&self, subject: &Identifier, created_at: TimestampInSeconds) -> bool {
+        self.revoked
+            .get(subject)
+            .map(|revoked_at| created_at <= *revoked_at)
+            .unwrap_or(false)
+    }
+
+    /// Return the list as the attributes of a credential
+    pub fn to_attributes(&self) -> Attributes {
+        let mut builder = AttributesBuilder::with_schema(REVOCATION_LIST_SCHEMA)
+            .with_attribute(REVOCATION_LIST_VERSION_KEY, self.version.to_string());
+        for (identifier, revoked_at) in &self.revoked {
+            builder = builder.with_attribute(
+                format!("{REVOKED_IDENTIFIER_KEY_PREFIX}{identifier}"),
+                revoked_at.0.to_string(),
+            );
+        }
+        builder.build()
+    }
+
+    /// Read the list from the attributes of a credential
+    pub fn from_attributes(attributes: &Attributes) -> Result<Self> {
+        if attributes.schema != REVOCATION_LIST_SCHEMA {
+            return Err(IdentityError::InvalidRevocationList)?;
+        }
+
+        let mut version = None;
+        let mut revoked = BTreeMap::new();
+        for (key, value) in &attributes.map {
+            let key = Self::utf8(key)?;
+            let value = Self::utf8(value)?;
+            if key == REVOCATION_LIST_VERSION_KEY {
+                version = Some(Self::number(&value)?);
+            } else if let Some(identifier) = key.strip_prefix(REVOKED_IDENTIFIER_KEY_PREFIX) {
+                let identifier = Identifier::from_str(identifier)?;
+                revoked.insert(identifier, TimestampInSeconds(Self::number(&value)?));
+            }
+        }
+
+        match version {
+            Some(version) => Ok(Self::new(version, revoked)),
+            None => Err(IdentityError::InvalidRevocationList)?,
+        }
+    }
+
+    fn utf8(bytes: &[u8]) -> Result<String> {
+        String::from_utf8(Vec::from(bytes)).map_err(|_| IdentityError::InvalidRevocationList.into())
+    }
+
+    fn number(value: &str) -> Result<u64> {
+        value
+            .parse()
+            .map_err(|_| IdentityError::InvalidRevocationList.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::identities;
+
+    #[tokio::test]
+    async fn test_revocation_list_attributes() -> Result<()> {
+        let identities = identities().await?;
+        let revoked = identities.identities_creation().create_identity().await?;
+        let member = identities.identities_creation().create_identity().await?;
+
+        let list = RevocationList::new(
+            3,
+            BTreeMap::from([(revoked.clone(), TimestampInSeconds(100))]),
+        );
+        let decoded = RevocationList::from_attributes(&list.to_attributes())?;
+        assert_eq!(decoded, list);
+
+        assert!(decoded.is_revoked(&revoked, TimestampInSeconds(99)));
+        assert!(decoded.is_revoked(&revoked, TimestampInSeconds(100)));
+        assert!(!decoded.is_revoked(&revoked, TimestampInSeconds(101)));
+        assert!(!decoded.is_revoked(&member, TimestampInSeconds(99)));
+
+        let other_schema = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+            .with_attribute(REVOCATION_LIST_VERSION_KEY, "1")
+            .build();
+        assert!(RevocationList::from_attributes(&other_schema).is_err());
+        Ok(())
+    }
+}
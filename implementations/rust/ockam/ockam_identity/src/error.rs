@@ -42,6 +42,9 @@ pub enum IdentityError {
     SecureChannelVerificationFailedIncorrectCredential,
     /// Credentials could not be checked because the Authority is missing
     SecureChannelVerificationFailedMissingAuthority,
+    /// The credentials presented by the other party don't contain the attributes required
+    /// by the Secure Channel Listener
+    SecureChannelVerificationFailedMissingAttributes,
     /// SecureChannelTrustCheckFailed
     SecureChannelTrustCheckFailed,
     /// Invalid Nonce value
@@ -68,6 +71,10 @@ pub enum IdentityError {
     AddressIsNotSubscribedForThatCredentialRetriever,
     /// Credential retriever couldn't return a credential
     NoCredential,
+    /// The subject of the credential has been revoked by the Authority
+    CredentialRevoked,
+    /// The revocation list sent by the Authority is invalid
+    InvalidRevocationList,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -12,16 +12,21 @@ use crate::identities::storage::CredentialRepository;
 use crate::identities::storage::CredentialSqlxDatabase;
 #[cfg(feature = "storage")]
 use crate::identities::storage::IdentityAttributesSqlxDatabase;
+#[cfg(feature = "storage")]
+use crate::identities::storage::RevocationListSqlxDatabase;
 use crate::identities::{ChangeHistoryRepository, IdentitiesKeys};
 use crate::models::ChangeHistory;
 use crate::purpose_keys::storage::PurposeKeysRepository;
 #[cfg(feature = "storage")]
 use crate::purpose_keys::storage::PurposeKeysSqlxDatabase;
 #[cfg(feature = "storage")]
+use crate::utils::system_time_source;
+use crate::utils::TimeSource;
+#[cfg(feature = "storage")]
 use crate::IdentitiesBuilder;
 use crate::{
     Credentials, Identifier, IdentitiesCreation, IdentitiesVerification, Identity,
-    IdentityAttributesRepository, PurposeKeys, Vault,
+    IdentityAttributesRepository, PurposeKeys, RevocationListRepository, Vault,
 };
 
 /// This struct supports all the services related to identities
@@ -32,6 +37,8 @@ pub struct Identities {
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     cached_credentials_repository: Arc<dyn CredentialRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
+    time_source: Arc<dyn TimeSource>,
 }
 
 impl Identities {
@@ -55,6 +62,16 @@ impl Identities {
         self.cached_credentials_repository.clone()
     }
 
+    /// Return the repository of the revocation lists received from authorities
+    pub fn revocation_list_repository(&self) -> Arc<dyn RevocationListRepository> {
+        self.revocation_list_repository.clone()
+    }
+
+    /// Return the time source used to issue and verify credentials
+    pub fn time_source(&self) -> Arc<dyn TimeSource> {
+        self.time_source.clone()
+    }
+
     /// Get an [`Identity`] from the repository
     pub async fn get_identity(&self, identifier: &Identifier) -> Result<Identity> {
         self.identities_verification()
@@ -124,6 +141,8 @@ impl Identities {
             self.purpose_keys(),
             self.identities_creation().clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
+            self.time_source.clone(),
         ))
     }
 }
@@ -136,6 +155,8 @@ impl Identities {
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
         purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
         cached_credentials_repository: Arc<dyn CredentialRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
+        time_source: Arc<dyn TimeSource>,
     ) -> Identities {
         Identities {
             vault,
@@ -143,6 +164,8 @@ impl Identities {
             identity_attributes_repository,
             purpose_keys_repository,
             cached_credentials_repository,
+            revocation_list_repository,
+            time_source,
         }
     }
 
@@ -164,7 +187,9 @@ impl Identities {
                 database.clone(),
             )),
             purpose_keys_repository: Arc::new(PurposeKeysSqlxDatabase::new(database.clone())),
-            cached_credentials_repository: Arc::new(CredentialSqlxDatabase::new(database)),
+            cached_credentials_repository: Arc::new(CredentialSqlxDatabase::new(database.clone())),
+            revocation_list_repository: Arc::new(RevocationListSqlxDatabase::new(database)),
+            time_source: system_time_source(),
         }
     }
 }
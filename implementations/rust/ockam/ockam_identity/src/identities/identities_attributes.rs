@@ -1,6 +1,7 @@
 use crate::utils::now;
 use crate::{AttributesEntry, Identifier, IdentityAttributesRepository};
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use tracing_attributes::instrument;
 
@@ -42,6 +43,11 @@ impl IdentitiesAttributes {
     pub async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         self.repository.put_attributes(subject, entry).await
     }
+
+    /// Return the identifiers starting with the given prefix which have attributes
+    pub async fn get_identifiers_by_prefix(&self, prefix: &str) -> Result<Vec<Identifier>> {
+        self.repository.get_identifiers_by_prefix(prefix).await
+    }
 }
 
 #[cfg(test)]
@@ -8,7 +8,8 @@ use ockam_vault::storage::SecretsRepository;
 use crate::identities::storage::CredentialRepository;
 use crate::identities::{ChangeHistoryRepository, Identities};
 use crate::purpose_keys::storage::PurposeKeysRepository;
-use crate::{IdentityAttributesRepository, Vault};
+use crate::utils::TimeSource;
+use crate::{IdentityAttributesRepository, RevocationListRepository, Vault};
 
 /// Builder for Identities services
 #[derive(Clone)]
@@ -18,6 +19,8 @@ pub struct IdentitiesBuilder {
     pub(crate) identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
     pub(crate) purpose_keys_repository: Arc<dyn PurposeKeysRepository>,
     pub(crate) cached_credentials_repository: Arc<dyn CredentialRepository>,
+    pub(crate) revocation_list_repository: Arc<dyn RevocationListRepository>,
+    pub(crate) time_source: Arc<dyn TimeSource>,
 }
 
 /// Return a default identities
@@ -81,6 +84,21 @@ impl IdentitiesBuilder {
         self
     }
 
+    /// Set a specific repository for the revocation lists received from authorities
+    pub fn with_revocation_list_repository(
+        mut self,
+        repository: Arc<dyn RevocationListRepository>,
+    ) -> Self {
+        self.revocation_list_repository = repository;
+        self
+    }
+
+    /// Set a specific time source, for example to simulate a clock skew
+    pub fn with_time_source(mut self, time_source: Arc<dyn TimeSource>) -> Self {
+        self.time_source = time_source;
+        self
+    }
+
     /// Build identities
     pub fn build(self) -> Arc<Identities> {
         Arc::new(Identities::new(
@@ -89,6 +107,8 @@ impl IdentitiesBuilder {
             self.identity_attributes_repository,
             self.purpose_keys_repository,
             self.cached_credentials_repository,
+            self.revocation_list_repository,
+            self.time_source,
         ))
     }
 }
@@ -29,4 +29,7 @@ pub trait ChangeHistoryRepository: Send + Sync + 'static {
 
     /// Return all the change histories
     async fn get_change_histories(&self) -> Result<Vec<ChangeHistory>>;
+
+    /// Return the identifiers starting with the given prefix, for example `I84502ce0`
+    async fn get_identifiers_by_prefix(&self, prefix: &str) -> Result<Vec<Identifier>>;
 }
@@ -116,6 +116,19 @@ impl ChangeHistoryRepository for ChangeHistorySqlxDatabase {
         let row: Vec<ChangeHistoryRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         row.iter().map(|r| r.change_history()).collect()
     }
+
+    async fn get_identifiers_by_prefix(&self, prefix: &str) -> Result<Vec<Identifier>> {
+        // GLOB is case-sensitive, which lets Sqlite use the index of the identifier column
+        let query = query_scalar::<_, String>(
+            "SELECT identifier FROM identity WHERE identifier GLOB ? ORDER BY identifier",
+        )
+        .bind(format!("{prefix}*").to_sql());
+        let identifiers = query.fetch_all(&*self.database.pool).await.into_core()?;
+        identifiers
+            .iter()
+            .map(|i| Identifier::from_str(i))
+            .collect()
+    }
 }
 
 impl ChangeHistorySqlxDatabase {
@@ -245,6 +258,29 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_identifiers_by_prefix() -> Result<()> {
+        let repository = create_repository().await?;
+        let (orchestrator_identifier, _) = orchestrator_identity();
+
+        let result = repository.get_identifiers_by_prefix("I84502ce0").await?;
+        assert_eq!(result, vec![orchestrator_identifier.clone()]);
+
+        // the prefix is case-sensitive, like the identifiers
+        let result = repository.get_identifiers_by_prefix("I84502CE0").await?;
+        assert!(result.is_empty());
+
+        let identity = create_identity().await?;
+        repository
+            .store_change_history(identity.identifier(), identity.change_history().clone())
+            .await?;
+        let result = repository.get_identifiers_by_prefix("I").await?;
+        assert_eq!(result.len(), 2);
+        assert!(result.contains(&orchestrator_identifier));
+        assert!(result.contains(identity.identifier()));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_update_identity() -> Result<()> {
         let identities = identities().await?;
@@ -1,6 +1,7 @@
 use crate::{AttributesEntry, Identifier, TimestampInSeconds};
 use async_trait::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 /// This trait supports the persistence of attributes associated to identities
@@ -19,4 +20,8 @@ pub trait IdentityAttributesRepository: Send + Sync + 'static {
 
     /// Remove all expired attributes
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()>;
+
+    /// Return the identifiers starting with the given prefix, for example `I84502ce0`,
+    /// which have attributes on any node
+    async fn get_identifiers_by_prefix(&self, prefix: &str) -> Result<Vec<Identifier>>;
 }
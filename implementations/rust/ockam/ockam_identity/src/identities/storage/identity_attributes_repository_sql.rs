@@ -79,6 +79,19 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
             .bind(self.database.node_name()?.to_sql());
         query.execute(&*self.database.pool).await.void()
     }
+
+    async fn get_identifiers_by_prefix(&self, prefix: &str) -> Result<Vec<Identifier>> {
+        // GLOB is case-sensitive, which lets Sqlite use the index starting with the identifier column
+        let query = query_scalar::<_, String>(
+            "SELECT DISTINCT identifier FROM identity_attributes WHERE identifier GLOB ? ORDER BY identifier",
+        )
+        .bind(format!("{prefix}*").to_sql());
+        let identifiers = query.fetch_all(&*self.database.pool).await.into_core()?;
+        identifiers
+            .iter()
+            .map(|i| Identifier::from_str(i))
+            .collect()
+    }
 }
 
 // Database serialization / deserialization
@@ -230,6 +243,27 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_identifiers_by_prefix() -> Result<()> {
+        let repository = create_repository().await?;
+        let now = now()?;
+
+        let identifier = create_identity().await?;
+        let attributes = create_attributes_entry(&identifier, now, None).await?;
+        repository.put_attributes(&identifier, attributes).await?;
+
+        let prefix = &identifier.to_string()[..9];
+        let result = repository.get_identifiers_by_prefix(prefix).await?;
+        assert_eq!(result, vec![identifier.clone()]);
+
+        // change the first hex digit of the prefix
+        let first = if prefix.starts_with("I0") { '1' } else { '0' };
+        let other_prefix = format!("I{first}{}", &prefix[2..]);
+        let result = repository.get_identifiers_by_prefix(&other_prefix).await?;
+        assert!(result.is_empty());
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_attributes_entry(
         identifier: &Identifier,
@@ -0,0 +1,19 @@
+use crate::{Identifier, RevocationList};
+use async_trait::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+
+/// This trait supports the persistence of the revocation lists fetched from authorities
+#[async_trait]
+pub trait RevocationListRepository: Send + Sync + 'static {
+    /// Get the last revocation list stored for an authority
+    async fn get_revocation_list(&self, authority: &Identifier) -> Result<Option<RevocationList>>;
+
+    /// Store a revocation list if its version is greater than the version of the stored list.
+    /// Return true if the list has been stored
+    async fn store_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: &RevocationList,
+    ) -> Result<bool>;
+}
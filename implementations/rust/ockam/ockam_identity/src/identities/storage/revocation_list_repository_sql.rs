@@ -0,0 +1,140 @@
+use sqlx::*;
+use tracing::debug;
+
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToSqlxType};
+
+use crate::{Identifier, RevocationList, RevocationListRepository};
+
+/// Implementation of [`RevocationListRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RevocationListSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl RevocationListSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for revocation lists");
+        Self { database }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(SqlxDatabase::in_memory("revocation list").await?))
+    }
+
+    /// Create a new in-memory database, passing a node name to isolate data between nodes where needed
+    pub async fn create_with_node_name(node_name: &str) -> Result<Self> {
+        let mut db = SqlxDatabase::in_memory("revocation list").await?;
+        db.set_node_name(node_name);
+        Ok(Self::new(db))
+    }
+}
+
+#[async_trait]
+impl RevocationListRepository for RevocationListSqlxDatabase {
+    async fn get_revocation_list(&self, authority: &Identifier) -> Result<Option<RevocationList>> {
+        let query = query_as(
+            "SELECT list FROM revocation_list WHERE authority_identifier=$1 AND node_name=$2",
+        )
+        .bind(authority.to_sql())
+        .bind(self.database.node_name()?.to_sql());
+        let row: Option<RevocationListRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.revocation_list()).transpose()
+    }
+
+    async fn store_revocation_list(
+        &self,
+        authority: &Identifier,
+        revocation_list: &RevocationList,
+    ) -> Result<bool> {
+        // The list is only replaced by a more recent version
+        let query = query(
+            "INSERT INTO revocation_list (authority_identifier, version, list, node_name) VALUES ($1, $2, $3, $4)
+             ON CONFLICT (authority_identifier, node_name)
+             DO UPDATE SET version = excluded.version, list = excluded.list
+             WHERE excluded.version > revocation_list.version",
+        )
+        .bind(authority.to_sql())
+        .bind(revocation_list.version.to_sql())
+        .bind(minicbor::to_vec(revocation_list)?.to_sql())
+        .bind(self.database.node_name()?.to_sql());
+        let result = query.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevocationListRow {
+    list: Vec<u8>,
+}
+
+impl RevocationListRow {
+    fn revocation_list(&self) -> Result<RevocationList> {
+        Ok(minicbor::decode(&self.list)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::compat::collections::BTreeMap;
+    use ockam_core::compat::rand::random_string;
+    use ockam_core::compat::sync::Arc;
+
+    use super::*;
+    use crate::{identities, TimestampInSeconds};
+
+    #[tokio::test]
+    async fn test_revocation_list_repository() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let identities = identities().await?;
+        let authority = identities.identities_creation().create_identity().await?;
+        let revoked = identities.identities_creation().create_identity().await?;
+        assert_eq!(repository.get_revocation_list(&authority).await?, None);
+
+        let list1 = RevocationList::new(
+            1,
+            BTreeMap::from([(revoked.clone(), TimestampInSeconds(10))]),
+        );
+        assert!(repository.store_revocation_list(&authority, &list1).await?);
+        assert_eq!(
+            repository.get_revocation_list(&authority).await?,
+            Some(list1.clone())
+        );
+
+        // an older or identical version doesn't replace the stored list
+        assert!(
+            !repository
+                .store_revocation_list(&authority, &RevocationList::default())
+                .await?
+        );
+        assert!(!repository.store_revocation_list(&authority, &list1).await?);
+        assert_eq!(
+            repository.get_revocation_list(&authority).await?,
+            Some(list1)
+        );
+
+        let list2 = RevocationList::new(2, BTreeMap::from([(revoked, TimestampInSeconds(20))]));
+        assert!(repository.store_revocation_list(&authority, &list2).await?);
+        assert_eq!(
+            repository.get_revocation_list(&authority).await?,
+            Some(list2)
+        );
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn create_repository() -> Result<Arc<dyn RevocationListRepository>> {
+        Ok(Arc::new(
+            RevocationListSqlxDatabase::create_with_node_name(&random_string()).await?,
+        ))
+    }
+}
@@ -7,6 +7,17 @@ use ockam_vault::{
 };
 
 /// Identity Change History
+///
+/// Its encoding is canonical, so that an identity is always encoded to the same bytes:
+///
+///  - every structure is encoded as a CBOR array with its fields in the order of their index.
+///    A `None` field is encoded as `null`, unless it is the last field, in which case the array
+///    is shortened
+///  - an enum is encoded as an array containing the index of the variant and an array of its fields
+///  - integers and lengths use their shortest form, byte strings have a definite length
+///  - there are no maps
+///
+/// The golden fixtures in `tests/fixtures/wire` can be used to check another implementation.
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 #[rustfmt::skip]
 #[cbor(transparent)]
@@ -1,7 +1,8 @@
 use crate::models::{ChangeHash, Identifier, TimestampInSeconds};
 use core::fmt::{Display, Formatter};
 use minicbor::bytes::ByteVec;
-use minicbor::{Decode, Encode};
+use minicbor::encode::{self, Write};
+use minicbor::{Decode, Encode, Encoder};
 use ockam_core::compat::string::String;
 use ockam_core::compat::{collections::BTreeMap, vec::Vec};
 use ockam_vault::{ECDSASHA256CurveP256Signature, EdDSACurve25519Signature};
@@ -56,15 +57,42 @@ pub struct CredentialData {
 pub struct CredentialSchemaIdentifier(#[n(0)] pub u64);
 
 /// Set a keys&values that an Authority (issuer) attests about the Subject
+///
+/// The map is the only CBOR map of the credential structures. It is encoded in the canonical
+/// order of [RFC 8949](https://www.rfc-editor.org/rfc/rfc8949#section-4.2.1): the keys are sorted
+/// by their encoded form, which means that a shorter key comes first, and that keys of the same
+/// length are sorted bytewise.
+///
+/// A map encoded in another order, as in the credentials issued before this canonical form was
+/// enforced, is still decoded. The signature of a [`Credential`] is always verified against
+/// the bytes which were signed, never against a new encoding of the data.
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
 #[rustfmt::skip]
 pub struct Attributes {
     /// [`CredentialSchemaIdentifier`] that determines which keys&values to expect in the [`Attributes`]
     #[n(0)] pub schema: CredentialSchemaIdentifier,
     /// Set of keys&values
+    #[cbor(encode_with = "encode_canonical_map")]
     #[n(1)] pub map: BTreeMap<ByteVec, ByteVec>,
 }
 
+/// Encode the attributes map with its keys in the canonical order.
+/// The entries of a [`BTreeMap`] are already sorted bytewise, so a stable sort by length is enough
+fn encode_canonical_map<Ctx, W: Write>(
+    map: &BTreeMap<ByteVec, ByteVec>,
+    e: &mut Encoder<W>,
+    ctx: &mut Ctx,
+) -> Result<(), encode::Error<W::Error>> {
+    let mut entries: Vec<(&ByteVec, &ByteVec)> = map.iter().collect();
+    entries.sort_by_key(|(key, _)| key.len());
+    e.map(entries.len() as u64)?;
+    for (key, value) in entries {
+        key.encode(e, ctx)?;
+        value.encode(e, ctx)?;
+    }
+    Ok(())
+}
+
 impl Display for Attributes {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut attributes = vec![];
@@ -13,6 +13,8 @@ pub const CHANGE_HASH_LEN: usize = 32;
 /// Unique identifier for an [`super::super::identity::Identity`]
 /// Equals to the [`ChangeHash`] of the first [`super::Change`] in the [`super::ChangeHistory`]
 /// Computed as SHA256 of the first [`super::ChangeData`] CBOR binary
+///
+/// Its CBOR encoding, a byte string, is wire-stable.
 #[derive(Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Encode, Decode)]
 #[cbor(transparent)]
 pub struct Identifier(#[cbor(n(0), with = "minicbor::bytes")] pub [u8; IDENTIFIER_LEN]);
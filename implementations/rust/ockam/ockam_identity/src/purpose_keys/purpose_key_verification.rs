@@ -44,6 +44,17 @@ impl PurposeKeyVerification {
         &self,
         expected_subject: Option<&Identifier>,
         attestation: &PurposeKeyAttestation,
+    ) -> Result<PurposeKeyAttestationData> {
+        self.verify_purpose_key_attestation_at(expected_subject, attestation, now()?)
+            .await
+    }
+
+    /// Verify a [`PurposeKeyAttestation`] at a given time
+    pub async fn verify_purpose_key_attestation_at(
+        &self,
+        expected_subject: Option<&Identifier>,
+        attestation: &PurposeKeyAttestation,
+        now: TimestampInSeconds,
     ) -> Result<PurposeKeyAttestationData> {
         let versioned_data_hash = self.verifying_vault.sha256(&attestation.data).await?;
 
@@ -90,8 +101,6 @@ impl PurposeKeyVerification {
             return Err(IdentityError::PurposeKeyAttestationVerificationFailed)?;
         }
 
-        let now = now()?;
-
         if purpose_key_data.created_at > now
             && purpose_key_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
         {
@@ -0,0 +1,75 @@
+use core::fmt;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+
+use crate::TimestampInSeconds;
+
+/// Above this difference between the clocks of both sides of a secure channel, a credential
+/// failing its validity check is most likely rejected because of a badly set clock
+pub const MAX_ALLOWED_CLOCK_SKEW_IN_SECONDS: u64 = 60;
+
+/// Difference, in seconds, between the wall-clock time sent by the other side of a secure channel
+/// and our own time when the message was received. A positive value means that the other side
+/// is ahead of us.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClockSkew(i64);
+
+impl ClockSkew {
+    /// Measure the skew between their time and our time
+    pub fn measure(their_time: TimestampInSeconds, our_time: TimestampInSeconds) -> Self {
+        Self((their_time.0 as i64).saturating_sub(our_time.0 as i64))
+    }
+
+    /// Create a clock skew from a number of seconds
+    pub fn from_seconds(seconds: i64) -> Self {
+        Self(seconds)
+    }
+
+    /// Number of seconds the other side is ahead of us (negative if it is behind)
+    pub fn seconds(&self) -> i64 {
+        self.0
+    }
+
+    /// Return true if the skew is large enough to explain a credential validity check failure
+    pub fn exceeds_threshold(&self) -> bool {
+        self.0.unsigned_abs() > MAX_ALLOWED_CLOCK_SKEW_IN_SECONDS
+    }
+
+    /// Return the error reported instead of a generic credential verification failure
+    /// when the skew exceeds the threshold
+    pub fn error(&self) -> Error {
+        Error::new(
+            Origin::Identity,
+            Kind::Invalid,
+            format!("clock skew detected: peer time differs by {}s", self.0),
+        )
+    }
+}
+
+impl fmt::Display for ClockSkew {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:+}s", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_clock_skew() {
+        let skew = ClockSkew::measure(TimestampInSeconds(1000), TimestampInSeconds(1030));
+        assert_eq!(skew.seconds(), -30);
+        assert!(!skew.exceeds_threshold());
+        assert_eq!(skew.to_string(), "-30s");
+
+        let skew = ClockSkew::measure(TimestampInSeconds(8200), TimestampInSeconds(1000));
+        assert_eq!(skew.seconds(), 7200);
+        assert!(skew.exceeds_threshold());
+        assert_eq!(skew.to_string(), "+7200s");
+        assert!(skew
+            .error()
+            .to_string()
+            .contains("clock skew detected: peer time differs by 7200s"));
+    }
+}
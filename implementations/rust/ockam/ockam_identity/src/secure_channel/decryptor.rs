@@ -1,9 +1,12 @@
 use core::sync::atomic::Ordering;
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
-use ockam_core::{Any, Result, Routed};
+use ockam_core::{Any, IncomingAccessControl, IngressInfo, RelayMessage, Result, Routed};
 use ockam_core::{Decodable, LocalMessage};
-use ockam_node::Context;
+#[cfg(feature = "telemetry")]
+use ockam_node::telemetry::{TransportMetrics, SECURE_CHANNEL_TRANSPORT_TYPE};
+use ockam_node::{Context, MessageSizeRecorder};
 
 use crate::models::Identifier;
 use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
@@ -23,6 +26,10 @@ use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
 use tracing::{debug, info, trace, warn};
 use tracing_attributes::instrument;
 
+/// Maximum size, in bytes, of an encrypted message in addition to its payload:
+/// nonce, AEAD tag, routes and encoding of the secure channel message
+const MAX_ENCRYPTED_MESSAGE_OVERHEAD: u64 = 16 * 1024;
+
 pub(crate) struct DecryptorHandler {
     //for debug purposes only
     pub(crate) role: &'static str,
@@ -33,6 +40,11 @@ pub(crate) struct DecryptorHandler {
     identities: Arc<Identities>,
     authority: Option<Identifier>,
     shared_state: SecureChannelSharedState,
+    message_sizes: MessageSizeRecorder,
+    #[cfg(feature = "telemetry")]
+    metrics: TransportMetrics,
+    decrypted_messages_access_control: Option<Arc<dyn IncomingAccessControl>>,
+    max_plaintext_size: u32,
 }
 
 impl DecryptorHandler {
@@ -46,6 +58,9 @@ impl DecryptorHandler {
         vault: Arc<dyn VaultForSecureChannels>,
         their_identity_id: Identifier,
         shared_state: SecureChannelSharedState,
+        message_sizes: MessageSizeRecorder,
+        decrypted_messages_access_control: Option<Arc<dyn IncomingAccessControl>>,
+        max_plaintext_size: u32,
     ) -> Self {
         Self {
             role,
@@ -55,6 +70,11 @@ impl DecryptorHandler {
             identities,
             authority,
             shared_state,
+            message_sizes,
+            #[cfg(feature = "telemetry")]
+            metrics: TransportMetrics::new(SECURE_CHANNEL_TRANSPORT_TYPE),
+            decrypted_messages_access_control,
+            max_plaintext_size,
         }
     }
 
@@ -94,7 +114,23 @@ impl DecryptorHandler {
         &mut self,
         ctx: &mut Context,
         mut msg: PlaintextPayloadMessage<'_>,
+        ingress: Option<IngressInfo>,
     ) -> Result<()> {
+        // The other party should not send payloads larger than the negotiated maximum size
+        if msg.payload.len() as u64 > self.max_plaintext_size as u64 {
+            self.shared_state
+                .dropped_oversized_messages
+                .fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "a message of {} bytes from {} was dropped by the secure channel {}: the maximum size is {} bytes",
+                msg.payload.len(),
+                self.their_identity_id,
+                self.addresses.decryptor_remote,
+                self.max_plaintext_size
+            );
+            return Ok(());
+        }
+
         // Add encryptor hop in the return_route (instead of our address)
         msg.return_route
             .modify()
@@ -102,15 +138,40 @@ impl DecryptorHandler {
 
         // Mark message LocalInfo with IdentitySecureChannelLocalInfo,
         // replacing any pre-existing entries
-        let local_info =
-            IdentitySecureChannelLocalInfo::mark(vec![], self.their_identity_id.clone())?;
+        let mut local_info = IdentitySecureChannelLocalInfo::mark(
+            vec![],
+            self.their_identity_id.clone(),
+            self.authority.clone(),
+        )?;
+
+        // Keep the description of the connection on which the encrypted message was received
+        if let Some(ingress) = ingress {
+            local_info = IngressInfo::mark(local_info, &ingress)?;
+        }
 
+        self.message_sizes.record_outbound(msg.payload.len());
         let msg = LocalMessage::new()
             .with_onward_route(msg.onward_route)
             .with_return_route(msg.return_route)
             .with_payload(msg.payload.to_vec())
             .with_local_info(local_info);
 
+        // Check decrypted messages, for example to limit the rate of messages sent by the other party
+        if let Some(access_control) = &self.decrypted_messages_access_control {
+            let relay_msg = RelayMessage::new(
+                self.addresses.decryptor_internal.clone(),
+                msg.next_on_onward_route()?,
+                msg.clone(),
+            );
+            if !access_control.is_authorized(&relay_msg).await? {
+                warn!(
+                    "a message from {} was denied by the secure channel {}",
+                    self.their_identity_id, self.addresses.decryptor_remote
+                );
+                return Ok(());
+            }
+        }
+
         match ctx
             .forward_from_address(msg, self.addresses.decryptor_internal.clone())
             .await
@@ -145,14 +206,18 @@ impl DecryptorHandler {
             self.addresses.decryptor_remote
         );
 
+        let their_clock_skew =
+            CommonStateMachine::measure_clock_skew(&self.identities, msg.timestamp)?;
         CommonStateMachine::process_identity_payload_static(
             self.identities.clone(),
             None,
             self.authority.clone(),
+            &BTreeMap::new(),
             Some(self.their_identity_id.clone()),
             msg.change_history,
             msg.credentials,
             None,
+            their_clock_skew,
         )
         .await?;
 
@@ -176,18 +241,39 @@ impl DecryptorHandler {
             &self.addresses.decryptor_remote
         );
 
-        // Decode raw payload binary
+        let ingress = msg.ingress();
         let payload = msg.into_payload();
-        let payload =
-            ockam_core::bare::read_slice(payload.as_slice(), &mut 0).ok_or_else(|| {
-                ockam_core::Error::new(Origin::Transport, Kind::Protocol, "Invalid message")
-            })?;
+        self.message_sizes.record_inbound(payload.len());
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_inbound(payload.len());
+
+        // Drop the messages which can't fit the maximum size once decrypted, without decrypting them
+        if payload.len() as u64 > self.max_plaintext_size as u64 + MAX_ENCRYPTED_MESSAGE_OVERHEAD {
+            self.shared_state
+                .dropped_oversized_messages
+                .fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "an encrypted message of {} bytes from {} was dropped by the secure channel {}: the maximum payload size is {} bytes",
+                payload.len(),
+                self.their_identity_id,
+                self.addresses.decryptor_remote,
+                self.max_plaintext_size
+            );
+            return Ok(());
+        }
 
-        // Decrypt the binary
-        let decrypted_payload = self.decryptor.decrypt(payload).await?;
+        let decrypted_payload = self.decrypt_payload(&payload).await;
+        #[cfg(feature = "telemetry")]
+        if decrypted_payload.is_err() {
+            self.metrics.record_decode_failure();
+        }
+        let decrypted_payload = decrypted_payload?;
+        self.shared_state
+            .decryptor_rekeys
+            .store(self.decryptor.rekeys(), Ordering::Relaxed);
         let msg: SecureChannelMessage = minicbor::decode(&decrypted_payload)?;
         match msg {
-            SecureChannelMessage::Payload(msg) => self.handle_payload(ctx, msg).await?,
+            SecureChannelMessage::Payload(msg) => self.handle_payload(ctx, msg, ingress).await?,
             SecureChannelMessage::RefreshCredentials(msg) => {
                 self.handle_refresh_credentials(ctx, msg).await?
             }
@@ -197,6 +283,14 @@ impl DecryptorHandler {
         Ok(())
     }
 
+    /// Decode the raw payload binary and decrypt it
+    async fn decrypt_payload(&mut self, payload: &[u8]) -> Result<Vec<u8>> {
+        let payload = ockam_core::bare::read_slice(payload, &mut 0).ok_or_else(|| {
+            ockam_core::Error::new(Origin::Transport, Kind::Protocol, "Invalid message")
+        })?;
+        self.decryptor.decrypt(payload).await
+    }
+
     /// Remove the channel keys on shutdown
     pub(crate) async fn shutdown(&self) -> Result<()> {
         self.decryptor.shutdown().await
@@ -260,6 +354,11 @@ impl Decryptor {
         result
     }
 
+    /// Number of keys renewals since the channel was created
+    pub(crate) fn rekeys(&self) -> u64 {
+        self.key_tracker.number_of_rekeys()
+    }
+
     /// Remove the channel keys on shutdown
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
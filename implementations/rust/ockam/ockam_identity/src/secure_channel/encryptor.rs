@@ -5,12 +5,20 @@ use ockam_core::{Error, Result};
 use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
 use tracing_attributes::instrument;
 
-use crate::IdentityError;
+use crate::secure_channel::KeyRotation;
+use crate::{IdentityError, TimestampInSeconds};
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: u64,
     vault: Arc<dyn VaultForSecureChannels>,
+    key_rotation: KeyRotation,
+    // Time when the current key started being used
+    key_used_since: Option<TimestampInSeconds>,
+    // Number of messages encrypted with the current key
+    messages_with_current_key: u64,
+    // Number of keys renewals since the channel was created
+    rekeys: u64,
 }
 
 // To simplify the implementation we use the same constant for the size of the message
@@ -67,7 +75,10 @@ impl Encryptor {
             let new_key = Self::rekey(&self.vault, &self.key).await?;
             let old_key = core::mem::replace(&mut self.key, new_key);
             self.vault.delete_aead_secret_key(old_key).await?;
+            self.rekeys += 1;
+            self.messages_with_current_key = 0;
         }
+        self.messages_with_current_key += 1;
 
         let (small_nonce, nonce) = Self::convert_nonce_from_u64(current_nonce);
         destination.extend_from_slice(&small_nonce);
@@ -84,7 +95,52 @@ impl Encryptor {
         nonce: u64,
         vault: Arc<dyn VaultForSecureChannels>,
     ) -> Self {
-        Self { key, nonce, vault }
+        Self {
+            key,
+            nonce,
+            vault,
+            key_rotation: KeyRotation::new(None, None),
+            key_used_since: None,
+            messages_with_current_key: 0,
+            rekeys: 0,
+        }
+    }
+
+    /// Set the thresholds after which the key must be rotated
+    pub fn with_key_rotation(mut self, key_rotation: KeyRotation) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
+    /// Number of keys renewals since the channel was created
+    pub(crate) fn rekeys(&self) -> u64 {
+        self.rekeys
+    }
+
+    /// If the current key must be rotated, skip the remaining nonces of the current
+    /// renewal interval so that the next message is encrypted with a new key.
+    /// The decryptor on the other side then derives the same key when receiving that message.
+    pub(crate) fn rotate_key_if_due(&mut self, now: TimestampInSeconds) {
+        let key_used_since = *self.key_used_since.get_or_insert(now);
+        let next_message_uses_new_key = |nonce: u64| nonce > 0 && nonce % KEY_RENEWAL_INTERVAL == 0;
+
+        if self.nonce > 0
+            && !next_message_uses_new_key(self.nonce)
+            && self.key_rotation.is_due(
+                self.messages_with_current_key,
+                now.0.saturating_sub(key_used_since.0),
+            )
+        {
+            if let Some(next_interval_start) =
+                (self.nonce / KEY_RENEWAL_INTERVAL + 1).checked_mul(KEY_RENEWAL_INTERVAL)
+            {
+                self.nonce = next_interval_start;
+            }
+        }
+
+        if next_message_uses_new_key(self.nonce) {
+            self.key_used_since = Some(now);
+        }
     }
 
     #[instrument(skip_all)]
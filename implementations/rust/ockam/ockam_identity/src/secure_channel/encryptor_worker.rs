@@ -1,4 +1,4 @@
-use core::sync::atomic::{AtomicBool, Ordering};
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use tracing::{debug, error, info, warn};
 use tracing_attributes::instrument;
@@ -9,12 +9,15 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Decodable, Error, LocalMessage, Route};
 use ockam_core::{Any, Result, Routed, Worker};
-use ockam_node::Context;
+#[cfg(feature = "telemetry")]
+use ockam_node::telemetry::{TransportMetrics, SECURE_CHANNEL_TRANSPORT_TYPE};
+use ockam_node::{Context, MessageSizeRecorder};
 
 use crate::models::CredentialAndPurposeKey;
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::{Encryptor, SIZE_OF_ENCRYPT_OVERHEAD};
+use crate::utils::TimeSource;
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, Identifier, IdentityError,
     PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
@@ -25,6 +28,26 @@ pub(crate) struct SecureChannelSharedState {
     /// Allows Decryptor to flag that we're closing the channel because we received a Close message from the other side,
     /// therefore, we don't need to send that message again to the other side
     pub(crate) should_send_close: Arc<AtomicBool>,
+    /// Number of keys renewals done by the Encryptor
+    pub(crate) encryptor_rekeys: Arc<AtomicU64>,
+    /// Number of keys renewals done by the Decryptor
+    pub(crate) decryptor_rekeys: Arc<AtomicU64>,
+    /// Number of messages refused by the Encryptor because their payload is too large
+    pub(crate) refused_oversized_messages: Arc<AtomicU64>,
+    /// Number of messages dropped by the Decryptor because their payload is too large
+    pub(crate) dropped_oversized_messages: Arc<AtomicU64>,
+}
+
+impl SecureChannelSharedState {
+    pub(crate) fn new() -> Self {
+        Self {
+            should_send_close: Arc::new(AtomicBool::new(true)),
+            encryptor_rekeys: Arc::new(AtomicU64::new(0)),
+            decryptor_rekeys: Arc::new(AtomicU64::new(0)),
+            refused_oversized_messages: Arc::new(AtomicU64::new(0)),
+            dropped_oversized_messages: Arc::new(AtomicU64::new(0)),
+        }
+    }
 }
 
 pub(crate) struct EncryptorWorker {
@@ -37,6 +60,11 @@ pub(crate) struct EncryptorWorker {
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     last_presented_credential: Option<CredentialAndPurposeKey>,
     shared_state: SecureChannelSharedState,
+    message_sizes: MessageSizeRecorder,
+    #[cfg(feature = "telemetry")]
+    metrics: TransportMetrics,
+    time_source: Arc<dyn TimeSource>,
+    max_plaintext_size: u32,
 }
 
 impl EncryptorWorker {
@@ -51,6 +79,9 @@ impl EncryptorWorker {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         last_presented_credential: Option<CredentialAndPurposeKey>,
         shared_state: SecureChannelSharedState,
+        message_sizes: MessageSizeRecorder,
+        time_source: Arc<dyn TimeSource>,
+        max_plaintext_size: u32,
     ) -> Self {
         Self {
             role,
@@ -62,6 +93,11 @@ impl EncryptorWorker {
             credential_retriever,
             last_presented_credential,
             shared_state,
+            message_sizes,
+            #[cfg(feature = "telemetry")]
+            metrics: TransportMetrics::new(SECURE_CHANNEL_TRANSPORT_TYPE),
+            time_source,
+            max_plaintext_size,
         }
     }
 
@@ -82,8 +118,14 @@ impl EncryptorWorker {
         // by reserving the capacity beforehand, we can avoid copying memory later
         destination.reserve(SIZE_OF_ENCRYPT_OVERHEAD + payload.len());
 
+        self.encryptor.rotate_key_if_due(self.time_source.now()?);
         match self.encryptor.encrypt(destination, payload).await {
-            Ok(()) => Ok(()),
+            Ok(()) => {
+                self.shared_state
+                    .encryptor_rekeys
+                    .store(self.encryptor.rekeys(), Ordering::Relaxed);
+                Ok(())
+            }
             // If encryption failed, that means we have some internal error,
             // and we may be in an invalid state, it's better to stop the Worker
             Err(err) => {
@@ -162,6 +204,22 @@ impl EncryptorWorker {
         let _ = onward_route.step();
 
         let payload = msg.into_payload();
+        self.message_sizes.record_inbound(payload.len());
+        if payload.len() as u64 > self.max_plaintext_size as u64 {
+            self.shared_state
+                .refused_oversized_messages
+                .fetch_add(1, Ordering::Relaxed);
+            return Err(Error::new(
+                Origin::Channel,
+                Kind::Invalid,
+                format!(
+                    "the message of {} bytes can't be sent on the secure channel {}: the maximum size is {} bytes",
+                    payload.len(),
+                    self.addresses.encryptor,
+                    self.max_plaintext_size
+                ),
+            ));
+        }
         let msg = PlaintextPayloadMessage {
             onward_route,
             return_route,
@@ -200,6 +258,9 @@ impl EncryptorWorker {
 
             buffer
         };
+        self.message_sizes.record_outbound(payload.len());
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_outbound(payload.len());
 
         // Decryptor doesn't need the return_route since it has `self.remote_route` as well
         let msg = LocalMessage::new()
@@ -265,6 +326,7 @@ impl EncryptorWorker {
         let msg = RefreshCredentialsMessage {
             change_history,
             credentials: vec![credential.clone()],
+            timestamp: Some(self.time_source.now()?),
         };
         let msg = SecureChannelMessage::RefreshCredentials(msg);
 
@@ -2,7 +2,8 @@ use minicbor::{Decode, Encode};
 use tracing::{debug, warn};
 
 use ockam_core::compat::boxed::Box;
-use ockam_core::compat::string::ToString;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{async_trait, Result};
@@ -12,7 +13,8 @@ use crate::models::{
     ChangeHistory, CredentialAndPurposeKey, PurposeKeyAttestation, PurposePublicKey,
 };
 use crate::{
-    CredentialRetriever, Identifier, Identities, IdentityError, SecureChannelTrustInfo, TrustPolicy,
+    ClockSkew, CredentialRetriever, CredentialsVerification, Identifier, Identities, IdentityError,
+    SecureChannelTrustInfo, TimestampInSeconds, TrustPolicy,
 };
 
 /// Interface for a state machine in a key exchange protocol
@@ -61,6 +63,8 @@ pub(super) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    pub(super) their_clock_skew: Option<ClockSkew>,
+    pub(super) max_plaintext_size: u32,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -71,11 +75,16 @@ pub(crate) struct CommonStateMachine {
     pub(super) credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     pub(super) trust_policy: Arc<dyn TrustPolicy>,
     pub(super) authority: Option<Identifier>, // TODO: Replace with ABAC
+    pub(super) required_attributes: BTreeMap<String, String>,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    pub(super) max_plaintext_size: u32,
     their_identifier: Option<Identifier>,
+    their_clock_skew: Option<ClockSkew>,
+    their_max_plaintext_size: Option<u32>,
 }
 
 impl CommonStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub(super) fn new(
         identities: Arc<Identities>,
         identifier: Identifier,
@@ -83,6 +92,8 @@ impl CommonStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        required_attributes: BTreeMap<String, String>,
+        max_plaintext_size: u32,
     ) -> Self {
         Self {
             identities,
@@ -91,8 +102,12 @@ impl CommonStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            required_attributes,
             presented_credential: None,
+            max_plaintext_size,
             their_identifier: None,
+            their_clock_skew: None,
+            their_max_plaintext_size: None,
         }
     }
 
@@ -102,6 +117,8 @@ impl CommonStateMachine {
     ///  - the current Identity Change History
     ///  - the current Secure Channel Purpose Key Attestation
     ///  - the Identity Credentials and corresponding Credentials Purpose Key Attestations
+    ///  - the current time, used by the other party to detect a clock skew
+    ///  - the maximum size of the payload of the messages we accept
     ///
     pub(super) async fn make_identity_payload(&mut self) -> Result<Vec<u8>> {
         // prepare the payload that will be sent either in message 2 or message 3
@@ -118,6 +135,8 @@ impl CommonStateMachine {
             change_history,
             purpose_key_attestation: self.purpose_key_attestation.clone(),
             credentials,
+            timestamp: Some(self.identities.time_source().now()?),
+            max_plaintext_size: Some(self.max_plaintext_size),
         };
         Ok(minicbor::to_vec(payload)?)
     }
@@ -130,14 +149,18 @@ impl CommonStateMachine {
         peer: IdentityAndCredentials,
         peer_public_key: X25519PublicKey,
     ) -> Result<()> {
+        self.their_clock_skew = Self::measure_clock_skew(&self.identities, peer.timestamp)?;
+        self.their_max_plaintext_size = peer.max_plaintext_size;
         let identifier = Self::process_identity_payload_static(
             self.identities.clone(),
             Some(self.trust_policy.clone()),
             self.authority.clone(),
+            &self.required_attributes,
             None,
             peer.change_history,
             peer.credentials,
             Some((peer.purpose_key_attestation, peer_public_key)),
+            self.their_clock_skew,
         )
         .await?;
 
@@ -149,6 +172,7 @@ impl CommonStateMachine {
     /// Return the results of the full handshake
     ///  - the other party identity
     ///  - the encryption and decryption keys to use on the next messages to exchange
+    ///  - the maximum size of the payload of the messages, negotiated with the other party
     pub(super) fn make_handshake_results(
         &self,
         handshake_keys: Option<HandshakeKeys>,
@@ -158,6 +182,8 @@ impl CommonStateMachine {
                 their_identifier,
                 handshake_keys,
                 presented_credential: self.presented_credential.clone(),
+                their_clock_skew: self.their_clock_skew,
+                max_plaintext_size: self.negotiated_max_plaintext_size(),
             }),
             _ => None,
         }
@@ -165,6 +191,32 @@ impl CommonStateMachine {
 }
 
 impl CommonStateMachine {
+    /// Both parties enforce the smallest of their maximum payload sizes.
+    /// A party which did not send its maximum size accepts payloads of any size
+    fn negotiated_max_plaintext_size(&self) -> u32 {
+        match self.their_max_plaintext_size {
+            Some(their_max_plaintext_size) => self.max_plaintext_size.min(their_max_plaintext_size),
+            None => self.max_plaintext_size,
+        }
+    }
+
+    /// Measure the difference between the time sent by the other party, if any, and our own time
+    pub(crate) fn measure_clock_skew(
+        identities: &Identities,
+        their_time: Option<TimestampInSeconds>,
+    ) -> Result<Option<ClockSkew>> {
+        match their_time {
+            Some(their_time) => {
+                let skew = ClockSkew::measure(their_time, identities.time_source().now()?);
+                if skew.exceeds_threshold() {
+                    warn!("the clock of the other party differs from ours by {skew}");
+                }
+                Ok(Some(skew))
+            }
+            None => Ok(None),
+        }
+    }
+
     /// Verify the identity sent by the other party: the Purpose Key and the credentials must be valid
     /// If everything is valid, store the identity identifier which will used to make the
     /// final state machine result
@@ -173,11 +225,13 @@ impl CommonStateMachine {
         identities: Arc<Identities>,
         trust_policy: Option<Arc<dyn TrustPolicy>>,
         authority: Option<Identifier>,
+        required_attributes: &BTreeMap<String, String>,
         expected_identifier: Option<Identifier>,
         change_history: ChangeHistory,
         credentials: Vec<CredentialAndPurposeKey>,
         // Has value if it's the identity payload during the handshake and not credential refresh
         peer_public_key: Option<(PurposeKeyAttestation, X25519PublicKey)>,
+        their_clock_skew: Option<ClockSkew>,
     ) -> Result<Identifier> {
         let their_identifier = identities
             .identities_verification()
@@ -204,7 +258,15 @@ impl CommonStateMachine {
         }
 
         Self::check_trust_policy(trust_policy, &their_identifier).await?;
-        Self::verify_credentials(identities, authority, &their_identifier, credentials).await?;
+        Self::verify_credentials(
+            identities,
+            authority,
+            &their_identifier,
+            &credentials,
+            their_clock_skew,
+        )
+        .await?;
+        Self::check_required_attributes(required_attributes, &their_identifier, &credentials)?;
 
         Ok(their_identifier)
     }
@@ -240,14 +302,15 @@ impl CommonStateMachine {
         //       Also, ABAC will be used here as well
         authority: Option<Identifier>,
         their_identifier: &Identifier,
-        credentials: Vec<CredentialAndPurposeKey>,
+        credentials: &[CredentialAndPurposeKey],
+        their_clock_skew: Option<ClockSkew>,
     ) -> Result<()> {
         if let Some(authority) = &authority {
             debug!(
                 "Got an Authority to check the credentials. There are {} credentials to check",
                 credentials.len()
             );
-            for credential in &credentials {
+            for credential in credentials {
                 let result = identities
                     .credentials()
                     .credentials_verification()
@@ -260,6 +323,13 @@ impl CommonStateMachine {
 
                 if let Some(err) = result.err() {
                     warn!("a credential could not be validated {}", err.to_string());
+                    if let Some(skew) = Self::clock_skew_explaining_failure(
+                        &identities,
+                        credential,
+                        their_clock_skew,
+                    )? {
+                        return Err(skew.error());
+                    }
                     // TODO: consider the possibility of keep going when a credential validation fails
                     return Err(IdentityError::SecureChannelVerificationFailedIncorrectCredential)?;
                 }
@@ -272,6 +342,72 @@ impl CommonStateMachine {
 
         Ok(())
     }
+
+    /// Return the clock skew with the other party if it is large enough and if the credential
+    /// failed its validity period check, so that the failure can be reported as a clock problem
+    /// rather than as a trust problem
+    fn clock_skew_explaining_failure(
+        identities: &Identities,
+        credential: &CredentialAndPurposeKey,
+        their_clock_skew: Option<ClockSkew>,
+    ) -> Result<Option<ClockSkew>> {
+        let skew = match their_clock_skew {
+            Some(skew) if skew.exceeds_threshold() => skew,
+            _ => return Ok(None),
+        };
+        let credential_data = match credential.get_credential_data() {
+            Ok(credential_data) => credential_data,
+            Err(_) => return Ok(None),
+        };
+        let now = identities.time_source().now()?;
+        if CredentialsVerification::is_valid_at(&credential_data, now) {
+            Ok(None)
+        } else {
+            Ok(Some(skew))
+        }
+    }
+
+    /// Check that the credentials sent by the other party, which have already been verified,
+    /// contain all the required attributes
+    fn check_required_attributes(
+        required_attributes: &BTreeMap<String, String>,
+        their_identifier: &Identifier,
+        credentials: &[CredentialAndPurposeKey],
+    ) -> Result<()> {
+        if required_attributes.is_empty() {
+            return Ok(());
+        }
+
+        let mut attributes = BTreeMap::new();
+        for credential in credentials {
+            let credential_data = credential.get_credential_data()?;
+            attributes.extend(
+                credential_data
+                    .subject_attributes
+                    .map
+                    .into_iter()
+                    .map(|(k, v)| (Vec::<u8>::from(k), Vec::<u8>::from(v))),
+            );
+        }
+
+        for (key, value) in required_attributes {
+            let actual = attributes.get(key.as_bytes()).map(Vec::as_slice);
+            if actual != Some(value.as_bytes()) {
+                warn!(
+                    %their_identifier,
+                    attribute = %key,
+                    "secure channel denied: the required attribute {key}={value} was not presented"
+                );
+                return Err(IdentityError::SecureChannelVerificationFailedMissingAttributes)?;
+            }
+        }
+        debug!(
+            "Checked the required attributes for SecureChannel from: {}",
+            their_identifier
+        );
+
+        Ok(())
+    }
 }
 
 /// This internal structure is used as a payload in the XX protocol
@@ -287,4 +423,8 @@ pub(super) struct IdentityAndCredentials {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(2)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
+    /// Wall-clock time of the sender, used to detect a clock skew between both parties
+    #[n(3)] pub(super) timestamp: Option<TimestampInSeconds>,
+    /// Maximum size of the payload of the messages accepted by the sender
+    #[n(4)] pub(super) max_plaintext_size: Option<u32>,
 }
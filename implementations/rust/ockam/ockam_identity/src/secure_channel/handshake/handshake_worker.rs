@@ -1,16 +1,19 @@
 use alloc::sync::Arc;
-use core::sync::atomic::AtomicBool;
 use core::time::Duration;
-use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::compat::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{
-    AllowAll, Any, Decodable, DenyAll, Error, Mailbox, Mailboxes, OutgoingAccessControl, Route,
-    Routed,
+    AllowAll, AllowSourceAddress, Any, Decodable, DenyAll, Error, IncomingAccessControl, Mailbox,
+    Mailboxes, OutgoingAccessControl, Route, Routed,
 };
-use ockam_core::{AllowOnwardAddress, Result, Worker};
+use ockam_core::{AllowOnwardAddress, MessagePriority, Result, Worker};
 use ockam_node::callback::CallbackSender;
-use ockam_node::{Context, WorkerBuilder};
-use tracing::{debug, error, info};
+use ockam_node::{Context, DelayedEvent, WorkerBuilder};
+#[cfg(feature = "std")]
+use ockam_node::{NodeEvent, NodeEventKind};
+#[cfg(feature = "telemetry")]
+use std::time::Instant;
+use tracing::{debug, error, info, warn};
 use tracing_attributes::instrument;
 
 use crate::models::Identifier;
@@ -26,17 +29,17 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{Addresses, KeyRotation, Role};
 use crate::{
-    ChangeHistoryRepository, CredentialRetriever, IdentityError, SecureChannelPurposeKey,
-    SecureChannelRegistryEntry, SecureChannels, TrustPolicy,
+    ChangeHistoryRepository, CredentialRetriever, IdentityError, SecureChannelListenerStatistics,
+    SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannels, TrustPolicy,
 };
 
 /// This struct implements a Worker receiving and sending messages
 /// on one side of the secure channel creation as specified with its role: INITIATOR or RESPONDER
 pub(crate) struct HandshakeWorker {
     secure_channels: Arc<SecureChannels>,
-    callback_sender: Option<CallbackSender<()>>,
+    callback_sender: Option<CallbackSender<Result<()>>>,
     state_machine: Box<dyn StateMachine>,
     identifier: Identifier,
     addresses: Addresses,
@@ -48,8 +51,22 @@ pub(crate) struct HandshakeWorker {
     change_history_repository: Arc<dyn ChangeHistoryRepository>,
 
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
+    decrypted_messages_access_control: Option<Arc<dyn IncomingAccessControl>>,
 
     shared_state: SecureChannelSharedState,
+    key_rotation: KeyRotation,
+
+    /// Time after which the handshake is torn down if it is not completed
+    handshake_timeout: Option<Duration>,
+    /// Timer sending a message to the internal address when the handshake times out.
+    /// It is dropped, and cancelled, once the handshake is completed
+    handshake_timer: Option<DelayedEvent<()>>,
+    /// Statistics of the listener which started this worker, for a responder
+    listener_statistics: Option<SecureChannelListenerStatistics>,
+
+    /// Start of the handshake, to record its duration
+    #[cfg(feature = "telemetry")]
+    started_at: Instant,
 }
 
 #[ockam_core::worker]
@@ -64,6 +81,10 @@ impl Worker for HandshakeWorker {
             credential_retriever.initialize().await?;
         }
 
+        if let (Some(timer), Some(timeout)) = (&mut self.handshake_timer, self.handshake_timeout) {
+            timer.schedule(timeout).await?;
+        }
+
         match self.state_machine.on_event(Initialize).await? {
             SendMessage(message) => {
                 debug!(
@@ -72,10 +93,11 @@ impl Worker for HandshakeWorker {
                     self.addresses.decryptor_remote.clone()
                 );
                 context
-                    .send_from_address(
+                    .send_from_address_with_priority(
                         self.remote_route()?,
                         message,
                         self.addresses.decryptor_remote.clone(),
+                        MessagePriority::High,
                     )
                     .await
             }
@@ -97,7 +119,9 @@ impl Worker for HandshakeWorker {
         // Some messages can come from other systems using the remote address
         // and some messages can come from the current node when the decryptor
         // used to support the decryption of Kafka messages for example
-        if self.decryptor_handler.is_some() {
+        if message.msg_addr() == self.addresses.decryptor_internal {
+            self.handle_handshake_timeout(context).await
+        } else if self.decryptor_handler.is_some() {
             self.handle_decrypt(context, message).await
         } else {
             self.handle_handshake(context, message).await
@@ -111,6 +135,14 @@ impl Worker for HandshakeWorker {
             .unregister_channel(&self.addresses.encryptor);
 
         if let Some(handler) = &self.decryptor_handler {
+            #[cfg(feature = "std")]
+            context.node_events().publish(
+                NodeEvent::new(
+                    NodeEventKind::SecureChannelClosed,
+                    self.addresses.encryptor.address(),
+                )
+                .with_detail("role", self.role.str()),
+            );
             handler.shutdown().await?
         }
 
@@ -129,10 +161,15 @@ impl HandshakeWorker {
         purpose_key: SecureChannelPurposeKey,
         trust_policy: Arc<dyn TrustPolicy>,
         decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        decrypted_messages_access_control: Option<Arc<dyn IncomingAccessControl>>,
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         authority: Option<Identifier>,
+        required_attributes: BTreeMap<String, String>,
         remote_route: Option<Route>,
         timeout: Option<Duration>,
+        listener_statistics: Option<SecureChannelListenerStatistics>,
+        key_rotation: KeyRotation,
+        max_plaintext_size: u32,
         role: Role,
     ) -> Result<()> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
@@ -148,6 +185,7 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    max_plaintext_size,
                 )
                 .await?,
             )
@@ -161,6 +199,8 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    required_attributes,
+                    max_plaintext_size,
                 )
                 .await?,
             )
@@ -173,9 +213,21 @@ impl HandshakeWorker {
             (None, None)
         };
 
-        let shared_state = SecureChannelSharedState {
-            should_send_close: Arc::new(AtomicBool::new(true)),
+        // the timer is the only sender allowed to send messages to the internal address
+        // of the worker, since that address is not used before the handshake is completed
+        let handshake_timer = match timeout {
+            Some(_) => {
+                Some(DelayedEvent::create(context, addresses.decryptor_internal.clone(), ()).await?)
+            }
+            None => None,
         };
+        let decryptor_internal_access_control: Arc<dyn IncomingAccessControl> =
+            match &handshake_timer {
+                Some(timer) => Arc::new(AllowSourceAddress(timer.address())),
+                None => Arc::new(DenyAll),
+            };
+
+        let shared_state = SecureChannelSharedState::new();
         let worker = Self {
             secure_channels,
             callback_sender,
@@ -186,14 +238,22 @@ impl HandshakeWorker {
             addresses: addresses.clone(),
             decryptor_handler: None,
             credential_retriever,
+            decrypted_messages_access_control,
             authority,
             change_history_repository: identities.change_history_repository(),
             shared_state,
+            key_rotation,
+            handshake_timeout: timeout,
+            handshake_timer,
+            listener_statistics,
+            #[cfg(feature = "telemetry")]
+            started_at: Instant::now(),
         };
 
         WorkerBuilder::new(worker)
             .with_mailboxes(Self::create_mailboxes(
                 &addresses,
+                decryptor_internal_access_control,
                 decryptor_outgoing_access_control,
             ))
             .start(context)
@@ -208,21 +268,16 @@ impl HandshakeWorker {
         // the encryptor worker is ready
         if role.is_initiator() {
             if let Some(callback_waiter) = callback_waiter {
-                // wait until the handshake is finished
-                if let Some(timeout) = timeout {
-                    let res = callback_waiter.receive_timeout(timeout).await;
-
-                    if let Some(err) = res.err() {
-                        error!(
-                            "Timeout {:?} reached when creating secure channel for: {}. Encryptor: {}",
-                            timeout, identifier, addresses.encryptor
-                        );
+                // the worker is stopped if the handshake fails, or if the creation
+                // of the secure channel is cancelled while waiting for the handshake
+                let stop_on_drop = context.stop_worker_on_drop(addresses.decryptor_remote.clone());
 
-                        return Err(err);
-                    }
-                } else {
-                    callback_waiter.receive().await?;
-                }
+                // wait until the handshake is finished
+                // the handshake result is an error if the handshake failed, for example
+                // when the credentials of the other party could not be verified, or when
+                // the handshake timed out
+                callback_waiter.receive().await??;
+                stop_on_drop.disarm();
             }
         }
 
@@ -241,11 +296,33 @@ impl HandshakeWorker {
         message: Routed<Any>,
     ) -> Result<()> {
         let payload = message.payload();
-        if let SendMessage(send_message) = self
+        let action = match self
             .state_machine
             .on_event(ReceivedMessage(Vec::<u8>::decode(payload)?))
-            .await?
+            .await
         {
+            Ok(action) => action,
+            Err(err) => {
+                #[cfg(feature = "telemetry")]
+                ockam_node::telemetry::record_handshake_duration(
+                    self.role.str(),
+                    self.started_at.elapsed(),
+                    false,
+                );
+                // let the initiator know why the handshake failed instead of letting it time out
+                return match self.callback_sender.take() {
+                    Some(callback_sender) => {
+                        error!(
+                            "The secure channel handshake failed for {}: {err}",
+                            self.addresses.encryptor
+                        );
+                        callback_sender.send(Err(err))
+                    }
+                    None => Err(err),
+                };
+            }
+        };
+        if let SendMessage(send_message) = action {
             // set the remote route by taking the most up to date message return route
             // In the case of the initiator the first return route mentions the secure channel listener
             // address so we need to wait for the return route corresponding to the remote handshake worker
@@ -253,26 +330,71 @@ impl HandshakeWorker {
             self.remote_route = Some(message.return_route());
 
             context
-                .send_from_address(
+                .send_from_address_with_priority(
                     self.remote_route()?,
                     send_message,
                     self.addresses.decryptor_remote.clone(),
+                    MessagePriority::High,
                 )
                 .await?
         };
 
         // if we reached the final state we can make a pair of encryptor/decryptor
         if let Some(final_state) = self.state_machine.get_handshake_results() {
+            // the handshake can't time out anymore
+            self.handshake_timer = None;
             // start the encryptor worker and return the decryptor
             self.decryptor_handler = Some(self.finalize(context, final_state).await?);
+            #[cfg(feature = "telemetry")]
+            ockam_node::telemetry::record_handshake_duration(
+                self.role.str(),
+                self.started_at.elapsed(),
+                true,
+            );
             if let Some(callback_sender) = self.callback_sender.take() {
-                callback_sender.send(())?;
+                callback_sender.send(Ok(()))?;
             }
         };
 
         Ok(())
     }
 
+    /// Tear down a handshake which was not completed in time:
+    /// the worker is stopped, which deregisters all its addresses, and the initiator, if this
+    /// worker is the initiator, receives an error instead of waiting for the handshake
+    async fn handle_handshake_timeout(&mut self, context: &mut Context) -> Result<()> {
+        // the timer may fire while the last handshake message is being processed
+        if self.decryptor_handler.is_some() {
+            return Ok(());
+        }
+        let timeout = self.handshake_timeout.unwrap_or_default();
+        warn!(
+            "The secure channel handshake was not completed after {timeout:?}. Stopping {} {}",
+            self.role, self.addresses.decryptor_remote
+        );
+        #[cfg(feature = "telemetry")]
+        ockam_node::telemetry::record_handshake_duration(
+            self.role.str(),
+            self.started_at.elapsed(),
+            false,
+        );
+        if let Some(statistics) = &self.listener_statistics {
+            statistics.add_abandoned_handshake();
+        }
+        if let Some(callback_sender) = self.callback_sender.take() {
+            let _ = callback_sender.send(Err(Error::new(
+                Origin::KeyExchange,
+                Kind::Timeout,
+                format!(
+                    "the secure channel handshake with the other party was not completed after {timeout:?}"
+                ),
+            )));
+        }
+        context
+            .stop_worker(self.addresses.decryptor_remote.clone())
+            .await
+    }
+
     /// This function is instrumented as if there was a DecryptorWorker type for a better
     /// readability of traces (Because there's a corresponding EncryptorWorker::handle_message)
     ///
@@ -305,6 +427,7 @@ impl HandshakeWorker {
     /// Create mailboxes and access rights for the workers involved in the secure channel creation
     pub(crate) fn create_mailboxes(
         addresses: &Addresses,
+        decryptor_internal_access_control: Arc<dyn IncomingAccessControl>,
         decryptor_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     ) -> Mailboxes {
         let remote_mailbox = Mailbox::new(
@@ -316,9 +439,10 @@ impl HandshakeWorker {
             // Communicate to the other side of the channel during key exchange
             Arc::new(AllowAll),
         );
+        // Only receives the notification of a handshake timeout
         let internal_mailbox = Mailbox::new(
             addresses.decryptor_internal.clone(),
-            Arc::new(DenyAll),
+            decryptor_internal_access_control,
             decryptor_outgoing_access_control,
         );
         let api_mailbox = Mailbox::new(
@@ -348,6 +472,11 @@ impl HandshakeWorker {
             self.secure_channels.identities.vault().secure_channel_vault,
             handshake_results.their_identifier.clone(),
             self.shared_state.clone(),
+            context
+                .message_sizes()
+                .recorder(&self.addresses.decryptor_remote),
+            self.decrypted_messages_access_control.clone(),
+            handshake_results.max_plaintext_size,
         );
 
         // create a separate encryptor worker which will be started independently
@@ -360,12 +489,16 @@ impl HandshakeWorker {
                     handshake_results.handshake_keys.encryption_key,
                     0,
                     self.secure_channels.identities.vault().secure_channel_vault,
-                ),
+                )
+                .with_key_rotation(self.key_rotation),
                 self.identifier.clone(),
                 self.change_history_repository.clone(),
                 self.credential_retriever.clone(),
                 handshake_results.presented_credential,
                 self.shared_state.clone(),
+                context.message_sizes().recorder(&self.addresses.encryptor),
+                self.secure_channels.identities.time_source(),
+                handshake_results.max_plaintext_size,
             );
 
             let next_hop = self.remote_route()?.next()?.clone();
@@ -415,8 +548,29 @@ impl HandshakeWorker {
             self.addresses.decryptor_api.clone(),
             self.role.is_initiator(),
             self.identifier.clone(),
-            handshake_results.their_identifier,
+            handshake_results.their_identifier.clone(),
             their_decryptor_address,
+        )
+        .with_their_clock_skew(handshake_results.their_clock_skew)
+        .with_rekeys_counters(
+            self.shared_state.encryptor_rekeys.clone(),
+            self.shared_state.decryptor_rekeys.clone(),
+        )
+        .with_max_plaintext_size(
+            handshake_results.max_plaintext_size,
+            self.shared_state.refused_oversized_messages.clone(),
+            self.shared_state.dropped_oversized_messages.clone(),
+        );
+
+        #[cfg(feature = "std")]
+        context.node_events().publish(
+            NodeEvent::new(
+                NodeEventKind::SecureChannelEstablished,
+                self.addresses.encryptor.address(),
+            )
+            .with_detail("role", self.role.str())
+            .with_detail("local_identifier", &self.identifier)
+            .with_detail("remote_identifier", &handshake_results.their_identifier),
         );
 
         self.secure_channels
@@ -1,7 +1,7 @@
 use delegate::delegate;
 use ockam_core::async_trait;
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::compat::{boxed::Box, collections::BTreeMap, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
@@ -97,6 +97,7 @@ impl InitiatorStateMachine {
 }
 
 impl InitiatorStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -105,6 +106,7 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        max_plaintext_size: u32,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -113,6 +115,8 @@ impl InitiatorStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            BTreeMap::new(),
+            max_plaintext_size,
         );
 
         Ok(InitiatorStateMachine {
@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use delegate::delegate;
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::{boxed::Box, vec::Vec};
+use ockam_core::compat::{boxed::Box, collections::BTreeMap, string::String, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
@@ -100,6 +100,7 @@ impl ResponderStateMachine {
 }
 
 impl ResponderStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -108,6 +109,8 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        required_attributes: BTreeMap<String, String>,
+        max_plaintext_size: u32,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -116,6 +119,8 @@ impl ResponderStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            required_attributes,
+            max_plaintext_size,
         );
 
         Ok(ResponderStateMachine {
@@ -0,0 +1,95 @@
+use core::time::Duration;
+
+/// Default maximum duration during which the same key is used to encrypt the messages of a secure channel
+pub const DEFAULT_KEY_ROTATION_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Thresholds after which the encryptor of a secure channel starts using a new key.
+///
+/// The keys of a secure channel are always renewed every 32 messages, as part of the protocol,
+/// by deriving a new key from the current one. A key rotation skips the remaining messages of the
+/// current renewal interval so that the next message is encrypted with the next key.
+/// The other side of the channel derives the same key from the nonce of that message, so the rotation
+/// is transparent for the workers sending messages through the channel.
+///
+/// This is mostly useful for channels which stay up for a long time with little traffic.
+/// The rotation is checked before sending a message. A channel which does not send any messages
+/// keeps its key until the next message is sent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyRotation {
+    interval: Option<Duration>,
+    messages: Option<u64>,
+}
+
+impl Default for KeyRotation {
+    fn default() -> Self {
+        Self {
+            interval: Some(DEFAULT_KEY_ROTATION_INTERVAL),
+            messages: None,
+        }
+    }
+}
+
+impl KeyRotation {
+    /// Rotate the key when the current key has been used for longer than `interval`
+    /// and/or when the current key has been used to encrypt `messages` messages.
+    ///
+    /// Since keys are renewed every 32 messages anyway, a number of messages larger than 32
+    /// has no effect.
+    pub fn new(interval: Option<Duration>, messages: Option<u64>) -> Self {
+        Self { interval, messages }
+    }
+
+    /// Set the maximum duration during which a key is used
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(interval);
+        self
+    }
+
+    /// Set the maximum number of messages encrypted with the same key
+    pub fn with_messages(mut self, messages: u64) -> Self {
+        self.messages = Some(messages);
+        self
+    }
+
+    /// Maximum duration during which a key is used
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Maximum number of messages encrypted with the same key
+    pub fn messages(&self) -> Option<u64> {
+        self.messages
+    }
+
+    /// Return true if a key which was used to encrypt `messages` messages
+    /// during `elapsed_seconds` seconds must be rotated
+    pub(crate) fn is_due(&self, messages: u64, elapsed_seconds: u64) -> bool {
+        self.messages.map(|max| messages >= max).unwrap_or(false)
+            || self
+                .interval
+                .map(|interval| elapsed_seconds >= interval.as_secs())
+                .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_rotation_is_due() {
+        let key_rotation = KeyRotation::new(Some(Duration::from_secs(10)), Some(5));
+        assert!(!key_rotation.is_due(0, 0));
+        assert!(!key_rotation.is_due(4, 9));
+        assert!(key_rotation.is_due(5, 0));
+        assert!(key_rotation.is_due(0, 10));
+
+        let key_rotation = KeyRotation::new(None, None);
+        assert!(!key_rotation.is_due(u64::MAX, u64::MAX));
+
+        assert_eq!(
+            KeyRotation::default().interval(),
+            Some(DEFAULT_KEY_ROTATION_INTERVAL)
+        );
+    }
+}
@@ -26,6 +26,11 @@ impl KeyTracker {
 }
 
 impl KeyTracker {
+    /// Number of keys renewals since the channel was created
+    pub(crate) fn number_of_rekeys(&self) -> u64 {
+        self.number_of_rekeys
+    }
+
     /// The rekeying algorithm specifies a series of intervals of size self.renewal_interval
     /// where each interval corresponds to a set of contiguous nonces using the same key.
     ///
@@ -1,7 +1,7 @@
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Address, Any, Result, Routed, Worker};
-use ockam_node::Context;
+use ockam_node::{Context, WorkerBuilder};
 
 use crate::models::Identifier;
 use crate::secure_channel::addresses::Addresses;
@@ -38,9 +38,14 @@ impl SecureChannelListenerWorker {
     ) -> Result<()> {
         options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
 
+        let incoming_access_control = options.incoming_access_control();
         let listener = Self::new(secure_channels.clone(), identifier.clone(), options);
 
-        ctx.start_worker(address, listener).await?;
+        WorkerBuilder::new(listener)
+            .with_address(address)
+            .with_incoming_access_control_arc(incoming_access_control)
+            .start(ctx)
+            .await?;
 
         Ok(())
     }
@@ -94,10 +99,15 @@ impl Worker for SecureChannelListenerWorker {
             purpose_key,
             self.options.trust_policy.clone(),
             access_control.decryptor_outgoing_access_control,
+            self.options.decrypted_messages_access_control(),
             credential_retriever,
             self.options.authority.clone(),
+            self.options.required_attributes.clone(),
             None,
-            None,
+            Some(self.options.handshake_timeout),
+            Some(self.options.statistics.clone()),
+            self.options.key_rotation,
+            self.options.max_plaintext_size,
             Role::Responder,
         )
         .await?;
@@ -1,9 +1,12 @@
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::vec::Vec;
-use ockam_core::{Decodable, Encodable, LocalInfo, LocalMessage, Result};
+use ockam_core::{
+    async_trait, Decodable, Encodable, LocalInfo, LocalMessage, Message, Result, Routed,
+};
 use serde::{Deserialize, Serialize};
 
 use crate::models::Identifier;
-use crate::IdentityError;
+use crate::{AttributesEntry, IdentitiesAttributes, IdentityError};
 
 /// Identity SecureChannel LocalInfo unique Identifier
 pub const IDENTITY_SECURE_CHANNEL_IDENTIFIER: &str = "IDENTITY_SECURE_CHANNEL_IDENTIFIER";
@@ -12,6 +15,7 @@ pub const IDENTITY_SECURE_CHANNEL_IDENTIFIER: &str = "IDENTITY_SECURE_CHANNEL_ID
 #[derive(Serialize, Deserialize)]
 pub struct IdentitySecureChannelLocalInfo {
     their_identity_id: Identifier,
+    authority: Option<Identifier>,
 }
 
 impl IdentitySecureChannelLocalInfo {
@@ -59,6 +63,31 @@ impl IdentitySecureChannelLocalInfo {
     pub fn their_identity_id(&self) -> Identifier {
         self.their_identity_id.clone()
     }
+
+    /// Authority verifying the credentials presented on the secure channel, if any
+    pub fn authority(&self) -> Option<Identifier> {
+        self.authority.clone()
+    }
+
+    /// Return the attributes of the other side of the secure channel, attested by the
+    /// authority of the channel.
+    ///
+    /// The returned entry also contains the issuer and the expiration of the attributes.
+    /// `None` is returned if the channel has no authority, or if no valid credential was
+    /// presented by the other side.
+    pub async fn peer_attributes(
+        &self,
+        identities_attributes: &IdentitiesAttributes,
+    ) -> Result<Option<AttributesEntry>> {
+        match &self.authority {
+            Some(authority) => {
+                identities_attributes
+                    .get_attributes(&self.their_identity_id, authority)
+                    .await
+            }
+            None => Ok(None),
+        }
+    }
 }
 
 impl IdentitySecureChannelLocalInfo {
@@ -67,13 +96,47 @@ impl IdentitySecureChannelLocalInfo {
     pub fn mark(
         mut local_info: Vec<LocalInfo>,
         their_identity_id: Identifier,
+        authority: Option<Identifier>,
     ) -> Result<Vec<LocalInfo>> {
         // strip out any pre-existing IdentitySecureChannelLocalInfo
         local_info.retain(|x| x.type_identifier() != IDENTITY_SECURE_CHANNEL_IDENTIFIER);
 
         // mark the vector
-        local_info.push(Self { their_identity_id }.to_local_info()?);
+        local_info.push(
+            Self {
+                their_identity_id,
+                authority,
+            }
+            .to_local_info()?,
+        );
 
         Ok(local_info)
     }
 }
+
+/// Access to the attributes of the identity which sent a message through a secure channel
+#[async_trait]
+pub trait PeerAttributes {
+    /// Return the attributes of the identity which sent the message, attested by the
+    /// authority of the secure channel the message was received from.
+    ///
+    /// `None` is returned if the message was not received through a secure channel,
+    /// or if the sender has no valid attributes attested by the authority of the channel.
+    async fn peer_attributes(
+        &self,
+        identities_attributes: &IdentitiesAttributes,
+    ) -> Result<Option<AttributesEntry>>;
+}
+
+#[async_trait]
+impl<M: Message + Sync> PeerAttributes for Routed<M> {
+    async fn peer_attributes(
+        &self,
+        identities_attributes: &IdentitiesAttributes,
+    ) -> Result<Option<AttributesEntry>> {
+        match IdentitySecureChannelLocalInfo::find_info(self.local_message()) {
+            Ok(info) => info.peer_attributes(identities_attributes).await,
+            Err(_) => Ok(None),
+        }
+    }
+}
@@ -1,9 +1,16 @@
 use crate::models::{ChangeHistory, CredentialAndPurposeKey};
+use crate::TimestampInSeconds;
 use minicbor::{Decode, Encode};
 use ockam_core::compat::vec::Vec;
 use ockam_core::Route;
 
 /// Secure Channel Message format.
+///
+/// # Wire stability
+///
+/// This message, [`PlaintextPayloadMessage`] and [`RefreshCredentialsMessage`] are exchanged
+/// between nodes once the secure channel is established. Their CBOR encoding is stable and is
+/// checked against the fixtures of `ockam_identity/tests/fixtures/wire`.
 #[derive(Debug, Encode, Decode, Clone)]
 #[rustfmt::skip]
 pub enum SecureChannelMessage<'a> {
@@ -37,4 +44,6 @@ pub struct RefreshCredentialsMessage {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(1)] pub credentials: Vec<CredentialAndPurposeKey>,
+    /// Wall-clock time of the sender, used to detect a clock skew between both parties
+    #[n(2)] pub timestamp: Option<TimestampInSeconds>,
 }
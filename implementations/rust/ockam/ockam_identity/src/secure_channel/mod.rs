@@ -2,10 +2,12 @@
 pub mod access_control;
 mod addresses;
 mod api;
+mod clock_skew;
 mod decryptor;
 mod encryptor;
 mod encryptor_worker;
 pub(crate) mod handshake;
+mod key_rotation;
 mod key_tracker;
 mod listener;
 mod local_info;
@@ -21,7 +23,9 @@ pub mod trust_policy;
 pub use access_control::*;
 pub(crate) use addresses::*;
 pub use api::*;
+pub use clock_skew::*;
 pub(crate) use handshake::*;
+pub use key_rotation::*;
 pub(crate) use listener::*;
 pub use local_info::*;
 pub use message::*;
@@ -32,7 +36,9 @@ pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
-    use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor, KeyRotation};
+    use crate::TimestampInSeconds;
+    use core::time::Duration;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::Result;
     use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
@@ -128,6 +134,41 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_key_rotation_by_messages() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let mut encryptor = encryptor.with_key_rotation(KeyRotation::new(None, Some(5)));
+
+        for n in 0..100 {
+            let msg = vec![n];
+            let mut ciphertext = Vec::new();
+            encryptor.rotate_key_if_due(TimestampInSeconds(0));
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap());
+        }
+        // a new key is used every 5 messages
+        assert_eq!(encryptor.rekeys(), 19);
+        assert_eq!(decryptor.rekeys(), 19);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_key_rotation_by_time() {
+        let (encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+        let mut encryptor =
+            encryptor.with_key_rotation(KeyRotation::new(Some(Duration::from_secs(10)), None));
+
+        for (n, now) in [0, 5, 9, 10, 11, 25, 26].into_iter().enumerate() {
+            let msg = vec![n as u8];
+            let mut ciphertext = Vec::new();
+            encryptor.rotate_key_if_due(TimestampInSeconds(now));
+            encryptor.encrypt(&mut ciphertext, &msg).await.unwrap();
+            assert_eq!(msg, decryptor.decrypt(&ciphertext).await.unwrap());
+        }
+        // the key is rotated at 10s and 25s
+        assert_eq!(encryptor.rekeys(), 2);
+        assert_eq!(decryptor.rekeys(), 2);
+    }
+
     async fn create_encryptor_decryptor() -> Result<(Encryptor, Decryptor)> {
         let vault1 = SoftwareVaultForSecureChannels::create().await?;
         let vault2 = SoftwareVaultForSecureChannels::create().await?;
@@ -1,13 +1,17 @@
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, OutgoingAccessControl, Result};
+use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl, Result};
+#[cfg(feature = "std")]
+use ockam_core::{RateLimit, RateLimitingAccessControl};
 
 use crate::models::CredentialAndPurposeKey;
-use crate::secure_channel::Addresses;
+use crate::secure_channel::{Addresses, KeyRotation};
 use crate::{
     CredentialRetrieverCreator, Identifier, IdentityError, MemoryCredentialRetrieverCreator,
-    TrustEveryonePolicy, TrustPolicy,
+    SecureChannelListenerStatistics, TrustEveryonePolicy, TrustPolicy,
 };
 
 use core::fmt;
@@ -17,6 +21,14 @@ use core::time::Duration;
 /// This is the default timeout for creating a secure channel
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
 
+/// This is the default timeout after which a secure channel listener tears down
+/// a handshake which was started but not completed
+pub const DEFAULT_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// This is the default maximum size, in bytes, of the payload of a message sent through
+/// a secure channel. It is high enough to not limit the messages exchanged by default
+pub const DEFAULT_MAX_PLAINTEXT_SIZE: u32 = u32::MAX;
+
 /// Trust options for a Secure Channel
 pub struct SecureChannelOptions {
     pub(crate) flow_control_id: FlowControlId,
@@ -26,6 +38,10 @@ pub struct SecureChannelOptions {
     // To obtain our credentials
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
     pub(crate) timeout: Duration,
+    // Thresholds after which the encryption key is rotated
+    pub(crate) key_rotation: KeyRotation,
+    // Maximum size of the payload of the messages exchanged on the channel
+    pub(crate) max_plaintext_size: u32,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -48,6 +64,8 @@ impl SecureChannelOptions {
             authority: None,
             credential_retriever_creator: None,
             timeout: DEFAULT_TIMEOUT,
+            key_rotation: KeyRotation::default(),
+            max_plaintext_size: DEFAULT_MAX_PLAINTEXT_SIZE,
         }
     }
 
@@ -57,6 +75,24 @@ impl SecureChannelOptions {
         self
     }
 
+    /// Sets the thresholds after which the encryption key is rotated,
+    /// different from the default ones [`KeyRotation::default`]
+    pub fn with_key_rotation(mut self, key_rotation: KeyRotation) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of the payload of the messages exchanged on the channel,
+    /// different from the default one [`DEFAULT_MAX_PLAINTEXT_SIZE`].
+    ///
+    /// The maximum size is exchanged during the handshake and both sides enforce the smallest
+    /// of their maximum sizes: larger messages are refused by the encryptor and dropped by the decryptor.
+    /// The data encrypted and decrypted with the API of the channel is not limited
+    pub fn with_max_plaintext_size(mut self, max_plaintext_size: u32) -> Self {
+        self.max_plaintext_size = max_plaintext_size;
+        self
+    }
+
     /// Set [`CredentialRetrieverCreator`]
     pub fn with_credential_retriever_creator(
         mut self,
@@ -144,6 +180,21 @@ pub struct SecureChannelListenerOptions {
     pub(crate) authority: Option<Identifier>,
     // To obtain our credentials
     pub(crate) credential_retriever_creator: Option<Arc<dyn CredentialRetrieverCreator>>,
+    // Attributes which must be present in the other party's credentials
+    pub(crate) required_attributes: BTreeMap<String, String>,
+    // Access control for the messages sent to the listener and through the spawned secure channels
+    pub(crate) incoming_access_control: Option<Arc<dyn IncomingAccessControl>>,
+    // Rate limit for the messages sent to the listener and through the spawned secure channels
+    #[cfg(feature = "std")]
+    pub(crate) rate_limiting_access_control: Option<Arc<RateLimitingAccessControl>>,
+    // Thresholds after which the encryption key of the spawned secure channels is rotated
+    pub(crate) key_rotation: KeyRotation,
+    // Time after which an incomplete handshake is torn down
+    pub(crate) handshake_timeout: Duration,
+    // Statistics of the handshakes started with the listener
+    pub(crate) statistics: SecureChannelListenerStatistics,
+    // Maximum size of the payload of the messages exchanged on the spawned secure channels
+    pub(crate) max_plaintext_size: u32,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -164,6 +215,14 @@ impl SecureChannelListenerOptions {
             trust_policy: Arc::new(TrustEveryonePolicy),
             authority: None,
             credential_retriever_creator: None,
+            required_attributes: BTreeMap::new(),
+            incoming_access_control: None,
+            #[cfg(feature = "std")]
+            rate_limiting_access_control: None,
+            key_rotation: KeyRotation::default(),
+            handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
+            statistics: SecureChannelListenerStatistics::default(),
+            max_plaintext_size: DEFAULT_MAX_PLAINTEXT_SIZE,
         }
     }
 
@@ -207,6 +266,66 @@ impl SecureChannelListenerOptions {
         self
     }
 
+    /// Sets the thresholds after which the encryption key of the spawned secure channels
+    /// is rotated, different from the default ones [`KeyRotation::default`]
+    pub fn with_key_rotation(mut self, key_rotation: KeyRotation) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
+    /// Sets the time after which a handshake which was started but not completed is torn down,
+    /// different from the default one [`DEFAULT_HANDSHAKE_TIMEOUT`]
+    pub fn with_handshake_timeout(mut self, handshake_timeout: Duration) -> Self {
+        self.handshake_timeout = handshake_timeout;
+        self
+    }
+
+    /// Sets the maximum size, in bytes, of the payload of the messages exchanged on the spawned
+    /// secure channels, different from the default one [`DEFAULT_MAX_PLAINTEXT_SIZE`].
+    ///
+    /// Each spawned secure channel enforces the smallest of this size and of the size
+    /// sent by the other party during the handshake
+    pub fn with_max_plaintext_size(mut self, max_plaintext_size: u32) -> Self {
+        self.max_plaintext_size = max_plaintext_size;
+        self
+    }
+
+    /// Require the other party to present, during the handshake, a credential
+    /// containing the attribute `key` with the given `value`.
+    /// Credentials are verified with the Authority set with [`Self::with_authority`]
+    pub fn with_required_attribute(mut self, key: &str, value: &str) -> Self {
+        self.required_attributes
+            .insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Limit the rate of messages accepted from each sender.
+    /// Handshake requests sent to the listener are counted per source address and the messages
+    /// received on the spawned secure channels are counted per identifier of the other party
+    #[cfg(feature = "std")]
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        let access_control = Arc::new(
+            RateLimitingAccessControl::new(Arc::new(AllowAll), rate_limit)
+                .with_sender_local_info(crate::IDENTITY_SECURE_CHANNEL_IDENTIFIER),
+        );
+        self.incoming_access_control = Some(access_control.clone());
+        self.rate_limiting_access_control = Some(access_control);
+        self
+    }
+
+    /// Return the rate limiting access control, if a rate limit was set,
+    /// in order to retrieve the number of denied messages
+    #[cfg(feature = "std")]
+    pub fn rate_limiting_access_control(&self) -> Option<Arc<RateLimitingAccessControl>> {
+        self.rate_limiting_access_control.clone()
+    }
+
+    /// Return the statistics of the handshakes started with the listener,
+    /// for example the number of abandoned handshakes
+    pub fn statistics(&self) -> SecureChannelListenerStatistics {
+        self.statistics.clone()
+    }
+
     /// Freshly generated [`FlowControlId`]
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
@@ -214,6 +333,21 @@ impl SecureChannelListenerOptions {
 }
 
 impl SecureChannelListenerOptions {
+    /// Access control for the messages sent to the listener
+    pub(crate) fn incoming_access_control(&self) -> Arc<dyn IncomingAccessControl> {
+        match &self.incoming_access_control {
+            Some(access_control) => access_control.clone(),
+            None => Arc::new(AllowAll),
+        }
+    }
+
+    /// Access control for the messages decrypted by the spawned secure channels
+    pub(crate) fn decrypted_messages_access_control(
+        &self,
+    ) -> Option<Arc<dyn IncomingAccessControl>> {
+        self.incoming_access_control.clone()
+    }
+
     pub(crate) fn setup_flow_control_for_listener(
         &self,
         flow_controls: &FlowControls,
@@ -1,10 +1,11 @@
+use core::sync::atomic::{AtomicU64, Ordering};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{Address, Result};
 
 use crate::models::Identifier;
-use crate::IdentityError;
+use crate::{ClockSkew, IdentityError, DEFAULT_MAX_PLAINTEXT_SIZE};
 
 /// Known information about particular SecureChannel
 #[derive(Clone, Debug)]
@@ -17,6 +18,12 @@ pub struct SecureChannelRegistryEntry {
     my_id: Identifier,
     their_id: Identifier,
     their_decryptor_address: Address,
+    their_clock_skew: Option<ClockSkew>,
+    encryptor_rekeys: Arc<AtomicU64>,
+    decryptor_rekeys: Arc<AtomicU64>,
+    max_plaintext_size: u32,
+    refused_oversized_messages: Arc<AtomicU64>,
+    dropped_oversized_messages: Arc<AtomicU64>,
 }
 
 impl SecureChannelRegistryEntry {
@@ -41,9 +48,46 @@ impl SecureChannelRegistryEntry {
             my_id,
             their_id,
             their_decryptor_address,
+            their_clock_skew: None,
+            encryptor_rekeys: Arc::new(AtomicU64::new(0)),
+            decryptor_rekeys: Arc::new(AtomicU64::new(0)),
+            max_plaintext_size: DEFAULT_MAX_PLAINTEXT_SIZE,
+            refused_oversized_messages: Arc::new(AtomicU64::new(0)),
+            dropped_oversized_messages: Arc::new(AtomicU64::new(0)),
         }
     }
 
+    /// Set the clock skew measured with the other side during the handshake
+    pub fn with_their_clock_skew(mut self, their_clock_skew: Option<ClockSkew>) -> Self {
+        self.their_clock_skew = their_clock_skew;
+        self
+    }
+
+    /// Share the counters of keys renewals updated by the encryptor and decryptor of the channel
+    pub fn with_rekeys_counters(
+        mut self,
+        encryptor_rekeys: Arc<AtomicU64>,
+        decryptor_rekeys: Arc<AtomicU64>,
+    ) -> Self {
+        self.encryptor_rekeys = encryptor_rekeys;
+        self.decryptor_rekeys = decryptor_rekeys;
+        self
+    }
+
+    /// Set the maximum size of the payload of the messages negotiated during the handshake,
+    /// and share the counters of oversized messages updated by the encryptor and decryptor
+    pub fn with_max_plaintext_size(
+        mut self,
+        max_plaintext_size: u32,
+        refused_oversized_messages: Arc<AtomicU64>,
+        dropped_oversized_messages: Arc<AtomicU64>,
+    ) -> Self {
+        self.max_plaintext_size = max_plaintext_size;
+        self.refused_oversized_messages = refused_oversized_messages;
+        self.dropped_oversized_messages = dropped_oversized_messages;
+        self
+    }
+
     /// Encryptor messaging address
     pub fn encryptor_messaging_address(&self) -> &Address {
         &self.encryptor_messaging_address
@@ -83,6 +127,40 @@ impl SecureChannelRegistryEntry {
     pub fn their_decryptor_address(&self) -> Address {
         self.their_decryptor_address.clone()
     }
+
+    /// Difference between the clock of the other side and ours, measured during the handshake.
+    /// It is not available if the other side did not send its time
+    pub fn their_clock_skew(&self) -> Option<ClockSkew> {
+        self.their_clock_skew
+    }
+
+    /// Number of keys renewals done so far to encrypt the messages sent to the other side
+    pub fn encryptor_rekeys(&self) -> u64 {
+        self.encryptor_rekeys.load(Ordering::Relaxed)
+    }
+
+    /// Number of keys renewals done so far to decrypt the messages received from the other side
+    pub fn decryptor_rekeys(&self) -> u64 {
+        self.decryptor_rekeys.load(Ordering::Relaxed)
+    }
+
+    /// Maximum size of the payload of the messages, negotiated with the other side:
+    /// the smallest of the maximum sizes of both sides
+    pub fn max_plaintext_size(&self) -> u32 {
+        self.max_plaintext_size
+    }
+
+    /// Number of messages which were not sent to the other side because their payload
+    /// exceeds the maximum size
+    pub fn refused_oversized_messages(&self) -> u64 {
+        self.refused_oversized_messages.load(Ordering::Relaxed)
+    }
+
+    /// Number of messages received from the other side which were dropped because their
+    /// payload exceeds the maximum size
+    pub fn dropped_oversized_messages(&self) -> u64 {
+        self.dropped_oversized_messages.load(Ordering::Relaxed)
+    }
 }
 
 /// Registry of all known Secure Channels
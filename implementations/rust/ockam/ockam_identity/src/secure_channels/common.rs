@@ -1,5 +1,7 @@
 use core::fmt;
 use core::fmt::Formatter;
+use core::sync::atomic::{AtomicU64, Ordering};
+use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Address;
 
@@ -61,6 +63,7 @@ impl SecureChannel {
 pub struct SecureChannelListener {
     address: Address,
     flow_control_id: FlowControlId,
+    statistics: SecureChannelListenerStatistics,
 }
 
 impl fmt::Display for SecureChannelListener {
@@ -79,8 +82,14 @@ impl SecureChannelListener {
         Self {
             address,
             flow_control_id,
+            statistics: SecureChannelListenerStatistics::default(),
         }
     }
+    /// Set the statistics updated by the listener
+    pub fn with_statistics(mut self, statistics: SecureChannelListenerStatistics) -> Self {
+        self.statistics = statistics;
+        self
+    }
     /// [`Address`] of the corresponding
     /// [`SecureChannelListener`](super::super::SecureChannelListener) Worker that can be used
     /// to stop it
@@ -91,4 +100,27 @@ impl SecureChannelListener {
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
     }
+    /// Statistics of the handshakes started with the listener
+    pub fn statistics(&self) -> &SecureChannelListenerStatistics {
+        &self.statistics
+    }
+}
+
+/// Statistics of the handshakes started with a
+/// [`SecureChannelListener`](super::super::SecureChannelListener).
+/// The statistics are shared by all the clones of this struct.
+#[derive(Debug, Clone, Default)]
+pub struct SecureChannelListenerStatistics {
+    abandoned_handshakes: Arc<AtomicU64>,
+}
+
+impl SecureChannelListenerStatistics {
+    /// Number of handshakes which were torn down because they were not completed in time
+    pub fn abandoned_handshakes(&self) -> u64 {
+        self.abandoned_handshakes.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_abandoned_handshake(&self) {
+        self.abandoned_handshakes.fetch_add(1, Ordering::Relaxed);
+    }
 }
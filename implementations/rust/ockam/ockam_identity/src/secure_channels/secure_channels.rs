@@ -1,3 +1,4 @@
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 use ockam_core::{Address, Route};
@@ -10,6 +11,7 @@ use crate::secure_channel::{
     Addresses, Role, SecureChannelListenerOptions, SecureChannelListenerWorker,
     SecureChannelOptions, SecureChannelRegistry,
 };
+use crate::CredentialIssuerConnections;
 #[cfg(feature = "storage")]
 use crate::SecureChannelsBuilder;
 use crate::{SecureChannel, SecureChannelListener, Vault};
@@ -19,6 +21,7 @@ use crate::{SecureChannel, SecureChannelListener, Vault};
 pub struct SecureChannels {
     pub(crate) identities: Arc<Identities>,
     pub(crate) secure_channel_registry: SecureChannelRegistry,
+    pub(crate) credential_issuer_connections: CredentialIssuerConnections,
 }
 
 impl SecureChannels {
@@ -30,6 +33,7 @@ impl SecureChannels {
         Self {
             identities,
             secure_channel_registry,
+            credential_issuer_connections: CredentialIssuerConnections::default(),
         }
     }
 
@@ -53,6 +57,11 @@ impl SecureChannels {
         self.secure_channel_registry.clone()
     }
 
+    /// Return the connections to the credential issuers, shared by all the credential retrievers
+    pub fn credential_issuer_connections(&self) -> CredentialIssuerConnections {
+        self.credential_issuer_connections.clone()
+    }
+
     /// Create a builder for secure channels
     #[cfg(feature = "storage")]
     pub async fn builder() -> Result<SecureChannelsBuilder> {
@@ -75,6 +84,7 @@ impl SecureChannels {
         let address = address.into();
         let options = options.into();
         let flow_control_id = options.flow_control_id.clone();
+        let statistics = options.statistics();
 
         SecureChannelListenerWorker::create(
             ctx,
@@ -85,7 +95,7 @@ impl SecureChannels {
         )
         .await?;
 
-        Ok(SecureChannelListener::new(address, flow_control_id))
+        Ok(SecureChannelListener::new(address, flow_control_id).with_statistics(statistics))
     }
 
     /// Initiate a SecureChannel using `Route` to the SecureChannel listener and [`SecureChannelOptions`]
@@ -130,10 +140,15 @@ impl SecureChannels {
             purpose_key,
             options.trust_policy,
             access_control.decryptor_outgoing_access_control,
+            None,
             credential_retriever,
             options.authority,
+            BTreeMap::new(),
             Some(route),
             Some(options.timeout),
+            None,
+            options.key_rotation,
+            options.max_plaintext_size,
             Role::Initiator,
         )
         .await?;
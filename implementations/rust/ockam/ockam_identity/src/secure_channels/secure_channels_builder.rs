@@ -7,6 +7,7 @@ use crate::identities::ChangeHistoryRepository;
 use crate::purpose_keys::storage::PurposeKeysRepository;
 use crate::secure_channel::SecureChannelRegistry;
 use crate::secure_channels::SecureChannels;
+use crate::utils::TimeSource;
 use crate::{CredentialRepository, IdentitiesBuilder, IdentityAttributesRepository, Vault};
 
 /// This struct supports all the services related to secure channels
@@ -80,6 +81,12 @@ impl SecureChannelsBuilder {
         self
     }
 
+    /// Set a specific time source, for example to simulate a clock skew
+    pub fn with_time_source(mut self, time_source: Arc<dyn TimeSource>) -> Self {
+        self.identities_builder = self.identities_builder.with_time_source(time_source);
+        self
+    }
+
     /// Set a specific channel registry
     pub fn with_secure_channels_registry(mut self, registry: SecureChannelRegistry) -> Self {
         self.registry = registry;
@@ -1,5 +1,6 @@
 use minicbor::bytes::ByteVec;
 use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
@@ -11,6 +12,52 @@ pub fn now() -> Result<TimestampInSeconds> {
     Ok(TimestampInSeconds(utc_seconds))
 }
 
+/// Source of the wall-clock time used to issue and verify credentials and to
+/// measure the clock skew with the other side of a secure channel
+pub trait TimeSource: Send + Sync + 'static {
+    /// Return the current time
+    fn now(&self) -> Result<TimestampInSeconds>;
+}
+
+/// [`TimeSource`] using the system time
+#[derive(Clone, Debug, Default)]
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Result<TimestampInSeconds> {
+        now()
+    }
+}
+
+/// [`TimeSource`] shifted from the system time by a fixed number of seconds.
+/// This is mostly useful to simulate a machine with a bad clock in tests
+#[derive(Clone, Debug)]
+pub struct ShiftedTimeSource {
+    shift_in_seconds: i64,
+}
+
+impl ShiftedTimeSource {
+    /// Create a time source which is `shift_in_seconds` ahead of the system time
+    /// (or behind it if the shift is negative)
+    pub fn new(shift_in_seconds: i64) -> Self {
+        Self { shift_in_seconds }
+    }
+}
+
+impl TimeSource for ShiftedTimeSource {
+    fn now(&self) -> Result<TimestampInSeconds> {
+        let now = now()?;
+        Ok(TimestampInSeconds(
+            (now.0 as i64).saturating_add(self.shift_in_seconds).max(0) as u64,
+        ))
+    }
+}
+
+/// Return the default time source
+pub fn system_time_source() -> Arc<dyn TimeSource> {
+    Arc::new(SystemTimeSource)
+}
+
 /// Convenient builder for the [`Attributes`] struct
 pub struct AttributesBuilder {
     schema_id: CredentialSchemaIdentifier,
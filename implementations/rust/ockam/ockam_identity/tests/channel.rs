@@ -2,16 +2,21 @@ use core::time::Duration;
 use std::sync::atomic::{AtomicU8, Ordering};
 
 use ockam_core::compat::sync::Arc;
-use ockam_core::{route, Address, AllowAll, Any, DenyAll, Mailboxes, Result, Routed, Worker};
+use ockam_core::errcode::Kind;
+use ockam_core::{
+    route, Address, AllowAll, Any, DenyAll, IngressInfo, Mailboxes, RateLimit, Result, Routed,
+    TransportType, Worker,
+};
 use ockam_identity::models::{CredentialSchemaIdentifier, Identifier};
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
     DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    IdentitySecureChannelLocalInfo, SecureChannelListenerOptions, SecureChannelOptions,
-    SecureChannels, TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
+    IdentitySecureChannelLocalInfo, KeyRotation, PeerAttributes, PlaintextPayloadMessage,
+    SecureChannelListenerOptions, SecureChannelMessage, SecureChannelOptions, SecureChannels,
+    TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
 };
-use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
+use ockam_node::{Context, MessageDirection, MessageReceiveOptions, WorkerBuilder};
 use ockam_vault::{
     SoftwareVaultForSecureChannels, SoftwareVaultForSigning, SoftwareVaultForVerifyingSignatures,
 };
@@ -80,6 +85,87 @@ async fn test_channel(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// Mark the messages it forwards as if they had been received by a transport
+struct IngressMarker;
+
+#[ockam_core::async_trait]
+impl Worker for IngressMarker {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let local_message = msg
+            .into_local_message()
+            .step_forward(&ctx.address())?
+            .with_ingress(&test_ingress())?;
+        ctx.forward(local_message).await
+    }
+}
+
+fn test_ingress() -> IngressInfo {
+    IngressInfo::new(
+        TransportType::new(1),
+        "127.0.0.1:4000",
+        "127.0.0.1:5000",
+        "connection",
+    )
+}
+
+#[ockam_macros::test]
+async fn test_channel_keeps_ingress_info(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    ctx.start_worker("ingress_marker", IngressMarker).await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["ingress_marker", "bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    child_ctx
+        .send(
+            route![alice_channel, child_ctx.address()],
+            "Hello, Bob!".to_string(),
+        )
+        .await?;
+
+    // the decrypted message still describes the connection of the encrypted message
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!(msg.ingress(), Some(test_ingress()));
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(local_info.their_identity_id(), alice);
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -211,6 +297,108 @@ async fn test_channel_send_credentials(context: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_peer_attributes(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let identities_attributes = secure_channels.identities().identities_attributes();
+
+    let authority = identities_creation.create_identity().await?;
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let alice_credential = secure_channels
+        .identities()
+        .credentials()
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &alice,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("role", "admin")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_authority(authority.clone()),
+        )
+        .await?;
+    let bob_listener_without_authority = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener_without_authority",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener_without_authority.flow_control_id());
+
+    // the attributes of a channel presenting a credential are attested by the authority
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new()
+                .with_authority(authority.clone())
+                .with_credential(alice_credential)?,
+        )
+        .await?;
+    child_ctx
+        .send(route![alice_channel, "child"], "Hello, Bob!".to_string())
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+
+    let local_info = IdentitySecureChannelLocalInfo::find_info(msg.local_message())?;
+    assert_eq!(local_info.authority(), Some(authority.clone()));
+    let attributes = msg.peer_attributes(&identities_attributes).await?.unwrap();
+    assert_eq!(
+        attributes.attrs().get("role".as_bytes()),
+        Some(&"admin".as_bytes().to_vec())
+    );
+    assert_eq!(attributes.attested_by(), Some(authority.clone()));
+    assert!(attributes.expires_at().is_some());
+
+    // there are no attributes without an authority on the channel
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener_without_authority"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    child_ctx
+        .send(route![alice_channel, "child"], "Hello, Bob!".to_string())
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert!(msg.peer_attributes(&identities_attributes).await?.is_none());
+
+    // there are no attributes for a message which was not received through a secure channel
+    child_ctx.send("child", "Hello, Bob!".to_string()).await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert!(msg.peer_attributes(&identities_attributes).await?.is_none());
+
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_rejected_trust_policy(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -823,6 +1011,232 @@ async fn access_control__known_participant__should_pass_messages(ctx: &mut Conte
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_listener_rate_limit(ctx: &mut Context) -> Result<()> {
+    let received_count = Arc::new(AtomicU8::new(0));
+    let receiver = Receiver {
+        received_count: received_count.clone(),
+    };
+    WorkerBuilder::new(receiver)
+        .with_address("receiver")
+        .with_incoming_access_control(AllowAll)
+        .with_outgoing_access_control(DenyAll)
+        .start(ctx)
+        .await?;
+
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+    let charlie = identities_creation.create_identity().await?;
+
+    let options = SecureChannelListenerOptions::new()
+        .with_rate_limit(RateLimit::new(3, Duration::from_secs(60)));
+    let rate_limiting_access_control = options.rate_limiting_access_control().unwrap();
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "listener", options)
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("receiver", bob_listener.flow_control_id());
+
+    // a burst over the limit: only the first messages go through
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["listener"], SecureChannelOptions::new())
+        .await?;
+    for _ in 0..5 {
+        ctx.send(
+            route![alice_channel.clone(), "receiver"],
+            "Hello".to_string(),
+        )
+        .await?;
+    }
+    ctx.sleep(Duration::from_millis(200)).await;
+    assert_eq!(received_count.load(Ordering::Relaxed), 3);
+    assert_eq!(rate_limiting_access_control.denied_messages_count(), 2);
+
+    // the messages are counted per identifier, even on a new channel
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["listener"], SecureChannelOptions::new())
+        .await?;
+    ctx.send(route![alice_channel, "receiver"], "Hello".to_string())
+        .await?;
+
+    // a burst under the limit from another identity goes through
+    let charlie_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &charlie,
+            route!["listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    for _ in 0..3 {
+        ctx.send(
+            route![charlie_channel.clone(), "receiver"],
+            "Hello".to_string(),
+        )
+        .await?;
+    }
+    ctx.sleep(Duration::from_millis(200)).await;
+    assert_eq!(received_count.load(Ordering::Relaxed), 6);
+    assert_eq!(rate_limiting_access_control.denied_messages_count(), 3);
+
+    Ok(())
+}
+
+/// Forward the first message it receives, then drop all the messages,
+/// as a peer disappearing in the middle of a handshake
+struct SilentPeer {
+    forwarded: bool,
+}
+
+#[ockam_core::async_trait]
+impl Worker for SilentPeer {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        if self.forwarded {
+            return Ok(());
+        }
+        self.forwarded = true;
+        let local_message = msg.into_local_message().step_forward(&ctx.address())?;
+        ctx.forward(local_message).await
+    }
+}
+
+#[ockam_macros::test]
+async fn test_channel_incomplete_handshake_is_torn_down(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    WorkerBuilder::new(SilentPeer { forwarded: false })
+        .with_address("silent_peer")
+        .with_incoming_access_control(AllowAll)
+        .with_outgoing_access_control(AllowAll)
+        .start(ctx)
+        .await?;
+    let options =
+        SecureChannelListenerOptions::new().with_handshake_timeout(Duration::from_millis(500));
+    let statistics = options.statistics();
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(ctx, &bob, "listener", options)
+        .await?;
+    assert_eq!(bob_listener.statistics().abandoned_handshakes(), 0);
+
+    let mut workers_before = ctx.list_workers().await?;
+    workers_before.sort();
+
+    // the responder answers the first message but never receives the last one,
+    // and the initiator never receives the answer
+    let result = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["silent_peer", "listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_millis(300)),
+        )
+        .await;
+    let error = result.err().expect("the handshake should time out");
+    assert_eq!(error.code().kind, Kind::Timeout);
+    assert!(error.to_string().contains("not completed"), "{error}");
+
+    ctx.sleep(Duration::from_millis(500)).await;
+
+    // both sides of the handshake are torn down
+    assert_eq!(statistics.abandoned_handshakes(), 1);
+    assert_eq!(bob_listener.statistics().abandoned_handshakes(), 1);
+    let mut workers_after = ctx.list_workers().await?;
+    workers_after.sort();
+    assert_eq!(workers_before, workers_after);
+    assert!(secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .is_empty());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_key_rotation(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    // rotate the keys every 3 messages on both sides
+    let key_rotation = KeyRotation::new(None, Some(3));
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_key_rotation(key_rotation),
+        )
+        .await?;
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_key_rotation(key_rotation),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer("child", alice_channel.flow_control_id());
+
+    // the traffic continues after the keys are rotated, in both directions
+    let mut bob_channel = None;
+    for i in 0..10 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                format!("Hello, Bob! {i}"),
+            )
+            .await?;
+        let msg = child_ctx.receive::<String>().await?;
+        let return_route = msg.return_route();
+        bob_channel = return_route.next().ok().cloned();
+        assert_eq!(format!("Hello, Bob! {i}"), msg.into_body()?);
+
+        child_ctx
+            .send(return_route, format!("Hello, Alice! {i}"))
+            .await?;
+        let msg = child_ctx.receive::<String>().await?;
+        assert_eq!(format!("Hello, Alice! {i}"), msg.into_body()?);
+    }
+
+    let alice_channel_data = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(alice_channel.encryptor_address())
+        .unwrap();
+    let bob_channel_data = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(&bob_channel.unwrap())
+        .unwrap();
+
+    // 10 messages with a new key every 3 messages
+    assert_eq!(alice_channel_data.encryptor_rekeys(), 3);
+    assert_eq!(bob_channel_data.decryptor_rekeys(), 3);
+    assert_eq!(bob_channel_data.encryptor_rekeys(), 3);
+    assert_eq!(alice_channel_data.decryptor_rekeys(), 3);
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test]
 async fn access_control__unknown_participant__should_not_pass_messages(
@@ -1088,3 +1502,214 @@ async fn should_stop_encryptor__and__decryptor__in__secure_channel(
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn test_channel_message_sizes(ctx: &mut Context) -> Result<()> {
+    ctx.message_sizes().set_enabled(true);
+
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new(),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    // A 1000 characters string is encoded as a 2 bytes length followed by its content
+    let message = "a".repeat(1000);
+    let plaintext_size = 1002;
+    for _ in 0..20 {
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.address()],
+                message.clone(),
+            )
+            .await?;
+        let msg = child_ctx.receive::<String>().await?;
+        assert_eq!(msg.into_body()?, message);
+    }
+
+    let stats = ctx.message_sizes().stats();
+    let encryptor_address = alice_channel.encryptor_address();
+    let encryptor_inbound = stats
+        .iter()
+        .find(|s| &s.address == encryptor_address && s.direction == MessageDirection::Inbound)
+        .unwrap();
+    let encryptor_outbound = stats
+        .iter()
+        .find(|s| &s.address == encryptor_address && s.direction == MessageDirection::Outbound)
+        .unwrap();
+
+    // The encryptor receives the plaintext and sends the ciphertext
+    assert_eq!(encryptor_inbound.count, 20);
+    assert_eq!(encryptor_inbound.max, plaintext_size);
+    assert_eq!(encryptor_outbound.count, 20);
+    // The ciphertext adds a nonce, a tag and the encoded routes of the message
+    let inflation = encryptor_outbound.max - encryptor_inbound.max;
+    assert!(
+        (8 + 16..8 + 16 + 200).contains(&inflation),
+        "unexpected inflation {inflation}"
+    );
+    assert!(encryptor_outbound.p50 <= encryptor_outbound.max);
+    assert!(encryptor_outbound.p50 >= encryptor_inbound.p50);
+
+    // The decryptor on the other side receives the ciphertext and sends the plaintext
+    let decryptor_inbound = stats
+        .iter()
+        .find(|s| &s.address != encryptor_address && s.direction == MessageDirection::Inbound)
+        .unwrap();
+    let decryptor_outbound = stats
+        .iter()
+        .find(|s| &s.address != encryptor_address && s.direction == MessageDirection::Outbound)
+        .unwrap();
+    assert_eq!(decryptor_inbound.address, decryptor_outbound.address);
+    assert_eq!(decryptor_inbound.count, 20);
+    assert_eq!(decryptor_inbound.max, encryptor_outbound.max);
+    assert_eq!(decryptor_outbound.max, plaintext_size);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn test_channel_max_plaintext_size(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let bob_listener = secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &bob,
+            "bob_listener",
+            SecureChannelListenerOptions::new().with_max_plaintext_size(100),
+        )
+        .await?;
+
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["bob_listener"],
+            SecureChannelOptions::new().with_max_plaintext_size(1000),
+        )
+        .await?;
+
+    let mut child_ctx = ctx
+        .new_detached_with_mailboxes(Mailboxes::main(
+            "child",
+            Arc::new(AllowAll),
+            Arc::new(AllowAll),
+        ))
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("child", bob_listener.flow_control_id());
+
+    // a message smaller than both maximum sizes is received
+    child_ctx
+        .send(route![alice_channel.clone(), "child"], "a".repeat(50))
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    let bob_channel = msg.return_route().next()?.clone();
+    assert_eq!(msg.into_body()?, "a".repeat(50));
+
+    // both sides enforce the smallest maximum size
+    let alice_channel_data = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(alice_channel.encryptor_address())
+        .unwrap();
+    let bob_channel_data = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(&bob_channel)
+        .unwrap();
+    assert_eq!(alice_channel_data.max_plaintext_size(), 100);
+    assert_eq!(bob_channel_data.max_plaintext_size(), 100);
+
+    // a larger message is refused by the encryptor, even if alice accepts it
+    child_ctx
+        .send(route![alice_channel.clone(), "child"], "a".repeat(200))
+        .await?;
+    let result = child_ctx
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(alice_channel_data.refused_oversized_messages(), 1);
+    assert_eq!(bob_channel_data.dropped_oversized_messages(), 0);
+
+    // a larger message sent by a party which ignores the maximum size is dropped by the decryptor
+    let payload = vec![0u8; 200];
+    let oversized = minicbor::to_vec(SecureChannelMessage::Payload(PlaintextPayloadMessage {
+        onward_route: route!["child"],
+        return_route: route![],
+        payload: &payload,
+    }))?;
+    let encrypted: EncryptionResponse = ctx
+        .send_and_receive(
+            route![alice_channel_data.encryptor_api_address().clone()],
+            EncryptionRequest(oversized),
+        )
+        .await?;
+    let encrypted = match encrypted {
+        EncryptionResponse::Ok(p) => p,
+        EncryptionResponse::Err(err) => return Err(err),
+    };
+    ctx.send(
+        route![alice_channel_data.their_decryptor_address()],
+        encrypted,
+    )
+    .await?;
+    let result = child_ctx
+        .receive_extended::<Vec<u8>>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(bob_channel_data.dropped_oversized_messages(), 1);
+
+    // a much larger encrypted message is dropped before being decrypted
+    ctx.send(
+        route![alice_channel_data.their_decryptor_address()],
+        vec![0u8; 20_000],
+    )
+    .await?;
+    ctx.sleep(Duration::from_millis(250)).await;
+    assert_eq!(bob_channel_data.dropped_oversized_messages(), 2);
+
+    // the channel can still be used
+    child_ctx
+        .send(route![alice_channel, "child"], "a".repeat(50))
+        .await?;
+    let msg = child_ctx.receive::<String>().await?;
+    assert_eq!(msg.into_body()?, "a".repeat(50));
+
+    Ok(())
+}
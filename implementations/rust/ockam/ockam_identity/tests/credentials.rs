@@ -2,13 +2,13 @@ use std::sync::atomic::{AtomicI8, Ordering};
 use std::time::Duration;
 
 use ockam_core::compat::sync::Arc;
-use ockam_core::{async_trait, Any, DenyAll};
+use ockam_core::{async_trait, AllowAll, Any, DenyAll};
 use ockam_core::{route, Result, Routed, Worker};
 use ockam_identity::models::CredentialSchemaIdentifier;
 use ockam_identity::secure_channels::secure_channels;
-use ockam_identity::utils::AttributesBuilder;
+use ockam_identity::utils::{AttributesBuilder, ShiftedTimeSource};
 use ockam_identity::{
-    CredentialAccessControl, SecureChannelListenerOptions, SecureChannelOptions,
+    CredentialAccessControl, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
     TrustIdentifierPolicy,
 };
 use ockam_node::{Context, WorkerBuilder};
@@ -243,6 +243,174 @@ async fn access_control(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn required_attribute_accept(ctx: &mut Context) -> Result<()> {
+    let received = required_attribute_flow(ctx, Some("edge-gateway")).await?;
+    assert!(received);
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn required_attribute_reject(ctx: &mut Context) -> Result<()> {
+    let received = required_attribute_flow(ctx, Some("cloud-gateway")).await?;
+    assert!(!received);
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn required_attribute_no_credential(ctx: &mut Context) -> Result<()> {
+    let received = required_attribute_flow(ctx, None).await?;
+    assert!(!received);
+    Ok(())
+}
+
+/// Create a listener requiring the attribute role=edge-gateway and connect to it
+/// with a client presenting a credential with the given role, if any.
+/// Return true if a message sent by the client was received behind the listener
+async fn required_attribute_flow(ctx: &mut Context, role: Option<&str>) -> Result<bool> {
+    let secure_channels = secure_channels().await?;
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let options = SecureChannelListenerOptions::new()
+        .with_authority(authority.clone())
+        .with_required_attribute("role", "edge-gateway");
+    let listener = secure_channels
+        .create_secure_channel_listener(ctx, &server, "listener", options)
+        .await?;
+
+    let counter = Arc::new(AtomicI8::new(0));
+    let worker = CountingWorker {
+        msgs_count: counter.clone(),
+    };
+    ctx.flow_controls()
+        .add_consumer("counter", listener.flow_control_id());
+    WorkerBuilder::new(worker)
+        .with_address("counter")
+        .with_incoming_access_control(AllowAll)
+        .with_outgoing_access_control(DenyAll)
+        .start(ctx)
+        .await?;
+
+    let mut options =
+        SecureChannelOptions::new().with_trust_policy(TrustIdentifierPolicy::new(server.clone()));
+    if let Some(role) = role {
+        let credential = credentials
+            .credentials_creation()
+            .issue_credential(
+                &authority,
+                &client,
+                AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                    .with_attribute("role", role)
+                    .build(),
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        options = options.with_credential(credential)?;
+    }
+
+    // the initiator side completes its part of the handshake before the listener checks
+    // its credentials, so the channel is only missing on the listener side when it is rejected
+    let channel = secure_channels
+        .create_secure_channel(ctx, &client, route!["listener"], options)
+        .await?;
+    ctx.sleep(Duration::from_millis(200)).await;
+
+    let channels_count = secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .len();
+
+    ctx.send(route![channel, "counter"], "Hello".to_string())
+        .await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    let received = counter.load(Ordering::Relaxed) == 1;
+
+    assert_eq!(channels_count, if received { 2 } else { 1 });
+    Ok(received)
+}
+
+#[ockam_macros::test]
+async fn credential_rejected_because_of_a_clock_skew(ctx: &mut Context) -> Result<()> {
+    let builder = SecureChannels::builder().await?;
+    let secure_channels = builder.clone().build();
+    // the client machine clock is 2 hours late
+    let late_secure_channels = builder
+        .with_time_source(Arc::new(ShiftedTimeSource::new(-2 * 60 * 60)))
+        .build();
+
+    let identities = secure_channels.identities();
+    let identities_creation = identities.identities_creation();
+    let authority = identities_creation.create_identity().await?;
+    let server = identities_creation.create_identity().await?;
+    let client = identities_creation.create_identity().await?;
+
+    let credential = identities
+        .credentials()
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &server,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("is_server", "true")
+                .build(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+
+    secure_channels
+        .create_secure_channel_listener(
+            ctx,
+            &server,
+            "listener",
+            SecureChannelListenerOptions::new().with_credential(credential)?,
+        )
+        .await?;
+
+    // for the client, the server credential is only valid in 2 hours
+    let error = late_secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new()
+                .with_authority(authority.clone())
+                .with_timeout(Duration::from_secs(5)),
+        )
+        .await
+        .err()
+        .unwrap();
+    assert!(
+        error
+            .to_string()
+            .contains("clock skew detected: peer time differs by"),
+        "{error}"
+    );
+
+    // without a clock skew, the measured skew is available in the secure channel registry
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route!["listener"],
+            SecureChannelOptions::new().with_authority(authority.clone()),
+        )
+        .await?;
+    let entry = secure_channels
+        .secure_channel_registry()
+        .get_channel_by_encryptor_address(channel.encryptor_address())
+        .unwrap();
+    let skew = entry.their_clock_skew().unwrap();
+    assert!(!skew.exceeds_threshold());
+
+    Ok(())
+}
+
 struct CountingWorker {
     msgs_count: Arc<AtomicI8>,
 }
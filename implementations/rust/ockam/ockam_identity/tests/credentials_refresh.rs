@@ -2,16 +2,18 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
 use ockam_core::api::Response;
-use ockam_core::compat::sync::Arc;
-use ockam_core::{async_trait, Any, AsyncTryClone, Routed, Worker};
+use ockam_core::compat::backoff::Backoff;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{async_trait, Address, Any, AsyncTryClone, Routed, Worker};
 use ockam_core::{route, Result};
 use ockam_identity::models::CredentialSchemaIdentifier;
 use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
-    Credentials, Identifier, IdentitySecureChannelLocalInfo, RemoteCredentialRetrieverCreator,
-    RemoteCredentialRetrieverInfo, RemoteCredentialRetrieverTimingOptions,
-    SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+    CredentialRetrieverCreator, Credentials, Identifier, IdentitySecureChannelLocalInfo,
+    RemoteCredentialRetrieverCreator, RemoteCredentialRetrieverInfo,
+    RemoteCredentialRetrieverTimingOptions, SecureChannelListenerOptions, SecureChannelOptions,
+    SecureChannels,
 };
 use ockam_node::Context;
 use ockam_transport_tcp::TcpTransport;
@@ -19,6 +21,8 @@ use ockam_transport_tcp::TcpTransport;
 struct CredentialIssuer {
     delay: Duration,
     call_counter: Arc<AtomicU64>,
+    /// Secure channel used by each request, identified by the first address of its return route
+    request_channels: Arc<Mutex<Vec<Address>>>,
     pause: Arc<AtomicBool>,
     credentials: Arc<Credentials>,
     authority: Identifier,
@@ -57,6 +61,10 @@ impl Worker for CredentialIssuer {
         let response = Response::ok().body(credential).to_vec()?;
 
         self.call_counter.fetch_add(1, Ordering::Relaxed);
+        self.request_channels
+            .lock()
+            .unwrap()
+            .push(msg.return_route().next()?.clone());
 
         ctx.sleep(self.delay).await;
         ctx.send(msg.return_route(), response).await?;
@@ -68,7 +76,7 @@ impl Worker for CredentialIssuer {
 #[ockam_macros::test]
 async fn autorefresh(ctx: &mut Context) -> Result<()> {
     let timing_options = RemoteCredentialRetrieverTimingOptions {
-        min_refresh_interval: Duration::from_secs(1),
+        refresh_retry_backoff: Backoff::new(Duration::from_secs(1), Duration::from_secs(1)),
         proactive_refresh_gap: 1.into(),
         clock_skew_gap: 0.into(),
         request_timeout: Duration::from_secs(2),
@@ -153,7 +161,7 @@ async fn autorefresh(ctx: &mut Context) -> Result<()> {
 #[ockam_macros::test]
 async fn init_fail(ctx: &mut Context) -> Result<()> {
     let timing_options = RemoteCredentialRetrieverTimingOptions {
-        min_refresh_interval: Duration::from_secs(1),
+        refresh_retry_backoff: Backoff::new(Duration::from_secs(1), Duration::from_secs(1)),
         proactive_refresh_gap: 1.into(),
         clock_skew_gap: 0.into(),
         request_timeout: Duration::from_secs(2),
@@ -186,9 +194,72 @@ async fn init_fail(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn retrievers_for_the_same_issuer_share_their_refreshes(ctx: &mut Context) -> Result<()> {
+    let timing_options = RemoteCredentialRetrieverTimingOptions {
+        refresh_retry_backoff: Backoff::new(Duration::from_secs(1), Duration::from_secs(1)),
+        proactive_refresh_gap: 2.into(),
+        clock_skew_gap: 0.into(),
+        request_timeout: Duration::from_secs(2),
+        ..Default::default()
+    };
+    let res = init(
+        ctx,
+        Duration::from_secs(0),
+        Duration::from_secs(6),
+        timing_options,
+    )
+    .await?;
+
+    // a second creator, for another scope, using the same issuer
+    let other_creator = RemoteCredentialRetrieverCreator::new_extended(
+        ctx.async_try_clone().await?,
+        res.tcp.clone(),
+        res.client_secure_channels.clone(),
+        RemoteCredentialRetrieverInfo::new(
+            res.authority.clone(),
+            route!["authority_api"],
+            "credential_issuer".into(),
+        ),
+        timing_options,
+    );
+
+    let retriever1 = res.retriever.create(&res.client).await?;
+    retriever1.initialize().await?;
+    ctx.sleep(Duration::from_secs(1)).await;
+    let retriever2 = other_creator.create(&res.client).await?;
+    retriever2.initialize().await?;
+    assert_eq!(res.call_counter.load(Ordering::Relaxed), 2);
+
+    // the credentials expire one second apart but are refreshed in the same round,
+    // through a single secure channel
+    ctx.sleep(Duration::from_secs(4)).await;
+    assert_eq!(res.call_counter.load(Ordering::Relaxed), 4);
+    let request_channels = res.request_channels.lock().unwrap().clone();
+    assert_eq!(request_channels.len(), 4);
+    assert_eq!(request_channels[2], request_channels[3]);
+    assert_ne!(request_channels[1], request_channels[2]);
+
+    // both credentials are valid
+    retriever1.retrieve().await?;
+    retriever2.retrieve().await?;
+
+    // the secure channel is stopped once both credentials are refreshed
+    ctx.sleep(Duration::from_secs(1)).await;
+    assert!(res
+        .client_secure_channels
+        .secure_channel_registry()
+        .get_channel_list()
+        .is_empty());
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 struct InitResult {
+    tcp: Arc<TcpTransport>,
     call_counter: Arc<AtomicU64>,
+    request_channels: Arc<Mutex<Vec<Address>>>,
     pause: Arc<AtomicBool>,
 
     client: Identifier,
@@ -208,7 +279,7 @@ async fn init(
     ttl: Duration,
     timing_options: RemoteCredentialRetrieverTimingOptions,
 ) -> Result<InitResult> {
-    let tcp = TcpTransport::create(ctx).await?;
+    let tcp = Arc::new(TcpTransport::create(ctx).await?);
 
     let client_secure_channels = secure_channels().await?;
     let authority_secure_channels = secure_channels().await?;
@@ -240,10 +311,12 @@ async fn init(
         .await?;
 
     let call_counter = Arc::new(AtomicU64::new(0));
+    let request_channels = Arc::new(Mutex::new(vec![]));
     let pause = Arc::new(AtomicBool::new(false));
     let issuer = CredentialIssuer {
         delay,
         call_counter: call_counter.clone(),
+        request_channels: request_channels.clone(),
         pause: pause.clone(),
         credentials: authority_identities.credentials(),
         authority: authority.clone(),
@@ -275,7 +348,7 @@ async fn init(
 
     let retriever = Arc::new(RemoteCredentialRetrieverCreator::new_extended(
         ctx.async_try_clone().await?,
-        Arc::new(tcp),
+        tcp.clone(),
         client_secure_channels.clone(),
         RemoteCredentialRetrieverInfo::new(
             authority.clone(),
@@ -286,7 +359,9 @@ async fn init(
     ));
 
     Ok(InitResult {
+        tcp,
         call_counter,
+        request_channels,
         pause,
         client,
         server,
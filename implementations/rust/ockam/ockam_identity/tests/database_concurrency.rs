@@ -0,0 +1,134 @@
+use core::str::FromStr;
+use core::time::Duration;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use sqlx::sqlite::SqliteJournalMode;
+use tempfile::NamedTempFile;
+use tokio::task::JoinSet;
+
+use ockam_core::Result;
+use ockam_identity::models::{ChangeHistory, Identifier};
+use ockam_identity::{
+    identities, AttributesEntry, ChangeHistoryRepository, ChangeHistorySqlxDatabase,
+    IdentityAttributesRepository, IdentityAttributesSqlxDatabase, TimestampInSeconds,
+};
+use ockam_node::database::{DatabaseConfiguration, SqlxDatabase};
+
+const WRITERS: usize = 50;
+const WRITES_PER_WRITER: usize = 20;
+
+/// Many writers, using two different repositories, write concurrently to the same database file
+#[tokio::test]
+async fn concurrent_writers_across_repositories() -> Result<()> {
+    let db_file = NamedTempFile::new().unwrap();
+    let mut database =
+        SqlxDatabase::create_with_configuration(db_file.path(), DatabaseConfiguration::default())
+            .await?;
+    database.set_node_name("node");
+
+    let change_history = Arc::new(create_change_history().await?);
+    let change_histories = Arc::new(ChangeHistorySqlxDatabase::new(database.clone()));
+    let attributes = Arc::new(IdentityAttributesSqlxDatabase::new(database.clone()));
+
+    let mut writers = JoinSet::new();
+    for writer in 0..WRITERS {
+        let change_history = change_history.clone();
+        let change_histories = change_histories.clone();
+        let attributes = attributes.clone();
+        writers.spawn(async move {
+            for write in 0..WRITES_PER_WRITER {
+                let identifier = make_identifier(writer * WRITES_PER_WRITER + write);
+                if writer % 2 == 0 {
+                    change_histories
+                        .store_change_history(&identifier, (*change_history).clone())
+                        .await?;
+                } else {
+                    attributes
+                        .put_attributes(&identifier, make_attributes_entry())
+                        .await?;
+                }
+            }
+            Result::Ok(())
+        });
+    }
+
+    while let Some(result) = writers.join_next().await {
+        result.unwrap()?;
+    }
+
+    let stored = change_histories.get_change_histories().await?;
+    assert_eq!(stored.len(), WRITERS / 2 * WRITES_PER_WRITER);
+    Ok(())
+}
+
+/// Without a busy timeout, a write fails with a `database is locked` error as soon as
+/// another connection holds the write lock. With the default configuration, the write waits
+/// for the lock to be released.
+#[tokio::test]
+async fn busy_timeout_waits_for_a_locked_database() -> Result<()> {
+    let unconfigured = DatabaseConfiguration::default()
+        .with_journal_mode(SqliteJournalMode::Delete)
+        .with_busy_timeout(Duration::ZERO);
+    let result = write_while_locked(unconfigured).await;
+    let error = result.expect_err("the write must fail while the database is locked");
+    assert!(error.to_string().contains("database is locked"), "{error}");
+
+    let result = write_while_locked(DatabaseConfiguration::default()).await;
+    assert!(result.is_ok(), "{result:?}");
+    Ok(())
+}
+
+/// HELPERS
+
+/// Take the write lock on the database for a short time with one connection
+/// and write with a repository using another connection of the same pool
+async fn write_while_locked(configuration: DatabaseConfiguration) -> Result<()> {
+    let db_file = NamedTempFile::new().unwrap();
+    let mut database =
+        SqlxDatabase::create_with_configuration(db_file.path(), configuration).await?;
+    database.set_node_name("node");
+    let attributes = IdentityAttributesSqlxDatabase::new(database.clone());
+
+    let mut connection = database.pool.acquire().await.unwrap();
+    sqlx::query("BEGIN IMMEDIATE")
+        .execute(&mut *connection)
+        .await
+        .unwrap();
+    let lock = tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        sqlx::query("COMMIT")
+            .execute(&mut *connection)
+            .await
+            .unwrap();
+    });
+
+    let result = attributes
+        .put_attributes(&make_identifier(0), make_attributes_entry())
+        .await;
+    lock.await.unwrap();
+    result
+}
+
+async fn create_change_history() -> Result<ChangeHistory> {
+    let identities = identities().await?;
+    let identifier = identities.identities_creation().create_identity().await?;
+    let identity = identities
+        .identities_verification()
+        .get_identity(&identifier)
+        .await?;
+    Ok(identity.change_history().clone())
+}
+
+fn make_identifier(n: usize) -> Identifier {
+    Identifier::from_str(&format!("I{n:064x}")).unwrap()
+}
+
+fn make_attributes_entry() -> AttributesEntry {
+    AttributesEntry::new(
+        BTreeMap::from([(b"role".to_vec(), b"member".to_vec())]),
+        TimestampInSeconds(1000),
+        None,
+        None,
+    )
+}
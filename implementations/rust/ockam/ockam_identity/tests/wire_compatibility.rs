@@ -0,0 +1,412 @@
+//! Golden fixtures for the secure channel messages and the identity types sent over the wire.
+//!
+//! The files in `tests/fixtures/wire` contain the hex-encoded canonical CBOR encoding of:
+//!
+//!  - the [`SecureChannelMessage`] variants: a payload, a credentials refresh and a close message
+//!  - an [`Identifier`]
+//!  - a [`ChangeHistory`] with two changes, a [`Credential`] and a [`PurposeKeyAttestation`]
+//!
+//! These encodings are wire-stable: the current code must decode the fixtures and produce
+//! byte-identical encodings. The fixtures for the transport messages and routes are in `ockam_core`.
+//!
+//! The identity and credential fixtures are canonical CBOR, as documented on [`ChangeHistory`]
+//! and [`Attributes`], so that they can be vendored by other implementations to check that
+//! they produce the same bytes. Their keys, hashes and signatures are fixed bytes, not valid ones.
+//!
+//! If a format is changed on purpose, the fixtures can be regenerated with:
+//!
+//! ```sh
+//! OCKAM_REGENERATE_WIRE_FIXTURES=1 cargo test -p ockam_identity --test wire_compatibility -- --ignored
+//! ```
+//!
+//! The regeneration is refused on CI, so that a fixture can only be changed by a reviewed commit.
+
+use ockam_core::{route, Address, Result, Route, TransportType};
+use ockam_identity::models::{
+    Change, ChangeData, ChangeHash, ChangeHistory, ChangeSignature, CredentialData,
+    CredentialSchemaIdentifier, CredentialSignature, PrimaryPublicKey, PurposeKeyAttestation,
+    PurposeKeyAttestationData, PurposeKeyAttestationSignature, PurposePublicKey, VersionedData,
+    PURPOSE_KEY_ATTESTATION_DATA_TYPE,
+};
+use ockam_identity::utils::AttributesBuilder;
+use ockam_identity::{
+    identities, Attributes, Credential, Identifier, Identity, PlaintextPayloadMessage,
+    RefreshCredentialsMessage, SecureChannelMessage, TimestampInSeconds,
+};
+use ockam_vault::{EdDSACurve25519PublicKey, EdDSACurve25519Signature, X25519PublicKey};
+use std::path::PathBuf;
+use std::time::Duration;
+
+const REGENERATE_ENV_VAR: &str = "OCKAM_REGENERATE_WIRE_FIXTURES";
+
+#[test]
+fn secure_channel_payload() {
+    let fixture = read_fixture("secure_channel_payload");
+    match minicbor::decode::<SecureChannelMessage>(&fixture).unwrap() {
+        SecureChannelMessage::Payload(message) => {
+            assert_eq!(message.onward_route, payload_onward_route());
+            assert_eq!(message.return_route, payload_return_route());
+            assert_eq!(message.payload, PAYLOAD);
+        }
+        other => panic!("unexpected message {other:?}"),
+    }
+    assert_eq!(minicbor::to_vec(payload_message()).unwrap(), fixture);
+}
+
+#[test]
+fn secure_channel_refresh_credentials() {
+    let fixture = read_fixture("secure_channel_refresh_credentials");
+    match minicbor::decode::<SecureChannelMessage>(&fixture).unwrap() {
+        SecureChannelMessage::RefreshCredentials(message) => {
+            assert_eq!(message.change_history, change_history());
+            assert!(message.credentials.is_empty());
+            assert_eq!(message.timestamp, Some(TimestampInSeconds(1700000000)));
+        }
+        other => panic!("unexpected message {other:?}"),
+    }
+    assert_eq!(
+        minicbor::to_vec(refresh_credentials_message()).unwrap(),
+        fixture
+    );
+}
+
+#[test]
+fn secure_channel_close() {
+    let fixture = read_fixture("secure_channel_close");
+    assert!(matches!(
+        minicbor::decode::<SecureChannelMessage>(&fixture).unwrap(),
+        SecureChannelMessage::Close
+    ));
+    assert_eq!(
+        minicbor::to_vec(SecureChannelMessage::Close).unwrap(),
+        fixture
+    );
+}
+
+#[test]
+fn identifier() {
+    let fixture = read_fixture("identifier");
+    assert_eq!(
+        minicbor::decode::<Identifier>(&fixture).unwrap(),
+        identifier()
+    );
+    assert_eq!(minicbor::to_vec(identifier()).unwrap(), fixture);
+}
+
+#[test]
+fn identity_change_history() {
+    let fixture = read_fixture("change_history");
+    let decoded = minicbor::decode::<ChangeHistory>(&fixture).unwrap();
+    assert_eq!(decoded, identity_change_history_value());
+    for (change, data) in decoded.0.iter().zip(change_data()) {
+        let versioned_data = minicbor::decode::<VersionedData>(&change.data).unwrap();
+        assert_eq!(ChangeData::get_data(&versioned_data).unwrap(), data);
+    }
+    assert_eq!(decoded.export().unwrap(), fixture);
+}
+
+#[test]
+fn credential() {
+    let fixture = read_fixture("credential");
+    let decoded = minicbor::decode::<Credential>(&fixture).unwrap();
+    assert_eq!(decoded, credential_value());
+    assert_eq!(decoded.get_credential_data().unwrap(), credential_data());
+    assert_eq!(minicbor::to_vec(decoded).unwrap(), fixture);
+}
+
+#[test]
+fn purpose_key_attestation() {
+    let fixture = read_fixture("purpose_key_attestation");
+    assert_eq!(
+        minicbor::decode::<PurposeKeyAttestation>(&fixture).unwrap(),
+        purpose_key_attestation_value()
+    );
+    assert_eq!(
+        minicbor::to_vec(purpose_key_attestation_value()).unwrap(),
+        fixture
+    );
+}
+
+/// Two values built separately must have the same encoding
+#[test]
+fn the_same_values_are_encoded_to_the_same_bytes() {
+    assert_eq!(
+        minicbor::to_vec(identity_change_history_value()).unwrap(),
+        minicbor::to_vec(identity_change_history_value()).unwrap()
+    );
+    assert_eq!(
+        minicbor::to_vec(credential_value()).unwrap(),
+        minicbor::to_vec(credential_value()).unwrap()
+    );
+
+    // the order in which the attributes are added doesn't matter
+    let reversed = AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+        .with_attribute("ockam-role", "member")
+        .with_attribute("aa", "2")
+        .with_attribute("b", "1")
+        .build();
+    assert_eq!(
+        minicbor::to_vec(&reversed).unwrap(),
+        minicbor::to_vec(attributes()).unwrap()
+    );
+}
+
+/// The credentials issued before the canonical form of the attributes can still be decoded
+#[test]
+fn attributes_encoded_in_another_order_are_decoded() {
+    let attributes = attributes();
+    // the keys of a BTreeMap are encoded bytewise: "aa" comes before "b"
+    let non_canonical = minicbor::to_vec((attributes.schema, attributes.map.clone())).unwrap();
+    let canonical = minicbor::to_vec(&attributes).unwrap();
+    assert_ne!(non_canonical, canonical);
+    assert_eq!(
+        minicbor::decode::<Attributes>(&non_canonical).unwrap(),
+        attributes
+    );
+}
+
+/// The change history of an identity and an issued credential are encoded to the same bytes
+/// after being decoded, so that the signatures can be checked over their encoding
+#[tokio::test]
+async fn identities_and_credentials_are_encoded_to_the_same_bytes() -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let identifier = identities_creation.create_identity().await?;
+    identities_creation.rotate_identity(&identifier).await?;
+
+    let exported = identities.export_identity(&identifier).await?;
+    let identity = Identity::import(
+        Some(&identifier),
+        &exported,
+        identities.vault().verifying_vault,
+    )
+    .await?;
+    assert_eq!(identity.export()?, exported);
+    assert_eq!(identity.change_history().0.len(), 2);
+
+    let subject = identities_creation.create_identity().await?;
+    let credential = identities
+        .credentials()
+        .credentials_creation()
+        .issue_credential(
+            &identifier,
+            &subject,
+            attributes(),
+            Duration::from_secs(60 * 60),
+        )
+        .await?;
+    let encoded = minicbor::to_vec(&credential.credential)?;
+    let decoded = minicbor::decode::<Credential>(&encoded)?;
+    assert_eq!(minicbor::to_vec(&decoded)?, encoded);
+    assert_eq!(
+        minicbor::to_vec(&decoded.get_credential_data()?.subject_attributes)?,
+        minicbor::to_vec(attributes())?
+    );
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn regenerate_fixtures() {
+    check_regeneration_is_allowed();
+
+    write_fixture(
+        "secure_channel_payload",
+        &minicbor::to_vec(payload_message()).unwrap(),
+    );
+    write_fixture(
+        "secure_channel_refresh_credentials",
+        &minicbor::to_vec(refresh_credentials_message()).unwrap(),
+    );
+    write_fixture(
+        "secure_channel_close",
+        &minicbor::to_vec(SecureChannelMessage::Close).unwrap(),
+    );
+    write_fixture("identifier", &minicbor::to_vec(identifier()).unwrap());
+    write_fixture(
+        "change_history",
+        &identity_change_history_value().export().unwrap(),
+    );
+    write_fixture("credential", &minicbor::to_vec(credential_value()).unwrap());
+    write_fixture(
+        "purpose_key_attestation",
+        &minicbor::to_vec(purpose_key_attestation_value()).unwrap(),
+    );
+}
+
+const PAYLOAD: &[u8] = b"hello";
+
+fn payload_onward_route() -> Route {
+    route!["echo"]
+}
+
+fn payload_return_route() -> Route {
+    route![
+        Address::from((TransportType::new(1), b"127.0.0.1:4000".to_vec())),
+        "app"
+    ]
+}
+
+fn payload_message() -> SecureChannelMessage<'static> {
+    SecureChannelMessage::Payload(PlaintextPayloadMessage {
+        onward_route: payload_onward_route(),
+        return_route: payload_return_route(),
+        payload: PAYLOAD,
+    })
+}
+
+/// The change history is only decoded here, so it doesn't need valid signatures
+fn change_history() -> ChangeHistory {
+    ChangeHistory(vec![Change {
+        data: vec![1, 2, 3],
+        signature: ChangeSignature::EdDSACurve25519(EdDSACurve25519Signature([0x11; 64])),
+        previous_signature: None,
+    }])
+}
+
+fn refresh_credentials_message() -> SecureChannelMessage<'static> {
+    SecureChannelMessage::RefreshCredentials(RefreshCredentialsMessage {
+        change_history: change_history(),
+        credentials: vec![],
+        timestamp: Some(TimestampInSeconds(1700000000)),
+    })
+}
+
+fn identifier() -> Identifier {
+    let mut bytes = [0u8; 20];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = 0xa0 + i as u8;
+    }
+    Identifier(bytes)
+}
+
+fn change_hash() -> ChangeHash {
+    ChangeHash([0x22; 32])
+}
+
+/// The data of the first change, without a previous change, and of the second change,
+/// revoking the purpose keys of the first one
+fn change_data() -> Vec<ChangeData> {
+    vec![
+        ChangeData {
+            previous_change: None,
+            primary_public_key: PrimaryPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey(
+                [0x01; 32],
+            )),
+            revoke_all_purpose_keys: false,
+            attestations_valid_from: TimestampInSeconds(1700000000),
+            attestations_valid_until: TimestampInSeconds(2015000000),
+        },
+        ChangeData {
+            previous_change: Some(change_hash()),
+            primary_public_key: PrimaryPublicKey::EdDSACurve25519(EdDSACurve25519PublicKey(
+                [0x02; 32],
+            )),
+            revoke_all_purpose_keys: true,
+            attestations_valid_from: TimestampInSeconds(1700000100),
+            attestations_valid_until: TimestampInSeconds(2015000100),
+        },
+    ]
+}
+
+fn identity_change_history_value() -> ChangeHistory {
+    let [first, second]: [ChangeData; 2] = change_data().try_into().unwrap();
+    ChangeHistory(vec![
+        Change {
+            data: minicbor::to_vec(Change::create_versioned_data(
+                minicbor::to_vec(first).unwrap(),
+            ))
+            .unwrap(),
+            signature: ChangeSignature::EdDSACurve25519(EdDSACurve25519Signature([0x11; 64])),
+            previous_signature: None,
+        },
+        Change {
+            data: minicbor::to_vec(Change::create_versioned_data(
+                minicbor::to_vec(second).unwrap(),
+            ))
+            .unwrap(),
+            signature: ChangeSignature::EdDSACurve25519(EdDSACurve25519Signature([0x33; 64])),
+            previous_signature: Some(ChangeSignature::EdDSACurve25519(EdDSACurve25519Signature(
+                [0x44; 64],
+            ))),
+        },
+    ])
+}
+
+/// Attributes whose keys have different lengths, so that the canonical order,
+/// "b", "aa", "ockam-role", differs from the bytewise order
+fn attributes() -> Attributes {
+    AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+        .with_attribute("b", "1")
+        .with_attribute("aa", "2")
+        .with_attribute("ockam-role", "member")
+        .build()
+}
+
+fn credential_data() -> CredentialData {
+    CredentialData {
+        subject: Some(identifier()),
+        subject_latest_change_hash: Some(change_hash()),
+        subject_attributes: attributes(),
+        created_at: TimestampInSeconds(1700000000),
+        expires_at: TimestampInSeconds(1700086400),
+    }
+}
+
+fn credential_value() -> Credential {
+    Credential {
+        data: minicbor::to_vec(Credential::create_versioned_data(
+            minicbor::to_vec(credential_data()).unwrap(),
+        ))
+        .unwrap(),
+        signature: CredentialSignature::EdDSACurve25519(EdDSACurve25519Signature([0x55; 64])),
+    }
+}
+
+fn purpose_key_attestation_value() -> PurposeKeyAttestation {
+    let data = PurposeKeyAttestationData {
+        subject: identifier(),
+        subject_latest_change_hash: change_hash(),
+        public_key: PurposePublicKey::SecureChannelStatic(X25519PublicKey([0x66; 32])),
+        created_at: TimestampInSeconds(1700000000),
+        expires_at: TimestampInSeconds(1700086400),
+    };
+    PurposeKeyAttestation {
+        data: minicbor::to_vec(VersionedData {
+            version: 1,
+            data_type: PURPOSE_KEY_ATTESTATION_DATA_TYPE,
+            data: minicbor::to_vec(data).unwrap(),
+        })
+        .unwrap(),
+        signature: PurposeKeyAttestationSignature::EdDSACurve25519(EdDSACurve25519Signature(
+            [0x77; 64],
+        )),
+    }
+}
+
+fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/wire")
+        .join(format!("{name}.hex"))
+}
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = fixture_path(name);
+    let content = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("cannot read the fixture {}: {e}", path.display()));
+    hex::decode(content.trim())
+        .unwrap_or_else(|e| panic!("the fixture {} is not valid hex: {e}", path.display()))
+}
+
+fn write_fixture(name: &str, bytes: &[u8]) {
+    std::fs::write(fixture_path(name), format!("{}\n", hex::encode(bytes))).unwrap();
+}
+
+fn check_regeneration_is_allowed() {
+    if std::env::var(REGENERATE_ENV_VAR).as_deref() != Ok("1") {
+        panic!("set {REGENERATE_ENV_VAR}=1 to regenerate the wire fixtures");
+    }
+    if std::env::var_os("CI").is_some() {
+        panic!("the wire fixtures must not be regenerated on CI");
+    }
+}
@@ -7,7 +7,7 @@ use ockam_core::api::Reply::Successful;
 use ockam_core::api::{Error, Reply, Request, Response};
 use ockam_core::compat::time::Duration;
 use ockam_core::compat::vec::Vec;
-use ockam_core::{LocalInfo, Result, Route};
+use ockam_core::{LocalInfo, MessagePriority, Result, Route};
 
 /// This struct provides some support for making requests to another node
 /// and receiving replies
@@ -123,6 +123,9 @@ impl Client {
     where
         T: Encode<()>,
     {
+        // ask for an immediate response if the request is denied by the access control of the
+        // destination, instead of waiting until the timeout
+        let req = req.notify_on_deny();
         let mut buf = Vec::new();
 
         req.encode(&mut buf)?;
@@ -133,10 +136,12 @@ impl Client {
             path   = %req.header().path(),
             body   = %req.header().has_body(),
         };
+        // requests are control messages, they are handled before data messages
+        let options = MessageSendReceiveOptions::new().with_priority(MessagePriority::High);
         let options = if let Some(t) = timeout {
-            MessageSendReceiveOptions::new().with_timeout(t)
+            options.with_timeout(t)
         } else {
-            MessageSendReceiveOptions::new().without_timeout()
+            options.without_timeout()
         };
 
         // TODO: Check IdentityId is the same we sent message to?
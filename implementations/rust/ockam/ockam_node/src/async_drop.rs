@@ -20,6 +20,7 @@ use ockam_core::Address;
 pub struct AsyncDrop {
     rx: Receiver<Address>,
     sender: DefaultSender<NodeMessage>,
+    detached: bool,
 }
 
 impl AsyncDrop {
@@ -31,7 +32,29 @@ impl AsyncDrop {
     /// this way.
     pub fn new(sender: DefaultSender<NodeMessage>) -> (Self, Sender<Address>) {
         let (tx, rx) = oneshot::channel();
-        (Self { rx, sender }, tx)
+        let detached = true;
+        (
+            Self {
+                rx,
+                sender,
+                detached,
+            },
+            tx,
+        )
+    }
+
+    /// Create a new AsyncDrop stopping a worker, instead of a detached context
+    pub fn new_for_worker(sender: DefaultSender<NodeMessage>) -> (Self, Sender<Address>) {
+        let (tx, rx) = oneshot::channel();
+        let detached = false;
+        (
+            Self {
+                rx,
+                sender,
+                detached,
+            },
+            tx,
+        )
     }
 
     /// Wait for the cancellation of the channel and then send a
@@ -43,7 +66,7 @@ impl AsyncDrop {
         if let Ok(addr) = self.rx.await {
             debug!("Received AsyncDrop request for address: {}", addr);
 
-            let (msg, mut reply) = NodeMessage::stop_worker(addr, true);
+            let (msg, mut reply) = NodeMessage::stop_worker(addr, self.detached);
             if let Err(e) = self.sender.send(msg).await {
                 debug!("Failed sending AsyncDrop request to router: {}", e);
             }
@@ -55,3 +78,34 @@ impl AsyncDrop {
         }
     }
 }
+
+/// Stop a worker when this guard is dropped, unless it is disarmed.
+///
+/// See [`crate::Context::stop_worker_on_drop`]
+pub struct StopWorkerOnDrop {
+    address: Address,
+    sender: Option<Sender<Address>>,
+}
+
+impl StopWorkerOnDrop {
+    pub(crate) fn new(address: Address, sender: Sender<Address>) -> Self {
+        Self {
+            address,
+            sender: Some(sender),
+        }
+    }
+
+    /// Keep the worker running when the guard is dropped
+    pub fn disarm(mut self) {
+        self.sender = None;
+    }
+}
+
+impl Drop for StopWorkerOnDrop {
+    fn drop(&mut self) {
+        if let Some(sender) = self.sender.take() {
+            debug!("Stopping the worker {} on drop", self.address);
+            let _ = sender.send(self.address.clone());
+        }
+    }
+}
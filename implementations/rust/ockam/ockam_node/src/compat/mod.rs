@@ -5,9 +5,9 @@ pub mod asynchronous {
     pub use tokio::sync::RwLock;
 }
 
-/// FutureExt
+/// FutureExt and StreamExt
 pub mod futures {
-    pub use futures::FutureExt;
+    pub use futures::{FutureExt, StreamExt};
 }
 
 #[cfg(not(feature = "std"))]
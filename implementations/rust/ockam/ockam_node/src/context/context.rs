@@ -1,6 +1,9 @@
 use crate::channel_types::{SmallReceiver, SmallSender};
+use crate::context::priority_lanes::PriorityLanes;
 use crate::tokio::runtime::Handle;
-use crate::{error::*, AsyncDropSender, NodeMessage};
+#[cfg(feature = "std")]
+use crate::NodeEvents;
+use crate::{error::*, AsyncDropSender, MessageSizes, NodeMessage, WorkerInfo};
 use core::sync::atomic::AtomicUsize;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::sync::{Arc, RwLock};
@@ -26,9 +29,14 @@ pub struct Context {
     pub(super) receiver: SmallReceiver<RelayMessage>,
     pub(super) async_drop_sender: Option<AsyncDropSender>,
     pub(super) mailbox_count: Arc<AtomicUsize>,
+    /// Set when the messages are handled according to their priority
+    pub(super) priority_lanes: Option<PriorityLanes>,
     /// List of transports used to resolve external addresses to local workers in routes
     pub(super) transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
     pub(super) flow_controls: FlowControls,
+    pub(super) message_sizes: MessageSizes,
+    #[cfg(feature = "std")]
+    pub(super) node_events: NodeEvents,
     #[cfg(feature = "std")]
     pub(super) tracing_context: OpenTelemetryContext,
 }
@@ -57,6 +65,13 @@ impl Context {
         &self.rt
     }
 
+    /// Handle the messages received by this context according to their priority,
+    /// with at most `max_priority_messages_in_a_row` high priority messages handled before a
+    /// waiting normal priority message
+    pub(crate) fn set_priority_lanes(&mut self, max_priority_messages_in_a_row: usize) {
+        self.priority_lanes = Some(PriorityLanes::new(max_priority_messages_in_a_row));
+    }
+
     /// Return mailbox_count clone
     pub(crate) fn mailbox_count(&self) -> Arc<AtomicUsize> {
         self.mailbox_count.clone()
@@ -87,6 +102,17 @@ impl Context {
         &self.flow_controls
     }
 
+    /// Shared [`MessageSizes`] instance
+    pub fn message_sizes(&self) -> &MessageSizes {
+        &self.message_sizes
+    }
+
+    /// Shared [`NodeEvents`] bus
+    #[cfg(feature = "std")]
+    pub fn node_events(&self) -> &NodeEvents {
+        &self.node_events
+    }
+
     /// Return the tracing context
     #[cfg(feature = "std")]
     pub fn tracing_context(&self) -> OpenTelemetryContext {
@@ -144,6 +170,23 @@ impl Context {
             .take_workers()
     }
 
+    /// Return a description of all the workers and processors registered on a node,
+    /// with their addresses and owner types
+    pub async fn list_workers_info(&self) -> Result<Vec<WorkerInfo>> {
+        let (msg, mut reply_rx) = NodeMessage::list_workers_info();
+
+        self.sender
+            .send(msg)
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        reply_rx
+            .recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??
+            .take_workers_info()
+    }
+
     /// Send a shutdown acknowledgement to the router
     pub(crate) async fn send_stop_ack(&self) -> Result<()> {
         self.sender
@@ -1,5 +1,7 @@
 use core::time::Duration;
 
+#[cfg(feature = "std")]
+use crate::NodeEvents;
 use ockam_core::compat::collections::HashMap;
 use ockam_core::compat::time::now;
 use ockam_core::compat::{boxed::Box, sync::Arc, sync::RwLock};
@@ -16,13 +18,16 @@ use ockam_transport_core::Transport;
 use crate::async_drop::AsyncDrop;
 use crate::channel_types::{message_channel, small_channel, SmallReceiver, SmallSender};
 use crate::tokio::{self, runtime::Handle};
-use crate::{debugger, Context};
+use crate::{debugger, Context, MessageSizes};
 use crate::{error::*, relay::CtrlSignal, router::SenderPair, NodeMessage};
 
 /// A special type of `Context` that has no worker relay and inherits
 /// the parent `Context`'s access control
 pub type DetachedContext = Context;
 
+/// Owner type name reported for the addresses of a detached context
+pub(crate) const DETACHED_CONTEXT_OWNER: &str = "ockam_node::DetachedContext";
+
 /// A special sender type that connects a type to an AsyncDrop handler
 pub type AsyncDropSender = crate::tokio::sync::oneshot::Sender<Address>;
 
@@ -65,6 +70,8 @@ impl Context {
         async_drop_sender: Option<AsyncDropSender>,
         transports: Arc<RwLock<HashMap<TransportType, Arc<dyn Transport>>>>,
         flow_controls: &FlowControls,
+        message_sizes: &MessageSizes,
+        #[cfg(feature = "std")] node_events: &NodeEvents,
         #[cfg(feature = "std")] tracing_context: OpenTelemetryContext,
     ) -> (Self, SenderPair, SmallReceiver<CtrlSignal>) {
         let (mailbox_tx, receiver) = message_channel();
@@ -77,8 +84,12 @@ impl Context {
                 receiver,
                 async_drop_sender,
                 mailbox_count: Arc::new(0.into()),
+                priority_lanes: None,
                 transports,
                 flow_controls: flow_controls.clone(),
+                message_sizes: message_sizes.clone(),
+                #[cfg(feature = "std")]
+                node_events: node_events.clone(),
                 #[cfg(feature = "std")]
                 tracing_context,
             },
@@ -101,6 +112,9 @@ impl Context {
             None,
             self.transports.clone(),
             &self.flow_controls,
+            &self.message_sizes,
+            #[cfg(feature = "std")]
+            &self.node_events,
             #[cfg(feature = "std")]
             self.tracing_context(),
         )
@@ -118,6 +132,9 @@ impl Context {
             Some(drop_sender),
             self.transports.clone(),
             &self.flow_controls,
+            &self.message_sizes,
+            #[cfg(feature = "std")]
+            &self.node_events,
             #[cfg(feature = "std")]
             OpenTelemetryContext::current(),
         )
@@ -208,8 +225,13 @@ impl Context {
         let (ctx, sender, _) = self.copy_with_mailboxes_detached(mailboxes, drop_sender);
 
         // Create a "detached relay" and register it with the router
-        let (msg, mut rx) =
-            NodeMessage::start_worker(addresses, sender, true, Arc::clone(&self.mailbox_count));
+        let (msg, mut rx) = NodeMessage::start_worker(
+            addresses,
+            sender,
+            true,
+            Arc::clone(&self.mailbox_count),
+            DETACHED_CONTEXT_OWNER,
+        );
         self.sender
             .send(msg)
             .await
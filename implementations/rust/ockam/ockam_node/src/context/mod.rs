@@ -1,6 +1,7 @@
 #[allow(clippy::module_inception)]
 mod context;
 mod context_lifecycle;
+mod priority_lanes;
 mod receive_message;
 mod register_router;
 mod send_message;
@@ -10,5 +11,6 @@ mod worker_lifecycle;
 
 pub use context::*;
 pub use context_lifecycle::*;
+pub use priority_lanes::DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW;
 pub use receive_message::*;
 pub use send_message::*;
@@ -0,0 +1,117 @@
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::RelayMessage;
+
+/// Default maximum number of high priority messages handled in a row
+/// while normal priority messages are waiting
+pub const DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW: usize = 8;
+
+/// Maximum number of messages taken out of the mailbox channel to be sorted.
+/// The other messages stay in the channel so that their senders are still slowed down
+/// when the worker is busy.
+const MAX_SORTED_MESSAGES: usize = 16;
+
+/// Mailbox queues of a worker started with priority lanes.
+///
+/// The messages marked with a high [`MessagePriority`](ockam_core::MessagePriority) are handled
+/// first. In order to avoid starving the normal priority messages, at most
+/// `max_high_in_a_row` high priority messages are handled before a waiting normal priority
+/// message is handled.
+pub(crate) struct PriorityLanes {
+    high: VecDeque<RelayMessage>,
+    normal: VecDeque<RelayMessage>,
+    max_high_in_a_row: usize,
+    high_in_a_row: usize,
+}
+
+impl PriorityLanes {
+    pub(crate) fn new(max_high_in_a_row: usize) -> Self {
+        Self {
+            high: VecDeque::new(),
+            normal: VecDeque::new(),
+            max_high_in_a_row: max_high_in_a_row.max(1),
+            high_in_a_row: 0,
+        }
+    }
+
+    /// Return true if there are no messages to handle
+    pub(crate) fn is_empty(&self) -> bool {
+        self.high.is_empty() && self.normal.is_empty()
+    }
+
+    /// Return true if no more messages should be taken out of the mailbox channel
+    pub(crate) fn is_full(&self) -> bool {
+        self.high.len() + self.normal.len() >= MAX_SORTED_MESSAGES
+    }
+
+    /// Add a message to the lane corresponding to its priority
+    pub(crate) fn push(&mut self, relay_msg: RelayMessage) {
+        if relay_msg.local_message().priority().is_high() {
+            self.high.push_back(relay_msg)
+        } else {
+            self.normal.push_back(relay_msg)
+        }
+    }
+
+    /// Return the next message to handle
+    pub(crate) fn pop(&mut self) -> Option<RelayMessage> {
+        let take_high = !self.high.is_empty()
+            && (self.normal.is_empty() || self.high_in_a_row < self.max_high_in_a_row);
+        if take_high {
+            self.high_in_a_row += 1;
+            self.high.pop_front()
+        } else {
+            self.high_in_a_row = 0;
+            self.normal.pop_front()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::compat::vec::Vec;
+    use ockam_core::{Address, LocalMessage, MessagePriority};
+
+    fn message(payload: u8, priority: MessagePriority) -> RelayMessage {
+        RelayMessage::new(
+            Address::random_local(),
+            Address::random_local(),
+            LocalMessage::new()
+                .with_payload(vec![payload])
+                .with_priority(priority),
+        )
+    }
+
+    fn drain(lanes: &mut PriorityLanes) -> Vec<u8> {
+        let mut payloads = Vec::new();
+        while let Some(relay_msg) = lanes.pop() {
+            payloads.push(relay_msg.local_message().payload_ref()[0]);
+        }
+        payloads
+    }
+
+    #[test]
+    fn high_priority_messages_are_handled_first() {
+        let mut lanes = PriorityLanes::new(8);
+        lanes.push(message(1, MessagePriority::Normal));
+        lanes.push(message(2, MessagePriority::Normal));
+        lanes.push(message(3, MessagePriority::High));
+        lanes.push(message(4, MessagePriority::High));
+
+        assert_eq!(drain(&mut lanes), vec![3, 4, 1, 2]);
+        assert!(lanes.is_empty());
+    }
+
+    #[test]
+    fn normal_priority_messages_are_not_starved() {
+        let mut lanes = PriorityLanes::new(2);
+        for i in 1..=2 {
+            lanes.push(message(i, MessagePriority::Normal));
+        }
+        for i in 10..=15 {
+            lanes.push(message(i, MessagePriority::High));
+        }
+
+        assert_eq!(drain(&mut lanes), vec![10, 11, 1, 12, 13, 2, 14, 15]);
+    }
+}
@@ -1,7 +1,13 @@
 use core::sync::atomic::Ordering;
 use core::time::Duration;
 
-use ockam_core::{Message, RelayMessage, Result, Routed};
+use futures::stream::{self, Stream};
+use ockam_core::api::{RequestHeader, Response};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{
+    Any, Decodable, Message, PayloadMigrationRegistry, PayloadMigrations, RelayMessage, Result,
+    Routed, TypedMessage, TypedPayload,
+};
 
 use crate::debugger;
 use crate::error::*;
@@ -60,14 +66,7 @@ impl Context {
     /// Wait for the next message from the mailbox
     pub(crate) async fn receiver_next(&mut self) -> Result<Option<RelayMessage>> {
         loop {
-            let relay_msg = if let Some(msg) = self.receiver.recv().await.map(|msg| {
-                trace!("{}: received new message!", self.address());
-
-                // First we update the mailbox fill metrics
-                self.mailbox_count.fetch_sub(1, Ordering::Acquire);
-
-                msg
-            }) {
+            let relay_msg = if let Some(msg) = self.next_relay_message().await {
                 msg
             } else {
                 // no more messages
@@ -82,6 +81,7 @@ impl Context {
                     relay_msg.return_route(),
                     relay_msg.destination()
                 );
+                self.notify_denied_request(&relay_msg).await;
                 continue;
             }
 
@@ -89,17 +89,93 @@ impl Context {
         }
     }
 
+    /// Reply with an access denied response to a request denied by the incoming access control,
+    /// if its sender asked for it. Other denied messages are dropped silently
+    async fn notify_denied_request(&self, relay_msg: &RelayMessage) {
+        if relay_msg.return_route().is_empty() {
+            return;
+        }
+        let Ok(request) = Vec::<u8>::decode(relay_msg.payload()) else {
+            return;
+        };
+        let Ok(header) = minicbor::decode::<RequestHeader>(&request) else {
+            return;
+        };
+        if !header.notify_on_deny() {
+            return;
+        }
+
+        let reason = self.mailboxes.incoming_deny_reason(relay_msg).await;
+        let response = match Response::access_denied(&header, reason.as_ref()).to_vec() {
+            Ok(response) => response,
+            Err(e) => {
+                warn!("Cannot encode the access denied response: {e}");
+                return;
+            }
+        };
+        if let Err(e) = self
+            .send_from_address(
+                relay_msg.return_route().clone(),
+                response,
+                relay_msg.destination().clone(),
+            )
+            .await
+        {
+            warn!(
+                "Cannot notify {} that its request was denied: {e}",
+                relay_msg.return_route()
+            );
+        }
+    }
+
+    /// Return the next message from the mailbox, taking its priority into account
+    /// if this context uses priority lanes
+    async fn next_relay_message(&mut self) -> Option<RelayMessage> {
+        if self.priority_lanes.is_none() {
+            return self.receive_from_mailbox().await;
+        }
+
+        // wait for a message only if there is none left to handle
+        if matches!(&self.priority_lanes, Some(lanes) if lanes.is_empty()) {
+            let relay_msg = self.receive_from_mailbox().await?;
+            if let Some(lanes) = self.priority_lanes.as_mut() {
+                lanes.push(relay_msg);
+            }
+        }
+
+        // sort the messages already waiting in the mailbox
+        #[cfg(feature = "std")]
+        while matches!(&self.priority_lanes, Some(lanes) if !lanes.is_full()) {
+            let Ok(relay_msg) = self.receiver.try_recv() else {
+                break;
+            };
+            self.mailbox_count.fetch_sub(1, Ordering::Acquire);
+            if let Some(lanes) = self.priority_lanes.as_mut() {
+                lanes.push(relay_msg);
+            }
+        }
+
+        self.priority_lanes.as_mut().and_then(|lanes| lanes.pop())
+    }
+
+    /// Wait for the next message in the mailbox channel
+    async fn receive_from_mailbox(&mut self) -> Option<RelayMessage> {
+        let relay_msg = self.receiver.recv().await?;
+        trace!("{}: received new message!", self.address());
+
+        // First we update the mailbox fill metrics
+        self.mailbox_count.fetch_sub(1, Ordering::Acquire);
+        Some(relay_msg)
+    }
+
     /// A convenience function to get a Routed message from the Mailbox
     async fn next_from_mailbox<M: Message>(&mut self) -> Result<Routed<M>> {
         let msg = self
             .receiver_next()
             .await?
             .ok_or_else(|| NodeError::Data.not_found())?;
-        let destination_addr = msg.destination().clone();
-        let src_addr = msg.source().clone();
-        let local_msg = msg.into_local_message();
 
-        Ok(Routed::new(destination_addr, src_addr, local_msg))
+        Ok(msg.into_routed())
     }
 
     /// Block the current worker to wait for a typed message
@@ -130,4 +206,65 @@ impl Context {
             MessageWait::Blocking => self.next_from_mailbox().await,
         }
     }
+
+    /// Wait for a message sent with [`send_typed()`](Self::send_typed)
+    ///
+    /// This function returns an error naming the expected and received schemas
+    /// if the message doesn't have the schema and version of `M`.
+    pub async fn receive_typed<M: TypedMessage>(&mut self) -> Result<M> {
+        self.receive_typed_extended(
+            MessageReceiveOptions::new(),
+            &PayloadMigrationRegistry::default(),
+        )
+        .await
+    }
+
+    /// Wait for a message sent with [`send_typed()`](Self::send_typed),
+    /// migrating it if it was encoded with an older version of the schema of `M`
+    pub async fn receive_typed_extended<M: TypedMessage>(
+        &mut self,
+        options: MessageReceiveOptions,
+        migrations: &dyn PayloadMigrations,
+    ) -> Result<M> {
+        self.receive_extended::<TypedPayload>(options)
+            .await?
+            .into_body()?
+            .decode_typed_with(migrations)
+    }
+
+    /// Return a stream of the messages received on the addresses of this context
+    ///
+    /// This allows messages to be processed with the `futures::Stream` combinators, for example
+    /// to buffer them, or to stop waiting for them after some time. Messages are only taken from
+    /// the mailbox when the stream is polled: a slow consumer slows down the senders, since the
+    /// mailbox of a context is bounded.
+    ///
+    /// The stream ends once the context is stopped, or once the node shuts down. Messages which
+    /// don't pass the incoming access control of the context are skipped, as with
+    /// [`Context::receive`], and the body of a message is only decoded by [`Routed::into_body`].
+    ///
+    /// The stream takes the messages of all the addresses of the context. Register several
+    /// addresses on the same context, with [`Context::new_detached_with_mailboxes`], to select
+    /// over them, and use [`Routed::msg_addr`] to find out the address of each message.
+    ///
+    /// A message taken by the stream is not delivered to [`Worker::handle_message`]: the stream
+    /// should be used with a detached context, or with addresses which are not handled by a
+    /// worker while the stream is polled.
+    ///
+    /// [`Worker::handle_message`]: ockam_core::Worker::handle_message
+    pub fn receiver_stream<M: Message>(&mut self) -> impl Stream<Item = Result<Routed<M>>> + '_ {
+        stream::unfold(self, |ctx| async move {
+            match ctx.receiver_next().await {
+                Ok(Some(relay_msg)) => Some((Ok(relay_msg.into_routed()), ctx)),
+                Ok(None) => None,
+                Err(e) => Some((Err(e), ctx)),
+            }
+        })
+    }
+
+    /// Return a stream of the messages received on the addresses of this context,
+    /// without decoding their payload. See [`Context::receiver_stream`]
+    pub fn any_receiver_stream(&mut self) -> impl Stream<Item = Result<Routed<Any>>> + '_ {
+        self.receiver_stream::<Any>()
+    }
 }
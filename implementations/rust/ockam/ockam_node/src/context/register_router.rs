@@ -1,5 +1,6 @@
 use crate::channel_types::small_channel;
 use crate::{error::*, Context, NodeMessage};
+use ockam_core::compat::string::String;
 use ockam_core::{Address, Result, TransportType};
 
 impl Context {
@@ -21,4 +22,50 @@ impl Context {
             .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
         Ok(())
     }
+
+    /// Register a worker for all the addresses of a transport type starting with a prefix.
+    ///
+    /// Messages sent to such an address are delivered to the worker registered for the
+    /// longest matching prefix, unless a worker is registered with the exact address.
+    /// Addresses matching no prefix are delivered to the router of the transport type.
+    pub async fn register_prefix<A: Into<Address>>(
+        &self,
+        type_: TransportType,
+        prefix: impl Into<String>,
+        addr: A,
+    ) -> Result<()> {
+        let (tx, mut rx) = small_channel();
+        self.sender
+            .send(NodeMessage::RegisterPrefix(
+                type_,
+                prefix.into(),
+                addr.into(),
+                tx,
+            ))
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
+        Ok(())
+    }
+
+    /// Remove a prefix registered with [`Context::register_prefix`]
+    pub async fn unregister_prefix(
+        &self,
+        type_: TransportType,
+        prefix: impl Into<String>,
+    ) -> Result<()> {
+        let (tx, mut rx) = small_channel();
+        self.sender
+            .send(NodeMessage::UnregisterPrefix(type_, prefix.into(), tx))
+            .await
+            .map_err(NodeError::from_send_err)?;
+
+        rx.recv()
+            .await
+            .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())??;
+        Ok(())
+    }
 }
@@ -5,16 +5,19 @@ use crate::{error::*, NodeMessage};
 use cfg_if::cfg_if;
 use core::time::Duration;
 use ockam_core::compat::{sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use ockam_core::LocalBody;
 use ockam_core::{
     errcode::{Kind, Origin},
     route, Address, AllowAll, AllowOnwardAddress, Error, LocalMessage, Mailboxes, Message,
-    RelayMessage, Result, Route, Routed,
+    RelayMessage, Result, Route, Routed, TypedMessage, TypedPayload,
 };
-use ockam_core::{LocalInfo, Mailbox};
+use ockam_core::{LocalInfo, Mailbox, MessagePriority};
 
 /// Full set of options to `send_and_receive_extended` function
 pub struct MessageSendReceiveOptions {
     message_wait: MessageWait,
+    priority: MessagePriority,
 }
 
 impl Default for MessageSendReceiveOptions {
@@ -28,6 +31,7 @@ impl MessageSendReceiveOptions {
     pub fn new() -> Self {
         Self {
             message_wait: MessageWait::Timeout(DEFAULT_TIMEOUT),
+            priority: MessagePriority::Normal,
         }
     }
 
@@ -42,6 +46,12 @@ impl MessageSendReceiveOptions {
         self.message_wait = MessageWait::Blocking;
         self
     }
+
+    /// Set the priority of the sent message
+    pub fn with_priority(mut self, priority: MessagePriority) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 impl Context {
@@ -109,7 +119,9 @@ impl Context {
         #[cfg(feature = "std")]
         child_ctx.set_tracing_context(self.tracing_context());
 
-        child_ctx.send(route, msg).await?;
+        child_ctx
+            .send_with_priority(route, msg, options.priority)
+            .await?;
         child_ctx
             .receive_extended::<M>(
                 MessageReceiveOptions::new().with_message_wait(options.message_wait),
@@ -173,6 +185,56 @@ impl Context {
             .await
     }
 
+    /// Send a message with a given priority.
+    ///
+    /// The high priority messages are handled first by the workers started with priority lanes.
+    pub async fn send_with_priority<R, M>(
+        &self,
+        route: R,
+        msg: M,
+        priority: MessagePriority,
+    ) -> Result<()>
+    where
+        R: Into<Route>,
+        M: Message + Send + 'static,
+    {
+        self.send_from_address_with_priority(route, msg, self.address(), priority)
+            .await
+    }
+
+    /// Send a message with a given priority, from a specific address of this worker
+    pub async fn send_from_address_with_priority<R, M>(
+        &self,
+        route: R,
+        msg: M,
+        sending_address: Address,
+        priority: MessagePriority,
+    ) -> Result<()>
+    where
+        R: Into<Route>,
+        M: Message + Send + 'static,
+    {
+        let local_info = if priority.is_high() {
+            vec![priority.to_local_info()]
+        } else {
+            Vec::new()
+        };
+        self.send_from_address_impl(route.into(), msg, sending_address, local_info)
+            .await
+    }
+
+    /// Send a message wrapped in a [`TypedPayload`], so that the receiver can check
+    /// that it expects the same schema and version of the message type.
+    ///
+    /// Use [`receive_typed()`](Self::receive_typed) to receive such a message.
+    pub async fn send_typed<R, M>(&self, route: R, msg: M) -> Result<()>
+    where
+        R: Into<Route>,
+        M: TypedMessage,
+    {
+        self.send(route, TypedPayload::new(msg)?).await
+    }
+
     /// Send a message to an address or via a fully-qualified route
     /// after attaching the given [`LocalInfo`] to the message.
     pub async fn send_with_local_info<R, M>(
@@ -242,6 +304,11 @@ impl Context {
             }
         };
 
+        // A message for a worker of this node, which is the last address of its route,
+        // is moved to that worker instead of being encoded here and decoded there
+        #[cfg(feature = "std")]
+        let is_local_destination = addr.is_local() && route.len() == 1;
+
         let req = NodeMessage::SenderReq(addr, reply_tx);
         self.sender
             .send(req)
@@ -254,7 +321,17 @@ impl Context {
             .take_sender()?;
 
         // Pack the payload into a TransportMessage
-        let payload = msg.encode().map_err(|_| NodeError::Data.internal())?;
+        cfg_if! {
+            if #[cfg(feature = "std")] {
+                let (payload, local_body) = if is_local_destination {
+                    (Vec::new(), Some(LocalBody::new(msg)))
+                } else {
+                    (msg.encode().map_err(|_| NodeError::Data.internal())?, None)
+                };
+            } else {
+                let payload = msg.encode().map_err(|_| NodeError::Data.internal())?;
+            }
+        }
 
         // Pack transport message into a LocalMessage wrapper
         cfg_if! {
@@ -277,6 +354,11 @@ impl Context {
 
         // Pack local message into a RelayMessage wrapper
         let relay_msg = RelayMessage::new(sending_address.clone(), addr, local_msg);
+        #[cfg(feature = "std")]
+        let relay_msg = match local_body {
+            Some(local_body) => relay_msg.with_local_body(local_body),
+            None => relay_msg,
+        };
 
         debugger::log_outgoing_message(self, &relay_msg);
 
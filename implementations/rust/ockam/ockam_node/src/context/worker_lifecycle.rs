@@ -1,3 +1,4 @@
+use crate::async_drop::{AsyncDrop, StopWorkerOnDrop};
 use crate::{Context, NodeError, NodeMessage, NodeReason};
 use crate::{ProcessorBuilder, WorkerBuilder};
 use ockam_core::{
@@ -166,6 +167,16 @@ impl Context {
         self.stop_address(addr.into(), AddressType::Worker).await
     }
 
+    /// Return a guard stopping a local worker when the guard is dropped, unless it is disarmed.
+    ///
+    /// This is used by a future starting a worker, then waiting for it, so that the worker is
+    /// stopped if the future fails or is cancelled before the worker is ready.
+    pub fn stop_worker_on_drop<A: Into<Address>>(&self, addr: A) -> StopWorkerOnDrop {
+        let (async_drop, drop_sender) = AsyncDrop::new_for_worker(self.sender.clone());
+        self.rt.spawn(async_drop.run());
+        StopWorkerOnDrop::new(addr.into(), drop_sender)
+    }
+
     /// Shut down a local processor by its address
     pub async fn stop_processor<A: Into<Address>>(&self, addr: A) -> Result<()> {
         self.stop_address(addr.into(), AddressType::Processor).await
@@ -1,7 +1,7 @@
 use crate::tokio::{sync::mpsc::error::SendError, time::error::Elapsed};
 use core::fmt;
 use ockam_core::{
-    compat::error::Error as StdError,
+    compat::{error::Error as StdError, string::String},
     errcode::{Kind, Origin},
     Address, Error,
 };
@@ -14,6 +14,13 @@ pub enum NodeError {
     ///
     /// An address either refers to a Worker or a Processor
     Address(Address),
+    /// An address could not be registered because it is already used by another worker
+    AddressAlreadyInUse {
+        /// The address which could not be registered
+        address: Address,
+        /// Type name of the worker already owning the address
+        owner: String,
+    },
     /// A data retrieval operation failed
     Data,
     /// A failure occurred because of invalid node state
@@ -72,6 +79,10 @@ impl fmt::Display for NodeError {
             "{}",
             match self {
                 Self::Address(addr) => format!("operation failed for address {}", addr),
+                Self::AddressAlreadyInUse { address, owner } => format!(
+                    "address {} is already in use by a worker of type {}",
+                    address, owner
+                ),
                 Self::Data => "failed to load data".into(),
                 Self::NodeState(reason) => format!("failed because node state: {}", reason),
                 Self::WorkerState(reason) => format!("failed because worker state: {}", reason),
@@ -91,6 +102,10 @@ pub enum RouterReason {
     InvalidAddrType,
     /// Empty Address Set
     EmptyAddressSet,
+    /// A worker is already registered for this address prefix
+    DuplicatePrefix,
+    /// No worker is registered for this address prefix
+    UnknownPrefix,
 }
 
 impl fmt::Display for RouterReason {
@@ -102,6 +117,8 @@ impl fmt::Display for RouterReason {
                 Self::Duplicate => "a router for this type already exists",
                 Self::InvalidAddrType => "you can not register router for this address type",
                 Self::EmptyAddressSet => "address set cannot be empty",
+                Self::DuplicatePrefix => "a worker for this address prefix already exists",
+                Self::UnknownPrefix => "no worker is registered for this address prefix",
             }
         )
     }
@@ -0,0 +1,132 @@
+use crate::{Context, NodeEvent, NodeEventKind, NodeEvents};
+use core::time::Duration;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use tokio::task::AbortHandle;
+
+/// Service watching files, for example TLS certificates or credentials, and notifying
+/// its subscribers when their content changes, so that they can reload them without
+/// a restart of the node.
+///
+/// The files are polled at a regular interval, and a subscriber is only notified when
+/// the content of its file has changed and the file could be read entirely. Each
+/// successful reload is recorded with a [`NodeEventKind::FileReloaded`] event.
+#[derive(Clone)]
+pub struct FileWatcher {
+    interval: Duration,
+    node_events: NodeEvents,
+}
+
+impl FileWatcher {
+    /// Default interval between two checks of a file
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Create a file watcher publishing its events on the bus of a node
+    pub fn new(ctx: &Context) -> Self {
+        Self {
+            interval: Self::DEFAULT_INTERVAL,
+            node_events: ctx.node_events().clone(),
+        }
+    }
+
+    /// Check the files at a given interval instead of [`Self::DEFAULT_INTERVAL`]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Watch a file and call `on_change` with its new content every time it changes.
+    /// The current content of the file is not reported to the callback, and an error
+    /// returned by the callback means that the new content could not be applied.
+    ///
+    /// `subscriber` is a name for the component using the file, recorded in the reload events.
+    /// The file stops being watched when the returned [`FileWatch`] is dropped.
+    pub async fn watch(
+        &self,
+        subscriber: impl Into<String>,
+        path: impl Into<PathBuf>,
+        on_change: impl Fn(&Path, &[u8]) -> Result<()> + Send + Sync + 'static,
+    ) -> Result<FileWatch> {
+        let path = path.into();
+        let subscriber = subscriber.into();
+        let content = tokio::fs::read(&path).await.map_err(|e| {
+            Error::new(
+                Origin::Node,
+                Kind::Io,
+                format!("cannot watch the file {}: {e}", path.display()),
+            )
+        })?;
+        let mut fingerprint = Self::fingerprint(&content);
+        let interval = self.interval;
+        let node_events = self.node_events.clone();
+        let watched_path = path.clone();
+
+        let handle = tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                // the file might be missing or truncated while it is being replaced
+                let content = match tokio::fs::read(&watched_path).await {
+                    Ok(content) if !content.is_empty() => content,
+                    _ => continue,
+                };
+                let new_fingerprint = Self::fingerprint(&content);
+                if new_fingerprint == fingerprint {
+                    continue;
+                }
+                fingerprint = new_fingerprint;
+                match on_change(&watched_path, &content) {
+                    Ok(()) => {
+                        info!(path = %watched_path.display(), %subscriber, "reloaded a file");
+                        node_events.publish(
+                            NodeEvent::new(
+                                NodeEventKind::FileReloaded,
+                                watched_path.display().to_string(),
+                            )
+                            .with_detail("subscriber", &subscriber),
+                        )
+                    }
+                    Err(e) => {
+                        warn!(path = %watched_path.display(), %subscriber, "the new content of the file could not be loaded: {e}")
+                    }
+                }
+            }
+        });
+        debug!(path = %path.display(), "watching a file");
+
+        Ok(FileWatch {
+            path,
+            handle: handle.abort_handle(),
+        })
+    }
+
+    fn fingerprint(content: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        content.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Subscription to the changes of a file. The file stops being watched when it is dropped
+#[derive(Debug)]
+pub struct FileWatch {
+    path: PathBuf,
+    handle: AbortHandle,
+}
+
+impl FileWatch {
+    /// Path of the watched file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileWatch {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -54,8 +54,15 @@ mod context;
 mod delayed;
 mod error;
 mod executor;
+#[cfg(feature = "std")]
+mod file_watcher;
+mod message_sizes;
 mod messages;
 mod node;
+#[cfg(feature = "std")]
+mod node_events;
+#[cfg(feature = "std")]
+mod panics;
 mod processor_builder;
 mod relay;
 mod router;
@@ -68,11 +75,23 @@ mod worker_builder;
 #[cfg(feature = "std")]
 pub mod runtime;
 
+/// OpenTelemetry metrics recorded by transports and secure channels
+#[cfg(feature = "telemetry")]
+pub mod telemetry;
+
+pub use async_drop::StopWorkerOnDrop;
 pub use context::*;
 pub use delayed::*;
 pub use error::*;
 pub use executor::*;
+#[cfg(feature = "std")]
+pub use file_watcher::*;
+pub use message_sizes::*;
 pub use messages::*;
+#[cfg(feature = "std")]
+pub use node_events::*;
+#[cfg(feature = "std")]
+pub use panics::{log_panic, PANIC_LOG_TARGET};
 pub use processor_builder::ProcessorBuilder;
 #[cfg(feature = "std")]
 pub use storage::database;
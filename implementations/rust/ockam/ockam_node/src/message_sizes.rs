@@ -0,0 +1,287 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Address;
+
+/// Sizes below this value have their own bucket
+const LINEAR_BUCKETS: usize = 8;
+/// Number of buckets for each power of two above [`LINEAR_BUCKETS`]
+const SUB_BUCKETS: usize = 8;
+/// log2 of [`SUB_BUCKETS`]
+const SUB_BUCKETS_BITS: u32 = 3;
+/// Total number of buckets, covering sizes up to `u32::MAX`
+const BUCKETS: usize = LINEAR_BUCKETS + (32 - SUB_BUCKETS_BITS as usize) * SUB_BUCKETS;
+
+/// Direction of a message relative to the worker recording its size
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MessageDirection {
+    /// Message received by the worker
+    Inbound,
+    /// Message sent by the worker
+    Outbound,
+}
+
+/// Size accounting of the messages going through the workers of a node
+///
+/// Transports and secure channels register a [`MessageSizeRecorder`] for
+/// each of their workers. Recording is disabled by default and can be
+/// switched on and off at runtime, which lets us find out which hop of a
+/// route inflates the size of messages.
+#[derive(Clone, Default)]
+pub struct MessageSizes {
+    enabled: Arc<AtomicBool>,
+    histograms: Arc<RwLock<BTreeMap<Address, Arc<WorkerHistograms>>>>,
+}
+
+#[derive(Default)]
+struct WorkerHistograms {
+    inbound: SizeHistogram,
+    outbound: SizeHistogram,
+}
+
+impl MessageSizes {
+    /// Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if message sizes are currently recorded
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Enable or disable the recording of message sizes.
+    /// Enabling the recording resets all the collected statistics.
+    pub fn set_enabled(&self, enabled: bool) {
+        if enabled && !self.is_enabled() {
+            for histograms in self.histograms.read().unwrap().values() {
+                histograms.inbound.reset();
+                histograms.outbound.reset();
+            }
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Create a recorder for the worker at the given address.
+    ///
+    /// The histograms of the worker are allocated here, so that recording
+    /// a message size never allocates. They are removed when the recorder
+    /// is dropped.
+    pub fn recorder(&self, address: &Address) -> MessageSizeRecorder {
+        let histograms = Arc::new(WorkerHistograms::default());
+        self.histograms
+            .write()
+            .unwrap()
+            .insert(address.clone(), histograms.clone());
+        MessageSizeRecorder {
+            address: address.clone(),
+            enabled: self.enabled.clone(),
+            histograms,
+            registry: self.histograms.clone(),
+        }
+    }
+
+    /// Return the statistics of all the workers having recorded at least one message
+    pub fn stats(&self) -> Vec<MessageSizeStats> {
+        let mut stats = vec![];
+        for (address, histograms) in self.histograms.read().unwrap().iter() {
+            for (direction, histogram) in [
+                (MessageDirection::Inbound, &histograms.inbound),
+                (MessageDirection::Outbound, &histograms.outbound),
+            ] {
+                if let Some(s) = histogram.stats(address, direction) {
+                    stats.push(s);
+                }
+            }
+        }
+        stats
+    }
+}
+
+/// Records the sizes of the messages handled by a worker
+pub struct MessageSizeRecorder {
+    address: Address,
+    enabled: Arc<AtomicBool>,
+    histograms: Arc<WorkerHistograms>,
+    registry: Arc<RwLock<BTreeMap<Address, Arc<WorkerHistograms>>>>,
+}
+
+impl MessageSizeRecorder {
+    /// Record the size of a message received by the worker
+    pub fn record_inbound(&self, size: usize) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.histograms.inbound.record(size)
+        }
+    }
+
+    /// Record the size of a message sent by the worker
+    pub fn record_outbound(&self, size: usize) {
+        if self.enabled.load(Ordering::Relaxed) {
+            self.histograms.outbound.record(size)
+        }
+    }
+}
+
+impl Drop for MessageSizeRecorder {
+    fn drop(&mut self) {
+        let mut registry = self.registry.write().unwrap();
+        // Only remove our own histograms, the address may have been registered again
+        if let Some(histograms) = registry.get(&self.address) {
+            if Arc::ptr_eq(histograms, &self.histograms) {
+                registry.remove(&self.address);
+            }
+        }
+    }
+}
+
+/// Statistics of the sizes of the messages handled by a worker in one direction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MessageSizeStats {
+    /// Address of the worker
+    pub address: Address,
+    /// Direction of the messages
+    pub direction: MessageDirection,
+    /// Number of recorded messages
+    pub count: usize,
+    /// Median size, in bytes
+    pub p50: usize,
+    /// 95th percentile of the sizes, in bytes
+    pub p95: usize,
+    /// Largest size, in bytes
+    pub max: usize,
+}
+
+/// Log-linear histogram of message sizes, with preallocated atomic buckets.
+///
+/// Sizes below 8 bytes are exact, larger sizes are rounded up to 1/8th of
+/// their power of two.
+struct SizeHistogram {
+    buckets: [AtomicUsize; BUCKETS],
+    count: AtomicUsize,
+    max: AtomicUsize,
+}
+
+impl Default for SizeHistogram {
+    fn default() -> Self {
+        Self {
+            buckets: core::array::from_fn(|_| AtomicUsize::new(0)),
+            count: AtomicUsize::new(0),
+            max: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl SizeHistogram {
+    fn bucket_index(size: usize) -> usize {
+        let size = size.min(u32::MAX as usize) as u32;
+        if (size as usize) < LINEAR_BUCKETS {
+            return size as usize;
+        }
+        let power = 31 - size.leading_zeros();
+        let shift = power - SUB_BUCKETS_BITS;
+        let sub_bucket = ((size >> shift) as usize) & (SUB_BUCKETS - 1);
+        LINEAR_BUCKETS + (shift as usize) * SUB_BUCKETS + sub_bucket
+    }
+
+    /// Largest size falling into a bucket
+    fn bucket_upper_bound(index: usize) -> usize {
+        if index < LINEAR_BUCKETS {
+            return index;
+        }
+        let shift = (index - LINEAR_BUCKETS) / SUB_BUCKETS;
+        let sub_bucket = (index - LINEAR_BUCKETS) % SUB_BUCKETS;
+        let upper = ((SUB_BUCKETS + sub_bucket + 1) as u64) << shift;
+        (upper - 1).min(u32::MAX as u64) as usize
+    }
+
+    fn record(&self, size: usize) {
+        self.buckets[Self::bucket_index(size)].fetch_add(1, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.max.fetch_max(size, Ordering::Relaxed);
+    }
+
+    fn reset(&self) {
+        for bucket in self.buckets.iter() {
+            bucket.store(0, Ordering::Relaxed);
+        }
+        self.count.store(0, Ordering::Relaxed);
+        self.max.store(0, Ordering::Relaxed);
+    }
+
+    /// Smallest bucket bound below which `percent`% of the sizes are
+    fn percentile(&self, count: usize, max: usize, percent: usize) -> usize {
+        let rank = (count * percent).div_ceil(100).max(1);
+        let mut seen = 0;
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            seen += bucket.load(Ordering::Relaxed);
+            if seen >= rank {
+                return Self::bucket_upper_bound(index).min(max);
+            }
+        }
+        max
+    }
+
+    fn stats(&self, address: &Address, direction: MessageDirection) -> Option<MessageSizeStats> {
+        let count = self.count.load(Ordering::Relaxed);
+        if count == 0 {
+            return None;
+        }
+        let max = self.max.load(Ordering::Relaxed);
+        Some(MessageSizeStats {
+            address: address.clone(),
+            direction,
+            count,
+            p50: self.percentile(count, max, 50),
+            p95: self.percentile(count, max, 95),
+            max,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bucket_bounds_contain_sizes() {
+        for size in (0..5000).chain([65535, 65536, 1 << 20, u32::MAX as usize]) {
+            let index = SizeHistogram::bucket_index(size);
+            assert!(index < BUCKETS);
+            assert!(SizeHistogram::bucket_upper_bound(index) >= size);
+            if index > 0 {
+                assert!(SizeHistogram::bucket_upper_bound(index - 1) < size);
+            }
+        }
+    }
+
+    #[test]
+    fn record_only_when_enabled() {
+        let sizes = MessageSizes::new();
+        let address = Address::from_string("worker");
+        let recorder = sizes.recorder(&address);
+
+        recorder.record_inbound(100);
+        assert!(sizes.stats().is_empty());
+
+        sizes.set_enabled(true);
+        for size in 1..=100 {
+            recorder.record_inbound(size);
+        }
+        recorder.record_outbound(1000);
+
+        let stats = sizes.stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].direction, MessageDirection::Inbound);
+        assert_eq!(stats[0].count, 100);
+        assert_eq!(stats[0].max, 100);
+        assert!((50..=56).contains(&stats[0].p50));
+        assert!((95..=100).contains(&stats[0].p95));
+        assert_eq!(stats[1].direction, MessageDirection::Outbound);
+        assert_eq!(stats[1].count, 1);
+        assert_eq!(stats[1].p50, 1000);
+
+        drop(recorder);
+        assert!(sizes.stats().is_empty());
+    }
+}
@@ -20,17 +20,26 @@ pub enum NodeMessage {
         detached: bool,
         /// A mechanism to read channel fill-state for a worker
         mailbox_count: Arc<AtomicUsize>,
+        /// Type name of the worker, used to diagnose address collisions
+        owner: &'static str,
         /// Reply channel for command confirmation
         reply: SmallSender<NodeReplyResult>,
     },
     /// Return a list of all worker addresses
     ListWorkers(SmallSender<NodeReplyResult>),
+    /// Return a list of all workers, with their addresses and owner types
+    ListWorkersInfo(SmallSender<NodeReplyResult>),
     /// Add an existing address to a cluster
     SetCluster(Address, String, SmallSender<NodeReplyResult>),
     /// Stop an existing worker
     StopWorker(Address, bool, SmallSender<NodeReplyResult>),
-    /// Start a new processor
-    StartProcessor(Address, SenderPair, SmallSender<NodeReplyResult>),
+    /// Start a new processor, the `&str` is the type name of the processor
+    StartProcessor(
+        Address,
+        SenderPair,
+        &'static str,
+        SmallSender<NodeReplyResult>,
+    ),
     /// Stop an existing processor
     StopProcessor(Address, SmallSender<NodeReplyResult>),
     /// Stop the node (and all workers)
@@ -43,6 +52,10 @@ pub enum NodeMessage {
     SenderReq(Address, SmallSender<NodeReplyResult>),
     /// Register a new router for a route id type
     Router(TransportType, Address, SmallSender<NodeReplyResult>),
+    /// Register a worker for the addresses of a transport type starting with a prefix
+    RegisterPrefix(TransportType, String, Address, SmallSender<NodeReplyResult>),
+    /// Remove a prefix registered for a transport type
+    UnregisterPrefix(TransportType, String, SmallSender<NodeReplyResult>),
     /// Message the router to set an address as "ready"
     SetReady(Address),
     /// Check whether an address has been marked as "ready"
@@ -54,15 +67,18 @@ impl fmt::Display for NodeMessage {
         match self {
             NodeMessage::StartWorker { .. } => write!(f, "StartWorker"),
             NodeMessage::ListWorkers(_) => write!(f, "ListWorkers"),
+            NodeMessage::ListWorkersInfo(_) => write!(f, "ListWorkersInfo"),
             NodeMessage::SetCluster(_, _, _) => write!(f, "SetCluster"),
             NodeMessage::StopWorker(_, _, _) => write!(f, "StopWorker"),
-            NodeMessage::StartProcessor(_, _, _) => write!(f, "StartProcessor"),
+            NodeMessage::StartProcessor(_, _, _, _) => write!(f, "StartProcessor"),
             NodeMessage::StopProcessor(_, _) => write!(f, "StopProcessor"),
             NodeMessage::StopNode(_, _) => write!(f, "StopNode"),
             NodeMessage::AbortNode => write!(f, "AbortNode"),
             NodeMessage::StopAck(_) => write!(f, "StopAck"),
             NodeMessage::SenderReq(_, _) => write!(f, "SenderReq"),
             NodeMessage::Router(_, _, _) => write!(f, "Router"),
+            NodeMessage::RegisterPrefix(_, _, _, _) => write!(f, "RegisterPrefix"),
+            NodeMessage::UnregisterPrefix(_, _, _) => write!(f, "UnregisterPrefix"),
             NodeMessage::SetReady(_) => write!(f, "SetReady"),
             NodeMessage::CheckReady(_, _) => write!(f, "CheckReady"),
         }
@@ -78,11 +94,14 @@ impl NodeMessage {
     ///               relay behind it that can respond to shutdown
     ///               commands.  Setting this to `true` will disable
     ///               stop ACK support in the router
+    ///
+    /// * `owner`: type name of the worker registering the addresses
     pub fn start_worker(
         addrs: Vec<Address>,
         senders: SenderPair,
         detached: bool,
         mailbox_count: Arc<AtomicUsize>,
+        owner: &'static str,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (reply, rx) = small_channel();
         (
@@ -91,6 +110,7 @@ impl NodeMessage {
                 senders,
                 detached,
                 mailbox_count,
+                owner,
                 reply,
             },
             rx,
@@ -101,9 +121,10 @@ impl NodeMessage {
     pub fn start_processor(
         address: Address,
         senders: SenderPair,
+        owner: &'static str,
     ) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
-        (Self::StartProcessor(address, senders, tx), rx)
+        (Self::StartProcessor(address, senders, owner, tx), rx)
     }
 
     /// Create a stop worker message and reply receiver
@@ -118,6 +139,12 @@ impl NodeMessage {
         (Self::ListWorkers(tx), rx)
     }
 
+    /// Create a list workers info message and reply receiver
+    pub fn list_workers_info() -> (Self, SmallReceiver<NodeReplyResult>) {
+        let (tx, rx) = small_channel();
+        (Self::ListWorkersInfo(tx), rx)
+    }
+
     /// Create a set cluster message and reply receiver
     pub fn set_cluster(addr: Address, label: String) -> (Self, SmallReceiver<NodeReplyResult>) {
         let (tx, rx) = small_channel();
@@ -164,6 +191,8 @@ pub enum RouterReply {
     Ok,
     /// A list of worker addresses
     Workers(Vec<Address>),
+    /// A list of workers with their addresses and owner types
+    WorkersInfo(Vec<WorkerInfo>),
     /// Message sender to a specific worker
     Sender {
         /// The address a message is being sent to
@@ -247,6 +276,24 @@ impl RouterReply {
         Err(NodeError::RouterState(RouterReason::Duplicate).already_exists())
     }
 
+    /// Return [NodeError::RouterState] already exists for a prefix
+    #[track_caller]
+    pub fn prefix_exists() -> NodeReplyResult {
+        Err(NodeError::RouterState(RouterReason::DuplicatePrefix).already_exists())
+    }
+
+    /// Return [NodeError::RouterState] not found for a prefix
+    #[track_caller]
+    pub fn no_such_prefix() -> NodeReplyResult {
+        Err(NodeError::RouterState(RouterReason::UnknownPrefix).not_found())
+    }
+
+    /// Return [NodeError::RouterState] conflict
+    #[track_caller]
+    pub fn router_rejected(reason: RouterReason) -> NodeReplyResult {
+        Err(NodeError::RouterState(reason).conflict())
+    }
+
     /// Return [NodeError::NodeState] conflict
     #[track_caller]
     pub fn node_rejected(reason: NodeReason) -> NodeReplyResult {
@@ -264,6 +311,11 @@ impl RouterReply {
         Ok(Self::Workers(v))
     }
 
+    /// Return [RouterReply::WorkersInfo] for the given workers
+    pub fn workers_info(v: Vec<WorkerInfo>) -> NodeReplyResult {
+        Ok(Self::WorkersInfo(v))
+    }
+
     /// Return [RouterReply::Sender] for the given information
     pub fn sender(addr: Address, sender: MessageSender<RelayMessage>) -> NodeReplyResult {
         Ok(RouterReply::Sender { addr, sender })
@@ -285,6 +337,14 @@ impl RouterReply {
         }
     }
 
+    /// Consume the wrapper and return [RouterReply::WorkersInfo]
+    pub fn take_workers_info(self) -> Result<Vec<WorkerInfo>> {
+        match self {
+            Self::WorkersInfo(w) => Ok(w),
+            _ => Err(NodeError::NodeState(NodeReason::Unknown).internal()),
+        }
+    }
+
     /// Consume the wrapper and return [RouterReply::State]
     pub fn take_state(self) -> Result<bool> {
         match self {
@@ -301,3 +361,57 @@ impl RouterReply {
         }
     }
 }
+
+/// Description of a worker, or processor, registered in the router
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkerInfo {
+    address: Address,
+    addresses: Vec<Address>,
+    owner: String,
+    registered_at: u64,
+    processor: bool,
+}
+
+impl WorkerInfo {
+    /// Create a new worker description
+    pub fn new(
+        address: Address,
+        addresses: Vec<Address>,
+        owner: impl Into<String>,
+        registered_at: u64,
+        processor: bool,
+    ) -> Self {
+        Self {
+            address,
+            addresses,
+            owner: owner.into(),
+            registered_at,
+            processor,
+        }
+    }
+
+    /// Primary address of the worker
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// All the addresses registered by the worker, including the primary address
+    pub fn addresses(&self) -> &[Address] {
+        &self.addresses
+    }
+
+    /// Type name of the worker
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// Registration time, in seconds since the Unix epoch
+    pub fn registered_at(&self) -> u64 {
+        self.registered_at
+    }
+
+    /// Return true if this is a processor
+    pub fn is_processor(&self) -> bool {
+        self.processor
+    }
+}
@@ -1,5 +1,7 @@
 use crate::tokio::runtime::Runtime;
-use crate::{debugger, Context, Executor};
+#[cfg(feature = "std")]
+use crate::NodeEvents;
+use crate::{debugger, Context, Executor, MessageSizes};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControls;
 #[cfg(feature = "std")]
@@ -22,6 +24,7 @@ impl ockam_core::Worker for NullWorker {
 pub struct NodeBuilder {
     logging: bool,
     exit_on_panic: bool,
+    print_panics: bool,
     rt: Option<Arc<Runtime>>,
 }
 
@@ -37,6 +40,7 @@ impl NodeBuilder {
         Self {
             logging: true,
             exit_on_panic: true,
+            print_panics: true,
             rt: None,
         }
     }
@@ -46,6 +50,7 @@ impl NodeBuilder {
         Self {
             logging: false,
             exit_on_panic: self.exit_on_panic,
+            print_panics: self.print_panics,
             rt: self.rt,
         }
     }
@@ -55,6 +60,18 @@ impl NodeBuilder {
         Self {
             logging: self.logging,
             exit_on_panic: false,
+            print_panics: self.print_panics,
+            rt: self.rt,
+        }
+    }
+
+    /// Only report panics as error records in the logs, without printing them on the console.
+    /// This is necessary when the console output is made of structured log records
+    pub fn no_panic_output(self) -> Self {
+        Self {
+            logging: self.logging,
+            exit_on_panic: self.exit_on_panic,
+            print_panics: false,
             rt: self.rt,
         }
     }
@@ -64,6 +81,7 @@ impl NodeBuilder {
         Self {
             logging: self.logging,
             exit_on_panic: self.exit_on_panic,
+            print_panics: self.print_panics,
             rt: Some(rt),
         }
     }
@@ -80,16 +98,24 @@ impl NodeBuilder {
         // Since the Executor is used to run async functions we need to catch
         // any panic raised by those functions and exit the current process in case this happens.
         // Otherwise the Executor might stay blocked on the Router execution.
+        //
+        // Panics are always logged as error records, with the address of the worker which panicked, if any.
         #[cfg(feature = "std")]
-        if self.exit_on_panic {
-            std::panic::set_hook(Box::new(|panic_info| {
-                let message1 = format!("A fatal error occurred: {panic_info}.");
-                let message2 = "Please report this issue, with a copy of your logs, to https://github.com/build-trust/ockam/issues.";
-                error!(message1);
-                error!(message2);
-                println!("{}", message1);
-                println!("{}", message2);
-                std::process::exit(1);
+        if self.exit_on_panic || !self.print_panics {
+            let exit_on_panic = self.exit_on_panic;
+            let print_panics = self.print_panics;
+            std::panic::set_hook(Box::new(move |panic_info| {
+                crate::log_panic(panic_info);
+                if exit_on_panic {
+                    let message1 = format!("A fatal error occurred: {panic_info}.");
+                    let message2 = "Please report this issue, with a copy of your logs, to https://github.com/build-trust/ockam/issues.";
+                    error!(message2);
+                    if print_panics {
+                        println!("{}", message1);
+                        println!("{}", message2);
+                    }
+                    std::process::exit(1);
+                }
             }));
         }
 
@@ -116,6 +142,9 @@ impl NodeBuilder {
             None,
             Default::default(),
             &flow_controls,
+            &MessageSizes::new(),
+            #[cfg(feature = "std")]
+            &NodeEvents::default(),
             #[cfg(feature = "std")]
             OpenTelemetryContext::current(),
         );
@@ -0,0 +1,262 @@
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+
+/// Number of events buffered for each subscriber before new events are dropped
+pub const DEFAULT_NODE_EVENTS_CAPACITY: usize = 1024;
+
+/// Kind of the events emitted during the lifecycle of a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum NodeEventKind {
+    /// The node has started
+    NodeStarted,
+    /// The node is stopping
+    NodeStopped,
+    /// A transport listener is bound to a socket address
+    ListenerBound,
+    /// A secure channel has been established with a peer
+    SecureChannelEstablished,
+    /// A secure channel has been closed
+    SecureChannelClosed,
+    /// A relay has been created, or re-created after a failure
+    RelayUp,
+    /// A relay has been lost
+    RelayDown,
+    /// A message has been rejected by a policy
+    PolicyDenied,
+    /// A file used by a component of the node has changed and has been reloaded
+    FileReloaded,
+    /// The messages buffered for a connection were dropped because it could not be re-established
+    OutboxDropped,
+}
+
+impl NodeEventKind {
+    /// All the event kinds
+    pub fn all() -> [NodeEventKind; 10] {
+        [
+            NodeEventKind::NodeStarted,
+            NodeEventKind::NodeStopped,
+            NodeEventKind::ListenerBound,
+            NodeEventKind::SecureChannelEstablished,
+            NodeEventKind::SecureChannelClosed,
+            NodeEventKind::RelayUp,
+            NodeEventKind::RelayDown,
+            NodeEventKind::PolicyDenied,
+            NodeEventKind::FileReloaded,
+            NodeEventKind::OutboxDropped,
+        ]
+    }
+
+    /// Name of the kind, as stored and displayed
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NodeEventKind::NodeStarted => "node-started",
+            NodeEventKind::NodeStopped => "node-stopped",
+            NodeEventKind::ListenerBound => "listener-bound",
+            NodeEventKind::SecureChannelEstablished => "secure-channel-established",
+            NodeEventKind::SecureChannelClosed => "secure-channel-closed",
+            NodeEventKind::RelayUp => "relay-up",
+            NodeEventKind::RelayDown => "relay-down",
+            NodeEventKind::PolicyDenied => "policy-denied",
+            NodeEventKind::FileReloaded => "file-reloaded",
+            NodeEventKind::OutboxDropped => "outbox-dropped",
+        }
+    }
+}
+
+impl Display for NodeEventKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NodeEventKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NodeEventKind::all()
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| {
+                Error::new(
+                    Origin::Node,
+                    Kind::Invalid,
+                    format!("unknown node event kind: {s}"),
+                )
+            })
+    }
+}
+
+/// Event emitted by a component of a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeEvent {
+    kind: NodeEventKind,
+    subject: String,
+    details: BTreeMap<String, String>,
+    timestamp: u64,
+}
+
+impl NodeEvent {
+    /// Create an event about a subject (a worker address, a socket address, a resource name, ...),
+    /// timestamped with the current time
+    pub fn new(kind: NodeEventKind, subject: impl Into<String>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            kind,
+            subject: subject.into(),
+            details: BTreeMap::new(),
+            timestamp,
+        }
+    }
+
+    /// Add a detail to the event
+    pub fn with_detail(mut self, key: &str, value: impl ToString) -> Self {
+        self.details.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Kind of the event
+    pub fn kind(&self) -> NodeEventKind {
+        self.kind
+    }
+
+    /// Subject of the event
+    pub fn subject(&self) -> &str {
+        &self.subject
+    }
+
+    /// Additional information about the event
+    pub fn details(&self) -> &BTreeMap<String, String> {
+        &self.details
+    }
+
+    /// Time of the event, in seconds since the Unix epoch
+    pub fn timestamp(&self) -> u64 {
+        self.timestamp
+    }
+}
+
+/// Event bus of a node.
+///
+/// Core components publish [`NodeEvent`]s on the bus, which is shared by all
+/// the contexts of a node. Publishing never blocks: when a subscriber is too slow
+/// its oldest events are dropped and counted in [`NodeEvents::dropped`].
+#[derive(Clone)]
+pub struct NodeEvents {
+    sender: broadcast::Sender<NodeEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Default for NodeEvents {
+    fn default() -> Self {
+        Self::new(DEFAULT_NODE_EVENTS_CAPACITY)
+    }
+}
+
+impl NodeEvents {
+    /// Create an event bus buffering at most `capacity` events per subscriber
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            dropped: Default::default(),
+        }
+    }
+
+    /// Publish an event. The event is discarded if there are no subscribers
+    pub fn publish(&self, event: NodeEvent) {
+        trace!("publish node event {} {}", event.kind, event.subject);
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribe to the events published from now on
+    pub fn subscribe(&self) -> NodeEventsSubscriber {
+        NodeEventsSubscriber {
+            receiver: self.sender.subscribe(),
+            dropped: self.dropped.clone(),
+        }
+    }
+
+    /// Number of events which were dropped because a subscriber could not keep up
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+/// Subscriber to the events of a [`NodeEvents`] bus
+pub struct NodeEventsSubscriber {
+    receiver: broadcast::Receiver<NodeEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl NodeEventsSubscriber {
+    /// Wait for the next event. Return `None` once the bus is closed
+    pub async fn recv(&mut self) -> Option<NodeEvent> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(n)) => self.lagged(n),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Return the next event if one is available
+    pub fn try_recv(&mut self) -> Option<NodeEvent> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(n)) => self.lagged(n),
+                Err(_) => return None,
+            }
+        }
+    }
+
+    fn lagged(&self, n: u64) {
+        warn!("{n} node events were dropped");
+        self.dropped.fetch_add(n, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn event_kinds_are_parsed() {
+        for kind in NodeEventKind::all() {
+            assert_eq!(NodeEventKind::from_str(&kind.to_string()).unwrap(), kind);
+        }
+        assert!(NodeEventKind::from_str("policy_denied").is_err());
+    }
+
+    #[test]
+    fn events_are_dropped_when_a_subscriber_lags() {
+        let events = NodeEvents::new(2);
+        // without subscribers, events are discarded
+        events.publish(NodeEvent::new(NodeEventKind::NodeStarted, "n1"));
+
+        let mut subscriber = events.subscribe();
+        for i in 0..5 {
+            events.publish(NodeEvent::new(NodeEventKind::RelayUp, format!("relay-{i}")));
+        }
+
+        let received = subscriber.try_recv().unwrap();
+        assert_eq!(received.kind(), NodeEventKind::RelayUp);
+        assert_eq!(received.subject(), "relay-3");
+        assert_eq!(subscriber.try_recv().unwrap().subject(), "relay-4");
+        assert!(subscriber.try_recv().is_none());
+        assert_eq!(events.dropped(), 3);
+    }
+}
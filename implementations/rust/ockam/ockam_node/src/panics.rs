@@ -0,0 +1,84 @@
+use core::future::Future;
+use ockam_core::Address;
+use std::panic::PanicInfo;
+
+/// Target of the log records created for panics
+pub const PANIC_LOG_TARGET: &str = "ockam_node::panic";
+
+tokio::task_local! {
+    static CURRENT_WORKER: CurrentWorker;
+}
+
+/// Worker or processor run by the current task
+#[derive(Clone, Debug)]
+struct CurrentWorker {
+    address: Address,
+    /// Type of the messages handled by a worker, none for a processor
+    message_type: Option<&'static str>,
+}
+
+/// Run the relay of a worker or processor, so that a panic raised while it runs
+/// can be attributed to that worker
+pub(crate) async fn with_current_worker<F: Future>(
+    address: Address,
+    message_type: Option<&'static str>,
+    f: F,
+) -> F::Output {
+    CURRENT_WORKER
+        .scope(
+            CurrentWorker {
+                address,
+                message_type,
+            },
+            f,
+        )
+        .await
+}
+
+/// Log a panic as an error record.
+///
+/// When the panic is raised by a worker, or a processor, the record contains its address,
+/// and the type of the messages handled by the worker.
+pub fn log_panic(panic_info: &PanicInfo<'_>) {
+    let message = panic_message(panic_info);
+    let location = panic_info
+        .location()
+        .map(|l| l.to_string())
+        .unwrap_or_default();
+
+    match CURRENT_WORKER.try_with(|worker| worker.clone()) {
+        Ok(CurrentWorker {
+            address,
+            message_type: Some(message_type),
+        }) => error!(
+            target: PANIC_LOG_TARGET,
+            worker = %address,
+            message_type,
+            location = %location,
+            "The worker {address} panicked: {message}"
+        ),
+        Ok(CurrentWorker { address, .. }) => error!(
+            target: PANIC_LOG_TARGET,
+            processor = %address,
+            location = %location,
+            "The processor {address} panicked: {message}"
+        ),
+        Err(_) => error!(
+            target: PANIC_LOG_TARGET,
+            location = %location,
+            "A panic occurred: {message}"
+        ),
+    }
+}
+
+/// Return the message given to `panic!`
+fn panic_message(panic_info: &PanicInfo<'_>) -> String {
+    let payload = panic_info.payload();
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
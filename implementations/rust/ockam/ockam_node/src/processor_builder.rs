@@ -156,7 +156,8 @@ where
     debugger::log_inherit_context("PROCESSOR", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) = NodeMessage::start_processor(main_address.clone(), sender);
+    let (msg, mut rx) =
+        NodeMessage::start_processor(main_address.clone(), sender, core::any::type_name::<P>());
     context
         .sender()
         .send(msg)
@@ -110,7 +110,16 @@ where
         ctx: Context,
         ctrl_rx: SmallReceiver<CtrlSignal>,
     ) {
+        #[cfg(feature = "std")]
+        let address = ctx.address();
         let relay = ProcessorRelay::<P>::new(processor, ctx);
+        #[cfg(feature = "std")]
+        rt.spawn(crate::panics::with_current_worker(
+            address,
+            None,
+            relay.run(ctrl_rx),
+        ));
+        #[cfg(not(feature = "std"))]
         rt.spawn(relay.run(ctrl_rx));
     }
 }
@@ -45,11 +45,7 @@ where
     ///    to perform a cheaper clone on the message.
     ///
     fn wrap_direct_message(relay_msg: RelayMessage) -> Routed<M> {
-        Routed::new(
-            relay_msg.destination().clone(),
-            relay_msg.source().clone(),
-            relay_msg.into_local_message(),
-        )
+        relay_msg.into_routed()
     }
 
     /// Receive and handle a single message
@@ -181,7 +177,16 @@ where
 
     /// Build and spawn a new worker relay, returning a send handle to it
     pub(crate) fn init(rt: &Handle, worker: W, ctx: Context, ctrl_rx: SmallReceiver<CtrlSignal>) {
+        #[cfg(feature = "std")]
+        let address = ctx.address();
         let relay = WorkerRelay::new(worker, ctx);
+        #[cfg(feature = "std")]
+        rt.spawn(crate::panics::with_current_worker(
+            address,
+            Some(core::any::type_name::<M>()),
+            relay.run(ctrl_rx),
+        ));
+        #[cfg(not(feature = "std"))]
         rt.spawn(relay.run(ctrl_rx));
     }
 }
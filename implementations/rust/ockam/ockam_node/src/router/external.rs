@@ -0,0 +1,156 @@
+use ockam_core::compat::{collections::BTreeMap, string::String};
+use ockam_core::{Address, TransportType};
+
+/// Workers handling the messages sent to the addresses of other transport types.
+///
+/// A router can be registered for a whole transport type, and workers can be registered for
+/// the addresses of a transport type starting with a given prefix.
+/// An address is resolved to the worker of the longest registered prefix matching it, or to
+/// the router of its transport type if no prefix matches.
+///
+/// Since a prefix can only be registered once per transport type, there is at most one
+/// longest matching prefix and the resolution is deterministic.
+#[derive(Default)]
+pub(super) struct ExternalRoutes {
+    routers: BTreeMap<TransportType, Address>,
+    prefixes: BTreeMap<TransportType, BTreeMap<String, Address>>,
+}
+
+impl ExternalRoutes {
+    /// Register the router of a transport type.
+    /// Return false if a router is already registered for that type
+    pub(super) fn register_router(&mut self, tt: TransportType, router: Address) -> bool {
+        if self.routers.contains_key(&tt) {
+            return false;
+        }
+        self.routers.insert(tt, router);
+        true
+    }
+
+    /// Register a worker for the addresses of a transport type starting with a prefix.
+    /// Return false if a worker is already registered for that prefix
+    pub(super) fn register_prefix(
+        &mut self,
+        tt: TransportType,
+        prefix: String,
+        worker: Address,
+    ) -> bool {
+        let prefixes = self.prefixes.entry(tt).or_default();
+        if prefixes.contains_key(&prefix) {
+            return false;
+        }
+        prefixes.insert(prefix, worker);
+        true
+    }
+
+    /// Remove a prefix and return the worker which was registered for it
+    pub(super) fn unregister_prefix(&mut self, tt: TransportType, prefix: &str) -> Option<Address> {
+        let prefixes = self.prefixes.get_mut(&tt)?;
+        let worker = prefixes.remove(prefix);
+        if prefixes.is_empty() {
+            self.prefixes.remove(&tt);
+        }
+        worker
+    }
+
+    /// Return the worker handling the messages sent to an address of another transport type
+    pub(super) fn resolve(&self, addr: &Address) -> Option<&Address> {
+        self.longest_prefix_match(addr)
+            .or_else(|| self.routers.get(&addr.transport_type()))
+    }
+
+    fn longest_prefix_match(&self, addr: &Address) -> Option<&Address> {
+        self.prefixes
+            .get(&addr.transport_type())?
+            .iter()
+            .filter(|(prefix, _)| addr.starts_with(prefix.as_bytes()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, worker)| worker)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TT: TransportType = TransportType::new(42);
+
+    fn addr(value: &str) -> Address {
+        Address::new(TT, value)
+    }
+
+    #[test]
+    fn an_exact_prefix_is_preferred_to_a_shorter_prefix() {
+        let mut routes = ExternalRoutes::default();
+        assert!(routes.register_router(TT, "router".into()));
+        assert!(routes.register_prefix(TT, "10.0.0.".into(), "subnet".into()));
+        assert!(routes.register_prefix(TT, "10.0.0.1:4000".into(), "peer".into()));
+
+        assert_eq!(routes.resolve(&addr("10.0.0.1:4000")), Some(&"peer".into()));
+        assert_eq!(
+            routes.resolve(&addr("10.0.0.2:4000")),
+            Some(&"subnet".into())
+        );
+    }
+
+    #[test]
+    fn the_longest_matching_prefix_is_selected() {
+        let mut routes = ExternalRoutes::default();
+        assert!(routes.register_prefix(TT, "10.".into(), "short".into()));
+        assert!(routes.register_prefix(TT, "10.0.".into(), "medium".into()));
+        assert!(routes.register_prefix(TT, "10.0.0.".into(), "long".into()));
+        assert!(routes.register_prefix(TT, "10.1.".into(), "other".into()));
+
+        assert_eq!(routes.resolve(&addr("10.0.0.1:4000")), Some(&"long".into()));
+        assert_eq!(
+            routes.resolve(&addr("10.0.1.1:4000")),
+            Some(&"medium".into())
+        );
+        assert_eq!(
+            routes.resolve(&addr("10.2.0.1:4000")),
+            Some(&"short".into())
+        );
+        // without a router, an address matching no prefix can't be resolved
+        assert_eq!(routes.resolve(&addr("192.168.0.1:4000")), None);
+        // prefixes only apply to their transport type
+        assert_eq!(
+            routes.resolve(&Address::new(TransportType::new(43), "10.0.0.1:4000")),
+            None
+        );
+    }
+
+    #[test]
+    fn a_prefix_can_only_be_registered_once() {
+        let mut routes = ExternalRoutes::default();
+        assert!(routes.register_prefix(TT, "10.".into(), "first".into()));
+        assert!(!routes.register_prefix(TT, "10.".into(), "second".into()));
+        assert_eq!(
+            routes.resolve(&addr("10.0.0.1:4000")),
+            Some(&"first".into())
+        );
+
+        assert!(routes.register_router(TT, "router".into()));
+        assert!(!routes.register_router(TT, "other router".into()));
+    }
+
+    #[test]
+    fn a_removed_prefix_is_not_matched_anymore() {
+        let mut routes = ExternalRoutes::default();
+        assert!(routes.register_router(TT, "router".into()));
+        assert!(routes.register_prefix(TT, "10.".into(), "short".into()));
+        assert!(routes.register_prefix(TT, "10.0.".into(), "long".into()));
+
+        assert_eq!(routes.unregister_prefix(TT, "10.0."), Some("long".into()));
+        assert_eq!(routes.unregister_prefix(TT, "10.0."), None);
+        assert_eq!(
+            routes.resolve(&addr("10.0.0.1:4000")),
+            Some(&"short".into())
+        );
+
+        assert_eq!(routes.unregister_prefix(TT, "10."), Some("short".into()));
+        assert_eq!(
+            routes.resolve(&addr("10.0.0.1:4000")),
+            Some(&"router".into())
+        );
+    }
+}
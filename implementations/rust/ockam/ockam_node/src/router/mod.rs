@@ -1,3 +1,4 @@
+mod external;
 mod record;
 mod shutdown;
 mod start_processor;
@@ -10,16 +11,17 @@ mod utils;
 #[cfg(feature = "metrics")]
 use std::sync::atomic::AtomicUsize;
 
+use external::ExternalRoutes;
 use record::{AddressMeta, AddressRecord, InternalMap};
 use state::{NodeState, RouterState};
 
 use crate::channel_types::{router_channel, MessageSender, RouterReceiver, SmallSender};
 use crate::{
-    error::{NodeError, NodeReason},
+    error::{NodeError, NodeReason, RouterReason},
     relay::CtrlSignal,
-    NodeMessage, NodeReplyResult, RouterReply, ShutdownType,
+    NodeMessage, NodeReplyResult, RouterReply, ShutdownType, WorkerInfo, DETACHED_CONTEXT_OWNER,
 };
-use ockam_core::compat::{collections::BTreeMap, sync::Arc};
+use ockam_core::compat::{string::ToString, sync::Arc, vec::Vec};
 use ockam_core::flow_control::FlowControls;
 use ockam_core::{Address, RelayMessage, Result, TransportType};
 
@@ -38,28 +40,37 @@ pub struct SenderPair {
 ///
 /// External routing is supported only after a plugin component
 /// registers itself with this router.  Only one router can be
-/// registered per address type.
+/// registered per address type.  Workers can also be registered for
+/// the external addresses starting with a given prefix, in which case
+/// the worker of the longest matching prefix is preferred to the
+/// router of the address type.  A worker registered with the exact
+/// external address is always preferred.
 pub struct Router {
     /// Keep track of some additional router state information
     state: RouterState,
     /// Internal address state
     map: InternalMap,
-    /// Externally registered router components
-    external: BTreeMap<TransportType, Address>,
+    /// Externally registered router components and address prefixes
+    external: ExternalRoutes,
     /// Receiver for messages from node
     receiver: Option<RouterReceiver<NodeMessage>>,
 }
 
+/// Return the current time, in seconds, to timestamp address registrations
+fn registration_time() -> u64 {
+    ockam_core::compat::time::now().unwrap_or_default()
+}
+
 enum RouteType {
     Internal,
-    External(TransportType),
+    External,
 }
 
 fn determine_type(next: &Address) -> RouteType {
     if next.transport_type().is_local() {
         RouteType::Internal
     } else {
-        RouteType::External(next.transport_type())
+        RouteType::External
     }
 }
 
@@ -69,7 +80,7 @@ impl Router {
         Self {
             state: RouterState::new(sender),
             map: InternalMap::new(flow_controls),
-            external: BTreeMap::new(),
+            external: ExternalRoutes::default(),
             receiver: Some(receiver),
         }
     }
@@ -97,6 +108,8 @@ impl Router {
                 AddressMeta {
                     processor: false,
                     detached: true,
+                    owner: DETACHED_CONTEXT_OWNER,
+                    registered_at: registration_time(),
                 },
             ),
         );
@@ -126,13 +139,34 @@ impl Router {
         }
     }
 
-    async fn check_addr_not_exist(
+    /// Check that none of the addresses is already registered, either as the
+    /// primary address or as an alias of another worker.
+    /// If one of them is, reply with an error naming the type of the worker owning that address
+    async fn check_addrs_not_exist(
         &self,
-        addr: &Address,
+        addrs: &[Address],
+        owner: &str,
         reply: &SmallSender<NodeReplyResult>,
     ) -> Result<()> {
-        if self.map.address_records_map().contains_key(addr) {
-            let node = NodeError::Address(addr.clone());
+        let in_use = addrs.iter().find_map(|addr| {
+            let primary = self.map.get_primary_address(addr)?;
+            let existing_owner = self
+                .map
+                .get_address_record(primary)
+                .map(|record| record.meta().owner)
+                .unwrap_or("unknown");
+            Some((addr, existing_owner))
+        });
+
+        if let Some((addr, existing_owner)) = in_use {
+            warn!(
+                "Address {} can't be registered by {}: it is already in use by {}",
+                addr, owner, existing_owner
+            );
+            let node = NodeError::AddressAlreadyInUse {
+                address: addr.clone(),
+                owner: existing_owner.to_string(),
+            };
 
             reply
                 .send(Err(node.clone().already_exists()))
@@ -145,6 +179,24 @@ impl Router {
         }
     }
 
+    /// Return a description of all the registered workers and processors
+    fn workers_info(&self) -> Vec<WorkerInfo> {
+        self.map
+            .address_records_map()
+            .iter()
+            .map(|(primary, record)| {
+                let meta = record.meta();
+                WorkerInfo::new(
+                    primary.clone(),
+                    record.address_set().to_vec(),
+                    meta.owner,
+                    meta.registered_at,
+                    meta.processor,
+                )
+            })
+            .collect()
+    }
+
     async fn handle_msg(&mut self, msg: NodeMessage) -> Result<bool> {
         #[cfg(feature = "metrics")]
         self.map.update_metrics(); // Possibly remove this from the hot path?
@@ -157,21 +209,55 @@ impl Router {
         );
         match msg {
             // Successful router registration command
-            Router(tt, addr, sender) if !self.external.contains_key(&tt) => {
+            Router(tt, addr, sender) => {
                 // TODO: Remove after other transport implementations are moved to new architecture
-                trace!("Registering new router for type {}", tt);
+                let reply = if self.external.register_router(tt, addr) {
+                    trace!("Registering new router for type {}", tt);
+                    RouterReply::ok()
+                } else {
+                    // Rejected router registration command
+                    RouterReply::router_exists()
+                };
+                sender
+                    .send(reply)
+                    .await
+                    .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?
+            }
 
-                self.external.insert(tt, addr);
+            RegisterPrefix(tt, prefix, addr, sender) => {
+                let reply = if tt.is_local() {
+                    RouterReply::router_rejected(RouterReason::InvalidAddrType)
+                } else if self
+                    .external
+                    .register_prefix(tt, prefix.clone(), addr.clone())
+                {
+                    debug!(
+                        "Registering {} for the prefix '{}' of type {}",
+                        addr, prefix, tt
+                    );
+                    RouterReply::ok()
+                } else {
+                    RouterReply::prefix_exists()
+                };
                 sender
-                    .send(RouterReply::ok())
+                    .send(reply)
                     .await
                     .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?
             }
-            // Rejected router registration command
-            Router(_, _, sender) => {
-                // TODO: Remove after other transport implementations are moved to new architecture
+
+            UnregisterPrefix(tt, prefix, sender) => {
+                let reply = match self.external.unregister_prefix(tt, &prefix) {
+                    Some(addr) => {
+                        debug!(
+                            "Unregistering {} for the prefix '{}' of type {}",
+                            addr, prefix, tt
+                        );
+                        RouterReply::ok()
+                    }
+                    None => RouterReply::no_such_prefix(),
+                };
                 sender
-                    .send(RouterReply::router_exists())
+                    .send(reply)
                     .await
                     .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?
             }
@@ -182,15 +268,19 @@ impl Router {
                 senders,
                 detached,
                 mailbox_count,
+                owner,
                 ref reply,
-            } => start_worker::exec(self, addrs, senders, detached, mailbox_count, reply).await?,
+            } => {
+                start_worker::exec(self, addrs, senders, detached, mailbox_count, owner, reply)
+                    .await?
+            }
             StopWorker(ref addr, ref detached, ref reply) => {
                 stop_worker::exec(self, addr, *detached, reply).await?
             }
 
             //// ==! Basic processor control
-            StartProcessor(addr, senders, ref reply) => {
-                start_processor::exec(self, addr, senders, reply).await?
+            StartProcessor(addr, senders, owner, ref reply) => {
+                start_processor::exec(self, addr, senders, owner, reply).await?
             }
             StopProcessor(ref addr, ref reply) => stop_processor::exec(self, addr, reply).await?,
 
@@ -249,6 +339,11 @@ impl Router {
                 .await
                 .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
 
+            ListWorkersInfo(sender) => sender
+                .send(RouterReply::workers_info(self.workers_info()))
+                .await
+                .map_err(|_| NodeError::NodeState(NodeReason::Unknown).internal())?,
+
             SetCluster(addr, label, reply) => {
                 debug!("Setting cluster on address {}", addr);
                 let msg = self.map.set_cluster(label, addr);
@@ -286,8 +381,8 @@ impl Router {
             SenderReq(addr, ref reply) => match determine_type(&addr) {
                 RouteType::Internal => utils::resolve(self, addr, reply).await?,
                 // TODO: Remove after other transport implementations are moved to new architecture
-                RouteType::External(tt) => {
-                    let addr = utils::router_addr(self, tt)?;
+                RouteType::External => {
+                    let addr = utils::external_addr(self, addr)?;
                     utils::resolve(self, addr, reply).await?
                 }
             },
@@ -239,6 +239,10 @@ impl InternalMap {
 pub struct AddressMeta {
     pub processor: bool,
     pub detached: bool,
+    /// Type name of the worker which registered the addresses
+    pub owner: &'static str,
+    /// Registration time, in seconds since the Unix epoch
+    pub registered_at: u64,
 }
 
 #[derive(Debug)]
@@ -257,6 +261,10 @@ impl AddressRecord {
         &self.address_set
     }
 
+    pub fn meta(&self) -> &AddressMeta {
+        &self.meta
+    }
+
     pub fn sender(&self) -> MessageSender<RelayMessage> {
         self.sender.clone().expect("No such sender!")
     }
@@ -398,6 +406,8 @@ mod test {
             AddressMeta {
                 processor: false,
                 detached: false,
+                owner: "test",
+                registered_at: 0,
             },
         )
     }
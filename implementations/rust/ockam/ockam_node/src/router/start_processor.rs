@@ -1,4 +1,4 @@
-use super::{AddressMeta, AddressRecord, NodeState, Router, SenderPair};
+use super::{registration_time, AddressMeta, AddressRecord, NodeState, Router, SenderPair};
 use crate::channel_types::SmallSender;
 use crate::{
     error::{NodeError, NodeReason},
@@ -13,10 +13,11 @@ pub(super) async fn exec(
     router: &mut Router,
     addrs: Address,
     senders: SenderPair,
+    owner: &'static str,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => start(router, addrs, senders, reply).await,
+        NodeState::Running => start(router, addrs, senders, owner, reply).await,
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -27,11 +28,14 @@ async fn start(
     router: &mut Router,
     addr: Address,
     senders: SenderPair,
+    owner: &'static str,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
-    router.check_addr_not_exist(&addr, reply).await?;
+    router
+        .check_addrs_not_exist(core::slice::from_ref(&addr), owner, reply)
+        .await?;
 
-    debug!("Starting new processor '{}'", &addr);
+    debug!("Starting new processor '{}' ({})", &addr, owner);
 
     let SenderPair { msgs, ctrl } = senders;
 
@@ -48,6 +52,8 @@ async fn start(
         AddressMeta {
             processor: true,
             detached: false,
+            owner,
+            registered_at: registration_time(),
         },
     );
 
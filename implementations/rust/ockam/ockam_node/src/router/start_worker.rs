@@ -1,4 +1,4 @@
-use super::{AddressMeta, AddressRecord, NodeState, Router, SenderPair};
+use super::{registration_time, AddressMeta, AddressRecord, NodeState, Router, SenderPair};
 use crate::channel_types::SmallSender;
 use crate::{
     error::{NodeError, NodeReason},
@@ -19,10 +19,11 @@ pub(super) async fn exec(
     senders: SenderPair,
     detached: bool,
     metrics: Arc<AtomicUsize>,
+    owner: &'static str,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     match router.state.node_state() {
-        NodeState::Running => start(router, addrs, senders, detached, metrics, reply).await,
+        NodeState::Running => start(router, addrs, senders, detached, metrics, owner, reply).await,
         NodeState::Stopping(_) => reject(reply).await,
         NodeState::Dead => unreachable!(),
     }?;
@@ -35,21 +36,20 @@ async fn start(
     senders: SenderPair,
     detached: bool,
     metrics: Arc<AtomicUsize>,
+    owner: &'static str,
     reply: &SmallSender<NodeReplyResult>,
 ) -> Result<()> {
     let primary_addr = addrs
         .first()
         .ok_or_else(|| NodeError::RouterState(RouterReason::EmptyAddressSet).internal())?;
 
-    router.check_addr_not_exist(primary_addr, reply).await?;
+    router.check_addrs_not_exist(&addrs, owner, reply).await?;
 
-    debug!("Starting new worker '{}'", primary_addr);
+    debug!("Starting new worker '{}' ({})", primary_addr, owner);
 
     let SenderPair { msgs, ctrl } = senders;
 
     // Create an address record and insert it into the internal map
-
-    // FIXME: Check for duplicates
     let address_record = AddressRecord::new(
         addrs.clone(),
         msgs,
@@ -58,6 +58,8 @@ async fn start(
         AddressMeta {
             processor: false,
             detached,
+            owner,
+            registered_at: registration_time(),
         },
     );
 
@@ -4,7 +4,7 @@ use crate::{
     error::{NodeError, NodeReason, WorkerReason},
     NodeReplyResult, RouterReply,
 };
-use ockam_core::{Address, Result, TransportType};
+use ockam_core::{Address, Result};
 
 /// Receive an address and resolve it to a sender
 ///
@@ -50,10 +50,16 @@ pub(super) async fn resolve(
     Ok(())
 }
 
-pub(super) fn router_addr(router: &mut Router, tt: TransportType) -> Result<Address> {
+/// Return the address of the worker handling the messages sent to an external address:
+/// a worker registered with that address, or else the worker of the longest registered prefix
+/// of that address, or else the router of the address type
+pub(super) fn external_addr(router: &Router, addr: Address) -> Result<Address> {
+    if router.map.get_primary_address(&addr).is_some() {
+        return Ok(addr);
+    }
     router
         .external
-        .get(&tt)
+        .resolve(&addr)
         .cloned()
         .ok_or_else(|| NodeError::NodeState(NodeReason::Unknown).internal())
 }
@@ -0,0 +1,350 @@
+use std::path::Path;
+
+use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::{query, query_as, query_scalar, Connection, FromRow, SqliteConnection, SqlitePool};
+
+use ockam_core::compat::rand::random_string;
+use ockam_core::compat::time::now;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+use crate::database::{FromSqlxError, MigrationSet, SqlxDatabase, ToSqlxType, ToVoid};
+
+/// Name of the table storing the manifest of a snapshot.
+/// Tables starting with `_` are internal tables which are never restored.
+const MANIFEST_TABLE: &str = "_backup_manifest";
+
+/// Description of a database snapshot, stored in the snapshot file itself
+#[derive(Debug, Clone, PartialEq, Eq, FromRow)]
+pub struct BackupManifest {
+    /// Version of the latest sql migration applied to the database when the snapshot was taken
+    pub schema_version: i64,
+    /// Version of the crate which took the snapshot
+    pub crate_version: String,
+    /// Time of the snapshot, in seconds since the Unix epoch
+    pub created_at: i64,
+    /// Name of the node using the database, if any
+    pub node_name: Option<String>,
+}
+
+/// These functions create a snapshot of a database in a single file, and restore
+/// the content of a database from such a snapshot.
+impl SqlxDatabase {
+    /// Return the version of the latest sql migration applied to this database
+    pub async fn schema_version(&self) -> Result<Option<i64>> {
+        query_scalar::<_, Option<i64>>(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1",
+        )
+        .fetch_one(&*self.pool)
+        .await
+        .into_core()
+    }
+
+    /// Write a snapshot of this database to a new file, along with its manifest.
+    ///
+    /// The snapshot is taken with `VACUUM INTO` which runs in a read transaction
+    /// and can then be safely executed while the database is being used.
+    pub async fn backup_to(&self, path: impl AsRef<Path>) -> Result<BackupManifest> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::AlreadyExists,
+                format!("the backup file {path:?} already exists"),
+            ));
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| Error::new(Origin::Node, Kind::Io, e.to_string()))?;
+        }
+
+        let manifest = BackupManifest {
+            schema_version: self.schema_version().await?.unwrap_or_default(),
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: now()? as i64,
+            node_name: self.node_name.clone(),
+        };
+
+        // the snapshot is only moved to its final path once it is complete
+        let temporary_path = path.with_extension("tmp");
+        let _ = std::fs::remove_file(&temporary_path);
+        query("VACUUM INTO ?")
+            .bind(temporary_path.to_string_lossy().to_string().to_sql())
+            .execute(&*self.pool)
+            .await
+            .void()?;
+
+        let snapshot = open_snapshot(&temporary_path, false).await?;
+        let result = write_manifest(&snapshot, &manifest).await;
+        snapshot.close().await;
+        result?;
+
+        std::fs::rename(&temporary_path, path)
+            .map_err(|e| Error::new(Origin::Node, Kind::Io, e.to_string()))?;
+        Ok(manifest)
+    }
+
+    /// Read the manifest of a snapshot file
+    pub async fn read_backup_manifest(path: impl AsRef<Path>) -> Result<BackupManifest> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::NotFound,
+                format!("the backup file {path:?} does not exist"),
+            ));
+        }
+        let snapshot = open_snapshot(path, true).await?;
+        let manifest = query_as::<_, BackupManifest>(&format!(
+            "SELECT schema_version, crate_version, created_at, node_name FROM {MANIFEST_TABLE}"
+        ))
+        .fetch_optional(&snapshot)
+        .await;
+        snapshot.close().await;
+
+        manifest.ok().flatten().ok_or_else(|| {
+            Error::new(
+                Origin::Node,
+                Kind::Invalid,
+                format!("the file {path:?} is not a valid backup file"),
+            )
+        })
+    }
+
+    /// Replace the content of this database with the content of a snapshot.
+    ///
+    /// A snapshot created with an older schema is migrated before being restored.
+    /// A snapshot created with a newer schema than the one supported by the migration set is rejected.
+    pub async fn restore_from(
+        &self,
+        path: impl AsRef<Path>,
+        migration_set: impl MigrationSet,
+    ) -> Result<BackupManifest> {
+        let path = path.as_ref();
+        let manifest = Self::read_backup_manifest(path).await?;
+        let migrator = migration_set.create_migrator()?;
+        let supported_version = migrator.latest_schema_version().unwrap_or_default();
+        if manifest.schema_version > supported_version {
+            return Err(Error::new(
+                Origin::Node,
+                Kind::Unsupported,
+                format!(
+                    "the backup file {path:?} has the schema version {}, which is newer than the latest supported version {supported_version}. Please upgrade to version {} or later to restore it",
+                    manifest.schema_version, manifest.crate_version
+                ),
+            ));
+        }
+
+        // the snapshot file is left untouched, a copy of it is migrated then restored
+        let copy = std::env::temp_dir().join(format!("ockam-restore-{}.sqlite3", random_string()));
+        std::fs::copy(path, &copy)
+            .map_err(|e| Error::new(Origin::Node, Kind::Io, e.to_string()))?;
+        let result = async {
+            let snapshot = open_snapshot(&copy, false).await?;
+            let migrated = migrator.migrate(&snapshot).await;
+            snapshot.close().await;
+            migrated?;
+            self.copy_tables_from(&copy).await
+        }
+        .await;
+        let _ = std::fs::remove_file(&copy);
+        result?;
+
+        Ok(manifest)
+    }
+
+    /// Replace the content of all the tables of this database with the content of the
+    /// same tables in another database file, in a single transaction
+    async fn copy_tables_from(&self, path: &Path) -> Result<()> {
+        // attached databases are only visible to the connection which attached them
+        let mut connection = self.pool.acquire().await.into_core()?;
+        query("ATTACH DATABASE ? AS snapshot")
+            .bind(path.to_string_lossy().to_string().to_sql())
+            .execute(&mut *connection)
+            .await
+            .void()?;
+        let result = copy_snapshot_tables(&mut connection).await;
+        query("DETACH DATABASE snapshot")
+            .execute(&mut *connection)
+            .await
+            .void()?;
+        result
+    }
+}
+
+async fn copy_snapshot_tables(connection: &mut SqliteConnection) -> Result<()> {
+    let mut transaction = connection.begin().await.into_core()?;
+    // rows are deleted and inserted in no particular order
+    query("PRAGMA defer_foreign_keys = ON")
+        .execute(&mut *transaction)
+        .await
+        .void()?;
+
+    let tables = user_tables(&mut transaction, "main").await?;
+    let snapshot_tables = user_tables(&mut transaction, "snapshot").await?;
+    for table in tables.iter() {
+        let sql = format!("DELETE FROM main.\"{table}\"");
+        query(&sql).execute(&mut *transaction).await.void()?;
+    }
+    for table in snapshot_tables.iter().filter(|t| tables.contains(t)) {
+        let columns: Vec<String> =
+            query_scalar("SELECT name FROM pragma_table_info(?, 'snapshot')")
+                .bind(table.to_sql())
+                .fetch_all(&mut *transaction)
+                .await
+                .into_core()?;
+        let columns = columns
+            .iter()
+            .map(|c| format!("\"{c}\""))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "INSERT INTO main.\"{table}\" ({columns}) SELECT {columns} FROM snapshot.\"{table}\""
+        );
+        query(&sql).execute(&mut *transaction).await.void()?;
+    }
+    transaction.commit().await.void()
+}
+
+/// Return the tables of a given schema, excluding the Sqlite tables and the internal tables
+/// like the migrations tables
+async fn user_tables(connection: &mut SqliteConnection, schema: &str) -> Result<Vec<String>> {
+    let sql = format!(
+        "SELECT name FROM {schema}.sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE '\\_%' ESCAPE '\\' ORDER BY name"
+    );
+    query_scalar(&sql)
+        .fetch_all(&mut *connection)
+        .await
+        .into_core()
+}
+
+async fn open_snapshot(path: &Path, read_only: bool) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(false)
+        .read_only(read_only);
+    SqlitePool::connect_with(options)
+        .await
+        .map_err(SqlxDatabase::map_sql_err)
+}
+
+async fn write_manifest(snapshot: &SqlitePool, manifest: &BackupManifest) -> Result<()> {
+    let sql = format!(
+        "CREATE TABLE {MANIFEST_TABLE} (schema_version INTEGER NOT NULL, crate_version TEXT NOT NULL, created_at INTEGER NOT NULL, node_name TEXT)"
+    );
+    query(&sql).execute(snapshot).await.void()?;
+    let sql = format!("INSERT INTO {MANIFEST_TABLE} VALUES (?, ?, ?, ?)");
+    query(&sql)
+        .bind(manifest.schema_version.to_sql())
+        .bind(manifest.crate_version.to_sql())
+        .bind(manifest.created_at.to_sql())
+        .bind(manifest.node_name.as_ref().map(|n| n.to_sql()))
+        .execute(snapshot)
+        .await
+        .void()
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::{NamedTempFile, TempDir};
+
+    use crate::database::NodeMigrationSet;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_backup_and_restore() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create_with_node_name(db_file.path(), "node").await?;
+        insert_identity(&db, "I1").await?;
+
+        let backup_dir = TempDir::new().unwrap();
+        let backup_path = backup_dir.path().join("backup.sqlite3");
+        let manifest = db.backup_to(&backup_path).await?;
+        assert_eq!(manifest.node_name, Some("node".to_string()));
+        assert_eq!(manifest.schema_version, db.schema_version().await?.unwrap());
+        assert_eq!(
+            SqlxDatabase::read_backup_manifest(&backup_path).await?,
+            manifest
+        );
+
+        // the same file can't be overwritten
+        assert!(db.backup_to(&backup_path).await.is_err());
+
+        // mutate the database
+        insert_identity(&db, "I2").await?;
+        sqlx::query("DELETE FROM identity WHERE identifier = 'I1'")
+            .execute(&*db.pool)
+            .await
+            .void()?;
+        assert_eq!(get_identifiers(&db).await?, vec!["I2".to_string()]);
+
+        // the state before the mutation is restored
+        db.restore_from(&backup_path, NodeMigrationSet).await?;
+        assert_eq!(get_identifiers(&db).await?, vec!["I1".to_string()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_newer_schema_is_refused() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create(db_file.path()).await?;
+        insert_identity(&db, "I1").await?;
+
+        let backup_dir = TempDir::new().unwrap();
+        let backup_path = backup_dir.path().join("backup.sqlite3");
+        db.backup_to(&backup_path).await?;
+
+        // simulate a snapshot created by a more recent binary
+        let snapshot = open_snapshot(&backup_path, false).await?;
+        query(&format!(
+            "UPDATE {MANIFEST_TABLE} SET schema_version = schema_version + 1"
+        ))
+        .execute(&snapshot)
+        .await
+        .void()?;
+        snapshot.close().await;
+
+        insert_identity(&db, "I2").await?;
+        let result = db.restore_from(&backup_path, NodeMigrationSet).await;
+        assert!(result.is_err());
+
+        // the database is left untouched
+        assert_eq!(
+            get_identifiers(&db).await?,
+            vec!["I1".to_string(), "I2".to_string()]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_restore_invalid_file_is_refused() -> Result<()> {
+        let db = SqlxDatabase::in_memory("backup").await?;
+        let not_a_backup = NamedTempFile::new().unwrap();
+        std::fs::write(not_a_backup.path(), "not a database").unwrap();
+
+        let result = db.restore_from(not_a_backup.path(), NodeMigrationSet).await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn insert_identity(db: &SqlxDatabase, identifier: &str) -> Result<()> {
+        query("INSERT INTO identity VALUES (?, ?)")
+            .bind(identifier.to_sql())
+            .bind("change history".to_sql())
+            .execute(&*db.pool)
+            .await
+            .void()
+    }
+
+    async fn get_identifiers(db: &SqlxDatabase) -> Result<Vec<String>> {
+        // the identity table also contains the identity of the orchestrator
+        query_scalar(
+            "SELECT identifier FROM identity WHERE identifier IN ('I1', 'I2') ORDER BY identifier",
+        )
+        .fetch_all(&*db.pool)
+        .await
+        .into_core()
+    }
+}
@@ -0,0 +1,172 @@
+use core::str::FromStr;
+use core::time::Duration;
+use std::path::Path;
+
+use ockam_core::env::get_env;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+use sqlx::pool::PoolOptions;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+use sqlx::{ConnectOptions, Sqlite};
+use tracing::log::LevelFilter;
+
+/// Environment variable overriding the maximum number of connections of a database pool
+pub const OCKAM_DATABASE_MAX_CONNECTIONS: &str = "OCKAM_DATABASE_MAX_CONNECTIONS";
+/// Environment variable overriding the maximum time spent waiting for a connection of the pool
+pub const OCKAM_DATABASE_ACQUIRE_TIMEOUT: &str = "OCKAM_DATABASE_ACQUIRE_TIMEOUT";
+/// Environment variable overriding the maximum time spent waiting for a locked database
+pub const OCKAM_DATABASE_BUSY_TIMEOUT: &str = "OCKAM_DATABASE_BUSY_TIMEOUT";
+/// Environment variable overriding the journal mode: wal, delete, truncate, persist, memory or off
+pub const OCKAM_DATABASE_JOURNAL_MODE: &str = "OCKAM_DATABASE_JOURNAL_MODE";
+/// Environment variable overriding the synchronous level: off, normal, full or extra
+pub const OCKAM_DATABASE_SYNCHRONOUS: &str = "OCKAM_DATABASE_SYNCHRONOUS";
+
+/// Configuration of the pool of connections used to access a database persisted on disk.
+///
+/// By default the database uses the WAL journal mode, so that readers don't block writers,
+/// and waits for a while when the database is locked by another writer instead of failing
+/// with a `database is locked` error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatabaseConfiguration {
+    /// Maximum number of connections in the pool
+    pub max_connections: u32,
+    /// Maximum time spent waiting for a connection to be available in the pool
+    pub acquire_timeout: Duration,
+    /// Maximum time spent waiting for a lock on the database before returning an error
+    pub busy_timeout: Duration,
+    /// Journal mode of the database
+    pub journal_mode: SqliteJournalMode,
+    /// Level of synchronization of the database file with the disk
+    pub synchronous: SqliteSynchronous,
+}
+
+impl Default for DatabaseConfiguration {
+    fn default() -> Self {
+        Self {
+            max_connections: 10,
+            acquire_timeout: Duration::from_secs(30),
+            busy_timeout: Duration::from_secs(10),
+            journal_mode: SqliteJournalMode::Wal,
+            // NORMAL is safe from corruption in WAL mode and avoids a sync on every commit
+            synchronous: SqliteSynchronous::Normal,
+        }
+    }
+}
+
+impl DatabaseConfiguration {
+    /// Create the default configuration, overridden by the OCKAM_DATABASE_* environment variables
+    pub fn from_env() -> Result<Self> {
+        let mut configuration = Self::default();
+        if let Some(max_connections) = get_env::<u32>(OCKAM_DATABASE_MAX_CONNECTIONS)? {
+            configuration.max_connections = max_connections;
+        }
+        if let Some(acquire_timeout) = get_env::<Duration>(OCKAM_DATABASE_ACQUIRE_TIMEOUT)? {
+            configuration.acquire_timeout = acquire_timeout;
+        }
+        if let Some(busy_timeout) = get_env::<Duration>(OCKAM_DATABASE_BUSY_TIMEOUT)? {
+            configuration.busy_timeout = busy_timeout;
+        }
+        if let Some(journal_mode) = get_env::<String>(OCKAM_DATABASE_JOURNAL_MODE)? {
+            configuration.journal_mode = SqliteJournalMode::from_str(&journal_mode)
+                .map_err(|e| invalid_variable(OCKAM_DATABASE_JOURNAL_MODE, e))?;
+        }
+        if let Some(synchronous) = get_env::<String>(OCKAM_DATABASE_SYNCHRONOUS)? {
+            configuration.synchronous = SqliteSynchronous::from_str(&synchronous)
+                .map_err(|e| invalid_variable(OCKAM_DATABASE_SYNCHRONOUS, e))?;
+        }
+        Ok(configuration)
+    }
+
+    /// Set the maximum number of connections in the pool
+    pub fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Set the maximum time spent waiting for a connection to be available in the pool
+    pub fn with_acquire_timeout(mut self, acquire_timeout: Duration) -> Self {
+        self.acquire_timeout = acquire_timeout;
+        self
+    }
+
+    /// Set the maximum time spent waiting for a lock on the database
+    pub fn with_busy_timeout(mut self, busy_timeout: Duration) -> Self {
+        self.busy_timeout = busy_timeout;
+        self
+    }
+
+    /// Set the journal mode of the database
+    pub fn with_journal_mode(mut self, journal_mode: SqliteJournalMode) -> Self {
+        self.journal_mode = journal_mode;
+        self
+    }
+
+    /// Set the level of synchronization of the database file with the disk
+    pub fn with_synchronous(mut self, synchronous: SqliteSynchronous) -> Self {
+        self.synchronous = synchronous;
+        self
+    }
+
+    /// Options used to open a connection to a database file
+    pub(crate) fn connect_options(&self, path: &Path) -> SqliteConnectOptions {
+        SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true)
+            .journal_mode(self.journal_mode)
+            .busy_timeout(self.busy_timeout)
+            .synchronous(self.synchronous)
+            .log_statements(LevelFilter::Debug)
+    }
+
+    /// Options used to create the pool of connections
+    pub(crate) fn pool_options(&self) -> PoolOptions<Sqlite> {
+        PoolOptions::new()
+            .max_connections(self.max_connections)
+            .acquire_timeout(self.acquire_timeout)
+    }
+}
+
+fn invalid_variable(variable: &str, error: sqlx::Error) -> Error {
+    Error::new(
+        Origin::Node,
+        Kind::Invalid,
+        format!("invalid value for {variable}: {error}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_configuration_from_env() -> Result<()> {
+        std::env::set_var(OCKAM_DATABASE_MAX_CONNECTIONS, "3");
+        std::env::set_var(OCKAM_DATABASE_BUSY_TIMEOUT, "2s");
+        std::env::set_var(OCKAM_DATABASE_JOURNAL_MODE, "delete");
+        std::env::set_var(OCKAM_DATABASE_SYNCHRONOUS, "full");
+        let configuration = DatabaseConfiguration::from_env();
+
+        std::env::set_var(OCKAM_DATABASE_SYNCHRONOUS, "sometimes");
+        let invalid = DatabaseConfiguration::from_env();
+
+        for variable in [
+            OCKAM_DATABASE_MAX_CONNECTIONS,
+            OCKAM_DATABASE_BUSY_TIMEOUT,
+            OCKAM_DATABASE_JOURNAL_MODE,
+            OCKAM_DATABASE_SYNCHRONOUS,
+        ] {
+            std::env::remove_var(variable);
+        }
+
+        assert_eq!(
+            configuration?,
+            DatabaseConfiguration::default()
+                .with_max_connections(3)
+                .with_busy_timeout(Duration::from_secs(2))
+                .with_journal_mode(SqliteJournalMode::Delete)
+                .with_synchronous(SqliteSynchronous::Full)
+        );
+        assert!(invalid.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use core::fmt::{Display, Formatter};
+
+use serde::Serialize;
+use sqlx::{query, query_scalar};
+
+use ockam_core::Result;
+
+use crate::database::{FromSqlxError, MigrationSet, SqlxDatabase, ToSqlxType, ToVoid};
+
+/// Name of the check run by Sqlite on the database file
+const INTEGRITY_CHECK: &str = "integrity";
+
+/// Name of the check of the references between tables
+const REFERENCES_CHECK: &str = "references";
+
+/// Columns referencing an identity, stored in the `identity` table.
+/// The rows of these tables are useless once the identity they reference is gone.
+const IDENTITY_REFERENCES: [(&str, &str); 5] = [
+    ("named_identity", "identifier"),
+    ("identity_attributes", "identifier"),
+    ("credential", "subject_identifier"),
+    ("purpose_key", "identifier"),
+    ("identity_enrollment_attribute", "identifier"),
+];
+
+/// Severity of an issue found when checking a database
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// The database is consistent but could be cleaned up
+    Warning,
+    /// The database is corrupted, or can not be used safely by this version
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// Issue found when checking a database
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DatabaseFinding {
+    /// Severity of the issue
+    pub severity: Severity,
+    /// Name of the check which found the issue
+    pub check: String,
+    /// Description of the issue
+    pub message: String,
+}
+
+impl DatabaseFinding {
+    /// Create a finding for an issue which makes the database unusable
+    pub fn error(check: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            check: check.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Create a finding for an issue which does not prevent the database from being used
+    pub fn warning(check: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            check: check.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Result of [`SqlxDatabase::check_integrity`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DatabaseCheckReport {
+    /// Issues found in the database, the most severe first
+    pub findings: Vec<DatabaseFinding>,
+    /// Number of rows referencing an identity which does not exist
+    pub orphaned_rows: u64,
+}
+
+impl DatabaseCheckReport {
+    /// Return true if at least one issue makes the database unusable
+    pub fn is_corrupted(&self) -> bool {
+        self.findings.iter().any(|f| f.severity == Severity::Error)
+    }
+}
+
+/// These functions check that a database file is not corrupted, that its schema matches the
+/// migrations known by this binary, and that its content is consistent. They also offer
+/// some repairs which can be applied without losing useful data.
+impl SqlxDatabase {
+    /// Check the database and return the issues which have been found.
+    ///
+    /// If `quick` is true, Sqlite runs a `quick_check` instead of a full `integrity_check`.
+    /// The quick check does not verify that the indexes match the content of the tables.
+    pub async fn check_integrity(
+        &self,
+        migration_set: impl MigrationSet,
+        quick: bool,
+    ) -> Result<DatabaseCheckReport> {
+        let mut report = DatabaseCheckReport::default();
+
+        let pragma = if quick {
+            "PRAGMA quick_check"
+        } else {
+            "PRAGMA integrity_check"
+        };
+        match query_scalar::<_, String>(pragma)
+            .fetch_all(&*self.pool)
+            .await
+        {
+            Ok(lines) => report.findings.extend(
+                lines
+                    .into_iter()
+                    .filter(|l| l != "ok")
+                    .map(|l| DatabaseFinding::error(INTEGRITY_CHECK, l)),
+            ),
+            // a badly damaged file can not even be checked
+            Err(e) => report.findings.push(DatabaseFinding::error(
+                INTEGRITY_CHECK,
+                format!("the database file can not be checked: {e}"),
+            )),
+        }
+        // the other checks need to read the database
+        if report.is_corrupted() {
+            return Ok(report);
+        }
+
+        report.findings.extend(
+            migration_set
+                .create_migrator()?
+                .check_applied_migrations(&self.pool)
+                .await?,
+        );
+
+        for (table, column) in IDENTITY_REFERENCES {
+            if !self.has_table(table).await? {
+                continue;
+            }
+            let count: i64 = query_scalar(&format!(
+                "SELECT COUNT(*) FROM {table} WHERE {column} NOT IN (SELECT identifier FROM identity)"
+            ))
+            .fetch_one(&*self.pool)
+            .await
+            .into_core()?;
+            if count > 0 {
+                report.orphaned_rows += count as u64;
+                report.findings.push(DatabaseFinding::warning(
+                    REFERENCES_CHECK,
+                    format!("{count} row(s) of the table {table} reference an identity which does not exist"),
+                ));
+            }
+        }
+
+        report
+            .findings
+            .sort_by(|f1, f2| f2.severity.cmp(&f1.severity));
+        Ok(report)
+    }
+
+    /// Rebuild the indexes of the database and compact its file.
+    /// This repairs indexes which do not match the content of their table.
+    pub async fn reindex_and_vacuum(&self) -> Result<()> {
+        query("REINDEX").execute(&*self.pool).await.void()?;
+        query("VACUUM").execute(&*self.pool).await.void()
+    }
+
+    /// Delete the rows referencing an identity which does not exist and return their number
+    pub async fn delete_orphaned_rows(&self) -> Result<u64> {
+        let mut deleted = 0;
+        for (table, column) in IDENTITY_REFERENCES {
+            if !self.has_table(table).await? {
+                continue;
+            }
+            deleted += query(&format!(
+                "DELETE FROM {table} WHERE {column} NOT IN (SELECT identifier FROM identity)"
+            ))
+            .execute(&*self.pool)
+            .await
+            .into_core()?
+            .rows_affected();
+        }
+        Ok(deleted)
+    }
+
+    async fn has_table(&self, table: &str) -> Result<bool> {
+        let count: i64 =
+            query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?")
+                .bind(table.to_sql())
+                .fetch_one(&*self.pool)
+                .await
+                .into_core()?;
+        Ok(count > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tempfile::NamedTempFile;
+
+    use crate::database::NodeMigrationSet;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_check_healthy_database() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create(db_file.path()).await?;
+        insert_identity(&db, "I1").await?;
+        insert_attributes(&db, "I1").await?;
+
+        let report = db.check_integrity(NodeMigrationSet, false).await?;
+        assert_eq!(report, DatabaseCheckReport::default());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_orphaned_rows() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create(db_file.path()).await?;
+        insert_identity(&db, "I1").await?;
+        insert_attributes(&db, "I1").await?;
+        insert_attributes(&db, "I2").await?;
+
+        let report = db.check_integrity(NodeMigrationSet, true).await?;
+        assert!(!report.is_corrupted());
+        assert_eq!(report.orphaned_rows, 1);
+        assert_eq!(report.findings.len(), 1);
+        assert_eq!(report.findings[0].severity, Severity::Warning);
+        assert_eq!(report.findings[0].check, REFERENCES_CHECK);
+
+        assert_eq!(db.delete_orphaned_rows().await?, 1);
+        let report = db.check_integrity(NodeMigrationSet, true).await?;
+        assert_eq!(report, DatabaseCheckReport::default());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_inconsistent_migrations() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create(db_file.path()).await?;
+
+        // a migration applied by a more recent binary
+        query("INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time) VALUES (99990101100000, 'future', 1, x'00', 0)")
+            .execute(&*db.pool)
+            .await
+            .void()?;
+        // a skipped migration
+        query("DELETE FROM _sqlx_migrations WHERE version = 20240111100003")
+            .execute(&*db.pool)
+            .await
+            .void()?;
+
+        let report = db.check_integrity(NodeMigrationSet, true).await?;
+        assert!(report.is_corrupted());
+        let messages: Vec<&str> = report.findings.iter().map(|f| f.message.as_str()).collect();
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.contains("99990101100000") && m.contains("unknown")),
+            "{messages:?}"
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|m| m.contains("20240111100003") && m.contains("skipped")),
+            "{messages:?}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_check_corrupted_index() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+        let db = SqlxDatabase::create(db_file.path()).await?;
+        insert_identity(&db, "I1").await?;
+        insert_attributes(&db, "I1").await?;
+        db.pool.close().await;
+
+        corrupt_index(db_file.path()).await?;
+
+        let db = SqlxDatabase::create_no_migration(db_file.path()).await?;
+        let report = db.check_integrity(NodeMigrationSet, false).await?;
+        assert!(report.is_corrupted(), "{report:?}");
+        assert_eq!(report.findings[0].check, INTEGRITY_CHECK);
+
+        // rebuilding the index repairs the database
+        db.reindex_and_vacuum().await?;
+        let report = db.check_integrity(NodeMigrationSet, false).await?;
+        assert!(!report.is_corrupted(), "{report:?}");
+        Ok(())
+    }
+
+    /// HELPERS
+    async fn insert_identity(db: &SqlxDatabase, identifier: &str) -> Result<()> {
+        query("INSERT INTO identity VALUES (?, ?)")
+            .bind(identifier.to_sql())
+            .bind("change history".to_sql())
+            .execute(&*db.pool)
+            .await
+            .void()
+    }
+
+    async fn insert_attributes(db: &SqlxDatabase, identifier: &str) -> Result<()> {
+        query("INSERT INTO identity_attributes (identifier, attributes, added, node_name) VALUES (?, ?, ?, ?)")
+            .bind(identifier.to_sql())
+            .bind(vec![1u8].to_sql())
+            .bind(0u64.to_sql())
+            .bind("node".to_sql())
+            .execute(&*db.pool)
+            .await
+            .void()
+    }
+
+    /// Change the definition of the index of the identity_attributes table without rebuilding it,
+    /// so that its entries do not match the rows of the table anymore
+    async fn corrupt_index(path: &Path) -> Result<()> {
+        let db = SqlxDatabase::create_no_migration(path).await?;
+        let mut connection = db.pool.acquire().await.into_core()?;
+        query("PRAGMA writable_schema = ON")
+            .execute(&mut *connection)
+            .await
+            .void()?;
+        query("UPDATE sqlite_master SET sql = 'CREATE UNIQUE INDEX identity_attributes_index ON identity_attributes (node_name, identifier)' WHERE name = 'identity_attributes_index'")
+            .execute(&mut *connection)
+            .await
+            .void()?;
+        query("PRAGMA writable_schema = OFF")
+            .execute(&mut *connection)
+            .await
+            .void()?;
+        drop(connection);
+        db.pool.close().await;
+        Ok(())
+    }
+}
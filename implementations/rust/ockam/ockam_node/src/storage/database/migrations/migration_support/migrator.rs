@@ -3,12 +3,12 @@ use ockam_core::compat::time::now;
 use ockam_core::errcode::{Kind, Origin};
 use sqlx::migrate::{AppliedMigration, Migrate, Migration as SqlxMigration};
 use sqlx::sqlite::SqliteRow;
-use sqlx::{query, Row, SqliteConnection, SqlitePool};
+use sqlx::{query, query_scalar, Row, SqliteConnection, SqlitePool};
 use std::cmp::Ordering;
 use time::OffsetDateTime;
 
 use crate::database::migrations::migration_support::rust_migration::RustMigration;
-use crate::database::{FromSqlxError, ToSqlxType, ToVoid};
+use crate::database::{DatabaseFinding, FromSqlxError, ToSqlxType, ToVoid};
 use ockam_core::Result;
 
 /// Migrator is responsible for running Sql and Rust migrations side by side in the correct order,
@@ -187,6 +187,219 @@ impl Migrator {
     pub async fn migrate(&self, pool: &SqlitePool) -> Result<()> {
         self.migrate_up_to(pool, i64::MAX).await
     }
+
+    /// Return the version of the latest sql migration known by this migrator.
+    /// This is the most recent schema version which can be handled by this binary
+    pub fn latest_schema_version(&self) -> Option<Version> {
+        self.sql_migrator
+            .migrations
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .map(|m| m.version)
+            .max()
+    }
+
+    /// Return the current schema version of a database and the migrations
+    /// which have not been applied to it yet
+    pub async fn status(&self, pool: &SqlitePool) -> Result<MigrationStatus> {
+        let mut connection = pool.acquire().await.into_core()?;
+        connection.ensure_migrations_table().await.into_core()?;
+        let applied_migrations = connection.list_applied_migrations().await.into_core()?;
+
+        let mut current_version = None;
+        let mut pending = vec![];
+        for migration in self.sql_migrator.migrations.iter() {
+            if migration.migration_type.is_down_migration() {
+                continue;
+            }
+            if applied_migrations
+                .iter()
+                .any(|m| m.version == migration.version)
+            {
+                current_version = current_version.max(Some(migration.version));
+            } else {
+                pending.push((migration.version, migration.description.replace(' ', "_")));
+            }
+        }
+        // The table tracking rust migrations is itself created by an sql migration
+        let rust_migrations_table: Option<SqliteRow> =
+            query("SELECT name FROM sqlite_master WHERE type='table' AND name='_rust_migrations'")
+                .fetch_optional(&mut *connection)
+                .await
+                .into_core()?;
+        for migration in self.rust_migrations.iter() {
+            if rust_migrations_table.is_some()
+                && Self::has_migrated(&mut connection, migration.name()).await?
+            {
+                current_version = current_version.max(Some(migration.version()));
+            } else {
+                pending.push((migration.version(), migration.name().to_string()));
+            }
+        }
+        pending.sort();
+
+        Ok(MigrationStatus {
+            current_version,
+            pending_migrations: pending
+                .into_iter()
+                .map(|(version, name)| format!("{version}_{name}"))
+                .collect(),
+        })
+    }
+}
+
+impl Migrator {
+    /// Check that the migrations recorded in a database are consistent with the migrations
+    /// known by this migrator:
+    ///
+    ///  - no migration failed
+    ///  - no migration unknown to this migrator was applied
+    ///  - no migration was skipped: all the migrations older than the latest applied one are applied
+    ///
+    /// Unlike [`Migrator::status`], this function does not modify the database.
+    pub async fn check_applied_migrations(
+        &self,
+        pool: &SqlitePool,
+    ) -> Result<Vec<DatabaseFinding>> {
+        let mut connection = pool.acquire().await.into_core()?;
+        let mut findings = vec![];
+
+        if !Self::has_table(&mut connection, "_sqlx_migrations").await? {
+            findings.push(DatabaseFinding::error(
+                MIGRATIONS_CHECK,
+                "the table listing the applied migrations is missing",
+            ));
+            return Ok(findings);
+        }
+
+        if let Some(version) = connection.dirty_version().await.into_core()? {
+            findings.push(DatabaseFinding::error(
+                MIGRATIONS_CHECK,
+                format!("the migration {version} failed and left the database in a partial state"),
+            ));
+        }
+
+        let applied_migrations = connection.list_applied_migrations().await.into_core()?;
+        let known_migrations: Vec<&SqlxMigration> = self
+            .sql_migrator
+            .migrations
+            .iter()
+            .filter(|m| !m.migration_type.is_down_migration())
+            .collect();
+
+        for applied_migration in applied_migrations.iter() {
+            match known_migrations
+                .iter()
+                .find(|m| m.version == applied_migration.version)
+            {
+                Some(migration) if migration.checksum != applied_migration.checksum => {
+                    findings.push(DatabaseFinding::warning(
+                        MIGRATIONS_CHECK,
+                        format!(
+                            "the migration {} was applied with a different content than the one of this version",
+                            applied_migration.version
+                        ),
+                    ))
+                }
+                Some(_) => (),
+                None if RENAMED_MIGRATIONS.contains(&applied_migration.version) => (),
+                None => findings.push(DatabaseFinding::error(
+                    MIGRATIONS_CHECK,
+                    format!(
+                        "the migration {} is unknown. The database might have been migrated by a more recent version",
+                        applied_migration.version
+                    ),
+                )),
+            }
+        }
+
+        let applied_rust_migrations: Vec<String> =
+            if Self::has_table(&mut connection, "_rust_migrations").await? {
+                query_scalar("SELECT name FROM _rust_migrations")
+                    .fetch_all(&mut *connection)
+                    .await
+                    .into_core()?
+            } else {
+                vec![]
+            };
+        for name in applied_rust_migrations.iter() {
+            if !self.rust_migrations.iter().any(|m| m.name() == name) {
+                findings.push(DatabaseFinding::error(
+                    MIGRATIONS_CHECK,
+                    format!("the data migration {name} is unknown. The database might have been migrated by a more recent version"),
+                ));
+            }
+        }
+
+        // all the migrations older than the latest applied one must have been applied
+        let latest_applied_version = applied_migrations
+            .iter()
+            .map(|m| m.version)
+            .filter(|v| known_migrations.iter().any(|m| m.version == *v))
+            .max();
+        if let Some(latest_applied_version) = latest_applied_version {
+            for migration in known_migrations.iter() {
+                if migration.version < latest_applied_version
+                    && !applied_migrations
+                        .iter()
+                        .any(|m| m.version == migration.version)
+                {
+                    findings.push(DatabaseFinding::error(
+                        MIGRATIONS_CHECK,
+                        format!(
+                            "the migration {}_{} was skipped",
+                            migration.version,
+                            migration.description.replace(' ', "_")
+                        ),
+                    ));
+                }
+            }
+            for migration in self.rust_migrations.iter() {
+                if migration.version() < latest_applied_version
+                    && !applied_rust_migrations
+                        .iter()
+                        .any(|name| name == migration.name())
+                {
+                    findings.push(DatabaseFinding::error(
+                        MIGRATIONS_CHECK,
+                        format!(
+                            "the data migration {}_{} was skipped",
+                            migration.version(),
+                            migration.name()
+                        ),
+                    ));
+                }
+            }
+        }
+
+        Ok(findings)
+    }
+
+    async fn has_table(connection: &mut SqliteConnection, table: &str) -> Result<bool> {
+        let table: Option<SqliteRow> =
+            query("SELECT name FROM sqlite_master WHERE type='table' AND name=?")
+                .bind(table.to_sql())
+                .fetch_optional(&mut *connection)
+                .await
+                .into_core()?;
+        Ok(table.is_some())
+    }
+}
+
+/// Name of the check of the applied migrations, used in the [`DatabaseFinding`]s
+const MIGRATIONS_CHECK: &str = "migrations";
+
+/// Versions of migrations which were applied with a version which was later changed.
+/// 20240111100000_add_rust_migrations.sql was renamed to 20231230100000_add_rust_migrations.sql
+const RENAMED_MIGRATIONS: [Version; 1] = [20240111100000];
+
+/// Migration state of a database, as returned by [`Migrator::status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationStatus {
+    /// Version of the latest applied migration, if any
+    pub current_version: Option<i64>,
+    /// Migrations which are not applied yet, sorted by version
+    pub pending_migrations: Vec<String>,
 }
 
 #[cfg(test)]
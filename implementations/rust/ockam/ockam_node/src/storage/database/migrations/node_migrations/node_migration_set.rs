@@ -48,4 +48,29 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_status() -> Result<()> {
+        let db_file = NamedTempFile::new().unwrap();
+
+        let db = SqlxDatabase::create_no_migration(db_file.path()).await?;
+        let migrator = NodeMigrationSet.create_migrator()?;
+
+        let status = migrator.status(&db.pool).await?;
+        assert_eq!(status.current_version, None);
+        assert!(status
+            .pending_migrations
+            .contains(&"20231006100000_create_database".to_string()));
+        assert!(status
+            .pending_migrations
+            .contains(&"20240212100000_migration_20240212100000_migrate_policies".to_string()));
+
+        migrator.migrate(&db.pool).await?;
+
+        let status = migrator.status(&db.pool).await?;
+        assert!(status.current_version.is_some());
+        assert!(status.pending_migrations.is_empty());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,13 @@
+-- Events produced by an authority node when a credential is issued, an enrollment token is redeemed
+-- or a member is added / deleted. Only the most recent events are kept in this table.
+CREATE TABLE authority_events
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    kind       TEXT    NOT NULL,
+    subject    TEXT    NOT NULL,
+    actor      TEXT,
+    reference  TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX authority_events_created_at_index ON authority_events(created_at);
@@ -0,0 +1,3 @@
+-- Ephemeral nodes are only registered while their process is running.
+-- They are never set as the default node and they are removed when they stop
+ALTER TABLE node ADD COLUMN is_ephemeral INTEGER NOT NULL DEFAULT 0; -- boolean indicating if this node is ephemeral (1 means true)
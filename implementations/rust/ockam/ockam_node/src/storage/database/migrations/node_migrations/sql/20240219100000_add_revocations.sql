@@ -0,0 +1,21 @@
+-- Members revoked by an authority node.
+-- Each revocation increments the version of the revocation list published by the authority
+CREATE TABLE authority_revocation
+(
+    identifier TEXT    NOT NULL UNIQUE,
+    revoked_at INTEGER NOT NULL,
+    reason     TEXT,
+    version    INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_revocation_identifier_index ON authority_revocation(identifier);
+
+-- Last revocation list fetched by a node from an authority
+CREATE TABLE revocation_list
+(
+    authority_identifier TEXT    NOT NULL,
+    version              INTEGER NOT NULL,
+    list                 BLOB    NOT NULL,
+    node_name            TEXT    NOT NULL,
+    PRIMARY KEY (authority_identifier, node_name)
+);
@@ -0,0 +1,14 @@
+-- Events emitted by the components of a node: node started / stopped, listener bound,
+-- secure channel established / closed, relay up / down, message denied by a policy.
+-- Only the most recent events are kept in this table.
+CREATE TABLE node_events
+(
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    node_name  TEXT    NOT NULL,
+    kind       TEXT    NOT NULL,
+    subject    TEXT    NOT NULL,
+    details    TEXT    NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX node_events_node_name_created_at_index ON node_events(node_name, created_at);
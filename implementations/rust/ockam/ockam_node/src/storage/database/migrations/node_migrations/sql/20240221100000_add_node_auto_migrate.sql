@@ -0,0 +1,3 @@
+-- When auto_migrate is false for at least one node, the pending migrations of the database are not
+-- applied when the database is opened. They must be applied explicitly with `ockam node migrate`
+ALTER TABLE node ADD COLUMN auto_migrate INTEGER NOT NULL DEFAULT 1; -- boolean indicating if migrations can be applied automatically (1 means true)
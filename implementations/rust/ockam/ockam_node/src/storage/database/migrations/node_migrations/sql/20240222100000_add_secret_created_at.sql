@@ -0,0 +1,4 @@
+-- The creation time of secrets is used to help users decide which orphaned keys can be pruned
+-- It is NULL for the secrets which were stored before this migration
+ALTER TABLE signing_secret ADD COLUMN created_at INTEGER; -- Unix timestamp, in seconds, of the secret creation
+ALTER TABLE x25519_secret ADD COLUMN created_at INTEGER;  -- Unix timestamp, in seconds, of the secret creation
@@ -0,0 +1,10 @@
+-- Version of the ockam binary which created, or last started, each node.
+-- It is compared to the version of the command line connecting to the node, in order to warn
+-- the user when a background node still runs an older version of the code
+CREATE TABLE node_metadata
+(
+    node_name      TEXT PRIMARY KEY, -- name of the node
+    binary_version TEXT    NOT NULL, -- version of the binary, for example 0.118.0
+    git_hash       TEXT,             -- hash of the commit the binary was compiled from, if known
+    updated_at     INTEGER NOT NULL  -- time of the last update, in seconds since the Unix epoch
+);
@@ -0,0 +1,10 @@
+-- Owners of the aliases registered with the relay service of a node.
+-- The first identity registering an alias owns it until it releases it
+CREATE TABLE relay_alias
+(
+    alias         TEXT    NOT NULL,
+    identifier    TEXT    NOT NULL,
+    registered_at INTEGER NOT NULL,
+    node_name     TEXT    NOT NULL,
+    PRIMARY KEY (alias, node_name)
+);
@@ -0,0 +1,4 @@
+-- Maximum time to live of the credentials issued to the members enrolled with an enrollment token.
+-- When it is not set, the time to live configured for the credential issuer is used
+ALTER TABLE authority_enrollment_token ADD COLUMN credential_ttl INTEGER;
+ALTER TABLE authority_member ADD COLUMN credential_ttl INTEGER;
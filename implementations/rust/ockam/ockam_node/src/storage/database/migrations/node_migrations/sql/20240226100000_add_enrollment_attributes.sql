@@ -0,0 +1,12 @@
+-- Attributes granted to a local identity when it enrolled with a project.
+-- They are copied from the redeemed enrollment ticket and from the credential issued at enrollment.
+-- This copy is only advisory: the project authority remains the source of truth for the attributes
+CREATE TABLE identity_enrollment_attribute
+(
+    identifier      TEXT    NOT NULL,
+    project_id      TEXT    NOT NULL,
+    attribute_name  TEXT    NOT NULL,
+    attribute_value TEXT    NOT NULL,
+    source          TEXT    NOT NULL, -- 'ticket' or 'credential'
+    PRIMARY KEY (identifier, project_id, attribute_name)
+);
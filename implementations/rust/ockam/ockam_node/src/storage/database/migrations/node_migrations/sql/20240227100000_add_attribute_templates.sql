@@ -0,0 +1,11 @@
+-- Attribute templates constraining the attributes granted by the enrollment tokens of an authority node
+CREATE TABLE authority_attribute_template
+(
+    name       TEXT    PRIMARY KEY,
+    attributes BLOB    NOT NULL, -- CBOR encoded map of attribute names to constraints
+    created_by TEXT    NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+-- Name of the attribute template referenced by an enrollment token, if any
+ALTER TABLE authority_enrollment_token ADD COLUMN template TEXT;
@@ -0,0 +1,8 @@
+-- Named routes, which can be used as `@<name>` by the command line wherever a route is accepted.
+-- The route of an alias can itself reference other aliases
+CREATE TABLE route_alias
+(
+    name       TEXT    PRIMARY KEY,
+    route      TEXT    NOT NULL,
+    created_at INTEGER NOT NULL
+);
@@ -1,7 +1,13 @@
+mod database_backup;
+mod database_configuration;
+mod database_integrity;
 mod migrations;
 mod sqlx_database;
 mod sqlx_types;
 
+pub use database_backup::*;
+pub use database_configuration::*;
+pub use database_integrity::*;
 pub use migrations::*;
 pub use sqlx_database::*;
 pub use sqlx_types::*;
@@ -1,19 +1,18 @@
 use core::fmt::{Debug, Formatter};
 use sqlx::pool::PoolOptions;
-use sqlx::sqlite::SqliteConnectOptions;
+use sqlx::SqlitePool;
 use std::ops::Deref;
 use std::path::Path;
 
 use ockam_core::errcode::{Kind, Origin};
-use sqlx::{ConnectOptions, SqlitePool};
 use tokio_retry::strategy::{jitter, FixedInterval};
 use tokio_retry::Retry;
 use tracing::debug;
-use tracing::log::LevelFilter;
 
 use crate::database::migrations::application_migration_set::ApplicationMigrationSet;
 use crate::database::migrations::node_migration_set::NodeMigrationSet;
 use crate::database::migrations::MigrationSet;
+use crate::database::DatabaseConfiguration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::{Error, Result};
 
@@ -23,6 +22,9 @@ use ockam_core::{Error, Result};
 ///
 /// We use sqlx as our primary interface for interacting with the database
 /// The database driver is currently Sqlite
+///
+/// The pool of connections is configured with a [`DatabaseConfiguration`] and is meant to be
+/// shared by all the repositories accessing the same database file, by cloning this struct.
 #[derive(Clone)]
 pub struct SqlxDatabase {
     /// Pool of connections to the database
@@ -48,7 +50,15 @@ impl Deref for SqlxDatabase {
 impl SqlxDatabase {
     /// Constructor for a database persisted on disk
     pub async fn create(path: impl AsRef<Path>) -> Result<Self> {
-        Self::create_impl(path, Some(NodeMigrationSet), None).await
+        Self::create_with_configuration(path, DatabaseConfiguration::from_env()?).await
+    }
+
+    /// Constructor for a database persisted on disk, with a specific configuration for the pool of connections
+    pub async fn create_with_configuration(
+        path: impl AsRef<Path>,
+        configuration: DatabaseConfiguration,
+    ) -> Result<Self> {
+        Self::create_impl(path, Some(NodeMigrationSet), None, configuration).await
     }
 
     /// Constructor for a database persisted on disk, with a specific schema / migration
@@ -56,23 +66,42 @@ impl SqlxDatabase {
         path: impl AsRef<Path>,
         migration_set: impl MigrationSet,
     ) -> Result<Self> {
-        Self::create_impl(path, Some(migration_set), None).await
+        Self::create_impl(
+            path,
+            Some(migration_set),
+            None,
+            DatabaseConfiguration::from_env()?,
+        )
+        .await
     }
 
     /// Constructor for a database persisted on disk without migration
     pub async fn create_no_migration(path: impl AsRef<Path>) -> Result<Self> {
-        Self::create_impl(path, None::<NodeMigrationSet>, None).await
+        Self::create_impl(
+            path,
+            None::<NodeMigrationSet>,
+            None,
+            DatabaseConfiguration::from_env()?,
+        )
+        .await
     }
 
     /// Constructor for a database persisted on disk, passing a node name to isolate data between nodes where needed
     pub async fn create_with_node_name(path: impl AsRef<Path>, node_name: &str) -> Result<Self> {
-        Self::create_impl(path, Some(NodeMigrationSet), Some(node_name.to_string())).await
+        Self::create_impl(
+            path,
+            Some(NodeMigrationSet),
+            Some(node_name.to_string()),
+            DatabaseConfiguration::from_env()?,
+        )
+        .await
     }
 
     async fn create_impl(
         path: impl AsRef<Path>,
         migration_set: Option<impl MigrationSet>,
         node_name: Option<String>,
+        configuration: DatabaseConfiguration,
     ) -> Result<Self> {
         path.as_ref()
             .parent()
@@ -88,7 +117,7 @@ impl SqlxDatabase {
             .take(10); // limit to 10 retries
 
         let db = Retry::spawn(retry_strategy, || async {
-            Self::create_at(path.as_ref(), node_name.clone()).await
+            Self::create_at(path.as_ref(), node_name.clone(), &configuration).await
         })
         .await?;
 
@@ -130,9 +159,13 @@ impl SqlxDatabase {
         Ok(db)
     }
 
-    async fn create_at(path: &Path, node_name: Option<String>) -> Result<Self> {
+    async fn create_at(
+        path: &Path,
+        node_name: Option<String>,
+        configuration: &DatabaseConfiguration,
+    ) -> Result<Self> {
         // Creates database file if it doesn't exist
-        let pool = Self::create_connection_pool(path).await?;
+        let pool = Self::create_connection_pool_with_configuration(path, configuration).await?;
         Ok(SqlxDatabase {
             pool: Arc::new(pool),
             node_name,
@@ -140,11 +173,18 @@ impl SqlxDatabase {
     }
 
     pub(crate) async fn create_connection_pool(path: &Path) -> Result<SqlitePool> {
-        let options = SqliteConnectOptions::new()
-            .filename(path)
-            .create_if_missing(true)
-            .log_statements(LevelFilter::Debug);
-        let pool = SqlitePool::connect_with(options)
+        Self::create_connection_pool_with_configuration(path, &DatabaseConfiguration::default())
+            .await
+    }
+
+    pub(crate) async fn create_connection_pool_with_configuration(
+        path: &Path,
+        configuration: &DatabaseConfiguration,
+    ) -> Result<SqlitePool> {
+        debug!(?configuration, "create a connection pool for {path:?}");
+        let pool = configuration
+            .pool_options()
+            .connect_with(configuration.connect_options(path))
             .await
             .map_err(Self::map_sql_err)?;
         Ok(pool)
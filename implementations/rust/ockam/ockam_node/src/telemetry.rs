@@ -0,0 +1,92 @@
+use core::time::Duration;
+use opentelemetry::metrics::{Counter, Histogram, Unit};
+use opentelemetry::{global, KeyValue};
+
+/// Name of the OpenTelemetry meter used for the metrics of a node
+pub const METER_NAME: &str = "ockam";
+
+/// Attribute set on the transport metrics to distinguish the type of transport
+pub const TRANSPORT_TYPE_ATTRIBUTE: &str = "transport.type";
+
+/// Value of the transport type attribute for secure channels
+pub const SECURE_CHANNEL_TRANSPORT_TYPE: &str = "secure_channel";
+
+/// OpenTelemetry meters recording the messages handled by the workers of a transport,
+/// or by the encryptor and decryptor of a secure channel.
+///
+/// The instruments are created from the global meter provider when the workers start,
+/// so the meter provider must be set before nodes are started for the metrics to be exported.
+#[derive(Clone)]
+pub struct TransportMetrics {
+    attributes: [KeyValue; 1],
+    messages_in: Counter<u64>,
+    messages_out: Counter<u64>,
+    bytes_in: Counter<u64>,
+    bytes_out: Counter<u64>,
+    decode_failures: Counter<u64>,
+}
+
+impl TransportMetrics {
+    /// Create the meters for a given type of transport: "tcp", "udp", "secure_channel"
+    pub fn new(transport_type: &'static str) -> Self {
+        let meter = global::meter(METER_NAME);
+        Self {
+            attributes: [KeyValue::new(TRANSPORT_TYPE_ATTRIBUTE, transport_type)],
+            messages_in: meter
+                .u64_counter("ockam.messages.in")
+                .with_description("Number of messages received")
+                .init(),
+            messages_out: meter
+                .u64_counter("ockam.messages.out")
+                .with_description("Number of messages sent")
+                .init(),
+            bytes_in: meter
+                .u64_counter("ockam.bytes.in")
+                .with_description("Number of bytes received")
+                .with_unit(Unit::new("By"))
+                .init(),
+            bytes_out: meter
+                .u64_counter("ockam.bytes.out")
+                .with_description("Number of bytes sent")
+                .with_unit(Unit::new("By"))
+                .init(),
+            decode_failures: meter
+                .u64_counter("ockam.decode_failures")
+                .with_description("Number of received messages which could not be decoded")
+                .init(),
+        }
+    }
+
+    /// Record a received message and its size
+    pub fn record_inbound(&self, size: usize) {
+        self.messages_in.add(1, &self.attributes);
+        self.bytes_in.add(size as u64, &self.attributes);
+    }
+
+    /// Record a sent message and its size
+    pub fn record_outbound(&self, size: usize) {
+        self.messages_out.add(1, &self.attributes);
+        self.bytes_out.add(size as u64, &self.attributes);
+    }
+
+    /// Record a received message which could not be decoded (or decrypted)
+    pub fn record_decode_failure(&self) {
+        self.decode_failures.add(1, &self.attributes);
+    }
+}
+
+/// Record the duration of a secure channel handshake, for the initiator or the responder
+pub fn record_handshake_duration(role: &'static str, duration: Duration, succeeded: bool) {
+    let histogram: Histogram<f64> = global::meter(METER_NAME)
+        .f64_histogram("ockam.secure_channel.handshake.duration")
+        .with_description("Duration of the secure channel handshakes")
+        .with_unit(Unit::new("s"))
+        .init();
+    histogram.record(
+        duration.as_secs_f64(),
+        &[
+            KeyValue::new("secure_channel.role", role),
+            KeyValue::new("secure_channel.handshake.succeeded", succeeded),
+        ],
+    );
+}
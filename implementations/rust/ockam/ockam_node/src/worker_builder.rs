@@ -40,6 +40,7 @@ where
             outgoing_ac: Arc::new(AllowAll),
             worker: self.worker,
             address: address.into(),
+            priority_lanes: None,
         }
     }
 
@@ -48,6 +49,7 @@ where
         WorkerBuilderMultipleAddresses {
             mailboxes,
             worker: self.worker,
+            priority_lanes: None,
         }
     }
 }
@@ -58,15 +60,25 @@ where
 {
     mailboxes: Mailboxes,
     worker: W,
+    priority_lanes: Option<usize>,
 }
 
 impl<W> WorkerBuilderMultipleAddresses<W>
 where
     W: Worker<Context = Context>,
 {
+    /// Handle the high priority messages received by the worker first.
+    ///
+    /// At most `max_priority_messages_in_a_row` high priority messages are handled before a
+    /// waiting normal priority message, see [`MessagePriority`](ockam_core::MessagePriority).
+    pub fn with_priority_lanes(mut self, max_priority_messages_in_a_row: usize) -> Self {
+        self.priority_lanes = Some(max_priority_messages_in_a_row);
+        self
+    }
+
     /// Consume this builder and start a new Ockam [`Worker`] from the given context
     pub async fn start(self, context: &Context) -> Result<()> {
-        start(context, self.mailboxes, self.worker).await
+        start(context, self.mailboxes, self.worker, self.priority_lanes).await
     }
 }
 
@@ -78,6 +90,7 @@ where
     outgoing_ac: Arc<dyn OutgoingAccessControl>,
     address: Address,
     worker: W,
+    priority_lanes: Option<usize>,
 }
 
 impl<W> WorkerBuilderOneAddress<W>
@@ -90,6 +103,7 @@ where
             context,
             Mailboxes::main(self.address, self.incoming_ac, self.outgoing_ac),
             self.worker,
+            self.priority_lanes,
         )
         .await
     }
@@ -134,10 +148,24 @@ where
         self.outgoing_ac = outgoing_access_control.clone();
         self
     }
+
+    /// Handle the high priority messages received by the worker first.
+    ///
+    /// At most `max_priority_messages_in_a_row` high priority messages are handled before a
+    /// waiting normal priority message, see [`MessagePriority`](ockam_core::MessagePriority).
+    pub fn with_priority_lanes(mut self, max_priority_messages_in_a_row: usize) -> Self {
+        self.priority_lanes = Some(max_priority_messages_in_a_row);
+        self
+    }
 }
 
 /// Consume this builder and start a new Ockam [`Worker`] from the given context
-async fn start<W>(context: &Context, mailboxes: Mailboxes, worker: W) -> Result<()>
+async fn start<W>(
+    context: &Context,
+    mailboxes: Mailboxes,
+    worker: W,
+    priority_lanes: Option<usize>,
+) -> Result<()>
 where
     W: Worker<Context = Context>,
 {
@@ -151,13 +179,21 @@ where
     let addresses = mailboxes.addresses();
 
     // Pass it to the context
-    let (ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    let (mut ctx, sender, ctrl_rx) = context.copy_with_mailboxes(mailboxes);
+    if let Some(max_priority_messages_in_a_row) = priority_lanes {
+        ctx.set_priority_lanes(max_priority_messages_in_a_row);
+    }
 
     debugger::log_inherit_context("WORKER", context, &ctx);
 
     // Send start request to router
-    let (msg, mut rx) =
-        NodeMessage::start_worker(addresses, sender, false, context.mailbox_count());
+    let (msg, mut rx) = NodeMessage::start_worker(
+        addresses,
+        sender,
+        false,
+        context.mailbox_count(),
+        core::any::type_name::<W>(),
+    );
     context
         .sender()
         .send(msg)
@@ -0,0 +1,35 @@
+use ockam_core::{route, AllowAll, Encodable, Message, NeutralMessage, Result};
+use ockam_node::Context;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Message)]
+struct Items(Vec<String>);
+
+/// Compare a message moved to a local worker with the same message encoded by the sender
+/// and decoded by the receiver, as it was before local messages were moved
+#[ockam_macros::test]
+async fn benchmark(ctx: &mut Context) -> Result<()> {
+    const ROUNDS: u32 = 10_000;
+    let mut receiver = ctx.new_detached("receiver", AllowAll, AllowAll).await?;
+    let items = Items((0..100).map(|i| format!("item-{i}")).collect());
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        ctx.send(route!["receiver"], items.clone()).await?;
+        let received = receiver.receive::<Items>().await?.into_body()?;
+        assert_eq!(received.0.len(), 100);
+    }
+    eprintln!("local-route/moved: {:0.2?}", start.elapsed() / ROUNDS);
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        let encoded = NeutralMessage::from(items.clone().encode()?);
+        ctx.send(route!["receiver"], encoded).await?;
+        let received = receiver.receive::<Items>().await?.into_body()?;
+        assert_eq!(received.0.len(), 100);
+    }
+    eprintln!("local-route/encoded: {:0.2?}", start.elapsed() / ROUNDS);
+
+    Ok(())
+}
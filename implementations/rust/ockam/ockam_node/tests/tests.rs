@@ -1,18 +1,29 @@
 use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
+use ockam_core::api::{Reply, Request, Status};
 use ockam_core::compat::{
     boxed::Box,
     string::{String, ToString},
     sync::Arc,
 };
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Address, AllowAll, Any, Decodable, DenyAll, Message, LOCAL};
-use ockam_core::{route, Processor, Result, Routed, Worker};
-use ockam_node::compat::futures::FutureExt;
-use ockam_node::{Context, MessageReceiveOptions, NodeBuilder};
+use ockam_core::{
+    async_trait, Address, AllowAll, Any, Decodable, DenyAll, DenyReason, Encodable,
+    IncomingAccessControl, Mailbox, Mailboxes, Message, MessagePriority, NeutralMessage,
+    RelayMessage, TransportType, LOCAL,
+};
+use ockam_core::{
+    route, PayloadMigrationRegistry, Processor, Result, Routed, TypedMessage, Worker,
+};
+use ockam_node::api::Client;
+use ockam_node::compat::futures::{FutureExt, StreamExt};
+use ockam_node::{
+    Context, FileWatcher, MessageReceiveOptions, MessageSendReceiveOptions, NodeBuilder,
+    NodeEventKind, WorkerBuilder, DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicI8, AtomicU32};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::time::sleep;
 use tracing::info;
 
@@ -155,6 +166,42 @@ async fn starting_processor_with_dup_address_should_fail(ctx: &mut Context) -> R
     Ok(())
 }
 
+struct RegisteredWorker;
+
+#[ockam_core::worker]
+impl Worker for RegisteredWorker {
+    type Context = Context;
+    type Message = ();
+}
+
+#[ockam_macros::test]
+async fn address_collision_should_name_the_existing_owner(ctx: &mut Context) -> Result<()> {
+    ctx.start_worker("registered", RegisteredWorker).await?;
+
+    let error = ctx
+        .start_processor("registered", DummyProcessor)
+        .await
+        .unwrap_err();
+    assert_eq!(error.code().kind, Kind::AlreadyExists);
+    let message = error.to_string();
+    assert!(message.contains("address 0#registered is already in use"));
+    assert!(message.contains(core::any::type_name::<RegisteredWorker>()));
+
+    let workers = ctx.list_workers_info().await?;
+    let registered = workers
+        .iter()
+        .find(|w| w.address() == &Address::from("registered"))
+        .unwrap();
+    assert_eq!(
+        registered.owner(),
+        core::any::type_name::<RegisteredWorker>()
+    );
+    assert!(!registered.is_processor());
+    assert!(registered.registered_at() > 0);
+
+    ctx.stop().await
+}
+
 struct CountingProcessor {
     initialize_was_called: Arc<AtomicBool>,
     shutdown_was_called: Arc<AtomicBool>,
@@ -610,3 +657,437 @@ async fn message_handle__error_during_handling__keep_worker_running(
 
     Ok(())
 }
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Message)]
+struct Ping(u32);
+
+impl TypedMessage for Ping {
+    const SCHEMA: &'static str = "ping";
+    const VERSION: u32 = 1;
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Message)]
+struct PingWithLabel(u32, String);
+
+impl TypedMessage for PingWithLabel {
+    const SCHEMA: &'static str = "ping";
+    const VERSION: u32 = 2;
+}
+
+#[ockam_macros::test]
+async fn typed_messages_are_checked_on_receive(ctx: &mut Context) -> Result<()> {
+    let mut child_ctx = ctx.new_detached("typed", AllowAll, AllowAll).await?;
+
+    ctx.send_typed(route!["typed"], Ping(1)).await?;
+    assert_eq!(child_ctx.receive_typed::<Ping>().await?, Ping(1));
+
+    ctx.send_typed(route!["typed"], Ping(2)).await?;
+    let error = child_ctx
+        .receive_typed::<PingWithLabel>()
+        .await
+        .unwrap_err();
+    assert_eq!(error.code().kind, Kind::Invalid);
+    assert!(
+        error
+            .to_string()
+            .contains("schema ping v2, received ping v1"),
+        "{error}"
+    );
+
+    let mut migrations = PayloadMigrationRegistry::new();
+    migrations.register_message(|ping: Ping| PingWithLabel(ping.0, "migrated".to_string()));
+    ctx.send_typed(route!["typed"], Ping(3)).await?;
+    let ping = child_ctx
+        .receive_typed_extended::<PingWithLabel>(MessageReceiveOptions::new(), &migrations)
+        .await?;
+    assert_eq!(ping, PingWithLabel(3, "migrated".to_string()));
+
+    Ok(())
+}
+
+struct RecordingWorker {
+    gate: Arc<tokio::sync::Notify>,
+    received: Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Worker for RecordingWorker {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        let body = msg.into_body()?;
+        // the first message blocks the worker until its mailbox is saturated
+        if body == "data-0" {
+            self.gate.notified().await;
+        }
+        sleep(Duration::from_millis(5)).await;
+        self.received.lock().unwrap().push(body);
+        Ok(())
+    }
+}
+
+#[ockam_macros::test]
+async fn priority_messages_are_handled_before_data_messages(ctx: &mut Context) -> Result<()> {
+    let gate = Arc::new(tokio::sync::Notify::new());
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    WorkerBuilder::new(RecordingWorker {
+        gate: gate.clone(),
+        received: received.clone(),
+    })
+    .with_address("recording")
+    .with_priority_lanes(DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW)
+    .start(ctx)
+    .await?;
+
+    ctx.send(route!["recording"], "data-0".to_string()).await?;
+    sleep(Duration::from_millis(100)).await;
+
+    // saturate the worker mailbox with data messages
+    let data_sender = ctx.new_detached("data-sender", AllowAll, AllowAll).await?;
+    tokio::spawn(async move {
+        for i in 1..=30 {
+            data_sender
+                .send(route!["recording"], format!("data-{i}"))
+                .await
+                .unwrap();
+        }
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    let control_sender = ctx
+        .new_detached("control-sender", AllowAll, AllowAll)
+        .await?;
+    tokio::spawn(async move {
+        control_sender
+            .send_with_priority(
+                route!["recording"],
+                "control".to_string(),
+                MessagePriority::High,
+            )
+            .await
+            .unwrap();
+    });
+    sleep(Duration::from_millis(100)).await;
+
+    gate.notify_one();
+    for _ in 0..100 {
+        if received.lock().unwrap().len() == 32 {
+            break;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+
+    let received = received.lock().unwrap().clone();
+    assert_eq!(received.len(), 32);
+    // the control message only waits for the data messages which were already handled
+    // when it reached the worker mailbox, not for the ones queued before it
+    let position = received.iter().position(|m| m == "control").unwrap();
+    assert!(position <= 3, "{received:?}");
+
+    // the data messages are still handled in order
+    let data: Vec<_> = received.iter().filter(|m| *m != "control").collect();
+    let expected: Vec<_> = (0..=30).map(|i| format!("data-{i}")).collect();
+    assert_eq!(data, expected.iter().collect::<Vec<_>>());
+
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Message)]
+struct Items(Vec<String>);
+
+#[ockam_macros::test]
+async fn local_messages_are_received_as_if_they_were_encoded(ctx: &mut Context) -> Result<()> {
+    let mut child_ctx = ctx.new_detached("local", AllowAll, AllowAll).await?;
+    let items = Items(vec!["first".to_string(), "second".to_string()]);
+
+    // the message is moved when it is received with its own type
+    ctx.send(route!["local"], items.clone()).await?;
+    let msg = child_ctx.receive::<Items>().await?;
+    assert_eq!(msg.onward_route(), route!["local"]);
+    assert_eq!(msg.return_route(), route![ctx.address()]);
+    assert_eq!(msg.into_body()?, items);
+
+    // it is encoded when its payload is accessed
+    ctx.send(route!["local"], items.clone()).await?;
+    let msg = child_ctx.receive::<Items>().await?;
+    assert_eq!(msg.payload(), items.clone().encode()?);
+    assert_eq!(msg.into_body()?, items);
+
+    // or when it is received with another type
+    ctx.send(route!["local"], items.clone()).await?;
+    let msg = child_ctx.receive::<NeutralMessage>().await?;
+    assert_eq!(msg.into_body()?.into_vec(), items.clone().encode()?);
+
+    // the local information of the message is kept
+    ctx.send_with_priority(route!["local"], items.clone(), MessagePriority::High)
+        .await?;
+    let msg = child_ctx.receive::<Items>().await?;
+    assert!(msg.local_message().priority().is_high());
+    assert_eq!(msg.into_body()?, items);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_to_an_address_prefix(ctx: &mut Context) -> Result<()> {
+    let tt = TransportType::new(42);
+    let mut subnet = ctx.new_detached("subnet", AllowAll, AllowAll).await?;
+    let mut peer = ctx.new_detached("peer", AllowAll, AllowAll).await?;
+    ctx.register_prefix(tt, "10.0.0.", "subnet").await?;
+    ctx.register_prefix(tt, "10.0.0.1:", "peer").await?;
+
+    // a prefix can only be registered once
+    assert!(ctx.register_prefix(tt, "10.0.0.", "peer").await.is_err());
+
+    // the worker of the longest matching prefix receives the message
+    ctx.send(
+        route![Address::new(tt, "10.0.0.1:4000")],
+        "hello".to_string(),
+    )
+    .await?;
+    let msg = peer.receive::<String>().await?;
+    assert_eq!(
+        msg.onward_route().next()?,
+        &Address::new(tt, "10.0.0.1:4000")
+    );
+
+    ctx.send(
+        route![Address::new(tt, "10.0.0.2:4000")],
+        "hello".to_string(),
+    )
+    .await?;
+    subnet.receive::<String>().await?;
+
+    // once its prefix is removed, the messages go to the worker of a shorter prefix
+    ctx.unregister_prefix(tt, "10.0.0.1:").await?;
+    assert!(ctx.unregister_prefix(tt, "10.0.0.1:").await.is_err());
+    ctx.send(
+        route![Address::new(tt, "10.0.0.1:4000")],
+        "hello".to_string(),
+    )
+    .await?;
+    subnet.receive::<String>().await?;
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn file_watcher__file_replaced__should_notify_the_new_content(
+    ctx: &mut Context,
+) -> Result<()> {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("cert.pem");
+    std::fs::write(&path, "certificate 1").unwrap();
+
+    let mut events = ctx.node_events().subscribe();
+    let reloaded: Arc<std::sync::Mutex<Vec<Vec<u8>>>> = Default::default();
+    let received = reloaded.clone();
+    let watch = FileWatcher::new(ctx)
+        .with_interval(Duration::from_millis(20))
+        .watch(
+            "tls inlet",
+            &path,
+            move |_: &std::path::Path, content: &[u8]| {
+                if content.starts_with(b"invalid") {
+                    return Err(ockam_core::Error::new(
+                        Origin::Node,
+                        Kind::Invalid,
+                        "invalid certificate",
+                    ));
+                }
+                received.lock().unwrap().push(content.to_vec());
+                Ok(())
+            },
+        )
+        .await?;
+
+    // an invalid content is not recorded as a reload
+    std::fs::write(&path, "invalid certificate").unwrap();
+    sleep(Duration::from_millis(100)).await;
+    assert!(reloaded.lock().unwrap().is_empty());
+    assert!(events.try_recv().is_none());
+
+    // the file can be missing for a short time while it is replaced
+    std::fs::remove_file(&path).unwrap();
+    sleep(Duration::from_millis(100)).await;
+    std::fs::write(&path, "certificate 2").unwrap();
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        reloaded.lock().unwrap().clone(),
+        vec![b"certificate 2".to_vec()]
+    );
+    let event = events.try_recv().unwrap();
+    assert_eq!(event.kind(), NodeEventKind::FileReloaded);
+    assert_eq!(event.subject(), path.display().to_string());
+    assert_eq!(event.details()["subscriber"], "tls inlet");
+
+    // the file is not watched anymore once the subscription is dropped
+    drop(watch);
+    std::fs::write(&path, "certificate 3").unwrap();
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(reloaded.lock().unwrap().len(), 1);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn receiver_stream__context_stopped__should_end_the_stream(ctx: &mut Context) -> Result<()> {
+    // the stream takes the messages of all the addresses of the context
+    let mut consumer = ctx
+        .new_detached_with_mailboxes(Mailboxes::new(
+            Mailbox::new("stream-control", Arc::new(AllowAll), Arc::new(AllowAll)),
+            vec![Mailbox::new(
+                "stream-data",
+                Arc::new(AllowAll),
+                Arc::new(AllowAll),
+            )],
+        ))
+        .await?;
+    ctx.send(route!["stream-data"], "data".to_string()).await?;
+    ctx.send(route!["stream-control"], "control".to_string())
+        .await?;
+    ctx.stop_worker("stream-control").await?;
+
+    // the messages received before the stop are still delivered
+    let received: Vec<(Address, String)> = consumer
+        .receiver_stream::<String>()
+        .map(|msg| {
+            let msg = msg.unwrap();
+            (msg.msg_addr(), msg.into_body().unwrap())
+        })
+        .collect()
+        .await;
+    assert_eq!(
+        received,
+        vec![
+            ("stream-data".into(), "data".to_string()),
+            ("stream-control".into(), "control".to_string())
+        ]
+    );
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn receiver_stream__slow_consumer__should_slow_down_the_sender(
+    ctx: &mut Context,
+) -> Result<()> {
+    let mut consumer = ctx
+        .new_detached("slow-consumer", AllowAll, AllowAll)
+        .await?;
+    let producer = ctx.new_detached("producer", AllowAll, AllowAll).await?;
+
+    let sent = Arc::new(AtomicU32::new(0));
+    let sent_by_producer = sent.clone();
+    let producer_task = tokio::spawn(async move {
+        for i in 0..20u32 {
+            producer
+                .send(route!["slow-consumer"], i.to_string())
+                .await
+                .unwrap();
+            sent_by_producer.fetch_add(1, Ordering::Relaxed);
+        }
+    });
+
+    // the mailbox is bounded, the producer waits until the messages are consumed
+    sleep(Duration::from_millis(200)).await;
+    assert!(sent.load(Ordering::Relaxed) < 20);
+
+    let received: Vec<String> = consumer
+        .any_receiver_stream()
+        .take(20)
+        .then(|msg| async move {
+            sleep(Duration::from_millis(5)).await;
+            String::decode(msg.unwrap().payload()).unwrap()
+        })
+        .collect()
+        .await;
+    producer_task.await.unwrap();
+
+    // all the messages are received, in order
+    let expected: Vec<String> = (0..20u32).map(|i| i.to_string()).collect();
+    assert_eq!(received, expected);
+    assert_eq!(sent.load(Ordering::Relaxed), 20);
+
+    ctx.stop().await
+}
+
+#[derive(Debug)]
+struct DenyWithReason;
+
+#[async_trait]
+impl IncomingAccessControl for DenyWithReason {
+    async fn is_authorized(&self, _relay_msg: &RelayMessage) -> Result<bool> {
+        ockam_core::deny()
+    }
+
+    async fn deny_reason(&self, _relay_msg: &RelayMessage) -> Option<DenyReason> {
+        Some(DenyReason::policy("protected/handle_message").with_missing_attribute("subject.role"))
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn access_control__denied_api_request__should_return_a_permission_error(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.start_worker_with_access_control("protected", DummyWorker, DenyWithReason, AllowAll)
+        .await?;
+
+    let client = Client::new(&route!["protected"], Some(Duration::from_secs(10)));
+    let start = Instant::now();
+    let reply: Reply<String> = client.ask(ctx, Request::get("/resource")).await?;
+
+    // the denial is reported right away instead of when the request times out
+    assert!(start.elapsed() < Duration::from_secs(5));
+    match reply.clone() {
+        Reply::Failed(error, status) => {
+            assert_eq!(status, Some(Status::Forbidden));
+            let message = error.message().unwrap();
+            assert!(message.contains("access denied"), "{message}");
+            assert!(message.contains("protected/handle_message"), "{message}");
+            assert!(message.contains("subject.role"), "{message}");
+        }
+        Reply::Successful(_) => panic!("the request should be denied"),
+    }
+    let error = reply.success().unwrap_err();
+    assert_eq!(error.code().origin, Origin::Authorization);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn access_control__denied_message_without_notification__should_be_dropped(
+    ctx: &mut Context,
+) -> Result<()> {
+    ctx.start_worker_with_access_control("protected", DummyWorker, DenyWithReason, AllowAll)
+        .await?;
+
+    // a request which does not ask to be notified of a denial
+    let request = Request::get("/resource").to_vec().unwrap();
+    let result = ctx
+        .send_and_receive_extended::<Vec<u8>>(
+            route!["protected"],
+            request,
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(1)),
+        )
+        .await;
+    assert!(result.is_err(), "no response should be sent");
+
+    // data messages are dropped silently as well
+    let result = ctx
+        .send_and_receive_extended::<String>(
+            route!["protected"],
+            "hello".to_string(),
+            MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(1)),
+        )
+        .await;
+    assert!(result.is_err(), "no response should be sent");
+
+    ctx.stop().await
+}
@@ -115,11 +115,13 @@ where
         msg: Routed<TransportMessage>,
     ) -> Result<()> {
         trace!("BleSendWorker::handle_message -> {:?}", msg);
-        let mut msg = msg.into_body()?;
+        let msg = msg.into_body()?;
 
         // Remove our own address from the route so the other end
         // knows what to do with the incoming message
-        msg.onward_route.step()?;
+        let mut onward_route = msg.onward_route.clone();
+        onward_route.step()?;
+        let msg = msg.update_tracing_for_hop(&ctx.address().to_string(), onward_route);
 
         // encode message
         let msg = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
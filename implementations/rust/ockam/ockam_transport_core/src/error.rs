@@ -41,6 +41,8 @@ pub enum TransportError {
     /// Excessive length of header, possible DoS attack
     /// https://github.com/advisories/GHSA-9mcr-873m-xcxp
     AttackAttmept,
+    /// The outbox of a connection which is re-established is full
+    OutboxFull,
 }
 
 impl ockam_core::compat::error::Error for TransportError {}
@@ -63,6 +65,7 @@ impl core::fmt::Display for TransportError {
             Self::PortalInvalidState => write!(f, "portal entered invalid state"),
             Self::InvalidRouterResponseType => write!(f, "router responded with invalid type"),
             Self::AttackAttmept => write!(f, "excessive length of header, possible DoS attack"),
+            Self::OutboxFull => write!(f, "the outbox of the reconnecting connection is full"),
         }
     }
 }
@@ -88,6 +91,7 @@ impl From<TransportError> for Error {
             PortalInvalidState => Kind::Invalid,
             InvalidRouterResponseType => Kind::Invalid,
             AttackAttmept => Kind::Misuse,
+            OutboxFull => Kind::ResourceExhausted,
         };
 
         Error::new(Origin::Transport, kind, err)
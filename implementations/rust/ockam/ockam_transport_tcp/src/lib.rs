@@ -18,14 +18,21 @@ extern crate core;
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+mod listener_limits;
 mod options;
 mod portal;
+mod protocol;
+mod reconnect;
 mod registry;
 mod transport;
 
+pub(crate) use listener_limits::*;
+pub use listener_limits::{IpCidr, TcpListenerStats};
 use ockam_core::TransportType;
 pub use options::{TcpConnectionOptions, TcpListenerOptions};
-pub use portal::{PortalInternalMessage, PortalMessage, MAX_PAYLOAD_SIZE};
+pub use portal::{PortalInternalMessage, PortalMessage, PortalType, MAX_PAYLOAD_SIZE};
+pub use protocol::{TcpCapabilities, TcpProtocol, TCP_PROTOCOL_VERSION};
+pub(crate) use reconnect::*;
 pub use registry::*;
 pub use transport::common::*;
 pub use transport::*;
@@ -0,0 +1,395 @@
+use core::fmt;
+use core::fmt::Formatter;
+use core::str::FromStr;
+use core::sync::atomic::{AtomicU64, Ordering};
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::net::{IpAddr, SocketAddr};
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use std::time::Instant;
+
+/// Range of IP addresses, written in the CIDR notation, for example `10.0.0.0/8`.
+/// A single IP address is a range containing only this address
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IpCidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// Create a range of addresses from a network address and the length of its prefix
+    pub fn new(network: IpAddr, prefix_len: u8) -> Result<Self> {
+        let max_prefix_len = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        if prefix_len > max_prefix_len {
+            return Err(TransportError::InvalidAddress)?;
+        }
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+
+    /// Return true if the address is part of this range.
+    /// IPv4 addresses are never part of an IPv6 range, and conversely
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match (self.network, address) {
+            (IpAddr::V4(network), IpAddr::V4(address)) => {
+                Self::same_prefix(u32::from(network), u32::from(*address), self.prefix_len)
+            }
+            (IpAddr::V6(network), IpAddr::V6(address)) => {
+                Self::same_prefix(u128::from(network), u128::from(*address), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+
+    fn same_prefix<T>(network: T, address: T, prefix_len: u8) -> bool
+    where
+        T: Into<u128>,
+    {
+        let bits = core::mem::size_of::<T>() as u32 * 8;
+        let (network, address) = (network.into(), address.into());
+        if prefix_len == 0 {
+            return true;
+        }
+        let shift = bits - prefix_len as u32;
+        (network >> shift) == (address >> shift)
+    }
+}
+
+impl FromStr for IpCidr {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (network, prefix_len) = match s.split_once('/') {
+            Some((network, prefix_len)) => (
+                network,
+                Some(
+                    prefix_len
+                        .parse::<u8>()
+                        .map_err(|_| TransportError::InvalidAddress)?,
+                ),
+            ),
+            None => (s, None),
+        };
+        let network: IpAddr = network
+            .parse()
+            .map_err(|_| TransportError::InvalidAddress)?;
+        let prefix_len = prefix_len.unwrap_or(match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        });
+        Self::new(network, prefix_len)
+    }
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// Statistics of the connections received by a TCP listener
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TcpListenerStats {
+    /// Number of connections handed to a receiver processor
+    pub accepted: u64,
+    /// Number of connections closed because the maximum accept rate was exceeded
+    pub rejected_by_rate: u64,
+    /// Number of connections closed because their source had too many connections
+    pub rejected_by_source_limit: u64,
+    /// Number of connections closed because their source is not allowed
+    pub rejected_by_source_filter: u64,
+}
+
+impl TcpListenerStats {
+    /// Total number of connections closed right after being accepted
+    pub fn rejected(&self) -> u64 {
+        self.rejected_by_rate + self.rejected_by_source_limit + self.rejected_by_source_filter
+    }
+}
+
+/// Counters shared by a listener processor and the registry
+#[derive(Debug, Default)]
+pub(crate) struct TcpListenerCounters {
+    accepted: AtomicU64,
+    rejected_by_rate: AtomicU64,
+    rejected_by_source_limit: AtomicU64,
+    rejected_by_source_filter: AtomicU64,
+}
+
+impl TcpListenerCounters {
+    pub(crate) fn stats(&self) -> TcpListenerStats {
+        TcpListenerStats {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            rejected_by_rate: self.rejected_by_rate.load(Ordering::Relaxed),
+            rejected_by_source_limit: self.rejected_by_source_limit.load(Ordering::Relaxed),
+            rejected_by_source_filter: self.rejected_by_source_filter.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Protections of a TCP listener against connection floods, set on the
+/// [`TcpListenerOptions`](crate::TcpListenerOptions)
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TcpListenerLimits {
+    /// Average number of connections accepted per second, and maximum size of a burst
+    pub(crate) max_accept_rate: Option<(u32, u32)>,
+    pub(crate) max_connections_per_source: Option<usize>,
+    pub(crate) allowed_sources: Vec<IpCidr>,
+    pub(crate) denied_sources: Vec<IpCidr>,
+}
+
+/// Reason why an accepted connection is closed immediately
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TcpConnectionRejection {
+    Rate,
+    SourceLimit,
+    SourceFilter,
+}
+
+impl fmt::Display for TcpConnectionRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TcpConnectionRejection::Rate => write!(f, "the maximum accept rate is exceeded"),
+            TcpConnectionRejection::SourceLimit => {
+                write!(f, "the source has too many connections")
+            }
+            TcpConnectionRejection::SourceFilter => write!(f, "the source is not allowed"),
+        }
+    }
+}
+
+/// Token bucket refilled continuously at `rate` tokens per second, up to `burst` tokens
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, burst: u32) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            rate: rate as f64,
+            burst,
+            tokens: burst,
+            refilled_at: Instant::now(),
+        }
+    }
+
+    fn try_take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Decide if a connection accepted by a listener is handed to a receiver processor.
+///
+/// The checks only use the address of the peer, so that a rejected connection costs
+/// nothing more than its accept and its close
+#[derive(Debug)]
+pub(crate) struct TcpListenerGuard {
+    limits: TcpListenerLimits,
+    bucket: Option<TokenBucket>,
+    connections: Arc<Mutex<HashMap<IpAddr, usize>>>,
+    counters: Arc<TcpListenerCounters>,
+}
+
+impl TcpListenerGuard {
+    pub(crate) fn new(limits: TcpListenerLimits) -> Self {
+        Self {
+            bucket: limits
+                .max_accept_rate
+                .map(|(rate, burst)| TokenBucket::new(rate, burst)),
+            limits,
+            connections: Default::default(),
+            counters: Default::default(),
+        }
+    }
+
+    pub(crate) fn counters(&self) -> Arc<TcpListenerCounters> {
+        self.counters.clone()
+    }
+
+    /// Admit a new connection from `peer`.
+    /// The returned permit must be kept as long as the connection is open
+    pub(crate) fn admit(
+        &mut self,
+        peer: &SocketAddr,
+    ) -> core::result::Result<TcpConnectionPermit, TcpConnectionRejection> {
+        let result = self.check(peer);
+        let counter = match result {
+            Ok(_) => &self.counters.accepted,
+            Err(TcpConnectionRejection::Rate) => &self.counters.rejected_by_rate,
+            Err(TcpConnectionRejection::SourceLimit) => &self.counters.rejected_by_source_limit,
+            Err(TcpConnectionRejection::SourceFilter) => &self.counters.rejected_by_source_filter,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    fn check(
+        &mut self,
+        peer: &SocketAddr,
+    ) -> core::result::Result<TcpConnectionPermit, TcpConnectionRejection> {
+        let source = peer.ip();
+        // the denied sources take precedence over the allowed ones
+        if self
+            .limits
+            .denied_sources
+            .iter()
+            .any(|c| c.contains(&source))
+            || (!self.limits.allowed_sources.is_empty()
+                && !self
+                    .limits
+                    .allowed_sources
+                    .iter()
+                    .any(|c| c.contains(&source)))
+        {
+            return Err(TcpConnectionRejection::SourceFilter);
+        }
+
+        // the connections rejected for their source don't use the accept rate of other sources
+        let mut connections = self.connections.lock().unwrap();
+        let count = connections.get(&source).copied().unwrap_or_default();
+        if let Some(max) = self.limits.max_connections_per_source {
+            if count >= max {
+                return Err(TcpConnectionRejection::SourceLimit);
+            }
+        }
+        if let Some(bucket) = self.bucket.as_mut() {
+            if !bucket.try_take() {
+                return Err(TcpConnectionRejection::Rate);
+            }
+        }
+        connections.insert(source, count + 1);
+
+        Ok(TcpConnectionPermit {
+            source,
+            connections: self.connections.clone(),
+        })
+    }
+}
+
+/// Count one connection of a source for as long as the connection is open
+#[derive(Debug)]
+pub(crate) struct TcpConnectionPermit {
+    source: IpAddr,
+    connections: Arc<Mutex<HashMap<IpAddr, usize>>>,
+}
+
+impl Drop for TcpConnectionPermit {
+    fn drop(&mut self) {
+        let mut connections = self.connections.lock().unwrap();
+        if let Some(count) = connections.get_mut(&self.source) {
+            *count -= 1;
+            if *count == 0 {
+                connections.remove(&self.source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(ip: &str) -> SocketAddr {
+        SocketAddr::new(ip.parse().unwrap(), 4000)
+    }
+
+    #[test]
+    fn cidr_ranges() -> Result<()> {
+        let range = IpCidr::from_str("10.1.0.0/16")?;
+        assert!(range.contains(&"10.1.200.3".parse().unwrap()));
+        assert!(!range.contains(&"10.2.0.1".parse().unwrap()));
+        assert!(!range.contains(&"::1".parse().unwrap()));
+        assert_eq!(range.to_string(), "10.1.0.0/16");
+
+        let single = IpCidr::from_str("127.0.0.1")?;
+        assert!(single.contains(&"127.0.0.1".parse().unwrap()));
+        assert!(!single.contains(&"127.0.0.2".parse().unwrap()));
+
+        assert!(IpCidr::from_str("0.0.0.0/0")?.contains(&"192.168.1.1".parse().unwrap()));
+        assert!(IpCidr::from_str("fd00::/8")?.contains(&"fd12::1".parse().unwrap()));
+        assert!(IpCidr::from_str("10.0.0.0/33").is_err());
+        assert!(IpCidr::from_str("10.0.0/8").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn sources_are_filtered_and_limited() -> Result<()> {
+        let mut guard = TcpListenerGuard::new(TcpListenerLimits {
+            max_connections_per_source: Some(2),
+            allowed_sources: vec![IpCidr::from_str("10.0.0.0/8")?],
+            denied_sources: vec![IpCidr::from_str("10.0.0.66")?],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            guard.admit(&peer("192.168.0.1")).unwrap_err(),
+            TcpConnectionRejection::SourceFilter
+        );
+        assert_eq!(
+            guard.admit(&peer("10.0.0.66")).unwrap_err(),
+            TcpConnectionRejection::SourceFilter
+        );
+
+        let first = guard.admit(&peer("10.0.0.1")).unwrap();
+        let _second = guard.admit(&peer("10.0.0.1")).unwrap();
+        assert_eq!(
+            guard.admit(&peer("10.0.0.1")).unwrap_err(),
+            TcpConnectionRejection::SourceLimit
+        );
+        // other sources are not affected
+        let _other = guard.admit(&peer("10.0.0.2")).unwrap();
+
+        // a closed connection makes room for a new one
+        drop(first);
+        let _third = guard.admit(&peer("10.0.0.1")).unwrap();
+
+        assert_eq!(
+            guard.counters().stats(),
+            TcpListenerStats {
+                accepted: 4,
+                rejected_by_rate: 0,
+                rejected_by_source_limit: 1,
+                rejected_by_source_filter: 2,
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn accepts_are_rate_limited() {
+        let mut guard = TcpListenerGuard::new(TcpListenerLimits {
+            max_accept_rate: Some((1, 3)),
+            ..Default::default()
+        });
+
+        let mut permits = vec![];
+        for _ in 0..3 {
+            permits.push(guard.admit(&peer("127.0.0.1")).unwrap());
+        }
+        assert_eq!(
+            guard.admit(&peer("127.0.0.1")).unwrap_err(),
+            TcpConnectionRejection::Rate
+        );
+        assert_eq!(guard.counters().stats().rejected(), 1);
+    }
+}
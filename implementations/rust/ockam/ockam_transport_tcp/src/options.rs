@@ -1,4 +1,6 @@
 use crate::workers::Addresses;
+use crate::{IpCidr, TcpListenerLimits, TcpOutboxLimits, TcpReconnectPolicy};
+use ockam_core::compat::backoff::Backoff;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl, OutgoingAccessControl};
@@ -13,6 +15,9 @@ pub(crate) struct TcpConnectionAccessControl {
 pub struct TcpConnectionOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) shared: bool,
+    pub(crate) reconnect_backoff: Option<Backoff>,
+    pub(crate) outbox: Option<TcpOutboxLimits>,
 }
 
 impl TcpConnectionOptions {
@@ -22,9 +27,49 @@ impl TcpConnectionOptions {
         Self {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
+            shared: false,
+            reconnect_backoff: None,
+            outbox: None,
         }
     }
 
+    /// Share the connection with the other users connecting to the same peer with this option.
+    ///
+    /// By default, a new connection is created. With this option, an outgoing connection to a
+    /// peer which is already connected reuses the existing shared connection. Each user keeps
+    /// its own [`FlowControlId`], so the messages received by one user can't be delivered to the
+    /// consumers of another user
+    pub fn shared(mut self) -> Self {
+        self.shared = true;
+        self
+    }
+
+    /// Connect to the peer again when the connection is closed, waiting between the attempts
+    /// as specified by `backoff`. The connection keeps its addresses, and is only closed once
+    /// the maximum number of attempts of the backoff is reached.
+    ///
+    /// The messages sent while the connection is re-established are rejected,
+    /// unless an outbox is configured with [`Self::with_outbox`]
+    pub fn with_reconnect(mut self, backoff: Backoff) -> Self {
+        self.reconnect_backoff = Some(backoff);
+        self
+    }
+
+    /// Buffer up to `max_messages` messages, and up to `max_bytes` bytes, while the connection
+    /// is re-established, and send them in order once it is back. The messages exceeding that
+    /// budget are rejected with a [`TransportError::OutboxFull`](ockam_transport_core::TransportError::OutboxFull)
+    /// error, and the buffered messages are dropped if the connection can't be re-established.
+    ///
+    /// This changes the delivery semantics of the connection, since messages are delayed rather
+    /// than lost during a reconnection. It is only used with [`Self::with_reconnect`]
+    pub fn with_outbox(mut self, max_messages: usize, max_bytes: usize) -> Self {
+        self.outbox = Some(TcpOutboxLimits {
+            max_messages,
+            max_bytes,
+        });
+        self
+    }
+
     /// Mark that this Connection is a Consumer for to the given [`FlowControlId`]
     pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
         self.consumer.push(id.clone());
@@ -39,6 +84,13 @@ impl TcpConnectionOptions {
 }
 
 impl TcpConnectionOptions {
+    pub(crate) fn reconnect_policy(&self) -> Option<TcpReconnectPolicy> {
+        self.reconnect_backoff.map(|backoff| TcpReconnectPolicy {
+            backoff,
+            outbox: self.outbox,
+        })
+    }
+
     pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, addresses: &Addresses) {
         flow_controls.add_producer(
             addresses.receiver_address().clone(),
@@ -52,6 +104,40 @@ impl TcpConnectionOptions {
         }
     }
 
+    /// Options of the connection shared by several users. That connection only delivers
+    /// messages to the workers of its users, which use the flow controls of their own options
+    pub(crate) fn for_shared_connection(&self) -> Self {
+        Self {
+            consumer: vec![],
+            flow_control_id: FlowControls::generate_flow_control_id(),
+            shared: true,
+            reconnect_backoff: self.reconnect_backoff,
+            outbox: self.outbox,
+        }
+    }
+
+    /// The worker of a user of a shared connection receives the messages of that connection,
+    /// and produces them for the flow control of that user
+    pub(crate) fn setup_flow_control_for_shared_connection(
+        &self,
+        flow_controls: &FlowControls,
+        connection_flow_control_id: &FlowControlId,
+        sender_address: &Address,
+        receiver_address: &Address,
+    ) {
+        flow_controls.add_consumer(receiver_address.clone(), connection_flow_control_id);
+        flow_controls.add_producer(
+            receiver_address.clone(),
+            &self.flow_control_id,
+            None,
+            vec![sender_address.clone()],
+        );
+
+        for id in &self.consumer {
+            flow_controls.add_consumer(sender_address.clone(), id);
+        }
+    }
+
     pub(crate) fn create_access_control(
         self,
         flow_controls: &FlowControls,
@@ -71,6 +157,7 @@ impl TcpConnectionOptions {
 #[derive(Debug)]
 pub struct TcpListenerOptions {
     pub(crate) flow_control_id: FlowControlId,
+    pub(crate) limits: TcpListenerLimits,
 }
 
 impl TcpListenerOptions {
@@ -81,6 +168,7 @@ impl TcpListenerOptions {
     pub fn new() -> Self {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
+            limits: TcpListenerLimits::default(),
         }
     }
 
@@ -88,6 +176,34 @@ impl TcpListenerOptions {
     pub fn spawner_flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
     }
+
+    /// Accept on average at most `rate` connections per second, with bursts of up to
+    /// `burst` connections. The connections exceeding that rate are closed right away
+    pub fn with_max_accept_rate(mut self, rate: u32, burst: u32) -> Self {
+        self.limits.max_accept_rate = Some((rate, burst));
+        self
+    }
+
+    /// Close the new connections coming from an IP address which already has
+    /// `max` open connections to this listener
+    pub fn with_max_connections_per_source(mut self, max: usize) -> Self {
+        self.limits.max_connections_per_source = Some(max);
+        self
+    }
+
+    /// Only accept the connections coming from the given range of addresses, and from the
+    /// other allowed ranges. All the sources are allowed when no range is allowed
+    pub fn allow_source(mut self, source: IpCidr) -> Self {
+        self.limits.allowed_sources.push(source);
+        self
+    }
+
+    /// Close the connections coming from the given range of addresses,
+    /// even if they are part of an allowed range
+    pub fn deny_source(mut self, source: IpCidr) -> Self {
+        self.limits.denied_sources.push(source);
+        self
+    }
 }
 
 impl TcpListenerOptions {
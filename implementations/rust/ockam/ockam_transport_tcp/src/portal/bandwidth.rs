@@ -0,0 +1,202 @@
+use crate::MAX_PAYLOAD_SIZE;
+use core::fmt::{Debug, Formatter};
+use core::time::Duration;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Limit of the bandwidth used by the connections of a TCP portal
+///
+/// The limit is enforced with a token bucket, shared by all the connections of an inlet or an
+/// outlet: when the budget is exhausted, reading from the local TCP connections is delayed
+/// until enough time has passed, so that the peers are slowed down without dropping any data.
+///
+/// The limiter also measures the throughput of the portal, whether a limit is set or not.
+/// Clones share the same state, which allows the limit to be changed while the portal is running.
+#[derive(Clone)]
+pub struct TcpPortalBandwidthLimiter {
+    state: Arc<Mutex<BandwidthState>>,
+}
+
+impl TcpPortalBandwidthLimiter {
+    /// Amount of traffic which can be sent at once after the connections were idle,
+    /// expressed as a duration at the configured rate
+    pub const BURST: Duration = Duration::from_millis(100);
+
+    /// Period over which the throughput is averaged
+    pub const THROUGHPUT_WINDOW: Duration = Duration::from_secs(5);
+
+    /// Maximum time spent waiting before checking the budget again,
+    /// so that a change of limit is taken into account quickly
+    const MAX_WAIT: Duration = Duration::from_millis(100);
+
+    /// Create a limiter with an optional limit, in bytes per second
+    pub fn new(limit: Option<u64>) -> Self {
+        let limit = limit.map(|l| l.max(1));
+        let now = Instant::now();
+        Self {
+            state: Arc::new(Mutex::new(BandwidthState {
+                limit,
+                tokens: limit.map(burst_capacity).unwrap_or_default(),
+                last_refill: now,
+                created_at: now,
+                samples: VecDeque::new(),
+            })),
+        }
+    }
+
+    /// Create a limiter which only measures the throughput
+    pub fn unlimited() -> Self {
+        Self::new(None)
+    }
+
+    /// Current limit, in bytes per second
+    pub fn limit(&self) -> Option<u64> {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Change the limit, in bytes per second. The change applies to the existing connections
+    pub fn set_limit(&self, limit: Option<u64>) {
+        let limit = limit.map(|l| l.max(1));
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.refill(now);
+        state.limit = limit;
+        state.tokens = match limit {
+            Some(limit) => state.tokens.min(burst_capacity(limit)),
+            None => 0.0,
+        };
+    }
+
+    /// Average number of bytes per second read from the local connections
+    /// during the last [`Self::THROUGHPUT_WINDOW`]
+    pub fn throughput(&self) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.prune(now);
+        let total: u64 = state.samples.iter().map(|(_, bytes)| bytes).sum();
+        let period = now
+            .duration_since(state.created_at)
+            .clamp(Duration::from_secs(1), Self::THROUGHPUT_WINDOW);
+        (total as f64 / period.as_secs_f64()) as u64
+    }
+
+    /// Wait until the budget allows some bytes to be read and
+    /// return the maximum number of bytes which can be read
+    pub(crate) async fn acquire(&self) -> usize {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                let limit = match state.limit {
+                    Some(limit) => limit,
+                    None => return MAX_PAYLOAD_SIZE,
+                };
+                state.refill(Instant::now());
+                if state.tokens >= 1.0 {
+                    return (state.tokens as usize).clamp(1, MAX_PAYLOAD_SIZE);
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / limit as f64)
+            };
+            tokio::time::sleep(wait.min(Self::MAX_WAIT)).await;
+        }
+    }
+
+    /// Record a number of bytes read from a local connection
+    pub(crate) fn consume(&self, bytes: usize) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.limit.is_some() {
+            state.refill(now);
+            state.tokens -= bytes as f64;
+        }
+        state.record(now, bytes as u64);
+    }
+}
+
+impl Debug for TcpPortalBandwidthLimiter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TcpPortalBandwidthLimiter")
+            .field("limit", &self.limit())
+            .finish()
+    }
+}
+
+struct BandwidthState {
+    /// Maximum number of bytes per second
+    limit: Option<u64>,
+    /// Number of bytes which can be read right now. It becomes negative when
+    /// more bytes than available were read, delaying the next reads
+    tokens: f64,
+    last_refill: Instant,
+    created_at: Instant,
+    /// Number of bytes read, aggregated per period of time
+    samples: VecDeque<(Instant, u64)>,
+}
+
+impl BandwidthState {
+    /// Period of time aggregating the bytes read for the throughput computation
+    const SAMPLE_PERIOD: Duration = Duration::from_millis(100);
+
+    fn refill(&mut self, now: Instant) {
+        if let Some(limit) = self.limit {
+            let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * limit as f64).min(burst_capacity(limit));
+        }
+        self.last_refill = now;
+    }
+
+    fn record(&mut self, now: Instant, bytes: u64) {
+        match self.samples.back_mut() {
+            Some((start, total)) if now.duration_since(*start) < Self::SAMPLE_PERIOD => {
+                *total += bytes
+            }
+            _ => self.samples.push_back((now, bytes)),
+        }
+        self.prune(now);
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some((start, _)) = self.samples.front() {
+            if now.duration_since(*start) > TcpPortalBandwidthLimiter::THROUGHPUT_WINDOW {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+/// Maximum number of tokens in the bucket for a given limit
+fn burst_capacity(limit: u64) -> f64 {
+    (limit as f64 * TcpPortalBandwidthLimiter::BURST.as_secs_f64()).max(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_the_budget() {
+        let limiter = TcpPortalBandwidthLimiter::new(Some(10_000));
+        // the initial burst is available right away
+        assert_eq!(limiter.acquire().await, 1_000);
+        limiter.consume(1_000);
+
+        // the next read must wait for the budget to be refilled
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_limit_can_be_changed() {
+        let limiter = TcpPortalBandwidthLimiter::new(Some(1));
+        limiter.consume(1_000);
+
+        let shared = limiter.clone();
+        shared.set_limit(None);
+        assert_eq!(limiter.limit(), None);
+        assert_eq!(limiter.acquire().await, MAX_PAYLOAD_SIZE);
+        assert_eq!(limiter.throughput(), 1_000);
+    }
+}
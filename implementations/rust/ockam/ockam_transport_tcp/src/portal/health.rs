@@ -0,0 +1,176 @@
+use crate::PortalMessage;
+use core::time::Duration;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::{NeutralMessage, Result, Route};
+use ockam_node::{Context, MessageSendReceiveOptions};
+use ockam_transport_core::TransportError;
+use serde::{Deserialize, Serialize};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use tokio::net::TcpStream;
+use tracing::debug;
+
+/// Health of the far side of a portal, as seen from an inlet
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TcpPortalHealth {
+    /// The outlet answered, and could connect to its target
+    Healthy,
+    /// The outlet answered, but could not connect to its target
+    UpstreamDown,
+    /// The outlet did not answer in time
+    OutletUnreachable,
+}
+
+impl TcpPortalHealth {
+    /// Return true if local connections can be accepted
+    pub fn is_healthy(&self) -> bool {
+        matches!(self, TcpPortalHealth::Healthy)
+    }
+}
+
+/// Result of the last health check performed by an inlet
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TcpPortalHealthStatus {
+    pub health: TcpPortalHealth,
+    /// Time of the check, in seconds since the Unix epoch
+    pub checked_at: u64,
+}
+
+/// Check of the far side of an inlet, performed when a local client connects
+///
+/// The inlet sends a [`PortalMessage::PortalPing`] to the outlet, and resets the local
+/// connection if the outlet doesn't answer in time, or reports that its target is down.
+/// Outlets which don't support the check never answer: they are reported as unreachable.
+///
+/// Clones share the same state, which allows the last status to be read while the
+/// inlet is running.
+#[derive(Debug, Clone)]
+pub struct TcpInletPreCheck {
+    timeout: Duration,
+    last_status: Arc<Mutex<Option<TcpPortalHealthStatus>>>,
+}
+
+impl TcpInletPreCheck {
+    /// Default time to wait for the answer of the outlet
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Constructor
+    pub fn new() -> Self {
+        Self {
+            timeout: Self::DEFAULT_TIMEOUT,
+            last_status: Default::default(),
+        }
+    }
+
+    /// Wait for the answer of the outlet for a given time instead of [`Self::DEFAULT_TIMEOUT`]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Time to wait for the answer of the outlet
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Result of the last check, if the inlet already accepted a connection
+    pub fn last_status(&self) -> Option<TcpPortalHealthStatus> {
+        *self.last_status.lock().unwrap()
+    }
+
+    /// Ping the outlet listener and record its answer
+    pub(crate) async fn check(
+        &self,
+        ctx: &Context,
+        outlet_listener_route: Route,
+    ) -> TcpPortalHealth {
+        let health = match self.ping(ctx, outlet_listener_route).await {
+            Ok(true) => TcpPortalHealth::Healthy,
+            Ok(false) => TcpPortalHealth::UpstreamDown,
+            Err(e) => {
+                debug!("The outlet did not answer the health check: {e}");
+                TcpPortalHealth::OutletUnreachable
+            }
+        };
+        let checked_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        *self.last_status.lock().unwrap() = Some(TcpPortalHealthStatus { health, checked_at });
+        health
+    }
+
+    async fn ping(&self, ctx: &Context, outlet_listener_route: Route) -> Result<bool> {
+        let reply: NeutralMessage = ctx
+            .send_and_receive_extended(
+                outlet_listener_route,
+                PortalMessage::PortalPing.to_neutral_message()?,
+                MessageSendReceiveOptions::new().with_timeout(self.timeout),
+            )
+            .await?
+            .into_body()?;
+        let reply = reply.into_vec();
+        match PortalMessage::decode(&reply)? {
+            PortalMessage::PortalPingReply(upstream_reachable) => Ok(upstream_reachable),
+            _ => Err(TransportError::Protocol)?,
+        }
+    }
+}
+
+impl Default for TcpInletPreCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Probe of the target of an outlet, used to answer the health checks of the inlets
+///
+/// The outlet connects to its target when it receives a health check, and closes the
+/// connection right away. The result is cached, so that the target is not probed for
+/// each connection accepted by the inlets.
+#[derive(Debug)]
+pub struct TcpOutletHealthProbe {
+    timeout: Duration,
+    cache_ttl: Duration,
+    last_probe: Option<(Instant, bool)>,
+}
+
+impl TcpOutletHealthProbe {
+    /// Default time to wait for the connection to the target
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Default time during which the result of a probe is reused
+    pub const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(5);
+
+    /// Constructor
+    pub fn new(timeout: Duration, cache_ttl: Duration) -> Self {
+        Self {
+            timeout,
+            cache_ttl,
+            last_probe: None,
+        }
+    }
+
+    /// Return true if the target could be reached by the last probe, probing it again
+    /// if the last result is too old
+    pub(crate) async fn is_reachable(&mut self, peer: SocketAddr) -> bool {
+        if let Some((probed_at, reachable)) = self.last_probe {
+            if probed_at.elapsed() < self.cache_ttl {
+                return reachable;
+            }
+        }
+        let reachable = matches!(
+            tokio::time::timeout(self.timeout, TcpStream::connect(peer)).await,
+            Ok(Ok(_))
+        );
+        debug!(%peer, %reachable, "probed the target of the outlet");
+        self.last_probe = Some((Instant::now(), reachable));
+        reachable
+    }
+}
+
+impl Default for TcpOutletHealthProbe {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_TIMEOUT, Self::DEFAULT_CACHE_TTL)
+    }
+}
@@ -0,0 +1,399 @@
+use crate::portal::identity::{find, MAX_HTTP_HEAD_SIZE};
+use crate::{
+    PortalDirection, PortalInterceptor, PortalInterceptorFactory, TcpOutletIdentityFormat,
+};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, Result};
+
+/// Rewriting of the HTTP/1.x requests sent through a portal
+///
+/// The `Host` header of each request can be replaced, and headers can be added to each request.
+/// A header with the same name as an added header, sent by the client, is removed.
+/// The bodies of the requests, sized with `Content-Length` or chunked, are forwarded unchanged.
+///
+/// After a `CONNECT` request, or a request asking for a protocol upgrade, the remaining bytes
+/// of the connection are forwarded unchanged. A connection which doesn't send HTTP requests
+/// is closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HttpRewrite {
+    host: Option<String>,
+    headers: Vec<(String, String)>,
+}
+
+impl HttpRewrite {
+    /// Constructor. Fails if the host, or one of the headers, can't be used in an HTTP request
+    pub fn new(host: Option<String>, headers: Vec<(String, String)>) -> Result<Self> {
+        if let Some(host) = &host {
+            if host.is_empty() || host.bytes().any(|b| b.is_ascii_whitespace() || b == 0) {
+                return Err(invalid(format!("'{host}' is not a valid HTTP host")));
+            }
+        }
+        for (name, value) in &headers {
+            if !TcpOutletIdentityFormat::is_valid_http_header_name(name) {
+                return Err(invalid(format!("'{name}' is not a valid HTTP header name")));
+            }
+            if value.bytes().any(|b| b == b'\r' || b == b'\n' || b == 0) {
+                return Err(invalid(format!(
+                    "the value of the HTTP header '{name}' must be on a single line"
+                )));
+            }
+        }
+        Ok(Self { host, headers })
+    }
+
+    /// Host replacing the `Host` header of the requests
+    pub fn host(&self) -> Option<&str> {
+        self.host.as_deref()
+    }
+
+    /// Headers added to the requests
+    pub fn headers(&self) -> &[(String, String)] {
+        &self.headers
+    }
+}
+
+impl PortalInterceptorFactory for HttpRewrite {
+    fn create(&self, direction: PortalDirection) -> Option<Box<dyn PortalInterceptor>> {
+        match direction {
+            PortalDirection::Request => Some(Box::new(HttpRequestRewriter::new(self.clone()))),
+            PortalDirection::Response => None,
+        }
+    }
+}
+
+/// Position of the rewriter in the stream of requests of a connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Buffering the head of a request
+    Head,
+    /// Forwarding the given amount of body bytes
+    Body(u64),
+    /// Buffering the line starting a chunk of a chunked body
+    ChunkSize,
+    /// Forwarding the given amount of chunk bytes, including the line end following the chunk
+    ChunkData(u64),
+    /// Forwarding the trailer fields ending a chunked body, until the empty line
+    Trailers,
+    /// Forwarding all the remaining bytes of the connection
+    Passthrough,
+}
+
+/// Interceptor rewriting the requests of one connection
+struct HttpRequestRewriter {
+    rewrite: HttpRewrite,
+    state: State,
+    buffer: Vec<u8>,
+}
+
+impl HttpRequestRewriter {
+    fn new(rewrite: HttpRewrite) -> Self {
+        Self {
+            rewrite,
+            state: State::Head,
+            buffer: vec![],
+        }
+    }
+
+    /// Remove the first line of the buffer, including its line end,
+    /// or return `None` if the line is not complete yet
+    fn take_line(&mut self) -> Result<Option<Vec<u8>>> {
+        match find(&self.buffer, b"\r\n") {
+            Some(end) => Ok(Some(self.buffer.drain(..end + 2).collect())),
+            None if self.buffer.len() > MAX_HTTP_HEAD_SIZE => {
+                Err(protocol("the HTTP line is too long"))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Rewrite the head of a request, ending with an empty line,
+    /// and return the state to use for the rest of the request
+    fn rewrite_head(&self, head: &[u8]) -> Result<(Vec<u8>, State)> {
+        let mut lines = head.split_inclusive(|b| *b == b'\n');
+        let request_line = lines.next().unwrap_or_default();
+        let method = request_line
+            .split(|b| *b == b' ')
+            .next()
+            .unwrap_or_default();
+        if !request_line.windows(7).any(|w| w == b" HTTP/1") {
+            return Err(protocol("the connection did not send an HTTP request"));
+        }
+
+        let mut rewritten = Vec::with_capacity(head.len() + 256);
+        rewritten.extend_from_slice(request_line);
+
+        let mut content_length = 0;
+        let mut chunked = false;
+        let mut upgrade = method == b"CONNECT";
+        let mut host_replaced = false;
+        // true while the lines of a removed header are skipped
+        let mut skipping = false;
+        for line in lines {
+            if line == b"\r\n" {
+                break;
+            }
+            // an obsolete line folding continues the value of the previous header
+            if line.starts_with(b" ") || line.starts_with(b"\t") {
+                if !skipping {
+                    rewritten.extend_from_slice(line);
+                }
+                continue;
+            }
+            let (name, value) = split_header(line);
+            skipping = false;
+            if name.eq_ignore_ascii_case(b"host") {
+                if let Some(host) = &self.rewrite.host {
+                    if !host_replaced {
+                        rewritten.extend_from_slice(format!("Host: {host}\r\n").as_bytes());
+                        host_replaced = true;
+                    }
+                    skipping = true;
+                    continue;
+                }
+            } else if name.eq_ignore_ascii_case(b"content-length") {
+                content_length = core::str::from_utf8(value)
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .ok_or_else(|| protocol("the HTTP request has an invalid Content-Length"))?;
+            } else if name.eq_ignore_ascii_case(b"transfer-encoding") {
+                chunked = value
+                    .rsplit(|b| *b == b',')
+                    .next()
+                    .map(|e| trim(e).eq_ignore_ascii_case(b"chunked"))
+                    .unwrap_or_default();
+            } else if name.eq_ignore_ascii_case(b"upgrade") {
+                upgrade = true;
+            }
+            if self
+                .rewrite
+                .headers
+                .iter()
+                .any(|(n, _)| name.eq_ignore_ascii_case(n.as_bytes()))
+            {
+                skipping = true;
+                continue;
+            }
+            rewritten.extend_from_slice(line);
+        }
+
+        if let (Some(host), false) = (&self.rewrite.host, host_replaced) {
+            rewritten.extend_from_slice(format!("Host: {host}\r\n").as_bytes());
+        }
+        for (name, value) in &self.rewrite.headers {
+            rewritten.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        rewritten.extend_from_slice(b"\r\n");
+
+        let state = if upgrade {
+            State::Passthrough
+        } else if chunked {
+            State::ChunkSize
+        } else if content_length > 0 {
+            State::Body(content_length)
+        } else {
+            State::Head
+        };
+        Ok((rewritten, state))
+    }
+}
+
+impl PortalInterceptor for HttpRequestRewriter {
+    fn intercept(&mut self, chunk: &[u8]) -> Result<Vec<u8>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut output = Vec::with_capacity(self.buffer.len());
+        loop {
+            match self.state {
+                State::Passthrough => {
+                    output.append(&mut self.buffer);
+                    break;
+                }
+                State::Body(remaining) | State::ChunkData(remaining) => {
+                    let length = remaining.min(self.buffer.len() as u64);
+                    output.extend(self.buffer.drain(..length as usize));
+                    self.state = match (self.state, remaining - length) {
+                        (State::Body(_), 0) => State::Head,
+                        (_, 0) => State::ChunkSize,
+                        (State::Body(_), remaining) => State::Body(remaining),
+                        (_, remaining) => State::ChunkData(remaining),
+                    };
+                    if remaining > length {
+                        break;
+                    }
+                }
+                State::Head => match find(&self.buffer, b"\r\n\r\n") {
+                    Some(end) => {
+                        let head: Vec<u8> = self.buffer.drain(..end + 4).collect();
+                        let (rewritten, state) = self.rewrite_head(&head)?;
+                        output.extend_from_slice(&rewritten);
+                        self.state = state;
+                    }
+                    None if self.buffer.len() > MAX_HTTP_HEAD_SIZE => {
+                        return Err(protocol("the connection did not send an HTTP request"));
+                    }
+                    None => break,
+                },
+                State::ChunkSize => match self.take_line()? {
+                    Some(line) => {
+                        let size = line
+                            .split(|b| *b == b';' || *b == b'\r')
+                            .next()
+                            .and_then(|s| core::str::from_utf8(s).ok())
+                            .and_then(|s| u64::from_str_radix(s.trim(), 16).ok())
+                            .ok_or_else(|| protocol("the HTTP request has an invalid chunk"))?;
+                        output.extend_from_slice(&line);
+                        self.state = if size == 0 {
+                            State::Trailers
+                        } else {
+                            State::ChunkData(size + 2)
+                        };
+                    }
+                    None => break,
+                },
+                State::Trailers => match self.take_line()? {
+                    Some(line) => {
+                        output.extend_from_slice(&line);
+                        if line == b"\r\n" {
+                            self.state = State::Head;
+                        }
+                    }
+                    None => break,
+                },
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Split a header line into its trimmed name and value
+fn split_header(line: &[u8]) -> (&[u8], &[u8]) {
+    match line.iter().position(|b| *b == b':') {
+        Some(colon) => (trim(&line[..colon]), trim(&line[colon + 1..])),
+        None => (trim(line), &[]),
+    }
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |i| i + 1);
+    &bytes[start..end]
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(Origin::Transport, Kind::Invalid, message)
+}
+
+fn protocol(message: &str) -> Error {
+    Error::new(Origin::Transport, Kind::Protocol, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn http_rewriter(host: Option<&str>, headers: &[(&str, &str)]) -> Box<dyn PortalInterceptor> {
+        HttpRewrite::new(
+            host.map(|h| h.to_string()),
+            headers
+                .iter()
+                .map(|(n, v)| (n.to_string(), v.to_string()))
+                .collect(),
+        )
+        .unwrap()
+        .create(PortalDirection::Request)
+        .unwrap()
+    }
+
+    #[test]
+    fn rewrite_the_host_and_inject_headers() {
+        let mut rewriter = http_rewriter(Some("backend:8080"), &[("X-Env", "prod")]);
+        let request = rewriter
+            .intercept(b"GET / HTTP/1.1\r\nHost: localhost\r\nx-env: dev\r\nAccept: */*\r\n\r\n")
+            .unwrap();
+        assert_eq!(
+            request,
+            b"GET / HTTP/1.1\r\nHost: backend:8080\r\nAccept: */*\r\nX-Env: prod\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn add_the_host_when_it_is_missing() {
+        let mut rewriter = http_rewriter(Some("backend"), &[]);
+        let request = rewriter.intercept(b"GET / HTTP/1.0\r\n\r\n").unwrap();
+        assert_eq!(request, b"GET / HTTP/1.0\r\nHost: backend\r\n\r\n");
+    }
+
+    #[test]
+    fn rewrite_requests_spanning_several_chunks() {
+        let mut rewriter = http_rewriter(Some("backend"), &[]);
+        assert!(rewriter
+            .intercept(b"POST /a HTTP/1.1\r\nHo")
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            rewriter
+                .intercept(b"st: localhost\r\nContent-Length: 10\r\n\r\n0123")
+                .unwrap(),
+            b"POST /a HTTP/1.1\r\nHost: backend\r\nContent-Length: 10\r\n\r\n0123"
+        );
+        // the body ends in the middle of the next chunk, which starts the next request
+        assert_eq!(
+            rewriter
+                .intercept(b"456789GET /b HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap(),
+            b"456789GET /b HTTP/1.1\r\nHost: backend\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn forward_chunked_bodies_unchanged() {
+        let mut rewriter = http_rewriter(Some("backend"), &[]);
+        let body = b"5\r\nHost:\r\n0\r\nX-Trailer: 1\r\n\r\n";
+        let mut request = b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        request.extend_from_slice(body);
+        request.extend_from_slice(b"GET / HTTP/1.1\r\n\r\n");
+
+        let mut rewritten = vec![];
+        for byte in request {
+            rewritten.extend(rewriter.intercept(&[byte]).unwrap());
+        }
+        let mut expected =
+            b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nHost: backend\r\n\r\n".to_vec();
+        expected.extend_from_slice(body);
+        expected.extend_from_slice(b"GET / HTTP/1.1\r\nHost: backend\r\n\r\n");
+        assert_eq!(rewritten, expected);
+    }
+
+    #[test]
+    fn forward_the_bytes_following_an_upgrade_unchanged() {
+        let mut rewriter = http_rewriter(None, &[("X-Env", "prod")]);
+        let request = rewriter
+            .intercept(b"GET /ws HTTP/1.1\r\nUpgrade: websocket\r\n\r\nGET / HTTP/1.1\r\n")
+            .unwrap();
+        assert_eq!(
+            request,
+            b"GET /ws HTTP/1.1\r\nUpgrade: websocket\r\nX-Env: prod\r\n\r\nGET / HTTP/1.1\r\n"
+        );
+    }
+
+    #[test]
+    fn refuse_connections_which_do_not_send_http() {
+        let mut rewriter = http_rewriter(Some("backend"), &[]);
+        assert!(rewriter.intercept(b"SSH-2.0-OpenSSH\r\n\r\n").is_err());
+
+        let mut rewriter = http_rewriter(Some("backend"), &[]);
+        assert!(rewriter.intercept(&[0u8; MAX_HTTP_HEAD_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn refuse_invalid_rewrites() {
+        assert!(HttpRewrite::new(Some("a b".to_string()), vec![]).is_err());
+        assert!(HttpRewrite::new(None, vec![("X Env".to_string(), "1".to_string())]).is_err());
+        assert!(HttpRewrite::new(None, vec![("X-Env".to_string(), "1\r\n".to_string())]).is_err());
+    }
+}
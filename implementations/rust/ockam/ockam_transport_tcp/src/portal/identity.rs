@@ -0,0 +1,316 @@
+use core::fmt::Debug;
+use ockam_core::compat::net::{IpAddr, Ipv6Addr, SocketAddr};
+use ockam_core::compat::sync::Arc;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Error, LocalInfo, Result};
+
+/// Type of the PROXY protocol v2 TLV containing the identifier of the peer.
+/// It is the first type of the range reserved for custom TLVs
+pub const PROXY_PROTOCOL_IDENTIFIER_TLV: u8 = 0xE0;
+
+/// Signature starting every PROXY protocol v2 header
+const PROXY_PROTOCOL_V2_SIGNATURE: [u8; 12] = [
+    0x0D, 0x0A, 0x0D, 0x0A, 0x00, 0x0D, 0x0A, 0x51, 0x55, 0x49, 0x54, 0x0A,
+];
+
+/// Maximum size of the head of the first HTTP request of a connection.
+/// A connection sending more bytes without ending the request headers is not HTTP
+pub(super) const MAX_HTTP_HEAD_SIZE: usize = 16 * 1024;
+
+/// Maximum length of a peer identifier passed to the target of an outlet
+const MAX_IDENTIFIER_LENGTH: usize = 256;
+
+/// Format used by an outlet to pass the identifier of the peer to its target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TcpOutletIdentityFormat {
+    /// Add a header with the given name to the first HTTP request of each connection.
+    /// A header with the same name sent by the peer in that request is removed
+    HttpHeader(String),
+    /// Send a PROXY protocol v2 header before the payload of each connection. The identifier
+    /// is sent in a TLV of type [`PROXY_PROTOCOL_IDENTIFIER_TLV`]
+    ProxyProtocolV2,
+}
+
+impl TcpOutletIdentityFormat {
+    /// Default name of the HTTP header containing the identifier of the peer
+    pub const DEFAULT_HTTP_HEADER: &'static str = "X-Ockam-Identifier";
+
+    /// Return true if `name` can be used as an HTTP header name
+    pub fn is_valid_http_header_name(name: &str) -> bool {
+        !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+    }
+}
+
+/// Find the identifier of the peer which created a portal connection
+/// in the local information of the first message sent by its inlet
+pub trait TcpPortalPeerIdentifier: Debug + Send + Sync + 'static {
+    /// Return the identifier of the peer, if it has been authenticated
+    fn peer_identifier(&self, local_info: &[LocalInfo]) -> Option<String>;
+}
+
+/// Identity forwarding for an outlet
+///
+/// The identifier of the peer which created a portal connection, for example the identity
+/// authenticated by a secure channel, is passed to the target of the outlet when the
+/// connection is established. Connections from unauthenticated peers are refused.
+#[derive(Debug, Clone)]
+pub struct TcpOutletIdentityForwarding {
+    format: TcpOutletIdentityFormat,
+    peer_identifier: Arc<dyn TcpPortalPeerIdentifier>,
+}
+
+impl TcpOutletIdentityForwarding {
+    /// Constructor. Fails if the format is an HTTP header with an invalid name
+    pub fn new(
+        format: TcpOutletIdentityFormat,
+        peer_identifier: Arc<dyn TcpPortalPeerIdentifier>,
+    ) -> Result<Self> {
+        if let TcpOutletIdentityFormat::HttpHeader(name) = &format {
+            if !TcpOutletIdentityFormat::is_valid_http_header_name(name) {
+                return Err(Error::new(
+                    Origin::Transport,
+                    Kind::Invalid,
+                    format!("'{name}' is not a valid HTTP header name"),
+                ));
+            }
+        }
+        Ok(Self {
+            format,
+            peer_identifier,
+        })
+    }
+
+    /// Format used to pass the identifier of the peer
+    pub fn format(&self) -> &TcpOutletIdentityFormat {
+        &self.format
+    }
+
+    /// Prepare the header passing the peer identifier to the target,
+    /// or return `None` if the peer has not been authenticated
+    pub(super) fn header_for(&self, local_info: &[LocalInfo]) -> Option<OutletIdentityHeader> {
+        let identifier = self
+            .peer_identifier
+            .peer_identifier(local_info)
+            .filter(|i| is_valid_identifier(i))?;
+        Some(match &self.format {
+            TcpOutletIdentityFormat::HttpHeader(name) => {
+                OutletIdentityHeader::Http(HttpHeaderInjector::new(name, &identifier))
+            }
+            TcpOutletIdentityFormat::ProxyProtocolV2 => {
+                OutletIdentityHeader::ProxyProtocolV2(identifier)
+            }
+        })
+    }
+}
+
+/// The identifier is written in headers, it must not contain separators
+fn is_valid_identifier(identifier: &str) -> bool {
+    !identifier.is_empty()
+        && identifier.len() <= MAX_IDENTIFIER_LENGTH
+        && identifier.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Header passing the peer identifier, which still has to be sent on a connection
+#[derive(Debug)]
+pub(super) enum OutletIdentityHeader {
+    /// Injected in the first HTTP request received from the inlet
+    Http(HttpHeaderInjector),
+    /// Sent as soon as the connection to the target is established
+    ProxyProtocolV2(String),
+}
+
+/// Build a PROXY protocol v2 header for a connection from `source` to `destination`,
+/// with the identifier of the peer in a TLV
+pub(super) fn proxy_protocol_v2_header(
+    source: SocketAddr,
+    destination: SocketAddr,
+    identifier: &str,
+) -> Vec<u8> {
+    let mut addresses = vec![];
+    let family = match (source.ip(), destination.ip()) {
+        (IpAddr::V4(source_ip), IpAddr::V4(destination_ip)) => {
+            addresses.extend_from_slice(&source_ip.octets());
+            addresses.extend_from_slice(&destination_ip.octets());
+            // TCP over IPv4
+            0x11
+        }
+        (source_ip, destination_ip) => {
+            addresses.extend_from_slice(&to_ipv6(source_ip).octets());
+            addresses.extend_from_slice(&to_ipv6(destination_ip).octets());
+            // TCP over IPv6
+            0x21
+        }
+    };
+    addresses.extend_from_slice(&source.port().to_be_bytes());
+    addresses.extend_from_slice(&destination.port().to_be_bytes());
+
+    let length = addresses.len() + 3 + identifier.len();
+    let mut header = Vec::with_capacity(PROXY_PROTOCOL_V2_SIGNATURE.len() + 4 + length);
+    header.extend_from_slice(&PROXY_PROTOCOL_V2_SIGNATURE);
+    // version 2, PROXY command
+    header.push(0x21);
+    header.push(family);
+    header.extend_from_slice(&(length as u16).to_be_bytes());
+    header.extend_from_slice(&addresses);
+    header.push(PROXY_PROTOCOL_IDENTIFIER_TLV);
+    header.extend_from_slice(&(identifier.len() as u16).to_be_bytes());
+    header.extend_from_slice(identifier.as_bytes());
+    header
+}
+
+fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+/// Buffer the head of the first HTTP request of a connection, until its headers are
+/// complete, then add the header containing the identifier of the peer
+#[derive(Debug)]
+pub(super) struct HttpHeaderInjector {
+    name: String,
+    identifier: String,
+    buffer: Vec<u8>,
+}
+
+impl HttpHeaderInjector {
+    fn new(name: &str, identifier: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            identifier: identifier.to_string(),
+            buffer: vec![],
+        }
+    }
+
+    /// Add bytes received from the inlet. Return the bytes to write to the target once the
+    /// request headers are complete, or `None` if more bytes are needed.
+    /// Fail if the bytes received so far can't be the head of an HTTP request
+    pub(super) fn push(&mut self, payload: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.buffer.extend_from_slice(payload);
+        match find(&self.buffer, b"\r\n\r\n") {
+            Some(end) => Ok(Some(self.inject(end))),
+            None if self.buffer.len() > MAX_HTTP_HEAD_SIZE => Err(Error::new(
+                Origin::Transport,
+                Kind::Protocol,
+                "the connection did not start with an HTTP request",
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Rewrite the buffered request, where `end` is the position of the "\r\n\r\n" separator
+    /// ending the headers. The request line is kept first, followed by the identity header
+    fn inject(&mut self, end: usize) -> Vec<u8> {
+        let buffer = core::mem::take(&mut self.buffer);
+        // the head keeps the end of its last line, the body starts with the empty line
+        let (head, body) = buffer.split_at(end + 2);
+        let mut lines = head.split_inclusive(|b| *b == b'\n');
+
+        let mut request = Vec::with_capacity(buffer.len() + self.name.len() + 64);
+        if let Some(request_line) = lines.next() {
+            request.extend_from_slice(request_line);
+        }
+        request.extend_from_slice(format!("{}: {}\r\n", self.name, self.identifier).as_bytes());
+        for line in lines {
+            // remove the headers which could impersonate another identity
+            if !self.is_identity_header(line) {
+                request.extend_from_slice(line);
+            }
+        }
+        request.extend_from_slice(body);
+        request
+    }
+
+    fn is_identity_header(&self, line: &[u8]) -> bool {
+        let name: Vec<u8> = line
+            .split(|b| *b == b':')
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .copied()
+            .filter(|b| !b.is_ascii_whitespace())
+            .collect();
+        name.eq_ignore_ascii_case(self.name.as_bytes())
+    }
+}
+
+pub(super) fn find(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+    bytes.windows(pattern.len()).position(|w| w == pattern)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inject_http_header_after_the_request_line() {
+        let mut injector = HttpHeaderInjector::new("X-Ockam-Identifier", "I123");
+        assert_eq!(injector.push(b"GET / HTTP/1.1\r\nHost: ").unwrap(), None);
+        let request = injector
+            .push(b"localhost\r\nx-ockam-identifier: Ifake\r\nAccept: */*\r\n\r\nbody")
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET / HTTP/1.1\r\nX-Ockam-Identifier: I123\r\nHost: localhost\r\nAccept: */*\r\n\r\nbody"
+        );
+    }
+
+    #[test]
+    fn inject_http_header_in_a_request_without_headers() {
+        let mut injector = HttpHeaderInjector::new("X-Ockam-Identifier", "I123");
+        let request = injector.push(b"GET / HTTP/1.1\r\n\r\n").unwrap().unwrap();
+        assert_eq!(
+            String::from_utf8(request).unwrap(),
+            "GET / HTTP/1.1\r\nX-Ockam-Identifier: I123\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn reject_a_connection_which_is_not_http() {
+        let mut injector = HttpHeaderInjector::new("X-Ockam-Identifier", "I123");
+        assert!(injector.push(&[0u8; MAX_HTTP_HEAD_SIZE + 1]).is_err());
+    }
+
+    #[test]
+    fn build_proxy_protocol_v2_header() {
+        let header = proxy_protocol_v2_header(
+            "127.0.0.1:5000".parse().unwrap(),
+            "10.0.0.1:80".parse().unwrap(),
+            "I123",
+        );
+        let mut expected = PROXY_PROTOCOL_V2_SIGNATURE.to_vec();
+        expected.extend_from_slice(&[0x21, 0x11, 0x00, 12 + 3 + 4]);
+        expected.extend_from_slice(&[127, 0, 0, 1, 10, 0, 0, 1, 0x13, 0x88, 0x00, 0x50]);
+        expected.extend_from_slice(&[PROXY_PROTOCOL_IDENTIFIER_TLV, 0x00, 0x04]);
+        expected.extend_from_slice(b"I123");
+        assert_eq!(header, expected);
+
+        // mixed address families are sent as IPv6 addresses
+        let header = proxy_protocol_v2_header(
+            "127.0.0.1:5000".parse().unwrap(),
+            "[::1]:80".parse().unwrap(),
+            "I123",
+        );
+        assert_eq!(header[13], 0x21);
+        assert_eq!(
+            u16::from_be_bytes([header[14], header[15]]) as usize,
+            36 + 3 + 4
+        );
+    }
+
+    #[test]
+    fn check_http_header_names() {
+        assert!(TcpOutletIdentityFormat::is_valid_http_header_name(
+            TcpOutletIdentityFormat::DEFAULT_HTTP_HEADER
+        ));
+        assert!(!TcpOutletIdentityFormat::is_valid_http_header_name(""));
+        assert!(!TcpOutletIdentityFormat::is_valid_http_header_name(
+            "X-Id: I1\r\nHost"
+        ));
+    }
+}
@@ -1,12 +1,13 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::{portal::TcpPortalWorker, TcpInletOptions, TcpRegistry};
+use core::time::Duration;
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::{async_trait, compat::boxed::Box};
 use ockam_core::{Address, Processor, Result, Route};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use tokio::net::TcpListener;
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, instrument, warn};
 
 /// A TCP Portal Inlet listen processor
 ///
@@ -44,8 +45,6 @@ impl TcpInletListenProcessor {
         addr: SocketAddr,
         options: TcpInletOptions,
     ) -> Result<(SocketAddr, Address)> {
-        let processor_address = Address::random_tagged("TcpInletListenProcessor");
-
         debug!("Binding TcpPortalListenerWorker to {}", addr);
         let inner = match TcpListener::bind(addr).await {
             Ok(addr) => addr,
@@ -54,6 +53,19 @@ impl TcpInletListenProcessor {
                 return Err(TransportError::from(err))?;
             }
         };
+        Self::start_with_listener(ctx, registry, outlet_listener_route, inner, options).await
+    }
+
+    /// Start a new `TcpInletListenProcessor` accepting the connections of an already bound listener
+    #[instrument(skip_all, name = "TcpInletListenProcessor::start_with_listener")]
+    pub(crate) async fn start_with_listener(
+        ctx: &Context,
+        registry: TcpRegistry,
+        outlet_listener_route: Route,
+        inner: TcpListener,
+        options: TcpInletOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        let processor_address = Address::random_tagged("TcpInletListenProcessor");
         let socket_addr = inner.local_addr().map_err(TransportError::from)?;
         let processor = Self::new(registry, inner, outlet_listener_route, options);
 
@@ -95,14 +107,40 @@ impl Processor for TcpInletListenProcessor {
         );
 
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+
+        // The listener stays bound while the inlet is paused, the clients are disconnected
+        if self.options.pause.is_paused() {
+            debug!(%peer, "the inlet is paused, resetting the connection");
+            let _ = stream.set_linger(Some(Duration::ZERO));
+            drop(stream);
+            return Ok(true);
+        }
+
+        if let Some(pre_check) = &self.options.pre_check {
+            let health = pre_check
+                .check(ctx, self.outlet_listener_route.clone())
+                .await;
+            if !health.is_healthy() {
+                warn!(%peer, ?health, "the far side of the inlet is unhealthy, resetting the connection");
+                // Closing the socket without lingering sends a reset to the client
+                let _ = stream.set_linger(Some(Duration::ZERO));
+                drop(stream);
+                return Ok(true);
+            }
+        }
+
         TcpPortalWorker::start_new_inlet(
             ctx,
             self.registry.clone(),
+            ctx.address(),
             stream,
             peer,
             outlet_listener_route,
             addresses,
             self.options.incoming_access_control.clone(),
+            self.options.packing,
+            self.options.bandwidth.clone(),
+            self.options.interceptor.clone(),
         )
         .await?;
 
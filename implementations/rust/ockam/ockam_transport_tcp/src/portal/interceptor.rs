@@ -0,0 +1,53 @@
+use core::fmt::Debug;
+use ockam_core::Result;
+
+use crate::PortalType;
+
+/// Direction of the bytes flowing through a portal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortalDirection {
+    /// Bytes sent by the client connected to the inlet, to the target of the outlet
+    Request,
+    /// Bytes sent by the target of the outlet, to the client connected to the inlet
+    Response,
+}
+
+impl PortalDirection {
+    /// Direction of the bytes read from the TCP connection of a portal end
+    pub(super) fn read_by(portal_type: PortalType) -> Self {
+        match portal_type {
+            PortalType::Inlet => PortalDirection::Request,
+            PortalType::Outlet => PortalDirection::Response,
+        }
+    }
+
+    /// Direction of the bytes written to the TCP connection of a portal end
+    pub(super) fn written_by(portal_type: PortalType) -> Self {
+        match portal_type {
+            PortalType::Inlet => PortalDirection::Response,
+            PortalType::Outlet => PortalDirection::Request,
+        }
+    }
+}
+
+/// Transformation of the bytes flowing in one direction of a portal connection
+///
+/// An interceptor is created for each connection and direction, and sees the bytes of that
+/// connection in order, as they are read from, or written to, the TCP connection.
+pub trait PortalInterceptor: Send + 'static {
+    /// Transform a chunk of bytes, and return the bytes to send in its place.
+    ///
+    /// The returned bytes can be longer or shorter than the chunk. An interceptor which needs
+    /// more bytes to transform a chunk, for example because a header spans several chunks,
+    /// keeps them and returns them with the next chunks.
+    ///
+    /// Returning an error closes the connection.
+    fn intercept(&mut self, chunk: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Creation of the interceptors of the connections of an inlet or an outlet
+pub trait PortalInterceptorFactory: Debug + Send + Sync + 'static {
+    /// Create the interceptor of a new connection for the given direction,
+    /// or return `None` if the bytes flowing in that direction are not intercepted
+    fn create(&self, direction: PortalDirection) -> Option<Box<dyn PortalInterceptor>>;
+}
@@ -1,11 +1,19 @@
 mod addresses;
+pub mod bandwidth;
+pub mod health;
+pub mod http_rewrite;
+pub mod identity;
 mod inlet_listener;
+pub mod interceptor;
 pub mod options;
 mod outlet_listener;
+pub mod pause;
+pub mod pool;
 mod portal_message;
 mod portal_receiver;
 mod portal_worker;
 
+pub use addresses::PortalType;
 pub(crate) use inlet_listener::*;
 pub(crate) use outlet_listener::*;
 pub use portal_message::*;
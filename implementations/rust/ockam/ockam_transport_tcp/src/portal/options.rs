@@ -1,12 +1,65 @@
 use crate::portal::addresses::Addresses;
+use crate::{
+    PortalInterceptorFactory, TcpInletPreCheck, TcpOutletConnectionPool, TcpOutletHealthProbe,
+    TcpOutletIdentityForwarding, TcpPortalBandwidthLimiter, TcpPortalPause, MAX_PAYLOAD_SIZE,
+};
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControls};
 use ockam_core::{Address, AllowAll, IncomingAccessControl};
 
+/// Packing of small writes into fewer portal messages
+///
+/// Bytes read from the TCP connection are buffered for up to `delay`, or until `threshold`
+/// bytes are available, before being sent as a single portal message. Buffered bytes are
+/// always flushed before a disconnection is sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TcpPortalPacking {
+    pub(super) delay: Duration,
+    pub(super) threshold: usize,
+}
+
+impl TcpPortalPacking {
+    /// Default delay to wait for more bytes before sending a portal message
+    pub const DEFAULT_DELAY: Duration = Duration::from_millis(5);
+
+    /// Default amount of bytes that triggers sending a portal message without waiting
+    pub const DEFAULT_THRESHOLD: usize = 1024;
+
+    /// Constructor. The threshold is capped to [`MAX_PAYLOAD_SIZE`]
+    pub fn new(delay: Duration, threshold: usize) -> Self {
+        Self {
+            delay,
+            threshold: threshold.clamp(1, MAX_PAYLOAD_SIZE),
+        }
+    }
+
+    /// Maximum time to wait for more bytes
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Amount of bytes that triggers sending a portal message without waiting
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+}
+
+impl Default for TcpPortalPacking {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_DELAY, Self::DEFAULT_THRESHOLD)
+    }
+}
+
 /// Trust Options for an Inlet
 #[derive(Debug)]
 pub struct TcpInletOptions {
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) packing: Option<TcpPortalPacking>,
+    pub(super) bandwidth: Option<TcpPortalBandwidthLimiter>,
+    pub(super) pre_check: Option<TcpInletPreCheck>,
+    pub(super) interceptor: Option<Arc<dyn PortalInterceptorFactory>>,
+    pub(super) pause: TcpPortalPause,
 }
 
 impl TcpInletOptions {
@@ -14,9 +67,49 @@ impl TcpInletOptions {
     pub fn new() -> Self {
         Self {
             incoming_access_control: Arc::new(AllowAll),
+            packing: None,
+            bandwidth: None,
+            pre_check: None,
+            interceptor: None,
+            pause: TcpPortalPause::new(),
         }
     }
 
+    /// Pack small writes on the inlet connections into fewer portal messages.
+    /// Disabled by default
+    pub fn with_packing(mut self, packing: TcpPortalPacking) -> Self {
+        self.packing = Some(packing);
+        self
+    }
+
+    /// Limit the bandwidth used to read from the inlet connections.
+    /// The limiter is shared by all the connections of the inlet
+    pub fn with_bandwidth_limiter(mut self, bandwidth: TcpPortalBandwidthLimiter) -> Self {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+
+    /// Check the health of the outlet when a local client connects, and reset the
+    /// connection if the outlet or its target can't be reached. Disabled by default
+    pub fn with_pre_check(mut self, pre_check: TcpInletPreCheck) -> Self {
+        self.pre_check = Some(pre_check);
+        self
+    }
+
+    /// Transform the bytes of the inlet connections with the interceptors created by
+    /// the given factory. Disabled by default
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn PortalInterceptorFactory>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Close the connections accepted while the given pause state is paused,
+    /// instead of a pause state which is never paused
+    pub fn with_pause(mut self, pause: TcpPortalPause) -> Self {
+        self.pause = pause;
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -62,6 +155,13 @@ impl Default for TcpInletOptions {
 pub struct TcpOutletOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(super) incoming_access_control: Arc<dyn IncomingAccessControl>,
+    pub(super) packing: Option<TcpPortalPacking>,
+    pub(super) bandwidth: Option<TcpPortalBandwidthLimiter>,
+    pub(super) pool: Option<TcpOutletConnectionPool>,
+    pub(super) health_probe: TcpOutletHealthProbe,
+    pub(super) identity_forwarding: Option<TcpOutletIdentityForwarding>,
+    pub(super) interceptor: Option<Arc<dyn PortalInterceptorFactory>>,
+    pub(super) pause: TcpPortalPause,
 }
 
 impl TcpOutletOptions {
@@ -70,9 +170,69 @@ impl TcpOutletOptions {
         Self {
             consumer: vec![],
             incoming_access_control: Arc::new(AllowAll),
+            packing: None,
+            bandwidth: None,
+            pool: None,
+            health_probe: TcpOutletHealthProbe::default(),
+            identity_forwarding: None,
+            interceptor: None,
+            pause: TcpPortalPause::new(),
         }
     }
 
+    /// Pack small writes on the outlet connections into fewer portal messages.
+    /// Disabled by default
+    pub fn with_packing(mut self, packing: TcpPortalPacking) -> Self {
+        self.packing = Some(packing);
+        self
+    }
+
+    /// Limit the bandwidth used to read from the outlet connections.
+    /// The limiter is shared by all the connections of the outlet
+    pub fn with_bandwidth_limiter(mut self, bandwidth: TcpPortalBandwidthLimiter) -> Self {
+        self.bandwidth = Some(bandwidth);
+        self
+    }
+
+    /// Connect to the outlet peer in advance, and hand the established connections
+    /// to the new portal connections. Disabled by default
+    pub fn with_connection_pool(mut self, pool: TcpOutletConnectionPool) -> Self {
+        self.pool = Some(pool);
+        self
+    }
+
+    /// Probe the target with the given timeout and cache duration when answering the
+    /// health checks of the inlets, instead of the defaults of [`TcpOutletHealthProbe`]
+    pub fn with_health_probe(mut self, health_probe: TcpOutletHealthProbe) -> Self {
+        self.health_probe = health_probe;
+        self
+    }
+
+    /// Pass the identifier of the peer which created each connection to the target of the
+    /// outlet. Connections from peers without an identifier are refused. Disabled by default
+    pub fn with_identity_forwarding(
+        mut self,
+        identity_forwarding: TcpOutletIdentityForwarding,
+    ) -> Self {
+        self.identity_forwarding = Some(identity_forwarding);
+        self
+    }
+
+    /// Transform the bytes of the outlet connections with the interceptors created by
+    /// the given factory. The bytes written to the target are intercepted before the
+    /// identity of the peer is added. Disabled by default
+    pub fn with_interceptor(mut self, interceptor: Arc<dyn PortalInterceptorFactory>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Refuse the new portal connections while the given pause state is paused,
+    /// instead of a pause state which is never paused
+    pub fn with_pause(mut self, pause: TcpPortalPause) -> Self {
+        self.pause = pause;
+        self
+    }
+
     /// Set Incoming Access Control
     pub fn with_incoming_access_control_impl(
         mut self,
@@ -1,10 +1,13 @@
 use crate::portal::addresses::{Addresses, PortalType};
 use crate::{portal::TcpPortalWorker, PortalMessage, TcpOutletOptions, TcpRegistry};
-use ockam_core::{async_trait, Address, DenyAll, NeutralMessage, Result, Routed, Worker};
+use ockam_core::{
+    async_trait, Address, AllowOnwardAddress, DenyAll, NeutralMessage, Result, Route, Routed,
+    Worker,
+};
 use ockam_node::{Context, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use std::net::SocketAddr;
-use tracing::{debug, instrument};
+use tracing::{debug, instrument, warn};
 
 /// A TCP Portal Outlet listen worker
 ///
@@ -51,6 +54,53 @@ impl TcpOutletListenWorker {
     }
 }
 
+impl TcpOutletListenWorker {
+    /// Answer the health check of an inlet with the reachability of the target.
+    /// A paused outlet is reported as unable to reach its target
+    #[instrument(skip_all)]
+    async fn handle_portal_ping(&mut self, ctx: &Context, return_route: Route) -> Result<()> {
+        let upstream_reachable = !self.options.pause.is_paused()
+            && self.options.health_probe.is_reachable(self.peer).await;
+        Self::reply(
+            ctx,
+            return_route,
+            PortalMessage::PortalPingReply(upstream_reachable),
+        )
+        .await?;
+        debug!(%upstream_reachable, "Tcp Outlet at {} answered a health check", ctx.address());
+        Ok(())
+    }
+
+    /// Refuse a new portal connection while the outlet is paused. The inlet closes its local
+    /// connection when it receives a disconnection instead of the pong, and its client can
+    /// connect again later
+    #[instrument(skip_all)]
+    async fn refuse_connection(&self, ctx: &Context, return_route: Route) -> Result<()> {
+        Self::reply(ctx, return_route, PortalMessage::Disconnect).await?;
+        debug!(
+            "Tcp Outlet at {} is paused, refused a connection",
+            ctx.address()
+        );
+        Ok(())
+    }
+
+    /// The listener can't send messages: the answer is sent from a temporary address
+    /// which can only send messages to the next hop of the return route
+    async fn reply(ctx: &Context, return_route: Route, message: PortalMessage<'_>) -> Result<()> {
+        let next = return_route.next()?.clone();
+        let child_ctx = ctx
+            .new_detached(
+                Address::random_tagged("TcpOutletListenWorker.reply"),
+                DenyAll,
+                AllowOnwardAddress(next),
+            )
+            .await?;
+        child_ctx
+            .send(return_route, message.to_neutral_message()?)
+            .await
+    }
+}
+
 #[async_trait]
 impl Worker for TcpOutletListenWorker {
     type Context = Context;
@@ -60,6 +110,10 @@ impl Worker for TcpOutletListenWorker {
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.add_outlet_listener_worker(&ctx.address());
 
+        if let Some(pool) = &self.options.pool {
+            pool.start(self.peer);
+        }
+
         Ok(())
     }
 
@@ -67,6 +121,10 @@ impl Worker for TcpOutletListenWorker {
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_outlet_listener_worker(&ctx.address());
 
+        if let Some(pool) = &self.options.pool {
+            pool.stop();
+        }
+
         Ok(())
     }
 
@@ -78,25 +136,55 @@ impl Worker for TcpOutletListenWorker {
     ) -> Result<()> {
         let return_route = msg.return_route();
         let src_addr = msg.src_addr();
+        let local_info = msg.local_message().local_info();
         let body = msg.into_body()?.into_vec();
         let msg = PortalMessage::decode(&body)?;
 
-        if !matches!(msg, PortalMessage::Ping) {
-            return Err(TransportError::Protocol)?;
+        match msg {
+            PortalMessage::Ping => {}
+            PortalMessage::PortalPing => return self.handle_portal_ping(ctx, return_route).await,
+            _ => return Err(TransportError::Protocol)?,
+        }
+
+        if self.options.pause.is_paused() {
+            return self.refuse_connection(ctx, return_route).await;
         }
 
+        let identity_header = match &self.options.identity_forwarding {
+            Some(identity_forwarding) => match identity_forwarding.header_for(&local_info) {
+                Some(identity_header) => Some(identity_header),
+                None => {
+                    warn!(
+                        "Tcp Outlet at {} refused a connection from a peer without identifier",
+                        ctx.address()
+                    );
+                    return Ok(());
+                }
+            },
+            None => None,
+        };
+
         let addresses = Addresses::generate(PortalType::Outlet);
 
         self.options
             .setup_flow_control_for_outlet(ctx.flow_controls(), &addresses, &src_addr);
 
+        // Use a connection established in advance if there is one
+        let stream = self.options.pool.as_ref().and_then(|pool| pool.take());
+
         TcpPortalWorker::start_new_outlet(
             ctx,
             self.registry.clone(),
+            ctx.address(),
+            stream,
             self.peer,
             return_route.clone(),
             addresses.clone(),
             self.options.incoming_access_control.clone(),
+            self.options.packing,
+            self.options.bandwidth.clone(),
+            identity_header,
+            self.options.interceptor.clone(),
         )
         .await?;
 
@@ -0,0 +1,79 @@
+use core::fmt::{Debug, Formatter};
+use ockam_core::compat::sync::{Arc, Mutex};
+
+/// Pause of the new connections of a TCP portal
+///
+/// A paused inlet keeps its listener bound, but closes the connections it accepts right away.
+/// A paused outlet refuses the new portal connections requested by the inlets, which close
+/// the corresponding local connections: the clients can connect again once the outlet resumes.
+/// The existing connections are not affected by a pause.
+///
+/// Clones share the same state, which allows the portal to be paused and resumed while it is
+/// running, and the state to be kept when an inlet is re-created.
+#[derive(Clone, Default)]
+pub struct TcpPortalPause {
+    /// Unix timestamp, in seconds, of the pause, if the portal is paused
+    paused_at: Arc<Mutex<Option<u64>>>,
+}
+
+impl TcpPortalPause {
+    /// Create a pause state for a portal which accepts new connections
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new connections. Return false if the portal was already paused
+    pub fn pause(&self) -> bool {
+        let mut paused_at = self.paused_at.lock().unwrap();
+        if paused_at.is_some() {
+            return false;
+        }
+        *paused_at = Some(ockam_core::compat::time::now().unwrap_or_default());
+        true
+    }
+
+    /// Accept new connections again. Return false if the portal was not paused
+    pub fn resume(&self) -> bool {
+        self.paused_at.lock().unwrap().take().is_some()
+    }
+
+    /// Return true if new connections are refused
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.lock().unwrap().is_some()
+    }
+
+    /// Unix timestamp, in seconds, of the pause, if the portal is paused
+    pub fn paused_at(&self) -> Option<u64> {
+        *self.paused_at.lock().unwrap()
+    }
+}
+
+impl Debug for TcpPortalPause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TcpPortalPause")
+            .field("paused_at", &self.paused_at())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clones_share_the_pause_state() {
+        let pause = TcpPortalPause::new();
+        let clone = pause.clone();
+        assert!(!clone.is_paused());
+
+        assert!(pause.pause());
+        assert!(!pause.pause());
+        assert!(clone.is_paused());
+        assert!(clone.paused_at().is_some());
+
+        assert!(clone.resume());
+        assert!(!clone.resume());
+        assert!(!pause.is_paused());
+        assert_eq!(pause.paused_at(), None);
+    }
+}
@@ -0,0 +1,247 @@
+use core::fmt::{Debug, Formatter};
+use core::mem::MaybeUninit;
+use core::time::Duration;
+use ockam_core::compat::backoff::Backoff;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::sync::{Arc, Mutex};
+use socket2::SockRef;
+use std::io::ErrorKind;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Pool of connections established in advance by a TCP outlet
+///
+/// Without a pool, the outlet connects to its peer when a new portal connection is created,
+/// which adds the latency of a TCP handshake to the first bytes sent by the inlet side.
+/// With a pool, up to `size` connections are kept ready and handed to the new portal
+/// connections, then replaced in the background.
+///
+/// Idle connections are closed after an idle timeout, and checked before being handed out,
+/// so that connections closed by the peer are not used.
+/// Clones share the same state, which allows the statistics of the pool to be read while the
+/// outlet is running.
+#[derive(Clone)]
+pub struct TcpOutletConnectionPool {
+    size: usize,
+    state: Arc<Mutex<PoolState>>,
+    refill: Arc<Notify>,
+}
+
+impl TcpOutletConnectionPool {
+    /// Time after which an unused connection is closed
+    pub const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+    /// Interval between two health checks of the idle connections
+    const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Default delays before connecting again when the peer could not be reached
+    pub const DEFAULT_RECONNECT_BACKOFF: Backoff =
+        Backoff::new(Duration::from_secs(1), Duration::from_secs(30)).with_jitter(true);
+
+    /// Create a pool keeping up to `size` connections ready.
+    /// A pool of size 0 never establishes connections in advance
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            state: Arc::new(Mutex::new(PoolState {
+                idle_timeout: Self::DEFAULT_IDLE_TIMEOUT,
+                reconnect_backoff: Self::DEFAULT_RECONNECT_BACKOFF,
+                connections: VecDeque::new(),
+                task: None,
+                stats: TcpOutletPoolStats::default(),
+            })),
+            refill: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Close the unused connections after a given time instead of [`Self::DEFAULT_IDLE_TIMEOUT`]
+    pub fn with_idle_timeout(self, idle_timeout: Duration) -> Self {
+        self.state.lock().unwrap().idle_timeout = idle_timeout;
+        self
+    }
+
+    /// Wait between the connection attempts as specified by `backoff` when the peer can't be
+    /// reached, instead of [`Self::DEFAULT_RECONNECT_BACKOFF`].
+    /// The maximum number of attempts is ignored: the pool keeps trying to connect
+    pub fn with_reconnect_backoff(self, backoff: Backoff) -> Self {
+        self.state.lock().unwrap().reconnect_backoff = backoff;
+        self
+    }
+
+    /// Maximum number of connections established in advance
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Current statistics of the pool
+    pub fn stats(&self) -> TcpOutletPoolStats {
+        let state = self.state.lock().unwrap();
+        TcpOutletPoolStats {
+            size: self.size,
+            available: state.connections.len(),
+            ..state.stats.clone()
+        }
+    }
+
+    /// Start establishing connections to the outlet peer in the background
+    pub(crate) fn start(&self, peer: SocketAddr) {
+        if self.size == 0 {
+            return;
+        }
+        let pool = self.clone();
+        let task = tokio::spawn(async move { pool.maintain(peer).await });
+        if let Some(previous) = self.state.lock().unwrap().task.replace(task) {
+            previous.abort();
+        }
+    }
+
+    /// Stop establishing connections and close the idle ones
+    pub(crate) fn stop(&self) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(task) = state.task.take() {
+            task.abort();
+        }
+        state.connections.clear();
+    }
+
+    /// Take a healthy connection from the pool, if there is one.
+    /// The connection is replaced in the background
+    pub(crate) fn take(&self) -> Option<TcpStream> {
+        let stream = {
+            let mut state = self.state.lock().unwrap();
+            let idle_timeout = state.idle_timeout;
+            let mut stream = None;
+            while let Some(connection) = state.connections.pop_front() {
+                if connection.is_usable(idle_timeout) {
+                    stream = Some(connection.stream);
+                    break;
+                }
+                state.stats.evicted += 1;
+            }
+            match stream {
+                Some(_) => state.stats.hits += 1,
+                None => state.stats.misses += 1,
+            }
+            stream
+        };
+        self.refill.notify_one();
+        stream
+    }
+
+    /// Keep the pool full, and regularly evict the stale connections
+    async fn maintain(self, peer: SocketAddr) {
+        let backoff = self.state.lock().unwrap().reconnect_backoff;
+        let mut reconnect_delays = backoff.delays();
+        loop {
+            self.evict_stale_connections();
+
+            let mut failed = false;
+            while self.missing_connections() > 0 {
+                match TcpStream::connect(peer).await {
+                    Ok(stream) => {
+                        let mut state = self.state.lock().unwrap();
+                        state.stats.established += 1;
+                        state.connections.push_back(PooledConnection {
+                            stream,
+                            established_at: Instant::now(),
+                        });
+                    }
+                    Err(e) => {
+                        warn!(%peer, "the outlet pool could not connect to its peer: {e}");
+                        failed = true;
+                        break;
+                    }
+                }
+            }
+            if failed {
+                let delay = reconnect_delays.next_delay().unwrap_or(backoff.max_delay());
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+            reconnect_delays.reset();
+
+            // wait for a connection to be taken, or for the next health check
+            tokio::select! {
+                _ = self.refill.notified() => {}
+                _ = tokio::time::sleep(Self::HEALTH_CHECK_INTERVAL) => {}
+            }
+        }
+    }
+
+    fn missing_connections(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        self.size.saturating_sub(state.connections.len())
+    }
+
+    fn evict_stale_connections(&self) {
+        let mut state = self.state.lock().unwrap();
+        let idle_timeout = state.idle_timeout;
+        let before = state.connections.len();
+        state
+            .connections
+            .retain(|connection| connection.is_usable(idle_timeout));
+        let evicted = before - state.connections.len();
+        if evicted > 0 {
+            debug!("evicted {evicted} stale connections from the outlet pool");
+            state.stats.evicted += evicted as u64;
+        }
+    }
+}
+
+impl Debug for TcpOutletConnectionPool {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TcpOutletConnectionPool")
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// Statistics of a [`TcpOutletConnectionPool`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TcpOutletPoolStats {
+    /// Maximum number of connections established in advance
+    pub size: usize,
+    /// Number of connections currently ready to be used
+    pub available: usize,
+    /// Number of portal connections which used a connection from the pool
+    pub hits: u64,
+    /// Number of portal connections which had to connect to the peer
+    pub misses: u64,
+    /// Number of connections closed because they were idle for too long or closed by the peer
+    pub evicted: u64,
+    /// Number of connections established by the pool
+    pub established: u64,
+}
+
+struct PoolState {
+    idle_timeout: Duration,
+    reconnect_backoff: Backoff,
+    connections: VecDeque<PooledConnection>,
+    task: Option<JoinHandle<()>>,
+    stats: TcpOutletPoolStats,
+}
+
+struct PooledConnection {
+    stream: TcpStream,
+    established_at: Instant,
+}
+
+impl PooledConnection {
+    /// A connection can be used if it has not been idle for too long and if it is still open.
+    /// The peer may have sent some data already, which stays in the socket buffer
+    fn is_usable(&self, idle_timeout: Duration) -> bool {
+        if self.established_at.elapsed() >= idle_timeout {
+            return false;
+        }
+        let mut buffer = [MaybeUninit::<u8>::uninit(); 1];
+        match SockRef::from(&self.stream).peek(&mut buffer) {
+            Ok(0) => false,
+            Ok(_) => true,
+            Err(e) => e.kind() == ErrorKind::WouldBlock,
+        }
+    }
+}
@@ -15,6 +15,10 @@ pub enum PortalMessage<'de> {
     Disconnect,
     /// Message with binary payload and packet counter
     Payload(&'de [u8], Option<u16>),
+    /// Health check that an Inlet sends to the Outlet listener
+    PortalPing,
+    /// Answer of the Outlet listener to a health check, true if its target is reachable
+    PortalPingReply(bool),
 }
 
 impl<'de> PortalMessage<'de> {
@@ -53,6 +57,12 @@ impl<'de> PortalMessage<'de> {
                     None
                 }
             }
+            4 => Some(PortalMessage::PortalPing),
+            5 => match slice.get(index)? {
+                0 => Some(PortalMessage::PortalPingReply(false)),
+                1 => Some(PortalMessage::PortalPingReply(true)),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -92,6 +102,10 @@ impl PortalMessage<'_> {
                 }
                 Ok(vec)
             }
+            PortalMessage::PortalPing => Ok(vec![4]),
+            PortalMessage::PortalPingReply(upstream_reachable) => {
+                Ok(vec![5, upstream_reachable as u8])
+            }
         }
     }
 }
@@ -171,6 +185,28 @@ mod test {
         }
     }
 
+    #[test]
+    fn health_check_messages_can_be_decoded() {
+        let encoded = PortalMessage::encode(PortalMessage::PortalPing).unwrap();
+        assert_eq!(
+            PortalMessage::decode(&encoded).unwrap(),
+            PortalMessage::PortalPing
+        );
+
+        for upstream_reachable in [true, false] {
+            let encoded =
+                PortalMessage::encode(PortalMessage::PortalPingReply(upstream_reachable)).unwrap();
+            assert_eq!(
+                PortalMessage::decode(&encoded).unwrap(),
+                PortalMessage::PortalPingReply(upstream_reachable)
+            );
+        }
+
+        // an older outlet can't decode a health check
+        let encoded = PortalMessage::encode(PortalMessage::PortalPing).unwrap();
+        assert!(PortalMessageV1::decode(&encoded).is_err());
+    }
+
     #[ignore]
     #[test]
     fn newer_message_can_be_encoded() {
@@ -1,5 +1,9 @@
 use crate::portal::portal_message::MAX_PAYLOAD_SIZE;
-use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::{
+    PortalInterceptor, PortalInternalMessage, PortalMessage, TcpPortalBandwidthLimiter,
+    TcpPortalConnectionInfo, TcpPortalPacking, TcpRegistry,
+};
+use ockam_core::compat::boxed::Box;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{
     async_trait, Encodable, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
@@ -8,8 +12,9 @@ use ockam_core::{route, Address, Processor, Result};
 use ockam_node::Context;
 use opentelemetry::global;
 use opentelemetry::trace::Tracer;
+use tokio::time::{timeout_at, Instant};
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
-use tracing::{error, instrument, warn};
+use tracing::{debug, error, instrument, warn};
 
 /// A TCP Portal receiving message processor
 ///
@@ -18,30 +23,100 @@ use tracing::{error, instrument, warn};
 /// [`TcpPortalWorker::start_receiver`](crate::TcpPortalWorker::start_receiver)
 pub(crate) struct TcpPortalRecvProcessor {
     registry: TcpRegistry,
+    connection: TcpPortalConnectionInfo,
     buf: Vec<u8>,
     read_half: OwnedReadHalf,
     sender_address: Address,
     onward_route: Route,
     payload_packet_counter: u16,
+    packing: Option<TcpPortalPacking>,
+    bandwidth: Option<TcpPortalBandwidthLimiter>,
+    interceptor: Option<Box<dyn PortalInterceptor>>,
 }
 
 impl TcpPortalRecvProcessor {
     /// Create a new `TcpPortalRecvProcessor`
     pub fn new(
         registry: TcpRegistry,
+        connection: TcpPortalConnectionInfo,
         read_half: OwnedReadHalf,
         sender_address: Address,
         onward_route: Route,
+        packing: Option<TcpPortalPacking>,
+        bandwidth: Option<TcpPortalBandwidthLimiter>,
+        interceptor: Option<Box<dyn PortalInterceptor>>,
     ) -> Self {
         Self {
             registry,
+            connection,
             buf: Vec::with_capacity(MAX_PAYLOAD_SIZE),
             read_half,
             sender_address,
             onward_route,
             payload_packet_counter: 0,
+            packing,
+            bandwidth,
+            interceptor,
         }
     }
+
+    /// Read from the Tcp stream into the buffer.
+    /// When the bandwidth is limited, wait for the budget to be available before reading,
+    /// so that the local peer is slowed down by the TCP backpressure
+    async fn read(
+        read_half: &mut OwnedReadHalf,
+        buf: &mut Vec<u8>,
+        bandwidth: Option<&TcpPortalBandwidthLimiter>,
+    ) -> std::io::Result<usize> {
+        match bandwidth {
+            Some(bandwidth) => {
+                let budget = bandwidth.acquire().await;
+                read_half.take(budget as u64).read_buf(buf).await
+            }
+            None => read_half.read_buf(buf).await,
+        }
+    }
+
+    /// Keep reading into the buffer until the packing threshold is reached,
+    /// the packing delay elapses, or the connection is closed.
+    /// Return false if the connection was closed or failed
+    async fn read_packed(&mut self, packing: TcpPortalPacking) -> bool {
+        let deadline = Instant::now() + packing.delay();
+        while self.buf.len() < packing.threshold() {
+            match timeout_at(deadline, self.read_half.read_buf(&mut self.buf)).await {
+                // The delay elapsed, send what we have
+                Err(_) => return true,
+                Ok(Ok(0)) => return false,
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => {
+                    error!("Tcp Portal connection read failed with error: {}", err);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    async fn send_payload(
+        &mut self,
+        ctx: &Context,
+        tracing_context: &OpenTelemetryContext,
+    ) -> Result<()> {
+        // Loop just in case buf was extended (should not happen though)
+        for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
+            let msg = LocalMessage::new()
+                .with_tracing_context(tracing_context.clone())
+                .with_onward_route(self.onward_route.clone())
+                .with_return_route(route![self.sender_address.clone()])
+                .with_payload(
+                    PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
+                );
+
+            self.payload_packet_counter += 1;
+            ctx.forward(msg).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -67,7 +142,17 @@ impl Processor for TcpPortalRecvProcessor {
     async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
         self.buf.clear();
 
-        let _len = match self.read_half.read_buf(&mut self.buf).await {
+        // A connection closed on request is handled as if the local peer had dropped it,
+        // so that both the portal worker and the other end of the portal are notified
+        let read = tokio::select! {
+            read = Self::read(&mut self.read_half, &mut self.buf, self.bandwidth.as_ref()) => read,
+            _ = self.connection.closed() => {
+                debug!("Tcp Portal connection {} was closed on request", self.connection.id());
+                Ok(0)
+            }
+        };
+
+        let _len = match read {
             Ok(len) => len,
             Err(err) => {
                 error!("Tcp Portal connection read failed with error: {}", err);
@@ -75,12 +160,47 @@ impl Processor for TcpPortalRecvProcessor {
             }
         };
 
+        let mut is_connected = !self.buf.is_empty();
+        if let (true, Some(packing)) = (is_connected, self.packing) {
+            is_connected = self.read_packed(packing).await;
+        }
+
+        if let Some(bandwidth) = &self.bandwidth {
+            bandwidth.consume(self.buf.len());
+        }
+        self.connection.add_bytes_read(self.buf.len());
+
+        // The interceptor can keep some bytes until the next reads. When it fails,
+        // the connection is closed without sending the bytes it refused
+        if let Some(interceptor) = &mut self.interceptor {
+            match interceptor.intercept(&self.buf) {
+                Ok(bytes) => {
+                    self.buf.clear();
+                    self.buf.extend_from_slice(&bytes);
+                }
+                Err(err) => {
+                    warn!(
+                        "Tcp Portal connection {} was closed by its interceptor: {}",
+                        self.connection.id(),
+                        err
+                    );
+                    self.buf.clear();
+                    is_connected = false;
+                }
+            }
+        }
+
         let tracer = global::tracer(OCKAM_TRACER_NAME);
         let tracing_context = tracer.in_span("TcpPortalRecvProcessor::forward_message", |cx| {
             OpenTelemetryContext::inject(&cx)
         });
 
-        if self.buf.is_empty() {
+        // Bytes buffered before the connection was closed are sent before the disconnection
+        if !self.buf.is_empty() {
+            self.send_payload(ctx, &tracing_context).await?;
+        }
+
+        if !is_connected {
             // Notify Sender that connection was closed
             ctx.set_tracing_context(tracing_context.clone());
             if let Err(err) = ctx
@@ -108,20 +228,6 @@ impl Processor for TcpPortalRecvProcessor {
             return Ok(false);
         }
 
-        // Loop just in case buf was extended (should not happen though)
-        for chunk in self.buf.chunks(MAX_PAYLOAD_SIZE) {
-            let msg = LocalMessage::new()
-                .with_tracing_context(tracing_context.clone())
-                .with_onward_route(self.onward_route.clone())
-                .with_return_route(route![self.sender_address.clone()])
-                .with_payload(
-                    PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
-                );
-
-            self.payload_packet_counter += 1;
-            ctx.forward(msg).await?;
-        }
-
         Ok(true)
     }
 }
@@ -1,12 +1,17 @@
 use crate::portal::addresses::{Addresses, PortalType};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
+use crate::portal::identity::{proxy_protocol_v2_header, OutletIdentityHeader};
+use crate::{
+    portal::TcpPortalRecvProcessor, PortalDirection, PortalInterceptor, PortalInterceptorFactory,
+    PortalInternalMessage, PortalMessage, TcpPortalBandwidthLimiter, TcpPortalConnectionInfo,
+    TcpPortalPacking, TcpRegistry,
+};
 use core::time::Duration;
-use ockam_core::compat::{boxed::Box, net::SocketAddr, sync::Arc};
+use ockam_core::compat::{borrow::Cow, boxed::Box, net::SocketAddr, sync::Arc};
 use ockam_core::{
     async_trait, AllowAll, AllowOnwardAddresses, AllowSourceAddress, Decodable, DenyAll,
     IncomingAccessControl, Mailbox, Mailboxes,
 };
-use ockam_core::{Any, Result, Route, Routed, Worker};
+use ockam_core::{Address, Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
 use ockam_transport_core::TransportError;
 use tokio::io::AsyncWriteExt;
@@ -44,53 +49,83 @@ pub(crate) struct TcpPortalWorker {
     remote_route: Option<Route>,
     is_disconnecting: bool,
     portal_type: PortalType,
+    connection: TcpPortalConnectionInfo,
     last_received_packet_counter: u16,
+    packing: Option<TcpPortalPacking>,
+    bandwidth: Option<TcpPortalBandwidthLimiter>,
+    identity_header: Option<OutletIdentityHeader>,
+    /// Interceptor of the bytes written to the TCP connection
+    write_interceptor: Option<Box<dyn PortalInterceptor>>,
+    /// Interceptor of the bytes read from the TCP connection, handed to the receiver
+    read_interceptor: Option<Box<dyn PortalInterceptor>>,
 }
 
 impl TcpPortalWorker {
     /// Start a new `TcpPortalWorker` of type [`TypeName::Inlet`]
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub(super) async fn start_new_inlet(
         ctx: &Context,
         registry: TcpRegistry,
+        listener_address: Address,
         stream: TcpStream,
         peer: SocketAddr,
         ping_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        packing: Option<TcpPortalPacking>,
+        bandwidth: Option<TcpPortalBandwidthLimiter>,
+        interceptor: Option<Arc<dyn PortalInterceptorFactory>>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
+            listener_address,
             peer,
             State::SendPing { ping_route },
             Some(stream),
             addresses,
             PortalType::Inlet,
             access_control,
+            packing,
+            bandwidth,
+            None,
+            interceptor,
         )
         .await
     }
 
     /// Start a new `TcpPortalWorker` of type [`TypeName::Outlet`]
+    #[allow(clippy::too_many_arguments)]
     #[instrument(skip_all)]
     pub(super) async fn start_new_outlet(
         ctx: &Context,
         registry: TcpRegistry,
+        listener_address: Address,
+        stream: Option<TcpStream>,
         peer: SocketAddr,
         pong_route: Route,
         addresses: Addresses,
         access_control: Arc<dyn IncomingAccessControl>,
+        packing: Option<TcpPortalPacking>,
+        bandwidth: Option<TcpPortalBandwidthLimiter>,
+        identity_header: Option<OutletIdentityHeader>,
+        interceptor: Option<Arc<dyn PortalInterceptorFactory>>,
     ) -> Result<()> {
         Self::start(
             ctx,
             registry,
+            listener_address,
             peer,
             State::SendPong { pong_route },
-            None,
+            stream,
             addresses,
             PortalType::Outlet,
             access_control,
+            packing,
+            bandwidth,
+            identity_header,
+            interceptor,
         )
         .await
     }
@@ -101,12 +136,17 @@ impl TcpPortalWorker {
     async fn start(
         ctx: &Context,
         registry: TcpRegistry,
+        listener_address: Address,
         peer: SocketAddr,
         state: State,
         stream: Option<TcpStream>,
         addresses: Addresses,
         portal_type: PortalType,
         access_control: Arc<dyn IncomingAccessControl>,
+        packing: Option<TcpPortalPacking>,
+        bandwidth: Option<TcpPortalBandwidthLimiter>,
+        identity_header: Option<OutletIdentityHeader>,
+        interceptor: Option<Arc<dyn PortalInterceptorFactory>>,
     ) -> Result<()> {
         info!(
             "Creating new {:?} at internal: {}, remote: {}",
@@ -123,6 +163,21 @@ impl TcpPortalWorker {
             None => (None, None),
         };
 
+        let connection = TcpPortalConnectionInfo::new(
+            addresses.remote.clone(),
+            portal_type,
+            listener_address,
+            peer,
+        );
+
+        let (write_interceptor, read_interceptor) = match interceptor {
+            Some(interceptor) => (
+                interceptor.create(PortalDirection::written_by(portal_type)),
+                interceptor.create(PortalDirection::read_by(portal_type)),
+            ),
+            None => (None, None),
+        };
+
         let worker = Self {
             registry,
             state,
@@ -133,7 +188,13 @@ impl TcpPortalWorker {
             remote_route: None,
             is_disconnecting: false,
             portal_type,
+            connection,
             last_received_packet_counter: u16::MAX,
+            packing,
+            bandwidth,
+            identity_header,
+            write_interceptor,
+            read_interceptor,
         };
 
         let internal_mailbox = Mailbox::new(
@@ -176,9 +237,13 @@ impl TcpPortalWorker {
             let next_hop = onward_route.next()?.clone();
             let receiver = TcpPortalRecvProcessor::new(
                 self.registry.clone(),
+                self.connection.clone(),
                 rx,
                 self.addresses.internal.clone(),
                 onward_route,
+                self.packing,
+                self.bandwidth.clone(),
+                self.read_interceptor.take(),
             );
 
             ProcessorBuilder::new(receiver)
@@ -298,6 +363,7 @@ impl TcpPortalWorker {
 
     #[instrument(skip_all)]
     async fn handle_send_pong(&mut self, ctx: &Context, pong_route: Route) -> Result<State> {
+        // The connection may have been established in advance by the outlet connection pool
         if self.write_half.is_none() {
             let stream = TcpStream::connect(self.peer)
                 .await
@@ -306,31 +372,33 @@ impl TcpPortalWorker {
             self.write_half = Some(tx);
             self.read_half = Some(rx);
 
-            // Respond to Inlet before starting the processor but
-            // after the connection has been established
-            // to avoid a payload being sent before the pong
-            ctx.send_from_address(
-                pong_route.clone(),
-                PortalMessage::Pong.to_neutral_message()?,
-                self.addresses.remote.clone(),
-            )
-            .await?;
-
-            self.start_receiver(ctx, pong_route.clone()).await?;
-
             debug!(
                 "Outlet at: {} successfully connected",
                 self.addresses.internal
             );
-        } else {
-            ctx.send_from_address(
-                pong_route.clone(),
-                PortalMessage::Pong.to_neutral_message()?,
-                self.addresses.remote.clone(),
-            )
-            .await?;
         }
 
+        if let Some(OutletIdentityHeader::ProxyProtocolV2(identifier)) = &self.identity_header {
+            if let Some(tx) = &mut self.write_half {
+                let source = tx.local_addr().map_err(TransportError::from)?;
+                let header = proxy_protocol_v2_header(source, self.peer, identifier);
+                tx.write_all(&header).await.map_err(TransportError::from)?;
+            }
+            self.identity_header = None;
+        }
+
+        // Respond to Inlet before starting the processor but
+        // after the connection has been established
+        // to avoid a payload being sent before the pong
+        ctx.send_from_address(
+            pong_route.clone(),
+            PortalMessage::Pong.to_neutral_message()?,
+            self.addresses.remote.clone(),
+        )
+        .await?;
+
+        self.start_receiver(ctx, pong_route.clone()).await?;
+
         debug!("Outlet at: {} sent pong", self.addresses.internal);
 
         self.remote_route = Some(pong_route);
@@ -360,6 +428,7 @@ impl Worker for TcpPortalWorker {
         }
 
         self.registry.add_portal_worker(&self.addresses.remote);
+        self.registry.add_portal_connection(self.connection.clone());
 
         Ok(())
     }
@@ -367,6 +436,7 @@ impl Worker for TcpPortalWorker {
     #[instrument(skip_all, name = "TcpPortalWorker::shutdown")]
     async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_portal_worker(&self.addresses.remote);
+        self.registry.remove_portal_connection(self.connection.id());
 
         Ok(())
     }
@@ -396,10 +466,12 @@ impl Worker for TcpPortalWorker {
                 if !remote_packet {
                     return Err(TransportError::PortalInvalidState)?;
                 };
-                if PortalMessage::decode(&payload)? != PortalMessage::Pong {
-                    return Err(TransportError::Protocol)?;
-                };
-                self.handle_receive_pong(ctx, return_route).await
+                match PortalMessage::decode(&payload)? {
+                    PortalMessage::Pong => self.handle_receive_pong(ctx, return_route).await,
+                    // the outlet refused the connection, for example because it is paused
+                    PortalMessage::Disconnect => self.handle_refused_connection(ctx).await,
+                    _ => Err(TransportError::Protocol)?,
+                }
             }
             State::Initialized => {
                 trace!(
@@ -420,7 +492,10 @@ impl Worker for TcpPortalWorker {
                             self.start_disconnection(ctx, DisconnectionReason::Remote)
                                 .await
                         }
-                        PortalMessage::Ping | PortalMessage::Pong => {
+                        PortalMessage::Ping
+                        | PortalMessage::Pong
+                        | PortalMessage::PortalPing
+                        | PortalMessage::PortalPingReply(_) => {
                             return Err(TransportError::Protocol)?;
                         }
                     }
@@ -449,6 +524,24 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    #[instrument(skip_all)]
+    async fn handle_refused_connection(&mut self, ctx: &Context) -> Result<()> {
+        info!(
+            "Inlet at: {} was refused a connection by the outlet",
+            self.addresses.internal
+        );
+        // The receiver is not started yet, the stream is still owned by this worker.
+        // Closing it without lingering resets the connection of the client,
+        // which can connect again later
+        if let (Some(rx), Some(tx)) = (self.read_half.take(), self.write_half.take()) {
+            if let Ok(stream) = rx.reunite(tx) {
+                let _ = stream.set_linger(Some(Duration::ZERO));
+            }
+        }
+        self.is_disconnecting = true;
+        ctx.stop_worker(self.addresses.internal.clone()).await
+    }
+
     #[instrument(skip_all)]
     async fn handle_disconnect(&mut self, ctx: &Context) -> Result<()> {
         info!(
@@ -469,9 +562,53 @@ impl TcpPortalWorker {
     ) -> Result<()> {
         // detects both missing or out of order packets
         self.check_packet_counter(ctx, packet_counter).await?;
+
+        // an interceptor can keep the payload until it receives the next ones
+        let intercepted;
+        let payload = match &mut self.write_interceptor {
+            Some(interceptor) => match interceptor.intercept(payload) {
+                Ok(bytes) if bytes.is_empty() => return Ok(()),
+                Ok(bytes) => {
+                    intercepted = bytes;
+                    intercepted.as_slice()
+                }
+                Err(err) => {
+                    warn!(
+                        "Failed to intercept the message for peer {} with error: {}",
+                        self.peer, err
+                    );
+                    self.start_disconnection(ctx, DisconnectionReason::FailedTx)
+                        .await?;
+                    return Ok(());
+                }
+            },
+            None => payload,
+        };
+
+        // the head of the first HTTP request is buffered until the identity header is added
+        let payload = match &mut self.identity_header {
+            Some(OutletIdentityHeader::Http(injector)) => match injector.push(payload) {
+                Ok(Some(request)) => {
+                    self.identity_header = None;
+                    Cow::Owned(request)
+                }
+                Ok(None) => return Ok(()),
+                Err(err) => {
+                    warn!(
+                        "Failed to add the identity header for peer {} with error: {}",
+                        self.peer, err
+                    );
+                    self.start_disconnection(ctx, DisconnectionReason::FailedTx)
+                        .await?;
+                    return Ok(());
+                }
+            },
+            _ => Cow::Borrowed(payload),
+        };
+
         if let Some(tx) = &mut self.write_half {
-            match tx.write_all(payload).await {
-                Ok(()) => {}
+            match tx.write_all(&payload).await {
+                Ok(()) => self.connection.add_bytes_written(payload.len()),
                 Err(err) => {
                     warn!(
                         "Failed to send message to peer {} with error: {}",
@@ -0,0 +1,210 @@
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{route, Decodable, Encodable, Message, Result, TransportMessage};
+use ockam_transport_core::encode_transport_message;
+use serde::{Deserialize, Serialize};
+
+/// Magic bytes starting the handshake sent by each side of a TCP connection
+pub(crate) const TCP_HANDSHAKE_MAGIC: &[u8] = b"OCKAM-TCP";
+
+/// Latest version of the framing protocol supported by this implementation
+pub const TCP_PROTOCOL_VERSION: u8 = 1;
+
+/// Versions of the framing protocol supported by this implementation
+const SUPPORTED_TCP_PROTOCOL_VERSIONS: &[u8] = &[1];
+
+/// Optional features of the framing protocol, like batching or compression.
+/// A capability is only used on a connection if both peers support it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TcpCapabilities(u32);
+
+impl TcpCapabilities {
+    /// No optional feature
+    pub const NONE: TcpCapabilities = TcpCapabilities(0);
+
+    /// Capabilities supported by this implementation
+    pub(crate) fn supported() -> Self {
+        Self::NONE
+    }
+
+    /// Create capabilities from their binary representation
+    pub fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    /// Binary representation of the capabilities
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// Return true if all the given capabilities are enabled
+    pub fn contains(&self, other: TcpCapabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Capabilities enabled on both sides
+    pub fn intersection(&self, other: TcpCapabilities) -> Self {
+        Self(self.0 & other.0)
+    }
+}
+
+/// Framing protocol used on a TCP connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TcpProtocol {
+    /// The handshake of the peer has not been received yet.
+    /// Messages are sent as in the legacy mode until then
+    Pending,
+    /// The peer does not send a handshake: it runs a version of Ockam released before
+    /// the handshake was introduced. This mode is supported for at least one release
+    Legacy,
+    /// The highest version and the capabilities supported by both peers
+    Negotiated {
+        /// Agreed version of the framing protocol
+        version: u8,
+        /// Agreed capabilities
+        capabilities: TcpCapabilities,
+    },
+}
+
+impl TcpProtocol {
+    /// Capabilities which can be used on the connection
+    pub fn capabilities(&self) -> TcpCapabilities {
+        match self {
+            TcpProtocol::Negotiated { capabilities, .. } => *capabilities,
+            _ => TcpCapabilities::NONE,
+        }
+    }
+}
+
+impl fmt::Display for TcpProtocol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            TcpProtocol::Pending => write!(f, "pending"),
+            TcpProtocol::Legacy => write!(f, "legacy"),
+            TcpProtocol::Negotiated { version, .. } => write!(f, "v{version}"),
+        }
+    }
+}
+
+/// Protocol state shared by the sender and the receiver of a TCP connection
+#[derive(Clone, Debug)]
+pub(crate) struct TcpProtocolState(Arc<RwLock<TcpProtocol>>);
+
+impl Default for TcpProtocolState {
+    fn default() -> Self {
+        Self(Arc::new(RwLock::new(TcpProtocol::Pending)))
+    }
+}
+
+impl TcpProtocolState {
+    pub(crate) fn get(&self) -> TcpProtocol {
+        *self.0.read().unwrap()
+    }
+
+    pub(crate) fn set(&self, protocol: TcpProtocol) {
+        *self.0.write().unwrap() = protocol;
+    }
+}
+
+/// Handshake sent by each side when a TCP connection is established.
+///
+/// It is sent as the payload of a [`TransportMessage`] with an empty onward route,
+/// prefixed with [`TCP_HANDSHAKE_MAGIC`], so that peers which don't support the handshake
+/// discard it like a heartbeat.
+#[derive(Serialize, Deserialize, Message, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TcpHandshake {
+    versions: Vec<u8>,
+    capabilities: TcpCapabilities,
+}
+
+impl TcpHandshake {
+    pub(crate) fn supported() -> Self {
+        Self {
+            versions: SUPPORTED_TCP_PROTOCOL_VERSIONS.to_vec(),
+            capabilities: TcpCapabilities::supported(),
+        }
+    }
+
+    /// Length-prefixed frame containing the handshake
+    pub(crate) fn encode_frame(&self) -> Result<Vec<u8>> {
+        let mut payload = TCP_HANDSHAKE_MAGIC.to_vec();
+        payload.extend(self.encode()?);
+        encode_transport_message(TransportMessage::v1(route![], route![], payload))
+    }
+
+    /// Return the handshake contained in a message, if any
+    pub(crate) fn from_message(message: &TransportMessage) -> Option<Self> {
+        if !message.onward_route.is_empty() {
+            return None;
+        }
+        let body = message.payload.strip_prefix(TCP_HANDSHAKE_MAGIC)?;
+        Self::decode(body).ok()
+    }
+
+    /// Return the highest version and the capabilities supported by both peers.
+    /// Fall back to the legacy mode if there is no common version
+    pub(crate) fn negotiate(&self, peer: &TcpHandshake) -> TcpProtocol {
+        let version = self
+            .versions
+            .iter()
+            .filter(|v| peer.versions.contains(v))
+            .max();
+        match version {
+            Some(version) => TcpProtocol::Negotiated {
+                version: *version,
+                capabilities: self.capabilities.intersection(peer.capabilities),
+            },
+            None => TcpProtocol::Legacy,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::LocalMessage;
+
+    #[test]
+    fn the_highest_common_version_is_negotiated() {
+        let ours = TcpHandshake {
+            versions: vec![1, 2, 3],
+            capabilities: TcpCapabilities::from_bits(0b011),
+        };
+        let theirs = TcpHandshake {
+            versions: vec![1, 2, 4],
+            capabilities: TcpCapabilities::from_bits(0b110),
+        };
+        assert_eq!(
+            ours.negotiate(&theirs),
+            TcpProtocol::Negotiated {
+                version: 2,
+                capabilities: TcpCapabilities::from_bits(0b010),
+            }
+        );
+
+        let unknown = TcpHandshake {
+            versions: vec![5],
+            capabilities: TcpCapabilities::NONE,
+        };
+        assert_eq!(ours.negotiate(&unknown), TcpProtocol::Legacy);
+    }
+
+    #[test]
+    fn the_handshake_is_recognized_by_its_magic() -> Result<()> {
+        let frame = TcpHandshake::supported().encode_frame()?;
+        let message = TransportMessage::decode(&frame[2..])?;
+        assert_eq!(
+            TcpHandshake::from_message(&message),
+            Some(TcpHandshake::supported())
+        );
+
+        // peers which don't support the handshake see it as a heartbeat
+        assert!(!LocalMessage::from_transport_message(message).has_next_on_onward_route());
+
+        let other = TransportMessage::v1(route![], route![], b"OCKAM-UDP".to_vec());
+        assert_eq!(TcpHandshake::from_message(&other), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+//! Reconnection of the outgoing TCP connections.
+//!
+//! When an outgoing connection is created with [`TcpConnectionOptions::with_reconnect`](crate::TcpConnectionOptions::with_reconnect),
+//! the receiver of the connection connects to the peer again when the connection is closed,
+//! waiting between the attempts as specified by the backoff policy. The sender and receiver
+//! keep their addresses, so that the routes going through the connection stay valid.
+//!
+//! While the connection is re-established, the messages sent to the connection are either
+//! rejected or, with [`TcpConnectionOptions::with_outbox`](crate::TcpConnectionOptions::with_outbox),
+//! buffered in an outbox with a limited budget and sent in order once the connection is back.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use ockam_core::compat::backoff::Backoff;
+use ockam_core::compat::collections::VecDeque;
+use ockam_core::compat::sync::{Arc, Mutex};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_transport_core::TransportError;
+use tokio::net::tcp::OwnedWriteHalf;
+
+/// Budget of the outbox of a connection
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct TcpOutboxLimits {
+    pub(crate) max_messages: usize,
+    pub(crate) max_bytes: usize,
+}
+
+/// Reconnection policy of an outgoing connection
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct TcpReconnectPolicy {
+    pub(crate) backoff: Backoff,
+    pub(crate) outbox: Option<TcpOutboxLimits>,
+}
+
+/// Messages buffered by the sender of a connection while it is re-established.
+/// The messages are stored encoded, as they are written on the connection
+#[derive(Debug)]
+pub(crate) struct TcpOutbox {
+    limits: TcpOutboxLimits,
+    messages: VecDeque<Vec<u8>>,
+    bytes: usize,
+}
+
+impl TcpOutbox {
+    pub(crate) fn new(limits: TcpOutboxLimits) -> Self {
+        Self {
+            limits,
+            messages: VecDeque::new(),
+            bytes: 0,
+        }
+    }
+
+    /// Buffer a message, or return a [`TransportError::OutboxFull`] error
+    /// if the message exceeds the budget of the outbox
+    pub(crate) fn push(&mut self, message: Vec<u8>) -> Result<()> {
+        if self.messages.len() >= self.limits.max_messages
+            || self.bytes + message.len() > self.limits.max_bytes
+        {
+            return Err(TransportError::OutboxFull)?;
+        }
+        self.bytes += message.len();
+        self.messages.push_back(message);
+        Ok(())
+    }
+
+    /// Put back a message which could not be sent, so that it is sent first.
+    /// The budget is not checked since the message was already counted
+    pub(crate) fn push_front(&mut self, message: Vec<u8>) {
+        self.bytes += message.len();
+        self.messages.push_front(message);
+    }
+
+    /// Take the oldest message
+    pub(crate) fn pop_front(&mut self) -> Option<Vec<u8>> {
+        let message = self.messages.pop_front()?;
+        self.bytes -= message.len();
+        Some(message)
+    }
+
+    /// Drop all the messages, and return their number and total size
+    pub(crate) fn clear(&mut self) -> (usize, usize) {
+        let dropped = (self.messages.len(), self.bytes);
+        self.messages.clear();
+        self.bytes = 0;
+        dropped
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.messages.len()
+    }
+}
+
+/// State shared by the sender and the receiver of a connection which reconnects.
+/// The receiver hands the write half of the new connection to the sender
+#[derive(Clone, Debug)]
+pub(crate) struct TcpReconnection {
+    policy: TcpReconnectPolicy,
+    reconnecting: Arc<AtomicBool>,
+    write_half: Arc<Mutex<Option<OwnedWriteHalf>>>,
+}
+
+impl TcpReconnection {
+    pub(crate) fn new(policy: TcpReconnectPolicy) -> Self {
+        Self {
+            policy,
+            reconnecting: Default::default(),
+            write_half: Default::default(),
+        }
+    }
+
+    pub(crate) fn backoff(&self) -> &Backoff {
+        &self.policy.backoff
+    }
+
+    pub(crate) fn outbox(&self) -> Option<TcpOutbox> {
+        self.policy.outbox.map(TcpOutbox::new)
+    }
+
+    pub(crate) fn is_reconnecting(&self) -> bool {
+        self.reconnecting.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_reconnecting(&self, reconnecting: bool) {
+        self.reconnecting.store(reconnecting, Ordering::Relaxed)
+    }
+
+    /// Store the write half of a new connection, until it is taken by the sender
+    pub(crate) fn set_write_half(&self, write_half: OwnedWriteHalf) {
+        *self.write_half.lock().unwrap() = Some(write_half);
+    }
+
+    pub(crate) fn take_write_half(&self) -> Option<OwnedWriteHalf> {
+        self.write_half.lock().unwrap().take()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::errcode::Kind;
+
+    #[test]
+    fn the_outbox_rejects_the_messages_exceeding_its_budget() {
+        let mut outbox = TcpOutbox::new(TcpOutboxLimits {
+            max_messages: 3,
+            max_bytes: 10,
+        });
+        outbox.push(vec![1; 4]).unwrap();
+        outbox.push(vec![2; 4]).unwrap();
+
+        // too many bytes
+        let error = outbox.push(vec![3; 4]).unwrap_err();
+        assert_eq!(error.code().kind, Kind::ResourceExhausted);
+        assert!(error.to_string().contains("outbox"));
+        outbox.push(vec![3; 2]).unwrap();
+
+        // too many messages
+        assert!(outbox.push(vec![]).is_err());
+
+        assert_eq!(outbox.pop_front(), Some(vec![1; 4]));
+        outbox.push(vec![4; 4]).unwrap();
+        assert_eq!(outbox.len(), 3);
+        assert_eq!(outbox.clear(), (3, 10));
+        assert_eq!(outbox.pop_front(), None);
+    }
+
+    #[test]
+    fn a_message_put_back_is_sent_first() {
+        let mut outbox = TcpOutbox::new(TcpOutboxLimits {
+            max_messages: 2,
+            max_bytes: 10,
+        });
+        outbox.push(vec![1]).unwrap();
+        outbox.push(vec![2]).unwrap();
+        let first = outbox.pop_front().unwrap();
+        outbox.push_front(first);
+        assert_eq!(outbox.pop_front(), Some(vec![1]));
+        assert_eq!(outbox.pop_front(), Some(vec![2]));
+    }
+}
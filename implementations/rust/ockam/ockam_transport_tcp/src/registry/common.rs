@@ -1,8 +1,13 @@
+use crate::protocol::TcpProtocolState;
+use crate::{PortalType, TcpListenerCounters, TcpListenerStats, TcpProtocol, TcpReconnection};
 use core::fmt;
 use core::fmt::Formatter;
+use core::sync::atomic::{AtomicU64, Ordering};
+use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::Address;
 use std::net::SocketAddr;
+use tokio::sync::Notify;
 
 /// Tcp connection mode
 #[derive(Copy, Debug, Clone)]
@@ -30,6 +35,8 @@ pub struct TcpSenderInfo {
     socket_address: SocketAddr,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    protocol: TcpProtocolState,
+    reconnection: Option<TcpReconnection>,
 }
 
 impl TcpSenderInfo {
@@ -47,9 +54,21 @@ impl TcpSenderInfo {
             socket_address,
             mode,
             flow_control_id,
+            protocol: TcpProtocolState::default(),
+            reconnection: None,
         }
     }
 
+    pub(crate) fn with_protocol(mut self, protocol: TcpProtocolState) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub(crate) fn with_reconnection(mut self, reconnection: Option<TcpReconnection>) -> Self {
+        self.reconnection = reconnection;
+        self
+    }
+
     /// Address of the Sender worker
     pub fn address(&self) -> &Address {
         &self.address
@@ -70,6 +89,17 @@ impl TcpSenderInfo {
     pub fn mode(&self) -> &TcpConnectionMode {
         &self.mode
     }
+    /// [`TcpProtocol`] negotiated with the peer for this connection
+    pub fn protocol(&self) -> TcpProtocol {
+        self.protocol.get()
+    }
+    /// Return true if the connection was closed and is currently re-established
+    pub fn is_reconnecting(&self) -> bool {
+        self.reconnection
+            .as_ref()
+            .map(|reconnection| reconnection.is_reconnecting())
+            .unwrap_or(false)
+    }
 }
 
 /// Information about specific Tcp sender (corresponds to one specific Tcp connection)
@@ -80,6 +110,7 @@ pub struct TcpReceiverInfo {
     socket_address: SocketAddr,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    protocol: TcpProtocolState,
 }
 
 impl TcpReceiverInfo {
@@ -97,9 +128,15 @@ impl TcpReceiverInfo {
             socket_address,
             mode,
             flow_control_id,
+            protocol: TcpProtocolState::default(),
         }
     }
 
+    pub(crate) fn with_protocol(mut self, protocol: TcpProtocolState) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
     /// Address of the Receiver processor
     pub fn address(&self) -> &Address {
         &self.address
@@ -120,6 +157,10 @@ impl TcpReceiverInfo {
     pub fn mode(&self) -> &TcpConnectionMode {
         &self.mode
     }
+    /// [`TcpProtocol`] negotiated with the peer for this connection
+    pub fn protocol(&self) -> TcpProtocol {
+        self.protocol.get()
+    }
 }
 
 /// Information about specific Tcp listener
@@ -128,6 +169,7 @@ pub struct TcpListenerInfo {
     address: Address,
     socket_address: SocketAddr,
     flow_control_id: FlowControlId,
+    counters: Arc<TcpListenerCounters>,
 }
 
 impl TcpListenerInfo {
@@ -141,9 +183,15 @@ impl TcpListenerInfo {
             address,
             socket_address,
             flow_control_id,
+            counters: Default::default(),
         }
     }
 
+    pub(crate) fn with_counters(mut self, counters: Arc<TcpListenerCounters>) -> Self {
+        self.counters = counters;
+        self
+    }
+
     /// Address of the Processor
     pub fn address(&self) -> &Address {
         &self.address
@@ -156,4 +204,90 @@ impl TcpListenerInfo {
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
     }
+    /// Statistics of the connections accepted and rejected by this listener
+    pub fn stats(&self) -> TcpListenerStats {
+        self.counters.stats()
+    }
+}
+
+/// Information about a specific portal connection (corresponds to one Tcp stream
+/// accepted by an inlet or opened by an outlet)
+#[derive(Debug, Clone)]
+pub struct TcpPortalConnectionInfo {
+    id: Address,
+    portal_type: PortalType,
+    listener_address: Address,
+    peer: SocketAddr,
+    established_at: u64,
+    bytes_read: Arc<AtomicU64>,
+    bytes_written: Arc<AtomicU64>,
+    close: Arc<Notify>,
+}
+
+impl TcpPortalConnectionInfo {
+    /// Constructor
+    pub(crate) fn new(
+        id: Address,
+        portal_type: PortalType,
+        listener_address: Address,
+        peer: SocketAddr,
+    ) -> Self {
+        Self {
+            id,
+            portal_type,
+            listener_address,
+            peer,
+            established_at: ockam_core::compat::time::now().unwrap_or_default(),
+            bytes_read: Default::default(),
+            bytes_written: Default::default(),
+            close: Default::default(),
+        }
+    }
+
+    /// Identifier of the connection, which is the remote address of its portal worker
+    pub fn id(&self) -> &Address {
+        &self.id
+    }
+    /// [`PortalType`] of the portal end handling this connection
+    pub fn portal_type(&self) -> PortalType {
+        self.portal_type
+    }
+    /// Address of the inlet listener processor or outlet listener worker
+    /// which created this connection
+    pub fn listener_address(&self) -> &Address {
+        &self.listener_address
+    }
+    /// Socket address of the local Tcp peer
+    pub fn peer(&self) -> SocketAddr {
+        self.peer
+    }
+    /// Unix timestamp, in seconds, of the connection creation
+    pub fn established_at(&self) -> u64 {
+        self.established_at
+    }
+    /// Number of bytes read from the Tcp peer and sent through the portal
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read.load(Ordering::Relaxed)
+    }
+    /// Number of bytes received through the portal and written to the Tcp peer
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn add_bytes_read(&self, len: usize) {
+        self.bytes_read.fetch_add(len as u64, Ordering::Relaxed);
+    }
+    pub(crate) fn add_bytes_written(&self, len: usize) {
+        self.bytes_written.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    /// Ask the receiver of this connection to close it.
+    /// If the receiver is not started yet, it closes the connection as soon as it starts
+    pub(crate) fn close(&self) {
+        self.close.notify_one();
+    }
+    /// Resolve when the connection was asked to be closed
+    pub(crate) async fn closed(&self) {
+        self.close.notified().await
+    }
 }
@@ -1,5 +1,9 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpRegistry, TcpSenderInfo};
+use crate::{
+    TcpConnection, TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpRegistry,
+    TcpSenderInfo,
+};
 use ockam_core::Address;
+use std::net::SocketAddr;
 
 impl TcpRegistry {
     pub(crate) fn add_portal_worker(&self, addr: &Address) {
@@ -22,6 +26,16 @@ impl TcpRegistry {
             lock.remove_portal_receiver_processor(addr);
         }
     }
+    pub(crate) fn add_portal_connection(&self, info: TcpPortalConnectionInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_portal_connection(info);
+        }
+    }
+    pub(crate) fn remove_portal_connection(&self, id: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_portal_connection(id);
+        }
+    }
     pub(crate) fn add_inlet_listener_processor(&self, addr: &Address) {
         if let Ok(mut lock) = self.registry.write() {
             lock.add_inlet_listener_processor(addr);
@@ -72,4 +86,43 @@ impl TcpRegistry {
             lock.remove_receiver_processor(addr);
         }
     }
+    pub(crate) fn add_shared_connection(&self, connection: TcpConnection) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_shared_connection(connection);
+        }
+    }
+    /// Return the shared outgoing connection to a peer, if there is one
+    pub(crate) fn find_shared_connection(
+        &self,
+        socket_address: &SocketAddr,
+    ) -> Option<TcpConnection> {
+        self.registry
+            .read()
+            .ok()?
+            .find_shared_connection(socket_address)
+    }
+    pub(crate) fn add_shared_connection_user(
+        &self,
+        connection_sender_address: &Address,
+        user_sender_address: Address,
+    ) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_shared_connection_user(connection_sender_address, user_sender_address);
+        }
+    }
+    /// Remove a closed shared connection and return the sender addresses of its users
+    pub(crate) fn remove_shared_connection(&self, sender_address: &Address) -> Vec<Address> {
+        match self.registry.write() {
+            Ok(mut lock) => lock.remove_shared_connection(sender_address),
+            Err(_) => vec![],
+        }
+    }
+    /// Remove a user of a shared connection, given the sender address of its worker.
+    /// Return true if the connection is not used anymore and must be stopped
+    pub(crate) fn release_connection(&self, user_sender_address: &Address) -> bool {
+        match self.registry.write() {
+            Ok(mut lock) => lock.release_connection(user_sender_address),
+            Err(_) => false,
+        }
+    }
 }
@@ -1,15 +1,28 @@
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{
+    TcpConnection, TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpSenderInfo,
+};
 use ockam_core::Address;
+use std::net::SocketAddr;
+
+/// Outgoing connection shared by all the users connecting to the same peer
+#[derive(Debug)]
+pub(super) struct SharedTcpConnection {
+    pub(super) connection: TcpConnection,
+    /// Sender addresses of the workers of the users of the connection
+    pub(super) users: Vec<Address>,
+}
 
 #[derive(Default, Debug)]
 pub(super) struct InternalRegistry {
     pub(super) portal_workers: Vec<Address>,
     pub(super) portal_receiver_processors: Vec<Address>,
+    pub(super) portal_connections: Vec<TcpPortalConnectionInfo>,
     pub(super) inlet_listener_processors: Vec<Address>,
     pub(super) outlet_listener_workers: Vec<Address>,
     pub(super) listener_processors: Vec<TcpListenerInfo>,
     pub(super) sender_workers: Vec<TcpSenderInfo>,
     pub(super) receiver_processors: Vec<TcpReceiverInfo>,
+    pub(super) shared_connections: Vec<SharedTcpConnection>,
 }
 
 impl InternalRegistry {
@@ -25,6 +38,12 @@ impl InternalRegistry {
     pub(super) fn remove_portal_receiver_processor(&mut self, addr: &Address) {
         self.portal_receiver_processors.retain(|x| x != addr);
     }
+    pub(super) fn add_portal_connection(&mut self, info: TcpPortalConnectionInfo) {
+        self.portal_connections.push(info)
+    }
+    pub(super) fn remove_portal_connection(&mut self, id: &Address) {
+        self.portal_connections.retain(|x| x.id() != id);
+    }
     pub(super) fn add_inlet_listener_processor(&mut self, addr: &Address) {
         self.inlet_listener_processors.push(addr.clone())
     }
@@ -55,4 +74,68 @@ impl InternalRegistry {
     pub(super) fn remove_receiver_processor(&mut self, addr: &Address) {
         self.receiver_processors.retain(|x| x.address() != addr);
     }
+    pub(super) fn add_shared_connection(&mut self, connection: TcpConnection) {
+        self.shared_connections.push(SharedTcpConnection {
+            connection,
+            users: vec![],
+        })
+    }
+    pub(super) fn find_shared_connection(
+        &self,
+        socket_address: &SocketAddr,
+    ) -> Option<TcpConnection> {
+        self.shared_connections
+            .iter()
+            .find(|x| x.connection.socket_address() == socket_address)
+            .map(|x| x.connection.clone())
+    }
+    pub(super) fn add_shared_connection_user(
+        &mut self,
+        connection_sender_address: &Address,
+        user_sender_address: Address,
+    ) {
+        if let Some(shared) = self
+            .shared_connections
+            .iter_mut()
+            .find(|x| x.connection.sender_address() == connection_sender_address)
+        {
+            shared.users.push(user_sender_address);
+        }
+    }
+    pub(super) fn remove_shared_connection(&mut self, sender_address: &Address) -> Vec<Address> {
+        match self
+            .shared_connections
+            .iter()
+            .position(|x| x.connection.sender_address() == sender_address)
+        {
+            Some(index) => self.shared_connections.remove(index).users,
+            None => vec![],
+        }
+    }
+    pub(super) fn release_connection(&mut self, user_sender_address: &Address) -> bool {
+        let index = match self
+            .shared_connections
+            .iter()
+            .position(|x| x.users.contains(user_sender_address))
+        {
+            Some(index) => index,
+            None => return false,
+        };
+        let shared = &mut self.shared_connections[index];
+        shared.users.retain(|x| x != user_sender_address);
+        if shared.users.is_empty() {
+            self.shared_connections.remove(index);
+            true
+        } else {
+            false
+        }
+    }
+    pub(super) fn get_shared_connection_users(&self, sender_address: &Address) -> Option<usize> {
+        self.shared_connections
+            .iter()
+            .find(|x| {
+                x.connection.sender_address() == sender_address || x.users.contains(sender_address)
+            })
+            .map(|x| x.users.len())
+    }
 }
@@ -1,6 +1,7 @@
 use crate::registry::internal::InternalRegistry;
-use crate::{TcpListenerInfo, TcpReceiverInfo, TcpSenderInfo};
+use crate::{TcpListenerInfo, TcpPortalConnectionInfo, TcpReceiverInfo, TcpSenderInfo};
 use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::Address;
 
 /// Registry of all active workers and processors in TCP Transport to ease their lifecycle management
 #[derive(Default, Clone, Debug)]
@@ -23,4 +24,18 @@ impl TcpRegistry {
     pub fn get_all_listeners(&self) -> Vec<TcpListenerInfo> {
         self.registry.read().unwrap().listener_processors.clone()
     }
+
+    /// Return all active portal connections
+    pub fn get_all_portal_connections(&self) -> Vec<TcpPortalConnectionInfo> {
+        self.registry.read().unwrap().portal_connections.clone()
+    }
+
+    /// Return the number of users of a shared outgoing connection, given its sender [`Address`],
+    /// or the sender [`Address`] of one of its users. Return `None` if the connection is not shared
+    pub fn get_shared_connection_users(&self, sender_address: &Address) -> Option<usize> {
+        self.registry
+            .read()
+            .unwrap()
+            .get_shared_connection_users(sender_address)
+    }
 }
@@ -15,6 +15,8 @@ pub struct TcpConnection {
     socket_address: SocketAddr,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    /// True if the connection is used through a shared connection
+    shared: bool,
 }
 
 impl fmt::Display for TcpConnection {
@@ -48,16 +50,33 @@ impl TcpConnection {
             socket_address,
             mode,
             flow_control_id,
+            shared: false,
         }
     }
+
+    /// Mark the connection of a user of a shared connection
+    pub(crate) fn shared(mut self) -> Self {
+        self.shared = true;
+        self
+    }
+
     /// Stops the [`TcpConnection`], this method must be called to avoid
     /// leakage of the connection.
-    /// Simply dropping this object won't close the connection
+    /// Simply dropping this object won't close the connection.
+    ///
+    /// A shared connection is only closed when its last user stops it
     pub async fn stop(&self, context: &Context) -> Result<()> {
         context.stop_worker(self.sender_address.clone()).await
     }
+
+    /// Return true if the connection is used through a connection shared with other callers of
+    /// [`TcpTransport::connect`](crate::TcpTransport::connect)
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
     /// Corresponding [`TcpSendWorker`](super::workers::TcpSendWorker) [`Address`] that can be used
-    /// in a route to send messages to the other side of the TCP connection
+    /// in a route to send messages to the other side of the TCP connection.
+    /// For a shared connection, this is the address of the worker of this user
     pub fn sender_address(&self) -> &Address {
         &self.sender_address
     }
@@ -1,11 +1,23 @@
+use crate::protocol::TcpProtocolState;
 use crate::transport::common::{resolve_peer, TcpConnection};
-use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker};
-use crate::{TcpConnectionMode, TcpConnectionOptions, TcpTransport};
+use crate::workers::{Addresses, TcpRecvProcessor, TcpSendWorker, TcpSharedConnectionWorker};
+use crate::{TcpConnectionMode, TcpConnectionOptions, TcpReconnection, TcpTransport};
+use ockam_core::compat::net::SocketAddr;
 use ockam_core::{Address, Result};
+use tracing::debug;
 
 impl TcpTransport {
     /// Establish an outgoing TCP connection.
     ///
+    /// With [`TcpConnectionOptions::shared`], an outgoing connection to the same peer created
+    /// with that option as well is reused. Each user of a shared connection gets its own
+    /// addresses, and the [`FlowControlId`](ockam_core::flow_control::FlowControlId) of its options,
+    /// so the messages received for one user are never delivered to the consumers of another.
+    /// A shared connection is only closed when all its users have stopped it.
+    ///
+    /// With [`TcpConnectionOptions::with_reconnect`], the connection is re-established when it
+    /// is closed, instead of being stopped.
+    ///
     /// ```rust
     /// use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
     /// # use ockam_node::Context;
@@ -24,6 +36,42 @@ impl TcpTransport {
         // Resolve peer address
         let socket = resolve_peer(peer.into())?;
 
+        if !options.shared {
+            return self.create_connection(socket, options).await;
+        }
+
+        let connection = match self.registry.find_shared_connection(&socket) {
+            Some(connection) => {
+                debug!(addr = %socket, sender = %connection.sender_address(), "Reusing connection");
+                connection
+            }
+            None => {
+                let connection = self
+                    .create_connection(socket, options.for_shared_connection())
+                    .await?;
+                self.registry.add_shared_connection(connection.clone());
+                connection
+            }
+        };
+        let user_connection = TcpSharedConnectionWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            &connection,
+            options,
+        )
+        .await?;
+        self.registry.add_shared_connection_user(
+            connection.sender_address(),
+            user_connection.sender_address().clone(),
+        );
+        Ok(user_connection)
+    }
+
+    async fn create_connection(
+        &self,
+        socket: SocketAddr,
+        options: TcpConnectionOptions,
+    ) -> Result<TcpConnection> {
         let (read_half, write_half) = TcpSendWorker::connect(socket).await?;
 
         let mode = TcpConnectionMode::Outgoing;
@@ -31,7 +79,9 @@ impl TcpTransport {
 
         options.setup_flow_control(self.ctx.flow_controls(), &addresses);
         let flow_control_id = options.flow_control_id.clone();
+        let reconnection = options.reconnect_policy().map(TcpReconnection::new);
         let access_control = options.create_access_control(self.ctx.flow_controls());
+        let protocol = TcpProtocolState::default();
 
         TcpSendWorker::start(
             &self.ctx,
@@ -42,6 +92,8 @@ impl TcpTransport {
             mode,
             access_control.sender_incoming_access_control,
             &flow_control_id,
+            &protocol,
+            reconnection.clone(),
         )
         .await?;
 
@@ -54,6 +106,9 @@ impl TcpTransport {
             mode,
             &flow_control_id,
             access_control.receiver_outgoing_access_control,
+            &protocol,
+            None,
+            reconnection,
         )
         .await?;
 
@@ -66,7 +121,8 @@ impl TcpTransport {
         ))
     }
 
-    /// Interrupt an active TCP connection given its Sender `Address`
+    /// Interrupt an active TCP connection given its Sender `Address`.
+    /// A shared connection is only interrupted when it is disconnected by its last user
     pub async fn disconnect(&self, address: impl Into<Address>) -> Result<()> {
         self.ctx.stop_worker(address.into()).await
     }
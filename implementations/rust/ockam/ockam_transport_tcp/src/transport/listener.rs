@@ -2,6 +2,7 @@ use crate::transport::common::{parse_socket_addr, TcpListener};
 use crate::workers::TcpListenProcessor;
 use crate::{TcpListenerOptions, TcpTransport};
 use ockam_core::{Address, Result};
+use ockam_node::{NodeEvent, NodeEventKind};
 
 impl TcpTransport {
     /// Start listening to incoming connections on an existing transport
@@ -29,6 +30,11 @@ impl TcpTransport {
         // Could be different from the bind_addr, e.g., if binding to port 0\
         let (socket_addr, address) =
             TcpListenProcessor::start(&self.ctx, self.registry.clone(), bind_addr, options).await?;
+        self.ctx.node_events().publish(
+            NodeEvent::new(NodeEventKind::ListenerBound, socket_addr.to_string())
+                .with_detail("transport", "tcp")
+                .with_detail("address", &address),
+        );
 
         Ok(TcpListener::new(address, socket_addr, flow_control_id))
     }
@@ -6,7 +6,14 @@ mod portals;
 
 pub use common::*;
 
+pub use crate::portal::bandwidth::*;
+pub use crate::portal::health::*;
+pub use crate::portal::http_rewrite::*;
+pub use crate::portal::identity::*;
+pub use crate::portal::interceptor::*;
 pub use crate::portal::options::*;
+pub use crate::portal::pause::*;
+pub use crate::portal::pool::*;
 
 use crate::TcpRegistry;
 use ockam_core::compat::sync::Arc;
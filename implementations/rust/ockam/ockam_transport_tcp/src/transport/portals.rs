@@ -1,9 +1,14 @@
 use crate::portal::TcpInletListenProcessor;
 use crate::transport::common::{parse_socket_addr, resolve_peer};
-use crate::{portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpTransport};
+use crate::{
+    portal::TcpOutletListenWorker, TcpInletOptions, TcpOutletOptions, TcpPortalConnectionInfo,
+    TcpTransport,
+};
 use core::fmt::Debug;
 use ockam_core::compat::net::SocketAddr;
-use ockam_core::{Address, Result, Route};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{Address, Error, Result, Route};
+use ockam_transport_core::TransportError;
 use tracing::instrument;
 
 impl TcpTransport {
@@ -42,6 +47,30 @@ impl TcpTransport {
         .await
     }
 
+    /// Create Tcp Inlet accepting the connections of a listener which is already bound,
+    /// for example to keep a port assigned by the OS until the outlet route is known.
+    /// The connections waiting in the backlog of the listener are served by the inlet
+    #[instrument(skip(self, listener), fields(outlet_route = ?outlet_route.clone()))]
+    pub async fn create_inlet_with_listener(
+        &self,
+        listener: std::net::TcpListener,
+        outlet_route: impl Into<Route> + Clone + Debug,
+        options: TcpInletOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        listener
+            .set_nonblocking(true)
+            .map_err(TransportError::from)?;
+        let listener = tokio::net::TcpListener::from_std(listener).map_err(TransportError::from)?;
+        TcpInletListenProcessor::start_with_listener(
+            &self.ctx,
+            self.registry.clone(),
+            outlet_route.into(),
+            listener,
+            options,
+        )
+        .await
+    }
+
     /// Stop inlet at addr
     ///
     /// ```rust
@@ -133,4 +162,48 @@ impl TcpTransport {
         self.ctx.stop_worker(addr).await?;
         Ok(())
     }
+
+    /// Return the active connections of the inlet listener processor or
+    /// outlet listener worker at the given address
+    pub fn portal_connections(&self, listener_address: &Address) -> Vec<TcpPortalConnectionInfo> {
+        self.registry
+            .get_all_portal_connections()
+            .into_iter()
+            .filter(|c| c.listener_address() == listener_address)
+            .collect()
+    }
+
+    /// Close a single portal connection, identified by [`TcpPortalConnectionInfo::id`].
+    /// The local Tcp stream is dropped and the other end of the portal is notified,
+    /// the other connections of the same inlet or outlet are left untouched
+    #[instrument(skip(self))]
+    pub fn close_portal_connection(&self, id: &Address) -> Result<()> {
+        match self
+            .registry
+            .get_all_portal_connections()
+            .into_iter()
+            .find(|c| c.id() == id)
+        {
+            Some(connection) => {
+                connection.close();
+                Ok(())
+            }
+            None => Err(Error::new(
+                Origin::Transport,
+                Kind::NotFound,
+                format!("portal connection {id} not found"),
+            )),
+        }
+    }
+
+    /// Close all the connections of the inlet or outlet listening at `listener_address`,
+    /// for example to drain a paused portal. Return the number of closed connections
+    #[instrument(skip(self))]
+    pub fn close_portal_connections(&self, listener_address: &Address) -> usize {
+        let connections = self.portal_connections(listener_address);
+        for connection in &connections {
+            connection.close();
+        }
+        connections.len()
+    }
 }
@@ -1,5 +1,9 @@
+use crate::protocol::TcpProtocolState;
 use crate::workers::{Addresses, TcpRecvProcessor};
-use crate::{TcpConnectionMode, TcpListenerInfo, TcpListenerOptions, TcpRegistry, TcpSendWorker};
+use crate::{
+    TcpConnectionMode, TcpListenerGuard, TcpListenerInfo, TcpListenerOptions, TcpRegistry,
+    TcpSendWorker,
+};
 use ockam_core::{async_trait, compat::net::SocketAddr};
 use ockam_core::{Address, Processor, Result};
 use ockam_node::Context;
@@ -17,6 +21,7 @@ pub(crate) struct TcpListenProcessor {
     inner: TcpListener,
     socket_address: SocketAddr,
     options: TcpListenerOptions,
+    guard: TcpListenerGuard,
 }
 
 impl TcpListenProcessor {
@@ -40,6 +45,7 @@ impl TcpListenProcessor {
             registry,
             inner,
             socket_address: saddr,
+            guard: TcpListenerGuard::new(options.limits.clone()),
             options,
         };
 
@@ -57,11 +63,14 @@ impl Processor for TcpListenProcessor {
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
         ctx.set_cluster(crate::CLUSTER_NAME).await?;
 
-        self.registry.add_listener_processor(TcpListenerInfo::new(
-            ctx.address(),
-            self.socket_address,
-            self.options.flow_control_id.clone(),
-        ));
+        self.registry.add_listener_processor(
+            TcpListenerInfo::new(
+                ctx.address(),
+                self.socket_address,
+                self.options.flow_control_id.clone(),
+            )
+            .with_counters(self.guard.counters()),
+        );
 
         Ok(())
     }
@@ -79,6 +88,16 @@ impl Processor for TcpListenProcessor {
 
         // Wait for an incoming connection
         let (stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+
+        // Close the rejected connections before any worker is started for them
+        let permit = match self.guard.admit(&peer) {
+            Ok(permit) => permit,
+            Err(rejection) => {
+                debug!("closing the TCP connection from {peer}: {rejection}");
+                drop(stream);
+                return Ok(true);
+            }
+        };
         debug!("TCP connection accepted");
 
         let mode = TcpConnectionMode::Incoming;
@@ -92,6 +111,7 @@ impl Processor for TcpListenProcessor {
             .create_access_control(ctx.flow_controls(), receiver_flow_control_id.clone());
 
         let (read_half, write_half) = stream.into_split();
+        let protocol = TcpProtocolState::default();
 
         // Worker to receive messages from the Node and send them over the wire
         TcpSendWorker::start(
@@ -103,6 +123,8 @@ impl Processor for TcpListenProcessor {
             mode,
             access_control.sender_incoming_access_control,
             &receiver_flow_control_id,
+            &protocol,
+            None,
         )
         .await?;
 
@@ -116,6 +138,9 @@ impl Processor for TcpListenProcessor {
             mode,
             &receiver_flow_control_id,
             access_control.receiver_outgoing_access_control,
+            &protocol,
+            Some(permit),
+            None,
         )
         .await?;
 
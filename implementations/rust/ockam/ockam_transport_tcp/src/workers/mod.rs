@@ -2,8 +2,10 @@ mod addresses;
 mod listener;
 mod receiver;
 mod sender;
+mod shared;
 
 pub(crate) use addresses::*;
 pub(crate) use listener::*;
 pub(crate) use receiver::*;
 pub(crate) use sender::*;
+pub(crate) use shared::*;
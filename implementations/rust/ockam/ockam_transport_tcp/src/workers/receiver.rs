@@ -1,16 +1,22 @@
+use crate::protocol::{TcpHandshake, TcpProtocolState};
 use crate::workers::Addresses;
-use crate::{TcpConnectionMode, TcpReceiverInfo, TcpRegistry, TcpSendWorkerMsg};
+use crate::{
+    TcpConnectionMode, TcpConnectionPermit, TcpProtocol, TcpReceiverInfo, TcpReconnection,
+    TcpRegistry, TcpSendWorker, TcpSendWorkerMsg, TCP,
+};
 use ockam_core::compat::net::SocketAddr;
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
     async_trait, AllowOnwardAddress, DenyAll, Mailbox, Mailboxes, OutgoingAccessControl,
 };
-use ockam_core::{Decodable, LocalMessage, Processor, Result, TransportMessage};
-use ockam_node::{Context, ProcessorBuilder};
+use ockam_core::{Decodable, IngressInfo, LocalMessage, Processor, Result, TransportMessage};
+#[cfg(feature = "telemetry")]
+use ockam_node::telemetry::TransportMetrics;
+use ockam_node::{Context, MessageSizeRecorder, ProcessorBuilder};
 use ockam_transport_core::TransportError;
 use tokio::{io::AsyncReadExt, net::tcp::OwnedReadHalf};
-use tracing::{error, info, instrument, trace};
+use tracing::{debug, error, info, instrument, trace, warn};
 
 /// A TCP receiving message processor
 ///
@@ -27,10 +33,20 @@ pub(crate) struct TcpRecvProcessor {
     addresses: Addresses,
     mode: TcpConnectionMode,
     flow_control_id: FlowControlId,
+    message_sizes: MessageSizeRecorder,
+    #[cfg(feature = "telemetry")]
+    metrics: TransportMetrics,
+    protocol: TcpProtocolState,
+    ingress_info: IngressInfo,
+    /// Permit of the listener which accepted this connection, released when the connection closes
+    permit: Option<TcpConnectionPermit>,
+    /// Set when the connection must be re-established after being closed
+    reconnection: Option<TcpReconnection>,
 }
 
 impl TcpRecvProcessor {
     /// Create a new `TcpRecvProcessor`
+    #[allow(clippy::too_many_arguments)]
     fn new(
         registry: TcpRegistry,
         read_half: OwnedReadHalf,
@@ -38,7 +54,12 @@ impl TcpRecvProcessor {
         addresses: Addresses,
         mode: TcpConnectionMode,
         flow_control_id: FlowControlId,
+        message_sizes: MessageSizeRecorder,
+        protocol: TcpProtocolState,
+        permit: Option<TcpConnectionPermit>,
+        reconnection: Option<TcpReconnection>,
     ) -> Self {
+        let ingress_info = Self::ingress_info(&read_half, socket_address, &addresses);
         Self {
             registry,
             read_half,
@@ -46,6 +67,114 @@ impl TcpRecvProcessor {
             addresses,
             mode,
             flow_control_id,
+            message_sizes,
+            #[cfg(feature = "telemetry")]
+            metrics: TransportMetrics::new("tcp"),
+            protocol,
+            ingress_info,
+            permit,
+            reconnection,
+        }
+    }
+
+    fn ingress_info(
+        read_half: &OwnedReadHalf,
+        socket_address: SocketAddr,
+        addresses: &Addresses,
+    ) -> IngressInfo {
+        let local_address = read_half
+            .local_addr()
+            .map(|address| address.to_string())
+            .unwrap_or_default();
+        IngressInfo::new(
+            TCP,
+            local_address,
+            socket_address.to_string(),
+            addresses.sender_address().address(),
+        )
+    }
+
+    /// Notify the sender that the connection is closed
+    async fn notify_connection_closed(&self, ctx: &Context) -> Result<()> {
+        self.notify_sender(ctx, TcpSendWorkerMsg::ConnectionClosed)
+            .await
+    }
+
+    async fn notify_sender(&self, ctx: &Context, msg: TcpSendWorkerMsg) -> Result<()> {
+        ctx.send_from_address(
+            self.addresses.sender_internal_address().clone(),
+            msg,
+            self.addresses.receiver_internal_address().clone(),
+        )
+        .await
+    }
+
+    /// Connect to the peer again, waiting between the attempts as specified by the backoff
+    /// policy of the connection. The sender is notified when the connection is back, or closed
+    /// when the maximum number of attempts is reached.
+    /// Return false if the connection could not be re-established
+    async fn reconnect(&mut self, ctx: &Context, reconnection: &TcpReconnection) -> Result<bool> {
+        reconnection.set_reconnecting(true);
+        self.notify_sender(ctx, TcpSendWorkerMsg::Reconnecting)
+            .await?;
+
+        let mut delays = reconnection.backoff().delays();
+        loop {
+            match TcpSendWorker::connect(self.socket_address).await {
+                Ok((read_half, write_half)) => {
+                    info!("Reconnected to peer '{}'", self.socket_address);
+                    self.ingress_info =
+                        Self::ingress_info(&read_half, self.socket_address, &self.addresses);
+                    self.read_half = read_half;
+                    // the handshake is sent again by the sender on the new connection
+                    self.protocol.set(TcpProtocol::Pending);
+                    reconnection.set_write_half(write_half);
+                    reconnection.set_reconnecting(false);
+                    self.notify_sender(ctx, TcpSendWorkerMsg::Reconnected)
+                        .await?;
+                    return Ok(true);
+                }
+                Err(e) => match delays.next_delay() {
+                    Some(delay) => {
+                        debug!(
+                            "Failed to reconnect to peer '{}', retrying in {delay:?}: {e}",
+                            self.socket_address
+                        );
+                        tokio::time::sleep(delay).await;
+                    }
+                    None => {
+                        warn!(
+                            "Failed to reconnect to peer '{}' after {} attempts, closing the connection",
+                            self.socket_address,
+                            delays.attempts() + 1
+                        );
+                        reconnection.set_reconnecting(false);
+                        self.notify_connection_closed(ctx).await?;
+                        return Ok(false);
+                    }
+                },
+            }
+        }
+    }
+
+    /// Settle the protocol used on the connection with the first message sent by the peer.
+    /// Return true if that message was the handshake of the peer
+    fn negotiate(&self, first_message: &TransportMessage) -> bool {
+        match TcpHandshake::from_message(first_message) {
+            Some(peer) => {
+                let protocol = TcpHandshake::supported().negotiate(&peer);
+                debug!(%protocol, "negotiated the protocol with peer {}", self.socket_address);
+                self.protocol.set(protocol);
+                true
+            }
+            None => {
+                debug!(
+                    "peer {} did not send a handshake, using the legacy protocol",
+                    self.socket_address
+                );
+                self.protocol.set(TcpProtocol::Legacy);
+                false
+            }
         }
     }
 
@@ -60,6 +189,9 @@ impl TcpRecvProcessor {
         mode: TcpConnectionMode,
         flow_control_id: &FlowControlId,
         receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+        protocol: &TcpProtocolState,
+        permit: Option<TcpConnectionPermit>,
+        reconnection: Option<TcpReconnection>,
     ) -> Result<()> {
         let receiver = TcpRecvProcessor::new(
             registry,
@@ -68,6 +200,10 @@ impl TcpRecvProcessor {
             addresses.clone(),
             mode,
             flow_control_id.clone(),
+            ctx.message_sizes().recorder(addresses.receiver_address()),
+            protocol.clone(),
+            permit,
+            reconnection,
         );
 
         let mailbox = Mailbox::new(
@@ -99,13 +235,16 @@ impl Processor for TcpRecvProcessor {
     async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
         ctx.set_cluster(crate::CLUSTER_NAME).await?;
 
-        self.registry.add_receiver_processor(TcpReceiverInfo::new(
-            ctx.address(),
-            self.addresses.sender_address().clone(),
-            self.socket_address,
-            self.mode,
-            self.flow_control_id.clone(),
-        ));
+        self.registry.add_receiver_processor(
+            TcpReceiverInfo::new(
+                ctx.address(),
+                self.addresses.sender_address().clone(),
+                self.socket_address,
+                self.mode,
+                self.flow_control_id.clone(),
+            )
+            .with_protocol(self.protocol.clone()),
+        );
 
         Ok(())
     }
@@ -113,6 +252,7 @@ impl Processor for TcpRecvProcessor {
     #[instrument(skip_all, name = "TcpRecvProcessor::shutdown")]
     async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
         self.registry.remove_receiver_processor(&ctx.address());
+        self.permit.take();
 
         Ok(())
     }
@@ -135,18 +275,20 @@ impl Processor for TcpRecvProcessor {
         let len = match self.read_half.read_u16().await {
             Ok(len) => len,
             Err(_e) => {
+                if let Some(reconnection) = self.reconnection.clone() {
+                    info!(
+                        "Connection to peer '{}' was closed; reconnecting",
+                        self.socket_address
+                    );
+                    return self.reconnect(ctx, &reconnection).await;
+                }
                 info!(
                     "Connection to peer '{}' was closed; dropping stream",
                     self.socket_address
                 );
 
                 // Notify sender tx is closed
-                ctx.send_from_address(
-                    self.addresses.sender_internal_address().clone(),
-                    TcpSendWorkerMsg::ConnectionClosed,
-                    self.addresses.receiver_internal_address().clone(),
-                )
-                .await?;
+                self.notify_connection_closed(ctx).await?;
                 return Ok(false);
             }
         };
@@ -165,11 +307,37 @@ impl Processor for TcpRecvProcessor {
             }
         }
 
+        // Account for the length header as well, like the sender does
+        self.message_sizes.record_inbound(buf.len() + 2);
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_inbound(buf.len() + 2);
+
         // Deserialize the message now
-        let transport_message = TransportMessage::decode(&buf).map_err(|e| {
-            error!("Error decoding message: {:?}", e);
-            TransportError::RecvBadMessage
-        })?;
+        let transport_message = match TransportMessage::decode(&buf) {
+            Ok(transport_message) => transport_message,
+            // The first bytes sent by a peer which is not an Ockam node can't be decoded
+            Err(e) if self.protocol.get() == TcpProtocol::Pending => {
+                #[cfg(feature = "telemetry")]
+                self.metrics.record_decode_failure();
+                warn!(
+                    "Unexpected first message from peer {}, closing the connection: {:?}",
+                    self.socket_address, e
+                );
+                self.notify_connection_closed(ctx).await?;
+                return Ok(false);
+            }
+            Err(e) => {
+                #[cfg(feature = "telemetry")]
+                self.metrics.record_decode_failure();
+                error!("Error decoding message: {:?}", e);
+                return Err(TransportError::RecvBadMessage.into());
+            }
+        };
+
+        if self.protocol.get() == TcpProtocol::Pending && self.negotiate(&transport_message) {
+            return Ok(true);
+        }
+
         let local_message = LocalMessage::from_transport_message(transport_message);
         if !local_message.has_next_on_onward_route() {
             trace!("Got heartbeat message from: {}", self.socket_address);
@@ -178,7 +346,9 @@ impl Processor for TcpRecvProcessor {
 
         // Insert the peer address into the return route so that
         // reply routing can be properly resolved
-        let local_message = local_message.push_front_return_route(self.addresses.sender_address());
+        let local_message = local_message
+            .push_front_return_route(self.addresses.sender_address())
+            .with_ingress(&self.ingress_info)?;
 
         trace!("Message onward route: {}", local_message.onward_route_ref());
         trace!("Message return route: {}", local_message.return_route_ref());
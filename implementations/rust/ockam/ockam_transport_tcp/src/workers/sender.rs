@@ -1,5 +1,6 @@
+use crate::protocol::{TcpHandshake, TcpProtocolState};
 use crate::workers::Addresses;
-use crate::{TcpConnectionMode, TcpRegistry, TcpSenderInfo};
+use crate::{TcpConnectionMode, TcpOutbox, TcpReconnection, TcpRegistry, TcpSenderInfo};
 use cfg_if::cfg_if;
 use core::time::Duration;
 use ockam_core::flow_control::FlowControlId;
@@ -9,7 +10,12 @@ use ockam_core::{
     AllowSourceAddress, DenyAll, IncomingAccessControl,
 };
 use ockam_core::{Any, Decodable, Mailbox, Mailboxes, Message, Result, Routed, Worker};
-use ockam_node::{Context, WorkerBuilder};
+#[cfg(feature = "telemetry")]
+use ockam_node::telemetry::TransportMetrics;
+use ockam_node::{
+    Context, MessageSizeRecorder, NodeEvent, NodeEventKind, WorkerBuilder,
+    DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW,
+};
 use ockam_transport_core::{encode_transport_message, TransportError};
 
 use serde::{Deserialize, Serialize};
@@ -22,6 +28,10 @@ use tracing::{debug, info, instrument, trace, warn};
 #[derive(Serialize, Deserialize, Message, Clone)]
 pub(crate) enum TcpSendWorkerMsg {
     ConnectionClosed,
+    /// The connection is closed and the receiver is connecting to the peer again
+    Reconnecting,
+    /// The receiver connected to the peer again, the new write half is in the [`TcpReconnection`]
+    Reconnected,
 }
 
 /// A TCP sending message worker
@@ -34,16 +44,24 @@ pub(crate) enum TcpSendWorkerMsg {
 /// to dispatch to a remote peer.
 pub(crate) struct TcpSendWorker {
     registry: TcpRegistry,
-    write_half: OwnedWriteHalf,
+    /// Write half of the connection, unset while the connection is re-established
+    write_half: Option<OwnedWriteHalf>,
     socket_address: SocketAddr,
     addresses: Addresses,
     mode: TcpConnectionMode,
     receiver_flow_control_id: FlowControlId,
     rx_should_be_stopped: bool,
+    message_sizes: MessageSizeRecorder,
+    #[cfg(feature = "telemetry")]
+    metrics: TransportMetrics,
+    protocol: TcpProtocolState,
+    reconnection: Option<TcpReconnection>,
+    outbox: Option<TcpOutbox>,
 }
 
 impl TcpSendWorker {
     /// Create a new `TcpSendWorker`
+    #[allow(clippy::too_many_arguments)]
     fn new(
         registry: TcpRegistry,
         write_half: OwnedWriteHalf,
@@ -51,15 +69,25 @@ impl TcpSendWorker {
         addresses: Addresses,
         mode: TcpConnectionMode,
         receiver_flow_control_id: FlowControlId,
+        message_sizes: MessageSizeRecorder,
+        protocol: TcpProtocolState,
+        reconnection: Option<TcpReconnection>,
     ) -> Self {
+        let outbox = reconnection.as_ref().and_then(|r| r.outbox());
         Self {
             registry,
-            write_half,
+            write_half: Some(write_half),
             socket_address,
             addresses,
             receiver_flow_control_id,
             mode,
             rx_should_be_stopped: true,
+            message_sizes,
+            #[cfg(feature = "telemetry")]
+            metrics: TransportMetrics::new("tcp"),
+            protocol,
+            reconnection,
+            outbox,
         }
     }
 }
@@ -78,6 +106,8 @@ impl TcpSendWorker {
         mode: TcpConnectionMode,
         sender_incoming_access_control: Arc<dyn IncomingAccessControl>,
         receiver_flow_control_id: &FlowControlId,
+        protocol: &TcpProtocolState,
+        reconnection: Option<TcpReconnection>,
     ) -> Result<()> {
         trace!("Creating new TCP worker pair");
         let sender_worker = Self::new(
@@ -87,6 +117,9 @@ impl TcpSendWorker {
             addresses.clone(),
             mode,
             receiver_flow_control_id.clone(),
+            ctx.message_sizes().recorder(addresses.sender_address()),
+            protocol.clone(),
+            reconnection,
         );
 
         let main_mailbox = Mailbox::new(
@@ -103,8 +136,10 @@ impl TcpSendWorker {
             Arc::new(DenyAll),
         );
 
+        // handshake messages must not wait behind portal messages
         WorkerBuilder::new(sender_worker)
             .with_mailboxes(Mailboxes::new(main_mailbox, vec![internal_mailbox]))
+            .with_priority_lanes(DEFAULT_MAX_PRIORITY_MESSAGES_IN_A_ROW)
             .start(ctx)
             .await?;
 
@@ -119,6 +154,81 @@ impl TcpSendWorker {
         Ok(())
     }
 
+    /// Start the protocol negotiation. The handshake of the peer is received by the
+    /// TcpRecvProcessor which stores the agreed protocol in the shared protocol state
+    async fn send_handshake(&mut self) -> Result<()> {
+        let handshake = TcpHandshake::supported().encode_frame()?;
+        let Some(write_half) = self.write_half.as_mut() else {
+            return Ok(());
+        };
+        if write_half.write_all(handshake.as_slice()).await.is_err() {
+            warn!(
+                "Failed to send the handshake to peer {}",
+                self.socket_address
+            );
+        }
+        Ok(())
+    }
+
+    /// Buffer a message while the connection is re-established,
+    /// or reject it if there is no outbox
+    fn buffer(&mut self, msg: Vec<u8>) -> Result<()> {
+        match self.outbox.as_mut() {
+            Some(outbox) => outbox.push(msg),
+            None => Err(TransportError::ConnectionDrop)?,
+        }
+    }
+
+    /// Send the messages buffered while the connection was re-established, in order.
+    /// If the connection is closed again, the remaining messages are kept for the next connection
+    async fn flush_outbox(&mut self) {
+        let (Some(outbox), Some(write_half)) = (self.outbox.as_mut(), self.write_half.as_mut())
+        else {
+            return;
+        };
+        debug!(
+            "Sending {} buffered messages to peer {}",
+            outbox.len(),
+            self.socket_address
+        );
+        while let Some(msg) = outbox.pop_front() {
+            if write_half.write_all(msg.as_slice()).await.is_err() {
+                warn!(
+                    "Failed to send a buffered message to peer {}",
+                    self.socket_address
+                );
+                outbox.push_front(msg);
+                self.write_half = None;
+                return;
+            }
+        }
+    }
+
+    /// Drop the buffered messages, since the connection could not be re-established
+    fn drop_outbox(&mut self, ctx: &Context) {
+        let Some(outbox) = self.outbox.as_mut() else {
+            return;
+        };
+        let (messages, bytes) = outbox.clear();
+        if messages == 0 {
+            return;
+        }
+        warn!(
+            "Dropping {messages} buffered messages, the connection to peer {} could not be re-established",
+            self.socket_address
+        );
+        ctx.node_events().publish(
+            NodeEvent::new(
+                NodeEventKind::OutboxDropped,
+                self.addresses.sender_address().to_string(),
+            )
+            .with_detail("transport", "tcp")
+            .with_detail("peer", self.socket_address)
+            .with_detail("messages", messages)
+            .with_detail("bytes", bytes),
+        );
+    }
+
     #[instrument(skip_all, name = "TcpSendWorker::connect")]
     pub(crate) async fn connect(
         socket_address: SocketAddr,
@@ -161,15 +271,19 @@ impl Worker for TcpSendWorker {
     async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         ctx.set_cluster(crate::CLUSTER_NAME).await?;
 
-        self.registry.add_sender_worker(TcpSenderInfo::new(
-            self.addresses.sender_address().clone(),
-            self.addresses.receiver_address().clone(),
-            self.socket_address,
-            self.mode,
-            self.receiver_flow_control_id.clone(),
-        ));
+        self.registry.add_sender_worker(
+            TcpSenderInfo::new(
+                self.addresses.sender_address().clone(),
+                self.addresses.receiver_address().clone(),
+                self.socket_address,
+                self.mode,
+                self.receiver_flow_control_id.clone(),
+            )
+            .with_protocol(self.protocol.clone())
+            .with_reconnection(self.reconnection.clone()),
+        );
 
-        Ok(())
+        self.send_handshake().await
     }
 
     #[instrument(skip_all, name = "TcpSendWorker::shutdown")]
@@ -177,6 +291,14 @@ impl Worker for TcpSendWorker {
         self.registry
             .remove_sender_worker(self.addresses.sender_address());
 
+        // the users of a closed shared connection can't use it anymore
+        for user in self
+            .registry
+            .remove_shared_connection(self.addresses.sender_address())
+        {
+            let _ = ctx.stop_worker(user).await;
+        }
+
         if self.rx_should_be_stopped {
             let _ = ctx
                 .stop_processor(self.addresses.receiver_address().clone())
@@ -207,25 +329,55 @@ impl Worker for TcpSendWorker {
                     // No need to stop Receiver as it notified us about connection drop and will
                     // stop itself
                     self.rx_should_be_stopped = false;
+                    self.drop_outbox(ctx);
                     self.stop(ctx).await?;
 
                     return Ok(());
                 }
+                TcpSendWorkerMsg::Reconnecting => {
+                    info!("Reconnecting to peer {}", self.socket_address);
+                    self.write_half = None;
+                    return Ok(());
+                }
+                TcpSendWorkerMsg::Reconnected => {
+                    info!("Reconnected to peer {}", self.socket_address);
+                    self.write_half = self
+                        .reconnection
+                        .as_ref()
+                        .and_then(|reconnection| reconnection.take_write_half());
+                    self.send_handshake().await?;
+                    self.flush_outbox().await;
+                    return Ok(());
+                }
             }
         } else {
             let mut local_message = msg.into_local_message();
             // Remove our own address from the route so the other end
             // knows what to do with the incoming message
             local_message = local_message.pop_front_onward_route()?;
-            // Create a message buffer with prepended length
+            // Create a message buffer with prepended length.
+            // All the protocol versions supported so far share the same framing, whatever
+            // the outcome of the negotiation
+            trace!(protocol = %self.protocol.get(), "sending a message");
             let transport_message = local_message.into_transport_message();
             let msg = encode_transport_message(transport_message)?;
+            self.message_sizes.record_outbound(msg.len());
+            #[cfg(feature = "telemetry")]
+            self.metrics.record_outbound(msg.len());
 
-            if self.write_half.write_all(msg.as_slice()).await.is_err() {
+            let Some(write_half) = self.write_half.as_mut() else {
+                return self.buffer(msg);
+            };
+            if write_half.write_all(msg.as_slice()).await.is_err() {
                 warn!("Failed to send message to peer {}", self.socket_address);
-                self.stop(ctx).await?;
-
-                return Ok(());
+                if self.reconnection.is_none() {
+                    self.stop(ctx).await?;
+                    return Ok(());
+                }
+                // The receiver connects to the peer again once it notices that the
+                // connection is closed
+                self.write_half = None;
+                return self.buffer(msg);
             }
         }
 
@@ -0,0 +1,137 @@
+use crate::{TcpConnection, TcpConnectionOptions, TcpRegistry};
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControlOutgoingAccessControl;
+use ockam_core::{
+    async_trait, Address, AllowAll, AllowOnwardAddress, AllowSourceAddress, Any, Mailbox,
+    Mailboxes, Result, Route, Routed, Worker,
+};
+use ockam_node::{Context, WorkerBuilder};
+use tracing::{debug, instrument};
+
+/// A worker used by one of the users of a shared TCP connection
+///
+/// The messages sent by the user are sent through the shared connection, with the address
+/// of this worker in their return route, so that the replies come back through this worker.
+/// The replies are then delivered to the consumers of the [`FlowControlId`](ockam_core::flow_control::FlowControlId) of that user only,
+/// so that the users of a shared connection can't reach each other's workers.
+pub(crate) struct TcpSharedConnectionWorker {
+    registry: TcpRegistry,
+    /// Address used by the user to send messages
+    sender_address: Address,
+    /// Address receiving the replies from the shared connection
+    receiver_address: Address,
+    /// Sender address of the shared connection
+    connection_sender_address: Address,
+}
+
+impl TcpSharedConnectionWorker {
+    /// Start a worker for a new user of a shared connection and return the connection of that user
+    #[instrument(skip_all, name = "TcpSharedConnectionWorker::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: TcpRegistry,
+        connection: &TcpConnection,
+        options: TcpConnectionOptions,
+    ) -> Result<TcpConnection> {
+        let mode = connection.mode();
+        let sender_address =
+            Address::random_tagged(&format!("TcpSharedConnection_tx_addr_{}", mode));
+        let receiver_address =
+            Address::random_tagged(&format!("TcpSharedConnection_rx_addr_{}", mode));
+        options.setup_flow_control_for_shared_connection(
+            ctx.flow_controls(),
+            connection.flow_control_id(),
+            &sender_address,
+            &receiver_address,
+        );
+        let flow_control_id = options.flow_control_id;
+
+        let main_mailbox = Mailbox::new(
+            sender_address.clone(),
+            Arc::new(AllowAll),
+            Arc::new(AllowOnwardAddress(connection.sender_address().clone())),
+        );
+        let receiver_mailbox = Mailbox::new(
+            receiver_address.clone(),
+            Arc::new(AllowSourceAddress(connection.receiver_address().clone())),
+            Arc::new(FlowControlOutgoingAccessControl::new(
+                ctx.flow_controls(),
+                flow_control_id.clone(),
+                None,
+            )),
+        );
+
+        let worker = Self {
+            registry,
+            sender_address: sender_address.clone(),
+            receiver_address: receiver_address.clone(),
+            connection_sender_address: connection.sender_address().clone(),
+        };
+        WorkerBuilder::new(worker)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![receiver_mailbox]))
+            .start(ctx)
+            .await?;
+
+        Ok(TcpConnection::new(
+            sender_address,
+            receiver_address,
+            *connection.socket_address(),
+            mode,
+            flow_control_id,
+        )
+        .shared())
+    }
+}
+
+#[async_trait]
+impl Worker for TcpSharedConnectionWorker {
+    type Context = Context;
+    type Message = Any;
+
+    #[instrument(skip_all, name = "TcpSharedConnectionWorker::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        ctx.set_cluster(crate::CLUSTER_NAME).await
+    }
+
+    /// The shared connection is stopped with its last user
+    #[instrument(skip_all, name = "TcpSharedConnectionWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        if self.registry.release_connection(&self.sender_address) {
+            debug!(sender = %self.connection_sender_address, "Stopping the shared connection");
+            let _ = ctx
+                .stop_worker(self.connection_sender_address.clone())
+                .await;
+        }
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "TcpSharedConnectionWorker::handle_message", fields(worker = %ctx.address()))]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let recipient = msg.msg_addr();
+        let local_message = msg.into_local_message();
+        if recipient == self.sender_address {
+            // The replies come back through this worker
+            let local_message = local_message
+                .replace_front_onward_route(&self.connection_sender_address)?
+                .push_front_return_route(&self.receiver_address);
+            ctx.forward_from_address(local_message, self.sender_address.clone())
+                .await
+        } else {
+            // The answers to the replies are sent through this worker as well
+            let mut return_route = local_message.return_route();
+            let return_route: Route = return_route
+                .modify()
+                .replace(self.sender_address.clone())
+                .into();
+            let local_message = local_message
+                .pop_front_onward_route()?
+                .set_return_route(return_route);
+            ctx.forward_from_address(local_message, self.receiver_address.clone())
+                .await
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use ockam_core::{route, Decodable, Encodable, Result, Routed, TransportMessage, Worker};
+use ockam_node::Context;
+use ockam_transport_core::encode_transport_message;
+use ockam_transport_tcp::{
+    TcpConnectionOptions, TcpListenerOptions, TcpProtocol, TcpTransport, TCP_PROTOCOL_VERSION,
+};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.into_body()?).await
+    }
+}
+
+async fn setup(ctx: &mut Context) -> Result<(TcpTransport, String)> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+    Ok((transport, listener.socket_string()))
+}
+
+/// Read a length-prefixed transport message from a raw socket
+async fn read_frame(stream: &mut TcpStream) -> Result<TransportMessage> {
+    let len = stream.read_u16().await.unwrap();
+    let mut buf = vec![0; len as usize];
+    stream.read_exact(&mut buf).await.unwrap();
+    TransportMessage::decode(&buf)
+}
+
+#[ockam_macros::test]
+async fn new_peers_negotiate_the_protocol(ctx: &mut Context) -> Result<()> {
+    let (transport, listener_address) = setup(ctx).await?;
+    let connection = transport
+        .connect(listener_address, TcpConnectionOptions::new())
+        .await?;
+
+    let reply: String = ctx
+        .send_and_receive(
+            route![connection.sender_address().clone(), "echoer"],
+            "hello".to_string(),
+        )
+        .await?;
+    assert_eq!(reply, "hello");
+
+    // Both sides of the connection have received the handshake of their peer
+    let negotiated = TcpProtocol::Negotiated {
+        version: TCP_PROTOCOL_VERSION,
+        capabilities: Default::default(),
+    };
+    let registry = transport.registry();
+    assert_eq!(registry.get_all_sender_workers().len(), 2);
+    for sender in registry.get_all_sender_workers() {
+        assert_eq!(sender.protocol(), negotiated);
+    }
+    for receiver in registry.get_all_receiver_processors() {
+        assert_eq!(receiver.protocol(), negotiated);
+    }
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn a_peer_without_handshake_uses_the_legacy_protocol(ctx: &mut Context) -> Result<()> {
+    let (transport, listener_address) = setup(ctx).await?;
+
+    // An old peer sends its first message right away
+    let mut stream = TcpStream::connect(listener_address).await.unwrap();
+    let message = TransportMessage::v1(route!["echoer"], route!["old_peer"], "hello".encode()?);
+    stream
+        .write_all(&encode_transport_message(message)?)
+        .await
+        .unwrap();
+
+    // The handshake of the node is discarded by old peers, like a heartbeat
+    let handshake = read_frame(&mut stream).await?;
+    assert!(handshake.onward_route.is_empty());
+
+    let reply = read_frame(&mut stream).await?;
+    assert_eq!(reply.onward_route, route!["old_peer"]);
+    assert_eq!(String::decode(&reply.payload)?, "hello");
+
+    let senders = transport.registry().get_all_sender_workers();
+    assert_eq!(senders.len(), 1);
+    assert_eq!(senders[0].protocol(), TcpProtocol::Legacy);
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn a_connection_starting_with_garbage_is_closed(ctx: &mut Context) -> Result<()> {
+    let (transport, listener_address) = setup(ctx).await?;
+
+    let mut stream = TcpStream::connect(listener_address.clone()).await.unwrap();
+    stream
+        .write_all(&[0, 8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff])
+        .await
+        .unwrap();
+
+    // The node closes the connection after sending its handshake
+    let mut received = vec![];
+    let closed = tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut received))
+        .await
+        .is_ok();
+    assert!(closed, "the connection should be closed");
+
+    // Other connections are not impacted
+    let connection = transport
+        .connect(listener_address, TcpConnectionOptions::new())
+        .await?;
+    let reply: String = ctx
+        .send_and_receive(
+            route![connection.sender_address().clone(), "echoer"],
+            "hello".to_string(),
+        )
+        .await?;
+    assert_eq!(reply, "hello");
+    Ok(())
+}
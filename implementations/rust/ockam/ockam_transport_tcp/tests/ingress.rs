@@ -0,0 +1,72 @@
+use ockam_core::{route, Any, Decodable, Encodable, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport, TCP};
+
+/// Send back the received message, with the addresses seen by this worker as a payload.
+/// The local info of the received message, including its ingress information, is kept.
+pub struct Bouncer;
+
+#[ockam_core::worker]
+impl Worker for Bouncer {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let local_message = msg.into_local_message();
+        let ingress = local_message
+            .ingress()
+            .expect("missing ingress information");
+        assert_eq!(ingress.transport_type(), TCP);
+        assert!(!ingress.connection_id().is_empty());
+
+        let payload = format!("{} {}", ingress.local_address(), ingress.remote_address());
+        let return_route = local_message.return_route();
+        let local_message = local_message
+            .set_onward_route(return_route)
+            .set_return_route(route![])
+            .set_payload(payload.encode()?);
+        ctx.forward(local_message).await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn ingress_info__received_message__describes_the_tcp_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("bouncer", &options.spawner_flow_control_id());
+    ctx.start_worker("bouncer", Bouncer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+    let connection = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+
+    ctx.send(
+        route![connection.sender_address().clone(), "bouncer"],
+        "hello".to_string(),
+    )
+    .await?;
+    let reply = ctx.receive::<String>().await?;
+
+    // the bouncer received the message on the listener socket, from the client socket
+    let seen_by_bouncer = String::decode(reply.payload())?;
+    let (bouncer_local, bouncer_remote) = seen_by_bouncer.split_once(' ').unwrap();
+    assert_eq!(bouncer_local, listener.socket_string());
+
+    // the reply only carries the ingress information of the client side of the connection
+    let ingress = reply.ingress().expect("missing ingress information");
+    assert_eq!(ingress.transport_type(), TCP);
+    assert_eq!(ingress.remote_address(), listener.socket_string());
+    assert_eq!(ingress.local_address(), bouncer_remote);
+    assert_eq!(
+        ingress.connection_id(),
+        connection.sender_address().address()
+    );
+    assert_eq!(reply.local_message().local_info_ref().len(), 1);
+
+    Ok(())
+}
@@ -1,8 +1,8 @@
 use core::time::Duration;
 use ockam_core::compat::rand::{self, Rng};
-use ockam_core::{route, Result, Routed, Worker};
-use ockam_node::Context;
-use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+use ockam_core::{route, AllowAll, Encodable, LocalMessage, Result, Routed, Worker};
+use ockam_node::{Context, MessageReceiveOptions};
+use ockam_transport_tcp::{TcpConnection, TcpConnectionOptions, TcpListenerOptions, TcpTransport};
 
 pub struct Echoer;
 
@@ -173,3 +173,67 @@ async fn tcp_lifecycle__stop_listener__should_stop_accepting_connections(
     assert_eq!(reply2, msg2, "Should receive the same message");
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn tcp_lifecycle__shared_connection__should_isolate_its_users(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    // alice and bob use the same socket, with their own flow controls
+    let alice = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+    let bob = transport
+        .connect(
+            listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+    assert_eq!(
+        transport
+            .registry()
+            .get_shared_connection_users(alice.sender_address()),
+        Some(2)
+    );
+    assert_ne!(alice.flow_control_id(), bob.flow_control_id());
+
+    let mut alice_consumer = ctx
+        .new_detached("alice_consumer", AllowAll, AllowAll)
+        .await?;
+    ctx.flow_controls()
+        .add_consumer("alice_consumer", alice.flow_control_id());
+
+    // the echoer replies to the consumer of alice through the connection which was used
+    let echo = |connection: &TcpConnection| -> Result<LocalMessage> {
+        Ok(LocalMessage::new()
+            .with_onward_route(route![connection.clone(), "echoer"])
+            .with_return_route(route!["alice_consumer"])
+            .with_payload("hello".to_string().encode()?))
+    };
+
+    ctx.forward(echo(&alice)?).await?;
+    let reply = alice_consumer.receive::<String>().await?.into_body()?;
+    assert_eq!(reply, "hello");
+
+    // a reply received by bob is not delivered to the consumer of alice
+    ctx.forward(echo(&bob)?).await?;
+    let reply = alice_consumer
+        .receive_extended::<String>(
+            MessageReceiveOptions::new().with_timeout(Duration::from_millis(250)),
+        )
+        .await;
+    assert!(reply.is_err());
+
+    Ok(())
+}
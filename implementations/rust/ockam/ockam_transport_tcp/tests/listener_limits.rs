@@ -0,0 +1,171 @@
+use core::str::FromStr;
+use core::time::Duration;
+use ockam_core::{route, Address, Result, Routed, Worker};
+use ockam_node::Context;
+use ockam_transport_tcp::{
+    IpCidr, TcpConnectionOptions, TcpListener, TcpListenerOptions, TcpListenerStats, TcpTransport,
+};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, Instant};
+
+pub struct Echoer;
+
+#[ockam_core::worker]
+impl Worker for Echoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.into_body()?).await
+    }
+}
+
+/// Start a listener with the given options, and connect a well-behaved client to it
+async fn setup(
+    ctx: &mut Context,
+    options: TcpListenerOptions,
+) -> Result<(TcpTransport, TcpListener, Address)> {
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+    let client = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    assert_echo(ctx, client.sender_address()).await?;
+    Ok((transport, listener, client.sender_address().clone()))
+}
+
+async fn assert_echo(ctx: &mut Context, client: &Address) -> Result<()> {
+    let reply: String = ctx
+        .send_and_receive(route![client.clone(), "echoer"], "hello".to_string())
+        .await?;
+    assert_eq!(reply, "hello");
+    Ok(())
+}
+
+/// Open raw connections to the listener, without sending anything
+async fn flood(listener: &TcpListener, count: usize) -> Vec<TcpStream> {
+    let mut streams = vec![];
+    for _ in 0..count {
+        streams.push(TcpStream::connect(listener.socket_address()).await.unwrap());
+    }
+    streams
+}
+
+/// Wait until the listener has handled `count` connections
+async fn wait_for_stats(
+    transport: &TcpTransport,
+    listener: &TcpListener,
+    count: u64,
+) -> TcpListenerStats {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let stats = transport
+            .find_listener_by_socketaddress(*listener.socket_address())
+            .map(|info| info.stats())
+            .unwrap_or_default();
+        if stats.accepted + stats.rejected() >= count || Instant::now() > deadline {
+            return stats;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// Return true if the peer closed the connection
+async fn is_closed(stream: &mut TcpStream) -> bool {
+    let mut buffer = [0u8; 16];
+    matches!(
+        tokio::time::timeout(Duration::from_millis(500), stream.read(&mut buffer)).await,
+        Ok(Ok(0)) | Ok(Err(_))
+    )
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn listener_limits__accept_rate__should_close_excess_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new().with_max_accept_rate(1, 5);
+    let (transport, listener, client) = setup(ctx, options).await?;
+
+    let mut streams = flood(&listener, 100).await;
+    let stats = wait_for_stats(&transport, &listener, 101).await;
+
+    // the burst allows a few connections, in addition to the steady client,
+    // and a token may have been added while the connections were opened
+    assert!(stats.accepted <= 8, "{stats:?}");
+    assert!(stats.rejected_by_rate >= 93, "{stats:?}");
+    assert_eq!(stats.accepted + stats.rejected_by_rate, 101, "{stats:?}");
+    assert!(is_closed(streams.last_mut().unwrap()).await);
+
+    assert_echo(ctx, &client).await?;
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn listener_limits__connections_per_source__should_close_excess_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new().with_max_connections_per_source(4);
+    let (transport, listener, client) = setup(ctx, options).await?;
+
+    let mut streams = flood(&listener, 50).await;
+    let stats = wait_for_stats(&transport, &listener, 51).await;
+    assert_eq!(stats.accepted, 4, "{stats:?}");
+    assert_eq!(stats.rejected_by_source_limit, 47, "{stats:?}");
+    assert!(!is_closed(&mut streams[0]).await);
+    assert!(is_closed(streams.last_mut().unwrap()).await);
+
+    assert_echo(ctx, &client).await?;
+
+    // once the connections are closed, the source can connect again
+    drop(streams);
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let mut stream = flood(&listener, 1).await;
+        if !is_closed(&mut stream[0]).await {
+            break;
+        }
+        assert!(Instant::now() < deadline, "the source can't connect again");
+    }
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn listener_limits__source_filter__should_close_denied_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new().deny_source(IpCidr::from_str("127.0.0.1/32")?);
+    ctx.start_worker("echoer", Echoer).await?;
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let mut streams = flood(&listener, 20).await;
+    let stats = wait_for_stats(&transport, &listener, 20).await;
+    assert_eq!(stats.accepted, 0, "{stats:?}");
+    assert_eq!(stats.rejected_by_source_filter, 20, "{stats:?}");
+    for stream in streams.iter_mut() {
+        assert!(is_closed(stream).await);
+    }
+
+    // loopback clients are allowed by this listener
+    let options = TcpListenerOptions::new()
+        .allow_source(IpCidr::from_str("10.0.0.0/8")?)
+        .allow_source(IpCidr::from_str("127.0.0.0/8")?);
+    ctx.flow_controls()
+        .add_consumer("echoer", &options.spawner_flow_control_id());
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+    let client = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    assert_echo(ctx, client.sender_address()).await?;
+
+    ctx.stop().await
+}
@@ -1,13 +1,19 @@
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 
 use ockam_core::compat::rand::random;
-use ockam_core::{route, Result};
+use ockam_core::{async_trait, route, Any, LocalInfo, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_tcp::{
-    TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
+    HttpRewrite, PortalMessage, TcpConnectionOptions, TcpInletOptions, TcpInletPreCheck,
+    TcpListenerOptions, TcpOutletConnectionPool, TcpOutletIdentityFormat,
+    TcpOutletIdentityForwarding, TcpOutletOptions, TcpPortalBandwidthLimiter, TcpPortalHealth,
+    TcpPortalPacking, TcpPortalPause, TcpPortalPeerIdentifier, TcpTransport,
+    PROXY_PROTOCOL_IDENTIFIER_TLV,
 };
 
 const LENGTH: usize = 32;
@@ -83,6 +89,50 @@ async fn portal__standard_flow__should_succeed(ctx: &mut Context) -> Result<()>
     Ok(())
 }
 
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__inlet_with_bound_listener__should_serve_waiting_clients(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let outlet_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet",
+        outlet_listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+
+    // A client connects before the inlet is created
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let inlet_addr = listener.local_addr().unwrap();
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+
+    let (socket_addr, _) = tcp
+        .create_inlet_with_listener(listener, route!["outlet"], TcpInletOptions::new())
+        .await?;
+    assert_eq!(socket_addr, inlet_addr);
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = outlet_listener.accept().await.unwrap();
+
+        read_assert_binary(&mut stream, payload1).await;
+        write_binary(&mut stream, payload2).await;
+        stream
+    });
+
+    write_binary(&mut stream, payload1).await;
+    read_assert_binary(&mut stream, payload2).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[ockam_macros::test(timeout = 5000)]
 async fn portal__reverse_flow__should_succeed(ctx: &mut Context) -> Result<()> {
@@ -228,3 +278,939 @@ async fn portal__tcp_connection_with_invalid_message_flow__should_not_succeed(
 
     Ok(())
 }
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__two_portals_to_the_same_peer__should_share_one_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    let outlet_flow_control_id = options.spawner_flow_control_id();
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let tcp_listener = tcp.listen("127.0.0.1:0", options).await?;
+
+    let connection1 = tcp
+        .connect(
+            tcp_listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+    let connection2 = tcp
+        .connect(
+            tcp_listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+
+    // only one socket is opened to the peer
+    ctx.sleep(Duration::from_millis(100)).await; // Wait for workers to add themselves to the registry
+    assert_ne!(connection1.sender_address(), connection2.sender_address());
+    let outgoing_senders = tcp
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .filter(|s| &s.socket_address() == tcp_listener.socket_address())
+        .count();
+    assert_eq!(outgoing_senders, 1);
+    assert_eq!(
+        tcp.registry()
+            .get_shared_connection_users(connection1.sender_address()),
+        Some(2)
+    );
+
+    // an echo server is the target of the outlet
+    let echo_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let echo_address = echo_listener.local_addr().unwrap().to_string();
+    let echo_server = tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = echo_listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let mut payload = [0u8; LENGTH];
+                while stream.read_exact(&mut payload).await.is_ok() {
+                    write_binary(&mut stream, payload).await;
+                }
+            });
+        }
+    });
+    tcp.create_outlet(
+        "outlet",
+        echo_address,
+        TcpOutletOptions::new().as_consumer(&outlet_flow_control_id),
+    )
+    .await?;
+
+    let (inlet1, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route![connection1.clone(), "outlet"],
+            TcpInletOptions::new(),
+        )
+        .await?;
+    let (inlet2, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route![connection2.clone(), "outlet"],
+            TcpInletOptions::new(),
+        )
+        .await?;
+
+    let mut stream1 = TcpStream::connect(inlet1).await.unwrap();
+    let mut stream2 = TcpStream::connect(inlet2).await.unwrap();
+    for stream in [&mut stream1, &mut stream2] {
+        let payload = generate_binary();
+        write_binary(stream, payload).await;
+        read_assert_binary(stream, payload).await;
+    }
+
+    // closing one portal releases the connection without breaking the other portal
+    connection1.stop(ctx).await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        tcp.registry()
+            .get_shared_connection_users(connection2.sender_address()),
+        Some(1)
+    );
+    let payload = generate_binary();
+    write_binary(&mut stream2, payload).await;
+    read_assert_binary(&mut stream2, payload).await;
+
+    // the connection is closed by its last user
+    connection2.stop(ctx).await?;
+    ctx.sleep(Duration::from_millis(100)).await;
+    assert!(!tcp
+        .registry()
+        .get_all_sender_workers()
+        .iter()
+        .any(|s| &s.socket_address() == tcp_listener.socket_address()));
+
+    echo_server.abort();
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__connection__should_not_be_shared_by_default(ctx: &mut Context) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+    let tcp_listener = tcp.listen("127.0.0.1:0", TcpListenerOptions::new()).await?;
+
+    let shared = tcp
+        .connect(
+            tcp_listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+    let isolated = tcp
+        .connect(tcp_listener.socket_string(), TcpConnectionOptions::new())
+        .await?;
+    assert!(shared.is_shared());
+    assert!(!isolated.is_shared());
+    assert_eq!(
+        tcp.registry()
+            .get_shared_connection_users(isolated.sender_address()),
+        None
+    );
+
+    // a connection which is not shared is never reused either
+    let reused = tcp
+        .connect(
+            tcp_listener.socket_string(),
+            TcpConnectionOptions::new().shared(),
+        )
+        .await?;
+    assert_eq!(
+        tcp.registry()
+            .get_shared_connection_users(reused.sender_address()),
+        Some(2)
+    );
+    ctx.sleep(Duration::from_millis(100)).await; // Wait for workers to add themselves to the registry
+    let outgoing_senders = tcp
+        .registry()
+        .get_all_sender_workers()
+        .into_iter()
+        .filter(|s| &s.socket_address() == tcp_listener.socket_address())
+        .count();
+    assert_eq!(outgoing_senders, 2);
+    Ok(())
+}
+
+/// Forward messages to the next hop and count the portal payloads going through
+struct PayloadCounter(Arc<AtomicUsize>);
+
+#[async_trait]
+impl Worker for PayloadCounter {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        if let Ok(PortalMessage::Payload(..)) = PortalMessage::decode(msg.payload()) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+        ctx.forward(msg.into_local_message().step_forward(&ctx.address())?)
+            .await
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__packing_small_writes__should_send_fewer_messages(ctx: &mut Context) -> Result<()> {
+    const WRITES: usize = 100;
+
+    let tcp = TcpTransport::create(ctx).await?;
+    let packing = TcpPortalPacking::new(Duration::from_millis(50), 1024);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_packing(packing),
+    )
+    .await?;
+
+    let payloads_count = Arc::new(AtomicUsize::new(0));
+    ctx.start_worker("counter", PayloadCounter(payloads_count.clone()))
+        .await?;
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["counter", "outlet"],
+            TcpInletOptions::new().with_packing(packing),
+        )
+        .await?;
+
+    let expected: Vec<u8> = (0..WRITES).map(|i| i as u8).collect();
+    let expected_clone = expected.clone();
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![0u8; WRITES];
+        stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected_clone);
+    });
+
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    for byte in expected {
+        stream.write_all(&[byte]).await.unwrap();
+        stream.flush().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    let payloads_count = payloads_count.load(Ordering::Relaxed);
+    assert!(payloads_count > 0);
+    assert!(
+        payloads_count < WRITES / 5,
+        "{payloads_count} portal messages were sent for {WRITES} writes"
+    );
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__bandwidth_limit__should_slow_down_the_transfer(ctx: &mut Context) -> Result<()> {
+    const LIMIT: u64 = 100_000;
+    const SIZE: usize = 300_000;
+
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+
+    let bandwidth = TcpPortalBandwidthLimiter::new(Some(LIMIT));
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_bandwidth_limiter(bandwidth.clone()),
+        )
+        .await?;
+
+    let expected: Vec<u8> = (0..SIZE).map(|i| i as u8).collect();
+    let expected_clone = expected.clone();
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![0u8; SIZE];
+        stream.read_exact(&mut received).await.unwrap();
+        assert_eq!(received, expected_clone);
+    });
+
+    let start = tokio::time::Instant::now();
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    stream.write_all(&expected).await.unwrap();
+    stream.flush().await.unwrap();
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    // The burst is sent right away, the rest of the data at the limited rate
+    let elapsed = start.elapsed();
+    let burst = LIMIT as f64 * TcpPortalBandwidthLimiter::BURST.as_secs_f64();
+    let expected_duration = Duration::from_secs_f64((SIZE as f64 - burst) / LIMIT as f64);
+    assert!(
+        elapsed >= expected_duration.mul_f64(0.8) && elapsed <= expected_duration.mul_f64(2.0),
+        "the transfer took {elapsed:?}, expected around {expected_duration:?}"
+    );
+    assert!(bandwidth.throughput() > 0);
+
+    Ok(())
+}
+
+/// Start a listener counting the accepted connections.
+/// Connections are echoed and the index of the last connection which received data is recorded,
+/// unless `close` is true, in which case connections are closed as soon as they are accepted.
+async fn counting_listener(close: bool) -> (String, Arc<AtomicUsize>, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    let accepted = Arc::new(AtomicUsize::new(0));
+    let served_by = Arc::new(AtomicUsize::new(usize::MAX));
+
+    let accepted_clone = accepted.clone();
+    let served_by_clone = served_by.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let index = accepted_clone.fetch_add(1, Ordering::Relaxed);
+            if close {
+                drop(stream);
+                continue;
+            }
+            let served_by = served_by_clone.clone();
+            tokio::spawn(async move {
+                let mut buffer = [0u8; LENGTH];
+                while let Ok(n) = stream.read(&mut buffer).await {
+                    if n == 0 {
+                        break;
+                    }
+                    served_by.store(index, Ordering::Relaxed);
+                    stream.write_all(&buffer[..n]).await.unwrap();
+                }
+            });
+        }
+    });
+
+    (bind_address, accepted, served_by)
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__outlet_pool__should_not_connect_on_new_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+    let (bind_address, accepted, served_by) = counting_listener(false).await;
+
+    let pool = TcpOutletConnectionPool::new(2);
+    tcp.create_outlet(
+        "outlet",
+        bind_address,
+        TcpOutletOptions::new().with_connection_pool(pool.clone()),
+    )
+    .await?;
+
+    // The pool is filled in the background
+    while pool.stats().available < 2 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(accepted.load(Ordering::Relaxed), 2);
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let payload = generate_binary();
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    write_binary(&mut stream, payload).await;
+    read_assert_binary(&mut stream, payload).await;
+
+    // The data went through one of the connections established in advance
+    assert!(served_by.load(Ordering::Relaxed) < 2);
+    let stats = pool.stats();
+    assert_eq!(stats.hits, 1);
+    assert_eq!(stats.misses, 0);
+
+    // The used connection is replaced
+    while pool.stats().available < 2 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(accepted.load(Ordering::Relaxed), 3);
+    assert_eq!(pool.stats().established, 3);
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__outlet_pool__should_not_hand_out_stale_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    // connections closed by the peer are evicted
+    let (bind_address, _accepted, _) = counting_listener(true).await;
+    let pool = TcpOutletConnectionPool::new(1);
+    tcp.create_outlet(
+        "closed_outlet",
+        bind_address,
+        TcpOutletOptions::new().with_connection_pool(pool.clone()),
+    )
+    .await?;
+    while pool.stats().available < 1 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    // let the connection close reach the pool
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["closed_outlet"],
+            TcpInletOptions::new(),
+        )
+        .await?;
+    let _stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    while pool.stats().misses < 1 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let stats = pool.stats();
+    assert_eq!(stats.hits, 0);
+    assert!(stats.evicted >= 1);
+
+    // connections idle for too long are evicted
+    let (bind_address, accepted, served_by) = counting_listener(false).await;
+    let pool = TcpOutletConnectionPool::new(1).with_idle_timeout(Duration::from_millis(200));
+    tcp.create_outlet(
+        "idle_outlet",
+        bind_address,
+        TcpOutletOptions::new().with_connection_pool(pool.clone()),
+    )
+    .await?;
+    while pool.stats().available < 1 {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["idle_outlet"], TcpInletOptions::new())
+        .await?;
+    let payload = generate_binary();
+    let mut stream = TcpStream::connect(inlet_saddr).await.unwrap();
+    write_binary(&mut stream, payload).await;
+    read_assert_binary(&mut stream, payload).await;
+
+    let stats = pool.stats();
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.misses, 1);
+    assert!(stats.evicted >= 1);
+    // the data went through a connection established by the outlet itself
+    assert!(served_by.load(Ordering::Relaxed) >= 1);
+    assert!(accepted.load(Ordering::Relaxed) >= 2);
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__close_connection__should_not_affect_other_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let (inlet_saddr, inlet_address) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    // Open the connections one after the other to know which target stream matches which client
+    let mut client1 = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client1, payload).await;
+    let (mut target1, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target1, payload).await;
+
+    let mut client2 = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client2, payload).await;
+    let (mut target2, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target2, payload).await;
+
+    let connections = tcp.portal_connections(&inlet_address);
+    assert_eq!(connections.len(), 2);
+    let connection1 = connections
+        .iter()
+        .find(|c| c.peer() == client1.local_addr().unwrap())
+        .unwrap();
+    assert_eq!(connection1.bytes_read(), LENGTH as u64);
+
+    tcp.close_portal_connection(connection1.id())?;
+
+    // Both the client and the target of the closed connection are disconnected
+    let mut buf = [0u8; LENGTH];
+    assert_eq!(client1.read(&mut buf).await.unwrap(), 0);
+    assert_eq!(target1.read(&mut buf).await.unwrap(), 0);
+
+    // The other connection keeps transferring data in both directions
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+    write_binary(&mut client2, payload1).await;
+    read_assert_binary(&mut target2, payload1).await;
+    write_binary(&mut target2, payload2).await;
+    read_assert_binary(&mut client2, payload2).await;
+
+    // Wait for the closed connection to be removed from the registry
+    tokio::time::sleep(Duration::from_secs(3)).await;
+    let connections = tcp.portal_connections(&inlet_address);
+    assert_eq!(connections.len(), 1);
+    assert_eq!(connections[0].peer(), client2.local_addr().unwrap());
+    assert_eq!(connections[0].bytes_read(), 2 * LENGTH as u64);
+    assert_eq!(connections[0].bytes_written(), LENGTH as u64);
+
+    assert!(tcp.close_portal_connection(connection1.id()).is_err());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__pre_check__should_reset_connections_when_the_far_side_is_unhealthy(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    // healthy: the outlet can reach its target
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let pre_check = TcpInletPreCheck::new();
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_pre_check(pre_check.clone()),
+        )
+        .await?;
+    assert_eq!(pre_check.last_status(), None);
+
+    let payload = generate_binary();
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    write_binary(&mut client, payload).await;
+    // the target was probed by the outlet before the portal connection
+    let (_, _) = listener.accept().await.unwrap();
+    let (mut target, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target, payload).await;
+    assert_eq!(
+        pre_check.last_status().map(|s| s.health),
+        Some(TcpPortalHealth::Healthy)
+    );
+
+    // upstream down: nothing listens on the target address of the outlet
+    let target_address = {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        listener.local_addr().unwrap().to_string()
+    };
+    tcp.create_outlet("down_outlet", target_address, TcpOutletOptions::new())
+        .await?;
+    let pre_check = TcpInletPreCheck::new();
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["down_outlet"],
+            TcpInletOptions::new().with_pre_check(pre_check.clone()),
+        )
+        .await?;
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert_connection_is_reset(&mut client).await;
+    assert_eq!(
+        pre_check.last_status().map(|s| s.health),
+        Some(TcpPortalHealth::UpstreamDown)
+    );
+
+    // outlet unreachable: there is no outlet at the end of the route
+    let pre_check = TcpInletPreCheck::new().with_timeout(Duration::from_millis(500));
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["missing_outlet"],
+            TcpInletOptions::new().with_pre_check(pre_check.clone()),
+        )
+        .await?;
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert_connection_is_reset(&mut client).await;
+    assert_eq!(
+        pre_check.last_status().map(|s| s.health),
+        Some(TcpPortalHealth::OutletUnreachable)
+    );
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__paused_inlet__should_only_refuse_new_connections(ctx: &mut Context) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new(),
+    )
+    .await?;
+    let pause = TcpPortalPause::new();
+    let (inlet_saddr, inlet_address) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_pause(pause.clone()),
+        )
+        .await?;
+
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client, payload).await;
+    let (mut target, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target, payload).await;
+
+    assert!(pause.pause());
+
+    // The new connections are reset while the inlet keeps listening
+    let mut refused = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert_connection_is_reset(&mut refused).await;
+
+    // The existing connection keeps transferring data in both directions
+    let payload1 = generate_binary();
+    let payload2 = generate_binary();
+    write_binary(&mut client, payload1).await;
+    read_assert_binary(&mut target, payload1).await;
+    write_binary(&mut target, payload2).await;
+    read_assert_binary(&mut client, payload2).await;
+
+    // Draining closes the existing connection
+    assert_eq!(tcp.close_portal_connections(&inlet_address), 1);
+    let mut buf = [0u8; LENGTH];
+    assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+
+    assert!(pause.resume());
+
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client, payload).await;
+    let (mut target, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target, payload).await;
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 15000)]
+async fn portal__paused_outlet__should_only_refuse_new_connections(
+    ctx: &mut Context,
+) -> Result<()> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let pause = TcpPortalPause::new();
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new().with_pause(pause.clone()),
+    )
+    .await?;
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client, payload).await;
+    let (mut target, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target, payload).await;
+
+    assert!(pause.pause());
+
+    // The outlet refuses the new portal connections, and the inlet resets its clients
+    let mut refused = TcpStream::connect(inlet_saddr).await.unwrap();
+    assert_connection_is_reset(&mut refused).await;
+
+    // The existing connection is not affected
+    let payload = generate_binary();
+    write_binary(&mut target, payload).await;
+    read_assert_binary(&mut client, payload).await;
+
+    assert!(pause.resume());
+
+    let mut client = TcpStream::connect(inlet_saddr).await.unwrap();
+    let payload = generate_binary();
+    write_binary(&mut client, payload).await;
+    let (mut target, _) = listener.accept().await.unwrap();
+    read_assert_binary(&mut target, payload).await;
+
+    Ok(())
+}
+
+async fn assert_connection_is_reset(stream: &mut TcpStream) {
+    let mut buf = [0u8; LENGTH];
+    let result = stream.read(&mut buf).await;
+    assert!(
+        !matches!(result, Ok(n) if n > 0),
+        "the connection should be closed"
+    );
+}
+
+/// Return the same identifier for every peer, or no identifier at all
+#[derive(Debug)]
+struct StaticPeerIdentifier(Option<String>);
+
+impl TcpPortalPeerIdentifier for StaticPeerIdentifier {
+    fn peer_identifier(&self, _local_info: &[LocalInfo]) -> Option<String> {
+        self.0.clone()
+    }
+}
+
+async fn setup_identity_forwarding(
+    ctx: &Context,
+    format: TcpOutletIdentityFormat,
+    identifier: Option<&str>,
+) -> Result<(String, TcpListener)> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let identity_forwarding = TcpOutletIdentityForwarding::new(
+        format,
+        Arc::new(StaticPeerIdentifier(identifier.map(|i| i.to_string()))),
+    )?;
+    tcp.create_outlet(
+        "outlet",
+        listener.local_addr().unwrap().to_string(),
+        TcpOutletOptions::new().with_identity_forwarding(identity_forwarding),
+    )
+    .await?;
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet("127.0.0.1:0", route!["outlet"], TcpInletOptions::new())
+        .await?;
+
+    Ok((inlet_saddr.to_string(), listener))
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__identity_forwarding_with_http_header__should_add_the_peer_identifier(
+    ctx: &mut Context,
+) -> Result<()> {
+    let (inlet_addr, listener) = setup_identity_forwarding(
+        ctx,
+        TcpOutletIdentityFormat::HttpHeader(TcpOutletIdentityFormat::DEFAULT_HTTP_HEADER.into()),
+        Some("I123"),
+    )
+    .await?;
+
+    // The upstream server echoes the head of the request
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut head = vec![];
+        let mut buf = [0u8; 1024];
+        while !head.ends_with(b"\r\n\r\n") {
+            let n = stream.read(&mut buf).await.unwrap();
+            assert_ne!(n, 0);
+            head.extend_from_slice(&buf[..n]);
+        }
+        stream.write_all(&head).await.unwrap();
+        stream
+    });
+
+    // The header sent by the client is replaced, even if the request is split
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    stream
+        .write_all(b"X-Ockam-Identifier: Ifake\r\n\r\n")
+        .await
+        .unwrap();
+
+    let expected = b"GET / HTTP/1.1\r\nX-Ockam-Identifier: I123\r\nHost: localhost\r\n\r\n";
+    let mut received = vec![0u8; expected.len()];
+    stream.read_exact(&mut received).await.unwrap();
+    assert_eq!(received, expected);
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__identity_forwarding_with_proxy_protocol__should_send_the_peer_identifier(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload = generate_binary();
+    let (inlet_addr, listener) =
+        setup_identity_forwarding(ctx, TcpOutletIdentityFormat::ProxyProtocolV2, Some("I123"))
+            .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut header = [0u8; 16];
+        stream.read_exact(&mut header).await.unwrap();
+        // version 2 PROXY command, TCP over IPv4
+        assert_eq!(&header[12..14], &[0x21, 0x11]);
+        let length = u16::from_be_bytes([header[14], header[15]]) as usize;
+        let mut content = vec![0u8; length];
+        stream.read_exact(&mut content).await.unwrap();
+        let tlv = &content[12..];
+        assert_eq!(tlv[0], PROXY_PROTOCOL_IDENTIFIER_TLV);
+        assert_eq!(u16::from_be_bytes([tlv[1], tlv[2]]), 4);
+        assert_eq!(&tlv[3..], b"I123");
+
+        // The payload follows the header
+        read_assert_binary(&mut stream, payload).await;
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, payload).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__identity_forwarding_without_peer_identifier__should_not_connect(
+    ctx: &mut Context,
+) -> Result<()> {
+    let (inlet_addr, listener) =
+        setup_identity_forwarding(ctx, TcpOutletIdentityFormat::ProxyProtocolV2, None).await?;
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    write_binary(&mut stream, generate_binary()).await;
+
+    let accepted = tokio::time::timeout(Duration::from_secs(1), listener.accept()).await;
+    assert!(accepted.is_err(), "the outlet should not connect");
+
+    Ok(())
+}
+
+async fn setup_http_rewrite(ctx: &Context, rewrite: HttpRewrite) -> Result<(String, TcpListener)> {
+    let tcp = TcpTransport::create(ctx).await?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet("outlet", bind_address, TcpOutletOptions::new())
+        .await?;
+
+    let (inlet_saddr, _) = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_interceptor(Arc::new(rewrite)),
+        )
+        .await?;
+
+    Ok((inlet_saddr.to_string(), listener))
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__http_rewrite__should_rewrite_the_requests_seen_by_the_target(
+    ctx: &mut Context,
+) -> Result<()> {
+    let rewrite = HttpRewrite::new(
+        Some("backend.internal:8080".into()),
+        vec![("X-Environment".into(), "production".into())],
+    )?;
+    let (inlet_addr, listener) = setup_http_rewrite(ctx, rewrite).await?;
+
+    let expected: &[u8] = b"POST /items HTTP/1.1\r\nHost: backend.internal:8080\r\n\
+        Content-Length: 11\r\nX-Environment: production\r\n\r\nHost: a\r\n\r\n\
+        GET /items HTTP/1.1\r\nHost: backend.internal:8080\r\n\
+        X-Environment: production\r\n\r\n";
+    let response: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n";
+
+    // The upstream server records the requests and answers them
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut received = vec![0u8; expected.len()];
+        stream.read_exact(&mut received).await.unwrap();
+        stream.write_all(response).await.unwrap();
+        received
+    });
+
+    // The first request is split in the middle of a header, and its body looks like a header.
+    // The second request is sent on the same connection
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    stream
+        .write_all(b"POST /items HTTP/1.1\r\nHost: local")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    stream
+        .write_all(b"host:4000\r\nContent-Length: 11\r\nX-Environment: dev\r\n\r\nHost: a")
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    stream
+        .write_all(b"\r\n\r\nGET /items HTTP/1.1\r\nHost: localhost:4000\r\n\r\n")
+        .await
+        .unwrap();
+
+    let received = handle.await.unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&received),
+        String::from_utf8_lossy(expected)
+    );
+
+    // The responses are not rewritten
+    let mut received = vec![0u8; response.len()];
+    stream.read_exact(&mut received).await.unwrap();
+    assert_eq!(received, response);
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__http_rewrite_without_http__should_close_the_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let rewrite = HttpRewrite::new(Some("backend.internal".into()), vec![])?;
+    let (inlet_addr, listener) = setup_http_rewrite(ctx, rewrite).await?;
+
+    // The upstream server doesn't receive the refused bytes
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; LENGTH];
+        let n = stream.read(&mut buf).await.unwrap_or_default();
+        assert_eq!(n, 0);
+    });
+
+    let mut stream = TcpStream::connect(inlet_addr).await.unwrap();
+    stream.write_all(b"SSH-2.0-OpenSSH\r\n\r\n").await.unwrap();
+    assert_connection_is_reset(&mut stream).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    Ok(())
+}
@@ -0,0 +1,167 @@
+use core::time::Duration;
+use ockam_core::compat::backoff::Backoff;
+use ockam_core::{route, Address, Result, Routed, Worker};
+use ockam_node::{Context, NodeEventKind};
+use ockam_transport_tcp::{
+    TcpConnectionMode, TcpConnectionOptions, TcpListener, TcpListenerOptions, TcpTransport,
+};
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep, timeout, Instant};
+
+/// Worker storing the messages it receives
+pub struct Collector(Arc<Mutex<Vec<String>>>);
+
+#[ockam_core::worker]
+impl Worker for Collector {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        self.0.lock().unwrap().push(msg.into_body()?);
+        Ok(())
+    }
+}
+
+async fn listen(
+    ctx: &Context,
+    transport: &TcpTransport,
+    address: impl AsRef<str>,
+) -> Result<TcpListener> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer("collector", &options.spawner_flow_control_id());
+    transport.listen(address, options).await
+}
+
+/// Stop the listener and close the connections it accepted
+async fn kill_connections(transport: &TcpTransport, listener: &TcpListener) -> Result<()> {
+    transport
+        .stop_listener(listener.processor_address())
+        .await?;
+    for sender in transport.registry().get_all_sender_workers() {
+        if matches!(sender.mode(), TcpConnectionMode::Incoming) {
+            transport.disconnect(sender.address().clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn send(ctx: &Context, sender: &Address, messages: impl Iterator<Item = u32>) -> Result<()> {
+    for i in messages {
+        ctx.send(route![sender.clone(), "collector"], i.to_string())
+            .await?;
+    }
+    Ok(())
+}
+
+async fn wait_until(condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while !condition() {
+        assert!(Instant::now() < deadline, "the condition was never met");
+        sleep(Duration::from_millis(20)).await;
+    }
+}
+
+fn is_reconnecting(transport: &TcpTransport, sender: &Address) -> bool {
+    transport
+        .registry()
+        .get_all_sender_workers()
+        .iter()
+        .any(|s| s.address() == sender && s.is_reconnecting())
+}
+
+fn expected(messages: impl Iterator<Item = u32>) -> Vec<String> {
+    messages.map(|i| i.to_string()).collect()
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn reconnect__outbox__should_deliver_the_messages_buffered_within_the_budget(
+    ctx: &mut Context,
+) -> Result<()> {
+    let received = Arc::new(Mutex::new(vec![]));
+    ctx.start_worker("collector", Collector(received.clone()))
+        .await?;
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = listen(ctx, &transport, "127.0.0.1:0").await?;
+
+    let options = TcpConnectionOptions::new()
+        .with_reconnect(Backoff::new(
+            Duration::from_millis(100),
+            Duration::from_millis(100),
+        ))
+        .with_outbox(5, 1024 * 1024);
+    let connection = transport.connect(listener.socket_string(), options).await?;
+    let sender = connection.sender_address().clone();
+
+    send(ctx, &sender, 0..5).await?;
+    wait_until(|| received.lock().unwrap().len() == 5).await;
+
+    // the connection is closed and the peer can't be reached until it listens again
+    kill_connections(&transport, &listener).await?;
+    wait_until(|| is_reconnecting(&transport, &sender)).await;
+
+    // the first messages fit in the outbox, the following ones are rejected
+    send(ctx, &sender, 5..12).await?;
+    listen(ctx, &transport, listener.socket_string()).await?;
+    wait_until(|| received.lock().unwrap().len() == 10).await;
+    assert!(!is_reconnecting(&transport, &sender));
+
+    // the connection is used as before once it is re-established
+    send(ctx, &sender, 12..13).await?;
+    wait_until(|| received.lock().unwrap().len() == 11).await;
+
+    let mut expected_messages = expected(0..10);
+    expected_messages.push("12".to_string());
+    assert_eq!(*received.lock().unwrap(), expected_messages);
+
+    ctx.stop().await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn reconnect__failed__should_drop_the_outbox_and_close_the_connection(
+    ctx: &mut Context,
+) -> Result<()> {
+    let received = Arc::new(Mutex::new(vec![]));
+    ctx.start_worker("collector", Collector(received.clone()))
+        .await?;
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = listen(ctx, &transport, "127.0.0.1:0").await?;
+    let mut events = ctx.node_events().subscribe();
+
+    let options = TcpConnectionOptions::new()
+        .with_reconnect(
+            Backoff::new(Duration::from_millis(200), Duration::from_millis(200))
+                .with_max_attempts(5),
+        )
+        .with_outbox(10, 1024 * 1024);
+    let connection = transport.connect(listener.socket_string(), options).await?;
+    let sender = connection.sender_address().clone();
+
+    send(ctx, &sender, 0..2).await?;
+    wait_until(|| received.lock().unwrap().len() == 2).await;
+
+    kill_connections(&transport, &listener).await?;
+    wait_until(|| is_reconnecting(&transport, &sender)).await;
+    send(ctx, &sender, 2..5).await?;
+
+    let event = timeout(Duration::from_secs(10), async {
+        loop {
+            let event = events.recv().await.unwrap();
+            if event.kind() == NodeEventKind::OutboxDropped {
+                return event;
+            }
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(event.subject(), sender.to_string());
+    assert_eq!(event.details().get("messages"), Some(&"3".to_string()));
+
+    // the connection is closed
+    wait_until(|| transport.registry().get_all_sender_workers().is_empty()).await;
+    assert_eq!(*received.lock().unwrap(), expected(0..2));
+
+    ctx.stop().await
+}
@@ -1,7 +1,8 @@
 use ockam_core::compat::rand::{self, Rng};
-use ockam_core::{route, Result, Routed, Worker};
+use ockam_core::{route, Any, Encodable, Message, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
+use serde::{Deserialize, Serialize};
 
 pub struct Echoer;
 
@@ -47,3 +48,94 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     };
     Ok(())
 }
+
+#[derive(Serialize, Deserialize, Message, Debug, Clone, PartialEq)]
+struct Request {
+    id: u32,
+    items: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Message, Debug, PartialEq)]
+struct Observation {
+    request: Request,
+    onward_route: String,
+}
+
+/// Reply with the received request and the onward route it was received with
+pub struct RequestEchoer;
+
+#[ockam_core::worker]
+impl Worker for RequestEchoer {
+    type Message = Request;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Request>) -> Result<()> {
+        let return_route = msg.return_route();
+        let onward_route = msg.onward_route().to_string();
+        let request = msg.into_body()?;
+        let observation = Observation {
+            request,
+            onward_route,
+        };
+        ctx.send(return_route, observation).await
+    }
+}
+
+/// Reply with the payload of the received message
+pub struct PayloadEchoer;
+
+#[ockam_core::worker]
+impl Worker for PayloadEchoer {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        ctx.send(msg.return_route(), msg.payload().to_vec()).await
+    }
+}
+
+#[ockam_macros::test]
+async fn local_and_remote_routes_deliver_the_same_message(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    for worker in ["request_echoer", "payload_echoer"] {
+        ctx.flow_controls()
+            .add_consumer(worker, &options.spawner_flow_control_id());
+    }
+    ctx.start_worker("request_echoer", RequestEchoer).await?;
+    ctx.start_worker("payload_echoer", PayloadEchoer).await?;
+
+    let transport = TcpTransport::create(ctx).await?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+    let addr = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?
+        .sender_address()
+        .clone();
+
+    let request = Request {
+        id: 42,
+        items: vec!["first".to_string(), "second".to_string()],
+    };
+
+    // the request is moved to a local worker, and encoded for a remote one
+    let local: Observation = ctx
+        .send_and_receive(route!["request_echoer"], request.clone())
+        .await?;
+    let remote: Observation = ctx
+        .send_and_receive(route![addr.clone(), "request_echoer"], request.clone())
+        .await?;
+    assert_eq!(local, remote);
+    assert_eq!(local.request, request);
+
+    // a worker accessing the payload receives the encoded request in both cases
+    let local: Vec<u8> = ctx
+        .send_and_receive(route!["payload_echoer"], request.clone())
+        .await?;
+    let remote: Vec<u8> = ctx
+        .send_and_receive(route![addr, "payload_echoer"], request.clone())
+        .await?;
+    assert_eq!(local, remote);
+    assert_eq!(local, request.encode()?);
+
+    Ok(())
+}
@@ -12,6 +12,10 @@ pub enum PunchError {
     /// Hole to peer not open
     HoleNotOpen,
 
+    /// The route of the peer was evicted from the Rendezvous service.
+    /// The query can be retried once the peer has registered again
+    PeerRouteEvicted,
+
     /// Internal error, possibly a bug
     Internal,
 }
@@ -29,6 +33,7 @@ impl From<PunchError> for Error {
         use PunchError::*;
         let kind = match err {
             RendezvousServiceNotFound | HoleNotOpen => Kind::NotFound,
+            PeerRouteEvicted => Kind::ResourceExhausted,
             Internal => Kind::Internal,
         };
         Error::new(Origin::Other, kind, err)
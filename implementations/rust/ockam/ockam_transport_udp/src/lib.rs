@@ -10,11 +10,15 @@
 use ockam_core::TransportType;
 
 pub use hole_puncher::{PunchError, UdpHolePuncher};
-pub use rendezvous_service::UdpRendezvousService;
+pub use options::{UdpReliabilityOptions, UdpRendezvousOptions, UdpTransportOptions};
+pub use rendezvous_service::{RendezvousCacheStats, UdpRendezvousService};
 pub use transport::UdpTransport;
 pub use transport::UdpTransportExtension;
+pub use workers::UdpReplayStats;
 
 mod hole_puncher;
+mod lru;
+mod options;
 mod rendezvous_service;
 mod router;
 mod transport;
@@ -0,0 +1,87 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// Map keeping track of the order in which its entries are used
+pub(crate) struct Lru<K, V> {
+    /// Value and last use of each entry
+    values: HashMap<K, (u64, V)>,
+    /// Entries by last use, from the least recently used
+    uses: BTreeMap<u64, K>,
+    last_use: u64,
+}
+
+impl<K, V> Default for Lru<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            uses: BTreeMap::new(),
+            last_use: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> Lru<K, V> {
+    pub(crate) fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.values.iter().map(|(key, (_, value))| (key, value))
+    }
+
+    pub(crate) fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.values.contains_key(key)
+    }
+
+    pub(crate) fn insert(&mut self, key: K, value: V) {
+        self.remove(&key);
+        self.last_use += 1;
+        self.uses.insert(self.last_use, key.clone());
+        self.values.insert(key, (self.last_use, value));
+    }
+
+    /// Return the value of an entry and mark it as the most recently used
+    pub(crate) fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// Return the value of an entry, to be modified, and mark it as the most recently used
+    pub(crate) fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (last_use, _) = self.values.get(key)?;
+        let owned_key = self.uses.remove(last_use)?;
+        self.last_use += 1;
+        self.uses.insert(self.last_use, owned_key);
+        let (last_use, value) = self.values.get_mut(key)?;
+        *last_use = self.last_use;
+        Some(value)
+    }
+
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (last_use, value) = self.values.remove(key)?;
+        self.uses.remove(&last_use);
+        Some(value)
+    }
+
+    pub(crate) fn pop_least_recently_used(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.uses.pop_first()?;
+        let (_, value) = self.values.remove(&key)?;
+        Some((key, value))
+    }
+}
@@ -0,0 +1,178 @@
+use core::time::Duration;
+
+/// Options of a UDP transport
+///
+/// Both the reliability layer and the replay protection are disabled by default.
+#[derive(Clone, Debug, Default)]
+pub struct UdpTransportOptions {
+    pub(crate) reliability: Option<UdpReliabilityOptions>,
+    pub(crate) replay_window: Option<usize>,
+}
+
+impl UdpTransportOptions {
+    /// Default number of sequence numbers tracked per peer by the replay protection
+    pub const DEFAULT_REPLAY_WINDOW: usize = 1024;
+
+    /// Transport options with the reliability layer and the replay protection disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enable the reliability layer on all the sockets of the transport
+    pub fn with_reliability(mut self, options: UdpReliabilityOptions) -> Self {
+        self.reliability = Some(options);
+        self
+    }
+
+    /// Enable the replay protection on all the sockets of the transport
+    ///
+    /// Every datagram is sent with a sequence number, incremented for each datagram sent
+    /// to the same peer. A received datagram is dropped if its sequence number was already
+    /// received from that peer, or if it is older than the last `window_size` sequence numbers.
+    ///
+    /// A datagram is dropped as well if its sequence number is too far ahead of the window,
+    /// since source addresses can be spoofed. The windows of the peers which have been idle
+    /// for a while, or of the least recently seen peers when there are too many of them,
+    /// are forgotten.
+    ///
+    /// Datagrams without a sequence number are only accepted from the peers which never sent
+    /// one, so that peers without replay protection can still talk to this transport.
+    /// Peers of this transport must support the sequence number header, but don't need to
+    /// enable the replay protection themselves.
+    pub fn with_replay_protection(mut self, window_size: usize) -> Self {
+        self.replay_window = Some(window_size.max(1));
+        self
+    }
+}
+
+/// Options for the optional reliability layer of the UDP transport
+///
+/// When enabled, every datagram carrying a message is tagged with a sequence
+/// number and acknowledged by the receiver. Unacknowledged datagrams are
+/// retransmitted with a capped exponential backoff, and duplicates are
+/// discarded by the receiver.
+///
+/// The reliability layer changes the datagram framing, so both peers must
+/// enable it for them to be able to talk to each other.
+#[derive(Clone, Debug)]
+pub struct UdpReliabilityOptions {
+    pub(crate) initial_retransmit_timeout: Duration,
+    pub(crate) max_retransmit_timeout: Duration,
+    pub(crate) max_retransmits: u32,
+}
+
+impl UdpReliabilityOptions {
+    /// Default delay before the first retransmission
+    pub const DEFAULT_INITIAL_RETRANSMIT_TIMEOUT: Duration = Duration::from_millis(200);
+    /// Default upper bound of the delay between two retransmissions
+    pub const DEFAULT_MAX_RETRANSMIT_TIMEOUT: Duration = Duration::from_secs(5);
+    /// Default number of retransmissions before a datagram is given up
+    pub const DEFAULT_MAX_RETRANSMITS: u32 = 10;
+
+    /// Reliability options with default values
+    pub fn new() -> Self {
+        Self {
+            initial_retransmit_timeout: Self::DEFAULT_INITIAL_RETRANSMIT_TIMEOUT,
+            max_retransmit_timeout: Self::DEFAULT_MAX_RETRANSMIT_TIMEOUT,
+            max_retransmits: Self::DEFAULT_MAX_RETRANSMITS,
+        }
+    }
+
+    /// Set the delay before the first retransmission
+    pub fn with_initial_retransmit_timeout(mut self, timeout: Duration) -> Self {
+        self.initial_retransmit_timeout = timeout;
+        self
+    }
+
+    /// Set the upper bound of the delay between two retransmissions
+    pub fn with_max_retransmit_timeout(mut self, timeout: Duration) -> Self {
+        self.max_retransmit_timeout = timeout;
+        self
+    }
+
+    /// Set the number of retransmissions before a datagram is given up
+    pub fn with_max_retransmits(mut self, max_retransmits: u32) -> Self {
+        self.max_retransmits = max_retransmits;
+        self
+    }
+
+    /// Delay before the given retransmission attempt, doubling on every attempt
+    pub(crate) fn retransmit_timeout(&self, attempt: u32) -> Duration {
+        let factor = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+        self.initial_retransmit_timeout
+            .saturating_mul(factor)
+            .min(self.max_retransmit_timeout)
+    }
+}
+
+impl Default for UdpReliabilityOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Options for the cache of a Rendezvous service
+///
+/// The Rendezvous service keeps the public route of every puncher which registered
+/// with it. The number of routes is bounded: when the cache is full the least recently
+/// used route is evicted. A route which was not updated by its puncher for longer than
+/// the time to live is evicted as well.
+///
+/// A puncher querying the route of an evicted puncher gets a retryable error: the route is
+/// available again as soon as that puncher registers again.
+#[derive(Clone, Debug)]
+pub struct UdpRendezvousOptions {
+    pub(crate) capacity: usize,
+    pub(crate) ttl: Duration,
+}
+
+impl UdpRendezvousOptions {
+    /// Default maximum number of routes kept by the service
+    pub const DEFAULT_CAPACITY: usize = 100_000;
+    /// Default time to live of a route which is not updated by its puncher
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(10 * 60);
+
+    /// Rendezvous options with default values
+    pub fn new() -> Self {
+        Self {
+            capacity: Self::DEFAULT_CAPACITY,
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Set the maximum number of routes kept by the service
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// Set the time to live of a route which is not updated by its puncher
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+impl Default for UdpRendezvousOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retransmit_timeout_is_capped() {
+        let options = UdpReliabilityOptions::new()
+            .with_initial_retransmit_timeout(Duration::from_millis(100))
+            .with_max_retransmit_timeout(Duration::from_millis(1000));
+
+        assert_eq!(options.retransmit_timeout(0), Duration::from_millis(100));
+        assert_eq!(options.retransmit_timeout(1), Duration::from_millis(200));
+        assert_eq!(options.retransmit_timeout(3), Duration::from_millis(800));
+        assert_eq!(options.retransmit_timeout(4), Duration::from_millis(1000));
+        assert_eq!(options.retransmit_timeout(40), Duration::from_millis(1000));
+    }
+}
@@ -0,0 +1,220 @@
+use crate::lru::Lru;
+use crate::UdpRendezvousOptions;
+use ockam_core::Route;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+use tracing::debug;
+
+/// Occupancy of the cache of a Rendezvous service and number of evicted routes
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RendezvousCacheStats {
+    /// Number of routes currently kept by the service
+    pub entries: usize,
+    /// Maximum number of routes kept by the service
+    pub capacity: usize,
+    /// Number of routes evicted because the cache was full
+    pub evicted: u64,
+    /// Number of routes evicted because they were not updated before their time to live
+    pub expired: u64,
+    /// Number of queries which failed with a retryable error because the route was evicted
+    pub evicted_queries: u64,
+}
+
+/// Result of the lookup of a puncher route
+#[derive(Debug, PartialEq)]
+pub(crate) enum RendezvousLookup {
+    Found(Route),
+    /// The route was known but has been evicted
+    Evicted,
+    NotFound,
+}
+
+/// Bounded cache of the public routes of the punchers registered with a Rendezvous service
+///
+/// The routes are evicted in least recently used order when the cache is full, and when
+/// they are not updated before their time to live. The names of the evicted punchers are
+/// kept, in a cache of the same capacity, to report a retryable error when they are queried.
+pub(crate) struct RendezvousCache {
+    options: UdpRendezvousOptions,
+    routes: Lru<String, (Route, Instant)>,
+    evicted: Lru<String, ()>,
+    stats: RendezvousCacheStats,
+}
+
+impl RendezvousCache {
+    pub(crate) fn new(options: UdpRendezvousOptions) -> Self {
+        let stats = RendezvousCacheStats {
+            capacity: options.capacity,
+            ..Default::default()
+        };
+        Self {
+            options,
+            routes: Lru::default(),
+            evicted: Lru::default(),
+            stats,
+        }
+    }
+
+    /// Set the route of a puncher, evicting the least recently used route if the cache is full
+    pub(crate) fn update(&mut self, puncher_name: &str, route: Route, now: Instant) {
+        self.evicted.remove(puncher_name);
+        self.routes.insert(puncher_name.to_string(), (route, now));
+        while self.routes.len() > self.options.capacity {
+            if let Some((name, (_, updated_at))) = self.routes.pop_least_recently_used() {
+                if self.is_expired(updated_at, now) {
+                    self.stats.expired += 1;
+                } else {
+                    debug!("The Rendezvous cache is full, evicting the route of {name}");
+                    self.stats.evicted += 1;
+                }
+                self.remember_evicted(name);
+            }
+        }
+    }
+
+    /// Return the route of a puncher, if it is known and has not expired
+    pub(crate) fn get(&mut self, puncher_name: &str, now: Instant) -> RendezvousLookup {
+        let updated_at = self
+            .routes
+            .get(puncher_name)
+            .map(|(_, updated_at)| *updated_at);
+        match updated_at {
+            Some(updated_at) if self.is_expired(updated_at, now) => {
+                self.routes.remove(puncher_name);
+                self.stats.expired += 1;
+                self.remember_evicted(puncher_name.to_string());
+                self.stats.evicted_queries += 1;
+                RendezvousLookup::Evicted
+            }
+            Some(_) => match self.routes.get(puncher_name) {
+                Some((route, _)) => RendezvousLookup::Found(route.clone()),
+                None => RendezvousLookup::NotFound,
+            },
+            None if self.evicted.get(puncher_name).is_some() => {
+                self.stats.evicted_queries += 1;
+                RendezvousLookup::Evicted
+            }
+            None => RendezvousLookup::NotFound,
+        }
+    }
+
+    /// Remove the expired routes and return the statistics of the cache
+    pub(crate) fn stats(&mut self, now: Instant) -> RendezvousCacheStats {
+        let expired: Vec<String> = self
+            .routes
+            .iter()
+            .filter(|(_, (_, updated_at))| self.is_expired(*updated_at, now))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in expired {
+            self.routes.remove(&name);
+            self.stats.expired += 1;
+            self.remember_evicted(name);
+        }
+        RendezvousCacheStats {
+            entries: self.routes.len(),
+            ..self.stats
+        }
+    }
+
+    fn is_expired(&self, updated_at: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(updated_at) >= self.options.ttl
+    }
+
+    fn remember_evicted(&mut self, puncher_name: String) {
+        self.evicted.insert(puncher_name, ());
+        while self.evicted.len() > self.options.capacity {
+            self.evicted.pop_least_recently_used();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UDP;
+    use ockam_core::route;
+    use std::time::Duration;
+
+    #[test]
+    fn least_recently_used_routes_are_evicted() {
+        let mut cache = RendezvousCache::new(UdpRendezvousOptions::new().with_capacity(10));
+        let now = Instant::now();
+
+        // simulate the registration of more punchers than the capacity of the cache
+        for i in 0..15 {
+            cache.update(&format!("puncher-{i}"), puncher_route(i), now);
+            // the first puncher keeps being queried by its peer
+            assert_eq!(
+                cache.get("puncher-0", now),
+                RendezvousLookup::Found(puncher_route(0))
+            );
+        }
+
+        assert_eq!(
+            cache.get("puncher-0", now),
+            RendezvousLookup::Found(puncher_route(0))
+        );
+        for i in 1..6 {
+            assert_eq!(
+                cache.get(&format!("puncher-{i}"), now),
+                RendezvousLookup::Evicted
+            );
+        }
+        for i in 6..15 {
+            assert_eq!(
+                cache.get(&format!("puncher-{i}"), now),
+                RendezvousLookup::Found(puncher_route(i))
+            );
+        }
+        assert_eq!(cache.get("unknown", now), RendezvousLookup::NotFound);
+
+        assert_eq!(
+            cache.stats(now),
+            RendezvousCacheStats {
+                entries: 10,
+                capacity: 10,
+                evicted: 5,
+                expired: 0,
+                evicted_queries: 5,
+            }
+        );
+
+        // an evicted puncher can register again
+        cache.update("puncher-1", puncher_route(1), now);
+        assert_eq!(
+            cache.get("puncher-1", now),
+            RendezvousLookup::Found(puncher_route(1))
+        );
+    }
+
+    #[test]
+    fn routes_expire_when_they_are_not_updated() {
+        let ttl = Duration::from_secs(60);
+        let mut cache = RendezvousCache::new(UdpRendezvousOptions::new().with_ttl(ttl));
+        let now = Instant::now();
+        cache.update("alice", puncher_route(1), now);
+        cache.update("bob", puncher_route(2), now);
+        cache.update("carol", puncher_route(3), now);
+
+        // alice keeps registering
+        let later = now + ttl;
+        cache.update("alice", puncher_route(1), later);
+        assert_eq!(
+            cache.get("alice", later),
+            RendezvousLookup::Found(puncher_route(1))
+        );
+        assert_eq!(cache.get("bob", later), RendezvousLookup::Evicted);
+
+        let stats = cache.stats(later);
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.expired, 2);
+        assert_eq!(stats.evicted, 0);
+        assert_eq!(stats.evicted_queries, 1);
+        assert_eq!(cache.get("carol", later), RendezvousLookup::Evicted);
+    }
+
+    fn puncher_route(i: usize) -> Route {
+        route![(UDP, format!("127.0.0.1:{}", 4000 + i)), "puncher"]
+    }
+}
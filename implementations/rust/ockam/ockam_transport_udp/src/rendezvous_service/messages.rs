@@ -1,3 +1,4 @@
+use crate::rendezvous_service::RendezvousCacheStats;
 use ockam_core::{Message, Result, Route};
 use serde::{Deserialize, Serialize};
 
@@ -20,6 +21,9 @@ pub enum RendezvousRequest {
     },
     /// Ping service to see if it is reachable and working.
     Ping,
+    /// Query the occupancy of the service's internal table
+    /// and the number of evicted entries.
+    Stats,
 }
 
 /// Response type for UDP Hole Punching Rendezvous service
@@ -27,4 +31,5 @@ pub enum RendezvousRequest {
 pub enum RendezvousResponse {
     Query(Result<Route>),
     Pong,
+    Stats(RendezvousCacheStats),
 }
@@ -1,5 +1,7 @@
+pub use cache::RendezvousCacheStats;
 pub(crate) use messages::{RendezvousRequest, RendezvousResponse};
 pub use rendezvous::UdpRendezvousService;
 
+mod cache;
 mod messages;
 mod rendezvous;
@@ -1,12 +1,13 @@
+use crate::rendezvous_service::cache::{RendezvousCache, RendezvousLookup};
 use crate::{
-    rendezvous_service::{RendezvousRequest, RendezvousResponse},
-    UDP,
+    rendezvous_service::{RendezvousCacheStats, RendezvousRequest, RendezvousResponse},
+    PunchError, UdpRendezvousOptions, UDP,
 };
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Address, Error, Result, Route, Routed, Worker};
-use ockam_node::Context;
-use std::collections::BTreeMap;
-use tracing::{debug, trace, warn};
+use ockam_node::{Context, MessageSendReceiveOptions};
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
 
 /// High level management interface for UDP Rendezvous Service
 ///
@@ -35,34 +36,60 @@ pub struct UdpRendezvousService;
 impl UdpRendezvousService {
     /// Start a new Rendezvous service with the given local address
     pub async fn start(ctx: &Context, address: impl Into<Address>) -> Result<()> {
-        ctx.start_worker(address.into(), RendezvousWorker::new())
+        Self::start_with_options(ctx, address, UdpRendezvousOptions::new()).await
+    }
+
+    /// Start a new Rendezvous service with the given local address and cache options
+    pub async fn start_with_options(
+        ctx: &Context,
+        address: impl Into<Address>,
+        options: UdpRendezvousOptions,
+    ) -> Result<()> {
+        ctx.start_worker(address.into(), RendezvousWorker::new(options))
             .await
     }
+
+    /// Return the occupancy of the cache of a Rendezvous service, local or remote,
+    /// and the number of evicted routes
+    pub async fn stats(ctx: &Context, route: impl Into<Route>) -> Result<RendezvousCacheStats> {
+        let res = ctx
+            .send_and_receive_extended::<RendezvousResponse>(
+                route,
+                RendezvousRequest::Stats,
+                MessageSendReceiveOptions::new().with_timeout(STATS_TIMEOUT),
+            )
+            .await?
+            .into_body()?;
+        match res {
+            RendezvousResponse::Stats(stats) => Ok(stats),
+            _ => Err(PunchError::Internal)?,
+        }
+    }
 }
 
-// TODO: Implement mechanism for removing entries from internal map, possibly by
-// deleting 'old' entries on heartbeat events.
+const STATS_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Worker for the UDP NAT Hole Punching Rendezvous service
 ///
-/// Maintains an internal map for remote nodes and the public IP address
-/// from which they send UDP datagrams.
+/// Maintains an internal cache for remote nodes and the public IP address
+/// from which they send UDP datagrams. The size of the cache is bounded, see
+/// [`UdpRendezvousOptions`].
 ///
-/// Remote nodes can send requests to update and query the map.
+/// Remote nodes can send requests to update and query the cache.
 struct RendezvousWorker {
-    map: BTreeMap<String, Route>,
+    cache: RendezvousCache,
 }
 
 impl Default for RendezvousWorker {
     fn default() -> Self {
-        Self::new()
+        Self::new(UdpRendezvousOptions::new())
     }
 }
 
 impl RendezvousWorker {
-    fn new() -> Self {
+    fn new(options: UdpRendezvousOptions) -> Self {
         Self {
-            map: BTreeMap::new(),
+            cache: RendezvousCache::new(options),
         }
     }
 
@@ -85,7 +112,7 @@ impl RendezvousWorker {
     fn handle_update(&mut self, puncher_name: &str, return_route: &Route) {
         let r = Self::parse_route(return_route);
         if !r.is_empty() {
-            self.map.insert(puncher_name.to_owned(), r);
+            self.cache.update(puncher_name, r, Instant::now());
         } else {
             // This could happen if a client erroneously contacts this service over TCP not UDP, for example
             warn!(
@@ -97,10 +124,14 @@ impl RendezvousWorker {
     }
 
     // Handle Query request
-    fn handle_query(&self, puncher_name: &String) -> Result<Route> {
-        match self.map.get(puncher_name) {
-            Some(route) => Ok(route.clone()),
-            None => Err(Error::new_without_cause(Origin::Other, Kind::NotFound)),
+    fn handle_query(&mut self, puncher_name: &str) -> Result<Route> {
+        match self.cache.get(puncher_name, Instant::now()) {
+            RendezvousLookup::Found(route) => Ok(route),
+            // The peer can retry once the puncher has registered again
+            RendezvousLookup::Evicted => Err(PunchError::PeerRouteEvicted)?,
+            RendezvousLookup::NotFound => {
+                Err(Error::new_without_cause(Origin::Other, Kind::NotFound))
+            }
         }
     }
 }
@@ -133,8 +164,12 @@ impl Worker for RendezvousWorker {
             RendezvousRequest::Ping => {
                 ctx.send(return_route, RendezvousResponse::Pong).await?;
             }
+            RendezvousRequest::Stats => {
+                let stats = self.cache.stats(Instant::now());
+                ctx.send(return_route, RendezvousResponse::Stats(stats))
+                    .await?;
+            }
         }
-        trace!("Map: {:?}", self.map);
         Ok(())
     }
 }
@@ -143,8 +178,8 @@ impl Worker for RendezvousWorker {
 mod tests {
     use super::RendezvousWorker;
     use crate::rendezvous_service::{RendezvousRequest, RendezvousResponse};
-    use crate::{UdpRendezvousService, UdpTransport, UDP};
-    use ockam_core::errcode::Origin;
+    use crate::{UdpRendezvousOptions, UdpRendezvousService, UdpTransport, UDP};
+    use ockam_core::errcode::{Kind, Origin};
     use ockam_core::{route, Error, Result, Route, Routed, TransportType, Worker};
     use ockam_node::Context;
     use std::net::SocketAddr;
@@ -211,6 +246,47 @@ mod tests {
         Ok(())
     }
 
+    #[ockam_macros::test]
+    async fn evicted_routes_are_reported(ctx: &mut Context) -> Result<()> {
+        let (rendezvous_route, _) = test_setup(ctx).await?;
+
+        // Start a service which can only keep one route
+        UdpRendezvousService::start_with_options(
+            ctx,
+            "small_rendezvous",
+            UdpRendezvousOptions::new().with_capacity(1),
+        )
+        .await?;
+        let udp_address = rendezvous_route.iter().next().unwrap().clone();
+        let small_rendezvous_route = route![udp_address, "small_rendezvous"];
+
+        // Bob's registration evicts Alice's route
+        update_operation("Alice", ctx, &small_rendezvous_route).await?;
+        update_operation("Bob", ctx, &small_rendezvous_route).await?;
+        query_operation("Bob", ctx, &small_rendezvous_route).await?;
+
+        // Querying Alice fails with a retryable error
+        let err = query_operation("Alice", ctx, &small_rendezvous_route)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code().kind, Kind::ResourceExhausted);
+        let err = query_operation("DoesNotExist", ctx, &small_rendezvous_route)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code().kind, Kind::NotFound);
+
+        let stats = UdpRendezvousService::stats(ctx, small_rendezvous_route.clone()).await?;
+        assert_eq!(stats.entries, 1);
+        assert_eq!(stats.capacity, 1);
+        assert_eq!(stats.evicted, 1);
+        assert_eq!(stats.evicted_queries, 1);
+
+        // Once Alice registers again, her route can be queried
+        update_operation("Alice", ctx, &small_rendezvous_route).await?;
+        query_operation("Alice", ctx, &small_rendezvous_route).await?;
+        Ok(())
+    }
+
     #[ockam_macros::test]
     async fn ping(ctx: &mut Context) -> Result<()> {
         let (rendezvous_route, _) = test_setup(ctx).await?;
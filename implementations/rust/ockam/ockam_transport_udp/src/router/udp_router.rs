@@ -1,6 +1,10 @@
 use crate::router::messages::{UdpRouterRequest, UdpRouterResponse};
 use crate::router::UdpRouterHandle;
-use crate::workers::{TransportMessageCodec, UdpListenProcessor, UdpSendWorker};
+use crate::workers::{
+    UdpListenProcessor, UdpPacketCodec, UdpReliability, UdpReplayCounters, UdpReplayProtection,
+    UdpSendWorker, UdpSink,
+};
+use crate::UdpTransportOptions;
 use futures_util::StreamExt;
 use ockam_core::{
     async_trait, Address, AllowAll, Any, Decodable, DenyAll, LocalMessage, Mailbox, Mailboxes,
@@ -38,11 +42,19 @@ pub(crate) struct UdpRouter {
     api_addr: Address,
     /// Sender for 'client' messages
     client_sender: Address,
+    /// Options of the sockets
+    options: UdpTransportOptions,
+    /// Replay protection counters of all the sockets
+    replay_counters: Arc<UdpReplayCounters>,
 }
 
 impl UdpRouter {
     /// Create and register a new UDP router with the node context
-    pub(crate) async fn register(ctx: &Context) -> Result<UdpRouterHandle> {
+    pub(crate) async fn register(
+        ctx: &Context,
+        options: UdpTransportOptions,
+        replay_counters: Arc<UdpReplayCounters>,
+    ) -> Result<UdpRouterHandle> {
         // This context is only used to start workers, doesn't need to send nor receive messages
         let child_ctx = ctx
             .new_detached(
@@ -62,6 +74,8 @@ impl UdpRouter {
         let client_sender = Self::create_sender_listener(
             &child_ctx,
             SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), 0),
+            &options,
+            &replay_counters,
         )
         .await?;
 
@@ -70,6 +84,8 @@ impl UdpRouter {
             main_addr: main_addr.clone(),
             api_addr: api_addr.clone(),
             client_sender,
+            options,
+            replay_counters,
         };
 
         let main_mailbox = Mailbox::new(
@@ -103,7 +119,12 @@ impl UdpRouter {
     /// Create a sender, listener pair for the given socket address.
     ///
     /// Returns the address of the created sender.
-    async fn create_sender_listener(ctx: &Context, local_addr: SocketAddr) -> Result<Address> {
+    async fn create_sender_listener(
+        ctx: &Context,
+        local_addr: SocketAddr,
+        options: &UdpTransportOptions,
+        replay_counters: &Arc<UdpReplayCounters>,
+    ) -> Result<Address> {
         // This transport only supports IPv4
         if !local_addr.is_ipv4() {
             error!(local_addr = %local_addr, "This transport only supprts IPv4");
@@ -115,19 +136,39 @@ impl UdpRouter {
             .await
             .map_err(|_| TransportError::InvalidAddress)?;
 
+        let local_addr = socket.local_addr().unwrap_or(local_addr);
+        let sender_addr = Address::random_tagged("UdpSendWorker");
+
         // Split socket into sink and stream
-        let (sink, stream) = UdpFramed::new(socket, TransportMessageCodec).split();
+        let codec = UdpPacketCodec::new(options.reliability.is_some())
+            .with_message_sizes(ctx.message_sizes().recorder(&sender_addr));
+        let (sink, stream) = UdpFramed::new(socket, codec).split();
+        let replay_protection = options
+            .replay_window
+            .map(|window_size| UdpReplayProtection::new(window_size, replay_counters.clone()));
+        let sink = UdpSink::new(sink, replay_protection.clone());
+        let reliability = options
+            .reliability
+            .clone()
+            .map(|options| UdpReliability::new(options, sink.clone()));
 
         debug!("Creating new sender and listener for {}", local_addr);
 
         // Create sender
-        let sender_addr = Address::random_tagged("UdpSendWorker");
-        let sender = UdpSendWorker::new(sink);
+        let sender = UdpSendWorker::new(sink, reliability.clone());
         // FIXME: @ac
         ctx.start_worker(sender_addr.clone(), sender).await?;
 
         // Create listener
-        UdpListenProcessor::start(ctx, stream, sender_addr.clone()).await?;
+        UdpListenProcessor::start(
+            ctx,
+            stream,
+            sender_addr.clone(),
+            local_addr,
+            reliability,
+            replay_protection,
+        )
+        .await?;
 
         Ok(sender_addr)
     }
@@ -162,7 +203,13 @@ impl Worker for UdpRouter {
             trace!("handle_message() API_ADDR: msg = {:?}", msg);
             match msg {
                 UdpRouterRequest::Listen { local_addr } => {
-                    let res = Self::create_sender_listener(&self.ctx, local_addr).await;
+                    let res = Self::create_sender_listener(
+                        &self.ctx,
+                        local_addr,
+                        &self.options,
+                        &self.replay_counters,
+                    )
+                    .await;
                     let res = res.map(|_| ());
                     ctx.send_from_address(return_route, UdpRouterResponse::Listen(res), msg_addr)
                         .await?;
@@ -1,7 +1,10 @@
 use crate::router::{UdpRouter, UdpRouterHandle};
+use crate::workers::{UdpReplayCounters, UdpReplayStats};
+use crate::{UdpReliabilityOptions, UdpTransportOptions};
 use ockam_core::{async_trait, Result};
 use ockam_node::{Context, HasContext};
 use ockam_transport_core::TransportError;
+use std::sync::Arc;
 
 /// High level management interface for UDP transport
 ///
@@ -10,13 +13,44 @@ use ockam_transport_core::TransportError;
 /// This transport only supports IPv4.
 pub struct UdpTransport {
     router_handle: UdpRouterHandle,
+    replay_counters: Arc<UdpReplayCounters>,
 }
 
 impl UdpTransport {
     /// Create a new UDP transport for the current node
     pub async fn create(ctx: &Context) -> Result<UdpTransport> {
-        let router_handle = UdpRouter::register(ctx).await?;
-        Ok(Self { router_handle })
+        Self::create_with_options(ctx, UdpTransportOptions::new()).await
+    }
+
+    /// Create a new UDP transport for the current node, with the
+    /// reliability layer enabled on all its sockets
+    ///
+    /// Peers of this transport must enable the reliability layer as well.
+    pub async fn create_with_reliability(
+        ctx: &Context,
+        options: UdpReliabilityOptions,
+    ) -> Result<UdpTransport> {
+        Self::create_with_options(ctx, UdpTransportOptions::new().with_reliability(options)).await
+    }
+
+    /// Create a new UDP transport for the current node, with the given options
+    /// applied to all its sockets
+    pub async fn create_with_options(
+        ctx: &Context,
+        options: UdpTransportOptions,
+    ) -> Result<UdpTransport> {
+        let replay_counters = Arc::new(UdpReplayCounters::default());
+        let router_handle = UdpRouter::register(ctx, options, replay_counters.clone()).await?;
+        Ok(Self {
+            router_handle,
+            replay_counters,
+        })
+    }
+
+    /// Return the number of datagrams accepted and dropped by the replay protection
+    /// of all the sockets of this transport
+    pub fn replay_stats(&self) -> UdpReplayStats {
+        self.replay_counters.stats()
     }
 
     /// Start listening to incoming datagrams on a specified local address
@@ -1,34 +1,279 @@
 use bytes::{Buf, BufMut, BytesMut};
 use ockam_core::TransportMessage;
 use ockam_core::{Decodable, Encodable};
+#[cfg(feature = "telemetry")]
+use ockam_node::telemetry::TransportMetrics;
+use ockam_node::MessageSizeRecorder;
 use ockam_transport_core::TransportError;
 use tokio_util::codec::{Decoder, Encoder};
 
-pub(crate) struct TransportMessageCodec;
+const DATA_TAG: u8 = 0;
+const ACK_TAG: u8 = 1;
 
-impl Encoder<TransportMessage> for TransportMessageCodec {
+/// Marker of the replay protection header. It can't be mistaken for the
+/// length of a plain message, nor for the tag of a reliable datagram
+const REPLAY_MARKER: [u8; 2] = [0xFF, 0xFF];
+
+/// A datagram exchanged by the UDP transport
+///
+/// Without the reliability layer, every datagram carries a plain
+/// [`TransportMessage`]. With the reliability layer, each message is
+/// tagged with a sequence number and acknowledged by the receiver.
+#[derive(Debug)]
+pub(crate) enum UdpPacket {
+    /// A message sent without the reliability layer
+    Message(TransportMessage),
+    /// A message sent with the reliability layer
+    Data { seq: u64, msg: TransportMessage },
+    /// Acknowledgement of a [`UdpPacket::Data`] datagram
+    Ack { seq: u64 },
+}
+
+/// A [`UdpPacket`] with its optional replay protection sequence number
+///
+/// The sequence number is sent in a header preceding the packet:
+/// `[0xFF 0xFF][u64 sequence number]`. Datagrams without this header
+/// are framed exactly as without replay protection.
+#[derive(Debug)]
+pub(crate) struct UdpFrame {
+    pub(crate) replay_seq: Option<u64>,
+    pub(crate) packet: UdpPacket,
+}
+
+impl UdpFrame {
+    pub(crate) fn new(replay_seq: Option<u64>, packet: UdpPacket) -> Self {
+        Self { replay_seq, packet }
+    }
+}
+
+/// Encode and decode [`UdpFrame`]s
+///
+/// Both peers must agree on whether the reliability layer is enabled,
+/// since the framing of datagrams differs between the two modes.
+pub(crate) struct UdpPacketCodec {
+    reliable: bool,
+    message_sizes: Option<MessageSizeRecorder>,
+    #[cfg(feature = "telemetry")]
+    metrics: TransportMetrics,
+}
+
+impl UdpPacketCodec {
+    pub(crate) fn new(reliable: bool) -> Self {
+        Self {
+            reliable,
+            message_sizes: None,
+            #[cfg(feature = "telemetry")]
+            metrics: TransportMetrics::new("udp"),
+        }
+    }
+
+    /// Record the sizes of the encoded and decoded datagrams
+    pub(crate) fn with_message_sizes(mut self, message_sizes: MessageSizeRecorder) -> Self {
+        self.message_sizes = Some(message_sizes);
+        self
+    }
+
+    fn decode_frame(&self, src: &mut BytesMut) -> Result<UdpFrame, TransportError> {
+        let replay_seq = if src.starts_with(&REPLAY_MARKER) {
+            if src.len() < 10 {
+                src.clear();
+                return Err(TransportError::RecvBadMessage);
+            }
+            src.advance(REPLAY_MARKER.len());
+            Some(src.get_u64())
+        } else {
+            None
+        };
+        Ok(UdpFrame::new(replay_seq, self.decode_packet(src)?))
+    }
+
+    fn decode_packet(&self, src: &mut BytesMut) -> Result<UdpPacket, TransportError> {
+        if !self.reliable {
+            return Ok(UdpPacket::Message(decode_message(src)?));
+        }
+
+        if src.len() < 9 {
+            src.clear();
+            return Err(TransportError::RecvBadMessage);
+        }
+        let tag = src.get_u8();
+        let seq = src.get_u64();
+        match tag {
+            DATA_TAG => Ok(UdpPacket::Data {
+                seq,
+                msg: decode_message(src)?,
+            }),
+            ACK_TAG => Ok(UdpPacket::Ack { seq }),
+            _ => {
+                src.clear();
+                Err(TransportError::RecvBadMessage)
+            }
+        }
+    }
+}
+
+fn encode_message(msg: TransportMessage, dst: &mut BytesMut) -> Result<(), TransportError> {
+    let msg_buf = msg.encode().map_err(|_| TransportError::SendBadMessage)?;
+    let len = msg_buf.len();
+    dst.put_u16(len as u16);
+    dst.put(&msg_buf[..]);
+    Ok(())
+}
+
+fn decode_message(src: &mut BytesMut) -> Result<TransportMessage, TransportError> {
+    if src.len() < 2 {
+        return Err(TransportError::RecvBadMessage);
+    }
+    let len = src.get_u16() as usize;
+    if src.len() < len {
+        return Err(TransportError::RecvBadMessage);
+    }
+    TransportMessage::decode(&src.split_to(len)[..]).map_err(|_| TransportError::RecvBadMessage)
+}
+
+impl Encoder<UdpFrame> for UdpPacketCodec {
     type Error = TransportError;
-    fn encode(&mut self, item: TransportMessage, dst: &mut BytesMut) -> Result<(), Self::Error> {
-        let msg_buf = item.encode().map_err(|_| TransportError::SendBadMessage)?;
-        let len = msg_buf.len();
-        dst.put_u16(len as u16);
-        dst.put(&msg_buf[..]);
+    fn encode(&mut self, item: UdpFrame, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let start = dst.len();
+        if let Some(replay_seq) = item.replay_seq {
+            dst.put(&REPLAY_MARKER[..]);
+            dst.put_u64(replay_seq);
+        }
+        match (self.reliable, item.packet) {
+            (false, UdpPacket::Message(msg)) => encode_message(msg, dst),
+            (true, UdpPacket::Data { seq, msg }) => {
+                dst.put_u8(DATA_TAG);
+                dst.put_u64(seq);
+                encode_message(msg, dst)
+            }
+            (true, UdpPacket::Ack { seq }) => {
+                dst.put_u8(ACK_TAG);
+                dst.put_u64(seq);
+                Ok(())
+            }
+            _ => Err(TransportError::SendBadMessage),
+        }?;
+        if let Some(message_sizes) = &self.message_sizes {
+            message_sizes.record_outbound(dst.len() - start);
+        }
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_outbound(dst.len() - start);
         Ok(())
     }
 }
 
-impl Decoder for TransportMessageCodec {
-    type Item = TransportMessage;
+impl Decoder for UdpPacketCodec {
+    type Item = UdpFrame;
     type Error = TransportError;
     fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
         if src.is_empty() {
             return Ok(None);
         }
+        if let Some(message_sizes) = &self.message_sizes {
+            message_sizes.record_inbound(src.len());
+        }
+        #[cfg(feature = "telemetry")]
+        self.metrics.record_inbound(src.len());
+
+        let frame = self.decode_frame(src);
+        #[cfg(feature = "telemetry")]
+        if frame.is_err() {
+            self.metrics.record_decode_failure();
+        }
+        frame.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::route;
+
+    fn message() -> TransportMessage {
+        TransportMessage::v1(route!["a"], route!["b"], vec![1, 2, 3])
+    }
+
+    #[test]
+    fn reliable_packets_roundtrip() {
+        let mut codec = UdpPacketCodec::new(true);
+        let mut buf = BytesMut::new();
+        codec
+            .encode(
+                UdpFrame::new(
+                    None,
+                    UdpPacket::Data {
+                        seq: 42,
+                        msg: message(),
+                    },
+                ),
+                &mut buf,
+            )
+            .unwrap();
+        match codec.decode(&mut buf).unwrap().map(|f| f.packet) {
+            Some(UdpPacket::Data { seq, msg }) => {
+                assert_eq!(seq, 42);
+                assert_eq!(msg.payload, vec![1, 2, 3]);
+            }
+            other => panic!("unexpected packet {other:?}"),
+        }
+
+        codec
+            .encode(UdpFrame::new(None, UdpPacket::Ack { seq: 7 }), &mut buf)
+            .unwrap();
+        assert!(matches!(
+            codec.decode(&mut buf).unwrap().map(|f| f.packet),
+            Some(UdpPacket::Ack { seq: 7 })
+        ));
+    }
+
+    #[test]
+    fn replay_header_roundtrip() {
+        for reliable in [false, true] {
+            let mut codec = UdpPacketCodec::new(reliable);
+            let packet = |msg| {
+                if reliable {
+                    UdpPacket::Data { seq: 3, msg }
+                } else {
+                    UdpPacket::Message(msg)
+                }
+            };
+
+            let mut buf = BytesMut::new();
+            codec
+                .encode(
+                    UdpFrame::new(Some(u64::MAX - 1), packet(message())),
+                    &mut buf,
+                )
+                .unwrap();
+            let frame = codec.decode(&mut buf).unwrap().unwrap();
+            assert_eq!(frame.replay_seq, Some(u64::MAX - 1));
+            assert!(buf.is_empty());
+
+            // datagrams without the header are still accepted
+            codec
+                .encode(UdpFrame::new(None, packet(message())), &mut buf)
+                .unwrap();
+            let frame = codec.decode(&mut buf).unwrap().unwrap();
+            assert_eq!(frame.replay_seq, None);
+        }
+
+        // a truncated header is rejected
+        let mut codec = UdpPacketCodec::new(false);
+        let mut buf = BytesMut::from(&[0xFF, 0xFF, 0, 0, 1][..]);
+        assert!(codec.decode(&mut buf).is_err());
+    }
 
-        let len = src.get_u16() as usize;
-        let msg = TransportMessage::decode(&src.split_to(len)[..])
-            .map_err(|_| TransportError::RecvBadMessage)?;
+    #[test]
+    fn mode_mismatch_is_rejected() {
+        let mut codec = UdpPacketCodec::new(false);
+        let mut buf = BytesMut::new();
+        assert!(codec
+            .encode(UdpFrame::new(None, UdpPacket::Ack { seq: 1 }), &mut buf)
+            .is_err());
 
-        Ok(Some(msg))
+        let mut codec = UdpPacketCodec::new(true);
+        assert!(codec
+            .encode(UdpFrame::new(None, UdpPacket::Message(message())), &mut buf)
+            .is_err());
     }
 }
@@ -1,9 +1,12 @@
-use super::TransportMessageCodec;
+use super::{UdpFrame, UdpPacket, UdpPacketCodec, UdpReliability, UdpReplayProtection};
 use crate::UDP;
 use futures_util::stream::SplitStream;
 use futures_util::StreamExt;
-use ockam_core::{async_trait, route, Address, AllowAll, LocalMessage, Processor, Result};
+use ockam_core::{
+    async_trait, route, Address, AllowAll, IngressInfo, LocalMessage, Processor, Result,
+};
 use ockam_node::Context;
+use std::net::SocketAddr;
 use tokio_util::udp::UdpFramed;
 use tracing::{debug, warn};
 
@@ -15,22 +18,35 @@ use tracing::{debug, warn};
 /// When a message is received, the address of the paired sender
 /// ([`UdpSendWorker`](crate::workers::UdpSendWorker)) is injected into the message's
 /// return route so that replies are sent to the sender.
+/// The message is also marked with an [`IngressInfo`] describing the socket and the peer.
 pub(crate) struct UdpListenProcessor {
     /// The read half of the udnerlying UDP socket.
-    stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+    stream: SplitStream<UdpFramed<UdpPacketCodec>>,
     /// Address of our sender counterpart
     sender_addr: Address,
+    /// Local address of the socket
+    local_addr: SocketAddr,
+    /// Optional reliability layer, shared with the sender of the same socket
+    reliability: Option<UdpReliability>,
+    /// Optional replay protection, shared with the sender of the same socket
+    replay_protection: Option<UdpReplayProtection>,
 }
 
 impl UdpListenProcessor {
     pub(crate) async fn start(
         ctx: &Context,
-        stream: SplitStream<UdpFramed<TransportMessageCodec>>,
+        stream: SplitStream<UdpFramed<UdpPacketCodec>>,
         sender_addr: Address,
+        local_addr: SocketAddr,
+        reliability: Option<UdpReliability>,
+        replay_protection: Option<UdpReplayProtection>,
     ) -> Result<()> {
         let processor = Self {
             stream,
             sender_addr,
+            local_addr,
+            reliability,
+            replay_protection,
         };
         let addr = Address::random_tagged("UdpListenProcessor");
 
@@ -52,9 +68,9 @@ impl Processor for UdpListenProcessor {
 
     async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
         debug!("Waiting for incoming UDP datagram...");
-        let (mut msg, addr) = match self.stream.next().await {
+        let (UdpFrame { replay_seq, packet }, addr) = match self.stream.next().await {
             Some(res) => match res {
-                Ok((msg, addr)) => (LocalMessage::from_transport_message(msg), addr),
+                Ok((frame, addr)) => (frame, addr),
                 Err(e) => {
                     warn!(
                         "Failed to read message, will wait for next message: {:?}",
@@ -69,13 +85,50 @@ impl Processor for UdpListenProcessor {
             }
         };
 
+        // Drop replayed datagrams before they reach the reliability layer
+        if let Some(replay_protection) = &self.replay_protection {
+            if !replay_protection.accept(addr, replay_seq) {
+                return Ok(true);
+            }
+        }
+
+        let msg = match (packet, &self.reliability) {
+            (UdpPacket::Message(msg), None) => msg,
+            (UdpPacket::Data { seq, msg }, Some(reliability)) => {
+                match reliability.receive(seq, addr).await {
+                    Ok(true) => msg,
+                    Ok(false) => return Ok(true),
+                    Err(e) => {
+                        warn!(%addr, seq, "Failed to acknowledge UDP datagram: {:?}", e);
+                        return Ok(true);
+                    }
+                }
+            }
+            (UdpPacket::Ack { seq }, Some(reliability)) => {
+                reliability.acknowledge(seq, addr);
+                return Ok(true);
+            }
+            (packet, _) => {
+                warn!(%addr, "Unexpected UDP datagram, will wait for next message: {:?}", packet);
+                return Ok(true);
+            }
+        };
+        let mut msg = LocalMessage::from_transport_message(msg);
+
         // Set return route to go directly to paired sender, skipping the UDP router
         let new_route = route![
             self.sender_addr.clone(),
             Address::new(UDP, addr.to_string()),
             msg.return_route(),
         ];
-        msg = msg.set_return_route(new_route);
+        msg = msg
+            .set_return_route(new_route)
+            .with_ingress(&IngressInfo::new(
+                UDP,
+                self.local_addr.to_string(),
+                addr.to_string(),
+                self.sender_addr.address(),
+            ))?;
 
         debug!(onward_route = %msg.onward_route_ref(),
             return_route = %msg.return_route_ref(),
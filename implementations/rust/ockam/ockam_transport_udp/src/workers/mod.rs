@@ -1,9 +1,17 @@
 // TODO: Would it be logical to move this `workers` directory into the `router` directory?
 
+pub use replay::UdpReplayStats;
+
 pub(crate) use codec::*;
 pub(crate) use listener::*;
+pub(crate) use reliability::*;
+pub(crate) use replay::*;
 pub(crate) use sender::*;
+pub(crate) use sink::*;
 
 mod codec;
 mod listener;
+mod reliability;
+mod replay;
 mod sender;
+mod sink;
@@ -0,0 +1,190 @@
+use super::{UdpPacket, UdpSink};
+use crate::UdpReliabilityOptions;
+use ockam_core::{Result, TransportMessage};
+use rand::random;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{trace, warn};
+
+/// Number of sequence numbers remembered per peer to discard duplicates
+const DEDUP_WINDOW: usize = 1024;
+
+/// Shortest interval between two checks for datagrams to retransmit
+const MIN_RETRANSMIT_TICK: Duration = Duration::from_millis(10);
+
+/// Reliability layer of a UDP socket
+///
+/// It sits between the socket workers and the decoding of messages: the
+/// sender tags messages with sequence numbers and keeps them until they are
+/// acknowledged, the listener acknowledges received messages and drops the
+/// ones it has already seen.
+#[derive(Clone)]
+pub(crate) struct UdpReliability {
+    options: UdpReliabilityOptions,
+    sink: UdpSink,
+    state: Arc<Mutex<ReliabilityState>>,
+}
+
+#[derive(Default)]
+struct ReliabilityState {
+    /// Next sequence number for each peer, starting at a random value so that
+    /// a restarted node is not mistaken for duplicates of its previous run
+    next_seq: HashMap<SocketAddr, u64>,
+    /// Messages waiting for an acknowledgement
+    pending: HashMap<(SocketAddr, u64), PendingMessage>,
+    /// Recently received sequence numbers for each peer
+    received: HashMap<SocketAddr, ReceivedWindow>,
+}
+
+struct PendingMessage {
+    msg: TransportMessage,
+    attempts: u32,
+    retransmit_at: Instant,
+}
+
+#[derive(Default)]
+struct ReceivedWindow {
+    order: VecDeque<u64>,
+    seen: HashSet<u64>,
+}
+
+impl ReceivedWindow {
+    /// Record a sequence number, return false if it was already seen
+    fn insert(&mut self, seq: u64) -> bool {
+        if !self.seen.insert(seq) {
+            return false;
+        }
+        self.order.push_back(seq);
+        if self.order.len() > DEDUP_WINDOW {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+impl UdpReliability {
+    pub(crate) fn new(options: UdpReliabilityOptions, sink: UdpSink) -> Self {
+        Self {
+            options,
+            sink,
+            state: Default::default(),
+        }
+    }
+
+    /// Send a message and keep it until it is acknowledged by the peer
+    pub(crate) async fn send(&self, msg: TransportMessage, addr: SocketAddr) -> Result<()> {
+        let seq = {
+            let mut state = self.state.lock().unwrap();
+            let next_seq = state.next_seq.entry(addr).or_insert_with(random);
+            let seq = *next_seq;
+            *next_seq = next_seq.wrapping_add(1);
+            state.pending.insert(
+                (addr, seq),
+                PendingMessage {
+                    msg: msg.clone(),
+                    attempts: 0,
+                    retransmit_at: Instant::now() + self.options.retransmit_timeout(0),
+                },
+            );
+            seq
+        };
+
+        trace!(%addr, seq, "Sending reliable UDP datagram");
+        self.sink.send(UdpPacket::Data { seq, msg }, addr).await
+    }
+
+    /// Handle a received message. Acknowledge it and return false if
+    /// it is a duplicate which must be discarded.
+    pub(crate) async fn receive(&self, seq: u64, addr: SocketAddr) -> Result<bool> {
+        // Always acknowledge, since the previous acknowledgement may have been lost
+        self.sink.send(UdpPacket::Ack { seq }, addr).await?;
+
+        let is_new = self
+            .state
+            .lock()
+            .unwrap()
+            .received
+            .entry(addr)
+            .or_default()
+            .insert(seq);
+        if !is_new {
+            trace!(%addr, seq, "Discarding duplicate UDP datagram");
+        }
+        Ok(is_new)
+    }
+
+    /// Handle an acknowledgement sent by a peer
+    pub(crate) fn acknowledge(&self, seq: u64, addr: SocketAddr) {
+        trace!(%addr, seq, "Received acknowledgement");
+        self.state.lock().unwrap().pending.remove(&(addr, seq));
+    }
+
+    /// Retransmit the messages which were not acknowledged in time
+    async fn retransmit(&self) {
+        let now = Instant::now();
+        let due: Vec<_> = {
+            let mut state = self.state.lock().unwrap();
+            let mut due = vec![];
+            state.pending.retain(|(addr, seq), pending| {
+                if pending.retransmit_at > now {
+                    return true;
+                }
+                if pending.attempts >= self.options.max_retransmits {
+                    warn!(%addr, seq, "Giving up on unacknowledged UDP datagram");
+                    return false;
+                }
+                pending.attempts += 1;
+                pending.retransmit_at = now + self.options.retransmit_timeout(pending.attempts);
+                due.push((*addr, *seq, pending.msg.clone()));
+                true
+            });
+            due
+        };
+
+        for (addr, seq, msg) in due {
+            trace!(%addr, seq, "Retransmitting UDP datagram");
+            if let Err(e) = self.sink.send(UdpPacket::Data { seq, msg }, addr).await {
+                warn!(%addr, seq, "Failed to retransmit UDP datagram: {:?}", e);
+            }
+        }
+    }
+
+    /// Start a background task retransmitting unacknowledged messages
+    pub(crate) fn start_retransmissions(&self) -> JoinHandle<()> {
+        let reliability = self.clone();
+        let tick = (self.options.initial_retransmit_timeout / 2).max(MIN_RETRANSMIT_TICK);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(tick);
+            loop {
+                interval.tick().await;
+                reliability.retransmit().await;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn received_window_discards_duplicates() {
+        let mut window = ReceivedWindow::default();
+        assert!(window.insert(1));
+        assert!(window.insert(2));
+        assert!(!window.insert(1));
+
+        for seq in 3..(DEDUP_WINDOW as u64 + 2) {
+            assert!(window.insert(seq));
+        }
+        // The oldest sequence number has been forgotten
+        assert!(window.insert(1));
+        assert!(!window.insert(DEDUP_WINDOW as u64));
+    }
+}
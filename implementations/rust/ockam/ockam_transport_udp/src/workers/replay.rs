@@ -0,0 +1,400 @@
+use crate::lru::Lru;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, trace};
+
+/// Maximum number of peers tracked by the replay protection of a socket.
+/// Beyond that the least recently used peers are forgotten, so that datagrams with spoofed
+/// source addresses can't make the replay protection grow without bounds
+pub(crate) const MAX_REPLAY_PEERS: usize = 4096;
+
+/// The window of a peer is forgotten when no datagram of that peer was accepted for that long,
+/// so that a restarted peer, whose sequence numbers jump ahead, can be accepted again
+pub(crate) const REPLAY_WINDOW_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Maximum distance by which a single datagram can move the window of a peer forward.
+/// A datagram further ahead is dropped, so that a forged datagram with a huge sequence number
+/// can't make all the following datagrams of that peer look stale
+pub(crate) const MAX_SEQUENCE_JUMP: u64 = 1 << 16;
+
+/// Number of datagrams accepted and dropped by the replay protection of a UDP transport
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UdpReplayStats {
+    /// Number of protected datagrams which were accepted
+    pub accepted: u64,
+    /// Number of datagrams dropped because their sequence number was already received
+    pub duplicates: u64,
+    /// Number of datagrams dropped because their sequence number is older than the window
+    pub stale: u64,
+    /// Number of datagrams dropped because their sequence number is too far ahead of the window
+    pub ahead: u64,
+    /// Number of datagrams dropped because they were not protected,
+    /// while their peer had already sent protected datagrams
+    pub unprotected: u64,
+}
+
+impl UdpReplayStats {
+    /// Total number of dropped datagrams
+    pub fn dropped(&self) -> u64 {
+        self.duplicates + self.stale + self.ahead + self.unprotected
+    }
+}
+
+/// Counters shared by the sockets of a transport
+#[derive(Default, Debug)]
+pub(crate) struct UdpReplayCounters {
+    accepted: AtomicU64,
+    duplicates: AtomicU64,
+    stale: AtomicU64,
+    ahead: AtomicU64,
+    unprotected: AtomicU64,
+}
+
+impl UdpReplayCounters {
+    pub(crate) fn stats(&self) -> UdpReplayStats {
+        UdpReplayStats {
+            accepted: self.accepted.load(Ordering::Relaxed),
+            duplicates: self.duplicates.load(Ordering::Relaxed),
+            stale: self.stale.load(Ordering::Relaxed),
+            ahead: self.ahead.load(Ordering::Relaxed),
+            unprotected: self.unprotected.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Result of the check of a received sequence number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReplayCheck {
+    Accepted,
+    Duplicate,
+    Stale,
+    TooFar,
+}
+
+/// Sliding window of the sequence numbers received from a peer
+///
+/// The window covers the `size` sequence numbers ending with the highest one received.
+/// A bitmap, used as a ring buffer, records which of them were already received.
+/// Sequence numbers below the window are rejected as stale, and sequence numbers more than
+/// [`MAX_SEQUENCE_JUMP`] above it are rejected as too far ahead.
+#[derive(Debug)]
+pub(crate) struct ReplayWindow {
+    size: u64,
+    top: Option<u64>,
+    bitmap: Vec<u64>,
+}
+
+impl ReplayWindow {
+    pub(crate) fn new(size: usize) -> Self {
+        let size = size.max(1) as u64;
+        Self {
+            size,
+            top: None,
+            bitmap: vec![0; size.div_ceil(64) as usize],
+        }
+    }
+
+    fn capacity(&self) -> u64 {
+        self.bitmap.len() as u64 * 64
+    }
+
+    fn bit(&self, seq: u64) -> (usize, u64) {
+        let index = seq % self.capacity();
+        ((index / 64) as usize, 1 << (index % 64))
+    }
+
+    fn is_set(&self, seq: u64) -> bool {
+        let (word, mask) = self.bit(seq);
+        self.bitmap[word] & mask != 0
+    }
+
+    fn set(&mut self, seq: u64) {
+        let (word, mask) = self.bit(seq);
+        self.bitmap[word] |= mask;
+    }
+
+    fn clear(&mut self, seq: u64) {
+        let (word, mask) = self.bit(seq);
+        self.bitmap[word] &= !mask;
+    }
+
+    /// Check a sequence number and record it if it is accepted
+    pub(crate) fn check(&mut self, seq: u64) -> ReplayCheck {
+        let top = match self.top {
+            None => {
+                self.top = Some(seq);
+                self.set(seq);
+                return ReplayCheck::Accepted;
+            }
+            Some(top) => top,
+        };
+
+        if seq > top {
+            if seq - top > MAX_SEQUENCE_JUMP {
+                return ReplayCheck::TooFar;
+            }
+            // Slide the window, forgetting the sequence numbers which are now out of it
+            if seq - top >= self.capacity() {
+                self.bitmap.iter_mut().for_each(|w| *w = 0);
+            } else {
+                for s in top + 1..=seq {
+                    self.clear(s);
+                }
+            }
+            self.top = Some(seq);
+            self.set(seq);
+            ReplayCheck::Accepted
+        } else if top - seq >= self.size {
+            ReplayCheck::Stale
+        } else if self.is_set(seq) {
+            ReplayCheck::Duplicate
+        } else {
+            self.set(seq);
+            ReplayCheck::Accepted
+        }
+    }
+}
+
+/// Replay protection of a UDP socket
+///
+/// Every datagram sent to a peer carries a sequence number, incremented for each datagram.
+/// The sequence numbers start from the current time in microseconds, so that the datagrams
+/// of a restarted node are not mistaken for replays of its previous run.
+///
+/// Received datagrams are checked against a [`ReplayWindow`] per peer, before being decoded.
+/// Datagrams without a sequence number are accepted from the peers which never sent a protected
+/// datagram, so that peers without replay protection can still be reached.
+///
+/// Source addresses are not authenticated, so the number of tracked peers is bounded by
+/// [`MAX_REPLAY_PEERS`], and the window of a peer is restarted after
+/// [`REPLAY_WINDOW_IDLE_TIMEOUT`] without any accepted datagram. A forgotten peer is treated
+/// as a new one, its datagrams are only protected against replays by the upper layers,
+/// like secure channels.
+#[derive(Clone)]
+pub(crate) struct UdpReplayProtection {
+    window_size: usize,
+    state: Arc<Mutex<ReplayState>>,
+    counters: Arc<UdpReplayCounters>,
+}
+
+#[derive(Default)]
+struct ReplayState {
+    next_seq: Lru<SocketAddr, u64>,
+    received: Lru<SocketAddr, PeerWindow>,
+}
+
+/// Window of a peer, with the time of the last datagram accepted from that peer
+struct PeerWindow {
+    window: ReplayWindow,
+    last_accepted: Instant,
+}
+
+impl ReplayState {
+    /// Check a sequence number with the window of its peer, which is created on the first
+    /// protected datagram of the peer, or when the previous window has been idle for too long
+    fn check(
+        &mut self,
+        addr: SocketAddr,
+        seq: u64,
+        window_size: usize,
+        now: Instant,
+    ) -> ReplayCheck {
+        let mut peer = match self.received.remove(&addr) {
+            Some(peer)
+                if now.saturating_duration_since(peer.last_accepted)
+                    < REPLAY_WINDOW_IDLE_TIMEOUT =>
+            {
+                peer
+            }
+            _ => PeerWindow {
+                window: ReplayWindow::new(window_size),
+                last_accepted: now,
+            },
+        };
+        let check = peer.window.check(seq);
+        if check == ReplayCheck::Accepted {
+            peer.last_accepted = now;
+        }
+        self.received.insert(addr, peer);
+        while self.received.len() > MAX_REPLAY_PEERS {
+            if let Some((evicted, _)) = self.received.pop_least_recently_used() {
+                debug!(%evicted, "Too many UDP peers, forgetting the replay window of {evicted}");
+            }
+        }
+        check
+    }
+}
+
+impl UdpReplayProtection {
+    pub(crate) fn new(window_size: usize, counters: Arc<UdpReplayCounters>) -> Self {
+        Self {
+            window_size,
+            state: Default::default(),
+            counters,
+        }
+    }
+
+    /// Return the sequence number of the next datagram sent to a peer
+    pub(crate) fn next_seq(&self, addr: SocketAddr) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let seq = state
+            .next_seq
+            .get(&addr)
+            .copied()
+            .unwrap_or_else(initial_seq);
+        state.next_seq.insert(addr, seq.wrapping_add(1));
+        while state.next_seq.len() > MAX_REPLAY_PEERS {
+            state.next_seq.pop_least_recently_used();
+        }
+        seq
+    }
+
+    /// Check a received datagram, return false if it must be dropped
+    pub(crate) fn accept(&self, addr: SocketAddr, seq: Option<u64>) -> bool {
+        self.accept_at(addr, seq, Instant::now())
+    }
+
+    fn accept_at(&self, addr: SocketAddr, seq: Option<u64>, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+        let (counter, accepted) = match seq {
+            Some(seq) => {
+                let check = state.check(addr, seq, self.window_size, now);
+                if check != ReplayCheck::Accepted {
+                    trace!(%addr, seq, ?check, "Dropping replayed UDP datagram");
+                }
+                match check {
+                    ReplayCheck::Accepted => (&self.counters.accepted, true),
+                    ReplayCheck::Duplicate => (&self.counters.duplicates, false),
+                    ReplayCheck::Stale => (&self.counters.stale, false),
+                    ReplayCheck::TooFar => (&self.counters.ahead, false),
+                }
+            }
+            None if state.received.contains_key(&addr) => {
+                trace!(%addr, "Dropping unprotected UDP datagram");
+                (&self.counters.unprotected, false)
+            }
+            None => return true,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        accepted
+    }
+}
+
+fn initial_seq() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_window_drops_duplicates_and_stale_sequence_numbers() {
+        let mut window = ReplayWindow::new(100);
+        assert_eq!(window.check(1000), ReplayCheck::Accepted);
+        assert_eq!(window.check(1000), ReplayCheck::Duplicate);
+
+        // out of order sequence numbers within the window are accepted once
+        assert_eq!(window.check(1002), ReplayCheck::Accepted);
+        assert_eq!(window.check(1001), ReplayCheck::Accepted);
+        assert_eq!(window.check(1001), ReplayCheck::Duplicate);
+        assert_eq!(window.check(950), ReplayCheck::Accepted);
+        assert_eq!(window.check(903), ReplayCheck::Accepted);
+        assert_eq!(window.check(902), ReplayCheck::Stale);
+
+        // the window slides with the highest sequence number
+        assert_eq!(window.check(1100), ReplayCheck::Accepted);
+        assert_eq!(window.check(1000), ReplayCheck::Stale);
+        assert_eq!(window.check(1002), ReplayCheck::Duplicate);
+        assert_eq!(window.check(1003), ReplayCheck::Accepted);
+
+        // a jump beyond the window forgets everything
+        assert_eq!(window.check(5000), ReplayCheck::Accepted);
+        assert_eq!(window.check(4901), ReplayCheck::Accepted);
+        assert_eq!(window.check(4900), ReplayCheck::Stale);
+    }
+
+    #[test]
+    fn unprotected_datagrams_are_dropped_once_a_peer_is_protected() {
+        let counters = Arc::new(UdpReplayCounters::default());
+        let protection = UdpReplayProtection::new(16, counters.clone());
+        let peer: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+
+        assert!(protection.accept(peer, None));
+        assert!(protection.accept(peer, Some(10)));
+        assert!(!protection.accept(peer, Some(10)));
+        assert!(!protection.accept(peer, None));
+        assert!(!protection.accept(peer, Some(1)));
+
+        assert_eq!(
+            counters.stats(),
+            UdpReplayStats {
+                accepted: 1,
+                duplicates: 1,
+                stale: 1,
+                ahead: 0,
+                unprotected: 1,
+            }
+        );
+        assert_eq!(counters.stats().dropped(), 3);
+
+        let seq = protection.next_seq(peer);
+        assert_eq!(protection.next_seq(peer), seq + 1);
+    }
+
+    #[test]
+    fn a_forged_sequence_number_far_ahead_does_not_move_the_window() {
+        let counters = Arc::new(UdpReplayCounters::default());
+        let protection = UdpReplayProtection::new(16, counters.clone());
+        let peer: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+        let now = Instant::now();
+
+        assert!(protection.accept_at(peer, Some(1000), now));
+        assert!(!protection.accept_at(peer, Some(u64::MAX), now));
+        assert!(!protection.accept_at(peer, Some(1001 + MAX_SEQUENCE_JUMP), now));
+
+        // the next datagrams of the peer are still accepted
+        assert!(protection.accept_at(peer, Some(1001), now));
+        assert!(protection.accept_at(peer, Some(1000 + MAX_SEQUENCE_JUMP), now));
+        assert_eq!(counters.stats().ahead, 2);
+
+        // a restarted peer is accepted again once its window is idle
+        let later = now + REPLAY_WINDOW_IDLE_TIMEOUT;
+        assert!(!protection.accept_at(peer, Some(u64::MAX / 2), later - Duration::from_secs(1)));
+        assert!(protection.accept_at(peer, Some(u64::MAX / 2), later));
+        assert!(!protection.accept_at(peer, Some(1001), later));
+    }
+
+    #[test]
+    fn the_number_of_tracked_peers_is_bounded() {
+        let counters = Arc::new(UdpReplayCounters::default());
+        let protection = UdpReplayProtection::new(16, counters.clone());
+        let peer: SocketAddr = "127.0.0.1:4000".parse().unwrap();
+        let now = Instant::now();
+        assert!(protection.accept_at(peer, Some(10), now));
+
+        // datagrams with spoofed source addresses
+        for port in 0..(2 * MAX_REPLAY_PEERS) as u16 {
+            let spoofed = SocketAddr::from(([10, 0, 0, 1], port));
+            assert!(protection.accept_at(spoofed, Some(1), now));
+            // the peer keeps sending datagrams
+            if port % 100 == 0 {
+                assert!(protection.accept_at(peer, Some(11 + port as u64), now));
+            }
+        }
+        let state = protection.state.lock().unwrap();
+        assert_eq!(state.received.len(), MAX_REPLAY_PEERS);
+        drop(state);
+
+        // the active peer is still tracked, the first spoofed peers are forgotten
+        assert!(!protection.accept_at(peer, Some(11), now));
+        let spoofed = SocketAddr::from(([10, 0, 0, 1], 0));
+        assert!(protection.accept_at(spoofed, Some(1), now));
+    }
+}
@@ -1,11 +1,10 @@
-use super::TransportMessageCodec;
+use super::{UdpPacket, UdpReliability, UdpSink};
 use crate::UDP;
-use futures_util::{stream::SplitSink, SinkExt};
-use ockam_core::{async_trait, Any, Result, Routed, TransportMessage, Worker};
+use ockam_core::{async_trait, Any, Result, Routed, Worker};
 use ockam_node::Context;
 use ockam_transport_core::TransportError;
 use std::net::{SocketAddr, ToSocketAddrs};
-use tokio_util::udp::UdpFramed;
+use tokio::task::JoinHandle;
 use tracing::{error, trace, warn};
 
 /// A sender for the UDP transport
@@ -13,16 +12,22 @@ use tracing::{error, trace, warn};
 /// This worker handles the sending of messages on a
 /// local socket. See [`UdpRouter`](crate::router::UdpRouter) for more details.
 pub(crate) struct UdpSendWorker {
-    /// The read half of the udnerlying UDP socket.
-    sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
+    /// The write half of the underlying UDP socket.
+    sink: UdpSink,
+    /// Optional reliability layer, shared with the listener of the same socket
+    reliability: Option<UdpReliability>,
+    /// Background task retransmitting unacknowledged messages
+    retransmissions: Option<JoinHandle<()>>,
 }
 
 impl UdpSendWorker {
     /// Create a new `UdpSendWorker`
-    pub(crate) fn new(
-        sink: SplitSink<UdpFramed<TransportMessageCodec>, (TransportMessage, SocketAddr)>,
-    ) -> Self {
-        Self { sink }
+    pub(crate) fn new(sink: UdpSink, reliability: Option<UdpReliability>) -> Self {
+        Self {
+            sink,
+            reliability,
+            retransmissions: None,
+        }
     }
 }
 
@@ -31,6 +36,21 @@ impl Worker for UdpSendWorker {
     type Message = Any;
     type Context = Context;
 
+    async fn initialize(&mut self, _ctx: &mut Context) -> Result<()> {
+        self.retransmissions = self
+            .reliability
+            .as_ref()
+            .map(UdpReliability::start_retransmissions);
+        Ok(())
+    }
+
+    async fn shutdown(&mut self, _ctx: &mut Context) -> Result<()> {
+        if let Some(retransmissions) = self.retransmissions.take() {
+            retransmissions.abort();
+        }
+        Ok(())
+    }
+
     async fn handle_message(
         &mut self,
         _ctx: &mut Context,
@@ -74,14 +94,19 @@ impl Worker for UdpSendWorker {
         }
 
         // Send
-        match self.sink.send((msg.into_transport_message(), addr)).await {
+        let msg = msg.into_transport_message();
+        let res = match &self.reliability {
+            Some(reliability) => reliability.send(msg, addr).await,
+            None => self.sink.send(UdpPacket::Message(msg), addr).await,
+        };
+        match res {
             Ok(()) => {
                 trace!("Successful send to {}", addr);
                 Ok(())
             }
             Err(e) => {
                 error!("Failed send to {}: {:?}", addr, e);
-                Err(e)?
+                Err(e)
             }
         }
     }
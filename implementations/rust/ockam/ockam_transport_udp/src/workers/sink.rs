@@ -0,0 +1,40 @@
+use super::{UdpFrame, UdpPacket, UdpPacketCodec, UdpReplayProtection};
+use futures_util::{stream::SplitSink, SinkExt};
+use ockam_core::Result;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tokio_util::udp::UdpFramed;
+
+/// Write half of a UDP socket, shared between the workers of that socket
+///
+/// When replay protection is enabled, every datagram is sent with the
+/// next sequence number of its peer.
+#[derive(Clone)]
+pub(crate) struct UdpSink {
+    inner: Arc<Mutex<SplitSink<UdpFramed<UdpPacketCodec>, (UdpFrame, SocketAddr)>>>,
+    replay_protection: Option<UdpReplayProtection>,
+}
+
+impl UdpSink {
+    pub(crate) fn new(
+        inner: SplitSink<UdpFramed<UdpPacketCodec>, (UdpFrame, SocketAddr)>,
+        replay_protection: Option<UdpReplayProtection>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            replay_protection,
+        }
+    }
+
+    /// Send a datagram to a peer
+    pub(crate) async fn send(&self, packet: UdpPacket, addr: SocketAddr) -> Result<()> {
+        let mut inner = self.inner.lock().await;
+        // The sequence number is taken while holding the lock, so that
+        // datagrams leave the socket in the order of their sequence numbers
+        let replay_seq = self.replay_protection.as_ref().map(|r| r.next_seq(addr));
+        Ok(inner
+            .send((UdpFrame::new(replay_seq, packet), addr))
+            .await?)
+    }
+}
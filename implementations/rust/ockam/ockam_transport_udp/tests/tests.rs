@@ -1,8 +1,12 @@
+use ockam::identity::{secure_channels, SecureChannelListenerOptions, SecureChannelOptions};
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Address, AllowAll, Result, Routed, Worker};
 use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
-use ockam_transport_udp::{UdpTransport, UDP};
+use ockam_transport_udp::{
+    UdpReliabilityOptions, UdpReplayStats, UdpTransport, UdpTransportOptions, UDP,
+};
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::{debug, error, trace};
 
@@ -187,6 +191,154 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// With the reliability layer enabled, a secure channel handshake and
+/// the messages sent through it should survive a lossy link.
+#[ockam_macros::test(timeout = 60000)]
+async fn reliable_send_receive_over_lossy_link(ctx: &mut Context) -> Result<()> {
+    // Find an available port
+    let bind_addr = *utils::available_local_ports(1).await?.first().unwrap();
+    debug!("bind_addr = {:?}", bind_addr);
+
+    // Drop 30% of the datagrams between both sides
+    let proxy_addr = utils::start_lossy_proxy(bind_addr, 0.3).await?;
+
+    // Transport
+    let options = UdpReliabilityOptions::new()
+        .with_initial_retransmit_timeout(Duration::from_millis(50))
+        .with_max_retransmit_timeout(Duration::from_millis(400))
+        .with_max_retransmits(30);
+    let transport = UdpTransport::create_with_reliability(ctx, options).await?;
+
+    // Listener
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let server = identities_creation.create_identity().await?;
+    let listener_options = SecureChannelListenerOptions::new();
+    ctx.start_worker("echoer", ockam::workers::Echoer).await?;
+    ctx.flow_controls()
+        .add_consumer("echoer", &listener_options.spawner_flow_control_id());
+    secure_channels
+        .create_secure_channel_listener(ctx, &server, "secure_listener", listener_options)
+        .await?;
+    transport.listen(bind_addr.to_string()).await?;
+
+    // Handshake
+    let client = identities_creation.create_identity().await?;
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &client,
+            route![(UDP, proxy_addr.to_string()), "secure_listener"],
+            SecureChannelOptions::new().with_timeout(Duration::from_secs(30)),
+        )
+        .await?;
+
+    // Sender
+    for i in 0..20 {
+        let msg = format!("Ockam. Testing. {i}");
+        let reply = ctx
+            .send_and_receive_extended::<String>(
+                route![channel.clone(), "echoer"],
+                msg.clone(),
+                MessageSendReceiveOptions::new().with_timeout(Duration::from_secs(10)),
+            )
+            .await?
+            .into_body()?;
+        assert_eq!(reply, msg, "Should receive the same message");
+    }
+
+    Ok(())
+}
+
+/// With the replay protection enabled, replayed datagrams are dropped
+/// and every message is delivered exactly once.
+#[ockam_macros::test]
+async fn replayed_datagrams_are_dropped(ctx: &mut Context) -> Result<()> {
+    const WINDOW_SIZE: usize = 8;
+    const MESSAGES: usize = 20;
+
+    // Find an available port
+    let bind_addr = *utils::available_local_ports(1).await?.first().unwrap();
+    debug!("bind_addr = {:?}", bind_addr);
+
+    let (proxy_addr, proxy) = utils::start_capturing_proxy(bind_addr).await?;
+
+    // Transport
+    let options = UdpTransportOptions::new().with_replay_protection(WINDOW_SIZE);
+    let transport = UdpTransport::create_with_options(ctx, options).await?;
+
+    // Listener
+    let received = Arc::new(Mutex::new(vec![]));
+    ctx.start_worker("collector", Collector(received.clone()))
+        .await?;
+    transport.listen(bind_addr.to_string()).await?;
+
+    // Sender
+    let expected: Vec<String> = (0..MESSAGES)
+        .map(|i| format!("Ockam. Testing. {i}"))
+        .collect();
+    for msg in &expected {
+        ctx.send(
+            route![(UDP, proxy_addr.to_string()), "collector"],
+            msg.clone(),
+        )
+        .await?;
+    }
+    wait_for(|| received.lock().unwrap().len() == MESSAGES).await;
+
+    // Replay all the captured datagrams, then the same datagrams without their sequence number
+    let captured = proxy.captured();
+    assert_eq!(captured.len(), MESSAGES);
+    for datagram in &captured {
+        proxy.send_to_server(datagram).await?;
+    }
+    proxy.send_to_server(&captured[MESSAGES - 1][10..]).await?;
+
+    let all_dropped = MESSAGES + 1;
+    wait_for(|| transport.replay_stats().dropped() == all_dropped as u64).await;
+
+    // The datagrams still in the window are duplicates, the older ones are stale
+    assert_eq!(
+        transport.replay_stats(),
+        UdpReplayStats {
+            accepted: MESSAGES as u64,
+            duplicates: WINDOW_SIZE as u64,
+            stale: (MESSAGES - WINDOW_SIZE) as u64,
+            ahead: 0,
+            unprotected: 1,
+        }
+    );
+    assert_eq!(*received.lock().unwrap(), expected);
+
+    Ok(())
+}
+
+/// Wait until a condition holds, for at most `TIMEOUT`
+async fn wait_for(condition: impl Fn() -> bool) {
+    let deadline = tokio::time::Instant::now() + TIMEOUT;
+    while !condition() {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "Timed out waiting for the condition"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Worker recording the messages it receives
+struct Collector(Arc<Mutex<Vec<String>>>);
+
+#[ockam_core::worker]
+impl Worker for Collector {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, _ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        self.0.lock().unwrap().push(msg.into_body()?);
+        Ok(())
+    }
+}
+
 pub struct Echoer {
     prev_src_addr: Option<String>,
 }
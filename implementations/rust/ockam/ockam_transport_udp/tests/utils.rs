@@ -1,6 +1,7 @@
 use ockam::{errcode::Origin, Error};
 use ockam_core::Result;
 use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
 use tokio::net::UdpSocket;
 
 const AVAILABLE_LOCAL_PORTS_ADDR: &str = "127.0.0.1:0";
@@ -26,3 +27,114 @@ pub async fn available_local_ports(count: usize) -> Result<Vec<SocketAddr>> {
 
     Ok(addrs)
 }
+
+/// Helper function. Start an in-process UDP proxy in front of `server_addr`
+/// which randomly drops the given ratio of datagrams in both directions.
+///
+/// Returns the address clients should send to.
+pub async fn start_lossy_proxy(server_addr: SocketAddr, loss_ratio: f64) -> Result<SocketAddr> {
+    let socket = UdpSocket::bind(AVAILABLE_LOCAL_PORTS_ADDR)
+        .await
+        .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+    let proxy_addr = socket
+        .local_addr()
+        .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+
+    tokio::spawn(async move {
+        let mut client_addr = None;
+        let mut buf = vec![0u8; u16::MAX as usize];
+        loop {
+            let (len, from) = match socket.recv_from(&mut buf).await {
+                Ok(res) => res,
+                Err(_) => continue,
+            };
+
+            let to = if from == server_addr {
+                match client_addr {
+                    Some(addr) => addr,
+                    None => continue,
+                }
+            } else {
+                client_addr = Some(from);
+                server_addr
+            };
+
+            if rand::random::<f64>() < loss_ratio {
+                continue;
+            }
+            let _ = socket.send_to(&buf[..len], to).await;
+        }
+    });
+
+    Ok(proxy_addr)
+}
+
+/// An in-process UDP proxy in front of a server, capturing the datagrams sent to that server
+pub struct CapturingProxy {
+    socket: Arc<UdpSocket>,
+    server_addr: SocketAddr,
+    captured: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl CapturingProxy {
+    /// Datagrams sent to the server so far
+    pub fn captured(&self) -> Vec<Vec<u8>> {
+        self.captured.lock().unwrap().clone()
+    }
+
+    /// Send a datagram to the server, as if it was sent by the client
+    pub async fn send_to_server(&self, datagram: &[u8]) -> Result<()> {
+        self.socket
+            .send_to(datagram, self.server_addr)
+            .await
+            .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+        Ok(())
+    }
+}
+
+/// Helper function. Start an in-process UDP proxy in front of `server_addr`
+/// which captures the datagrams sent to the server, so that they can be replayed.
+///
+/// Returns the address clients should send to, and the proxy.
+pub async fn start_capturing_proxy(
+    server_addr: SocketAddr,
+) -> Result<(SocketAddr, CapturingProxy)> {
+    let socket = UdpSocket::bind(AVAILABLE_LOCAL_PORTS_ADDR)
+        .await
+        .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+    let proxy_addr = socket
+        .local_addr()
+        .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+    let proxy = CapturingProxy {
+        socket: Arc::new(socket),
+        server_addr,
+        captured: Default::default(),
+    };
+
+    let socket = proxy.socket.clone();
+    let captured = proxy.captured.clone();
+    tokio::spawn(async move {
+        let mut client_addr = None;
+        let mut buf = vec![0u8; u16::MAX as usize];
+        loop {
+            let (len, from) = match socket.recv_from(&mut buf).await {
+                Ok(res) => res,
+                Err(_) => continue,
+            };
+
+            let to = if from == server_addr {
+                match client_addr {
+                    Some(addr) => addr,
+                    None => continue,
+                }
+            } else {
+                client_addr = Some(from);
+                captured.lock().unwrap().push(buf[..len].to_vec());
+                server_addr
+            };
+            let _ = socket.send_to(&buf[..len], to).await;
+        }
+    });
+
+    Ok((proxy_addr, proxy))
+}
@@ -1,4 +1,6 @@
-use crate::{SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle};
+use crate::{
+    HandleToSecret, SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
+};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::vec::Vec;
@@ -51,6 +53,38 @@ pub trait SecretsRepository: Send + Sync + 'static {
     /// Get the list of all X25519 secret handles
     async fn get_x25519_secret_handles(&self) -> Result<Vec<X25519SecretKeyHandle>>;
 
+    /// Get the handles and creation times of all the signing and X25519 secrets
+    async fn get_stored_secrets(&self) -> Result<Vec<StoredSecret>>;
+
     /// Delete all secrets
     async fn delete_all(&self) -> Result<()>;
 }
+
+/// Handle to a persisted secret, which can either be a signing secret or a X25519 secret
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum StoredSecretKeyHandle {
+    /// Handle to a signing secret
+    Signing(SigningSecretKeyHandle),
+    /// Handle to a X25519 secret
+    X25519(X25519SecretKeyHandle),
+}
+
+impl StoredSecretKeyHandle {
+    /// [`HandleToSecret`]
+    pub fn handle(&self) -> &HandleToSecret {
+        match self {
+            StoredSecretKeyHandle::Signing(handle) => handle.handle(),
+            StoredSecretKeyHandle::X25519(handle) => &handle.0,
+        }
+    }
+}
+
+/// Description of a persisted secret, without the secret value
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StoredSecret {
+    /// Handle to the secret
+    pub handle: StoredSecretKeyHandle,
+    /// Creation time of the secret, as a Unix timestamp in seconds.
+    /// It is not known for secrets stored by older versions
+    pub created_at: Option<u64>,
+}
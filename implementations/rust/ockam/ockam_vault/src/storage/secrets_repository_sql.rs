@@ -2,12 +2,13 @@ use sqlx::*;
 use tracing::debug;
 
 use ockam_core::async_trait;
+use ockam_core::compat::time::now;
 use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Result;
 use ockam_node::database::{FromSqlxError, SqlxDatabase, SqlxType, ToSqlxType, ToVoid};
 
-use crate::storage::secrets_repository::SecretsRepository;
+use crate::storage::secrets_repository::{SecretsRepository, StoredSecret, StoredSecretKeyHandle};
 
 use crate::{
     ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey, HandleToSecret, SigningSecret,
@@ -48,10 +49,13 @@ impl SecretsRepository for SecretsSqlxDatabase {
             SigningSecretKeyHandle::ECDSASHA256CurveP256(_) => EC_DSA_SHA256_CURVE_P256.into(),
         };
 
-        let query = query("INSERT OR REPLACE INTO signing_secret VALUES (?, ?, ?)")
-            .bind(handle.to_sql())
-            .bind(secret_type.to_sql())
-            .bind(secret.to_sql());
+        let query = query(
+            "INSERT OR REPLACE INTO signing_secret (handle, secret_type, secret, created_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(handle.to_sql())
+        .bind(secret_type.to_sql())
+        .bind(secret.to_sql())
+        .bind(now()?.to_sql());
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -107,9 +111,12 @@ impl SecretsRepository for SecretsSqlxDatabase {
         handle: &X25519SecretKeyHandle,
         secret: X25519SecretKey,
     ) -> Result<()> {
-        let query = query("INSERT OR REPLACE INTO x25519_secret VALUES (?, ?)")
-            .bind(handle.to_sql())
-            .bind(secret.to_sql());
+        let query = query(
+            "INSERT OR REPLACE INTO x25519_secret (handle, secret, created_at) VALUES (?, ?, ?)",
+        )
+        .bind(handle.to_sql())
+        .bind(secret.to_sql())
+        .bind(now()?.to_sql());
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -157,6 +164,22 @@ impl SecretsRepository for SecretsSqlxDatabase {
             .collect::<Result<Vec<_>>>()?)
     }
 
+    async fn get_stored_secrets(&self) -> Result<Vec<StoredSecret>> {
+        let query1 = query_as("SELECT handle, secret_type, created_at FROM signing_secret");
+        let signing_rows: Vec<StoredSigningSecretRow> =
+            query1.fetch_all(&*self.database.pool).await.into_core()?;
+        let query2 = query_as("SELECT handle, created_at FROM x25519_secret");
+        let x25519_rows: Vec<StoredX25519SecretRow> =
+            query2.fetch_all(&*self.database.pool).await.into_core()?;
+
+        let mut secrets = signing_rows
+            .iter()
+            .map(|r| r.stored_secret())
+            .collect::<Result<Vec<_>>>()?;
+        secrets.extend(x25519_rows.iter().map(|r| r.stored_secret()));
+        Ok(secrets)
+    }
+
     async fn delete_all(&self) -> Result<()> {
         let mut transaction = self.database.begin().await.into_core()?;
         let query1 = query("DELETE FROM signing_secret");
@@ -274,6 +297,51 @@ impl X25519SecretRow {
     }
 }
 
+#[derive(FromRow)]
+struct StoredSigningSecretRow {
+    handle: Vec<u8>,
+    secret_type: String,
+    created_at: Option<i64>,
+}
+
+impl StoredSigningSecretRow {
+    fn stored_secret(&self) -> Result<StoredSecret> {
+        let handle = HandleToSecret::new(self.handle.clone());
+        let handle = match self.secret_type.as_str() {
+            ED_DSA_CURVE_25519 => SigningSecretKeyHandle::EdDSACurve25519(handle),
+            EC_DSA_SHA256_CURVE_P256 => SigningSecretKeyHandle::ECDSASHA256CurveP256(handle),
+            _ => {
+                return Err(ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Serialization,
+                    "cannot deserialize a signing secret handle",
+                ))
+            }
+        };
+        Ok(StoredSecret {
+            handle: StoredSecretKeyHandle::Signing(handle),
+            created_at: self.created_at.map(|t| t as u64),
+        })
+    }
+}
+
+#[derive(FromRow)]
+struct StoredX25519SecretRow {
+    handle: Vec<u8>,
+    created_at: Option<i64>,
+}
+
+impl StoredX25519SecretRow {
+    fn stored_secret(&self) -> StoredSecret {
+        StoredSecret {
+            handle: StoredSecretKeyHandle::X25519(X25519SecretKeyHandle(HandleToSecret::new(
+                self.handle.clone(),
+            ))),
+            created_at: self.created_at.map(|t| t as u64),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -344,6 +412,42 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_stored_secrets() -> Result<()> {
+        let repository = create_repository().await?;
+
+        let signing_handle =
+            SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(vec![1, 2, 3]));
+        let signing_secret = SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new([1; 32]));
+        let x25519_handle = X25519SecretKeyHandle(HandleToSecret::new(vec![4, 5, 6]));
+        let x25519_secret = X25519SecretKey::new([1; 32]);
+
+        let before = now()?;
+        repository
+            .store_signing_secret(&signing_handle, signing_secret)
+            .await?;
+        repository
+            .store_x25519_secret(&x25519_handle, x25519_secret)
+            .await?;
+
+        let result = repository.get_stored_secrets().await?;
+        let handles: Vec<StoredSecretKeyHandle> = result.iter().map(|s| s.handle.clone()).collect();
+        assert_eq!(
+            handles,
+            vec![
+                StoredSecretKeyHandle::Signing(signing_handle.clone()),
+                StoredSecretKeyHandle::X25519(x25519_handle)
+            ]
+        );
+        assert!(result.iter().all(|s| s.created_at.unwrap() >= before));
+
+        repository.delete_signing_secret(&signing_handle).await?;
+        let result = repository.get_stored_secrets().await?;
+        assert_eq!(result.len(), 1);
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn SecretsRepository>> {
         Ok(Arc::new(SecretsSqlxDatabase::create().await?))